@@ -1,4 +1,4 @@
-use crate::progress::ProgressManager;
+use crate::progress::{ProgressManager, ProgressMode};
 
 /// Shared progress configuration for CLI commands.
 /// Use with `#[clap(flatten)]` in command arg structs.
@@ -7,6 +7,10 @@ pub struct WithProgressConfig {
     /// Disable progress output (useful for CI/scripts)
     #[clap(long, default_value_t = false, help = "disable progress output")]
     pub no_progress: bool,
+
+    /// How progress is shown; log lines in CI, bars otherwise
+    #[clap(long = "progress", value_enum, env = "KINTSU_PROGRESS")]
+    pub progress_mode: Option<ProgressMode>,
 }
 
 impl WithProgressConfig {
@@ -14,7 +18,11 @@ impl WithProgressConfig {
         !self.no_progress
     }
 
+    /// Installs the progress mode, then creates a manager in it
     pub fn create_manager(&self) -> ProgressManager {
+        self.progress_mode
+            .unwrap_or_else(ProgressMode::detect)
+            .install();
         ProgressManager::new(self.progress_enabled())
     }
 }
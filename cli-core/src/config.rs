@@ -0,0 +1,582 @@
+//! Layered configuration for the command line tools.
+//!
+//! Settings are read, in increasing precedence, from the defaults of the configuration type,
+//! its file in the config directory, environment variables under its prefix and command line
+//! flags. Every value remembers the layer it came from, so a misconfiguration names the file,
+//! variable or flag to fix rather than only the key.
+
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use config::{Map, Source, Value, ValueKind};
+use convert_case::{Case, Casing};
+use kintsu_manifests::NewForConfig;
+
+/// Origins of values from the environment and flags are tagged with these, file origins are
+/// the path config read them from.
+const ENV_ORIGIN: &str = "env:";
+const FLAG_ORIGIN: &str = "flag:";
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "layer", content = "name", rename_all = "snake_case")]
+pub enum Provenance {
+    /// The default of the configuration type
+    Default,
+    /// The configuration file, relative to the working directory
+    File(PathBuf),
+    /// An environment variable, e.g. `KS_ADDR`
+    Env(String),
+    /// A command line flag, e.g. `--addr`
+    Flag(String),
+}
+
+impl Provenance {
+    fn from_origin(origin: Option<&str>) -> Self {
+        let Some(origin) = origin else {
+            return Self::Default;
+        };
+        if let Some(var) = origin.strip_prefix(ENV_ORIGIN) {
+            Self::Env(var.into())
+        } else if let Some(flag) = origin.strip_prefix(FLAG_ORIGIN) {
+            Self::Flag(flag.into())
+        } else {
+            Self::File(origin.into())
+        }
+    }
+}
+
+impl std::fmt::Display for Provenance {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "the default"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Env(var) => write!(f, "{var}"),
+            Self::Flag(flag) => write!(f, "{flag}"),
+        }
+    }
+}
+
+/// A misconfiguration, naming the setting and the layer it was read from.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("failed to parse {}: {reason}", file.display())]
+    Parse { file: PathBuf, reason: String },
+
+    #[error("`{key}` is required, set it in {} or with {env}", file.display())]
+    Missing {
+        key: String,
+        file: PathBuf,
+        env: String,
+    },
+
+    #[error("`{key}` from {provenance} is invalid: {reason}")]
+    Invalid {
+        key: String,
+        provenance: Provenance,
+        reason: String,
+    },
+
+    #[error("invalid configuration: {reason}")]
+    Malformed { reason: String },
+}
+
+/// A loaded configuration, with the layer each of its values came from.
+#[derive(Debug)]
+pub struct Layered<T> {
+    config: T,
+    provenance: BTreeMap<String, Provenance>,
+}
+
+impl<T> Layered<T> {
+    /// Where the value at the dotted `key` came from: keys no layer set keep their default.
+    pub fn provenance(
+        &self,
+        key: &str,
+    ) -> &Provenance {
+        self.provenance
+            .get(key)
+            .unwrap_or(&Provenance::Default)
+    }
+
+    /// Every key a layer set, in order, with the layer it came from.
+    pub fn sources(&self) -> impl Iterator<Item = (&str, &Provenance)> {
+        self.provenance
+            .iter()
+            .map(|(key, provenance)| (key.as_str(), provenance))
+    }
+
+    pub fn into_inner(self) -> T {
+        self.config
+    }
+}
+
+impl<T> std::ops::Deref for Layered<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.config
+    }
+}
+
+/// Loads a [`NewForConfig`] from its file in the config directory, its environment variables
+/// and the flags given to it.
+#[derive(Debug)]
+pub struct ConfigLoader<T> {
+    dir: PathBuf,
+    vars: Vec<(String, String)>,
+    flags: Vec<(String, String, ValueKind)>,
+    config: PhantomData<fn() -> T>,
+}
+
+impl<T: NewForConfig> Default for ConfigLoader<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: NewForConfig> ConfigLoader<T> {
+    /// A loader reading the configuration file from the working directory, and variables
+    /// from the environment of the process.
+    pub fn new() -> Self {
+        Self {
+            dir: PathBuf::from("./"),
+            vars: std::env::vars_os()
+                .filter_map(|(var, value)| {
+                    Some((var.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect(),
+            flags: Vec::new(),
+            config: PhantomData,
+        }
+    }
+
+    /// Reads the configuration file from `dir`, when given.
+    pub fn dir(
+        mut self,
+        dir: Option<impl AsRef<Path>>,
+    ) -> Self {
+        if let Some(dir) = dir {
+            self.dir = dir.as_ref().to_path_buf();
+        }
+        self
+    }
+
+    /// Reads variables from `vars` instead of the environment of the process.
+    pub fn vars<K: Into<String>, V: Into<String>>(
+        mut self,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.vars = vars
+            .into_iter()
+            .map(|(var, value)| (var.into(), value.into()))
+            .collect();
+        self
+    }
+
+    /// Sets the dotted `key` to the value of `flag`, over every other layer, when the flag
+    /// was given.
+    pub fn flag<V: Into<ValueKind>>(
+        mut self,
+        key: &str,
+        flag: &str,
+        value: Option<V>,
+    ) -> Self {
+        if let Some(value) = value {
+            self.flags
+                .push((key.into(), flag.into(), value.into()));
+        }
+        self
+    }
+
+    /// The configuration file, with the extension it is looked up with.
+    pub fn file(&self) -> PathBuf {
+        self.dir.join(format!("{}.toml", T::NAME))
+    }
+
+    /// The environment variable the dotted `key` is read from.
+    pub fn env_var(key: &str) -> String {
+        let var = key.replace('.', "_").to_uppercase();
+        if T::ENV.is_empty() {
+            var
+        } else {
+            format!("{}_{var}", T::ENV)
+        }
+    }
+
+    pub fn load(self) -> Result<Layered<T>, ConfigError> {
+        let file = self.file();
+        let name = self.dir.join(T::NAME);
+        let built = config::Config::builder()
+            .add_source(config::File::with_name(&name.to_string_lossy()).required(false))
+            .add_source(EnvVars {
+                prefix: T::ENV.to_string(),
+                vars: self.vars,
+            })
+            .add_source(Flags(self.flags))
+            .build()
+            .map_err(|err| Self::error(err, &file, &BTreeMap::new()))?;
+
+        let mut provenance = BTreeMap::new();
+        let values = built
+            .collect()
+            .map_err(|err| Self::error(err, &file, &provenance))?;
+        record_provenance(&mut provenance, None, values);
+
+        let config: T = built
+            .try_deserialize()
+            .map_err(|err| Self::error(err, &file, &provenance))?;
+        config
+            .validate()
+            .map_err(|errors| validation_error(None, &errors, &provenance))?;
+
+        Ok(Layered { config, provenance })
+    }
+
+    fn error(
+        err: config::ConfigError,
+        file: &Path,
+        provenance: &BTreeMap<String, Provenance>,
+    ) -> ConfigError {
+        let provenance_of = |key: &str, origin: Option<&str>| {
+            match origin {
+                Some(origin) => Provenance::from_origin(Some(origin)),
+                None => {
+                    provenance
+                        .get(key)
+                        .cloned()
+                        .unwrap_or(Provenance::Default)
+                },
+            }
+        };
+
+        match err {
+            config::ConfigError::FileParse { uri, cause } => {
+                ConfigError::Parse {
+                    file: uri.map_or_else(|| file.to_path_buf(), PathBuf::from),
+                    reason: cause.to_string(),
+                }
+            },
+            config::ConfigError::NotFound(key) => {
+                ConfigError::Missing {
+                    env: Self::env_var(&key),
+                    key,
+                    file: file.to_path_buf(),
+                }
+            },
+            config::ConfigError::Type {
+                origin,
+                unexpected,
+                expected,
+                key: Some(key),
+            } => {
+                ConfigError::Invalid {
+                    provenance: provenance_of(&key, origin.as_deref()),
+                    key,
+                    reason: format!("expected {expected}, found {unexpected}"),
+                }
+            },
+            config::ConfigError::At {
+                error,
+                origin,
+                key: Some(key),
+            } => {
+                match Self::error(*error, file, provenance) {
+                    ConfigError::Malformed { reason } => {
+                        ConfigError::Invalid {
+                            provenance: provenance_of(&key, origin.as_deref()),
+                            key,
+                            reason,
+                        }
+                    },
+                    ConfigError::Missing { key: field, .. } => {
+                        let key = format!("{key}.{field}");
+                        ConfigError::Missing {
+                            env: Self::env_var(&key),
+                            key,
+                            file: file.to_path_buf(),
+                        }
+                    },
+                    err => err,
+                }
+            },
+            config::ConfigError::At { error, .. } => Self::error(*error, file, provenance),
+            err => {
+                ConfigError::Malformed {
+                    reason: err.to_string(),
+                }
+            },
+        }
+    }
+}
+
+/// The first validation error in `errors`, at the key of the field it failed for.
+fn validation_error(
+    parent: Option<&str>,
+    errors: &validator::ValidationErrors,
+    provenance: &BTreeMap<String, Provenance>,
+) -> ConfigError {
+    let key_of = |field: &str| {
+        match parent {
+            Some(parent) => format!("{parent}.{field}"),
+            None => field.to_string(),
+        }
+    };
+
+    for (field, kind) in errors.errors() {
+        let key = key_of(field);
+        match kind {
+            validator::ValidationErrorsKind::Field(errors) => {
+                if let Some(error) = errors.first() {
+                    let reason = error
+                        .message
+                        .as_ref()
+                        .map(|message| message.to_string())
+                        .unwrap_or_else(|| format!("failed the `{}` check", error.code));
+                    return ConfigError::Invalid {
+                        provenance: provenance
+                            .get(&key)
+                            .cloned()
+                            .unwrap_or(Provenance::Default),
+                        key,
+                        reason,
+                    };
+                }
+            },
+            validator::ValidationErrorsKind::Struct(errors) => {
+                return validation_error(Some(&key), errors, provenance);
+            },
+            validator::ValidationErrorsKind::List(items) => {
+                if let Some((index, errors)) = items.iter().next() {
+                    return validation_error(Some(&format!("{key}[{index}]")), errors, provenance);
+                }
+            },
+        }
+    }
+
+    ConfigError::Malformed {
+        reason: errors.to_string(),
+    }
+}
+
+fn record_provenance(
+    provenance: &mut BTreeMap<String, Provenance>,
+    parent: Option<&str>,
+    values: Map<String, Value>,
+) {
+    for (key, value) in values {
+        let key = match parent {
+            Some(parent) => format!("{parent}.{key}"),
+            None => key,
+        };
+        let origin = Provenance::from_origin(value.origin());
+        match value.kind {
+            ValueKind::Table(table) => record_provenance(provenance, Some(&key), table),
+            _ => {
+                provenance.insert(key, origin);
+            },
+        }
+    }
+}
+
+/// Variables under a prefix, named as [`config::Environment`] with a `_` separator maps them
+/// to keys: `KS_DATABASE_URL` sets `database.url`.
+#[derive(Debug, Clone)]
+struct EnvVars {
+    prefix: String,
+    vars: Vec<(String, String)>,
+}
+
+impl Source for EnvVars {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, config::ConfigError> {
+        let prefix = format!("{}_", self.prefix.to_lowercase());
+        let mut values = Map::new();
+        for (var, value) in &self.vars {
+            let key = var.to_lowercase();
+            let key = if self.prefix.is_empty() {
+                key.as_str()
+            } else {
+                match key.strip_prefix(&prefix) {
+                    Some(key) => key,
+                    None => continue,
+                }
+            };
+            let origin = format!("{ENV_ORIGIN}{var}");
+            values.insert(
+                key.replace('_', ".").to_case(Case::Snake),
+                Value::new(Some(&origin), ValueKind::String(value.clone())),
+            );
+        }
+        Ok(values)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Flags(Vec<(String, String, ValueKind)>);
+
+impl Source for Flags {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, config::ConfigError> {
+        Ok(self
+            .0
+            .iter()
+            .map(|(key, flag, value)| {
+                let origin = format!("{FLAG_ORIGIN}{flag}");
+                (key.clone(), Value::new(Some(&origin), value.clone()))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use validator::Validate;
+
+    use super::*;
+
+    fn default_workers() -> u32 {
+        4
+    }
+
+    #[derive(serde::Deserialize, Debug, Validate)]
+    struct Settings {
+        #[validate(url)]
+        url: String,
+
+        #[serde(default = "default_workers")]
+        workers: u32,
+
+        #[serde(default)]
+        #[validate(nested)]
+        database: Database,
+    }
+
+    #[derive(serde::Deserialize, Debug, Default, Validate)]
+    struct Database {
+        #[validate(length(min = 1))]
+        name: Option<String>,
+
+        #[serde(default)]
+        pool: u32,
+    }
+
+    impl NewForConfig for Settings {
+        const NAME: &'static str = "settings";
+        const ENV: &'static str = "KS";
+    }
+
+    fn settings_in(file: Option<&str>) -> (tempfile::TempDir, ConfigLoader<Settings>) {
+        let dir = tempfile::tempdir().unwrap();
+        if let Some(file) = file {
+            std::fs::write(dir.path().join("settings.toml"), file).unwrap();
+        }
+        let loader = ConfigLoader::new()
+            .dir(Some(dir.path()))
+            .vars(Vec::<(String, String)>::new());
+        (dir, loader)
+    }
+
+    #[test]
+    fn later_layers_take_precedence() {
+        let (_dir, loader) = settings_in(Some(
+            "url = \"https://file.example.com\"\n[database]\nname = \"kintsu\"\npool = 2\n",
+        ));
+        let loaded = loader
+            .vars([
+                ("KS_URL", "https://env.example.com"),
+                ("KS_DATABASE_POOL", "8"),
+                ("OTHER_URL", "https://other.example.com"),
+            ])
+            .flag("database.pool", "--pool", Some(16))
+            .load()
+            .unwrap();
+
+        assert_eq!(loaded.url, "https://env.example.com");
+        assert_eq!(loaded.workers, 4);
+        assert_eq!(loaded.database.name.as_deref(), Some("kintsu"));
+        assert_eq!(loaded.database.pool, 16);
+
+        assert_eq!(loaded.provenance("url"), &Provenance::Env("KS_URL".into()));
+        assert_eq!(loaded.provenance("workers"), &Provenance::Default);
+        assert!(matches!(
+            loaded.provenance("database.name"),
+            Provenance::File(path) if path.ends_with("settings.toml")
+        ));
+        assert_eq!(
+            loaded.provenance("database.pool"),
+            &Provenance::Flag("--pool".into())
+        );
+    }
+
+    #[test]
+    fn flags_not_given_leave_other_layers() {
+        let (_dir, loader) = settings_in(Some("url = \"https://file.example.com\"\n"));
+        let loaded = loader
+            .flag("url", "--url", None::<String>)
+            .load()
+            .unwrap();
+        assert_eq!(loaded.url, "https://file.example.com");
+    }
+
+    #[test]
+    fn misconfigurations_name_their_layer() {
+        let (_dir, loader) = settings_in(None);
+        match loader.load().unwrap_err() {
+            ConfigError::Missing { key, env, .. } => {
+                assert_eq!(key, "url");
+                assert_eq!(env, "KS_URL");
+            },
+            err => panic!("unexpected {err:?}"),
+        }
+
+        let (_dir, loader) = settings_in(Some("url = \"https://file.example.com\"\n"));
+        let err = loader
+            .vars([("KS_WORKERS", "many")])
+            .load()
+            .unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                ConfigError::Invalid { key, provenance: Provenance::Env(var), .. }
+                    if key == "workers" && var == "KS_WORKERS"
+            ),
+            "{err:?}"
+        );
+
+        let (_dir, loader) = settings_in(Some("url = \"not a url\"\n"));
+        let err = loader.load().unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::Invalid { key, provenance: Provenance::File(_), .. } if key == "url"),
+            "{err:?}"
+        );
+
+        let (_dir, loader) = settings_in(Some("url = \"https://file.example.com\"\n"));
+        let err = loader
+            .flag("database.name", "--database", Some(""))
+            .load()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`database.name` from --database is invalid: failed the `length` check"
+        );
+
+        let (_dir, loader) = settings_in(Some("url = \n"));
+        assert!(matches!(
+            loader.load().unwrap_err(),
+            ConfigError::Parse { .. }
+        ));
+    }
+}
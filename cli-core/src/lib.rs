@@ -1,6 +1,12 @@
 pub mod args;
+pub mod config;
+pub mod message;
 pub mod progress;
 
 pub use args::WithProgressConfig;
 pub use indicatif::ProgressBar;
-pub use progress::{CompilationProgress, ProgressManager, colors, prefixes, templates};
+pub use message::{Message, MessageFormat};
+pub use progress::{
+    CompilationProgress, PhaseProgress, ProgressEvent, ProgressManager, ProgressMode, colors,
+    prefixes, templates,
+};
@@ -0,0 +1,93 @@
+//! Machine-readable output, selected with `--message-format=json`.
+//!
+//! Like cargo's `--message-format=json`, every message is one JSON object on its own line of
+//! stdout, tagged by `reason`: `build-started`, `artifact`, `diagnostic` and
+//! `build-finished`. Diagnostics are written by the diagnostic reporters, the rest through
+//! [`emit`]. Progress bars and human readable progress are hidden meanwhile, so stdout holds
+//! nothing else.
+
+use std::{io::Write, path::Path, sync::OnceLock};
+
+static FORMAT: OnceLock<MessageFormat> = OnceLock::new();
+
+#[derive(clap::ValueEnum, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Progress bars and rendered diagnostics
+    #[default]
+    Human,
+    /// Newline delimited JSON messages on stdout
+    Json,
+}
+
+impl MessageFormat {
+    /// Selects the format of the whole process. Only the first format set takes effect.
+    pub fn install(self) {
+        let _ = FORMAT.set(self);
+    }
+
+    /// The installed format, or [`MessageFormat::Human`].
+    pub fn current() -> Self {
+        FORMAT.get().copied().unwrap_or_default()
+    }
+
+    pub fn is_json(self) -> bool {
+        self == Self::Json
+    }
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+pub enum Message<'a> {
+    BuildStarted,
+    /// A package compiled successfully
+    Artifact {
+        package: &'a str,
+        version: &'a str,
+        root: &'a Path,
+    },
+    BuildFinished {
+        success: bool,
+        elapsed_ms: f64,
+    },
+}
+
+/// Writes `message` as a line of stdout, when the JSON format is installed.
+pub fn emit(message: &Message) {
+    if !MessageFormat::current().is_json() {
+        return;
+    }
+
+    let mut line = serde_json::to_string(message).expect("messages are serializable");
+    line.push('\n');
+    // one write per line, so lines written from several threads are not interleaved
+    let _ = std::io::stdout()
+        .lock()
+        .write_all(line.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_tagged_by_reason() {
+        let artifact = Message::Artifact {
+            package: "pkg",
+            version: "1.0.0",
+            root: Path::new("./pkg"),
+        };
+        assert_eq!(
+            serde_json::to_string(&artifact).unwrap(),
+            r#"{"reason":"artifact","package":"pkg","version":"1.0.0","root":"./pkg"}"#
+        );
+
+        let finished = Message::BuildFinished {
+            success: false,
+            elapsed_ms: 1.5,
+        };
+        assert_eq!(
+            serde_json::to_string(&finished).unwrap(),
+            r#"{"reason":"build-finished","success":false,"elapsed_ms":1.5}"#
+        );
+    }
+}
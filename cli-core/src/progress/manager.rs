@@ -1,11 +1,16 @@
 use std::{
-    sync::{Arc, RwLock},
+    path::Path,
+    sync::{Arc, Mutex, RwLock},
     time::{Duration, Instant},
 };
 
-use indicatif::{MultiProgress, ProgressBar};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget};
 
-use super::style::{colors, templates};
+use super::{
+    style::{colors, templates},
+    tree::{PhaseProgress, ProgressEvent, ProgressMode, ProgressTree},
+};
+use crate::message::{self, Message, MessageFormat};
 
 #[derive(Clone)]
 pub struct ProgressManager {
@@ -17,16 +22,26 @@ struct ProgressManagerInner {
     start_time: Instant,
     enabled: bool,
     current_phase: RwLock<Option<String>>,
+    tree: Mutex<ProgressTree>,
 }
 
 impl ProgressManager {
-    /// Create a new progress manager
+    /// Create a new progress manager, shown in the installed [`ProgressMode`]. Progress is
+    /// never shown with the JSON message format, which owns stdout.
     pub fn new(enabled: bool) -> Self {
+        let mode = ProgressMode::current();
+        // log lines replace every bar
+        let multi = match mode {
+            ProgressMode::Bars => MultiProgress::new(),
+            ProgressMode::Log => MultiProgress::with_draw_target(ProgressDrawTarget::hidden()),
+        };
+
         Self {
             inner: Arc::new(ProgressManagerInner {
-                multi: MultiProgress::new(),
+                tree: Mutex::new(ProgressTree::new(mode, multi.clone())),
+                multi,
                 start_time: Instant::now(),
-                enabled,
+                enabled: enabled && !MessageFormat::current().is_json(),
                 current_phase: RwLock::new(None),
             }),
         }
@@ -77,6 +92,34 @@ impl ProgressManager {
         spinner
     }
 
+    /// Report what compilation is doing, shown in the package and phase hierarchy
+    pub fn event(
+        &self,
+        event: &ProgressEvent,
+    ) {
+        if !self.inner.enabled {
+            return;
+        }
+
+        let lines = match self.inner.tree.lock() {
+            Ok(mut tree) => tree.apply(event, Instant::now()),
+            Err(_) => return,
+        };
+        for line in lines {
+            eprintln!("{line}");
+        }
+    }
+
+    /// Start `phase` of `package`, with `total` steps if they are known
+    pub fn phase(
+        &self,
+        package: &str,
+        phase: &'static str,
+        total: Option<u64>,
+    ) -> PhaseProgress {
+        PhaseProgress::start(self.clone(), package, phase, total)
+    }
+
     /// Print a styled message
     pub fn println(
         &self,
@@ -133,6 +176,20 @@ impl ProgressManager {
         );
     }
 
+    /// Report a compiled package, as an `artifact` message with the JSON message format
+    pub fn artifact(
+        &self,
+        package: &str,
+        version: &str,
+        root: &Path,
+    ) {
+        message::emit(&Message::Artifact {
+            package,
+            version,
+            root,
+        });
+    }
+
     /// Complete with default "Finished" message (for backwards compat)
     pub fn finish(&self) {
         self.complete("compilation");
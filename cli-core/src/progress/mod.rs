@@ -1,5 +1,7 @@
 mod manager;
 mod style;
+mod tree;
 
 pub use manager::*;
 pub use style::*;
+pub use tree::{LOG_INTERVAL, PhaseProgress, ProgressEvent, ProgressMode, eta};
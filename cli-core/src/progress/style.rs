@@ -31,6 +31,15 @@ pub mod templates {
             .progress_chars("=> ")
     }
 
+    /// A phase of a package, with the estimated time remaining
+    pub fn phase() -> ProgressStyle {
+        ProgressStyle::with_template(
+            "{prefix:>14.magenta} [{bar:38}] {pos}/{len} ~{eta} {wide_msg}",
+        )
+        .unwrap()
+        .progress_chars("=> ")
+    }
+
     pub fn spinner() -> ProgressStyle {
         ProgressStyle::with_template("{prefix:>12.magenta.bold} {spinner} {wide_msg}").unwrap()
     }
@@ -42,7 +51,9 @@ pub mod prefixes {
     pub const PROCESSING: &str = "Processing";
     pub const FINISHED: &str = "Finished";
 
+    pub const WORKSPACE: &str = "Workspace";
     pub const COMPILING: &str = "Compiling";
+    pub const COMPILED: &str = "Compiled";
     pub const RESOLVING: &str = "Resolving";
     pub const LOADING: &str = "Loading";
 
@@ -53,6 +64,8 @@ pub mod prefixes {
     pub const UPLOADING: &str = "Uploading";
     pub const PUBLISHING: &str = "Publishing";
     pub const PUBLISHED: &str = "Published";
+    pub const WAITING: &str = "Waiting";
+    pub const YANKING: &str = "Yanking";
 
     pub const GENERATING: &str = "Generating";
     pub const WRITING: &str = "Writing";
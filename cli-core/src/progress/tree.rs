@@ -0,0 +1,482 @@
+//! Hierarchical progress: workspace → package → phase.
+//!
+//! Compilation reports what it is doing as [`ProgressEvent`]s, and the [`ProgressManager`]
+//! renders them. With [`ProgressMode::Bars`] every package is a line with a bar per running
+//! phase below it, and estimated time remaining; with [`ProgressMode::Log`], for CI, the
+//! same events become log lines, at most one per phase every [`LOG_INTERVAL`].
+//!
+//! [`ProgressManager`]: super::ProgressManager
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+use indicatif::{MultiProgress, ProgressBar};
+
+use super::style::{prefixes, templates};
+
+static MODE: OnceLock<ProgressMode> = OnceLock::new();
+
+/// Minimum time between two log lines of one running phase.
+pub const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How progress is shown.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Redrawn progress bars
+    Bars,
+    /// Periodic log lines on stderr, for CI
+    Log,
+}
+
+impl ProgressMode {
+    /// Selects the mode of the whole process. Only the first mode set takes effect.
+    pub fn install(self) {
+        let _ = MODE.set(self);
+    }
+
+    /// The installed mode, or the [detected](Self::detect) one.
+    pub fn current() -> Self {
+        MODE.get()
+            .copied()
+            .unwrap_or_else(Self::detect)
+    }
+
+    /// Log lines in CI, bars otherwise. Bars are hidden when stderr is not a terminal.
+    pub fn detect() -> Self {
+        if std::env::var_os("CI").is_some() {
+            Self::Log
+        } else {
+            Self::Bars
+        }
+    }
+}
+
+/// What compilation is doing, reported to [`ProgressManager::event`](super::ProgressManager::event).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent<'a> {
+    /// A workspace of `members` packages starts compiling
+    WorkspaceStarted {
+        members: u64,
+    },
+    PackageStarted {
+        package: &'a str,
+    },
+    /// A phase of `package` starts, with `total` steps if they are known
+    PhaseStarted {
+        package: &'a str,
+        phase: &'a str,
+        total: Option<u64>,
+    },
+    /// `steps` more steps of a phase are done, the last on `item`
+    PhaseAdvanced {
+        package: &'a str,
+        phase: &'a str,
+        steps: u64,
+        item: Option<&'a str>,
+    },
+    PhaseFinished {
+        package: &'a str,
+        phase: &'a str,
+    },
+    PackageFinished {
+        package: &'a str,
+    },
+}
+
+struct PackageNode {
+    line: ProgressBar,
+    /// The bar drawn last below the package line
+    last: ProgressBar,
+    started: Instant,
+    phases: BTreeMap<String, PhaseNode>,
+}
+
+struct PhaseNode {
+    bar: ProgressBar,
+    total: Option<u64>,
+    done: u64,
+    started: Instant,
+    last_logged: Instant,
+}
+
+/// The packages and phases in progress, and their bars.
+pub(super) struct ProgressTree {
+    mode: ProgressMode,
+    multi: MultiProgress,
+    workspace: Option<ProgressBar>,
+    packages: BTreeMap<String, PackageNode>,
+}
+
+impl ProgressTree {
+    pub(super) fn new(
+        mode: ProgressMode,
+        multi: MultiProgress,
+    ) -> Self {
+        Self {
+            mode,
+            multi,
+            workspace: None,
+            packages: BTreeMap::new(),
+        }
+    }
+
+    fn bars(&self) -> bool {
+        self.mode == ProgressMode::Bars
+    }
+
+    /// Applies `event` at `now`, returning the lines to log.
+    pub(super) fn apply(
+        &mut self,
+        event: &ProgressEvent,
+        now: Instant,
+    ) -> Vec<String> {
+        let mut lines = Vec::new();
+        match *event {
+            ProgressEvent::WorkspaceStarted { members } => {
+                if self.bars() {
+                    let bar = self.multi.add(ProgressBar::new(members));
+                    bar.set_style(templates::bar());
+                    bar.set_prefix(prefixes::WORKSPACE);
+                    bar.set_message("packages");
+                    self.workspace = Some(bar);
+                } else {
+                    lines.push(log_line(
+                        prefixes::WORKSPACE,
+                        &format!("{members} packages"),
+                    ));
+                }
+            },
+            ProgressEvent::PackageStarted { package } => {
+                let line = if self.bars() {
+                    let line = self.multi.add(ProgressBar::new_spinner());
+                    line.set_style(templates::spinner());
+                    line.set_prefix(prefixes::COMPILING);
+                    line.set_message(package.to_string());
+                    line.enable_steady_tick(Duration::from_millis(100));
+                    line
+                } else {
+                    lines.push(log_line(prefixes::COMPILING, package));
+                    ProgressBar::hidden()
+                };
+                self.packages.insert(
+                    package.to_string(),
+                    PackageNode {
+                        last: line.clone(),
+                        line,
+                        started: now,
+                        phases: BTreeMap::new(),
+                    },
+                );
+            },
+            ProgressEvent::PhaseStarted {
+                package,
+                phase,
+                total,
+            } => {
+                if !self.packages.contains_key(package) {
+                    // the package starts with its first phase
+                    lines = self.apply(&ProgressEvent::PackageStarted { package }, now);
+                }
+                let node = self
+                    .packages
+                    .get_mut(package)
+                    .expect("started above");
+                let bar = if self.mode == ProgressMode::Bars {
+                    let bar = match total {
+                        Some(total) => {
+                            let bar = ProgressBar::new(total);
+                            bar.set_style(templates::phase());
+                            bar
+                        },
+                        None => {
+                            let bar = ProgressBar::new_spinner();
+                            bar.set_style(templates::spinner());
+                            bar.enable_steady_tick(Duration::from_millis(100));
+                            bar
+                        },
+                    };
+                    // phases are drawn below their package, in the order they started
+                    let bar = self.multi.insert_after(&node.last, bar);
+                    bar.set_prefix(phase.to_string());
+                    node.last = bar.clone();
+                    bar
+                } else {
+                    ProgressBar::hidden()
+                };
+                node.phases.insert(
+                    phase.to_string(),
+                    PhaseNode {
+                        bar,
+                        total,
+                        done: 0,
+                        started: now,
+                        last_logged: now,
+                    },
+                );
+            },
+            ProgressEvent::PhaseAdvanced {
+                package,
+                phase,
+                steps,
+                item,
+            } => {
+                let bars = self.bars();
+                let Some(node) = self
+                    .packages
+                    .get_mut(package)
+                    .and_then(|node| node.phases.get_mut(phase))
+                else {
+                    return lines;
+                };
+                node.done += steps;
+                if bars {
+                    node.bar.inc(steps);
+                    if let Some(item) = item {
+                        node.bar.set_message(item.to_string());
+                    }
+                } else if now.duration_since(node.last_logged) >= LOG_INTERVAL {
+                    node.last_logged = now;
+                    lines.push(log_line(
+                        phase,
+                        &format!("{package}: {}", phase_status(node, now)),
+                    ));
+                }
+            },
+            ProgressEvent::PhaseFinished { package, phase } => {
+                let Some(node) = self
+                    .packages
+                    .get_mut(package)
+                    .and_then(|node| node.phases.remove(phase))
+                else {
+                    return lines;
+                };
+                node.bar.finish_and_clear();
+                if !self.bars() {
+                    lines.push(log_line(
+                        phase,
+                        &format!(
+                            "{package}: finished in {}",
+                            format_duration(now.duration_since(node.started))
+                        ),
+                    ));
+                }
+            },
+            ProgressEvent::PackageFinished { package } => {
+                let Some(node) = self.packages.remove(package) else {
+                    return lines;
+                };
+                for phase in node.phases.values() {
+                    phase.bar.finish_and_clear();
+                }
+                let message = format!(
+                    "{package} in {}",
+                    format_duration(now.duration_since(node.started))
+                );
+                if self.bars() {
+                    node.line.set_prefix(prefixes::COMPILED);
+                    node.line.finish_with_message(message);
+                } else {
+                    lines.push(log_line(prefixes::COMPILED, &message));
+                }
+                if let Some(workspace) = &self.workspace {
+                    workspace.inc(1);
+                    if workspace.length() == Some(workspace.position()) {
+                        workspace.finish_and_clear();
+                    }
+                }
+            },
+        }
+        lines
+    }
+}
+
+/// `done/total (percent), eta` of a phase, or the steps done when the total is unknown.
+fn phase_status(
+    node: &PhaseNode,
+    now: Instant,
+) -> String {
+    let Some(total) = node.total.filter(|total| *total > 0) else {
+        return format!("{} done", node.done);
+    };
+    let percent = node.done.min(total) * 100 / total;
+    match eta(now.duration_since(node.started), node.done, total) {
+        Some(eta) => {
+            format!(
+                "{}/{total} ({percent}%), about {} left",
+                node.done,
+                format_duration(eta)
+            )
+        },
+        None => format!("{}/{total} ({percent}%)", node.done),
+    }
+}
+
+/// Time left to complete `total` steps at the rate `done` took `elapsed`.
+pub fn eta(
+    elapsed: Duration,
+    done: u64,
+    total: u64,
+) -> Option<Duration> {
+    if done == 0 || done >= total {
+        return None;
+    }
+    Some(elapsed.mul_f64((total - done) as f64 / done as f64))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 60.0 {
+        format!("{secs:.1}s")
+    } else {
+        format!(
+            "{}m {:02}s",
+            duration.as_secs() / 60,
+            duration.as_secs() % 60
+        )
+    }
+}
+
+fn log_line(
+    prefix: &str,
+    message: &str,
+) -> String {
+    format!("{prefix:>12} {message}")
+}
+
+/// A running phase of a package, which reports its steps as [`ProgressEvent`]s.
+#[derive(Clone)]
+pub struct PhaseProgress {
+    manager: super::ProgressManager,
+    package: Arc<str>,
+    phase: &'static str,
+}
+
+impl PhaseProgress {
+    pub(super) fn start(
+        manager: super::ProgressManager,
+        package: &str,
+        phase: &'static str,
+        total: Option<u64>,
+    ) -> Self {
+        manager.event(&ProgressEvent::PhaseStarted {
+            package,
+            phase,
+            total,
+        });
+        Self {
+            manager,
+            package: package.into(),
+            phase,
+        }
+    }
+
+    /// One more step is done, on `item`.
+    pub fn advance(
+        &self,
+        item: &str,
+    ) {
+        self.manager
+            .event(&ProgressEvent::PhaseAdvanced {
+                package: &self.package,
+                phase: self.phase,
+                steps: 1,
+                item: Some(item),
+            });
+    }
+
+    pub fn finish(&self) {
+        self.manager
+            .event(&ProgressEvent::PhaseFinished {
+                package: &self.package,
+                phase: self.phase,
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_tree() -> ProgressTree {
+        ProgressTree::new(
+            ProgressMode::Log,
+            MultiProgress::with_draw_target(indicatif::ProgressDrawTarget::hidden()),
+        )
+    }
+
+    #[test]
+    fn estimates_remaining_time() {
+        let second = Duration::from_secs(1);
+        assert_eq!(eta(second * 2, 1, 3), Some(second * 4));
+        assert_eq!(eta(second, 0, 3), None);
+        assert_eq!(eta(second, 3, 3), None);
+        assert_eq!(format_duration(Duration::from_secs(75)), "1m 15s");
+    }
+
+    #[test]
+    fn logs_phases_periodically() {
+        let mut tree = log_tree();
+        let start = Instant::now();
+        let phase = |steps| {
+            ProgressEvent::PhaseAdvanced {
+                package: "pkg",
+                phase: "Resolving",
+                steps,
+                item: None,
+            }
+        };
+
+        assert_eq!(
+            tree.apply(&ProgressEvent::WorkspaceStarted { members: 2 }, start),
+            ["   Workspace 2 packages"]
+        );
+        // the package starts with its first phase
+        assert_eq!(
+            tree.apply(
+                &ProgressEvent::PhaseStarted {
+                    package: "pkg",
+                    phase: "Resolving",
+                    total: Some(4),
+                },
+                start
+            ),
+            ["   Compiling pkg"]
+        );
+        assert!(
+            tree.apply(&phase(1), start + Duration::from_secs(1))
+                .is_empty()
+        );
+        assert_eq!(
+            tree.apply(&phase(1), start + LOG_INTERVAL * 2),
+            ["   Resolving pkg: 2/4 (50%), about 10.0s left"]
+        );
+        assert!(
+            tree.apply(&phase(1), start + LOG_INTERVAL * 2)
+                .is_empty()
+        );
+
+        let end = start + LOG_INTERVAL * 3;
+        assert_eq!(
+            tree.apply(
+                &ProgressEvent::PhaseFinished {
+                    package: "pkg",
+                    phase: "Resolving",
+                },
+                end
+            ),
+            ["   Resolving pkg: finished in 15.0s"]
+        );
+        assert_eq!(
+            tree.apply(&ProgressEvent::PackageFinished { package: "pkg" }, end),
+            ["    Compiled pkg in 15.0s"]
+        );
+        // events of phases that are not running are ignored
+        assert!(
+            tree.apply(&phase(1), end + LOG_INTERVAL)
+                .is_empty()
+        );
+    }
+}
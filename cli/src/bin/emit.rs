@@ -9,5 +9,9 @@ fn main() {
             .title("Cli Reference".into());
         let md = clap_markdown::help_markdown_custom::<Cli>(&opts);
         std::fs::write("./cli.md", md).unwrap();
+
+        std::fs::write("./errors.md", kintsu_errors::catalog::render_markdown()).unwrap();
+        std::fs::write("./errors.json", kintsu_errors::catalog::render_json()).unwrap();
+        std::fs::write("./errors.ftl", kintsu_errors::catalog::render_fluent()).unwrap();
     }
 }
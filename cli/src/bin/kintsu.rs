@@ -3,10 +3,11 @@
 use clap::Parser;
 use human_panic::{Metadata, setup_panic};
 use kintsu_cli::cli::Cli;
+use kintsu_cli_core::{Message, message};
 use miette::GraphicalReportHandler;
 use std::process::ExitCode;
 use tracing::level_filters::LevelFilter;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{Layer, filter::filter_fn, layer::SubscriberExt, util::SubscriberInitExt};
 
 fn main() -> Result<ExitCode, ()> {
     miette::set_hook(Box::new(|_| {
@@ -27,6 +28,17 @@ fn main() -> Result<ExitCode, ()> {
     );
 
     let cli = Cli::parse();
+    let message_format = cli.message_format;
+    message_format.install();
+
+    if let Some(path) = &cli.locale {
+        match kintsu_errors::Locale::load(path) {
+            Ok(locale) => {
+                let _ = locale.install();
+            },
+            Err(err) => eprintln!("warning: ignoring locale {}: {err}", path.display()),
+        }
+    }
 
     let log_level: LevelFilter = cli.log_level.clone().into();
 
@@ -49,10 +61,14 @@ fn main() -> Result<ExitCode, ()> {
                     .with_line_number(false);
             }
 
-            layer
+            layer.with_filter(log_level)
         })
-        .with(indicatif_layer)
-        .with(log_level);
+        .with(indicatif_layer.with_filter(log_level))
+        // spans are captured regardless of the log level
+        .with(cli.trace_output().map(|output| {
+            kintsu_parser::ctx::compile::trace::SpanCapture::install(output)
+                .with_filter(filter_fn(|metadata| metadata.is_span()))
+        }));
 
     layer
         .try_init()
@@ -66,20 +82,74 @@ fn main() -> Result<ExitCode, ()> {
     });
 
     Ok(system.block_on(async {
-        kintsu_events::init(vec![Box::new(kintsu_events::StderrReporter)]);
+        let start = std::time::Instant::now();
+        let reporter: Box<dyn kintsu_events::DiagnosticReporter> = if message_format.is_json() {
+            Box::new(kintsu_events::JsonLinesReporter::messages(std::io::stdout()))
+        } else {
+            Box::new(kintsu_events::StderrReporter)
+        };
+        kintsu_events::init_with_config(
+            vec![reporter],
+            kintsu_events::CollectorConfig {
+                // tools reading messages want every diagnostic
+                max_per_group: (!message_format.is_json()).then_some(10),
+            },
+        );
+        message::emit(&Message::BuildStarted);
 
-        let result = cli.run().await;
+        let result = cli
+            .run()
+            .await
+            .map_err(kintsu_errors::CompilerError::from);
+        if message_format.is_json()
+            && let Err(err) = &result
+        {
+            for err in err.flatten() {
+                kintsu_events::emit(err.clone());
+            }
+        }
         let bundle = kintsu_events::shutdown().await;
 
-        match result {
-            Ok(()) if bundle.has_errors() => ExitCode::FAILURE,
-            Ok(()) => ExitCode::SUCCESS,
-            Err(e) => {
-                let compiler_error: kintsu_errors::CompilerError = e.into();
-                let report = compiler_error.to_report();
+        let success = result.is_ok() && !bundle.has_errors();
+        message::emit(&Message::BuildFinished {
+            success,
+            elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+        });
+
+        if let Err(err) = result
+            && !message_format.is_json()
+        {
+            for report in err.to_reports() {
                 eprintln!("{report:?}");
-                ExitCode::FAILURE
-            },
+            }
+
+            let mut explained: Vec<_> = err
+                .flatten()
+                .into_iter()
+                .map(|err| err.error_code())
+                .filter(|code| kintsu_errors::explain::details(*code).is_some())
+                .map(|code| code.to_string())
+                .collect();
+            explained.sort();
+            explained.dedup();
+            match explained.as_slice() {
+                [] => {},
+                [code] => {
+                    eprintln!("For more information about this error, try `kintsu explain {code}`.")
+                },
+                codes => {
+                    eprintln!(
+                        "For more information about these errors, try `kintsu explain <code>`: {}",
+                        codes.join(", ")
+                    )
+                },
+            }
+        }
+
+        if success {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
         }
     }))
 }
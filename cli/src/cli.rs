@@ -1,7 +1,7 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use kintsu_cli_core::WithProgressConfig;
-use kintsu_manifests::NewForConfig;
+use kintsu_cli_core::{WithProgressConfig, config::ConfigLoader};
+use kintsu_manifests::config::NewForNamed;
 use tracing::level_filters::LevelFilter;
 
 #[derive(Default, clap::ValueEnum, Clone, Debug)]
@@ -29,6 +29,74 @@ impl From<LogLevel> for LevelFilter {
     }
 }
 
+#[derive(Default, clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ExplainFormat {
+    /// Human readable table
+    #[default]
+    Table,
+    /// Structured JSON
+    Json,
+}
+
+#[derive(Default, clap::ValueEnum, Clone, Copy, Debug)]
+pub enum SqlDialectArg {
+    #[default]
+    Postgres,
+    Sqlite,
+}
+
+impl From<SqlDialectArg> for kintsu_core::generate::SqlDialect {
+    fn from(val: SqlDialectArg) -> Self {
+        match val {
+            SqlDialectArg::Postgres => Self::Postgres,
+            SqlDialectArg::Sqlite => Self::Sqlite,
+        }
+    }
+}
+
+#[derive(Default, clap::ValueEnum, Clone, Copy, Debug)]
+pub enum EnumStyleArg {
+    /// `CHECK` constraints listing the allowed values
+    #[default]
+    Check,
+    /// Native enum types, on Postgres only
+    Type,
+}
+
+impl From<EnumStyleArg> for kintsu_core::generate::EnumStyle {
+    fn from(val: EnumStyleArg) -> Self {
+        match val {
+            EnumStyleArg::Check => Self::Check,
+            EnumStyleArg::Type => Self::Type,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum BumpArg {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl From<BumpArg> for kintsu_manifests::version::Bump {
+    fn from(val: BumpArg) -> Self {
+        match val {
+            BumpArg::Patch => Self::Patch,
+            BumpArg::Minor => Self::Minor,
+            BumpArg::Major => Self::Major,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum SchemaFile {
+    /// `schema.toml`
+    Manifest,
+    /// `schema.lock.toml`
+    Lockfile,
+}
+
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(name = "")]
 pub struct Cli {
@@ -41,37 +109,406 @@ pub struct Cli {
     )]
     pub log_level: LogLevel,
 
+    #[clap(
+        long,
+        global = true,
+        env = "KINTSU_LOCALE",
+        help = "a Fluent file of translated diagnostic messages, e.g. `fr.ftl`. Untranslated messages are printed in English."
+    )]
+    pub locale: Option<PathBuf>,
+
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        default_value = "human",
+        help = "how to print progress and diagnostics. `json` prints one JSON message per line on stdout."
+    )]
+    pub message_format: kintsu_cli_core::MessageFormat,
+
     #[clap(subcommand)]
     command: Command,
 }
 
 impl Cli {
+    /// Where to write a compiler trace, when the command asked for one.
+    pub fn trace_output(&self) -> Option<&Path> {
+        match &self.command {
+            Command::Check(args) => args.trace.as_deref(),
+            _ => None,
+        }
+    }
+
     pub async fn run(self) -> kintsu_core::Result<()> {
         match self.command {
+            Command::Generate(GenArgs {
+                command: Some(GenCommand::Graphql(args)),
+                ..
+            }) => {
+                use kintsu_core::generate::{GenOpts, GraphqlConfig, graphql::GraphqlGenerator};
+
+                let progress = args.progress.create_manager();
+                let root_dir = args.config.config_dir.unwrap_or("./".into());
+
+                let mut ctx = compile(&root_dir, progress.is_enabled(), false).await?;
+                report_artifacts(&progress, std::slice::from_ref(&ctx));
+                ctx.add_manifest_hooks();
+                let kintsu_core::declare::DeclarationVersion::V1(bundle) =
+                    emit(&ctx, args.profile.as_deref()).await?;
+                progress.complete("compilation");
+
+                let opts = GenOpts {
+                    output_dir: args.output_dir,
+                    opts: GraphqlConfig {
+                        query_prefixes: args.query_prefixes,
+                    },
+                    mem: false,
+                };
+
+                // types of dependencies are resolved by their own schema, so only the
+                // root package is emitted
+                let generator = GraphqlGenerator::new();
+                generator.generate(&bundle.root, &opts, None)?;
+
+                for skipped in generator.unsupported() {
+                    eprintln!("warning: skipped {skipped}");
+                }
+                Ok(())
+            },
             Command::Generate(args) => {
-                let gen_conf = kintsu_core::generate::GenerationConfig::new(
-                    args.config.config_dir.as_deref(),
-                )?;
+                let gen_conf = ConfigLoader::<kintsu_core::generate::GenerationConfig>::new()
+                    .dir(args.config.config_dir.as_deref())
+                    .load()
+                    .map_err(|err| kintsu_core::Error::Settings(err.to_string()))?
+                    .into_inner();
                 kintsu_core::generate::Generation::new(gen_conf)?
                     .generate_all(None)
                     .await
             },
             Command::Check(args) => {
                 let progress = args.progress.create_manager();
+                let root_dir = args.config.config_dir.unwrap_or("./".into());
+
+                let mut passes = 0;
+                let compiled = loop {
+                    match check(&root_dir, progress.is_enabled(), args.deny_warnings).await {
+                        Err(err) if args.fix && passes < MAX_FIX_PASSES => {
+                            let err: kintsu_errors::CompilerError = err.into();
+                            let applied = kintsu_fs::fix::apply_fixes(
+                                &kintsu_fs::physical::Physical,
+                                &err.machine_applicable_fixes(),
+                            )
+                            .await?;
+
+                            // nothing left that can be fixed automatically
+                            if applied == 0 {
+                                return Err(kintsu_parser::Error::from(err).into());
+                            }
+
+                            eprintln!("applied {applied} fix(es)");
+                            passes += 1;
+                        },
+                        Ok(compiled) if args.fix && passes < MAX_FIX_PASSES => {
+                            // lint warnings do not fail the check, but may still carry fixes
+                            let mut fixes = Vec::new();
+                            for ctx in &compiled {
+                                fixes.extend(ctx.lint_fixes().await?);
+                            }
+                            let applied =
+                                kintsu_fs::fix::apply_fixes(&kintsu_fs::physical::Physical, &fixes)
+                                    .await?;
+
+                            if applied == 0 {
+                                break compiled;
+                            }
+
+                            eprintln!("applied {applied} fix(es)");
+                            passes += 1;
+                        },
+                        result => break result?,
+                    }
+                };
+
+                report_artifacts(&progress, &compiled);
+                progress.complete("compilation");
+
+                if let Some(format) = args.explain {
+                    let mut reports = Vec::with_capacity(compiled.len());
+                    for ctx in &compiled {
+                        reports.push(ctx.build_report().await);
+                    }
+                    match format {
+                        ExplainFormat::Table => {
+                            for report in reports {
+                                println!("{report}")
+                            }
+                        },
+                        ExplainFormat::Json => {
+                            // a workspace reports each member
+                            let json = match reports.as_slice() {
+                                [report] => serde_json::to_string_pretty(report),
+                                reports => serde_json::to_string_pretty(reports),
+                            };
+                            println!("{}", json.expect("build report is serializable"))
+                        },
+                    }
+                }
+                Ok(())
+            },
+            Command::Sql(args) => {
+                use kintsu_core::generate::{GenOpts, SqlConfig, sql::SqlGenerator};
+
+                let progress = args.progress.create_manager();
+                let root_dir = args.config.config_dir.unwrap_or("./".into());
+
+                let mut ctx = compile(&root_dir, progress.is_enabled(), false).await?;
+                report_artifacts(&progress, std::slice::from_ref(&ctx));
+                ctx.add_manifest_hooks();
+                let kintsu_core::declare::DeclarationVersion::V1(bundle) =
+                    emit(&ctx, args.profile.as_deref()).await?;
+                progress.complete("compilation");
+
+                let opts = GenOpts {
+                    output_dir: args.output_dir,
+                    opts: SqlConfig {
+                        dialect: args.dialect.into(),
+                        enums: args.enums.into(),
+                    },
+                    mem: false,
+                };
+
+                // dependencies keep their own tables, so only the root package is emitted
+                let generator = SqlGenerator::new();
+                generator.generate(&bundle.root, &opts, None)?;
+
+                for skipped in generator.unsupported() {
+                    eprintln!("warning: skipped {skipped}");
+                }
+                Ok(())
+            },
+            Command::Tree(args) => {
+                let progress = args.progress.create_manager();
+                let root_dir = args.config.config_dir.unwrap_or("./".into());
+
+                let compiled = check(&root_dir, progress.is_enabled(), false).await?;
+                report_artifacts(&progress, &compiled);
+                progress.complete("compilation");
+
+                let mut trees = Vec::with_capacity(compiled.len());
+                for ctx in &compiled {
+                    trees.push(ctx.dependency_tree().await);
+                }
+                // members of a workspace may resolve a package at different versions
+                let duplicates = kintsu_parser::ctx::DependencyTree::duplicates(&trees);
+
+                let rendered = trees
+                    .iter()
+                    .map(|tree| {
+                        match &args.invert {
+                            Some(package) => tree.render_inverted(package, &duplicates),
+                            None if args.namespaces => Ok(tree.render_namespaces()),
+                            None => Ok(tree.render(&duplicates)),
+                        }
+                    })
+                    .collect::<kintsu_parser::Result<Vec<_>>>()?;
+                println!("{}", rendered.join("\n"));
+                Ok(())
+            },
+            Command::Coverage(args) => {
+                let progress = args.progress.create_manager();
+                let root_dir = args.config.config_dir.unwrap_or("./".into());
+
+                let compiled = check(&root_dir, progress.is_enabled(), false).await?;
+                report_artifacts(&progress, &compiled);
+                progress.complete("compilation");
 
-                let ctx = kintsu_parser::ctx::CompileCtx::from_entry_point_with_progress(
-                    args.config.config_dir.unwrap_or("./".into()),
-                    progress.is_enabled(),
+                let mut reports = Vec::with_capacity(compiled.len());
+                for ctx in &compiled {
+                    reports.push(ctx.doc_coverage().await?);
+                }
+                match args.format {
+                    ExplainFormat::Table => {
+                        for report in reports {
+                            println!("{report}")
+                        }
+                    },
+                    ExplainFormat::Json => {
+                        // a workspace reports each member
+                        let json = match reports.as_slice() {
+                            [report] => serde_json::to_string_pretty(report),
+                            reports => serde_json::to_string_pretty(reports),
+                        };
+                        println!("{}", json.expect("coverage is serializable"))
+                    },
+                }
+                Ok(())
+            },
+            Command::Init(args) => {
+                let mut templates = kintsu_manifests::TemplateRegistry::builtin();
+                for dir in &args.template_dirs {
+                    templates.load_dir(dir)?;
+                }
+
+                if args.list_templates {
+                    for template in templates.templates() {
+                        println!("{:<16} {}", template.name, template.description);
+                    }
+                    return Ok(());
+                }
+
+                let options = kintsu_manifests::InitOptions {
+                    name: args
+                        .name
+                        .expect("clap requires a name unless templates are listed"),
+                    dir: args.dir,
+                    template: args.template,
+                    examples: args.examples,
+                };
+                let dir = kintsu_manifests::init(&options, &templates)?;
+                eprintln!(
+                    "created {} from template '{}'",
+                    dir.display(),
+                    options.template
+                );
+                Ok(())
+            },
+
+            Command::Add(args) => {
+                let root_dir = args.config.config_dir.unwrap_or("./".into());
+                let source = match args.path {
+                    Some(path) => kintsu_parser::ctx::DependencySource::Path(path),
+                    None => kintsu_parser::ctx::DependencySource::Registry(args.version),
+                };
+
+                let fs: std::sync::Arc<dyn kintsu_fs::FileSystem> =
+                    std::sync::Arc::new(kintsu_fs::physical::Physical);
+                let added = kintsu_parser::ctx::CompileCtx::add_dependency(
+                    fs.clone(),
+                    std::sync::Arc::new(kintsu_parser::ctx::compile::resolver::Resolver::new(fs)),
+                    &root_dir,
+                    &args.name,
+                    source,
                 )
                 .await?;
+                println!("{added}");
+                Ok(())
+            },
+            Command::Remove(args) => {
+                let root_dir = args.config.config_dir.unwrap_or("./".into());
 
-                ctx.finalize().await?;
+                let fs: std::sync::Arc<dyn kintsu_fs::FileSystem> =
+                    std::sync::Arc::new(kintsu_fs::physical::Physical);
+                let removed = kintsu_parser::ctx::CompileCtx::remove_dependency(
+                    fs.clone(),
+                    std::sync::Arc::new(kintsu_parser::ctx::compile::resolver::Resolver::new(fs)),
+                    &root_dir,
+                    &args.name,
+                )
+                .await?;
+                if removed {
+                    println!("removed {}", args.name);
+                } else {
+                    eprintln!("{} is not a dependency", args.name);
+                }
+                Ok(())
+            },
 
-                progress.complete("compilation");
+            Command::Version(args) => {
+                let root_dir = args.config.config_dir.unwrap_or("./".into());
+                let fs: std::sync::Arc<dyn kintsu_fs::FileSystem> =
+                    std::sync::Arc::new(kintsu_fs::physical::Physical);
+
+                let published = if let Some(path) = &args.published {
+                    let declarations =
+                        kintsu_parser::declare::DeclarationVersion::decode(&std::fs::read(path)?)
+                            .map_err(|err| {
+                            kintsu_parser::Error::from(
+                                kintsu_parser::PackageError::parse_error(format!(
+                                    "{}: {err}",
+                                    path.display()
+                                ))
+                                .unlocated()
+                                .build(),
+                            )
+                        })?;
+                    Some(declarations)
+                } else if let Some(registry) = &args.registry_url {
+                    let manifest =
+                        kintsu_manifests::package::PackageManifests::new(fs.as_ref(), &root_dir)?;
+                    let package = manifest.package();
+                    let client = kintsu_env_client::RegistryClient::new(registry, None)?;
+                    if client
+                        .version_exists(&package.name, &package.version)
+                        .await?
+                    {
+                        Some(
+                            client
+                                .download_declarations(&package.name, &package.version)
+                                .await?,
+                        )
+                    } else {
+                        eprintln!(
+                            "{}@{} is not published, skipping the check of the bump",
+                            package.name, package.version
+                        );
+                        None
+                    }
+                } else {
+                    None
+                };
+
+                let bumped = kintsu_parser::ctx::CompileCtx::bump_version(
+                    fs.clone(),
+                    std::sync::Arc::new(kintsu_parser::ctx::compile::resolver::Resolver::new(fs)),
+                    &root_dir,
+                    kintsu_parser::ctx::BumpOptions {
+                        bump: args.bump.into(),
+                        published,
+                        tag: args.tag,
+                    },
+                )
+                .await?;
+                println!("{bumped}");
                 Ok(())
             },
-            Command::Init(args) => Ok(kintsu_manifests::init(args.name, args.dir)?),
 
+            Command::Schema(args) => {
+                let schema = match args.file {
+                    SchemaFile::Manifest => kintsu_manifests::json_schema::manifest_schema(),
+                    SchemaFile::Lockfile => kintsu_manifests::json_schema::lockfile_schema(),
+                };
+                let json = serde_json::to_string_pretty(&schema)?;
+                match &args.output {
+                    Some(output) => std::fs::write(output, json + "\n")?,
+                    None => println!("{json}"),
+                }
+                Ok(())
+            },
+
+            Command::Explain(args) => {
+                println!("{}", args.code.render());
+                Ok(())
+            },
+
+            Command::Fmt(args) if args.stdin => {
+                use std::io::{Read, Write};
+
+                let mut source = String::new();
+                std::io::stdin().read_to_string(&mut source)?;
+
+                let name = args
+                    .stdin_filepath
+                    .unwrap_or_else(|| PathBuf::from("<stdin>"));
+                let formatted = kintsu_parser::fmt::format_stdin(
+                    args.config.config_dir,
+                    &name,
+                    &source,
+                    args.check_idempotent,
+                )?;
+
+                std::io::stdout().write_all(formatted.as_bytes())?;
+                Ok(())
+            },
             Command::Fmt(args) => {
                 let progress = args.progress.create_manager();
                 let targets = kintsu_fs::match_paths::match_paths(&args.include, &args.exclude)?;
@@ -80,6 +517,7 @@ impl Cli {
                     args.config.config_dir,
                     targets,
                     args.dry,
+                    args.check_idempotent,
                     progress.clone(),
                 )
                 .await?;
@@ -105,14 +543,29 @@ impl Cli {
                         // Phase 2: Publishing
                         progress.transition_phase("Publishing");
 
-                        let client = kintsu_env_client::RegistryClient::new(
-                            &opts.registry.base_url,
-                            Some(opts.registry.token),
-                        )?;
+                        let client = opts.registry.client()?;
 
                         let pkg_name = ctx.root.package.package().name.clone();
                         let version = ctx.root.package.package().version.clone();
 
+                        if client.is_dry_run() {
+                            let preview = client
+                                .publish_dry_run(
+                                    ctx.root.package.clone(),
+                                    ctx.root_fs.clone(),
+                                    root_dir.clone(),
+                                )
+                                .await?;
+                            for warning in &preview.warnings {
+                                progress.println(
+                                    kintsu_cli_core::prefixes::WARNING,
+                                    &warning.to_string(),
+                                );
+                            }
+                            progress.complete(format!("dry run of {}@{}", pkg_name, version));
+                            return Ok(());
+                        }
+
                         client
                             .publish_compiled_package_with_progress(
                                 ctx.root.package.clone(),
@@ -125,22 +578,210 @@ impl Cli {
                         progress.complete(format!("published {}@{}", pkg_name, version));
                         Ok(())
                     },
+                    RegistryCommand::PublishWorkspace(opts) => {
+                        let progress = opts.progress.create_manager();
+
+                        // every member must compile before anything is published
+                        let compiled = match &opts.workspace {
+                            Some(root) => {
+                                let ctx = kintsu_parser::ctx::CompileCtx::from_workspace(
+                                    root,
+                                    progress.is_enabled(),
+                                )
+                                .await?;
+                                ctx.finalize().await?;
+                                ctx.members
+                            },
+                            None => {
+                                let mut compiled = Vec::with_capacity(opts.members.len());
+                                for root in &opts.members {
+                                    compiled
+                                        .push(compile(root, progress.is_enabled(), false).await?);
+                                }
+                                compiled
+                            },
+                        };
+                        let members: Vec<_> = compiled
+                            .iter()
+                            .map(|ctx| {
+                                kintsu_env_client::workspace::WorkspaceMember {
+                                    root: ctx.root.root_path.clone(),
+                                    manifest: ctx.root.package.clone(),
+                                    fs: ctx.root_fs.clone(),
+                                }
+                            })
+                            .collect();
+
+                        progress.transition_phase("Publishing");
+
+                        let client = opts.registry.client()?;
+
+                        let published = kintsu_env_client::workspace::WorkspacePublisher::new(
+                            &client,
+                            kintsu_env_client::workspace::PublishOptions {
+                                poll_interval: std::time::Duration::from_secs(opts.poll_interval),
+                                resolve_timeout: std::time::Duration::from_secs(
+                                    opts.resolve_timeout,
+                                ),
+                            },
+                            progress.clone(),
+                        )
+                        .publish(&members)
+                        .await?;
+
+                        if client.is_dry_run() {
+                            progress.complete(format!("dry run of {} package(s)", published.len()));
+                        } else {
+                            progress.complete(format!("published {} package(s)", published.len()));
+                        }
+                        Ok(())
+                    },
+                    RegistryCommand::Yank(opts) => {
+                        let client = opts.registry.client()?;
+                        let version = opts.version.clone().into();
+
+                        let outcome = if opts.undo {
+                            client
+                                .unyank_version(&opts.name, &version)
+                                .await?
+                        } else {
+                            client
+                                .yank_version(&opts.name, &version)
+                                .await?
+                        };
+
+                        if let kintsu_env_client::Mutation::DryRun(plan) = &outcome {
+                            print!("{plan}");
+                        } else {
+                            let action = if opts.undo {
+                                "unyanked"
+                            } else {
+                                "yanked"
+                            };
+                            println!("{action} {}@{version}", opts.name);
+                        }
+                        Ok(())
+                    },
+                    RegistryCommand::Deprecate(opts) => {
+                        let client = opts.registry.client()?;
+                        let version = opts.version.clone().map(Into::into);
+
+                        let outcome = if opts.undo {
+                            client
+                                .undeprecate(&opts.name, version.as_ref())
+                                .await?
+                        } else {
+                            let body = kintsu_registry_core::models::DeprecatePackageRequest {
+                                message: opts.message.clone().unwrap_or_default(),
+                                replacement: opts.replacement.clone(),
+                            };
+                            client
+                                .deprecate(&opts.name, version.as_ref(), &body)
+                                .await?
+                        };
+
+                        if let kintsu_env_client::Mutation::DryRun(plan) = &outcome {
+                            print!("{plan}");
+                        } else {
+                            let action = if opts.undo {
+                                "undeprecated"
+                            } else {
+                                "deprecated"
+                            };
+                            match &version {
+                                Some(version) => println!("{action} {}@{version}", opts.name),
+                                None => println!("{action} {}", opts.name),
+                            }
+                        }
+                        Ok(())
+                    },
                 }
             },
         }
     }
 }
 
+/// Upper bound on compile and fix rounds for `check --fix`, since fixing one error can
+/// reveal another.
+const MAX_FIX_PASSES: usize = 8;
+
+async fn compile(
+    root_dir: &str,
+    show_progress: bool,
+    deny_warnings: bool,
+) -> kintsu_parser::Result<kintsu_parser::ctx::CompileCtx> {
+    let mut ctx =
+        kintsu_parser::ctx::CompileCtx::from_entry_point_with_progress(root_dir, show_progress)
+            .await?;
+    ctx.set_deny_warnings(deny_warnings);
+    ctx.finalize().await?;
+    Ok(ctx)
+}
+
+/// The declarations of `ctx`, narrowed to the manifest's `profile` when one is given.
+async fn emit(
+    ctx: &kintsu_parser::ctx::CompileCtx,
+    profile: Option<&str>,
+) -> kintsu_parser::Result<kintsu_core::declare::DeclarationVersion> {
+    match profile {
+        Some(profile) => ctx.emit_profile_declarations(profile).await,
+        None => ctx.emit_declarations().await,
+    }
+}
+
+/// Compiles the package in `root_dir`, or every member if it is a workspace root.
+async fn check(
+    root_dir: &str,
+    show_progress: bool,
+    deny_warnings: bool,
+) -> kintsu_parser::Result<Vec<kintsu_parser::ctx::CompileCtx>> {
+    use kintsu_manifests::workspace::WorkspaceManifests;
+
+    if !WorkspaceManifests::is_workspace(&kintsu_fs::physical::Physical, root_dir) {
+        return Ok(vec![compile(root_dir, show_progress, deny_warnings).await?]);
+    }
+
+    let mut ctx = kintsu_parser::ctx::CompileCtx::from_workspace(root_dir, show_progress).await?;
+    ctx.set_deny_warnings(deny_warnings);
+    ctx.finalize().await?;
+    Ok(ctx.members)
+}
+
+/// Reports each compiled package as an `artifact` message of `--message-format=json`.
+fn report_artifacts(
+    progress: &kintsu_cli_core::ProgressManager,
+    compiled: &[kintsu_parser::ctx::CompileCtx],
+) {
+    for ctx in compiled {
+        let package = ctx.root.package.package();
+        progress.artifact(
+            &package.name,
+            &package.version.to_string(),
+            &ctx.root.root_path,
+        );
+    }
+}
+
 #[derive(clap::Subcommand, Debug, Clone)]
 enum Command {
     #[clap(alias = "gen", alias = "g")]
-    /// generates models as defined in `op-gen.toml`
+    /// generates models as defined in `op-gen.toml`, or for the backend given
     Generate(GenArgs),
 
     #[clap(alias = "c")]
     /// checks models for soundness
     Check(CheckArgs),
 
+    /// generates SQL DDL for the structs of a package
+    Sql(SqlArgs),
+
+    #[clap(alias = "t")]
+    /// prints the resolved dependency tree of a package
+    Tree(TreeArgs),
+
+    /// reports how much of each namespace is documented
+    Coverage(CoverageArgs),
+
     #[clap(alias = "i")]
     /// initializes a new schema project
     Init(InitArgs),
@@ -149,6 +790,24 @@ enum Command {
     /// formats schemas
     Fmt(FmtArgs),
 
+    #[clap(alias = "a")]
+    /// adds a dependency, resolving it and updating the lockfile
+    Add(AddArgs),
+
+    #[clap(alias = "rm")]
+    /// removes a dependency and updates the lockfile
+    Remove(RemoveArgs),
+
+    /// bumps the package version, checked against the declarations it was published with
+    Version(VersionArgs),
+
+    /// prints the JSON Schema of the manifest or lockfile, for editors to validate them with
+    Schema(SchemaArgs),
+
+    #[clap(alias = "e")]
+    /// explains an error code, e.g. `kintsu explain KTR1002`
+    Explain(ExplainArgs),
+
     #[clap(alias = "r")]
     /// registry sub commands
     Registry {
@@ -173,6 +832,31 @@ struct WithRegistry {
         help = "the API key for the registry."
     )]
     token: secrecy::SecretString,
+
+    #[clap(
+        long = "mirror",
+        env = "KINTSU_REGISTRY_MIRRORS",
+        value_delimiter = ',',
+        help = "base urls of read-only mirrors, tried in order when the registry is unavailable."
+    )]
+    mirrors: Vec<String>,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "build and validate requests, then print them with credentials redacted instead of sending them."
+    )]
+    dry_run: bool,
+}
+
+impl WithRegistry {
+    fn client(&self) -> kintsu_core::Result<kintsu_env_client::RegistryClient> {
+        Ok(kintsu_env_client::RegistryClient::builder(&self.base_url)
+            .token(self.token.clone())
+            .mirrors(self.mirrors.clone())
+            .dry_run(self.dry_run)
+            .build()?)
+    }
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -182,12 +866,53 @@ struct WithConfig {
 }
 
 #[derive(clap::Args, Debug, Clone)]
+#[clap(args_conflicts_with_subcommands = true)]
 struct GenArgs {
     #[clap(flatten)]
     config: WithConfig,
 
     #[clap(flatten)]
     progress: WithProgressConfig,
+
+    #[clap(subcommand)]
+    command: Option<GenCommand>,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+enum GenCommand {
+    /// generates GraphQL SDL for the types and operations of a package
+    Graphql(GraphqlArgs),
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct GraphqlArgs {
+    #[clap(flatten)]
+    config: WithConfig,
+
+    #[clap(flatten)]
+    progress: WithProgressConfig,
+
+    #[clap(
+        long = "query-prefix",
+        value_delimiter = ',',
+        default_value = "get,list,find,search,count",
+        help = "operations named with one of these prefixes become queries, all others mutations, unless they have #[graphql(...)]."
+    )]
+    query_prefixes: Vec<String>,
+
+    #[clap(
+        long,
+        help = "the profile under [profiles.<name>] in schema.toml selecting which namespaces and items are emitted."
+    )]
+    profile: Option<String>,
+
+    #[clap(
+        short = 'o',
+        long,
+        default_value = "./graphql",
+        help = "the directory to write one .graphql file per namespace to, plus the root operation types."
+    )]
+    output_dir: PathBuf,
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -197,18 +922,264 @@ struct CheckArgs {
 
     #[clap(flatten)]
     progress: WithProgressConfig,
+
+    #[clap(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "table",
+        help = "print a report of phase timings, cache hits, dependency fetches and diagnostics."
+    )]
+    explain: Option<ExplainFormat>,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "apply machine-applicable fix suggestions to schema files, then check again."
+    )]
+    fix: bool,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "fail when warnings are emitted, except those whose codes `allow_warnings` under [lint] in schema.toml lists."
+    )]
+    deny_warnings: bool,
+
+    #[clap(
+        long,
+        help = "write a trace of compiler spans to this path: collapsed stacks if it ends in .folded, otherwise a chrome trace."
+    )]
+    trace: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct SqlArgs {
+    #[clap(flatten)]
+    config: WithConfig,
+
+    #[clap(flatten)]
+    progress: WithProgressConfig,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value = "postgres",
+        help = "the SQL dialect to emit."
+    )]
+    dialect: SqlDialectArg,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value = "check",
+        help = "how enum columns are restricted to their values."
+    )]
+    enums: EnumStyleArg,
+
+    #[clap(
+        long,
+        help = "the profile under [profiles.<name>] in schema.toml selecting which namespaces and items are emitted."
+    )]
+    profile: Option<String>,
+
+    #[clap(
+        short = 'o',
+        long,
+        default_value = "./sql",
+        help = "the directory to write one .sql file per namespace to."
+    )]
+    output_dir: PathBuf,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct TreeArgs {
+    #[clap(flatten)]
+    config: WithConfig,
+
+    #[clap(flatten)]
+    progress: WithProgressConfig,
+
+    #[clap(
+        short = 'i',
+        long,
+        help = "print the packages depending on this package instead, to explain why it is loaded."
+    )]
+    invert: Option<String>,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        conflicts_with = "invert",
+        help = "print the namespaces each namespace imports instead of packages."
+    )]
+    namespaces: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct CoverageArgs {
+    #[clap(flatten)]
+    config: WithConfig,
+
+    #[clap(flatten)]
+    progress: WithProgressConfig,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value = "table",
+        help = "print the coverage as a table, or as JSON listing the undocumented items."
+    )]
+    format: ExplainFormat,
 }
 
 #[derive(clap::Args, Debug, Clone)]
 struct InitArgs {
-    #[clap(short = 'n', long, help = "the name of the package to create.")]
-    name: String,
+    #[clap(
+        short = 'n',
+        long,
+        required_unless_present = "list_templates",
+        help = "the name of the package to create."
+    )]
+    name: Option<String>,
     #[clap(
         short = 'd',
         long,
-        help = "the directory to create the new package in."
+        help = "the directory to create the new package in. Defaults to the package name, next to the members of the enclosing workspace."
     )]
     dir: Option<PathBuf>,
+
+    #[clap(
+        short = 't',
+        long,
+        default_value = kintsu_manifests::scaffold::DEFAULT_TEMPLATE,
+        help = "the template to start from: lib, service, events, or one loaded from --template-dir."
+    )]
+    template: String,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "add the example types and operations of the template."
+    )]
+    examples: bool,
+
+    #[clap(
+        long = "template-dir",
+        env = "KINTSU_TEMPLATE_DIRS",
+        value_delimiter = ',',
+        help = "directories of custom templates, one subdirectory per template."
+    )]
+    template_dirs: Vec<PathBuf>,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        conflicts_with = "name",
+        help = "list the available templates instead of creating a package."
+    )]
+    list_templates: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct AddArgs {
+    #[clap(flatten)]
+    config: WithConfig,
+
+    #[clap(help = "the name of the package to depend on.")]
+    name: String,
+
+    #[clap(
+        short = 'v',
+        long,
+        value_parser = kintsu_manifests::version::parse_version_req,
+        conflicts_with = "path",
+        help = "the version requirement, e.g. ^1.2. Defaults to the latest published version."
+    )]
+    version: Option<kintsu_manifests::version::VersionReq>,
+
+    #[clap(
+        long,
+        help = "depend on the package in this directory, relative to the package, instead of the registry."
+    )]
+    path: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct RemoveArgs {
+    #[clap(flatten)]
+    config: WithConfig,
+
+    #[clap(help = "the name of the dependency to remove.")]
+    name: String,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct VersionArgs {
+    #[clap(flatten)]
+    config: WithConfig,
+
+    #[clap(value_enum, help = "the part of the version to increment.")]
+    bump: BumpArg,
+
+    #[clap(
+        short = 'r',
+        long,
+        env = "KINTSU_REGISTRY_URL",
+        help = "the registry to check the bump against the published declarations of the current version in."
+    )]
+    registry_url: Option<String>,
+
+    #[clap(
+        long,
+        conflicts_with = "registry_url",
+        help = "declarations of the current version, JSON or binary, to check the bump against instead of the registry."
+    )]
+    published: Option<PathBuf>,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "keep a copy of the lockfile for the new version in `releases/`."
+    )]
+    tag: bool,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct SchemaArgs {
+    #[clap(value_enum, help = "the file to print the schema of.")]
+    file: SchemaFile,
+
+    #[clap(
+        short = 'o',
+        long,
+        help = "write the schema to this file instead of printing it."
+    )]
+    output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct ExplainArgs {
+    #[clap(
+        value_parser = parse_error_code,
+        help = "the error code to explain, e.g. KTR1002."
+    )]
+    code: kintsu_errors::Explanation,
+}
+
+fn parse_error_code(code: &str) -> Result<kintsu_errors::Explanation, String> {
+    if let Some(explanation) = kintsu_errors::explain(code) {
+        return Ok(explanation);
+    }
+
+    let codes: Vec<_> = kintsu_errors::catalog()
+        .iter()
+        .map(|entry| entry.code.to_string())
+        .collect();
+    match kintsu_errors::closest_match(&code.to_uppercase(), codes.iter().map(String::as_str)) {
+        Some((closest, _)) => Err(format!("unknown error code, did you mean {closest}?")),
+        None => Err("unknown error code, expected one like KTR1002".into()),
+    }
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -226,6 +1197,28 @@ struct FmtArgs {
     )]
     dry: bool,
 
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "verify that formatting keeps every comment and is stable when run again, failing instead of writing files which are not."
+    )]
+    check_idempotent: bool,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["dry", "exclude", "include"],
+        help = "format a schema read from stdin and write it to stdout, leaving files untouched. Errors are reported on stderr and nothing is written to stdout."
+    )]
+    stdin: bool,
+
+    #[clap(
+        long,
+        requires = "stdin",
+        help = "the path of the schema read with --stdin, used in diagnostics and to resolve per-directory settings."
+    )]
+    stdin_filepath: Option<PathBuf>,
+
     #[clap(
         long,
         default_value_t = true,
@@ -253,6 +1246,63 @@ struct FmtArgs {
 #[derive(clap::Subcommand, Debug, Clone)]
 enum RegistryCommand {
     Publish(PublishArgs),
+
+    /// publishes several packages which depend on each other by path, in dependency order
+    PublishWorkspace(PublishWorkspaceArgs),
+
+    /// yanks a published version, so new resolutions no longer choose it
+    Yank(YankArgs),
+
+    /// deprecates a package or one of its versions, so resolving it warns
+    Deprecate(DeprecateArgs),
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct YankArgs {
+    #[clap(help = "the package name.")]
+    name: String,
+
+    #[clap(value_parser = kintsu_manifests::version::parse_version, help = "the version to yank.")]
+    version: kintsu_manifests::version::Version,
+
+    #[clap(long, default_value_t = false, help = "unyank the version instead.")]
+    undo: bool,
+
+    #[clap(flatten)]
+    registry: WithRegistry,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+struct DeprecateArgs {
+    #[clap(help = "the package name.")]
+    name: String,
+
+    #[clap(
+        value_parser = kintsu_manifests::version::parse_version,
+        help = "the version to deprecate. the whole package when omitted."
+    )]
+    version: Option<kintsu_manifests::version::Version>,
+
+    #[clap(
+        short,
+        long,
+        required_unless_present = "undo",
+        help = "why the package should no longer be used."
+    )]
+    message: Option<String>,
+
+    #[clap(long, conflicts_with = "undo", help = "a package to use instead.")]
+    replacement: Option<String>,
+
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "remove the deprecation instead."
+    )]
+    undo: bool,
+
+    #[clap(flatten)]
+    registry: WithRegistry,
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -266,3 +1316,42 @@ struct PublishArgs {
     #[clap(flatten)]
     progress: WithProgressConfig,
 }
+
+#[derive(clap::Args, Debug, Clone)]
+struct PublishWorkspaceArgs {
+    #[clap(
+        short = 'm',
+        long = "member",
+        required_unless_present = "workspace",
+        help = "the directory of a member package. may be repeated."
+    )]
+    members: Vec<String>,
+
+    #[clap(
+        short = 'w',
+        long,
+        conflicts_with = "members",
+        help = "the root of a workspace whose members are all published."
+    )]
+    workspace: Option<String>,
+
+    #[clap(flatten)]
+    registry: WithRegistry,
+
+    #[clap(flatten)]
+    progress: WithProgressConfig,
+
+    #[clap(
+        long,
+        default_value_t = 2,
+        help = "seconds between checks for a published version to become resolvable."
+    )]
+    poll_interval: u64,
+
+    #[clap(
+        long,
+        default_value_t = 120,
+        help = "seconds to wait for a published version to become resolvable before rolling back."
+    )]
+    resolve_timeout: u64,
+}
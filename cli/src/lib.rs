@@ -1 +1,3 @@
+#![allow(clippy::result_large_err)]
+
 pub mod cli;
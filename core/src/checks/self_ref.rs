@@ -1,7 +0,0 @@
-use crate::checks::*;
-
-crate::rule! {
-    SelfRef in Form @ Warn: Unsafe; ""
-}
-
-impl Check for SelfRef {}
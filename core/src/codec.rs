@@ -0,0 +1,1105 @@
+//! Canonical binary encoding of values of declared types.
+//!
+//! Values are given as JSON and encoded against the [`DeclType`] they are declared with, so
+//! the same value always encodes to the same bytes whatever produced it:
+//!
+//! - `bool` is a single `0` or `1` byte
+//! - unsigned integers are LEB128 varints, signed integers are zigzag LEB128 varints
+//! - `f16` and `f32` are 4 little-endian bytes, `f64` is 8
+//! - `str`, `datetime` and `base64` are a varint byte length followed by UTF-8
+//! - `binary` is a varint length followed by the bytes, given as a JSON array of bytes
+//! - arrays are a varint count followed by each element, sized arrays omit the count
+//! - optional values, and fields marked optional, are a `0` byte when absent or `1` followed
+//!   by the value
+//! - maps are a varint count followed by key and value pairs, ordered by encoded key
+//! - structs are their fields in declaration order, without names
+//! - enums are their variant's value, as an unsigned varint or a string
+//! - oneofs and errors are a varint variant index in declaration order followed by the
+//!   variant's value. As tagging only concerns JSON, their values are always given
+//!   externally tagged as `{ "<variant>": <value> }`
+//! - results are a `0` byte followed by the value, given as `{ "ok": <value> }`, or `1`
+//!   followed by the error, given as `{ "err": <error> }`
+//!
+//! Decoding produces the same JSON forms. Type expressions, `complex` and `never` have no
+//! encoding. An array count is never trusted beyond what the input can hold: elements which
+//! take at least a byte cannot outnumber the remaining bytes, and elements which encode to
+//! nothing are capped at [`MAX_EMPTY_ELEMENTS`]. Values nested deeper than [`MAX_DEPTH`], as
+//! recursive types allow, are rejected rather than decoded.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Number, Value};
+
+use crate::declare::{
+    Builtin, DeclEnum, DeclNamedItemContext, DeclNamespace, DeclOneOfVariant, DeclType,
+    DeclarationBundle, TypeDefinition, TypeRegistryDeclaration,
+};
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum CodecError {
+    #[error("`{0}` is not declared")]
+    Unresolved(String),
+    #[error("{0} has no encoding")]
+    Unsupported(String),
+    #[error("{path}: expected {expected}")]
+    Mismatch { path: String, expected: String },
+    #[error("{path}: {reason}")]
+    Invalid { path: String, reason: String },
+    #[error("input ended before the value was complete")]
+    Truncated,
+    #[error("{0} bytes remain after the value")]
+    Trailing(usize),
+}
+
+type Result<T> = std::result::Result<T, CodecError>;
+
+/// Most elements decoded for an array whose element type encodes to no bytes, e.g. an empty
+/// struct, as their count is the only thing bounding the work
+pub const MAX_EMPTY_ELEMENTS: u64 = 1 << 16;
+
+/// Deepest nesting of values decoded, as recursive types, e.g. `struct Node { next?: Node }`,
+/// otherwise let the input decide how deep decoding recurses
+pub const MAX_DEPTH: usize = 128;
+
+/// Encodes and decodes values of the types declared in a bundle.
+pub struct Codec<'a> {
+    bundle: &'a DeclarationBundle,
+}
+
+impl<'a> Codec<'a> {
+    pub fn new(bundle: &'a DeclarationBundle) -> Self {
+        Self { bundle }
+    }
+
+    pub fn encode(
+        &self,
+        ty: &DeclType,
+        value: &Value,
+    ) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        self.encode_type(ty, value, "$", &mut out)?;
+        Ok(out)
+    }
+
+    pub fn decode(
+        &self,
+        ty: &DeclType,
+        bytes: &[u8],
+    ) -> Result<Value> {
+        let mut reader = Reader { bytes, pos: 0 };
+        let value = self.decode_type(ty, &mut reader, "$", 0)?;
+        match bytes.len() - reader.pos {
+            0 => Ok(value),
+            rest => Err(CodecError::Trailing(rest)),
+        }
+    }
+
+    fn resolve(
+        &self,
+        reference: &DeclNamedItemContext,
+    ) -> Result<&'a TypeDefinition> {
+        let unresolved = || CodecError::Unresolved(reference.qualified_path());
+
+        let root = &self.bundle.root;
+        // references name packages in snake case, as dependencies are keyed
+        let package: &TypeRegistryDeclaration =
+            if root.package.replace('-', "_") == reference.context.package {
+                root
+            } else {
+                self.bundle
+                    .dependencies
+                    .get(&reference.context.package)
+                    .ok_or_else(unresolved)?
+            };
+
+        let mut path = reference.context.namespace.iter();
+        let mut ns: &DeclNamespace = path
+            .next()
+            .and_then(|name| package.namespaces.get(name))
+            .ok_or_else(unresolved)?;
+        for name in path {
+            ns = ns
+                .namespaces
+                .get(name)
+                .ok_or_else(unresolved)?;
+        }
+
+        ns.types
+            .iter()
+            .find(|def| def.name() == reference.name)
+            .ok_or_else(unresolved)
+    }
+
+    fn encode_type(
+        &self,
+        ty: &DeclType,
+        value: &Value,
+        path: &str,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        match ty {
+            DeclType::Builtin { ty } => encode_builtin(ty, value, path, out),
+            DeclType::Paren { inner_type } => self.encode_type(inner_type, value, path, out),
+            DeclType::Optional { inner_type } => {
+                if value.is_null() {
+                    out.push(0);
+                    Ok(())
+                } else {
+                    out.push(1);
+                    self.encode_type(inner_type, value, path, out)
+                }
+            },
+            DeclType::Array { element_type } => {
+                let items = value
+                    .as_array()
+                    .ok_or_else(|| mismatch(path, "an array"))?;
+                write_varint(out, items.len() as u64);
+                for (i, item) in items.iter().enumerate() {
+                    self.encode_type(element_type, item, &format!("{path}[{i}]"), out)?;
+                }
+                Ok(())
+            },
+            DeclType::SizedArray { element_type, size } => {
+                let items = value
+                    .as_array()
+                    .filter(|items| items.len() as u64 == *size)
+                    .ok_or_else(|| mismatch(path, format!("an array of {size}")))?;
+                for (i, item) in items.iter().enumerate() {
+                    self.encode_type(element_type, item, &format!("{path}[{i}]"), out)?;
+                }
+                Ok(())
+            },
+            DeclType::Map {
+                key_type,
+                value_type,
+            } => {
+                let entries = value
+                    .as_object()
+                    .ok_or_else(|| mismatch(path, "an object"))?;
+                let mut encoded = BTreeMap::new();
+                for (key, value) in entries {
+                    let entry_path = format!("{path}.{key}");
+                    let mut key_bytes = vec![];
+                    self.encode_type(
+                        key_type,
+                        &map_key(key_type, key),
+                        &entry_path,
+                        &mut key_bytes,
+                    )?;
+                    let mut value_bytes = vec![];
+                    self.encode_type(value_type, value, &entry_path, &mut value_bytes)?;
+                    if encoded
+                        .insert(key_bytes, value_bytes)
+                        .is_some()
+                    {
+                        return Err(invalid(&entry_path, "key repeats another key of the map"));
+                    }
+                }
+                write_varint(out, encoded.len() as u64);
+                for (key, value) in encoded {
+                    out.extend(key);
+                    out.extend(value);
+                }
+                Ok(())
+            },
+            DeclType::Result { ok_type, error } => {
+                let (tag, inner) = single_entry(value, path, "`ok` or `err`")?;
+                match tag.as_str() {
+                    "ok" => {
+                        out.push(0);
+                        self.encode_type(ok_type, inner, &format!("{path}.ok"), out)
+                    },
+                    "err" => {
+                        out.push(1);
+                        let error = DeclType::Named {
+                            reference: error.clone(),
+                        };
+                        self.encode_type(&error, inner, &format!("{path}.err"), out)
+                    },
+                    _ => Err(mismatch(path, "`ok` or `err`")),
+                }
+            },
+            DeclType::Named { reference } => {
+                match self.resolve(reference)? {
+                    TypeDefinition::Struct(def) => {
+                        let fields = value
+                            .as_object()
+                            .ok_or_else(|| mismatch(path, format!("a `{}`", def.name)))?;
+                        if let Some(unknown) = fields
+                            .keys()
+                            .find(|key| !def.fields.iter().any(|f| &f.name == *key))
+                        {
+                            return Err(invalid(
+                                path,
+                                format!("`{}` has no field `{unknown}`", def.name),
+                            ));
+                        }
+                        for field in &def.fields {
+                            let field_path = format!("{path}.{}", field.name);
+                            let value = fields
+                                .get(&field.name)
+                                .unwrap_or(&Value::Null);
+                            if field.optional {
+                                if value.is_null() {
+                                    out.push(0);
+                                    continue;
+                                }
+                                out.push(1);
+                            } else if !fields.contains_key(&field.name) {
+                                return Err(invalid(&field_path, "missing required field"));
+                            }
+                            self.encode_type(&field.ty, value, &field_path, out)?;
+                        }
+                        Ok(())
+                    },
+                    TypeDefinition::Enum(def) => {
+                        match &def.enum_def {
+                            DeclEnum::Int(variants) => {
+                                let known = value
+                                    .as_u64()
+                                    .filter(|v| {
+                                        variants
+                                            .iter()
+                                            .any(|var| var.value as u64 == *v)
+                                    })
+                                    .ok_or_else(|| {
+                                        mismatch(path, format!("a value of `{}`", def.name))
+                                    })?;
+                                write_varint(out, known);
+                            },
+                            DeclEnum::String(variants) => {
+                                let known = value
+                                    .as_str()
+                                    .filter(|v| variants.iter().any(|var| var.value == *v))
+                                    .ok_or_else(|| {
+                                        mismatch(path, format!("a value of `{}`", def.name))
+                                    })?;
+                                write_str(out, known);
+                            },
+                        }
+                        Ok(())
+                    },
+                    TypeDefinition::OneOf(def) => {
+                        self.encode_variant(&def.name, &def.variants, value, path, out)
+                    },
+                    TypeDefinition::Error(def) => {
+                        self.encode_variant(&def.name, &def.variants, value, path, out)
+                    },
+                    TypeDefinition::TypeAlias(def) => {
+                        self.encode_type(&def.target, value, path, out)
+                    },
+                    TypeDefinition::Operation(def) => {
+                        Err(CodecError::Unsupported(format!("operation `{}`", def.name)))
+                    },
+                }
+            },
+            DeclType::TypeExpr { .. } => Err(CodecError::Unsupported("a type expression".into())),
+        }
+    }
+
+    fn encode_variant(
+        &self,
+        name: &str,
+        variants: &[DeclOneOfVariant],
+        value: &Value,
+        path: &str,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        let expected = || format!("a variant of `{name}`");
+        let (tag, inner) = single_entry(value, path, &expected())?;
+        let (index, variant) = variants
+            .iter()
+            .enumerate()
+            .find(|(_, var)| &var.name == tag)
+            .ok_or_else(|| mismatch(path, expected()))?;
+        write_varint(out, index as u64);
+        self.encode_type(&variant.ty, inner, &format!("{path}.{tag}"), out)
+    }
+
+    fn decode_type(
+        &self,
+        ty: &DeclType,
+        reader: &mut Reader<'_>,
+        path: &str,
+        depth: usize,
+    ) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(invalid(
+                path,
+                format!("values are nested deeper than the limit of {MAX_DEPTH}"),
+            ));
+        }
+
+        match ty {
+            DeclType::Builtin { ty } => decode_builtin(ty, reader, path),
+            DeclType::Paren { inner_type } => self.decode_type(inner_type, reader, path, depth + 1),
+            DeclType::Optional { inner_type } => {
+                match read_flag(reader, path)? {
+                    false => Ok(Value::Null),
+                    true => self.decode_type(inner_type, reader, path, depth + 1),
+                }
+            },
+            DeclType::Array { element_type } => {
+                let count = reader.varint()?;
+                let remaining = reader.bytes.len() - reader.pos;
+                if self.encodes_empty(element_type, &mut vec![]) {
+                    if count > MAX_EMPTY_ELEMENTS {
+                        return Err(invalid(
+                            path,
+                            format!(
+                                "{count} empty elements exceed the limit of {MAX_EMPTY_ELEMENTS}"
+                            ),
+                        ));
+                    }
+                } else if count > remaining as u64 {
+                    return Err(invalid(
+                        path,
+                        format!("{count} elements cannot fit in the remaining {remaining} bytes"),
+                    ));
+                }
+
+                let mut items = vec![];
+                for i in 0..count {
+                    items.push(self.decode_type(
+                        element_type,
+                        reader,
+                        &format!("{path}[{i}]"),
+                        depth + 1,
+                    )?);
+                }
+                Ok(Value::Array(items))
+            },
+            DeclType::SizedArray { element_type, size } => {
+                let mut items = vec![];
+                for i in 0..*size {
+                    items.push(self.decode_type(
+                        element_type,
+                        reader,
+                        &format!("{path}[{i}]"),
+                        depth + 1,
+                    )?);
+                }
+                Ok(Value::Array(items))
+            },
+            DeclType::Map {
+                key_type,
+                value_type,
+            } => {
+                let count = reader.varint()?;
+                let mut entries = Map::new();
+                let mut last: Option<&[u8]> = None;
+                for _ in 0..count {
+                    let start = reader.pos;
+                    let key = self.decode_type(key_type, reader, path, depth + 1)?;
+                    let key_bytes = &reader.bytes[start..reader.pos];
+                    if last.is_some_and(|last| last >= key_bytes) {
+                        return Err(invalid(path, "map keys are not in canonical order"));
+                    }
+                    last = Some(key_bytes);
+
+                    let key = match key {
+                        Value::String(key) => key,
+                        other => other.to_string(),
+                    };
+                    let value =
+                        self.decode_type(value_type, reader, &format!("{path}.{key}"), depth + 1)?;
+                    entries.insert(key, value);
+                }
+                Ok(Value::Object(entries))
+            },
+            DeclType::Result { ok_type, error } => {
+                let (tag, value) = match read_flag(reader, path)? {
+                    false => {
+                        (
+                            "ok",
+                            self.decode_type(ok_type, reader, &format!("{path}.ok"), depth + 1)?,
+                        )
+                    },
+                    true => {
+                        let error = DeclType::Named {
+                            reference: error.clone(),
+                        };
+                        (
+                            "err",
+                            self.decode_type(&error, reader, &format!("{path}.err"), depth + 1)?,
+                        )
+                    },
+                };
+                Ok(Value::Object(Map::from_iter([(tag.to_string(), value)])))
+            },
+            DeclType::Named { reference } => {
+                match self.resolve(reference)? {
+                    TypeDefinition::Struct(def) => {
+                        let mut fields = Map::new();
+                        for field in &def.fields {
+                            let field_path = format!("{path}.{}", field.name);
+                            if field.optional && !read_flag(reader, &field_path)? {
+                                continue;
+                            }
+                            let value =
+                                self.decode_type(&field.ty, reader, &field_path, depth + 1)?;
+                            fields.insert(field.name.clone(), value);
+                        }
+                        Ok(Value::Object(fields))
+                    },
+                    TypeDefinition::Enum(def) => {
+                        let unknown = || invalid(path, format!("not a value of `{}`", def.name));
+                        match &def.enum_def {
+                            DeclEnum::Int(variants) => {
+                                let value = reader.varint()?;
+                                if !variants
+                                    .iter()
+                                    .any(|var| var.value as u64 == value)
+                                {
+                                    return Err(unknown());
+                                }
+                                Ok(Value::from(value))
+                            },
+                            DeclEnum::String(variants) => {
+                                let value = reader.str(path)?;
+                                if !variants.iter().any(|var| var.value == value) {
+                                    return Err(unknown());
+                                }
+                                Ok(Value::String(value))
+                            },
+                        }
+                    },
+                    TypeDefinition::OneOf(def) => {
+                        self.decode_variant(&def.name, &def.variants, reader, path, depth)
+                    },
+                    TypeDefinition::Error(def) => {
+                        self.decode_variant(&def.name, &def.variants, reader, path, depth)
+                    },
+                    TypeDefinition::TypeAlias(def) => {
+                        self.decode_type(&def.target, reader, path, depth + 1)
+                    },
+                    TypeDefinition::Operation(def) => {
+                        Err(CodecError::Unsupported(format!("operation `{}`", def.name)))
+                    },
+                }
+            },
+            DeclType::TypeExpr { .. } => Err(CodecError::Unsupported("a type expression".into())),
+        }
+    }
+
+    /// Whether a value of `ty` can encode to zero bytes. `seen` holds the types being checked,
+    /// so recursive types end the walk.
+    fn encodes_empty<'t>(
+        &self,
+        ty: &'t DeclType,
+        seen: &mut Vec<&'t DeclType>,
+    ) -> bool
+    where
+        'a: 't, {
+        if seen.contains(&ty) {
+            return false;
+        }
+        seen.push(ty);
+
+        let empty = match ty {
+            DeclType::Paren { inner_type } => self.encodes_empty(inner_type, seen),
+            DeclType::SizedArray { element_type, size } => {
+                *size == 0 || self.encodes_empty(element_type, seen)
+            },
+            DeclType::Named { reference } => {
+                match self.resolve(reference) {
+                    Ok(TypeDefinition::Struct(def)) => {
+                        def.fields
+                            .iter()
+                            .all(|field| !field.optional && self.encodes_empty(&field.ty, seen))
+                    },
+                    Ok(TypeDefinition::TypeAlias(def)) => self.encodes_empty(&def.target, seen),
+                    _ => false,
+                }
+            },
+            _ => false,
+        };
+
+        seen.pop();
+        empty
+    }
+
+    fn decode_variant(
+        &self,
+        name: &str,
+        variants: &[DeclOneOfVariant],
+        reader: &mut Reader<'_>,
+        path: &str,
+        depth: usize,
+    ) -> Result<Value> {
+        let index = reader.varint()?;
+        let variant = usize::try_from(index)
+            .ok()
+            .and_then(|index| variants.get(index))
+            .ok_or_else(|| invalid(path, format!("`{name}` has no variant {index}")))?;
+        let value = self.decode_type(
+            &variant.ty,
+            reader,
+            &format!("{path}.{}", variant.name),
+            depth + 1,
+        )?;
+        Ok(Value::Object(Map::from_iter([(
+            variant.name.clone(),
+            value,
+        )])))
+    }
+}
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take(
+        &mut self,
+        len: usize,
+    ) -> Result<&'b [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(CodecError::Truncated)?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u64::from(byte & 0x7f);
+            // the tenth byte may only carry the top bit, and canonical varints have no
+            // trailing zero groups
+            if (shift == 63 && bits > 1) || (shift > 0 && byte == 0) {
+                return Err(invalid("$", "varint is not canonical"));
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("$", "varint is longer than 10 bytes"))
+    }
+
+    fn bytes(&mut self) -> Result<&'b [u8]> {
+        let len = usize::try_from(self.varint()?).map_err(|_| CodecError::Truncated)?;
+        self.take(len)
+    }
+
+    fn str(
+        &mut self,
+        path: &str,
+    ) -> Result<String> {
+        let bytes = self.bytes()?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| invalid(path, "string is not UTF-8"))
+    }
+}
+
+fn mismatch(
+    path: &str,
+    expected: impl Into<String>,
+) -> CodecError {
+    CodecError::Mismatch {
+        path: path.into(),
+        expected: expected.into(),
+    }
+}
+
+fn invalid(
+    path: &str,
+    reason: impl Into<String>,
+) -> CodecError {
+    CodecError::Invalid {
+        path: path.into(),
+        reason: reason.into(),
+    }
+}
+
+/// The only entry of an object used as a tagged value.
+fn single_entry<'v>(
+    value: &'v Value,
+    path: &str,
+    expected: &str,
+) -> Result<(&'v String, &'v Value)> {
+    match value.as_object() {
+        Some(entries) if entries.len() == 1 => Ok(entries.iter().next().unwrap()),
+        _ => Err(mismatch(path, format!("an object holding {expected}"))),
+    }
+}
+
+/// Map keys are JSON strings, so keys of other types are parsed from them.
+fn map_key(
+    key_type: &DeclType,
+    key: &str,
+) -> Value {
+    match key_type {
+        DeclType::Builtin {
+            ty: Builtin::Str | Builtin::DateTime | Builtin::Base64,
+        } => Value::String(key.into()),
+        _ => serde_json::from_str(key).unwrap_or_else(|_| Value::String(key.into())),
+    }
+}
+
+fn read_flag(
+    reader: &mut Reader<'_>,
+    path: &str,
+) -> Result<bool> {
+    match reader.byte()? {
+        0 => Ok(false),
+        1 => Ok(true),
+        other => {
+            Err(invalid(
+                path,
+                format!("expected a 0 or 1 byte, found {other}"),
+            ))
+        },
+    }
+}
+
+fn write_varint(
+    out: &mut Vec<u8>,
+    mut value: u64,
+) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_str(
+    out: &mut Vec<u8>,
+    value: &str,
+) {
+    write_varint(out, value.len() as u64);
+    out.extend(value.as_bytes());
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Inclusive range of an integer builtin, and whether it is signed.
+fn int_range(ty: &Builtin) -> Option<(i128, i128, bool)> {
+    Some(match ty {
+        Builtin::I8 => (i8::MIN.into(), i8::MAX.into(), true),
+        Builtin::I16 => (i16::MIN.into(), i16::MAX.into(), true),
+        Builtin::I32 => (i32::MIN.into(), i32::MAX.into(), true),
+        Builtin::I64 => (i64::MIN.into(), i64::MAX.into(), true),
+        Builtin::U8 => (0, u8::MAX.into(), false),
+        Builtin::U16 => (0, u16::MAX.into(), false),
+        Builtin::U32 => (0, u32::MAX.into(), false),
+        Builtin::U64 | Builtin::Usize => (0, u64::MAX.into(), false),
+        _ => return None,
+    })
+}
+
+fn builtin_name(ty: &Builtin) -> String {
+    serde_json::to_value(ty)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_else(|| format!("{ty:?}"))
+}
+
+fn encode_builtin(
+    ty: &Builtin,
+    value: &Value,
+    path: &str,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    let expected = || mismatch(path, format!("a `{}`", builtin_name(ty)));
+
+    if let Some((min, max, signed)) = int_range(ty) {
+        let int = value
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| value.as_u64().map(i128::from))
+            .filter(|int| (min..=max).contains(int))
+            .ok_or_else(expected)?;
+        match signed {
+            true => write_varint(out, zigzag(int as i64)),
+            false => write_varint(out, int as u64),
+        }
+        return Ok(());
+    }
+
+    match ty {
+        Builtin::Bool => out.push(value.as_bool().ok_or_else(expected)? as u8),
+        Builtin::F16 | Builtin::F32 => {
+            let float = value.as_f64().ok_or_else(expected)? as f32;
+            out.extend(float.to_le_bytes());
+        },
+        Builtin::F64 => {
+            out.extend(
+                value
+                    .as_f64()
+                    .ok_or_else(expected)?
+                    .to_le_bytes(),
+            )
+        },
+        Builtin::Str | Builtin::DateTime | Builtin::Base64 => {
+            write_str(out, value.as_str().ok_or_else(expected)?)
+        },
+        Builtin::Binary => {
+            let bytes = value
+                .as_array()
+                .ok_or_else(expected)?
+                .iter()
+                .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(expected)?;
+            write_varint(out, bytes.len() as u64);
+            out.extend(bytes);
+        },
+        Builtin::Complex | Builtin::Never => {
+            return Err(CodecError::Unsupported(format!("`{}`", builtin_name(ty))));
+        },
+        _ => unreachable!("integers are encoded above"),
+    }
+    Ok(())
+}
+
+fn decode_builtin(
+    ty: &Builtin,
+    reader: &mut Reader<'_>,
+    path: &str,
+) -> Result<Value> {
+    if let Some((min, max, signed)) = int_range(ty) {
+        let raw = reader.varint()?;
+        let int = match signed {
+            true => i128::from(unzigzag(raw)),
+            false => i128::from(raw),
+        };
+        if !(min..=max).contains(&int) {
+            return Err(invalid(
+                path,
+                format!("{int} is out of range for `{}`", builtin_name(ty)),
+            ));
+        }
+        return Ok(match signed {
+            true => Value::from(int as i64),
+            false => Value::from(int as u64),
+        });
+    }
+
+    let float = |value: f64| {
+        Number::from_f64(value)
+            .map(Value::Number)
+            .ok_or_else(|| invalid(path, "float is not finite"))
+    };
+
+    match ty {
+        Builtin::Bool => Ok(Value::Bool(read_flag(reader, path)?)),
+        Builtin::F16 | Builtin::F32 => {
+            let bytes = reader.take(4)?;
+            float(f32::from_le_bytes(bytes.try_into().unwrap()).into())
+        },
+        Builtin::F64 => {
+            let bytes = reader.take(8)?;
+            float(f64::from_le_bytes(bytes.try_into().unwrap()))
+        },
+        Builtin::Str | Builtin::DateTime | Builtin::Base64 => Ok(Value::String(reader.str(path)?)),
+        Builtin::Binary => Ok(Value::from(reader.bytes()?.to_vec())),
+        Builtin::Complex | Builtin::Never => {
+            Err(CodecError::Unsupported(format!("`{}`", builtin_name(ty))))
+        },
+        _ => unreachable!("integers are decoded above"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn named(name: &str) -> DeclType {
+        serde_json::from_value(json!({
+            "type": "named",
+            "reference": {
+                "context": { "package": "test_pkg", "namespace": ["shop"] },
+                "name": name,
+            },
+        }))
+        .unwrap()
+    }
+
+    fn bundle() -> DeclarationBundle {
+        let builtin = |ty: &str| json!({ "type": "builtin", "ty": ty });
+        let named = |name: &str| serde_json::to_value(named(name)).unwrap();
+        let types = json!([
+            {
+                "definition_type": "struct",
+                "name": "Order",
+                "meta": { "version": 1 },
+                "fields": [
+                    { "name": "id", "ty": builtin("u64") },
+                    { "name": "note", "ty": builtin("str"), "optional": true },
+                    { "name": "lines", "ty": { "type": "array", "element_type": builtin("i32") } },
+                    {
+                        "name": "tags",
+                        "ty": {
+                            "type": "map",
+                            "key_type": builtin("str"),
+                            "value_type": builtin("bool"),
+                        },
+                    },
+                    { "name": "status", "ty": named("Status") },
+                    { "name": "payment", "ty": named("Payment") },
+                ],
+            },
+            {
+                "definition_type": "enum",
+                "name": "Status",
+                "meta": { "version": 1 },
+                "enum_def": {
+                    "enum_type": "int",
+                    "variants": [
+                        { "name": "Open", "value": 1 },
+                        { "name": "Closed", "value": 300 },
+                    ],
+                },
+            },
+            {
+                "definition_type": "one_of",
+                "name": "Payment",
+                "meta": { "version": 1 },
+                "variants": [
+                    { "name": "Card", "ty": builtin("str") },
+                    { "name": "Points", "ty": builtin("i64") },
+                ],
+            },
+            {
+                "definition_type": "struct",
+                "name": "Marker",
+                "meta": { "version": 1 },
+                "fields": [],
+            },
+            {
+                "definition_type": "struct",
+                "name": "Node",
+                "meta": { "version": 1 },
+                "fields": [{ "name": "next", "ty": named("Node"), "optional": true }],
+            },
+        ]);
+        serde_json::from_value(json!({
+            "root": {
+                "package": "test-pkg",
+                "namespaces": { "shop": { "name": "shop", "types": types } },
+                "external_refs": [],
+            },
+            "dependencies": {},
+        }))
+        .unwrap()
+    }
+
+    fn order() -> Value {
+        json!({
+            "id": 7,
+            "lines": [1, -1],
+            "tags": { "gift": true, "bulk": false },
+            "status": 300,
+            "payment": { "Points": -2 },
+        })
+    }
+
+    #[test]
+    fn test_struct_layout() {
+        let bundle = bundle();
+        let bytes = Codec::new(&bundle)
+            .encode(&named("Order"), &order())
+            .unwrap();
+
+        #[rustfmt::skip]
+        let expect = vec![
+            7, // id
+            0, // note absent
+            2, 2, 1, // lines: count, zigzag 1, zigzag -1
+            2, 4, b'b', b'u', b'l', b'k', 0, 4, b'g', b'i', b'f', b't', 1, // tags by key
+            0xac, 0x02, // status 300
+            1, 3, // payment: variant index 1, zigzag -2
+        ];
+        assert_eq!(bytes, expect);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bundle = bundle();
+        let codec = Codec::new(&bundle);
+
+        let mut value = order();
+        value["note"] = json!("leave at door");
+        value["payment"] = json!({ "Card": "4242" });
+
+        let bytes = codec
+            .encode(&named("Order"), &value)
+            .unwrap();
+        assert_eq!(
+            codec
+                .decode(&named("Order"), &bytes)
+                .unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_result_and_optional() {
+        let bundle = bundle();
+        let codec = Codec::new(&bundle);
+        let ty: DeclType = serde_json::from_value(json!({
+            "type": "result",
+            "ok_type": { "type": "optional", "inner_type": { "type": "builtin", "ty": "f64" } },
+            "error": { "context": { "package": "test_pkg", "namespace": ["shop"] }, "name": "Payment" },
+        }))
+        .unwrap();
+
+        for value in [
+            json!({ "ok": 1.5 }),
+            json!({ "ok": null }),
+            json!({ "err": { "Card": "x" } }),
+        ] {
+            let bytes = codec.encode(&ty, &value).unwrap();
+            assert_eq!(codec.decode(&ty, &bytes).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_mismatch_reports_path() {
+        let bundle = bundle();
+        let mut value = order();
+        value["lines"][1] = json!("x");
+
+        assert_eq!(
+            Codec::new(&bundle).encode(&named("Order"), &value),
+            Err(mismatch("$.lines[1]", "a `i32`"))
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_values() {
+        let bundle = bundle();
+        let codec = Codec::new(&bundle);
+
+        let mut value = order();
+        value["status"] = json!(2);
+        assert!(
+            codec
+                .encode(&named("Order"), &value)
+                .is_err()
+        );
+
+        let mut value = order();
+        value["extra"] = json!(1);
+        assert!(
+            codec
+                .encode(&named("Order"), &value)
+                .is_err()
+        );
+
+        assert_eq!(
+            codec.encode(&named("Missing"), &json!({})),
+            Err(CodecError::Unresolved("test_pkg::shop::Missing".into()))
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_canonical_input() {
+        let bundle = bundle();
+        let codec = Codec::new(&bundle);
+        let u64_ty: DeclType =
+            serde_json::from_value(json!({ "type": "builtin", "ty": "u64" })).unwrap();
+
+        assert_eq!(
+            codec.decode(&u64_ty, &[0x81, 0x00]),
+            Err(invalid("$", "varint is not canonical"))
+        );
+        assert_eq!(codec.decode(&u64_ty, &[0x81]), Err(CodecError::Truncated));
+        assert_eq!(codec.decode(&u64_ty, &[1, 2]), Err(CodecError::Trailing(1)));
+
+        let mut bytes = codec
+            .encode(&named("Order"), &order())
+            .unwrap();
+        // swap the order of the two map keys
+        bytes.splice(
+            6..18,
+            [4, b'g', b'i', b'f', b't', 1, 4, b'b', b'u', b'l', b'k', 0],
+        );
+        assert_eq!(
+            codec.decode(&named("Order"), &bytes),
+            Err(invalid("$.tags", "map keys are not in canonical order"))
+        );
+    }
+
+    #[test]
+    fn test_rejects_hostile_array_counts() {
+        let bundle = bundle();
+        let codec = Codec::new(&bundle);
+        let array = |element: DeclType| {
+            DeclType::Array {
+                element_type: Box::new(element),
+            }
+        };
+        let count = |count: u64| {
+            let mut out = vec![];
+            write_varint(&mut out, count);
+            out
+        };
+
+        let ints =
+            array(serde_json::from_value(json!({ "type": "builtin", "ty": "i32" })).unwrap());
+        let mut bytes = count(u64::MAX);
+        bytes.extend([2, 4]);
+        assert_eq!(
+            codec.decode(&ints, &bytes),
+            Err(invalid(
+                "$",
+                format!("{} elements cannot fit in the remaining 2 bytes", u64::MAX)
+            ))
+        );
+
+        // empty structs take no bytes, so only the hard limit bounds their count
+        let markers = array(named("Marker"));
+        assert_eq!(codec.decode(&markers, &count(3)), Ok(json!([{}, {}, {}])));
+        assert_eq!(
+            codec.decode(&markers, &count(MAX_EMPTY_ELEMENTS + 1)),
+            Err(invalid(
+                "$",
+                format!(
+                    "{} empty elements exceed the limit of {MAX_EMPTY_ELEMENTS}",
+                    MAX_EMPTY_ELEMENTS + 1
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn test_rejects_deeply_nested_values() {
+        let bundle = bundle();
+        let codec = Codec::new(&bundle);
+
+        // each `1` byte marks a present `next`, the `0` ends the chain
+        let mut bytes = vec![1; MAX_DEPTH];
+        bytes.push(0);
+        let value = codec.decode(&named("Node"), &bytes).unwrap();
+        assert_eq!(value.to_string().matches("next").count(), MAX_DEPTH);
+
+        let bytes = vec![1; MAX_DEPTH + 1];
+        assert_eq!(
+            codec.decode(&named("Node"), &bytes),
+            Err(invalid(
+                &format!("${}", ".next".repeat(MAX_DEPTH + 1)),
+                format!("values are nested deeper than the limit of {MAX_DEPTH}")
+            ))
+        );
+    }
+}
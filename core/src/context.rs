@@ -103,6 +103,7 @@ impl Context {
                 package,
                 namespaces,
                 external_refs: Default::default(),
+                metadata: Default::default(),
             },
             dependencies: Default::default(),
         }
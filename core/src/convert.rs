@@ -120,6 +120,8 @@ impl From<&Field<Ident>> for DeclField {
             default_value: None,
             optional: field.optional,
             comments: DeclComment::default(),
+            constraints: Vec::new(),
+            examples: Vec::new(),
         }
     }
 }
@@ -137,6 +139,8 @@ impl From<&Field<Option<Ident>>> for DeclField {
             default_value: None,
             optional: field.optional,
             comments: DeclComment::default(),
+            constraints: Vec::new(),
+            examples: Vec::new(),
         }
     }
 }
@@ -185,6 +189,7 @@ impl From<&Enum> for DeclEnumDef {
                                     _ => 0,
                                 },
                                 comments: doc_to_comment(&v.meta.description),
+                                aliases: Vec::new(),
                             }
                         })
                         .collect(),
@@ -202,6 +207,7 @@ impl From<&Enum> for DeclEnumDef {
                                     _ => name.to_string(),
                                 },
                                 comments: doc_to_comment(&v.meta.description),
+                                aliases: Vec::new(),
                             }
                         })
                         .collect(),
@@ -239,6 +245,7 @@ impl From<&OneOf> for DeclOneOf {
                 .collect(),
             meta: DeclMeta::new(o.meta.version.get() as u32),
             comments: doc_to_comment(&o.meta.description),
+            tag: Default::default(),
         }
     }
 }
@@ -254,6 +261,7 @@ impl From<&ErrorTy> for DeclError {
                 .collect(),
             meta: DeclMeta::new(e.meta.version.get() as u32),
             comments: doc_to_comment(&e.meta.description),
+            tag: Default::default(),
         }
     }
 }
@@ -271,6 +279,8 @@ impl From<&Operation> for DeclOperation {
                             ty: (&f.ty).into(),
                             default_value: None,
                             comments: doc_to_comment(&f.meta.description),
+                            constraints: Vec::new(),
+                            examples: Vec::new(),
                         })
                     },
                     FieldOrRef::Ref { .. } => None,
@@ -296,6 +306,9 @@ impl From<&Operation> for DeclOperation {
             return_type,
             meta: DeclMeta::new(op.meta.version.get() as u32),
             comments: doc_to_comment(&op.meta.description),
+            http: None,
+            graphql: None,
+            examples: Vec::new(),
         }
     }
 }
@@ -344,6 +357,7 @@ impl From<&Namespace> for DeclNamespace {
             version: Some(ns.version.get() as u32),
             error: None,
             types,
+            constants: Vec::new(),
             namespaces: Default::default(),
             comments: DeclComment::default(),
         }
@@ -2,11 +2,13 @@ pub mod context;
 pub mod decl_ext;
 pub mod decl_gen;
 pub mod files;
+pub mod graphql;
 pub mod matcher;
 pub mod python;
 pub mod remote;
 pub mod rust;
 pub mod rust_decl;
+pub mod sql;
 
 use std::{
     collections::BTreeMap,
@@ -55,6 +57,8 @@ pub enum Target {
     Client,
     Server,
     Types,
+    /// Per-operation mock harnesses, compiled under `cfg(any(test, feature = "mock"))`
+    Mock,
 }
 
 pub trait ConfigExt: Debug + PartialEq + Clone + Validate {}
@@ -168,6 +172,70 @@ pub struct RustConfig {
 
 impl ConfigExt for RustConfig {}
 
+#[derive(Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[serde(rename_all = "snake_case")]
+pub enum SqlDialect {
+    #[default]
+    #[serde(alias = "postgresql")]
+    Postgres,
+    Sqlite,
+}
+
+/// How enums are represented in generated DDL.
+#[derive(Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[serde(rename_all = "snake_case")]
+pub enum EnumStyle {
+    /// A `CHECK (column IN (...))` constraint on every column of the enum type
+    #[default]
+    Check,
+    /// A `CREATE TYPE ... AS ENUM` per string enum. Postgres only; other
+    /// dialects and integer enums fall back to [`EnumStyle::Check`].
+    Type,
+}
+
+#[derive(Deserialize, PartialEq, Debug, Clone, Default, Validate)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[serde(rename_all = "kebab-case")]
+pub struct SqlConfig {
+    #[serde(default)]
+    pub dialect: SqlDialect,
+
+    #[serde(default)]
+    pub enums: EnumStyle,
+}
+
+impl ConfigExt for SqlConfig {}
+
+crate::default!(
+    Vec<String>: {
+        query_prefixes = ["get", "list", "find", "search", "count"]
+            .map(String::from)
+            .to_vec()
+    },
+);
+
+#[derive(Deserialize, PartialEq, Debug, Clone, Validate)]
+#[cfg_attr(test, derive(serde::Serialize))]
+#[serde(rename_all = "kebab-case")]
+pub struct GraphqlConfig {
+    /// Operations named `<prefix>` or `<prefix>_...` are queries, all others are
+    /// mutations. `#[graphql(query)]` and `#[graphql(mutation)]` take precedence.
+    #[serde(default = "default_query_prefixes")]
+    pub query_prefixes: Vec<String>,
+}
+
+impl Default for GraphqlConfig {
+    fn default() -> Self {
+        Self {
+            query_prefixes: default_query_prefixes(),
+        }
+    }
+}
+
+impl ConfigExt for GraphqlConfig {}
+
 #[derive(Deserialize, PartialEq, Debug, Clone, Validate)]
 #[cfg_attr(test, derive(serde::Serialize))]
 #[serde(rename_all = "kebab-case")]
@@ -278,6 +346,10 @@ where
                 }
             }
 
+            if root.targets.contains(&Target::Client) && !ns.ops.is_empty() {
+                self.gen_client(&ns_ctx, &ns.ops)?;
+            }
+
             ctx_ns.insert(ns.name.clone(), ns_ctx);
         }
 
@@ -298,6 +370,16 @@ where
         def: &Operation,
     ) -> Result<()>;
 
+    /// Client for the operations of a namespace, generated for [`Target::Client`].
+    #[allow(unused)]
+    fn gen_client(
+        &self,
+        state: &WithNsContext<'_, State, Ext, Self>,
+        ops: &BTreeMap<Ident, Operation>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     fn gen_enum(
         &self,
         state: &WithNsContext<'_, State, Ext, Self>,
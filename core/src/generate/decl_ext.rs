@@ -6,7 +6,9 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use crate::{
-    declare::{Builtin, DeclComment, DeclEnumDef, DeclField, DeclMeta, DeclType},
+    declare::{
+        Builtin, DeclComment, DeclEnumDef, DeclField, DeclMeta, DeclTagStyle, DeclTagging, DeclType,
+    },
     generate::{DateTimeLibrary, RustConfig},
 };
 
@@ -161,8 +163,8 @@ impl DeclCommentExt for DeclComment {
             return quote!();
         }
         let comments: Vec<_> = self
-            .comments
-            .iter()
+            .docs()
+            .into_iter()
             .map(|c| quote!(#[doc = #c]))
             .collect();
         quote!(#(#comments)*)
@@ -220,3 +222,24 @@ impl DeclEnumDefExt for DeclEnumDef {
         matches!(&self.enum_def, crate::declare::DeclEnum::String(_))
     }
 }
+
+pub trait DeclTaggingExt {
+    /// The serde container attribute for an explicitly chosen tag style, or `None` to keep the
+    /// generator's default representation.
+    fn serde_attr(&self) -> Option<TokenStream>;
+}
+
+impl DeclTaggingExt for DeclTagging {
+    fn serde_attr(&self) -> Option<TokenStream> {
+        match &self.style {
+            DeclTagStyle::TypeHint => None,
+            DeclTagStyle::External => Some(quote!()),
+            DeclTagStyle::Internal { tag } => Some(quote!(#[serde(tag = #tag)])),
+            DeclTagStyle::Adjacent { tag, content } => {
+                Some(quote!(#[serde(tag = #tag, content = #content)]))
+            },
+            // serde has no numeric tags; index tagging is decoded by shape like untagged.
+            DeclTagStyle::Untagged | DeclTagStyle::Index { .. } => Some(quote!(#[serde(untagged)])),
+        }
+    }
+}
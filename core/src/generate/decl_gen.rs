@@ -12,7 +12,7 @@ use std::{
 
 use crate::{
     declare::{
-        DeclEnumDef, DeclError, DeclNamespace, DeclOneOf, DeclOperation, DeclStruct,
+        DeclConst, DeclEnumDef, DeclError, DeclNamespace, DeclOneOf, DeclOperation, DeclStruct,
         DeclarationBundle, TypeDefinition, TypeRegistryDeclaration,
     },
     generate::{
@@ -31,6 +31,24 @@ type OnCreateDecl<'ns, T, Config, L> = Box<
         + Sync,
 >;
 
+/// A declaration a generator could not express in its target language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    pub namespace: String,
+    /// The item name, or `Item.member` for a single field, argument or variant
+    pub item: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{}::{}: {}", self.namespace, self.item, self.reason)
+    }
+}
+
 pub struct DeclNsContext<'ns, T, Config: ConfigExt, L: LanguageTrait> {
     pub ns: &'ns DeclNamespace,
     pub state: Arc<T>,
@@ -190,6 +208,10 @@ where
         );
 
         if targets.contains(&Target::Types) {
+            for constant in &ns.constants {
+                self.gen_decl_const(&ns_ctx, constant)?;
+            }
+
             for type_def in &ns.types {
                 match type_def {
                     TypeDefinition::Struct(s) => self.gen_decl_struct(&ns_ctx, s)?,
@@ -202,6 +224,14 @@ where
             }
         }
 
+        if targets.contains(&Target::Mock) {
+            for type_def in &ns.types {
+                if let TypeDefinition::Operation(op) = type_def {
+                    self.gen_decl_mock(&ns_ctx, op)?;
+                }
+            }
+        }
+
         for child_ns in ns.namespaces.values() {
             self.gen_namespace(child_ns, state.clone(), opts, mem_flush.clone(), targets)?;
         }
@@ -238,4 +268,17 @@ where
         state: &DeclNsContext<'_, State, Ext, Self>,
         def: &DeclError,
     ) -> Result<()>;
+
+    fn gen_decl_const(
+        &self,
+        state: &DeclNsContext<'_, State, Ext, Self>,
+        def: &DeclConst,
+    ) -> Result<()>;
+
+    /// Test double for an operation, generated for [`Target::Mock`].
+    fn gen_decl_mock(
+        &self,
+        state: &DeclNsContext<'_, State, Ext, Self>,
+        def: &DeclOperation,
+    ) -> Result<()>;
 }
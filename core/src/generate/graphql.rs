@@ -0,0 +1,881 @@
+//! GraphQL SDL generation from parser declaration types.
+//!
+//! Each namespace is written to its own `.graphql` file: structs become object
+//! types, enums become enums and one-ofs become unions. Operations are gathered
+//! into the `Query` and `Mutation` root types of [`SCHEMA_FILE`], next to the
+//! custom scalars the package uses. An operation is a query when it has
+//! `#[graphql(query)]` or its name starts with one of
+//! [`GraphqlConfig::query_prefixes`], and a mutation otherwise.
+//!
+//! GraphQL type names share a single scope, so references are resolved by name
+//! within the package. Structs taken by operation arguments also get an `input`
+//! type suffixed with `Input`, as arguments cannot be object types. Names that
+//! clash with built-in or reserved GraphQL names get a trailing `_`, and anything
+//! without a GraphQL mapping is recorded as an [`Unsupported`] entry and noted in
+//! the emitted file.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use convert_case::Casing;
+use kintsu_parser::lint::reserved::{GRAPHQL_TYPES, GRAPHQL_VALUES};
+
+use crate::{
+    declare::{
+        Builtin, DeclComment, DeclConst, DeclEnum, DeclEnumDef, DeclError, DeclField,
+        DeclGraphqlKind, DeclNamedItemContext, DeclNamespace, DeclOneOf, DeclOperation, DeclStruct,
+        DeclType, TypeDefinition, TypeRegistryDeclaration,
+    },
+    generate::{
+        GenOpts, GraphqlConfig, LanguageTrait, Target,
+        decl_gen::{DeclNsContext, GenerateDecl, Unsupported},
+        files::{FileOrMem, MemFlush, WithFlush},
+    },
+};
+
+/// The file holding the root operation types and custom scalar declarations.
+pub const SCHEMA_FILE: &str = "_schema.graphql";
+
+#[derive(Default)]
+pub struct GraphqlGenerator {
+    unsupported: Mutex<Vec<Unsupported>>,
+}
+
+impl GraphqlGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the SDL of every namespace of `registry`, one file per namespace, and the
+    /// root operation types to [`SCHEMA_FILE`].
+    pub fn generate(
+        &self,
+        registry: &TypeRegistryDeclaration,
+        opts: &GenOpts<GraphqlConfig>,
+        mem_flush: Option<MemFlush>,
+    ) -> crate::generate::Result<()> {
+        self.gen_from_registry(registry, opts, mem_flush, &[Target::Types])
+    }
+
+    /// Every construct skipped so far, in generation order.
+    pub fn unsupported(&self) -> Vec<Unsupported> {
+        self.unsupported.lock().unwrap().clone()
+    }
+
+    fn report(
+        &self,
+        state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        item: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> crate::generate::Result<()> {
+        let entry = Unsupported {
+            namespace: state.ns.name.clone(),
+            item: item.into(),
+            reason: reason.into(),
+        };
+
+        tracing::warn!("graphql: skipping {entry}");
+        let line = format!("# unsupported: {}: {}\n\n", entry.item, entry.reason);
+        self.unsupported.lock().unwrap().push(entry);
+
+        state.with_file_handle(state.ns_file(), |w| write!(w, "{line}"))
+    }
+
+    fn write_schema(
+        &self,
+        state: &GraphqlGenState,
+        opts: &GenOpts<GraphqlConfig>,
+        mem_flush: Option<MemFlush>,
+    ) -> crate::generate::Result<()> {
+        let path = opts.output_dir.join(SCHEMA_FILE);
+        tracing::info!("creating '{}'", path.display());
+
+        if !opts.mem {
+            std::fs::create_dir_all(&opts.output_dir)?;
+        }
+
+        let mut f = FileOrMem::new(path, opts.mem)?;
+        if let Some(mem_flush) = mem_flush {
+            f.with_flush(mem_flush);
+        }
+
+        writeln!(f, "# generated from package `{}`\n", state.package)?;
+
+        for scalar in state.scalars.lock().unwrap().iter() {
+            writeln!(f, "scalar {scalar}\n")?;
+        }
+
+        for (root, fields) in [("Query", &state.queries), ("Mutation", &state.mutations)] {
+            let fields = fields.lock().unwrap();
+            if !fields.is_empty() {
+                let fields: String = fields.values().cloned().collect();
+                writeln!(f, "type {root} {{\n{fields}}}\n")?;
+            }
+        }
+
+        f.flush()?;
+        Ok(())
+    }
+}
+
+impl LanguageTrait for GraphqlGenerator {
+    fn file_case() -> convert_case::Case<'static> {
+        convert_case::Case::Snake
+    }
+
+    fn file_ext() -> &'static str {
+        "graphql"
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct GraphqlGenState {
+    /// The package references are resolved against, in snake case as references name it
+    package: String,
+    /// Every type of the package by name, operations excluded
+    types: BTreeMap<String, TypeDefinition>,
+    /// Structs reachable from operation arguments, which also get an input type
+    inputs: BTreeSet<String>,
+    /// Root fields by operation name
+    queries: Mutex<BTreeMap<String, String>>,
+    mutations: Mutex<BTreeMap<String, String>>,
+    /// Custom scalars referred to by any emitted type
+    scalars: Mutex<BTreeSet<&'static str>>,
+}
+
+impl GraphqlGenState {
+    fn new(registry: &TypeRegistryDeclaration) -> Self {
+        let mut state = Self {
+            package: registry.package.replace('-', "_"),
+            ..Default::default()
+        };
+
+        let mut operations = vec![];
+        for ns in registry.namespaces.values() {
+            visit(ns, &mut |def| {
+                match def {
+                    TypeDefinition::Operation(op) => operations.push(op.clone()),
+                    def => {
+                        state
+                            .types
+                            .entry(def.name().to_string())
+                            .or_insert_with(|| def.clone());
+                    },
+                }
+            });
+        }
+
+        let mut inputs = BTreeSet::new();
+        for arg in operations.iter().flat_map(|op| &op.args) {
+            state.collect_inputs(&arg.ty, &mut inputs);
+        }
+        state.inputs = inputs;
+
+        state
+    }
+
+    fn resolve(
+        &self,
+        reference: &DeclNamedItemContext,
+    ) -> Result<&TypeDefinition, String> {
+        if reference.context.package != self.package {
+            return Err(format!(
+                "`{}` is declared in another package",
+                reference.qualified_path()
+            ));
+        }
+
+        self.types
+            .get(&reference.name)
+            .ok_or_else(|| format!("`{}` is not declared", reference.qualified_path()))
+    }
+
+    fn collect_inputs(
+        &self,
+        ty: &DeclType,
+        inputs: &mut BTreeSet<String>,
+    ) {
+        match ty {
+            DeclType::Optional { inner_type } | DeclType::Paren { inner_type } => {
+                self.collect_inputs(inner_type, inputs)
+            },
+            DeclType::Array { element_type } | DeclType::SizedArray { element_type, .. } => {
+                self.collect_inputs(element_type, inputs)
+            },
+            DeclType::Named { reference } => {
+                match self.resolve(reference) {
+                    Ok(TypeDefinition::Struct(def)) if inputs.insert(def.name.clone()) => {
+                        for field in &def.fields {
+                            self.collect_inputs(&field.ty, inputs);
+                        }
+                    },
+                    Ok(TypeDefinition::TypeAlias(alias)) => {
+                        self.collect_inputs(&alias.target, inputs)
+                    },
+                    _ => {},
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn use_scalar(
+        &self,
+        name: &'static str,
+    ) {
+        if !matches!(name, "Int" | "Float" | "String" | "Boolean" | "ID") {
+            self.scalars.lock().unwrap().insert(name);
+        }
+    }
+
+    /// The type name of `ty` and whether it is nullable, for an argument when `input` is set.
+    fn render(
+        &self,
+        ty: &DeclType,
+        input: bool,
+    ) -> Result<(String, bool), String> {
+        match ty {
+            DeclType::Builtin { ty } => {
+                let name = builtin_type(ty)?;
+                self.use_scalar(name);
+                Ok((name.into(), false))
+            },
+            DeclType::Paren { inner_type } => self.render(inner_type, input),
+            DeclType::Optional { inner_type } => Ok((self.render(inner_type, input)?.0, true)),
+            DeclType::Array { element_type } | DeclType::SizedArray { element_type, .. } => {
+                Ok((
+                    format!("[{}]", self.render_type(element_type, input)?),
+                    false,
+                ))
+            },
+            DeclType::Map { .. } => {
+                self.use_scalar("JSON");
+                Ok(("JSON".into(), false))
+            },
+            DeclType::Named { reference } => {
+                match self.resolve(reference)? {
+                    TypeDefinition::Struct(def) if input => Ok((input_name(&def.name), false)),
+                    TypeDefinition::Struct(DeclStruct { name, .. })
+                    | TypeDefinition::Enum(DeclEnumDef { name, .. }) => {
+                        Ok((type_name(name), false))
+                    },
+                    TypeDefinition::OneOf(_) if input => {
+                        Err("one-ofs cannot be used as input".into())
+                    },
+                    TypeDefinition::OneOf(def) => Ok((type_name(&def.name), false)),
+                    TypeDefinition::TypeAlias(alias) => self.render(&alias.target, input),
+                    TypeDefinition::Error(_) | TypeDefinition::Operation(_) => {
+                        Err(format!("`{}` is not a GraphQL type", reference.name))
+                    },
+                }
+            },
+            // errors surface in the response's `errors` list
+            DeclType::Result { ok_type, .. } if !input => self.render(ok_type, input),
+            DeclType::Result { .. } => Err("result types cannot be used as input".into()),
+            DeclType::TypeExpr { .. } => Err("type expressions are not supported".into()),
+        }
+    }
+
+    fn render_type(
+        &self,
+        ty: &DeclType,
+        input: bool,
+    ) -> Result<String, String> {
+        let (name, nullable) = self.render(ty, input)?;
+        Ok(non_null(name, nullable))
+    }
+
+    fn field_def(
+        &self,
+        field: &DeclField,
+        input: bool,
+    ) -> Result<String, String> {
+        let (ty, nullable) = self.render(&field.ty, input)?;
+        Ok(format!(
+            "{}  {}: {}\n",
+            description(&field.comments, "  "),
+            field_name(&field.name),
+            non_null(ty, nullable || field.optional)
+        ))
+    }
+}
+
+fn visit<'a>(
+    ns: &'a DeclNamespace,
+    f: &mut impl FnMut(&'a TypeDefinition),
+) {
+    for def in &ns.types {
+        f(def);
+    }
+    for child in ns.namespaces.values() {
+        visit(child, f);
+    }
+}
+
+fn non_null(
+    name: String,
+    nullable: bool,
+) -> String {
+    if nullable {
+        name
+    } else {
+        format!("{name}!")
+    }
+}
+
+fn escape(
+    name: &str,
+    reserved: &[&str],
+) -> String {
+    let mut name = name.replace('-', "_");
+    // names starting with `__` belong to introspection
+    if name.starts_with("__") {
+        name = format!("_{}", name.trim_start_matches('_'));
+    }
+    if reserved.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+fn type_name(name: &str) -> String {
+    escape(name, GRAPHQL_TYPES)
+}
+
+fn input_name(name: &str) -> String {
+    type_name(&format!("{name}Input"))
+}
+
+fn field_name(name: &str) -> String {
+    escape(name, &[])
+}
+
+fn enum_value(name: &str) -> String {
+    escape(name, GRAPHQL_VALUES)
+}
+
+fn builtin_type(ty: &Builtin) -> Result<&'static str, String> {
+    // `Int` is a signed 32 bit integer, so wider integers get their own scalars
+    Ok(match ty {
+        Builtin::I8 | Builtin::I16 | Builtin::I32 | Builtin::U8 | Builtin::U16 => "Int",
+        Builtin::I64 | Builtin::U32 => "Int64",
+        Builtin::U64 | Builtin::Usize => "UInt64",
+        Builtin::F16 | Builtin::F32 | Builtin::F64 => "Float",
+        Builtin::Bool => "Boolean",
+        Builtin::Str | Builtin::Base64 => "String",
+        Builtin::DateTime => "DateTime",
+        Builtin::Binary => "Bytes",
+        Builtin::Complex => return Err("`complex` has no GraphQL type".into()),
+        Builtin::Never => return Err("`never` has no GraphQL type".into()),
+    })
+}
+
+fn description(
+    comments: &DeclComment,
+    indent: &str,
+) -> String {
+    let lines: Vec<String> = comments
+        .docs()
+        .into_iter()
+        .map(|line| line.trim().replace("\"\"\"", "\\\"\"\""))
+        .collect();
+
+    match lines.as_slice() {
+        [] => String::new(),
+        [line] if !line.ends_with('"') => format!("{indent}\"\"\"{line}\"\"\"\n"),
+        lines => {
+            let mut out = format!("{indent}\"\"\"\n");
+            for line in lines {
+                out.push_str(&format!("{indent}{line}\n"));
+            }
+            out.push_str(&format!("{indent}\"\"\"\n"));
+            out
+        },
+    }
+}
+
+fn operation_kind(
+    def: &DeclOperation,
+    opts: &GraphqlConfig,
+) -> DeclGraphqlKind {
+    if let Some(kind) = def.graphql {
+        return kind;
+    }
+
+    let name = def.name.to_case(convert_case::Case::Snake);
+    let is_query = opts.query_prefixes.iter().any(|prefix| {
+        name.strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('_'))
+    });
+
+    if is_query {
+        DeclGraphqlKind::Query
+    } else {
+        DeclGraphqlKind::Mutation
+    }
+}
+
+impl GenerateDecl<GraphqlGenState, GraphqlConfig> for GraphqlGenerator {
+    fn on_create_decl(
+        state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        _fname: &Path,
+        f: &mut Box<dyn WithFlush>,
+    ) -> std::io::Result<()> {
+        writeln!(f, "# generated from namespace `{}`\n", state.ns.name)
+    }
+
+    fn new_state_decl(
+        &self,
+        _opts: &GenOpts<GraphqlConfig>,
+    ) -> GraphqlGenState {
+        GraphqlGenState::default()
+    }
+
+    // types resolve against the whole package, and the root types are written once every
+    // namespace has contributed its operations
+    fn gen_from_registry(
+        &self,
+        registry: &TypeRegistryDeclaration,
+        opts: &GenOpts<GraphqlConfig>,
+        mem_flush: Option<MemFlush>,
+        targets: &[Target],
+    ) -> crate::generate::Result<()> {
+        let state = Arc::new(GraphqlGenState::new(registry));
+
+        for ns in registry.namespaces.values() {
+            self.gen_namespace(ns, state.clone(), opts, mem_flush.clone(), targets)?;
+        }
+
+        self.write_schema(&state, opts, mem_flush)
+    }
+
+    fn gen_decl_struct(
+        &self,
+        state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        def: &DeclStruct,
+    ) -> crate::generate::Result<()> {
+        let mut out = String::new();
+
+        let mut kinds = vec![(false, type_name(&def.name), "type")];
+        if state.state.inputs.contains(&def.name) {
+            kinds.push((true, input_name(&def.name), "input"));
+        }
+
+        for (input, name, keyword) in kinds {
+            let mut fields = String::new();
+            for field in &def.fields {
+                match state.state.field_def(field, input) {
+                    Ok(line) => fields.push_str(&line),
+                    Err(reason) => self.report(state, format!("{name}.{}", field.name), reason)?,
+                }
+            }
+
+            if fields.is_empty() {
+                self.report(state, &name, "struct has no representable fields")?;
+                continue;
+            }
+
+            out.push_str(&description(&def.comments, ""));
+            out.push_str(&format!("{keyword} {name} {{\n{fields}}}\n\n"));
+        }
+
+        tracing::info!("writing {} to '{}'", def.name, state.ns_file().display());
+
+        state.with_file_handle(state.ns_file(), |w| write!(w, "{out}"))?;
+        Ok(())
+    }
+
+    fn gen_decl_operation(
+        &self,
+        state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        def: &DeclOperation,
+    ) -> crate::generate::Result<()> {
+        let mut args = vec![];
+        for arg in &def.args {
+            match state.state.render_type(&arg.ty, true) {
+                Ok(ty) => args.push(format!("{}: {ty}", field_name(&arg.name))),
+                Err(reason) => {
+                    return self.report(state, format!("{}.{}", def.name, arg.name), reason);
+                },
+            }
+        }
+
+        let return_type = match state
+            .state
+            .render_type(&def.return_type, false)
+        {
+            Ok(ty) => ty,
+            Err(reason) => return self.report(state, &def.name, reason),
+        };
+
+        let name = field_name(&def.name);
+        let mut field = description(&def.comments, "  ");
+        field.push_str(&format!("  {name}"));
+        if !args.is_empty() {
+            field.push_str(&format!("({})", args.join(", ")));
+        }
+        field.push_str(&format!(": {return_type}\n"));
+
+        let (root, fields) = match operation_kind(def, &state.opts.opts) {
+            DeclGraphqlKind::Query => ("Query", &state.state.queries),
+            DeclGraphqlKind::Mutation => ("Mutation", &state.state.mutations),
+        };
+
+        let mut fields = fields.lock().unwrap();
+        if fields.contains_key(&name) {
+            drop(fields);
+            return self.report(
+                state,
+                &def.name,
+                format!("`{name}` is already a field of `{root}`"),
+            );
+        }
+        fields.insert(name, field);
+        Ok(())
+    }
+
+    fn gen_decl_enum(
+        &self,
+        state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        def: &DeclEnumDef,
+    ) -> crate::generate::Result<()> {
+        let variants: Vec<(&str, &DeclComment)> = match &def.enum_def {
+            DeclEnum::Int(variants) => {
+                variants
+                    .iter()
+                    .map(|var| (var.name.as_str(), &var.comments))
+                    .collect()
+            },
+            DeclEnum::String(variants) => {
+                variants
+                    .iter()
+                    .map(|var| (var.name.as_str(), &var.comments))
+                    .collect()
+            },
+        };
+
+        let mut out = description(&def.comments, "");
+        out.push_str(&format!("enum {} {{\n", type_name(&def.name)));
+        for (name, comments) in variants {
+            out.push_str(&description(comments, "  "));
+            out.push_str(&format!("  {}\n", enum_value(name)));
+        }
+        out.push_str("}\n\n");
+
+        state.with_file_handle(state.ns_file(), |w| write!(w, "{out}"))?;
+        Ok(())
+    }
+
+    fn gen_decl_one_of(
+        &self,
+        state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        def: &DeclOneOf,
+    ) -> crate::generate::Result<()> {
+        let mut members: Vec<String> = vec![];
+        let mut out = String::new();
+
+        for variant in &def.variants {
+            let direct = match &variant.ty {
+                DeclType::Named { reference } => {
+                    match state.state.resolve(reference) {
+                        Ok(TypeDefinition::Struct(def)) => Some(type_name(&def.name)),
+                        _ => None,
+                    }
+                },
+                _ => None,
+            };
+
+            // union members must be distinct object types, so anything else is wrapped
+            match direct {
+                Some(name) if !members.contains(&name) => members.push(name),
+                _ => {
+                    let wrapper = type_name(&format!(
+                        "{}{}",
+                        def.name,
+                        variant
+                            .name
+                            .to_case(convert_case::Case::Pascal)
+                    ));
+                    match state.state.render_type(&variant.ty, false) {
+                        Ok(ty) => {
+                            out.push_str(&description(&variant.comments, ""));
+                            out.push_str(&format!("type {wrapper} {{\n  value: {ty}\n}}\n\n"));
+                            members.push(wrapper);
+                        },
+                        Err(reason) => {
+                            self.report(state, format!("{}.{}", def.name, variant.name), reason)?
+                        },
+                    }
+                },
+            }
+        }
+
+        if members.is_empty() {
+            return self.report(state, &def.name, "one-of has no representable variants");
+        }
+
+        out.push_str(&description(&def.comments, ""));
+        out.push_str(&format!(
+            "union {} = {}\n\n",
+            type_name(&def.name),
+            members.join(" | ")
+        ));
+
+        state.with_file_handle(state.ns_file(), |w| write!(w, "{out}"))?;
+        Ok(())
+    }
+
+    // errors surface in the response's `errors` list rather than as types
+    fn gen_decl_error(
+        &self,
+        _state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        _def: &DeclError,
+    ) -> crate::generate::Result<()> {
+        Ok(())
+    }
+
+    fn gen_decl_const(
+        &self,
+        state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        def: &DeclConst,
+    ) -> crate::generate::Result<()> {
+        self.report(state, &def.name, "constants have no GraphQL mapping")
+    }
+
+    fn gen_decl_mock(
+        &self,
+        state: &DeclNsContext<'_, GraphqlGenState, GraphqlConfig, Self>,
+        def: &DeclOperation,
+    ) -> crate::generate::Result<()> {
+        self.report(
+            state,
+            &def.name,
+            "mock harnesses have no GraphQL equivalent",
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        declare::TypeRegistryDeclaration,
+        generate::{GenOpts, GraphqlConfig, files::MemCollector},
+    };
+
+    use super::{GraphqlGenerator, SCHEMA_FILE};
+
+    fn registry() -> TypeRegistryDeclaration {
+        let builtin = |ty: &str| serde_json::json!({ "type": "builtin", "ty": ty });
+        let named = |name: &str| {
+            serde_json::json!({
+                "type": "named",
+                "reference": {
+                    "context": { "package": "shop", "namespace": ["accounts"] },
+                    "name": name
+                }
+            })
+        };
+        let meta = serde_json::json!({ "version": 1 });
+
+        let accounts = serde_json::json!({
+            "name": "accounts",
+            "constants": [{ "name": "MAX_NAME", "ty": "u32", "value": 64 }],
+            "types": [
+                {
+                    "definition_type": "struct",
+                    "name": "User",
+                    "comments": { "comments": ["a registered user"] },
+                    "meta": meta,
+                    "fields": [
+                        { "name": "id", "ty": builtin("i64") },
+                        { "name": "display-name", "ty": builtin("str") },
+                        { "name": "nickname", "ty": builtin("str"), "optional": true },
+                        { "name": "born", "ty": { "type": "optional", "inner_type": builtin("datetime") } },
+                        { "name": "status", "ty": named("Status") },
+                        { "name": "tags", "ty": { "type": "array", "element_type": builtin("str") } },
+                        { "name": "__meta", "ty": { "type": "map", "key_type": builtin("str"), "value_type": builtin("str") } },
+                        { "name": "angle", "ty": builtin("complex") }
+                    ]
+                },
+                {
+                    "definition_type": "struct",
+                    "name": "Query",
+                    "meta": meta,
+                    "fields": [{ "name": "text", "ty": builtin("str") }]
+                },
+                {
+                    "definition_type": "enum",
+                    "name": "Status",
+                    "meta": meta,
+                    "enum_def": {
+                        "enum_type": "string",
+                        "variants": [
+                            { "name": "Active", "value": "active" },
+                            { "name": "null", "value": "none" }
+                        ]
+                    }
+                },
+                {
+                    "definition_type": "one_of",
+                    "name": "Lookup",
+                    "meta": meta,
+                    "variants": [
+                        { "name": "user", "ty": named("User") },
+                        { "name": "id", "ty": builtin("i64") }
+                    ]
+                },
+                {
+                    "definition_type": "operation",
+                    "name": "get_user",
+                    "meta": meta,
+                    "args": [{ "name": "id", "ty": builtin("i64") }],
+                    "return_type": { "type": "optional", "inner_type": named("User") }
+                },
+                {
+                    "definition_type": "operation",
+                    "name": "create_user",
+                    "meta": meta,
+                    "comments": { "comments": ["registers a user"] },
+                    "args": [{ "name": "user", "ty": named("User") }],
+                    "return_type": builtin("u64")
+                },
+                {
+                    "definition_type": "operation",
+                    "name": "resolve",
+                    "meta": meta,
+                    "graphql": "query",
+                    "args": [{ "name": "query", "ty": named("Query") }],
+                    "return_type": named("Lookup")
+                },
+                {
+                    "definition_type": "operation",
+                    "name": "list_by_lookup",
+                    "meta": meta,
+                    "args": [{ "name": "lookup", "ty": named("Lookup") }],
+                    "return_type": { "type": "array", "element_type": named("User") }
+                }
+            ]
+        });
+
+        let mut registry = TypeRegistryDeclaration::new("shop".into());
+        registry
+            .namespaces
+            .insert("accounts".into(), serde_json::from_value(accounts).unwrap());
+        registry
+    }
+
+    fn generate() -> (String, String, GraphqlGenerator) {
+        let opts = GenOpts {
+            output_dir: "out".into(),
+            opts: GraphqlConfig::default(),
+            mem: true,
+        };
+
+        let generator = GraphqlGenerator::new();
+        let collector = MemCollector::new();
+        generator
+            .generate(&registry(), &opts, Some(collector.mem_flush()))
+            .unwrap();
+
+        let files = collector.files();
+        let read = |name: &str| {
+            files
+                .get(&std::path::Path::new("out").join(name))
+                .map(|data| String::from_utf8_lossy(data).into_owned())
+                .unwrap_or_default()
+        };
+
+        (read("accounts.graphql"), read(SCHEMA_FILE), generator)
+    }
+
+    #[test]
+    fn test_types() {
+        let (out, _, _) = generate();
+
+        for block in [
+            "\"\"\"a registered user\"\"\"\ntype User {\n  id: Int64!\n  display_name: String!\n  nickname: String\n  born: DateTime\n  status: Status!\n  tags: [String!]!\n  _meta: JSON!\n}",
+            "input UserInput {\n  id: Int64!\n",
+            "type Query_ {\n  text: String!\n}",
+            "input QueryInput {\n  text: String!\n}",
+            "enum Status {\n  Active\n  null_\n}",
+            "type LookupId {\n  value: Int64!\n}",
+            "union Lookup = User | LookupId",
+        ] {
+            assert!(out.contains(block), "missing `{block}` in\n{out}");
+        }
+    }
+
+    #[test]
+    fn test_operations() {
+        let (_, schema, _) = generate();
+
+        assert!(
+            schema.starts_with("# generated from package `shop`"),
+            "{schema}"
+        );
+        for block in [
+            "scalar DateTime\n\nscalar Int64\n\nscalar JSON\n\nscalar UInt64\n",
+            "type Query {\n  get_user(id: Int64!): User\n  resolve(query: QueryInput!): Lookup!\n}",
+            "type Mutation {\n  \"\"\"registers a user\"\"\"\n  create_user(user: UserInput!): UInt64!\n}",
+        ] {
+            assert!(schema.contains(block), "missing `{block}` in\n{schema}");
+        }
+    }
+
+    #[test]
+    fn test_query_prefixes() {
+        let op = |name: &str| -> crate::declare::DeclOperation {
+            serde_json::from_value(serde_json::json!({
+                "name": name,
+                "meta": { "version": 1 },
+                "return_type": { "type": "builtin", "ty": "bool" }
+            }))
+            .unwrap()
+        };
+        let opts = GraphqlConfig::default();
+
+        for (name, query) in [
+            ("get", true),
+            ("list_users", true),
+            ("findUser", true),
+            ("getter", false),
+            ("delete_user", false),
+        ] {
+            let kind = super::operation_kind(&op(name), &opts);
+            assert_eq!(
+                kind == crate::declare::DeclGraphqlKind::Query,
+                query,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unsupported_report() {
+        let (out, _, generator) = generate();
+
+        let report: Vec<_> = generator
+            .unsupported()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            report,
+            [
+                "accounts::MAX_NAME: constants have no GraphQL mapping",
+                "accounts::User.angle: `complex` has no GraphQL type",
+                "accounts::UserInput.angle: `complex` has no GraphQL type",
+                "accounts::list_by_lookup.lookup: one-ofs cannot be used as input",
+            ]
+        );
+        assert!(
+            out.contains("# unsupported: User.angle: `complex` has no GraphQL type"),
+            "{out}"
+        );
+    }
+}
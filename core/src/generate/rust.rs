@@ -52,6 +52,10 @@ impl Generate<RustGenState, RustConfig> for RustGenerator {
             keys.append(&mut ctx.ns.enums.keys().collect());
             keys.append(&mut ctx.ns.one_ofs.keys().collect());
             keys.append(&mut ctx.ns.errors.keys().collect());
+            // a namespace of operations alone has no types to register
+            if keys.is_empty() {
+                continue;
+            }
             for it in keys {
                 let created = def_ident(it.clone());
                 tt.extend(quote!(
@@ -150,6 +154,144 @@ impl Generate<RustGenState, RustConfig> for RustGenerator {
         Ok(())
     }
 
+    fn gen_client(
+        &self,
+        state: &WithNsContext<'_, RustGenState, RustConfig, Self>,
+        ops: &BTreeMap<crate::Ident, Operation>,
+    ) -> super::Result<()> {
+        let ns_file = state.ns_file();
+        let mut tt = quote!();
+        let mut methods = quote!();
+
+        let namespace = state.ns.name.to_string();
+
+        for op in ops.values() {
+            let vis = state
+                .opts
+                .opts
+                .vis
+                .as_rust(state, &op.meta.name);
+            let name = op.meta.name.to_string();
+            let pascal = op.meta.ident_as_pascal();
+            let method = ident(name.to_case(convert_case::Case::Snake));
+            let request = ident(format!("{pascal}Request"));
+            let response = ident(format!("{pascal}Response"));
+            let error = ident(format!("{pascal}Error"));
+            let version = op.meta.version.get();
+            let doc_comment = op.meta.doc_comment();
+            let request_doc = format!("Request of [`Client::{method}`]");
+            let response_doc = format!("Response of [`Client::{method}`]");
+            let error_doc = format!("Error of [`Client::{method}`]");
+
+            let request_fields = client_fields(state, &vis, &op.inputs);
+            let response_fields = client_fields(state, &vis, &op.outputs);
+
+            // the declared error is only reachable from fallible operations
+            let declared = op
+                .error
+                .as_ref()
+                .filter(|_| !op.infallible)
+                .map(|err| def_ident(err.clone()));
+
+            let (error_variant, reply_error, on_error) = match &declared {
+                Some(declared) => {
+                    (
+                        quote!(
+                            /// The error declared by the operation
+                            Operation(#declared),
+                        ),
+                        quote!(#declared),
+                        quote!(Ok(Err(err)) => Err(#error::Operation(err)),),
+                    )
+                },
+                None => {
+                    (
+                        quote!(),
+                        quote!(kintsu_sdk::NoError),
+                        quote!(Ok(Err(never)) => match never {},),
+                    )
+                },
+            };
+
+            tt.extend(quote! {
+                #[doc = #request_doc]
+                #[derive(serde::Serialize, serde::Deserialize)]
+                #vis struct #request {
+                    #request_fields
+                }
+
+                #[doc = #response_doc]
+                #[derive(serde::Serialize, serde::Deserialize)]
+                #vis struct #response {
+                    #response_fields
+                }
+
+                #[doc = #error_doc]
+                #vis enum #error<E> {
+                    #error_variant
+                    /// No reply was obtained from the transport
+                    Transport(E),
+                }
+            });
+
+            methods.extend(quote! {
+                #doc_comment
+                #vis async fn #method(
+                    &self,
+                    request: #request,
+                ) -> Result<#response, #error<T::Error>> {
+                    const OPERATION: kintsu_sdk::OperationId = kintsu_sdk::OperationId {
+                        namespace: #namespace,
+                        name: #name,
+                        version: #version,
+                    };
+
+                    match self
+                        .transport
+                        .call::<_, #response, #reply_error>(OPERATION, &request)
+                        .await
+                    {
+                        Ok(Ok(response)) => Ok(response),
+                        #on_error
+                        Err(err) => Err(#error::Transport(err)),
+                    }
+                }
+            });
+        }
+
+        let client_doc = format!("Client for the operations of `{namespace}`");
+        let vis = state
+            .opts
+            .opts
+            .vis
+            .as_rust(state, &crate::Ident::new("Client"));
+
+        tt.extend(quote! {
+            #[doc = #client_doc]
+            #vis struct Client<T> {
+                transport: T,
+            }
+
+            impl<T: kintsu_sdk::Transport> Client<T> {
+                #vis fn new(transport: T) -> Self {
+                    Self { transport }
+                }
+
+                #vis fn transport(&self) -> &T {
+                    &self.transport
+                }
+
+                #methods
+            }
+        });
+
+        state.with_file_handle(ns_file, |w| {
+            write!(w, "{tt}")?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
     fn gen_enum(
         &self,
         state: &WithNsContext<'_, RustGenState, RustConfig, Self>,
@@ -339,6 +481,30 @@ impl Generate<RustGenState, RustConfig> for RustGenerator {
     }
 }
 
+/// Fields of a client request or response. These are plain serde structs, not definitions.
+fn client_fields(
+    state: &WithNsContext<'_, RustGenState, RustConfig, RustGenerator>,
+    vis: &TokenStream,
+    fields: &crate::FieldsList,
+) -> TokenStream {
+    fields
+        .iter()
+        .map(|(field_name, field)| {
+            let name = field_name.to_string();
+            let f = ident(name.to_case(convert_case::Case::Snake));
+            let field = field.unwrap_value();
+            let comment = field.meta.doc_comment();
+            let ty = field.ty.ty(&state.opts.opts);
+            quote!(
+                #[serde(rename = #name)]
+
+                #comment
+                #vis #f: #ty,
+            )
+        })
+        .collect()
+}
+
 fn def_ident(def: crate::Ident) -> Ident {
     ident(
         def.to_string()
@@ -7,10 +7,15 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use crate::{
-    declare::{DeclEnum, DeclEnumDef, DeclError, DeclOneOf, DeclOperation, DeclStruct},
+    declare::{
+        Builtin, DeclArg, DeclConst, DeclConstValue, DeclConstraint, DeclEnum, DeclEnumAlias,
+        DeclEnumDef, DeclError, DeclOneOf, DeclOperation, DeclStruct, DeclType,
+    },
     generate::{
         RustConfig,
-        decl_ext::{DeclCommentExt, DeclFieldExt, DeclMetaExt, DeclTypeExt},
+        decl_ext::{
+            BuiltinExt, DeclCommentExt, DeclFieldExt, DeclMetaExt, DeclTaggingExt, DeclTypeExt,
+        },
         decl_gen::{DeclNsContext, GenerateDecl},
         files::WithFlush,
         rust::{RustGenState, RustGenerator, ident, lit},
@@ -71,10 +76,29 @@ impl GenerateDecl<RustGenState, RustConfig> for RustGenerator {
     fn gen_decl_operation(
         &self,
         state: &DeclNsContext<'_, RustGenState, RustConfig, Self>,
-        _def: &DeclOperation,
+        def: &DeclOperation,
     ) -> crate::generate::Result<()> {
         let ns_file = state.ns_file();
-        let tt = quote!();
+        let mut tt = quote!();
+
+        if let Some(http) = &def.http {
+            let prefix = def
+                .name
+                .to_case(convert_case::Case::UpperSnake);
+            let method_name = ident(format!("{prefix}_HTTP_METHOD"));
+            let path_name = ident(format!("{prefix}_HTTP_PATH"));
+            let method = &http.method;
+            let path = &http.path;
+            let method_doc = format!(" HTTP method bound to `{}`.", def.name);
+            let path_doc = format!(" HTTP path template bound to `{}`.", def.name);
+
+            tt.extend(quote! {
+                #[doc = #method_doc]
+                pub const #method_name: &str = #method;
+                #[doc = #path_doc]
+                pub const #path_name: &str = #path;
+            });
+        }
 
         state.with_file_handle(ns_file, |w| {
             write!(w, "{tt}")?;
@@ -124,10 +148,18 @@ impl GenerateDecl<RustGenState, RustConfig> for RustGenerator {
                         let iden = ident(var.name.to_case(convert_case::Case::Pascal));
                         let value = &var.value;
                         let vdoc = var.comments.doc_comment();
+                        // serde only matches string aliases against string variants
+                        let aliases = var.aliases.iter().filter_map(|alias| {
+                            match alias {
+                                DeclEnumAlias::Str(alias) => Some(quote!(#[serde(alias = #alias)])),
+                                DeclEnumAlias::Int(_) => None,
+                            }
+                        });
                         quote! {
                             #vdoc
                             #[fields(str_value = #value)]
                             #[serde(rename = #value)]
+                            #(#aliases)*
                             #iden,
                         }
                     })
@@ -191,9 +223,14 @@ impl GenerateDecl<RustGenState, RustConfig> for RustGenerator {
             })
             .collect();
 
+        let tag = def
+            .tag
+            .serde_attr()
+            .unwrap_or_else(|| quote!(#[serde(untagged)]));
+
         tt.extend(quote! {
             #[derive(serde::Serialize, serde::Deserialize, kintsu_sdk::OneOf)]
-            #[serde(untagged)]
+            #tag
             #[fields(version = #version)]
             #doc_comment
             pub enum #name {
@@ -231,11 +268,16 @@ impl GenerateDecl<RustGenState, RustConfig> for RustGenerator {
             })
             .collect();
 
+        let tag = def
+            .tag
+            .serde_attr()
+            .unwrap_or_else(|| quote!(#[serde(tag = "type", rename_all = "snake_case")]));
+
         tt.extend(quote! {
             #[derive(serde::Serialize, serde::Deserialize, kintsu_sdk::Error)]
             #[fields(version = #version)]
             #doc_comment
-            #[serde(tag = "type", rename_all = "snake_case")]
+            #tag
             pub enum #name {
                 #variants
             }
@@ -247,4 +289,349 @@ impl GenerateDecl<RustGenState, RustConfig> for RustGenerator {
         })?;
         Ok(())
     }
+
+    fn gen_decl_const(
+        &self,
+        state: &DeclNsContext<'_, RustGenState, RustConfig, Self>,
+        def: &DeclConst,
+    ) -> crate::generate::Result<()> {
+        let ns_file = state.ns_file();
+
+        let name = ident(
+            def.name
+                .to_case(convert_case::Case::UpperSnake),
+        );
+        let doc_comment = def.comments.doc_comment();
+
+        let (ty, value) = match (&def.ty, &def.value) {
+            (Builtin::Str, DeclConstValue::Str(s)) => (quote!(&str), quote!(#s)),
+            (ty, DeclConstValue::Int(v)) => {
+                let v = proc_macro2::Literal::i64_unsuffixed(*v);
+                (ty.to_rust_tokens(&state.opts.opts), quote!(#v))
+            },
+            (ty, DeclConstValue::Str(s)) => (ty.to_rust_tokens(&state.opts.opts), quote!(#s)),
+        };
+
+        let tt = quote! {
+            #doc_comment
+            pub const #name: #ty = #value;
+        };
+
+        state.with_file_handle(ns_file, |w| {
+            write!(w, "{tt}")?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    fn gen_decl_mock(
+        &self,
+        state: &DeclNsContext<'_, RustGenState, RustConfig, Self>,
+        def: &DeclOperation,
+    ) -> crate::generate::Result<()> {
+        let ns_file = state.ns_file();
+        let opts = &state.opts.opts;
+
+        let op_name = &def.name;
+        let method = ident(def.name.to_case(convert_case::Case::Snake));
+        let pascal = def.name.to_case(convert_case::Case::Pascal);
+        let call = ident(format!("{pascal}Call"));
+        let stub = ident(format!("{pascal}Stub"));
+        let mock = ident(format!("Mock{pascal}"));
+        let ret = def.return_type.to_rust_tokens(opts);
+
+        let arg_idents: Vec<_> = def
+            .args
+            .iter()
+            .map(|arg| ident(arg.name.to_case(convert_case::Case::Snake)))
+            .collect();
+        let arg_types: Vec<_> = def
+            .args
+            .iter()
+            .map(|arg| arg.ty.to_rust_tokens(opts))
+            .collect();
+        let checks: TokenStream = def
+            .args
+            .iter()
+            .map(constraint_checks)
+            .collect();
+
+        let call_doc = format!(" Arguments received by one call to `{op_name}`.");
+        let stub_doc = format!(
+            " Typed stub for `{op_name}`, implemented by [`{mock}`] and by hand-written fakes."
+        );
+        let mock_doc = format!(" Programmable [`{stub}`] that records every call it receives.");
+        let count_msg = format!("expected `{op_name}` to be called {{}} time(s), got {{}}");
+        let invalid_msg = format!("`{op_name}` called with arguments violating the schema: {{}}");
+        let unprogrammed_msg =
+            format!("no response programmed for `{op_name}`; call `{mock}::returning` first");
+
+        let tt = quote! {
+            #[doc = #call_doc]
+            #[cfg(any(test, feature = "mock"))]
+            #[derive(serde::Serialize)]
+            pub struct #call {
+                #(pub #arg_idents: #arg_types,)*
+            }
+
+            #[cfg(any(test, feature = "mock"))]
+            impl #call {
+                /// Checks the arguments against the constraints declared in the schema.
+                /// `#[pattern]` is not checked, so generated code needs no regex engine.
+                #[allow(unused_mut)]
+                pub fn validate(&self) -> Result<(), Vec<String>> {
+                    let mut violations: Vec<String> = Vec::new();
+                    #checks
+                    if violations.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(violations)
+                    }
+                }
+            }
+
+            #[doc = #stub_doc]
+            #[cfg(any(test, feature = "mock"))]
+            pub trait #stub {
+                fn #method(&self, #(#arg_idents: #arg_types),*) -> #ret;
+            }
+
+            #[doc = #mock_doc]
+            #[cfg(any(test, feature = "mock"))]
+            #[derive(Default)]
+            #[allow(clippy::type_complexity)]
+            pub struct #mock {
+                responder: std::sync::Mutex<Option<Box<dyn FnMut(&#call) -> #ret + Send>>>,
+                calls: std::sync::Mutex<Vec<#call>>,
+            }
+
+            #[cfg(any(test, feature = "mock"))]
+            impl #mock {
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                /// Answers every following call with the result of `respond`.
+                pub fn returning(
+                    &self,
+                    respond: impl FnMut(&#call) -> #ret + Send + 'static,
+                ) -> &Self {
+                    *self.responder.lock().unwrap() = Some(Box::new(respond));
+                    self
+                }
+
+                /// Calls received so far, oldest first.
+                pub fn calls(&self) -> std::sync::MutexGuard<'_, Vec<#call>> {
+                    self.calls.lock().unwrap()
+                }
+
+                pub fn call_count(&self) -> usize {
+                    self.calls().len()
+                }
+
+                /// Panics unless the operation was called exactly `expected` times.
+                #[track_caller]
+                pub fn assert_called(&self, expected: usize) {
+                    let actual = self.call_count();
+                    assert_eq!(actual, expected, #count_msg, expected, actual);
+                }
+
+                #[track_caller]
+                pub fn assert_not_called(&self) {
+                    self.assert_called(0);
+                }
+            }
+
+            #[cfg(any(test, feature = "mock"))]
+            impl #stub for #mock {
+                fn #method(&self, #(#arg_idents: #arg_types),*) -> #ret {
+                    let call = #call { #(#arg_idents,)* };
+                    if let Err(violations) = call.validate() {
+                        panic!(#invalid_msg, violations.join("; "));
+                    }
+
+                    let response = match self.responder.lock().unwrap().as_mut() {
+                        Some(respond) => respond(&call),
+                        None => panic!(#unprogrammed_msg),
+                    };
+                    self.calls.lock().unwrap().push(call);
+                    response
+                }
+            }
+        };
+
+        tracing::info!("writing mock for {} to '{}'", def.name, ns_file.display());
+
+        state.with_file_handle(ns_file, |w| {
+            write!(w, "{tt}")?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+/// Statements pushing a message onto `violations` for each constraint `arg` breaks.
+fn constraint_checks(arg: &DeclArg) -> TokenStream {
+    if arg.constraints.is_empty() {
+        return quote!();
+    }
+
+    let name = &arg.name;
+    let field = ident(arg.name.to_case(convert_case::Case::Snake));
+    let (ty, optional) = match &arg.ty {
+        DeclType::Optional { inner_type } => (inner_type.as_ref(), true),
+        ty => (ty, false),
+    };
+    let is_float = matches!(
+        ty,
+        DeclType::Builtin {
+            ty: Builtin::F16 | Builtin::F32 | Builtin::F64 | Builtin::Complex
+        }
+    );
+    let len = match ty {
+        DeclType::Builtin { ty: Builtin::Str } => quote!(value.chars().count()),
+        _ => quote!(value.len()),
+    };
+    let bound = |value: i64| {
+        if is_float {
+            let value = value as f64;
+            (quote!((*value as f64)), quote!(#value))
+        } else {
+            let value = value as i128;
+            (quote!((*value as i128)), quote!(#value))
+        }
+    };
+
+    let checks: TokenStream = arg
+        .constraints
+        .iter()
+        .map(|constraint| {
+            match constraint {
+                DeclConstraint::Min { value } => {
+                    let msg = format!("{name} must be at least {value}");
+                    let (actual, min) = bound(*value);
+                    quote!(if #actual < #min { violations.push(#msg.to_string()); })
+                },
+                DeclConstraint::Max { value } => {
+                    let msg = format!("{name} must be at most {value}");
+                    let (actual, max) = bound(*value);
+                    quote!(if #actual > #max { violations.push(#msg.to_string()); })
+                },
+                DeclConstraint::Len { min, max } => {
+                    let min = min.map(|min| {
+                        let msg = format!("{name} must have a length of at least {min}");
+                        let min = min as usize;
+                        quote!(if #len < #min { violations.push(#msg.to_string()); })
+                    });
+                    let max = max.map(|max| {
+                        let msg = format!("{name} must have a length of at most {max}");
+                        let max = max as usize;
+                        quote!(if #len > #max { violations.push(#msg.to_string()); })
+                    });
+                    quote!(#min #max)
+                },
+                DeclConstraint::Pattern { .. } => quote!(),
+            }
+        })
+        .collect();
+
+    if optional {
+        quote!(if let Some(value) = &self.#field { #checks })
+    } else {
+        quote!({ let value = &self.#field; #checks })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        declare::DeclNamespace,
+        generate::{
+            GenOpts, RustConfig, Target, decl_gen::GenerateDecl, files::MemCollector,
+            rust::RustGenState,
+        },
+    };
+
+    use super::RustGenerator;
+
+    fn generate(targets: &[Target]) -> String {
+        let ns: DeclNamespace = serde_json::from_value(serde_json::json!({
+            "name": "accounts",
+            "types": [{
+                "definition_type": "operation",
+                "name": "create_account",
+                "args": [
+                    {
+                        "name": "email",
+                        "ty": { "type": "builtin", "ty": "str" },
+                        "constraints": [{ "kind": "len", "min": 3, "max": 254 }]
+                    },
+                    {
+                        "name": "age",
+                        "ty": {
+                            "type": "optional",
+                            "inner_type": { "type": "builtin", "ty": "u8" }
+                        },
+                        "constraints": [{ "kind": "min", "value": 13 }]
+                    }
+                ],
+                "return_type": { "type": "builtin", "ty": "i64" },
+                "meta": { "version": 1 }
+            }]
+        }))
+        .unwrap();
+
+        let opts = GenOpts {
+            output_dir: "out".into(),
+            opts: RustConfig {
+                vis: Default::default(),
+                time: Default::default(),
+            },
+            mem: true,
+        };
+
+        let collector = MemCollector::new();
+        RustGenerator
+            .gen_namespace(
+                &ns,
+                Arc::new(RustGenState {}),
+                &opts,
+                Some(collector.mem_flush()),
+                targets,
+            )
+            .unwrap();
+
+        let files = collector.files();
+        files
+            .get(std::path::Path::new("out/accounts.rs"))
+            .map(|data| String::from_utf8_lossy(data).into_owned())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_mock_harness() {
+        let out = generate(&[Target::Types, Target::Mock]);
+
+        for item in [
+            "pub struct CreateAccountCall",
+            "pub trait CreateAccountStub",
+            "pub struct MockCreateAccount",
+            "impl CreateAccountStub for MockCreateAccount",
+            "fn create_account (& self , email : String , age : Option < u8 >) -> i64",
+            "pub fn assert_called",
+            "cfg (any (test , feature = \"mock\"))",
+            "\"email must have a length of at most 254\"",
+            "\"age must be at least 13\"",
+        ] {
+            assert!(out.contains(item), "missing `{item}` in {out}");
+        }
+    }
+
+    #[test]
+    fn test_mock_requires_target() {
+        let out = generate(&[Target::Types]);
+        assert!(!out.contains("MockCreateAccount"), "{out}");
+    }
 }
@@ -0,0 +1,767 @@
+//! Experimental SQL DDL generation from parser declaration types.
+//!
+//! Structs become `CREATE TABLE` statements, optional fields become nullable
+//! columns, and arrays, maps, nested structs and one-ofs are stored as JSON
+//! documents. Enums become `CHECK` constraints, or native enum types on
+//! Postgres when [`EnumStyle::Type`] is selected. Anything without a table
+//! mapping is recorded as an [`Unsupported`] entry and noted in the emitted
+//! file instead of being dropped silently.
+
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use convert_case::Casing;
+
+use crate::{
+    declare::{
+        Builtin, DeclConst, DeclEnum, DeclEnumDef, DeclError, DeclField, DeclNamedItemContext,
+        DeclNamespace, DeclOneOf, DeclOperation, DeclStruct, DeclType, DeclarationBundle,
+        TypeDefinition, TypeRegistryDeclaration,
+    },
+    generate::{
+        EnumStyle, GenOpts, LanguageTrait, SqlConfig, SqlDialect, Target,
+        decl_gen::{DeclNsContext, GenerateDecl, Unsupported},
+        files::{MemFlush, WithFlush},
+    },
+};
+
+#[derive(Default)]
+pub struct SqlGenerator {
+    unsupported: Mutex<Vec<Unsupported>>,
+}
+
+impl SqlGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the DDL of every namespace of `registry`, one file per namespace.
+    pub fn generate(
+        &self,
+        registry: &TypeRegistryDeclaration,
+        opts: &GenOpts<SqlConfig>,
+        mem_flush: Option<MemFlush>,
+    ) -> crate::generate::Result<()> {
+        self.gen_from_registry(registry, opts, mem_flush, &[Target::Types])
+    }
+
+    /// Every construct skipped so far, in generation order.
+    pub fn unsupported(&self) -> Vec<Unsupported> {
+        self.unsupported.lock().unwrap().clone()
+    }
+
+    fn report(
+        &self,
+        state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        item: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> crate::generate::Result<()> {
+        let entry = Unsupported {
+            namespace: state.ns.name.clone(),
+            item: item.into(),
+            reason: reason.into(),
+        };
+
+        tracing::warn!("sql: skipping {entry}");
+        let line = format!("-- unsupported: {}: {}\n\n", entry.item, entry.reason);
+        self.unsupported.lock().unwrap().push(entry);
+
+        state.with_file_handle(state.ns_file(), |w| write!(w, "{line}"))
+    }
+}
+
+impl LanguageTrait for SqlGenerator {
+    fn file_case() -> convert_case::Case<'static> {
+        convert_case::Case::Snake
+    }
+
+    fn file_ext() -> &'static str {
+        "sql"
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SqlGenState {
+    /// Every type of the generated packages, by qualified path
+    types: BTreeMap<String, TypeDefinition>,
+}
+
+impl SqlGenState {
+    fn new<'a>(registries: impl IntoIterator<Item = &'a TypeRegistryDeclaration>) -> Self {
+        let mut state = Self::default();
+        for registry in registries {
+            for ns in registry.namespaces.values() {
+                state.index(&registry.package, &mut vec![], ns);
+            }
+        }
+        state
+    }
+
+    fn index(
+        &mut self,
+        package: &str,
+        path: &mut Vec<String>,
+        ns: &DeclNamespace,
+    ) {
+        path.push(ns.name.clone());
+        for def in &ns.types {
+            let mut parts = vec![package.replace('-', "_")];
+            parts.extend(path.iter().cloned());
+            parts.push(def.name().to_string());
+            self.types
+                .insert(parts.join("::"), def.clone());
+        }
+        for child in ns.namespaces.values() {
+            self.index(package, path, child);
+        }
+        path.pop();
+    }
+
+    fn resolve(
+        &self,
+        reference: &DeclNamedItemContext,
+    ) -> Option<&TypeDefinition> {
+        self.types
+            .get(&reference.qualified_path().replace('-', "_"))
+    }
+}
+
+/// A column type plus the values it is restricted to, if any.
+struct Column {
+    ty: String,
+    nullable: bool,
+    allowed: Option<Vec<String>>,
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn quote_str(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn sql_name(name: &str) -> String {
+    name.replace('-', "_")
+        .to_case(convert_case::Case::Snake)
+}
+
+fn uses_enum_type(opts: &SqlConfig) -> bool {
+    opts.dialect == SqlDialect::Postgres && opts.enums == EnumStyle::Type
+}
+
+fn json_type(dialect: SqlDialect) -> &'static str {
+    match dialect {
+        SqlDialect::Postgres => "JSONB",
+        SqlDialect::Sqlite => "TEXT",
+    }
+}
+
+fn builtin_type(
+    ty: &Builtin,
+    dialect: SqlDialect,
+) -> Result<&'static str, String> {
+    Ok(match dialect {
+        SqlDialect::Postgres => {
+            match ty {
+                Builtin::I8 | Builtin::I16 | Builtin::U8 => "SMALLINT",
+                Builtin::I32 | Builtin::U16 => "INTEGER",
+                Builtin::I64 | Builtin::U32 => "BIGINT",
+                Builtin::U64 | Builtin::Usize => "NUMERIC(20, 0)",
+                Builtin::F16 | Builtin::F32 => "REAL",
+                Builtin::F64 | Builtin::Complex => "DOUBLE PRECISION",
+                Builtin::Bool => "BOOLEAN",
+                Builtin::Str | Builtin::Base64 => "TEXT",
+                Builtin::DateTime => "TIMESTAMPTZ",
+                Builtin::Binary => "BYTEA",
+                Builtin::Never => return Err("`never` has no column type".into()),
+            }
+        },
+        // sqlite stores at most a signed 64 bit integer, so u64 values above
+        // i64::MAX do not round trip
+        SqlDialect::Sqlite => {
+            match ty {
+                Builtin::I8
+                | Builtin::I16
+                | Builtin::I32
+                | Builtin::I64
+                | Builtin::U8
+                | Builtin::U16
+                | Builtin::U32
+                | Builtin::U64
+                | Builtin::Usize
+                | Builtin::Bool => "INTEGER",
+                Builtin::F16 | Builtin::F32 | Builtin::F64 | Builtin::Complex => "REAL",
+                Builtin::Str | Builtin::Base64 | Builtin::DateTime => "TEXT",
+                Builtin::Binary => "BLOB",
+                Builtin::Never => return Err("`never` has no column type".into()),
+            }
+        },
+    })
+}
+
+fn enum_column(
+    def: &DeclEnumDef,
+    opts: &SqlConfig,
+) -> Column {
+    match &def.enum_def {
+        DeclEnum::Int(variants) => {
+            Column {
+                ty: builtin_type(&Builtin::U32, opts.dialect)
+                    .unwrap()
+                    .into(),
+                nullable: false,
+                allowed: Some(
+                    variants
+                        .iter()
+                        .map(|var| var.value.to_string())
+                        .collect(),
+                ),
+            }
+        },
+        DeclEnum::String(_) if uses_enum_type(opts) => {
+            Column {
+                ty: quote_ident(&sql_name(&def.name)),
+                nullable: false,
+                allowed: None,
+            }
+        },
+        DeclEnum::String(variants) => {
+            Column {
+                ty: "TEXT".into(),
+                nullable: false,
+                allowed: Some(
+                    variants
+                        .iter()
+                        .map(|var| quote_str(&var.value))
+                        .collect(),
+                ),
+            }
+        },
+    }
+}
+
+fn column(
+    state: &DeclNsContext<'_, SqlGenState, SqlConfig, SqlGenerator>,
+    ty: &DeclType,
+) -> Result<Column, String> {
+    let opts = &state.opts.opts;
+    let plain = |ty: &str| {
+        Column {
+            ty: ty.into(),
+            nullable: false,
+            allowed: None,
+        }
+    };
+
+    match ty {
+        DeclType::Builtin { ty } => Ok(plain(builtin_type(ty, opts.dialect)?)),
+        DeclType::Optional { inner_type } | DeclType::Paren { inner_type } => {
+            let mut col = column(state, inner_type)?;
+            col.nullable |= matches!(ty, DeclType::Optional { .. });
+            Ok(col)
+        },
+        DeclType::Array { .. } | DeclType::SizedArray { .. } | DeclType::Map { .. } => {
+            Ok(plain(json_type(opts.dialect)))
+        },
+        DeclType::Named { reference } => {
+            let def = state
+                .state
+                .resolve(reference)
+                .ok_or_else(|| format!("`{}` is not declared", reference.qualified_path()))?;
+
+            match def {
+                TypeDefinition::Enum(enm) => Ok(enum_column(enm, opts)),
+                TypeDefinition::Struct(_) | TypeDefinition::OneOf(_) => {
+                    Ok(plain(json_type(opts.dialect)))
+                },
+                TypeDefinition::TypeAlias(alias) => column(state, &alias.target),
+                TypeDefinition::Error(_) | TypeDefinition::Operation(_) => {
+                    Err(format!("`{}` cannot be stored in a column", reference.name))
+                },
+            }
+        },
+        DeclType::Result { .. } => Err("result types cannot be stored in a column".into()),
+        DeclType::TypeExpr { .. } => Err("type expressions are not supported".into()),
+    }
+}
+
+fn column_def(
+    field: &DeclField,
+    col: Column,
+) -> String {
+    let name = quote_ident(&sql_name(&field.name));
+    let mut out = format!("{name} {}", col.ty);
+
+    if !(col.nullable || field.optional) {
+        out.push_str(" NOT NULL");
+    }
+
+    if let Some(allowed) = col.allowed {
+        out.push_str(&format!(" CHECK ({name} IN ({}))", allowed.join(", ")));
+    }
+
+    out
+}
+
+impl GenerateDecl<SqlGenState, SqlConfig> for SqlGenerator {
+    fn on_create_decl(
+        state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        _fname: &Path,
+        f: &mut Box<dyn WithFlush>,
+    ) -> std::io::Result<()> {
+        writeln!(f, "-- generated from namespace `{}`\n", state.ns.name)?;
+
+        // enum types are written up front so every table can refer to them
+        if uses_enum_type(&state.opts.opts) {
+            for def in &state.ns.types {
+                if let TypeDefinition::Enum(DeclEnumDef {
+                    name,
+                    enum_def: DeclEnum::String(variants),
+                    ..
+                }) = def
+                {
+                    let values: Vec<_> = variants
+                        .iter()
+                        .map(|var| quote_str(&var.value))
+                        .collect();
+                    writeln!(
+                        f,
+                        "CREATE TYPE {} AS ENUM ({});\n",
+                        quote_ident(&sql_name(name)),
+                        values.join(", ")
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn new_state_decl(
+        &self,
+        _opts: &GenOpts<SqlConfig>,
+    ) -> SqlGenState {
+        SqlGenState::default()
+    }
+
+    // named columns resolve against every package being generated, so dependencies are
+    // indexed before any namespace is written
+    fn gen_from_bundle(
+        &self,
+        bundle: &DeclarationBundle,
+        opts: &GenOpts<SqlConfig>,
+        mem_flush: Option<MemFlush>,
+        targets: &[Target],
+    ) -> crate::generate::Result<()> {
+        let registries: Vec<_> = std::iter::once(&bundle.root)
+            .chain(bundle.dependencies.values())
+            .collect();
+        let state = Arc::new(SqlGenState::new(registries.iter().copied()));
+
+        for ns in registries
+            .iter()
+            .flat_map(|registry| registry.namespaces.values())
+        {
+            self.gen_namespace(ns, state.clone(), opts, mem_flush.clone(), targets)?;
+        }
+
+        Ok(())
+    }
+
+    fn gen_from_registry(
+        &self,
+        registry: &TypeRegistryDeclaration,
+        opts: &GenOpts<SqlConfig>,
+        mem_flush: Option<MemFlush>,
+        targets: &[Target],
+    ) -> crate::generate::Result<()> {
+        let state = Arc::new(SqlGenState::new([registry]));
+
+        for ns in registry.namespaces.values() {
+            self.gen_namespace(ns, state.clone(), opts, mem_flush.clone(), targets)?;
+        }
+
+        Ok(())
+    }
+
+    fn gen_decl_struct(
+        &self,
+        state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        def: &DeclStruct,
+    ) -> crate::generate::Result<()> {
+        let mut columns = vec![];
+
+        for field in &def.fields {
+            match column(state, &field.ty) {
+                Ok(col) => columns.push(column_def(field, col)),
+                Err(reason) => {
+                    self.report(state, format!("{}.{}", def.name, field.name), reason)?
+                },
+            }
+        }
+
+        if columns.is_empty() {
+            return self.report(state, &def.name, "struct has no storable fields");
+        }
+
+        let mut out = String::new();
+        for comment in def.comments.docs() {
+            out.push_str(&format!("-- {}\n", comment.trim()));
+        }
+        out.push_str(&format!(
+            "CREATE TABLE {} (\n    {}\n);\n\n",
+            quote_ident(&sql_name(&def.name)),
+            columns.join(",\n    ")
+        ));
+
+        tracing::info!("writing {} to '{}'", def.name, state.ns_file().display());
+
+        state.with_file_handle(state.ns_file(), |w| write!(w, "{out}"))?;
+        Ok(())
+    }
+
+    fn gen_decl_operation(
+        &self,
+        state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        def: &DeclOperation,
+    ) -> crate::generate::Result<()> {
+        self.report(state, &def.name, "operations have no table mapping")
+    }
+
+    // enums are emitted inline as checks, or as types when the file is created
+    fn gen_decl_enum(
+        &self,
+        _state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        _def: &DeclEnumDef,
+    ) -> crate::generate::Result<()> {
+        Ok(())
+    }
+
+    // one-ofs are stored as json wherever a struct refers to them
+    fn gen_decl_one_of(
+        &self,
+        _state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        _def: &DeclOneOf,
+    ) -> crate::generate::Result<()> {
+        Ok(())
+    }
+
+    fn gen_decl_error(
+        &self,
+        state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        def: &DeclError,
+    ) -> crate::generate::Result<()> {
+        self.report(state, &def.name, "errors have no table mapping")
+    }
+
+    fn gen_decl_const(
+        &self,
+        state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        def: &DeclConst,
+    ) -> crate::generate::Result<()> {
+        self.report(state, &def.name, "constants have no table mapping")
+    }
+
+    fn gen_decl_mock(
+        &self,
+        state: &DeclNsContext<'_, SqlGenState, SqlConfig, Self>,
+        def: &DeclOperation,
+    ) -> crate::generate::Result<()> {
+        self.report(state, &def.name, "mock harnesses have no SQL equivalent")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        declare::{DeclNamespace, TypeRegistryDeclaration},
+        generate::{
+            EnumStyle, GenOpts, SqlConfig, SqlDialect, Target, decl_gen::GenerateDecl,
+            files::MemCollector,
+        },
+    };
+
+    use super::{SqlGenState, SqlGenerator};
+
+    fn namespace() -> DeclNamespace {
+        let builtin = |ty: &str| serde_json::json!({ "type": "builtin", "ty": ty });
+        let named = |name: &str| {
+            serde_json::json!({
+                "type": "named",
+                "reference": {
+                    "context": { "package": "shop", "namespace": ["accounts"] },
+                    "name": name
+                }
+            })
+        };
+        let meta = serde_json::json!({ "version": 1 });
+
+        serde_json::from_value(serde_json::json!({
+            "name": "accounts",
+            "constants": [{
+                "name": "MAX_NAME",
+                "ty": "u32",
+                "value": 64
+            }],
+            "types": [
+                {
+                    "definition_type": "struct",
+                    "name": "UserAccount",
+                    "comments": { "comments": ["a registered user"] },
+                    "meta": meta,
+                    "fields": [
+                        { "name": "id", "ty": builtin("i64") },
+                        { "name": "display-name", "ty": builtin("str") },
+                        { "name": "nickname", "ty": builtin("str"), "optional": true },
+                        {
+                            "name": "born",
+                            "ty": { "type": "optional", "inner_type": builtin("datetime") }
+                        },
+                        { "name": "status", "ty": named("Status") },
+                        { "name": "level", "ty": named("Level") },
+                        { "name": "tags", "ty": { "type": "array", "element_type": builtin("str") } },
+                        { "name": "settings", "ty": named("Settings") },
+                        { "name": "nothing", "ty": builtin("never") }
+                    ]
+                },
+                {
+                    "definition_type": "struct",
+                    "name": "Settings",
+                    "meta": meta,
+                    "fields": [{ "name": "theme", "ty": builtin("str") }]
+                },
+                {
+                    "definition_type": "enum",
+                    "name": "Status",
+                    "meta": meta,
+                    "enum_def": {
+                        "enum_type": "string",
+                        "variants": [
+                            { "name": "Active", "value": "active" },
+                            { "name": "Banned", "value": "it's banned" }
+                        ]
+                    }
+                },
+                {
+                    "definition_type": "enum",
+                    "name": "Level",
+                    "meta": meta,
+                    "enum_def": {
+                        "enum_type": "int",
+                        "variants": [
+                            { "name": "Low", "value": 1 },
+                            { "name": "High", "value": 2 }
+                        ]
+                    }
+                },
+                {
+                    "definition_type": "operation",
+                    "name": "ban_user",
+                    "meta": meta,
+                    "args": [{ "name": "id", "ty": builtin("i64") }],
+                    "return_type": builtin("bool")
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn generate(
+        dialect: SqlDialect,
+        enums: EnumStyle,
+    ) -> (String, SqlGenerator) {
+        let opts = GenOpts {
+            output_dir: "out".into(),
+            opts: SqlConfig { dialect, enums },
+            mem: true,
+        };
+
+        let mut registry = TypeRegistryDeclaration::new("shop".into());
+        registry
+            .namespaces
+            .insert("accounts".into(), namespace());
+
+        let generator = SqlGenerator::new();
+        let collector = MemCollector::new();
+        generator
+            .gen_namespace(
+                &namespace(),
+                Arc::new(SqlGenState::new([&registry])),
+                &opts,
+                Some(collector.mem_flush()),
+                &[Target::Types],
+            )
+            .unwrap();
+
+        let files = collector.files();
+        let out = files
+            .get(std::path::Path::new("out/accounts.sql"))
+            .map(|data| String::from_utf8_lossy(data).into_owned())
+            .unwrap_or_default();
+
+        (out, generator)
+    }
+
+    #[test]
+    fn test_postgres_check_enums() {
+        let (out, _) = generate(SqlDialect::Postgres, EnumStyle::Check);
+
+        for line in [
+            "-- a registered user\nCREATE TABLE \"user_account\" (",
+            "\"id\" BIGINT NOT NULL,",
+            "\"display_name\" TEXT NOT NULL,",
+            "\"nickname\" TEXT,",
+            "\"born\" TIMESTAMPTZ,",
+            "\"status\" TEXT NOT NULL CHECK (\"status\" IN ('active', 'it''s banned')),",
+            "\"level\" BIGINT NOT NULL CHECK (\"level\" IN (1, 2)),",
+            "\"tags\" JSONB NOT NULL,",
+            "\"settings\" JSONB NOT NULL\n);",
+            "CREATE TABLE \"settings\" (\n    \"theme\" TEXT NOT NULL\n);",
+        ] {
+            assert!(out.contains(line), "missing `{line}` in\n{out}");
+        }
+
+        assert!(!out.contains("CREATE TYPE"), "{out}");
+        assert!(!out.contains("\"nothing\" "), "{out}");
+    }
+
+    #[test]
+    fn test_postgres_enum_types() {
+        let (out, _) = generate(SqlDialect::Postgres, EnumStyle::Type);
+
+        let create = out
+            .find("CREATE TYPE \"status\" AS ENUM ('active', 'it''s banned');")
+            .expect(&out);
+        assert!(create < out.find("CREATE TABLE").unwrap(), "{out}");
+        assert!(out.contains("\"status\" \"status\" NOT NULL,"), "{out}");
+        assert!(
+            out.contains("\"level\" BIGINT NOT NULL CHECK (\"level\" IN (1, 2)),"),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn test_sqlite() {
+        let (out, _) = generate(SqlDialect::Sqlite, EnumStyle::Type);
+
+        for line in [
+            "\"id\" INTEGER NOT NULL,",
+            "\"born\" TEXT,",
+            "\"status\" TEXT NOT NULL CHECK (\"status\" IN ('active', 'it''s banned')),",
+            "\"tags\" TEXT NOT NULL,",
+        ] {
+            assert!(out.contains(line), "missing `{line}` in\n{out}");
+        }
+        assert!(!out.contains("CREATE TYPE"), "{out}");
+    }
+
+    #[test]
+    fn test_cross_namespace_reference() {
+        let billing: DeclNamespace = serde_json::from_value(serde_json::json!({
+            "name": "billing",
+            "types": [{
+                "definition_type": "struct",
+                "name": "Invoice",
+                "meta": { "version": 1 },
+                "fields": [
+                    {
+                        "name": "status",
+                        "ty": {
+                            "type": "named",
+                            "reference": {
+                                "context": { "package": "shop", "namespace": ["accounts"] },
+                                "name": "Status"
+                            }
+                        }
+                    },
+                    {
+                        "name": "level",
+                        "ty": {
+                            "type": "named",
+                            "reference": {
+                                "context": { "package": "shop", "namespace": ["billing"] },
+                                "name": "Level"
+                            }
+                        }
+                    }
+                ]
+            }]
+        }))
+        .unwrap();
+
+        let mut registry = TypeRegistryDeclaration::new("shop".into());
+        registry
+            .namespaces
+            .insert("accounts".into(), namespace());
+        registry
+            .namespaces
+            .insert("billing".into(), billing);
+
+        let opts = GenOpts {
+            output_dir: "out".into(),
+            opts: SqlConfig {
+                dialect: SqlDialect::Postgres,
+                enums: EnumStyle::Check,
+            },
+            mem: true,
+        };
+        let generator = SqlGenerator::new();
+        let collector = MemCollector::new();
+        generator
+            .generate(&registry, &opts, Some(collector.mem_flush()))
+            .unwrap();
+
+        let files = collector.files();
+        let out = String::from_utf8_lossy(&files[std::path::Path::new("out/billing.sql")]);
+
+        // `Status` lives in `accounts`, while `billing` declares no `Level` even though
+        // `accounts` does
+        assert!(
+            out.contains(
+                "\"status\" TEXT NOT NULL CHECK (\"status\" IN ('active', 'it''s banned'))"
+            ),
+            "{out}"
+        );
+        assert!(!out.contains("\"level\" "), "{out}");
+        assert_eq!(
+            generator
+                .unsupported()
+                .iter()
+                .map(ToString::to_string)
+                .filter(|entry| entry.starts_with("billing::"))
+                .collect::<Vec<_>>(),
+            ["billing::Invoice.level: `shop::billing::Level` is not declared"]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_report() {
+        let (out, generator) = generate(SqlDialect::Postgres, EnumStyle::Check);
+
+        let report: Vec<_> = generator
+            .unsupported()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            report,
+            [
+                "accounts::MAX_NAME: constants have no table mapping",
+                "accounts::UserAccount.nothing: `never` has no column type",
+                "accounts::ban_user: operations have no table mapping",
+            ]
+        );
+        assert!(
+            out.contains("-- unsupported: UserAccount.nothing: `never` has no column type"),
+            "{out}"
+        );
+    }
+}
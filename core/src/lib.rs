@@ -3,11 +3,17 @@
 pub mod context;
 
 pub mod checks;
+pub mod codec;
 pub mod convert;
 pub mod namespace;
 pub mod ty;
 pub(crate) mod utils;
-use std::marker::PhantomData;
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{LazyLock, RwLock},
+};
 
 pub mod protocol;
 pub use paste::paste;
@@ -19,11 +25,12 @@ pub mod declare {
     //! These types represent the canonical declaration format used for code generation
     //! and schema serialization.
     pub use kintsu_parser::declare::{
-        Builtin, DeclArg, DeclComment, DeclEnum, DeclEnumDef, DeclEnumValueType, DeclError,
-        DeclField, DeclIntVariant, DeclNamedItemContext, DeclNamespace, DeclOneOf,
-        DeclOneOfVariant, DeclOperation, DeclRefContext, DeclStringVariant, DeclStruct, DeclType,
-        DeclTypeAlias, DeclarationBundle, DeclarationVersion, Meta as DeclMeta, TypeDefinition,
-        TypeRegistryDeclaration,
+        Builtin, Coverage, DeclArg, DeclComment, DeclConst, DeclConstValue, DeclConstraint,
+        DeclEnum, DeclEnumAlias, DeclEnumDef, DeclEnumValueType, DeclError, DeclExample, DeclField,
+        DeclGraphqlKind, DeclHttpBinding, DeclIntVariant, DeclNamedItemContext, DeclNamespace,
+        DeclOneOf, DeclOneOfVariant, DeclOperation, DeclRefContext, DeclStringVariant, DeclStruct,
+        DeclTagStyle, DeclTagging, DeclType, DeclTypeAlias, DeclarationBundle, DeclarationVersion,
+        DocCoverage, Meta as DeclMeta, NamespaceCoverage, TypeDefinition, TypeRegistryDeclaration,
     };
 }
 
@@ -46,6 +53,31 @@ pub trait Defined: Sized + Send + Sync {
     fn definition() -> &'static Definitions;
 }
 
+/// Definition of one instantiation of a generic type, built on first use and kept for the
+/// life of the program.
+///
+/// Derived `Defined` impls of generic types call this, since a `static` cannot depend on
+/// type parameters.
+pub fn instance_definition<T: 'static>(
+    build: impl FnOnce() -> Definitions
+) -> &'static Definitions {
+    static INSTANCES: LazyLock<RwLock<HashMap<TypeId, &'static Definitions>>> =
+        LazyLock::new(Default::default);
+
+    let id = TypeId::of::<T>();
+    if let Some(def) = INSTANCES.read().unwrap().get(&id) {
+        return def;
+    }
+
+    // built without holding the lock, as fields may refer to other instantiations
+    let def = build();
+    INSTANCES
+        .write()
+        .unwrap()
+        .entry(id)
+        .or_insert_with(|| Box::leak(Box::new(def)))
+}
+
 /// Trait for types that provide a `TypeDefinition` descriptor.
 ///
 /// This is the new, unified way to get type metadata using parser types.
@@ -93,6 +125,9 @@ pub enum Error {
     #[error("config error: {0}")]
     Config(#[from] ::config::ConfigError),
 
+    #[error("{0}")]
+    Settings(String),
+
     #[error("[{src}] {error}")]
     SourceFile { error: Box<Self>, src: String },
 
@@ -191,6 +226,11 @@ impl From<Error> for kintsu_errors::CompilerError {
                     .unlocated()
                     .build()
             },
+            Error::Settings(e) => {
+                PackageError::manifest_error(e.to_string())
+                    .unlocated()
+                    .build()
+            },
             Error::SourceFile { error, .. } => (*error).into(),
             Error::ContiguousError { ident, desc } => {
                 InternalError::internal(format!("'{ident}' is not contiguous with {desc}"))
@@ -254,7 +294,7 @@ mod test {
 
     use crate::{
         CompoundType, Definitions, Enum, Field, FieldOrRef, FieldsList, Meta, Named, Operation,
-        Struct, Type, Typed, VariantKind, Version, map, ty,
+        Struct, Type, Typed, VariantKind, Version, map,
     };
 
     const BASIC_STRUCT: &str = include_str!("../../samples/basic-struct.toml");
@@ -14,8 +14,19 @@ pub trait OfNamespace {
     const NAMESPACE: &'static str;
 }
 
+/// Implement `OfNamespace` for each listed type. Generic types list their parameters, e.g.
+/// `Page<T>`, with any bounds the type declares (`Page<T: Typed>`).
 #[macro_export]
 macro_rules! namespace {
+    ($ns: literal {
+        $($t: ident $(< $($g: ident $(: $bound: path)?), + >)?), + $(,)?
+    }) => {
+        $(
+            impl $(< $($g $(: $bound)?), + >)? $crate::namespace::OfNamespace for $t $(< $($g), + >)? {
+                const NAMESPACE: &'static str = $ns;
+            }
+        )*
+    };
     ($ns: literal {
         $($t: path), + $(,)?
     }) => {
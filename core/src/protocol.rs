@@ -18,6 +18,36 @@ impl<T: Typed, E: Typed> From<Result<T, E>> for ProtoResult<T, E> {
 
 impl<T: Typed, E: Typed> ProtoResult<T, E> {}
 
+/// Identity of the operation a client call is made against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationId {
+    pub namespace: &'static str,
+    pub name: &'static str,
+    pub version: usize,
+}
+
+/// Error of operations which declare none. It has no values, so a reply can never hold one.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub enum NoError {}
+
+/// Carries the requests of generated clients to a server.
+///
+/// A call resolves to the operation's reply, either its response or its declared error, or
+/// to `Self::Error` when no reply could be obtained.
+pub trait Transport: Send + Sync {
+    type Error;
+
+    fn call<Req, Res, Err>(
+        &self,
+        operation: OperationId,
+        request: &Req,
+    ) -> impl Future<Output = Result<Result<Res, Err>, Self::Error>> + Send
+    where
+        Req: serde::Serialize + Sync,
+        Res: serde::de::DeserializeOwned + Send,
+        Err: serde::de::DeserializeOwned + Send;
+}
+
 // impl<T: Typed, E: Typed, This: Into<E>> From<This> for ProtoResult<T, This> {
 //     fn from(value: This) -> Self {
 
@@ -283,6 +283,46 @@ pub trait Typed {
     fn ty() -> Type;
 }
 
+impl Type {
+    /// PascalCase name of this type as the argument of a generic type, e.g. `[User]` is
+    /// `UserList` and `i32 | never` is `OptionalI32`.
+    pub fn instance_fragment(&self) -> String {
+        match self {
+            Self::CompoundType(ty) => {
+                match ty {
+                    CompoundType::Option { ty } => format!("Optional{}", ty.instance_fragment()),
+                    CompoundType::Array { ty } => format!("{}List", ty.instance_fragment()),
+                    CompoundType::SizedArray { ty, size } => {
+                        format!("{}Array{size}", ty.instance_fragment())
+                    },
+                    CompoundType::Union { lhs, rhs } => {
+                        format!("{}Or{}", lhs.instance_fragment(), rhs.instance_fragment())
+                    },
+                    CompoundType::Enum { to }
+                    | CompoundType::OneOf { to }
+                    | CompoundType::Struct { to } => to.to_string(),
+                }
+            },
+            ty => {
+                ty.to_string()
+                    .to_case(convert_case::Case::Pascal)
+            },
+        }
+    }
+}
+
+/// Name of a generic type instantiated with `args`, e.g. `Page` over `User` is `PageUser`.
+pub fn instance_name(
+    base: &str,
+    args: &[Type],
+) -> Ident {
+    args.iter()
+        .fold(base.to_string(), |name, arg| {
+            name + &arg.instance_fragment()
+        })
+        .into()
+}
+
 macro_rules! transparent {
     ($ty: ty) => {
         impl Typed for $ty {
@@ -27,6 +27,7 @@ pub struct Enum {
     attrs: Vec<Attribute>,
 
     ident: Ident,
+    generics: syn::Generics,
     data: darling::ast::Data<Field, ()>,
 
     version: usize,
@@ -44,6 +45,7 @@ pub fn derive_enum(tokens: TokenStream) -> TokenStream {
     };
 
     call_span!(s.resolve_defs());
+    call_span!(no_generics(&s.generics, "Enum"));
 
     let desc = DescOrPath::resolve_defs(&s.ident, s.describe);
 
@@ -42,6 +42,7 @@ pub struct ErrorDesc {
     pub vis: Visibility,
 
     pub ident: Ident,
+    pub generics: syn::Generics,
     pub data: darling::ast::Data<ErrorVariantDesc, ()>,
 
     pub version: usize,
@@ -60,6 +61,7 @@ pub fn derive_error(tokens: TokenStream) -> TokenStream {
     };
 
     call_span!(s.resolve_defs());
+    call_span!(no_generics(&s.generics, "Error"));
 
     let desc = DescOrPath::resolve_defs(&s.ident, s.describe.clone());
     let desc_value = desc.desc_value;
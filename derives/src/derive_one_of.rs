@@ -1,4 +1,3 @@
-use convert_case::{Case, Casing};
 use darling::{FromDeriveInput, FromField, ast::Fields};
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -35,6 +34,7 @@ pub struct OneOfDesc {
     pub attrs: Vec<Attribute>,
 
     ident: Ident,
+    generics: syn::Generics,
     data: darling::ast::Data<OneOfField, ()>,
 
     version: usize,
@@ -124,48 +124,28 @@ pub fn derive_one_of(tokens: TokenStream) -> TokenStream {
         ));
     }
 
-    let iden = s.ident.clone();
-    let iden_lit = iden.to_string();
     let version = s.version;
-    let iden_def = ident(format!("{iden}_DEF").to_case(Case::UpperSnake));
-
-    let def = quote!(
-        static #iden_def: std::sync::LazyLock<kintsu_sdk::Definitions> = std::sync::LazyLock::new(|| {
-            use kintsu_sdk::{OfNamespace, Typed};
 
+    let def = call_span!(defined_impls(
+        &s.ident,
+        &s.generics,
+        ident("OneOf"),
+        quote!(
             #fields_map
             #fields_def
 
             const VERSION: kintsu_sdk::Version = kintsu_sdk::Version::new(#version);
             kintsu_sdk::Definitions::OneOfV1(kintsu_sdk::OneOf{
                 meta: kintsu_sdk::Meta {
-                    name: #iden_lit.into(),
-                    namespace: #iden::NAMESPACE.into(),
+                    name: name.into(),
+                    namespace: namespace.into(),
                     version: VERSION.into(),
                     description: #desc_value,
                 },
                 variants: kintsu_sdk::Named::new(m),
             })
-        });
-
-
-        impl kintsu_sdk::Typed for #iden {
-            fn ty() -> kintsu_sdk::Type {
-                kintsu_sdk::Type::CompoundType(
-                    kintsu_sdk::CompoundType::OneOf{
-                        to: #iden_lit.into()
-                    }
-                )
-            }
-        }
-
-        impl kintsu_sdk::Defined for #iden {
-            fn definition() -> &'static kintsu_sdk::Definitions {
-                use std::ops::Deref;
-                #iden_def.deref()
-            }
-        }
-    );
+        ),
+    ));
     quote! {
         #desc
 
@@ -1,5 +1,4 @@
 use crate::{resolve_defs, shared::*};
-use convert_case::{Case, Casing};
 use darling::FromDeriveInput;
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -23,6 +22,39 @@ pub struct Field {
 
     #[darling(default)]
     describe: Option<DescOrPath>,
+
+    #[darling(default)]
+    rename: Option<syn::LitStr>,
+
+    #[darling(default)]
+    skip: bool,
+
+    #[darling(default)]
+    flatten: bool,
+}
+
+impl Field {
+    /// Reject modifier combinations which have no meaningful definition.
+    fn check_modifiers(&self) -> syn::Result<()> {
+        let conflict = if self.enm && self.one_of {
+            "cannot have both enum and one_of types. if you are using a literal, use enum. if you are using type discriminants, use one_of."
+        } else if self.skip && (self.rename.is_some() || self.flatten) {
+            "skipped fields cannot also be renamed or flattened"
+        } else if self.flatten && self.rename.is_some() {
+            "flattened fields cannot be renamed; rename the fields of the flattened struct instead"
+        } else if self.flatten && (self.enm || self.one_of) {
+            "only struct fields can be flattened"
+        } else {
+            if let Some(rename) = &self.rename
+                && rename.value().is_empty()
+            {
+                return Err(syn::Error::new(rename.span(), "rename must not be empty"));
+            }
+            return Ok(());
+        };
+
+        Err(syn::Error::new(self.ty.span(), conflict))
+    }
 }
 
 #[derive(darling::FromDeriveInput)]
@@ -31,6 +63,7 @@ pub struct Struct {
     attrs: Vec<Attribute>,
 
     ident: Ident,
+    generics: syn::Generics,
     data: darling::ast::Data<(), Field>,
 
     version: usize,
@@ -71,8 +104,6 @@ pub fn derive_struct(tokens: TokenStream) -> TokenStream {
         let mut m = std::collections::BTreeMap::<_, _>::new();
     );
 
-    let parent_iden = s.ident.clone();
-
     if let Some(bad) = fields.iter().find(|f| f.ident.is_none()) {
         let err: syn::Result<()> = Err(syn::Error::new(
             bad.ty.span(),
@@ -81,20 +112,55 @@ pub fn derive_struct(tokens: TokenStream) -> TokenStream {
         call_span!(err);
     }
 
+    let mut names = std::collections::BTreeMap::new();
     let mut fields_def = quote!();
+    // flattened fields are merged last so they are checked against every direct field
+    let mut flatten_def = quote!();
     for field in &fields {
         let Some(iden) = field.ident.clone() else {
             continue;
         };
 
-        if field.enm && field.one_of {
-            return syn::Error::new(
-                field.ty.span(), "cannot have both enum and one_of types. if you are using a literal, use enum. if you are using type discriminants, use one_of."
-            ).into_compile_error();
+        call_span!(field.check_modifiers());
+
+        if field.skip {
+            continue;
         }
 
         let ty = field.ty.clone();
-        let iden_str = format!("{iden}");
+
+        if field.flatten {
+            let iden_str = iden.to_string();
+            flatten_def.extend(quote!(
+                match <#ty as kintsu_sdk::Defined>::definition() {
+                    kintsu_sdk::Definitions::StructV1(flat) => {
+                        for (name, field) in flat.fields.iter() {
+                            if m.insert(name.clone(), field.clone()).is_some() {
+                                panic!("flattened field `{}` redefines field `{}`", #iden_str, name);
+                            }
+                        }
+                    },
+                    _ => panic!("flattened field `{}` must be a struct", #iden_str),
+                }
+            ));
+            continue;
+        }
+
+        let iden_str = match &field.rename {
+            Some(rename) => rename.value(),
+            None => iden.to_string(),
+        };
+
+        if let Some(prev) = names.insert(iden_str.clone(), iden.clone()) {
+            let err: syn::Result<()> = Err(syn::Error::new(
+                field
+                    .rename
+                    .as_ref()
+                    .map_or(iden.span(), |r| r.span()),
+                format!("field name '{iden_str}' is already used by '{prev}'"),
+            ));
+            call_span!(err);
+        }
 
         let desc = DescOrPath::resolve_defs(&iden, field.describe.clone());
 
@@ -104,10 +170,10 @@ pub fn derive_struct(tokens: TokenStream) -> TokenStream {
         fields_def.extend(quote!(
             #desc
 
-            m.insert(stringify!(#iden).into(), kintsu_sdk::Field{
+            m.insert(#iden_str.into(), kintsu_sdk::Field{
                 meta: kintsu_sdk::Meta {
                     name: Some(#iden_str.into()),
-                    namespace: Some(#parent_iden::NAMESPACE.into()),
+                    namespace: Some(namespace.into()),
                     description: #desc_value,
                     version: None,
                 },
@@ -117,47 +183,29 @@ pub fn derive_struct(tokens: TokenStream) -> TokenStream {
         ));
     }
 
-    let iden = s.ident.clone();
-    let iden_lit = iden.to_string();
     let version = s.version;
-    let iden_def = ident(format!("{iden}_DEF").to_case(Case::UpperSnake));
-
-    let def = quote!(
-        static #iden_def: std::sync::LazyLock<kintsu_sdk::Definitions> = std::sync::LazyLock::new(|| {
-            use kintsu_sdk::{OfNamespace, Typed};
 
+    let def = call_span!(defined_impls(
+        &s.ident,
+        &s.generics,
+        ident("Struct"),
+        quote!(
             #fields_map
             #fields_def
+            #flatten_def
 
             const VERSION: kintsu_sdk::Version = kintsu_sdk::Version::new(#version);
             kintsu_sdk::Definitions::StructV1(kintsu_sdk::Struct{
                 meta: kintsu_sdk::Meta {
-                    name: #iden_lit.into(),
-                    namespace: #iden::NAMESPACE.into(),
+                    name: name.into(),
+                    namespace: namespace.into(),
                     version: VERSION.into(),
                     description: #desc_value,
                 },
                 fields: kintsu_sdk::FieldsList::new(m),
             })
-        });
-
-        impl kintsu_sdk::Typed for #iden {
-            fn ty() -> kintsu_sdk::Type {
-                kintsu_sdk::Type::CompoundType(
-                    kintsu_sdk::CompoundType::Struct{
-                        to: #iden_lit.into()
-                    }
-                )
-            }
-        }
-
-        impl kintsu_sdk::Defined for #iden {
-            fn definition() -> &'static kintsu_sdk::Definitions {
-                use std::ops::Deref;
-                #iden_def.deref()
-            }
-        }
-    );
+        ),
+    ));
     quote! {
         #desc
 
@@ -5,6 +5,7 @@ use quote::{ToTokens, quote};
 use syn::{
     Expr, Ident, Lit, LitStr, Token,
     parse::{Parse, ParseStream},
+    spanned::Spanned,
     token::Token,
 };
 
@@ -190,6 +191,129 @@ pub fn assign_maybe_docs(
     Ok(())
 }
 
+/// Reject generic parameters on derives whose definitions cannot vary with them.
+pub fn no_generics(
+    generics: &syn::Generics,
+    derive: &str,
+) -> syn::Result<()> {
+    match generics.params.first() {
+        Some(param) => {
+            Err(syn::Error::new(
+                param.span(),
+                format!("#[derive({derive})] does not support generic parameters"),
+            ))
+        },
+        None => Ok(()),
+    }
+}
+
+/// `Typed` and `Defined` impls for a derived struct or one_of. `build` evaluates to the
+/// `Definitions` of the type with `name` and `namespace` bound, and `kind` is the
+/// `CompoundType` variant referring to it.
+///
+/// Non-generic types build their definition once into a static. Generic types build one
+/// per instantiation, named after their type arguments (see `kintsu_sdk::instance_name`).
+pub fn defined_impls(
+    iden: &Ident,
+    generics: &syn::Generics,
+    kind: Ident,
+    build: TokenStream,
+) -> syn::Result<TokenStream> {
+    let iden_lit = iden.to_string();
+
+    if generics.params.is_empty() {
+        let iden_def = ident(format!("{iden}_DEF").to_case(Case::UpperSnake));
+        return Ok(quote!(
+            static #iden_def: std::sync::LazyLock<kintsu_sdk::Definitions> = std::sync::LazyLock::new(|| {
+                use kintsu_sdk::{OfNamespace, Typed};
+
+                let name = #iden_lit;
+                let namespace = #iden::NAMESPACE;
+                #build
+            });
+
+            impl kintsu_sdk::Typed for #iden {
+                fn ty() -> kintsu_sdk::Type {
+                    kintsu_sdk::Type::CompoundType(
+                        kintsu_sdk::CompoundType::#kind{
+                            to: #iden_lit.into()
+                        }
+                    )
+                }
+            }
+
+            impl kintsu_sdk::Defined for #iden {
+                fn definition() -> &'static kintsu_sdk::Definitions {
+                    use std::ops::Deref;
+                    #iden_def.deref()
+                }
+            }
+        ));
+    }
+
+    // instantiations are keyed by `TypeId` and named after their type arguments, so neither
+    // borrowed nor const parameters can be told apart
+    let mut params = vec![];
+    for param in &generics.params {
+        match param {
+            syn::GenericParam::Type(ty) => params.push(ty.ident.clone()),
+            param => {
+                return Err(syn::Error::new(
+                    param.span(),
+                    "only type parameters are supported on derived types",
+                ));
+            },
+        }
+    }
+
+    let mut typed_generics = generics.clone();
+    let where_clause = typed_generics.make_where_clause();
+    for param in &params {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#param: kintsu_sdk::Typed));
+    }
+    let mut defined_generics = typed_generics.clone();
+    let where_clause = defined_generics.make_where_clause();
+    where_clause
+        .predicates
+        .push(syn::parse_quote!(Self: kintsu_sdk::OfNamespace));
+    for param in &params {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#param: Send + Sync + 'static));
+    }
+
+    let (impl_generics, ty_generics, typed_where) = typed_generics.split_for_impl();
+    let (_, _, defined_where) = defined_generics.split_for_impl();
+    let name =
+        quote!(kintsu_sdk::instance_name(#iden_lit, &[#(<#params as kintsu_sdk::Typed>::ty()),*]));
+
+    Ok(quote!(
+        impl #impl_generics kintsu_sdk::Typed for #iden #ty_generics #typed_where {
+            fn ty() -> kintsu_sdk::Type {
+                kintsu_sdk::Type::CompoundType(
+                    kintsu_sdk::CompoundType::#kind{
+                        to: #name
+                    }
+                )
+            }
+        }
+
+        impl #impl_generics kintsu_sdk::Defined for #iden #ty_generics #defined_where {
+            fn definition() -> &'static kintsu_sdk::Definitions {
+                kintsu_sdk::instance_definition::<Self>(|| {
+                    use kintsu_sdk::{OfNamespace, Typed};
+
+                    let name = #name;
+                    let namespace = <Self as kintsu_sdk::OfNamespace>::NAMESPACE;
+                    #build
+                })
+            }
+        }
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use syn::Attribute;
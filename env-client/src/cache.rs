@@ -0,0 +1,147 @@
+//! Responses the registry tagged with an `ETag`, kept so repeated reads are revalidated with
+//! `If-None-Match` instead of fetched again.
+
+use std::collections::{HashMap, VecDeque};
+
+/// Responses kept by a client unless configured otherwise.
+pub const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+#[derive(Clone)]
+struct Cached {
+    etag: reqwest::header::HeaderValue,
+    body: bytes::Bytes,
+}
+
+/// The most recently stored responses up to a fixed number, keyed by url.
+pub(crate) struct ConditionalCache {
+    capacity: usize,
+    entries: std::sync::Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    by_url: HashMap<url::Url, Cached>,
+    /// Urls from least to most recently stored
+    order: VecDeque<url::Url>,
+}
+
+impl ConditionalCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Default::default(),
+        }
+    }
+
+    /// Makes `req` conditional on the cached response to it, returning that response's body
+    /// for when the registry answers `304 Not Modified`.
+    pub(crate) fn revalidate(
+        &self,
+        req: &mut reqwest::Request,
+    ) -> Option<bytes::Bytes> {
+        if *req.method() != reqwest::Method::GET {
+            return None;
+        }
+
+        let cached = self
+            .entries
+            .lock()
+            .unwrap()
+            .by_url
+            .get(req.url())
+            .cloned()?;
+        req.headers_mut()
+            .insert(reqwest::header::IF_NONE_MATCH, cached.etag);
+        Some(cached.body)
+    }
+
+    /// Keeps the response to a `GET` of `url` if the registry tagged it.
+    pub(crate) fn store(
+        &self,
+        url: &url::Url,
+        headers: &reqwest::header::HeaderMap,
+        body: &bytes::Bytes,
+    ) {
+        let Some(etag) = headers.get(reqwest::header::ETAG) else {
+            return;
+        };
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let cached = Cached {
+            etag: etag.clone(),
+            body: body.clone(),
+        };
+        if entries
+            .by_url
+            .insert(url.clone(), cached)
+            .is_some()
+        {
+            entries.order.retain(|stored| stored != url);
+        }
+        entries.order.push_back(url.clone());
+
+        while entries.order.len() > self.capacity {
+            if let Some(evicted) = entries.order.pop_front() {
+                entries.by_url.remove(&evicted);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tagged(etag: &str) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::ETAG, etag.parse().unwrap());
+        headers
+    }
+
+    fn get(url: &url::Url) -> reqwest::Request {
+        reqwest::Request::new(reqwest::Method::GET, url.clone())
+    }
+
+    #[test]
+    fn evicts_the_oldest_response() {
+        let cache = ConditionalCache::new(2);
+        let urls: Vec<url::Url> = (0..3)
+            .map(|i| {
+                format!("https://registry.example.com/{i}")
+                    .parse()
+                    .unwrap()
+            })
+            .collect();
+
+        for url in &urls {
+            cache.store(url, &tagged("\"a\""), &bytes::Bytes::from_static(b"body"));
+        }
+
+        assert!(
+            cache
+                .revalidate(&mut get(&urls[0]))
+                .is_none()
+        );
+
+        let mut request = get(&urls[2]);
+        assert_eq!(
+            cache.revalidate(&mut request).unwrap(),
+            bytes::Bytes::from_static(b"body")
+        );
+        assert_eq!(request.headers()[reqwest::header::IF_NONE_MATCH], "\"a\"");
+    }
+
+    #[test]
+    fn keeps_only_tagged_responses() {
+        let cache = ConditionalCache::new(2);
+        let url: url::Url = "https://registry.example.com/a"
+            .parse()
+            .unwrap();
+
+        cache.store(&url, &Default::default(), &bytes::Bytes::new());
+        assert!(cache.revalidate(&mut get(&url)).is_none());
+    }
+}
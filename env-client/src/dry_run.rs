@@ -0,0 +1,230 @@
+//! Dry runs of mutating registry requests.
+//!
+//! A client in dry-run mode builds, validates and authenticates every mutating request
+//! exactly as it would send it, then returns a [`DryRun`] describing the request instead
+//! of performing it. Credentials are redacted from the description.
+
+use std::fmt::Display;
+
+const REDACTED: &str = "<redacted>";
+
+/// Longest string value shown verbatim in a summary, e.g. file contents of a publish
+const MAX_SUMMARY_STRING: usize = 120;
+
+/// Body keys whose values are never shown
+const SENSITIVE_KEYS: &[&str] = &["key", "token", "secret", "password"];
+
+/// The outcome of a mutating client operation.
+#[derive(Debug)]
+pub enum Mutation<T> {
+    Performed(T),
+    DryRun(DryRun),
+}
+
+impl<T> Mutation<T> {
+    pub fn performed(self) -> Option<T> {
+        match self {
+            Self::Performed(value) => Some(value),
+            Self::DryRun(_) => None,
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        matches!(self, Self::DryRun(_))
+    }
+}
+
+/// A fully built request which was not sent.
+#[derive(Debug, Clone)]
+pub struct DryRun {
+    pub method: reqwest::Method,
+    pub url: url::Url,
+    /// Request headers, with credentials redacted
+    pub headers: Vec<(String, String)>,
+    /// The serialized body, with sensitive values redacted
+    pub body: Option<serde_json::Value>,
+    /// Size of the serialized body in bytes
+    pub body_len: usize,
+}
+
+impl DryRun {
+    pub(crate) fn from_request(request: &reqwest::Request) -> Result<Self, crate::Error> {
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = if name == reqwest::header::AUTHORIZATION {
+                    match value.to_str() {
+                        Ok(v) if v.starts_with("Bearer ") => format!("Bearer {REDACTED}"),
+                        _ => REDACTED.to_string(),
+                    }
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+
+        let bytes = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .unwrap_or_default();
+
+        let body = if bytes.is_empty() {
+            None
+        } else {
+            let mut value: serde_json::Value = serde_json::from_slice(bytes)?;
+            redact(&mut value);
+            Some(value)
+        };
+
+        Ok(Self {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers,
+            body,
+            body_len: bytes.len(),
+        })
+    }
+
+    /// The body with long strings collapsed to their size.
+    pub fn body_summary(&self) -> Option<serde_json::Value> {
+        let mut body = self.body.clone()?;
+        summarize(&mut body);
+        Some(body)
+    }
+}
+
+impl Display for DryRun {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        writeln!(f, "[dry run] {} {}", self.method, self.url)?;
+        for (name, value) in &self.headers {
+            writeln!(f, "  {name}: {value}")?;
+        }
+
+        if let Some(body) = self.body_summary() {
+            writeln!(f, "  body ({} bytes):", self.body_len)?;
+            let pretty = serde_json::to_string_pretty(&body).map_err(|_| std::fmt::Error)?;
+            for line in pretty.lines() {
+                writeln!(f, "    {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn redact(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if SENSITIVE_KEYS.contains(&key.to_ascii_lowercase().as_str()) {
+                    *value = serde_json::Value::String(REDACTED.into());
+                } else {
+                    redact(value);
+                }
+            }
+        },
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {},
+    }
+}
+
+fn summarize(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) if text.len() > MAX_SUMMARY_STRING => {
+            *text = format!("<{} bytes>", text.len());
+        },
+        serde_json::Value::Object(map) => map.values_mut().for_each(summarize),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(summarize),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request(body: serde_json::Value) -> reqwest::Request {
+        let mut request = reqwest::Request::new(
+            reqwest::Method::POST,
+            "https://registry.example/auth/token"
+                .parse()
+                .unwrap(),
+        );
+        request.headers_mut().insert(
+            reqwest::header::AUTHORIZATION,
+            "Bearer kintsu_secret".parse().unwrap(),
+        );
+        *request.body_mut() = Some(serde_json::to_vec(&body).unwrap().into());
+        request
+    }
+
+    #[test]
+    fn redacts_credentials() {
+        let plan = DryRun::from_request(&request(serde_json::json!({
+            "description": "ci",
+            "nested": { "Token": "abc" },
+        })))
+        .unwrap();
+
+        let summary = plan.to_string();
+        assert!(!summary.contains("kintsu_secret"), "{summary}");
+        assert!(!summary.contains("abc"), "{summary}");
+        assert!(
+            summary.contains("authorization: Bearer <redacted>"),
+            "{summary}"
+        );
+        assert!(summary.contains("\"description\": \"ci\""), "{summary}");
+    }
+
+    #[test]
+    fn summarizes_large_values() {
+        let contents = "a".repeat(MAX_SUMMARY_STRING + 1);
+        let plan = DryRun::from_request(&request(serde_json::json!({
+            "package_data": { "schema/lib.ks": contents },
+        })))
+        .unwrap();
+
+        let summary = plan.to_string();
+        assert!(
+            summary.contains(&format!(
+                "\"schema/lib.ks\": \"<{} bytes>\"",
+                contents.len()
+            )),
+            "{summary}"
+        );
+        // the plan keeps the full body for callers which inspect it
+        assert_eq!(
+            plan.body.unwrap()["package_data"]["schema/lib.ks"],
+            contents.as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn client_does_not_send_in_dry_run() {
+        // nothing listens on the discard port, so a sent request would fail
+        let client = crate::RegistryClient::new(
+            "http://127.0.0.1:9",
+            Some(secrecy::SecretString::from("kintsu_secret")),
+        )
+        .unwrap()
+        .with_dry_run(true);
+
+        let Mutation::DryRun(plan) = client.revoke_token(7).await.unwrap() else {
+            panic!("expected a dry run");
+        };
+        assert_eq!(plan.method, reqwest::Method::DELETE);
+        assert_eq!(plan.url.path(), "/auth/tokens/7");
+
+        let unauthenticated = crate::RegistryClient::new("http://127.0.0.1:9", None)
+            .unwrap()
+            .with_dry_run(true);
+        assert!(matches!(
+            unauthenticated.revoke_token(7).await,
+            Err(crate::Error::NoAuth)
+        ));
+    }
+}
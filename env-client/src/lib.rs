@@ -1,6 +1,15 @@
 #![allow(clippy::result_large_err)]
 
+mod cache;
+mod dry_run;
+mod retry;
+pub mod workspace;
+
+pub use cache::DEFAULT_CACHE_CAPACITY;
+pub use dry_run::{DryRun, Mutation};
+pub use retry::RetryPolicy;
 use secrecy::ExposeSecret;
+use validator::Validate;
 
 use kintsu_registry_core::ErrorResponse;
 
@@ -20,6 +29,43 @@ pub enum Error {
     Validation(#[from] validator::ValidationErrors),
     #[error("{0}")]
     Fs(#[from] kintsu_fs::Error),
+    #[error(
+        "Invalid package data: {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    Packaging(Vec<kintsu_registry_core::PackagingError>),
+    #[error("workspace members form a dependency cycle: {}", .0.join(" -> "))]
+    WorkspaceCycle(Vec<String>),
+    #[error("workspace member '{0}' is declared more than once")]
+    DuplicateMember(String),
+    #[error("{0}")]
+    Declarations(#[from] kintsu_parser::declare::encoding::EncodingError),
+    #[error(
+        "{artifact} of {package} does not match its checksum (expected {expected}, got {actual})"
+    )]
+    ChecksumMismatch {
+        package: String,
+        artifact: &'static str,
+        expected: String,
+        actual: String,
+    },
+    #[error("{package} was not resolvable after {waited:?}")]
+    NotResolvable {
+        package: String,
+        waited: std::time::Duration,
+    },
+    #[error(
+        "failed to publish workspace member {package} (yanked: [{}], not yanked: [{}]): {source}",
+        yanked.join(", "),
+        not_yanked.join(", ")
+    )]
+    WorkspacePublish {
+        package: String,
+        #[source]
+        source: Box<Error>,
+        yanked: Vec<String>,
+        not_yanked: Vec<String>,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -29,11 +75,17 @@ pub enum ErrorOrResponseError {
     #[error("{0:?}")]
     ResponseError(ErrorResponse),
 
-    #[error("{}: {source:?}", status.as_u16())]
+    #[error(
+        "{}: {source:?}{}",
+        status.as_u16(),
+        request_id.as_ref().map(|id| format!(" (request id: {id})")).unwrap_or_default()
+    )]
     WithStatus {
         #[source]
         source: Box<ErrorOrResponseError>,
         status: reqwest::StatusCode,
+        /// Id the registry logged the request under, for reporting the failure
+        request_id: Option<String>,
     },
 }
 
@@ -41,35 +93,163 @@ impl ErrorOrResponseError {
     pub fn with_status(
         self,
         status: reqwest::StatusCode,
+        request_id: Option<String>,
     ) -> Self {
         Self::WithStatus {
             source: Box::new(self),
             status,
+            request_id,
+        }
+    }
+
+    /// Id of the failed request, when the registry reported one.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Self::WithStatus {
+                request_id: Some(request_id),
+                ..
+            } => Some(request_id),
+            Self::WithStatus { source, .. } => source.request_id(),
+            Self::ResponseError(response) => response.request_id.as_deref(),
+            Self::ErrorString(_) => None,
         }
     }
 }
 
+/// Packages whose serialized data is larger than this many bytes are uploaded directly to
+/// package storage instead of through the registry API, when the registry supports it.
+pub const STAGED_PUBLISH_THRESHOLD: usize = 1024 * 1024;
+
+/// Time allowed for a whole request, including uploading package data.
+pub const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Time allowed to connect to the registry.
+pub const DEFAULT_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 pub struct RegistryClient {
     client: reqwest::Client,
     base_url: url::Url,
     token: Option<secrecy::SecretString>,
+    dry_run: bool,
+    retry: RetryPolicy,
+    mirrors: Vec<url::Url>,
+    cache: cache::ConditionalCache,
 }
 
+/// A package fetched by [`RegistryClient::fetch_package`].
+pub struct FetchedPackage {
+    pub source: kintsu_fs::memory::MemoryFileSystem,
+    pub declarations: kintsu_parser::declare::DeclarationVersion,
+    /// Checksum of the source, as published
+    pub checksum: String,
+    pub locked_source: kintsu_manifests::lock::LockedSource,
+}
+
+#[bon::bon]
 impl RegistryClient {
+    /// A client for the registry at `base_url` with the default timeouts, pooling and retries.
     pub fn new(
         base_url: &str,
         token: Option<secrecy::SecretString>,
+    ) -> Result<Self, Error> {
+        Self::builder(base_url)
+            .maybe_token(token)
+            .build()
+    }
+
+    /// A client for a configured registry, failing over to its mirrors.
+    pub fn for_registry(
+        endpoints: &kintsu_manifests::registries::RegistryEndpoints,
+        token: Option<secrecy::SecretString>,
+    ) -> Result<Self, Error> {
+        Self::builder(&endpoints.url)
+            .maybe_token(token)
+            .mirrors(endpoints.mirrors.clone())
+            .build()
+    }
+
+    /// Configures a client for the registry at `base_url`.
+    #[builder(start_fn = builder, finish_fn = build)]
+    pub fn with_options(
+        #[builder(start_fn)] base_url: &str,
+        token: Option<secrecy::SecretString>,
+        #[builder(default)] dry_run: bool,
+        #[builder(default)] retry: RetryPolicy,
+        /// Base urls of read-only mirrors, tried in order when the registry is unavailable
+        #[builder(default)]
+        mirrors: Vec<String>,
+        #[builder(default = DEFAULT_TIMEOUT)] timeout: std::time::Duration,
+        #[builder(default = DEFAULT_CONNECT_TIMEOUT)] connect_timeout: std::time::Duration,
+        /// Idle connections kept open to each host, unlimited by default
+        pool_max_idle_per_host: Option<usize>,
+        /// How long an idle connection is kept open
+        pool_idle_timeout: Option<std::time::Duration>,
+        /// Proxy requests are sent through, in addition to the proxies set in the environment
+        proxy: Option<reqwest::Proxy>,
+        /// Ignore proxies set in the environment
+        #[builder(default)]
+        no_proxy: bool,
+        /// Certificates trusted in addition to the built in roots, e.g. a private CA
+        #[builder(default)]
+        root_certificates: Vec<reqwest::Certificate>,
+        /// Trust the built in root certificates
+        #[builder(default = true)]
+        built_in_root_certs: bool,
+        /// Responses kept to revalidate repeated reads with, 0 disables revalidation
+        #[builder(default = DEFAULT_CACHE_CAPACITY)]
+        cache_capacity: usize,
     ) -> Result<Self, Error> {
         let base_url = url::Url::parse(base_url)?;
-        let client = reqwest::Client::new();
+        let mirrors = mirrors
+            .iter()
+            .map(|mirror| url::Url::parse(mirror))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut client = reqwest::Client::builder()
+            .user_agent(kintsu_registry_core::client_user_agent())
+            .timeout(timeout)
+            .connect_timeout(connect_timeout)
+            .pool_idle_timeout(pool_idle_timeout)
+            .tls_built_in_root_certs(built_in_root_certs);
+
+        if let Some(max_idle) = pool_max_idle_per_host {
+            client = client.pool_max_idle_per_host(max_idle);
+        }
+        if no_proxy {
+            client = client.no_proxy();
+        }
+        if let Some(proxy) = proxy {
+            client = client.proxy(proxy);
+        }
+        for certificate in root_certificates {
+            client = client.add_root_certificate(certificate);
+        }
 
         Ok(Self {
-            client,
+            client: client.build()?,
             base_url,
             token,
+            dry_run,
+            retry,
+            mirrors,
+            cache: cache::ConditionalCache::new(cache_capacity),
         })
     }
 
+    /// In dry-run mode, mutating operations print and return the request they would send
+    /// instead of sending it.
+    pub fn with_dry_run(
+        mut self,
+        dry_run: bool,
+    ) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
     pub fn url(
         &self,
         path: &str,
@@ -77,20 +257,156 @@ impl RegistryClient {
         self.base_url.join(path).unwrap()
     }
 
+    /// The url of `path` under package `name`, e.g. `/{version}/files`.
+    fn package_url(
+        &self,
+        name: &str,
+        path: &str,
+    ) -> url::Url {
+        self.url(&format!("/package/{}{path}", encode_package_name(name)))
+    }
+
+    /// Sends `req` tagged with a fresh request id, which the registry logs and audits it
+    /// under. Idempotent requests are retried under the same id by the client's
+    /// [`RetryPolicy`], and unauthenticated reads fail over to the client's mirrors while the
+    /// registry is unavailable.
+    async fn execute(
+        &self,
+        mut req: reqwest::Request,
+    ) -> Result<reqwest::Response, Error> {
+        let request_id = kintsu_registry_core::new_request_id();
+        tracing::debug!("{} {} (request id: {request_id})", req.method(), req.url());
+
+        req.headers_mut().insert(
+            kintsu_registry_core::REQUEST_ID_HEADER,
+            request_id.parse().unwrap(),
+        );
+
+        if !self.can_fail_over(&req) {
+            return self.send(req, &request_id).await;
+        }
+
+        let mut outcome = self
+            .send(req.try_clone().unwrap(), &request_id)
+            .await;
+        for mirror in &self.mirrors {
+            if !is_unavailable(&outcome) {
+                break;
+            }
+
+            let mut mirrored = req.try_clone().unwrap();
+            *mirrored.url_mut() = mirror.join(&req.url()[url::Position::BeforePath..])?;
+            tracing::warn!(
+                "{} is unavailable, trying mirror {mirror} (request id: {request_id})",
+                self.base_url
+            );
+            outcome = self.send(mirrored, &request_id).await;
+        }
+
+        outcome
+    }
+
+    /// Mirrors are read-only and are not sent credentials.
+    fn can_fail_over(
+        &self,
+        req: &reqwest::Request,
+    ) -> bool {
+        !self.mirrors.is_empty()
+            && matches!(*req.method(), reqwest::Method::GET | reqwest::Method::HEAD)
+            && !req
+                .headers()
+                .contains_key(reqwest::header::AUTHORIZATION)
+            && req.try_clone().is_some()
+    }
+
+    /// Sends `req` to the endpoint it names, retrying as the [`RetryPolicy`] allows.
+    async fn send(
+        &self,
+        req: reqwest::Request,
+        request_id: &str,
+    ) -> Result<reqwest::Response, Error> {
+        // streamed bodies cannot be sent twice
+        if !retry::is_idempotent(req.method()) || req.try_clone().is_none() {
+            return Ok(self.client.execute(req).await?);
+        }
+
+        let mut attempt = 0;
+        loop {
+            let outcome = self
+                .client
+                .execute(req.try_clone().unwrap())
+                .await;
+
+            let delay = match &outcome {
+                Ok(resp) => {
+                    self.retry
+                        .delay(attempt, Some(resp.status()), retry::retry_after(resp))
+                },
+                Err(err) if err.is_connect() || err.is_timeout() => {
+                    self.retry.delay(attempt, None, None)
+                },
+                Err(_) => None,
+            };
+
+            let Some(delay) = delay else {
+                return Ok(outcome?);
+            };
+
+            tracing::debug!(
+                "retrying {} {} in {delay:?} (request id: {request_id}, attempt {})",
+                req.method(),
+                req.url(),
+                attempt + 1
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// The request id the registry echoed back.
+    fn request_id(resp: &reqwest::Response) -> Option<String> {
+        resp.headers()
+            .get(kintsu_registry_core::REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    }
+
     pub async fn perform<T: serde::de::DeserializeOwned>(
         &self,
         req: reqwest::Request,
     ) -> Result<T, Error> {
-        let resp = self.client.execute(req).await?;
+        let (body, _) = self.read(req).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// The body of a successful response to `req` and the url which served it. Reads the
+    /// registry tagged before are revalidated, reusing the kept body while it is unchanged.
+    async fn read(
+        &self,
+        mut req: reqwest::Request,
+    ) -> Result<(bytes::Bytes, url::Url), Error> {
+        let url = req.url().clone();
+        let cached = self.cache.revalidate(&mut req);
 
+        let resp = self.execute(req).await?;
         let status = resp.status();
-        let body = resp.bytes().await?;
+        let served_by = resp.url().clone();
+
+        if status == reqwest::StatusCode::NOT_MODIFIED
+            && let Some(cached) = cached
+        {
+            return Ok((cached, served_by));
+        }
 
         if status.is_success() {
-            let parsed: T = serde_json::from_slice(&body)?;
-            Ok(parsed)
+            let headers = resp.headers().clone();
+            let body = resp.bytes().await?;
+            self.cache.store(&url, &headers, &body);
+            Ok((body, served_by))
         } else {
-            Err(Self::handle_response_with_errors(status, body).await)
+            let request_id = Self::request_id(&resp);
+            let body = resp.bytes().await?;
+            Err(Self::handle_response_with_errors(status, request_id, body).await)
         }
     }
 
@@ -98,12 +414,73 @@ impl RegistryClient {
         &self,
         req: reqwest::Request,
     ) -> Result<T, Error> {
+        let req = self.authenticate(req)?;
+        self.perform(req).await
+    }
+
+    /// Authenticates and sends a request which changes registry state, unless the client is
+    /// in dry-run mode.
+    pub async fn mutate<T: serde::de::DeserializeOwned>(
+        &self,
+        req: reqwest::Request,
+    ) -> Result<Mutation<T>, Error> {
+        let req = self.authenticate(req)?;
+        if self.dry_run {
+            return Self::dry_run(&req);
+        }
+        self.perform(req)
+            .await
+            .map(Mutation::Performed)
+    }
+
+    /// Like [`Self::mutate`], for endpoints which respond without a body.
+    pub async fn mutate_empty(
+        &self,
+        req: reqwest::Request,
+    ) -> Result<Mutation<()>, Error> {
+        let req = self.authenticate(req)?;
+        if self.dry_run {
+            return Self::dry_run(&req);
+        }
+
+        let resp = self.execute(req).await?;
+        let status = resp.status();
+        if status.is_success() {
+            Ok(Mutation::Performed(()))
+        } else {
+            let request_id = Self::request_id(&resp);
+            let body = resp.bytes().await?;
+            Err(Self::handle_response_with_errors(status, request_id, body).await)
+        }
+    }
+
+    fn dry_run<T>(req: &reqwest::Request) -> Result<Mutation<T>, Error> {
+        Ok(Mutation::DryRun(DryRun::from_request(req)?))
+    }
+
+    fn json_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: &impl serde::Serialize,
+    ) -> Result<reqwest::Request, Error> {
+        let mut request = reqwest::Request::new(method, self.url(path));
+        *request.body_mut() = Some(reqwest::Body::from(serde_json::to_vec(body)?));
+        request.headers_mut().insert(
+            reqwest::header::CONTENT_TYPE,
+            "application/json".parse().unwrap(),
+        );
+        Ok(request)
+    }
+
+    fn authenticate(
+        &self,
+        mut req: reqwest::Request,
+    ) -> Result<reqwest::Request, Error> {
         let Some(token) = &self.token else {
             return Err(Error::NoAuth);
         };
 
-        let mut req = req;
-
         req.headers_mut().insert(
             reqwest::header::AUTHORIZATION,
             format!("Bearer {}", token.expose_secret())
@@ -111,11 +488,452 @@ impl RegistryClient {
                 .unwrap(),
         );
 
-        self.perform(req).await
+        Ok(req)
+    }
+
+    /// Whether `name@version` can be fetched from the registry.
+    pub async fn version_exists(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<bool, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::GET,
+            self.package_url(name, &format!("/{version}")),
+        );
+        let resp = self.execute(request).await?;
+
+        match resp.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => {
+                let request_id = Self::request_id(&resp);
+                let body = resp.bytes().await?;
+                Err(Self::handle_response_with_errors(status, request_id, body).await)
+            },
+        }
+    }
+
+    /// The source of `name@version`, with files fetched from the registry as they are read.
+    pub async fn package_fs(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<kintsu_fs::http::HttpFileSystem, Error> {
+        Ok(kintsu_fs::http::HttpFileSystem::connect(
+            self.client.clone(),
+            self.package_url(name, &format!("/{version}/files")),
+            kintsu_fs::http::HttpConfig::default(),
+        )
+        .await?)
+    }
+
+    /// The README of `name@version`, as published and rendered to sanitized HTML.
+    pub async fn package_readme(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<kintsu_registry_core::models::PackageReadme, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::GET,
+            self.package_url(name, &format!("/{version}/readme")),
+        );
+
+        self.perform(request).await
+    }
+
+    /// The checksums `name@version` was published with.
+    async fn version_checksums(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<VersionChecksums, Error> {
+        #[derive(serde::Deserialize)]
+        struct VersionMetadata {
+            version: VersionChecksums,
+        }
+
+        let request = reqwest::Request::new(
+            reqwest::Method::GET,
+            self.package_url(name, &format!("/{version}")),
+        );
+        let metadata: VersionMetadata = self.perform(request).await?;
+
+        Ok(metadata.version)
+    }
+
+    /// The complete source of `name@version`, verified against the checksum it was published
+    /// with. Use [`Self::package_fs`] to read only some of its files.
+    pub async fn download_package(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<kintsu_fs::memory::MemoryFileSystem, Error> {
+        let checksums = self.version_checksums(name, version).await?;
+        let (source, _) = self
+            .fetch_source(name, version, &checksums)
+            .await?;
+        Ok(source)
+    }
+
+    /// The declarations of `name@version`, verified against the checksum they were published
+    /// with.
+    pub async fn download_declarations(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<kintsu_parser::declare::DeclarationVersion, Error> {
+        let checksums = self.version_checksums(name, version).await?;
+        self.fetch_declarations(name, version, &checksums)
+            .await
+    }
+
+    /// The verified source and declarations of `name@version`, with the lockfile source
+    /// recording whether a mirror served them.
+    pub async fn fetch_package(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<FetchedPackage, Error> {
+        let checksums = self.version_checksums(name, version).await?;
+        let (source, served_by) = self
+            .fetch_source(name, version, &checksums)
+            .await?;
+        let declarations = self
+            .fetch_declarations(name, version, &checksums)
+            .await?;
+
+        Ok(FetchedPackage {
+            source,
+            declarations,
+            checksum: checksums.source_checksum,
+            locked_source: self.locked_source(&served_by),
+        })
+    }
+
+    async fn fetch_source(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+        checksums: &VersionChecksums,
+    ) -> Result<(kintsu_fs::memory::MemoryFileSystem, url::Url), Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::GET,
+            self.package_url(name, &format!("/{version}/download")),
+        );
+        let (body, served_by) = self.read(request).await?;
+        let source: kintsu_fs::memory::MemoryFileSystem = serde_json::from_slice(&body)?;
+
+        // the registry stores source as compact json in path order, which re-serializing
+        // reproduces regardless of how the response was formatted
+        verify_checksum(
+            &format!("{name}@{version}"),
+            "source",
+            &checksums.source_checksum,
+            [serde_json::to_vec(&source)?],
+        )?;
+
+        Ok((source, served_by))
+    }
+
+    async fn fetch_declarations(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+        checksums: &VersionChecksums,
+    ) -> Result<kintsu_parser::declare::DeclarationVersion, Error> {
+        use kintsu_parser::declare::{
+            DeclarationVersion,
+            encoding::{BINARY_MEDIA_TYPE, DeclarationEncoding},
+        };
+
+        let mut request = reqwest::Request::new(
+            reqwest::Method::GET,
+            self.package_url(name, &format!("/{version}/declarations")),
+        );
+        request.headers_mut().insert(
+            reqwest::header::ACCEPT,
+            format!("{BINARY_MEDIA_TYPE}, application/json")
+                .parse()
+                .unwrap(),
+        );
+        let (body, _) = self.read(request).await?;
+        let declarations = DeclarationVersion::decode(&body)?;
+
+        // the checksum covers the encoding the registry stores declarations in, which need
+        // not be the one they were served in
+        verify_checksum(
+            &format!("{name}@{version}"),
+            "declarations",
+            &checksums.declarations_checksum,
+            [
+                declarations.encode(DeclarationEncoding::Binary)?,
+                declarations.encode(DeclarationEncoding::Json)?,
+            ],
+        )?;
+
+        Ok(declarations)
+    }
+
+    /// Where a package served from `served_by` is locked to: this registry, and the mirror if
+    /// one served it.
+    fn locked_source(
+        &self,
+        served_by: &url::Url,
+    ) -> kintsu_manifests::lock::LockedSource {
+        let mirror = if served_by.origin() == self.base_url.origin() {
+            None
+        } else {
+            self.mirrors
+                .iter()
+                .find(|mirror| mirror.origin() == served_by.origin())
+                .map(endpoint_url)
+        };
+
+        kintsu_manifests::lock::LockedSource::Registry {
+            url: endpoint_url(&self.base_url),
+            mirror,
+        }
+    }
+
+    pub async fn yank_version(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<Mutation<()>, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::POST,
+            self.package_url(name, &format!("/{version}/yank")),
+        );
+
+        let outcome = self.mutate_empty(request).await?;
+        if !outcome.is_dry_run() {
+            tracing::info!("Yanked {}@{}", name, version);
+        }
+        Ok(outcome)
+    }
+
+    pub async fn unyank_version(
+        &self,
+        name: &str,
+        version: &kintsu_manifests::version::VersionSerde,
+    ) -> Result<Mutation<()>, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::POST,
+            self.package_url(name, &format!("/{version}/unyank")),
+        );
+
+        let outcome = self.mutate_empty(request).await?;
+        if !outcome.is_dry_run() {
+            tracing::info!("Unyanked {}@{}", name, version);
+        }
+        Ok(outcome)
+    }
+
+    /// Deprecates `name`, or only `name@version` when a version is given.
+    pub async fn deprecate(
+        &self,
+        name: &str,
+        version: Option<&kintsu_manifests::version::VersionSerde>,
+        body: &kintsu_registry_core::models::DeprecatePackageRequest,
+    ) -> Result<Mutation<()>, Error> {
+        body.validate()?;
+        let request = self.json_request(
+            reqwest::Method::POST,
+            &Self::lifecycle_path(name, version, "deprecate"),
+            body,
+        )?;
+
+        let outcome = self.mutate_empty(request).await?;
+        if !outcome.is_dry_run() {
+            tracing::info!("Deprecated {}", Self::lifecycle_target(name, version));
+        }
+        Ok(outcome)
+    }
+
+    pub async fn undeprecate(
+        &self,
+        name: &str,
+        version: Option<&kintsu_manifests::version::VersionSerde>,
+    ) -> Result<Mutation<()>, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::POST,
+            self.url(&Self::lifecycle_path(name, version, "undeprecate")),
+        );
+
+        let outcome = self.mutate_empty(request).await?;
+        if !outcome.is_dry_run() {
+            tracing::info!("Undeprecated {}", Self::lifecycle_target(name, version));
+        }
+        Ok(outcome)
+    }
+
+    fn lifecycle_path(
+        name: &str,
+        version: Option<&kintsu_manifests::version::VersionSerde>,
+        action: &str,
+    ) -> String {
+        match version {
+            Some(version) => format!("/package/{}/{version}/{action}", encode_package_name(name)),
+            None => format!("/package/{}/{action}", encode_package_name(name)),
+        }
+    }
+
+    fn lifecycle_target(
+        name: &str,
+        version: Option<&kintsu_manifests::version::VersionSerde>,
+    ) -> String {
+        match version {
+            Some(version) => format!("{name}@{version}"),
+            None => name.to_string(),
+        }
+    }
+
+    pub async fn create_token(
+        &self,
+        body: &kintsu_registry_core::models::CreateTokenRequest,
+    ) -> Result<Mutation<kintsu_registry_core::models::OneTimeApiKey>, Error> {
+        body.validate()?;
+        let request = self.json_request(reqwest::Method::POST, "/auth/token", body)?;
+        self.mutate(request).await
+    }
+
+    pub async fn create_org_token(
+        &self,
+        org_id: i64,
+        body: &kintsu_registry_core::models::CreateTokenRequest,
+    ) -> Result<Mutation<kintsu_registry_core::models::OneTimeApiKey>, Error> {
+        body.validate()?;
+        let request = self.json_request(
+            reqwest::Method::POST,
+            &format!("/org/{org_id}/tokens"),
+            body,
+        )?;
+        self.mutate(request).await
+    }
+
+    pub async fn revoke_token(
+        &self,
+        token_id: i64,
+    ) -> Result<Mutation<()>, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::DELETE,
+            self.url(&format!("/auth/tokens/{token_id}")),
+        );
+        self.mutate(request).await
+    }
+
+    pub async fn grant_org_role(
+        &self,
+        body: &kintsu_registry_core::models::GrantOrgRoleRequest,
+    ) -> Result<Mutation<kintsu_registry_core::models::OrgRole>, Error> {
+        body.validate()?;
+        let request = self.json_request(reqwest::Method::POST, "/roles/org", body)?;
+        self.mutate(request).await
+    }
+
+    pub async fn revoke_org_role(
+        &self,
+        body: &kintsu_registry_core::models::RevokeOrgRoleRequest,
+    ) -> Result<Mutation<()>, Error> {
+        body.validate()?;
+        let request = self.json_request(reqwest::Method::DELETE, "/roles/org", body)?;
+        self.mutate_empty(request).await
+    }
+
+    pub async fn list_teams(
+        &self,
+        org_id: i64,
+    ) -> Result<Vec<kintsu_registry_core::models::Team>, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::GET,
+            self.url(&format!("/org/{org_id}/teams")),
+        );
+        self.perform(request).await
+    }
+
+    pub async fn create_team(
+        &self,
+        org_id: i64,
+        body: &kintsu_registry_core::models::CreateTeamRequest,
+    ) -> Result<Mutation<kintsu_registry_core::models::Team>, Error> {
+        body.validate()?;
+        let request =
+            self.json_request(reqwest::Method::POST, &format!("/org/{org_id}/teams"), body)?;
+        self.mutate(request).await
+    }
+
+    pub async fn update_team(
+        &self,
+        org_id: i64,
+        team_id: i64,
+        body: &kintsu_registry_core::models::UpdateTeamRequest,
+    ) -> Result<Mutation<kintsu_registry_core::models::Team>, Error> {
+        body.validate()?;
+        let request = self.json_request(
+            reqwest::Method::PATCH,
+            &format!("/org/{org_id}/teams/{team_id}"),
+            body,
+        )?;
+        self.mutate(request).await
+    }
+
+    pub async fn delete_team(
+        &self,
+        org_id: i64,
+        team_id: i64,
+    ) -> Result<Mutation<()>, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::DELETE,
+            self.url(&format!("/org/{org_id}/teams/{team_id}")),
+        );
+        self.mutate_empty(request).await
+    }
+
+    pub async fn team_members(
+        &self,
+        org_id: i64,
+        team_id: i64,
+    ) -> Result<Vec<kintsu_registry_core::models::TeamMemberWithUser>, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::GET,
+            self.url(&format!("/org/{org_id}/teams/{team_id}/members")),
+        );
+        self.perform(request).await
+    }
+
+    /// Adds a user to a team, or changes the role of a current member.
+    pub async fn add_team_member(
+        &self,
+        org_id: i64,
+        team_id: i64,
+        body: &kintsu_registry_core::models::AddTeamMemberRequest,
+    ) -> Result<Mutation<kintsu_registry_core::models::TeamMember>, Error> {
+        body.validate()?;
+        let request = self.json_request(
+            reqwest::Method::POST,
+            &format!("/org/{org_id}/teams/{team_id}/members"),
+            body,
+        )?;
+        self.mutate(request).await
+    }
+
+    pub async fn remove_team_member(
+        &self,
+        org_id: i64,
+        team_id: i64,
+        user_id: i64,
+    ) -> Result<Mutation<()>, Error> {
+        let request = reqwest::Request::new(
+            reqwest::Method::DELETE,
+            self.url(&format!("/org/{org_id}/teams/{team_id}/members/{user_id}")),
+        );
+        self.mutate_empty(request).await
     }
 
     async fn handle_response_with_errors(
         status: reqwest::StatusCode,
+        request_id: Option<String>,
         body: bytes::Bytes,
     ) -> Error {
         (if !body.is_empty() {
@@ -133,38 +951,123 @@ impl RegistryClient {
         } else {
             ErrorOrResponseError::ErrorString("empty response body".to_string())
         })
-        .with_status(status)
+        .with_status(status, request_id)
         .into()
     }
 
-    pub async fn publish_compiled_package(
+    /// Asks for a presigned URL to upload package data to, or `None` when the registry's
+    /// storage cannot accept direct uploads.
+    pub async fn request_publish_upload(
+        &self,
+        body: &kintsu_registry_core::models::PublishUploadRequest,
+    ) -> Result<Option<kintsu_registry_core::models::PublishUploadResponse>, Error> {
+        body.validate()?;
+        let request = self.json_request(reqwest::Method::POST, "/packages/publish/upload", body)?;
+        let resp = self
+            .execute(self.authenticate(request)?)
+            .await?;
+
+        let status = resp.status();
+        let request_id = Self::request_id(&resp);
+        let body = resp.bytes().await?;
+        match status {
+            status if status.is_success() => Ok(Some(serde_json::from_slice(&body)?)),
+            reqwest::StatusCode::NOT_IMPLEMENTED => Ok(None),
+            status => Err(Self::handle_response_with_errors(status, request_id, body).await),
+        }
+    }
+
+    /// Uploads serialized package data to a URL from [`Self::request_publish_upload`]. The
+    /// URL carries its own authorization, so the registry token is not sent.
+    pub async fn upload_publish_source(
+        &self,
+        url: &str,
+        source: Vec<u8>,
+    ) -> Result<(), Error> {
+        let resp = self
+            .client
+            .put(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(source)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            let body = resp.bytes().await?;
+            Err(Self::handle_response_with_errors(status, None, body).await)
+        }
+    }
+
+    pub async fn finalize_publish(
+        &self,
+        body: &kintsu_registry_core::models::FinalizePublishRequest,
+    ) -> Result<Mutation<kintsu_registry_core::models::Version>, Error> {
+        body.validate()?;
+        let request =
+            self.json_request(reqwest::Method::POST, "/packages/publish/finalize", body)?;
+        self.mutate(request).await
+    }
+
+    /// Publishes through a presigned upload, returning `None` when the registry only accepts
+    /// direct publishes.
+    async fn publish_staged(
+        &self,
+        manifest: &kintsu_manifests::package::PackageManifests,
+        source: Vec<u8>,
+    ) -> Result<Option<kintsu_registry_core::models::Version>, Error> {
+        let package = manifest.package();
+        let source_checksum = sha256::digest(source.as_slice());
+
+        let Some(upload) = self
+            .request_publish_upload(&kintsu_registry_core::models::PublishUploadRequest {
+                package: package.name.clone(),
+                version: package.version.to_string(),
+                source_checksum: source_checksum.clone(),
+            })
+            .await?
+        else {
+            tracing::debug!("registry does not support staged publishes, publishing directly");
+            return Ok(None);
+        };
+
+        self.upload_publish_source(&upload.url, source)
+            .await?;
+
+        match self
+            .finalize_publish(&kintsu_registry_core::models::FinalizePublishRequest {
+                manifest: manifest.clone(),
+                source_checksum,
+            })
+            .await?
+        {
+            Mutation::Performed(published) => Ok(Some(published)),
+            Mutation::DryRun(_) => Ok(None),
+        }
+    }
+
+    /// Has the registry validate and compile the package as it would for a publish, returning
+    /// the version which would be created and any warnings. Nothing is stored, so this is sent
+    /// even when the client is in dry-run mode.
+    pub async fn publish_dry_run(
         &self,
         manifest: kintsu_manifests::package::PackageManifests,
         package_data: std::sync::Arc<dyn kintsu_fs::FileSystem>,
         root_path: impl AsRef<std::path::Path>,
-    ) -> Result<(), Error> {
-        self.publish_compiled_package_with_progress(
-            manifest,
-            package_data,
-            root_path,
-            kintsu_cli_core::ProgressManager::disabled(),
-        )
-        .await
+    ) -> Result<kintsu_registry_core::models::PublishPreview, Error> {
+        let body = Self::publish_request(manifest, package_data, root_path).await?;
+        let request =
+            self.json_request(reqwest::Method::POST, "/packages/publish/dry-run", &body)?;
+        self.perform_authenticated(request).await
     }
 
-    pub async fn publish_compiled_package_with_progress(
-        &self,
+    async fn publish_request(
         mut manifest: kintsu_manifests::package::PackageManifests,
         package_data: std::sync::Arc<dyn kintsu_fs::FileSystem>,
         root_path: impl AsRef<std::path::Path>,
-        progress: kintsu_cli_core::ProgressManager,
-    ) -> Result<(), Error> {
-        let package_name = manifest.package().name.clone();
-
-        progress.println(
-            kintsu_cli_core::prefixes::PREPARING,
-            &format!("package {}...", package_name),
-        );
+    ) -> Result<kintsu_registry_core::models::PublishPackageRequest, Error> {
         manifest.prepare_publish()?;
 
         let package_data = kintsu_fs::memory::MemoryFileSystem::extract_from(
@@ -172,6 +1075,7 @@ impl RegistryClient {
             &root_path,
             &["/**/*.ks", "/schema.toml", "/**/*.md", "/**/*.txt"],
             &Vec::<String>::new(),
+            kintsu_registry_core::package_quota(),
         )
         .await?;
 
@@ -179,25 +1083,77 @@ impl RegistryClient {
             manifest,
             package_data,
         };
+        body.validate()?;
+        body.validate_publishing_package_data()
+            .map_err(Error::Packaging)?;
 
-        let mut request =
-            reqwest::Request::new(reqwest::Method::POST, self.url("/packages/publish"));
+        Ok(body)
+    }
 
-        *request.body_mut() = Some(reqwest::Body::from(serde_json::to_vec(&body)?));
+    pub async fn publish_compiled_package(
+        &self,
+        manifest: kintsu_manifests::package::PackageManifests,
+        package_data: std::sync::Arc<dyn kintsu_fs::FileSystem>,
+        root_path: impl AsRef<std::path::Path>,
+    ) -> Result<Mutation<kintsu_registry_core::models::Version>, Error> {
+        self.publish_compiled_package_with_progress(
+            manifest,
+            package_data,
+            root_path,
+            kintsu_cli_core::ProgressManager::disabled(),
+        )
+        .await
+    }
 
-        request.headers_mut().insert(
-            reqwest::header::CONTENT_TYPE,
-            "application/json".parse().unwrap(),
-        );
+    pub async fn publish_compiled_package_with_progress(
+        &self,
+        manifest: kintsu_manifests::package::PackageManifests,
+        package_data: std::sync::Arc<dyn kintsu_fs::FileSystem>,
+        root_path: impl AsRef<std::path::Path>,
+        progress: kintsu_cli_core::ProgressManager,
+    ) -> Result<Mutation<kintsu_registry_core::models::Version>, Error> {
+        let package_name = manifest.package().name.clone();
 
         progress.println(
-            kintsu_cli_core::prefixes::UPLOADING,
-            &format!("to {}...", self.base_url),
+            kintsu_cli_core::prefixes::PREPARING,
+            &format!("package {}...", package_name),
         );
+        let body = Self::publish_request(manifest, package_data, root_path).await?;
 
-        let published = self
-            .perform_authenticated::<kintsu_registry_core::models::Version>(request)
-            .await?;
+        if !self.dry_run {
+            progress.println(
+                kintsu_cli_core::prefixes::UPLOADING,
+                &format!("to {}...", self.base_url),
+            );
+        }
+
+        let source = if self.dry_run {
+            None
+        } else {
+            Some(serde_json::to_vec(&body.package_data)?)
+        };
+        let staged = match source {
+            Some(source) if source.len() > STAGED_PUBLISH_THRESHOLD => {
+                self.publish_staged(&body.manifest, source)
+                    .await?
+            },
+            _ => None,
+        };
+
+        let published = match staged {
+            Some(published) => published,
+            None => {
+                let request =
+                    self.json_request(reqwest::Method::POST, "/packages/publish", &body)?;
+                match self
+                    .mutate::<kintsu_registry_core::models::Version>(request)
+                    .await?
+                {
+                    Mutation::Performed(published) => published,
+                    dry_run => return Ok(dry_run),
+                }
+            },
+        };
 
         progress.println(
             kintsu_cli_core::prefixes::PUBLISHED,
@@ -221,6 +1177,330 @@ impl RegistryClient {
             ))
         );
 
-        Ok(())
+        Ok(Mutation::Performed(published))
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct VersionChecksums {
+    source_checksum: String,
+    declarations_checksum: String,
+}
+
+/// `name` as a single path segment. The `/` of scoped names such as `@acme/billing` is
+/// percent-encoded, which the registry decodes when matching its `{name}` routes.
+fn encode_package_name(name: &str) -> std::borrow::Cow<'_, str> {
+    if name.contains('/') {
+        std::borrow::Cow::Owned(name.replace('/', "%2F"))
+    } else {
+        std::borrow::Cow::Borrowed(name)
+    }
+}
+
+/// Whether a request failed because its endpoint is down or overloaded, rather than because
+/// of the request itself.
+fn is_unavailable(outcome: &Result<reqwest::Response, Error>) -> bool {
+    match outcome {
+        Ok(resp) => {
+            matches!(
+                resp.status(),
+                reqwest::StatusCode::BAD_GATEWAY
+                    | reqwest::StatusCode::SERVICE_UNAVAILABLE
+                    | reqwest::StatusCode::GATEWAY_TIMEOUT
+            )
+        },
+        Err(Error::Reqwest(err)) => err.is_connect() || err.is_timeout(),
+        Err(_) => false,
+    }
+}
+
+/// `url` as written in lockfiles, without a trailing slash.
+fn endpoint_url(url: &url::Url) -> String {
+    url.as_str()
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Checks that one of the `candidates` encodings of an artifact hashes to `expected`.
+fn verify_checksum<const N: usize>(
+    package: &str,
+    artifact: &'static str,
+    expected: &str,
+    candidates: [Vec<u8>; N],
+) -> Result<(), Error> {
+    let digests = candidates.map(|candidate| sha256::digest(candidate.as_slice()));
+    if digests
+        .iter()
+        .any(|digest| digest.eq_ignore_ascii_case(expected))
+    {
+        return Ok(());
+    }
+
+    Err(Error::ChecksumMismatch {
+        package: package.to_string(),
+        artifact,
+        expected: expected.to_string(),
+        actual: digests.join(" or "),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Answers one request per connection with each of `responses` in turn, returning the
+    /// server's url and the number of requests it received.
+    async fn serve(
+        responses: Vec<impl AsRef<[u8]> + Send + 'static>
+    ) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let received = Arc::new(AtomicUsize::new(0));
+
+        let counter = received.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![];
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..read]);
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+                stream
+                    .write_all(response.as_ref())
+                    .await
+                    .unwrap();
+            }
+        });
+
+        (url, received)
+    }
+
+    const UNAVAILABLE: &str = "HTTP/1.1 503 Service Unavailable\r\nretry-after: 0\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\ncontent-length: 4\r\nconnection: close\r\n\r\ntrue";
+
+    #[tokio::test]
+    async fn retries_idempotent_requests() {
+        let (url, received) = serve(vec![UNAVAILABLE, UNAVAILABLE, OK]).await;
+        let client = RegistryClient::builder(&url)
+            .retry(RetryPolicy {
+                initial_backoff: std::time::Duration::from_millis(1),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        let request = reqwest::Request::new(reqwest::Method::GET, client.url("/ping"));
+        let ok: bool = client.perform(request).await.unwrap();
+
+        assert!(ok);
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_posts() {
+        let (url, received) = serve(vec![UNAVAILABLE, OK]).await;
+        let client = RegistryClient::builder(&url)
+            .build()
+            .unwrap();
+
+        let request = reqwest::Request::new(reqwest::Method::POST, client.url("/ping"));
+        let err = client
+            .perform::<bool>(request)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("503"), "{err}");
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
+    fn ok_json(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[tokio::test]
+    async fn downloads_verify_checksums() {
+        let source = kintsu_fs::memory::MemoryFileSystem::with_files([
+            ("schema/lib.ks", "namespace abc;"),
+            ("schema.toml", "[package]"),
+        ]);
+        let body = serde_json::to_string(&source).unwrap();
+        let metadata = |checksum: &str| {
+            ok_json(
+                &serde_json::json!({
+                    "version": {
+                        "source_checksum": checksum,
+                        "declarations_checksum": "",
+                    }
+                })
+                .to_string(),
+            )
+        };
+        let version = kintsu_manifests::version::VersionSerde(
+            kintsu_manifests::version::parse_version("1.0.0").unwrap(),
+        );
+
+        let checksum = sha256::digest(body.as_bytes());
+        let (url, _) = serve(vec![metadata(&checksum), ok_json(&body)]).await;
+        let client = RegistryClient::new(&url, None).unwrap();
+        let downloaded = client
+            .download_package("abc", &version)
+            .await
+            .unwrap();
+        assert_eq!(downloaded.file_count(), 2);
+
+        let (url, _) = serve(vec![metadata(&sha256::digest("tampered")), ok_json(&body)]).await;
+        let client = RegistryClient::new(&url, None).unwrap();
+        let err = client
+            .download_package("abc", &version)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                Error::ChecksumMismatch {
+                    artifact: "source",
+                    ..
+                }
+            ),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_fail_over_to_mirrors() {
+        let source = kintsu_fs::memory::MemoryFileSystem::with_files([("schema.toml", "")]);
+        let body = serde_json::to_string(&source).unwrap();
+        let metadata = serde_json::json!({
+            "version": {
+                "source_checksum": sha256::digest(body.as_bytes()),
+                "declarations_checksum": "",
+            }
+        })
+        .to_string();
+        let version = kintsu_manifests::version::VersionSerde(
+            kintsu_manifests::version::parse_version("1.0.0").unwrap(),
+        );
+
+        let (primary, primary_received) = serve(vec![UNAVAILABLE, UNAVAILABLE, UNAVAILABLE]).await;
+        let (mirror, mirror_received) = serve(vec![ok_json(&metadata), ok_json(&body)]).await;
+        let client = RegistryClient::builder(&primary)
+            .retry(RetryPolicy::none())
+            .mirrors(vec![mirror.clone()])
+            .build()
+            .unwrap();
+
+        let checksums = client
+            .version_checksums("abc", &version)
+            .await
+            .unwrap();
+        let (_, served_by) = client
+            .fetch_source("abc", &version, &checksums)
+            .await
+            .unwrap();
+        assert_eq!(
+            client.locked_source(&served_by),
+            kintsu_manifests::lock::LockedSource::Registry {
+                url: primary.clone(),
+                mirror: Some(mirror),
+            }
+        );
+        assert_eq!(primary_received.load(Ordering::SeqCst), 2);
+        assert_eq!(mirror_received.load(Ordering::SeqCst), 2);
+
+        // credentials are never sent to mirrors
+        let client = RegistryClient::builder(&primary)
+            .token("secret".into())
+            .retry(RetryPolicy::none())
+            .mirrors(vec!["http://127.0.0.1:1".into()])
+            .build()
+            .unwrap();
+        let request = reqwest::Request::new(reqwest::Method::GET, client.url("/ping"));
+        let err = client
+            .perform_authenticated::<bool>(request)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("503"), "{err}");
+    }
+
+    #[test]
+    fn scoped_package_names_are_one_path_segment() {
+        let client = RegistryClient::new("https://registry.example.com", None).unwrap();
+        assert_eq!(
+            client
+                .package_url("@acme/billing", "/1.0.0/files")
+                .as_str(),
+            "https://registry.example.com/package/@acme%2Fbilling/1.0.0/files"
+        );
+    }
+
+    #[tokio::test]
+    async fn revalidates_tagged_reads() {
+        const TAGGED: &str =
+            "HTTP/1.1 200 OK\r\netag: \"v1\"\r\ncontent-length: 4\r\nconnection: close\r\n\r\ntrue";
+        const NOT_MODIFIED: &str =
+            "HTTP/1.1 304 Not Modified\r\netag: \"v1\"\r\nconnection: close\r\n\r\n";
+
+        let (url, received) = serve(vec![TAGGED, NOT_MODIFIED]).await;
+        let client = RegistryClient::builder(&url)
+            .build()
+            .unwrap();
+
+        for _ in 0..2 {
+            let request = reqwest::Request::new(reqwest::Method::GET, client.url("/ping"));
+            let ok: bool = client.perform(request).await.unwrap();
+            assert!(ok);
+        }
+        assert_eq!(received.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn errors_name_the_request_id() {
+        let body = serde_json::json!({
+            "error": "internal-server-error",
+            "errors": [],
+            "error_description": null,
+            "error_uri": null,
+            "validation": null,
+            "request_id": "from-body",
+        });
+        let err = RegistryClient::handle_response_with_errors(
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            Some("from-header".into()),
+            serde_json::to_vec(&body).unwrap().into(),
+        )
+        .await;
+
+        let Error::Response(response) = &err else {
+            panic!("expected a response error, got {err:?}");
+        };
+        assert_eq!(response.request_id(), Some("from-header"));
+        assert!(
+            err.to_string()
+                .ends_with("(request id: from-header)")
+        );
+
+        let err = RegistryClient::handle_response_with_errors(
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            None,
+            serde_json::to_vec(&body).unwrap().into(),
+        )
+        .await;
+        let Error::Response(response) = &err else {
+            panic!("expected a response error, got {err:?}");
+        };
+        assert_eq!(response.request_id(), Some("from-body"));
     }
 }
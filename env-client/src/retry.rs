@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+/// When and how long [`crate::RegistryClient`] waits before trying a request again.
+///
+/// Only idempotent requests are retried, after connection failures, timeouts, and responses
+/// the registry sends while overloaded or restarting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts after the first, `0` disables retries
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubling for each retry after it
+    pub initial_backoff: Duration,
+    /// Upper bound of the exponential backoff
+    pub max_backoff: Duration,
+    /// Longest `Retry-After` the client waits out, longer waits fail instead
+    pub max_retry_after: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Exponential backoff for retry number `attempt` (from `0`), with jitter so clients which
+    /// failed together do not retry together.
+    pub fn backoff(
+        &self,
+        attempt: u32,
+    ) -> Duration {
+        let ceiling = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+
+        // equal jitter: wait at least half the backoff
+        let half = ceiling / 2;
+        half + half.mul_f64(rand::random::<f64>())
+    }
+
+    /// How long to wait before retry number `attempt`, or `None` to give up. `status` is `None`
+    /// when the request failed before a response, and `retry_after` is the response's
+    /// `Retry-After`.
+    pub fn delay(
+        &self,
+        attempt: u32,
+        status: Option<reqwest::StatusCode>,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+
+        match status {
+            None => Some(self.backoff(attempt)),
+            Some(status) if is_retryable_status(status) => {
+                match retry_after {
+                    Some(wait) if wait > self.max_retry_after => None,
+                    Some(wait) => Some(wait),
+                    None => Some(self.backoff(attempt)),
+                }
+            },
+            Some(_) => None,
+        }
+    }
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(
+        status,
+        reqwest::StatusCode::TOO_MANY_REQUESTS
+            | reqwest::StatusCode::BAD_GATEWAY
+            | reqwest::StatusCode::SERVICE_UNAVAILABLE
+            | reqwest::StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Methods which can be sent again without repeating their effect.
+pub(crate) fn is_idempotent(method: &reqwest::Method) -> bool {
+    matches!(
+        *method,
+        reqwest::Method::GET
+            | reqwest::Method::HEAD
+            | reqwest::Method::OPTIONS
+            | reqwest::Method::PUT
+            | reqwest::Method::DELETE
+    )
+}
+
+/// A `Retry-After` in seconds. The HTTP date form is not sent by the registry, and is treated
+/// as absent.
+pub(crate) fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn backoff_grows_with_jitter_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            ..Default::default()
+        };
+
+        for (attempt, ceiling) in [(0, 100), (1, 200), (2, 400), (3, 800), (4, 1000), (9, 1000)] {
+            let ceiling = Duration::from_millis(ceiling);
+            for _ in 0..20 {
+                let backoff = policy.backoff(attempt);
+                assert!(backoff >= ceiling / 2, "{backoff:?} below {ceiling:?} / 2");
+                assert!(backoff <= ceiling, "{backoff:?} above {ceiling:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn retries_transient_failures_only() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.delay(0, None, None).is_some());
+        assert!(
+            policy
+                .delay(0, Some(StatusCode::SERVICE_UNAVAILABLE), None)
+                .is_some()
+        );
+        assert!(
+            policy
+                .delay(0, Some(StatusCode::INTERNAL_SERVER_ERROR), None)
+                .is_none()
+        );
+        assert!(
+            policy
+                .delay(0, Some(StatusCode::NOT_FOUND), None)
+                .is_none()
+        );
+        assert!(policy.delay(3, None, None).is_none());
+        assert!(
+            RetryPolicy::none()
+                .delay(0, None, None)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn honors_retry_after() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(
+            policy.delay(
+                0,
+                Some(StatusCode::TOO_MANY_REQUESTS),
+                Some(Duration::from_secs(7))
+            ),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            policy.delay(
+                0,
+                Some(StatusCode::TOO_MANY_REQUESTS),
+                Some(Duration::from_secs(3600))
+            ),
+            None
+        );
+    }
+}
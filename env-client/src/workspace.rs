@@ -0,0 +1,550 @@
+//! Ordered publishing of several packages which depend on each other by path.
+//!
+//! Members are published dependencies-first. Before a member is published, its path
+//! dependencies on other members are pinned to the versions published earlier in the run,
+//! and each published version is polled until the registry can resolve it. If any step
+//! fails, the members published so far are yanked in reverse order.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use kintsu_cli_core::{ProgressManager, prefixes};
+use kintsu_manifests::{package::PackageManifests, version::VersionSerde};
+
+use crate::{Error, Mutation};
+
+/// A compiled package taking part in a workspace publish.
+pub struct WorkspaceMember {
+    pub root: PathBuf,
+    pub manifest: PackageManifests,
+    pub fs: Arc<dyn kintsu_fs::FileSystem>,
+}
+
+impl WorkspaceMember {
+    pub fn name(&self) -> &str {
+        &self.manifest.package().name
+    }
+}
+
+/// The registry operations a workspace publish relies on.
+pub trait PublishTarget: Send + Sync {
+    /// Publishes `member` with `manifest`, which has inter-member dependencies pinned.
+    fn publish(
+        &self,
+        member: &WorkspaceMember,
+        manifest: PackageManifests,
+        progress: &ProgressManager,
+    ) -> impl Future<Output = Result<VersionSerde, Error>> + Send;
+
+    fn is_resolvable(
+        &self,
+        name: &str,
+        version: &VersionSerde,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    fn yank(
+        &self,
+        name: &str,
+        version: &VersionSerde,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+impl PublishTarget for crate::RegistryClient {
+    async fn publish(
+        &self,
+        member: &WorkspaceMember,
+        manifest: PackageManifests,
+        progress: &ProgressManager,
+    ) -> Result<VersionSerde, Error> {
+        // a dry run pins dependents to the version the member would be published as
+        let version = manifest.package().version.clone();
+        let published = self
+            .publish_compiled_package_with_progress(
+                manifest,
+                member.fs.clone(),
+                &member.root,
+                progress.clone(),
+            )
+            .await?;
+        match published {
+            Mutation::Performed(published) => Ok(published.qualified_version),
+            Mutation::DryRun(plan) => {
+                progress.println(prefixes::PUBLISHING, plan.to_string().trim_end());
+                Ok(version)
+            },
+        }
+    }
+
+    async fn is_resolvable(
+        &self,
+        name: &str,
+        version: &VersionSerde,
+    ) -> Result<bool, Error> {
+        if self.is_dry_run() {
+            return Ok(true);
+        }
+        self.version_exists(name, version).await
+    }
+
+    async fn yank(
+        &self,
+        name: &str,
+        version: &VersionSerde,
+    ) -> Result<(), Error> {
+        self.yank_version(name, version).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PublishOptions {
+    /// Delay between checks for a published version
+    pub poll_interval: Duration,
+    /// How long to wait for a published version before giving up
+    pub resolve_timeout: Duration,
+}
+
+impl Default for PublishOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(2),
+            resolve_timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+pub struct WorkspacePublisher<'a, T: PublishTarget> {
+    target: &'a T,
+    options: PublishOptions,
+    progress: ProgressManager,
+}
+
+impl<'a, T: PublishTarget> WorkspacePublisher<'a, T> {
+    pub fn new(
+        target: &'a T,
+        options: PublishOptions,
+        progress: ProgressManager,
+    ) -> Self {
+        Self {
+            target,
+            options,
+            progress,
+        }
+    }
+
+    /// Publishes every member in dependency order, returning the published `(name, version)`
+    /// pairs in that order.
+    pub async fn publish(
+        &self,
+        members: &[WorkspaceMember],
+    ) -> Result<Vec<(String, VersionSerde)>, Error> {
+        let dependencies = member_dependencies(members);
+        let order = publish_order(members, &dependencies)?;
+
+        let mut published: Vec<(String, VersionSerde)> = Vec::with_capacity(members.len());
+        let mut versions: BTreeMap<usize, VersionSerde> = BTreeMap::new();
+
+        for idx in order {
+            let member = &members[idx];
+
+            let mut manifest = member.manifest.clone();
+            for (dep_name, dep_idx) in &dependencies[idx] {
+                let version = &versions[dep_idx];
+                if let Some(dep) = manifest.dependencies_mut().get_mut(dep_name) {
+                    dep.pin_to_published(version);
+                }
+            }
+
+            self.progress
+                .println(prefixes::PUBLISHING, member.name());
+
+            let version = match self
+                .target
+                .publish(member, manifest, &self.progress)
+                .await
+            {
+                Ok(version) => version,
+                Err(err) => {
+                    return Err(self
+                        .roll_back(member.name(), err, &published)
+                        .await);
+                },
+            };
+            published.push((member.name().to_string(), version.clone()));
+
+            if let Err(err) = self
+                .wait_until_resolvable(member.name(), &version)
+                .await
+            {
+                return Err(self
+                    .roll_back(member.name(), err, &published)
+                    .await);
+            }
+            versions.insert(idx, version);
+        }
+
+        Ok(published)
+    }
+
+    async fn wait_until_resolvable(
+        &self,
+        name: &str,
+        version: &VersionSerde,
+    ) -> Result<(), Error> {
+        let started = Instant::now();
+        self.progress
+            .println(prefixes::WAITING, &format!("for {name}@{version}..."));
+
+        loop {
+            if self
+                .target
+                .is_resolvable(name, version)
+                .await?
+            {
+                return Ok(());
+            }
+
+            let waited = started.elapsed();
+            if waited >= self.options.resolve_timeout {
+                return Err(Error::NotResolvable {
+                    package: format!("{name}@{version}"),
+                    waited,
+                });
+            }
+
+            tokio::time::sleep(self.options.poll_interval).await;
+        }
+    }
+
+    /// Yanks everything in `published`, newest first, and wraps `source` with the outcome.
+    async fn roll_back(
+        &self,
+        package: &str,
+        source: Error,
+        published: &[(String, VersionSerde)],
+    ) -> Error {
+        let mut yanked = vec![];
+        let mut not_yanked = vec![];
+
+        for (name, version) in published.iter().rev() {
+            let qualified = format!("{name}@{version}");
+            self.progress
+                .println(prefixes::YANKING, &qualified);
+
+            match self.target.yank(name, version).await {
+                Ok(()) => yanked.push(qualified),
+                Err(err) => {
+                    tracing::error!("failed to yank {qualified}: {err}");
+                    not_yanked.push(qualified);
+                },
+            }
+        }
+
+        Error::WorkspacePublish {
+            package: package.to_string(),
+            source: Box::new(source),
+            yanked,
+            not_yanked,
+        }
+    }
+}
+
+/// For each member, its path dependencies on other members as `dependency name -> member index`.
+fn member_dependencies(members: &[WorkspaceMember]) -> Vec<BTreeMap<String, usize>> {
+    let roots: BTreeMap<PathBuf, usize> = members
+        .iter()
+        .enumerate()
+        .map(|(idx, member)| (normalize(&member.root), idx))
+        .collect();
+
+    members
+        .iter()
+        .enumerate()
+        .map(|(idx, member)| {
+            member
+                .manifest
+                .dependencies()
+                .iter()
+                .filter_map(|(name, dep)| {
+                    let target = roots.get(&normalize(&member.root.join(dep.path()?)))?;
+                    (*target != idx).then(|| (name.clone(), *target))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Orders members so each is published after the members it depends on. Independent
+/// members are ordered by name, so the order is stable across runs.
+fn publish_order(
+    members: &[WorkspaceMember],
+    dependencies: &[BTreeMap<String, usize>],
+) -> Result<Vec<usize>, Error> {
+    let mut seen = BTreeSet::new();
+    for member in members {
+        if !seen.insert(member.name()) {
+            return Err(Error::DuplicateMember(member.name().to_string()));
+        }
+    }
+
+    let mut remaining: Vec<BTreeSet<usize>> = dependencies
+        .iter()
+        .map(|deps| deps.values().copied().collect())
+        .collect();
+    let mut ready: BTreeSet<(&str, usize)> = remaining
+        .iter()
+        .enumerate()
+        .filter(|(_, deps)| deps.is_empty())
+        .map(|(idx, _)| (members[idx].name(), idx))
+        .collect();
+
+    let mut order = Vec::with_capacity(members.len());
+    while let Some((_, idx)) = ready.pop_first() {
+        order.push(idx);
+        for (dependent, deps) in remaining.iter_mut().enumerate() {
+            if deps.remove(&idx) && deps.is_empty() {
+                ready.insert((members[dependent].name(), dependent));
+            }
+        }
+    }
+
+    if order.len() == members.len() {
+        return Ok(order);
+    }
+
+    // every member left over still waits on another left-over member, so following
+    // any of them eventually revisits a member
+    let mut path = vec![
+        remaining
+            .iter()
+            .position(|deps| !deps.is_empty())
+            .expect("unordered members have pending dependencies"),
+    ];
+    loop {
+        let next = *remaining[*path.last().unwrap()]
+            .first()
+            .expect("unordered members have pending dependencies");
+        if let Some(start) = path.iter().position(|idx| *idx == next) {
+            let mut cycle: Vec<String> = path[start..]
+                .iter()
+                .map(|idx| members[*idx].name().to_string())
+                .collect();
+            cycle.push(members[next].name().to_string());
+            return Err(Error::WorkspaceCycle(cycle));
+        }
+        path.push(next);
+    }
+}
+
+/// Resolves `.` and `..` lexically, so member roots and dependency paths compare equal
+/// without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir
+                if matches!(out.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                out.pop();
+            },
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MockTarget {
+        fail: Option<&'static str>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl MockTarget {
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl PublishTarget for MockTarget {
+        async fn publish(
+            &self,
+            member: &WorkspaceMember,
+            manifest: PackageManifests,
+            _: &ProgressManager,
+        ) -> Result<VersionSerde, Error> {
+            let deps = manifest
+                .dependencies()
+                .iter()
+                .map(|(name, dep)| {
+                    format!(
+                        "{name}{}",
+                        dep.version()
+                            .map(|v| v.to_string())
+                            .unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("publish {} [{deps}]", member.name()));
+
+            if self.fail == Some(member.name()) {
+                return Err(Error::NoAuth);
+            }
+            Ok(manifest.package().version.clone())
+        }
+
+        async fn is_resolvable(
+            &self,
+            _: &str,
+            _: &VersionSerde,
+        ) -> Result<bool, Error> {
+            Ok(true)
+        }
+
+        async fn yank(
+            &self,
+            name: &str,
+            version: &VersionSerde,
+        ) -> Result<(), Error> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("yank {name}@{version}"));
+            Ok(())
+        }
+    }
+
+    fn member(
+        root: &str,
+        name: &str,
+        deps: &[(&str, &str)],
+    ) -> WorkspaceMember {
+        let deps = deps
+            .iter()
+            .map(|(dep, path)| format!("{dep} = {{ path = \"{path}\" }}\n"))
+            .collect::<String>();
+        let src = format!(
+            "version = \"v1\"\n[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n[dependencies]\n{deps}"
+        );
+
+        WorkspaceMember {
+            root: root.into(),
+            manifest: toml::from_str(&src).unwrap(),
+            fs: Arc::new(kintsu_fs::memory::MemoryFileSystem::new()),
+        }
+    }
+
+    fn workspace() -> Vec<WorkspaceMember> {
+        vec![
+            member(
+                "./pkgs/app",
+                "app",
+                &[("api", "../api"), ("base", "../base")],
+            ),
+            member("./pkgs/api", "api", &[("base", "../base")]),
+            member("pkgs/base", "base", &[]),
+            member("./pkgs/tools", "tools", &[]),
+        ]
+    }
+
+    fn publisher(target: &MockTarget) -> WorkspacePublisher<'_, MockTarget> {
+        WorkspacePublisher::new(
+            target,
+            PublishOptions {
+                poll_interval: Duration::from_millis(1),
+                resolve_timeout: Duration::from_millis(10),
+            },
+            ProgressManager::disabled(),
+        )
+    }
+
+    #[tokio::test]
+    async fn publishes_in_dependency_order_with_pinned_versions() {
+        let target = MockTarget::default();
+        let published = publisher(&target)
+            .publish(&workspace())
+            .await
+            .unwrap();
+
+        let names: Vec<_> = published
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(names, ["base", "api", "app", "tools"]);
+        assert_eq!(
+            target.calls(),
+            [
+                "publish base []",
+                "publish api [base=0.1.0]",
+                "publish app [api=0.1.0,base=0.1.0]",
+                "publish tools []",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn yanks_published_members_when_a_later_publish_fails() {
+        let target = MockTarget {
+            fail: Some("app"),
+            ..Default::default()
+        };
+        let err = publisher(&target)
+            .publish(&workspace())
+            .await
+            .unwrap_err();
+
+        let Error::WorkspacePublish {
+            package,
+            yanked,
+            not_yanked,
+            ..
+        } = err
+        else {
+            panic!("expected a workspace publish error, got {err}");
+        };
+        assert_eq!(package, "app");
+        assert_eq!(yanked, ["api@0.1.0", "base@0.1.0"]);
+        assert!(not_yanked.is_empty());
+        assert_eq!(target.calls()[3..], ["yank api@0.1.0", "yank base@0.1.0"]);
+    }
+
+    #[test]
+    fn reports_dependency_cycles() {
+        let members = vec![
+            member("a", "a", &[("b", "../b")]),
+            member("b", "b", &[("c", "../c")]),
+            member("c", "c", &[("a", "../a")]),
+            member("d", "d", &[]),
+        ];
+        let err = publish_order(&members, &member_dependencies(&members)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "workspace members form a dependency cycle: a -> b -> c -> a"
+        );
+    }
+
+    #[test_case::test_case("./a/../b/./c", "b/c"; "dots")]
+    #[test_case::test_case("../a/b/..", "../a"; "leading parent")]
+    #[test_case::test_case("/x/y/../z", "/x/z"; "absolute")]
+    fn normalizes_lexically(
+        path: &str,
+        expect: &str,
+    ) {
+        assert_eq!(normalize(Path::new(path)), PathBuf::from(expect));
+    }
+}
@@ -0,0 +1,346 @@
+//! Error catalog - every error code defined by the domain errors, for documentation.
+//!
+//! Entries are generated by [`define_domain_errors!`] from the same definitions the compiler
+//! reports with, so the pages rendered by [`render_json`] and [`render_markdown`] cannot drift
+//! from the codes the compiler emits.
+//!
+//! Codes are stable: assigning one code twice fails the build, and the tests check the
+//! catalog against the codes assigned in `errors/codes.txt`, so codes are neither reused nor
+//! renumbered.
+
+use std::fmt::Write;
+
+use crate::{Domain, ErrorCode, Severity, domains::*};
+
+/// One defined error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorEntry {
+    pub code: ErrorCode,
+    /// The variant of the domain error, e.g. `UndefinedType`
+    pub name: &'static str,
+    /// Message template, with `{field}` placeholders
+    pub message: &'static str,
+    pub help: Option<&'static str>,
+    pub severity: Severity,
+    /// Fields interpolated into the message
+    pub fields: &'static [&'static str],
+}
+
+/// The catalogs of every domain error.
+const DOMAINS: &[&[ErrorEntry]] = &[
+    LexicalError::CATALOG,
+    ParsingError::CATALOG,
+    NamespaceError::CATALOG,
+    TypeDefError::CATALOG,
+    ResolutionError::CATALOG,
+    UnionError::CATALOG,
+    MetadataError::CATALOG,
+    TaggingError::CATALOG,
+    TypeExprError::CATALOG,
+    PackageError::CATALOG,
+    FilesystemError::CATALOG,
+    InternalError::CATALOG,
+    LintError::CATALOG,
+];
+
+// assigning one code to two errors fails the build, across domains as well as within them
+const _: () = assert_unique_codes(DOMAINS);
+
+/// Panics on the first code assigned to more than one entry of `domains`.
+const fn assert_unique_codes(domains: &[&[ErrorEntry]]) {
+    let mut d = 0;
+    while d < domains.len() {
+        let mut e = 0;
+        while e < domains[d].len() {
+            let code = domains[d][e].code;
+            // every later entry, in this domain and the following ones
+            let (mut other_d, mut other_e) = (d, e + 1);
+            while other_d < domains.len() {
+                while other_e < domains[other_d].len() {
+                    let other = domains[other_d][other_e].code;
+                    if code.domain as u8 == other.domain as u8
+                        && code.category as u8 == other.category as u8
+                        && code.sequence == other.sequence
+                    {
+                        panic!("an error code is assigned to more than one error");
+                    }
+                    other_e += 1;
+                }
+                other_d += 1;
+                other_e = 0;
+            }
+            e += 1;
+        }
+        d += 1;
+    }
+}
+
+/// Every defined error code, ordered by code.
+pub fn catalog() -> Vec<&'static ErrorEntry> {
+    let mut entries: Vec<_> = DOMAINS.iter().copied().flatten().collect();
+
+    entries.sort_by_key(|entry| {
+        (
+            domain_doc(entry.code.domain).1,
+            entry.code.category.as_digit(),
+            entry.code.sequence,
+        )
+    });
+    entries
+}
+
+/// Title and ERR specification of a domain, per ERR-0001
+pub(crate) const fn domain_doc(domain: Domain) -> (&'static str, &'static str) {
+    match domain {
+        Domain::LX => ("Lexical errors", "ERR-0002"),
+        Domain::PR => ("Parsing errors", "ERR-0003"),
+        Domain::NS => ("Namespace errors", "ERR-0004"),
+        Domain::TY => ("Type definition errors", "ERR-0005"),
+        Domain::TR => ("Type resolution errors", "ERR-0006"),
+        Domain::UN => ("Union errors", "ERR-0007"),
+        Domain::MT => ("Metadata errors", "ERR-0008"),
+        Domain::TG => ("Tagging errors", "ERR-0009"),
+        Domain::TE => ("Type expression errors", "ERR-0010"),
+        Domain::PK => ("Package errors", "ERR-0011"),
+        Domain::RG => ("Registry errors", "ERR-0012"),
+        Domain::FS => ("Filesystem errors", "ERR-0013"),
+        Domain::IN => ("Internal errors", "ERR-0014"),
+        Domain::LT => ("Lint errors", "ERR-0015"),
+    }
+}
+
+pub(crate) fn spec_url(spec: &str) -> String {
+    format!("https://docs.kintsu.dev/specs/err/{spec}")
+}
+
+/// Renders the catalog as a JSON array, one object per code.
+pub fn render_json() -> String {
+    let mut out = String::from("[\n");
+    let entries = catalog();
+    for (index, entry) in entries.iter().enumerate() {
+        let (_, spec) = domain_doc(entry.code.domain);
+        let fields = entry
+            .fields
+            .iter()
+            .map(|field| json_string(field))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        out.push_str("  {\n");
+        let _ = writeln!(
+            out,
+            "    \"code\": {},",
+            json_string(&entry.code.to_string())
+        );
+        let _ = writeln!(out, "    \"name\": {},", json_string(entry.name));
+        let _ = writeln!(
+            out,
+            "    \"domain\": {},",
+            json_string(entry.code.domain.as_str())
+        );
+        let _ = writeln!(out, "    \"category\": {},", entry.code.category.as_digit());
+        let _ = writeln!(
+            out,
+            "    \"severity\": {},",
+            json_string(entry.severity.as_str())
+        );
+        let _ = writeln!(out, "    \"message\": {},", json_string(entry.message));
+        let _ = writeln!(
+            out,
+            "    \"help\": {},",
+            entry
+                .help
+                .map(json_string)
+                .unwrap_or_else(|| "null".into())
+        );
+        let _ = writeln!(out, "    \"fields\": [{fields}],");
+        let _ = writeln!(out, "    \"spec\": {}", json_string(&spec_url(spec)));
+        out.push_str(if index + 1 == entries.len() {
+            "  }\n"
+        } else {
+            "  },\n"
+        });
+    }
+    out.push(']');
+    out
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            },
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Renders the catalog as Markdown, one section per domain and one heading per code.
+pub fn render_markdown() -> String {
+    let mut out = String::from("# Error Codes\n");
+    let mut domain = None;
+    for entry in catalog() {
+        if domain != Some(entry.code.domain) {
+            domain = Some(entry.code.domain);
+            let (title, spec) = domain_doc(entry.code.domain);
+            let _ = write!(
+                out,
+                "\n## {title} (K{})\n\nSpecified by [{spec}]({}).\n",
+                entry.code.domain,
+                spec_url(spec)
+            );
+        }
+
+        let _ = write!(
+            out,
+            "\n### {}\n\n`{}` ({})\n\n```text\n{}\n```\n",
+            entry.code, entry.name, entry.severity, entry.message
+        );
+        if !entry.fields.is_empty() {
+            let fields = entry
+                .fields
+                .iter()
+                .map(|field| format!("`{field}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = write!(out, "\nFields: {fields}\n");
+        }
+        if let Some(help) = entry.help {
+            let _ = write!(out, "\nHelp: {help}\n");
+        }
+        if let Some(details) = crate::explain::details(entry.code) {
+            let _ = write!(out, "\n{details}\n");
+        }
+    }
+    out
+}
+
+/// Renders the English messages as a [`Locale`](crate::Locale) template, to translate.
+pub fn render_fluent() -> String {
+    let mut out = String::from("# Kintsu diagnostic messages\n");
+    for entry in catalog() {
+        let mut message = entry.message.to_string();
+        for field in entry.fields {
+            message = message.replace(&format!("{{{field}}}"), &format!("{{ ${field} }}"));
+        }
+        // continuation lines are indented
+        let message = message.replace('\n', "\n    ");
+        let _ = write!(out, "\n{} = {message}\n", entry.code);
+        if let Some(help) = entry.help {
+            let _ = writeln!(out, "    .help = {help}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn codes_are_unique_and_ordered() {
+        let codes: Vec<_> = catalog()
+            .iter()
+            .map(|entry| entry.code.to_string())
+            .collect();
+        let mut unique = codes.clone();
+        unique.dedup();
+        assert_eq!(codes, unique);
+        assert_eq!(codes.first().map(String::as_str), Some("KLX0001"));
+    }
+
+    #[test]
+    #[should_panic(expected = "assigned to more than one error")]
+    fn duplicate_codes_across_domains_are_rejected() {
+        let duplicate = [ErrorEntry {
+            name: "Duplicate",
+            ..InternalError::CATALOG[0]
+        }];
+        assert_unique_codes(&[LexicalError::CATALOG, InternalError::CATALOG, &duplicate]);
+    }
+
+    #[test]
+    fn codes_match_stability_snapshot() {
+        let assigned: BTreeMap<&str, &str> = include_str!("../codes.txt")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.split_once(' ').unwrap())
+            .collect();
+        let current: BTreeMap<String, &str> = catalog()
+            .iter()
+            .map(|entry| (entry.code.to_string(), entry.name))
+            .collect();
+
+        for (code, name) in &assigned {
+            match current.get(*code) {
+                Some(current) => {
+                    assert_eq!(
+                        current, name,
+                        "{code} is assigned to {name} in codes.txt, and codes are never reused"
+                    )
+                },
+                None => {
+                    assert_eq!(
+                        *name, "retired",
+                        "{code} ({name}) was removed or renumbered; keep its code and name it `retired` in codes.txt"
+                    )
+                },
+            }
+        }
+
+        let unassigned: Vec<_> = current
+            .iter()
+            .filter(|(code, _)| !assigned.contains_key(code.as_str()))
+            .map(|(code, name)| format!("{code} {name}"))
+            .collect();
+        assert!(
+            unassigned.is_empty(),
+            "append the new error codes to errors/codes.txt:\n{}",
+            unassigned.join("\n")
+        );
+    }
+
+    #[test]
+    fn entries_match_constructed_errors() {
+        let entry = catalog()
+            .into_iter()
+            .find(|entry| entry.code.to_string() == "KTR1002")
+            .unwrap();
+        let err = ResolutionError::undefined_type("User")
+            .unlocated()
+            .build();
+
+        assert_eq!(entry.name, "UndefinedType");
+        assert_eq!(entry.message.replace("{name}", "User"), err.message());
+        assert_eq!(entry.help, err.help_text());
+        assert_eq!(entry.severity, err.severity());
+        assert_eq!(entry.fields, ["name"]);
+    }
+
+    #[test]
+    fn renders_every_code() {
+        let json = render_json();
+        let markdown = render_markdown();
+        for entry in catalog() {
+            let code = entry.code.to_string();
+            assert!(json.contains(&format!("\"code\": \"{code}\"")), "{code}");
+            assert!(markdown.contains(&format!("### {code}\n")), "{code}");
+        }
+        assert!(markdown.contains("## Package errors (KPK)"));
+        assert!(render_fluent().contains("\nKTR1002 = undefined type: '{ $name }'\n"));
+        assert_eq!(json_string("a \"b\"\n"), r#""a \"b\"\n""#);
+    }
+}
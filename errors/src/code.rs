@@ -29,6 +29,8 @@ pub enum Domain {
     FS,
     /// Internal errors (KIN) - ERR-0014
     IN,
+    /// Lint errors (KLT) - ERR-0015
+    LT,
 }
 
 impl Domain {
@@ -47,6 +49,7 @@ impl Domain {
             Self::RG => "RG",
             Self::FS => "FS",
             Self::IN => "IN",
+            Self::LT => "LT",
         }
     }
 }
@@ -1,7 +1,7 @@
 //! Diagnostic integration with miette.
 //! Converts errors to miette-compatible diagnostics for rich error reporting.
 
-use crate::{ErrorCode, Severity, Span};
+use crate::{ErrorCode, Locale, Severity, Span};
 use miette::{Diagnostic, NamedSource, SourceSpan};
 use std::path::Path;
 
@@ -212,6 +212,22 @@ impl DiagnosticBuilder {
         self
     }
 
+    /// Translates the message and help with `locale`, where it has templates for the code,
+    /// interpolating `args`.
+    pub fn locale(
+        mut self,
+        locale: &Locale,
+        args: &[(&str, String)],
+    ) -> Self {
+        if let Some(message) = locale.message(self.code, args) {
+            self.message = message;
+        }
+        if let Some(help) = locale.help(self.code, args) {
+            self.help = Some(help);
+        }
+        self
+    }
+
     pub fn span(
         mut self,
         span: Span,
@@ -20,6 +20,14 @@ define_domain_errors! {
             help: "ensure schema directory contains .ks files",
         },
 
+        /// KFS2003: Filesystem quota exceeded
+        QuotaExceeded {
+            code: (FS, Validation, 3),
+            message: "filesystem quota exceeded: {resource} limit is {limit}, {requested} requested",
+            help: "reduce the size or number of files, or raise the quota",
+            fields: { resource: String, limit: u64, requested: u64 },
+        },
+
         /// KFS4001: File not found
         FileNotFound {
             code: (FS, Missing, 1),
@@ -66,6 +74,19 @@ impl FilesystemError {
         ErrorBuilder::new(Self::EmptyFileList { span: None })
     }
 
+    pub fn quota_exceeded(
+        resource: impl Into<String>,
+        limit: u64,
+        requested: u64,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::QuotaExceeded {
+            resource: resource.into(),
+            limit,
+            requested,
+            span: None,
+        })
+    }
+
     pub fn file_not_found(path: impl Into<String>) -> ErrorBuilder<Unspanned, Self> {
         ErrorBuilder::new(Self::FileNotFound {
             path: path.into(),
@@ -0,0 +1,78 @@
+//! Lint errors (KLT) - [ERR-0015](https://docs.kintsu.dev/specs/err/ERR-0015)
+//!
+//! Violations of lint rules run over resolved declarations. Rules set a default level which
+//! the `[lint]` table of a package manifest can override.
+
+define_domain_errors! {
+    /// Lint errors (KLT domain)
+    /// https://docs.kintsu.dev/specs/err/ERR-0015
+    pub enum LintError {
+        /// KLT2001: Lint rule denied at error level
+        RuleDenied {
+            code: (LT, Validation, 1),
+            message: "{group}::{rule}: {reason}",
+            help: "fix the declaration, or lower the level of the rule under [lint.overrides] in schema.toml",
+            fields: { group: String, rule: String, reason: String },
+        },
+
+        /// KLT2002: Warnings denied by the compile policy
+        WarningsDenied {
+            code: (LT, Validation, 2),
+            message: "warnings are denied: {codes}",
+            help: "fix the warnings, or allow their codes with `allow_warnings` under [lint] in schema.toml",
+            fields: { codes: String },
+        },
+
+        /// KLT8001: Lint rule violated
+        RuleViolated {
+            code: (LT, Warning, 1),
+            message: "{group}::{rule}: {reason}",
+            help: "set the level of the rule to \"silent\" under [lint.overrides] in schema.toml to allow this",
+            severity: Warning,
+            fields: { group: String, rule: String, reason: String },
+        },
+    }
+}
+
+use crate::builder::{ErrorBuilder, Unspanned};
+
+impl LintError {
+    pub fn rule_denied(
+        group: impl Into<String>,
+        rule: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::RuleDenied {
+            group: group.into(),
+            rule: rule.into(),
+            reason: reason.into(),
+            span: None,
+        })
+    }
+
+    pub fn warnings_denied<'a>(
+        codes: impl IntoIterator<Item = &'a String>
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::WarningsDenied {
+            codes: codes
+                .into_iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", "),
+            span: None,
+        })
+    }
+
+    pub fn rule_violated(
+        group: impl Into<String>,
+        rule: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::RuleViolated {
+            group: group.into(),
+            rule: rule.into(),
+            reason: reason.into(),
+            span: None,
+        })
+    }
+}
@@ -21,6 +21,54 @@ define_domain_errors! {
             fields: { reason: String },
         },
 
+        /// KMT2003: Invalid validation constraint
+        InvalidConstraint {
+            code: (MT, Validation, 3),
+            message: "invalid #[{constraint}] constraint on '{field}': {reason}",
+            help: "min/max apply to numeric types, pattern applies to str, len applies to str, binary and arrays",
+            fields: { constraint: String, field: String, reason: String },
+        },
+
+        /// KMT2004: Attribute used in an unsupported position
+        MisplacedAttribute {
+            code: (MT, Validation, 4),
+            message: "#[{attribute}] attribute is not valid on {target}",
+            help: "constraint attributes belong on struct fields and operation arguments, #[contiguous], #[min] and #[max] also on integer enums; other attributes belong on items or namespaces",
+            fields: { attribute: String, target: String },
+        },
+
+        /// KMT2005: Invalid HTTP binding
+        InvalidHttpBinding {
+            code: (MT, Validation, 5),
+            message: "invalid #[http] binding on operation '{operation}': {reason}",
+            help: "use #[http(method = \"GET\", path = \"/items/{id}\")] where every {param} in the path names an operation argument",
+            fields: { operation: String, reason: String },
+        },
+
+        /// KMT2006: Enum discriminant outside its declared layout
+        InvalidDiscriminant {
+            code: (MT, Validation, 6),
+            message: "invalid discriminant for '{variant}' in enum '{name}': {reason}",
+            help: "#[contiguous] requires discriminants without gaps or repeats, and #[min]/#[max] bound each discriminant",
+            fields: { name: String, variant: String, reason: String },
+        },
+
+        /// KMT2007: Example payload not matching its declared type
+        InvalidExample {
+            code: (MT, Validation, 7),
+            message: "invalid #[example] on '{target}': {reason}",
+            help: "examples are JSON, e.g. #[example('{\"id\": 1}')] on fields and #[example(input = '{...}', output = '...')] on operations, and must match the declared type",
+            fields: { target: String, reason: String },
+        },
+
+        /// KMT2008: Invalid GraphQL operation kind
+        InvalidGraphqlKind {
+            code: (MT, Validation, 8),
+            message: "invalid #[graphql] attribute on operation '{operation}': {reason}",
+            help: "use #[graphql(query)] or #[graphql(mutation)]",
+            fields: { operation: String, reason: String },
+        },
+
         /// KMT3001: Version conflict
         VersionConflict {
             code: (MT, Conflict, 1),
@@ -64,6 +112,76 @@ impl MetadataError {
         })
     }
 
+    pub fn invalid_constraint(
+        constraint: impl Into<String>,
+        field: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::InvalidConstraint {
+            constraint: constraint.into(),
+            field: field.into(),
+            reason: reason.into(),
+            span: None,
+        })
+    }
+
+    pub fn misplaced_attribute(
+        attribute: impl Into<String>,
+        target: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::MisplacedAttribute {
+            attribute: attribute.into(),
+            target: target.into(),
+            span: None,
+        })
+    }
+
+    pub fn invalid_http_binding(
+        operation: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::InvalidHttpBinding {
+            operation: operation.into(),
+            reason: reason.into(),
+            span: None,
+        })
+    }
+
+    pub fn invalid_example(
+        target: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::InvalidExample {
+            target: target.into(),
+            reason: reason.into(),
+            span: None,
+        })
+    }
+
+    pub fn invalid_graphql_kind(
+        operation: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::InvalidGraphqlKind {
+            operation: operation.into(),
+            reason: reason.into(),
+            span: None,
+        })
+    }
+
+    pub fn invalid_discriminant(
+        name: impl Into<String>,
+        variant: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::InvalidDiscriminant {
+            name: name.into(),
+            variant: variant.into(),
+            reason: reason.into(),
+            span: None,
+        })
+    }
+
     pub fn version_conflict(
         values: impl IntoIterator<Item = usize>
     ) -> ErrorBuilder<Unspanned, Self> {
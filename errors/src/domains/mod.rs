@@ -1,6 +1,7 @@
 pub mod filesystem;
 pub mod internal;
 pub mod lexical;
+pub mod lint;
 pub mod metadata;
 pub mod namespace;
 pub mod package;
@@ -14,6 +15,7 @@ pub mod union;
 pub use filesystem::FilesystemError;
 pub use internal::InternalError;
 pub use lexical::LexicalError;
+pub use lint::LintError;
 pub use metadata::MetadataError;
 pub use namespace::NamespaceError;
 pub use package::PackageError;
@@ -13,6 +13,14 @@ define_domain_errors! {
             fields: { reason: String },
         },
 
+        /// KPK2001: Compile hook failed
+        HookFailed {
+            code: (PK, Validation, 1),
+            message: "hook '{hook}' failed: {reason}",
+            help: "fix what the hook reported, or remove the hook",
+            fields: { hook: String, reason: String },
+        },
+
         /// KPK3001: Duplicate dependency
         DuplicateDependency {
             code: (PK, Conflict, 1),
@@ -21,6 +29,14 @@ define_domain_errors! {
             fields: { name: String },
         },
 
+        /// KPK3002: Profile excludes a referenced item
+        ProfileExcludesReference {
+            code: (PK, Conflict, 2),
+            message: "profile '{profile}' excludes '{item}', which '{referrer}' refers to",
+            help: "exclude the referring item as well, or stop excluding the item it refers to",
+            fields: { profile: String, item: String, referrer: String },
+        },
+
         /// KPK4001: Manifest not found
         ManifestNotFound {
             code: (PK, Missing, 1),
@@ -36,6 +52,14 @@ define_domain_errors! {
             help: "run 'kintsu install' to resolve and lock dependencies",
         },
 
+        /// KPK4003: Profile not found
+        UnknownProfile {
+            code: (PK, Missing, 3),
+            message: "no profile named '{profile}' in the manifest of '{package}'",
+            help: "declare the profile under [profiles.<name>] in schema.toml",
+            fields: { profile: String, package: String },
+        },
+
         /// KPK6001: Dependency version mismatch
         DependencyVersionMismatch {
             code: (PK, Compatibility, 1),
@@ -52,6 +76,40 @@ define_domain_errors! {
             severity: Warning,
         },
 
+        /// KPK6003: Unsatisfiable dependency requirements
+        UnsatisfiableDependencies {
+            code: (PK, Compatibility, 3),
+            message: "dependency requirements cannot be satisfied:\n{derivation}",
+            help: "relax the conflicting version requirements or publish compatible versions",
+            fields: { derivation: String },
+        },
+
+        /// KPK6004: Yanked version in use
+        YankedVersion {
+            code: (PK, Compatibility, 4),
+            message: "{package}@{version} has been yanked, but is pinned by the lockfile",
+            help: "update the dependency to a version which has not been yanked",
+            severity: Warning,
+            fields: { package: String, version: String },
+        },
+
+        /// KPK6005: Deprecated dependency in use
+        DeprecatedDependency {
+            code: (PK, Compatibility, 5),
+            message: "{package}@{version} is deprecated: {notice}",
+            help: "migrate away from the dependency before it stops receiving updates",
+            severity: Warning,
+            fields: { package: String, version: String, notice: String },
+        },
+
+        /// KPK6006: Declarations in a newer format
+        UnsupportedDeclarationVersion {
+            code: (PK, Compatibility, 6),
+            message: "declarations use format version {found}, but this build reads up to version {supported}",
+            help: "upgrade kintsu to load declarations published by newer compilers",
+            fields: { found: String, supported: String },
+        },
+
         /// Generic manifest error (for wrapping kintsu_manifests::Error)
         ManifestError {
             code: (PK, Internal, 1),
@@ -71,6 +129,17 @@ impl PackageError {
         })
     }
 
+    pub fn hook_failed(
+        hook: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::HookFailed {
+            hook: hook.into(),
+            reason: reason.into(),
+            span: None,
+        })
+    }
+
     pub fn duplicate_dep(name: impl Into<String>) -> ErrorBuilder<Unspanned, Self> {
         ErrorBuilder::new(Self::DuplicateDependency {
             name: name.into(),
@@ -85,6 +154,30 @@ impl PackageError {
         })
     }
 
+    pub fn profile_excludes_reference(
+        profile: impl Into<String>,
+        item: impl Into<String>,
+        referrer: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::ProfileExcludesReference {
+            profile: profile.into(),
+            item: item.into(),
+            referrer: referrer.into(),
+            span: None,
+        })
+    }
+
+    pub fn unknown_profile(
+        profile: impl Into<String>,
+        package: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::UnknownProfile {
+            profile: profile.into(),
+            package: package.into(),
+            span: None,
+        })
+    }
+
     pub fn lockfile_not_found() -> ErrorBuilder<Unspanned, Self> {
         ErrorBuilder::new(Self::LockfileNotFound { span: None })
     }
@@ -108,6 +201,50 @@ impl PackageError {
         ErrorBuilder::new(Self::LockfileOutOfDate { span: None })
     }
 
+    pub fn unsatisfiable_dependencies(
+        derivation: impl Into<String>
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::UnsatisfiableDependencies {
+            derivation: derivation.into(),
+            span: None,
+        })
+    }
+
+    pub fn yanked_version(
+        package: impl Into<String>,
+        version: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::YankedVersion {
+            package: package.into(),
+            version: version.into(),
+            span: None,
+        })
+    }
+
+    pub fn deprecated_dependency(
+        package: impl Into<String>,
+        version: impl Into<String>,
+        notice: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::DeprecatedDependency {
+            package: package.into(),
+            version: version.into(),
+            notice: notice.into(),
+            span: None,
+        })
+    }
+
+    pub fn unsupported_declaration_version(
+        found: impl Into<String>,
+        supported: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::UnsupportedDeclarationVersion {
+            found: found.into(),
+            supported: supported.into(),
+            span: None,
+        })
+    }
+
     pub fn manifest_error(reason: impl Into<String>) -> ErrorBuilder<Unspanned, Self> {
         ErrorBuilder::new(Self::ManifestError {
             reason: reason.into(),
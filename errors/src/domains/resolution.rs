@@ -29,6 +29,30 @@ define_domain_errors! {
             fields: { name: String },
         },
 
+        /// KTR1004: Internal item referenced from another package
+        InternalItem {
+            code: (TR, Resolution, 4),
+            message: "'{name}' is internal to package '{package}'",
+            help: "only items declared without a visibility modifier or with `pub` can be used by other packages",
+            fields: { name: String, package: String },
+        },
+
+        /// KTR1005: Internal type exposed by a public item
+        InternalTypeExposed {
+            code: (TR, Resolution, 5),
+            message: "public item '{item}' exposes internal type '{name}'",
+            help: "internal types are excluded from declarations; mark the item `internal` or make the type `pub`",
+            fields: { item: String, name: String },
+        },
+
+        /// KTR3001: Ambiguous glob import
+        AmbiguousGlobImport {
+            code: (TR, Conflict, 1),
+            message: "ambiguous type '{name}': exported by multiple glob imports ({candidates})",
+            help: "import the intended type explicitly, e.g. `use pkg::ns::Type`",
+            fields: { name: String, candidates: String },
+        },
+
         /// KTR5001: Circular dependency
         CircularDependency {
             code: (TR, Cycle, 1),
@@ -52,6 +76,14 @@ define_domain_errors! {
             help: "break the cycle by removing one alias",
             fields: { chain: String },
         },
+
+        /// KTR5004: Recursive type without indirection
+        RecursiveType {
+            code: (TR, Cycle, 4),
+            message: "recursive type has infinite size: {chain}",
+            help: "make a field on the cycle optional (`field?: T`) or an unsized array (`T[]`) to break the recursion",
+            fields: { chain: String },
+        },
     }
 }
 
@@ -80,6 +112,44 @@ impl ResolutionError {
         })
     }
 
+    pub fn internal_item(
+        name: impl Into<String>,
+        package: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::InternalItem {
+            name: name.into(),
+            package: package.into(),
+            span: None,
+        })
+    }
+
+    pub fn internal_type_exposed(
+        item: impl Into<String>,
+        name: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::InternalTypeExposed {
+            item: item.into(),
+            name: name.into(),
+            span: None,
+        })
+    }
+
+    pub fn ambiguous_glob_import(
+        name: impl Into<String>,
+        candidates: impl IntoIterator<Item = impl Into<String>>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        let candidates = candidates
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>()
+            .join(", ");
+        ErrorBuilder::new(Self::AmbiguousGlobImport {
+            name: name.into(),
+            candidates,
+            span: None,
+        })
+    }
+
     pub fn circular_dependency(
         deps: impl IntoIterator<Item = impl Into<String>>
     ) -> ErrorBuilder<Unspanned, Self> {
@@ -134,4 +204,15 @@ impl ResolutionError {
             .join(" -> ");
         ErrorBuilder::new(Self::CircularDependency { chain, span: None })
     }
+
+    pub fn recursive_type(
+        types: impl IntoIterator<Item = impl Into<String>>
+    ) -> ErrorBuilder<Unspanned, Self> {
+        let chain = types
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        ErrorBuilder::new(Self::RecursiveType { chain, span: None })
+    }
 }
@@ -22,6 +22,22 @@ define_domain_errors! {
             fields: { found_type: String, operand_name: String },
         },
 
+        /// KTY2003: Non-constant expression where a constant is required
+        NonConstantExpression {
+            code: (TY, Validation, 3),
+            message: "'{expression}' is not a constant expression",
+            help: "use an integer or string literal, or the name of a `const` declared in this namespace",
+            fields: { expression: String },
+        },
+
+        /// KTY2004: Constant value does not fit its declared type
+        ConstTypeMismatch {
+            code: (TY, Validation, 4),
+            message: "constant '{name}' of type {expected} cannot hold value {value}",
+            help: "change the declared type or the value of the constant",
+            fields: { name: String, expected: String, value: String },
+        },
+
         /// KTY3001: Identifier conflict in namespace
         IdentConflict {
             code: (TY, Conflict, 1),
@@ -46,6 +62,14 @@ define_domain_errors! {
             fields: { name: String, type_kind: String, type_name: String },
         },
 
+        /// KTY3004: Enum alias colliding with another value of the enum
+        DuplicateEnumValue {
+            code: (TY, Conflict, 4),
+            message: "value {value} of '{variant}' in enum '{name}' is already used by '{other}'",
+            help: "enum aliases must differ from every variant value and from each other",
+            fields: { name: String, variant: String, other: String, value: String },
+        },
+
         /// KTY5001: Type circular dependency
         TypeCircularDependency {
             code: (TY, Cycle, 1),
@@ -143,6 +167,26 @@ impl TypeDefError {
         })
     }
 
+    pub fn non_constant_expression(expression: impl Into<String>) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::NonConstantExpression {
+            expression: expression.into(),
+            span: None,
+        })
+    }
+
+    pub fn const_type_mismatch(
+        name: impl Into<String>,
+        expected: impl Into<String>,
+        value: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::ConstTypeMismatch {
+            name: name.into(),
+            expected: expected.into(),
+            value: value.into(),
+            span: None,
+        })
+    }
+
     pub fn ident_conflict(
         namespace: impl Into<String>,
         tag: impl Into<String>,
@@ -176,6 +220,21 @@ impl TypeDefError {
         })
     }
 
+    pub fn duplicate_enum_value(
+        name: impl Into<String>,
+        variant: impl Into<String>,
+        other: impl Into<String>,
+        value: impl Into<String>,
+    ) -> ErrorBuilder<Unspanned, Self> {
+        ErrorBuilder::new(Self::DuplicateEnumValue {
+            name: name.into(),
+            variant: variant.into(),
+            other: other.into(),
+            value: value.into(),
+            span: None,
+        })
+    }
+
     pub fn circular_dependency(
         types: impl IntoIterator<Item = impl Into<String>>
     ) -> ErrorBuilder<Unspanned, Self> {
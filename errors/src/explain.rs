@@ -0,0 +1,126 @@
+//! Extended explanations of error codes, as shown by `kintsu explain <code>`.
+//!
+//! An [`Explanation`] combines the [catalog](crate::catalog) entry of a code with prose,
+//! examples and common fixes written in `errors/explain/<code>.md`. Codes without such a page
+//! are explained from their catalog entry alone.
+
+use std::fmt::Write;
+
+use crate::{
+    ErrorCode,
+    catalog::{ErrorEntry, catalog, domain_doc, spec_url},
+};
+
+/// Extended pages, by code.
+const PAGES: &[(&str, &str)] = &[
+    ("KNS1001", include_str!("../explain/KNS1001.md")),
+    ("KNS1002", include_str!("../explain/KNS1002.md")),
+    ("KPK6003", include_str!("../explain/KPK6003.md")),
+    ("KTR1002", include_str!("../explain/KTR1002.md")),
+    ("KTR1004", include_str!("../explain/KTR1004.md")),
+    ("KTR3001", include_str!("../explain/KTR3001.md")),
+    ("KTR5004", include_str!("../explain/KTR5004.md")),
+];
+
+/// Everything known about one error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+    pub entry: &'static ErrorEntry,
+    /// Prose, examples and common fixes, in Markdown
+    pub details: Option<&'static str>,
+}
+
+/// Explains `code`, e.g. `KTR1002` or `ktr1002`.
+pub fn explain(code: &str) -> Option<Explanation> {
+    let code = code.trim().to_ascii_uppercase();
+    let entry = catalog()
+        .into_iter()
+        .find(|entry| entry.code.to_string() == code)?;
+    Some(Explanation {
+        entry,
+        details: details(entry.code),
+    })
+}
+
+/// The extended page of `code`, if it has one.
+pub fn details(code: ErrorCode) -> Option<&'static str> {
+    let code = code.to_string();
+    PAGES
+        .iter()
+        .find(|(page, _)| *page == code)
+        .map(|(_, details)| details.trim_end())
+}
+
+impl Explanation {
+    /// Renders the explanation for a terminal.
+    pub fn render(&self) -> String {
+        let entry = self.entry;
+        let (title, spec) = domain_doc(entry.code.domain);
+
+        let mut out = format!(
+            "{} {} ({}, {})\n\n    {}\n",
+            entry.code,
+            entry.name,
+            entry.severity,
+            title.to_lowercase(),
+            entry.message.replace('\n', "\n    ")
+        );
+        if !entry.fields.is_empty() {
+            let _ = writeln!(out, "\nFields: {}", entry.fields.join(", "));
+        }
+        if let Some(details) = self.details {
+            let _ = writeln!(out, "\n{details}");
+        }
+        if let Some(help) = entry.help {
+            let _ = writeln!(out, "\nHelp: {help}");
+        }
+        let _ = write!(out, "\nSpecified by {spec}: {}", spec_url(spec));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explains_codes_case_insensitively() {
+        let explanation = explain(" ktr1002 ").unwrap();
+        assert_eq!(explanation.entry.name, "UndefinedType");
+        assert!(explanation.details.is_some());
+
+        let rendered = explanation.render();
+        assert!(
+            rendered.starts_with("KTR1002 UndefinedType (error, type resolution errors)"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("Common fixes:"), "{rendered}");
+        assert!(rendered.contains("Help: check spelling"), "{rendered}");
+        assert!(rendered.ends_with("https://docs.kintsu.dev/specs/err/ERR-0006"));
+
+        assert_eq!(explain("KXX0001"), None);
+    }
+
+    #[test]
+    fn codes_without_pages_are_explained_from_the_catalog() {
+        let explanation = explain("KLX0001").unwrap();
+        assert_eq!(explanation.details, None);
+        assert!(
+            explanation
+                .render()
+                .contains(explanation.entry.message)
+        );
+    }
+
+    #[test]
+    fn pages_explain_defined_codes() {
+        for (code, details) in PAGES {
+            assert!(
+                explain(code).is_some(),
+                "{code} is not a defined error code"
+            );
+            assert!(details.contains("Common fixes:"), "{code}");
+        }
+        assert!(PAGES.is_sorted_by_key(|(code, _)| *code));
+    }
+}
@@ -23,21 +23,30 @@
 
 mod code;
 mod diagnostic;
+mod locale;
 #[macro_use]
 mod macros;
 mod builder;
 mod span;
+mod suggestion;
 
+pub mod catalog;
 pub mod domains;
+pub mod explain;
 
 pub use builder::{DomainError, ErrorBuilder, SourceContext, Spanned, Unlocated, Unspanned};
+pub use catalog::{ErrorEntry, catalog};
 pub use code::{Category, Domain, ErrorCode, Severity};
 pub use diagnostic::{DiagnosticBuilder, SpanDiagnostic};
+pub use explain::{Explanation, explain};
+pub use locale::{Locale, LocaleError};
 pub use span::{HasSpan, SourceAttachment, Span};
+pub use suggestion::{Applicability, Suggestion, apply_suggestions, closest_match};
 
 pub use domains::{
-    FilesystemError, InternalError, LexicalError, MetadataError, NamespaceError, PackageError,
-    ParsingError, ResolutionError, TaggingError, TypeDefError, TypeExprError, UnionError,
+    FilesystemError, InternalError, LexicalError, LintError, MetadataError, NamespaceError,
+    PackageError, ParsingError, ResolutionError, TaggingError, TypeDefError, TypeExprError,
+    UnionError,
 };
 
 use std::{path::PathBuf, sync::Arc};
@@ -60,6 +69,7 @@ pub enum CompilerError {
     Package(PackageError),
     Filesystem(FilesystemError),
     Internal(InternalError),
+    Lint(LintError),
 
     /// Error with attached source file context.
     WithSource {
@@ -74,8 +84,18 @@ pub enum CompilerError {
         labels: Vec<(Span, String)>,
     },
 
+    /// Error with fix suggestions, applied by `check --fix` when machine-applicable.
+    WithSuggestions {
+        inner: Box<CompilerError>,
+        suggestions: Vec<Suggestion>,
+    },
+
     /// Multiple errors collected together.
     Multiple(Vec<CompilerError>),
+
+    /// Follow-on error likely caused by an earlier error, such as a reference into a file
+    /// that failed to parse. Reported after the root causes.
+    Secondary(Box<CompilerError>),
 }
 
 impl CompilerError {
@@ -94,8 +114,11 @@ impl CompilerError {
             Self::Package(e) => e.error_code(),
             Self::Filesystem(e) => e.error_code(),
             Self::Internal(e) => e.error_code(),
+            Self::Lint(e) => e.error_code(),
             Self::WithSource { inner, .. } => inner.error_code(),
             Self::WithSecondaryLabels { inner, .. } => inner.error_code(),
+            Self::WithSuggestions { inner, .. } => inner.error_code(),
+            Self::Secondary(inner) => inner.error_code(),
             Self::Multiple(errs) => {
                 errs.first()
                     .map(|e| e.error_code())
@@ -119,8 +142,11 @@ impl CompilerError {
             Self::Package(e) => e.message(),
             Self::Filesystem(e) => e.message(),
             Self::Internal(e) => e.message(),
+            Self::Lint(e) => e.message(),
             Self::WithSource { inner, .. } => inner.message(),
             Self::WithSecondaryLabels { inner, .. } => inner.message(),
+            Self::WithSuggestions { inner, .. } => inner.message(),
+            Self::Secondary(inner) => inner.message(),
             Self::Multiple(errs) => {
                 if errs.len() == 1 {
                     errs[0].message()
@@ -146,8 +172,11 @@ impl CompilerError {
             Self::Package(e) => e.severity(),
             Self::Filesystem(e) => e.severity(),
             Self::Internal(e) => e.severity(),
+            Self::Lint(e) => e.severity(),
             Self::WithSource { inner, .. } => inner.severity(),
             Self::WithSecondaryLabels { inner, .. } => inner.severity(),
+            Self::WithSuggestions { inner, .. } => inner.severity(),
+            Self::Secondary(inner) => inner.severity(),
             Self::Multiple(errs) => {
                 errs.iter()
                     .map(|e| e.severity())
@@ -164,6 +193,65 @@ impl CompilerError {
         }
     }
 
+    /// Returns the values interpolated into the message, by field name.
+    pub fn args(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Lexical(e) => e.args(),
+            Self::Parsing(e) => e.args(),
+            Self::Namespace(e) => e.args(),
+            Self::TypeDef(e) => e.args(),
+            Self::Resolution(e) => e.args(),
+            Self::Union(e) => e.args(),
+            Self::Metadata(e) => e.args(),
+            Self::Tagging(e) => e.args(),
+            Self::TypeExpr(e) => e.args(),
+            Self::Package(e) => e.args(),
+            Self::Filesystem(e) => e.args(),
+            Self::Internal(e) => e.args(),
+            Self::Lint(e) => e.args(),
+            Self::WithSource { inner, .. } => inner.args(),
+            Self::WithSecondaryLabels { inner, .. } => inner.args(),
+            Self::WithSuggestions { inner, .. } => inner.args(),
+            Self::Secondary(inner) => inner.args(),
+            Self::Multiple(errs) => {
+                match errs.as_slice() {
+                    [err] => err.args(),
+                    _ => Vec::new(),
+                }
+            },
+        }
+    }
+
+    /// Returns the message translated by `locale`, or the English message.
+    pub fn localized_message(
+        &self,
+        locale: &Locale,
+    ) -> String {
+        match self {
+            Self::Multiple(errs) if errs.len() != 1 => self.message(),
+            _ => {
+                locale
+                    .message(self.error_code(), &self.args())
+                    .unwrap_or_else(|| self.message())
+            },
+        }
+    }
+
+    /// Returns the help text translated by `locale`, or the English help text.
+    pub fn localized_help(
+        &self,
+        locale: &Locale,
+    ) -> Option<String> {
+        match self {
+            Self::Multiple(_) | Self::Secondary(_) => self.help_text().map(String::from),
+            _ => {
+                locale
+                    .help(self.error_code(), &self.args())
+                    .or_else(|| self.help_text().map(String::from))
+            },
+        }
+    }
+
     /// Returns optional help text.
     pub fn help_text(&self) -> Option<&'static str> {
         match self {
@@ -179,9 +267,14 @@ impl CompilerError {
             Self::Package(e) => e.help_text(),
             Self::Filesystem(e) => e.help_text(),
             Self::Internal(e) => e.help_text(),
+            Self::Lint(e) => e.help_text(),
             Self::WithSource { inner, .. } => inner.help_text(),
             Self::WithSecondaryLabels { inner, .. } => inner.help_text(),
+            Self::WithSuggestions { inner, .. } => inner.help_text(),
             Self::Multiple(_) => None,
+            Self::Secondary(_) => {
+                Some("this may be caused by an earlier error; fix the errors reported above first")
+            },
         }
     }
 
@@ -200,9 +293,12 @@ impl CompilerError {
             Self::Package(e) => e.span(),
             Self::Filesystem(e) => e.span(),
             Self::Internal(e) => e.span(),
+            Self::Lint(e) => e.span(),
             Self::WithSource { inner, .. } => inner.span(),
             Self::WithSecondaryLabels { inner, .. } => inner.span(),
+            Self::WithSuggestions { inner, .. } => inner.span(),
             Self::Multiple(errs) => errs.first().and_then(|e| e.span()),
+            Self::Secondary(inner) => inner.span(),
         }
     }
 
@@ -260,11 +356,76 @@ impl CompilerError {
                 all_labels.extend(labels.clone());
                 all_labels
             },
-            Self::WithSource { inner, .. } => inner.extract_secondary_labels(),
+            Self::WithSource { inner, .. }
+            | Self::WithSuggestions { inner, .. }
+            | Self::Secondary(inner) => inner.extract_secondary_labels(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Attaches a fix suggestion.
+    pub fn with_suggestion(
+        self,
+        suggestion: Suggestion,
+    ) -> Self {
+        match self {
+            Self::WithSuggestions {
+                inner,
+                mut suggestions,
+            } => {
+                suggestions.push(suggestion);
+                Self::WithSuggestions { inner, suggestions }
+            },
+            other => {
+                Self::WithSuggestions {
+                    inner: Box::new(other),
+                    suggestions: vec![suggestion],
+                }
+            },
+        }
+    }
+
+    /// Extracts suggestions from nested WithSuggestions wrappers.
+    pub fn extract_suggestions(&self) -> Vec<Suggestion> {
+        match self {
+            Self::WithSuggestions { inner, suggestions } => {
+                let mut all = inner.extract_suggestions();
+                all.extend(suggestions.clone());
+                all
+            },
+            Self::WithSource { inner, .. }
+            | Self::WithSecondaryLabels { inner, .. }
+            | Self::Secondary(inner) => inner.extract_suggestions(),
             _ => Vec::new(),
         }
     }
 
+    /// Groups the machine-applicable suggestions of every flattened error by source file.
+    pub fn machine_applicable_fixes(&self) -> Vec<(PathBuf, Vec<Suggestion>)> {
+        let mut fixes: Vec<(PathBuf, Vec<Suggestion>)> = Vec::new();
+
+        for err in self.flatten() {
+            let Some((path, _)) = err.extract_source() else {
+                continue;
+            };
+            let suggestions: Vec<_> = err
+                .extract_suggestions()
+                .into_iter()
+                .filter(Suggestion::is_machine_applicable)
+                .collect();
+            if suggestions.is_empty() {
+                continue;
+            }
+
+            match fixes.iter_mut().find(|(p, _)| p == path) {
+                Some((_, existing)) => existing.extend(suggestions),
+                None => fixes.push((path.to_path_buf(), suggestions)),
+            }
+        }
+
+        fixes
+    }
+
     /// Extracts source information from nested WithSource wrappers.
     pub fn extract_source(&self) -> Option<(&std::path::Path, &str)> {
         match self {
@@ -277,7 +438,9 @@ impl CompilerError {
                     .extract_source()
                     .or(Some((path.as_path(), source.as_str())))
             },
-            Self::WithSecondaryLabels { inner, .. } => inner.extract_source(),
+            Self::WithSecondaryLabels { inner, .. }
+            | Self::WithSuggestions { inner, .. }
+            | Self::Secondary(inner) => inner.extract_source(),
             _ => None,
         }
     }
@@ -290,7 +453,9 @@ impl CompilerError {
                     .extract_deepest_span()
                     .or_else(|| inner.span())
             },
-            Self::WithSecondaryLabels { inner, .. } => {
+            Self::WithSecondaryLabels { inner, .. }
+            | Self::WithSuggestions { inner, .. }
+            | Self::Secondary(inner) => {
                 inner
                     .extract_deepest_span()
                     .or_else(|| inner.span())
@@ -299,8 +464,65 @@ impl CompilerError {
         }
     }
 
-    /// Converts to a miette Report for display.
+    /// Marks this error as a follow-on of an earlier error.
+    pub fn secondary(self) -> Self {
+        match self {
+            Self::Secondary(_) => self,
+            other => Self::Secondary(Box::new(other)),
+        }
+    }
+
+    /// Returns true if this error was marked as a follow-on of an earlier error.
+    pub fn is_secondary(&self) -> bool {
+        match self {
+            Self::Secondary(_) => true,
+            Self::WithSource { inner, .. }
+            | Self::WithSecondaryLabels { inner, .. }
+            | Self::WithSuggestions { inner, .. } => inner.is_secondary(),
+            _ => false,
+        }
+    }
+
+    /// Flattens nested [`CompilerError::Multiple`] into individual errors, root causes first.
+    pub fn flatten(&self) -> Vec<&CompilerError> {
+        let mut out = Vec::new();
+        self.flatten_into(&mut out);
+        out.sort_by_key(|e| e.is_secondary());
+        out
+    }
+
+    fn flatten_into<'a>(
+        &'a self,
+        out: &mut Vec<&'a CompilerError>,
+    ) {
+        match self {
+            Self::Multiple(errs) => {
+                for err in errs {
+                    err.flatten_into(out);
+                }
+            },
+            other => out.push(other),
+        }
+    }
+
+    /// Converts to one miette Report per error, root causes first.
+    pub fn to_reports(&self) -> Vec<miette::Report> {
+        self.flatten()
+            .into_iter()
+            .map(|err| err.to_report())
+            .collect()
+    }
+
+    /// Converts to a miette Report for display, in the installed [`Locale`].
     pub fn to_report(&self) -> miette::Report {
+        self.to_report_in(Locale::current())
+    }
+
+    /// Converts to a miette Report for display, translated by `locale`.
+    pub fn to_report_in(
+        &self,
+        locale: &Locale,
+    ) -> miette::Report {
         let (path, source) = self.extract_source().unzip();
         let span = self.extract_deepest_span();
         let secondary_labels = self.extract_secondary_labels();
@@ -310,6 +532,21 @@ impl CompilerError {
                 .help_opt(self.help_text())
                 .span_opt(span)
                 .secondary_labels(secondary_labels);
+        // several errors are summarized rather than translated
+        if !matches!(self, Self::Multiple(errs) if errs.len() != 1) {
+            builder = builder.locale(locale, &self.args());
+        }
+
+        let suggestions = self.extract_suggestions();
+        if !suggestions.is_empty() {
+            let help = self
+                .localized_help(locale)
+                .into_iter()
+                .chain(suggestions.into_iter().map(|s| s.message))
+                .collect::<Vec<_>>()
+                .join("\n");
+            builder = builder.help(help);
+        }
 
         if let (Some(p), Some(s)) = (path, source) {
             builder = builder.source(p, s);
@@ -351,6 +588,12 @@ impl std::fmt::Display for CompilerError {
 
 impl std::error::Error for CompilerError {}
 
+impl From<LintError> for CompilerError {
+    fn from(e: LintError) -> Self {
+        Self::Lint(e)
+    }
+}
+
 impl From<LexicalError> for CompilerError {
     fn from(e: LexicalError) -> Self {
         Self::Lexical(e)
@@ -493,4 +736,58 @@ mod tests {
         let err = CompilerError::Multiple(errs);
         assert_eq!(err.message(), "2 errors occurred");
     }
+
+    #[test]
+    fn flatten_orders_secondary_last() {
+        let follow_on = ResolutionError::undefined_type("A")
+            .unlocated()
+            .build()
+            .secondary();
+        let root = LexicalError::lexer_error("unexpected token")
+            .unlocated()
+            .build();
+        let err = CompilerError::Multiple(vec![follow_on, CompilerError::Multiple(vec![root])]);
+
+        let flat = err.flatten();
+        assert_eq!(flat.len(), 2);
+        assert!(!flat[0].is_secondary());
+        assert!(flat[1].is_secondary());
+        assert_eq!(flat[1].error_code().to_string(), "KTR1002");
+        assert!(flat[1].help_text().is_some());
+    }
+
+    #[test]
+    fn machine_applicable_fixes_grouped_by_file() {
+        let typo = ResolutionError::undefined_type("Usr")
+            .at(Span::new(7, 10))
+            .build()
+            .with_suggestion(Suggestion::new(
+                Span::new(7, 10),
+                "User",
+                Applicability::MachineApplicable,
+                "a type with a similar name exists: `User`",
+            ))
+            .with_source("types.ks", "field: Usr,");
+        let unsure = ResolutionError::undefined_type("Acct")
+            .at(Span::new(0, 4))
+            .build()
+            .with_suggestion(Suggestion::new(
+                Span::new(0, 4),
+                "Account",
+                Applicability::MaybeIncorrect,
+                "a type with a similar name exists: `Account`",
+            ))
+            .with_source("types.ks", "Acct");
+        let err = CompilerError::Multiple(vec![typo, unsure]);
+
+        let fixes = err.machine_applicable_fixes();
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].0, PathBuf::from("types.ks"));
+        assert_eq!(fixes[0].1.len(), 1);
+        assert_eq!(fixes[0].1[0].replacement, "User");
+
+        let report = format!("{:?}", err.flatten()[0].to_report());
+        assert!(report.contains("a type with a similar name exists: `User`"));
+        assert_eq!(err.flatten()[0].error_code().to_string(), "KTR1002");
+    }
 }
@@ -0,0 +1,374 @@
+//! Localized diagnostic messages.
+//!
+//! A [`Locale`] holds translated message and help templates keyed by error code, written in
+//! a subset of [Fluent](https://projectfluent.org) syntax:
+//!
+//! ```text
+//! # comments start with '#'
+//! KTR1002 = type non défini : '{ $name }'
+//!     .help = vérifiez l'orthographe ou définissez le type
+//! ```
+//!
+//! Placeholders name the fields of the error, as listed by the [catalog](crate::catalog).
+//! Codes a locale does not translate fall back to the English messages compiled into the
+//! domain errors; [`catalog::render_fluent`](crate::catalog::render_fluent) renders those as
+//! the template to translate.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{LazyLock, OnceLock},
+};
+
+use crate::{
+    ErrorCode,
+    catalog::{ErrorEntry, catalog},
+};
+
+static INSTALLED: OnceLock<Locale> = OnceLock::new();
+static ENGLISH: LazyLock<Locale> = LazyLock::new(Locale::english);
+
+/// Translated templates of one language.
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    tag: String,
+    messages: HashMap<String, Translation>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Translation {
+    message: Option<String>,
+    help: Option<String>,
+}
+
+/// A line of a locale which could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocaleError {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl std::fmt::Display for LocaleError {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for LocaleError {}
+
+impl Locale {
+    /// The embedded fallback, which translates nothing.
+    pub fn english() -> Self {
+        Self {
+            tag: "en".into(),
+            messages: HashMap::new(),
+        }
+    }
+
+    /// Parses the templates of the language `tag`, e.g. `fr`, rejecting unknown codes and
+    /// placeholders.
+    pub fn parse(
+        tag: impl Into<String>,
+        source: &str,
+    ) -> Result<Self, LocaleError> {
+        let entries = catalog();
+        let mut messages = HashMap::<String, Translation>::new();
+        // the code and attribute the last template line belongs to
+        let mut current: Option<(String, bool)> = None;
+
+        for (index, raw) in source.lines().enumerate() {
+            let line = index + 1;
+            let error = |reason: String| LocaleError { line, reason };
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let indented = raw.starts_with([' ', '\t']);
+            let (is_help, text) = match (indented, trimmed.strip_prefix(".help")) {
+                (true, Some(rest)) => {
+                    if current.is_none() {
+                        return Err(error("'.help' outside of a message".into()));
+                    }
+                    let text = rest
+                        .trim_start()
+                        .strip_prefix('=')
+                        .ok_or_else(|| error("expected '=' after '.help'".into()))?;
+                    (true, text.trim())
+                },
+                (true, None) => {
+                    // continues the last template on a new line
+                    let Some((code, is_help)) = &current else {
+                        return Err(error("continuation outside of a message".into()));
+                    };
+                    check_placeholders(&entries, code, trimmed).map_err(error)?;
+                    let translation = messages
+                        .get_mut(code)
+                        .expect("current message exists");
+                    let template = if *is_help {
+                        &mut translation.help
+                    } else {
+                        &mut translation.message
+                    };
+                    let template = template.get_or_insert_default();
+                    template.push('\n');
+                    template.push_str(trimmed);
+                    continue;
+                },
+                (false, _) => {
+                    let (code, text) = trimmed.split_once('=').ok_or_else(|| {
+                        error(format!("expected '<code> = <message>', found '{trimmed}'"))
+                    })?;
+                    let code = code.trim().to_string();
+                    if !entries
+                        .iter()
+                        .any(|entry| entry.code.to_string() == code)
+                    {
+                        return Err(error(format!("unknown error code '{code}'")));
+                    }
+                    if messages.contains_key(&code) {
+                        return Err(error(format!("'{code}' is translated more than once")));
+                    }
+                    messages.insert(code.clone(), Translation::default());
+                    current = Some((code, false));
+                    (false, text.trim())
+                },
+            };
+
+            let (code, _) = current.as_ref().expect("set above");
+            check_placeholders(&entries, code, text).map_err(error)?;
+
+            let translation = messages
+                .get_mut(code.as_str())
+                .expect("inserted above");
+            if is_help {
+                translation.help = Some(text.to_string());
+            } else if !text.is_empty() {
+                translation.message = Some(text.to_string());
+            }
+            current = Some((code.clone(), is_help));
+        }
+
+        Ok(Self {
+            tag: tag.into(),
+            messages,
+        })
+    }
+
+    /// Reads a locale file, tagged by its stem, e.g. `fr.ftl`.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let source = std::fs::read_to_string(path)?;
+        let tag = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self::parse(tag, &source)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// Selects this locale for every diagnostic rendered afterwards. Only the first locale
+    /// installed takes effect, which is returned otherwise.
+    pub fn install(self) -> Result<(), Self> {
+        INSTALLED.set(self)
+    }
+
+    /// The installed locale, or English.
+    pub fn current() -> &'static Self {
+        INSTALLED.get().unwrap_or(&ENGLISH)
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// The translated message of `code`, with `args` interpolated.
+    pub fn message(
+        &self,
+        code: ErrorCode,
+        args: &[(&str, String)],
+    ) -> Option<String> {
+        self.messages
+            .get(&code.to_string())?
+            .message
+            .as_deref()
+            .map(|template| interpolate(template, args))
+    }
+
+    /// The translated help of `code`, with `args` interpolated.
+    pub fn help(
+        &self,
+        code: ErrorCode,
+        args: &[(&str, String)],
+    ) -> Option<String> {
+        self.messages
+            .get(&code.to_string())?
+            .help
+            .as_deref()
+            .map(|template| interpolate(template, args))
+    }
+}
+
+/// Checks the placeholders of a template of `code` name its fields.
+fn check_placeholders(
+    entries: &[&ErrorEntry],
+    code: &str,
+    template: &str,
+) -> Result<(), String> {
+    let entry = entries
+        .iter()
+        .find(|entry| entry.code.to_string() == code)
+        .expect("codes are checked when first read");
+    match placeholders(template)
+        .into_iter()
+        .find(|placeholder| !entry.fields.contains(placeholder))
+    {
+        Some(placeholder) => {
+            Err(format!(
+                "{code} has no field '{placeholder}', expected one of {:?}",
+                entry.fields
+            ))
+        },
+        None => Ok(()),
+    }
+}
+
+/// Names of the `{ $name }` placeholders in `template`.
+fn placeholders(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        if let Some(name) = rest[start + 1..start + end]
+            .trim()
+            .strip_prefix('$')
+        {
+            names.push(name);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    names
+}
+
+/// Replaces the `{ $name }` placeholders of `template`, keeping unknown ones as written.
+fn interpolate(
+    template: &str,
+    args: &[(&str, String)],
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let placeholder = &rest[start..start + end + 1];
+        let value = placeholder[1..placeholder.len() - 1]
+            .trim()
+            .strip_prefix('$')
+            .and_then(|name| args.iter().find(|(arg, _)| *arg == name));
+        match value {
+            Some((_, value)) => out.push_str(value),
+            None => out.push_str(placeholder),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompilerError, ResolutionError};
+
+    const FR: &str = "\
+# French
+KTR1002 = type non défini : '{ $name }'
+    .help = vérifiez l'orthographe
+        ou définissez le type
+KIN9001 =
+    .help = signalez ce bogue
+";
+
+    fn undefined_type() -> CompilerError {
+        ResolutionError::undefined_type("User")
+            .unlocated()
+            .build()
+    }
+
+    #[test]
+    fn translates_messages_and_help() {
+        let fr = Locale::parse("fr", FR).unwrap();
+        let err = undefined_type();
+
+        assert_eq!(fr.tag(), "fr");
+        assert_eq!(err.localized_message(&fr), "type non défini : 'User'");
+        assert_eq!(
+            err.localized_help(&fr).as_deref(),
+            Some("vérifiez l'orthographe\nou définissez le type")
+        );
+
+        let report = format!("{:?}", err.to_report_in(&fr));
+        assert!(report.contains("type non défini : 'User'"), "{report}");
+    }
+
+    #[test]
+    fn falls_back_to_english() {
+        let fr = Locale::parse("fr", FR).unwrap();
+        let internal: CompilerError = crate::InternalError::internal("boom")
+            .unlocated()
+            .build();
+
+        // help only
+        assert_eq!(internal.localized_message(&fr), internal.message());
+        assert_eq!(
+            internal.localized_help(&fr).as_deref(),
+            Some("signalez ce bogue")
+        );
+
+        let english = Locale::english();
+        assert_eq!(
+            undefined_type().localized_message(&english),
+            undefined_type().message()
+        );
+        assert_eq!(Locale::current().tag(), "en");
+    }
+
+    #[test]
+    fn rejects_unknown_codes_and_placeholders() {
+        assert_eq!(
+            Locale::parse("fr", "KXX0001 = inconnu").unwrap_err(),
+            LocaleError {
+                line: 1,
+                reason: "unknown error code 'KXX0001'".into()
+            }
+        );
+
+        let err = Locale::parse("fr", "\nKTR1002 = type non défini : { $type }").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.reason.contains("no field 'type'"), "{err}");
+
+        assert!(Locale::parse("fr", "    .help = orphelin").is_err());
+    }
+
+    #[test]
+    fn english_template_reproduces_english_messages() {
+        let english = Locale::parse("en", &crate::catalog::render_fluent()).unwrap();
+        let err = undefined_type();
+
+        assert_eq!(err.localized_message(&english), err.message());
+        assert_eq!(err.localized_help(&english).as_deref(), err.help_text());
+    }
+
+    #[test]
+    fn interpolates_placeholders() {
+        let args = [("name", "User".to_string())];
+        assert_eq!(interpolate("{ $name } / {$name}", &args), "User / User");
+        assert_eq!(interpolate("{ $other } {", &args), "{ $other } {");
+    }
+}
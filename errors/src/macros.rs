@@ -35,6 +35,24 @@ macro_rules! define_domain_errors {
         }
 
         impl $name {
+            /// Every error of this domain, in declaration order.
+            pub const CATALOG: &'static [$crate::catalog::ErrorEntry] = &[
+                $(
+                    $crate::catalog::ErrorEntry {
+                        code: $crate::ErrorCode::new(
+                            $crate::Domain::$domain,
+                            $crate::Category::$category,
+                            $seq
+                        ),
+                        name: stringify!($variant),
+                        message: $msg,
+                        help: $crate::define_domain_errors!(@help $($help)?),
+                        severity: $crate::define_domain_errors!(@severity $($severity)?),
+                        fields: &[$($(stringify!($field)),*)?],
+                    },
+                )*
+            ];
+
             pub const fn error_code(&self) -> $crate::ErrorCode {
                 match self {
                     $(
@@ -57,6 +75,17 @@ macro_rules! define_domain_errors {
                 }
             }
 
+            /// The values interpolated into the message, by field name.
+            pub fn args(&self) -> Vec<(&'static str, String)> {
+                match self {
+                    $(
+                        Self::$variant { $($($field,)*)? .. } => {
+                            vec![$($((stringify!($field), $field.to_string())),*)?]
+                        }
+                    )*
+                }
+            }
+
             pub fn help_text(&self) -> Option<&'static str> {
                 match self {
                     $(
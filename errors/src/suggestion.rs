@@ -0,0 +1,212 @@
+//! Span-aware fix suggestions attached to compiler errors.
+//!
+//! A suggestion replaces the source covered by its span. Insertions use an
+//! empty span. Only [`Applicability::MachineApplicable`] suggestions are
+//! applied by `kintsu check --fix`; the rest are rendered as help text.
+
+use crate::Span;
+
+/// How confident the compiler is that a suggestion is correct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// Safe to apply without review.
+    MachineApplicable,
+    /// Likely correct, but should be confirmed by the user.
+    MaybeIncorrect,
+}
+
+/// A replacement of the source covered by `span`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub span: Span,
+    pub replacement: String,
+    pub applicability: Applicability,
+    /// Human-readable description, shown alongside the help text
+    pub message: String,
+}
+
+impl Suggestion {
+    pub fn new(
+        span: impl Into<Span>,
+        replacement: impl Into<String>,
+        applicability: Applicability,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            span: span.into(),
+            replacement: replacement.into(),
+            applicability,
+            message: message.into(),
+        }
+    }
+
+    /// Insert `text` at byte offset `at`.
+    pub fn insert(
+        at: usize,
+        text: impl Into<String>,
+        applicability: Applicability,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(Span::new(at, at), text, applicability, message)
+    }
+
+    pub fn is_machine_applicable(&self) -> bool {
+        self.applicability == Applicability::MachineApplicable
+    }
+}
+
+/// Applies `suggestions` to `source`, returning the new source and the number applied.
+///
+/// Suggestions outside the source, off a char boundary, or overlapping an
+/// earlier suggestion are skipped, so the result never depends on the order
+/// of conflicting edits.
+pub fn apply_suggestions<'a>(
+    source: &str,
+    suggestions: impl IntoIterator<Item = &'a Suggestion>,
+) -> (String, usize) {
+    let mut edits: Vec<&Suggestion> = suggestions.into_iter().collect();
+    edits.sort_by_key(|s| (s.span.start, s.span.end));
+
+    let mut out = String::with_capacity(source.len());
+    let mut cursor = 0;
+    let mut applied = 0;
+
+    for edit in edits {
+        let Span { start, end } = edit.span;
+        if start < cursor
+            || end < start
+            || end > source.len()
+            || !source.is_char_boundary(start)
+            || !source.is_char_boundary(end)
+        {
+            continue;
+        }
+
+        out.push_str(&source[cursor..start]);
+        out.push_str(&edit.replacement);
+        cursor = end;
+        applied += 1;
+    }
+
+    out.push_str(&source[cursor..]);
+    (out, applied)
+}
+
+/// Picks the candidate closest to `name` by edit distance, if any is close enough
+/// to be a plausible typo.
+///
+/// Returns `None` when no candidate is within a third of the name's length (at
+/// least one edit), and reports whether the best match was unique.
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<(&'a str, bool)> {
+    let limit = (name.chars().count() / 3).max(1);
+    let mut best: Option<(&'a str, usize, bool)> = None;
+
+    for candidate in candidates {
+        if candidate == name {
+            continue;
+        }
+
+        let distance = edit_distance(&name.to_lowercase(), &candidate.to_lowercase());
+        if distance > limit {
+            continue;
+        }
+
+        best = match best {
+            Some((current, best_distance, unique)) if distance == best_distance => {
+                Some((current, best_distance, unique && current == candidate))
+            },
+            Some((_, best_distance, _)) if distance > best_distance => best,
+            _ => Some((candidate, distance, true)),
+        };
+    }
+
+    best.map(|(candidate, _, unique)| (candidate, unique))
+}
+
+/// Edit distance over chars, counting an adjacent transposition as one edit.
+fn edit_distance(
+    a: &str,
+    b: &str,
+) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j - 1] + cost)
+                .min(d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(
+        start: usize,
+        end: usize,
+        text: &str,
+    ) -> Suggestion {
+        Suggestion::new(
+            Span::new(start, end),
+            text,
+            Applicability::MachineApplicable,
+            "",
+        )
+    }
+
+    #[test]
+    fn applies_in_span_order() {
+        let (out, applied) = apply_suggestions(
+            "struct Foo { a: Usr }",
+            &[fix(21, 21, ";"), fix(16, 19, "User")],
+        );
+        assert_eq!(out, "struct Foo { a: User };");
+        assert_eq!(applied, 2);
+    }
+
+    #[test]
+    fn skips_overlapping_and_out_of_bounds() {
+        let (out, applied) =
+            apply_suggestions("abcdef", &[fix(1, 3, "X"), fix(2, 4, "Y"), fix(4, 10, "Z")]);
+        assert_eq!(out, "aXdef");
+        assert_eq!(applied, 1);
+    }
+
+    #[test]
+    fn closest_match_typos() {
+        let names = ["User", "Users", "Account"];
+        assert_eq!(closest_match("Usr", names), Some(("User", true)));
+        assert_eq!(closest_match("Acount", names), Some(("Account", true)));
+        assert_eq!(closest_match("Invoice", names), None);
+        assert_eq!(closest_match("Userz", names), Some(("User", false)));
+    }
+
+    #[test]
+    fn edit_distance_basics() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+        assert_eq!(edit_distance("Usre", "User"), 1);
+    }
+}
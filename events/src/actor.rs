@@ -0,0 +1,125 @@
+//! The [`DiagnosticCollector`] as an actor, which compilation tasks send diagnostics to.
+
+use crate::{Diagnostic, DiagnosticBundle, DiagnosticCollector};
+use actix::{Actor, Context, Handler, Message, MessageResult, Supervised};
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct EmitDiagnostic(pub Diagnostic);
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct EmitBatch(pub Vec<Diagnostic>);
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Flush;
+
+#[derive(Message)]
+#[rtype(result = "DiagnosticBundle")]
+pub struct TakeBundle;
+
+/// Returns a copy of the diagnostics collected so far without draining the bundle.
+#[derive(Message)]
+#[rtype(result = "DiagnosticBundle")]
+pub struct SnapshotBundle;
+
+/// Returns `(errors, warnings)` collected so far without draining the bundle.
+#[derive(Message)]
+#[rtype(result = "(usize, usize)")]
+pub struct CountDiagnostics;
+
+impl Actor for DiagnosticCollector {
+    type Context = Context<Self>;
+
+    fn started(
+        &mut self,
+        _ctx: &mut Self::Context,
+    ) {
+        tracing::debug!("DiagnosticCollector started");
+    }
+
+    fn stopped(
+        &mut self,
+        _ctx: &mut Self::Context,
+    ) {
+        let (errors, warnings) = self.counts();
+        tracing::debug!("DiagnosticCollector stopped with {errors} errors, {warnings} warnings");
+    }
+}
+
+impl Supervised for DiagnosticCollector {}
+
+impl Handler<EmitDiagnostic> for DiagnosticCollector {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: EmitDiagnostic,
+        _ctx: &mut Self::Context,
+    ) {
+        self.collect(msg.0);
+    }
+}
+
+impl Handler<EmitBatch> for DiagnosticCollector {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: EmitBatch,
+        _ctx: &mut Self::Context,
+    ) {
+        for diagnostic in msg.0 {
+            self.collect(diagnostic);
+        }
+    }
+}
+
+impl Handler<TakeBundle> for DiagnosticCollector {
+    type Result = MessageResult<TakeBundle>;
+
+    fn handle(
+        &mut self,
+        _msg: TakeBundle,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        MessageResult(self.take_bundle())
+    }
+}
+
+impl Handler<SnapshotBundle> for DiagnosticCollector {
+    type Result = MessageResult<SnapshotBundle>;
+
+    fn handle(
+        &mut self,
+        _msg: SnapshotBundle,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        MessageResult(self.snapshot())
+    }
+}
+
+impl Handler<CountDiagnostics> for DiagnosticCollector {
+    type Result = MessageResult<CountDiagnostics>;
+
+    fn handle(
+        &mut self,
+        _msg: CountDiagnostics,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        MessageResult(self.counts())
+    }
+}
+
+impl Handler<Flush> for DiagnosticCollector {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        _msg: Flush,
+        _ctx: &mut Self::Context,
+    ) {
+        self.flush();
+    }
+}
@@ -1,5 +1,11 @@
 use crate::Diagnostic;
+use kintsu_errors::Severity;
 use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DiagnosticBundle {
@@ -67,24 +73,161 @@ impl DiagnosticBundle {
         self.warnings.extend(other.warnings);
     }
 
-    pub fn print_to_stderr(&self) {
-        for err in &self.errors {
-            eprintln!("{:?}", err.to_report());
+    /// The codes of warnings which `allowed` does not list, e.g. `KLT8001`, for builds
+    /// denying warnings. Info and hint diagnostics are never denied.
+    pub fn denied_warnings(
+        &self,
+        allowed: &BTreeSet<String>,
+    ) -> BTreeSet<String> {
+        self.warnings
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+            .map(|diagnostic| diagnostic.code.to_string())
+            .filter(|code| !allowed.contains(code))
+            .collect()
+    }
+
+    /// Sorts errors and warnings by file, then span start, then code. The sort is stable,
+    /// so diagnostics equal in all three keep the order they were emitted in.
+    pub fn sort(&mut self) {
+        self.errors.sort_by(Self::order);
+        self.warnings.sort_by(Self::order);
+    }
+
+    fn order(
+        a: &Diagnostic,
+        b: &Diagnostic,
+    ) -> Ordering {
+        let key = |d: &Diagnostic| {
+            (
+                d.source_name.clone(),
+                d.span.map(|s| s.start),
+                d.code.to_string(),
+            )
+        };
+        key(a).cmp(&key(b))
+    }
+
+    /// Errors and warnings together, in the order of [`sort`](Self::sort).
+    pub fn sorted(&self) -> Vec<&Diagnostic> {
+        let mut all: Vec<_> = self
+            .errors
+            .iter()
+            .chain(&self.warnings)
+            .collect();
+        all.sort_by(|a, b| Self::order(a, b));
+        all
+    }
+
+    /// Diagnostics grouped by file, sorted within each. Diagnostics without a file are
+    /// grouped under `None`.
+    pub fn by_file(&self) -> BTreeMap<Option<&str>, Vec<&Diagnostic>> {
+        let mut files = BTreeMap::<_, Vec<_>>::new();
+        for diagnostic in self.sorted() {
+            files
+                .entry(diagnostic.source_name.as_deref())
+                .or_default()
+                .push(diagnostic);
         }
-        for warn in &self.warnings {
-            eprintln!("{:?}", warn.to_report());
+        files
+    }
+
+    /// Diagnostics grouped by code, e.g. `KTR1002`, sorted within each.
+    pub fn by_code(&self) -> BTreeMap<String, Vec<&Diagnostic>> {
+        let mut codes = BTreeMap::<_, Vec<_>>::new();
+        for diagnostic in self.sorted() {
+            codes
+                .entry(diagnostic.code.to_string())
+                .or_default()
+                .push(diagnostic);
         }
+        codes
     }
 
+    /// Renders one line per diagnostic grouped by file, then counts by code.
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        for (file, diagnostics) in self.by_file() {
+            let _ = writeln!(out, "{}:", file.unwrap_or("<no file>"));
+            for diagnostic in diagnostics {
+                let location = diagnostic
+                    .span
+                    .map(|span| {
+                        match &diagnostic.source_content {
+                            Some(source) => {
+                                let (line, column) = line_column(source, span.start);
+                                format!("{line}:{column} ")
+                            },
+                            None => format!("@{} ", span.start),
+                        }
+                    })
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "  {location}{}[{}]: {}",
+                    diagnostic.severity, diagnostic.code, diagnostic.message
+                );
+            }
+        }
+
+        if !self.is_empty() {
+            out.push('\n');
+            for (code, diagnostics) in self.by_code() {
+                let _ = writeln!(out, "{code}: {}", diagnostics.len());
+            }
+        }
+        let _ = writeln!(out, "{self}");
+        out
+    }
+
+    pub fn print_to_stderr(&self) {
+        for diagnostic in self.sorted() {
+            eprintln!("{:?}", diagnostic.to_report());
+        }
+    }
+
+    /// Serializes with errors and warnings sorted, so reports of one input are identical
+    /// across runs.
     pub fn to_json(&self) -> serde_json::Result<String> {
-        serde_json::to_string_pretty(self)
+        serde_json::to_string_pretty(&self.sorted_clone())
     }
 
     pub fn to_json_compact(&self) -> serde_json::Result<String> {
-        serde_json::to_string(self)
+        serde_json::to_string(&self.sorted_clone())
+    }
+
+    /// One JSON diagnostic per line, in the order of [`sorted`](Self::sorted).
+    pub fn to_json_lines(&self) -> serde_json::Result<String> {
+        let mut out = String::new();
+        for diagnostic in self.sorted() {
+            out.push_str(&serde_json::to_string(diagnostic)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    fn sorted_clone(&self) -> Self {
+        let mut sorted = self.clone();
+        sorted.sort();
+        sorted
     }
 }
 
+/// The 1-based line and column of byte `offset` in `source`.
+fn line_column(
+    source: &str,
+    offset: usize,
+) -> (usize, usize) {
+    let before = &source[..source.floor_char_boundary(offset.min(source.len()))];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    (line, column)
+}
+
 impl std::fmt::Display for DiagnosticBundle {
     fn fmt(
         &self,
@@ -125,7 +268,7 @@ impl Extend<Diagnostic> for DiagnosticBundle {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use kintsu_errors::{Category, Domain, ErrorCode, Severity};
+    use kintsu_errors::{Category, Domain, ErrorCode, Severity, Span};
 
     #[test]
     fn bundle_push_categorizes_correctly() {
@@ -147,6 +290,140 @@ mod tests {
         assert_eq!(bundle.warning_count(), 1);
     }
 
+    fn diagnostic(
+        code: (Category, u16),
+        file: Option<&str>,
+        start: usize,
+        severity: Severity,
+    ) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            ErrorCode::new(Domain::TR, code.0, code.1),
+            format!("at {start}"),
+            severity,
+        )
+        .with_span(Span::new(start, start + 1));
+        if let Some(file) = file {
+            diagnostic = diagnostic.with_source(file, "namespace a;\nstruct A { b: B };\n");
+        }
+        diagnostic
+    }
+
+    fn bundle() -> DiagnosticBundle {
+        [
+            diagnostic((Category::Resolution, 2), Some("b.ks"), 27, Severity::Error),
+            diagnostic((Category::Resolution, 2), Some("a.ks"), 27, Severity::Error),
+            diagnostic((Category::Warning, 1), Some("a.ks"), 13, Severity::Warning),
+            diagnostic((Category::Resolution, 1), Some("a.ks"), 27, Severity::Error),
+            diagnostic((Category::Resolution, 2), None, 0, Severity::Error),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn order(diagnostics: &[&Diagnostic]) -> Vec<String> {
+        diagnostics
+            .iter()
+            .map(|d| {
+                format!(
+                    "{}@{}{}",
+                    d.source_name.as_deref().unwrap_or("-"),
+                    d.span.unwrap().start,
+                    d.code
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bundle_sorts_by_file_span_and_code() {
+        let mut bundle = bundle();
+        assert_eq!(
+            order(&bundle.sorted()),
+            [
+                "-@0KTR1002",
+                "a.ks@13KTR8001",
+                "a.ks@27KTR1001",
+                "a.ks@27KTR1002",
+                "b.ks@27KTR1002"
+            ]
+        );
+
+        bundle.sort();
+        assert_eq!(
+            order(&bundle.errors.iter().collect::<Vec<_>>()),
+            [
+                "-@0KTR1002",
+                "a.ks@27KTR1001",
+                "a.ks@27KTR1002",
+                "b.ks@27KTR1002"
+            ]
+        );
+
+        let by_file = bundle.by_file();
+        assert_eq!(
+            by_file.keys().collect::<Vec<_>>(),
+            [&None, &Some("a.ks"), &Some("b.ks")]
+        );
+        assert_eq!(by_file[&Some("a.ks")].len(), 3);
+        assert_eq!(bundle.by_code()["KTR1002"].len(), 3);
+    }
+
+    #[test]
+    fn bundle_output_is_independent_of_emission_order() {
+        let mut reversed: Vec<_> = bundle()
+            .sorted()
+            .into_iter()
+            .cloned()
+            .collect();
+        reversed.reverse();
+        let reversed: DiagnosticBundle = reversed.into_iter().collect();
+
+        assert_eq!(bundle().to_json().unwrap(), reversed.to_json().unwrap());
+        assert_eq!(
+            bundle().to_json_lines().unwrap(),
+            reversed.to_json_lines().unwrap()
+        );
+        assert_eq!(bundle().render_text(), reversed.render_text());
+    }
+
+    #[test]
+    fn bundle_renders_grouped_text() {
+        assert_eq!(
+            bundle().render_text(),
+            "\
+<no file>:
+  @0 error[KTR1002]: at 0
+a.ks:
+  2:1 warning[KTR8001]: at 13
+  2:15 error[KTR1001]: at 27
+  2:15 error[KTR1002]: at 27
+b.ks:
+  2:15 error[KTR1002]: at 27
+
+KTR1001: 1
+KTR1002: 3
+KTR8001: 1
+4 error(s), 1 warning(s)
+"
+        );
+    }
+
+    #[test]
+    fn denies_warnings_not_allowed() {
+        let mut bundle = bundle();
+        bundle.push(diagnostic((Category::Warning, 2), None, 0, Severity::Info));
+
+        assert_eq!(
+            bundle.denied_warnings(&BTreeSet::new()),
+            BTreeSet::from(["KTR8001".to_string()])
+        );
+        assert!(
+            bundle
+                .denied_warnings(&BTreeSet::from(["KTR8001".to_string()]))
+                .is_empty()
+        );
+    }
+
     #[test]
     fn bundle_serializable() {
         let mut bundle = DiagnosticBundle::new();
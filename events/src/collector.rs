@@ -1,36 +1,112 @@
 use crate::{Diagnostic, DiagnosticBundle, reporter::DiagnosticReporter};
-use actix::{Actor, Context, Handler, Message, MessageResult, Supervised};
-use std::sync::Arc;
-
-#[derive(Message)]
-#[rtype(result = "()")]
-pub struct EmitDiagnostic(pub Diagnostic);
-
-#[derive(Message)]
-#[rtype(result = "()")]
-pub struct EmitBatch(pub Vec<Diagnostic>);
-
-#[derive(Message)]
-#[rtype(result = "()")]
-pub struct Flush;
+use kintsu_errors::{ErrorCode, Severity};
+use std::{collections::HashMap, sync::Arc};
+
+/// Configuration of a [`DiagnosticCollector`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CollectorConfig {
+    /// Diagnostics reported per code and message, e.g. one missing import used in every
+    /// file. Later ones are still collected, and summarized to reporters on flush. `None`
+    /// reports every diagnostic.
+    pub max_per_group: Option<usize>,
+}
 
-#[derive(Message)]
-#[rtype(result = "DiagnosticBundle")]
-pub struct TakeBundle;
+/// Diagnostics of one code and message.
+#[derive(Debug)]
+struct Group {
+    severity: Severity,
+    reported: usize,
+    /// Diagnostics not reported since the last summary
+    suppressed: usize,
+}
 
 pub struct DiagnosticCollector {
     bundle: DiagnosticBundle,
     reporters: Vec<Arc<dyn DiagnosticReporter>>,
+    config: CollectorConfig,
+    groups: HashMap<(ErrorCode, String), Group>,
 }
 
 impl DiagnosticCollector {
     pub fn new(reporters: Vec<Box<dyn DiagnosticReporter>>) -> Self {
+        Self::with_config(reporters, CollectorConfig::default())
+    }
+
+    pub fn with_config(
+        reporters: Vec<Box<dyn DiagnosticReporter>>,
+        config: CollectorConfig,
+    ) -> Self {
         Self {
             bundle: DiagnosticBundle::new(),
             reporters: reporters
                 .into_iter()
                 .map(Arc::from)
                 .collect(),
+            config,
+            groups: HashMap::new(),
+        }
+    }
+
+    /// Reports `diagnostic` unless its group reached the limit, and collects it either way.
+    pub(crate) fn collect(
+        &mut self,
+        diagnostic: Diagnostic,
+    ) {
+        let report = match self.config.max_per_group {
+            Some(max) => {
+                let group = self
+                    .groups
+                    .entry((diagnostic.code, diagnostic.message.clone()))
+                    .or_insert_with(|| {
+                        Group {
+                            severity: diagnostic.severity,
+                            reported: 0,
+                            suppressed: 0,
+                        }
+                    });
+                if group.reported < max {
+                    group.reported += 1;
+                    true
+                } else {
+                    group.suppressed += 1;
+                    false
+                }
+            },
+            None => true,
+        };
+
+        if report {
+            self.emit_to_reporters(&diagnostic);
+        }
+        self.bundle.push(diagnostic);
+    }
+
+    /// Reports how many diagnostics of each group were suppressed since the last summary.
+    fn summarize_suppressed(&mut self) {
+        let mut summaries: Vec<_> = self
+            .groups
+            .iter_mut()
+            .filter(|(_, group)| group.suppressed > 0)
+            .map(|((code, message), group)| {
+                let suppressed = std::mem::take(&mut group.suppressed);
+                (
+                    *code,
+                    message.clone(),
+                    group.severity,
+                    suppressed,
+                    group.reported,
+                )
+            })
+            .collect();
+        summaries.sort_by(|a, b| (a.0.to_string(), &a.1).cmp(&(b.0.to_string(), &b.1)));
+
+        for (code, message, severity, suppressed, reported) in summaries {
+            let summary =
+                Diagnostic::new(code, format!("{message} (and {suppressed} more)"), severity)
+                    .with_help(format!(
+                        "only the first {reported} occurrences of this diagnostic are shown"
+                    ));
+            self.emit_to_reporters(&summary);
         }
     }
 
@@ -52,81 +128,108 @@ impl DiagnosticCollector {
             }
         }
     }
-}
 
-impl Actor for DiagnosticCollector {
-    type Context = Context<Self>;
-
-    fn started(
-        &mut self,
-        _ctx: &mut Self::Context,
-    ) {
-        tracing::debug!("DiagnosticCollector started");
+    /// Drains the diagnostics collected so far.
+    pub(crate) fn take_bundle(&mut self) -> DiagnosticBundle {
+        // diagnostics arrive in whatever order compilation finishes in
+        let mut bundle = std::mem::take(&mut self.bundle);
+        bundle.sort();
+        bundle
     }
 
-    fn stopped(
-        &mut self,
-        _ctx: &mut Self::Context,
-    ) {
-        tracing::debug!(
-            "DiagnosticCollector stopped with {} errors, {} warnings",
-            self.bundle.error_count(),
-            self.bundle.warning_count()
-        );
+    /// The diagnostics collected so far, sorted, leaving them in place.
+    pub(crate) fn snapshot(&self) -> DiagnosticBundle {
+        let mut bundle = self.bundle.clone();
+        bundle.sort();
+        bundle
     }
-}
-
-impl Supervised for DiagnosticCollector {}
 
-impl Handler<EmitDiagnostic> for DiagnosticCollector {
-    type Result = ();
+    pub(crate) fn counts(&self) -> (usize, usize) {
+        (self.bundle.error_count(), self.bundle.warning_count())
+    }
 
-    fn handle(
-        &mut self,
-        msg: EmitDiagnostic,
-        _ctx: &mut Self::Context,
-    ) {
-        let diagnostic = msg.0;
-        self.emit_to_reporters(&diagnostic);
-        self.bundle.push(diagnostic);
+    pub(crate) fn flush(&mut self) {
+        self.summarize_suppressed();
+        self.flush_reporters();
     }
 }
 
-impl Handler<EmitBatch> for DiagnosticCollector {
-    type Result = ();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CollectingReporter, ReporterError};
+    use kintsu_errors::{Category, Domain};
 
-    fn handle(
-        &mut self,
-        msg: EmitBatch,
-        _ctx: &mut Self::Context,
-    ) {
-        for diagnostic in msg.0 {
-            self.emit_to_reporters(&diagnostic);
-            self.bundle.push(diagnostic);
+    struct Shared(Arc<CollectingReporter>);
+
+    impl DiagnosticReporter for Shared {
+        fn emit(
+            &self,
+            diagnostic: &Diagnostic,
+        ) -> Result<(), ReporterError> {
+            self.0.emit(diagnostic)
         }
     }
-}
 
-impl Handler<TakeBundle> for DiagnosticCollector {
-    type Result = MessageResult<TakeBundle>;
+    fn undefined(name: &str) -> Diagnostic {
+        Diagnostic::new(
+            ErrorCode::new(Domain::TR, Category::Resolution, 2),
+            format!("undefined type: '{name}'"),
+            Severity::Error,
+        )
+    }
 
-    fn handle(
-        &mut self,
-        _msg: TakeBundle,
-        _ctx: &mut Self::Context,
-    ) -> Self::Result {
-        MessageResult(std::mem::take(&mut self.bundle))
+    #[test]
+    fn repeated_diagnostics_are_summarized() {
+        let reporter = Arc::new(CollectingReporter::new());
+        let mut collector = DiagnosticCollector::with_config(
+            vec![Box::new(Shared(reporter.clone()))],
+            CollectorConfig {
+                max_per_group: Some(2),
+            },
+        );
+
+        for _ in 0..50 {
+            collector.collect(undefined("Money"));
+        }
+        collector.collect(undefined("Order"));
+        collector.summarize_suppressed();
+
+        let reported: Vec<_> = reporter
+            .take()
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                "undefined type: 'Money'",
+                "undefined type: 'Money'",
+                "undefined type: 'Order'",
+                "undefined type: 'Money' (and 48 more)",
+            ]
+        );
+        // every diagnostic is still collected
+        assert_eq!(collector.bundle.error_count(), 51);
+
+        // summaries cover what was suppressed since the last one
+        collector.collect(undefined("Money"));
+        collector.summarize_suppressed();
+        assert_eq!(
+            reporter.take()[0].message,
+            "undefined type: 'Money' (and 1 more)"
+        );
     }
-}
 
-impl Handler<Flush> for DiagnosticCollector {
-    type Result = ();
+    #[test]
+    fn unlimited_by_default() {
+        let reporter = Arc::new(CollectingReporter::new());
+        let mut collector = DiagnosticCollector::new(vec![Box::new(Shared(reporter.clone()))]);
 
-    fn handle(
-        &mut self,
-        _msg: Flush,
-        _ctx: &mut Self::Context,
-    ) {
-        self.flush_reporters();
+        for _ in 0..20 {
+            collector.collect(undefined("Money"));
+        }
+        collector.summarize_suppressed();
+        assert_eq!(reporter.take().len(), 20);
     }
 }
@@ -1,4 +1,4 @@
-use kintsu_errors::{CompilerError, DiagnosticBuilder, ErrorCode, Severity, Span};
+use kintsu_errors::{CompilerError, DiagnosticBuilder, ErrorCode, Locale, Severity, Span};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -182,6 +182,7 @@ fn parse_error_code(s: &str) -> Option<ErrorCode> {
         "RG" => Domain::RG,
         "FS" => Domain::FS,
         "IN" => Domain::IN,
+        "LT" => Domain::LT,
         _ => return None,
     };
 
@@ -312,12 +313,12 @@ impl From<CompilerError> for Diagnostic {
 
         Self {
             code: err.error_code(),
-            message: err.message(),
+            message: err.localized_message(Locale::current()),
             severity: err.severity(),
             span: err.extract_deepest_span(),
             source_name,
             source_content,
-            help: err.help_text().map(String::from),
+            help: err.localized_help(Locale::current()),
             labels,
         }
     }
@@ -3,28 +3,38 @@
 //! Provides thread-safe diagnostic collection and reporting for the compiler.
 //! Inspired by the `registry-events` actor pattern.
 
-use actix::Addr;
 use std::sync::RwLock;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod actor;
 mod bundle;
 mod collector;
 mod diagnostic;
 mod reporter;
+mod system;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use actor::{CountDiagnostics, EmitBatch, EmitDiagnostic, Flush, SnapshotBundle, TakeBundle};
 pub use bundle::DiagnosticBundle;
-pub use collector::{DiagnosticCollector, EmitBatch, EmitDiagnostic, Flush, TakeBundle};
+pub use collector::{CollectorConfig, DiagnosticCollector};
 pub use diagnostic::{Diagnostic, DiagnosticLabel};
 pub use reporter::{
     CollectingReporter, DiagnosticReporter, JsonLinesReporter, NoOpReporter, ReporterError,
     StderrReporter,
 };
 
-static DIAGNOSTIC_SYSTEM: RwLock<Option<Addr<DiagnosticCollector>>> = RwLock::new(None);
+static DIAGNOSTIC_SYSTEM: RwLock<Option<system::System>> = RwLock::new(None);
 
 pub fn init(reporters: Vec<Box<dyn DiagnosticReporter>>) {
-    use actix::Actor;
+    init_with_config(reporters, CollectorConfig::default());
+}
 
-    let collector = DiagnosticCollector::new(reporters).start();
+/// Like [`init`], e.g. limiting how many repeats of one diagnostic are reported.
+pub fn init_with_config(
+    reporters: Vec<Box<dyn DiagnosticReporter>>,
+    config: CollectorConfig,
+) {
+    let collector = system::System::start(DiagnosticCollector::with_config(reporters, config));
     let mut guard = DIAGNOSTIC_SYSTEM.write().unwrap();
     if guard.is_some() {
         tracing::warn!("diagnostic system already initialized, replacing");
@@ -39,8 +49,8 @@ pub fn is_initialized() -> bool {
 
 pub fn emit(diagnostic: impl Into<Diagnostic>) {
     let guard = DIAGNOSTIC_SYSTEM.read().unwrap();
-    if let Some(addr) = guard.as_ref() {
-        addr.do_send(EmitDiagnostic(diagnostic.into()));
+    if let Some(system) = guard.as_ref() {
+        system.emit(diagnostic.into());
     } else {
         let diag = diagnostic.into();
         tracing::warn!("diagnostic system not initialized, printing directly");
@@ -58,8 +68,8 @@ pub fn emit_warning(warn: impl Into<Diagnostic>) {
 
 pub fn emit_batch(diagnostics: Vec<Diagnostic>) {
     let guard = DIAGNOSTIC_SYSTEM.read().unwrap();
-    if let Some(addr) = guard.as_ref() {
-        addr.do_send(EmitBatch(diagnostics));
+    if let Some(system) = guard.as_ref() {
+        system.emit_batch(diagnostics);
     } else {
         tracing::warn!("diagnostic system not initialized, printing directly");
         for diag in diagnostics {
@@ -72,20 +82,38 @@ pub fn emit_batch(diagnostics: Vec<Diagnostic>) {
 pub async fn take_bundle() -> DiagnosticBundle {
     let guard = DIAGNOSTIC_SYSTEM.read().unwrap();
     match guard.as_ref() {
-        Some(addr) => {
-            addr.send(TakeBundle)
-                .await
-                .unwrap_or_default()
-        },
+        Some(system) => system.take_bundle().await,
+        None => DiagnosticBundle::new(),
+    }
+}
+
+/// The diagnostics emitted so far. Unlike [`take_bundle`], they are left in place to be
+/// reported on shutdown.
+#[allow(clippy::await_holding_lock)]
+pub async fn snapshot() -> DiagnosticBundle {
+    let guard = DIAGNOSTIC_SYSTEM.read().unwrap();
+    match guard.as_ref() {
+        Some(system) => system.snapshot().await,
         None => DiagnosticBundle::new(),
     }
 }
 
+/// Counts of `(errors, warnings)` emitted so far. Unlike [`take_bundle`], the collected
+/// diagnostics are left in place.
+#[allow(clippy::await_holding_lock)]
+pub async fn counts() -> (usize, usize) {
+    let guard = DIAGNOSTIC_SYSTEM.read().unwrap();
+    match guard.as_ref() {
+        Some(system) => system.counts().await,
+        None => (0, 0),
+    }
+}
+
 #[allow(clippy::await_holding_lock)]
 pub async fn flush() {
     let guard = DIAGNOSTIC_SYSTEM.read().unwrap();
-    if let Some(addr) = guard.as_ref() {
-        let _ = addr.send(Flush).await;
+    if let Some(system) = guard.as_ref() {
+        system.flush().await;
     }
 }
 
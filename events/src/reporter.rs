@@ -50,24 +50,51 @@ impl DiagnosticReporter for NoOpReporter {
 
 pub struct JsonLinesReporter<W: Write + Send + Sync> {
     writer: std::sync::Mutex<W>,
+    /// Wrap diagnostics as `diagnostic` messages of `--message-format=json`
+    messages: bool,
 }
 
 impl<W: Write + Send + Sync> JsonLinesReporter<W> {
     pub fn new(writer: W) -> Self {
         Self {
             writer: std::sync::Mutex::new(writer),
+            messages: false,
+        }
+    }
+
+    /// Writes each diagnostic as `{"reason":"diagnostic","diagnostic":{..}}`, alongside the
+    /// other messages of `--message-format=json`.
+    pub fn messages(writer: W) -> Self {
+        Self {
+            writer: std::sync::Mutex::new(writer),
+            messages: true,
         }
     }
 }
 
+#[derive(serde::Serialize)]
+struct DiagnosticMessage<'a> {
+    reason: &'static str,
+    diagnostic: &'a Diagnostic,
+}
+
 impl<W: Write + Send + Sync> DiagnosticReporter for JsonLinesReporter<W> {
     fn emit(
         &self,
         diagnostic: &Diagnostic,
     ) -> Result<(), ReporterError> {
-        let json = serde_json::to_string(diagnostic)?;
+        let mut json = if self.messages {
+            serde_json::to_string(&DiagnosticMessage {
+                reason: "diagnostic",
+                diagnostic,
+            })?
+        } else {
+            serde_json::to_string(diagnostic)?
+        };
+        // one write per line, so lines shared with other writers of stdout stay whole
+        json.push('\n');
         let mut w = self.writer.lock().unwrap();
-        writeln!(w, "{}", json)?;
+        w.write_all(json.as_bytes())?;
         Ok(())
     }
 
@@ -152,4 +179,25 @@ mod tests {
         assert!(output.contains("KTR1001"));
         assert!(output.ends_with('\n'));
     }
+
+    #[test]
+    fn json_lines_reporter_writes_messages() {
+        let mut buffer = Vec::new();
+        JsonLinesReporter::messages(&mut buffer)
+            .emit(&Diagnostic::new(
+                ErrorCode::new(Domain::TR, Category::Resolution, 1),
+                "test",
+                Severity::Error,
+            ))
+            .unwrap();
+
+        let message: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(message["reason"], "diagnostic");
+        assert_eq!(message["diagnostic"]["code"], "KTR1001");
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .starts_with(r#"{"reason":"diagnostic""#)
+        );
+    }
 }
@@ -0,0 +1,100 @@
+//! The running [`DiagnosticCollector`]. Natively it is an actor, so that compilation tasks
+//! emit without waiting on each other; in the browser there is no runtime to run one, and
+//! diagnostics are collected in place.
+
+use crate::{Diagnostic, DiagnosticBundle, DiagnosticCollector};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct System(actix::Addr<DiagnosticCollector>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl System {
+    pub(crate) fn start(collector: DiagnosticCollector) -> Self {
+        use actix::Actor;
+
+        Self(collector.start())
+    }
+
+    pub(crate) fn emit(
+        &self,
+        diagnostic: Diagnostic,
+    ) {
+        self.0
+            .do_send(crate::EmitDiagnostic(diagnostic));
+    }
+
+    pub(crate) fn emit_batch(
+        &self,
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        self.0.do_send(crate::EmitBatch(diagnostics));
+    }
+
+    pub(crate) async fn take_bundle(&self) -> DiagnosticBundle {
+        self.0
+            .send(crate::TakeBundle)
+            .await
+            .unwrap_or_default()
+    }
+
+    pub(crate) async fn snapshot(&self) -> DiagnosticBundle {
+        self.0
+            .send(crate::SnapshotBundle)
+            .await
+            .unwrap_or_default()
+    }
+
+    pub(crate) async fn counts(&self) -> (usize, usize) {
+        self.0
+            .send(crate::CountDiagnostics)
+            .await
+            .unwrap_or_default()
+    }
+
+    pub(crate) async fn flush(&self) {
+        let _ = self.0.send(crate::Flush).await;
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) struct System(std::sync::Mutex<DiagnosticCollector>);
+
+#[cfg(target_arch = "wasm32")]
+impl System {
+    pub(crate) fn start(collector: DiagnosticCollector) -> Self {
+        Self(std::sync::Mutex::new(collector))
+    }
+
+    pub(crate) fn emit(
+        &self,
+        diagnostic: Diagnostic,
+    ) {
+        self.0.lock().unwrap().collect(diagnostic);
+    }
+
+    pub(crate) fn emit_batch(
+        &self,
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        let mut collector = self.0.lock().unwrap();
+        for diagnostic in diagnostics {
+            collector.collect(diagnostic);
+        }
+    }
+
+    pub(crate) async fn take_bundle(&self) -> DiagnosticBundle {
+        self.0.lock().unwrap().take_bundle()
+    }
+
+    pub(crate) async fn snapshot(&self) -> DiagnosticBundle {
+        self.0.lock().unwrap().snapshot()
+    }
+
+    pub(crate) async fn counts(&self) -> (usize, usize) {
+        self.0.lock().unwrap().counts()
+    }
+
+    pub(crate) async fn flush(&self) {
+        self.0.lock().unwrap().flush();
+    }
+}
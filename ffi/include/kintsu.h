@@ -0,0 +1,48 @@
+/*
+ * C interface to kintsu declarations, built from the kintsu-ffi crate.
+ *
+ * Strings are NUL-terminated UTF-8. Strings returned by the library are owned by the caller
+ * and freed with kintsu_string_free. A function which fails returns NULL, and
+ * kintsu_last_error describes why.
+ */
+
+#ifndef KINTSU_H
+#define KINTSU_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct KintsuDeclarations KintsuDeclarations;
+
+/* Parses declarations as the registry serves them, or a bare declaration bundle. */
+KintsuDeclarations *kintsu_declarations_parse(const char *json);
+
+void kintsu_declarations_free(KintsuDeclarations *declarations);
+
+/*
+ * Checks the JSON value against the type at type_path, e.g. "users::User". Returns a JSON
+ * array with the reason for each mismatch, "[]" when the value is valid.
+ */
+char *kintsu_validate(
+    const KintsuDeclarations *declarations,
+    const char *type_path,
+    const char *value
+);
+
+/* Every error code the compiler reports, as a JSON array. */
+char *kintsu_error_catalog(void);
+
+/*
+ * Why the last call on this thread failed, or NULL if it did not. Owned by the library and
+ * valid until the next call on this thread.
+ */
+const char *kintsu_last_error(void);
+
+void kintsu_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* KINTSU_H */
@@ -0,0 +1,280 @@
+//! A C interface to kintsu declarations, so gateways written in Go, Python or other languages
+//! validate payloads against a package without re-implementing its type system.
+//!
+//! Strings cross the boundary as NUL-terminated UTF-8. Strings returned by the library are
+//! owned by the caller and freed with [`kintsu_string_free`]. A function which fails returns
+//! `NULL`, and [`kintsu_last_error`] describes why. The declarations of `include/kintsu.h`
+//! mirror this module.
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    panic::{AssertUnwindSafe, catch_unwind},
+    ptr,
+};
+
+use kintsu_parser::declare::{DeclarationBundle, DeclarationVersion};
+
+/// Declarations parsed by [`kintsu_declarations_parse`].
+pub struct KintsuDeclarations(DeclarationBundle);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(reason: impl Into<String>) {
+    let reason = reason.into().replace('\0', " ");
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(reason).ok());
+}
+
+/// Runs `f`, recording why it failed, or that it panicked, for [`kintsu_last_error`].
+fn guard<T>(
+    fallback: T,
+    f: impl FnOnce() -> Result<T, String>,
+) -> T {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(reason)) => {
+            set_last_error(reason);
+            fallback
+        },
+        Err(_) => {
+            set_last_error("kintsu panicked - please report it");
+            fallback
+        },
+    }
+}
+
+/// # Safety
+///
+/// `s` is NULL or a NUL-terminated string valid for the duration of the call.
+unsafe fn read_str<'a>(
+    s: *const c_char,
+    what: &str,
+) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("{what} is NULL"));
+    }
+    // SAFETY: upheld by the caller
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| format!("{what} is not UTF-8"))
+}
+
+fn into_raw(s: String) -> *mut c_char {
+    CString::new(s.replace('\0', " "))
+        .expect("NUL bytes are replaced")
+        .into_raw()
+}
+
+/// Parses declarations as the registry serves them, or a bare declaration bundle. Returns
+/// `NULL` when `json` is not declarations.
+///
+/// # Safety
+///
+/// `json` is NULL or a NUL-terminated string. The declarations returned are freed with
+/// [`kintsu_declarations_free`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn kintsu_declarations_parse(json: *const c_char) -> *mut KintsuDeclarations {
+    guard(ptr::null_mut(), || {
+        // SAFETY: upheld by the caller
+        let json = unsafe { read_str(json, "json") }?;
+        let bundle = match serde_json::from_str::<DeclarationVersion>(json) {
+            Ok(DeclarationVersion::V1(bundle)) => bundle,
+            Err(err) => {
+                serde_json::from_str::<DeclarationBundle>(json)
+                    .map_err(|_| format!("invalid declarations: {err}"))?
+            },
+        };
+        Ok(Box::into_raw(Box::new(KintsuDeclarations(bundle))))
+    })
+}
+
+/// Frees declarations returned by [`kintsu_declarations_parse`]. Does nothing for `NULL`.
+///
+/// # Safety
+///
+/// `declarations` is NULL or was returned by [`kintsu_declarations_parse`], and is not used
+/// again.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn kintsu_declarations_free(declarations: *mut KintsuDeclarations) {
+    if !declarations.is_null() {
+        // SAFETY: upheld by the caller
+        drop(unsafe { Box::from_raw(declarations) });
+    }
+}
+
+/// Checks the JSON `value` against the type at `type_path`, e.g. `users::User`, returning a
+/// JSON array with the reason for each mismatch, empty when the value is valid. Returns
+/// `NULL` when the type is not declared or `value` is not JSON.
+///
+/// # Safety
+///
+/// `declarations` was returned by [`kintsu_declarations_parse`] and not freed, and
+/// `type_path` and `value` are NULL or NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn kintsu_validate(
+    declarations: *const KintsuDeclarations,
+    type_path: *const c_char,
+    value: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        // SAFETY: upheld by the caller
+        let declarations = unsafe { declarations.as_ref() }.ok_or("declarations is NULL")?;
+        // SAFETY: upheld by the caller
+        let (type_path, value) =
+            unsafe { (read_str(type_path, "type_path")?, read_str(value, "value")?) };
+
+        let value = serde_json::from_str(value).map_err(|err| format!("invalid value: {err}"))?;
+        let reasons = declarations
+            .0
+            .check_value(type_path, &value)?;
+        let json = serde_json::to_string(&reasons).map_err(|err| err.to_string())?;
+        Ok(into_raw(json))
+    })
+}
+
+/// Every error code the compiler reports, as the JSON array `kintsu explain` is built from.
+#[unsafe(no_mangle)]
+pub extern "C" fn kintsu_error_catalog() -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        Ok(into_raw(kintsu_errors::catalog::render_json()))
+    })
+}
+
+/// Why the last call on this thread failed, or `NULL` if it did not. The string is owned by
+/// the library, and valid until the next call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn kintsu_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |reason| reason.as_ptr())
+    })
+}
+
+/// Frees a string returned by the library. Does nothing for `NULL`.
+///
+/// # Safety
+///
+/// `s` is NULL or was returned by the library, other than by [`kintsu_last_error`], and is
+/// not used again.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn kintsu_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: upheld by the caller
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DECLARATIONS: &str = r#"{
+        "version": "v1",
+        "declarations": {
+            "root": {
+                "package": "shop",
+                "namespaces": {
+                    "users": {
+                        "name": "users",
+                        "types": [{
+                            "definition_type": "struct",
+                            "name": "User",
+                            "fields": [
+                                { "name": "id", "ty": { "type": "builtin", "ty": "i64" } },
+                                {
+                                    "name": "name",
+                                    "ty": { "type": "builtin", "ty": "str" },
+                                    "constraints": [{ "kind": "len", "min": 1 }]
+                                }
+                            ],
+                            "meta": { "version": 1 }
+                        }]
+                    }
+                },
+                "external_refs": []
+            },
+            "dependencies": {}
+        }
+    }"#;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    unsafe fn take(s: *mut c_char) -> String {
+        let owned = unsafe { CStr::from_ptr(s) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { kintsu_string_free(s) };
+        owned
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(kintsu_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn validates_values() {
+        unsafe {
+            let declarations = kintsu_declarations_parse(c(DECLARATIONS).as_ptr());
+            assert!(!declarations.is_null(), "{}", last_error());
+
+            let valid = kintsu_validate(
+                declarations,
+                c("users::User").as_ptr(),
+                c(r#"{"id": 1, "name": "ada"}"#).as_ptr(),
+            );
+            assert_eq!(take(valid), "[]");
+
+            let invalid = kintsu_validate(
+                declarations,
+                c("users::User").as_ptr(),
+                c(r#"{"id": "1", "name": ""}"#).as_ptr(),
+            );
+            assert_eq!(
+                take(invalid),
+                r#"["at $.id: expected i64, found string","at $.name: length 0 is less than 1"]"#
+            );
+
+            let missing =
+                kintsu_validate(declarations, c("users::Admin").as_ptr(), c("{}").as_ptr());
+            assert!(missing.is_null());
+            assert_eq!(last_error(), "'users::Admin' is not a type of the package");
+
+            kintsu_declarations_free(declarations);
+        }
+    }
+
+    #[test]
+    fn reports_invalid_input() {
+        unsafe {
+            assert!(kintsu_declarations_parse(c("{}").as_ptr()).is_null());
+            assert!(last_error().starts_with("invalid declarations"));
+
+            assert!(kintsu_declarations_parse(ptr::null()).is_null());
+            assert_eq!(last_error(), "json is NULL");
+        }
+    }
+
+    #[test]
+    fn lists_the_error_catalog() {
+        let catalog = unsafe { take(kintsu_error_catalog()) };
+        let catalog: serde_json::Value = serde_json::from_str(&catalog).unwrap();
+        assert!(
+            catalog
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|entry| entry["code"] == "KTR1001")
+        );
+        assert!(kintsu_last_error().is_null());
+    }
+}
@@ -0,0 +1,81 @@
+//! Applies machine-applicable compiler suggestions to source files.
+
+use std::path::PathBuf;
+
+use kintsu_errors::{Suggestion, apply_suggestions};
+
+use crate::FileSystem;
+
+/// Rewrites each file with its suggestions applied, returning how many were applied.
+///
+/// Callers are expected to pass only machine-applicable suggestions, grouped by
+/// file as returned by `CompilerError::machine_applicable_fixes`.
+pub async fn apply_fixes<Fs: FileSystem + ?Sized>(
+    fs: &Fs,
+    fixes: &[(PathBuf, Vec<Suggestion>)],
+) -> crate::Result<usize> {
+    let mut total = 0;
+
+    for (path, suggestions) in fixes {
+        let source = fs.read_to_string(path).await?;
+        let (fixed, applied) = apply_suggestions(&source, suggestions);
+
+        if applied > 0 {
+            tracing::info!("applying {applied} fix(es) to '{}'", path.display());
+            fs.write(path, fixed.into_bytes()).await?;
+            total += applied;
+        }
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use kintsu_errors::{Applicability, Span, Suggestion};
+
+    use super::apply_fixes;
+    use crate::memory::MemoryFileSystem;
+
+    #[tokio::test]
+    async fn rewrites_files_in_place() {
+        let fs = MemoryFileSystem::new();
+        fs.add_file("schema/types.ks", "struct A { b: Usr }");
+        fs.add_file("schema/lib.ks", "namespace pkg");
+
+        let fixes = vec![
+            (
+                PathBuf::from("schema/types.ks"),
+                vec![Suggestion::new(
+                    Span::new(14, 17),
+                    "User",
+                    Applicability::MachineApplicable,
+                    "",
+                )],
+            ),
+            (
+                PathBuf::from("schema/lib.ks"),
+                vec![Suggestion::insert(
+                    13,
+                    ";",
+                    Applicability::MachineApplicable,
+                    "",
+                )],
+            ),
+        ];
+
+        assert_eq!(apply_fixes(&fs, &fixes).await.unwrap(), 2);
+        assert_eq!(
+            fs.get_file_content(Path::new("schema/types.ks"))
+                .unwrap(),
+            "struct A { b: User }".as_bytes()
+        );
+        assert_eq!(
+            fs.get_file_content(Path::new("schema/lib.ks"))
+                .unwrap(),
+            "namespace pkg;".as_bytes()
+        );
+    }
+}
@@ -0,0 +1,415 @@
+//! A read-only [`FileSystem`] served over HTTP, so dependency sources can be read lazily
+//! from the registry instead of being downloaded whole up front.
+//!
+//! The filesystem is rooted at a base URL which responds with a JSON array of the file
+//! paths it serves. Each file is then fetched from `<base>/<path>` the first time it is read
+//! and cached for the lifetime of the filesystem (and its clones). Concurrent reads of the
+//! same file share one request, and at most [`HttpConfig::max_concurrent_requests`] requests
+//! are in flight at once.
+
+use std::{
+    collections::BTreeSet,
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use dashmap::DashMap;
+use tokio::sync::{OnceCell, Semaphore};
+
+use crate::{Error, FileSystem, Result, memory::remove_relative, watch::FsEventStream};
+
+#[derive(Clone, Copy, Debug)]
+pub struct HttpConfig {
+    pub max_concurrent_requests: usize,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 8,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpFileSystem {
+    client: reqwest::Client,
+    base: url::Url,
+    files: Arc<BTreeSet<PathBuf>>,
+    cache: Arc<DashMap<PathBuf, Arc<OnceCell<Bytes>>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl HttpFileSystem {
+    /// Fetches the listing at `base`. File contents are not requested until read.
+    pub async fn connect(
+        client: reqwest::Client,
+        base: url::Url,
+        config: HttpConfig,
+    ) -> Result<Self> {
+        let files: Vec<PathBuf> = client
+            .get(base.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(Self {
+            client,
+            base,
+            files: Arc::new(
+                files
+                    .iter()
+                    .map(|path| remove_relative(path))
+                    .collect(),
+            ),
+            cache: Arc::new(DashMap::new()),
+            permits: Arc::new(Semaphore::new(config.max_concurrent_requests.max(1))),
+        })
+    }
+
+    /// All file paths, sorted.
+    pub fn list_files(&self) -> Vec<PathBuf> {
+        self.files.iter().cloned().collect()
+    }
+
+    /// How many files have been fetched so far.
+    pub fn fetched_files(&self) -> usize {
+        self.cache
+            .iter()
+            .filter(|entry| entry.value().initialized())
+            .count()
+    }
+
+    fn not_found(path: &Path) -> Error {
+        Error::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("File not found: {}", path.display()),
+        ))
+    }
+
+    fn file_url(
+        &self,
+        path: &Path,
+    ) -> url::Url {
+        let mut url = self.base.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments
+                .pop_if_empty()
+                .extend(path.components().filter_map(|component| {
+                    match component {
+                        Component::Normal(segment) => Some(segment.to_string_lossy()),
+                        _ => None,
+                    }
+                }));
+        }
+        url
+    }
+
+    /// The contents of `path`, requesting them unless an earlier read already did.
+    fn fetch(
+        &self,
+        path: &Path,
+    ) -> impl Future<Output = Result<Bytes>> + Send + 'static {
+        let path = remove_relative(path);
+        let cell = self.files.contains(&path).then(|| {
+            self.cache
+                .entry(path.clone())
+                .or_default()
+                .clone()
+        });
+        let url = self.file_url(&path);
+        let client = self.client.clone();
+        let permits = self.permits.clone();
+
+        async move {
+            let Some(cell) = cell else {
+                return Err(Self::not_found(&path));
+            };
+
+            cell.get_or_try_init(|| {
+                async {
+                    let _permit = permits
+                        .acquire()
+                        .await
+                        .expect("request semaphore is never closed");
+
+                    let response = client.get(url).send().await?;
+                    if response.status() == reqwest::StatusCode::NOT_FOUND {
+                        return Err(Self::not_found(&path));
+                    }
+                    Ok(response.error_for_status()?.bytes().await?)
+                }
+            })
+            .await
+            .cloned()
+        }
+    }
+
+    /// [`Self::fetch`] as a `Sync` future, which request futures are not, by running the
+    /// request as its own task.
+    fn fetch_sync(
+        &self,
+        path: &Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Bytes>> + Send + Sync>> {
+        let fs = self.clone();
+        let path = path.to_path_buf();
+        Box::pin(async move {
+            tokio::spawn(async move { fs.fetch(&path).await })
+                .await
+                .map_err(|e| Error::IoError(std::io::Error::other(e)))?
+        })
+    }
+}
+
+fn utf8(bytes: Bytes) -> Result<String> {
+    String::from_utf8(bytes.to_vec()).map_err(|e| {
+        Error::IoError(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid UTF-8: {}", e),
+        ))
+    })
+}
+
+impl FileSystem for HttpFileSystem {
+    fn exists_sync(
+        &self,
+        path: &Path,
+    ) -> bool {
+        self.files.contains(&remove_relative(path))
+    }
+
+    fn find_glob(
+        &self,
+        include: &[String],
+        exclude: &[String],
+    ) -> Result<Vec<PathBuf>> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    glob::Pattern::new(
+                        &remove_relative(Path::new(pattern))
+                            .display()
+                            .to_string(),
+                    )
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()
+        };
+        let include_patterns = compile(include)?;
+        let exclude_patterns = compile(exclude)?;
+
+        Ok(self
+            .files
+            .iter()
+            .filter(|path| {
+                (include_patterns.is_empty()
+                    || include_patterns
+                        .iter()
+                        .any(|pattern| pattern.matches_path(path)))
+                    && !exclude_patterns
+                        .iter()
+                        .any(|pattern| pattern.matches_path(path))
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn read(
+        &self,
+        path: &Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + Sync>> {
+        let fetch = self.fetch_sync(path);
+        Box::pin(async move { Ok(fetch.await?.to_vec()) })
+    }
+
+    fn read_to_string(
+        &self,
+        path: &Path,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + Sync>> {
+        let fetch = self.fetch_sync(path);
+        Box::pin(async move { utf8(fetch.await?) })
+    }
+
+    /// Served from the cache, otherwise fetched by blocking the current worker thread, which
+    /// requires a multi-threaded tokio runtime.
+    fn read_to_string_sync(
+        &self,
+        path: &Path,
+    ) -> Result<String> {
+        let cached = self
+            .cache
+            .get(&remove_relative(path))
+            .and_then(|cell| cell.get().cloned());
+        if let Some(bytes) = cached {
+            return utf8(bytes);
+        }
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+                utf8(tokio::task::block_in_place(|| {
+                    handle.block_on(self.fetch(path))
+                })?)
+            },
+            _ => {
+                Err(Error::IoError(std::io::Error::new(
+                    std::io::ErrorKind::WouldBlock,
+                    format!(
+                        "{} has not been fetched yet, read it asynchronously first",
+                        path.display()
+                    ),
+                )))
+            },
+        }
+    }
+
+    fn write(
+        &self,
+        path: &Path,
+        _contents: Vec<u8>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + Sync>> {
+        let path = path.to_path_buf();
+        Box::pin(async move {
+            Err(Error::IoError(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("cannot write {}: filesystem is read-only", path.display()),
+            )))
+        })
+    }
+
+    /// Served files never change, so the stream ends immediately.
+    fn watch(
+        &self,
+        _paths: &[PathBuf],
+        _include: &[String],
+    ) -> Result<FsEventStream> {
+        Ok(Box::pin(futures::stream::empty()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    /// Serves `files` as a listing at `/pkg` and contents below it, counting file requests.
+    async fn serve(files: &'static [(&'static str, &'static str)]) -> (url::Url, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let counter = counter.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0; 4096];
+                    let n = stream.read(&mut buf).await.unwrap();
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let target = request
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap()
+                        .to_string();
+
+                    let (status, body) = if target == "/pkg" {
+                        let listing: Vec<_> = files.iter().map(|(path, _)| *path).collect();
+                        ("200 OK", serde_json::to_string(&listing).unwrap())
+                    } else {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        match files
+                            .iter()
+                            .find(|(path, _)| target == format!("/pkg/{path}"))
+                        {
+                            Some((_, contents)) => ("200 OK", contents.to_string()),
+                            None => ("404 Not Found", String::new()),
+                        }
+                    };
+
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    stream
+                        .write_all(response.as_bytes())
+                        .await
+                        .unwrap();
+                });
+            }
+        });
+
+        (format!("http://{addr}/pkg").parse().unwrap(), requests)
+    }
+
+    async fn connect(base: url::Url) -> HttpFileSystem {
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .unwrap();
+        HttpFileSystem::connect(client, base, HttpConfig::default())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reads_lazily_and_caches() {
+        let (base, requests) = serve(&[
+            ("schema.toml", "[package]"),
+            ("schema/lib.ks", "namespace foo;"),
+        ])
+        .await;
+        let fs = connect(base).await;
+
+        assert!(fs.exists_sync("./schema/lib.ks".as_ref()));
+        assert_eq!(
+            fs.find_glob(&["schema/*.ks".to_string()], &[])
+                .unwrap(),
+            vec![PathBuf::from("schema/lib.ks")]
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        for _ in 0..3 {
+            assert_eq!(
+                fs.read_to_string("schema/lib.ks".as_ref())
+                    .await
+                    .unwrap(),
+                "namespace foo;"
+            );
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            fs.read_to_string_sync("schema/lib.ks".as_ref())
+                .unwrap(),
+            "namespace foo;"
+        );
+        assert_eq!(fs.fetched_files(), 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_files_are_not_requested() {
+        let (base, requests) = serve(&[("schema.toml", "[package]")]).await;
+        let fs = connect(base).await;
+
+        let err = fs
+            .read("missing.ks".as_ref())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::IoError(e) if e.kind() == std::io::ErrorKind::NotFound));
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        assert!(
+            fs.write("schema.toml".as_ref(), vec![])
+                .await
+                .is_err()
+        );
+    }
+}
@@ -4,9 +4,14 @@ use std::{
     path::{Path, PathBuf},
     pin::Pin,
 };
+pub mod fix;
+#[cfg(feature = "http")]
+pub mod http;
 pub mod match_paths;
 pub mod memory;
+pub mod overlay;
 pub mod physical;
+pub mod watch;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -16,6 +21,13 @@ pub enum Error {
     GlobPattern(#[from] glob::PatternError),
     #[error("io error: {0}")]
     IoError(#[from] std::io::Error),
+    #[error("quota error: {0}")]
+    QuotaExceeded(#[from] memory::QuotaExceeded),
+    #[error("watch error: {0}")]
+    Watch(#[from] notify::Error),
+    #[cfg(feature = "http")]
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
 }
 
 impl From<Error> for kintsu_errors::CompilerError {
@@ -37,6 +49,26 @@ impl From<Error> for kintsu_errors::CompilerError {
                     .unlocated()
                     .build()
             },
+            #[cfg(feature = "http")]
+            Error::Http(e) => {
+                FilesystemError::io_error(e.to_string())
+                    .unlocated()
+                    .build()
+            },
+            Error::Watch(e) => {
+                FilesystemError::io_error(e.to_string())
+                    .unlocated()
+                    .build()
+            },
+            Error::QuotaExceeded(e) => {
+                FilesystemError::quota_exceeded(
+                    e.kind.to_string(),
+                    e.limit as u64,
+                    e.requested as u64,
+                )
+                .unlocated()
+                .build()
+            },
         }
     }
 }
@@ -75,6 +107,14 @@ pub trait FileSystem: Send + Sync {
         path: &Path,
         contents: Vec<u8>,
     ) -> Pin<Box<dyn Future<Output = crate::Result<()>> + Send + Sync>>;
+
+    /// Streams changes to files below `paths` which match one of the `include` globs, or any
+    /// file when `include` is empty.
+    fn watch(
+        &self,
+        paths: &[PathBuf],
+        include: &[String],
+    ) -> Result<watch::FsEventStream>;
 }
 
 impl<T: FileSystem + ?Sized> FileSystem for std::sync::Arc<T> {
@@ -121,4 +161,12 @@ impl<T: FileSystem + ?Sized> FileSystem for std::sync::Arc<T> {
     ) -> Pin<Box<dyn Future<Output = crate::Result<()>> + Send + Sync>> {
         self.as_ref().write(path, contents)
     }
+
+    fn watch(
+        &self,
+        paths: &[PathBuf],
+        include: &[String],
+    ) -> Result<watch::FsEventStream> {
+        self.as_ref().watch(paths, include)
+    }
 }
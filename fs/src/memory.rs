@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
     pin::Pin,
     sync::{Arc, Mutex},
@@ -9,7 +9,10 @@ use bytes::Bytes;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 
-use crate::{Error, FileSystem, Result};
+use crate::{
+    Error, FileSystem, Result,
+    watch::{FsEvent, FsEventKind, FsEventStream, WatchFilter},
+};
 use std::{
     ffi::OsString,
     path::{Component, MAIN_SEPARATOR},
@@ -38,6 +41,68 @@ pub enum FsOperation {
     },
 }
 
+/// Per-instance limits on the contents of a [`MemoryFileSystem`]. `None` is unbounded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryQuota {
+    pub max_bytes: Option<usize>,
+    pub max_files: Option<usize>,
+}
+
+impl MemoryQuota {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_bytes(
+        mut self,
+        max_bytes: usize,
+    ) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    pub fn with_max_files(
+        mut self,
+        max_files: usize,
+    ) -> Self {
+        self.max_files = Some(max_files);
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaKind {
+    Bytes,
+    Files,
+}
+
+impl std::fmt::Display for QuotaKind {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            Self::Bytes => write!(f, "byte"),
+            Self::Files => write!(f, "file count"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("{kind} limit is {limit}, {requested} requested")]
+pub struct QuotaExceeded {
+    pub kind: QuotaKind,
+    pub limit: usize,
+    pub requested: usize,
+}
+
+/// Events buffered per watcher before the slowest one starts missing them.
+const WATCH_CAPACITY: usize = 1024;
+
+fn event_channel() -> tokio::sync::broadcast::Sender<FsEvent> {
+    tokio::sync::broadcast::channel(WATCH_CAPACITY).0
+}
+
 fn de_with_utf<'de, D>(deserializer: D) -> std::result::Result<HashMap<PathBuf, Bytes>, D::Error>
 where
     D: serde::Deserializer<'de>, {
@@ -54,7 +119,7 @@ fn ser_with_utf<S>(
 ) -> std::result::Result<S::Ok, S::Error>
 where
     S: serde::Serializer, {
-    let map: HashMap<PathBuf, String> = orig
+    let map: BTreeMap<PathBuf, String> = orig
         .iter()
         .map(|ref_multi| {
             (
@@ -73,13 +138,27 @@ struct MemoryFileSystemSerdeHelper {
     files: HashMap<String, String>,
 }
 
+/// An in-memory [`FileSystem`].
+///
+/// Listing, globbing, serialization and physical writes always visit paths in sorted order,
+/// independent of insertion order. Writes through [`FileSystem::write`] and
+/// [`MemoryFileSystem::try_add_file`] are checked against the instance's [`MemoryQuota`], as
+/// are files gathered by [`MemoryFileSystem::merge`] and [`MemoryFileSystem::extract_from`].
 #[cfg_attr(feature = "db", derive(sea_orm::prelude::FromJsonQueryResult))]
 #[derive(Clone, Debug)]
 pub struct MemoryFileSystem {
     files: Arc<DashMap<PathBuf, Bytes>>,
 
+    /// Running total of the bytes in `files`. Its lock is held while files are stored or
+    /// removed, so quota checks and the inserts they admit cannot interleave.
+    stored_bytes: Arc<Mutex<usize>>,
+
+    quota: MemoryQuota,
+
     pattern_cache: Arc<Mutex<HashMap<String, glob::Pattern>>>,
 
+    events: tokio::sync::broadcast::Sender<FsEvent>,
+
     #[cfg(feature = "fs-test")]
     operations: Arc<Mutex<Vec<FsOperation>>>,
 }
@@ -150,20 +229,16 @@ impl<'de> serde::Deserialize<'de> for MemoryFileSystem {
     where
         D: serde::Deserializer<'de>, {
         let files_map = de_with_utf(deserializer)?;
-        let files = Arc::new(DashMap::new());
+        let fs = MemoryFileSystem::new();
         for (k, v) in files_map {
-            files.insert(k, v);
+            fs.store(k, v, false)
+                .expect("unlimited filesystems take every file");
         }
-        Ok(Self {
-            files,
-            pattern_cache: Arc::new(Mutex::new(HashMap::new())),
-            #[cfg(feature = "fs-test")]
-            operations: Arc::new(Mutex::new(Vec::new())),
-        })
+        Ok(fs)
     }
 }
 
-fn remove_relative(path: &Path) -> PathBuf {
+pub(crate) fn remove_relative(path: &Path) -> PathBuf {
     let mut prefix: Option<OsString> = None;
     let mut has_root = false;
     let mut stack: Vec<OsString> = Vec::new();
@@ -206,9 +281,16 @@ fn remove_relative(path: &Path) -> PathBuf {
 
 impl MemoryFileSystem {
     pub fn new() -> Self {
+        Self::with_quota(MemoryQuota::default())
+    }
+
+    pub fn with_quota(quota: MemoryQuota) -> Self {
         Self {
             files: Arc::new(DashMap::new()),
+            stored_bytes: Arc::new(Mutex::new(0)),
+            quota,
             pattern_cache: Arc::new(Mutex::new(HashMap::new())),
+            events: event_channel(),
             #[cfg(feature = "fs-test")]
             operations: Arc::new(Mutex::new(Vec::new())),
         }
@@ -217,45 +299,125 @@ impl MemoryFileSystem {
     pub fn with_files(
         files: impl IntoIterator<Item = (impl Into<PathBuf>, impl Into<Vec<u8>>)>
     ) -> Self {
-        let map = DashMap::new();
+        let fs = Self::new();
         for (path, contents) in files {
-            map.insert(path.into(), Bytes::from(contents.into()));
-        }
-        Self {
-            files: Arc::new(map),
-            pattern_cache: Arc::new(Mutex::new(HashMap::new())),
-            #[cfg(feature = "fs-test")]
-            operations: Arc::new(Mutex::new(Vec::new())),
+            fs.store(path.into(), Bytes::from(contents.into()), false)
+                .expect("unlimited filesystems take every file");
         }
+        fs
+    }
+
+    pub fn quota(&self) -> MemoryQuota {
+        self.quota
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        *self.stored_bytes.lock().unwrap()
     }
 
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Adds a file without checking the quota. Intended for seeding fixtures.
     pub fn add_file(
         &self,
         path: impl Into<PathBuf>,
         contents: impl AsRef<[u8]>,
     ) {
-        self.files
-            .insert(path.into(), Bytes::from(contents.as_ref().to_vec()));
+        let path = path.into();
+        let existed = self
+            .store(path.clone(), Bytes::from(contents.as_ref().to_vec()), false)
+            .expect("unchecked stores never exceed the quota");
+        self.notify_write(path, existed);
+    }
+
+    pub fn try_add_file(
+        &self,
+        path: impl Into<PathBuf>,
+        contents: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        let path = path.into();
+        let existed = self.store(path.clone(), Bytes::from(contents.as_ref().to_vec()), true)?;
+        self.notify_write(path, existed);
+        Ok(())
     }
 
     pub fn remove_file(
         &self,
         path: &Path,
     ) -> bool {
-        self.files.remove(path).is_some()
+        let removed = {
+            let mut stored = self.stored_bytes.lock().unwrap();
+            match self.files.remove(path) {
+                Some((_, contents)) => {
+                    *stored -= contents.len();
+                    true
+                },
+                None => false,
+            }
+        };
+        if removed {
+            let _ = self
+                .events
+                .send(FsEvent::new(FsEventKind::Removed, path));
+        }
+        removed
+    }
+
+    /// Stores `contents` at `path`, checked against the quota when `checked`. Returns whether
+    /// a file was replaced.
+    fn store(
+        &self,
+        path: PathBuf,
+        contents: Bytes,
+        checked: bool,
+    ) -> std::result::Result<bool, QuotaExceeded> {
+        let quota = checked.then_some(&self.quota);
+        store(&self.files, &self.stored_bytes, quota, path, contents)
+    }
+
+    fn notify_write(
+        &self,
+        path: PathBuf,
+        existed: bool,
+    ) {
+        let kind = if existed {
+            FsEventKind::Modified
+        } else {
+            FsEventKind::Created
+        };
+        // only fails when nothing is watching
+        let _ = self.events.send(FsEvent::new(kind, path));
     }
 
     pub fn clear(&self) {
-        self.files.clear();
+        for path in self.list_files() {
+            self.remove_file(&path);
+        }
         #[cfg(feature = "fs-test")]
         self.clear_operations();
     }
 
+    /// All file paths, sorted.
     pub fn list_files(&self) -> Vec<PathBuf> {
-        self.files
+        let mut paths: Vec<_> = self
+            .files
             .iter()
             .map(|entry| entry.key().clone())
-            .collect()
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn sorted_entries(&self) -> Vec<(PathBuf, Bytes)> {
+        let mut entries: Vec<_> = self
+            .files
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
     }
 
     pub fn get_file_content(
@@ -290,8 +452,7 @@ impl MemoryFileSystem {
 
     #[cfg(feature = "fs-test")]
     pub fn debug_print_files(&self) {
-        for entry in self.files.iter() {
-            let (path, contents) = (entry.key(), entry.value());
+        for (path, contents) in self.sorted_entries() {
             println!("File: {} ({} bytes)", path.display(), contents.len());
             println!("```\n{}\n```", String::from_utf8_lossy(contents.as_ref()));
         }
@@ -303,8 +464,7 @@ impl MemoryFileSystem {
         &self,
         root_path: impl AsRef<Path>,
     ) -> std::io::Result<()> {
-        for entry in self.files.iter() {
-            let (path, contents) = (entry.key(), entry.value());
+        for (path, contents) in self.sorted_entries() {
             let full_path = root_path.as_ref().join(path);
             if let Some(parent) = full_path.parent()
                 && !parent.exists()
@@ -316,31 +476,33 @@ impl MemoryFileSystem {
         Ok(())
     }
 
+    /// Merges `many` under `root_path` into a filesystem limited by `quota`.
     pub fn merge(
         root_path: impl AsRef<Path>,
         many: Vec<MemoryFileSystem>,
-    ) -> Self {
+        quota: MemoryQuota,
+    ) -> Result<Self> {
         let root_path = root_path.as_ref();
-        let merged = MemoryFileSystem::new();
+        let merged = MemoryFileSystem::with_quota(quota);
         for fs in many {
-            for entry in fs.files.iter() {
-                merged.files.insert(
-                    root_path.join(entry.key()).to_path_buf(),
-                    entry.value().clone(),
-                );
+            for (path, contents) in fs.sorted_entries() {
+                merged.store(root_path.join(path), contents, true)?;
             }
         }
-        merged
+        Ok(merged)
     }
 
+    /// Copies the files of `fs` matching `include` and not `exclude` into a filesystem
+    /// limited by `quota`, relative to `root_path`.
     pub async fn extract_from<Fs: crate::FileSystem + Send + Sync>(
         fs: &Fs,
         root_path: impl AsRef<Path>,
         include: &[impl std::fmt::Display],
         exclude: &[impl std::fmt::Display],
+        quota: MemoryQuota,
     ) -> std::result::Result<Self, Error> {
         let root_path = root_path.as_ref();
-        let memory_fs = MemoryFileSystem::new();
+        let memory_fs = MemoryFileSystem::with_quota(quota);
 
         let include = include
             .iter()
@@ -364,14 +526,69 @@ impl MemoryFileSystem {
                 .strip_prefix(&normalized_root)
                 .unwrap_or(&normalized_file);
             let content = fs.read(&file_path).await?;
-            memory_fs
-                .files
-                .insert(relative_path.to_path_buf(), Bytes::from(content));
+            memory_fs.store(relative_path.to_path_buf(), Bytes::from(content), true)?;
         }
         Ok(memory_fs)
     }
 }
 
+/// Stores `contents` at `path`, checked against `quota` when given, returning whether a file
+/// was replaced. The check, the insert and the running total all happen under the lock of
+/// `stored_bytes`, so concurrent writes cannot each pass the check and overshoot together.
+fn store(
+    files: &DashMap<PathBuf, Bytes>,
+    stored_bytes: &Mutex<usize>,
+    quota: Option<&MemoryQuota>,
+    path: PathBuf,
+    contents: Bytes,
+) -> std::result::Result<bool, QuotaExceeded> {
+    let mut stored = stored_bytes.lock().unwrap();
+    let existing = files
+        .get(&path)
+        .map(|entry| entry.value().len());
+
+    if let Some(quota) = quota {
+        check_quota(quota, files.len(), *stored, existing, contents.len())?;
+    }
+
+    *stored = *stored - existing.unwrap_or(0) + contents.len();
+    Ok(files.insert(path, contents).is_some())
+}
+
+/// Checks that storing `len` bytes keeps `file_count` files of `stored` bytes within `quota`,
+/// accounting for the `existing` contents being replaced.
+fn check_quota(
+    quota: &MemoryQuota,
+    file_count: usize,
+    stored: usize,
+    existing: Option<usize>,
+    len: usize,
+) -> std::result::Result<(), QuotaExceeded> {
+    if let Some(limit) = quota.max_files {
+        let requested = file_count + usize::from(existing.is_none());
+        if requested > limit {
+            return Err(QuotaExceeded {
+                kind: QuotaKind::Files,
+                limit,
+                requested,
+            });
+        }
+    }
+
+    if let Some(limit) = quota.max_bytes {
+        let requested = stored - existing.unwrap_or(0) + len;
+        if requested > limit {
+            return Err(QuotaExceeded {
+                kind: QuotaKind::Bytes,
+                limit,
+                requested,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 impl Default for MemoryFileSystem {
     fn default() -> Self {
         Self::new()
@@ -578,6 +795,8 @@ impl FileSystem for MemoryFileSystem {
         contents: Vec<u8>,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + Sync>> {
         let files = self.files.clone();
+        let stored_bytes = self.stored_bytes.clone();
+        let quota = self.quota;
         let path = remove_relative(path);
 
         #[cfg(feature = "fs-test")]
@@ -585,8 +804,22 @@ impl FileSystem for MemoryFileSystem {
         #[cfg(feature = "fs-test")]
         let size = contents.len();
 
+        let events = self.events.clone();
+
         Box::pin(async move {
-            files.insert(path.clone(), Bytes::from(contents));
+            let replaced = store(
+                &files,
+                &stored_bytes,
+                Some(&quota),
+                path.clone(),
+                Bytes::from(contents),
+            )?;
+            let kind = if replaced {
+                FsEventKind::Modified
+            } else {
+                FsEventKind::Created
+            };
+            let _ = events.send(FsEvent::new(kind, path.clone()));
 
             #[cfg(feature = "fs-test")]
             {
@@ -600,6 +833,35 @@ impl FileSystem for MemoryFileSystem {
             Ok(())
         })
     }
+
+    fn watch(
+        &self,
+        paths: &[PathBuf],
+        include: &[String],
+    ) -> Result<FsEventStream> {
+        let filter = WatchFilter::new(paths, include)?;
+        let rx = self.events.subscribe();
+
+        Ok(Box::pin(futures::stream::unfold(
+            (rx, filter),
+            |(mut rx, filter)| {
+                async move {
+                    loop {
+                        match rx.recv().await {
+                            Ok(event) if filter.matches(&event.path) => {
+                                return Some((Ok(event), (rx, filter)));
+                            },
+                            Ok(_) => {},
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                                tracing::warn!("memory filesystem watcher missed {missed} events");
+                            },
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => return None,
+                        }
+                    }
+                }
+            },
+        )))
+    }
 }
 
 /// ```
@@ -692,6 +954,156 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_list_files_sorted() {
+        let fs = memory! {
+            "z.ks" => "",
+            "a/b.ks" => "",
+            "m.ks" => "",
+            "a/a.ks" => "",
+        };
+
+        assert_eq!(
+            fs.list_files(),
+            vec![
+                PathBuf::from("a/a.ks"),
+                PathBuf::from("a/b.ks"),
+                PathBuf::from("m.ks"),
+                PathBuf::from("z.ks"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize_is_insertion_order_independent() {
+        let forward = memory! { "a.ks" => "1", "b.ks" => "2", "c.ks" => "3" };
+        let backward = memory! { "c.ks" => "3", "b.ks" => "2", "a.ks" => "1" };
+
+        let forward = serde_json::to_string(&forward).unwrap();
+        assert_eq!(forward, serde_json::to_string(&backward).unwrap());
+        assert_eq!(forward, r#"{"a.ks":"1","b.ks":"2","c.ks":"3"}"#);
+    }
+
+    #[test]
+    fn test_file_count_quota() {
+        let fs = MemoryFileSystem::with_quota(MemoryQuota::default().with_max_files(2));
+
+        fs.try_add_file("a.ks", "a").unwrap();
+        fs.try_add_file("b.ks", "b").unwrap();
+        // replacing an existing file does not add to the count
+        fs.try_add_file("b.ks", "bb").unwrap();
+
+        let err = fs.try_add_file("c.ks", "c").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::QuotaExceeded(QuotaExceeded {
+                kind: QuotaKind::Files,
+                limit: 2,
+                requested: 3,
+            })
+        ));
+        assert!(!fs.exists_sync("c.ks".as_ref()));
+    }
+
+    #[tokio::test]
+    async fn test_byte_quota() {
+        let fs = MemoryFileSystem::with_quota(MemoryQuota::default().with_max_bytes(8));
+
+        fs.write("a.ks".as_ref(), b"12345".to_vec())
+            .await
+            .unwrap();
+        // replacing accounts for the bytes being freed
+        fs.write("a.ks".as_ref(), b"1234".to_vec())
+            .await
+            .unwrap();
+
+        let err = fs
+            .write("b.ks".as_ref(), b"12345".to_vec())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::QuotaExceeded(QuotaExceeded {
+                kind: QuotaKind::Bytes,
+                limit: 8,
+                requested: 9,
+            })
+        ));
+        assert_eq!(fs.total_bytes(), 4);
+        assert_eq!(fs.file_count(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_writes_stay_within_quota() {
+        let fs = MemoryFileSystem::with_quota(MemoryQuota::default().with_max_bytes(40));
+
+        let writes = (0..32).map(|i| {
+            let fs = fs.clone();
+            tokio::spawn(async move {
+                fs.write(format!("{i}.ks").as_ref(), b"12345".to_vec())
+                    .await
+            })
+        });
+        let mut written = 0;
+        for write in writes {
+            if write.await.unwrap().is_ok() {
+                written += 1;
+            }
+        }
+
+        assert_eq!(written, 8);
+        assert_eq!(fs.total_bytes(), 40);
+        assert_eq!(fs.file_count(), 8);
+    }
+
+    #[test]
+    fn test_removal_frees_quota() {
+        let fs = MemoryFileSystem::with_quota(MemoryQuota::default().with_max_bytes(4));
+        fs.try_add_file("a.ks", "1234").unwrap();
+        assert!(fs.try_add_file("b.ks", "1").is_err());
+
+        assert!(fs.remove_file("a.ks".as_ref()));
+        assert_eq!(fs.total_bytes(), 0);
+        fs.try_add_file("b.ks", "1234").unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_merge_and_extract_respect_quota() {
+        let quota = MemoryQuota::default().with_max_files(2);
+
+        let merged = MemoryFileSystem::merge(
+            "root",
+            vec![
+                memory! { "a.ks" => "a" },
+                memory! { "b.ks" => "b", "c.ks" => "c" },
+            ],
+            quota,
+        );
+        assert!(matches!(
+            merged,
+            Err(Error::QuotaExceeded(QuotaExceeded {
+                kind: QuotaKind::Files,
+                ..
+            }))
+        ));
+
+        let source = memory! { "pkg/a.ks" => "a", "pkg/b.ks" => "b", "pkg/c.ks" => "c" };
+        let extracted = MemoryFileSystem::extract_from(
+            &source,
+            "pkg",
+            &["/**/*.ks"],
+            &Vec::<String>::new(),
+            quota,
+        )
+        .await;
+        assert!(matches!(extracted, Err(Error::QuotaExceeded(..))));
+
+        let merged =
+            MemoryFileSystem::merge("root", vec![memory! { "a.ks" => "a" }], quota).unwrap();
+        assert!(merged.exists_sync("root/a.ks".as_ref()));
+        assert_eq!(merged.total_bytes(), 1);
+    }
+
     #[tokio::test]
     async fn test_extract_from_with_dot_slash_prefix() {
         // Regression test: extract_from should normalize paths so that
@@ -707,6 +1119,7 @@ mod test {
             "./pkg-1",
             &["/**/*.ks", "/schema.toml"],
             &Vec::<String>::new(),
+            MemoryQuota::unlimited(),
         )
         .await
         .unwrap();
@@ -748,6 +1161,7 @@ mod test {
             "pkg-1",
             &["/**/*.ks", "/schema.toml"],
             &Vec::<String>::new(),
+            MemoryQuota::unlimited(),
         )
         .await
         .unwrap();
@@ -776,6 +1190,7 @@ mod test {
             "./a/b/c",
             &["/**/*.txt"],
             &Vec::<String>::new(),
+            MemoryQuota::unlimited(),
         )
         .await
         .unwrap();
@@ -784,4 +1199,39 @@ mod test {
         assert!(extracted.exists_sync("sub/nested.txt".as_ref()));
         assert!(!extracted.exists_sync("a/b/c/file.txt".as_ref()));
     }
+
+    #[tokio::test]
+    async fn test_watch_emits_write_events() {
+        use futures::StreamExt;
+
+        let fs = MemoryFileSystem::new();
+        let mut events = fs
+            .watch(&[PathBuf::from("schema")], &["**/*.ks".to_string()])
+            .unwrap();
+
+        fs.write("schema/a.ks".as_ref(), b"one".to_vec())
+            .await
+            .unwrap();
+        fs.write("other/b.ks".as_ref(), b"ignored".to_vec())
+            .await
+            .unwrap();
+        fs.write("schema/notes.md".as_ref(), b"ignored".to_vec())
+            .await
+            .unwrap();
+        fs.add_file("schema/a.ks", "two");
+        fs.remove_file("schema/a.ks".as_ref());
+
+        let mut seen = vec![];
+        for _ in 0..3 {
+            seen.push(events.next().await.unwrap().unwrap());
+        }
+        assert_eq!(
+            seen,
+            vec![
+                FsEvent::new(FsEventKind::Created, "schema/a.ks"),
+                FsEvent::new(FsEventKind::Modified, "schema/a.ks"),
+                FsEvent::new(FsEventKind::Removed, "schema/a.ks"),
+            ]
+        );
+    }
 }
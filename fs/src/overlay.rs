@@ -0,0 +1,230 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+};
+
+use crate::{FileSystem, Result, memory::MemoryFileSystem};
+
+/// A writable [`MemoryFileSystem`] layered over a read-only [`FileSystem`].
+///
+/// Reads resolve top-down: a path present in the upper layer shadows the lower one. Writes
+/// only ever reach the upper layer, so compiling against local edits leaves the lower
+/// filesystem (usually the on-disk workspace) untouched.
+#[derive(Clone)]
+pub struct OverlayFileSystem {
+    upper: MemoryFileSystem,
+    lower: Arc<dyn FileSystem>,
+}
+
+impl OverlayFileSystem {
+    pub fn new(lower: Arc<dyn FileSystem>) -> Self {
+        Self::with_upper(MemoryFileSystem::new(), lower)
+    }
+
+    pub fn with_upper(
+        upper: MemoryFileSystem,
+        lower: Arc<dyn FileSystem>,
+    ) -> Self {
+        Self { upper, lower }
+    }
+
+    /// The writable layer, holding every file written through the overlay.
+    pub fn upper(&self) -> &MemoryFileSystem {
+        &self.upper
+    }
+
+    pub fn lower(&self) -> &Arc<dyn FileSystem> {
+        &self.lower
+    }
+
+    fn layer_for(
+        &self,
+        path: &Path,
+    ) -> &dyn FileSystem {
+        if self.upper.exists_sync(path) {
+            &self.upper
+        } else {
+            self.lower.as_ref()
+        }
+    }
+}
+
+impl FileSystem for OverlayFileSystem {
+    fn exists_sync(
+        &self,
+        path: &Path,
+    ) -> bool {
+        self.upper.exists_sync(path) || self.lower.exists_sync(path)
+    }
+
+    fn find_glob(
+        &self,
+        include: &[String],
+        exclude: &[String],
+    ) -> Result<Vec<PathBuf>> {
+        // a physical lower layer may find a file by its absolute path which the upper layer
+        // holds relative to the working directory, so both are keyed by the same form
+        let cwd = std::env::current_dir().ok();
+        let key = |path: &Path| {
+            let path = crate::memory::remove_relative(path);
+            match &cwd {
+                Some(cwd) => {
+                    path.strip_prefix(cwd)
+                        .map(Path::to_path_buf)
+                        .unwrap_or(path)
+                },
+                None => path,
+            }
+        };
+
+        let mut found = BTreeMap::new();
+        for path in self.lower.find_glob(include, exclude)? {
+            found.insert(key(&path), path);
+        }
+        for path in self.upper.find_glob(include, exclude)? {
+            found.insert(key(&path), path);
+        }
+        Ok(found.into_values().collect())
+    }
+
+    fn read(
+        &self,
+        path: &Path,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + Sync>> {
+        self.layer_for(path).read(path)
+    }
+
+    fn read_to_string(
+        &self,
+        path: &Path,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + Sync>> {
+        self.layer_for(path).read_to_string(path)
+    }
+
+    fn read_to_string_sync(
+        &self,
+        path: &Path,
+    ) -> Result<String> {
+        self.layer_for(path)
+            .read_to_string_sync(path)
+    }
+
+    fn write(
+        &self,
+        path: &Path,
+        contents: Vec<u8>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + Sync>> {
+        self.upper.write(path, contents)
+    }
+
+    /// Changes from both layers, so edits to files shadowed by the upper layer are still
+    /// reported.
+    fn watch(
+        &self,
+        paths: &[PathBuf],
+        include: &[String],
+    ) -> Result<crate::watch::FsEventStream> {
+        Ok(Box::pin(futures::stream::select(
+            self.upper.watch(paths, include)?,
+            self.lower.watch(paths, include)?,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn overlay() -> (OverlayFileSystem, MemoryFileSystem) {
+        let lower = crate::memory! {
+            "lib.ks" => "lower lib",
+            "schema/a.ks" => "lower a",
+        };
+        (OverlayFileSystem::new(Arc::new(lower.clone())), lower)
+    }
+
+    #[tokio::test]
+    async fn reads_fall_through_to_lower() {
+        let (fs, _) = overlay();
+
+        assert!(fs.exists_sync("schema/a.ks".as_ref()));
+        assert_eq!(
+            fs.read_to_string("schema/a.ks".as_ref())
+                .await
+                .unwrap(),
+            "lower a"
+        );
+        assert!(!fs.exists_sync("missing.ks".as_ref()));
+        assert!(fs.read("missing.ks".as_ref()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn writes_shadow_lower_without_mutating_it() {
+        let (fs, lower) = overlay();
+
+        fs.write("lib.ks".as_ref(), b"edited lib".to_vec())
+            .await
+            .unwrap();
+        fs.write("schema/b.ks".as_ref(), b"new b".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fs.read_to_string_sync("lib.ks".as_ref())
+                .unwrap(),
+            "edited lib"
+        );
+        assert_eq!(
+            fs.read("schema/b.ks".as_ref())
+                .await
+                .unwrap(),
+            b"new b"
+        );
+
+        assert_eq!(
+            lower
+                .read_to_string_sync("lib.ks".as_ref())
+                .unwrap(),
+            "lower lib"
+        );
+        assert!(!lower.exists_sync("schema/b.ks".as_ref()));
+        assert_eq!(fs.upper().file_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn glob_merges_layers_once() {
+        let (fs, _) = overlay();
+        fs.write("./schema/a.ks".as_ref(), b"upper a".to_vec())
+            .await
+            .unwrap();
+        fs.write("schema/c.ks".as_ref(), b"upper c".to_vec())
+            .await
+            .unwrap();
+
+        let found = fs
+            .find_glob(&["schema/*.ks".to_string()], &[])
+            .unwrap();
+        assert_eq!(
+            found,
+            vec![PathBuf::from("schema/a.ks"), PathBuf::from("schema/c.ks")]
+        );
+    }
+
+    #[tokio::test]
+    async fn glob_keys_absolute_and_relative_paths_once() {
+        let cwd = std::env::current_dir().unwrap();
+        let fs = OverlayFileSystem::new(Arc::new(crate::physical::Physical));
+        fs.write(&cwd.join("src/overlay.rs"), b"edited".to_vec())
+            .await
+            .unwrap();
+
+        // the physical layer finds the file relative to the working directory, the memory
+        // layer by the absolute path it was written to
+        let found = fs
+            .find_glob(&["**/overlay.rs".to_string()], &[])
+            .unwrap();
+        assert_eq!(found, vec![cwd.join("src/overlay.rs")]);
+    }
+}
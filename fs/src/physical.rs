@@ -1,5 +1,34 @@
 use std::{path::Path, pin::Pin};
 
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::fs;
+
+/// There is no filesystem in the browser, where `std` reports each operation as
+/// unsupported.
+#[cfg(target_arch = "wasm32")]
+mod fs {
+    use std::{io, path::Path};
+
+    pub async fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    pub async fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    pub async fn write(
+        path: impl AsRef<Path>,
+        contents: Vec<u8>,
+    ) -> io::Result<()> {
+        std::fs::write(path, contents)
+    }
+
+    pub async fn create_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+}
+
 pub struct Physical;
 
 impl crate::FileSystem for Physical {
@@ -23,7 +52,7 @@ impl crate::FileSystem for Physical {
         path: &Path,
     ) -> Pin<Box<dyn Future<Output = crate::Result<Vec<u8>>> + Send + Sync>> {
         let path = path.to_path_buf();
-        Box::pin(async move { Ok(tokio::fs::read(path).await?) })
+        Box::pin(async move { Ok(fs::read(path).await?) })
     }
 
     fn read_to_string(
@@ -31,7 +60,7 @@ impl crate::FileSystem for Physical {
         path: &Path,
     ) -> Pin<Box<dyn Future<Output = crate::Result<String>> + Send + Sync>> {
         let path = path.to_path_buf();
-        Box::pin(async move { Ok(tokio::fs::read_to_string(path).await?) })
+        Box::pin(async move { Ok(fs::read_to_string(path).await?) })
     }
 
     fn read_to_string_sync(
@@ -47,6 +76,22 @@ impl crate::FileSystem for Physical {
         contents: Vec<u8>,
     ) -> Pin<Box<dyn Future<Output = crate::Result<()>> + Send + Sync>> {
         let path = path.to_path_buf();
-        Box::pin(async move { Ok(tokio::fs::write(path, contents).await?) })
+        Box::pin(async move {
+            // like the memory filesystem, files can be written to directories not created yet
+            if let Some(parent) = path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                fs::create_dir_all(parent).await?;
+            }
+            Ok(fs::write(path, contents).await?)
+        })
+    }
+
+    fn watch(
+        &self,
+        paths: &[std::path::PathBuf],
+        include: &[String],
+    ) -> crate::Result<crate::watch::FsEventStream> {
+        crate::watch::watch_physical(paths, include)
     }
 }
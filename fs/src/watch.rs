@@ -0,0 +1,233 @@
+//! Change notifications for [`FileSystem::watch`](crate::FileSystem::watch).
+//!
+//! Physical filesystems are watched through `notify`, while a
+//! [`MemoryFileSystem`](crate::memory::MemoryFileSystem) emits events synthetically as it is
+//! written, so watch-mode compilation and the language server can consume either the same way.
+
+use std::{
+    path::{Path, PathBuf},
+    pin::Pin,
+};
+
+use futures::Stream;
+
+use crate::{Result, memory::remove_relative};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FsEventKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FsEvent {
+    pub kind: FsEventKind,
+    pub path: PathBuf,
+}
+
+impl FsEvent {
+    pub fn new(
+        kind: FsEventKind,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            kind,
+            path: path.into(),
+        }
+    }
+}
+
+/// Events for as long as the stream is held. Dropping it stops watching.
+pub type FsEventStream = Pin<Box<dyn Stream<Item = Result<FsEvent>> + Send>>;
+
+/// Selects the events a watcher reports: paths below one of `roots` which match one of the
+/// `include` globs, or any glob when none are given.
+#[derive(Clone, Debug)]
+pub(crate) struct WatchFilter {
+    roots: Vec<PathBuf>,
+    include: Vec<glob::Pattern>,
+}
+
+impl WatchFilter {
+    pub(crate) fn new(
+        roots: &[PathBuf],
+        include: &[String],
+    ) -> Result<Self> {
+        Ok(Self {
+            roots: roots
+                .iter()
+                .map(|root| remove_relative(root))
+                .collect(),
+            include: include
+                .iter()
+                .map(|pattern| {
+                    glob::Pattern::new(
+                        &remove_relative(Path::new(pattern))
+                            .display()
+                            .to_string(),
+                    )
+                })
+                .collect::<std::result::Result<_, _>>()?,
+        })
+    }
+
+    pub(crate) fn matches(
+        &self,
+        path: &Path,
+    ) -> bool {
+        let path = remove_relative(path);
+
+        let below_root = self
+            .roots
+            .iter()
+            .any(|root| root.as_os_str() == "." || path.starts_with(root));
+        let included = self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches_path(&path));
+
+        below_root && included
+    }
+}
+
+/// Watches `roots` recursively on disk. Reported paths are rebased onto the root they were
+/// found under, so they have the same form as the paths the caller asked to watch.
+pub(crate) fn watch_physical(
+    roots: &[PathBuf],
+    include: &[String],
+) -> Result<FsEventStream> {
+    use notify::Watcher;
+
+    let filter = WatchFilter::new(roots, include)?;
+    let rebase = rebase_roots(roots)?;
+    let watched = rebase
+        .iter()
+        .map(|(canonical, _)| canonical.clone())
+        .collect::<Vec<_>>();
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                let _ = tx.send(Err(err.into()));
+                return;
+            },
+        };
+
+        for event in physical_events(event) {
+            let path = rebase
+                .iter()
+                .find_map(|(canonical, root)| {
+                    event
+                        .path
+                        .strip_prefix(canonical)
+                        .ok()
+                        .map(|relative| root.join(relative))
+                })
+                .unwrap_or(event.path);
+
+            if filter.matches(&path) {
+                let _ = tx.send(Ok(FsEvent::new(event.kind, path)));
+            }
+        }
+    })?;
+
+    for path in &watched {
+        watcher.watch(path, notify::RecursiveMode::Recursive)?;
+    }
+
+    Ok(Box::pin(futures::stream::unfold(
+        (rx, watcher),
+        |(mut rx, watcher)| {
+            async move {
+                rx.recv()
+                    .await
+                    .map(|event| (event, (rx, watcher)))
+            }
+        },
+    )))
+}
+
+/// Pairs each root with its canonical form, which is how `notify` reports paths below it.
+fn rebase_roots(roots: &[PathBuf]) -> Result<Vec<(PathBuf, PathBuf)>> {
+    roots
+        .iter()
+        .map(|root| Ok((std::fs::canonicalize(root)?, root.clone())))
+        .collect()
+}
+
+/// The changes a `notify` event describes, ignoring accesses and metadata-only updates.
+fn physical_events(event: notify::Event) -> Vec<FsEvent> {
+    use notify::event::{EventKind, ModifyKind, RenameMode};
+
+    let kind = match event.kind {
+        EventKind::Create(_) => FsEventKind::Created,
+        EventKind::Remove(_) => FsEventKind::Removed,
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => FsEventKind::Removed,
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => FsEventKind::Created,
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            let mut paths = event.paths.into_iter();
+            return paths
+                .next()
+                .map(|from| FsEvent::new(FsEventKind::Removed, from))
+                .into_iter()
+                .chain(paths.map(|to| FsEvent::new(FsEventKind::Created, to)))
+                .collect();
+        },
+        EventKind::Modify(ModifyKind::Metadata(_)) => return vec![],
+        EventKind::Modify(_) => FsEventKind::Modified,
+        EventKind::Access(_) | EventKind::Any | EventKind::Other => return vec![],
+    };
+
+    event
+        .paths
+        .into_iter()
+        .map(|path| FsEvent::new(kind, path))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn filter_matches_roots_and_globs() {
+        let filter = WatchFilter::new(
+            &[PathBuf::from("./schema")],
+            &["schema/**/*.ks".to_string()],
+        )
+        .unwrap();
+
+        assert!(filter.matches(Path::new("schema/a.ks")));
+        assert!(filter.matches(Path::new("./schema/nested/b.ks")));
+        assert!(!filter.matches(Path::new("schema/readme.md")));
+        assert!(!filter.matches(Path::new("other/a.ks")));
+
+        let everything = WatchFilter::new(&[PathBuf::from(".")], &[]).unwrap();
+        assert!(everything.matches(Path::new("any/file.txt")));
+    }
+
+    #[tokio::test]
+    async fn physical_watch_reports_changes() {
+        use crate::FileSystem;
+        use futures::StreamExt;
+
+        let root = tempfile::tempdir().unwrap();
+        let mut events = crate::physical::Physical
+            .watch(&[root.path().to_path_buf()], &["**/*.ks".to_string()])
+            .unwrap();
+
+        std::fs::write(root.path().join("notes.md"), "ignored").unwrap();
+        std::fs::write(root.path().join("lib.ks"), "namespace foo;").unwrap();
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(10), events.next())
+            .await
+            .expect("no event within timeout")
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.path, root.path().join("lib.ks"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Differences between the declarations of two versions of a package.
+
+use std::collections::BTreeSet;
+
+pub use kintsu_parser::declare::diff::Change;
+use kintsu_parser::declare::{DeclarationBundle, DeclarationVersion, diff};
+
+use crate::{Bump, Version};
+
+/// Qualified names of the types and constants a package declares, e.g. `abc::v1::User`.
+/// Declarations of its dependencies are not included.
+pub fn declared_items(declarations: &DeclarationBundle) -> BTreeSet<String> {
+    diff::declared_items(&DeclarationVersion::V1(declarations.clone()))
+}
+
+/// Items `previous` declares which `next` does not, in name order.
+pub fn removed_items(
+    previous: &DeclarationBundle,
+    next: &DeclarationBundle,
+) -> Vec<String> {
+    diff::removed_items(
+        &DeclarationVersion::V1(previous.clone()),
+        &DeclarationVersion::V1(next.clone()),
+    )
+}
+
+/// Items `next` declares which `previous` does not, in name order.
+pub fn added_items(
+    previous: &DeclarationBundle,
+    next: &DeclarationBundle,
+) -> Vec<String> {
+    removed_items(next, previous)
+}
+
+/// Every difference between the items `previous` and `next` declare, in item order.
+pub fn changes(
+    previous: &DeclarationBundle,
+    next: &DeclarationBundle,
+) -> Vec<Change> {
+    diff::changes(
+        &DeclarationVersion::V1(previous.clone()),
+        &DeclarationVersion::V1(next.clone()),
+    )
+}
+
+/// The [`changes`] from `previous` to `next` which may break dependents.
+pub fn breaking_changes(
+    previous: &DeclarationBundle,
+    next: &DeclarationBundle,
+) -> Vec<Change> {
+    diff::breaking_changes(
+        &DeclarationVersion::V1(previous.clone()),
+        &DeclarationVersion::V1(next.clone()),
+    )
+}
+
+/// The smallest version bump after `version`, the version of `previous`, for a release
+/// declaring `next`.
+pub fn required_bump(
+    version: &Version,
+    previous: &DeclarationBundle,
+    next: &DeclarationBundle,
+) -> Bump {
+    diff::required_bump(
+        version,
+        &DeclarationVersion::V1(previous.clone()),
+        &DeclarationVersion::V1(next.clone()),
+    )
+}
+
+/// Whether going from `previous` to `next` is a major release under semver, where for `0.x`
+/// versions a minor bump counts as major.
+pub fn is_breaking_release(
+    previous: &Version,
+    next: &Version,
+) -> bool {
+    diff::is_breaking_release(previous, next)
+}
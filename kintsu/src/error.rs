@@ -0,0 +1,49 @@
+use kintsu_errors::CompilerError;
+use kintsu_events::Diagnostic;
+
+/// Why an operation failed, as the diagnostics reported for it, root causes first.
+#[derive(Debug, Clone)]
+pub struct Error {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Error {
+    pub(crate) fn from_compiler(err: impl Into<CompilerError>) -> Self {
+        let err = err.into();
+        Self {
+            diagnostics: err
+                .flatten()
+                .into_iter()
+                .map(|err| Diagnostic::from(err.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        let mut diagnostics = self.diagnostics.iter();
+        if let Some(first) = diagnostics.next() {
+            write!(f, "{first}")?;
+        }
+        for diagnostic in diagnostics {
+            write!(f, "\n{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
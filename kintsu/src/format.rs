@@ -0,0 +1,40 @@
+//! Formatting schema source the way `kintsu fmt` does.
+
+use std::path::Path;
+
+pub use kintsu_parser::fmt::FormatConfig;
+
+use crate::{Error, Result};
+
+/// Formats the schema source of the file or buffer `name`, keeping its comments and blank
+/// lines.
+pub fn format(
+    name: impl AsRef<Path>,
+    source: &str,
+    config: &FormatConfig,
+) -> Result<String> {
+    kintsu_parser::fmt::format_source(name, source, config).map_err(Error::from_compiler)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn formats_source() {
+        let formatted = format(
+            "lib.ks",
+            "namespace pkg;\nstruct   User {id:i64};",
+            &FormatConfig::default(),
+        )
+        .unwrap();
+        assert!(formatted.contains("struct User {"));
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let err = format("lib.ks", "struct {", &FormatConfig::default()).unwrap_err();
+        assert!(!err.diagnostics().is_empty());
+        assert_eq!(err.diagnostics()[0].source_name.as_deref(), Some("lib.ks"));
+    }
+}
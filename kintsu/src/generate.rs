@@ -0,0 +1,72 @@
+//! Generating GraphQL SDL and SQL DDL from declarations, in memory.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+pub use kintsu_core::generate::{EnumStyle, GraphqlConfig, SqlConfig, SqlDialect};
+use kintsu_core::generate::{
+    GenOpts, files::MemCollector, graphql::GraphqlGenerator, sql::SqlGenerator,
+};
+
+use crate::{Error, Result, declare::TypeRegistryDeclaration};
+
+/// The files a generator wrote, by their path relative to the output directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Generated {
+    pub files: BTreeMap<PathBuf, String>,
+    /// Each construct the target could not express, as `namespace::item: reason`
+    pub skipped: Vec<String>,
+}
+
+impl Generated {
+    fn collect(
+        collector: &MemCollector,
+        skipped: Vec<impl ToString>,
+    ) -> Self {
+        Self {
+            files: collector
+                .files()
+                .iter()
+                .map(|(path, data)| (path.clone(), String::from_utf8_lossy(data).into_owned()))
+                .collect(),
+            skipped: skipped
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+fn opts<Ext: kintsu_core::generate::ConfigExt>(opts: Ext) -> GenOpts<Ext> {
+    GenOpts {
+        output_dir: PathBuf::new(),
+        opts,
+        mem: true,
+    }
+}
+
+/// The SDL of each namespace of `declarations`, one file per namespace, with the root
+/// operation types in `_schema.graphql`.
+pub fn graphql(
+    declarations: &TypeRegistryDeclaration,
+    config: GraphqlConfig,
+) -> Result<Generated> {
+    let collector = MemCollector::new();
+    let generator = GraphqlGenerator::new();
+    generator
+        .generate(declarations, &opts(config), Some(collector.mem_flush()))
+        .map_err(Error::from_compiler)?;
+    Ok(Generated::collect(&collector, generator.unsupported()))
+}
+
+/// The DDL of the tables `declarations` declares, one file per namespace.
+pub fn sql(
+    declarations: &TypeRegistryDeclaration,
+    config: SqlConfig,
+) -> Result<Generated> {
+    let collector = MemCollector::new();
+    let generator = SqlGenerator::new();
+    generator
+        .generate(declarations, &opts(config), Some(collector.mem_flush()))
+        .map_err(Error::from_compiler)?;
+    Ok(Generated::collect(&collector, generator.unsupported()))
+}
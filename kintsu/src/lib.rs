@@ -0,0 +1,186 @@
+//! Compiling kintsu schemas from build tools, editors and other hosts.
+//!
+//! This crate is the stable interface to the compiler and follows semver: items are only
+//! removed or changed in a major release. The `kintsu-*` crates it is built on are
+//! implementation details, whose items may change in any release.
+//!
+//! ```no_run
+//! # async fn build() -> kintsu::Result<()> {
+//! let package = kintsu::compile("./schemas/shop").await?;
+//! for diagnostic in package.diagnostics().await? {
+//!     eprintln!("{diagnostic}");
+//! }
+//!
+//! let declarations = package.declarations().await?;
+//! let sdl = kintsu::generate::graphql(&declarations.root, Default::default())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{path::Path, sync::Arc};
+
+use kintsu_parser::ctx::{CompileCtx, CompileOutcome};
+
+pub mod diff;
+mod error;
+pub mod format;
+pub mod generate;
+
+pub use error::{Error, Result};
+pub use kintsu_errors::{ErrorCode, Severity, Span};
+pub use kintsu_events::{Diagnostic, DiagnosticLabel};
+pub use kintsu_fs::{FileSystem, memory::MemoryFileSystem};
+pub use kintsu_manifests::version::{Bump, Version};
+
+pub mod declare {
+    //! The declarations a package emits, as serialized for the registry and read by
+    //! generators.
+    pub use kintsu_core::declare::*;
+}
+
+/// A compiled package and its resolved dependencies.
+pub struct Package {
+    outcome: CompileOutcome,
+}
+
+/// Compiles and lints the package in `dir`, resolving its dependencies. The lockfile is
+/// read, but never written.
+pub async fn compile(dir: impl AsRef<Path>) -> Result<Package> {
+    compile_in(Arc::new(kintsu_fs::physical::Physical), dir).await
+}
+
+/// Compiles the package in `dir` of `fs`, e.g. a [`MemoryFileSystem`] holding the unsaved
+/// buffers of an editor.
+pub async fn compile_in(
+    fs: Arc<dyn FileSystem>,
+    dir: impl AsRef<Path>,
+) -> Result<Package> {
+    let ctx = CompileCtx::with_fs(fs, dir)
+        .await
+        .map_err(Error::from_compiler)?;
+    let outcome = ctx
+        .into_outcome()
+        .await
+        .map_err(Error::from_compiler)?;
+    Ok(Package { outcome })
+}
+
+impl Package {
+    pub fn name(&self) -> &str {
+        &self.outcome.ctx.root.package.package().name
+    }
+
+    pub fn version(&self) -> &Version {
+        &self
+            .outcome
+            .ctx
+            .root
+            .package
+            .package()
+            .version
+    }
+
+    /// The declarations of the package and its dependencies.
+    pub async fn declarations(&self) -> Result<declare::DeclarationBundle> {
+        let declare::DeclarationVersion::V1(bundle) = self
+            .outcome
+            .ctx
+            .emit_declarations()
+            .await
+            .map_err(Error::from_compiler)?;
+        Ok(bundle)
+    }
+
+    /// The declarations of the package, narrowed to the surface its manifest's `profile`
+    /// selects.
+    pub async fn profile_declarations(
+        &self,
+        profile: &str,
+    ) -> Result<declare::DeclarationBundle> {
+        let declare::DeclarationVersion::V1(bundle) = self
+            .outcome
+            .ctx
+            .emit_profile_declarations(profile)
+            .await
+            .map_err(Error::from_compiler)?;
+        Ok(bundle)
+    }
+
+    /// The lint violations of the package below the error level, with the severity their
+    /// rule is configured at. Violations at the error level fail [`compile`].
+    pub async fn diagnostics(&self) -> Result<Vec<Diagnostic>> {
+        Ok(self
+            .outcome
+            .warnings
+            .sorted()
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
+    /// How much of each namespace of the package is documented.
+    pub async fn doc_coverage(&self) -> Result<declare::DocCoverage> {
+        self.outcome
+            .ctx
+            .doc_coverage()
+            .await
+            .map_err(Error::from_compiler)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn shop(lib: &str) -> Arc<dyn FileSystem> {
+        let fs = MemoryFileSystem::new();
+        fs.add_file(
+            "shop/schema.toml",
+            "version = \"v1\"\n[package]\nname = \"shop\"\nversion = \"1.2.0\"\n",
+        );
+        fs.add_file("shop/schema/lib.ks", lib);
+        Arc::new(fs)
+    }
+
+    #[tokio::test]
+    async fn compiles_and_generates() {
+        let fs = shop("namespace shop;\nnamespace users { struct User { id: i64 }; };");
+        let package = compile_in(fs, "shop").await.unwrap();
+        assert_eq!(package.name(), "shop");
+        assert_eq!(package.version(), &Version::new(1, 2, 0));
+
+        let declarations = package.declarations().await.unwrap();
+        assert_eq!(
+            diff::declared_items(&declarations),
+            ["users::User".to_string()].into()
+        );
+
+        let coverage = package.doc_coverage().await.unwrap();
+        assert_eq!(
+            coverage.namespaces["users"].undocumented,
+            ["User", "User.id"]
+        );
+
+        let generated = generate::graphql(&declarations.root, Default::default()).unwrap();
+        assert!(
+            generated
+                .files
+                .values()
+                .any(|sdl| sdl.contains("User"))
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_errors_as_diagnostics() {
+        let fs = shop("namespace shop;\nnamespace users { struct User { id: Missing }; };");
+        let err = match compile_in(fs, "shop").await {
+            Ok(package) => package.declarations().await.unwrap_err(),
+            Err(err) => err,
+        };
+        assert!(
+            err.diagnostics()
+                .iter()
+                .any(|diagnostic| diagnostic.severity == Severity::Error)
+        );
+    }
+}
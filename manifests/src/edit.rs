@@ -0,0 +1,471 @@
+//! Editing manifests in place.
+//!
+//! [`ManifestEditor`] changes dependencies and package fields of a `schema.toml` while
+//! keeping its comments, ordering and formatting, so `kintsu add` and registry tooling can
+//! update a manifest someone maintains by hand. Edits are checked by parsing the edited
+//! manifest before it is written.
+//!
+//! ```
+//! use kintsu_manifests::{edit::ManifestEditor, package::Dependency, version::parse_version_req};
+//!
+//! let mut editor = ManifestEditor::parse(
+//!     "version = \"v1\"\n\n[package]\nname = \"shop\"\nversion = \"0.1.0\"\n",
+//! )
+//! .unwrap();
+//! let money = Dependency::remote(parse_version_req("^1.4").unwrap().into());
+//! editor.set_dependency("money", &money).unwrap();
+//! assert!(editor.to_string().ends_with("[dependencies]\nmoney = { version = \"^1.4\" }\n"));
+//! ```
+
+use std::{fmt, path::Path};
+
+use toml_edit::{DocumentMut, Item, Table, TableLike, Value};
+
+use crate::{
+    config::NewForNamed,
+    package::{Dependency, PackageManifests},
+    version::{VersionReqSerde, VersionSerde},
+    workspace::WorkspaceManifests,
+};
+
+/// The registry dependencies are fetched from unless they name another, left implicit in
+/// edited manifests.
+const DEFAULT_REGISTRY: &str = "kintsu-public";
+
+/// A manifest being edited, keeping the formatting of the source it was parsed from.
+#[derive(Debug, Clone)]
+pub struct ManifestEditor {
+    doc: DocumentMut,
+}
+
+impl ManifestEditor {
+    pub fn parse(source: &str) -> crate::Result<Self> {
+        Ok(Self {
+            doc: source.parse()?,
+        })
+    }
+
+    /// Reads the manifest in `root_dir`.
+    pub fn read(
+        fs: &dyn kintsu_fs::FileSystem,
+        root_dir: impl AsRef<Path>,
+    ) -> crate::Result<Self> {
+        let path = PackageManifests::path(root_dir);
+        let source = fs.read_to_string_sync(&path)?;
+        Self::parse(&source).map_err(|err| err.with_source(&path))
+    }
+
+    /// Checks the edited manifest and writes it to `root_dir`.
+    pub async fn write(
+        &self,
+        fs: &dyn kintsu_fs::FileSystem,
+        root_dir: impl AsRef<Path>,
+    ) -> crate::Result<()> {
+        let path = PackageManifests::path(root_dir);
+        self.check()
+            .map_err(|err| err.with_source(&path))?;
+        fs.write(&path, self.doc.to_string().into_bytes())
+            .await?;
+        Ok(())
+    }
+
+    /// Whether this is a workspace manifest, whose dependencies are under
+    /// `[workspace.dependencies]`.
+    pub fn is_workspace(&self) -> bool {
+        self.doc.contains_key("workspace")
+    }
+
+    /// The edited package manifest, validated.
+    pub fn manifest(&self) -> crate::Result<PackageManifests> {
+        use validator::Validate;
+
+        let manifest: PackageManifests = toml::from_str(&self.doc.to_string())?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Fails when the edited manifest is not a valid package or workspace manifest.
+    pub fn check(&self) -> crate::Result<()> {
+        use validator::Validate;
+
+        if self.is_workspace() {
+            toml::from_str::<WorkspaceManifests>(&self.doc.to_string())?.validate()?;
+        } else {
+            self.manifest()?;
+        }
+        Ok(())
+    }
+
+    /// The table holding the dependencies, created when `create` is set and it is missing.
+    fn dependencies(
+        &mut self,
+        create: bool,
+    ) -> Option<&mut dyn TableLike> {
+        let parent = if self.is_workspace() {
+            self.doc["workspace"].as_table_mut()?
+        } else {
+            self.doc.as_table_mut()
+        };
+        if create {
+            parent
+                .entry("dependencies")
+                .or_insert_with(|| Item::Table(Table::new()));
+        }
+        parent
+            .get_mut("dependencies")?
+            .as_table_like_mut()
+    }
+
+    /// Whether the manifest declares the dependency `name`.
+    pub fn has_dependency(
+        &self,
+        name: &str,
+    ) -> bool {
+        let dependencies = if self.is_workspace() {
+            self.doc
+                .get("workspace")
+                .and_then(|workspace| workspace.get("dependencies"))
+        } else {
+            self.doc.get("dependencies")
+        };
+        dependencies.is_some_and(|dependencies| dependencies.get(name).is_some())
+    }
+
+    /// Declares the dependency `name` as `dep`, replacing any declaration of it in place.
+    /// A dependency written as a `[dependencies.<name>]` table stays one.
+    pub fn set_dependency(
+        &mut self,
+        name: &str,
+        dep: &Dependency,
+    ) -> crate::Result<()> {
+        let mut value = dependency_value(dep)?;
+        let Some(dependencies) = self.dependencies(true) else {
+            return Err(crate::InvalidManifest::NotATable {
+                name: "dependencies".into(),
+            }
+            .into());
+        };
+        match dependencies.get_mut(name) {
+            Some(Item::Table(table)) => {
+                let mut replacement = value
+                    .as_inline_table()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_table();
+                std::mem::swap(replacement.decor_mut(), table.decor_mut());
+                if let Some(position) = table.position() {
+                    replacement.set_position(position);
+                }
+                *table = replacement;
+            },
+            Some(Item::Value(existing)) => {
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            },
+            _ => {
+                dependencies.insert(name, Item::Value(value));
+            },
+        }
+        Ok(())
+    }
+
+    /// Changes the version requirement of the dependency `name`, keeping the rest of its
+    /// declaration. Returns whether the manifest declares the dependency.
+    pub fn set_dependency_version(
+        &mut self,
+        name: &str,
+        version: &VersionReqSerde,
+    ) -> bool {
+        let Some(item) = self
+            .dependencies(false)
+            .and_then(|dependencies| dependencies.get_mut(name))
+        else {
+            return false;
+        };
+        let version = version.to_string();
+        if let Some(table) = item.as_table_like_mut() {
+            match table.get_mut("version") {
+                Some(Item::Value(existing)) => replace(existing, version.into()),
+                _ => {
+                    table.insert("version", toml_edit::value(version));
+                },
+            }
+        }
+        true
+    }
+
+    /// Removes the dependency `name`, returning whether the manifest declared it.
+    pub fn remove_dependency(
+        &mut self,
+        name: &str,
+    ) -> bool {
+        self.dependencies(false)
+            .and_then(|dependencies| dependencies.remove(name))
+            .is_some()
+    }
+
+    /// Sets `key` under `[package]`, e.g. `description`.
+    pub fn set_package_field(
+        &mut self,
+        key: &str,
+        value: impl Into<Value>,
+    ) {
+        let package = self.doc["package"].or_insert(Item::Table(Table::new()));
+        match package.get_mut(key) {
+            Some(Item::Value(existing)) => replace(existing, value.into()),
+            _ => package[key] = Item::Value(value.into()),
+        }
+    }
+
+    /// Removes `key` from `[package]`, returning whether it was set.
+    pub fn remove_package_field(
+        &mut self,
+        key: &str,
+    ) -> bool {
+        self.doc
+            .get_mut("package")
+            .and_then(Item::as_table_like_mut)
+            .and_then(|package| package.remove(key))
+            .is_some()
+    }
+
+    /// Sets the version of the package.
+    pub fn set_version(
+        &mut self,
+        version: &VersionSerde,
+    ) {
+        self.set_package_field("version", version.to_string());
+    }
+
+    /// Sets `key` in the `[package.metadata.<table>]` table.
+    pub fn set_metadata(
+        &mut self,
+        table: &str,
+        key: &str,
+        value: impl Into<Value>,
+    ) {
+        let package = self.doc["package"].or_insert(Item::Table(Table::new()));
+        let metadata = package["metadata"].or_insert(implicit_table());
+        let table = metadata[table].or_insert(Item::Table(Table::new()));
+        match table.get_mut(key) {
+            Some(Item::Value(existing)) => replace(existing, value.into()),
+            _ => table[key] = Item::Value(value.into()),
+        }
+    }
+}
+
+impl fmt::Display for ManifestEditor {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.doc)
+    }
+}
+
+impl Dependency {
+    /// A dependency on `version` from the default registry.
+    pub fn remote(version: VersionReqSerde) -> Self {
+        Dependency::Remote(crate::package::RemoteDependency {
+            name: Some(DEFAULT_REGISTRY.into()),
+            version,
+            registry: None,
+        })
+    }
+}
+
+/// Replaces `existing` with `value`, keeping the comments and spacing around it.
+fn replace(
+    existing: &mut Value,
+    mut value: Value,
+) {
+    *value.decor_mut() = existing.decor().clone();
+    *existing = value;
+}
+
+/// A table written only through its subtables, e.g. `metadata` in `[package.metadata.ci]`.
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+    Item::Table(table)
+}
+
+/// `dep` as an inline value, leaving out the default registry.
+fn dependency_value(dep: &Dependency) -> crate::Result<Value> {
+    let mut value = toml::Value::try_from(dep)?;
+    if let Some(table) = value.as_table_mut()
+        && table
+            .get("name")
+            .and_then(toml::Value::as_str)
+            == Some(DEFAULT_REGISTRY)
+    {
+        table.remove("name");
+    }
+    Ok(value.to_string().parse()?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::package::{PathDependency, PathWithRemote, RemoteDependency};
+
+    const MANIFEST: &str = r#"version = "v1"
+
+[package]
+name = "shop"   # the name in the registry
+version = "0.1.0"
+
+# shared types
+[dependencies]
+money = { version = "^1.4" } # pinned by finance
+common = { path = "../common" }
+
+[dependencies.billing]
+# billing lives in its own registry
+version = "^2"
+registry = "internal"
+"#;
+
+    fn editor() -> ManifestEditor {
+        ManifestEditor::parse(MANIFEST).unwrap()
+    }
+
+    fn req(req: &str) -> VersionReqSerde {
+        crate::version::parse_version_req(req)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn adds_dependencies_keeping_comments() {
+        let mut editor = editor();
+        editor
+            .set_dependency("tax", &Dependency::remote(req("^0.3")))
+            .unwrap();
+        editor
+            .set_dependency(
+                "money",
+                &Dependency::PathWithRemote(PathWithRemote {
+                    path: PathDependency {
+                        path: "../money".into(),
+                    },
+                    remote: RemoteDependency {
+                        name: Some(DEFAULT_REGISTRY.into()),
+                        version: req("^1.5"),
+                        registry: None,
+                    },
+                }),
+            )
+            .unwrap();
+
+        let edited = editor.to_string();
+        assert!(edited.contains("name = \"shop\"   # the name in the registry\n"));
+        assert!(
+            edited.contains(
+                "money = { path = \"../money\", version = \"^1.5\" } # pinned by finance\n"
+            ),
+            "{edited}"
+        );
+        assert!(
+            edited.contains("tax = { version = \"^0.3\" }\n"),
+            "{edited}"
+        );
+        assert!(
+            editor
+                .manifest()
+                .unwrap()
+                .dependencies()
+                .contains_key("tax")
+        );
+    }
+
+    #[test]
+    fn updates_versions_in_place() {
+        let mut editor = editor();
+        assert!(editor.set_dependency_version("billing", &req("^2.1")));
+        assert!(editor.set_dependency_version("money", &req("^1.6")));
+        assert!(!editor.set_dependency_version("missing", &req("^1")));
+
+        let edited = editor.to_string();
+        assert!(edited.contains("money = { version = \"^1.6\" } # pinned by finance\n"));
+        assert!(edited.contains(
+            "[dependencies.billing]\n# billing lives in its own registry\nversion = \"^2.1\"\n"
+        ));
+    }
+
+    #[test]
+    fn replaces_dependency_tables_as_tables() {
+        let mut editor = editor();
+        editor
+            .set_dependency("billing", &Dependency::remote(req("^3")))
+            .unwrap();
+        assert!(
+            editor
+                .to_string()
+                .ends_with("[dependencies.billing]\nversion = \"^3\"\n"),
+            "{editor}"
+        );
+    }
+
+    #[test]
+    fn removes_dependencies() {
+        let mut editor = editor();
+        assert!(editor.remove_dependency("common"));
+        assert!(editor.remove_dependency("billing"));
+        assert!(!editor.remove_dependency("common"));
+        assert_eq!(
+            editor.to_string(),
+            "version = \"v1\"\n\n[package]\nname = \"shop\"   # the name in the registry\nversion = \"0.1.0\"\n\n# shared types\n[dependencies]\nmoney = { version = \"^1.4\" } # pinned by finance\n"
+        );
+    }
+
+    #[test]
+    fn edits_package_fields() {
+        let mut editor = editor();
+        editor.set_version(
+            &crate::version::parse_version("0.2.0")
+                .unwrap()
+                .into(),
+        );
+        editor.set_package_field("description", "the shop");
+        editor.set_metadata("ci", "owner", "payments");
+        assert!(editor.remove_package_field("description"));
+
+        let manifest = editor.manifest().unwrap();
+        assert_eq!(manifest.package().version.to_string(), "0.2.0");
+        assert_eq!(
+            manifest.package().metadata["ci"]["owner"],
+            serde_json::json!("payments")
+        );
+        assert!(
+            editor
+                .to_string()
+                .contains("[package.metadata.ci]\nowner = \"payments\"\n")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_edits() {
+        let mut editor = editor();
+        editor.set_package_field("name", "Not A Name");
+        assert!(editor.check().is_err());
+    }
+
+    #[test]
+    fn edits_workspace_dependencies() {
+        let mut editor =
+            ManifestEditor::parse("version = \"v1\"\n\n[workspace]\nmembers = [\"packages/*\"]\n")
+                .unwrap();
+        editor
+            .set_dependency("money", &Dependency::remote(req("^1")))
+            .unwrap();
+        assert!(editor.has_dependency("money"));
+        assert!(
+            editor
+                .to_string()
+                .ends_with("[workspace.dependencies]\nmoney = { version = \"^1\" }\n"),
+            "{editor}"
+        );
+        editor.check().unwrap();
+    }
+}
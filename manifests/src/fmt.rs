@@ -0,0 +1,141 @@
+//! Formatter settings scoped to a directory.
+//!
+//! Besides the global `op-fmt` config, `kintsu fmt` reads the `[fmt]` table of every
+//! `schema.toml` and every `.op-fmt.toml` file in the directories above a target. Settings
+//! closer to the target take precedence, and in one directory `.op-fmt.toml` wins over the
+//! manifest.
+//!
+//! ```toml
+//! [fmt]
+//! max_width = 100
+//! indent_with_tabs = false
+//! ```
+
+use std::path::Path;
+
+use kintsu_fs::FileSystem;
+use validator::Validate;
+
+use crate::{Error, config::NewForNamed, package::PackageManifests};
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq, Eq, validator::Validate,
+)]
+#[serde(deny_unknown_fields)]
+pub struct FormatOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_width: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indent_with_tabs: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub indent_width: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preserve_adjacent_blank_lines: Option<bool>,
+}
+
+impl FormatOverrides {
+    pub const FILE_NAME: &str = ".op-fmt.toml";
+
+    /// These settings with those set in `nearer` taking precedence.
+    pub fn merge(
+        self,
+        nearer: Self,
+    ) -> Self {
+        Self {
+            max_width: nearer.max_width.or(self.max_width),
+            indent_with_tabs: nearer
+                .indent_with_tabs
+                .or(self.indent_with_tabs),
+            indent_width: nearer.indent_width.or(self.indent_width),
+            preserve_adjacent_blank_lines: nearer
+                .preserve_adjacent_blank_lines
+                .or(self.preserve_adjacent_blank_lines),
+        }
+    }
+
+    /// The settings declared in `dir` itself, by its manifest and its override file.
+    pub fn in_dir(
+        fs: &dyn FileSystem,
+        dir: &Path,
+    ) -> crate::Result<Self> {
+        let from_manifest = Self::read(fs, &PackageManifests::path(dir), true)?;
+        let from_file = Self::read(fs, &dir.join(Self::FILE_NAME), false)?;
+        Ok(from_manifest.merge(from_file))
+    }
+
+    /// Reads `path`, taking its `[fmt]` table when it is a manifest, or the whole file
+    /// otherwise. A missing file sets nothing.
+    fn read(
+        fs: &dyn FileSystem,
+        path: &Path,
+        is_manifest: bool,
+    ) -> crate::Result<Self> {
+        if !fs.exists_sync(path) {
+            return Ok(Self::default());
+        }
+
+        let data = fs.read_to_string_sync(path)?;
+        let parse = || -> crate::Result<Self> {
+            let overrides = if is_manifest {
+                let mut table: toml::Table = toml::from_str(&data)?;
+                match table.remove("fmt") {
+                    Some(fmt) => fmt.try_into()?,
+                    None => Self::default(),
+                }
+            } else {
+                toml::from_str(&data)?
+            };
+            overrides.validate()?;
+            Ok(overrides)
+        };
+        parse().map_err(Error::from_with_source_init(path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use kintsu_fs::memory::MemoryFileSystem;
+
+    use super::*;
+
+    #[test]
+    fn override_file_wins_over_manifest() {
+        let fs = MemoryFileSystem::with_files(vec![
+            (
+                "pkg/schema.toml",
+                "version = \"v1\"\n\n[package]\nname = \"pkg\"\nversion = \"0.1.0\"\n\n[fmt]\nmax_width = 80\nindent_width = 2\n"
+                    .to_string(),
+            ),
+            ("pkg/.op-fmt.toml", "max_width = 100\n".to_string()),
+        ]);
+
+        let overrides = FormatOverrides::in_dir(&fs, Path::new("pkg")).unwrap();
+        assert_eq!(overrides.max_width, Some(100));
+        assert_eq!(overrides.indent_width, Some(2));
+        assert_eq!(overrides.indent_with_tabs, None);
+
+        assert_eq!(
+            FormatOverrides::in_dir(&fs, Path::new("other")).unwrap(),
+            FormatOverrides::default()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        let fs = MemoryFileSystem::with_files(vec![
+            ("a/.op-fmt.toml", "max_width = 0\n".to_string()),
+            ("b/.op-fmt.toml", "tab_width = 4\n".to_string()),
+        ]);
+
+        for dir in ["a", "b"] {
+            let err = FormatOverrides::in_dir(&fs, Path::new(dir)).unwrap_err();
+            assert!(err.to_string().contains(".op-fmt.toml"), "{err}");
+        }
+    }
+}
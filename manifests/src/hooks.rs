@@ -0,0 +1,27 @@
+//! Commands run once the declarations of a package are emitted, declared under `[[hooks]]`.
+//!
+//! ```toml
+//! [[hooks]]
+//! name = "policy"
+//! command = "./scripts/check-policy"
+//! args = ["--strict"]
+//! ```
+
+use validator::Validate;
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct HookCommand {
+    /// Name the hook is reported by
+    #[validate(length(min = 1))]
+    pub name: String,
+
+    /// Program to run, relative to the package directory or on the `PATH`
+    #[validate(length(min = 1))]
+    pub command: String,
+
+    /// Arguments passed to the program
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
@@ -0,0 +1,258 @@
+//! JSON Schemas of the manifest and lockfile, for editors to complete and validate
+//! `schema.toml` and `schema.lock.toml` with.
+//!
+//! The schemas are generated from the OpenAPI schemas the registry documents manifests
+//! with, which follow the serde derives of the models. OpenAPI 3.1 schemas are JSON Schemas,
+//! so only references to other components are rewritten to point into `$defs`, with the
+//! variants of enums relaxed as serde matches them.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value, json};
+use utoipa::{
+    ToSchema,
+    openapi::{RefOr, schema::Schema},
+};
+
+use crate::{
+    config::NewForNamed,
+    lock::{Lockfiles, WorkspaceLockfiles},
+    package::PackageManifests,
+};
+
+/// The dialect of the generated schemas.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const COMPONENTS: &str = "#/components/schemas/";
+
+/// The schema of `schema.toml`, for a package.
+pub fn manifest_schema() -> Value {
+    let mut defs = BTreeMap::new();
+    let root = define::<PackageManifests>(&mut defs);
+    document(
+        PackageManifests::NAME,
+        "The manifest of a kintsu package",
+        root,
+        defs,
+    )
+}
+
+/// The schema of `schema.lock.toml`, written for a package or shared by a workspace.
+pub fn lockfile_schema() -> Value {
+    let mut defs = BTreeMap::new();
+    let root = json!({
+        "anyOf": [
+            define::<Lockfiles>(&mut defs),
+            define::<WorkspaceLockfiles>(&mut defs),
+        ]
+    });
+    document(
+        Lockfiles::NAME,
+        "The dependency versions a kintsu package or workspace is locked to",
+        root,
+        defs,
+    )
+}
+
+/// Adds `T` and the schemas it refers to to `defs`, returning a reference to it.
+fn define<T: ToSchema>(defs: &mut BTreeMap<String, Value>) -> Value {
+    let mut schemas = vec![(T::name().to_string(), T::schema())];
+    T::schemas(&mut schemas);
+    for (name, schema) in schemas {
+        defs.insert(name, to_json(&schema));
+    }
+    json!({ "$ref": format!("#/$defs/{}", T::name()) })
+}
+
+fn document(
+    title: &str,
+    description: &str,
+    root: Value,
+    defs: BTreeMap<String, Value>,
+) -> Value {
+    let mut document = Map::new();
+    document.insert("$schema".into(), DIALECT.into());
+    document.insert("title".into(), title.into());
+    document.insert("description".into(), description.into());
+    if let Value::Object(root) = root {
+        document.extend(root);
+    }
+    document.insert("$defs".into(), Value::Object(defs.into_iter().collect()));
+    Value::Object(document)
+}
+
+fn to_json(schema: &RefOr<Schema>) -> Value {
+    let mut value = serde_json::to_value(schema).expect("schemas serialize to json");
+    rewrite(&mut value);
+    value
+}
+
+/// Points references to OpenAPI components at the `$defs` of the document instead, and
+/// relaxes `oneOf` to `anyOf`: variants of untagged enums may overlap, e.g. a dependency
+/// with both a `path` and a `version` also matches a path dependency, where serde takes the
+/// first variant matching.
+fn rewrite(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            if let Some(variants) = object.remove("oneOf") {
+                object.insert("anyOf".into(), variants);
+            }
+            for (key, value) in object.iter_mut() {
+                match value {
+                    Value::String(reference) if key == "$ref" => {
+                        if let Some(name) = reference.strip_prefix(COMPONENTS) {
+                            *reference = format!("#/$defs/{name}");
+                        }
+                    },
+                    value => rewrite(value),
+                }
+            }
+        },
+        Value::Array(values) => values.iter_mut().for_each(rewrite),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        lock::{LockedDependencyRef, LockedPackage, LockedSource, Lockfile, WorkspaceLockfile},
+        version::parse_version,
+    };
+
+    /// Every section of a manifest, with each kind of dependency.
+    const MANIFEST: &str = r#"
+version = "v1"
+
+[package]
+name = "shop"
+version = "1.2.0"
+description = "Orders and payments"
+license = { path = "LICENSE" }
+authors = [{ name = "Ada", email = "ada@example.com" }]
+homepage = "https://example.com"
+keywords = ["orders"]
+embed_metadata = true
+
+[package.metadata.docs]
+theme = "dark"
+
+[files]
+exclude = ["schema/scratch.ks"]
+
+[dependencies]
+money = { version = "^1.4" }
+users = { path = "../users" }
+billing = { path = "../billing", version = "^0.3" }
+audit = { git = "https://example.com/audit.git", ref = "refs/tags/v1" }
+shared = { workspace = true }
+
+[fmt]
+max_width = 100
+
+[lint]
+allow_warnings = ["KLT8001"]
+
+[lint.docs]
+min_coverage = { min = 90, level = "error" }
+
+[registries.internal]
+url = "https://registry.example.com"
+mirrors = ["https://mirror.example.com"]
+
+[profiles.public]
+exclude = ["admin"]
+
+[[hooks]]
+name = "docs"
+command = "./gen-docs"
+args = ["--out", "docs"]
+"#;
+
+    fn validator(schema: &Value) -> jsonschema::Validator {
+        jsonschema::validator_for(schema).expect("a valid JSON Schema")
+    }
+
+    fn assert_valid(
+        validator: &jsonschema::Validator,
+        instance: &Value,
+    ) {
+        let errors: Vec<_> = validator
+            .iter_errors(instance)
+            .map(|err| format!("{err} at {}", err.instance_path()))
+            .collect();
+        assert!(errors.is_empty(), "{errors:#?}\n{instance:#}");
+    }
+
+    fn locked(
+        name: &str,
+        source: LockedSource,
+    ) -> LockedPackage {
+        LockedPackage {
+            name: name.into(),
+            version: parse_version("1.0.0").unwrap().into(),
+            checksum: "abc".into(),
+            source,
+            dependencies: BTreeMap::from([(
+                "money".to_string(),
+                LockedDependencyRef::new(parse_version("1.4.0").unwrap().into())
+                    .with_chain(vec![name.into(), "money".into()]),
+            )]),
+        }
+    }
+
+    #[test]
+    fn manifests_match_their_schema() {
+        let validator = validator(&manifest_schema());
+
+        let manifest: PackageManifests = toml::from_str(MANIFEST).unwrap();
+        assert_valid(&validator, &toml::from_str(MANIFEST).unwrap());
+        // what serde writes back must validate too
+        assert_valid(&validator, &serde_json::to_value(&manifest).unwrap());
+
+        for invalid in [
+            MANIFEST.replace("version = \"v1\"", "version = \"v9\""),
+            MANIFEST.replace("name = \"shop\"", "name = 4"),
+            MANIFEST.replace("command = \"./gen-docs\"", "program = \"./gen-docs\""),
+        ] {
+            let instance: Value = toml::from_str(&invalid).unwrap();
+            assert!(!validator.is_valid(&instance), "{invalid}");
+            assert!(toml::from_str::<PackageManifests>(&invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn lockfiles_match_their_schema() {
+        let validator = validator(&lockfile_schema());
+
+        let mut lockfile = Lockfile::new(locked("shop", LockedSource::Path { path: "./".into() }));
+        lockfile.add_package(locked(
+            "money",
+            LockedSource::Registry {
+                url: "https://registry.example.com".into(),
+                mirror: Some("https://mirror.example.com".into()),
+            },
+        ));
+        lockfile.add_package(locked(
+            "audit",
+            LockedSource::Git {
+                url: "https://example.com/audit.git".into(),
+                git_ref: "refs/tags/v1".into(),
+            },
+        ));
+        let mut workspace = WorkspaceLockfile::default();
+        workspace.add_member("members/shop".into(), lockfile.clone());
+
+        for written in [
+            toml::to_string(&Lockfiles::V1(lockfile)).unwrap(),
+            toml::to_string(&WorkspaceLockfiles::V1(workspace)).unwrap(),
+        ] {
+            assert_valid(&validator, &toml::from_str(&written).unwrap());
+        }
+
+        assert!(!validator.is_valid(&json!({ "version": "v1", "root": { "name": "shop" } })));
+    }
+}
@@ -1,16 +1,25 @@
 use std::path::{Path, PathBuf};
 
 pub mod config;
+pub mod edit;
+pub mod fmt;
+pub mod hooks;
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
 pub mod lock;
 pub mod manager;
 pub mod package;
+pub mod profiles;
+pub mod registries;
 pub mod rules;
+pub mod scaffold;
 pub mod version;
+pub mod workspace;
 
-use convert_case::{Case, Casing};
-
-pub use crate::config::NewForConfig;
-use crate::{config::NewForNamed, package::PackageManifests};
+pub use crate::{
+    config::NewForConfig,
+    scaffold::{InitOptions, Template, TemplateRegistry, init},
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -35,6 +44,12 @@ pub enum Error {
 
     #[error("{0}")]
     ManifestError(#[from] InvalidManifest),
+
+    #[error("{0}")]
+    EditError(#[from] toml_edit::TomlError),
+
+    #[error("unknown template '{name}', expected one of: {available}")]
+    UnknownTemplate { name: String, available: String },
 }
 
 #[derive(thiserror::Error, Debug, serde::Serialize)]
@@ -55,6 +70,23 @@ pub enum InvalidManifest {
     },
     #[error("Package manifest contains unresolved dependencies. See sources for details.")]
     UnresolvedDependencies { sources: Vec<InvalidManifest> },
+
+    #[error(
+        "Package manifest inherits '{name}' from the workspace, but it is not inside a workspace."
+    )]
+    MissingWorkspace { name: String },
+    #[error("Package manifest declares '{name}', but it is not a table.")]
+    NotATable { name: String },
+    #[error(
+        "Package manifest inherits '{name}' from the workspace, but the workspace does not declare it."
+    )]
+    UnknownWorkspaceDependency { name: String },
+    #[error("Workspace members {first} and {second} are both named '{name}'.")]
+    DuplicateWorkspaceMember {
+        name: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
 }
 
 impl Error {
@@ -119,62 +151,18 @@ impl From<Error> for kintsu_errors::CompilerError {
                     .unlocated()
                     .build()
             },
+            Error::EditError(e) => {
+                PackageError::parse_error(e.to_string())
+                    .unlocated()
+                    .build()
+            },
+            e @ Error::UnknownTemplate { .. } => {
+                PackageError::manifest_error(e.to_string())
+                    .unlocated()
+                    .build()
+            },
         }
     }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
-
-pub fn init(
-    name: String,
-    dir: Option<PathBuf>,
-) -> Result<()> {
-    use validator::Validate;
-
-    let pkg = package::PackageManifests::V1(package::PackageManifest {
-        package: package::PackageMeta {
-            name,
-            description: None,
-            version: version::VersionSerde(version::parse_version("0.1.0")?),
-            authors: vec![],
-            keywords: vec![],
-            homepage: None,
-            license: None,
-            readme: None,
-            repository: None,
-        },
-        dependencies: Default::default(),
-        files: Default::default(),
-    });
-
-    pkg.validate()?;
-
-    let dir = dir.unwrap_or_else(|| PathBuf::from(pkg.package().name.clone()));
-
-    let manifest = dir.join(PackageManifests::NAME);
-
-    if !dir.exists() {
-        std::fs::create_dir(&dir)?;
-    }
-
-    let out = toml::to_string(&pkg)?;
-    std::fs::write(manifest, out)?;
-
-    let schema = dir.join("schema/");
-    if !schema.exists() {
-        std::fs::create_dir(&schema)?;
-    }
-
-    let lib = schema.join("lib.ks");
-    if !lib.exists() {
-        std::fs::write(
-            lib,
-            format!(
-                "#![version(1)]\nnamespace {};\n",
-                pkg.package().name.to_case(Case::Snake)
-            ),
-        )?;
-    }
-
-    Ok(())
-}
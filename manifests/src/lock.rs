@@ -4,6 +4,7 @@ use validator::Validate;
 
 use crate::config::NewForNamed;
 
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 #[serde(tag = "version", rename_all = "snake_case")]
 pub enum Lockfiles {
@@ -29,15 +30,72 @@ impl Lockfiles {
     }
 }
 
+/// The lockfile of a workspace, written next to the workspace manifest and shared by
+/// every member in place of per-member lockfiles.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(tag = "version", rename_all = "snake_case")]
+pub enum WorkspaceLockfiles {
+    V1(WorkspaceLockfile),
+}
+
+impl Validate for WorkspaceLockfiles {
+    fn validate(&self) -> Result<(), validator::ValidationErrors> {
+        Ok(())
+    }
+}
+
+impl NewForNamed for WorkspaceLockfiles {
+    const NAME: &str = "schema.lock.toml";
+}
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default)]
+pub struct WorkspaceLockfile {
+    /// The root entry of each member, keyed by package name
+    pub members: BTreeMap<String, LockedPackage>,
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+impl WorkspaceLockfile {
+    /// The lockfile as seen by one member.
+    pub fn member(
+        &self,
+        name: &str,
+    ) -> Option<Lockfile> {
+        self.members.get(name).map(|root| {
+            Lockfile {
+                root: root.clone(),
+                packages: self.packages.clone(),
+            }
+        })
+    }
+
+    /// Adds the lockfile of the member at `member_dir`, relative to the workspace root.
+    pub fn add_member(
+        &mut self,
+        member_dir: PathBuf,
+        lockfile: Lockfile,
+    ) {
+        let mut root = lockfile.root;
+        root.source = LockedSource::Path { path: member_dir };
+        self.members.insert(root.name.clone(), root);
+        self.packages.extend(lockfile.packages);
+    }
+}
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct Lockfile {
     pub root: LockedPackage,
     pub packages: BTreeMap<String, LockedPackage>,
 }
 
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct LockedPackage {
     pub name: String,
+    #[cfg_attr(feature = "api", schema(value_type = String, format = "version"))]
     pub version: super::version::VersionSerde,
     pub checksum: String,
     pub source: LockedSource,
@@ -45,8 +103,10 @@ pub struct LockedPackage {
     pub dependencies: BTreeMap<String, LockedDependencyRef>,
 }
 
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct LockedDependencyRef {
+    #[cfg_attr(feature = "api", schema(value_type = String, format = "version"))]
     pub version: super::version::VersionSerde,
     #[serde(default)]
     pub provides: Vec<String>,
@@ -54,6 +114,7 @@ pub struct LockedDependencyRef {
     pub chain: Vec<String>,
 }
 
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum LockedSource {
@@ -63,10 +124,14 @@ pub enum LockedSource {
         git_ref: String,
     },
     Path {
+        #[cfg_attr(feature = "api", schema(value_type = String, format = "path"))]
         path: PathBuf,
     },
     Registry {
         url: String,
+        /// The mirror the package was fetched from, when the registry was unavailable
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mirror: Option<String>,
     },
 }
 
@@ -57,17 +57,46 @@ impl PathOrText {
 
 fn validate_name(name: &str) -> Result<(), ValidationError> {
     const ERR_SPEC: &str = "package name must be provided without spaces or special characters";
-    if let Some(capt) = PACKAGE_RE.find(name) {
-        if capt.as_str().len() != name.len() {
+    const ERR_SCOPE: &str = "scoped package names must be written as @scope/name";
+    const ERR_HYPHENS: &str = "package names may not contain consecutive hyphens";
+
+    let parts = match name.strip_prefix('@') {
+        Some(scoped) => {
+            let Some((scope, name)) = scoped.split_once('/') else {
+                return Err(ValidationError::new("package name").with_message(ERR_SCOPE.into()));
+            };
+            vec![scope, name]
+        },
+        None => vec![name],
+    };
+
+    for part in parts {
+        if PACKAGE_RE
+            .find(part)
+            .is_none_or(|capt| capt.as_str().len() != part.len())
+        {
             return Err(ValidationError::new("package name").with_message(ERR_SPEC.into()));
         }
-    } else {
-        return Err(ValidationError::new("package name").with_message(ERR_SPEC.into()));
+        // import names write scopes with a double underscore
+        if part.contains("--") {
+            return Err(ValidationError::new("package name").with_message(ERR_HYPHENS.into()));
+        }
     }
 
     Ok(())
 }
 
+/// The scope of a scoped package name such as `@acme/billing`, without the `@`. Scopes are
+/// owned by the organization of the same name.
+pub fn package_scope(name: &str) -> Option<&str> {
+    name.strip_prefix('@')?
+        .split_once('/')
+        .map(|(scope, _)| scope)
+}
+
+/// Free-form `[package.metadata.*]` tables, keyed by table name.
+pub type PackageMetadata = BTreeMap<String, serde_json::Value>;
+
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Clone, validator::Validate)]
 pub struct Author {
@@ -141,6 +170,16 @@ pub struct PackageMeta {
     #[serde(default)]
     #[validate(custom(function = validate_keywords))]
     pub keywords: Vec<String>,
+
+    /// Custom metadata under `[package.metadata.*]`. Not interpreted by the compiler, but
+    /// preserved through publish and stored in the registry
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[cfg_attr(feature = "api", schema(value_type = Object))]
+    pub metadata: PackageMetadata,
+
+    /// Whether `metadata` is embedded into emitted declarations
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub embed_metadata: bool,
 }
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
@@ -162,7 +201,36 @@ pub struct PackageManifest {
     pub files: FileConfig,
 
     #[serde(default = "BTreeMap::new")]
+    #[cfg_attr(feature = "api", schema(inline))]
     pub dependencies: NamedDependencies,
+
+    /// Formatter settings for the schemas of this package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub fmt: Option<crate::fmt::FormatOverrides>,
+
+    /// Lint settings for the schemas of this package: rule levels under
+    /// `[lint.overrides.<group>]` and naming conventions under `[lint.naming]`
+    #[serde(default, skip_serializing_if = "crate::rules::LintConfig::is_empty")]
+    #[cfg_attr(feature = "api", schema(value_type = Object))]
+    pub lint: crate::rules::LintConfig,
+
+    /// Registries dependencies name under `registry`, with their mirrors
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[validate(nested)]
+    #[cfg_attr(feature = "api", schema(inline))]
+    pub registries: crate::registries::NamedRegistries,
+
+    /// Compile profiles selecting the surface emitted into declarations, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[validate(nested)]
+    #[cfg_attr(feature = "api", schema(inline))]
+    pub profiles: crate::profiles::NamedProfiles,
+
+    /// Commands run once declarations are emitted, when the compiling tool opts into them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[validate(nested)]
+    pub hooks: Vec<crate::hooks::HookCommand>,
 }
 
 impl PackageManifest {
@@ -217,6 +285,13 @@ impl PackageManifest {
                         "git dependencies are not allowed for publishing",
                     )
                 },
+                Dependency::Workspace(_) => {
+                    Self::with_dependency_error(
+                        &mut errors,
+                        name,
+                        "workspace dependencies must be inherited before publishing",
+                    )
+                },
                 Dependency::PathWithRemote(pwr) => {
                     Self::validate_remote(
                         &mut errors,
@@ -299,6 +374,43 @@ pub struct RemoteDependency {
     pub registry: Option<String>,
 }
 
+impl RemoteDependency {
+    /// A dependency on exactly `version` from the default registry.
+    pub fn exact(version: &crate::version::VersionSerde) -> Self {
+        Self {
+            name: default_registry(),
+            version: Self::exact_req(version),
+            registry: None,
+        }
+    }
+
+    /// This dependency with its requirement narrowed to exactly `version`.
+    pub fn pinned(
+        &self,
+        version: &crate::version::VersionSerde,
+    ) -> Self {
+        Self {
+            version: Self::exact_req(version),
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn exact_req(
+        version: &crate::version::VersionSerde
+    ) -> crate::version::VersionReqSerde {
+        semver::VersionReq {
+            comparators: vec![semver::Comparator {
+                op: semver::Op::Exact,
+                major: version.major,
+                minor: Some(version.minor),
+                patch: Some(version.patch),
+                pre: version.pre.clone(),
+            }],
+        }
+        .into()
+    }
+}
+
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Clone, validator::Validate)]
 pub struct PathWithRemote {
@@ -310,10 +422,20 @@ pub struct PathWithRemote {
     pub remote: RemoteDependency,
 }
 
+/// A dependency declared once in the workspace manifest, written `name = { workspace = true }`.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceDependency {
+    pub workspace: bool,
+}
+
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Clone)]
 #[serde(untagged)]
 pub enum Dependency {
+    Workspace(WorkspaceDependency),
+
     PathWithRemote(PathWithRemote),
 
     Remote(RemoteDependency),
@@ -325,6 +447,7 @@ pub enum Dependency {
 impl Dependency {
     pub fn version(&self) -> Option<&crate::version::VersionReqSerde> {
         match self {
+            Dependency::Workspace(_) => None,
             Dependency::Git(_) => None,
             Dependency::Path(_) => None,
             Dependency::Remote(dep) => Some(&dep.version),
@@ -333,6 +456,38 @@ impl Dependency {
     }
 }
 
+impl Dependency {
+    pub fn path(&self) -> Option<&std::path::Path> {
+        match self {
+            Dependency::Path(dep) => Some(&dep.path),
+            Dependency::PathWithRemote(dep) => Some(&dep.path.path),
+            Dependency::Remote(_) | Dependency::Git(_) | Dependency::Workspace(_) => None,
+        }
+    }
+
+    /// Whether this dependency is inherited from the workspace manifest.
+    pub fn is_workspace(&self) -> bool {
+        matches!(self, Dependency::Workspace(dep) if dep.workspace)
+    }
+
+    /// Replaces a path dependency with a registry dependency on exactly `version`, keeping
+    /// the registry settings of a `path` + `version` dependency.
+    pub fn pin_to_published(
+        &mut self,
+        version: &crate::version::VersionSerde,
+    ) {
+        *self = match self {
+            Dependency::PathWithRemote(dep) => {
+                Dependency::Remote(RemoteDependency {
+                    version: RemoteDependency::exact_req(version),
+                    ..dep.remote.clone()
+                })
+            },
+            _ => Dependency::Remote(RemoteDependency::exact(version)),
+        };
+    }
+}
+
 impl validator::Validate for Dependency {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
@@ -340,6 +495,20 @@ impl validator::Validate for Dependency {
             Dependency::Path(dep) => dep.validate(),
             Dependency::Remote(dep) => dep.validate(),
             Dependency::PathWithRemote(dep) => dep.validate(),
+            Dependency::Workspace(dep) => {
+                if dep.workspace {
+                    Ok(())
+                } else {
+                    let mut errors = ValidationErrors::new();
+                    errors.add(
+                        "workspace",
+                        ValidationError::new("workspace").with_message(
+                            "'workspace' must be true, or the dependency declared in full".into(),
+                        ),
+                    );
+                    Err(errors)
+                }
+            },
         }
     }
 }
@@ -372,12 +541,42 @@ impl PackageManifests {
         }
     }
 
+    pub fn dependencies_mut(&mut self) -> &mut NamedDependencies {
+        match self {
+            PackageManifests::V1(manifest) => &mut manifest.dependencies,
+        }
+    }
+
     pub fn files(&self) -> &FileConfig {
         match self {
             PackageManifests::V1(manifest) => &manifest.files,
         }
     }
 
+    pub fn lint(&self) -> &crate::rules::LintConfig {
+        match self {
+            PackageManifests::V1(manifest) => &manifest.lint,
+        }
+    }
+
+    pub fn registries(&self) -> &crate::registries::NamedRegistries {
+        match self {
+            PackageManifests::V1(manifest) => &manifest.registries,
+        }
+    }
+
+    pub fn profiles(&self) -> &crate::profiles::NamedProfiles {
+        match self {
+            PackageManifests::V1(manifest) => &manifest.profiles,
+        }
+    }
+
+    pub fn hooks(&self) -> &[crate::hooks::HookCommand] {
+        match self {
+            PackageManifests::V1(manifest) => &manifest.hooks,
+        }
+    }
+
     pub fn prepare_publish(&mut self) -> Result<(), validator::ValidationErrors> {
         match self {
             PackageManifests::V1(manifest) => manifest.prepare_publish(),
@@ -387,6 +586,34 @@ impl PackageManifests {
 
 impl NewForNamed for PackageManifests {
     const NAME: &str = "schema.toml";
+
+    /// Reads and validates the manifest in `root_dir`, inheriting `{ workspace = true }`
+    /// dependencies from the closest enclosing workspace.
+    fn new<S: AsRef<std::path::Path>>(
+        f: &dyn kintsu_fs::FileSystem,
+        root_dir: S,
+    ) -> crate::Result<Self> {
+        let root_dir = root_dir.as_ref();
+        let data = f.read_to_string_sync(&Self::path(root_dir))?;
+        let mut this: Self = toml::from_str(&data)?;
+
+        if let Some(name) = this
+            .dependencies()
+            .iter()
+            .find(|(_, dep)| dep.is_workspace())
+            .map(|(name, _)| name.clone())
+        {
+            let Some((workspace_root, workspace)) =
+                crate::workspace::WorkspaceManifests::find(f, root_dir)?
+            else {
+                return Err(crate::InvalidManifest::MissingWorkspace { name }.into());
+            };
+            workspace.inherit(&workspace_root, root_dir, &mut this)?;
+        }
+
+        this.validate()?;
+        Ok(this)
+    }
 }
 
 #[cfg(test)]
@@ -398,6 +625,7 @@ mod test {
     #[test_case::test_case("abc-types", "0.1.0", "https://github.com/abc/foo.git"; "valid name with dash")]
     #[test_case::test_case("abc", "0.1.0", "https://github.com/abc/foo.git"; "simple name")]
     #[test_case::test_case("abc", "0.1.0-rc.0", "https://github.com/abc/foo.git"; "version with rc")]
+    #[test_case::test_case("@acme/billing-schemas", "0.1.0", "https://github.com/abc/foo.git"; "scoped name")]
     fn test_pkg_validate_ok(
         name: &str,
         version: &str,
@@ -413,10 +641,103 @@ mod test {
             license: None,
             readme: None,
             repository: None,
+            metadata: Default::default(),
+            embed_metadata: false,
         };
         p.validate().unwrap();
     }
 
+    #[test]
+    fn test_package_scope() {
+        assert_eq!(super::package_scope("@acme/billing"), Some("acme"));
+        assert_eq!(super::package_scope("billing"), None);
+        assert_eq!(super::package_scope("@acme"), None);
+    }
+
+    #[test]
+    fn test_pkg_metadata_passthrough() {
+        let src = r#"
+version = "v1"
+
+[package]
+name = "abc"
+version = "0.1.0"
+
+[package.metadata.acme]
+owner = "payments"
+tier = 1
+"#;
+        let manifest: super::PackageManifests = toml::from_str(src).unwrap();
+        manifest.validate().unwrap();
+
+        let metadata = &manifest.package().metadata;
+        assert_eq!(metadata["acme"]["owner"], "payments");
+        assert_eq!(metadata["acme"]["tier"], 1);
+        assert!(!manifest.package().embed_metadata);
+
+        let dumped = toml::to_string(&manifest).unwrap();
+        let reparsed: super::PackageManifests = toml::from_str(&dumped).unwrap();
+        assert_eq!(&reparsed.package().metadata, metadata);
+    }
+
+    #[test]
+    fn test_pin_path_dependencies_to_published() {
+        let src = r#"
+version = "v1"
+
+[package]
+name = "abc"
+version = "0.2.0"
+
+[dependencies]
+bar = { path = "../bar" }
+baz = { path = "../baz", version = "^0.1.0", registry = "internal" }
+"#;
+        let mut manifest: super::PackageManifests = toml::from_str(src).unwrap();
+        let published = VersionSerde(parse_version("0.1.3-rc.1").unwrap());
+        for dep in manifest.dependencies_mut().values_mut() {
+            dep.pin_to_published(&published);
+        }
+
+        let deps = manifest.dependencies();
+        for name in ["bar", "baz"] {
+            assert!(deps[name].path().is_none());
+            assert_eq!(deps[name].version().unwrap().to_string(), "=0.1.3-rc.1");
+        }
+        let super::Dependency::Remote(baz) = &deps["baz"] else {
+            panic!("expected a remote dependency");
+        };
+        assert_eq!(baz.registry.as_deref(), Some("internal"));
+    }
+
+    #[test]
+    fn test_registries_with_mirrors() {
+        let src = r#"
+version = "v1"
+
+[package]
+name = "abc"
+version = "0.2.0"
+
+[dependencies]
+baz = { version = "^0.1.0", registry = "internal" }
+
+[registries.internal]
+url = "https://registry.example.com"
+mirrors = ["https://mirror-a.example.com", "https://mirror-b.example.com"]
+"#;
+        let manifest: super::PackageManifests = toml::from_str(src).unwrap();
+        manifest.validate().unwrap();
+
+        let internal = &manifest.registries()["internal"];
+        assert_eq!(internal.url, "https://registry.example.com");
+        assert_eq!(internal.mirrors.len(), 2);
+
+        let dumped = toml::to_string(&manifest).unwrap();
+        let reparsed: super::PackageManifests = toml::from_str(&dumped).unwrap();
+        assert_eq!(reparsed.registries(), manifest.registries());
+    }
+
     #[test_case::test_case("abc_types", "0.1.0", "https://github.com/abc/foo.git", vec![], "name: package name"; "invalid name with underscore")]
     #[test_case::test_case("a", "0.1.0", "https://github.com/abc/foo.git", vec![], "name: Validation error: length"; "name too short")]
     #[test_case::test_case("a".repeat(129).as_str(),
@@ -424,6 +745,10 @@ mod test {
         "name: Validation error: length"; "name too long")]
     #[test_case::test_case("abc_types!", "0.1.0", "https://github.com/abc/foo.git", vec![], "name: package name must be provided without spaces or special characters"; "invalid character in name")]
     #[test_case::test_case("abc_types", "0.1.0", "not-a-url", vec![], "homepage: Validation error: url [{\"value\": String(\"not-a-url\")}]"; "invalid homepage url")]
+    #[test_case::test_case("@acme", "0.1.0", "https://github.com/abc/foo.git", vec![], "name: scoped package names must be written as @scope/name"; "scope without name")]
+    #[test_case::test_case("@acme/billing/v2", "0.1.0", "https://github.com/abc/foo.git", vec![], "name: package name must be provided"; "nested scope")]
+    #[test_case::test_case("@Acme/billing", "0.1.0", "https://github.com/abc/foo.git", vec![], "name: package name must be provided"; "uppercase scope")]
+    #[test_case::test_case("abc--types", "0.1.0", "https://github.com/abc/foo.git", vec![], "name: package names may not contain consecutive hyphens"; "consecutive hyphens")]
     #[test_case::test_case("abc", "0.1.0", "https://github.com/abc/foo.git", vec![" foo".into()], "keywords: keywords must be provided"; "keyword prefixed with space")]
     #[test_case::test_case("abc", "0.1.0", "https://github.com/abc/foo.git", vec!["foo bar".into()], "keywords: keywords must be provided"; "keyword with space inside")]
     #[test_case::test_case("abc", "0.1.0", "https://github.com/abc/foo.git", vec!["".into()], "keywords: keywords must be provided"; "empty keyword")]
@@ -445,6 +770,8 @@ mod test {
             license: None,
             readme: None,
             repository: None,
+            metadata: Default::default(),
+            embed_metadata: false,
         };
         let err = p.validate().unwrap_err();
         let msg = format!("{}", err);
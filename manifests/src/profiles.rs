@@ -0,0 +1,94 @@
+//! Compile profiles, each selecting the surface of a package emitted into its declarations.
+//!
+//! Without a profile, declarations hold every public item of the package. A profile can add
+//! the items declared `internal` and leave out namespaces or items by their path, so that one
+//! schema tree publishes both a public API and a superset used within an organisation.
+//!
+//! ```toml
+//! [profiles.public]
+//! exclude = ["admin", "users::purge"]
+//!
+//! [profiles.internal]
+//! internal = true
+//! ```
+
+use std::collections::BTreeMap;
+
+use validator::Validate;
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq, Eq, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Whether items declared `internal` are emitted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub internal: bool,
+
+    /// Namespaces and items left out, by their path within the package, e.g. `admin` for a
+    /// namespace or `users::purge` for an operation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[validate(custom(function = validate_paths))]
+    pub exclude: Vec<String>,
+}
+
+impl Profile {
+    /// Whether the namespace or item at `path` is left out, as it or a namespace containing
+    /// it is excluded.
+    pub fn excludes<S: AsRef<str>>(
+        &self,
+        path: &[S],
+    ) -> bool {
+        self.exclude.iter().any(|excluded| {
+            let excluded = excluded.split("::").collect::<Vec<_>>();
+            excluded.len() <= path.len()
+                && excluded
+                    .iter()
+                    .zip(path)
+                    .all(|(excluded, segment)| *excluded == segment.as_ref())
+        })
+    }
+}
+
+fn validate_paths(paths: &[String]) -> Result<(), validator::ValidationError> {
+    for path in paths {
+        if path
+            .split("::")
+            .any(|segment| segment.is_empty() || segment.contains(char::is_whitespace))
+        {
+            return Err(validator::ValidationError::new("path")
+                .with_message(format!("'{path}' is not a path like `namespace::item`").into()));
+        }
+    }
+    Ok(())
+}
+
+pub type NamedProfiles = BTreeMap<String, Profile>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn excludes_paths_and_their_contents() {
+        let profile = Profile {
+            internal: false,
+            exclude: vec!["admin".into(), "users::purge".into()],
+        };
+
+        assert!(profile.excludes(&["admin"]));
+        assert!(profile.excludes(&["admin", "audit", "Entry"]));
+        assert!(profile.excludes(&["users", "purge"]));
+        assert!(!profile.excludes(&["users"]));
+        assert!(!profile.excludes(&["users", "purge_all"]));
+        assert!(!profile.excludes(&["administration"]));
+    }
+
+    #[test]
+    fn paths_must_name_something() {
+        let profile = Profile {
+            internal: true,
+            exclude: vec!["users::".into()],
+        };
+        assert!(profile.validate().is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Named registries dependencies are fetched from, each a primary endpoint plus the mirrors
+//! which serve its packages when it cannot be reached.
+//!
+//! Registries are declared under `[registries.<name>]` in a package manifest or in the global
+//! [`RegistriesConfig`]. Entries in the manifest take precedence over global ones of the same
+//! name.
+
+use std::collections::BTreeMap;
+
+use validator::Validate;
+
+/// The url dependencies without a configured registry are fetched from.
+pub const DEFAULT_REGISTRY_URL: &str = "https://registry.kintsu.dev";
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Validate)]
+pub struct RegistryEndpoints {
+    /// Base url of the primary registry
+    #[validate(url)]
+    pub url: String,
+
+    /// Base urls of read-only mirrors, tried in order when the primary is unavailable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[validate(custom(function = validate_urls))]
+    pub mirrors: Vec<String>,
+}
+
+impl RegistryEndpoints {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            mirrors: vec![],
+        }
+    }
+}
+
+fn validate_urls(urls: &[String]) -> Result<(), validator::ValidationError> {
+    for url in urls {
+        if !validator::ValidateUrl::validate_url(url) {
+            return Err(validator::ValidationError::new("url")
+                .with_message(format!("'{url}' is not a valid URL").into()));
+        }
+    }
+    Ok(())
+}
+
+pub type NamedRegistries = BTreeMap<String, RegistryEndpoints>;
+
+/// Registries configured for every package, read from `kintsu-registries.toml` in the config
+/// directory and `KINTSU_REGISTRIES_*` environment variables.
+#[derive(serde::Deserialize, Clone, Debug, Default, Validate)]
+pub struct RegistriesConfig {
+    #[serde(default)]
+    #[validate(nested)]
+    pub registries: NamedRegistries,
+}
+
+impl crate::NewForConfig for RegistriesConfig {
+    const NAME: &'static str = "kintsu-registries";
+    const ENV: &'static str = "KINTSU_REGISTRIES";
+}
+
+impl RegistriesConfig {
+    /// The endpoints for `registry`, a configured name or otherwise a url. Registries declared
+    /// in `manifest` take precedence over global ones.
+    pub fn resolve(
+        &self,
+        manifest: &NamedRegistries,
+        registry: Option<&str>,
+    ) -> RegistryEndpoints {
+        let Some(registry) = registry else {
+            return RegistryEndpoints::new(DEFAULT_REGISTRY_URL);
+        };
+
+        manifest
+            .get(registry)
+            .or_else(|| self.registries.get(registry))
+            .cloned()
+            .unwrap_or_else(|| RegistryEndpoints::new(registry))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn manifest_registries_take_precedence() {
+        let global = RegistriesConfig {
+            registries: BTreeMap::from([
+                (
+                    "internal".to_string(),
+                    RegistryEndpoints {
+                        url: "https://global.example.com".into(),
+                        mirrors: vec!["https://mirror.example.com".into()],
+                    },
+                ),
+                (
+                    "shared".to_string(),
+                    RegistryEndpoints::new("https://shared.example.com"),
+                ),
+            ]),
+        };
+        let manifest = BTreeMap::from([(
+            "internal".to_string(),
+            RegistryEndpoints::new("https://local.example.com"),
+        )]);
+
+        assert_eq!(
+            global
+                .resolve(&manifest, Some("internal"))
+                .url,
+            "https://local.example.com"
+        );
+        assert_eq!(
+            global.resolve(&manifest, Some("shared")).url,
+            "https://shared.example.com"
+        );
+        assert_eq!(
+            global
+                .resolve(&manifest, Some("https://other.example.com"))
+                .url,
+            "https://other.example.com"
+        );
+        assert_eq!(global.resolve(&manifest, None).url, DEFAULT_REGISTRY_URL);
+    }
+
+    #[test]
+    fn mirrors_must_be_urls() {
+        let endpoints = RegistryEndpoints {
+            url: "https://registry.example.com".into(),
+            mirrors: vec!["not a url".into()],
+        };
+        assert!(endpoints.validate().is_err());
+    }
+}
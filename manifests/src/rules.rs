@@ -1,4 +1,34 @@
-use std::{collections::BTreeMap, hash::Hash};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    hash::Hash,
+};
+
+/// The groups lint rules are filed under, keying the `[lint.overrides]` table of a manifest.
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize, Clone, Copy, Debug,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleGroup {
+    /// The shape of declarations, e.g. types which cannot be constructed
+    Form,
+    /// The names given to declarations
+    Naming,
+    /// The size of declarations, e.g. structs with many fields
+    Complexity,
+    /// The documentation of declarations, e.g. doc links naming nothing
+    Docs,
+}
+
+impl RuleGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Form => "form",
+            Self::Naming => "naming",
+            Self::Complexity => "complexity",
+            Self::Docs => "docs",
+        }
+    }
+}
 
 #[derive(PartialEq, PartialOrd, serde::Serialize, serde::Deserialize, Default, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
@@ -53,20 +83,42 @@ pub trait WithRule {
     }
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct RuleOverrides {
-    #[serde(default)]
-    level: Option<RuleLevel>,
-    #[serde(default)]
-    fix: Option<Fix>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<RuleLevel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Fix>,
+    /// The threshold of rules which limit a count, e.g. the fields of a struct
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<usize>,
+    /// The threshold of rules which require a share, e.g. the percentage of items documented
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<usize>,
 }
 
-#[derive(serde::Deserialize, validator::Validate)]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, validator::Validate)]
 pub struct RuleConfig<RuleGroup: Ord + Hash> {
-    #[serde(default = "BTreeMap::new")]
+    #[serde(default = "BTreeMap::new", skip_serializing_if = "BTreeMap::is_empty")]
     pub overrides: BTreeMap<RuleGroup, BTreeMap<String, RuleOverrides>>,
 }
 
+impl<RuleGroup: Ord + Hash> RuleConfig<RuleGroup> {
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// The overrides set for the rule `name` in `group`, if any.
+    pub fn get(
+        &self,
+        group: &RuleGroup,
+        name: &str,
+    ) -> Option<&RuleOverrides> {
+        self.overrides.get(group)?.get(name)
+    }
+}
+
 impl<RuleGroup: Ord + Hash> Default for RuleConfig<RuleGroup> {
     fn default() -> Self {
         Self {
@@ -75,6 +127,193 @@ impl<RuleGroup: Ord + Hash> Default for RuleConfig<RuleGroup> {
     }
 }
 
+/// Casing conventions for names.
+#[derive(PartialEq, Eq, serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum NameCase {
+    /// `PascalCase`
+    Pascal,
+    /// `camelCase`
+    Camel,
+    /// `snake_case`
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+}
+
+/// The casing expected of each kind of name by the `naming` rules, under `[lint.naming]`.
+#[derive(PartialEq, Eq, serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NamingConventions {
+    #[serde(default = "NamingConventions::default_types")]
+    pub types: NameCase,
+    #[serde(default = "NamingConventions::default_fields")]
+    pub fields: NameCase,
+    #[serde(default = "NamingConventions::default_operations")]
+    pub operations: NameCase,
+    #[serde(default = "NamingConventions::default_variants")]
+    pub variants: NameCase,
+}
+
+impl NamingConventions {
+    fn default_types() -> NameCase {
+        NameCase::Pascal
+    }
+
+    fn default_fields() -> NameCase {
+        NameCase::Snake
+    }
+
+    fn default_operations() -> NameCase {
+        NameCase::Snake
+    }
+
+    fn default_variants() -> NameCase {
+        NameCase::ScreamingSnake
+    }
+
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl Default for NamingConventions {
+    fn default() -> Self {
+        Self {
+            types: Self::default_types(),
+            fields: Self::default_fields(),
+            operations: Self::default_operations(),
+            variants: Self::default_variants(),
+        }
+    }
+}
+
+/// A code generation backend, whose reserved words declared names are checked against.
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize, Clone, Copy, Debug,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    Rust,
+    Typescript,
+    Python,
+    Graphql,
+    Sql,
+}
+
+impl Backend {
+    /// The name of the language, as written in diagnostics.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Rust => "Rust",
+            Self::Typescript => "TypeScript",
+            Self::Python => "Python",
+            Self::Graphql => "GraphQL",
+            Self::Sql => "SQL",
+        }
+    }
+}
+
+/// Classes of characters a name may be written with.
+#[derive(PartialEq, Eq, serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CharClass {
+    /// Unicode letters, category `L`
+    Letter,
+    /// Unicode decimal digits, category `Nd`
+    Digit,
+    /// `_`
+    Underscore,
+}
+
+impl CharClass {
+    pub fn contains(
+        &self,
+        c: char,
+    ) -> bool {
+        match self {
+            Self::Letter => c.is_alphabetic(),
+            Self::Digit => c.is_numeric(),
+            Self::Underscore => c == '_',
+        }
+    }
+}
+
+/// What the `naming` rules allow in declared names, under `[lint.identifiers]`.
+#[derive(PartialEq, Eq, serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct IdentifierPolicy {
+    /// The longest name allowed, in characters
+    #[serde(default = "IdentifierPolicy::default_max_length")]
+    pub max_length: usize,
+    /// The characters names may be written with
+    #[serde(default = "IdentifierPolicy::default_chars")]
+    pub chars: Vec<CharClass>,
+    /// The backends whose reserved words names must avoid
+    #[serde(default)]
+    pub backends: Vec<Backend>,
+}
+
+impl IdentifierPolicy {
+    fn default_max_length() -> usize {
+        64
+    }
+
+    fn default_chars() -> Vec<CharClass> {
+        vec![CharClass::Letter, CharClass::Digit, CharClass::Underscore]
+    }
+
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Whether `c` is in one of the allowed classes.
+    pub fn allows(
+        &self,
+        c: char,
+    ) -> bool {
+        self.chars
+            .iter()
+            .any(|class| class.contains(c))
+    }
+}
+
+impl Default for IdentifierPolicy {
+    fn default() -> Self {
+        Self {
+            max_length: Self::default_max_length(),
+            chars: Self::default_chars(),
+            backends: Vec::new(),
+        }
+    }
+}
+
+/// The `[lint]` table of a package manifest.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, validator::Validate)]
+pub struct LintConfig {
+    #[serde(flatten)]
+    pub rules: RuleConfig<RuleGroup>,
+
+    #[serde(default, skip_serializing_if = "NamingConventions::is_default")]
+    pub naming: NamingConventions,
+
+    #[serde(default, skip_serializing_if = "IdentifierPolicy::is_default")]
+    pub identifiers: IdentifierPolicy,
+
+    /// Codes of warnings which do not fail a build denying warnings, e.g. `KLT8001`
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub allow_warnings: BTreeSet<String>,
+}
+
+impl LintConfig {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+            && self.naming.is_default()
+            && self.identifiers.is_default()
+            && self.allow_warnings.is_empty()
+    }
+}
+
 #[macro_export]
 macro_rules! rule_config {
     ($name: literal for $group_ty: ty $({$($cfg: ident: $t: ty), + $(,)?})?) => {
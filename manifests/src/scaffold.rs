@@ -0,0 +1,489 @@
+//! Scaffolding new packages from templates.
+//!
+//! `kintsu init` writes a manifest and the files of a [`Template`]. The built-in templates
+//! are `lib`, an empty library, `service`, a namespace of operations with its error type,
+//! and `events`, a namespace of event payloads. Each can add example types and operations
+//! on request.
+//!
+//! Organizations add their own starters as directories, one per template, which a
+//! [`TemplateRegistry`] loads alongside the built-ins:
+//!
+//! ```text
+//! templates/
+//!   billing/
+//!     template.toml        # description = "a billing service"
+//!     schema/lib.ks
+//!     examples/schema/lib.ks
+//! ```
+//!
+//! Files under `examples/` are only written when examples are requested, replacing the file
+//! at the same path. `{{name}}` and `{{namespace}}` in any file are replaced with the name
+//! of the package and its root namespace.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use convert_case::{Case, Casing};
+
+use crate::{config::NewForNamed, package::PackageManifests, workspace::WorkspaceManifests};
+
+/// The file describing a template loaded from a directory.
+const TEMPLATE_MANIFEST: &str = "template.toml";
+
+/// The directory holding the example files of a template loaded from a directory.
+const EXAMPLES_DIR: &str = "examples";
+
+/// The template `kintsu init` uses unless another is selected.
+pub const DEFAULT_TEMPLATE: &str = "lib";
+
+/// The files a new package starts from, relative to the package directory.
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub description: String,
+    files: BTreeMap<PathBuf, String>,
+    examples: BTreeMap<PathBuf, String>,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct TemplateMeta {
+    #[serde(default)]
+    description: String,
+}
+
+impl Template {
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            files: BTreeMap::new(),
+            examples: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a file written for every package.
+    pub fn file(
+        mut self,
+        path: impl Into<PathBuf>,
+        contents: impl Into<String>,
+    ) -> Self {
+        self.files
+            .insert(path.into(), contents.into());
+        self
+    }
+
+    /// Adds a file written when examples are requested, replacing the file at the same path.
+    pub fn example(
+        mut self,
+        path: impl Into<PathBuf>,
+        contents: impl Into<String>,
+    ) -> Self {
+        self.examples
+            .insert(path.into(), contents.into());
+        self
+    }
+
+    /// Loads the template in `dir`, named after the directory.
+    pub fn from_dir(dir: &Path) -> crate::Result<Self> {
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let meta_path = dir.join(TEMPLATE_MANIFEST);
+        let meta: TemplateMeta = if meta_path.exists() {
+            toml::from_str(&std::fs::read_to_string(&meta_path)?)
+                .map_err(crate::Error::from_with_source_init(&meta_path))?
+        } else {
+            TemplateMeta::default()
+        };
+
+        let mut template = Self::new(name, meta.description);
+        for path in files_in(dir)? {
+            let relative = path
+                .strip_prefix(dir)
+                .expect("files are found under the template")
+                .to_path_buf();
+            let contents = std::fs::read_to_string(&path)?;
+            match relative.strip_prefix(EXAMPLES_DIR) {
+                Ok(example) => template = template.example(example, contents),
+                Err(_) if relative == Path::new(TEMPLATE_MANIFEST) => {},
+                Err(_) => template = template.file(relative, contents),
+            }
+        }
+        Ok(template)
+    }
+
+    /// The files of a package named `name`, by their path in the package directory.
+    pub fn render(
+        &self,
+        name: &str,
+        examples: bool,
+    ) -> BTreeMap<PathBuf, String> {
+        let namespace = name.to_case(Case::Snake);
+        let mut files = self.files.clone();
+        if examples {
+            files.extend(self.examples.clone());
+        }
+        files
+            .into_iter()
+            .map(|(path, contents)| {
+                let contents = contents
+                    .replace("{{name}}", name)
+                    .replace("{{namespace}}", &namespace);
+                (path, contents)
+            })
+            .collect()
+    }
+
+    /// Whether the template has example files.
+    pub fn has_examples(&self) -> bool {
+        !self.examples.is_empty()
+    }
+}
+
+/// Every file under `dir`, recursively.
+fn files_in(dir: &Path) -> crate::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(files_in(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// The templates available to `kintsu init`, by name.
+#[derive(Debug, Clone)]
+pub struct TemplateRegistry {
+    templates: BTreeMap<String, Template>,
+}
+
+impl Default for TemplateRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl TemplateRegistry {
+    /// The templates which ship with kintsu.
+    pub fn builtin() -> Self {
+        let mut registry = Self {
+            templates: BTreeMap::new(),
+        };
+        registry.register(Template::new(DEFAULT_TEMPLATE, "an empty library").file(
+            "schema/lib.ks",
+            include_str!("../templates/lib/schema/lib.ks"),
+        ));
+        registry.register(
+            Template::new(
+                "service",
+                "a service API: operations and the errors they fail with",
+            )
+            .file(
+                "schema/lib.ks",
+                include_str!("../templates/service/schema/lib.ks"),
+            )
+            .file(
+                "schema/api.ks",
+                include_str!("../templates/service/schema/api.ks"),
+            )
+            .example(
+                "schema/api.ks",
+                include_str!("../templates/service/examples/schema/api.ks"),
+            ),
+        );
+        registry.register(
+            Template::new("events", "an event schema: payloads published to consumers")
+                .file(
+                    "schema/lib.ks",
+                    include_str!("../templates/events/schema/lib.ks"),
+                )
+                .file(
+                    "schema/events.ks",
+                    include_str!("../templates/events/schema/events.ks"),
+                )
+                .example(
+                    "schema/events.ks",
+                    include_str!("../templates/events/examples/schema/events.ks"),
+                ),
+        );
+        registry
+    }
+
+    /// Adds `template`, replacing any template of the same name.
+    pub fn register(
+        &mut self,
+        template: Template,
+    ) {
+        self.templates
+            .insert(template.name.clone(), template);
+    }
+
+    /// Registers each directory in `dir` as a template.
+    pub fn load_dir(
+        &mut self,
+        dir: &Path,
+    ) -> crate::Result<()> {
+        let mut dirs = std::fs::read_dir(dir)
+            .map_err(|err| crate::Error::from(err).with_source(dir))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        dirs.sort();
+        for dir in dirs.into_iter().filter(|dir| dir.is_dir()) {
+            self.register(Template::from_dir(&dir)?);
+        }
+        Ok(())
+    }
+
+    pub fn get(
+        &self,
+        name: &str,
+    ) -> crate::Result<&Template> {
+        self.templates.get(name).ok_or_else(|| {
+            crate::Error::UnknownTemplate {
+                name: name.to_string(),
+                available: self
+                    .templates
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }
+        })
+    }
+
+    pub fn templates(&self) -> impl Iterator<Item = &Template> {
+        self.templates.values()
+    }
+}
+
+/// What `kintsu init` creates.
+#[derive(Debug, Clone)]
+pub struct InitOptions {
+    pub name: String,
+    /// The package directory. Defaults to a directory named after the package, placed with
+    /// the members of the workspace the current directory is in, if any.
+    pub dir: Option<PathBuf>,
+    pub template: String,
+    /// Whether to add the example types and operations of the template
+    pub examples: bool,
+}
+
+impl InitOptions {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            dir: None,
+            template: DEFAULT_TEMPLATE.into(),
+            examples: false,
+        }
+    }
+}
+
+/// Where a package named `name` is created when no directory is given: next to the members
+/// of the workspace `cwd` is in, e.g. `packages/<name>` for members `packages/*`, otherwise
+/// in `cwd`.
+pub fn default_dir(
+    cwd: &Path,
+    name: &str,
+) -> PathBuf {
+    let fs = kintsu_fs::physical::Physical;
+    let workspace = if WorkspaceManifests::is_workspace(&fs, cwd) {
+        WorkspaceManifests::new(&fs, cwd)
+            .ok()
+            .map(|manifest| (cwd.to_path_buf(), manifest))
+    } else {
+        WorkspaceManifests::find(&fs, cwd)
+            .ok()
+            .flatten()
+    };
+
+    let members_dir = workspace.and_then(|(root, manifest)| {
+        manifest
+            .workspace()
+            .members
+            .iter()
+            .find_map(|member| {
+                member
+                    .strip_suffix("/*")
+                    .filter(|parent| !parent.contains(['*', '?', '[']))
+                    .map(|parent| root.join(parent))
+            })
+    });
+    members_dir
+        .unwrap_or_else(|| cwd.to_path_buf())
+        .join(name)
+}
+
+/// Creates a package from the template `options` selects, returning its directory. Files
+/// which already exist are left as they are.
+pub fn init(
+    options: &InitOptions,
+    templates: &TemplateRegistry,
+) -> crate::Result<PathBuf> {
+    use validator::Validate;
+
+    let template = templates.get(&options.template)?;
+    let pkg = PackageManifests::V1(crate::package::PackageManifest {
+        package: crate::package::PackageMeta {
+            name: options.name.clone(),
+            description: None,
+            version: crate::version::VersionSerde(crate::version::parse_version("0.1.0")?),
+            authors: vec![],
+            keywords: vec![],
+            homepage: None,
+            license: None,
+            readme: None,
+            repository: None,
+            metadata: Default::default(),
+            embed_metadata: false,
+        },
+        dependencies: Default::default(),
+        files: Default::default(),
+        fmt: None,
+        lint: Default::default(),
+        registries: Default::default(),
+        profiles: Default::default(),
+        hooks: Default::default(),
+    });
+
+    pkg.validate()?;
+
+    let dir = match &options.dir {
+        Some(dir) => dir.clone(),
+        None => default_dir(Path::new("."), &options.name),
+    };
+    std::fs::create_dir_all(&dir)?;
+
+    let out = toml::to_string(&pkg)?;
+    std::fs::write(PackageManifests::path(&dir), out)?;
+
+    for (path, contents) in template.render(&options.name, options.examples) {
+        let path = dir.join(path);
+        if path.exists() {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)?;
+    }
+
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders_and_examples() {
+        let template = TemplateRegistry::builtin()
+            .get("service")
+            .unwrap()
+            .clone();
+
+        let files = template.render("my-shop", false);
+        assert_eq!(
+            files[Path::new("schema/lib.ks")],
+            "#![version(1)]\nnamespace my_shop;\n\nuse api;\n"
+        );
+        assert!(
+            files[Path::new("schema/api.ks")].starts_with("/// The operations my-shop serves.")
+        );
+        assert!(!files[Path::new("schema/api.ks")].contains("operation get_user"));
+
+        let files = template.render("my-shop", true);
+        assert!(
+            files[Path::new("schema/api.ks")].contains("operation get_user(id: i64) -> User!;")
+        );
+    }
+
+    #[test]
+    fn loads_templates_from_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let billing = dir.path().join("billing");
+        std::fs::create_dir_all(billing.join("examples/schema")).unwrap();
+        std::fs::create_dir_all(billing.join("schema")).unwrap();
+        std::fs::write(billing.join("template.toml"), "description = \"billing\"").unwrap();
+        std::fs::write(billing.join("schema/lib.ks"), "namespace {{namespace}};").unwrap();
+        std::fs::write(
+            billing.join("examples/schema/lib.ks"),
+            "namespace {{namespace}};\nstruct Invoice { id: i64 };",
+        )
+        .unwrap();
+
+        let mut registry = TemplateRegistry::builtin();
+        registry.load_dir(dir.path()).unwrap();
+        let template = registry.get("billing").unwrap();
+        assert_eq!(template.description, "billing");
+        assert!(template.has_examples());
+        assert_eq!(
+            template.render("acme-billing", false),
+            BTreeMap::from([(
+                PathBuf::from("schema/lib.ks"),
+                "namespace acme_billing;".to_string()
+            )])
+        );
+
+        let err = registry.get("missing").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown template 'missing', expected one of: billing, events, lib, service"
+        );
+    }
+
+    #[test]
+    fn places_packages_with_workspace_members() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("schema.toml"),
+            "version = \"v1\"\n[workspace]\nmembers = [\"packages/*\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            default_dir(dir.path(), "shop"),
+            dir.path().join("packages/shop")
+        );
+
+        let nested = dir.path().join("packages");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(
+            default_dir(&nested, "shop"),
+            dir.path().join("packages/shop")
+        );
+
+        let outside = tempfile::tempdir().unwrap();
+        assert_eq!(
+            default_dir(outside.path(), "shop"),
+            outside.path().join("shop")
+        );
+    }
+
+    #[test]
+    fn scaffolds_a_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut options = InitOptions::new("shop");
+        options.dir = Some(dir.path().join("shop"));
+        options.template = "events".into();
+        options.examples = true;
+
+        let created = init(&options, &TemplateRegistry::builtin()).unwrap();
+        let manifest = std::fs::read_to_string(created.join("schema.toml")).unwrap();
+        assert!(manifest.contains("name = \"shop\""));
+        assert!(
+            std::fs::read_to_string(created.join("schema/events.ks"))
+                .unwrap()
+                .contains("oneof Event")
+        );
+    }
+}
@@ -87,6 +87,80 @@ impl VersionExt for Version {
     }
 }
 
+/// The part of a version a release increments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Bump {
+    /// `version` with this part incremented and the parts below it reset. A pre-release is
+    /// released instead when it already is a pre-release of that version, e.g. a minor bump
+    /// of `1.3.0-rc.1` is `1.3.0`.
+    pub fn apply(
+        self,
+        version: &Version,
+    ) -> Version {
+        let releases_pre = !version.pre.is_empty()
+            && match self {
+                Self::Patch => true,
+                Self::Minor => version.patch == 0,
+                Self::Major => version.minor == 0 && version.patch == 0,
+            };
+
+        let mut next = Version::new(version.major, version.minor, version.patch);
+        if releases_pre {
+            return next;
+        }
+        match self {
+            Self::Patch => next.patch += 1,
+            Self::Minor => {
+                next.minor += 1;
+                next.patch = 0;
+            },
+            Self::Major => {
+                next.major += 1;
+                next.minor = 0;
+                next.patch = 0;
+            },
+        }
+        next
+    }
+
+    /// The smallest bump after `previous` for a release which makes `breaking` changes, or
+    /// only `adds` to what it declares. For `0.x` versions a minor bump is breaking and a
+    /// patch may add items.
+    pub fn required(
+        previous: &Version,
+        breaking: bool,
+        adds: bool,
+    ) -> Self {
+        match (previous.major, breaking, adds) {
+            (0, true, _) => Self::Minor,
+            (_, true, _) => Self::Major,
+            (0, false, _) => Self::Patch,
+            (_, false, true) => Self::Minor,
+            (_, false, false) => Self::Patch,
+        }
+    }
+}
+
+impl Display for Bump {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        })
+    }
+}
+
 /// Wrapper for serde serialization/deserialization of Version
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct VersionSerde(pub Version);
@@ -380,6 +454,41 @@ mod db {
 mod test {
     use super::*;
 
+    #[test_case::test_case("1.2.3", Bump::Patch, "1.2.4"; "patch")]
+    #[test_case::test_case("1.2.3", Bump::Minor, "1.3.0"; "minor")]
+    #[test_case::test_case("1.2.3+build.4", Bump::Major, "2.0.0"; "major drops build")]
+    #[test_case::test_case("1.3.0-rc.1", Bump::Minor, "1.3.0"; "releases minor pre-release")]
+    #[test_case::test_case("1.3.1-rc.1", Bump::Minor, "1.4.0"; "bumps past patch pre-release")]
+    #[test_case::test_case("2.0.0-rc.1", Bump::Major, "2.0.0"; "releases major pre-release")]
+    fn bumps_versions(
+        version: &str,
+        bump: Bump,
+        expected: &str,
+    ) {
+        assert_eq!(
+            bump.apply(&parse_version(version).unwrap())
+                .to_string(),
+            expected
+        );
+    }
+
+    #[test_case::test_case("1.2.3", true, true, Bump::Major; "breaking")]
+    #[test_case::test_case("1.2.3", false, true, Bump::Minor; "additive")]
+    #[test_case::test_case("1.2.3", false, false, Bump::Patch; "unchanged")]
+    #[test_case::test_case("0.2.3", true, false, Bump::Minor; "unstable breaking")]
+    #[test_case::test_case("0.2.3", false, true, Bump::Patch; "unstable additive")]
+    fn requires_bumps(
+        previous: &str,
+        breaking: bool,
+        adds: bool,
+        expected: Bump,
+    ) {
+        assert_eq!(
+            Bump::required(&parse_version(previous).unwrap(), breaking, adds),
+            expected
+        );
+    }
+
     #[test_case::test_case("0.1.0"; "stable version")]
     #[test_case::test_case("1.2.3"; "full version")]
     #[test_case::test_case("0.1.0-rc.0"; "rc prerelease")]
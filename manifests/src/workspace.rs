@@ -0,0 +1,340 @@
+//! Workspace manifests.
+//!
+//! A workspace is a directory whose `schema.toml` has a `[workspace]` table instead of a
+//! `[package]` table. It lists its member packages by glob, declares dependencies which
+//! members inherit with `name = { workspace = true }`, and owns the single lockfile shared
+//! by every member.
+//!
+//! ```toml
+//! version = "v1"
+//!
+//! [workspace]
+//! members = ["packages/*"]
+//! exclude = ["packages/legacy"]
+//!
+//! [workspace.dependencies]
+//! common = { path = "packages/common", version = "^0.2.0" }
+//! money = { version = "^1.4" }
+//! ```
+
+use std::path::{Component, Path, PathBuf};
+
+use validator::Validate;
+
+use crate::{
+    InvalidManifest,
+    config::NewForNamed,
+    package::{Dependency, NamedDependencies, PackageManifests},
+};
+
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+#[serde(tag = "version", rename_all = "snake_case")]
+pub enum WorkspaceManifests {
+    V1(WorkspaceManifest),
+}
+
+impl Validate for WorkspaceManifests {
+    fn validate(&self) -> Result<(), validator::ValidationErrors> {
+        match self {
+            WorkspaceManifests::V1(manifest) => manifest.validate(),
+        }
+    }
+}
+
+impl NewForNamed for WorkspaceManifests {
+    const NAME: &str = "schema.toml";
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, validator::Validate)]
+pub struct WorkspaceManifest {
+    #[validate(nested)]
+    pub workspace: WorkspaceConfig,
+
+    /// Formatter settings shared by every member
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub fmt: Option<crate::fmt::FormatOverrides>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, validator::Validate)]
+pub struct WorkspaceConfig {
+    /// Globs matching member package directories, relative to the workspace root
+    #[validate(length(min = 1))]
+    pub members: Vec<String>,
+
+    /// Globs of directories left out of `members`
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Dependencies shared by members. Paths are relative to the workspace root
+    #[serde(default = "NamedDependencies::new")]
+    pub dependencies: NamedDependencies,
+}
+
+impl WorkspaceManifests {
+    pub fn workspace(&self) -> &WorkspaceConfig {
+        match self {
+            WorkspaceManifests::V1(manifest) => &manifest.workspace,
+        }
+    }
+
+    /// Whether the manifest in `root_dir` declares a workspace rather than a package.
+    pub fn is_workspace(
+        fs: &dyn kintsu_fs::FileSystem,
+        root_dir: impl AsRef<Path>,
+    ) -> bool {
+        fs.read_to_string_sync(&Self::path(root_dir))
+            .ok()
+            .and_then(|data| toml::from_str::<toml::Table>(&data).ok())
+            .is_some_and(|table| table.contains_key("workspace"))
+    }
+
+    /// Finds the closest workspace above `package_dir`, returning its root and manifest.
+    pub fn find(
+        fs: &dyn kintsu_fs::FileSystem,
+        package_dir: &Path,
+    ) -> crate::Result<Option<(PathBuf, Self)>> {
+        let mut candidates: Vec<PathBuf> = package_dir
+            .ancestors()
+            .skip(1)
+            .map(Path::to_path_buf)
+            .collect();
+
+        // relative paths run out of ancestors at the working directory
+        if package_dir.is_relative()
+            && let Ok(absolute) = std::path::absolute(package_dir)
+        {
+            candidates.extend(
+                absolute
+                    .ancestors()
+                    .skip(1)
+                    .map(Path::to_path_buf),
+            );
+        }
+
+        for candidate in candidates {
+            if Self::is_workspace(fs, &candidate) {
+                let manifest = Self::new(fs, &candidate)?;
+                return Ok(Some((candidate, manifest)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Directories of every member package, sorted.
+    pub fn member_dirs(
+        &self,
+        fs: &dyn kintsu_fs::FileSystem,
+        root_dir: impl AsRef<Path>,
+    ) -> crate::Result<Vec<PathBuf>> {
+        let root_dir = root_dir.as_ref();
+        let manifest_glob = |pattern: &String| {
+            root_dir
+                .join(pattern)
+                .join(PackageManifests::NAME)
+                .display()
+                .to_string()
+        };
+
+        let workspace = self.workspace();
+        let include: Vec<String> = workspace
+            .members
+            .iter()
+            .map(manifest_glob)
+            .collect();
+        let exclude: Vec<String> = workspace
+            .exclude
+            .iter()
+            .map(manifest_glob)
+            .collect();
+
+        let mut dirs: Vec<PathBuf> = fs
+            .find_glob(&include, &exclude)?
+            .into_iter()
+            .filter_map(|manifest| manifest.parent().map(Path::to_path_buf))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        Ok(dirs)
+    }
+
+    /// Replaces the `{ workspace = true }` dependencies of the member in `member_dir` with
+    /// the workspace's declaration, rebasing paths onto the member.
+    pub fn inherit(
+        &self,
+        root_dir: &Path,
+        member_dir: &Path,
+        manifest: &mut PackageManifests,
+    ) -> crate::Result<()> {
+        let shared = &self.workspace().dependencies;
+        let to_root = relative_to_root(root_dir, member_dir);
+
+        for (name, dep) in manifest.dependencies_mut().iter_mut() {
+            if !dep.is_workspace() {
+                continue;
+            }
+
+            let mut inherited = shared
+                .get(name)
+                .filter(|dep| !matches!(dep, Dependency::Workspace(_)))
+                .cloned()
+                .ok_or_else(|| {
+                    InvalidManifest::UnknownWorkspaceDependency { name: name.clone() }
+                })?;
+
+            match &mut inherited {
+                Dependency::Path(path) => rebase(&to_root, &mut path.path),
+                Dependency::PathWithRemote(pwr) => rebase(&to_root, &mut pwr.path.path),
+                Dependency::Remote(_) | Dependency::Git(_) | Dependency::Workspace(_) => {},
+            }
+            *dep = inherited;
+        }
+        Ok(())
+    }
+}
+
+/// The path from `member_dir` back up to `root_dir`, e.g. `../..` for `root/packages/a`.
+fn relative_to_root(
+    root_dir: &Path,
+    member_dir: &Path,
+) -> PathBuf {
+    let below = member_dir
+        .strip_prefix(root_dir)
+        .ok()
+        .map(Path::to_path_buf)
+        .or_else(|| {
+            let root = std::path::absolute(root_dir).ok()?;
+            let member = std::path::absolute(member_dir).ok()?;
+            member
+                .strip_prefix(root)
+                .ok()
+                .map(Path::to_path_buf)
+        })
+        .unwrap_or_default();
+
+    below
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .map(|_| Component::ParentDir)
+        .collect()
+}
+
+fn rebase(
+    to_root: &Path,
+    path: &mut PathBuf,
+) {
+    if path.is_relative() {
+        *path = to_root.join(&*path);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use kintsu_fs::memory::MemoryFileSystem;
+
+    use super::*;
+    use crate::config::NewForNamed;
+
+    const WORKSPACE: &str = r#"
+version = "v1"
+
+[workspace]
+members = ["packages/*"]
+exclude = ["packages/legacy"]
+
+[workspace.dependencies]
+common = { path = "packages/common", version = "^0.2.0" }
+money = { version = "^1.4" }
+"#;
+
+    fn member(
+        name: &str,
+        dependencies: &str,
+    ) -> String {
+        format!(
+            "version = \"v1\"\n\n[package]\nname = \"{name}\"\nversion = \"0.2.0\"\n\n[dependencies]\n{dependencies}"
+        )
+    }
+
+    fn fs() -> MemoryFileSystem {
+        MemoryFileSystem::with_files(vec![
+            ("schema.toml", WORKSPACE.to_string()),
+            ("packages/common/schema.toml", member("common", "")),
+            (
+                "packages/api/schema.toml",
+                member(
+                    "api",
+                    "common = { workspace = true }\nmoney = { workspace = true }\n",
+                ),
+            ),
+            ("packages/legacy/schema.toml", member("legacy", "")),
+        ])
+    }
+
+    #[test]
+    fn lists_members() {
+        let fs = fs();
+        assert!(WorkspaceManifests::is_workspace(&fs, ""));
+        assert!(!WorkspaceManifests::is_workspace(&fs, "packages/api"));
+
+        let workspace = WorkspaceManifests::new(&fs, "").unwrap();
+        assert_eq!(
+            workspace.member_dirs(&fs, "").unwrap(),
+            [
+                PathBuf::from("packages/api"),
+                PathBuf::from("packages/common")
+            ]
+        );
+    }
+
+    #[test]
+    fn members_inherit_dependencies() {
+        let fs = fs();
+        let manifest = PackageManifests::new(&fs, "packages/api").unwrap();
+        let deps = manifest.dependencies();
+
+        assert_eq!(
+            deps["common"].path(),
+            Some(Path::new("../../packages/common"))
+        );
+        assert_eq!(deps["common"].version().unwrap().to_string(), "^0.2.0");
+        assert!(matches!(deps["money"], Dependency::Remote(_)));
+    }
+
+    #[test]
+    fn inheriting_outside_a_workspace_fails() {
+        let fs = MemoryFileSystem::with_files(vec![(
+            "api/schema.toml",
+            member("api", "common = { workspace = true }\n"),
+        )]);
+        let err = PackageManifests::new(&fs, "api")
+            .err()
+            .unwrap();
+        assert!(
+            matches!(
+                err,
+                crate::Error::ManifestError(InvalidManifest::MissingWorkspace { ref name }) if name == "common"
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn inheriting_undeclared_dependency_fails() {
+        let fs = fs();
+        fs.add_file(
+            "packages/api/schema.toml",
+            member("api", "other = { workspace = true }\n"),
+        );
+        let err = PackageManifests::new(&fs, "packages/api")
+            .err()
+            .unwrap();
+        assert!(
+            matches!(
+                err,
+                crate::Error::ManifestError(InvalidManifest::UnknownWorkspaceDependency { ref name }) if name == "other"
+            ),
+            "{err}"
+        );
+    }
+}
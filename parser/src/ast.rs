@@ -1,6 +1,7 @@
 pub mod anonymous;
 pub mod array;
 pub mod comment;
+pub mod constant;
 pub mod enm;
 pub mod err;
 pub mod import;
@@ -16,6 +17,7 @@ pub mod ty_def;
 pub mod type_expr;
 pub mod union;
 pub mod variadic;
+pub mod vis;
 
 use std::{path::Path, sync::Arc};
 
@@ -33,14 +35,18 @@ pub struct AstStream {
     pub module_comments: CommentStream,
     pub module_meta: Spanned<meta::ItemMeta>,
     pub nodes: Vec<Spanned<items::Items>>,
+    /// Comments after the last item
+    #[serde(default)]
+    pub trailing_comments: CommentStream,
 }
 
 impl crate::Parse for AstStream {
     fn parse(stream: &mut crate::tokens::TokenStream) -> AstResult<Self> {
         Ok(Self {
-            module_comments: CommentStream::parse(stream)?,
+            module_comments: CommentStream::parse_leading(stream)?,
             module_meta: stream.parse()?,
             nodes: Vec::parse(stream)?,
+            trailing_comments: CommentStream::parse(stream)?,
         })
     }
 }
@@ -97,7 +103,10 @@ impl AstStream {
     }
 
     pub async fn from_file(path: impl AsRef<Path>) -> miette::Result<Self> {
-        let data = tokio::fs::read_to_string(path.as_ref())
+        use kintsu_fs::FileSystem;
+
+        let data = kintsu_fs::physical::Physical
+            .read_to_string(path.as_ref())
             .await
             .into_diagnostic()?;
 
@@ -131,9 +140,16 @@ impl crate::tokens::ToTokens for AstStream {
             tt.write(node);
             tt.add_newline();
             if i < self.nodes.len() - 1 {
-                tt.buf.push('\n');
+                // the blank line goes before the indentation of the line just opened
+                let line_start = tt
+                    .buf
+                    .rfind('\n')
+                    .map_or(0, |newline| newline + 1);
+                tt.buf.insert(line_start, '\n');
             }
         }
+
+        tt.write(&self.trailing_comments);
     }
 }
 
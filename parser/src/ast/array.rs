@@ -15,10 +15,56 @@ pub enum Array {
     Sized {
         ty: Box<Spanned<Type>>,
         bracket: Bracket,
-        size: SpannedToken![number],
+        size: ArraySize,
     },
 }
 
+/// The length of a sized array: either a literal or the name of a `const` declared in
+/// the same namespace.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ArraySize {
+    Literal(SpannedToken![number]),
+    Const(SpannedToken![ident]),
+}
+
+impl ArraySize {
+    pub fn display(&self) -> String {
+        match self {
+            Self::Literal(n) => n.borrow_i32().to_string(),
+            Self::Const(c) => c.borrow_string().clone(),
+        }
+    }
+}
+
+impl Peek for ArraySize {
+    fn is(token: &Token) -> bool {
+        <Token![number]>::is(token) || <Token![ident]>::is(token)
+    }
+}
+
+impl Parse for ArraySize {
+    fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, crate::tokens::LexingError> {
+        Ok(if stream.peek::<Token![ident]>() {
+            Self::Const(stream.parse()?)
+        } else {
+            Self::Literal(stream.parse()?)
+        })
+    }
+}
+
+impl ToTokens for ArraySize {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        match self {
+            Self::Literal(n) => tt.write(n),
+            Self::Const(c) => tt.write(c),
+        }
+    }
+}
+
 impl Array {
     pub fn type_name(&self) -> String {
         match self {
@@ -26,7 +72,7 @@ impl Array {
                 format!("{}[]", ty.type_name())
             },
             Self::Sized { ty, size, .. } => {
-                format!("{}[{}]", ty.type_name(), size.borrow_i32())
+                format!("{}[{}]", ty.type_name(), size.display())
             },
         }
     }
@@ -44,9 +90,9 @@ impl Parse for Array {
         let ty = Box::new(stream.parse()?);
         let mut inner;
         let bracket = bracket!(inner in stream);
-        Ok(if inner.peek::<Token![number]>() {
+        Ok(if inner.peek::<ArraySize>() {
             tracing::trace!("parsing sized array");
-            let size = inner.parse()?;
+            let size = ArraySize::parse(&mut inner)?;
             Self::Sized { ty, bracket, size }
         } else {
             tracing::trace!("parsing unsized array");
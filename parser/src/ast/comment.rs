@@ -47,6 +47,16 @@ impl tokens::ToTokens for CommentAst {
     }
 }
 
+impl CommentAst {
+    /// The text of a `///` doc comment, or `None` for any other comment.
+    pub fn doc(&self) -> Option<&str> {
+        match self {
+            Self::SingleLine(cmt) => crate::declare::doc_text(cmt.borrow_string()),
+            Self::MultiLine(_) => None,
+        }
+    }
+}
+
 impl ImplDiagnostic for CommentAst {
     fn fmt() -> &'static str {
         "// <comment> | /* comment */"
@@ -89,6 +99,34 @@ impl Default for CommentStream {
 }
 
 impl CommentStream {
+    /// Peeks for `T` past any comments leading it.
+    pub(crate) fn peek_past<T: tokens::Peek>(stream: &tokens::TokenStream) -> bool {
+        let mut fork = stream.fork();
+        while fork.peek::<CommentAst>() {
+            if fork.parse::<CommentAst>().is_err() {
+                break;
+            }
+        }
+        fork.peek::<T>()
+    }
+
+    /// Parses the comments leading a file, up to the first doc comment, which documents
+    /// the item after it rather than the file.
+    pub(crate) fn parse_leading(
+        stream: &mut tokens::TokenStream
+    ) -> Result<Self, tokens::LexingError> {
+        let mut comments = vec![];
+        while stream.peek::<CommentAst>() {
+            let mut fork = stream.fork();
+            let comment: Spanned<CommentAst> = fork.parse()?;
+            if comment.value.doc().is_some() {
+                break;
+            }
+            comments.push(stream.parse()?);
+        }
+        Ok(Self { comments })
+    }
+
     pub fn new() -> Self {
         Self { comments: vec![] }
     }
@@ -105,6 +143,13 @@ impl CommentStream {
             }
         })
     }
+
+    /// The text of each `///` doc comment.
+    pub fn docs(&self) -> impl Iterator<Item = &str> {
+        self.comments
+            .iter()
+            .filter_map(|it| it.value.doc())
+    }
 }
 
 impl tokens::ToTokens for CommentStream {
@@ -113,7 +158,11 @@ impl tokens::ToTokens for CommentStream {
         tt: &mut crate::fmt::Printer,
     ) {
         for c in &self.comments {
-            c.value.write(tt);
+            tt.leading_trivia(&c.span);
+            // a comment trailing earlier code may already have been written after it
+            if tt.claim_comment(&c.span) {
+                c.value.write(tt);
+            }
         }
     }
 }
@@ -154,4 +203,16 @@ mod test {
     pub fn round_trip(src: &str) {
         crate::tst::round_trip::<CommentStream>(src).unwrap();
     }
+
+    #[test]
+    fn separates_doc_comments() {
+        let mut t = tokenize("// plain\n/// The user\n///\n//// divider\n").unwrap();
+        let parsed: Spanned<CommentStream> = t.parse().unwrap();
+        assert_eq!(parsed.docs().collect::<Vec<_>>(), vec!["The user", ""]);
+
+        let mut t = tokenize("// license\n\n/// The namespace\nnamespace pkg;").unwrap();
+        let leading = CommentStream::parse_leading(&mut t).unwrap();
+        assert_eq!(leading.comments().collect::<Vec<_>>(), vec!["license"]);
+        assert!(t.peek::<CommentSingleLineToken>());
+    }
 }
@@ -0,0 +1,163 @@
+use crate::{
+    SpannedToken, Token,
+    ast::ty::Builtin,
+    defs::Spanned,
+    tokens::{ImplDiagnostic, LexingError, Parse, Peek, ToTokens},
+};
+
+/// A named constant declaration, e.g. `const MAX_PAGE_SIZE: u32 = 100`.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct Constant {
+    pub kw: SpannedToken![const],
+    pub name: SpannedToken![ident],
+    pub colon: SpannedToken![:],
+    pub ty: Spanned<Builtin>,
+    pub eq: SpannedToken![=],
+    pub value: Spanned<ConstValue>,
+}
+
+/// The right hand side of a constant declaration. Identifiers refer to other constants
+/// and are resolved during type resolution.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstValue {
+    Number(SpannedToken![number]),
+    String(SpannedToken![string]),
+    Ident(SpannedToken![ident]),
+}
+
+impl ConstValue {
+    pub fn span(&self) -> kintsu_errors::Span {
+        let sp = match self {
+            Self::Number(n) => n.span(),
+            Self::String(s) => s.span(),
+            Self::Ident(i) => i.span(),
+        };
+        kintsu_errors::Span::new(sp.start, sp.end)
+    }
+}
+
+/// A constant value after all references have been followed.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ConstLiteral {
+    Int(i64),
+    Str(String),
+}
+
+impl ConstLiteral {
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(v) => Some(*v),
+            Self::Str(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ConstLiteral {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            Self::Int(v) => write!(f, "{v}"),
+            Self::Str(s) => write!(f, "\"{s}\""),
+        }
+    }
+}
+
+impl ImplDiagnostic for ConstValue {
+    fn fmt() -> &'static str {
+        "number, string, or constant identifier"
+    }
+}
+
+impl Parse for ConstValue {
+    fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, LexingError> {
+        Ok(if stream.peek::<Token![number]>() {
+            Self::Number(stream.parse()?)
+        } else if stream.peek::<Token![string]>() {
+            Self::String(stream.parse()?)
+        } else if stream.peek::<Token![ident]>() {
+            Self::Ident(stream.parse()?)
+        } else {
+            let expect = vec![
+                <Token![number]>::fmt(),
+                <Token![string]>::fmt(),
+                <Token![ident]>::fmt(),
+            ];
+            return Err(if let Some(next) = stream.next() {
+                LexingError::expected_oneof(expect, next.value)
+            } else {
+                LexingError::empty_oneof(expect)
+            });
+        })
+    }
+}
+
+impl ToTokens for ConstValue {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        match self {
+            Self::Number(n) => tt.write(n),
+            Self::String(s) => tt.write(s),
+            Self::Ident(i) => tt.write(i),
+        }
+    }
+}
+
+impl Parse for Constant {
+    fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, LexingError> {
+        Ok(Self {
+            kw: stream.parse()?,
+            name: stream.parse()?,
+            colon: stream.parse()?,
+            ty: stream.parse()?,
+            eq: stream.parse()?,
+            value: stream.parse()?,
+        })
+    }
+}
+
+impl Peek for Constant {
+    fn is(token: &crate::tokens::Token) -> bool {
+        <Token![const]>::is(token)
+    }
+}
+
+impl ToTokens for Constant {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        tt.write(&self.kw);
+        tt.space();
+        tt.write(&self.name);
+        tt.write(&self.colon);
+        tt.space();
+        tt.write(&self.ty);
+        tt.space();
+        tt.write(&self.eq);
+        tt.space();
+        tt.write(&self.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_case::test_case("const MAX_PAGE_SIZE: u32 = 100"; "int constant")]
+    #[test_case::test_case("const GREETING: str = \"hello\""; "str constant")]
+    #[test_case::test_case("const ALIAS: u32 = MAX_PAGE_SIZE"; "constant reference")]
+    fn round_trip_constant(src: &str) {
+        crate::tst::round_trip::<Constant>(src).unwrap();
+    }
+
+    #[test]
+    fn rejects_type_value() {
+        assert!(crate::tst::basic_smoke::<Constant>("const A: u32 = u8").is_err());
+    }
+}
@@ -5,15 +5,18 @@ use crate::{
 
 use crate::{
     SpannedToken,
-    ast::comment::CommentStream,
+    ast::{comment::CommentStream, constant::ConstLiteral},
     defs::Spanned,
-    tokens::{ImplDiagnostic, Parse, Peek, Repeated, brace},
+    tokens::{ImplDiagnostic, LexingError, Parse, Peek, Repeated, brace},
 };
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct EnumValue<Value: Parse> {
     pub eq: Spanned<EqToken>,
     pub value: Spanned<Value>,
+    /// Alternative values accepted when deserializing, e.g. `Active = 1 | "active"`.
+    #[serde(default)]
+    pub aliases: Vec<EnumAlias>,
 }
 
 impl<Value: Parse> EnumValue<Value> {
@@ -31,10 +34,13 @@ impl<Value: Parse> Peek for EnumValue<Value> {
 
 impl<Value: Parse> Parse for EnumValue<Value> {
     fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, crate::tokens::LexingError> {
-        Ok(Self {
-            eq: stream.parse()?,
-            value: stream.parse()?,
-        })
+        let eq = stream.parse()?;
+        let value = stream.parse()?;
+        let mut aliases = Vec::new();
+        while stream.peek::<Token![|]>() {
+            aliases.push(EnumAlias::parse(stream)?);
+        }
+        Ok(Self { eq, value, aliases })
     }
 }
 
@@ -48,6 +54,75 @@ where
     ) {
         tt.write(&self.eq);
         tt.write(&self.value);
+        for alias in &self.aliases {
+            tt.write(alias);
+        }
+    }
+}
+
+/// A `| value` alias following the value of an enum variant. Aliases of either kind may be
+/// given on int and string enums alike.
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct EnumAlias {
+    pub pipe: SpannedToken![|],
+    pub value: EnumAliasValue,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum EnumAliasValue {
+    Number(SpannedToken![number]),
+    String(SpannedToken![string]),
+}
+
+impl EnumAliasValue {
+    pub fn literal(&self) -> ConstLiteral {
+        match self {
+            Self::Number(n) => ConstLiteral::Int(*n.borrow_i32() as i64),
+            Self::String(s) => ConstLiteral::Str(s.borrow_string().clone()),
+        }
+    }
+
+    pub fn span(&self) -> kintsu_errors::Span {
+        let sp = match self {
+            Self::Number(n) => n.span(),
+            Self::String(s) => s.span(),
+        };
+        kintsu_errors::Span::new(sp.start, sp.end)
+    }
+}
+
+impl Parse for EnumAlias {
+    fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, LexingError> {
+        let pipe = stream.parse()?;
+        let value = if stream.peek::<Token![number]>() {
+            EnumAliasValue::Number(stream.parse()?)
+        } else if stream.peek::<Token![string]>() {
+            EnumAliasValue::String(stream.parse()?)
+        } else {
+            let expect = vec![<Token![number]>::fmt(), <Token![string]>::fmt()];
+            return Err(if let Some(next) = stream.next() {
+                LexingError::expected_oneof(expect, next.value)
+            } else {
+                LexingError::empty_oneof(expect)
+            });
+        };
+        Ok(Self { pipe, value })
+    }
+}
+
+impl ToTokens for EnumAlias {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        tt.space();
+        tt.write(&self.pipe);
+        tt.space();
+        match &self.value {
+            EnumAliasValue::Number(n) => tt.write(n),
+            EnumAliasValue::String(s) => tt.write(s),
+        }
     }
 }
 
@@ -74,6 +149,10 @@ impl<Value: Parse + Peek> Peek for EnumVariant<Value> {
     fn is(token: &crate::tokens::toks::Token) -> bool {
         <Token![ident]>::is(token)
     }
+
+    fn peek(stream: &crate::tokens::TokenStream) -> bool {
+        CommentStream::peek_past::<Token![ident]>(stream)
+    }
 }
 
 impl<Value: Parse + Peek> Parse for EnumVariant<Value> {
@@ -107,6 +186,9 @@ where
             tt.write(&val.eq);
             tt.space();
             tt.write(&val.value);
+            for alias in &val.aliases {
+                tt.write(alias);
+            }
         }
     }
 }
@@ -261,6 +343,20 @@ mod tests {
         assert!(matches!(it, Enum::Str(..)))
     };
     "parses enum variant with str value"
+)]
+    #[test_case::test_case(
+    "enum Status {\n\tActive = 1 | \"active\" | \"enabled\",\n\tInactive = 2 | 0\n}", |it| {
+        let Enum::Int(typed) = it else { panic!("expected int enum") };
+        let aliases: Vec<_> = typed.variants.values.iter().map(|v| v.value.enum_value().unwrap().aliases.len()).collect();
+        assert_eq!(aliases, vec![2, 1]);
+    };
+    "parses int enum variants with aliases"
+)]
+    #[test_case::test_case(
+    "enum Color {\n\tGray = \"gray\" | \"grey\"\n}", |it| {
+        assert!(matches!(it, Enum::Str(..)))
+    };
+    "parses str enum variant with alias"
 )]
     fn test_enum_variant_parse_str(
         input: &str,
@@ -1,9 +1,10 @@
 use crate::{
     ImplDiagnostic, SpannedToken, Token,
     ast::ty::PathOrIdent,
+    bail_unchecked,
     ctx::{RefContext, RefOrItemContext},
     defs::Spanned,
-    tokens::{self, Brace, Parse, Peek, Repeated, ToTokens, Token, brace},
+    tokens::{self, Brace, LBraceToken, Parse, Peek, Repeated, ToTokens, Token, brace},
     utils::guard_schema,
 };
 
@@ -15,7 +16,7 @@ pub enum FinalOrNested {
 
 impl ImplDiagnostic for FinalOrNested {
     fn fmt() -> &'static str {
-        "`object` or `leading::trail::{object, next_object}`"
+        "`object`, `leading::trail::*` or `leading::trail::{object, next_object}`"
     }
 }
 
@@ -23,10 +24,17 @@ impl Parse for FinalOrNested {
     fn parse(stream: &mut tokens::TokenStream) -> Result<Self, tokens::LexingError> {
         let leading = PathOrIdent::parse(stream)?;
 
-        Ok(if stream.peek::<UseWithItems>() {
+        Ok(if stream.peek::<UseGlob>() {
+            Self::Nest(UsePath {
+                leading,
+                items: None,
+                glob: Option::parse(stream)?,
+            })
+        } else if stream.peek::<UseWithItems>() {
             Self::Nest(UsePath {
                 leading,
                 items: Option::parse(stream)?,
+                glob: None,
             })
         } else {
             Self::Final(leading)
@@ -67,6 +75,14 @@ impl Peek for UseWithItems {
     fn is(token: &Token) -> bool {
         <Token![::]>::is(token)
     }
+
+    fn peek(stream: &tokens::TokenStream) -> bool {
+        let mut fork = stream.fork();
+
+        let _: SpannedToken![::] = bail_unchecked!(fork.parse(); false);
+
+        fork.peek::<LBraceToken>()
+    }
 }
 
 impl Parse for UseWithItems {
@@ -98,10 +114,51 @@ impl ToTokens for UseWithItems {
     }
 }
 
+/// A glob tail (`::*`) importing every item of the leading namespace.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct UseGlob {
+    pub fish: SpannedToken![::],
+    pub star: SpannedToken![*],
+}
+
+impl Peek for UseGlob {
+    fn is(token: &Token) -> bool {
+        <Token![::]>::is(token)
+    }
+
+    fn peek(stream: &tokens::TokenStream) -> bool {
+        let mut fork = stream.fork();
+
+        let _: SpannedToken![::] = bail_unchecked!(fork.parse(); false);
+
+        fork.peek::<Token![*]>()
+    }
+}
+
+impl Parse for UseGlob {
+    fn parse(stream: &mut tokens::TokenStream) -> Result<Self, tokens::LexingError> {
+        Ok(Self {
+            fish: stream.parse()?,
+            star: stream.parse()?,
+        })
+    }
+}
+
+impl ToTokens for UseGlob {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        tt.write(&self.fish);
+        tt.write(&self.star);
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct UsePath {
     pub leading: PathOrIdent,
     pub items: Option<Spanned<UseWithItems>>,
+    pub glob: Option<Spanned<UseGlob>>,
 }
 
 impl UsePath {
@@ -129,8 +186,18 @@ impl UsePath {
                         }
                     },
                     FinalOrNested::Nest(ref np) => {
-                        let nested_paths = np.paths_with_context(ref_ctx);
-                        paths.extend(nested_paths);
+                        let nested_ctx = match &np.leading {
+                            PathOrIdent::Ident(id) => ref_ctx.enter(id.borrow_string()),
+                            PathOrIdent::Path(p) => {
+                                ref_ctx.extend(p.borrow_path_inner().segments())
+                            },
+                        };
+
+                        if np.glob.is_some() {
+                            paths.push(RefOrItemContext::Glob(nested_ctx));
+                        } else {
+                            paths.extend(np.paths_with_context(&nested_ctx));
+                        }
                     },
                 }
             }
@@ -159,7 +226,14 @@ impl UsePath {
 
     /// check if this is a single-segment path (no ::)
     pub fn is_single_segment(&self) -> bool {
-        matches!(&self.leading, PathOrIdent::Ident(_)) && self.items.is_none()
+        matches!(&self.leading, PathOrIdent::Ident(_))
+            && self.items.is_none()
+            && self.glob.is_none()
+    }
+
+    /// check if this is a glob import (::*)
+    pub fn is_glob(&self) -> bool {
+        self.glob.is_some()
     }
 
     /// check if this has nested items (::{ ... })
@@ -195,7 +269,9 @@ impl UsePath {
             RefContext::new(pkg, bits)
         };
 
-        if self.items.is_none() {
+        if self.glob.is_some() {
+            paths.push(RefOrItemContext::Glob(ref_ctx));
+        } else if self.items.is_none() {
             paths.push(ref_ctx.clone().into());
         } else {
             paths.extend(self.paths_with_context(&ref_ctx));
@@ -208,15 +284,23 @@ impl UsePath {
 impl Parse for UsePath {
     fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, crate::tokens::LexingError> {
         let leading = PathOrIdent::parse(stream)?;
-        Ok(if stream.peek::<UseWithItems>() {
+        Ok(if stream.peek::<UseGlob>() {
+            Self {
+                leading,
+                items: None,
+                glob: Option::parse(stream)?,
+            }
+        } else if stream.peek::<UseWithItems>() {
             Self {
                 leading,
                 items: Option::parse(stream)?,
+                glob: None,
             }
         } else {
             Self {
                 leading,
                 items: None,
+                glob: None,
             }
         })
     }
@@ -229,6 +313,7 @@ impl ToTokens for UsePath {
     ) {
         tt.write(&self.leading);
         tt.write(&self.items);
+        tt.write(&self.glob);
     }
 }
 
@@ -250,6 +335,10 @@ impl Use {
     pub fn has_nested_items(&self) -> bool {
         self.path.value.has_nested_items()
     }
+
+    pub fn is_glob(&self) -> bool {
+        self.path.value.is_glob()
+    }
 }
 
 impl Parse for Use {
@@ -287,6 +376,8 @@ mod test {
     #[test_case::test_case("use foo::bar::baz"; "use one object")]
     #[test_case::test_case("use bar_corp::baz::BazOrString"; "use corp object")]
     #[test_case::test_case("use foo::bar::{\n\tbaz,\n\tbin\n}"; "use two objects")]
+    #[test_case::test_case("use foo::*"; "use glob ident")]
+    #[test_case::test_case("use foo::bar::*"; "use glob path")]
     fn rt(src: &str) {
         crate::tst::round_trip::<super::Use>(src).unwrap();
     }
@@ -297,6 +388,9 @@ mod test {
     #[test_case::test_case("use bar_corp::baz::BazOrString", vec!["bar_corp::baz::BazOrString"]; "use corp object")]
     #[test_case::test_case("use foo::bar::{baz, Bin}", vec!["foo::bar::baz", "foo::bar::Bin"]; "simple nested use")]
     #[test_case::test_case("use schema::bar::{baz, bin}", vec!["my_pkg::bar::baz", "my_pkg::bar::bin"]; "local use with schema")]
+    #[test_case::test_case("use foo::bar::*", vec!["foo::bar::*"]; "glob use")]
+    #[test_case::test_case("use foo::{bar::*, baz::Qux}", vec!["foo::bar::*", "foo::baz::Qux"]; "glob in group")]
+    #[test_case::test_case("use foo::{bar::{Baz, Bin}}", vec!["foo::bar::Baz", "foo::bar::Bin"]; "nested group keeps leading")]
     fn resolved_use_paths(
         src: &str,
         expected: Vec<&str>,
@@ -318,4 +412,20 @@ mod test {
             .collect();
         assert_eq!(path_strs, expected_strs);
     }
+
+    #[test]
+    fn glob_is_flagged() {
+        let use_stmt = crate::tst::basic_smoke::<super::Use>("use foo::bar::*").unwrap();
+        assert!(use_stmt.is_glob());
+        assert!(!use_stmt.has_nested_items());
+
+        let paths = use_stmt
+            .path
+            .value
+            .qualified_paths("my_pkg".to_string());
+        assert!(matches!(
+            paths.as_slice(),
+            [crate::ctx::RefOrItemContext::Glob(_)]
+        ));
+    }
 }
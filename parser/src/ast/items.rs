@@ -1,6 +1,6 @@
 use crate::{
     Token,
-    ast::{self, comment::CommentStream, meta::ItemMeta},
+    ast::{self, comment::CommentStream, meta::ItemMeta, vis::Visibility},
     bail_unchecked,
     defs::{Span, Spanned},
     tokens::{ImplDiagnostic, LexingError, Parse, Peek, SemiToken, ToTokens, straight_through},
@@ -58,6 +58,7 @@ impl ToTokens for CommentOrMeta {
 #[derive(serde::Deserialize, serde::Serialize, Clone)]
 pub struct Item<T: Parse> {
     pub meta: Vec<Spanned<CommentOrMeta>>,
+    pub vis: Option<Spanned<Visibility>>,
     pub def: Spanned<T>,
     // ;
     pub end: Spanned<SemiToken>,
@@ -68,6 +69,13 @@ impl<T: Parse> Item<T> {
         &self.def.span
     }
 
+    /// Internal items may only be referenced from their own package.
+    pub fn is_internal(&self) -> bool {
+        self.vis
+            .as_ref()
+            .is_some_and(|vis| vis.value.is_internal())
+    }
+
     pub fn meta(&self) -> Vec<&Spanned<ItemMeta>> {
         let mut meta = vec![];
         for it in &self.meta {
@@ -89,19 +97,45 @@ impl<T: Parse> Item<T> {
     }
 }
 
-impl<T: Parse> Parse for Item<T> {
-    fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, crate::tokens::LexingError> {
+impl<T: Parse> Item<T> {
+    /// Parses the definition and its closing `;`, once meta and visibility are consumed.
+    fn parse_def(
+        meta: Vec<Spanned<CommentOrMeta>>,
+        vis: Option<Spanned<Visibility>>,
+        stream: &mut crate::tokens::TokenStream,
+    ) -> Result<Self, LexingError> {
+        let def: Spanned<T> = stream.parse()?;
+        // a `;` is only clearly missing when the next item (or the end of the file)
+        // follows the definition; anything else is malformed inside the definition
+        let ends_here = stream.is_empty() || stream.peek::<Items>();
+        let end = stream.parse().map_err(|err: LexingError| {
+            if ends_here {
+                err.missing_semicolon(def.span.span().end)
+            } else {
+                err
+            }
+        })?;
+
         Ok(Self {
-            meta: Vec::parse(stream)?,
-            def: stream.parse()?,
-            end: stream.parse()?,
+            meta,
+            vis,
+            def,
+            end,
         })
     }
 }
 
+impl<T: Parse> Parse for Item<T> {
+    fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, crate::tokens::LexingError> {
+        let meta = Vec::parse(stream)?;
+        let vis = Option::parse(stream)?;
+        Self::parse_def(meta, vis, stream)
+    }
+}
+
 straight_through! {
     Item<T> {
-        meta, def, end
+        meta, vis, def, end
     }
 }
 
@@ -114,6 +148,7 @@ pub type StructDef = Item<super::strct::Struct>;
 pub type TypeDef = Item<super::ty_def::NamedType>;
 pub type ErrorDef = Item<super::err::ErrorType>;
 pub type OperationDef = Item<super::op::Operation>;
+pub type ConstDef = Item<super::constant::Constant>;
 
 impl UseDef {
     /// get the root identifier of the use statement
@@ -141,71 +176,66 @@ pub enum Items {
     Type(TypeDef),
     Error(ErrorDef),
     Operation(OperationDef),
+    Const(ConstDef),
     Namespace(NamespaceDef),
     SpannedNamespace(SpannedNamespaceDef),
 }
 
+impl Items {
+    /// Item keywords a visibility modifier may precede
+    fn visible_items() -> Vec<&'static str> {
+        vec![
+            <Token![oneof]>::fmt(),
+            <Token![enum]>::fmt(),
+            <Token![struct]>::fmt(),
+            <Token![error]>::fmt(),
+            <Token![type]>::fmt(),
+            <Token![operation]>::fmt(),
+            <Token![const]>::fmt(),
+        ]
+    }
+}
+
 impl Parse for Items {
     #[tracing::instrument(skip(stream))]
     fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, crate::tokens::LexingError> {
         tracing::trace!("try parse meta");
         let meta = Vec::parse(stream)?;
         tracing::trace!("done parsing meta");
+        let vis: Option<Spanned<Visibility>> = Option::parse(stream)?;
+
+        if let Some(vis) = &vis
+            && (stream.peek::<ast::namespace::Namespace>()
+                || stream.peek::<ast::import::Use>()
+                || stream.peek::<ast::namespace::SpannedNamespace>())
+        {
+            return Err(LexingError::expected_oneof(
+                Self::visible_items(),
+                vis.value.kw().value.token(),
+            )
+            .with_span(vis.span.clone()));
+        }
 
         Ok(if stream.peek::<ast::namespace::Namespace>() {
-            Self::Namespace(NamespaceDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::Namespace(NamespaceDef::parse_def(meta, vis, stream)?)
         } else if stream.peek::<ast::import::Use>() {
-            Self::Use(UseDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::Use(UseDef::parse_def(meta, vis, stream)?)
         } else if stream.peek::<ast::one_of::OneOf>() {
-            Self::OneOf(OneOfDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::OneOf(OneOfDef::parse_def(meta, vis, stream)?)
         } else if stream.peek::<ast::err::ErrorType>() {
-            Self::Error(ErrorDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::Error(ErrorDef::parse_def(meta, vis, stream)?)
         } else if stream.peek::<ast::enm::Enum>() {
-            Self::Enum(EnumDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::Enum(EnumDef::parse_def(meta, vis, stream)?)
         } else if stream.peek::<ast::strct::Struct>() {
-            Self::Struct(StructDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::Struct(StructDef::parse_def(meta, vis, stream)?)
         } else if stream.peek::<ast::ty_def::NamedType>() {
-            Self::Type(TypeDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::Type(TypeDef::parse_def(meta, vis, stream)?)
         } else if stream.peek::<ast::op::Operation>() {
-            Self::Operation(OperationDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::Operation(OperationDef::parse_def(meta, vis, stream)?)
+        } else if stream.peek::<ast::constant::Constant>() {
+            Self::Const(ConstDef::parse_def(meta, vis, stream)?)
         } else if stream.peek::<ast::namespace::SpannedNamespace>() {
-            Self::SpannedNamespace(SpannedNamespaceDef {
-                meta,
-                def: stream.parse()?,
-                end: stream.parse()?,
-            })
+            Self::SpannedNamespace(SpannedNamespaceDef::parse_def(meta, vis, stream)?)
         } else {
             let expect = vec![
                 <Token![namespace]>::fmt(),
@@ -216,6 +246,7 @@ impl Parse for Items {
                 <Token![error]>::fmt(),
                 <Token![type]>::fmt(),
                 <Token![operation]>::fmt(),
+                <Token![const]>::fmt(),
             ];
             return Err(if let Some(next) = stream.next() {
                 LexingError::expected_oneof(expect, next.value)
@@ -236,6 +267,10 @@ impl Peek for Items {
             meta_result.as_ref().map(|v| v.len())
         );
 
+        if fork.peek::<Visibility>() {
+            fork.next();
+        }
+
         if let Some(token) = fork.next() {
             tracing::trace!("Items::peek next token: {:?}", token);
             let token = &token.value;
@@ -247,6 +282,7 @@ impl Peek for Items {
                 || <Token![error]>::is(token)
                 || <Token![type]>::is(token)
                 || <Token![operation]>::is(token)
+                || <Token![const]>::is(token)
         } else {
             tracing::trace!("Items::peek no token found, returning false");
             false
@@ -268,6 +304,7 @@ impl ToTokens for Items {
             Type(def) => tt.write(def),
             Error(def) => tt.write(def),
             Operation(def) => tt.write(def),
+            Const(def) => tt.write(def),
             Namespace(def) => tt.write(def),
             SpannedNamespace(def) => tt.write(def),
         }
@@ -363,6 +400,31 @@ enum Foo {
     Abc = \"life\"
 };
 ", 1; "parses str enum"
+    )]
+    #[test_case::test_case(
+        "
+namespace test;
+
+const MAX_LEN: u32 = 16;
+
+struct Buf {
+    data: u8[MAX_LEN]
+};
+", 3; "parses constant and sized array using it"
+    )]
+    #[test_case::test_case(
+        "
+namespace test;
+
+internal struct Cursor {
+    offset: u64
+};
+
+#[version(1)]
+pub enum Kind {
+    A
+};
+", 3; "parses visibility modifiers"
     )]
     fn basic_smoke(
         src: &str,
@@ -371,4 +433,45 @@ enum Foo {
         let items: Vec<Spanned<super::Items>> = crate::tst::basic_smoke(src).unwrap();
         assert_eq!(items.len(), n_items);
     }
+
+    #[test]
+    fn visibility_rejected_on_use() {
+        let src = "
+namespace test;
+pub use abc;
+";
+        assert!(crate::tst::basic_smoke::<Vec<Spanned<super::Items>>>(src).is_err());
+    }
+
+    #[test]
+    fn internal_as_namespace_name() {
+        let items: Vec<Spanned<super::Items>> =
+            crate::tst::basic_smoke("namespace internal;\ninternal struct A { a: i32 };").unwrap();
+        assert!(matches!(&items[1].value, super::Items::Struct(def) if def.is_internal()));
+    }
+
+    #[test_case::test_case("namespace test;\nstruct A { a: i32 }\nstruct B { b: i32 };", 35; "before next item")]
+    #[test_case::test_case("namespace test;\nstruct A { a: i32 }", 35; "at end of file")]
+    #[test_case::test_case("namespace test;\n// trailing\nstruct A { a: i32 }\n/// docs\nstruct B { b: i32 };", 47; "before commented item")]
+    fn missing_semicolon_insertion_point(
+        src: &str,
+        at: usize,
+    ) {
+        match crate::tst::basic_smoke::<Vec<Spanned<super::Items>>>(src) {
+            Err(crate::tokens::LexingError::MissingSemicolon { at: found, .. }) => {
+                assert_eq!(found, at)
+            },
+            Err(other) => panic!("expected a missing semicolon, found {other:?}"),
+            Ok(_) => panic!("expected a missing semicolon"),
+        }
+    }
+
+    #[test]
+    fn malformed_definition_is_not_a_missing_semicolon() {
+        let src = "namespace test;\ntype A = Pick B, id;";
+        assert!(matches!(
+            crate::tst::basic_smoke::<Vec<Spanned<super::Items>>>(src),
+            Err(err) if !matches!(err, crate::tokens::LexingError::MissingSemicolon { .. })
+        ));
+    }
 }
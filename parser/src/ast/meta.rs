@@ -1,5 +1,5 @@
 use crate::{
-    Token,
+    SpannedToken, Token,
     ast::ty::PathOrIdent,
     bail_unchecked,
     defs::Spanned,
@@ -114,10 +114,37 @@ impl ToTokens for RawTagContent {
     }
 }
 
+/// Raw content for `#[len(...)]` attributes.
+/// Parses `min..max`, `min..` or `..max` where both bounds are inclusive.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct RawLenRange {
+    pub min: Option<SpannedToken![number]>,
+    pub dots: SpannedToken![..],
+    pub max: Option<SpannedToken![number]>,
+}
+
+impl Peek for RawLenRange {
+    fn peek(stream: &crate::tokens::TokenStream) -> bool {
+        stream.peek::<Token![number]>() || stream.peek::<Token![..]>()
+    }
+}
+
+impl Parse for RawLenRange {
+    fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, crate::tokens::LexingError> {
+        Ok(Self {
+            min: Option::parse(stream)?,
+            dots: stream.parse()?,
+            max: Option::parse(stream)?,
+        })
+    }
+}
+
 /// Type alias for raw tag meta parsing
 pub type RawTagMeta = Meta<RawTagContent>;
 /// Type alias for rename meta - takes a single string argument
 pub type RawRenameMeta = Meta<crate::tokens::StringToken>;
+/// Type alias for raw len meta parsing
+pub type RawLenMeta = Meta<RawLenRange>;
 
 #[derive(serde::Deserialize, serde::Serialize, Clone)]
 pub struct Meta<Value: Parse> {
@@ -192,6 +219,27 @@ where
     }
 }
 
+/// An attribute without arguments, e.g. `#[contiguous]`.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct RawFlagMeta {
+    pub open: Spanned<HashToken>,
+    pub inner: Option<Spanned<BangToken>>,
+    pub bracket: Bracket,
+    pub name: Spanned<IdentToken>,
+}
+
+impl tokens::Parse for RawFlagMeta {
+    fn parse(stream: &mut tokens::TokenStream) -> Result<Self, tokens::LexingError> {
+        let mut bracket;
+        Ok(Self {
+            open: stream.parse()?,
+            inner: Option::parse(stream)?,
+            bracket: bracket!(bracket in stream),
+            name: bracket.parse()?,
+        })
+    }
+}
+
 pub type IntMeta = Meta<Token![number]>;
 pub type StrMeta = Meta<Token![string]>;
 pub type IdentMeta = Meta<PathOrIdent>;
@@ -232,6 +280,53 @@ pub enum ItemMetaItem {
     /// Rename attribute: `#[rename("name")]`
     /// Full parsing support added in Phase 3 (Tagging implementation)
     Rename(RenameMeta),
+    /// Validation constraint: `#[min(..)]`, `#[max(..)]`, `#[pattern(..)]` or `#[len(..)]`.
+    /// Only valid on struct fields and operation arguments.
+    Constraint(ConstraintMeta),
+    /// HTTP binding: `#[http(method = "POST", path = "/users/{id}")]`.
+    /// Only valid on operations.
+    Http(HttpMeta),
+    /// GraphQL operation kind: `#[graphql(query)]` or `#[graphql(mutation)]`.
+    /// Only valid on operations.
+    Graphql(GraphqlMeta),
+    /// Discriminant layout: `#[contiguous]`.
+    /// Only valid on integer enums.
+    Contiguous(ContiguousMeta),
+    /// Example payload: `#[example('...')]` on fields and arguments, or
+    /// `#[example(input = '...', output = '...')]` on operations.
+    Example(ExampleMeta),
+}
+
+impl ItemMetaItem {
+    /// The attribute name as written in source, e.g. `version`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Version(..) => "version",
+            Self::Error(..) => "err",
+            Self::Tag(..) => "tag",
+            Self::Rename(..) => "rename",
+            Self::Constraint(c) => c.value.name(),
+            Self::Http(..) => "http",
+            Self::Graphql(..) => "graphql",
+            Self::Contiguous(..) => "contiguous",
+            Self::Example(..) => "example",
+        }
+    }
+
+    pub fn span(&self) -> crate::Span {
+        let raw = match self {
+            Self::Version(m) => m.span(),
+            Self::Error(m) => m.span(),
+            Self::Tag(m) => m.span(),
+            Self::Rename(m) => m.span(),
+            Self::Constraint(m) => m.span(),
+            Self::Http(m) => m.span(),
+            Self::Graphql(m) => m.span(),
+            Self::Contiguous(m) => m.span(),
+            Self::Example(m) => m.span(),
+        };
+        crate::Span::new(raw.start, raw.end)
+    }
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -288,11 +383,114 @@ impl Parse for ItemMeta {
                         Spanned::new(raw.span.span().start, raw.span.span().end, rename_attr);
                     meta.push(ItemMetaItem::Rename(rename_meta));
                 },
+                Some("http") => {
+                    let raw: Spanned<RawTagMeta> = stream.parse()?;
+                    let http_attr = parse_http_content(&raw.value.value)?;
+                    let http_meta =
+                        Spanned::new(raw.span.span().start, raw.span.span().end, http_attr);
+                    meta.push(ItemMetaItem::Http(http_meta));
+                },
+                Some("graphql") => {
+                    let raw: Spanned<RawTagMeta> = stream.parse()?;
+                    let graphql_attr = parse_graphql_content(&raw.value.value)?;
+                    let graphql_meta =
+                        Spanned::new(raw.span.span().start, raw.span.span().end, graphql_attr);
+                    meta.push(ItemMetaItem::Graphql(graphql_meta));
+                },
+                Some("example") => {
+                    let (start, end, example) = if stream.peek::<StrMeta>() {
+                        let raw: Spanned<StrMeta> = stream.parse()?;
+                        let value = raw.value.value.borrow_string().to_string();
+                        (
+                            raw.span.span().start,
+                            raw.span.span().end,
+                            ExampleAttribute::Value(value),
+                        )
+                    } else {
+                        let raw: Spanned<RawTagMeta> = stream.parse()?;
+                        let example = parse_example_content(&raw.value.value)?;
+                        (raw.span.span().start, raw.span.span().end, example)
+                    };
+                    meta.push(ItemMetaItem::Example(Spanned::new(start, end, example)));
+                },
+                Some("untagged") => {
+                    let raw: Spanned<RawFlagMeta> = stream.parse()?;
+                    meta.push(ItemMetaItem::Tag(Spanned::new(
+                        raw.span.span().start,
+                        raw.span.span().end,
+                        TagAttribute::untagged(),
+                    )));
+                },
+                Some("contiguous") => {
+                    let raw: Spanned<RawFlagMeta> = stream.parse()?;
+                    meta.push(ItemMetaItem::Contiguous(Spanned::new(
+                        raw.span.span().start,
+                        raw.span.span().end,
+                        ContiguousAttribute,
+                    )));
+                },
+                Some(bound @ ("min" | "max")) => {
+                    let raw: Spanned<IntMeta> = stream.parse()?;
+                    let value = *raw.value.value.borrow_i32() as i64;
+                    let constraint = if bound == "min" {
+                        ConstraintAttribute::Min(value)
+                    } else {
+                        ConstraintAttribute::Max(value)
+                    };
+                    meta.push(ItemMetaItem::Constraint(Spanned::new(
+                        raw.span.span().start,
+                        raw.span.span().end,
+                        constraint,
+                    )));
+                },
+                Some("pattern") => {
+                    let raw: Spanned<StrMeta> = stream.parse()?;
+                    let constraint =
+                        ConstraintAttribute::Pattern(raw.value.value.borrow_string().to_string());
+                    meta.push(ItemMetaItem::Constraint(Spanned::new(
+                        raw.span.span().start,
+                        raw.span.span().end,
+                        constraint,
+                    )));
+                },
+                Some("len") => {
+                    let raw: Spanned<RawLenMeta> = stream.parse()?;
+                    let range = &raw.value.value;
+                    let constraint = ConstraintAttribute::Len {
+                        min: range
+                            .min
+                            .as_ref()
+                            .map(|n| *n.borrow_i32() as u64),
+                        max: range
+                            .max
+                            .as_ref()
+                            .map(|n| *n.borrow_i32() as u64),
+                    };
+                    meta.push(ItemMetaItem::Constraint(Spanned::new(
+                        raw.span.span().start,
+                        raw.span.span().end,
+                        constraint,
+                    )));
+                },
                 Some(unknown) => {
                     // Consume the meta using RawTagMeta which is most permissive
                     let raw: Spanned<RawTagMeta> = stream.parse()?;
                     return Err(crate::LexingError::unknown_meta(
-                        vec!["version", "err", "tag", "rename"],
+                        vec![
+                            "version",
+                            "err",
+                            "tag",
+                            "rename",
+                            "http",
+                            "graphql",
+                            "min",
+                            "max",
+                            "pattern",
+                            "len",
+                            "contiguous",
+                            "untagged",
+                            "example",
+                        ],
                         unknown.into(),
                         &raw.name.span,
                     ));
@@ -319,6 +517,100 @@ fn peek_meta_name(stream: &tokens::TokenStream) -> Option<String> {
     Some(name.borrow_string().to_string())
 }
 
+/// Parse RawTagContent into HttpAttribute. Values are checked in the metadata phase.
+fn parse_http_content(content: &RawTagContent) -> Result<HttpAttribute, crate::LexingError> {
+    let mut attr = HttpAttribute::default();
+
+    for arg in &content.args {
+        let (key, value) = match arg {
+            TagArg::StringValue { key, value, .. } => (key, value.borrow_string().to_string()),
+            TagArg::Keyword(key) | TagArg::BoolValue { key, .. } => {
+                return Err(crate::LexingError::unknown_meta(
+                    vec!["method = \"...\"", "path = \"...\""],
+                    key.borrow_string().to_string(),
+                    &key.span,
+                ));
+            },
+        };
+        match key.borrow_string().as_str() {
+            "method" => attr.method = Some(value),
+            "path" => attr.path = Some(value),
+            other => {
+                return Err(crate::LexingError::unknown_meta(
+                    vec!["method", "path"],
+                    other.to_string(),
+                    &key.span,
+                ));
+            },
+        }
+    }
+
+    Ok(attr)
+}
+
+/// Parse RawTagContent into GraphqlAttribute. The number of kinds is checked in the metadata
+/// phase.
+fn parse_graphql_content(content: &RawTagContent) -> Result<GraphqlAttribute, crate::LexingError> {
+    let mut attr = GraphqlAttribute::default();
+
+    for arg in &content.args {
+        let kind = match arg {
+            TagArg::Keyword(key) if key.borrow_string() == "query" => GraphqlKind::Query,
+            TagArg::Keyword(key) if key.borrow_string() == "mutation" => GraphqlKind::Mutation,
+            TagArg::Keyword(key)
+            | TagArg::StringValue { key, .. }
+            | TagArg::BoolValue { key, .. } => {
+                return Err(crate::LexingError::unknown_meta(
+                    vec!["query", "mutation"],
+                    key.borrow_string().to_string(),
+                    &key.span,
+                ));
+            },
+        };
+        attr.kinds.push(kind);
+    }
+
+    Ok(attr)
+}
+
+/// Parse RawTagContent into the operation form of ExampleAttribute. Payloads are checked
+/// during resolution.
+fn parse_example_content(content: &RawTagContent) -> Result<ExampleAttribute, crate::LexingError> {
+    let (mut input, mut output) = (None, None);
+
+    for arg in &content.args {
+        let (key, value) = match arg {
+            TagArg::StringValue { key, value, .. } => (key, value.borrow_string().to_string()),
+            TagArg::Keyword(key) | TagArg::BoolValue { key, .. } => {
+                return Err(crate::LexingError::unknown_meta(
+                    vec!["input = '...'", "output = '...'"],
+                    key.borrow_string().to_string(),
+                    &key.span,
+                ));
+            },
+        };
+        match key.borrow_string().as_str() {
+            "input" => input = Some(value),
+            "output" => output = Some(value),
+            other => {
+                return Err(crate::LexingError::unknown_meta(
+                    vec!["input", "output"],
+                    other.to_string(),
+                    &key.span,
+                ));
+            },
+        }
+    }
+
+    Ok(ExampleAttribute::Call { input, output })
+}
+
+/// Tag field of `#[tag(internal)]` and `#[tag(adjacent)]` when no name is given
+const DEFAULT_TAG_FIELD: &str = "kind";
+
+/// Content field of `#[tag(adjacent)]` when none is given
+const DEFAULT_CONTENT_FIELD: &str = "data";
+
 /// Parse RawTagContent into TagAttribute per RFC-0017 syntax
 fn parse_tag_content(content: &RawTagContent) -> Result<TagAttribute, crate::LexingError> {
     let mut style = TagStyle::TypeHint;
@@ -326,6 +618,8 @@ fn parse_tag_content(content: &RawTagContent) -> Result<TagAttribute, crate::Lex
     let mut name_field: Option<String> = None;
     let mut content_field: Option<String> = None;
     let mut is_index = false;
+    let mut is_internal = false;
+    let mut is_adjacent = false;
 
     for arg in &content.args {
         match arg {
@@ -338,11 +632,20 @@ fn parse_tag_content(content: &RawTagContent) -> Result<TagAttribute, crate::Lex
                         type_hint = false;
                     },
                     "index" => is_index = true,
+                    "internal" => is_internal = true,
+                    "adjacent" => is_adjacent = true,
                     "type_hint" => {}, // Default, just confirming
                     _ => {
                         // Unknown keyword in tag - use unknown_meta error
                         return Err(crate::LexingError::unknown_meta(
-                            vec!["external", "untagged", "index", "type_hint"],
+                            vec![
+                                "external",
+                                "internal",
+                                "adjacent",
+                                "untagged",
+                                "index",
+                                "type_hint",
+                            ],
                             kw_str.to_string(),
                             &kw.span,
                         ));
@@ -353,11 +656,15 @@ fn parse_tag_content(content: &RawTagContent) -> Result<TagAttribute, crate::Lex
                 let key_str = key.borrow_string();
                 let val_str = value.borrow_string().to_string();
                 match key_str.as_ref() {
-                    "name" => name_field = Some(val_str),
+                    "name" | "tag" => name_field = Some(val_str),
                     "content" => content_field = Some(val_str),
+                    "internal" => {
+                        is_internal = true;
+                        name_field = Some(val_str);
+                    },
                     _ => {
                         return Err(crate::LexingError::unknown_meta(
-                            vec!["name", "content"],
+                            vec!["name", "tag", "content", "internal"],
                             key_str.to_string(),
                             &key.span,
                         ));
@@ -386,6 +693,15 @@ fn parse_tag_content(content: &RawTagContent) -> Result<TagAttribute, crate::Lex
     // Determine final style based on parsed args
     if is_index {
         style = TagStyle::Index { name: name_field };
+    } else if is_adjacent {
+        style = TagStyle::Adjacent {
+            name: name_field.unwrap_or_else(|| DEFAULT_TAG_FIELD.into()),
+            content: content_field.unwrap_or_else(|| DEFAULT_CONTENT_FIELD.into()),
+        };
+    } else if is_internal {
+        style = TagStyle::Internal {
+            name: name_field.unwrap_or_else(|| DEFAULT_TAG_FIELD.into()),
+        };
     } else if let Some(name) = name_field {
         if let Some(content) = content_field {
             style = TagStyle::Adjacent { name, content };
@@ -394,7 +710,11 @@ fn parse_tag_content(content: &RawTagContent) -> Result<TagAttribute, crate::Lex
         }
     }
 
-    Ok(TagAttribute { style, type_hint })
+    Ok(TagAttribute {
+        style,
+        type_hint,
+        shorthand: false,
+    })
 }
 
 impl ToTokens for ItemMetaItem {
@@ -407,6 +727,11 @@ impl ToTokens for ItemMetaItem {
             ItemMetaItem::Error(m) => tt.write(m),
             ItemMetaItem::Tag(m) => m.write(tt),
             ItemMetaItem::Rename(m) => m.write(tt),
+            ItemMetaItem::Constraint(m) => m.write(tt),
+            ItemMetaItem::Http(m) => m.write(tt),
+            ItemMetaItem::Graphql(m) => m.write(tt),
+            ItemMetaItem::Contiguous(m) => m.write(tt),
+            ItemMetaItem::Example(m) => m.write(tt),
         }
     }
 }
@@ -474,6 +799,20 @@ pub struct TagAttribute {
     pub style: TagStyle,
     /// Whether type_hint is enabled (adds @kintsu field)
     pub type_hint: bool,
+    /// Whether this was written as `#[untagged]` rather than `#[tag(untagged)]`
+    #[serde(default, skip)]
+    pub shorthand: bool,
+}
+
+impl TagAttribute {
+    /// The attribute written as `#[untagged]`
+    pub fn untagged() -> Self {
+        Self {
+            style: TagStyle::Untagged,
+            type_hint: false,
+            shorthand: true,
+        }
+    }
 }
 
 impl Default for TagAttribute {
@@ -481,6 +820,7 @@ impl Default for TagAttribute {
         Self {
             style: TagStyle::TypeHint,
             type_hint: true,
+            shorthand: false,
         }
     }
 }
@@ -496,17 +836,149 @@ pub struct RenameAttribute {
     pub name: String,
 }
 
+/// Parsed validation constraint attribute on a struct field or operation argument.
+///
+/// `min`/`max` bound numeric values, `pattern` matches strings against a regular
+/// expression and `len` bounds the length of strings, binary data and arrays.
+/// All bounds are inclusive.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConstraintAttribute {
+    Min(i64),
+    Max(i64),
+    Pattern(String),
+    Len { min: Option<u64>, max: Option<u64> },
+}
+
+impl ConstraintAttribute {
+    /// The attribute name as written in source, e.g. `min`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Min(..) => "min",
+            Self::Max(..) => "max",
+            Self::Pattern(..) => "pattern",
+            Self::Len { .. } => "len",
+        }
+    }
+}
+
 /// Type alias for tag meta - parsed `#[tag(style)]` or `#[tag(name = "x")]`
 pub type TagMeta = Spanned<TagAttribute>;
 
 /// Type alias for rename meta - parsed `#[rename("custom_name")]`
 pub type RenameMeta = Spanned<RenameAttribute>;
 
+/// Type alias for constraint meta - parsed `#[min(1)]`, `#[len(1..64)]`, ...
+pub type ConstraintMeta = Spanned<ConstraintAttribute>;
+
+/// Parsed `#[contiguous]` attribute on an integer enum, requiring its discriminants to form
+/// a run without gaps or repeats.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ContiguousAttribute;
+
+/// Type alias for contiguous meta - parsed `#[contiguous]`
+pub type ContiguousMeta = Spanned<ContiguousAttribute>;
+
+/// Parsed `#[example(...)]` attribute. Payloads are JSON text, checked against the declared
+/// type during resolution.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExampleAttribute {
+    /// `#[example('...')]` on a field or operation argument
+    Value(String),
+    /// `#[example(input = '...', output = '...')]` on an operation, where `input` is an object
+    /// keyed by argument name and `output` is the successful return value
+    Call {
+        input: Option<String>,
+        output: Option<String>,
+    },
+}
+
+/// Type alias for example meta - parsed `#[example('{"id": 1}')]`
+pub type ExampleMeta = Spanned<ExampleAttribute>;
+
+/// HTTP methods accepted by `#[http(method = ...)]`.
+pub const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// Parsed `#[http(method = "...", path = "...")]` attribute on an operation.
+///
+/// Both keys are optional at parse time so the metadata phase can report a missing
+/// key with a span on the attribute.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HttpAttribute {
+    pub method: Option<String>,
+    pub path: Option<String>,
+}
+
+impl HttpAttribute {
+    /// Names of the `{param}` placeholders in `path`, in order of appearance.
+    pub fn path_params(&self) -> Result<Vec<String>, String> {
+        let Some(path) = &self.path else {
+            return Ok(vec![]);
+        };
+
+        let mut params = vec![];
+        let mut rest = path.as_str();
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                return Err(format!("unmatched '}}' in path \"{path}\""));
+            }
+            let after = &rest[open + 1..];
+            let Some(close) = after.find('}') else {
+                return Err(format!("unclosed '{{' in path \"{path}\""));
+            };
+            let name = &after[..close];
+            if name.is_empty() || name.contains('{') {
+                return Err(format!("invalid path parameter '{{{name}}}'"));
+            }
+            params.push(name.to_string());
+            rest = &after[close + 1..];
+        }
+        Ok(params)
+    }
+}
+
+/// Type alias for http meta - parsed `#[http(method = "GET", path = "/items/{id}")]`
+pub type HttpMeta = Spanned<HttpAttribute>;
+
+/// Whether an operation is exposed as a GraphQL query or mutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphqlKind {
+    Query,
+    Mutation,
+}
+
+impl GraphqlKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Query => "query",
+            Self::Mutation => "mutation",
+        }
+    }
+}
+
+/// Parsed `#[graphql(query)]` or `#[graphql(mutation)]` attribute on an operation.
+///
+/// Every keyword is kept so the metadata phase can report an empty or ambiguous attribute
+/// with a span on it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GraphqlAttribute {
+    pub kinds: Vec<GraphqlKind>,
+}
+
+/// Type alias for graphql meta - parsed `#[graphql(query)]`
+pub type GraphqlMeta = Spanned<GraphqlAttribute>;
+
 impl ToTokens for TagAttribute {
     fn write(
         &self,
         tt: &mut crate::fmt::Printer,
     ) {
+        if self.shorthand {
+            tt.word("#[untagged]");
+            return;
+        }
         tt.word("#[tag(");
         match &self.style {
             TagStyle::TypeHint => tt.word("type_hint"),
@@ -540,6 +1012,16 @@ impl ToTokens for TagAttribute {
     }
 }
 
+impl ToTokens for ContiguousAttribute {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        tt.word("#[contiguous]");
+        tt.add_newline();
+    }
+}
+
 impl ToTokens for RenameAttribute {
     fn write(
         &self,
@@ -551,6 +1033,106 @@ impl ToTokens for RenameAttribute {
     }
 }
 
+impl ToTokens for ConstraintAttribute {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        tt.word("#[");
+        tt.word(self.name());
+        tt.word("(");
+        match self {
+            Self::Min(v) | Self::Max(v) => tt.word(&v.to_string()),
+            Self::Pattern(p) => {
+                tt.word("\"");
+                tt.word(&p.replace('\\', "\\\\").replace('"', "\\\""));
+                tt.word("\"");
+            },
+            Self::Len { min, max } => {
+                if let Some(min) = min {
+                    tt.word(&min.to_string());
+                }
+                tt.word("..");
+                if let Some(max) = max {
+                    tt.word(&max.to_string());
+                }
+            },
+        }
+        tt.word(")]");
+        tt.add_newline();
+    }
+}
+
+impl ToTokens for HttpAttribute {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        let args: Vec<String> = [("method", &self.method), ("path", &self.path)]
+            .into_iter()
+            .filter_map(|(key, value)| {
+                value
+                    .as_ref()
+                    .map(|v| format!("{key} = \"{v}\""))
+            })
+            .collect();
+        tt.word("#[http(");
+        tt.word(&args.join(", "));
+        tt.word(")]");
+        tt.add_newline();
+    }
+}
+
+impl ToTokens for GraphqlAttribute {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        let kinds: Vec<&str> = self
+            .kinds
+            .iter()
+            .map(GraphqlKind::as_str)
+            .collect();
+        tt.word("#[graphql(");
+        tt.word(&kinds.join(", "));
+        tt.word(")]");
+        tt.add_newline();
+    }
+}
+
+impl ToTokens for ExampleAttribute {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        let quote = |payload: &str| {
+            format!(
+                "'{}'",
+                payload
+                    .replace('\\', "\\\\")
+                    .replace('\'', "\\'")
+            )
+        };
+        tt.word("#[example(");
+        match self {
+            Self::Value(payload) => tt.word(&quote(payload)),
+            Self::Call { input, output } => {
+                let args: Vec<String> = [("input", input), ("output", output)]
+                    .into_iter()
+                    .filter_map(|(key, value)| {
+                        value
+                            .as_ref()
+                            .map(|v| format!("{key} = {}", quote(v)))
+                    })
+                    .collect();
+                tt.word(&args.join(", "));
+            },
+        }
+        tt.word(")]");
+        tt.add_newline();
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::fmt::Debug;
@@ -670,6 +1252,24 @@ mod test {
         );
     }
 
+    #[test_case::test_case("#[tag(internal = \"type\")]", TagStyle::Internal { name: "type".into() }; "internal with name")]
+    #[test_case::test_case("#[tag(internal)]", TagStyle::Internal { name: "kind".into() }; "internal default name")]
+    #[test_case::test_case("#[tag(adjacent, tag = \"t\", content = \"c\")]", TagStyle::Adjacent { name: "t".into(), content: "c".into() }; "adjacent with names")]
+    #[test_case::test_case("#[tag(adjacent)]", TagStyle::Adjacent { name: "kind".into(), content: "data".into() }; "adjacent default names")]
+    #[test_case::test_case("#[untagged]", TagStyle::Untagged; "untagged shorthand")]
+    fn test_tag_override_parse(
+        src: &str,
+        expected_style: TagStyle,
+    ) {
+        let mut tt = tokenize(src).expect("Should parse");
+        let meta: Spanned<ItemMeta> = tt.parse().unwrap();
+        let tag = match meta.meta.first().unwrap() {
+            ItemMetaItem::Tag(t) => t,
+            _ => panic!("expected Tag"),
+        };
+        assert_eq!(tag.value.style, expected_style);
+    }
+
     #[test]
     fn test_tag_index_with_name() {
         let mut tt = tokenize("#[tag(index, name = \"t\")]").expect("Should parse");
@@ -735,4 +1335,100 @@ mod test {
             _ => panic!("Expected Error meta item"),
         }
     }
+
+    #[test_case::test_case("#[min(1)]", ConstraintAttribute::Min(1); "min")]
+    #[test_case::test_case("#[max(100)]", ConstraintAttribute::Max(100); "max")]
+    #[test_case::test_case("#[pattern(\"^[a-z]+$\")]", ConstraintAttribute::Pattern("^[a-z]+$".into()); "pattern")]
+    #[test_case::test_case("#[len(1..64)]", ConstraintAttribute::Len { min: Some(1), max: Some(64) }; "len range")]
+    #[test_case::test_case("#[len(1..)]", ConstraintAttribute::Len { min: Some(1), max: None }; "len lower bound")]
+    #[test_case::test_case("#[len(..64)]", ConstraintAttribute::Len { min: None, max: Some(64) }; "len upper bound")]
+    fn test_constraint_parse(
+        src: &str,
+        expected: ConstraintAttribute,
+    ) {
+        let mut tt = tokenize(src).expect("Should parse");
+        let meta: Spanned<ItemMeta> = tt.parse().unwrap();
+        let constraint = match meta.meta.first().unwrap() {
+            ItemMetaItem::Constraint(c) => c,
+            _ => panic!("expected Constraint"),
+        };
+        assert_eq!(constraint.value, expected);
+    }
+
+    #[test]
+    fn test_contiguous_parse() {
+        let mut tt = tokenize("#[contiguous]\n#[max(7)]").unwrap();
+        let meta: Spanned<ItemMeta> = tt.parse().unwrap();
+        assert!(matches!(meta.meta[0], ItemMetaItem::Contiguous(_)));
+        assert!(matches!(
+            &meta.meta[1],
+            ItemMetaItem::Constraint(c) if c.value == ConstraintAttribute::Max(7)
+        ));
+    }
+
+    #[test]
+    fn test_http_parse() {
+        let mut tt =
+            tokenize("#[http(method = \"POST\", path = \"/users/{id}/posts/{post}\")]").unwrap();
+        let meta: Spanned<ItemMeta> = tt.parse().unwrap();
+        let http = match meta.meta.first().unwrap() {
+            ItemMetaItem::Http(h) => h,
+            _ => panic!("expected Http"),
+        };
+        assert_eq!(http.value.method.as_deref(), Some("POST"));
+        assert_eq!(
+            http.value.path_params().unwrap(),
+            vec!["id".to_string(), "post".to_string()]
+        );
+    }
+
+    #[test_case::test_case("/users/{id"; "unclosed")]
+    #[test_case::test_case("/users/id}"; "unmatched")]
+    #[test_case::test_case("/users/{}"; "empty")]
+    fn test_http_invalid_path(path: &str) {
+        let attr = HttpAttribute {
+            method: Some("GET".into()),
+            path: Some(path.into()),
+        };
+        assert!(attr.path_params().is_err());
+    }
+
+    #[test_case::test_case("#[graphql(query)]", &[GraphqlKind::Query]; "query")]
+    #[test_case::test_case("#[graphql(mutation)]", &[GraphqlKind::Mutation]; "mutation")]
+    #[test_case::test_case("#[graphql(query, mutation)]", &[GraphqlKind::Query, GraphqlKind::Mutation]; "both")]
+    fn test_graphql_parse(
+        src: &str,
+        expected: &[GraphqlKind],
+    ) {
+        let mut tt = tokenize(src).expect("Should parse");
+        let meta: Spanned<ItemMeta> = tt.parse().unwrap();
+        let graphql = match meta.meta.first().unwrap() {
+            ItemMetaItem::Graphql(g) => g,
+            _ => panic!("expected Graphql"),
+        };
+        assert_eq!(graphql.value.kinds, expected);
+    }
+
+    #[test]
+    fn test_graphql_unknown_kind() {
+        let mut tt = tokenize("#[graphql(subscription)]").unwrap();
+        assert!(tt.parse::<Spanned<ItemMeta>>().is_err());
+    }
+
+    #[test_case::test_case("#[example('42')]", ExampleAttribute::Value("42".into()); "value")]
+    #[test_case::test_case(r#"#[example('{"it\'s": 1}')]"#, ExampleAttribute::Value(r#"{"it's": 1}"#.into()); "escaped quote")]
+    #[test_case::test_case(r#"#[example(input = '{"id": 1}', output = '"ada"')]"#, ExampleAttribute::Call { input: Some(r#"{"id": 1}"#.into()), output: Some(r#""ada""#.into()) }; "call")]
+    #[test_case::test_case(r#"#[example(output = 'null')]"#, ExampleAttribute::Call { input: None, output: Some("null".into()) }; "output only")]
+    fn test_example_parse(
+        src: &str,
+        expected: ExampleAttribute,
+    ) {
+        let mut tt = tokenize(src).expect("Should parse");
+        let meta: Spanned<ItemMeta> = tt.parse().unwrap();
+        let example = match meta.meta.first().unwrap() {
+            ItemMetaItem::Example(e) => e,
+            _ => panic!("expected Example"),
+        };
+        assert_eq!(example.value, expected);
+    }
 }
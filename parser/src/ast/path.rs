@@ -24,7 +24,7 @@ impl TriesQualify for Path {
         potential: &mut BTreeSet<NamedItemContext>,
     ) {
         let context = match context {
-            RefOrItemContext::Ref(ctx) => ctx,
+            RefOrItemContext::Ref(ctx) | RefOrItemContext::Glob(ctx) => ctx,
             RefOrItemContext::Item(_) => return,
         };
 
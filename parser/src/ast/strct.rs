@@ -1,6 +1,7 @@
 use crate::{
     ast::{
         comment::{CommentAst, CommentStream},
+        meta::ItemMeta,
         ty::Type,
     },
     tokens::{self, Token},
@@ -64,6 +65,8 @@ impl ImplDiagnostic for Sep {
 #[derive(serde::Deserialize, serde::Serialize, Clone)]
 pub struct Arg {
     pub comments: CommentStream,
+    /// Field-level attributes, e.g. validation constraints such as `#[min(1)]`
+    pub meta: ItemMeta,
     pub name: SpannedToken![ident],
     pub sep: Spanned<Sep>,
     pub typ: Type,
@@ -75,6 +78,7 @@ impl tokens::ToTokens for Arg {
         tt: &mut crate::fmt::Printer,
     ) {
         tt.write(&self.comments);
+        tt.write(&self.meta);
         tt.write(&self.name);
         tt.write(&self.sep);
         tt.space();
@@ -92,6 +96,7 @@ impl Parse for Arg {
     fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, crate::tokens::LexingError> {
         Ok(Self {
             comments: CommentStream::parse(stream)?,
+            meta: ItemMeta::parse(stream)?,
             name: stream.parse()?,
             sep: stream.parse()?,
             typ: Type::parse(stream)?,
@@ -110,7 +115,7 @@ impl Peek for Arg {
                 break;
             }
         }
-        fork.peek::<Token![ident]>()
+        fork.peek::<Token![#]>() || fork.peek::<Token![ident]>()
     }
 }
 
@@ -210,4 +215,30 @@ mod test {
             })
         }
     }
+
+    #[test]
+    fn test_parse_field_constraints() {
+        let src = r#"struct Foo { #[min(1)] #[max(100)] count: i32, /* the slug */ #[pattern("^[a-z]+$")] #[len(1..64)] slug: str }"#;
+        let mut stream = tokenize(src).unwrap();
+        let parsed = Struct::parse(&mut stream).expect("Should parse struct");
+
+        let names = |arg: &Arg| {
+            arg.meta
+                .meta
+                .iter()
+                .map(|m| m.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&parsed.args.values[0].value), vec!["min", "max"]);
+        assert_eq!(names(&parsed.args.values[1].value), vec!["pattern", "len"]);
+
+        let mut p = crate::fmt::Printer::new(&crate::fmt::FormatConfig::default());
+        p.write(&parsed);
+        let mut stream = tokenize(&p.buf).unwrap();
+        let reparsed = Struct::parse(&mut stream).expect("printed struct should parse");
+        assert_eq!(
+            names(&reparsed.args.values[1].value),
+            vec!["pattern", "len"]
+        );
+    }
 }
@@ -2,7 +2,12 @@ use std::collections::BTreeSet;
 
 use crate::{
     SpannedToken, Token,
-    ast::{anonymous::AnonymousStruct, array::Array, one_of::AnonymousOneOf, union::Union},
+    ast::{
+        anonymous::AnonymousStruct,
+        array::{Array, ArraySize},
+        one_of::AnonymousOneOf,
+        union::Union,
+    },
     ctx::{NamedItemContext, RefOrItemContext},
     defs::Spanned,
     tokens::*,
@@ -150,7 +155,7 @@ impl TriesQualify for SpannedToken![ident] {
         potential: &mut BTreeSet<NamedItemContext>,
     ) {
         match context {
-            RefOrItemContext::Ref(ctx) => {
+            RefOrItemContext::Ref(ctx) | RefOrItemContext::Glob(ctx) => {
                 potential.insert(ctx.item(self.clone()));
             },
             RefOrItemContext::Item(item) => {
@@ -174,7 +179,7 @@ pub trait TriesQualify {
         let mut qualified = BTreeSet::new();
         for ctx in context {
             match ctx {
-                RefOrItemContext::Ref(..) => {
+                RefOrItemContext::Ref(..) | RefOrItemContext::Glob(..) => {
                     self.qualify_one(ctx, &mut qualified);
                 },
                 RefOrItemContext::Item(qual) => {
@@ -367,8 +372,8 @@ impl Parse for Type {
         while stream.peek::<toks::LBracketToken>() {
             let mut inner_tokens;
             let bracket = bracket!(inner_tokens in stream);
-            let size: Option<SpannedToken![number]> = if inner_tokens.peek::<Token![number]>() {
-                Some(inner_tokens.parse()?)
+            let size: Option<ArraySize> = if inner_tokens.peek::<ArraySize>() {
+                Some(ArraySize::parse(&mut inner_tokens)?)
             } else {
                 None
             };
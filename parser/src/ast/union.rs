@@ -149,9 +149,11 @@ impl ToTokens for Union {
         let len = self.types.values.len();
         for (i, item) in self.types.values.iter().enumerate() {
             item.value.write(tt);
-            if item.sep.is_some() && i < len - 1 {
+            if let Some(sep) = &item.sep
+                && i < len - 1
+            {
                 tt.space();
-                item.sep.as_ref().unwrap().write(tt);
+                sep.write(tt);
                 tt.space();
             }
         }
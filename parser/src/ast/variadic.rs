@@ -67,6 +67,10 @@ impl Peek for Variant {
     fn is(token: &toks::Token) -> bool {
         <Token![ident]>::is(token)
     }
+
+    fn peek(stream: &crate::tokens::TokenStream) -> bool {
+        CommentStream::peek_past::<Token![ident]>(stream)
+    }
 }
 
 impl ToTokens for Variant {
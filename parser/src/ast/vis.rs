@@ -0,0 +1,66 @@
+use crate::{
+    SpannedToken,
+    tokens::{ImplDiagnostic, LexingError, Parse, Peek, ToTokens, Token},
+};
+
+/// Visibility modifier written before an item keyword, e.g. `internal struct Helper {...}`.
+///
+/// `pub` and `internal` are contextual keywords: they are only recognised directly before
+/// an item, so namespaces and fields may still use them as names. Items without a
+/// modifier are public.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub enum Visibility {
+    Public { kw: SpannedToken![ident] },
+    Internal { kw: SpannedToken![ident] },
+}
+
+impl Visibility {
+    pub const PUBLIC: &'static str = "pub";
+    pub const INTERNAL: &'static str = "internal";
+
+    pub fn is_internal(&self) -> bool {
+        matches!(self, Self::Internal { .. })
+    }
+
+    pub fn kw(&self) -> &SpannedToken![ident] {
+        match self {
+            Self::Public { kw } | Self::Internal { kw } => kw,
+        }
+    }
+}
+
+impl ImplDiagnostic for Visibility {
+    fn fmt() -> &'static str {
+        "pub | internal"
+    }
+}
+
+impl Peek for Visibility {
+    fn is(token: &Token) -> bool {
+        matches!(token, Token::Ident(name) if name == Self::PUBLIC || name == Self::INTERNAL)
+    }
+}
+
+impl Parse for Visibility {
+    fn parse(stream: &mut crate::tokens::TokenStream) -> Result<Self, LexingError> {
+        let kw: SpannedToken![ident] = stream.parse()?;
+        match kw.borrow_string().as_str() {
+            Self::PUBLIC => Ok(Self::Public { kw }),
+            Self::INTERNAL => Ok(Self::Internal { kw }),
+            _ => {
+                let span = kw.span.clone();
+                Err(LexingError::expected::<Self>(kw.value.token()).with_span(span))
+            },
+        }
+    }
+}
+
+impl ToTokens for Visibility {
+    fn write(
+        &self,
+        tt: &mut crate::fmt::Printer,
+    ) {
+        tt.write(self.kw());
+        tt.space();
+    }
+}
@@ -1,4 +1,10 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
 use kintsu_manifests::version::Version;
 use tokio::sync::MutexGuard;
@@ -71,6 +77,8 @@ impl CachedSchema {
 #[derive(Clone)]
 pub struct SchemaCache {
     inner: Arc<tokio::sync::Mutex<BTreeMap<CacheKey, CachedSchema>>>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
 }
 
 impl Default for SchemaCache {
@@ -83,6 +91,8 @@ impl SchemaCache {
     pub fn new() -> Self {
         Self {
             inner: Default::default(),
+            hits: Default::default(),
+            misses: Default::default(),
         }
     }
 
@@ -93,6 +103,16 @@ impl SchemaCache {
         f(&self.inner.lock().await)
     }
 
+    /// Number of lookups that found a cached schema.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of lookups that found nothing.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
     pub async fn entry_count(&self) -> usize {
         self.with_read(|inner| inner.len()).await
     }
@@ -127,9 +147,11 @@ impl SchemaCache {
             .get(key)
             .cloned()
             .inspect(|_| {
+                self.hits.fetch_add(1, Ordering::Relaxed);
                 tracing::trace!("cache hit for key: {}", key);
             })
             .or_else(|| {
+                self.misses.fetch_add(1, Ordering::Relaxed);
                 tracing::trace!("cache miss for key: {}", key);
                 None
             })
@@ -5,9 +5,11 @@ use crate::{
     ast::{
         comment::CommentStream,
         items::{
-            EnumDef, ErrorDef, NamespaceDef, OneOfDef, OperationDef, StructDef, TypeDef, UseDef,
+            ConstDef, EnumDef, ErrorDef, NamespaceDef, OneOfDef, OperationDef, StructDef, TypeDef,
+            UseDef,
         },
         meta::{ErrorMeta, VersionMeta},
+        vis::Visibility,
     },
     defs::{Span, Spanned, Spans},
 };
@@ -20,6 +22,7 @@ pub enum NamespaceChild {
     Type(TypeDef),
     Error(ErrorDef),
     Operation(OperationDef),
+    Const(ConstDef),
 }
 
 impl NamespaceChild {
@@ -32,8 +35,28 @@ impl NamespaceChild {
             NamespaceChild::Type(t) => t.def.ty.type_name(),
             NamespaceChild::Error(_) => "error".to_string(),
             NamespaceChild::Operation(_) => "operation".to_string(),
+            NamespaceChild::Const(_) => "const".to_string(),
         }
     }
+
+    pub fn visibility(&self) -> Option<&Spanned<Visibility>> {
+        match self {
+            NamespaceChild::Namespace(_) => None,
+            NamespaceChild::OneOf(def) => def.vis.as_ref(),
+            NamespaceChild::Enum(def) => def.vis.as_ref(),
+            NamespaceChild::Struct(def) => def.vis.as_ref(),
+            NamespaceChild::Type(def) => def.vis.as_ref(),
+            NamespaceChild::Error(def) => def.vis.as_ref(),
+            NamespaceChild::Operation(def) => def.vis.as_ref(),
+            NamespaceChild::Const(def) => def.vis.as_ref(),
+        }
+    }
+
+    /// Internal children are usable within their package but excluded from declarations.
+    pub fn is_internal(&self) -> bool {
+        self.visibility()
+            .is_some_and(|vis| vis.value.is_internal())
+    }
 }
 
 #[derive(Clone)]
@@ -46,6 +69,19 @@ pub enum Definition {
     Operation(Arc<OperationDef>),
 }
 
+impl Definition {
+    pub fn is_internal(&self) -> bool {
+        match self {
+            Definition::Struct(def) => def.is_internal(),
+            Definition::Enum(def) => def.is_internal(),
+            Definition::OneOf(def) => def.is_internal(),
+            Definition::Error(def) => def.is_internal(),
+            Definition::TypeAlias(def) => def.is_internal(),
+            Definition::Operation(def) => def.is_internal(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ResolvedType {
     pub kind: Definition,
@@ -4,7 +4,11 @@ use std::{
 };
 
 use kintsu_fs::FileSystem;
-use kintsu_manifests::{config::NewForNamed, lock::Lockfiles, version::parse_version};
+use kintsu_manifests::{
+    config::NewForNamed,
+    lock::{Lockfile, Lockfiles},
+    version::parse_version,
+};
 use tokio::sync::RwLock;
 
 use crate::{
@@ -13,11 +17,14 @@ use crate::{
 };
 
 use super::{
-    loader::DependencyLoader, lockfile::LockfileManager, resolver::PackageResolver,
+    loader::DependencyLoader,
+    lockfile::LockfileManager,
+    report::{BuildReport, CacheReport, DependencyFetch, DiagnosticReport, PhaseProfiler},
+    resolver::PackageResolver,
     state::SharedCompilationState,
 };
 
-use kintsu_cli_core::ProgressManager;
+use kintsu_cli_core::{ProgressEvent, ProgressManager};
 
 pub struct CompileCtx {
     pub root: Arc<SchemaCtx>,
@@ -32,6 +39,9 @@ pub struct CompileCtx {
 
     pub(super) root_path: PathBuf,
     pub(super) progress: ProgressManager,
+    pub(super) profiler: PhaseProfiler,
+    pub(super) hooks: Vec<Arc<dyn super::hooks::CompileHook>>,
+    pub(super) deny_warnings: bool,
 }
 
 impl CompileCtx {
@@ -73,21 +83,81 @@ impl CompileCtx {
         (self.cache.entry_count().await, self.cache.size_deep().await)
     }
 
+    /// Errors from files skipped while loading the root schema and its dependencies.
+    pub async fn recovered_errors(&self) -> Vec<crate::CompilerError> {
+        let mut errors = self.root.recovered_errors.clone();
+        for dep in self.state.read().await.dependencies.values() {
+            errors.extend(dep.recovered_errors.iter().cloned());
+        }
+        errors
+    }
+
+    /// Reports recovered errors as the root causes of a compilation, with any error from
+    /// `result` following them marked as secondary.
+    async fn with_recovered_errors(
+        &self,
+        result: crate::Result<()>,
+    ) -> crate::Result<()> {
+        let mut errors = self.recovered_errors().await;
+        if errors.is_empty() {
+            return result;
+        }
+
+        if let Err(e) = result {
+            errors.push(e.to_compiler_error().secondary());
+        }
+
+        Err(crate::Error::Compiler(crate::CompilerError::Multiple(
+            errors,
+        )))
+    }
+
     pub async fn should_write_lockfile(&self) -> bool {
         let state = self.state.read().await;
         state.lockfile_invalidated | state.lockfile.is_none()
     }
 
+    pub(super) fn root_version(&self) -> crate::Result<kintsu_manifests::version::Version> {
+        Ok(parse_version(
+            &self
+                .root
+                .package
+                .package()
+                .version
+                .to_string(),
+        )?)
+    }
+
+    /// The lockfile for this compilation, without writing it.
+    pub async fn build_lockfile(&self) -> crate::Result<Lockfile> {
+        LockfileManager::build_lockfile(
+            &self.state,
+            self.root_fs.clone(),
+            &self.root_path,
+            &self.root.package.package().name,
+            self.root_version()?,
+        )
+        .await
+    }
+
+    /// Fails [`Self::finalize`] when warnings were emitted, other than those whose codes
+    /// the `allow_warnings` of the manifest's `[lint]` table lists.
+    pub fn set_deny_warnings(
+        &mut self,
+        deny: bool,
+    ) {
+        self.deny_warnings = deny;
+    }
+
     pub async fn finalize(&self) -> crate::Result<()> {
+        let phase_start = web_time::Instant::now();
+        self.lint().await?;
+        if self.deny_warnings {
+            self.check_warnings().await?;
+        }
+
         if self.should_write_lockfile().await {
-            let root_version = parse_version(
-                &self
-                    .root
-                    .package
-                    .package()
-                    .version
-                    .to_string(),
-            )?;
+            let root_version = self.root_version()?;
             LockfileManager::write_lockfile(
                 &self.state,
                 self.root_fs.clone(),
@@ -105,6 +175,8 @@ impl CompileCtx {
         } else {
             tracing::debug!("Lockfile unchanged, skipping write");
         }
+        self.profiler
+            .record("finalize", phase_start.elapsed());
 
         tracing::info!(
             entries = {
@@ -114,6 +186,11 @@ impl CompileCtx {
             "compilation stats",
         );
 
+        #[cfg(feature = "profiling")]
+        if let Some(capture) = super::trace::SpanCapture::active() {
+            capture.write(self.root_fs.as_ref()).await?;
+        }
+
         #[cfg(debug_assertions)]
         {
             println!("Registered Types:\n{}", self.hierarchy());
@@ -122,6 +199,83 @@ impl CompileCtx {
         Ok(())
     }
 
+    /// Summarizes this compilation: phase timings, schema cache usage, loaded
+    /// dependencies and diagnostic counts. Call after [`Self::finalize`] so lockfile
+    /// writing is included in the timings.
+    pub async fn build_report(&self) -> BuildReport {
+        let state = self.state.read().await;
+        let mut dependencies = Vec::with_capacity(state.dependencies.len());
+        for (name, schema) in &state.dependencies {
+            let source = state
+                .resolved_metadata
+                .get(name)
+                .map(|meta| &meta.source);
+            dependencies.push(DependencyFetch::from_schema(name, schema, source).await);
+        }
+        drop(state);
+
+        let (errors, warnings) = kintsu_events::counts().await;
+        let (entries, size_bytes) = self.cache_stats().await;
+        let package = self.root.package.package();
+
+        BuildReport {
+            package: package.name.clone(),
+            version: package.version.to_string(),
+            total_ms: self.profiler.elapsed().as_secs_f64() * 1000.0,
+            phases: self.profiler.phases(),
+            cache: CacheReport {
+                hits: self.cache.hits(),
+                misses: self.cache.misses(),
+                entries,
+                size_bytes,
+            },
+            dependencies: dependencies.into_iter().collect(),
+            diagnostics: DiagnosticReport {
+                errors,
+                warnings,
+                recovered: self.recovered_errors().await.len(),
+            },
+        }
+    }
+
+    /// The packages and namespaces this compilation loaded, as the schemas import them.
+    pub async fn dependency_tree(&self) -> super::DependencyTree {
+        let state = self.state.read().await;
+        super::DependencyTree::from_schemas(
+            &self.root,
+            &state.dependencies,
+            &state.resolved_metadata,
+        )
+        .await
+    }
+
+    /// Every operation, field and variant depending on the type at `type_path`, e.g.
+    /// `pkg::foo::Data`, directly or through other types, across all loaded packages.
+    pub async fn impact_of(
+        &self,
+        type_path: &str,
+    ) -> crate::Result<crate::ctx::Impact> {
+        let state = self.state.read().await;
+        crate::ctx::Impact::analyse(
+            std::iter::once(&*self.root).chain(
+                state
+                    .dependencies
+                    .values()
+                    .map(|schema| &**schema),
+            ),
+            type_path,
+        )
+        .await
+    }
+
+    /// How much of each namespace of the root package is documented. Internal items are
+    /// not counted.
+    pub async fn doc_coverage(&self) -> crate::Result<crate::declare::DocCoverage> {
+        let declaration =
+            Self::convert_schema_to_declaration(&self.root, &self.type_registry(), false).await?;
+        Ok(declaration.doc_coverage())
+    }
+
     pub fn hierarchy(&self) -> String {
         let mut result = String::new();
 
@@ -134,7 +288,7 @@ impl CompileCtx {
 }
 
 impl CompileCtx {
-    fn default_resolver(fs: Arc<dyn FileSystem>) -> Arc<dyn PackageResolver> {
+    pub(super) fn default_resolver(fs: Arc<dyn FileSystem>) -> Arc<dyn PackageResolver> {
         Arc::new(super::resolver::Resolver::new(fs))
     }
 
@@ -170,7 +324,40 @@ impl CompileCtx {
         max_concurrent_tasks: usize,
         show_progress: bool,
     ) -> crate::Result<Self> {
+        let existing_lockfile =
+            Lockfiles::new_for_opt(fs.as_ref(), entry_path.as_ref())?.map(|lockfiles| {
+                match lockfiles {
+                    Lockfiles::V1(lockfile) => lockfile,
+                }
+            });
+
         let progress = ProgressManager::new(show_progress);
+        let ctx = Self::with_lockfile(
+            fs,
+            resolver,
+            entry_path,
+            existing_lockfile,
+            max_concurrent_tasks,
+            progress.clone(),
+        )
+        .await?;
+        progress.finish();
+
+        Ok(ctx)
+    }
+
+    /// Compiles the package in `entry_path` against `existing_lockfile` instead of the
+    /// lockfile in its own directory, as workspace members share one lockfile. Members
+    /// report their progress to the workspace's `progress`.
+    pub(super) async fn with_lockfile(
+        fs: Arc<dyn FileSystem>,
+        resolver: Arc<dyn super::resolver::PackageResolver>,
+        entry_path: impl AsRef<Path>,
+        existing_lockfile: Option<Lockfile>,
+        max_concurrent_tasks: usize,
+        progress: ProgressManager,
+    ) -> crate::Result<Self> {
+        let profiler = PhaseProfiler::new();
         let registry = TypeRegistry::new();
 
         let pb = progress.add_spinner("Initializing");
@@ -178,19 +365,21 @@ impl CompileCtx {
 
         let entry_path_ref = entry_path.as_ref();
         let root_path = entry_path_ref.to_path_buf();
-        let root =
-            Arc::new(SchemaCtx::from_path(fs.as_ref(), entry_path_ref, registry.clone()).await?);
+        let root = Arc::new(
+            profiler
+                .time(
+                    "load root schema",
+                    SchemaCtx::from_path(fs.as_ref(), entry_path_ref, registry.clone()),
+                )
+                .await?,
+        );
 
-        pb.finish_with_message("root schema");
+        pb.finish_and_clear();
+        let package = root.package.package().name.clone();
+        progress.event(&ProgressEvent::PackageStarted { package: &package });
 
         let cache = SchemaCache::new();
 
-        let existing_lockfile = Lockfiles::new_for_opt(fs.as_ref(), &root_path)?.map(|lockfiles| {
-            match lockfiles {
-                Lockfiles::V1(lockfile) => lockfile,
-            }
-        });
-
         let mut initial_state = SharedCompilationState::new();
         initial_state.lockfile = existing_lockfile;
 
@@ -205,23 +394,31 @@ impl CompileCtx {
             cache: cache.clone(),
             root_path: root_path.clone(),
             progress: progress.clone(),
+            profiler: profiler.clone(),
+            hooks: Vec::new(),
+            deny_warnings: false,
         };
 
-        DependencyLoader::load_dependencies_parallel(
-            &ctx.root,
-            state.clone(),
-            resolver.clone(),
-            cache.clone(),
-            registry.clone(),
-            root_path.clone(),
-            max_concurrent_tasks,
-            &progress,
-        )
-        .await?;
+        profiler
+            .time(
+                "load dependencies",
+                DependencyLoader::load_dependencies_parallel(
+                    &ctx.root,
+                    state.clone(),
+                    resolver.clone(),
+                    cache.clone(),
+                    registry.clone(),
+                    root_path.clone(),
+                    max_concurrent_tasks,
+                    &progress,
+                ),
+            )
+            .await?;
 
-        super::schema_compiler::SchemaCompiler::compile_all(&ctx).await?;
+        let compiled = super::schema_compiler::SchemaCompiler::compile_all(&ctx).await;
+        ctx.with_recovered_errors(compiled).await?;
 
-        progress.finish();
+        progress.event(&ProgressEvent::PackageFinished { package: &package });
 
         Ok(ctx)
     }
@@ -236,6 +433,7 @@ impl CompileCtx {
         show_progress: bool,
     ) -> crate::Result<Self> {
         let progress = ProgressManager::new(show_progress);
+        let profiler = PhaseProfiler::new();
         let registry = TypeRegistry::new();
 
         let pb = progress.add_spinner("Initializing");
@@ -247,10 +445,18 @@ impl CompileCtx {
         let entry_path_ref = entry_path.as_ref();
         let root_path = entry_path_ref.to_path_buf();
 
-        let root =
-            Arc::new(SchemaCtx::from_path(fs.as_ref(), entry_path_ref, registry.clone()).await?);
+        let root = Arc::new(
+            profiler
+                .time(
+                    "load root schema",
+                    SchemaCtx::from_path(fs.as_ref(), entry_path_ref, registry.clone()),
+                )
+                .await?,
+        );
 
-        pb.finish_with_message(format!("completed {}", root.package.package().name));
+        pb.finish_and_clear();
+        let package = root.package.package().name.clone();
+        progress.event(&ProgressEvent::PackageStarted { package: &package });
 
         let cache = SchemaCache::new();
 
@@ -274,22 +480,31 @@ impl CompileCtx {
             cache: cache.clone(),
             root_path: root_path.clone(),
             progress: progress.clone(),
+            profiler: profiler.clone(),
+            hooks: Vec::new(),
+            deny_warnings: false,
         };
 
-        DependencyLoader::load_dependencies_parallel(
-            &ctx.root,
-            state.clone(),
-            resolver.clone(),
-            cache.clone(),
-            registry.clone(),
-            root_path.clone(),
-            max_concurrent_tasks,
-            &progress,
-        )
-        .await?;
+        profiler
+            .time(
+                "load dependencies",
+                DependencyLoader::load_dependencies_parallel(
+                    &ctx.root,
+                    state.clone(),
+                    resolver.clone(),
+                    cache.clone(),
+                    registry.clone(),
+                    root_path.clone(),
+                    max_concurrent_tasks,
+                    &progress,
+                ),
+            )
+            .await?;
 
-        super::schema_compiler::SchemaCompiler::compile_all(&ctx).await?;
+        let compiled = super::schema_compiler::SchemaCompiler::compile_all(&ctx).await;
+        ctx.with_recovered_errors(compiled).await?;
 
+        progress.event(&ProgressEvent::PackageFinished { package: &package });
         progress.finish();
 
         Ok(ctx)
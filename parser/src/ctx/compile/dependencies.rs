@@ -0,0 +1,264 @@
+//! Adding and removing dependencies of a package: the manifest is edited in place, the
+//! package is compiled against the edit and the lockfile rewritten, so a dependency is only
+//! kept when it resolves. Failed edits leave the manifest as it was.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use kintsu_fs::FileSystem;
+use kintsu_manifests::{
+    config::NewForNamed,
+    edit::ManifestEditor,
+    package::{Dependency, PackageManifests, PathDependency},
+    version::{Version, VersionReq},
+};
+
+use super::{
+    CompileCtx,
+    resolver::{PackageIndex, PackageResolver},
+    utils::normalize_package_to_import_name,
+};
+
+/// Where a dependency being added comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencySource {
+    /// The registry, at the highest published version matching the requirement. Without
+    /// one, the highest stable version is required as `^version`.
+    Registry(Option<VersionReq>),
+    /// A package on disk, relative to the package it is added to.
+    Path(PathBuf),
+}
+
+/// A dependency added by [`CompileCtx::add_dependency`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct AddedDependency {
+    pub name: String,
+    /// The requirement written to the manifest, for registry dependencies
+    pub requirement: Option<String>,
+    /// The version the dependency resolved to and is locked at
+    pub version: String,
+    /// Whether the manifest declared the dependency before, which it now replaces
+    pub replaced: bool,
+}
+
+impl std::fmt::Display for AddedDependency {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        let action = if self.replaced {
+            "updated"
+        } else {
+            "added"
+        };
+        match &self.requirement {
+            Some(requirement) => {
+                write!(
+                    f,
+                    "{action} {} {requirement} (resolved {})",
+                    self.name, self.version
+                )
+            },
+            None => write!(f, "{action} {} (resolved {})", self.name, self.version),
+        }
+    }
+}
+
+/// The highest version of `package` in `index` matching `requirement`, or the highest
+/// stable one without a requirement. Yanked versions are never chosen.
+pub fn latest_version(
+    index: &dyn PackageIndex,
+    package: &str,
+    requirement: Option<&VersionReq>,
+) -> crate::Result<Option<Version>> {
+    let mut versions = index.versions(package)?;
+    versions.sort();
+
+    for version in versions.into_iter().rev() {
+        let matches = match requirement {
+            Some(requirement) => requirement.matches(&version),
+            None => version.pre.is_empty(),
+        };
+        if matches && !index.is_yanked(package, &version)? {
+            return Ok(Some(version));
+        }
+    }
+    Ok(None)
+}
+
+impl CompileCtx {
+    /// Adds the dependency `name` to the package in `root_path`, or replaces it, and
+    /// compiles the package with it, which updates the lockfile. Registry dependencies are
+    /// looked up in the index of `resolver`.
+    pub async fn add_dependency(
+        fs: Arc<dyn FileSystem>,
+        resolver: Arc<dyn PackageResolver>,
+        root_path: impl AsRef<Path>,
+        name: &str,
+        source: DependencySource,
+    ) -> crate::Result<AddedDependency> {
+        let root_path = root_path.as_ref();
+        let mut editor = Self::package_editor(fs.as_ref(), root_path)?;
+        let replaced = editor.has_dependency(name);
+
+        let (dependency, requirement) = match source {
+            DependencySource::Registry(requirement) => {
+                let Some(index) = resolver.index() else {
+                    return Err(crate::PackageError::manifest_error(format!(
+                        "cannot add '{name}': no registry is available to look it up in"
+                    ))
+                    .unlocated()
+                    .build()
+                    .into());
+                };
+                let Some(latest) = latest_version(index, name, requirement.as_ref())? else {
+                    let wanted = requirement
+                        .as_ref()
+                        .map(|requirement| format!(" matching {requirement}"))
+                        .unwrap_or_default();
+                    return Err(crate::PackageError::unsatisfiable_dependencies(format!(
+                        "no published version of '{name}'{wanted}"
+                    ))
+                    .unlocated()
+                    .build()
+                    .into());
+                };
+                let requirement = requirement.unwrap_or_else(|| {
+                    VersionReq::parse(&format!("^{latest}")).expect("a version is a requirement")
+                });
+                let dependency = Dependency::remote(requirement.clone().into());
+                (dependency, Some(requirement.to_string()))
+            },
+            DependencySource::Path(path) => (Dependency::Path(PathDependency { path }), None),
+        };
+
+        editor.set_dependency(name, &dependency)?;
+        let ctx = Self::compile_edit(fs, resolver.clone(), root_path, &editor).await?;
+        let version = ctx
+            .resolved_version(resolver.as_ref(), name, &dependency)
+            .await?;
+
+        Ok(AddedDependency {
+            name: name.to_string(),
+            requirement,
+            version,
+            replaced,
+        })
+    }
+
+    /// Removes the dependency `name` from the package in `root_path` and compiles the
+    /// package without it, which updates the lockfile. Returns whether the manifest declared
+    /// it; nothing is changed when it did not.
+    pub async fn remove_dependency(
+        fs: Arc<dyn FileSystem>,
+        resolver: Arc<dyn PackageResolver>,
+        root_path: impl AsRef<Path>,
+        name: &str,
+    ) -> crate::Result<bool> {
+        let root_path = root_path.as_ref();
+        let mut editor = Self::package_editor(fs.as_ref(), root_path)?;
+        if !editor.remove_dependency(name) {
+            return Ok(false);
+        }
+
+        Self::compile_edit(fs, resolver, root_path, &editor).await?;
+        Ok(true)
+    }
+
+    /// The version the dependency `name` resolved to: as loaded when the package imports
+    /// it, otherwise as solved or resolved on its own.
+    async fn resolved_version(
+        &self,
+        resolver: &dyn PackageResolver,
+        name: &str,
+        dependency: &Dependency,
+    ) -> crate::Result<String> {
+        let loaded = match self.get_dependency(name).await {
+            Some(loaded) => Some(loaded),
+            None => {
+                self.get_dependency(&normalize_package_to_import_name(name))
+                    .await
+            },
+        };
+        if let Some(loaded) = loaded {
+            return Ok(loaded.package.package().version.to_string());
+        }
+
+        if let Some(version) = self
+            .state
+            .read()
+            .await
+            .registry_solution
+            .get(name)
+        {
+            return Ok(version.to_string());
+        }
+
+        Ok(resolver
+            .resolve(&self.root_path, name, dependency)?
+            .version
+            .to_string())
+    }
+
+    /// An editor for the manifest of the package in `root_path`. Workspace dependencies
+    /// are shared by every member, so they are edited by hand.
+    fn package_editor(
+        fs: &dyn FileSystem,
+        root_path: &Path,
+    ) -> crate::Result<ManifestEditor> {
+        let editor = ManifestEditor::read(fs, root_path)?;
+        if editor.is_workspace() {
+            return Err(crate::PackageError::manifest_error(format!(
+                "{} is a workspace, add dependencies to one of its members instead",
+                root_path.display()
+            ))
+            .unlocated()
+            .build()
+            .into());
+        }
+        Ok(editor)
+    }
+
+    /// Writes `editor` and compiles the package against it, restoring the manifest when
+    /// compilation fails. The compile error is returned, with the restore's failure too
+    /// should the manifest not be restored.
+    pub(super) async fn compile_edit(
+        fs: Arc<dyn FileSystem>,
+        resolver: Arc<dyn PackageResolver>,
+        root_path: &Path,
+        editor: &ManifestEditor,
+    ) -> crate::Result<Self> {
+        let path = PackageManifests::path(root_path);
+        let original = fs.read_to_string_sync(&path)?;
+        editor.write(fs.as_ref(), root_path).await?;
+
+        let compiled = async {
+            let ctx =
+                Self::with_fs_and_config(fs.clone(), resolver, root_path, num_cpus::get(), false)
+                    .await?;
+            ctx.finalize().await?;
+            Ok(ctx)
+        }
+        .await;
+
+        let Err(err) = compiled else {
+            return compiled;
+        };
+        // written back as read, as a manifest which was already invalid fails the editor's
+        // checks
+        match fs.write(&path, original.into_bytes()).await {
+            Ok(()) => Err(err),
+            Err(restore) => {
+                let restore = crate::PackageError::manifest_error(format!(
+                    "could not restore {}: {restore}",
+                    path.display()
+                ))
+                .unlocated()
+                .build();
+                Err(crate::CompilerError::Multiple(vec![err.into(), restore]).into())
+            },
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Hooks run once the declarations of a package are emitted, e.g. to run custom generators
+//! or policy checks over the [`DeclarationBundle`].
+//!
+//! Hooks are registered on a [`CompileCtx`] with [`CompileCtx::add_hook`]. The commands
+//! under `[[hooks]]` in the root manifest only run once the compiling tool opts into them
+//! with [`CompileCtx::add_manifest_hooks`], as a registry compiling published packages must
+//! not run their commands. A hook which fails stops emission with KPK2001.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Arc,
+};
+
+use kintsu_manifests::hooks::HookCommand;
+
+use crate::declare::DeclarationBundle;
+
+use super::CompileCtx;
+
+pub trait CompileHook: Send + Sync {
+    /// Name the hook is reported by.
+    fn name(&self) -> &str;
+
+    /// Runs over the declarations just emitted, failing with the reason when they do not
+    /// pass.
+    fn after_emit(
+        &self,
+        bundle: &DeclarationBundle,
+    ) -> Result<(), String>;
+}
+
+/// A `[[hooks]]` command, given the bundle as JSON on stdin. It fails by exiting with a
+/// non-zero status, with its stderr as the reason.
+pub struct CommandHook {
+    command: HookCommand,
+    dir: PathBuf,
+}
+
+impl CommandHook {
+    /// Runs `command` in the package directory `dir`.
+    pub fn new(
+        command: HookCommand,
+        dir: impl AsRef<Path>,
+    ) -> Self {
+        Self {
+            command,
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl CompileHook for CommandHook {
+    fn name(&self) -> &str {
+        &self.command.name
+    }
+
+    fn after_emit(
+        &self,
+        bundle: &DeclarationBundle,
+    ) -> Result<(), String> {
+        let json = serde_json::to_vec(bundle).map_err(|err| err.to_string())?;
+        let mut child = Command::new(&self.command.command)
+            .args(&self.command.args)
+            .current_dir(&self.dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("could not run '{}': {err}", self.command.command))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // a command which exits without reading the bundle is judged by its status
+            let _ = stdin.write_all(&json);
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|err| err.to_string())?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(match stderr.trim() {
+            "" => format!("'{}' exited with {}", self.command.command, output.status),
+            reason => reason.to_string(),
+        })
+    }
+}
+
+impl CompileCtx {
+    /// Registers `hook` to run whenever declarations are emitted.
+    pub fn add_hook(
+        &mut self,
+        hook: impl CompileHook + 'static,
+    ) {
+        self.hooks.push(Arc::new(hook));
+    }
+
+    /// Registers the commands under `[[hooks]]` in the root manifest, run in the package
+    /// directory.
+    pub fn add_manifest_hooks(&mut self) {
+        let commands = self.root.package.hooks().to_vec();
+        for command in commands {
+            let hook = CommandHook::new(command, &self.root_path);
+            self.add_hook(hook);
+        }
+    }
+
+    /// Runs each registered hook in order, stopping at the first which fails.
+    pub(crate) fn run_hooks(
+        &self,
+        bundle: &DeclarationBundle,
+    ) -> crate::Result<()> {
+        for hook in &self.hooks {
+            let span = tracing::info_span!("hook", name = hook.name());
+            let _enter = span.enter();
+            hook.after_emit(bundle)
+                .map_err(|reason| -> crate::Error {
+                    crate::PackageError::hook_failed(hook.name(), reason)
+                        .unlocated()
+                        .build()
+                        .into()
+                })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use crate::declare::TypeRegistryDeclaration;
+
+    fn bundle() -> DeclarationBundle {
+        DeclarationBundle {
+            root: TypeRegistryDeclaration::new("shop".into()),
+            dependencies: Default::default(),
+        }
+    }
+
+    fn shell(script: &str) -> CommandHook {
+        CommandHook::new(
+            HookCommand {
+                name: "policy".into(),
+                command: "sh".into(),
+                args: vec!["-c".into(), script.into()],
+            },
+            ".",
+        )
+    }
+
+    #[test]
+    fn commands_read_the_bundle() {
+        let hook = shell("grep -q '\"package\":\"shop\"' || { echo 'no shop' >&2; exit 1; }");
+        assert_eq!(hook.after_emit(&bundle()), Ok(()));
+    }
+
+    #[test]
+    fn commands_fail_with_their_stderr() {
+        let hook = shell("echo 'operations must be documented' >&2; exit 3");
+        assert_eq!(
+            hook.after_emit(&bundle()),
+            Err("operations must be documented".into())
+        );
+
+        let hook = shell("exit 1");
+        assert_eq!(
+            hook.after_emit(&bundle()),
+            Err("'sh' exited with exit status: 1".into())
+        );
+    }
+}
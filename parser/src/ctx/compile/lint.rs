@@ -0,0 +1,336 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use convert_case::{Case, Casing};
+use kintsu_manifests::rules::RuleLevel;
+
+use crate::{
+    ast::enm::Enum,
+    ctx::{Definition, NamespaceCtx, common::NamespaceChild},
+    defs::Spanned,
+    lint::{RuleRegistry, Violation},
+};
+
+use super::CompileCtx;
+
+type Location = (PathBuf, crate::Span);
+
+/// The violation `error` as a diagnostic with the severity of its rule's `level`.
+pub(super) fn lint_diagnostic(
+    level: RuleLevel,
+    error: crate::CompilerError,
+) -> kintsu_events::Diagnostic {
+    let mut diagnostic = kintsu_events::Diagnostic::from(error);
+    diagnostic.severity = match level {
+        RuleLevel::Error => kintsu_errors::Severity::Error,
+        RuleLevel::Info => kintsu_errors::Severity::Info,
+        _ => kintsu_errors::Severity::Warning,
+    };
+    diagnostic
+}
+
+/// Fails the build with the violations of rules at the error level, if there are any.
+pub(super) fn lint_failure(mut errors: Vec<crate::CompilerError>) -> crate::Result<()> {
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0).into()),
+        _ => Err(crate::CompilerError::Multiple(errors).into()),
+    }
+}
+
+/// Where the names of a package were declared: items keyed by namespace path and name, and
+/// the fields and variants of types keyed by their type too.
+#[derive(Default)]
+struct ItemLocations {
+    items: BTreeMap<(Vec<String>, String), Location>,
+    members: BTreeMap<(Vec<String>, String, String), Location>,
+    sources: BTreeMap<PathBuf, Arc<String>>,
+}
+
+impl ItemLocations {
+    fn name_span<T>(name: &Spanned<T>) -> Option<crate::Span> {
+        let span = name.span();
+        // generated items have no name in the source
+        (span.start != span.end).then(|| crate::Span::new(span.start, span.end))
+    }
+
+    fn insert_item<T>(
+        &mut self,
+        namespace: &[String],
+        name: &Spanned<T>,
+        text: String,
+        source: &PathBuf,
+    ) {
+        if let Some(span) = Self::name_span(name) {
+            self.items
+                .insert((namespace.to_vec(), text), (source.clone(), span));
+        }
+    }
+
+    fn collect(
+        &mut self,
+        ns: &NamespaceCtx,
+    ) {
+        self.sources.extend(
+            ns.sources
+                .iter()
+                .map(|(path, source)| (path.clone(), source.clone())),
+        );
+
+        for (named, child) in &ns.children {
+            self.insert_item(
+                &named.context.namespace,
+                &named.name,
+                named.name.borrow_string().clone(),
+                &child.source,
+            );
+
+            if let NamespaceChild::Namespace(nested) = &child.value {
+                self.collect(nested);
+            }
+        }
+    }
+
+    /// Adds the types of `package` in the registry, including generated ones, along with
+    /// their fields and variants.
+    fn collect_types(
+        &mut self,
+        ctx: &CompileCtx,
+        package: &str,
+    ) {
+        for def in ctx.type_registry().definitions() {
+            let path = &def.value.qualified_path;
+            if path.context.package != package {
+                continue;
+            }
+
+            let namespace = &path.context.namespace;
+            let item = path.name.borrow_string().clone();
+            self.insert_item(namespace, &path.name, item.clone(), &def.source);
+
+            let mut members = Vec::new();
+            match &def.value.kind {
+                Definition::Struct(def) => {
+                    for arg in &def.def.args.values {
+                        members.push((
+                            arg.value.name.borrow_string().clone(),
+                            arg.value.name.span(),
+                        ));
+                    }
+                },
+                Definition::Enum(def) => {
+                    match &def.def.value {
+                        Enum::Int(def) => {
+                            for variant in &def.variants.values {
+                                members.push((
+                                    variant.value.name.borrow_string().clone(),
+                                    variant.value.name.span(),
+                                ));
+                            }
+                        },
+                        Enum::Str(def) => {
+                            for variant in &def.variants.values {
+                                members.push((
+                                    variant.value.name.borrow_string().clone(),
+                                    variant.value.name.span(),
+                                ));
+                            }
+                        },
+                    }
+                },
+                _ => {},
+            }
+
+            for (member, span) in members {
+                // member spans begin where the previous token ended, so only the name is kept
+                if span.start != span.end && span.end - span.start >= member.len() {
+                    let span = crate::Span::new(span.end - member.len(), span.end);
+                    self.members.insert(
+                        (namespace.clone(), item.clone(), member),
+                        (def.source.clone(), span),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Where the violation is: its member, its item, or its namespace for rules checking
+    /// namespaces.
+    fn find(
+        &self,
+        violation: &Violation,
+    ) -> Option<&Location> {
+        match (&violation.item, &violation.member) {
+            (Some(item), Some(member)) => {
+                // variants of one-ofs and errors and arguments of operations are not collected
+                self.members
+                    .get(&(violation.namespace.clone(), item.clone(), member.clone()))
+                    .or_else(|| {
+                        self.items
+                            .get(&(violation.namespace.clone(), item.clone()))
+                    })
+            },
+            (Some(item), None) => {
+                self.items
+                    .get(&(violation.namespace.clone(), item.clone()))
+            },
+            (None, _) => {
+                let (name, parent) = violation.namespace.split_last()?;
+                self.items
+                    .get(&(parent.to_vec(), name.clone()))
+            },
+        }
+    }
+
+    fn locate(
+        &self,
+        violation: &Violation,
+    ) -> crate::CompilerError {
+        match self.find(violation) {
+            Some((path, span)) => {
+                let error = violation.to_error(Some(*span));
+                match self.sources.get(path) {
+                    Some(source) => error.with_source_arc(path.clone(), source.clone()),
+                    None => error,
+                }
+            },
+            None => violation.to_error(None),
+        }
+    }
+}
+
+impl CompileCtx {
+    /// Runs the registered lint rules over the declarations of the root package, at the
+    /// levels set by its manifest. Violations below the error level are emitted as
+    /// diagnostics, while any at the error level fail the build.
+    pub async fn lint(&self) -> crate::Result<()> {
+        let mut errors = Vec::new();
+        let violations = self.lint_violations().await?;
+        let mut state = self.state.write().await;
+        for (level, error) in violations {
+            match level {
+                RuleLevel::Error => errors.push(error),
+                RuleLevel::Info => {
+                    let mut diagnostic = kintsu_events::Diagnostic::from(error);
+                    diagnostic.severity = kintsu_errors::Severity::Info;
+                    state.report(diagnostic);
+                },
+                _ => state.report(error),
+            }
+        }
+
+        lint_failure(errors)
+    }
+
+    /// The codes of `warnings` the manifest does not allow, when warnings are denied.
+    pub(super) fn denied_warnings(
+        &self,
+        warnings: &kintsu_events::DiagnosticBundle,
+    ) -> BTreeSet<String> {
+        if !self.deny_warnings {
+            return BTreeSet::new();
+        }
+        warnings.denied_warnings(&self.root.package.lint().allow_warnings)
+    }
+
+    /// Fails the build if this compilation reported warnings the manifest does not allow,
+    /// when warnings are denied. Warnings of other compilations in the process, such as
+    /// the other members of a workspace, are not counted.
+    pub(super) async fn check_warnings(&self) -> crate::Result<()> {
+        let denied = self.denied_warnings(&self.state.read().await.diagnostics);
+        if denied.is_empty() {
+            return Ok(());
+        }
+        Err(crate::LintError::warnings_denied(&denied)
+            .unlocated()
+            .build()
+            .into())
+    }
+
+    /// Each violation of a rule as a diagnostic with the severity of its level, for tools
+    /// which report them on their own rather than through [`Self::lint`].
+    pub async fn lint_diagnostics(&self) -> crate::Result<Vec<kintsu_events::Diagnostic>> {
+        Ok(self
+            .lint_violations()
+            .await?
+            .into_iter()
+            .map(|(level, error)| lint_diagnostic(level, error))
+            .collect())
+    }
+
+    /// The fixes of lint violations which `kintsu check --fix` may apply, grouped by file.
+    pub async fn lint_fixes(&self) -> crate::Result<Vec<(PathBuf, Vec<crate::Suggestion>)>> {
+        let errors = self
+            .lint_violations()
+            .await?
+            .into_iter()
+            .map(|(_, error)| error)
+            .collect();
+        Ok(crate::CompilerError::Multiple(errors).machine_applicable_fixes())
+    }
+
+    /// Each violation of a rule with its level, located in the source.
+    pub(super) async fn lint_violations(
+        &self
+    ) -> crate::Result<Vec<(RuleLevel, crate::CompilerError)>> {
+        let registry = RuleRegistry::new(self.root.package.lint());
+        // lints are advisory, so a schema which cannot be declared yet is not failed here
+        let declaration =
+            match Self::convert_schema_to_declaration(&self.root, &self.type_registry(), false)
+                .await
+            {
+                Ok(declaration) => declaration,
+                Err(e) => {
+                    tracing::warn!("skipping lints, declarations could not be extracted: {e}");
+                    return Ok(Vec::new());
+                },
+            };
+
+        let violations = registry.run(&declaration);
+        if violations.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut locations = ItemLocations::default();
+        for ns in self.root.namespaces.values() {
+            let ns = ns.lock().await;
+            locations.insert_item(
+                &[],
+                &ns.namespace.value.def.name,
+                ns.namespace
+                    .value
+                    .def
+                    .name
+                    .borrow_string()
+                    .clone(),
+                &ns.namespace.source,
+            );
+            locations.collect(&ns);
+        }
+        locations.collect_types(self, &declaration.package.to_case(Case::Snake));
+
+        // a field merged into generated types is reported once, where it was written
+        let mut seen = BTreeSet::new();
+        Ok(violations
+            .iter()
+            .filter(|violation| {
+                match locations.find(violation) {
+                    Some((path, span)) => {
+                        seen.insert((
+                            path.clone(),
+                            span.start,
+                            span.end,
+                            violation.rule.clone(),
+                            violation.reason.clone(),
+                        ))
+                    },
+                    None => true,
+                }
+            })
+            .map(|violation| (violation.level.clone(), locations.locate(violation)))
+            .collect())
+    }
+}
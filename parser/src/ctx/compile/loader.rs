@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeSet, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -8,6 +8,7 @@ use kintsu_fs::FileSystem;
 use kintsu_manifests::{
     lock::LockedSource,
     package::{Dependency, PackageManifests},
+    registries::RegistriesConfig,
     version::{Version, VersionExt},
 };
 use tokio::sync::RwLock;
@@ -15,7 +16,7 @@ use tokio::sync::RwLock;
 use crate::ctx::{
     SchemaCtx,
     cache::{CacheKey, CachedSchema, SchemaCache},
-    compile::resolver::PackageResolver,
+    compile::resolver::{PackageResolver, solver},
     registry::TypeRegistry,
 };
 
@@ -83,10 +84,16 @@ impl DependencyLoader {
             manifest: Arc::new(root.package.clone()),
         };
 
+        Self::solve_registry_dependencies(root, &state, resolver.as_ref()).await?;
+
+        let root_import_name = normalize_package_to_import_name(&root.package.package().name);
+
         for ns_ctx in root.namespaces.values() {
             for import in &ns_ctx.lock().await.imports {
                 let pkg_name = import.value.as_ref_context().package.clone();
-                if !root.namespaces.contains_key(&pkg_name)
+                // `schema::` imports resolve to the root package itself
+                if pkg_name != root_import_name
+                    && !root.namespaces.contains_key(&pkg_name)
                     && seen_packages.insert(pkg_name.clone())
                 {
                     initial_tasks.push(CompilationTask {
@@ -202,6 +209,78 @@ impl DependencyLoader {
         Ok(())
     }
 
+    /// Solves the registry requirements of `root` up front when the resolver has an index,
+    /// so each registry dependency is loaded at a version agreed across the whole graph.
+    async fn solve_registry_dependencies(
+        root: &SchemaCtx,
+        state: &Arc<RwLock<SharedCompilationState>>,
+        resolver: &dyn PackageResolver,
+    ) -> crate::Result<()> {
+        let Some(index) = resolver.index() else {
+            return Ok(());
+        };
+
+        let requirements: Vec<_> = root
+            .package
+            .dependencies()
+            .iter()
+            .filter_map(|(name, dep)| {
+                resolver
+                    .registry_requirement(name, dep)
+                    .map(|remote| (name.clone(), remote.version.0.clone()))
+            })
+            .collect();
+
+        if requirements.is_empty() {
+            return Ok(());
+        }
+
+        let preferred: BTreeMap<_, _> = state
+            .read()
+            .await
+            .lockfile
+            .iter()
+            .flat_map(|lockfile| lockfile.packages.values())
+            .map(|locked| (locked.name.clone(), locked.version.0.clone()))
+            .collect();
+
+        let package = root.package.package();
+        let solution = solver::solve(
+            index,
+            &package.name,
+            &package.version.0,
+            requirements,
+            &preferred,
+        )?;
+
+        // the solver only keeps yanked versions which the lockfile pins
+        for (name, version) in &solution {
+            if index.is_yanked(name, version)? {
+                state.write().await.report(
+                    crate::PackageError::yanked_version(name, version.to_string())
+                        .unlocated()
+                        .build(),
+                );
+            }
+
+            if let Some(notice) = index.deprecation(name, version)? {
+                state.write().await.report(
+                    crate::PackageError::deprecated_dependency(
+                        name,
+                        version.to_string(),
+                        notice.to_string(),
+                    )
+                    .unlocated()
+                    .build(),
+                );
+            }
+        }
+
+        tracing::info!("Solved {} registry dependencies", solution.len());
+        state.write().await.registry_solution = solution;
+        Ok(())
+    }
+
     pub(super) async fn process_dependency_task(
         task: CompilationTask,
         state: Arc<RwLock<SharedCompilationState>>,
@@ -248,6 +327,23 @@ impl DependencyLoader {
                 .into()
             })?;
 
+        let package_name = normalize_import_to_package_name(dep_name);
+        let solved = state
+            .read()
+            .await
+            .registry_solution
+            .get(&package_name)
+            .cloned();
+
+        let pinned;
+        let dep = match (resolver.registry_requirement(&package_name, dep), solved) {
+            (Some(remote), Some(version)) => {
+                pinned = Dependency::Remote(remote.pinned(&version.into()));
+                &pinned
+            },
+            _ => dep,
+        };
+
         let resolved = resolver.resolve(parent_path, dep_name, dep)?;
 
         let use_version = Self::resolve_version(&state, dep_name, &resolved).await?;
@@ -321,6 +417,14 @@ impl DependencyLoader {
         let state_read = state.read().await;
         let mut candidate_version = resolved.version.clone();
 
+        // solved versions already account for every requirement and the lockfile
+        if state_read
+            .registry_solution
+            .contains_key(&normalize_import_to_package_name(dep_name))
+        {
+            return Ok(candidate_version);
+        }
+
         if let Some(existing_version) = state_read.loaded_versions.get(dep_name) {
             if !existing_version.is_compatible(&candidate_version) {
                 return Err(crate::MetadataError::version_incompatibility(
@@ -501,25 +605,12 @@ impl DependencyLoader {
                 }
             },
             Some(Dependency::PathWithRemote(path)) => {
-                LockedSource::Registry {
-                    // todo: this should be the api download url from the remote config
-                    url: path
-                        .remote
-                        .registry
-                        .clone()
-                        .unwrap_or_else(|| "https://registry.kintsu.dev".to_string()),
-                }
+                Self::registry_source(root_package, path.remote.registry.as_deref())
             },
             Some(Dependency::Remote(remote)) => {
-                LockedSource::Registry {
-                    // todo: this should be the api download url from the remote config
-                    url: remote
-                        .registry
-                        .clone()
-                        .unwrap_or_else(|| "https://registry.kintsu.dev".to_string()),
-                }
+                Self::registry_source(root_package, remote.registry.as_deref())
             },
-            None => {
+            Some(Dependency::Workspace(_)) | None => {
                 LockedSource::Path {
                     path: resolved_path.to_path_buf(),
                 }
@@ -527,6 +618,20 @@ impl DependencyLoader {
         }
     }
 
+    /// Locks a dependency to the primary url of its registry, as named in the manifest's
+    /// `[registries]`. The mirror a package was fetched through is recorded by the client
+    /// which downloads it.
+    fn registry_source(
+        root_package: &PackageManifests,
+        registry: Option<&str>,
+    ) -> LockedSource {
+        let endpoints = RegistriesConfig::default().resolve(root_package.registries(), registry);
+        LockedSource::Registry {
+            url: endpoints.url,
+            mirror: None,
+        }
+    }
+
     pub(super) async fn collect_transitive_deps(
         dep_schema: &Arc<SchemaCtx>,
         dep_path: &Path,
@@ -544,9 +649,14 @@ impl DependencyLoader {
             manifest: Arc::new(dep_schema.package.clone()),
         };
 
+        let dep_import_name = normalize_package_to_import_name(&dep_schema.package.package().name);
+
         for ns_ctx in dep_schema.namespaces.values() {
             for nested_import in &ns_ctx.lock().await.imports {
                 let object = nested_import.value.as_ref_context();
+                if object.package == dep_import_name {
+                    continue;
+                }
                 let state_read = state.read().await;
                 if !dep_schema
                     .namespaces
@@ -56,6 +56,34 @@ impl LockfileManager {
         root_package_name: &str,
         root_version: Version,
     ) -> crate::Result<()> {
+        let lockfile = Self::build_lockfile(
+            state,
+            fs.clone(),
+            root_path,
+            root_package_name,
+            root_version,
+        )
+        .await?;
+
+        let lockfiles = Lockfiles::V1(lockfile);
+        let lockfile_content = NewForNamed::dump::<PathBuf>(&lockfiles)?;
+
+        fs.write(
+            &<Lockfiles as NewForNamed>::path(root_path),
+            lockfile_content.into_bytes(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn build_lockfile(
+        state: &Arc<RwLock<SharedCompilationState>>,
+        fs: Arc<dyn kintsu_fs::FileSystem>,
+        root_path: &PathBuf,
+        root_package_name: &str,
+        root_version: Version,
+    ) -> crate::Result<Lockfile> {
         Self::prune_compatible_versions(state).await;
 
         let state_read = state.read().await;
@@ -128,17 +156,6 @@ impl LockfileManager {
             dependencies: root_dependencies,
         };
 
-        let lockfile = Lockfile { root, packages };
-
-        let lockfiles = Lockfiles::V1(lockfile);
-        let lockfile_content = NewForNamed::dump::<PathBuf>(&lockfiles)?;
-
-        fs.write(
-            &<Lockfiles as NewForNamed>::path(root_path),
-            lockfile_content.into_bytes(),
-        )
-        .await?;
-
-        Ok(())
+        Ok(Lockfile { root, packages })
     }
 }
@@ -1,11 +1,31 @@
 pub use context::CompileCtx;
+pub use dependencies::{AddedDependency, DependencySource, latest_version};
+pub use hooks::{CommandHook, CompileHook};
 pub use kintsu_cli_core::CompilationProgress;
+pub use outcome::{CompileArtifact, CompileMetrics, CompileOutcome};
+pub use release::{BumpOptions, RELEASES_DIR, VersionBump, release_lockfile_path};
+pub use report::{
+    BuildReport, CacheReport, DependencyFetch, DependencyReport, DiagnosticReport, PhaseProfiler,
+    PhaseTiming,
+};
+pub use tree::{DependencyTree, TreePackage};
+pub use workspace::WorkspaceCtx;
 
 pub(crate) mod context;
 pub(crate) mod coordinator;
+pub(crate) mod dependencies;
+pub mod hooks;
+pub(crate) mod lint;
 pub(crate) mod loader;
 pub(crate) mod lockfile;
+pub(crate) mod outcome;
+pub(crate) mod release;
+pub mod report;
 pub mod resolver;
 pub(crate) mod schema_compiler;
 pub(crate) mod state;
+#[cfg(feature = "profiling")]
+pub mod trace;
+pub mod tree;
 pub(crate) mod utils;
+pub(crate) mod workspace;
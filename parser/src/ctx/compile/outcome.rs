@@ -0,0 +1,94 @@
+//! The result of a successful compilation.
+//!
+//! [`CompileCtx::with_fs`] and friends only tell whether a package compiled. A
+//! [`CompileOutcome`] keeps what a caller reports afterwards: the warnings the build passed
+//! with, the packages it produced and how long it took, so they are neither recomputed nor
+//! lost once compilation succeeds.
+
+use std::{collections::BTreeSet, path::PathBuf};
+
+use kintsu_events::DiagnosticBundle;
+use kintsu_manifests::rules::RuleLevel;
+use serde::Serialize;
+
+use super::{
+    CompileCtx, PhaseTiming,
+    lint::{lint_diagnostic, lint_failure},
+};
+
+/// A compiled package, as reported in `artifact` messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompileArtifact {
+    pub package: String,
+    pub version: String,
+    pub root: PathBuf,
+}
+
+/// How long compilation took, and each phase of it in the order they ran.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompileMetrics {
+    pub total_ms: f64,
+    pub phases: Vec<PhaseTiming>,
+}
+
+/// A compiled package with the warnings, artifacts and timings of its compilation.
+pub struct CompileOutcome {
+    pub ctx: CompileCtx,
+    /// Lint violations below the error level, with the severity of their rule. Holds no
+    /// errors, as those fail compilation.
+    pub warnings: DiagnosticBundle,
+    pub artifacts: Vec<CompileArtifact>,
+    pub metrics: CompileMetrics,
+    /// Codes of the warnings which failed the build, as
+    /// [`CompileCtx::set_deny_warnings`] denies them. Empty unless warnings are denied.
+    pub denied_warnings: BTreeSet<String>,
+}
+
+impl CompileOutcome {
+    /// Whether the build passed its warning policy.
+    pub fn is_success(&self) -> bool {
+        self.denied_warnings.is_empty()
+    }
+}
+
+impl CompileCtx {
+    /// Lints the compiled package and collects its outcome. Violations at the error level
+    /// fail as in [`Self::finalize`], while those below are kept in
+    /// [`CompileOutcome::warnings`] rather than emitted, and reported in
+    /// [`CompileOutcome::denied_warnings`] when warnings are denied. The lockfile is not
+    /// written.
+    pub async fn into_outcome(self) -> crate::Result<CompileOutcome> {
+        let phase_start = web_time::Instant::now();
+        let mut warnings = DiagnosticBundle::new();
+        let mut errors = Vec::new();
+        for (level, error) in self.lint_violations().await? {
+            match level {
+                RuleLevel::Error => errors.push(error),
+                level => warnings.push(lint_diagnostic(level, error)),
+            }
+        }
+        lint_failure(errors)?;
+        warnings.sort();
+        self.profiler
+            .record("lint", phase_start.elapsed());
+
+        let package = self.root.package.package();
+        let artifacts = vec![CompileArtifact {
+            package: package.name.clone(),
+            version: package.version.to_string(),
+            root: self.root.root_path.clone(),
+        }];
+        let metrics = CompileMetrics {
+            total_ms: self.profiler.elapsed().as_secs_f64() * 1000.0,
+            phases: self.profiler.phases(),
+        };
+
+        Ok(CompileOutcome {
+            denied_warnings: self.denied_warnings(&warnings),
+            ctx: self,
+            warnings,
+            artifacts,
+            metrics,
+        })
+    }
+}
@@ -0,0 +1,175 @@
+//! Preparing a release: bumping the version of a package, checked against the declarations
+//! of its published version, with the lockfile rewritten for the new version.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use kintsu_fs::FileSystem;
+use kintsu_manifests::{config::NewForNamed, edit::ManifestEditor, lock::Lockfiles, version::Bump};
+
+use super::{CompileCtx, lockfile::LockfileManager, resolver::PackageResolver};
+use crate::declare::{DeclarationVersion, diff};
+
+/// Where release lockfiles are kept, relative to the package.
+pub const RELEASES_DIR: &str = "releases";
+
+/// How [`CompileCtx::bump_version`] releases a package.
+#[derive(Debug, Clone)]
+pub struct BumpOptions {
+    pub bump: Bump,
+    /// Declarations of the current version as published, to check the bump against
+    pub published: Option<DeclarationVersion>,
+    /// Keep a copy of the lockfile for the new version in [`RELEASES_DIR`]
+    pub tag: bool,
+}
+
+/// A version bump made by [`CompileCtx::bump_version`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct VersionBump {
+    pub package: String,
+    pub previous: String,
+    pub version: String,
+    pub bump: Bump,
+    /// The smallest bump the changes since the published declarations need, when checked
+    pub required: Option<Bump>,
+    /// Items added since the published declarations
+    pub added: Vec<String>,
+    /// Items removed since the published declarations
+    pub removed: Vec<String>,
+    /// The lockfile kept for the release, when tagged
+    pub snapshot: Option<PathBuf>,
+}
+
+impl std::fmt::Display for VersionBump {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(
+            f,
+            "bumped {} from {} to {}",
+            self.package, self.previous, self.version
+        )?;
+        if let Some(required) = self.required
+            && required < self.bump
+        {
+            write!(f, " (a {required} bump would do)")?;
+        }
+        if let Some(snapshot) = &self.snapshot {
+            write!(f, ", lockfile tagged in {}", snapshot.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// The lockfile kept for `version` of the package in `root_path`.
+pub fn release_lockfile_path(
+    root_path: &Path,
+    version: &str,
+) -> PathBuf {
+    root_path
+        .join(RELEASES_DIR)
+        .join(format!("v{version}.{}", Lockfiles::NAME))
+}
+
+impl CompileCtx {
+    /// Bumps the version of the package in `root_path` and rewrites its lockfile. With
+    /// published declarations, the bump must be at least the one the changes since them
+    /// require: removing items needs a major release, adding them a minor one.
+    pub async fn bump_version(
+        fs: Arc<dyn FileSystem>,
+        resolver: Arc<dyn PackageResolver>,
+        root_path: impl AsRef<Path>,
+        options: BumpOptions,
+    ) -> crate::Result<VersionBump> {
+        let root_path = root_path.as_ref();
+        let ctx = Self::with_fs_and_config(
+            fs.clone(),
+            resolver.clone(),
+            root_path,
+            num_cpus::get(),
+            false,
+        )
+        .await?;
+        let package = ctx.root.package.package().name.clone();
+        let previous = ctx.root_version()?;
+        let version = options.bump.apply(&previous);
+
+        let (required, added, removed) = match &options.published {
+            Some(published) => {
+                let declarations = ctx.emit_declarations().await?;
+                let required = diff::required_bump(&previous, published, &declarations);
+                let removed = diff::removed_items(published, &declarations);
+                if required > options.bump {
+                    let breaking = diff::breaking_changes(published, &declarations);
+                    let reason = if breaking.is_empty() {
+                        "adds items".to_string()
+                    } else {
+                        format!(
+                            "breaks dependents ({})",
+                            breaking
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    };
+                    return Err(crate::PackageError::version_error(format!(
+                        "{package} {reason} since {previous}, which needs at least a {required} bump, not {}",
+                        options.bump
+                    ))
+                    .unlocated()
+                    .build()
+                    .into());
+                }
+                (
+                    Some(required),
+                    diff::added_items(published, &declarations),
+                    removed,
+                )
+            },
+            None => (None, Vec::new(), Vec::new()),
+        };
+        drop(ctx);
+
+        let mut editor = ManifestEditor::read(fs.as_ref(), root_path)?;
+        editor.set_version(&version.clone().into());
+        let ctx = Self::compile_edit(fs.clone(), resolver, root_path, &editor).await?;
+
+        // the lockfile records the version of the package itself, which is not a change
+        // its dependencies invalidate it for
+        LockfileManager::write_lockfile(
+            &ctx.state,
+            fs.clone(),
+            &ctx.root_path,
+            &package,
+            ctx.root_version()?,
+        )
+        .await?;
+
+        let snapshot = if options.tag {
+            let lockfile = fs
+                .read_to_string(&Lockfiles::path(root_path))
+                .await?;
+            let snapshot = release_lockfile_path(root_path, &version.to_string());
+            fs.write(&snapshot, lockfile.into_bytes())
+                .await?;
+            Some(snapshot)
+        } else {
+            None
+        };
+
+        Ok(VersionBump {
+            package,
+            previous: previous.to_string(),
+            version: version.to_string(),
+            bump: options.bump,
+            required,
+            added,
+            removed,
+            snapshot,
+        })
+    }
+}
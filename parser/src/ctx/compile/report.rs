@@ -0,0 +1,280 @@
+//! Post-compile build report.
+//!
+//! Merges phase timings, schema cache statistics, dependency fetches and diagnostic
+//! counts into one structure so a slow or noisy build can be explained from a single
+//! `kintsu check --explain` run.
+
+use std::{
+    collections::BTreeSet,
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use kintsu_manifests::lock::LockedSource;
+use serde::Serialize;
+use tracing::Instrument;
+use web_time::Instant;
+
+use crate::ctx::SchemaCtx;
+
+/// Records how long each compilation phase took, in the order the phases ran.
+#[derive(Clone)]
+pub struct PhaseProfiler {
+    started: Instant,
+    phases: Arc<Mutex<Vec<PhaseTiming>>>,
+}
+
+impl Default for PhaseProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhaseProfiler {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            phases: Default::default(),
+        }
+    }
+
+    pub fn record(
+        &self,
+        name: impl Into<String>,
+        elapsed: Duration,
+    ) {
+        self.phases
+            .lock()
+            .unwrap()
+            .push(PhaseTiming {
+                name: name.into(),
+                duration_ms: elapsed.as_secs_f64() * 1000.0,
+            });
+    }
+
+    /// Runs `fut` in a `phase` span and records its wall-clock duration under `name`.
+    pub async fn time<F: Future>(
+        &self,
+        name: impl Into<String>,
+        fut: F,
+    ) -> F::Output {
+        let name = name.into();
+        let start = Instant::now();
+        let output = fut
+            .instrument(tracing::info_span!("phase", phase = %name))
+            .await;
+        self.record(name, start.elapsed());
+        output
+    }
+
+    pub fn phases(&self) -> Vec<PhaseTiming> {
+        self.phases.lock().unwrap().clone()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseTiming {
+    pub name: String,
+    pub duration_ms: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CacheReport {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub size_bytes: usize,
+}
+
+/// A dependency loaded during the build, with the size of the sources read for it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DependencyFetch {
+    pub name: String,
+    pub version: String,
+    /// `path`, `git`, `registry` or `unknown` when no lock metadata was recorded
+    pub source: &'static str,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl DependencyFetch {
+    pub(super) async fn from_schema(
+        name: &str,
+        schema: &SchemaCtx,
+        source: Option<&LockedSource>,
+    ) -> Self {
+        let mut paths = BTreeSet::new();
+        let mut bytes = 0u64;
+        for ns in schema.namespaces.values() {
+            for (path, content) in &ns.lock().await.sources {
+                if paths.insert(path.clone()) {
+                    bytes += content.len() as u64;
+                }
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            version: schema.package.package().version.to_string(),
+            source: match source {
+                Some(LockedSource::Path { .. }) => "path",
+                Some(LockedSource::Git { .. }) => "git",
+                Some(LockedSource::Registry { .. }) => "registry",
+                None => "unknown",
+            },
+            files: paths.len(),
+            bytes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DependencyReport {
+    pub count: usize,
+    pub files: usize,
+    pub bytes: u64,
+    pub packages: Vec<DependencyFetch>,
+}
+
+impl FromIterator<DependencyFetch> for DependencyReport {
+    fn from_iter<T: IntoIterator<Item = DependencyFetch>>(iter: T) -> Self {
+        let packages: Vec<_> = iter.into_iter().collect();
+        Self {
+            count: packages.len(),
+            files: packages.iter().map(|p| p.files).sum(),
+            bytes: packages.iter().map(|p| p.bytes).sum(),
+            packages,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DiagnosticReport {
+    pub errors: usize,
+    pub warnings: usize,
+    /// Errors from files skipped while loading schemas
+    pub recovered: usize,
+}
+
+/// Summary of a finished compilation. Serializes to JSON and displays as a table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BuildReport {
+    pub package: String,
+    pub version: String,
+    pub total_ms: f64,
+    pub phases: Vec<PhaseTiming>,
+    pub cache: CacheReport,
+    pub dependencies: DependencyReport,
+    pub diagnostics: DiagnosticReport,
+}
+
+impl fmt::Display for BuildReport {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        writeln!(
+            f,
+            "build report for {}@{} ({:.1} ms)",
+            self.package, self.version, self.total_ms
+        )?;
+
+        writeln!(f)?;
+        writeln!(f, "{:<28} {:>12} {:>8}", "phase", "time (ms)", "share")?;
+        for phase in &self.phases {
+            let share = if self.total_ms > 0.0 {
+                phase.duration_ms / self.total_ms * 100.0
+            } else {
+                0.0
+            };
+            writeln!(
+                f,
+                "{:<28} {:>12.1} {:>7.1}%",
+                phase.name, phase.duration_ms, share
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "cache: {} hits, {} misses, {} entries ({} bytes)",
+            self.cache.hits, self.cache.misses, self.cache.entries, self.cache.size_bytes
+        )?;
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "dependencies: {} packages, {} files, {} bytes",
+            self.dependencies.count, self.dependencies.files, self.dependencies.bytes
+        )?;
+        if !self.dependencies.packages.is_empty() {
+            writeln!(
+                f,
+                "{:<28} {:<12} {:<10} {:>6} {:>10}",
+                "package", "version", "source", "files", "bytes"
+            )?;
+            for dep in &self.dependencies.packages {
+                writeln!(
+                    f,
+                    "{:<28} {:<12} {:<10} {:>6} {:>10}",
+                    dep.name, dep.version, dep.source, dep.files, dep.bytes
+                )?;
+            }
+        }
+
+        writeln!(f)?;
+        write!(
+            f,
+            "diagnostics: {} errors, {} warnings, {} recovered",
+            self.diagnostics.errors, self.diagnostics.warnings, self.diagnostics.recovered
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn profiler_records_phases_in_order() {
+        let profiler = PhaseProfiler::new();
+        profiler.time("first", async {}).await;
+        profiler.record("second", Duration::from_millis(5));
+
+        let phases = profiler.phases();
+        assert_eq!(
+            phases
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+        assert_eq!(phases[1].duration_ms, 5.0);
+    }
+
+    #[test]
+    fn dependency_report_totals() {
+        let report: DependencyReport = [("a", 2, 100), ("b", 3, 50)]
+            .into_iter()
+            .map(|(name, files, bytes)| {
+                DependencyFetch {
+                    name: name.into(),
+                    version: "1.0.0".into(),
+                    source: "path",
+                    files,
+                    bytes,
+                }
+            })
+            .collect();
+
+        assert_eq!(report.count, 2);
+        assert_eq!(report.files, 5);
+        assert_eq!(report.bytes, 150);
+    }
+}
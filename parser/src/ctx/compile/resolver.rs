@@ -11,8 +11,14 @@ use kintsu_manifests::{
     version::Version,
 };
 
+pub mod declarations;
 pub mod path;
+pub mod solver;
+pub mod workspace;
+pub use declarations::DeclarationResolver;
 pub use path::PathPackageResolver;
+pub use solver::{DeprecationNotice, PackageIndex, Solution};
+pub use workspace::{WorkspaceMember, WorkspaceResolver};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DependencyMutability {
@@ -83,6 +89,26 @@ pub trait PackageResolver: Send + Sync + PathResolver + RemoteResolver + GitReso
         false
     }
 
+    /// Registry index used to solve version requirements up front. Without one, each
+    /// registry dependency is resolved on its own as it is loaded.
+    fn index(&self) -> Option<&dyn PackageIndex> {
+        None
+    }
+
+    /// The registry requirement of the dependency `name` (as written in the manifest), if
+    /// this resolver treats it as remote.
+    fn registry_requirement<'d>(
+        &self,
+        _name: &str,
+        dependency: &'d Dependency,
+    ) -> Option<&'d RemoteDependency> {
+        match dependency {
+            Dependency::Remote(remote) => Some(remote),
+            Dependency::PathWithRemote(pwr) if self.dependency_as_remote() => Some(&pwr.remote),
+            _ => None,
+        }
+    }
+
     fn resolve(
         &self,
         root_path: &Path,
@@ -93,6 +119,14 @@ pub trait PackageResolver: Send + Sync + PathResolver + RemoteResolver + GitReso
             Dependency::Path(path) => self.resolve_path(dep_name, root_path, path),
             Dependency::Git(git) => self.resolve_git(dep_name, git),
             Dependency::Remote(remote) => self.resolve_remote(dep_name, remote),
+            Dependency::Workspace(_) => {
+                Err(crate::PackageError::manifest_error(format!(
+                    "dependency '{dep_name}' was not inherited from a workspace"
+                ))
+                .unlocated()
+                .build()
+                .into())
+            },
             Dependency::PathWithRemote(pwr) => {
                 if self.dependency_as_remote() {
                     self.resolve_remote(dep_name, &pwr.remote)
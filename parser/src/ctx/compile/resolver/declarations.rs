@@ -0,0 +1,213 @@
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use kintsu_fs::memory::MemoryFileSystem;
+use kintsu_manifests::{
+    package::{Dependency, GitDependency, PathDependency, RemoteDependency},
+    version::Version,
+};
+
+use super::*;
+use crate::{
+    ctx::compile::utils::normalize_import_to_package_name,
+    declare::{DeclarationBundle, TypeRegistryDeclaration},
+};
+
+/// Resolves dependencies on packages held as declarations to schema sources written from
+/// them, so they are loaded without their original sources, and everything else through
+/// `inner`.
+pub struct DeclarationResolver {
+    inner: Arc<dyn PackageResolver>,
+    /// Declarations by package name, then version
+    packages: BTreeMap<String, BTreeMap<Version, TypeRegistryDeclaration>>,
+}
+
+impl DeclarationResolver {
+    pub fn new(inner: Arc<dyn PackageResolver>) -> Self {
+        Self {
+            inner,
+            packages: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the declarations of `version` of a package.
+    pub fn with_declarations(
+        mut self,
+        version: Version,
+        declarations: TypeRegistryDeclaration,
+    ) -> Self {
+        self.packages
+            .entry(declarations.package.clone())
+            .or_default()
+            .insert(version, declarations);
+        self
+    }
+
+    /// Adds the root and every dependency of `bundle`, at their version in `versions` (by
+    /// package name), e.g. as locked when the bundle was emitted.
+    pub fn with_bundle(
+        self,
+        bundle: DeclarationBundle,
+        versions: &BTreeMap<String, Version>,
+    ) -> crate::Result<Self> {
+        std::iter::once(bundle.root)
+            .chain(bundle.dependencies.into_values())
+            .try_fold(self, |resolver, declarations| {
+                let version = versions
+                    .get(&declarations.package)
+                    .cloned()
+                    .ok_or_else(|| -> crate::Error {
+                        crate::PackageError::version_error(format!(
+                            "no version given for the declarations of '{}'",
+                            declarations.package
+                        ))
+                        .unlocated()
+                        .build()
+                        .into()
+                    })?;
+                Ok(resolver.with_declarations(version, declarations))
+            })
+    }
+
+    /// The highest held version of `package_name` satisfying `dependency`.
+    fn find(
+        &self,
+        package_name: &str,
+        dependency: &Dependency,
+    ) -> Option<crate::Result<(&Version, &TypeRegistryDeclaration)>> {
+        let versions = self.packages.get(package_name)?;
+        let requirement = match dependency {
+            Dependency::Git(_) | Dependency::Workspace(_) => return None,
+            dependency => dependency.version(),
+        };
+
+        let found = versions
+            .iter()
+            .rev()
+            .find(|(version, _)| requirement.is_none_or(|req| req.matches(version)));
+
+        Some(found.ok_or_else(|| {
+            let held = versions
+                .keys()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            crate::PackageError::version_error(format!(
+                "'{package_name}' requires {}, but its declarations are only available for {held}",
+                requirement
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            ))
+            .unlocated()
+            .build()
+            .into()
+        }))
+    }
+
+    /// A package holding `schema.toml` and the sources written from `declarations`. Its
+    /// dependencies are the packages the declarations reference.
+    fn package_fs(
+        version: &Version,
+        declarations: &TypeRegistryDeclaration,
+    ) -> crate::Result<MemoryFileSystem> {
+        let mut manifest = format!(
+            "version = \"v1\"\n\n[package]\nname = \"{}\"\nversion = \"{version}\"\n",
+            declarations.package
+        );
+
+        let dependencies = declarations
+            .external_refs
+            .iter()
+            .map(|reference| normalize_import_to_package_name(&reference.context.package))
+            .collect::<std::collections::BTreeSet<_>>();
+        if !dependencies.is_empty() {
+            manifest.push_str("\n[dependencies]\n");
+        }
+        for dependency in dependencies {
+            manifest.push_str(&format!("\"{dependency}\" = {{ version = \"*\" }}\n"));
+        }
+
+        let fs = MemoryFileSystem::new();
+        fs.add_file("schema.toml", manifest);
+        for (path, source) in declarations.to_sources()? {
+            fs.add_file(Path::new("schema").join(path), source);
+        }
+        Ok(fs)
+    }
+}
+
+impl PathResolver for DeclarationResolver {
+    fn resolve_path(
+        &self,
+        dep_name: &str,
+        root_path: &Path,
+        path: &PathDependency,
+    ) -> crate::Result<ResolvedDependency> {
+        self.inner
+            .resolve_path(dep_name, root_path, path)
+    }
+}
+
+impl GitResolver for DeclarationResolver {
+    fn resolve_git(
+        &self,
+        dep_name: &str,
+        git: &GitDependency,
+    ) -> crate::Result<ResolvedDependency> {
+        self.inner.resolve_git(dep_name, git)
+    }
+}
+
+impl RemoteResolver for DeclarationResolver {
+    fn resolve_remote(
+        &self,
+        dep_name: &str,
+        remote: &RemoteDependency,
+    ) -> crate::Result<ResolvedDependency> {
+        self.inner.resolve_remote(dep_name, remote)
+    }
+}
+
+impl PackageResolver for DeclarationResolver {
+    fn dependency_as_remote(&self) -> bool {
+        self.inner.dependency_as_remote()
+    }
+
+    fn index(&self) -> Option<&dyn PackageIndex> {
+        self.inner.index()
+    }
+
+    fn registry_requirement<'d>(
+        &self,
+        name: &str,
+        dependency: &'d Dependency,
+    ) -> Option<&'d RemoteDependency> {
+        if self.packages.contains_key(name) {
+            return None;
+        }
+        self.inner
+            .registry_requirement(name, dependency)
+    }
+
+    fn resolve(
+        &self,
+        root_path: &Path,
+        dep_name: &str,
+        dependency: &Dependency,
+    ) -> crate::Result<ResolvedDependency> {
+        match self.find(&normalize_import_to_package_name(dep_name), dependency) {
+            Some(found) => {
+                let (version, declarations) = found?;
+                Ok(ResolvedDependency {
+                    fs: Arc::new(Self::package_fs(version, declarations)?),
+                    path: "./".into(),
+                    mutability: DependencyMutability::Immutable,
+                    version: version.clone(),
+                })
+            },
+            None => {
+                self.inner
+                    .resolve(root_path, dep_name, dependency)
+            },
+        }
+    }
+}
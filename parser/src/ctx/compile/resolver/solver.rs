@@ -0,0 +1,1231 @@
+//! PubGrub-style version solving for registry dependencies.
+//!
+//! The solver picks one version per package such that every `^`, `~`, exact or range
+//! requirement reachable from the root is satisfied, backtracking out of choices that
+//! lead to conflicts. Each conflict is recorded as an incompatibility with the
+//! incompatibilities it was derived from, so an unsatisfiable set of requirements is
+//! reported as a numbered chain of reasons rather than a single failing package.
+//!
+//! Version sets are represented over the finite list of published versions of each
+//! package, with one extra bit for "not selected". This keeps requirement matching
+//! (including pre-release rules) exactly as `semver` defines it, and makes the
+//! complement of a term exact.
+//!
+//! Yanked versions are left out of that list unless they are the preferred version, so a
+//! lockfile keeps working after a yank while new resolutions move past it.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+};
+
+use kintsu_manifests::version::{Version, VersionReq};
+
+/// Published versions and their registry dependencies, consulted while solving.
+pub trait PackageIndex: Send + Sync {
+    /// Every available version of `package`, in any order. Unknown packages have none.
+    fn versions(
+        &self,
+        package: &str,
+    ) -> crate::Result<Vec<Version>>;
+
+    /// Registry dependencies of `package@version` as `(name, requirement)` pairs.
+    fn dependencies(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> crate::Result<Vec<(String, VersionReq)>>;
+
+    /// Whether `package@version` has been yanked. Yanked versions are only chosen when
+    /// they are the preferred version, i.e. pinned by the lockfile.
+    fn is_yanked(
+        &self,
+        _package: &str,
+        _version: &Version,
+    ) -> crate::Result<bool> {
+        Ok(false)
+    }
+
+    /// Why `package@version` (or its whole package) was deprecated by its owners, if it
+    /// was. Deprecation does not affect solving, chosen versions are only warned about.
+    fn deprecation(
+        &self,
+        _package: &str,
+        _version: &Version,
+    ) -> crate::Result<Option<DeprecationNotice>> {
+        Ok(None)
+    }
+}
+
+/// An owner's notice that a package or version should no longer be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecationNotice {
+    pub message: String,
+    /// Package to use instead
+    pub replacement: Option<String>,
+}
+
+impl std::fmt::Display for DeprecationNotice {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(replacement) = &self.replacement {
+            write!(f, " (use `{replacement}` instead)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The selected version of every package reachable from the root, excluding the root.
+pub type Solution = BTreeMap<String, Version>;
+
+/// Solves the requirements of `root`, preferring `preferred` versions (usually the
+/// lockfile) and otherwise the highest matching version.
+pub fn solve(
+    index: &dyn PackageIndex,
+    root: &str,
+    root_version: &Version,
+    requirements: Vec<(String, VersionReq)>,
+    preferred: &BTreeMap<String, Version>,
+) -> crate::Result<Solution> {
+    let mut solver = Solver {
+        index,
+        preferred,
+        root_dependencies: requirements,
+        packages: vec![],
+        package_ids: HashMap::new(),
+        incompatibilities: vec![],
+        assignments: vec![],
+        decision_level: 0,
+        loaded: HashSet::new(),
+        labels: HashMap::new(),
+    };
+    solver.run(root, root_version)
+}
+
+type PackageId = usize;
+type IncompatId = usize;
+
+/// A set of versions of one package, plus whether the package may be absent.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct Term {
+    bits: Vec<bool>,
+}
+
+impl Term {
+    fn any(versions: usize) -> Self {
+        Self {
+            bits: vec![true; versions + 1],
+        }
+    }
+
+    fn none(versions: usize) -> Self {
+        Self {
+            bits: vec![false; versions + 1],
+        }
+    }
+
+    fn exactly(
+        versions: usize,
+        version: usize,
+    ) -> Self {
+        let mut term = Self::none(versions);
+        term.bits[version] = true;
+        term
+    }
+
+    fn absent_bit(&self) -> usize {
+        self.bits.len() - 1
+    }
+
+    /// Positive terms require the package to be selected.
+    fn is_positive(&self) -> bool {
+        !self.bits[self.absent_bit()]
+    }
+
+    fn is_any(&self) -> bool {
+        self.bits.iter().all(|b| *b)
+    }
+
+    fn versions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits[..self.absent_bit()]
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b)
+            .map(|(idx, _)| idx)
+    }
+
+    fn negate(&self) -> Self {
+        Self {
+            bits: self.bits.iter().map(|b| !b).collect(),
+        }
+    }
+
+    fn intersect(
+        &self,
+        other: &Self,
+    ) -> Self {
+        Self {
+            bits: self
+                .bits
+                .iter()
+                .zip(&other.bits)
+                .map(|(a, b)| *a && *b)
+                .collect(),
+        }
+    }
+
+    fn union(
+        &self,
+        other: &Self,
+    ) -> Self {
+        Self {
+            bits: self
+                .bits
+                .iter()
+                .zip(&other.bits)
+                .map(|(a, b)| *a || *b)
+                .collect(),
+        }
+    }
+
+    fn is_subset_of(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.bits
+            .iter()
+            .zip(&other.bits)
+            .all(|(a, b)| !*a || *b)
+    }
+
+    fn is_disjoint(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.bits
+            .iter()
+            .zip(&other.bits)
+            .all(|(a, b)| !(*a && *b))
+    }
+}
+
+struct Package {
+    name: String,
+    /// Sorted ascending, excluding yanked versions
+    versions: Vec<Version>,
+    /// Number of yanked versions left out of `versions`
+    yanked: usize,
+}
+
+enum Cause {
+    Root,
+    NoVersions,
+    Dependency {
+        package: PackageId,
+        version: usize,
+        dependency: PackageId,
+        requirement: String,
+    },
+    Derived(IncompatId, IncompatId),
+}
+
+/// A set of terms which must not all hold at once.
+struct Incompatibility {
+    terms: BTreeMap<PackageId, Term>,
+    cause: Cause,
+}
+
+struct Assignment {
+    package: PackageId,
+    term: Term,
+    level: usize,
+    /// `None` for decisions, otherwise the incompatibility this was derived from
+    cause: Option<IncompatId>,
+}
+
+enum Relation {
+    Satisfied,
+    Contradicted,
+    AlmostSatisfied(PackageId),
+    Inconclusive,
+}
+
+struct Solver<'a> {
+    index: &'a dyn PackageIndex,
+    preferred: &'a BTreeMap<String, Version>,
+    root_dependencies: Vec<(String, VersionReq)>,
+    packages: Vec<Package>,
+    package_ids: HashMap<String, PackageId>,
+    incompatibilities: Vec<Incompatibility>,
+    assignments: Vec<Assignment>,
+    decision_level: usize,
+    /// Versions whose dependencies were added as incompatibilities
+    loaded: HashSet<(PackageId, usize)>,
+    /// Requirement strings for terms created from requirements, used in reports
+    labels: HashMap<(PackageId, Term), String>,
+}
+
+const ROOT: PackageId = 0;
+
+impl Solver<'_> {
+    fn run(
+        &mut self,
+        root: &str,
+        root_version: &Version,
+    ) -> crate::Result<Solution> {
+        self.packages.push(Package {
+            name: root.to_string(),
+            versions: vec![root_version.clone()],
+            yanked: 0,
+        });
+        self.package_ids
+            .insert(root.to_string(), ROOT);
+
+        self.add_incompatibility(
+            BTreeMap::from([(ROOT, Term::exactly(1, 0).negate())]),
+            Cause::Root,
+        );
+
+        let mut next = ROOT;
+        loop {
+            if let Err(failure) = self.propagate(next) {
+                return Err(crate::PackageError::unsatisfiable_dependencies(
+                    Reporter::new(self).report(failure),
+                )
+                .unlocated()
+                .build()
+                .into());
+            }
+
+            match self.decide()? {
+                Some(package) => next = package,
+                None => break,
+            }
+        }
+
+        Ok(self
+            .assignments
+            .iter()
+            .filter(|a| a.cause.is_none() && a.package != ROOT)
+            .filter_map(|a| {
+                let package = &self.packages[a.package];
+                let version = a.term.versions().next()?;
+                Some((package.name.clone(), package.versions[version].clone()))
+            })
+            .collect())
+    }
+
+    fn package_id(
+        &mut self,
+        name: &str,
+    ) -> crate::Result<PackageId> {
+        if let Some(id) = self.package_ids.get(name) {
+            return Ok(*id);
+        }
+
+        let mut published = self.index.versions(name)?;
+        published.sort();
+        published.dedup();
+
+        let pinned = self.preferred.get(name);
+        let mut versions = Vec::with_capacity(published.len());
+        let mut yanked = 0;
+        for version in published {
+            if pinned != Some(&version) && self.index.is_yanked(name, &version)? {
+                yanked += 1;
+            } else {
+                versions.push(version);
+            }
+        }
+
+        let id = self.packages.len();
+        self.packages.push(Package {
+            name: name.to_string(),
+            versions,
+            yanked,
+        });
+        self.package_ids.insert(name.to_string(), id);
+        Ok(id)
+    }
+
+    fn version_count(
+        &self,
+        package: PackageId,
+    ) -> usize {
+        self.packages[package].versions.len()
+    }
+
+    fn add_incompatibility(
+        &mut self,
+        mut terms: BTreeMap<PackageId, Term>,
+        cause: Cause,
+    ) -> IncompatId {
+        // a term allowing anything always holds, e.g. a requirement nothing matches
+        terms.retain(|_, term| !term.is_any());
+        self.incompatibilities
+            .push(Incompatibility { terms, cause });
+        self.incompatibilities.len() - 1
+    }
+
+    /// The intersection of every assignment to `package` among the first `upto`.
+    fn combined(
+        &self,
+        package: PackageId,
+        upto: usize,
+    ) -> Term {
+        self.assignments[..upto]
+            .iter()
+            .filter(|a| a.package == package)
+            .fold(Term::any(self.version_count(package)), |acc, a| {
+                acc.intersect(&a.term)
+            })
+    }
+
+    fn relation(
+        &self,
+        incompat: IncompatId,
+    ) -> Relation {
+        let mut unsatisfied = None;
+        for (package, term) in &self.incompatibilities[incompat].terms {
+            let combined = self.combined(*package, self.assignments.len());
+            if combined.is_subset_of(term) {
+                continue;
+            }
+            if combined.is_disjoint(term) {
+                return Relation::Contradicted;
+            }
+            if unsatisfied.is_some() {
+                return Relation::Inconclusive;
+            }
+            unsatisfied = Some(*package);
+        }
+
+        match unsatisfied {
+            Some(package) => Relation::AlmostSatisfied(package),
+            None => Relation::Satisfied,
+        }
+    }
+
+    fn derive(
+        &mut self,
+        package: PackageId,
+        term: Term,
+        cause: IncompatId,
+    ) {
+        self.assignments.push(Assignment {
+            package,
+            term,
+            level: self.decision_level,
+            cause: Some(cause),
+        });
+    }
+
+    fn propagate(
+        &mut self,
+        start: PackageId,
+    ) -> Result<(), IncompatId> {
+        let mut changed = vec![start];
+
+        while let Some(package) = changed.pop() {
+            for incompat in (0..self.incompatibilities.len()).rev() {
+                if !self.incompatibilities[incompat]
+                    .terms
+                    .contains_key(&package)
+                {
+                    continue;
+                }
+
+                match self.relation(incompat) {
+                    Relation::Satisfied => {
+                        let root_cause = self.resolve_conflict(incompat)?;
+                        let Relation::AlmostSatisfied(unsatisfied) = self.relation(root_cause)
+                        else {
+                            unreachable!("conflict resolution yields an almost satisfied cause")
+                        };
+                        let term = self.incompatibilities[root_cause].terms[&unsatisfied].negate();
+                        self.derive(unsatisfied, term, root_cause);
+                        changed.clear();
+                        changed.push(unsatisfied);
+                        break;
+                    },
+                    Relation::AlmostSatisfied(unsatisfied) => {
+                        let term = self.incompatibilities[incompat].terms[&unsatisfied].negate();
+                        self.derive(unsatisfied, term, incompat);
+                        changed.push(unsatisfied);
+                    },
+                    Relation::Contradicted | Relation::Inconclusive => {},
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_failure(
+        &self,
+        incompat: IncompatId,
+    ) -> bool {
+        let terms = &self.incompatibilities[incompat].terms;
+        terms.is_empty()
+            || (terms.len() == 1
+                && terms
+                    .get(&ROOT)
+                    .is_some_and(|term| term.is_positive()))
+    }
+
+    /// The index of the earliest assignment at which `term` for `package` is satisfied.
+    fn satisfier_of(
+        &self,
+        package: PackageId,
+        term: &Term,
+    ) -> usize {
+        let mut combined = Term::any(self.version_count(package));
+        for (idx, assignment) in self.assignments.iter().enumerate() {
+            if assignment.package == package {
+                combined = combined.intersect(&assignment.term);
+                if combined.is_subset_of(term) {
+                    return idx;
+                }
+            }
+        }
+        unreachable!("satisfied terms have a satisfier")
+    }
+
+    fn resolve_conflict(
+        &mut self,
+        mut incompat: IncompatId,
+    ) -> Result<IncompatId, IncompatId> {
+        loop {
+            if self.is_failure(incompat) {
+                return Err(incompat);
+            }
+
+            let terms = &self.incompatibilities[incompat].terms;
+            let mut satisfier = 0;
+            let mut satisfier_package = ROOT;
+            for (package, term) in terms {
+                let idx = self.satisfier_of(*package, term);
+                if idx >= satisfier {
+                    satisfier = idx;
+                    satisfier_package = *package;
+                }
+            }
+
+            // the latest level at which everything except the satisfier already held
+            let mut previous_level = 1;
+            for (package, term) in terms {
+                if *package == satisfier_package {
+                    let mut combined = Term::any(self.version_count(*package));
+                    let satisfier_term = &self.assignments[satisfier].term;
+                    for assignment in &self.assignments[..satisfier] {
+                        if assignment.package == *package {
+                            combined = combined.intersect(&assignment.term);
+                            if combined
+                                .intersect(satisfier_term)
+                                .is_subset_of(term)
+                            {
+                                previous_level = previous_level.max(assignment.level);
+                                break;
+                            }
+                        }
+                    }
+                } else {
+                    let idx = self.satisfier_of(*package, term);
+                    previous_level = previous_level.max(self.assignments[idx].level);
+                }
+            }
+
+            let assignment = &self.assignments[satisfier];
+            match assignment.cause {
+                Some(cause) if previous_level == assignment.level => {
+                    let terms = self.prior_cause(incompat, cause, satisfier_package);
+                    incompat = self.add_incompatibility(terms, Cause::Derived(incompat, cause));
+                },
+                _ => {
+                    self.backtrack(previous_level);
+                    return Ok(incompat);
+                },
+            }
+        }
+    }
+
+    /// Resolves `incompat` with `cause` on `package`, as in the resolution rule of logic.
+    fn prior_cause(
+        &self,
+        incompat: IncompatId,
+        cause: IncompatId,
+        package: PackageId,
+    ) -> BTreeMap<PackageId, Term> {
+        let left = &self.incompatibilities[incompat].terms;
+        let right = &self.incompatibilities[cause].terms;
+
+        let mut terms: BTreeMap<PackageId, Term> = left
+            .iter()
+            .filter(|(p, _)| **p != package)
+            .map(|(p, t)| (*p, t.clone()))
+            .collect();
+        for (p, t) in right.iter().filter(|(p, _)| **p != package) {
+            terms
+                .entry(*p)
+                .and_modify(|existing| *existing = existing.intersect(t))
+                .or_insert_with(|| t.clone());
+        }
+
+        let merged = left[&package].union(&right[&package]);
+        if !merged.is_any() {
+            terms.insert(package, merged);
+        }
+        terms
+    }
+
+    fn backtrack(
+        &mut self,
+        level: usize,
+    ) {
+        self.assignments.retain(|a| a.level <= level);
+        self.decision_level = level;
+    }
+
+    /// Picks the next package to decide on, returning `None` once every required package
+    /// has a version.
+    fn decide(&mut self) -> crate::Result<Option<PackageId>> {
+        let decided: Vec<PackageId> = self
+            .assignments
+            .iter()
+            .filter(|a| a.cause.is_none())
+            .map(|a| a.package)
+            .collect();
+
+        // fewest remaining candidates first, so conflicts surface early
+        let candidate = (0..self.packages.len())
+            .filter(|package| !decided.contains(package))
+            .map(|package| (package, self.combined(package, self.assignments.len())))
+            .filter(|(_, term)| term.is_positive())
+            .min_by_key(|(package, term)| {
+                (
+                    term.versions().count(),
+                    self.packages[*package].name.clone(),
+                )
+            });
+
+        let Some((package, allowed)) = candidate else {
+            return Ok(None);
+        };
+
+        let preferred = self
+            .preferred
+            .get(&self.packages[package].name)
+            .and_then(|v| {
+                self.packages[package]
+                    .versions
+                    .iter()
+                    .position(|candidate| candidate == v)
+            })
+            .filter(|idx| allowed.bits[*idx]);
+
+        let Some(version) = preferred.or_else(|| allowed.versions().last()) else {
+            self.add_incompatibility(BTreeMap::from([(package, allowed)]), Cause::NoVersions);
+            return Ok(Some(package));
+        };
+
+        let added = self.add_dependencies(package, version)?;
+
+        // deciding would immediately violate one of the new dependencies, so let
+        // propagation rule this version out instead
+        let conflicts = added.into_iter().any(|incompat| {
+            self.incompatibilities[incompat]
+                .terms
+                .iter()
+                .filter(|(p, _)| **p != package)
+                .all(|(p, term)| {
+                    self.combined(*p, self.assignments.len())
+                        .is_subset_of(term)
+                })
+        });
+
+        if !conflicts {
+            self.decision_level += 1;
+            self.assignments.push(Assignment {
+                package,
+                term: Term::exactly(self.version_count(package), version),
+                level: self.decision_level,
+                cause: None,
+            });
+        }
+
+        Ok(Some(package))
+    }
+
+    fn add_dependencies(
+        &mut self,
+        package: PackageId,
+        version: usize,
+    ) -> crate::Result<Vec<IncompatId>> {
+        if !self.loaded.insert((package, version)) {
+            return Ok(vec![]);
+        }
+
+        let dependencies = if package == ROOT {
+            self.root_dependencies.clone()
+        } else {
+            let name = self.packages[package].name.clone();
+            let version = self.packages[package].versions[version].clone();
+            self.index.dependencies(&name, &version)?
+        };
+
+        let mut added = Vec::with_capacity(dependencies.len());
+        for (name, requirement) in dependencies {
+            let dependency = self.package_id(&name)?;
+            if dependency == package {
+                continue;
+            }
+
+            let count = self.version_count(dependency);
+            let mut allowed = Term::none(count);
+            for (idx, candidate) in self.packages[dependency]
+                .versions
+                .iter()
+                .enumerate()
+            {
+                allowed.bits[idx] = requirement.matches(candidate);
+            }
+            self.labels
+                .insert((dependency, allowed.clone()), requirement.to_string());
+
+            added.push(self.add_incompatibility(
+                BTreeMap::from([
+                    (package, Term::exactly(self.version_count(package), version)),
+                    (dependency, allowed.negate()),
+                ]),
+                Cause::Dependency {
+                    package,
+                    version,
+                    dependency,
+                    requirement: requirement.to_string(),
+                },
+            ));
+        }
+        Ok(added)
+    }
+}
+
+/// Renders the derivation of a failed solve as numbered sentences.
+struct Reporter<'s, 'a> {
+    solver: &'s Solver<'a>,
+    lines: Vec<String>,
+    numbered: HashMap<IncompatId, usize>,
+}
+
+impl<'s, 'a> Reporter<'s, 'a> {
+    fn new(solver: &'s Solver<'a>) -> Self {
+        Self {
+            solver,
+            lines: vec![],
+            numbered: HashMap::new(),
+        }
+    }
+
+    fn report(
+        mut self,
+        failure: IncompatId,
+    ) -> String {
+        match self.solver.incompatibilities[failure].cause {
+            Cause::Derived(..) => {
+                self.explain(failure);
+            },
+            _ => {
+                self.lines.push(format!(
+                    "because {}, version solving failed.",
+                    self.describe(failure)
+                ))
+            },
+        }
+
+        let mut out = String::new();
+        for (idx, line) in self.lines.iter().enumerate() {
+            let _ = writeln!(out, "{:>3}. {line}", idx + 1);
+        }
+        out.trim_end().to_string()
+    }
+
+    /// Writes the line for a derived incompatibility once, returning its number.
+    fn explain(
+        &mut self,
+        incompat: IncompatId,
+    ) -> usize {
+        if let Some(line) = self.numbered.get(&incompat) {
+            return *line;
+        }
+
+        let Cause::Derived(left, right) = self.solver.incompatibilities[incompat].cause else {
+            unreachable!("only derived incompatibilities are explained")
+        };
+        let left = self.reason(left);
+        let right = self.reason(right);
+
+        let line = format!("because {left} and {right}, {}.", self.describe(incompat));
+        self.lines.push(line);
+        let number = self.lines.len();
+        self.numbered.insert(incompat, number);
+        number
+    }
+
+    fn reason(
+        &mut self,
+        incompat: IncompatId,
+    ) -> String {
+        match self.solver.incompatibilities[incompat].cause {
+            Cause::Derived(..) => {
+                let number = self.explain(incompat);
+                format!("{} ({number})", self.describe(incompat))
+            },
+            _ => self.describe(incompat),
+        }
+    }
+
+    fn describe(
+        &self,
+        incompat: IncompatId,
+    ) -> String {
+        let incompat = &self.solver.incompatibilities[incompat];
+        match &incompat.cause {
+            Cause::Root => format!("{} is the root package", self.package(ROOT)),
+            Cause::NoVersions => {
+                let (package, term) = incompat
+                    .terms
+                    .iter()
+                    .next()
+                    .expect("no-versions incompatibilities have a term");
+                if self.solver.packages[*package]
+                    .versions
+                    .is_empty()
+                {
+                    format!(
+                        "{} has no published versions{}",
+                        self.package(*package),
+                        self.yanked(*package)
+                    )
+                } else {
+                    format!(
+                        "no versions of {} match{}",
+                        self.term(*package, term),
+                        self.yanked(*package)
+                    )
+                }
+            },
+            Cause::Dependency {
+                package,
+                version,
+                dependency,
+                requirement,
+            } => {
+                let depender = if *package == ROOT {
+                    self.package(ROOT).to_string()
+                } else {
+                    format!(
+                        "{} {}",
+                        self.package(*package),
+                        self.solver.packages[*package].versions[*version]
+                    )
+                };
+                let name = self.package(*dependency);
+                if incompat.terms.contains_key(dependency) {
+                    format!("{depender} depends on {name} {requirement}")
+                } else if self.solver.packages[*dependency]
+                    .versions
+                    .is_empty()
+                {
+                    format!(
+                        "{depender} depends on {name} {requirement}, but {name} has no published versions{}",
+                        self.yanked(*dependency)
+                    )
+                } else {
+                    format!(
+                        "{depender} depends on {name} {requirement}, but no versions of {name} match{}",
+                        self.yanked(*dependency)
+                    )
+                }
+            },
+            Cause::Derived(..) => self.describe_terms(&incompat.terms),
+        }
+    }
+
+    /// Notes yanked versions of `package` which were not considered.
+    fn yanked(
+        &self,
+        package: PackageId,
+    ) -> String {
+        match self.solver.packages[package].yanked {
+            0 => String::new(),
+            1 => " (1 yanked version was skipped)".into(),
+            n => format!(" ({n} yanked versions were skipped)"),
+        }
+    }
+
+    fn describe_terms(
+        &self,
+        terms: &BTreeMap<PackageId, Term>,
+    ) -> String {
+        // the root is always selected, so it only matters on its own
+        let positive: Vec<_> = terms
+            .iter()
+            .filter(|(p, t)| t.is_positive() && (**p != ROOT || terms.len() == 1))
+            .collect();
+        let negative: Vec<_> = terms
+            .iter()
+            .filter(|(_, t)| !t.is_positive())
+            .collect();
+
+        match (positive.as_slice(), negative.as_slice()) {
+            ([], []) => "version solving failed".into(),
+            ([(package, _)], []) if **package == ROOT => "version solving failed".into(),
+            ([(package, term)], []) => format!("{} is forbidden", self.term(**package, term)),
+            ([], [(package, term)]) => {
+                format!("{} is required", self.term(**package, &term.negate()))
+            },
+            ([(package, term)], [(dependency, dep_term)]) => {
+                format!(
+                    "{} requires {}",
+                    self.term(**package, term),
+                    self.term(**dependency, &dep_term.negate())
+                )
+            },
+            _ => {
+                let mut parts: Vec<String> = positive
+                    .iter()
+                    .map(|(p, t)| self.term(**p, t))
+                    .collect();
+                parts.extend(
+                    negative
+                        .iter()
+                        .map(|(p, t)| format!("not {}", self.term(**p, &t.negate()))),
+                );
+                format!("{} are incompatible", parts.join(" and "))
+            },
+        }
+    }
+
+    fn package(
+        &self,
+        package: PackageId,
+    ) -> &str {
+        &self.solver.packages[package].name
+    }
+
+    /// Describes the selected versions of a term, ignoring whether absence is allowed.
+    fn term(
+        &self,
+        package: PackageId,
+        term: &Term,
+    ) -> String {
+        let name = self.package(package);
+        if package == ROOT {
+            return name.to_string();
+        }
+
+        let mut selected = term.clone();
+        let absent = selected.absent_bit();
+        selected.bits[absent] = false;
+        if let Some(label) = self
+            .solver
+            .labels
+            .get(&(package, selected.clone()))
+        {
+            return format!("{name} {label}");
+        }
+
+        let versions = &self.solver.packages[package].versions;
+        let indices: Vec<usize> = selected.versions().collect();
+        match indices.as_slice() {
+            [] => format!("{name} (no versions)"),
+            [only] => format!("{name} {}", versions[*only]),
+            _ if indices.len() == versions.len() => format!("any version of {name}"),
+            _ if indices.len() <= 4 => {
+                format!(
+                    "{name} ({})",
+                    indices
+                        .iter()
+                        .map(|idx| versions[*idx].to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            },
+            _ => {
+                format!(
+                    "{name} ({} versions from {} to {})",
+                    indices.len(),
+                    versions[indices[0]],
+                    versions[*indices.last().unwrap()]
+                )
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kintsu_manifests::version::{parse_version, parse_version_req};
+
+    /// `(package, version, [(dependency, requirement)])`
+    type Listing<'a> = &'a [(&'a str, &'a str, &'a [(&'a str, &'a str)])];
+
+    struct TestIndex {
+        listing: BTreeMap<(String, Version), Vec<(String, VersionReq)>>,
+        yanked: Vec<(String, Version)>,
+    }
+
+    impl TestIndex {
+        fn new(listing: Listing) -> Self {
+            Self {
+                listing: listing
+                    .iter()
+                    .map(|(name, version, deps)| {
+                        (
+                            (name.to_string(), parse_version(version).unwrap()),
+                            deps.iter()
+                                .map(|(dep, req)| {
+                                    (dep.to_string(), parse_version_req(req).unwrap())
+                                })
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+                yanked: vec![],
+            }
+        }
+
+        fn yank(
+            mut self,
+            package: &str,
+            version: &str,
+        ) -> Self {
+            self.yanked
+                .push((package.to_string(), parse_version(version).unwrap()));
+            self
+        }
+    }
+
+    impl PackageIndex for TestIndex {
+        fn versions(
+            &self,
+            package: &str,
+        ) -> crate::Result<Vec<Version>> {
+            Ok(self
+                .listing
+                .keys()
+                .filter(|(name, _)| name == package)
+                .map(|(_, version)| version.clone())
+                .collect())
+        }
+
+        fn dependencies(
+            &self,
+            package: &str,
+            version: &Version,
+        ) -> crate::Result<Vec<(String, VersionReq)>> {
+            Ok(self.listing[&(package.to_string(), version.clone())].clone())
+        }
+
+        fn is_yanked(
+            &self,
+            package: &str,
+            version: &Version,
+        ) -> crate::Result<bool> {
+            Ok(self
+                .yanked
+                .iter()
+                .any(|(name, yanked)| name == package && yanked == version))
+        }
+    }
+
+    fn run(
+        listing: Listing,
+        requirements: &[(&str, &str)],
+        preferred: &[(&str, &str)],
+    ) -> crate::Result<Vec<String>> {
+        run_index(&TestIndex::new(listing), requirements, preferred)
+    }
+
+    fn run_index(
+        index: &TestIndex,
+        requirements: &[(&str, &str)],
+        preferred: &[(&str, &str)],
+    ) -> crate::Result<Vec<String>> {
+        let solution = solve(
+            index,
+            "app",
+            &parse_version("0.1.0").unwrap(),
+            requirements
+                .iter()
+                .map(|(name, req)| (name.to_string(), parse_version_req(req).unwrap()))
+                .collect(),
+            &preferred
+                .iter()
+                .map(|(name, version)| (name.to_string(), parse_version(version).unwrap()))
+                .collect(),
+        )?;
+        Ok(solution
+            .into_iter()
+            .map(|(name, version)| format!("{name}@{version}"))
+            .collect())
+    }
+
+    fn failure(
+        listing: Listing,
+        requirements: &[(&str, &str)],
+    ) -> String {
+        match run(listing, requirements, &[]) {
+            Ok(solution) => panic!("expected a conflict, solved {solution:?}"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn picks_highest_matching_versions() {
+        let listing: Listing = &[
+            ("a", "1.0.0", &[("c", "~1.2")]),
+            ("a", "1.4.0", &[("c", "~1.2")]),
+            ("a", "2.0.0", &[]),
+            ("b", "1.0.0", &[]),
+            ("b", "1.4.0", &[]),
+            ("b", "1.5.0", &[]),
+            ("c", "1.2.0", &[]),
+            ("c", "1.2.9", &[]),
+            ("c", "1.3.0", &[]),
+        ];
+        assert_eq!(
+            run(listing, &[("a", "^1"), ("b", ">=1.0.0, <1.5.0")], &[]).unwrap(),
+            ["a@1.4.0", "b@1.4.0", "c@1.2.9"]
+        );
+    }
+
+    #[test]
+    fn backtracks_out_of_conflicting_choices() {
+        // the newest `a` needs a `b` the root cannot accept
+        let listing: Listing = &[
+            ("a", "1.0.0", &[("b", "^1")]),
+            ("a", "2.0.0", &[("b", "^2")]),
+            ("b", "1.0.0", &[("c", "^1")]),
+            ("b", "1.1.0", &[("c", "^2")]),
+            ("b", "2.0.0", &[]),
+            ("c", "1.0.0", &[]),
+        ];
+        assert_eq!(
+            run(listing, &[("a", "*"), ("b", "^1")], &[]).unwrap(),
+            ["a@1.0.0", "b@1.0.0", "c@1.0.0"]
+        );
+    }
+
+    #[test]
+    fn prefers_locked_versions_that_still_match() {
+        let listing: Listing = &[
+            ("a", "1.0.0", &[]),
+            ("a", "1.1.0", &[]),
+            ("b", "1.0.0", &[]),
+            ("b", "2.0.0", &[]),
+        ];
+        assert_eq!(
+            run(
+                listing,
+                &[("a", "^1"), ("b", "^2")],
+                &[("a", "1.0.0"), ("b", "1.0.0")]
+            )
+            .unwrap(),
+            ["a@1.0.0", "b@2.0.0"]
+        );
+    }
+
+    #[test]
+    fn excludes_pre_releases_unless_requested() {
+        let listing: Listing = &[("a", "1.0.0", &[]), ("a", "1.1.0-rc.1", &[])];
+        assert_eq!(run(listing, &[("a", "^1")], &[]).unwrap(), ["a@1.0.0"]);
+        assert_eq!(
+            run(listing, &[("a", "^1.1.0-rc.1")], &[]).unwrap(),
+            ["a@1.1.0-rc.1"]
+        );
+    }
+
+    #[test]
+    fn reports_conflict_derivation() {
+        let listing: Listing = &[
+            ("a", "1.0.0", &[("c", "^1")]),
+            ("b", "1.0.0", &[("c", "^2")]),
+            ("c", "1.0.0", &[]),
+            ("c", "2.0.0", &[]),
+        ];
+        let report = failure(listing, &[("a", "^1"), ("b", "^1")]);
+
+        assert!(report.contains("a 1.0.0 depends on c ^1"), "{report}");
+        assert!(report.contains("b 1.0.0 depends on c ^2"), "{report}");
+        assert!(report.contains("app depends on"), "{report}");
+        assert!(report.contains("version solving failed."), "{report}");
+    }
+
+    #[test]
+    fn reports_unpublished_packages() {
+        let report = failure(&[], &[("missing", "^1")]);
+        assert!(
+            report.contains("missing has no published versions"),
+            "{report}"
+        );
+    }
+
+    #[test]
+    fn reports_unmatched_requirements() {
+        let listing: Listing = &[("a", "1.0.0", &[]), ("a", "1.2.0", &[])];
+        let report = failure(listing, &[("a", "^2")]);
+        assert!(
+            report.contains("app depends on a ^2, but no versions of a match"),
+            "{report}"
+        );
+    }
+
+    #[test]
+    fn skips_yanked_versions() {
+        let listing: Listing = &[("a", "1.0.0", &[]), ("a", "1.1.0", &[])];
+        let index = TestIndex::new(listing).yank("a", "1.1.0");
+        assert_eq!(run_index(&index, &[("a", "^1")], &[]).unwrap(), ["a@1.0.0"]);
+    }
+
+    #[test]
+    fn keeps_locked_yanked_versions() {
+        let listing: Listing = &[("a", "1.0.0", &[]), ("a", "1.1.0", &[])];
+        let index = TestIndex::new(listing).yank("a", "1.1.0");
+        assert_eq!(
+            run_index(&index, &[("a", "^1")], &[("a", "1.1.0")]).unwrap(),
+            ["a@1.1.0"]
+        );
+    }
+
+    #[test]
+    fn reports_skipped_yanked_versions() {
+        let listing: Listing = &[("a", "1.0.0", &[]), ("a", "2.0.0", &[])];
+        let index = TestIndex::new(listing).yank("a", "2.0.0");
+        let report = run_index(&index, &[("a", "^2")], &[])
+            .unwrap_err()
+            .to_string();
+        assert!(
+            report.contains("but no versions of a match (1 yanked version was skipped)"),
+            "{report}"
+        );
+    }
+
+    #[test]
+    fn formats_deprecation_notices() {
+        let notice = DeprecationNotice {
+            message: "no longer maintained".into(),
+            replacement: None,
+        };
+        assert_eq!(notice.to_string(), "no longer maintained");
+
+        let notice = DeprecationNotice {
+            replacement: Some("b".into()),
+            ..notice
+        };
+        assert_eq!(notice.to_string(), "no longer maintained (use `b` instead)");
+    }
+}
@@ -0,0 +1,132 @@
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use kintsu_fs::FileSystem;
+use kintsu_manifests::{
+    package::{Dependency, GitDependency, PathDependency, RemoteDependency},
+    version::Version,
+};
+
+use super::*;
+use crate::ctx::compile::utils::normalize_import_to_package_name;
+
+/// A package compiled as part of a workspace.
+#[derive(Clone)]
+pub struct WorkspaceMember {
+    pub root: PathBuf,
+    pub version: Version,
+}
+
+/// Resolves dependencies on workspace members to the member's sources, whether they are
+/// declared by path or by registry version, and everything else through `inner`.
+pub struct WorkspaceResolver {
+    fs: Arc<dyn FileSystem>,
+    inner: Arc<dyn PackageResolver>,
+    /// Members by package name
+    members: BTreeMap<String, WorkspaceMember>,
+}
+
+impl WorkspaceResolver {
+    pub fn new(
+        fs: Arc<dyn FileSystem>,
+        inner: Arc<dyn PackageResolver>,
+        members: BTreeMap<String, WorkspaceMember>,
+    ) -> Self {
+        Self { fs, inner, members }
+    }
+
+    /// The member satisfying `dependency`, if it names one. Registry requirements the
+    /// member's version does not match are left to the registry.
+    fn member(
+        &self,
+        package_name: &str,
+        dependency: &Dependency,
+    ) -> Option<&WorkspaceMember> {
+        let member = self.members.get(package_name)?;
+        match dependency {
+            Dependency::Remote(remote) => {
+                remote
+                    .version
+                    .matches(&member.version)
+                    .then_some(member)
+            },
+            Dependency::Path(_) | Dependency::PathWithRemote(_) => Some(member),
+            Dependency::Git(_) | Dependency::Workspace(_) => None,
+        }
+    }
+}
+
+impl PathResolver for WorkspaceResolver {
+    fn resolve_path(
+        &self,
+        dep_name: &str,
+        root_path: &Path,
+        path: &PathDependency,
+    ) -> crate::Result<ResolvedDependency> {
+        self.inner
+            .resolve_path(dep_name, root_path, path)
+    }
+}
+
+impl GitResolver for WorkspaceResolver {
+    fn resolve_git(
+        &self,
+        dep_name: &str,
+        git: &GitDependency,
+    ) -> crate::Result<ResolvedDependency> {
+        self.inner.resolve_git(dep_name, git)
+    }
+}
+
+impl RemoteResolver for WorkspaceResolver {
+    fn resolve_remote(
+        &self,
+        dep_name: &str,
+        remote: &RemoteDependency,
+    ) -> crate::Result<ResolvedDependency> {
+        self.inner.resolve_remote(dep_name, remote)
+    }
+}
+
+impl PackageResolver for WorkspaceResolver {
+    fn dependency_as_remote(&self) -> bool {
+        self.inner.dependency_as_remote()
+    }
+
+    fn index(&self) -> Option<&dyn PackageIndex> {
+        self.inner.index()
+    }
+
+    fn registry_requirement<'d>(
+        &self,
+        name: &str,
+        dependency: &'d Dependency,
+    ) -> Option<&'d RemoteDependency> {
+        if self.member(name, dependency).is_some() {
+            return None;
+        }
+        self.inner
+            .registry_requirement(name, dependency)
+    }
+
+    fn resolve(
+        &self,
+        root_path: &Path,
+        dep_name: &str,
+        dependency: &Dependency,
+    ) -> crate::Result<ResolvedDependency> {
+        match self.member(&normalize_import_to_package_name(dep_name), dependency) {
+            Some(member) => {
+                Ok(ResolvedDependency {
+                    fs: self.fs.clone(),
+                    path: member.root.clone(),
+                    mutability: DependencyMutability::Mutable,
+                    version: member.version.clone(),
+                })
+            },
+            None => {
+                self.inner
+                    .resolve(root_path, dep_name, dependency)
+            },
+        }
+    }
+}
@@ -12,31 +12,26 @@ use crate::{
             schemas::{Import, SchemaDependencyGraph},
         },
         resolve::helpers::build_struct_def_from_anonymous,
+        source_map::{Origin, OriginKind},
     },
     defs::Spanned,
     tokens::{IdentToken, ToTokens},
 };
 use convert_case::{Case, Casing};
-use kintsu_cli_core::ProgressBar;
+use kintsu_cli_core::{PhaseProgress, prefixes};
 use pathfinding::prelude::*;
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
     sync::Arc,
 };
 
-async fn run_in_group<
-    T,
-    F: Fn(&ProgressBar, usize, Vec<T>) -> Fut,
-    Fut: Future<Output = crate::Result<()>>,
->(
-    compile_bar: ProgressBar,
+async fn run_in_group<T, F: Fn(usize, Vec<T>) -> Fut, Fut: Future<Output = crate::Result<()>>>(
     groups: Vec<Vec<T>>,
     f: F,
 ) -> crate::Result<()> {
     let mut futs = vec![];
     for (level, group) in groups.into_iter().enumerate() {
-        let compile_bar = compile_bar.clone();
-        futs.push(Box::pin(f(&compile_bar, level, group)));
+        futs.push(Box::pin(f(level, group)));
     }
 
     let _ = futures_util::future::try_join_all(futs).await?;
@@ -49,14 +44,17 @@ impl SchemaCompiler {
     pub async fn compile_all(ctx: &super::CompileCtx) -> crate::Result<()> {
         tracing::info!("Starting parallel schema compilation");
 
-        let graph_spinner = ctx.progress.add_spinner("Analyzing");
-        graph_spinner.set_message("schema dependencies");
+        let package = &ctx.root.package.package().name;
+        let analyzing = ctx
+            .progress
+            .phase(package, prefixes::ANALYZING, None);
+        let phase_start = web_time::Instant::now();
 
         tracing::trace!("Building schema dependency graph");
         let graph = Self::build_graph(ctx).await?;
         tracing::trace!(schema_count = graph.nodes.len(), "Dependency graph built");
 
-        graph_spinner.finish_with_message(format!("{} schemas", graph.nodes.len()));
+        analyzing.finish();
 
         if !graph.nodes.is_empty() {
             tracing::trace!("Detecting circular schema dependencies");
@@ -91,6 +89,8 @@ impl SchemaCompiler {
         tracing::trace!("Computing topological sort groups");
 
         let groups = graph.topological_groups()?;
+        ctx.profiler
+            .record("analyze schema graph", phase_start.elapsed());
 
         tracing::info!(
             group_count = groups.len(),
@@ -106,47 +106,46 @@ impl SchemaCompiler {
             );
         }
 
+        let phase_start = web_time::Instant::now();
         let total_schemas: u64 = groups.iter().map(|g| g.len() as u64).sum();
-        let compile_bar = ctx
+        let compiling = ctx
             .progress
-            .add_bar(total_schemas, "Compiling");
-
-        run_in_group(
-            compile_bar.clone(),
-            groups.clone(),
-            |compile_bar, level, group| {
-                let compile_bar = compile_bar.clone();
-                async move {
+            .phase(package, prefixes::COMPILING, Some(total_schemas));
+
+        run_in_group(groups.clone(), |level, group| {
+            let compiling = compiling.clone();
+            async move {
+                tracing::debug!(
+                    level = level,
+                    schemas_in_group = group.len(),
+                    schemas = ?group.iter().map(|k| &k.package_name).collect::<Vec<_>>(),
+                    "Compiling schema group"
+                );
+
+                for it in group {
                     tracing::debug!(
                         level = level,
-                        schemas_in_group = group.len(),
-                        schemas = ?group.iter().map(|k| &k.package_name).collect::<Vec<_>>(),
-                        "Compiling schema group"
+                        schema = %it.package_name,
+                        "Compiling schema"
                     );
-
-                    for it in group {
-                        tracing::debug!(
-                            level = level,
-                            schema = %it.package_name,
-                            "Compiling schema"
-                        );
-                        compile_bar.set_message(format!("compiling {}", it.package_name));
-                        Self::compile_schema(ctx, &it).await?;
-                        compile_bar.set_message(format!("completed {}", it.package_name));
-                        compile_bar.inc(1);
-                    }
-
-                    tracing::debug!(level = level, "Schema group compilation complete");
-                    Ok(())
+                    Self::compile_schema(ctx, &it).await?;
+                    compiling.advance(&it.package_name);
                 }
-            },
-        )
+
+                tracing::debug!(level = level, "Schema group compilation complete");
+                Ok(())
+            }
+        })
         .await?;
 
-        compile_bar.finish_with_message("all schemas");
+        compiling.finish();
+        ctx.profiler
+            .record("compile schemas", phase_start.elapsed());
 
         tracing::info!("Schema compilation complete, starting type resolution");
 
+        let phase_start = web_time::Instant::now();
+
         // calculate namespaces after first pass, no-op if progress disabled
         let total_namespaces: u64 = if ctx.progress.is_enabled() {
             let mut count = 0u64;
@@ -164,42 +163,37 @@ impl SchemaCompiler {
             0
         };
 
-        // Create progress bar for type resolution
-        let resolution_bar = ctx
+        let resolving = ctx
             .progress
-            .add_bar(total_namespaces, "Resolving");
-
-        run_in_group(
-            resolution_bar.clone(),
-            groups,
-            |resolution_bar, level, group| {
-                let resolution_bar = resolution_bar.clone();
-                async move {
+            .phase(package, prefixes::RESOLVING, Some(total_namespaces));
+
+        run_in_group(groups, |level, group| {
+            let resolving = resolving.clone();
+            async move {
+                tracing::debug!(
+                    level = level,
+                    schemas_in_group = group.len(),
+                    "Starting type resolution for schema group"
+                );
+
+                for it in group {
                     tracing::debug!(
                         level = level,
-                        schemas_in_group = group.len(),
-                        "Starting type resolution for schema group"
+                        schema = %it.package_name,
+                        "Resolving types for schema"
                     );
-
-                    for it in group {
-                        tracing::debug!(
-                            level = level,
-                            schema = %it.package_name,
-                            "Resolving types for schema"
-                        );
-                        resolution_bar.set_message(format!("resolving types: {}", it.package_name));
-                        Self::resolve_schema_types(ctx, &it, &resolution_bar).await?;
-                        resolution_bar.set_message(format!("completed: {}", it.package_name));
-                    }
-
-                    tracing::debug!(level = level, "Type resolution for schema group complete");
-                    Ok(())
+                    Self::resolve_schema_types(ctx, &it, &resolving).await?;
                 }
-            },
-        )
+
+                tracing::debug!(level = level, "Type resolution for schema group complete");
+                Ok(())
+            }
+        })
         .await?;
 
-        resolution_bar.finish_with_message("all namespaces");
+        resolving.finish();
+        ctx.profiler
+            .record("resolve types", phase_start.elapsed());
 
         tracing::info!("Type resolution complete");
 
@@ -309,11 +303,11 @@ impl SchemaCompiler {
         output
     }
 
-    #[tracing::instrument(skip(ctx, schema_id, resolution_bar), fields(package = %schema_id.package_name))]
+    #[tracing::instrument(skip(ctx, schema_id, resolving), fields(package = %schema_id.package_name))]
     async fn resolve_schema_types(
         ctx: &super::CompileCtx,
         schema_id: &CacheKey,
-        resolution_bar: &ProgressBar,
+        resolving: &PhaseProgress,
     ) -> crate::Result<()> {
         tracing::debug!("Starting schema type resolution");
 
@@ -361,10 +355,11 @@ impl SchemaCompiler {
                 .iter()
                 .map(|ns_name| {
                     let schema = Arc::clone(&schema);
+                    let state = Arc::clone(&ctx.state);
                     let ns_name = ns_name.clone();
-                    let resolution_bar = resolution_bar.clone();
+                    let resolving = resolving.clone();
                     async move {
-                        Self::resolve_namespace_types(&schema, &ns_name, &resolution_bar).await
+                        Self::resolve_namespace_types(&schema, &state, &ns_name, &resolving).await
                     }
                 })
                 .collect();
@@ -378,11 +373,12 @@ impl SchemaCompiler {
         Ok(())
     }
 
-    #[tracing::instrument(skip(schema, resolution_bar), fields(ns = %ns_name))]
+    #[tracing::instrument(skip(schema, state, resolving), fields(ns = %ns_name))]
     async fn resolve_namespace_types(
         schema: &Arc<SchemaCtx>,
+        state: &tokio::sync::RwLock<super::state::SharedCompilationState>,
         ns_name: &str,
-        resolution_bar: &ProgressBar,
+        resolving: &PhaseProgress,
     ) -> crate::Result<()> {
         tracing::debug!("Starting TypeResolver");
 
@@ -396,7 +392,13 @@ impl SchemaCompiler {
             })?;
 
         let resolver = TypeResolver::new(ns.clone());
-        let resolution = resolver.resolve().await?;
+        let mut resolution = resolver.resolve().await?;
+        if !resolution.warnings.is_empty() {
+            let mut state = state.write().await;
+            for warning in std::mem::take(&mut resolution.warnings) {
+                state.report(warning);
+            }
+        }
 
         tracing::debug!(
             anonymous_structs = resolution.anonymous_structs.len(),
@@ -430,14 +432,15 @@ impl SchemaCompiler {
                     &item_ctx.context,
                     &struct_def.def.name,
                     Definition::Struct(Arc::new(struct_def.clone())),
-                    struct_def.def_span().clone(),
+                    ns_resolved
+                        .source_map
+                        .locate(item_ctx.name.borrow_string(), struct_def.def_span()),
                     child.source.clone(),
                 )?;
             }
         }
 
-        resolution_bar.inc(1);
-        resolution_bar.set_message(ns_name.to_string());
+        resolving.advance(ns_name);
 
         tracing::debug!("Type resolution complete");
         Ok(())
@@ -558,6 +561,12 @@ impl SchemaCompiler {
 
         // Keep namespace sources available for error reporting
         let ns_sources = ns.sources.clone();
+        let source_map = ns.source_map.clone();
+        let child_sources: BTreeMap<_, _> = ns
+            .children
+            .iter()
+            .map(|(ctx, child)| (ctx.clone(), child.source.clone()))
+            .collect();
         drop(ns);
 
         tracing::trace!(
@@ -568,95 +577,53 @@ impl SchemaCompiler {
         let type_names = type_graph.type_names();
         if !type_names.is_empty() {
             tracing::trace!("Detecting type dependency cycles");
-            use pathfinding::prelude::*;
-
-            let successors_fn =
-                |node: &crate::ctx::paths::NamedItemContext| type_graph.required_successors(node);
-
-            let components = strongly_connected_components(&type_names, successors_fn);
-
-            tracing::trace!(component_count = components.len(), "SCC detection complete");
-
-            for component in &components {
-                if component.len() > 1 {
-                    if !type_graph.has_terminating_edge(component) {
-                        // Non-terminating cycle - error
-                        // Build cycle in deterministic dependency order starting from
-                        // lexicographically smallest node
-                        let cycle_set: std::collections::HashSet<_> =
-                            component.iter().cloned().collect();
-
-                        // Find the lexicographically smallest node to start from
-                        let start_node = component
-                            .iter()
-                            .min_by(|a, b| a.display().cmp(&b.display()))
-                            .unwrap()
-                            .clone();
-
-                        // Traverse dependencies in order to build deterministic cycle path
-                        // Successors now return definition-site nodes for consistent spans
-                        let mut ordered_cycle = vec![start_node.clone()];
-                        let mut current = start_node;
-                        let mut visited: std::collections::HashSet<String> =
-                            std::collections::HashSet::new();
-                        visited.insert(current.display());
-
-                        while ordered_cycle.len() < component.len() {
-                            // Get successors in the cycle, sorted for determinism
-                            let mut successors: Vec<_> = type_graph
-                                .required_successors(&current)
-                                .into_iter()
-                                .filter(|s| {
-                                    cycle_set.contains(s) && !visited.contains(&s.display())
-                                })
-                                .collect();
-                            successors.sort_by_key(|a| a.display());
-
-                            if let Some(next) = successors.into_iter().next() {
-                                visited.insert(next.display());
-                                ordered_cycle.push(next.clone());
-                                current = next;
-                            } else {
-                                break;
-                            }
-                        }
 
-                        let cycle_str: Vec<String> = ordered_cycle
-                            .iter()
-                            .map(|ctx| ctx.display())
-                            .collect();
-
-                        // Get span from first type in normalized cycle
-                        let first_ctx = &ordered_cycle[0];
-                        let raw_span = first_ctx.name.span.span();
-                        let span = crate::Span::new(raw_span.start, raw_span.end);
-
-                        // Use first available source for error context
-                        let (source_path, source_content) = ns_sources
-                            .iter()
-                            .next()
-                            .map(|(path, src)| (path.clone(), Some(src.clone())))
-                            .unwrap_or((std::path::PathBuf::new(), None));
-
-                        tracing::error!(cycle = ?cycle_str, "Non-terminating type cycle detected");
-                        let err: crate::Error = crate::ResolutionError::type_cycle(cycle_str)
-                            .at(span)
-                            .build()
-                            .into();
-                        return Err(err.with_source_arc_if(source_path, source_content));
+            if let Some(cycle) = type_graph
+                .required_cycles()
+                .into_iter()
+                .next()
+            {
+                let first_ctx = &cycle.edges[0].from;
+                let source_path = child_sources
+                    .get(first_ctx)
+                    .cloned()
+                    .or_else(|| ns_sources.keys().next().cloned())
+                    .unwrap_or_default();
+                let source_content = ns_sources.get(&source_path).cloned();
+
+                let span_of = |ctx: &crate::ctx::paths::NamedItemContext| {
+                    source_map.error_span(ctx.name.borrow_string(), &ctx.name.span)
+                };
+
+                tracing::error!(cycle = ?cycle.chain(), "Non-terminating type cycle detected");
+                let builder = if cycle.is_alias_chain() {
+                    crate::ResolutionError::type_cycle(cycle.chain())
+                } else {
+                    crate::ResolutionError::recursive_type(cycle.path())
+                };
+                let mut err: kintsu_errors::CompilerError = builder.at(span_of(first_ctx)).build();
+
+                // label the other participants, as long as they share the first type's file
+                for (i, edge) in cycle.edges.iter().enumerate().skip(1) {
+                    if child_sources.get(&edge.from) != Some(&source_path) {
+                        continue;
                     }
-                    // Terminating cycle - allowed (e.g., A has optional B, B has required A)
-                    tracing::trace!(
-                        cycle = ?component.iter().map(|c| c.display()).collect::<Vec<_>>(),
-                        "Terminating cycle allowed"
+                    err = err.with_secondary_label(
+                        span_of(&edge.from),
+                        format!("cycle element {} of {}", i + 1, cycle.edges.len()),
                     );
                 }
+
+                let err: crate::Error = err.into();
+                return Err(err.with_source_arc_if(source_path, source_content));
             }
 
             tracing::trace!("Computing topological sort for types");
 
+            // Only required edges are ordered: recursion through optional fields and
+            // unsized arrays is allowed, and without required cycles this graph is acyclic
             let successors_fn =
-                |node: &crate::ctx::paths::NamedItemContext| type_graph.all_successors(node);
+                |node: &crate::ctx::paths::NamedItemContext| type_graph.required_successors(node);
 
             let groups = match topological_sort_into_groups(&type_names, successors_fn) {
                 Ok(groups) => groups,
@@ -815,6 +782,11 @@ impl SchemaCompiler {
                 // They are handled separately during code generation
                 return Ok(());
             },
+            NamespaceChild::Const(_) => {
+                tracing::trace!("skipping const (not a type)");
+                // Constants are evaluated during resolution and emitted with declarations
+                return Ok(());
+            },
             NamespaceChild::Namespace(_) => {
                 tracing::trace!("skipping namespace (not a type)");
                 // Nested namespaces are not types - skip
@@ -823,7 +795,9 @@ impl SchemaCompiler {
         };
 
         let source = child.source.clone();
-        let span = span.clone();
+        let span = ns
+            .source_map
+            .locate(type_ctx.name.borrow_string(), &span);
 
         drop(ns);
 
@@ -852,6 +826,7 @@ impl SchemaCompiler {
             let ns = ns_ctx.lock().await;
 
             for (item_ctx, child) in &ns.children {
+                let first_extracted = extracted.len();
                 match &child.value {
                     // Extract LocalStruct variants from oneofs and errors
                     NamespaceChild::OneOf(oneof_def) => {
@@ -873,12 +848,17 @@ impl SchemaCompiler {
                                     inner.value.clone(),
                                     child.source.clone(),
                                 );
+                                let origin = Origin::new(
+                                    OriginKind::LocalStructVariant,
+                                    child.source.clone(),
+                                    variant.value.span.clone(),
+                                );
 
                                 let new_item_ctx = ns
                                     .ctx
                                     .item(Spanned::call_site(IdentToken::new(struct_name)));
 
-                                extracted.push((new_item_ctx, struct_def));
+                                extracted.push((new_item_ctx, struct_def, origin));
                             }
                         }
                     },
@@ -901,12 +881,17 @@ impl SchemaCompiler {
                                     inner.value.clone(),
                                     child.source.clone(),
                                 );
+                                let origin = Origin::new(
+                                    OriginKind::LocalStructVariant,
+                                    child.source.clone(),
+                                    variant.value.span.clone(),
+                                );
 
                                 let new_item_ctx = ns
                                     .ctx
                                     .item(Spanned::call_site(IdentToken::new(struct_name)));
 
-                                extracted.push((new_item_ctx, struct_def));
+                                extracted.push((new_item_ctx, struct_def, origin));
                             }
                         }
                     },
@@ -923,17 +908,44 @@ impl SchemaCompiler {
                     },
                     _ => {},
                 }
+
+                // generated structs share the visibility of the item they were lifted from
+                for (_, struct_def, _) in &mut extracted[first_extracted..] {
+                    struct_def.value.vis = child.value.visibility().cloned();
+                }
             }
         }
 
         // Insert extracted structs into namespace children
         if !extracted.is_empty() {
             let mut ns = ns_ctx.lock().await;
-            for (item_ctx, struct_def) in extracted {
+            for (item_ctx, struct_def, origin) in extracted {
+                let name = item_ctx.name.borrow_string().clone();
                 tracing::trace!(
-                    struct_name = %item_ctx.name.borrow_string(),
+                    struct_name = %name,
                     "Registering extracted anonymous struct as namespace child"
                 );
+
+                // a generated name may be declared already, unless it was generated before
+                if let Some((declared, child)) = ns.children.get_key_value(&item_ctx)
+                    && ns.source_map.origin(&name) != Some(&origin)
+                {
+                    let mut err = crate::TypeDefError::duplicate_type(item_ctx.display())
+                        .at(origin.error_span())
+                        .build();
+                    if child.source == origin.source {
+                        let declared = declared.name.span();
+                        err = err.with_secondary_label(
+                            crate::Span::new(declared.start, declared.end),
+                            "first declaration here",
+                        );
+                    }
+                    let err: crate::Error = err.into();
+                    let source = ns.sources.get(&origin.source).cloned();
+                    return Err(err.with_source_arc_if(origin.source.clone(), source));
+                }
+
+                ns.source_map.record(name, origin);
                 let child = NamespaceChild::Struct(struct_def.value);
                 ns.children
                     .insert(item_ctx, child.with_source(struct_def.source));
@@ -952,6 +964,7 @@ impl SchemaCompiler {
         extracted: &mut Vec<(
             crate::ctx::NamedItemContext,
             crate::ctx::common::FromNamedSource<crate::ast::items::StructDef>,
+            Origin,
         )>,
     ) {
         for field in fields {
@@ -973,12 +986,14 @@ impl SchemaCompiler {
                     ty.value.clone(),
                     source.clone(),
                 );
+                let origin =
+                    Origin::new(OriginKind::AnonymousStruct, source.clone(), ty.span.clone());
 
                 let new_item_ctx = ns
                     .ctx
                     .item(Spanned::call_site(IdentToken::new(struct_name.clone())));
 
-                extracted.push((new_item_ctx, struct_def));
+                extracted.push((new_item_ctx, struct_def, origin));
 
                 // Recursively extract from the nested struct's fields
                 Self::extract_anonymous_from_struct_fields(
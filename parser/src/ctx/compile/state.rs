@@ -8,6 +8,8 @@ use kintsu_manifests::{
     version::Version,
 };
 
+use kintsu_events::{Diagnostic, DiagnosticBundle};
+
 use crate::ctx::SchemaCtx;
 
 #[derive(Clone)]
@@ -41,6 +43,13 @@ pub struct SharedCompilationState {
     /// Track resolved dependency metadata for lockfile generation
     /// Map: package_name -> (version, source, checksum, provides)
     pub resolved_metadata: BTreeMap<String, ResolvedMetadata>,
+
+    /// Registry versions chosen by the solver, when the resolver provides an index
+    pub registry_solution: BTreeMap<String, Version>,
+
+    /// Diagnostics reported by this compilation, which denied warnings are checked
+    /// against rather than everything emitted in the process
+    pub diagnostics: DiagnosticBundle,
 }
 
 impl SharedCompilationState {
@@ -52,8 +61,20 @@ impl SharedCompilationState {
             lockfile: None,
             lockfile_invalidated: false,
             resolved_metadata: BTreeMap::new(),
+            registry_solution: BTreeMap::new(),
+            diagnostics: DiagnosticBundle::new(),
         }
     }
+
+    /// Emits `diagnostic` and keeps it with the diagnostics of this compilation.
+    pub fn report(
+        &mut self,
+        diagnostic: impl Into<Diagnostic>,
+    ) {
+        let diagnostic = diagnostic.into();
+        kintsu_events::emit(diagnostic.clone());
+        self.diagnostics.push(diagnostic);
+    }
 }
 
 impl Default for SharedCompilationState {
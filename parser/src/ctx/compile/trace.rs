@@ -0,0 +1,366 @@
+//! Span capture for compiler self-profiling.
+//!
+//! [`SpanCapture`] is a `tracing` layer which records every span entry and exit, so a slow
+//! compilation can be inspected in far more detail than the per-phase timings of the build
+//! report. Once installed, [`CompileCtx::finalize`](super::CompileCtx::finalize) writes the
+//! captured spans to the configured output: as collapsed stacks (for `inferno` or
+//! `flamegraph.pl`) when the path ends in `.folded`, otherwise as a chrome trace which
+//! chrome://tracing, Perfetto and speedscope all open.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use kintsu_fs::FileSystem;
+use serde::Serialize;
+use tracing::{
+    Subscriber,
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+use web_time::Instant;
+
+static ACTIVE: OnceLock<SpanCapture> = OnceLock::new();
+
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD: u64 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Phase {
+    #[serde(rename = "B")]
+    Begin,
+    #[serde(rename = "E")]
+    End,
+}
+
+/// One entry or exit of a span, in the chrome trace event format.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TraceEvent {
+    pub name: String,
+    #[serde(rename = "cat")]
+    pub target: String,
+    #[serde(rename = "ph")]
+    pub phase: Phase,
+    /// Microseconds since the capture started
+    #[serde(rename = "ts")]
+    pub timestamp: f64,
+    pub pid: u32,
+    pub tid: u64,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, String>,
+}
+
+/// Records span entries and exits for a flamegraph of the compilation.
+///
+/// Spans are labelled by name, except spans with a `phase` field (such as those opened by
+/// [`PhaseProfiler::time`](super::PhaseProfiler::time)) which are labelled by its value.
+#[derive(Clone)]
+pub struct SpanCapture {
+    started: Instant,
+    output: PathBuf,
+    events: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+impl SpanCapture {
+    pub fn new(output: impl Into<PathBuf>) -> Self {
+        Self {
+            started: Instant::now(),
+            output: output.into(),
+            events: Default::default(),
+        }
+    }
+
+    /// Makes a capture writing to `output` the process-wide one written on finalize. The
+    /// returned layer must still be added to the subscriber. Only the first install takes
+    /// effect.
+    pub fn install(output: impl Into<PathBuf>) -> Self {
+        ACTIVE
+            .get_or_init(|| Self::new(output))
+            .clone()
+    }
+
+    /// The installed capture, if profiling was requested.
+    pub fn active() -> Option<Self> {
+        ACTIVE.get().cloned()
+    }
+
+    pub fn output(&self) -> &Path {
+        &self.output
+    }
+
+    pub fn events(&self) -> Vec<TraceEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    pub fn to_chrome_trace(&self) -> String {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ChromeTrace<'a> {
+            trace_events: &'a [TraceEvent],
+            display_time_unit: &'static str,
+        }
+
+        serde_json::to_string(&ChromeTrace {
+            trace_events: &self.events.lock().unwrap(),
+            display_time_unit: "ms",
+        })
+        .expect("trace events are serializable")
+    }
+
+    /// Collapsed stacks, one `frame;frame;frame <self time in µs>` line per distinct stack.
+    pub fn to_folded(&self) -> String {
+        struct Frame<'a> {
+            name: &'a str,
+            started: f64,
+            children: f64,
+        }
+
+        let events = self.events.lock().unwrap();
+        let mut stacks: BTreeMap<u64, Vec<Frame>> = BTreeMap::new();
+        let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+
+        for event in events.iter() {
+            let stack = stacks.entry(event.tid).or_default();
+            match event.phase {
+                Phase::Begin => {
+                    stack.push(Frame {
+                        name: &event.name,
+                        started: event.timestamp,
+                        children: 0.0,
+                    })
+                },
+                Phase::End => {
+                    let Some(frame) = stack.pop() else {
+                        continue;
+                    };
+                    let elapsed = event.timestamp - frame.started;
+                    let key = stack
+                        .iter()
+                        .map(|parent| parent.name)
+                        .chain([frame.name])
+                        .collect::<Vec<_>>()
+                        .join(";");
+                    *totals.entry(key).or_default() += (elapsed - frame.children).max(0.0);
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children += elapsed;
+                    }
+                },
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|(stack, micros)| format!("{stack} {}\n", micros.round() as u64))
+            .collect()
+    }
+
+    /// Writes the spans captured so far to the output path.
+    pub async fn write(
+        &self,
+        fs: &dyn FileSystem,
+    ) -> crate::Result<()> {
+        let content = match self
+            .output
+            .extension()
+            .and_then(|ext| ext.to_str())
+        {
+            Some("folded") => self.to_folded(),
+            _ => self.to_chrome_trace(),
+        };
+        fs.write(&self.output, content.into_bytes())
+            .await?;
+
+        tracing::debug!("Compiler trace written to {}", self.output.display());
+        Ok(())
+    }
+
+    fn push<S>(
+        &self,
+        id: &span::Id,
+        ctx: &Context<'_, S>,
+        phase: Phase,
+    ) where
+        S: Subscriber + for<'a> LookupSpan<'a>, {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(fields) = extensions.get::<SpanFields>() else {
+            return;
+        };
+
+        let event = TraceEvent {
+            name: fields
+                .0
+                .get("phase")
+                .cloned()
+                .unwrap_or_else(|| span.name().to_string()),
+            target: span.metadata().target().to_string(),
+            phase,
+            timestamp: self.started.elapsed().as_secs_f64() * 1_000_000.0,
+            pid: std::process::id(),
+            tid: THREAD.with(|tid| *tid),
+            args: match phase {
+                Phase::Begin => fields.0.clone(),
+                Phase::End => BTreeMap::new(),
+            },
+        };
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+#[derive(Default)]
+struct SpanFields(BTreeMap<String, String>);
+
+impl Visit for SpanFields {
+    fn record_str(
+        &mut self,
+        field: &Field,
+        value: &str,
+    ) {
+        self.0
+            .insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(
+        &mut self,
+        field: &Field,
+        value: &dyn fmt::Debug,
+    ) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+impl<S> Layer<S> for SpanCapture
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        attrs: &span::Attributes<'_>,
+        id: &span::Id,
+        ctx: Context<'_, S>,
+    ) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = SpanFields::default();
+        attrs.record(&mut fields);
+        span.extensions_mut().insert(fields);
+    }
+
+    fn on_record(
+        &self,
+        id: &span::Id,
+        values: &span::Record<'_>,
+        ctx: Context<'_, S>,
+    ) {
+        if let Some(span) = ctx.span(id)
+            && let Some(fields) = span.extensions_mut().get_mut::<SpanFields>()
+        {
+            values.record(fields);
+        }
+    }
+
+    fn on_enter(
+        &self,
+        id: &span::Id,
+        ctx: Context<'_, S>,
+    ) {
+        self.push(id, &ctx, Phase::Begin);
+    }
+
+    fn on_exit(
+        &self,
+        id: &span::Id,
+        ctx: Context<'_, S>,
+    ) {
+        self.push(id, &ctx, Phase::End);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    fn capture_nested() -> SpanCapture {
+        let capture = SpanCapture::new("trace.json");
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _phase = tracing::info_span!("phase", phase = "resolve").entered();
+            for name in ["Foo", "Bar"] {
+                let _ty = tracing::debug_span!("resolve_type", type_name = name).entered();
+            }
+        });
+        capture
+    }
+
+    #[test]
+    fn records_nested_spans() {
+        let capture = capture_nested();
+        let events = capture.events();
+
+        assert_eq!(
+            events
+                .iter()
+                .map(|e| (e.name.as_str(), e.phase))
+                .collect::<Vec<_>>(),
+            vec![
+                ("resolve", Phase::Begin),
+                ("resolve_type", Phase::Begin),
+                ("resolve_type", Phase::End),
+                ("resolve_type", Phase::Begin),
+                ("resolve_type", Phase::End),
+                ("resolve", Phase::End),
+            ]
+        );
+        assert_eq!(events[3].args["type_name"], "Bar");
+        assert!(
+            events
+                .windows(2)
+                .all(|pair| pair[0].timestamp <= pair[1].timestamp)
+        );
+
+        let trace: serde_json::Value = serde_json::from_str(&capture.to_chrome_trace()).unwrap();
+        assert_eq!(trace["traceEvents"][0]["ph"], "B");
+        assert_eq!(trace["traceEvents"][0]["cat"], module_path!());
+    }
+
+    #[tokio::test]
+    async fn writes_folded_stacks() {
+        let capture = capture_nested();
+        let folded = capture.to_folded();
+
+        let stacks = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(stacks, vec!["resolve", "resolve;resolve_type"]);
+
+        let fs = kintsu_fs::memory::MemoryFileSystem::new();
+        let capture = SpanCapture {
+            output: "profile.folded".into(),
+            ..capture
+        };
+        capture.write(&fs).await.unwrap();
+        assert_eq!(
+            fs.read_to_string_sync("profile.folded".as_ref())
+                .unwrap(),
+            folded
+        );
+    }
+}
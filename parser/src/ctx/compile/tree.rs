@@ -0,0 +1,313 @@
+//! Resolved dependency tree of a compilation, printed by `kintsu tree`.
+//!
+//! Edges are read from the `use` imports of every loaded schema, so the tree shows which
+//! packages and namespaces are actually imported rather than what manifests declare.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt::Write,
+    sync::Arc,
+};
+
+use kintsu_manifests::version::Version;
+
+use crate::ctx::SchemaCtx;
+
+use super::{state::ResolvedMetadata, utils::normalize_package_to_import_name};
+
+const BRANCH: &str = "├── ";
+const LAST_BRANCH: &str = "└── ";
+const INDENT: &str = "│   ";
+const LAST_INDENT: &str = "    ";
+
+/// A package loaded by a compilation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreePackage {
+    pub name: String,
+    pub version: String,
+    /// Import names of the packages it imports from
+    pub dependencies: BTreeSet<String>,
+    /// Its namespaces, e.g. `dep::data`, with the namespaces each imports
+    pub namespaces: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Packages loaded by a compilation keyed by import name, e.g. `base_pkg` for `base-pkg`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyTree {
+    root: String,
+    packages: BTreeMap<String, TreePackage>,
+}
+
+impl DependencyTree {
+    pub(super) async fn from_schemas(
+        root: &SchemaCtx,
+        dependencies: &BTreeMap<String, Arc<SchemaCtx>>,
+        resolved: &BTreeMap<String, ResolvedMetadata>,
+    ) -> Self {
+        let root_name = normalize_package_to_import_name(&root.package.package().name);
+
+        let mut packages = BTreeMap::new();
+        packages.insert(
+            root_name.clone(),
+            Self::package(&root_name, root, None, dependencies).await,
+        );
+        for (name, schema) in dependencies {
+            packages.insert(
+                name.clone(),
+                Self::package(
+                    name,
+                    schema,
+                    resolved.get(name).map(|meta| &meta.version),
+                    dependencies,
+                )
+                .await,
+            );
+        }
+
+        Self {
+            root: root_name,
+            packages,
+        }
+    }
+
+    async fn package(
+        import_name: &str,
+        schema: &SchemaCtx,
+        version: Option<&Version>,
+        loaded: &BTreeMap<String, Arc<SchemaCtx>>,
+    ) -> TreePackage {
+        let manifest = schema.package.package();
+
+        let mut dependencies = BTreeSet::new();
+        let mut namespaces = BTreeMap::new();
+        for (name, ns) in &schema.namespaces {
+            let qualified = format!("{import_name}::{name}");
+            let mut imported = BTreeSet::new();
+
+            for import in &ns.lock().await.imports {
+                let target = import.value.as_ref_context();
+                // local namespaces are imported by name as well as through `schema::`
+                let (package, namespace) = if schema
+                    .namespaces
+                    .contains_key(&target.package)
+                {
+                    (import_name, Some(&target.package))
+                } else {
+                    (target.package.as_str(), target.namespace.first())
+                };
+
+                if package != import_name && loaded.contains_key(package) {
+                    dependencies.insert(package.to_string());
+                }
+
+                let target = match namespace {
+                    Some(namespace) => format!("{package}::{namespace}"),
+                    None => package.to_string(),
+                };
+                if target != qualified {
+                    imported.insert(target);
+                }
+            }
+
+            namespaces.insert(qualified, imported);
+        }
+
+        TreePackage {
+            name: manifest.name.clone(),
+            version: version
+                .map(ToString::to_string)
+                .unwrap_or_else(|| manifest.version.to_string()),
+            dependencies,
+            namespaces,
+        }
+    }
+
+    pub fn root(&self) -> &TreePackage {
+        &self.packages[&self.root]
+    }
+
+    pub fn packages(&self) -> impl Iterator<Item = &TreePackage> {
+        self.packages.values()
+    }
+
+    /// Names of packages loaded at more than one version across `trees`, e.g. by members of a
+    /// workspace.
+    pub fn duplicates<'a>(trees: impl IntoIterator<Item = &'a Self>) -> BTreeSet<String> {
+        let mut versions: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for package in trees.into_iter().flat_map(Self::packages) {
+            versions
+                .entry(&package.name)
+                .or_default()
+                .insert(&package.version);
+        }
+
+        versions
+            .into_iter()
+            .filter(|(_, versions)| versions.len() > 1)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// The packages the root imports from, transitively. Packages already shown are marked
+    /// `(*)` instead of repeating their dependencies, and those in `duplicates` are marked
+    /// `(duplicate)`.
+    pub fn render(
+        &self,
+        duplicates: &BTreeSet<String>,
+    ) -> String {
+        render(
+            &self.root,
+            |name| {
+                self.packages[name]
+                    .dependencies
+                    .iter()
+                    .cloned()
+                    .collect()
+            },
+            |name| self.label(name, duplicates),
+        )
+    }
+
+    /// The packages importing from `package`, up to the root, explaining why it was loaded.
+    /// `package` is either its manifest or its import name.
+    pub fn render_inverted(
+        &self,
+        package: &str,
+        duplicates: &BTreeSet<String>,
+    ) -> crate::Result<String> {
+        let target = self
+            .packages
+            .iter()
+            .find(|(import_name, loaded)| *import_name == package || loaded.name == package)
+            .map(|(import_name, _)| import_name)
+            .ok_or_else(|| -> crate::Error {
+                crate::PackageError::manifest_error(format!(
+                    "package '{package}' is not in the dependency tree of {}",
+                    self.root().name
+                ))
+                .unlocated()
+                .build()
+                .into()
+            })?;
+
+        Ok(render(
+            target,
+            |name| {
+                self.packages
+                    .iter()
+                    .filter(|(_, loaded)| loaded.dependencies.contains(name))
+                    .map(|(import_name, _)| import_name.clone())
+                    .collect()
+            },
+            |name| self.label(name, duplicates),
+        ))
+    }
+
+    /// The namespaces each namespace of the root imports, transitively across packages.
+    pub fn render_namespaces(&self) -> String {
+        let namespaces: BTreeMap<&str, (&str, &BTreeSet<String>)> = self
+            .packages
+            .iter()
+            .flat_map(|(import_name, package)| {
+                package
+                    .namespaces
+                    .iter()
+                    .map(move |(namespace, imported)| {
+                        (namespace.as_str(), (import_name.as_str(), imported))
+                    })
+            })
+            .collect();
+
+        let trees = self
+            .root()
+            .namespaces
+            .keys()
+            .map(|root| {
+                render(
+                    root,
+                    |namespace| {
+                        namespaces
+                            .get(namespace)
+                            .map(|(_, imported)| imported.iter().cloned().collect())
+                            .unwrap_or_default()
+                    },
+                    |namespace| {
+                        match namespaces.get(namespace) {
+                            Some((package, _)) if *package != self.root => {
+                                let package = &self.packages[*package];
+                                format!("{namespace} ({} v{})", package.name, package.version)
+                            },
+                            _ => namespace.to_string(),
+                        }
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        trees.join("\n")
+    }
+
+    fn label(
+        &self,
+        import_name: &str,
+        duplicates: &BTreeSet<String>,
+    ) -> String {
+        let package = &self.packages[import_name];
+        let mut label = format!("{} v{}", package.name, package.version);
+        if duplicates.contains(&package.name) {
+            label.push_str(" (duplicate)");
+        }
+        label
+    }
+}
+
+/// Draws the tree below `root`, expanding each node once.
+fn render(
+    root: &str,
+    children: impl Fn(&str) -> Vec<String>,
+    label: impl Fn(&str) -> String,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", label(root));
+
+    let mut expanded = HashSet::from([root.to_string()]);
+    render_children(&mut out, root, "", &children, &label, &mut expanded);
+    out
+}
+
+fn render_children(
+    out: &mut String,
+    node: &str,
+    prefix: &str,
+    children: &impl Fn(&str) -> Vec<String>,
+    label: &impl Fn(&str) -> String,
+    expanded: &mut HashSet<String>,
+) {
+    let nodes = children(node);
+    for (i, child) in nodes.iter().enumerate() {
+        let (branch, indent) = if i + 1 == nodes.len() {
+            (LAST_BRANCH, LAST_INDENT)
+        } else {
+            (BRANCH, INDENT)
+        };
+
+        let _ = write!(out, "{prefix}{branch}{}", label(child));
+        if !expanded.insert(child.clone()) {
+            if !children(child).is_empty() {
+                out.push_str(" (*)");
+            }
+            out.push('\n');
+            continue;
+        }
+        out.push('\n');
+
+        render_children(
+            out,
+            child,
+            &format!("{prefix}{indent}"),
+            children,
+            label,
+            expanded,
+        );
+    }
+}
@@ -1,9 +1,47 @@
-/// Normalize a package name from import format (abc_foo) to manifest format (abc-foo)
+/// Separates the scope from the name in the import name of a scoped package, e.g.
+/// `acme__billing` for `@acme/billing`. Package names never contain consecutive hyphens, so
+/// this cannot be mistaken for part of a name.
+const SCOPE_SEPARATOR: &str = "__";
+
+/// Normalize a package name from import format (abc_foo, acme__abc_foo) to manifest format
+/// (abc-foo, @acme/abc-foo)
 pub fn normalize_import_to_package_name(import_name: &str) -> String {
-    import_name.replace('_', "-")
+    match import_name.split_once(SCOPE_SEPARATOR) {
+        Some((scope, name)) => {
+            format!("@{}/{}", scope.replace('_', "-"), name.replace('_', "-"))
+        },
+        None => import_name.replace('_', "-"),
+    }
 }
 
-/// Normalize a package name from manifest format (abc-foo) to import format (abc_foo)
+/// Normalize a package name from manifest format (abc-foo, @acme/abc-foo) to import format
+/// (abc_foo, acme__abc_foo)
 pub fn normalize_package_to_import_name(package_name: &str) -> String {
-    package_name.replace('-', "_")
+    match kintsu_manifests::package::package_scope(package_name) {
+        Some(scope) => {
+            let name = &package_name[scope.len() + 2..];
+            format!(
+                "{}{SCOPE_SEPARATOR}{}",
+                scope.replace('-', "_"),
+                name.replace('-', "_")
+            )
+        },
+        None => package_name.replace('-', "_"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test_case::test_case("abc-foo", "abc_foo"; "unscoped")]
+    #[test_case::test_case("@acme/abc-foo", "acme__abc_foo"; "scoped")]
+    #[test_case::test_case("@acme-corp/abc", "acme_corp__abc"; "hyphenated scope")]
+    fn import_names_round_trip(
+        package_name: &str,
+        import_name: &str,
+    ) {
+        assert_eq!(normalize_package_to_import_name(package_name), import_name);
+        assert_eq!(normalize_import_to_package_name(import_name), package_name);
+    }
 }
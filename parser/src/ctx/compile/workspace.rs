@@ -0,0 +1,218 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use kintsu_cli_core::{ProgressEvent, ProgressManager};
+use kintsu_fs::FileSystem;
+use kintsu_manifests::{
+    InvalidManifest,
+    config::NewForNamed,
+    lock::{WorkspaceLockfile, WorkspaceLockfiles},
+    package::PackageManifests,
+    version::parse_version,
+    workspace::WorkspaceManifests,
+};
+
+use super::{
+    CompileCtx,
+    resolver::{PackageResolver, WorkspaceMember, WorkspaceResolver},
+};
+
+/// Every member of a workspace, compiled against one shared lockfile. Dependencies between
+/// members resolve to the members' own sources instead of the registry.
+pub struct WorkspaceCtx {
+    pub root_path: PathBuf,
+    pub root_fs: Arc<dyn FileSystem>,
+    pub manifest: WorkspaceManifests,
+    /// Compiled members, sorted by package name
+    pub members: Vec<CompileCtx>,
+
+    lockfile: Option<WorkspaceLockfile>,
+}
+
+impl CompileCtx {
+    /// Compiles every member of the workspace in `root_path` on the local filesystem.
+    pub async fn from_workspace(
+        root_path: impl AsRef<Path>,
+        show_progress: bool,
+    ) -> crate::Result<WorkspaceCtx> {
+        let fs: Arc<dyn FileSystem> = Arc::new(kintsu_fs::physical::Physical);
+        WorkspaceCtx::with_fs_and_config(
+            fs.clone(),
+            Self::default_resolver(fs),
+            root_path,
+            num_cpus::get(),
+            show_progress,
+        )
+        .await
+    }
+}
+
+impl WorkspaceCtx {
+    pub async fn with_fs_and_config(
+        fs: Arc<dyn FileSystem>,
+        resolver: Arc<dyn PackageResolver>,
+        root_path: impl AsRef<Path>,
+        max_concurrent_tasks: usize,
+        show_progress: bool,
+    ) -> crate::Result<Self> {
+        let root_path = root_path.as_ref().to_path_buf();
+        let manifest = WorkspaceManifests::new(fs.as_ref(), &root_path)?;
+
+        let member_dirs = manifest.member_dirs(fs.as_ref(), &root_path)?;
+        if member_dirs.is_empty() {
+            return Err(crate::PackageError::manifest_error(format!(
+                "workspace in {} has no members",
+                root_path.display()
+            ))
+            .unlocated()
+            .build()
+            .into());
+        }
+
+        let mut members: BTreeMap<String, WorkspaceMember> = BTreeMap::new();
+        for dir in member_dirs {
+            let package = PackageManifests::new(fs.as_ref(), &dir)?;
+            let name = package.package().name.clone();
+            let member = WorkspaceMember {
+                version: parse_version(&package.package().version.to_string())?,
+                root: dir,
+            };
+
+            if let Some(existing) = members.insert(name.clone(), member.clone()) {
+                return Err(kintsu_manifests::Error::from(
+                    InvalidManifest::DuplicateWorkspaceMember {
+                        name,
+                        first: existing.root,
+                        second: member.root,
+                    },
+                )
+                .into());
+            }
+        }
+
+        let lockfile = WorkspaceLockfiles::new_for_opt(fs.as_ref(), &root_path)?.map(|lockfiles| {
+            match lockfiles {
+                WorkspaceLockfiles::V1(lockfile) => lockfile,
+            }
+        });
+
+        let resolver: Arc<dyn PackageResolver> = Arc::new(WorkspaceResolver::new(
+            fs.clone(),
+            resolver,
+            members.clone(),
+        ));
+
+        let progress = ProgressManager::new(show_progress);
+        progress.event(&ProgressEvent::WorkspaceStarted {
+            members: members.len() as u64,
+        });
+
+        let mut compiled = Vec::with_capacity(members.len());
+        for (name, member) in &members {
+            tracing::info!("compiling workspace member {name}");
+            compiled.push(
+                CompileCtx::with_lockfile(
+                    fs.clone(),
+                    resolver.clone(),
+                    &member.root,
+                    lockfile
+                        .as_ref()
+                        .and_then(|lockfile| lockfile.member(name)),
+                    max_concurrent_tasks,
+                    progress.clone(),
+                )
+                .await?,
+            );
+        }
+        progress.finish();
+
+        Ok(Self {
+            root_path,
+            root_fs: fs,
+            manifest,
+            members: compiled,
+            lockfile,
+        })
+    }
+
+    pub fn member(
+        &self,
+        name: &str,
+    ) -> Option<&CompileCtx> {
+        self.members
+            .iter()
+            .find(|member| member.root.package.package().name == name)
+    }
+
+    pub async fn should_write_lockfile(&self) -> bool {
+        if self.lockfile.is_none() {
+            return true;
+        }
+        for member in &self.members {
+            if member.should_write_lockfile().await {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// The shared lockfile of every member, without writing it.
+    pub async fn build_lockfile(&self) -> crate::Result<WorkspaceLockfile> {
+        let mut lockfile = WorkspaceLockfile::default();
+        for member in &self.members {
+            let member_dir = member
+                .root_path
+                .strip_prefix(&self.root_path)
+                .unwrap_or(&member.root_path)
+                .to_path_buf();
+            lockfile.add_member(member_dir, member.build_lockfile().await?);
+        }
+        Ok(lockfile)
+    }
+
+    /// Denies warnings in every member, as [`CompileCtx::set_deny_warnings`] does.
+    pub fn set_deny_warnings(
+        &mut self,
+        deny: bool,
+    ) {
+        for member in &mut self.members {
+            member.set_deny_warnings(deny);
+        }
+    }
+
+    /// Lints every member, then writes the workspace lockfile if any member's dependencies
+    /// changed. When warnings are denied, the warnings of each member's compilation are
+    /// checked against that member's `allow_warnings`.
+    pub async fn finalize(&self) -> crate::Result<()> {
+        for member in &self.members {
+            member.lint().await?;
+        }
+
+        for member in &self.members {
+            member.check_warnings().await?;
+        }
+
+        if self.should_write_lockfile().await {
+            let lockfiles = WorkspaceLockfiles::V1(self.build_lockfile().await?);
+            let content = NewForNamed::dump::<PathBuf>(&lockfiles)?;
+            let path = <WorkspaceLockfiles as NewForNamed>::path(&self.root_path);
+            self.root_fs
+                .write(&path, content.into_bytes())
+                .await?;
+
+            tracing::debug!("Workspace lockfile written to {}", path.display());
+        } else {
+            tracing::debug!("Workspace lockfile unchanged, skipping write");
+        }
+
+        #[cfg(feature = "profiling")]
+        if let Some(capture) = super::trace::SpanCapture::active() {
+            capture.write(self.root_fs.as_ref()).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    ToTokens,
+    SpannedToken, ToTokens,
     ast::{
         err::ErrorType,
         one_of::OneOf,
@@ -64,11 +64,14 @@ impl TypeExtractor {
 
                                 Some(qual)
                             },
+                            // globs are lower priority than local items, see below
+                            RefOrItemContext::Glob(_) => None,
                         }
                     })
                     .collect::<Vec<_>>();
 
                 candidates.push(true_local);
+                candidates.extend(Self::glob_candidates(name, ns));
                 candidates
             },
             PathOrIdent::Path(path) => {
@@ -94,7 +97,7 @@ impl TypeExtractor {
                                     None
                                 }
                             },
-                            RefOrItemContext::Ref(r) => {
+                            RefOrItemContext::Ref(r) | RefOrItemContext::Glob(r) => {
                                 let adjusted_seg = if seg.first() == Some(&r.package) {
                                     &seg[1..]
                                 } else {
@@ -115,6 +118,28 @@ impl TypeExtractor {
         }
     }
 
+    /// Candidates contributed by glob imports (`use pkg::ns::*`) for a bare identifier.
+    pub(crate) fn glob_candidates(
+        name: &SpannedToken![ident],
+        ns: &NamespaceCtx,
+    ) -> Vec<NamedItemContext> {
+        ns.imports
+            .iter()
+            .filter_map(|it| {
+                match &it.value {
+                    RefOrItemContext::Glob(r) => {
+                        let qual = r.item(name.clone());
+                        tracing::trace! {
+                            candidate = qual.display(), "candidate from glob import"
+                        }
+                        Some(qual)
+                    },
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
     pub fn extract_from_namespace(
         ns_ctx: &NamespaceCtx,
         ref_context: &RefContext,
@@ -138,8 +163,8 @@ impl TypeExtractor {
                 NamespaceChild::Operation(op_def) => {
                     Self::extract_from_operation(&op_def.def.value, ref_context, ns_ctx)
                 },
-                NamespaceChild::Enum(_) => {
-                    // Enums have no type dependencies (just literals)
+                NamespaceChild::Enum(_) | NamespaceChild::Const(_) => {
+                    // Enums and constants have no type dependencies (just literals)
                     Vec::new()
                 },
                 NamespaceChild::Namespace(_) => {
@@ -357,18 +382,12 @@ impl TypeExtractor {
                 ));
             },
             Type::Array { ty } => {
-                let inner_ty = match &ty.value {
-                    crate::ast::array::Array::Unsized { ty, .. } => &ty.value,
-                    crate::ast::array::Array::Sized { ty, .. } => &ty.value,
+                // a fixed-size array always holds elements, so it cannot end recursion
+                let (inner_ty, kind) = match &ty.value {
+                    crate::ast::array::Array::Unsized { ty, .. } => (&ty.value, EdgeKind::Array),
+                    crate::ast::array::Array::Sized { ty, .. } => (&ty.value, current_kind),
                 };
-                Self::extract_from_type(
-                    inner_ty,
-                    deps,
-                    field_path,
-                    EdgeKind::Array,
-                    ref_context,
-                    ns_ctx,
-                );
+                Self::extract_from_type(inner_ty, deps, field_path, kind, ref_context, ns_ctx);
             },
             Type::Paren { ty, .. } => {
                 Self::extract_from_type(
@@ -730,8 +749,7 @@ mod tests {
         assert_eq!(graph.type_names().len(), 2);
 
         // Cycle should be detected as terminating
-        let cycle = vec![test_ctx("A"), test_ctx("B")];
-        assert!(graph.has_terminating_edge(&cycle));
+        assert!(graph.required_cycles().is_empty());
     }
 
     #[test]
@@ -759,8 +777,14 @@ mod tests {
         );
 
         // Cycle should NOT have terminating edges
-        let cycle = vec![test_ctx("A"), test_ctx("B")];
-        assert!(!graph.has_terminating_edge(&cycle));
+        assert_eq!(
+            graph.required_cycles()[0].chain(),
+            vec![
+                test_ctx("A").display(),
+                test_ctx("B").display(),
+                test_ctx("A").display()
+            ]
+        );
     }
 
     #[test]
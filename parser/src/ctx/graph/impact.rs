@@ -0,0 +1,169 @@
+//! Which items a change to a type reaches, built from the [`TypeDependencyGraph`] of every
+//! loaded namespace.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt,
+};
+
+use serde::Serialize;
+
+use crate::{
+    ToTokens,
+    ctx::{NamespaceCtx, SchemaCtx, common::NamespaceChild, paths::NamedItemContext},
+};
+
+use super::{TypeExtractor, types::TypeDependencyGraph};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageKind {
+    Struct,
+    OneOf,
+    Error,
+    Alias,
+    Operation,
+}
+
+impl fmt::Display for UsageKind {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(match self {
+            Self::Struct => "struct",
+            Self::OneOf => "oneof",
+            Self::Error => "error",
+            Self::Alias => "type",
+            Self::Operation => "operation",
+        })
+    }
+}
+
+/// A place where a type is referenced.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct TypeUsage {
+    /// Qualified name of the referencing item, e.g. `pkg::foo::Wrapper`
+    pub item: String,
+    pub kind: UsageKind,
+    /// Field, variant or operation position holding the reference, e.g. `data`,
+    /// `Created.id` or `param:id`. Empty for aliases.
+    pub path: Vec<String>,
+    /// The type referenced here, which is either the analysed type or depends on it
+    pub uses: String,
+}
+
+impl fmt::Display for TypeUsage {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.item)?;
+        if !self.path.is_empty() {
+            write!(f, ".{}", self.path.join("."))?;
+        }
+        write!(f, " -> {}", self.uses)
+    }
+}
+
+/// Everything that transitively depends on a type, i.e. what a change to it can break.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Impact {
+    pub target: String,
+    /// Ordered by referencing item, then position
+    pub usages: Vec<TypeUsage>,
+}
+
+impl Impact {
+    /// Qualified names of the items referencing the type, directly or not.
+    pub fn items(&self) -> BTreeSet<&str> {
+        self.usages
+            .iter()
+            .map(|usage| usage.item.as_str())
+            .collect()
+    }
+
+    /// Operations taking or returning the type, directly or not.
+    pub fn operations(&self) -> BTreeSet<&str> {
+        self.usages
+            .iter()
+            .filter(|usage| usage.kind == UsageKind::Operation)
+            .map(|usage| usage.item.as_str())
+            .collect()
+    }
+
+    pub(crate) async fn analyse<'a>(
+        schemas: impl IntoIterator<Item = &'a SchemaCtx>,
+        type_path: &str,
+    ) -> crate::Result<Self> {
+        let mut graph = TypeDependencyGraph::new();
+        let mut kinds = BTreeMap::new();
+        for schema in schemas {
+            for ns in schema.namespaces.values() {
+                collect(&*ns.lock().await, &mut graph, &mut kinds);
+            }
+        }
+
+        let target = graph
+            .type_names()
+            .into_iter()
+            .find(|name| name.display() == type_path)
+            .ok_or_else(|| -> crate::Error {
+                crate::ResolutionError::undefined_type(type_path)
+                    .unlocated()
+                    .build()
+                    .into()
+            })?;
+
+        let mut usages = BTreeSet::new();
+        let mut visited = BTreeSet::from([target.clone()]);
+        let mut queue = VecDeque::from([target]);
+        while let Some(current) = queue.pop_front() {
+            for (from, dep) in graph.dependents(&current) {
+                let Some(kind) = kinds.get(&from) else {
+                    continue;
+                };
+
+                usages.insert(TypeUsage {
+                    item: from.display(),
+                    kind: *kind,
+                    path: dep.field_path.clone(),
+                    uses: current.display(),
+                });
+                if visited.insert(from.clone()) {
+                    queue.push_back(from);
+                }
+            }
+        }
+
+        Ok(Self {
+            target: type_path.to_string(),
+            usages: usages.into_iter().collect(),
+        })
+    }
+}
+
+/// Adds the items of `ns` and its nested namespaces to `graph`, recording what kind each is.
+fn collect(
+    ns: &NamespaceCtx,
+    graph: &mut TypeDependencyGraph,
+    kinds: &mut BTreeMap<NamedItemContext, UsageKind>,
+) {
+    graph.extend(TypeExtractor::extract_from_namespace(ns, &ns.ctx));
+
+    for (name, child) in &ns.children {
+        let kind = match &child.value {
+            NamespaceChild::Namespace(nested) => {
+                collect(nested, graph, kinds);
+                continue;
+            },
+            NamespaceChild::Enum(_) | NamespaceChild::Const(_) => continue,
+            NamespaceChild::Struct(_) => UsageKind::Struct,
+            NamespaceChild::OneOf(_) => UsageKind::OneOf,
+            NamespaceChild::Error(_) => UsageKind::Error,
+            NamespaceChild::Type(_) => UsageKind::Alias,
+            NamespaceChild::Operation(_) => UsageKind::Operation,
+        };
+        kinds.insert(name.clone(), kind);
+    }
+}
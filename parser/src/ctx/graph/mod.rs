@@ -1,4 +1,5 @@
 pub mod extract;
+pub mod impact;
 pub mod schemas;
 pub mod types;
 
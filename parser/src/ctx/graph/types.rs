@@ -1,5 +1,5 @@
-use crate::ctx::paths::NamedItemContext;
-use std::collections::{BTreeMap, HashSet};
+use crate::{ToTokens, ctx::paths::NamedItemContext};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EdgeKind {
@@ -9,7 +9,8 @@ pub enum EdgeKind {
     /// terminates recursion
     Optional,
 
-    /// terminates recursion (empty array)
+    /// terminates recursion (empty array). Fixed-size arrays inherit the kind of their
+    /// position instead, since they always hold elements.
     Array,
 }
 
@@ -53,6 +54,64 @@ impl TypeDependency {
     }
 }
 
+/// One step of a [`TypeCycle`]: `from` holds `to` through the field at `field_path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleEdge {
+    pub from: NamedItemContext,
+    pub to: NamedItemContext,
+    pub field_path: Vec<String>,
+}
+
+/// A closed chain of [`EdgeKind::Required`] dependencies. None of the types on it has a
+/// finite value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeCycle {
+    pub edges: Vec<CycleEdge>,
+}
+
+impl TypeCycle {
+    /// Types on the cycle in dependency order, ending with the first one again.
+    pub fn chain(&self) -> Vec<String> {
+        self.edges
+            .iter()
+            .map(|edge| edge.from.display())
+            .chain(
+                self.edges
+                    .first()
+                    .map(|edge| edge.from.display()),
+            )
+            .collect()
+    }
+
+    /// Like [`Self::chain`], with the field each type holds the next one through, e.g.
+    /// `pkg::A.b -> pkg::B.a -> pkg::A`. Aliases refer to their target directly and have
+    /// no field.
+    pub fn path(&self) -> Vec<String> {
+        self.edges
+            .iter()
+            .map(|edge| {
+                if edge.field_path.is_empty() {
+                    edge.from.display()
+                } else {
+                    format!("{}.{}", edge.from.display(), edge.field_path.join("."))
+                }
+            })
+            .chain(
+                self.edges
+                    .first()
+                    .map(|edge| edge.from.display()),
+            )
+            .collect()
+    }
+
+    /// True when every step is an alias pointing at the next one.
+    pub fn is_alias_chain(&self) -> bool {
+        self.edges
+            .iter()
+            .all(|edge| edge.field_path.is_empty())
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TypeDependencyGraph {
     nodes: BTreeMap<NamedItemContext, Vec<TypeDependency>>,
@@ -106,6 +165,7 @@ impl TypeDependencyGraph {
             .unwrap_or_default()
     }
 
+    #[allow(dead_code)]
     pub fn all_successors(
         &self,
         type_name: &NamedItemContext,
@@ -120,28 +180,127 @@ impl TypeDependencyGraph {
         self.successors(type_name, true)
     }
 
-    pub fn has_terminating_edge(
+    /// Required edges leaving `type_name`, keyed by the definition-site target.
+    fn required_edges(
         &self,
-        cycle: &[NamedItemContext],
-    ) -> bool {
-        let cycle_set: HashSet<_> = cycle.iter().cloned().collect();
-
-        for type_name in cycle {
-            if let Some(dependencies) = self.nodes.get(type_name) {
-                for dep in dependencies {
-                    if dep
-                        .target_candidates
-                        .iter()
-                        .any(|candidate| cycle_set.contains(candidate))
-                        && dep.kind.is_terminating()
-                    {
-                        return true;
+        type_name: &NamedItemContext,
+    ) -> Vec<(NamedItemContext, &TypeDependency)> {
+        self.nodes
+            .get(type_name)
+            .map(|node| {
+                node.iter()
+                    .filter(|dep| !dep.kind.is_terminating())
+                    .flat_map(|dep| {
+                        dep.target_candidates
+                            .iter()
+                            .filter_map(move |candidate| {
+                                self.nodes
+                                    .get_key_value(candidate)
+                                    .map(|(def_key, _)| (def_key.clone(), dep))
+                            })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Finds every cycle that cannot terminate, i.e. one made only of required edges.
+    ///
+    /// Cycles through optional fields, oneof/error variants or unsized arrays are allowed.
+    /// One cycle is reported per strongly connected component: the shortest one through
+    /// the lexicographically smallest type, so the output is stable across runs.
+    pub fn required_cycles(&self) -> Vec<TypeCycle> {
+        use pathfinding::prelude::strongly_connected_components;
+
+        let type_names = self.type_names();
+        let components =
+            strongly_connected_components(&type_names, |node| self.required_successors(node));
+
+        let mut cycles: Vec<_> = components
+            .into_iter()
+            .filter_map(|component| {
+                let members: HashSet<_> = component.iter().cloned().collect();
+                let start = component
+                    .iter()
+                    .min_by_key(|node| node.display())?;
+                self.shortest_cycle(start, &members)
+            })
+            .collect();
+
+        cycles.sort_by_key(|cycle| cycle.chain());
+        cycles
+    }
+
+    /// Breadth-first search for the shortest required path from `start` back to itself,
+    /// staying within `members`. Returns `None` for a single type without a self edge.
+    fn shortest_cycle(
+        &self,
+        start: &NamedItemContext,
+        members: &HashSet<NamedItemContext>,
+    ) -> Option<TypeCycle> {
+        let mut parents: HashMap<NamedItemContext, CycleEdge> = HashMap::new();
+        let mut queue = VecDeque::from([start.clone()]);
+
+        while let Some(current) = queue.pop_front() {
+            for (target, dep) in self.required_edges(&current) {
+                if !members.contains(&target) {
+                    continue;
+                }
+
+                let edge = CycleEdge {
+                    from: current.clone(),
+                    to: target.clone(),
+                    field_path: dep.field_path.clone(),
+                };
+
+                if &target == start {
+                    let mut edges = vec![edge];
+                    let mut node = current.clone();
+                    while &node != start {
+                        let parent = parents[&node].clone();
+                        node = parent.from.clone();
+                        edges.push(parent);
                     }
+                    edges.reverse();
+                    return Some(TypeCycle { edges });
+                }
+
+                if !parents.contains_key(&target) {
+                    parents.insert(target.clone(), edge);
+                    queue.push_back(target);
                 }
             }
         }
 
-        false
+        None
+    }
+
+    /// Adds the types of `other`, e.g. another namespace, so references between them resolve.
+    pub fn extend(
+        &mut self,
+        other: TypeDependencyGraph,
+    ) {
+        self.nodes.extend(other.nodes);
+    }
+
+    /// Every edge pointing at `type_name`, of any kind, with the type it leaves from.
+    pub fn dependents(
+        &self,
+        type_name: &NamedItemContext,
+    ) -> Vec<(NamedItemContext, &TypeDependency)> {
+        self.nodes
+            .iter()
+            .flat_map(|(from, deps)| {
+                deps.iter()
+                    .filter(|dep| {
+                        dep.target_candidates
+                            .iter()
+                            .filter_map(|candidate| self.nodes.get_key_value(candidate))
+                            .any(|(def_key, _)| def_key == type_name)
+                    })
+                    .map(move |dep| (from.clone(), dep))
+            })
+            .collect()
     }
 
     #[allow(dead_code)]
@@ -201,8 +360,7 @@ mod tests {
             )],
         );
 
-        let cycle = vec![test_ctx("A"), test_ctx("B")];
-        assert!(graph.has_terminating_edge(&cycle));
+        assert!(graph.required_cycles().is_empty());
     }
 
     #[test]
@@ -229,8 +387,166 @@ mod tests {
             )],
         );
 
-        let cycle = vec![test_ctx("A"), test_ctx("B")];
-        assert!(!graph.has_terminating_edge(&cycle));
+        assert_eq!(
+            graph.required_cycles()[0].chain(),
+            vec![
+                test_ctx("A").display(),
+                test_ctx("B").display(),
+                test_ctx("A").display()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_self_cycle() {
+        let mut graph = TypeDependencyGraph::new();
+
+        // A has required field of type A
+        graph.add_type(
+            test_ctx("A"),
+            vec![TypeDependency::with_target(
+                test_ctx("A"),
+                EdgeKind::Required,
+                vec!["next".to_string()],
+            )],
+        );
+
+        let cycles = graph.required_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(
+            cycles[0].path(),
+            vec![
+                format!("{}.next", test_ctx("A").display()),
+                test_ctx("A").display()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optional_edge_does_not_break_required_cycle() {
+        let mut graph = TypeDependencyGraph::new();
+
+        // A has required and optional fields of type B, B has required field of type A
+        graph.add_type(
+            test_ctx("A"),
+            vec![
+                TypeDependency::with_target(
+                    test_ctx("B"),
+                    EdgeKind::Required,
+                    vec!["b".to_string()],
+                ),
+                TypeDependency::with_target(
+                    test_ctx("B"),
+                    EdgeKind::Optional,
+                    vec!["maybe_b".to_string()],
+                ),
+            ],
+        );
+        graph.add_type(
+            test_ctx("B"),
+            vec![TypeDependency::with_target(
+                test_ctx("A"),
+                EdgeKind::Required,
+                vec!["a".to_string()],
+            )],
+        );
+
+        let cycles = graph.required_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(
+            cycles[0].path(),
+            vec![
+                format!("{}.b", test_ctx("A").display()),
+                format!("{}.a", test_ctx("B").display()),
+                test_ctx("A").display()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shortest_cycle_reported() {
+        let mut graph = TypeDependencyGraph::new();
+
+        // A -> B -> C -> A and A -> C -> A, all required
+        graph.add_type(
+            test_ctx("A"),
+            vec![
+                TypeDependency::with_target(
+                    test_ctx("B"),
+                    EdgeKind::Required,
+                    vec!["b".to_string()],
+                ),
+                TypeDependency::with_target(
+                    test_ctx("C"),
+                    EdgeKind::Required,
+                    vec!["c".to_string()],
+                ),
+            ],
+        );
+        graph.add_type(
+            test_ctx("B"),
+            vec![TypeDependency::with_target(
+                test_ctx("C"),
+                EdgeKind::Required,
+                vec!["c".to_string()],
+            )],
+        );
+        graph.add_type(
+            test_ctx("C"),
+            vec![TypeDependency::with_target(
+                test_ctx("A"),
+                EdgeKind::Required,
+                vec!["a".to_string()],
+            )],
+        );
+
+        let cycles = graph.required_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(
+            cycles[0].chain(),
+            vec![
+                test_ctx("A").display(),
+                test_ctx("C").display(),
+                test_ctx("A").display()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dependents_of_any_kind() {
+        let mut graph = TypeDependencyGraph::new();
+
+        graph.add_type(
+            test_ctx("A"),
+            vec![TypeDependency::with_target(
+                test_ctx("C"),
+                EdgeKind::Required,
+                vec!["c".to_string()],
+            )],
+        );
+        graph.add_type(
+            test_ctx("B"),
+            vec![TypeDependency::with_target(
+                test_ctx("C"),
+                EdgeKind::Optional,
+                vec!["maybe_c".to_string()],
+            )],
+        );
+        graph.add_type(test_ctx("C"), vec![]);
+
+        let dependents: Vec<_> = graph
+            .dependents(&test_ctx("C"))
+            .into_iter()
+            .map(|(from, dep)| (from, dep.field_path.clone()))
+            .collect();
+        assert_eq!(
+            dependents,
+            vec![
+                (test_ctx("A"), vec!["c".to_string()]),
+                (test_ctx("B"), vec!["maybe_c".to_string()]),
+            ]
+        );
+        assert!(graph.dependents(&test_ctx("A")).is_empty());
     }
 
     #[test]
@@ -6,11 +6,17 @@ mod namespace;
 mod paths;
 pub mod registry;
 mod schema;
+mod source_map;
 
 pub mod resolve;
 
 pub use common::*;
-pub use compile::{CompilationProgress, CompileCtx};
+pub use compile::{
+    AddedDependency, BuildReport, BumpOptions, CompilationProgress, CompileCtx, CompileOutcome,
+    DependencySource, DependencyTree, VersionBump, WorkspaceCtx,
+};
+pub use graph::impact::{Impact, TypeUsage, UsageKind};
 pub use namespace::NamespaceCtx;
 pub use paths::*;
 pub use schema::SchemaCtx;
+pub use source_map::{Origin, OriginKind, SourceMap};
@@ -53,6 +53,12 @@ pub struct NamespaceCtx {
 
     /// Resolved type aliases (e.g., UnionOr resolved to Struct)
     pub resolved_aliases: BTreeMap<String, Spanned<crate::ast::ty::Type>>,
+
+    /// Evaluated `const` declarations, keyed by name
+    pub resolved_constants: BTreeMap<String, crate::ast::constant::ConstLiteral>,
+
+    /// Where the items generated in this namespace were generated from
+    pub source_map: super::SourceMap,
 }
 
 impl NamespaceCtx {
@@ -87,6 +93,8 @@ impl NamespaceCtx {
             resolved_versions: Default::default(),
             resolved_errors: Default::default(),
             resolved_aliases: Default::default(),
+            resolved_constants: Default::default(),
+            source_map: Default::default(),
         }
     }
 
@@ -109,13 +117,19 @@ impl NamespaceCtx {
         Self::from_ast_stream(ctx, ast, path.to_path_buf(), source, registry)
     }
 
+    /// Loads a namespace from `paths`.
+    ///
+    /// Files which fail to lex or parse are skipped and their errors pushed to `recovered`,
+    /// so the remaining files still contribute to the namespace. Returns `None` when every
+    /// file was skipped.
     pub async fn load_files(
         ref_ctx: RefContext,
         fs: &dyn kintsu_fs::FileSystem,
         paths: &[PathBuf],
         required_namespace: Option<&SpannedToken![ident]>,
         registry: TypeRegistry,
-    ) -> crate::Result<Self> {
+        recovered: &mut Vec<crate::CompilerError>,
+    ) -> crate::Result<Option<Self>> {
         if paths.is_empty() {
             return Err(crate::Error::Compiler(
                 crate::FilesystemError::empty_file_list()
@@ -145,11 +159,19 @@ impl NamespaceCtx {
             let (path, source_str) = result?;
             let source = Arc::new(source_str);
 
-            let mut tt = crate::tokens::tokenize(&source).map_err(|e: LexingError| {
-                crate::Error::from(e).with_source(path.clone(), Arc::clone(&source))
-            })?;
-
-            let ast = AstStream::from_tokens_with(&path, &mut tt)?;
+            let parsed = match crate::tokens::tokenize(&source) {
+                Ok(mut tt) => AstStream::from_tokens_with(&path, &mut tt),
+                Err(e) => Err(crate::Error::from(e).with_source(path.clone(), Arc::clone(&source))),
+            };
+
+            let ast = match parsed {
+                Ok(ast) => ast,
+                Err(e) => {
+                    tracing::debug!(path = %path.display(), "skipping file with syntax errors");
+                    recovered.push(e.to_compiler_error());
+                    continue;
+                },
+            };
 
             if let Some(ctx) = &mut namespace_ctx {
                 ctx.merge_ast_stream(ast, path, source, &mut found_namespace_decl)?;
@@ -186,13 +208,7 @@ impl NamespaceCtx {
             }
         }
 
-        namespace_ctx.ok_or_else(|| {
-            crate::Error::Compiler(
-                crate::InternalError::failed_namespace_ctx()
-                    .unlocated()
-                    .build(),
-            )
-        })
+        Ok(namespace_ctx)
     }
 
     fn handle_use(
@@ -465,6 +481,34 @@ impl NamespaceCtx {
                         "operation",
                     )?;
                 },
+                Items::Const(def) => {
+                    let name = def.def.name.clone();
+                    let ns_name = namespace
+                        .as_ref()
+                        .ok_or_else(|| {
+                            crate::Error::Compiler(
+                                crate::NamespaceError::not_declared()
+                                    .at_node(&def.def)
+                                    .build()
+                                    .with_source_arc(path.clone(), Arc::clone(&source)),
+                            )
+                        })?
+                        .value
+                        .def
+                        .name
+                        .clone();
+
+                    Self::insert_typed_child(
+                        &ctx,
+                        &mut children,
+                        ns_name,
+                        &name,
+                        || NamespaceChild::Const(def),
+                        &path,
+                        &source,
+                        "const",
+                    )?;
+                },
             }
         }
 
@@ -491,6 +535,8 @@ impl NamespaceCtx {
             resolved_versions: BTreeMap::new(),
             resolved_errors: BTreeMap::new(),
             resolved_aliases: BTreeMap::new(),
+            resolved_constants: BTreeMap::new(),
+            source_map: super::SourceMap::new(),
         })
     }
 
@@ -644,6 +690,19 @@ impl NamespaceCtx {
                         "operation",
                     )?;
                 },
+                Items::Const(def) => {
+                    let name = def.def.name.clone();
+                    Self::insert_typed_child(
+                        &self.ctx,
+                        &mut self.children,
+                        self.namespace.value.def.name.clone(),
+                        &name,
+                        || NamespaceChild::Const(def),
+                        &path,
+                        &source,
+                        "const",
+                    )?;
+                },
             }
         }
 
@@ -780,6 +839,20 @@ impl NamespaceCtx {
                     ItemMetaItem::Rename(_) => {
                         // Rename only valid on variants, not at namespace level - skip
                     },
+                    ItemMetaItem::Constraint(_)
+                    | ItemMetaItem::Http(_)
+                    | ItemMetaItem::Graphql(_)
+                    | ItemMetaItem::Contiguous(_)
+                    | ItemMetaItem::Example(_) => {
+                        return Err(crate::Error::Compiler(
+                            crate::MetadataError::misplaced_attribute(
+                                meta_item.name(),
+                                "a namespace",
+                            )
+                            .at(meta_item.span())
+                            .build(),
+                        ));
+                    },
                 }
             }
         }
@@ -824,6 +897,29 @@ impl NamespaceCtx {
                 "registering union struct"
             );
 
+            // a union merged from a type alias replaces the alias, anything else is declared
+            // under the generated name already
+            if let Some((declared, existing)) = self.children.get_key_value(&item_ctx)
+                && !matches!(existing.value, NamespaceChild::Type(_))
+                && let Some(origin) = resolution
+                    .origins
+                    .origin(name.borrow_string())
+            {
+                let mut err = crate::TypeDefError::duplicate_type(item_ctx.display())
+                    .at(origin.error_span())
+                    .build();
+                if existing.source == origin.source {
+                    let declared = declared.name.span();
+                    err = err.with_secondary_label(
+                        crate::Span::new(declared.start, declared.end),
+                        "first declaration here",
+                    );
+                }
+                let err: crate::Error = err.into();
+                let source = self.sources.get(&origin.source).cloned();
+                return Err(err.with_source_arc_if(origin.source.clone(), source));
+            }
+
             let child = NamespaceChild::Struct(struct_def.value.value);
             self.children
                 .insert(item_ctx, child.with_source(struct_def.source));
@@ -832,6 +928,8 @@ impl NamespaceCtx {
         self.resolved_versions = resolution.versions;
         self.resolved_errors = resolution.errors;
         self.resolved_aliases = resolution.resolved_aliases;
+        self.resolved_constants = resolution.constants;
+        self.source_map.extend(resolution.origins);
 
         tracing::debug!(
             total_children = self.children.len(),
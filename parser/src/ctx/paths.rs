@@ -157,6 +157,8 @@ impl ToTokens for NamedItemContext {
 pub enum RefOrItemContext {
     Ref(RefContext),
     Item(NamedItemContext),
+    /// Glob import (`use pkg::ns::*`) exposing every item of the namespace.
+    Glob(RefContext),
 }
 
 impl ToTokens for RefOrItemContext {
@@ -167,6 +169,11 @@ impl ToTokens for RefOrItemContext {
         match self {
             Self::Ref(r) => tt.write(r),
             Self::Item(i) => tt.write(i),
+            Self::Glob(r) => {
+                tt.write(r);
+                tt.token(&crate::tokens::Token::DoubleColon);
+                tt.token(&crate::tokens::Token::Star);
+            },
         }
     }
 }
@@ -192,6 +199,11 @@ impl RefOrItemContext {
         match self {
             RefOrItemContext::Ref(ctx) => ctx,
             RefOrItemContext::Item(item) => &item.context,
+            RefOrItemContext::Glob(ctx) => ctx,
         }
     }
+
+    pub fn is_glob(&self) -> bool {
+        matches!(self, RefOrItemContext::Glob(..))
+    }
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     path::PathBuf,
     sync::{Arc, Mutex},
 };
@@ -166,6 +166,48 @@ impl TypeRegistry {
             })
     }
 
+    /// Returns every registered type a bare identifier could refer to through glob
+    /// imports, when more than one glob provides it and no explicit import or local
+    /// definition shadows them.
+    pub fn glob_conflicts(
+        &self,
+        context: &super::paths::RefContext,
+        reference: &PathOrIdent,
+        ns: &NamespaceCtx,
+    ) -> Vec<NamedItemContext> {
+        let PathOrIdent::Ident(name) = reference else {
+            return Vec::new();
+        };
+
+        let explicit = ns.imports.iter().any(|it| {
+            match &it.value {
+                crate::ctx::RefOrItemContext::Item(item) => &item.name == name,
+                crate::ctx::RefOrItemContext::Ref(r) => {
+                    r.namespace.last() == Some(name.borrow_string())
+                },
+                crate::ctx::RefOrItemContext::Glob(_) => false,
+            }
+        });
+
+        self.with_lock(|inner| {
+            if explicit || inner.contains_key(&context.item(name.clone())) {
+                return Vec::new();
+            }
+
+            let found = super::graph::extract::TypeExtractor::glob_candidates(name, ns)
+                .into_iter()
+                .filter(|candidate| inner.contains_key(candidate))
+                .collect::<BTreeSet<_>>();
+
+            if found.len() > 1 {
+                found.into_iter().collect()
+            } else {
+                Vec::new()
+            }
+        })
+        .unwrap_or_default()
+    }
+
     /// Direct lookup by NamedItemContext
     pub fn get(
         &self,
@@ -186,6 +228,12 @@ impl TypeRegistry {
             .is_some()
     }
 
+    /// Every registered type with its definition.
+    pub fn definitions(&self) -> Vec<FromNamedSource<Spanned<ResolvedType>>> {
+        self.with_lock(|inner| inner.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
     pub fn all_types(&self) -> Vec<(NamedItemContext, PathBuf, Span)> {
         self.with_lock(|inner| {
             inner
@@ -260,6 +308,7 @@ mod tests {
                 &Spanned::call_site(IdentToken::new("Foo".into())),
                 Definition::TypeAlias(Arc::new(TypeDef {
                     meta: vec![],
+                    vis: None,
                     def: crate::tst::basic_smoke("type Foo = i32").unwrap(),
                     end: Spanned::call_site(SemiToken::new()),
                 })),
@@ -313,6 +313,7 @@ impl AliasGraph {
 }
 
 impl TypeResolver {
+    #[tracing::instrument(level = "debug", skip_all)]
     pub(super) async fn resolve_type_aliases(&mut self) -> crate::Result<()> {
         tracing::debug!("resolve_type_aliases: starting phase 3");
 
@@ -0,0 +1,161 @@
+use std::path::Path;
+
+use crate::{
+    SpannedToken,
+    ast::{
+        constant::{ConstLiteral, ConstValue},
+        items::ConstDef,
+        ty::Builtin,
+    },
+    ctx::{NamespaceChild, NamespaceCtx},
+    tokens::ToTokens,
+};
+
+use super::TypeResolver;
+
+impl TypeResolver {
+    pub(super) async fn resolve_constants(&mut self) -> crate::Result<()> {
+        tracing::debug!("resolve_constants: starting phase 7.5");
+
+        let ns = self.namespace.lock().await;
+
+        for (item_ctx, child) in &ns.children {
+            let NamespaceChild::Const(def) = &child.value else {
+                continue;
+            };
+
+            let value = evaluate(&ns, &def.def.name, &child.source)?;
+            check_type(def, &value).map_err(|err| {
+                err.with_source_arc_if(child.source.clone(), ns.sources.get(&child.source).cloned())
+            })?;
+
+            self.resolution
+                .constants
+                .insert(item_ctx.name.borrow_string().clone(), value);
+        }
+
+        tracing::debug!("resolve_constants: phase 7.5 complete");
+        Ok(())
+    }
+}
+
+/// Follows a constant reference within `ns` until a literal is reached.
+///
+/// Names which do not refer to a `const`, or which refer back to themselves, are not
+/// constant expressions.
+pub(crate) fn evaluate(
+    ns: &NamespaceCtx,
+    name: &SpannedToken![ident],
+    origin: &Path,
+) -> crate::Result<ConstLiteral> {
+    let mut current = name.clone();
+    let mut source = origin.to_path_buf();
+    let mut seen: Vec<String> = Vec::new();
+
+    loop {
+        let key = current.borrow_string().clone();
+        let child = ns
+            .children
+            .get(&ns.ctx.item(current.clone()));
+
+        let def = match child.map(|c| (&c.value, &c.source)) {
+            Some((NamespaceChild::Const(def), def_source)) if !seen.contains(&key) => {
+                source = def_source.clone();
+                def
+            },
+            _ => return Err(non_constant(ns, &current, &source)),
+        };
+
+        seen.push(key);
+
+        match &def.def.value.value.value {
+            ConstValue::Number(n) => return Ok(ConstLiteral::Int(*n.borrow_i32() as i64)),
+            ConstValue::String(s) => return Ok(ConstLiteral::Str(s.borrow_string().clone())),
+            ConstValue::Ident(next) => current = next.clone(),
+        }
+    }
+}
+
+fn non_constant(
+    ns: &NamespaceCtx,
+    name: &SpannedToken![ident],
+    source: &Path,
+) -> crate::Error {
+    let sp = name.span();
+    let err: crate::Error = crate::TypeDefError::non_constant_expression(name.borrow_string())
+        .at(crate::Span::new(sp.start, sp.end))
+        .build()
+        .into();
+    err.with_source_arc_if(source.to_path_buf(), ns.sources.get(source).cloned())
+}
+
+/// Per ERR-0005: KTY2004 when a constant does not fit its declared builtin type.
+fn check_type(
+    def: &ConstDef,
+    value: &ConstLiteral,
+) -> crate::Result<()> {
+    let ty = &def.def.value.ty.value;
+    let fits = match (ty, value) {
+        (Builtin::Str(_), ConstLiteral::Str(_)) => true,
+        (Builtin::I8(_), ConstLiteral::Int(v)) => i8::try_from(*v).is_ok(),
+        (Builtin::U8(_), ConstLiteral::Int(v)) => u8::try_from(*v).is_ok(),
+        (Builtin::I16(_), ConstLiteral::Int(v)) => i16::try_from(*v).is_ok(),
+        (Builtin::U16(_), ConstLiteral::Int(v)) => u16::try_from(*v).is_ok(),
+        (
+            Builtin::I32(_)
+            | Builtin::I64(_)
+            | Builtin::U32(_)
+            | Builtin::U64(_)
+            | Builtin::Usize(_)
+            | Builtin::F16(_)
+            | Builtin::F32(_)
+            | Builtin::F64(_),
+            ConstLiteral::Int(_),
+        ) => true,
+        _ => false,
+    };
+
+    if fits {
+        return Ok(());
+    }
+
+    Err(crate::TypeDefError::const_type_mismatch(
+        def.def.value.name.borrow_string(),
+        ty.display(),
+        value.to_string(),
+    )
+    .at(def.def.value.value.value.span())
+    .build()
+    .into())
+}
+
+/// Resolves the length of a sized array, evaluating constant references.
+pub(crate) fn array_len(
+    ns: &NamespaceCtx,
+    size: &crate::ast::array::ArraySize,
+    origin: &Path,
+) -> crate::Result<u64> {
+    use crate::ast::array::ArraySize;
+
+    match size {
+        ArraySize::Literal(n) => Ok(*n.borrow_i32() as u64),
+        ArraySize::Const(name) => {
+            match evaluate(ns, name, origin)? {
+                ConstLiteral::Int(v) => Ok(v as u64),
+                other => {
+                    let sp = name.span();
+                    let err: crate::Error = crate::TypeDefError::const_type_mismatch(
+                        name.borrow_string(),
+                        "usize",
+                        other.to_string(),
+                    )
+                    .at(crate::Span::new(sp.start, sp.end))
+                    .build()
+                    .into();
+                    Err(err
+                        .with_source_arc_if(origin.to_path_buf(), ns.sources.get(origin).cloned()))
+                },
+            }
+        },
+    }
+}
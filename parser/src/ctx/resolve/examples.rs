@@ -0,0 +1,511 @@
+//! Example Validation Phase (Phase 7.25)
+//!
+//! Checks `#[example(...)]` payloads against the types they illustrate, so examples
+//! carried into declarations always describe values the schema accepts.
+
+use serde_json::Value;
+
+use crate::{
+    ast::{
+        array::{Array, ArraySize},
+        constant::ConstLiteral,
+        enm::Enum,
+        items::CommentOrMeta,
+        meta::{ExampleAttribute, ItemMetaItem},
+        strct::{Arg, Sep},
+        ty::{Builtin, PathOrIdent, Type},
+    },
+    ctx::{NamespaceCtx, common::NamespaceChild},
+};
+
+use super::TypeResolver;
+
+/// Nesting limit for recursive types; deeper payloads are accepted unchecked.
+const MAX_EXAMPLE_DEPTH: usize = 32;
+
+impl TypeResolver {
+    /// Validate example payloads (Phase 7.25)
+    ///
+    /// `#[example('...')]` is accepted on struct fields and operation arguments, and
+    /// `#[example(input = '...', output = '...')]` on operations. Each payload must be
+    /// valid JSON matching the declared type.
+    pub(super) async fn validate_examples(&mut self) -> crate::Result<()> {
+        let ns = self.namespace.lock().await;
+
+        for (item_ctx, child) in &ns.children {
+            let item_name = item_ctx.name.borrow_string();
+            let source_path = child.source.clone();
+            let source_content = ns.sources.get(&source_path).cloned();
+
+            let meta = match &child.value {
+                NamespaceChild::Operation(def) => &def.meta,
+                NamespaceChild::Struct(def) => &def.meta,
+                NamespaceChild::Enum(def) => &def.meta,
+                NamespaceChild::OneOf(def) => &def.meta,
+                NamespaceChild::Type(def) => &def.meta,
+                NamespaceChild::Error(def) => &def.meta,
+                NamespaceChild::Const(def) => &def.meta,
+                NamespaceChild::Namespace(_) => continue,
+            };
+            let examples = meta
+                .iter()
+                .filter_map(|meta_or_comment| {
+                    match &meta_or_comment.value {
+                        CommentOrMeta::Meta(meta) => Some(meta.value.meta.iter()),
+                        _ => None,
+                    }
+                })
+                .flatten()
+                .filter(|item| matches!(item, ItemMetaItem::Example(_)));
+
+            let result = match &child.value {
+                NamespaceChild::Struct(def) => {
+                    def.def
+                        .value
+                        .args
+                        .values
+                        .iter()
+                        .try_for_each(|arg| {
+                            self.validate_field_examples(&ns, item_name, &arg.value)
+                        })
+                },
+                NamespaceChild::Operation(def) => {
+                    let args: Vec<&Arg> = def
+                        .def
+                        .value
+                        .args
+                        .iter()
+                        .flat_map(|args| args.value.values.iter())
+                        .map(|arg| &arg.value.value)
+                        .collect();
+                    args.iter()
+                        .try_for_each(|arg| self.validate_field_examples(&ns, item_name, arg))
+                        .and_then(|_| {
+                            examples.clone().try_for_each(|item| {
+                                self.validate_operation_example(
+                                    &ns,
+                                    item_name,
+                                    item,
+                                    &args,
+                                    &def.def.value.return_type.value,
+                                )
+                            })
+                        })
+                },
+                _ => Ok(()),
+            }
+            .and_then(|_| {
+                match (&child.value, examples.clone().next()) {
+                    (NamespaceChild::Operation(_), _) | (_, None) => Ok(()),
+                    (_, Some(item)) => {
+                        Err(crate::MetadataError::misplaced_attribute(
+                            "example",
+                            format!("item '{item_name}'"),
+                        )
+                        .at(item.span())
+                        .build()
+                        .into())
+                    },
+                }
+            });
+
+            if let Err(err) = result {
+                return Err(err.with_source_arc_if(source_path, source_content));
+            }
+        }
+
+        for anon in &self.resolution.anonymous_structs {
+            let source_content = ns.sources.get(&anon.source).cloned();
+            let def = &anon.value.value.def.value;
+            for arg in &def.args.values {
+                if let Err(err) =
+                    self.validate_field_examples(&ns, def.name.borrow_string(), &arg.value)
+                {
+                    return Err(err.with_source_arc_if(anon.source.clone(), source_content));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_field_examples(
+        &self,
+        ns: &NamespaceCtx,
+        parent: &str,
+        arg: &Arg,
+    ) -> crate::Result<()> {
+        let target = format!("{parent}.{}", arg.name.borrow_string());
+        let optional = matches!(arg.sep.value, Sep::Optional { .. });
+
+        for item in &arg.meta.meta {
+            let ItemMetaItem::Example(example) = item else {
+                continue;
+            };
+            let invalid = |reason: String| -> crate::Error {
+                crate::MetadataError::invalid_example(&target, reason)
+                    .at(item.span())
+                    .build()
+                    .into()
+            };
+
+            let ExampleAttribute::Value(payload) = &example.value else {
+                return Err(invalid(
+                    "fields and arguments take a single value, e.g. #[example('1')]".into(),
+                ));
+            };
+            let value = parse_payload(payload).map_err(&invalid)?;
+            if !(optional && value.is_null()) {
+                self.check_example(ns, &arg.typ, &value, "$", 0)
+                    .map_err(&invalid)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_operation_example(
+        &self,
+        ns: &NamespaceCtx,
+        op_name: &str,
+        item: &ItemMetaItem,
+        args: &[&Arg],
+        return_type: &Type,
+    ) -> crate::Result<()> {
+        let ItemMetaItem::Example(example) = item else {
+            return Ok(());
+        };
+        let invalid = |reason: String| -> crate::Error {
+            crate::MetadataError::invalid_example(op_name, reason)
+                .at(item.span())
+                .build()
+                .into()
+        };
+
+        let ExampleAttribute::Call { input, output } = &example.value else {
+            return Err(invalid(
+                "operations take #[example(input = '...', output = '...')]".into(),
+            ));
+        };
+
+        if let Some(payload) = input {
+            let input = parse_payload(payload).map_err(&invalid)?;
+            let Value::Object(input) = input else {
+                return Err(invalid(format!(
+                    "input must be an object keyed by argument name, found {}",
+                    json_kind(&input)
+                )));
+            };
+
+            if let Some(key) = input.keys().find(|key| {
+                !args
+                    .iter()
+                    .any(|arg| arg.name.borrow_string() == *key)
+            }) {
+                return Err(invalid(format!("input has unknown argument '{key}'")));
+            }
+
+            for arg in args {
+                let name = arg.name.borrow_string();
+                let optional = matches!(arg.sep.value, Sep::Optional { .. });
+                match input.get(name) {
+                    None if !optional => {
+                        return Err(invalid(format!(
+                            "input is missing required argument '{name}'"
+                        )));
+                    },
+                    None => {},
+                    Some(Value::Null) if optional => {},
+                    Some(value) => {
+                        self.check_example(ns, &arg.typ, value, &format!("$.{name}"), 0)
+                            .map_err(|reason| invalid(format!("input: {reason}")))?;
+                    },
+                }
+            }
+        }
+
+        if let Some(payload) = output {
+            let output = parse_payload(payload).map_err(&invalid)?;
+            self.check_example(ns, success_type(return_type), &output, "$", 0)
+                .map_err(|reason| invalid(format!("output: {reason}")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Check `value` against `ty`, describing the first mismatch found at `path`.
+    fn check_example(
+        &self,
+        ns: &NamespaceCtx,
+        ty: &Type,
+        value: &Value,
+        path: &str,
+        depth: usize,
+    ) -> Result<(), String> {
+        if depth > MAX_EXAMPLE_DEPTH {
+            return Ok(());
+        }
+        let mismatch =
+            |expected: &str| format!("at {path}: expected {expected}, found {}", json_kind(value));
+
+        match ty {
+            Type::Builtin { ty } => check_builtin(&ty.value, value).map_err(mismatch),
+            Type::Paren { ty, .. } | Type::Result { ty, .. } => {
+                self.check_example(ns, &ty.value, value, path, depth)
+            },
+            Type::Array { ty } => {
+                let Value::Array(items) = value else {
+                    return Err(mismatch("array"));
+                };
+                let (inner, size) = match &ty.value {
+                    Array::Unsized { ty, .. } => (ty, None),
+                    Array::Sized { ty, size, .. } => (ty, Some(size)),
+                };
+                if let Some(ArraySize::Literal(size)) = size {
+                    let size = *size.borrow_i32() as usize;
+                    if items.len() != size {
+                        return Err(format!(
+                            "at {path}: expected {size} elements, found {}",
+                            items.len()
+                        ));
+                    }
+                }
+                items
+                    .iter()
+                    .enumerate()
+                    .try_for_each(|(i, item)| {
+                        self.check_example(
+                            ns,
+                            &inner.value,
+                            item,
+                            &format!("{path}[{i}]"),
+                            depth + 1,
+                        )
+                    })
+            },
+            Type::Struct { ty } => {
+                let fields: Vec<&Arg> = ty
+                    .value
+                    .fields
+                    .value
+                    .values
+                    .iter()
+                    .map(|arg| &arg.value.value)
+                    .collect();
+                self.check_object(ns, &fields, value, path, depth)
+            },
+            Type::Ident {
+                to: PathOrIdent::Ident(ident),
+            } => self.check_named(ns, ident.borrow_string(), value, path, depth),
+            // oneofs, unions, type expressions and external types are not checked structurally
+            _ => Ok(()),
+        }
+    }
+
+    fn check_named(
+        &self,
+        ns: &NamespaceCtx,
+        name: &str,
+        value: &Value,
+        path: &str,
+        depth: usize,
+    ) -> Result<(), String> {
+        if let Some(alias) = self.resolution.resolved_aliases.get(name) {
+            return self.check_example(ns, &alias.value, value, path, depth + 1);
+        }
+
+        let local = ns
+            .children
+            .iter()
+            .find(|(item_ctx, _)| item_ctx.name.borrow_string() == name)
+            .map(|(_, child)| &child.value);
+
+        match local {
+            Some(NamespaceChild::Struct(def)) => {
+                let fields: Vec<&Arg> = def
+                    .def
+                    .value
+                    .args
+                    .values
+                    .iter()
+                    .map(|arg| &arg.value.value)
+                    .collect();
+                self.check_object(ns, &fields, value, path, depth)
+            },
+            Some(NamespaceChild::Enum(def)) => {
+                let values = match &def.def.value {
+                    Enum::Int(typed) => {
+                        Self::enum_values(
+                            typed,
+                            |v| ConstLiteral::Int(*v.borrow_i32() as i64),
+                            ConstLiteral::Int(0),
+                        )
+                    },
+                    Enum::Str(typed) => {
+                        Self::enum_values(
+                            typed,
+                            |v| ConstLiteral::Str(v.borrow_string().clone()),
+                            ConstLiteral::Str(String::new()),
+                        )
+                    },
+                };
+                let matches = |literal: &ConstLiteral| {
+                    match (literal, value) {
+                        (ConstLiteral::Int(i), Value::Number(n)) => n.as_i64() == Some(*i),
+                        (ConstLiteral::Str(s), Value::String(v)) => s == v,
+                        _ => false,
+                    }
+                };
+                if values
+                    .iter()
+                    .any(|(_, literal, _)| matches(literal))
+                {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "at {path}: {value} is not a value of enum '{name}'"
+                    ))
+                }
+            },
+            _ => {
+                match self
+                    .resolution
+                    .anonymous_structs
+                    .iter()
+                    .find(|anon| {
+                        anon.value
+                            .value
+                            .def
+                            .value
+                            .name
+                            .borrow_string()
+                            == name
+                    }) {
+                    Some(anon) => {
+                        let fields: Vec<&Arg> = anon
+                            .value
+                            .value
+                            .def
+                            .value
+                            .args
+                            .values
+                            .iter()
+                            .map(|arg| &arg.value.value)
+                            .collect();
+                        self.check_object(ns, &fields, value, path, depth)
+                    },
+                    None => Ok(()),
+                }
+            },
+        }
+    }
+
+    fn check_object(
+        &self,
+        ns: &NamespaceCtx,
+        fields: &[&Arg],
+        value: &Value,
+        path: &str,
+        depth: usize,
+    ) -> Result<(), String> {
+        let Value::Object(object) = value else {
+            return Err(format!(
+                "at {path}: expected object, found {}",
+                json_kind(value)
+            ));
+        };
+
+        if let Some(key) = object.keys().find(|key| {
+            !fields
+                .iter()
+                .any(|f| f.name.borrow_string() == *key)
+        }) {
+            return Err(format!("at {path}: unknown field '{key}'"));
+        }
+
+        for field in fields {
+            let name = field.name.borrow_string();
+            let optional = matches!(field.sep.value, Sep::Optional { .. });
+            match object.get(name) {
+                None if !optional => {
+                    return Err(format!("at {path}: missing required field '{name}'"));
+                },
+                None => {},
+                Some(Value::Null) if optional => {},
+                Some(inner) => {
+                    self.check_example(ns, &field.typ, inner, &format!("{path}.{name}"), depth + 1)?
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_payload(payload: &str) -> Result<Value, String> {
+    serde_json::from_str(payload).map_err(|err| format!("invalid JSON: {err}"))
+}
+
+/// The type an operation returns on success.
+fn success_type(ty: &Type) -> &Type {
+    match ty {
+        Type::Paren { ty, .. } | Type::Result { ty, .. } => success_type(&ty.value),
+        ty => ty,
+    }
+}
+
+fn check_builtin(
+    builtin: &Builtin,
+    value: &Value,
+) -> Result<(), &'static str> {
+    let int = |name: &'static str, min: i128, max: i128| {
+        let n = match value {
+            Value::Number(n) => {
+                n.as_i64()
+                    .map(i128::from)
+                    .or_else(|| n.as_u64().map(i128::from))
+            },
+            _ => None,
+        };
+        match n {
+            Some(n) if (min..=max).contains(&n) => Ok(()),
+            _ => Err(name),
+        }
+    };
+
+    match builtin {
+        Builtin::I8(_) => int("i8", i8::MIN.into(), i8::MAX.into()),
+        Builtin::I16(_) => int("i16", i16::MIN.into(), i16::MAX.into()),
+        Builtin::I32(_) => int("i32", i32::MIN.into(), i32::MAX.into()),
+        Builtin::I64(_) => int("i64", i64::MIN.into(), i64::MAX.into()),
+        Builtin::U8(_) => int("u8", 0, u8::MAX.into()),
+        Builtin::U16(_) => int("u16", 0, u16::MAX.into()),
+        Builtin::U32(_) => int("u32", 0, u32::MAX.into()),
+        Builtin::U64(_) | Builtin::Usize(_) => int("u64", 0, u64::MAX.into()),
+        Builtin::F16(_) | Builtin::F32(_) | Builtin::F64(_) if value.is_number() => Ok(()),
+        Builtin::F16(_) | Builtin::F32(_) | Builtin::F64(_) => Err("number"),
+        Builtin::Bool(_) if value.is_boolean() => Ok(()),
+        Builtin::Bool(_) => Err("bool"),
+        Builtin::Str(_) | Builtin::DateTime(_) | Builtin::Binary(_) | Builtin::Base64(_)
+            if value.is_string() =>
+        {
+            Ok(())
+        },
+        Builtin::Str(_) | Builtin::DateTime(_) | Builtin::Binary(_) | Builtin::Base64(_) => {
+            Err("string")
+        },
+        Builtin::Complex(_) => Ok(()),
+        Builtin::Never(_) => Err("no value (never)"),
+    }
+}
+
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
         strct::{Arg, Struct},
         ty::Type,
         union::Union,
+        vis::Visibility,
     },
     ctx::{
         SourceSpanned,
@@ -29,6 +30,7 @@ pub fn build_struct_def_from_anonymous(
     StructDef {
         // todo: pass parent version info
         meta: Vec::new(),
+        vis: None,
         def: Spanned::call_site(Struct {
             kw: Spanned::call_site(<Token![struct]>::new()),
             name: Spanned::call_site(IdentToken::new(generated_name)),
@@ -48,6 +50,8 @@ pub struct UnionRecord {
     pub in_oneof: bool,
     /// if in_oneof, the variant index for numeric suffix
     pub variant_index: Option<usize>,
+    /// visibility of the item the union appears in, inherited by the merged struct
+    pub vis: Option<Spanned<Visibility>>,
 }
 
 impl UnionRecord {
@@ -192,6 +196,7 @@ impl UnionWorkingSet {
 
         StructDef {
             meta: Vec::new(),
+            vis: None,
             def: Spanned::call_site(Struct {
                 kw: Spanned::call_site(<Token![struct]>::new()),
                 name: Spanned::call_site(IdentToken::new(generated_name)),
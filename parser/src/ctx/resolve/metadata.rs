@@ -1,6 +1,16 @@
 use std::{path::PathBuf, sync::Arc};
 
-use crate::{ast::ty::Type, ctx::common::NamespaceChild, defs::Spanned};
+use crate::{
+    ast::{
+        enm::Enum,
+        items::CommentOrMeta,
+        meta::{ConstraintAttribute, HTTP_METHODS, HttpAttribute, ItemMetaItem},
+        strct::Arg,
+        ty::{Builtin, PathOrIdent, Type},
+    },
+    ctx::common::NamespaceChild,
+    defs::Spanned,
+};
 
 use super::TypeResolver;
 
@@ -61,8 +71,8 @@ impl TypeResolver {
                         &source_content,
                     )?
                 },
-                NamespaceChild::Namespace(_) => {
-                    // Nested namespaces handle their own versions
+                NamespaceChild::Namespace(_) | NamespaceChild::Const(_) => {
+                    // Nested namespaces handle their own versions, constants are unversioned
                     continue;
                 },
             };
@@ -239,6 +249,15 @@ impl TypeResolver {
                         return true;
                     }
                 },
+                crate::ctx::RefOrItemContext::Glob(ref_ctx) => {
+                    // Glob imports only expose the name if the namespace defines it
+                    let candidate = ref_ctx.item(Spanned::call_site(
+                        crate::tokens::IdentToken::new(name.to_string()),
+                    ));
+                    if ns.registry.get(&candidate).is_some() {
+                        return true;
+                    }
+                },
             }
         }
 
@@ -272,4 +291,588 @@ impl TypeResolver {
         }
         None
     }
+
+    /// Validate field constraint attributes (`#[min]`, `#[max]`, `#[pattern]`, `#[len]`).
+    ///
+    /// Constraints are only accepted on struct fields and operation arguments, and each
+    /// must suit the (alias-resolved) type of the field it is attached to.
+    pub(super) async fn validate_constraints(&mut self) -> crate::Result<()> {
+        let ns = self.namespace.lock().await;
+
+        for (item_ctx, child) in &ns.children {
+            let source_path = child.source.clone();
+            let source_content = ns.sources.get(&source_path).cloned();
+
+            let (meta, fields) = match &child.value {
+                NamespaceChild::Struct(def) => (&def.meta, Some(&def.def.value.args)),
+                NamespaceChild::Operation(def) => {
+                    (
+                        &def.meta,
+                        def.def
+                            .value
+                            .args
+                            .as_ref()
+                            .map(|args| &args.value),
+                    )
+                },
+                NamespaceChild::Enum(def) => (&def.meta, None),
+                NamespaceChild::OneOf(def) => (&def.meta, None),
+                NamespaceChild::Type(def) => (&def.meta, None),
+                NamespaceChild::Error(def) => (&def.meta, None),
+                NamespaceChild::Const(def) => (&def.meta, None),
+                NamespaceChild::Namespace(_) => continue,
+            };
+
+            // integer enums take #[min] and #[max] as bounds on their discriminants
+            let bounded = matches!(&child.value, NamespaceChild::Enum(def) if matches!(def.def.value, Enum::Int(_)));
+            let result =
+                Self::reject_item_constraints(item_ctx.name.borrow_string(), meta, bounded)
+                    .and_then(|_| {
+                        fields
+                            .into_iter()
+                            .flat_map(|fields| fields.values.iter())
+                            .try_for_each(|arg| self.validate_field_constraints(&arg.value))
+                    });
+
+            if let Err(err) = result {
+                return Err(err.with_source_arc_if(source_path, source_content));
+            }
+        }
+
+        for anon in &self.resolution.anonymous_structs {
+            let source_content = ns.sources.get(&anon.source).cloned();
+            for arg in &anon.value.value.def.value.args.values {
+                if let Err(err) = self.validate_field_constraints(&arg.value) {
+                    return Err(err.with_source_arc_if(anon.source.clone(), source_content));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn reject_item_constraints(
+        item_name: &str,
+        meta_vec: &[Spanned<CommentOrMeta>],
+        bounded: bool,
+    ) -> crate::Result<()> {
+        for meta_or_comment in meta_vec {
+            if let CommentOrMeta::Meta(meta_spanned) = &meta_or_comment.value
+                && let Some(constraint) = meta_spanned.value.meta.iter().find(|item| {
+                    match item {
+                        ItemMetaItem::Constraint(constraint) => {
+                            !bounded
+                                || !matches!(
+                                    constraint.value,
+                                    ConstraintAttribute::Min(_) | ConstraintAttribute::Max(_)
+                                )
+                        },
+                        ItemMetaItem::Contiguous(_) => !bounded,
+                        _ => false,
+                    }
+                })
+            {
+                return Err(crate::MetadataError::misplaced_attribute(
+                    constraint.name(),
+                    format!("item '{item_name}'"),
+                )
+                .at(constraint.span())
+                .build()
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_field_constraints(
+        &self,
+        arg: &Arg,
+    ) -> crate::Result<()> {
+        let field = arg.name.borrow_string();
+        let kind = self.constraint_kind(&arg.typ);
+
+        let mut seen: Vec<&'static str> = Vec::new();
+        let mut min = None;
+        let mut max = None;
+
+        for item in &arg.meta.meta {
+            let constraint = match item {
+                ItemMetaItem::Constraint(constraint) => constraint,
+                // checked against the field type in the examples phase
+                ItemMetaItem::Example(_) => continue,
+                _ => {
+                    return Err(crate::MetadataError::misplaced_attribute(
+                        item.name(),
+                        format!("field '{field}'"),
+                    )
+                    .at(item.span())
+                    .build()
+                    .into());
+                },
+            };
+            let name = constraint.value.name();
+            let invalid = |reason: String| -> crate::Error {
+                crate::MetadataError::invalid_constraint(name, field, reason)
+                    .at(item.span())
+                    .build()
+                    .into()
+            };
+
+            if seen.contains(&name) {
+                return Err(invalid(format!("#[{name}] is declared multiple times")));
+            }
+            seen.push(name);
+
+            let applies = match &constraint.value {
+                ConstraintAttribute::Min(_) | ConstraintAttribute::Max(_) => {
+                    kind == ConstraintKind::Numeric
+                },
+                ConstraintAttribute::Pattern(_) => kind == ConstraintKind::Str,
+                ConstraintAttribute::Len { .. } => {
+                    matches!(
+                        kind,
+                        ConstraintKind::Str | ConstraintKind::Bytes | ConstraintKind::Array
+                    )
+                },
+            };
+            if !applies {
+                return Err(invalid(format!(
+                    "not applicable to type {}",
+                    arg.typ.type_name()
+                )));
+            }
+
+            match &constraint.value {
+                ConstraintAttribute::Min(v) => min = Some(*v),
+                ConstraintAttribute::Max(v) => max = Some((*v, item.span())),
+                ConstraintAttribute::Pattern(pattern) => {
+                    if let Err(err) = regex::Regex::new(pattern) {
+                        return Err(invalid(format!("invalid regular expression: {err}")));
+                    }
+                },
+                ConstraintAttribute::Len {
+                    min: Some(lo),
+                    max: Some(hi),
+                } if lo > hi => {
+                    return Err(invalid(format!(
+                        "lower bound {lo} exceeds upper bound {hi}"
+                    )));
+                },
+                ConstraintAttribute::Len { .. } => {},
+            }
+        }
+
+        if let (Some(lo), Some((hi, span))) = (min, max)
+            && lo > hi
+        {
+            return Err(crate::MetadataError::invalid_constraint(
+                "max",
+                field,
+                format!("maximum {hi} is less than minimum {lo}"),
+            )
+            .at(span)
+            .build()
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Validate the discriminants of integer enums against `#[contiguous]`, `#[min]` and
+    /// `#[max]`. A variant without a value has the discriminant 0, as in declarations.
+    pub(super) async fn validate_enum_discriminants(&mut self) -> crate::Result<()> {
+        let ns = self.namespace.lock().await;
+
+        for (item_ctx, child) in &ns.children {
+            let NamespaceChild::Enum(def) = &child.value else {
+                continue;
+            };
+            let Enum::Int(typed) = &def.def.value else {
+                continue;
+            };
+
+            let mut contiguous = false;
+            let (mut min, mut max) = (None, None);
+            for item in def
+                .meta
+                .iter()
+                .filter_map(|meta| {
+                    match &meta.value {
+                        CommentOrMeta::Meta(meta) => Some(meta.value.meta.iter()),
+                        _ => None,
+                    }
+                })
+                .flatten()
+            {
+                match item {
+                    ItemMetaItem::Contiguous(_) => contiguous = true,
+                    ItemMetaItem::Constraint(constraint) => {
+                        match constraint.value {
+                            ConstraintAttribute::Min(v) => min = Some(v),
+                            ConstraintAttribute::Max(v) => max = Some(v),
+                            _ => {},
+                        }
+                    },
+                    _ => {},
+                }
+            }
+            if !contiguous && min.is_none() && max.is_none() {
+                continue;
+            }
+
+            let variants: Vec<(&str, i64, crate::Span)> = typed
+                .variants
+                .values
+                .iter()
+                .map(|variant| {
+                    let variant = &variant.value;
+                    let name = variant.name();
+                    match variant.enum_value() {
+                        Some(value) => {
+                            let span = value.value.span();
+                            (
+                                name,
+                                *value.inner().borrow_i32() as i64,
+                                crate::Span::new(span.start, span.end),
+                            )
+                        },
+                        None => {
+                            // the name span begins where the previous token ended
+                            let span = variant.name.span();
+                            (
+                                name,
+                                0,
+                                crate::Span::new(span.end.saturating_sub(name.len()), span.end),
+                            )
+                        },
+                    }
+                })
+                .collect();
+
+            let result = Self::check_discriminants(&variants, contiguous, min, max).map_err(
+                |(variant, span, reason): (&str, crate::Span, String)| -> crate::Error {
+                    crate::MetadataError::invalid_discriminant(
+                        item_ctx.name.borrow_string(),
+                        variant,
+                        reason,
+                    )
+                    .at(span)
+                    .build()
+                    .into()
+                },
+            );
+
+            if let Err(err) = result {
+                let source_content = ns.sources.get(&child.source).cloned();
+                return Err(err.with_source_arc_if(child.source.clone(), source_content));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The first variant breaking the layout, with the reason.
+    fn check_discriminants<'v>(
+        variants: &[(&'v str, i64, crate::Span)],
+        contiguous: bool,
+        min: Option<i64>,
+        max: Option<i64>,
+    ) -> Result<(), (&'v str, crate::Span, String)> {
+        for &(name, value, span) in variants {
+            if let Some(min) = min
+                && value < min
+            {
+                return Err((name, span, format!("{value} is below the minimum {min}")));
+            }
+            if let Some(max) = max
+                && value > max
+            {
+                return Err((name, span, format!("{value} is above the maximum {max}")));
+            }
+        }
+
+        if contiguous {
+            let mut sorted: Vec<_> = variants.iter().collect();
+            sorted.sort_by_key(|(_, value, _)| *value);
+            for pair in sorted.windows(2) {
+                let (prev, prev_value, _) = pair[0];
+                let (name, value, span) = *pair[1];
+                if value == *prev_value {
+                    return Err((
+                        name,
+                        span,
+                        format!("{value} repeats the discriminant of '{prev}'"),
+                    ));
+                }
+                if value != prev_value + 1 {
+                    return Err((
+                        name,
+                        span,
+                        format!("{value} leaves a gap after '{prev}' = {prev_value}"),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate `#[http(method = "...", path = "...")]` bindings.
+    ///
+    /// Bindings are only accepted on operations, need both a known method and an absolute
+    /// path, and every `{param}` in the path must name one of the operation's arguments.
+    pub(super) async fn validate_http_bindings(&mut self) -> crate::Result<()> {
+        let ns = self.namespace.lock().await;
+
+        for (item_ctx, child) in &ns.children {
+            let item_name = item_ctx.name.borrow_string();
+            let source_path = child.source.clone();
+            let source_content = ns.sources.get(&source_path).cloned();
+
+            let meta = match &child.value {
+                NamespaceChild::Operation(def) => &def.meta,
+                NamespaceChild::Struct(def) => &def.meta,
+                NamespaceChild::Enum(def) => &def.meta,
+                NamespaceChild::OneOf(def) => &def.meta,
+                NamespaceChild::Type(def) => &def.meta,
+                NamespaceChild::Error(def) => &def.meta,
+                NamespaceChild::Const(def) => &def.meta,
+                NamespaceChild::Namespace(_) => continue,
+            };
+
+            let bindings: Vec<_> = meta
+                .iter()
+                .filter_map(|meta_or_comment| {
+                    match &meta_or_comment.value {
+                        CommentOrMeta::Meta(meta) => Some(meta.value.meta.iter()),
+                        _ => None,
+                    }
+                })
+                .flatten()
+                .filter_map(|item| {
+                    match item {
+                        ItemMetaItem::Http(http) => Some((item, &http.value)),
+                        _ => None,
+                    }
+                })
+                .collect();
+
+            let result: crate::Result<()> = match (&child.value, bindings.as_slice()) {
+                (_, []) => Ok(()),
+                (NamespaceChild::Operation(def), [(item, http)]) => {
+                    let args: Vec<&str> = def
+                        .def
+                        .value
+                        .args
+                        .iter()
+                        .flat_map(|args| args.value.values.iter())
+                        .map(|arg| arg.value.name.borrow_string().as_str())
+                        .collect();
+                    Self::validate_http_binding(http, &args).map_err(|reason| {
+                        crate::MetadataError::invalid_http_binding(item_name, reason)
+                            .at(item.span())
+                            .build()
+                            .into()
+                    })
+                },
+                (NamespaceChild::Operation(_), [_, (duplicate, _), ..]) => {
+                    Err(crate::MetadataError::duplicate_attribute(
+                        "http",
+                        source_path.display().to_string(),
+                    )
+                    .at(duplicate.span())
+                    .build()
+                    .into())
+                },
+                (_, [(item, _), ..]) => {
+                    Err(crate::MetadataError::misplaced_attribute(
+                        "http",
+                        format!("item '{item_name}'"),
+                    )
+                    .at(item.span())
+                    .build()
+                    .into())
+                },
+            };
+
+            if let Err(err) = result {
+                return Err(err.with_source_arc_if(source_path, source_content));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate `#[graphql(query)]` and `#[graphql(mutation)]` attributes.
+    ///
+    /// The attribute is only accepted once per operation and must name exactly one kind.
+    pub(super) async fn validate_graphql_kinds(&mut self) -> crate::Result<()> {
+        let ns = self.namespace.lock().await;
+
+        for (item_ctx, child) in &ns.children {
+            let item_name = item_ctx.name.borrow_string();
+            let source_path = child.source.clone();
+            let source_content = ns.sources.get(&source_path).cloned();
+
+            let meta = match &child.value {
+                NamespaceChild::Operation(def) => &def.meta,
+                NamespaceChild::Struct(def) => &def.meta,
+                NamespaceChild::Enum(def) => &def.meta,
+                NamespaceChild::OneOf(def) => &def.meta,
+                NamespaceChild::Type(def) => &def.meta,
+                NamespaceChild::Error(def) => &def.meta,
+                NamespaceChild::Const(def) => &def.meta,
+                NamespaceChild::Namespace(_) => continue,
+            };
+
+            let attrs: Vec<_> = meta
+                .iter()
+                .filter_map(|meta_or_comment| {
+                    match &meta_or_comment.value {
+                        CommentOrMeta::Meta(meta) => Some(meta.value.meta.iter()),
+                        _ => None,
+                    }
+                })
+                .flatten()
+                .filter_map(|item| {
+                    match item {
+                        ItemMetaItem::Graphql(graphql) => Some((item, &graphql.value)),
+                        _ => None,
+                    }
+                })
+                .collect();
+
+            let invalid = |item: &ItemMetaItem, reason: &str| -> crate::Error {
+                crate::MetadataError::invalid_graphql_kind(item_name, reason)
+                    .at(item.span())
+                    .build()
+                    .into()
+            };
+
+            let result: crate::Result<()> = match (&child.value, attrs.as_slice()) {
+                (_, []) => Ok(()),
+                (NamespaceChild::Operation(_), [(item, graphql)]) => {
+                    match graphql.kinds.as_slice() {
+                        [_] => Ok(()),
+                        [] => Err(invalid(item, "missing `query` or `mutation`")),
+                        _ => Err(invalid(item, "`query` and `mutation` are exclusive")),
+                    }
+                },
+                (NamespaceChild::Operation(_), [_, (duplicate, _), ..]) => {
+                    Err(crate::MetadataError::duplicate_attribute(
+                        "graphql",
+                        source_path.display().to_string(),
+                    )
+                    .at(duplicate.span())
+                    .build()
+                    .into())
+                },
+                (_, [(item, _), ..]) => {
+                    Err(crate::MetadataError::misplaced_attribute(
+                        "graphql",
+                        format!("item '{item_name}'"),
+                    )
+                    .at(item.span())
+                    .build()
+                    .into())
+                },
+            };
+
+            if let Err(err) = result {
+                return Err(err.with_source_arc_if(source_path, source_content));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_http_binding(
+        http: &HttpAttribute,
+        args: &[&str],
+    ) -> Result<(), String> {
+        let Some(method) = &http.method else {
+            return Err("missing `method`".into());
+        };
+        if !HTTP_METHODS
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(method))
+        {
+            return Err(format!(
+                "unknown method \"{method}\", expected one of {}",
+                HTTP_METHODS.join(", ")
+            ));
+        }
+
+        let Some(path) = &http.path else {
+            return Err("missing `path`".into());
+        };
+        if !path.starts_with('/') {
+            return Err(format!("path \"{path}\" must start with '/'"));
+        }
+
+        let params = http.path_params()?;
+        for (i, param) in params.iter().enumerate() {
+            if params[..i].contains(param) {
+                return Err(format!(
+                    "path parameter '{{{param}}}' appears more than once"
+                ));
+            }
+            if !args.contains(&param.as_str()) {
+                return Err(format!(
+                    "path parameter '{{{param}}}' does not match any operation argument"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Classify a field type for constraint compatibility, following local type aliases.
+    fn constraint_kind(
+        &self,
+        ty: &Type,
+    ) -> ConstraintKind {
+        match ty {
+            Type::Paren { ty, .. } => self.constraint_kind(&ty.value),
+            Type::Array { .. } => ConstraintKind::Array,
+            Type::Builtin { ty } => {
+                match &ty.value {
+                    Builtin::I8(_)
+                    | Builtin::I16(_)
+                    | Builtin::I32(_)
+                    | Builtin::I64(_)
+                    | Builtin::U8(_)
+                    | Builtin::U16(_)
+                    | Builtin::U32(_)
+                    | Builtin::U64(_)
+                    | Builtin::Usize(_)
+                    | Builtin::F16(_)
+                    | Builtin::F32(_)
+                    | Builtin::F64(_) => ConstraintKind::Numeric,
+                    Builtin::Str(_) => ConstraintKind::Str,
+                    Builtin::Binary(_) | Builtin::Base64(_) => ConstraintKind::Bytes,
+                    _ => ConstraintKind::Other,
+                }
+            },
+            Type::Ident {
+                to: PathOrIdent::Ident(ident),
+            } => {
+                self.resolution
+                    .resolved_aliases
+                    .get(ident.borrow_string())
+                    .map(|alias| self.constraint_kind(&alias.value))
+                    .unwrap_or(ConstraintKind::Other)
+            },
+            _ => ConstraintKind::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConstraintKind {
+    Numeric,
+    Str,
+    Bytes,
+    Array,
+    Other,
 }
@@ -1,5 +1,7 @@
 pub(super) mod aliases;
 pub(super) mod anonymous;
+pub(crate) mod constants;
+pub(super) mod examples;
 pub(super) mod helpers;
 pub(super) mod metadata;
 pub(super) mod tagging;
@@ -18,7 +20,7 @@ use tokio::sync::Mutex;
 
 use crate::{
     ast::ty::Type,
-    ctx::{SourceSpanned, common::WithSource},
+    ctx::{Origin, OriginKind, SourceSpanned, common::WithSource},
     defs::{Span, Spanned, Spans},
 };
 
@@ -32,6 +34,10 @@ pub struct NamespaceResolution {
     pub resolved_aliases: BTreeMap<String, Spanned<Type>>,
     pub versions: BTreeMap<String, Spanned<u32>>,
     pub errors: BTreeMap<String, Spanned<String>>,
+    pub constants: BTreeMap<String, crate::ast::constant::ConstLiteral>,
+    pub origins: super::SourceMap,
+    /// Warnings raised while resolving, which the compilation reports
+    pub warnings: Vec<crate::CompilerError>,
 }
 
 impl NamespaceResolution {
@@ -84,6 +90,7 @@ impl TypeResolver {
         }
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     pub async fn resolve(mut self) -> crate::Result<NamespaceResolution> {
         // Phase 1: Extract anonymous structs
         self.anonymous_structs().await?;
@@ -105,6 +112,18 @@ impl TypeResolver {
         self.resolve_versions().await?;
         // Phase 7: Resolve error types
         self.resolve_error_types().await?;
+        // Phase 7.1: Validate field constraint attributes
+        self.validate_constraints().await?;
+        // Phase 7.15: Validate enum discriminant layouts
+        self.validate_enum_discriminants().await?;
+        // Phase 7.2: Validate operation HTTP bindings
+        self.validate_http_bindings().await?;
+        // Phase 7.21: Validate operation GraphQL kinds
+        self.validate_graphql_kinds().await?;
+        // Phase 7.25: Validate example payloads
+        self.validate_examples().await?;
+        // Phase 7.5: Evaluate constants
+        self.resolve_constants().await?;
         // Phase 8: Validate all references
         self.validate_all_references().await?;
 
@@ -120,13 +139,28 @@ impl TypeResolver {
             name_gen.push(child_name.name.borrow_string().clone());
 
             let extracted = anonymous::from_child(&mut name_gen, child)?;
-            self.resolution
-                .anonymous_structs
-                .extend(extracted.into_iter().map(|it| {
+            for mut it in extracted {
+                it.value.vis = child.value.visibility().cloned();
+                // the lifted struct keeps the braces of the anonymous struct
+                self.resolution.origins.record(
+                    it.value
+                        .def
+                        .value
+                        .name
+                        .borrow_string()
+                        .clone(),
+                    Origin::new(
+                        OriginKind::AnonymousStruct,
+                        it.source.clone(),
+                        it.value.def.value.brace.span().clone(),
+                    ),
+                );
+                self.resolution.anonymous_structs.push(
                     it.value
                         .with_span(Span::CallSite)
-                        .with_source(it.source)
-                }));
+                        .with_source(it.source),
+                );
+            }
 
             name_gen.pop();
         }
@@ -143,11 +177,12 @@ impl TypeResolver {
             name_gen.push(child_name.name.borrow_string().clone());
 
             let unions_found = unions::identify_from_child(&mut name_gen, child)?;
-            self.resolution.identified_unions.extend(
-                unions_found
-                    .into_iter()
-                    .map(|u| (Spanned::call_site(u.value), u.source)),
-            );
+            self.resolution
+                .identified_unions
+                .extend(unions_found.into_iter().map(|mut u| {
+                    u.value.vis = child.value.visibility().cloned();
+                    (Spanned::call_site(u.value), u.source)
+                }));
 
             name_gen.pop();
         }
@@ -155,6 +190,7 @@ impl TypeResolver {
         Ok(())
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     async fn validate_unions(&mut self) -> crate::Result<()> {
         tracing::debug!("validate_unions: starting phase 4");
 
@@ -176,6 +212,7 @@ impl TypeResolver {
         Ok(())
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     async fn merge_unions(&mut self) -> crate::Result<()> {
         tracing::debug!("merge_unions: starting phase 5");
 
@@ -183,6 +220,23 @@ impl TypeResolver {
 
         for (union_record, source_path) in &self.resolution.identified_unions {
             let merged_struct = unions::merge_union(&union_record.value, &ns, source_path).await?;
+            let union_ref = &union_record.value.union_ref;
+            self.resolution.origins.record(
+                union_record.value.generate_name(),
+                Origin::new(
+                    OriginKind::UnionMerge,
+                    source_path.clone(),
+                    union_ref.span.clone(),
+                )
+                .with_parts(
+                    union_ref
+                        .value
+                        .types
+                        .values
+                        .iter()
+                        .map(|operand| unions::operand_span(&operand.value.value)),
+                ),
+            );
             self.resolution.union_structs.push(
                 merged_struct
                     .value
@@ -1025,6 +1025,8 @@ async fn test_version_namespace_inheritance() {
         registry: TypeRegistry::new(),
         resolved_errors: Default::default(),
         resolved_versions: Default::default(),
+        resolved_constants: Default::default(),
+        source_map: Default::default(),
         resolved_aliases: Default::default(),
     };
 
@@ -1165,6 +1167,8 @@ async fn test_error_namespace_inheritance() {
         registry: TypeRegistry::new(),
         resolved_errors: Default::default(),
         resolved_versions: Default::default(),
+        resolved_constants: Default::default(),
+        source_map: Default::default(),
         resolved_aliases: Default::default(),
     };
 
@@ -1235,7 +1239,7 @@ async fn test_error_non_fallible_operation_no_error_needed() {
         .expect("Should succeed for non-fallible operation");
 
     // Non-fallible operation should not have error in resolution
-    assert!(resolution.errors.get("get_user").is_none());
+    assert!(!resolution.errors.contains_key("get_user"));
 }
 
 #[tokio::test]
@@ -29,6 +29,7 @@ impl TypeResolver {
     /// namespace-to-type inheritance per SPEC-0016 Phase 4.
     ///
     /// **Spec references:** RFC-0017, SPEC-0016 Phases 2-5
+    #[tracing::instrument(level = "debug", skip_all)]
     pub(super) async fn validate_tagging(&mut self) -> crate::Result<()> {
         tracing::debug!("validate_tagging: starting phase 4.5");
 
@@ -313,10 +314,10 @@ impl TypeResolver {
                     Self::check_internal_tag_field_conflict(name, &variant.value)?;
                 }
             },
-            TagStyle::TypeHint
-            | TagStyle::External
-            | TagStyle::Untagged
-            | TagStyle::Index { .. } => {},
+            TagStyle::Untagged => {
+                Self::validate_untagged_distinguishability(&error_def.variants.values)?;
+            },
+            TagStyle::TypeHint | TagStyle::External | TagStyle::Index { .. } => {},
         }
         Ok(())
     }
@@ -14,6 +14,8 @@
 
 use std::collections::{BTreeMap, HashSet};
 
+use tracing::Instrument;
+
 use crate::{
     Token,
     ast::{
@@ -46,6 +48,7 @@ impl TypeResolver {
     ///
     /// Fully resolves type expression operators per RFC-0018, SPEC-0017, TSY-0014.
     /// Type expressions are evaluated at compile time.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub(super) async fn resolve_type_expressions(&mut self) -> crate::Result<()> {
         tracing::debug!("resolve_type_expressions: starting phase 3.6");
 
@@ -98,6 +101,7 @@ impl TypeResolver {
             // Resolve the type expression with source context for errors
             let resolved_type = self
                 .resolve_type_expr_in_type(&type_spanned.value, &ns)
+                .instrument(tracing::debug_span!("resolve_type_expr", alias = %alias_name))
                 .await
                 .map_err(|e| {
                     if let Some(source) = &source_content {
@@ -213,6 +217,7 @@ impl TypeResolver {
     }
 
     /// Resolve a type expression operator
+    #[tracing::instrument(level = "debug", skip_all)]
     async fn resolve_type_expr_op(
         &self,
         op: &TypeExprOp,
@@ -394,6 +399,7 @@ impl TypeResolver {
                 RepeatedItem {
                     value: Spanned::call_site(Arg {
                         comments: arg.value.comments.clone(),
+                        meta: arg.value.meta.clone(),
                         name: arg.value.name.clone(),
                         sep: new_sep,
                         typ: arg.value.typ.clone(),
@@ -458,6 +464,7 @@ impl TypeResolver {
                 RepeatedItem {
                     value: Spanned::call_site(Arg {
                         comments: arg.value.comments.clone(),
+                        meta: arg.value.meta.clone(),
                         name: arg.value.name.clone(),
                         sep: new_sep,
                         typ: arg.value.typ.clone(),
@@ -54,6 +54,7 @@ impl TypeResolver {
     ///
     /// Converts `A &| B` compositions into merged structs with oneof for conflicts.
     /// Per RFC-0016: non-conflicting fields pass through, conflicts become oneof.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub(super) async fn resolve_union_or(&mut self) -> crate::Result<()> {
         tracing::debug!("resolve_union_or: starting phase 3.5");
 
@@ -95,9 +96,16 @@ impl TypeResolver {
             drop(ns);
 
             // Validate and merge operands
+            let mut warnings = vec![];
             let merged_type = self
-                .merge_union_or_operands(&operands, &source_path, source_content.as_ref())
+                .merge_union_or_operands(
+                    &operands,
+                    &source_path,
+                    source_content.as_ref(),
+                    &mut warnings,
+                )
                 .await?;
+            self.resolution.warnings.extend(warnings);
 
             // Store the resolved type
             self.resolution
@@ -109,12 +117,14 @@ impl TypeResolver {
         Ok(())
     }
 
-    /// Merge operands from a union or expression into a single type
+    /// Merge operands from a union or expression into a single type, adding a warning for
+    /// each field shadowed or in conflict
     async fn merge_union_or_operands(
         &self,
         operands: &[Spanned<Type>],
         source_path: &std::path::Path,
         source_content: Option<&std::sync::Arc<String>>,
+        warnings: &mut Vec<crate::CompilerError>,
     ) -> crate::Result<Type> {
         let ns = self.namespace.lock().await;
 
@@ -166,7 +176,7 @@ impl TypeResolver {
                                 warning = warning
                                     .with_source_arc(source_path.to_path_buf(), content.clone());
                             }
-                            kintsu_events::emit_warning(warning);
+                            warnings.push(warning);
                         } else {
                             let mut warning: kintsu_errors::CompilerError =
                                 crate::UnionError::field_conflict(
@@ -180,7 +190,7 @@ impl TypeResolver {
                                 warning = warning
                                     .with_source_arc(source_path.to_path_buf(), content.clone());
                             }
-                            kintsu_events::emit_warning(warning);
+                            warnings.push(warning);
                         }
 
                         existing.types.push(arg.value.typ.clone());
@@ -209,6 +219,7 @@ impl TypeResolver {
                 RepeatedItem {
                     value: Spanned::call_site(Arg {
                         comments: CommentStream::default(),
+                        meta: field.arg.value.meta.clone(),
                         name: field.arg.value.name.clone(),
                         sep: field.arg.value.sep.clone(),
                         typ: final_type,
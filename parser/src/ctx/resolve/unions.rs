@@ -33,6 +33,7 @@ pub(super) fn identify_from_child(
                     context_stack: name_gen.stack.clone(),
                     in_oneof: false,
                     variant_index: None,
+                    vis: None,
                 };
                 Ok(vec![record.with_source(source)])
             } else {
@@ -172,6 +173,7 @@ fn identify_from_type(
                 context_stack: name_gen.stack.clone(),
                 in_oneof,
                 variant_index,
+                vis: None,
             };
             unions.push(record.with_source(source.clone()));
         },
@@ -434,6 +436,19 @@ fn validate_union_operand<'a>(
                                 source_content.cloned(),
                             ))
                         },
+                        NamespaceChild::Const(_) => {
+                            let err: crate::Error = crate::UnionError::non_struct_operand(
+                                ident_name,
+                                "const".to_string(),
+                            )
+                            .at(ident_span)
+                            .build()
+                            .into();
+                            Err(err.with_source_arc_if(
+                                source_path.to_path_buf(),
+                                source_content.cloned(),
+                            ))
+                        },
                         NamespaceChild::Namespace(_) => {
                             let err: crate::Error = crate::UnionError::non_struct_operand(
                                 ident_name,
@@ -547,6 +562,20 @@ fn validate_resolved_type<'a>(
     })
 }
 
+/// The source of one operand of a union, e.g. `User` in `User & Permissions`.
+pub(super) fn operand_span(operand: &IdentOrUnion) -> crate::defs::Span {
+    match operand {
+        IdentOrUnion::Ident(UnionDiscriminant::Ref(reference)) => {
+            let span = reference.span();
+            crate::defs::Span::new(span.start, span.end)
+        },
+        IdentOrUnion::Ident(UnionDiscriminant::Anonymous(anonymous)) => {
+            anonymous.brace.span().clone()
+        },
+        IdentOrUnion::Union { paren, .. } => paren.span().clone(),
+    }
+}
+
 pub(super) async fn merge_union(
     union_record: &UnionRecord,
     ns: &super::super::NamespaceCtx,
@@ -580,8 +609,9 @@ pub(super) async fn merge_union(
     let generated_name = union_record.generate_name();
     let source = source_path.to_path_buf();
 
-    let merged_struct =
+    let mut merged_struct =
         working_set.into_struct_def(generated_name.clone(), source, Brace::call_site());
+    merged_struct.value.vis = union_record.vis.clone();
 
     tracing::debug!("merge_union: generated struct '{}'", generated_name);
 
@@ -1,22 +1,32 @@
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use crate::{
-    ast::{ty::Type, variadic::Variant},
+    ast::{
+        constant::ConstLiteral,
+        enm::{Enum, TypedEnum},
+        ty::Type,
+        variadic::Variant,
+    },
     defs::Spanned,
-    tokens::ToTokens,
+    tokens::{Parse, Peek, ToTokens},
 };
 
 use super::TypeResolver;
 
 impl TypeResolver {
+    #[tracing::instrument(level = "debug", skip_all)]
     pub(super) async fn validate_all_references(&mut self) -> crate::Result<()> {
         tracing::debug!("validate_all_references: starting phase 8");
 
         let ns = self.namespace.lock().await;
 
-        for child in ns.children.values() {
+        Self::validate_import_visibility(&ns)?;
+
+        for (item_ctx, child) in &ns.children {
             let source_path = child.source.clone();
             let source_content = ns.sources.get(&source_path).cloned();
+            let public_item =
+                (!child.value.is_internal()).then(|| item_ctx.name.borrow_string().as_str());
 
             match &child.value {
                 super::super::NamespaceChild::Struct(struct_item) => {
@@ -32,6 +42,7 @@ impl TypeResolver {
                         Self::validate_type_reference(
                             &field.value.typ,
                             &ns,
+                            public_item,
                             &source_path,
                             &source_content,
                         )?;
@@ -43,6 +54,7 @@ impl TypeResolver {
                             Self::validate_type_reference(
                                 &param.value.typ,
                                 &ns,
+                                public_item,
                                 &source_path,
                                 &source_content,
                             )?;
@@ -51,10 +63,14 @@ impl TypeResolver {
                     Self::validate_type_reference(
                         &op_item.def.value.return_type,
                         &ns,
+                        public_item,
                         &source_path,
                         &source_content,
                     )?;
                 },
+                super::super::NamespaceChild::Enum(enum_item) => {
+                    Self::validate_enum_alias_collisions(enum_item, &source_path, &source_content)?;
+                },
                 super::super::NamespaceChild::OneOf(oneof_item) => {
                     for variant in &oneof_item.def.value.variants.values {
                         match &variant.value.value {
@@ -62,6 +78,7 @@ impl TypeResolver {
                                 Self::validate_type_reference(
                                     inner,
                                     &ns,
+                                    public_item,
                                     &source_path,
                                     &source_content,
                                 )?;
@@ -71,6 +88,7 @@ impl TypeResolver {
                                     Self::validate_type_reference(
                                         &field.value.typ,
                                         &ns,
+                                        public_item,
                                         &source_path,
                                         &source_content,
                                     )?;
@@ -89,6 +107,7 @@ impl TypeResolver {
                                 Self::validate_type_reference(
                                     inner,
                                     &ns,
+                                    public_item,
                                     &source_path,
                                     &source_content,
                                 )?;
@@ -98,6 +117,7 @@ impl TypeResolver {
                                     Self::validate_type_reference(
                                         &field.value.typ,
                                         &ns,
+                                        public_item,
                                         &source_path,
                                         &source_content,
                                     )?;
@@ -156,14 +176,114 @@ impl TypeResolver {
         Ok(())
     }
 
+    /// Validates that enum aliases differ from every variant value and from each other.
+    /// Variants without a value take 0 or the empty string, as in declarations.
+    fn validate_enum_alias_collisions(
+        enum_item: &crate::ast::items::EnumDef,
+        source_path: &PathBuf,
+        source_content: &Option<Arc<String>>,
+    ) -> crate::Result<()> {
+        let (enum_name, values) = match &enum_item.def.value {
+            Enum::Int(typed) => {
+                (
+                    typed.name.borrow_string(),
+                    Self::enum_values(
+                        typed,
+                        |v| ConstLiteral::Int(*v.borrow_i32() as i64),
+                        ConstLiteral::Int(0),
+                    ),
+                )
+            },
+            Enum::Str(typed) => {
+                (
+                    typed.name.borrow_string(),
+                    Self::enum_values(
+                        typed,
+                        |v| ConstLiteral::Str(v.borrow_string().clone()),
+                        ConstLiteral::Str(String::new()),
+                    ),
+                )
+            },
+        };
+
+        let mut seen: HashMap<String, (&str, Option<crate::Span>)> = HashMap::new();
+        for (variant, value, _) in values
+            .iter()
+            .filter(|(_, _, alias)| alias.is_none())
+        {
+            seen.entry(value.to_string())
+                .or_insert((variant, None));
+        }
+
+        for (variant, value, alias) in &values {
+            let Some(span) = alias else {
+                continue;
+            };
+            let key = value.to_string();
+            if let Some((other, first_span)) = seen.get(&key) {
+                let mut err = crate::TypeDefError::duplicate_enum_value(
+                    enum_name.clone(),
+                    *variant,
+                    *other,
+                    key.clone(),
+                )
+                .at(*span)
+                .build();
+                if let Some(first_span) = first_span {
+                    err = err.with_secondary_label(*first_span, "first used here");
+                }
+
+                return if let Some(source) = source_content {
+                    Err(err
+                        .with_source_arc(source_path.clone(), Arc::clone(source))
+                        .into())
+                } else {
+                    Err(err.into())
+                };
+            }
+            seen.insert(key, (variant, Some(*span)));
+        }
+        Ok(())
+    }
+
+    /// Every value of an enum as `(variant, value, alias span)`, variant values first.
+    pub(super) fn enum_values<V: Parse + Peek>(
+        typed: &TypedEnum<V>,
+        literal: impl Fn(&V) -> ConstLiteral,
+        default: ConstLiteral,
+    ) -> Vec<(&str, ConstLiteral, Option<crate::Span>)> {
+        let mut values = Vec::new();
+        for variant in &typed.variants.values {
+            let variant = &variant.value;
+            let Some(value) = variant.enum_value() else {
+                values.push((variant.name(), default.clone(), None));
+                continue;
+            };
+            values.push((variant.name(), literal(value.inner()), None));
+            for alias in &value.aliases {
+                values.push((
+                    variant.name(),
+                    alias.value.literal(),
+                    Some(alias.value.span()),
+                ));
+            }
+        }
+        values
+    }
+
+    /// `public_item` names the enclosing item when it is public, so references to internal
+    /// types of this package can be reported as leaking out of its declarations.
     fn validate_type_reference(
         ty: &Type,
         ns: &super::super::NamespaceCtx,
+        public_item: Option<&str>,
         source_path: &PathBuf,
         source_content: &Option<Arc<String>>,
     ) -> crate::Result<()> {
         match ty {
             Type::Ident { to } => {
+                Self::validate_glob_ambiguity(to, ns, source_path, source_content)?;
+                Self::validate_visibility(to, ns, public_item, source_path, source_content)?;
                 if !ns.registry.is_valid(&ns.ctx, to, ns) {
                     let type_name = match to {
                         crate::ast::ty::PathOrIdent::Ident(name_token) => {
@@ -177,9 +297,12 @@ impl TypeResolver {
 
                     tracing::error!("Undefined type reference: {}", type_name);
 
-                    let err = crate::ResolutionError::undefined_type(type_name)
+                    let mut err = crate::ResolutionError::undefined_type(type_name)
                         .at(span)
                         .build();
+                    if let Some(suggestion) = Self::suggest_type_name(to, ns) {
+                        err = err.with_suggestion(suggestion);
+                    }
                     return if let Some(source) = source_content {
                         Err(err
                             .with_source_arc(source_path.clone(), Arc::clone(source))
@@ -191,11 +314,26 @@ impl TypeResolver {
             },
             Type::Array { ty } => {
                 match &ty.value {
-                    crate::ast::array::Array::Sized { ty: inner, .. } => {
-                        Self::validate_type_reference(inner, ns, source_path, source_content)?;
+                    crate::ast::array::Array::Sized {
+                        ty: inner, size, ..
+                    } => {
+                        super::constants::array_len(ns, size, source_path)?;
+                        Self::validate_type_reference(
+                            inner,
+                            ns,
+                            public_item,
+                            source_path,
+                            source_content,
+                        )?;
                     },
                     crate::ast::array::Array::Unsized { ty: inner, .. } => {
-                        Self::validate_type_reference(inner, ns, source_path, source_content)?;
+                        Self::validate_type_reference(
+                            inner,
+                            ns,
+                            public_item,
+                            source_path,
+                            source_content,
+                        )?;
                     },
                 }
             },
@@ -206,6 +344,19 @@ impl TypeResolver {
                             // Validate the identifier reference
                             match union_disc {
                                 crate::ast::union::UnionDiscriminant::Ref(path_or_ident) => {
+                                    Self::validate_glob_ambiguity(
+                                        path_or_ident,
+                                        ns,
+                                        source_path,
+                                        source_content,
+                                    )?;
+                                    Self::validate_visibility(
+                                        path_or_ident,
+                                        ns,
+                                        public_item,
+                                        source_path,
+                                        source_content,
+                                    )?;
                                     if !ns
                                         .registry
                                         .is_valid(&ns.ctx, path_or_ident, ns)
@@ -244,6 +395,7 @@ impl TypeResolver {
                                         Self::validate_type_reference(
                                             &field.value.typ,
                                             ns,
+                                            public_item,
                                             source_path,
                                             source_content,
                                         )?;
@@ -255,6 +407,18 @@ impl TypeResolver {
                             for nested_item in &inner.value.types.values {
                                 match &nested_item.value.value {
                                     crate::ast::union::IdentOrUnion::Ident(nested_disc) => {
+                                        if let crate::ast::union::UnionDiscriminant::Ref(
+                                            nested_ref,
+                                        ) = nested_disc
+                                        {
+                                            Self::validate_visibility(
+                                                nested_ref,
+                                                ns,
+                                                public_item,
+                                                source_path,
+                                                source_content,
+                                            )?;
+                                        }
                                         if let crate::ast::union::UnionDiscriminant::Ref(nested_ref) =
                                             nested_disc
                                             && !ns.registry.is_valid(&ns.ctx, nested_ref, ns)
@@ -296,16 +460,29 @@ impl TypeResolver {
                 }
             },
             Type::Paren { ty, .. } => {
-                Self::validate_type_reference(&ty.value, ns, source_path, source_content)?;
+                Self::validate_type_reference(
+                    &ty.value,
+                    ns,
+                    public_item,
+                    source_path,
+                    source_content,
+                )?;
             },
             Type::Result { ty, .. } => {
-                Self::validate_type_reference(&ty.value, ns, source_path, source_content)?;
+                Self::validate_type_reference(
+                    &ty.value,
+                    ns,
+                    public_item,
+                    source_path,
+                    source_content,
+                )?;
             },
             Type::Struct { ty } => {
                 for field in &ty.value.fields.values {
                     Self::validate_type_reference(
                         &field.value.typ,
                         ns,
+                        public_item,
                         source_path,
                         source_content,
                     )?;
@@ -313,12 +490,30 @@ impl TypeResolver {
             },
             Type::OneOf { ty } => {
                 for variant in &ty.value.variants.values {
-                    Self::validate_type_reference(&variant.value, ns, source_path, source_content)?;
+                    Self::validate_type_reference(
+                        &variant.value,
+                        ns,
+                        public_item,
+                        source_path,
+                        source_content,
+                    )?;
                 }
             },
             Type::UnionOr { lhs, rhs, .. } => {
-                Self::validate_type_reference(&lhs.value, ns, source_path, source_content)?;
-                Self::validate_type_reference(&rhs.value, ns, source_path, source_content)?;
+                Self::validate_type_reference(
+                    &lhs.value,
+                    ns,
+                    public_item,
+                    source_path,
+                    source_content,
+                )?;
+                Self::validate_type_reference(
+                    &rhs.value,
+                    ns,
+                    public_item,
+                    source_path,
+                    source_content,
+                )?;
             },
             Type::TypeExpr { expr } => {
                 // Type expressions are validated during Phase 3.6 resolution
@@ -332,6 +527,160 @@ impl TypeResolver {
         Ok(())
     }
 
+    /// Per ERR-0006: KTR3001 when two glob imports export the referenced name.
+    fn validate_glob_ambiguity(
+        reference: &crate::ast::ty::PathOrIdent,
+        ns: &super::super::NamespaceCtx,
+        source_path: &PathBuf,
+        source_content: &Option<Arc<String>>,
+    ) -> crate::Result<()> {
+        let conflicts = ns
+            .registry
+            .glob_conflicts(&ns.ctx, reference, ns);
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+
+        let err = crate::ResolutionError::ambiguous_glob_import(
+            reference.display(),
+            conflicts.iter().map(|c| c.display()),
+        )
+        .at(reference.span())
+        .build();
+
+        if let Some(source) = source_content {
+            Err(err
+                .with_source_arc(source_path.clone(), Arc::clone(source))
+                .into())
+        } else {
+            Err(err.into())
+        }
+    }
+
+    /// Suggests the closest type name visible in `ns` for an undefined bare identifier.
+    fn suggest_type_name(
+        reference: &crate::ast::ty::PathOrIdent,
+        ns: &super::super::NamespaceCtx,
+    ) -> Option<crate::Suggestion> {
+        use super::super::{NamespaceChild, RefOrItemContext};
+
+        let crate::ast::ty::PathOrIdent::Ident(name) = reference else {
+            return None;
+        };
+
+        let mut candidates: Vec<String> = ns
+            .children
+            .iter()
+            .filter(|(_, child)| {
+                !matches!(
+                    child.value,
+                    NamespaceChild::Namespace(_)
+                        | NamespaceChild::Operation(_)
+                        | NamespaceChild::Const(_)
+                )
+            })
+            .map(|(item, _)| item.name.borrow_string().clone())
+            .collect();
+
+        for import in &ns.imports {
+            match &import.value {
+                RefOrItemContext::Item(item) => candidates.push(item.name.borrow_string().clone()),
+                RefOrItemContext::Glob(glob) => {
+                    candidates.extend(
+                        ns.registry
+                            .all_types()
+                            .into_iter()
+                            .filter(|(item, ..)| &item.context == glob)
+                            .map(|(item, ..)| item.name.borrow_string().clone()),
+                    );
+                },
+                RefOrItemContext::Ref(_) => {},
+            }
+        }
+
+        let name = name.value.borrow_string();
+        let (replacement, unique) =
+            kintsu_errors::closest_match(name, candidates.iter().map(String::as_str))?;
+
+        Some(crate::Suggestion::new(
+            reference.span(),
+            replacement,
+            if unique {
+                crate::Applicability::MachineApplicable
+            } else {
+                crate::Applicability::MaybeIncorrect
+            },
+            format!("a type with a similar name exists: `{replacement}`"),
+        ))
+    }
+
+    /// Per ERR-0006: KTR1004 when a reference reaches an internal item of another package,
+    /// KTR1005 when a public item references an internal type of its own package.
+    fn validate_visibility(
+        reference: &crate::ast::ty::PathOrIdent,
+        ns: &super::super::NamespaceCtx,
+        public_item: Option<&str>,
+        source_path: &PathBuf,
+        source_content: &Option<Arc<String>>,
+    ) -> crate::Result<()> {
+        let Some(resolved) = ns.registry.resolve(&ns.ctx, reference, ns) else {
+            return Ok(());
+        };
+        let resolved = &resolved.value.value;
+        if !resolved.kind.is_internal() {
+            return Ok(());
+        }
+
+        let package = &resolved.qualified_path.context.package;
+        let err = if *package != ns.ctx.package {
+            crate::ResolutionError::internal_item(reference.display(), package)
+        } else if let Some(item) = public_item {
+            crate::ResolutionError::internal_type_exposed(item, reference.display())
+        } else {
+            return Ok(());
+        };
+
+        let err = err.at(reference.span()).build();
+        if let Some(source) = source_content {
+            Err(err
+                .with_source_arc(source_path.clone(), Arc::clone(source))
+                .into())
+        } else {
+            Err(err.into())
+        }
+    }
+
+    /// Per ERR-0006: KTR1004 for `use` statements naming an internal item of another package.
+    fn validate_import_visibility(ns: &super::super::NamespaceCtx) -> crate::Result<()> {
+        for import in &ns.imports {
+            let crate::ctx::RefOrItemContext::Item(item) = &import.value else {
+                continue;
+            };
+            if item.context.package == ns.ctx.package {
+                continue;
+            }
+            let Some(resolved) = ns.registry.get(item) else {
+                continue;
+            };
+            if !resolved.value.value.kind.is_internal() {
+                continue;
+            }
+
+            let span = item.name.span();
+            let err = crate::ResolutionError::internal_item(item.display(), &item.context.package)
+                .at(crate::Span::new(span.start, span.end))
+                .build();
+            return Err(match ns.sources.get(&import.source) {
+                Some(source) => {
+                    err.with_source_arc(import.source.clone(), Arc::clone(source))
+                        .into()
+                },
+                None => err.into(),
+            });
+        }
+        Ok(())
+    }
+
     fn validate_type_expr_references(
         expr: &crate::ast::type_expr::TypeExpr,
         ns: &super::super::NamespaceCtx,
@@ -341,6 +690,10 @@ impl TypeResolver {
         use crate::ast::type_expr::{TypeExpr, TypeExprOp};
         match expr {
             TypeExpr::TypeRef { reference } => {
+                Self::validate_glob_ambiguity(reference, ns, source_path, source_content)?;
+                // type expressions copy fields out of their target, so only the package
+                // boundary applies here
+                Self::validate_visibility(reference, ns, None, source_path, source_content)?;
                 if !ns.registry.is_valid(&ns.ctx, reference, ns) {
                     let type_name = match reference {
                         crate::ast::ty::PathOrIdent::Ident(name_token) => {
@@ -351,9 +704,12 @@ impl TypeResolver {
                         },
                     };
                     let span = reference.span();
-                    let err = crate::ResolutionError::undefined_type(type_name)
+                    let mut err = crate::ResolutionError::undefined_type(type_name)
                         .at(span)
                         .build();
+                    if let Some(suggestion) = Self::suggest_type_name(reference, ns) {
+                        err = err.with_suggestion(suggestion);
+                    }
                     return if let Some(source) = source_content {
                         Err(err
                             .with_source_arc(source_path.clone(), Arc::clone(source))
@@ -30,6 +30,8 @@ pub struct SchemaCtx {
     pub package: kintsu_manifests::package::PackageManifests,
     pub namespaces: BTreeMap<String, Arc<Mutex<NamespaceCtx>>>,
     pub registry: TypeRegistry,
+    /// Errors from files skipped because they failed to lex or parse
+    pub recovered_errors: Vec<crate::CompilerError>,
 }
 
 impl SchemaCtx {
@@ -131,6 +133,18 @@ impl SchemaCtx {
                 ItemMetaItem::Rename(_) => {
                     // Rename is only valid on variants, not at module level - skip
                 },
+                ItemMetaItem::Constraint(_)
+                | ItemMetaItem::Http(_)
+                | ItemMetaItem::Graphql(_)
+                | ItemMetaItem::Contiguous(_)
+                | ItemMetaItem::Example(_) => {
+                    return Err(crate::Error::Compiler(
+                        MetadataError::misplaced_attribute(meta_item.name(), "a namespace")
+                            .at(meta_item.span())
+                            .build()
+                            .with_source_arc(lib_path, lib_source),
+                    ));
+                },
             }
         }
 
@@ -177,6 +191,7 @@ impl SchemaCtx {
 
                     let ns = NamespaceDef {
                         meta: spanned_ns.meta,
+                        vis: None,
                         def: Namespace {
                             kw: Spanned::call_site(crate::tokens::KwNamespaceToken::new()),
                             name: Spanned::new(
@@ -230,6 +245,7 @@ impl SchemaCtx {
         }
 
         let schema_dir = root_path.join("schema");
+        let mut recovered_errors = Vec::new();
 
         for (use_name, use_span) in use_statements {
             let ctx = root_ctx.enter(&use_name);
@@ -254,9 +270,21 @@ impl SchemaCtx {
                 ));
             }
 
-            let ns_ctx = NamespaceCtx::load_files(ctx, fs, &files_to_load, None, registry.clone())
-                .await
-                .map_err(|e| e.with_source(lib_path.clone(), Arc::clone(&lib_source)))?;
+            let Some(ns_ctx) = NamespaceCtx::load_files(
+                ctx,
+                fs,
+                &files_to_load,
+                None,
+                registry.clone(),
+                &mut recovered_errors,
+            )
+            .await
+            .map_err(|e| e.with_source(lib_path.clone(), Arc::clone(&lib_source)))?
+            else {
+                // every file failed to parse; references into this namespace surface as
+                // follow-on errors during resolution
+                continue;
+            };
 
             let ns_name = ns_ctx
                 .namespace
@@ -318,6 +346,7 @@ impl SchemaCtx {
             package,
             namespaces,
             registry,
+            recovered_errors,
         })
     }
 
@@ -0,0 +1,120 @@
+//! Where synthesized items come from.
+//!
+//! Anonymous structs lifted into named structs, local struct variants, and unions merged into
+//! structs have no declaration of their own, so their definitions are spanned at
+//! [`Span::CallSite`]. The [`SourceMap`] of a namespace records the expression each was
+//! generated from, so errors on a generated item, e.g. the `RequestAuth` merged from
+//! `User & Permissions`, point at that expression.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::defs::Span;
+
+/// How an item was synthesized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OriginKind {
+    /// An anonymous struct, e.g. `{ id: i64 }`, lifted into a named struct
+    AnonymousStruct,
+    /// A struct variant of a oneof or error, lifted into `{Parent}{Variant}`
+    LocalStructVariant,
+    /// A union, e.g. `User & Permissions`, merged into a struct
+    UnionMerge,
+}
+
+/// The expression a synthesized item was generated from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    pub kind: OriginKind,
+    pub source: PathBuf,
+    pub span: Span,
+    /// The operands of a merged union, e.g. `User` and `Permissions`
+    pub parts: Vec<Span>,
+}
+
+impl Origin {
+    pub fn new(
+        kind: OriginKind,
+        source: PathBuf,
+        span: Span,
+    ) -> Self {
+        Self {
+            kind,
+            source,
+            span,
+            parts: Vec::new(),
+        }
+    }
+
+    pub fn with_parts(
+        mut self,
+        parts: impl IntoIterator<Item = Span>,
+    ) -> Self {
+        self.parts.extend(parts);
+        self
+    }
+
+    pub fn error_span(&self) -> crate::Span {
+        let raw = self.span.span();
+        crate::Span::new(raw.start, raw.end)
+    }
+}
+
+/// Origins of the synthesized items of a namespace, by item name.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    origins: BTreeMap<String, Origin>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(
+        &mut self,
+        name: impl Into<String>,
+        origin: Origin,
+    ) {
+        self.origins.insert(name.into(), origin);
+    }
+
+    pub fn origin(
+        &self,
+        name: &str,
+    ) -> Option<&Origin> {
+        self.origins.get(name)
+    }
+
+    pub fn extend(
+        &mut self,
+        other: SourceMap,
+    ) {
+        self.origins.extend(other.origins);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.origins.is_empty()
+    }
+
+    /// Where errors on the item `name`, declared at `span`, are reported: the expression it
+    /// was generated from, if it was synthesized.
+    pub fn locate(
+        &self,
+        name: &str,
+        span: &Span,
+    ) -> Span {
+        self.origin(name)
+            .map_or_else(|| span.clone(), |origin| origin.span.clone())
+    }
+
+    /// [`locate`](Self::locate), as the span of an error.
+    pub fn error_span(
+        &self,
+        name: &str,
+        span: &Span,
+    ) -> crate::Span {
+        let raw = self.locate(name, span);
+        let raw = raw.span();
+        crate::Span::new(raw.start, raw.end)
+    }
+}
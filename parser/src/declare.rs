@@ -1,22 +1,37 @@
 pub mod comments;
+pub mod constants;
+pub mod constraints;
 pub mod context;
+pub mod coverage;
 pub mod definitions;
+pub mod diff;
+#[cfg(feature = "binary-declarations")]
+pub mod encoding;
 pub mod enums;
 pub mod fields;
+pub mod index;
 pub mod meta;
+pub mod migrate;
 pub mod namespace;
+pub mod profile;
 pub mod root;
 pub mod types;
+pub mod values;
 
 mod convert;
+mod source;
 
-pub use comments::DeclComment;
+pub use comments::{DeclComment, doc_text};
+pub use constants::{DeclConst, DeclConstValue};
+pub use constraints::DeclConstraint;
 pub use context::{DeclNamedItemContext, DeclRefContext};
+pub use coverage::{Coverage, DocCoverage, NamespaceCoverage};
 pub use definitions::{
-    DeclEnumDef, DeclError, DeclOneOf, DeclOneOfVariant, DeclOperation, DeclStruct, DeclTypeAlias,
+    DeclEnumDef, DeclError, DeclExample, DeclGraphqlKind, DeclHttpBinding, DeclOneOf,
+    DeclOneOfVariant, DeclOperation, DeclStruct, DeclTagStyle, DeclTagging, DeclTypeAlias,
     TypeDefinition,
 };
-pub use enums::{DeclEnum, DeclEnumValueType, DeclIntVariant, DeclStringVariant};
+pub use enums::{DeclEnum, DeclEnumAlias, DeclEnumValueType, DeclIntVariant, DeclStringVariant};
 pub use fields::{DeclArg, DeclField};
 pub use meta::Meta;
 pub use namespace::DeclNamespace;
@@ -26,7 +41,12 @@ pub use types::{Builtin, DeclType};
 #[cfg_attr(feature = "db", derive(sea_orm::prelude::FromJsonQueryResult))]
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Clone)]
-#[serde(tag = "version", content = "declarations", rename_all = "lowercase")]
+#[serde(
+    tag = "version",
+    content = "declarations",
+    rename_all = "lowercase",
+    try_from = "migrate::StoredDeclarations"
+)]
 pub enum DeclarationVersion {
     V1(DeclarationBundle),
 }
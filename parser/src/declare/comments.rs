@@ -37,4 +37,32 @@ impl DeclComment {
     ) {
         self.comments.extend(other.comments);
     }
+
+    /// The documentation of the item: its `///` doc comments, or every comment when it has
+    /// none.
+    pub fn docs(&self) -> Vec<&str> {
+        let docs: Vec<_> = self
+            .comments
+            .iter()
+            .filter_map(|comment| doc_text(comment))
+            .collect();
+        if docs.is_empty() {
+            self.comments
+                .iter()
+                .map(String::as_str)
+                .collect()
+        } else {
+            docs
+        }
+    }
+}
+
+/// The text of a `///` doc comment, as held by its token without the leading `//`.
+/// `////` dividers are not doc comments.
+pub fn doc_text(comment: &str) -> Option<&str> {
+    let text = comment.strip_prefix('/')?;
+    if text.starts_with('/') {
+        return None;
+    }
+    Some(text.strip_prefix(' ').unwrap_or(text))
 }
@@ -0,0 +1,33 @@
+//! Constant declarations
+
+use serde::{Deserialize, Serialize};
+
+use super::{comments::DeclComment, types::Builtin};
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DeclConstValue {
+    Int(i64),
+    Str(String),
+}
+
+impl From<&crate::ast::constant::ConstLiteral> for DeclConstValue {
+    fn from(value: &crate::ast::constant::ConstLiteral) -> Self {
+        use crate::ast::constant::ConstLiteral;
+        match value {
+            ConstLiteral::Int(v) => Self::Int(*v),
+            ConstLiteral::Str(s) => Self::Str(s.clone()),
+        }
+    }
+}
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclConst {
+    pub name: String,
+    pub ty: Builtin,
+    pub value: DeclConstValue,
+    #[serde(default, skip_serializing_if = "DeclComment::is_empty")]
+    pub comments: DeclComment,
+}
@@ -0,0 +1,45 @@
+//! Validation constraint declarations
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::meta::ConstraintAttribute;
+
+/// A validation constraint attached to a field or operation argument.
+///
+/// Bounds are inclusive. Generators use these to emit validators.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeclConstraint {
+    Min {
+        value: i64,
+    },
+    Max {
+        value: i64,
+    },
+    Pattern {
+        value: String,
+    },
+    Len {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<u64>,
+    },
+}
+
+impl From<&ConstraintAttribute> for DeclConstraint {
+    fn from(value: &ConstraintAttribute) -> Self {
+        match value {
+            ConstraintAttribute::Min(v) => Self::Min { value: *v },
+            ConstraintAttribute::Max(v) => Self::Max { value: *v },
+            ConstraintAttribute::Pattern(p) => Self::Pattern { value: p.clone() },
+            ConstraintAttribute::Len { min, max } => {
+                Self::Len {
+                    min: *min,
+                    max: *max,
+                }
+            },
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use super::{
     DeclarationVersion,
     comments::DeclComment,
+    constants::DeclConst,
+    constraints::DeclConstraint,
     context::{DeclNamedItemContext, DeclRefContext},
     definitions::{
-        DeclEnumDef, DeclError, DeclOneOf, DeclOneOfVariant, DeclOperation, DeclStruct,
-        DeclTypeAlias, TypeDefinition,
+        DeclEnumDef, DeclError, DeclExample, DeclGraphqlKind, DeclHttpBinding, DeclOneOf,
+        DeclOneOfVariant, DeclOperation, DeclStruct, DeclTagging, DeclTypeAlias, TypeDefinition,
     },
-    enums::{DeclEnum, DeclIntVariant, DeclStringVariant},
+    enums::{DeclEnum, DeclEnumAlias, DeclIntVariant, DeclStringVariant},
     fields::{DeclArg, DeclField},
     meta::Meta,
     namespace::DeclNamespace,
@@ -21,14 +23,16 @@ use crate::{
     Token,
     ast::{
         comment::{CommentAst, CommentStream},
-        enm::Enum,
+        enm::{Enum, EnumValue},
+        items::OperationDef,
+        meta::{ExampleAttribute, ItemMeta, ItemMetaItem},
         strct::{Arg, Sep},
         ty::{PathOrIdent, Type as AstType},
         variadic::Variant,
     },
     ctx::{ResolvedType, *},
     defs::{Span, Spanned, Spans},
-    tokens::toks::IdentToken,
+    tokens::{Parse, toks::IdentToken},
 };
 
 fn extract_comments(comment_stream: &CommentStream) -> DeclComment {
@@ -46,20 +50,155 @@ fn extract_comments(comment_stream: &CommentStream) -> DeclComment {
     DeclComment::from_vec(comments)
 }
 
+fn extract_constraints(meta: &ItemMeta) -> Vec<DeclConstraint> {
+    meta.meta
+        .iter()
+        .filter_map(|item| {
+            match item {
+                ItemMetaItem::Constraint(c) => Some(DeclConstraint::from(&c.value)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Field and argument examples. Payloads were checked in the resolver, so parsing here
+/// cannot fail for a compiled schema.
+fn extract_value_examples(meta: &ItemMeta) -> Vec<serde_json::Value> {
+    meta.meta
+        .iter()
+        .filter_map(|item| {
+            match item {
+                ItemMetaItem::Example(example) => {
+                    match &example.value {
+                        ExampleAttribute::Value(payload) => serde_json::from_str(payload).ok(),
+                        ExampleAttribute::Call { .. } => None,
+                    }
+                },
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn extract_operation_examples(meta: &[&Spanned<ItemMeta>]) -> Vec<DeclExample> {
+    let parse = |payload: &Option<String>| {
+        payload
+            .as_deref()
+            .and_then(|payload| serde_json::from_str(payload).ok())
+    };
+
+    meta.iter()
+        .flat_map(|meta| &meta.value.meta)
+        .filter_map(|item| {
+            match item {
+                ItemMetaItem::Example(example) => {
+                    match &example.value {
+                        ExampleAttribute::Call { input, output } => {
+                            Some(DeclExample {
+                                input: parse(input),
+                                output: parse(output),
+                            })
+                        },
+                        ExampleAttribute::Value(..) => None,
+                    }
+                },
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn extract_http_binding(meta: &ItemMeta) -> Option<DeclHttpBinding> {
+    meta.meta.iter().find_map(|item| {
+        match item {
+            ItemMetaItem::Http(http) => {
+                Some(DeclHttpBinding {
+                    method: http
+                        .value
+                        .method
+                        .clone()?
+                        .to_ascii_uppercase(),
+                    path: http.value.path.clone()?,
+                    path_params: http.value.path_params().ok()?,
+                })
+            },
+            _ => None,
+        }
+    })
+}
+
+fn extract_graphql_kind(meta: &ItemMeta) -> Option<DeclGraphqlKind> {
+    meta.meta.iter().find_map(|item| {
+        match item {
+            ItemMetaItem::Graphql(graphql) => {
+                match graphql.value.kinds.as_slice() {
+                    [kind] => Some(kind.into()),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    })
+}
+
+/// The tagging of a oneof or error: its own `#[tag(...)]`, else that of its namespace.
+fn extract_tagging(
+    meta: &[&Spanned<ItemMeta>],
+    ns_ctx: &NamespaceCtx,
+) -> DeclTagging {
+    meta.iter()
+        .flat_map(|meta| &meta.value.meta)
+        .find_map(|item| {
+            match item {
+                ItemMetaItem::Tag(tag) => Some(DeclTagging::from(&tag.value)),
+                _ => None,
+            }
+        })
+        .or_else(|| {
+            ns_ctx
+                .tag
+                .as_ref()
+                .map(|tag| DeclTagging::from(&tag.value))
+        })
+        .unwrap_or_default()
+}
+
+fn extract_enum_aliases<V: Parse>(value: Option<&EnumValue<V>>) -> Vec<DeclEnumAlias> {
+    value
+        .map(|value| {
+            value
+                .aliases
+                .iter()
+                .map(|alias| alias.value.literal().into())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 impl CompileCtx {
-    async fn convert_schema_to_declaration(
+    /// Declarations of the public items of `schema`, and of its internal items too when
+    /// `include_internal` is set.
+    pub(crate) async fn convert_schema_to_declaration(
         schema: &SchemaCtx,
         registry: &crate::ctx::registry::TypeRegistry,
+        include_internal: bool,
     ) -> crate::Result<TypeRegistryDeclaration> {
-        let package_name = schema.package.package().name.clone();
+        let package = schema.package.package();
+        let package_name = package.name.clone();
         let mut external_refs = BTreeSet::new();
 
         let mut namespaces = BTreeMap::new();
         for ns_arc in schema.namespaces.values() {
             let ns_ctx = ns_arc.lock().await;
-            let decl_ns =
-                Self::convert_namespace(&ns_ctx, registry, &package_name, &mut external_refs)
-                    .await?;
+            let decl_ns = Self::convert_namespace(
+                &ns_ctx,
+                registry,
+                &package_name,
+                include_internal,
+                &mut external_refs,
+            )
+            .await?;
             let ns_name = decl_ns.name.clone();
             namespaces.insert(ns_name, decl_ns);
         }
@@ -68,12 +207,50 @@ impl CompileCtx {
         declaration.namespaces = namespaces;
         declaration.extend_refs(external_refs);
 
+        if package.embed_metadata {
+            declaration.metadata = package.metadata.clone();
+        }
+
         Ok(declaration)
     }
 
     pub async fn emit_declarations(&self) -> crate::Result<DeclarationVersion> {
-        let root_declaration =
-            Self::convert_schema_to_declaration(&self.root, &self.type_registry()).await?;
+        self.emit_declarations_with(None).await
+    }
+
+    /// Declarations of the surface the profile `name` of the root manifest selects.
+    /// Dependencies are emitted whole, as their profiles are their own.
+    pub async fn emit_profile_declarations(
+        &self,
+        name: &str,
+    ) -> crate::Result<DeclarationVersion> {
+        let Some(profile) = self.root.package.profiles().get(name) else {
+            return Err(crate::PackageError::unknown_profile(
+                name,
+                &self.root.package.package().name,
+            )
+            .unlocated()
+            .build()
+            .into());
+        };
+        self.emit_declarations_with(Some((name, profile)))
+            .await
+    }
+
+    async fn emit_declarations_with(
+        &self,
+        profile: Option<(&str, &kintsu_manifests::profiles::Profile)>,
+    ) -> crate::Result<DeclarationVersion> {
+        let include_internal = profile.is_some_and(|(_, profile)| profile.internal);
+        let mut root_declaration = Self::convert_schema_to_declaration(
+            &self.root,
+            &self.type_registry(),
+            include_internal,
+        )
+        .await?;
+        if let Some((name, profile)) = profile {
+            root_declaration.apply_profile(name, profile)?;
+        }
 
         let root_pkg_name = &root_declaration.package;
         let mut dependency_packages: BTreeSet<String> = BTreeSet::new();
@@ -90,7 +267,8 @@ impl CompileCtx {
         for pkg_name in dependency_packages {
             if let Some(dep_schema) = self.get_dependency(&pkg_name).await {
                 let dep_declaration =
-                    Self::convert_schema_to_declaration(&dep_schema, &self.type_registry()).await?;
+                    Self::convert_schema_to_declaration(&dep_schema, &self.type_registry(), false)
+                        .await?;
                 dependencies.insert(pkg_name, dep_declaration);
             }
         }
@@ -99,6 +277,7 @@ impl CompileCtx {
             root: root_declaration,
             dependencies,
         };
+        self.run_hooks(&bundle)?;
 
         Ok(DeclarationVersion::V1(bundle))
     }
@@ -107,6 +286,7 @@ impl CompileCtx {
         ns_ctx: &'a NamespaceCtx,
         registry: &'a crate::ctx::registry::TypeRegistry,
         root_package: &'a str,
+        include_internal: bool,
         external_refs: &'a mut BTreeSet<DeclNamedItemContext>,
     ) -> BoxFuture<'a, crate::Result<DeclNamespace>> {
         let root_package = root_package.to_case(Case::Snake);
@@ -131,10 +311,15 @@ impl CompileCtx {
                 .and_then(|e| Self::resolve_path_or_ident(e.error_name(), ns_ctx).ok());
 
             let mut types = Vec::new();
+            let mut constants = Vec::new();
             let mut nested_namespaces = BTreeMap::new();
 
             for (named_ctx, child) in &ns_ctx.children {
-                if named_ctx.context.package != root_package {
+                // internal items are only visible to the package that declares them, unless
+                // a profile emits them
+                if named_ctx.context.package != root_package
+                    || (child.value.is_internal() && !include_internal)
+                {
                     continue;
                 }
 
@@ -145,6 +330,7 @@ impl CompileCtx {
                                 nested_ns_ctx,
                                 registry,
                                 &root_package,
+                                include_internal,
                                 external_refs,
                             )
                             .await?,
@@ -152,6 +338,18 @@ impl CompileCtx {
                         let nested_name = nested_decl.name.clone();
                         nested_namespaces.insert(nested_name, nested_decl);
                     },
+                    NamespaceChild::Const(const_def) => {
+                        constants.push(Self::convert_const(const_def, ns_ctx)?);
+                    },
+                    // operations are not types, so they are never registered
+                    NamespaceChild::Operation(op_def) => {
+                        types.push(Self::convert_operation(
+                            named_ctx,
+                            op_def,
+                            ns_ctx,
+                            external_refs,
+                        )?);
+                    },
                     _ => {
                         let Some(resolved) = registry.get(named_ctx) else {
                             return Err(crate::InternalError::internal(format!(
@@ -180,6 +378,7 @@ impl CompileCtx {
                 version,
                 error,
                 types,
+                constants,
                 namespaces: nested_namespaces,
                 comments: namespace_comments,
             })
@@ -187,6 +386,33 @@ impl CompileCtx {
         .boxed()
     }
 
+    fn convert_const(
+        const_def: &crate::ast::items::ConstDef,
+        ns_ctx: &NamespaceCtx,
+    ) -> crate::Result<DeclConst> {
+        let name = const_def.def.name.borrow_string().clone();
+        let Some(value) = ns_ctx.resolved_constants.get(&name) else {
+            return Err(crate::InternalError::internal(format!(
+                "Constant '{name}' was not evaluated before declaration conversion. This is a compiler error."
+            ))
+            .unlocated()
+            .build()
+            .into());
+        };
+
+        let mut comments = DeclComment::new();
+        for comment_stream in const_def.comments() {
+            comments.merge(extract_comments(comment_stream));
+        }
+
+        Ok(DeclConst {
+            name,
+            ty: Builtin::from_ast_builtin(&const_def.def.ty.value),
+            value: value.into(),
+            comments,
+        })
+    }
+
     fn convert_type(
         named_ctx: &NamedItemContext,
         resolved: &ResolvedType,
@@ -253,6 +479,7 @@ impl CompileCtx {
                     variants,
                     meta,
                     comments: type_comments,
+                    tag: extract_tagging(&oneof_def.meta(), ns_ctx),
                 }))
             },
             Definition::TypeAlias(typedef) => {
@@ -280,6 +507,7 @@ impl CompileCtx {
                         variants,
                         meta,
                         comments: type_comments,
+                        tag: extract_tagging(&typedef.meta(), ns_ctx),
                     }));
                 }
 
@@ -338,33 +566,59 @@ impl CompileCtx {
                     variants,
                     meta,
                     comments: type_comments,
+                    tag: extract_tagging(&error_def.meta(), ns_ctx),
                 }))
             },
             Definition::Operation(op_def) => {
-                let args =
-                    Self::convert_operation_args(&op_def.def.value.args, ns_ctx, external_refs)?;
+                Self::convert_operation(named_ctx, op_def, ns_ctx, external_refs)
+            },
+        }
+    }
 
-                let return_type = Self::convert_operation_return_type(
-                    &op_def.def.value.return_type.value,
-                    &item_name,
-                    ns_ctx,
-                    external_refs,
-                )?;
+    fn convert_operation(
+        named_ctx: &NamedItemContext,
+        op_def: &OperationDef,
+        ns_ctx: &NamespaceCtx,
+        external_refs: &mut BTreeSet<DeclNamedItemContext>,
+    ) -> crate::Result<TypeDefinition> {
+        let item_name = named_ctx.name.borrow_string().clone();
 
-                let mut type_comments = DeclComment::new();
-                for comment_stream in op_def.comments() {
-                    type_comments.merge(extract_comments(comment_stream));
-                }
+        let meta = Meta::from_resolved_version(&item_name, &ns_ctx.resolved_versions)
+            .unwrap_or_else(|| Meta::new(1));
 
-                Ok(TypeDefinition::Operation(DeclOperation {
-                    name: item_name,
-                    args,
-                    return_type,
-                    meta,
-                    comments: type_comments,
-                }))
-            },
+        let args = Self::convert_operation_args(&op_def.def.value.args, ns_ctx, external_refs)?;
+
+        let return_type = Self::convert_operation_return_type(
+            &op_def.def.value.return_type.value,
+            &item_name,
+            ns_ctx,
+            external_refs,
+        )?;
+
+        let mut type_comments = DeclComment::new();
+        for comment_stream in op_def.comments() {
+            type_comments.merge(extract_comments(comment_stream));
         }
+
+        let http = op_def
+            .meta()
+            .iter()
+            .find_map(|meta| extract_http_binding(&meta.value));
+        let graphql = op_def
+            .meta()
+            .iter()
+            .find_map(|meta| extract_graphql_kind(&meta.value));
+
+        Ok(TypeDefinition::Operation(DeclOperation {
+            name: item_name,
+            args,
+            return_type,
+            meta,
+            comments: type_comments,
+            http,
+            graphql,
+            examples: extract_operation_examples(&op_def.meta()),
+        }))
     }
 
     fn convert_ast_type(
@@ -405,7 +659,11 @@ impl CompileCtx {
                     } => {
                         let element_type =
                             Self::convert_ast_type(&inner_ty.value, ns_ctx, external_refs)?;
-                        let size_value = *size.value.borrow_i32() as u64;
+                        let size_value = crate::ctx::resolve::constants::array_len(
+                            ns_ctx,
+                            size,
+                            &ns_ctx.namespace.source,
+                        )?;
                         Ok(DeclType::SizedArray {
                             element_type: Box::new(element_type),
                             size: size_value,
@@ -696,13 +954,35 @@ impl CompileCtx {
                                 return Ok(DeclNamedItemContext::from_named_item_context(item_ctx));
                             }
                         },
+                        crate::ctx::RefOrItemContext::Glob(_) => {},
                     }
                 }
 
                 let item_ctx = ns_ctx.ctx.item(ident.clone());
+                if ns_ctx.registry.get(&item_ctx).is_none()
+                    && let Some(glob_ctx) =
+                        crate::ctx::graph::extract::TypeExtractor::glob_candidates(ident, ns_ctx)
+                            .into_iter()
+                            .find(|candidate| ns_ctx.registry.get(candidate).is_some())
+                {
+                    return Ok(DeclNamedItemContext::from_named_item_context(&glob_ctx));
+                }
+
                 Ok(DeclNamedItemContext::from_named_item_context(&item_ctx))
             },
             PathOrIdent::Path(path) => {
+                // paths through imports, e.g. `data::Data` after `use pkg::data;`
+                if let Some(found) = crate::ctx::graph::extract::TypeExtractor::generate_candidates(
+                    path_or_ident,
+                    &ns_ctx.ctx,
+                    ns_ctx,
+                )
+                .into_iter()
+                .find(|candidate| ns_ctx.registry.get(candidate).is_some())
+                {
+                    return Ok(DeclNamedItemContext::from_named_item_context(&found));
+                }
+
                 let path_inner = path.value.borrow_path_inner();
                 let path_str = path_inner.to_string();
                 let parts: Vec<&str> = path_str.split("::").collect();
@@ -761,6 +1041,8 @@ impl CompileCtx {
                 default_value: None, // TODO: default values
                 optional: matches!(arg.value.sep.value, Sep::Optional { .. }),
                 comments: extract_comments(&arg.value.comments),
+                constraints: extract_constraints(&arg.value.meta),
+                examples: extract_value_examples(&arg.value.meta),
             });
         }
 
@@ -783,6 +1065,8 @@ impl CompileCtx {
                     ty: arg_ty,
                     default_value: None, // TODO: Extract default value
                     comments: extract_comments(&arg.value.comments),
+                    constraints: extract_constraints(&arg.value.meta),
+                    examples: extract_value_examples(&arg.value.meta),
                 });
             }
         }
@@ -805,6 +1089,7 @@ impl CompileCtx {
                         name: enum_variant.name().to_string(),
                         value,
                         comments: extract_comments(&enum_variant.comments.value),
+                        aliases: extract_enum_aliases(enum_variant.enum_value()),
                     });
                 }
                 Ok(DeclEnum::Int(variants))
@@ -822,6 +1107,7 @@ impl CompileCtx {
                         name: enum_variant.name().to_string(),
                         value,
                         comments: extract_comments(&enum_variant.comments.value),
+                        aliases: extract_enum_aliases(enum_variant.enum_value()),
                     });
                 }
                 Ok(DeclEnum::String(variants))
@@ -0,0 +1,338 @@
+//! How much of a package is documented: the share of its types, fields and operations which
+//! carry a `///` doc comment, per namespace. Internal items are not part of the declarations,
+//! so only what the package exposes is counted.
+
+use std::{collections::BTreeMap, fmt};
+
+use serde::Serialize;
+
+use super::{DeclComment, DeclNamespace, TypeDefinition, TypeRegistryDeclaration, doc_text};
+
+/// Whether `comments` hold a doc comment with any text.
+pub fn is_documented(comments: &DeclComment) -> bool {
+    comments
+        .comments
+        .iter()
+        .filter_map(|comment| doc_text(comment))
+        .any(|text| !text.trim().is_empty())
+}
+
+/// How many of a kind of item are documented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    pub documented: usize,
+    pub total: usize,
+}
+
+impl Coverage {
+    fn count(
+        &mut self,
+        comments: &DeclComment,
+    ) {
+        self.total += 1;
+        if is_documented(comments) {
+            self.documented += 1;
+        }
+    }
+
+    fn add(
+        &mut self,
+        other: Coverage,
+    ) {
+        self.documented += other.documented;
+        self.total += other.total;
+    }
+
+    /// The documented share, from 0 to 100. Nothing to document is fully documented.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.documented as f64 / self.total as f64 * 100.0
+        }
+    }
+}
+
+/// The documentation coverage of the items declared directly in a namespace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NamespaceCoverage {
+    /// Structs, enums, one ofs, aliases and errors
+    pub types: Coverage,
+    /// Fields, variants and operation arguments
+    pub fields: Coverage,
+    pub operations: Coverage,
+    /// Items without a doc comment, as `Type` or `Type.member`
+    pub undocumented: Vec<String>,
+}
+
+impl NamespaceCoverage {
+    /// The coverage of the items of `ns`, without the namespaces nested in it.
+    pub fn of(ns: &DeclNamespace) -> Self {
+        let mut coverage = Self::default();
+        for def in &ns.types {
+            let kind = match def {
+                TypeDefinition::Operation(_) => &mut coverage.operations,
+                _ => &mut coverage.types,
+            };
+            kind.count(def.comments());
+            if !is_documented(def.comments()) {
+                coverage
+                    .undocumented
+                    .push(def.name().to_string());
+            }
+
+            for (member, comments) in def.members() {
+                coverage.fields.count(comments);
+                if !is_documented(comments) {
+                    coverage
+                        .undocumented
+                        .push(format!("{}.{member}", def.name()));
+                }
+            }
+        }
+        coverage
+    }
+
+    /// The coverage of types, fields and operations together.
+    pub fn overall(&self) -> Coverage {
+        let mut overall = self.types;
+        overall.add(self.fields);
+        overall.add(self.operations);
+        overall
+    }
+}
+
+/// The documentation coverage of a package, per namespace.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocCoverage {
+    pub package: String,
+    /// Keyed by the path of the namespace, e.g. `shop::orders`
+    pub namespaces: BTreeMap<String, NamespaceCoverage>,
+}
+
+impl DocCoverage {
+    /// The coverage of every namespace together.
+    pub fn overall(&self) -> Coverage {
+        let mut overall = Coverage::default();
+        for ns in self.namespaces.values() {
+            overall.add(ns.overall());
+        }
+        overall
+    }
+
+    /// Paths of the namespaces documented below `min` percent.
+    pub fn below(
+        &self,
+        min: f64,
+    ) -> Vec<&str> {
+        self.namespaces
+            .iter()
+            .filter(|(_, ns)| ns.overall().percent() < min)
+            .map(|(path, _)| path.as_str())
+            .collect()
+    }
+}
+
+impl TypeRegistryDeclaration {
+    /// The documentation coverage of each namespace of the package, nested ones included.
+    pub fn doc_coverage(&self) -> DocCoverage {
+        fn visit(
+            path: &str,
+            ns: &DeclNamespace,
+            namespaces: &mut BTreeMap<String, NamespaceCoverage>,
+        ) {
+            namespaces.insert(path.to_string(), NamespaceCoverage::of(ns));
+            for child in ns.namespaces.values() {
+                visit(&format!("{path}::{}", child.name), child, namespaces);
+            }
+        }
+
+        let mut namespaces = BTreeMap::new();
+        for ns in self.namespaces.values() {
+            visit(&ns.name, ns, &mut namespaces);
+        }
+        DocCoverage {
+            package: self.package.clone(),
+            namespaces,
+        }
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{:>4}/{:<4} {:>5.1}%",
+            self.documented,
+            self.total,
+            self.percent()
+        )
+    }
+}
+
+impl fmt::Display for DocCoverage {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        writeln!(f, "documentation coverage for {}", self.package)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<28} {:>16} {:>16} {:>16} {:>16}",
+            "namespace", "types", "fields", "operations", "total"
+        )?;
+        for (path, ns) in &self.namespaces {
+            writeln!(
+                f,
+                "{:<28} {:>16} {:>16} {:>16} {:>16}",
+                path,
+                ns.types.to_string(),
+                ns.fields.to_string(),
+                ns.operations.to_string(),
+                ns.overall().to_string()
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, "overall: {}", self.overall().to_string().trim_start())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::declare::{Builtin, DeclArg, DeclField, DeclOperation, DeclStruct, DeclType, Meta};
+
+    fn documented(doc: Option<&str>) -> DeclComment {
+        DeclComment::from_vec(
+            doc.into_iter()
+                .map(|doc| format!("/ {doc}"))
+                .collect(),
+        )
+    }
+
+    fn field(
+        name: &str,
+        doc: Option<&str>,
+    ) -> DeclField {
+        DeclField {
+            name: name.into(),
+            ty: DeclType::Builtin { ty: Builtin::I64 },
+            default_value: None,
+            optional: false,
+            comments: documented(doc),
+            constraints: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn counts_documented_items_per_namespace() {
+        let user = TypeDefinition::Struct(DeclStruct {
+            name: "User".into(),
+            fields: vec![field("id", Some("The id.")), field("name", None)],
+            meta: Meta::new(1),
+            comments: documented(Some("A user.")),
+        });
+        let mut ns = DeclNamespace {
+            name: "users".into(),
+            version: None,
+            error: None,
+            types: vec![user],
+            constants: Vec::new(),
+            namespaces: Default::default(),
+            comments: Default::default(),
+        };
+        let mut admin = ns.clone();
+        admin.name = "admin".into();
+        admin.types = Vec::new();
+        ns.namespaces
+            .insert("admin".into(), Box::new(admin));
+
+        let mut decl = TypeRegistryDeclaration::new("shop".into());
+        decl.namespaces.insert("users".into(), ns);
+        let coverage = decl.doc_coverage();
+
+        let users = &coverage.namespaces["users"];
+        assert_eq!(
+            users.types,
+            Coverage {
+                documented: 1,
+                total: 1
+            }
+        );
+        assert_eq!(
+            users.fields,
+            Coverage {
+                documented: 1,
+                total: 2
+            }
+        );
+        assert_eq!(users.undocumented, vec!["User.name".to_string()]);
+        assert_eq!(
+            coverage.namespaces["users::admin"]
+                .overall()
+                .percent(),
+            100.0
+        );
+        assert_eq!(
+            coverage.overall(),
+            Coverage {
+                documented: 2,
+                total: 3
+            }
+        );
+        assert_eq!(coverage.below(80.0), vec!["users"]);
+    }
+
+    #[test]
+    fn counts_operations_and_their_arguments() {
+        let op = DeclOperation {
+            name: "get_user".into(),
+            args: vec![DeclArg {
+                name: "id".into(),
+                ty: DeclType::Builtin { ty: Builtin::I64 },
+                default_value: None,
+                comments: documented(Some("")),
+                constraints: Vec::new(),
+                examples: Vec::new(),
+            }],
+            return_type: DeclType::Builtin { ty: Builtin::I64 },
+            meta: Meta::new(1),
+            comments: documented(Some("Finds a user.")),
+            http: None,
+            graphql: None,
+            examples: Vec::new(),
+        };
+        let coverage = NamespaceCoverage::of(&DeclNamespace {
+            name: "users".into(),
+            version: None,
+            error: None,
+            types: vec![TypeDefinition::Operation(op)],
+            constants: Vec::new(),
+            namespaces: Default::default(),
+            comments: Default::default(),
+        });
+
+        assert_eq!(
+            coverage.operations,
+            Coverage {
+                documented: 1,
+                total: 1
+            }
+        );
+        assert_eq!(coverage.types.total, 0);
+        // an empty doc comment documents nothing
+        assert_eq!(
+            coverage.fields,
+            Coverage {
+                documented: 0,
+                total: 1
+            }
+        );
+        assert_eq!(coverage.undocumented, vec!["get_user.id".to_string()]);
+    }
+}
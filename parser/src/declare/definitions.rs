@@ -4,6 +4,8 @@ use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
+use crate::ast::meta::{GraphqlKind, TagAttribute, TagStyle};
+
 use super::{
     comments::DeclComment,
     context::DeclNamedItemContext,
@@ -50,6 +52,8 @@ pub struct DeclOneOf {
     pub meta: Meta,
     #[serde(default, skip_serializing_if = "DeclComment::is_empty")]
     pub comments: DeclComment,
+    #[serde(default, skip_serializing_if = "DeclTagging::is_default")]
+    pub tag: DeclTagging,
 }
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
@@ -70,6 +74,74 @@ pub struct DeclError {
     pub meta: Meta,
     #[serde(default, skip_serializing_if = "DeclComment::is_empty")]
     pub comments: DeclComment,
+    #[serde(default, skip_serializing_if = "DeclTagging::is_default")]
+    pub tag: DeclTagging,
+}
+
+/// How the variants of a oneof or error are told apart when serialized, resolved from the
+/// `#[tag(...)]` of the type or its namespace.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclTagging {
+    #[serde(flatten)]
+    pub style: DeclTagStyle,
+    /// Whether a `@kintsu` type hint field is added to each variant
+    pub type_hint: bool,
+}
+
+impl DeclTagging {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl Default for DeclTagging {
+    fn default() -> Self {
+        Self {
+            style: DeclTagStyle::TypeHint,
+            type_hint: true,
+        }
+    }
+}
+
+impl From<&TagAttribute> for DeclTagging {
+    fn from(value: &TagAttribute) -> Self {
+        let style = match &value.style {
+            TagStyle::TypeHint => DeclTagStyle::TypeHint,
+            TagStyle::External => DeclTagStyle::External,
+            TagStyle::Internal { name } => DeclTagStyle::Internal { tag: name.clone() },
+            TagStyle::Adjacent { name, content } => {
+                DeclTagStyle::Adjacent {
+                    tag: name.clone(),
+                    content: content.clone(),
+                }
+            },
+            TagStyle::Untagged => DeclTagStyle::Untagged,
+            TagStyle::Index { name } => DeclTagStyle::Index { tag: name.clone() },
+        };
+        Self {
+            style,
+            type_hint: value.type_hint,
+        }
+    }
+}
+
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "style", rename_all = "snake_case")]
+pub enum DeclTagStyle {
+    /// Untagged, with a `@kintsu` discriminator field
+    TypeHint,
+    /// `{ "variant": { ... } }`
+    External,
+    /// `{ "<tag>": "variant", ... }`
+    Internal { tag: String },
+    /// `{ "<tag>": "variant", "<content>": { ... } }`
+    Adjacent { tag: String, content: String },
+    /// The variant alone, told apart by its shape
+    Untagged,
+    /// `{ "<tag>": 0, ... }`, numbering variants in order
+    Index { tag: Option<String> },
 }
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
@@ -82,6 +154,58 @@ pub struct DeclOperation {
     pub meta: Meta,
     #[serde(default, skip_serializing_if = "DeclComment::is_empty")]
     pub comments: DeclComment,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<DeclHttpBinding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphql: Option<DeclGraphqlKind>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<DeclExample>,
+}
+
+/// Example call declared with `#[example(input = '...', output = '...')]` on an operation,
+/// checked against the operation's signature at compile time.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeclExample {
+    /// Arguments keyed by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "api", schema(value_type = Option<Object>))]
+    pub input: Option<serde_json::Value>,
+    /// Successful return value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "api", schema(value_type = Option<Object>))]
+    pub output: Option<serde_json::Value>,
+}
+
+/// GraphQL root type declared with `#[graphql(query)]` or `#[graphql(mutation)]` on an
+/// operation.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeclGraphqlKind {
+    Query,
+    Mutation,
+}
+
+impl From<&GraphqlKind> for DeclGraphqlKind {
+    fn from(value: &GraphqlKind) -> Self {
+        match value {
+            GraphqlKind::Query => Self::Query,
+            GraphqlKind::Mutation => Self::Mutation,
+        }
+    }
+}
+
+/// HTTP route declared with `#[http(method = "...", path = "...")]` on an operation.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeclHttpBinding {
+    /// Upper-case HTTP method, e.g. `GET`
+    pub method: String,
+    pub path: String,
+    /// Names of the `{param}` placeholders in `path`, each matching an operation argument
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_params: Vec<String>,
 }
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
@@ -119,44 +243,103 @@ impl TypeDefinition {
         }
     }
 
+    pub fn comments(&self) -> &DeclComment {
+        match self {
+            Self::Struct(s) => &s.comments,
+            Self::Enum(e) => &e.comments,
+            Self::OneOf(o) => &o.comments,
+            Self::TypeAlias(t) => &t.comments,
+            Self::Error(e) => &e.comments,
+            Self::Operation(o) => &o.comments,
+        }
+    }
+
+    /// The fields, variants or arguments of the definition, with their comments.
+    pub fn members(&self) -> Vec<(&str, &DeclComment)> {
+        match self {
+            Self::Struct(s) => {
+                s.fields
+                    .iter()
+                    .map(|field| (field.name.as_str(), &field.comments))
+                    .collect()
+            },
+            Self::Enum(e) => {
+                match &e.enum_def {
+                    DeclEnum::Int(variants) => {
+                        variants
+                            .iter()
+                            .map(|v| (v.name.as_str(), &v.comments))
+                            .collect()
+                    },
+                    DeclEnum::String(variants) => {
+                        variants
+                            .iter()
+                            .map(|v| (v.name.as_str(), &v.comments))
+                            .collect()
+                    },
+                }
+            },
+            Self::OneOf(o) => {
+                o.variants
+                    .iter()
+                    .map(|v| (v.name.as_str(), &v.comments))
+                    .collect()
+            },
+            Self::Error(e) => {
+                e.variants
+                    .iter()
+                    .map(|v| (v.name.as_str(), &v.comments))
+                    .collect()
+            },
+            Self::Operation(o) => {
+                o.args
+                    .iter()
+                    .map(|arg| (arg.name.as_str(), &arg.comments))
+                    .collect()
+            },
+            Self::TypeAlias(_) => Vec::new(),
+        }
+    }
+
     pub fn collect_external_refs(
         &self,
         root_package: &str,
         refs: &mut HashSet<DeclNamedItemContext>,
+    ) {
+        self.for_each_ref(&mut |reference| {
+            if reference.is_external(root_package) {
+                refs.insert(reference.clone());
+            }
+        });
+    }
+
+    /// Calls `f` with each declaration the types of this definition refer to.
+    pub fn for_each_ref(
+        &self,
+        f: &mut impl FnMut(&DeclNamedItemContext),
     ) {
         match self {
             Self::Struct(s) => {
                 for field in &s.fields {
-                    field
-                        .ty
-                        .collect_external_refs(root_package, refs);
+                    field.ty.for_each_ref(f);
                 }
             },
             Self::OneOf(o) => {
                 for variant in &o.variants {
-                    variant
-                        .ty
-                        .collect_external_refs(root_package, refs);
+                    variant.ty.for_each_ref(f);
                 }
             },
-            Self::TypeAlias(t) => {
-                t.target
-                    .collect_external_refs(root_package, refs);
-            },
+            Self::TypeAlias(t) => t.target.for_each_ref(f),
             Self::Error(e) => {
                 for variant in &e.variants {
-                    variant
-                        .ty
-                        .collect_external_refs(root_package, refs);
+                    variant.ty.for_each_ref(f);
                 }
             },
             Self::Operation(o) => {
                 for arg in &o.args {
-                    arg.ty
-                        .collect_external_refs(root_package, refs);
+                    arg.ty.for_each_ref(f);
                 }
-                o.return_type
-                    .collect_external_refs(root_package, refs);
+                o.return_type.for_each_ref(f);
             },
             Self::Enum(_) => {
                 // Enums have no type references
@@ -0,0 +1,662 @@
+//! Differences between the declarations of two versions of a package, used to warn when a
+//! release breaks items dependents may rely on and to suggest the version it needs.
+//!
+//! Items are compared by qualified name and then by what they declare: the fields of
+//! structs, the variants of enums, oneofs and errors, the targets of aliases and the
+//! arguments and return types of operations.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use super::{
+    DeclArg, DeclEnum, DeclField, DeclOneOfVariant, DeclType, DeclarationVersion, TypeDefinition,
+    index::DeclaredItem,
+};
+
+/// One difference between the declarations of an item in two versions of a package.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Change {
+    /// Qualified name of the item, e.g. `abc::v1::User`
+    pub item: String,
+    /// What changed, e.g. ``removes field `email` ``
+    pub change: String,
+    /// Whether dependents written against the previous version may no longer work
+    pub breaking: bool,
+}
+
+impl Display for Change {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{} {}", self.item, self.change)
+    }
+}
+
+/// Qualified names of the types and constants a package declares, e.g. `abc::v1::User`.
+/// Declarations of its dependencies are not included.
+pub fn declared_items(declarations: &DeclarationVersion) -> BTreeSet<String> {
+    super::index::index(declarations)
+        .iter()
+        .map(super::index::IndexedItem::qualified_name)
+        .collect()
+}
+
+/// Items `previous` declares which `next` does not, in name order.
+pub fn removed_items(
+    previous: &DeclarationVersion,
+    next: &DeclarationVersion,
+) -> Vec<String> {
+    let next = declared_items(next);
+    declared_items(previous)
+        .into_iter()
+        .filter(|item| !next.contains(item))
+        .collect()
+}
+
+/// Items `next` declares which `previous` does not, in name order.
+pub fn added_items(
+    previous: &DeclarationVersion,
+    next: &DeclarationVersion,
+) -> Vec<String> {
+    removed_items(next, previous)
+}
+
+/// Every difference between the items `previous` and `next` declare, in item order.
+pub fn changes(
+    previous: &DeclarationVersion,
+    next: &DeclarationVersion,
+) -> Vec<Change> {
+    let previous = items(previous);
+    let next = items(next);
+
+    let mut changes = vec![];
+    for (name, item) in &previous {
+        let mut diff = ItemDiff {
+            item: name,
+            changes: &mut changes,
+        };
+        match next.get(name) {
+            Some(next) => diff.item(item, next),
+            None => diff.breaking("is removed"),
+        }
+    }
+    for name in next
+        .keys()
+        .filter(|name| !previous.contains_key(*name))
+    {
+        ItemDiff {
+            item: name,
+            changes: &mut changes,
+        }
+        .additive("is added");
+    }
+
+    changes.sort();
+    changes
+}
+
+/// The [`changes`] from `previous` to `next` which may break dependents.
+pub fn breaking_changes(
+    previous: &DeclarationVersion,
+    next: &DeclarationVersion,
+) -> Vec<Change> {
+    changes(previous, next)
+        .into_iter()
+        .filter(|change| change.breaking)
+        .collect()
+}
+
+/// The smallest version bump after `version`, the version of `previous`, for a release
+/// declaring `next`: any breaking change needs a major release, any other a minor one.
+pub fn required_bump(
+    version: &kintsu_manifests::version::Version,
+    previous: &DeclarationVersion,
+    next: &DeclarationVersion,
+) -> kintsu_manifests::version::Bump {
+    let changes = changes(previous, next);
+    kintsu_manifests::version::Bump::required(
+        version,
+        changes.iter().any(|change| change.breaking),
+        !changes.is_empty(),
+    )
+}
+
+fn items(declarations: &DeclarationVersion) -> BTreeMap<String, DeclaredItem> {
+    super::index::index(declarations)
+        .into_iter()
+        .map(|indexed| (indexed.qualified_name(), indexed.item))
+        .collect()
+}
+
+/// Collects the changes to a single item.
+struct ItemDiff<'a> {
+    item: &'a str,
+    changes: &'a mut Vec<Change>,
+}
+
+impl ItemDiff<'_> {
+    fn push(
+        &mut self,
+        change: impl Into<String>,
+        breaking: bool,
+    ) {
+        self.changes.push(Change {
+            item: self.item.to_string(),
+            change: change.into(),
+            breaking,
+        });
+    }
+
+    fn breaking(
+        &mut self,
+        change: impl Into<String>,
+    ) {
+        self.push(change, true)
+    }
+
+    fn additive(
+        &mut self,
+        change: impl Into<String>,
+    ) {
+        self.push(change, false)
+    }
+
+    fn item(
+        &mut self,
+        previous: &DeclaredItem,
+        next: &DeclaredItem,
+    ) {
+        if previous.kind() != next.kind() {
+            return self.breaking(format!(
+                "changes from {} to {}",
+                previous.kind(),
+                next.kind()
+            ));
+        }
+
+        match (previous, next) {
+            (DeclaredItem::Constant(previous), DeclaredItem::Constant(next)) => {
+                if previous.ty != next.ty {
+                    self.breaking("changes its type");
+                } else if previous.value != next.value {
+                    self.breaking("changes its value");
+                }
+            },
+            (DeclaredItem::Type(previous), DeclaredItem::Type(next)) => {
+                self.definition(previous, next)
+            },
+            _ => unreachable!("items of the same kind"),
+        }
+    }
+
+    fn definition(
+        &mut self,
+        previous: &TypeDefinition,
+        next: &TypeDefinition,
+    ) {
+        match (previous, next) {
+            (TypeDefinition::Struct(previous), TypeDefinition::Struct(next)) => {
+                self.fields(&previous.fields, &next.fields)
+            },
+            (TypeDefinition::Enum(previous), TypeDefinition::Enum(next)) => {
+                self.enum_variants(&previous.enum_def, &next.enum_def)
+            },
+            (TypeDefinition::OneOf(previous), TypeDefinition::OneOf(next)) => {
+                if previous.tag != next.tag {
+                    self.breaking("changes how its variants are tagged");
+                }
+                self.variants(&previous.variants, &next.variants)
+            },
+            (TypeDefinition::Error(previous), TypeDefinition::Error(next)) => {
+                if previous.tag != next.tag {
+                    self.breaking("changes how its variants are tagged");
+                }
+                self.variants(&previous.variants, &next.variants)
+            },
+            (TypeDefinition::TypeAlias(previous), TypeDefinition::TypeAlias(next)) => {
+                if previous.target != next.target {
+                    self.breaking("changes the type it aliases");
+                }
+            },
+            (TypeDefinition::Operation(previous), TypeDefinition::Operation(next)) => {
+                self.args(&previous.args, &next.args);
+                if previous.return_type != next.return_type {
+                    self.breaking("changes its return type");
+                }
+                if previous.http != next.http {
+                    self.breaking("changes its HTTP route");
+                }
+            },
+            _ => unreachable!("definitions of the same kind"),
+        }
+    }
+
+    fn fields(
+        &mut self,
+        previous: &[DeclField],
+        next: &[DeclField],
+    ) {
+        for field in previous {
+            let Some(next) = next
+                .iter()
+                .find(|next| next.name == field.name)
+            else {
+                self.breaking(format!("removes field `{}`", field.name));
+                continue;
+            };
+
+            if field.ty != next.ty {
+                self.breaking(format!("changes the type of field `{}`", field.name));
+            }
+            match (field.optional, next.optional) {
+                (true, false) => self.breaking(format!("makes field `{}` required", field.name)),
+                (false, true) => self.breaking(format!("makes field `{}` optional", field.name)),
+                _ => {},
+            }
+        }
+
+        for field in next.iter().filter(|field| {
+            !previous
+                .iter()
+                .any(|prev| prev.name == field.name)
+        }) {
+            if field.optional || matches!(field.ty, DeclType::Optional { .. }) {
+                self.additive(format!("adds optional field `{}`", field.name));
+            } else {
+                self.breaking(format!("adds required field `{}`", field.name));
+            }
+        }
+    }
+
+    fn enum_variants(
+        &mut self,
+        previous: &DeclEnum,
+        next: &DeclEnum,
+    ) {
+        let values = |def: &DeclEnum| -> BTreeMap<String, String> {
+            match def {
+                DeclEnum::Int(variants) => {
+                    variants
+                        .iter()
+                        .map(|var| (var.name.clone(), var.value.to_string()))
+                        .collect()
+                },
+                DeclEnum::String(variants) => {
+                    variants
+                        .iter()
+                        .map(|var| (var.name.clone(), format!("{:?}", var.value)))
+                        .collect()
+                },
+            }
+        };
+
+        if std::mem::discriminant(previous) != std::mem::discriminant(next) {
+            return self.breaking("changes the type of its values");
+        }
+
+        let (previous, next) = (values(previous), values(next));
+        for (name, value) in &previous {
+            match next.get(name) {
+                None => self.breaking(format!("removes variant `{name}`")),
+                Some(next) if next != value => {
+                    self.breaking(format!("changes the value of variant `{name}`"))
+                },
+                Some(_) => {},
+            }
+        }
+        for name in next
+            .keys()
+            .filter(|name| !previous.contains_key(*name))
+        {
+            self.additive(format!("adds variant `{name}`"));
+        }
+    }
+
+    fn variants(
+        &mut self,
+        previous: &[DeclOneOfVariant],
+        next: &[DeclOneOfVariant],
+    ) {
+        for (index, variant) in previous.iter().enumerate() {
+            let Some((next_index, next)) = next
+                .iter()
+                .enumerate()
+                .find(|(_, next)| next.name == variant.name)
+            else {
+                self.breaking(format!("removes variant `{}`", variant.name));
+                continue;
+            };
+
+            if variant.ty != next.ty {
+                self.breaking(format!("changes the type of variant `{}`", variant.name));
+            }
+            // variants are encoded by their index
+            if index != next_index {
+                self.breaking(format!("moves variant `{}`", variant.name));
+            }
+        }
+
+        for variant in next.iter().filter(|variant| {
+            !previous
+                .iter()
+                .any(|prev| prev.name == variant.name)
+        }) {
+            self.additive(format!("adds variant `{}`", variant.name));
+        }
+    }
+
+    fn args(
+        &mut self,
+        previous: &[DeclArg],
+        next: &[DeclArg],
+    ) {
+        for arg in previous {
+            let Some(next) = next
+                .iter()
+                .find(|next| next.name == arg.name)
+            else {
+                self.breaking(format!("removes argument `{}`", arg.name));
+                continue;
+            };
+
+            if arg.ty != next.ty {
+                self.breaking(format!("changes the type of argument `{}`", arg.name));
+            }
+        }
+
+        for arg in next.iter().filter(|arg| {
+            !previous
+                .iter()
+                .any(|prev| prev.name == arg.name)
+        }) {
+            if arg.default_value.is_some() || matches!(arg.ty, DeclType::Optional { .. }) {
+                self.additive(format!("adds optional argument `{}`", arg.name));
+            } else {
+                self.breaking(format!("adds required argument `{}`", arg.name));
+            }
+        }
+    }
+}
+
+/// Whether going from `previous` to `next` is a major release under semver, where for `0.x`
+/// versions a minor bump counts as major.
+pub fn is_breaking_release(
+    previous: &kintsu_manifests::version::Version,
+    next: &kintsu_manifests::version::Version,
+) -> bool {
+    if previous.major == 0 {
+        next.major > 0 || next.minor > previous.minor
+    } else {
+        next.major > previous.major
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::declare::{
+        Builtin, DeclComment, DeclConst, DeclConstValue, DeclNamespace, DeclarationBundle,
+        TypeRegistryDeclaration,
+    };
+
+    fn namespace(
+        name: &str,
+        constants: &[&str],
+        namespaces: Vec<DeclNamespace>,
+    ) -> DeclNamespace {
+        DeclNamespace {
+            name: name.into(),
+            version: None,
+            error: None,
+            types: vec![],
+            constants: constants
+                .iter()
+                .map(|constant| {
+                    DeclConst {
+                        name: constant.to_string(),
+                        ty: Builtin::I32,
+                        value: DeclConstValue::Int(1),
+                        comments: DeclComment::default(),
+                    }
+                })
+                .collect(),
+            namespaces: namespaces
+                .into_iter()
+                .map(|child| (child.name.clone(), Box::new(child)))
+                .collect(),
+            comments: DeclComment::default(),
+        }
+    }
+
+    fn declarations(root: DeclNamespace) -> DeclarationVersion {
+        let mut package = TypeRegistryDeclaration::new("abc".into());
+        package
+            .namespaces
+            .insert(root.name.clone(), root);
+        DeclarationVersion::V1(DeclarationBundle {
+            root: package,
+            dependencies: BTreeMap::new(),
+        })
+    }
+
+    #[test]
+    fn finds_removed_items() {
+        let previous = declarations(namespace(
+            "abc",
+            &["LIMIT", "TIMEOUT"],
+            vec![namespace("v1", &["PAGE"], vec![])],
+        ));
+        let next = declarations(namespace("abc", &["LIMIT", "RETRIES"], vec![]));
+
+        assert_eq!(
+            removed_items(&previous, &next),
+            vec!["abc::TIMEOUT", "abc::v1::PAGE"]
+        );
+        assert!(removed_items(&next, &next).is_empty());
+        assert_eq!(added_items(&previous, &next), vec!["abc::RETRIES"]);
+    }
+
+    #[test]
+    fn requires_a_bump_for_changes() {
+        use kintsu_manifests::version::{Bump, parse_version};
+
+        let previous = declarations(namespace("abc", &["LIMIT"], vec![]));
+        let added = declarations(namespace("abc", &["LIMIT", "RETRIES"], vec![]));
+        let version = parse_version("1.2.0").unwrap();
+
+        assert_eq!(required_bump(&version, &previous, &previous), Bump::Patch);
+        assert_eq!(required_bump(&version, &previous, &added), Bump::Minor);
+        assert_eq!(required_bump(&version, &added, &previous), Bump::Major);
+    }
+
+    fn with_types(types: serde_json::Value) -> DeclarationVersion {
+        let mut root = namespace("abc", &[], vec![]);
+        root.types = serde_json::from_value(types).unwrap();
+        declarations(root)
+    }
+
+    fn builtin(ty: &str) -> serde_json::Value {
+        serde_json::json!({ "type": "builtin", "ty": ty })
+    }
+
+    fn user(fields: serde_json::Value) -> DeclarationVersion {
+        with_types(serde_json::json!([{
+            "definition_type": "struct",
+            "name": "User",
+            "meta": { "version": 1 },
+            "fields": fields,
+        }]))
+    }
+
+    fn breaking(
+        previous: &DeclarationVersion,
+        next: &DeclarationVersion,
+    ) -> Vec<String> {
+        breaking_changes(previous, next)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn compares_struct_fields() {
+        use kintsu_manifests::version::{Bump, parse_version};
+
+        let version = parse_version("1.2.0").unwrap();
+        let previous = user(serde_json::json!([
+            { "name": "id", "ty": builtin("u64") },
+            { "name": "email", "ty": builtin("str") },
+            { "name": "nickname", "ty": builtin("str"), "optional": true },
+        ]));
+
+        let retyped = user(serde_json::json!([
+            { "name": "id", "ty": builtin("str") },
+            { "name": "email", "ty": builtin("str") },
+            { "name": "nickname", "ty": builtin("str"), "optional": true },
+        ]));
+        assert!(removed_items(&previous, &retyped).is_empty());
+        assert_eq!(
+            breaking(&previous, &retyped),
+            vec!["abc::User changes the type of field `id`"]
+        );
+        assert_eq!(required_bump(&version, &previous, &retyped), Bump::Major);
+
+        let reshaped = user(serde_json::json!([
+            { "name": "id", "ty": builtin("u64") },
+            { "name": "mail", "ty": builtin("str") },
+            { "name": "nickname", "ty": builtin("str") },
+        ]));
+        assert_eq!(
+            breaking(&previous, &reshaped),
+            vec![
+                "abc::User adds required field `mail`",
+                "abc::User makes field `nickname` required",
+                "abc::User removes field `email`",
+            ]
+        );
+
+        let extended = user(serde_json::json!([
+            { "name": "id", "ty": builtin("u64") },
+            { "name": "email", "ty": builtin("str") },
+            { "name": "nickname", "ty": builtin("str"), "optional": true },
+            { "name": "bio", "ty": { "type": "optional", "inner_type": builtin("str") } },
+        ]));
+        assert!(breaking(&previous, &extended).is_empty());
+        assert_eq!(required_bump(&version, &previous, &extended), Bump::Minor);
+        assert_eq!(required_bump(&version, &previous, &previous), Bump::Patch);
+    }
+
+    #[test]
+    fn compares_variants_and_operations() {
+        let previous = with_types(serde_json::json!([
+            {
+                "definition_type": "enum",
+                "name": "Status",
+                "meta": { "version": 1 },
+                "enum_def": {
+                    "enum_type": "int",
+                    "variants": [
+                        { "name": "Active", "value": 1 },
+                        { "name": "Banned", "value": 2 },
+                    ],
+                },
+            },
+            {
+                "definition_type": "one_of",
+                "name": "Payment",
+                "meta": { "version": 1 },
+                "variants": [
+                    { "name": "Card", "ty": builtin("str") },
+                    { "name": "Points", "ty": builtin("i64") },
+                ],
+            },
+            {
+                "definition_type": "operation",
+                "name": "ban",
+                "meta": { "version": 1 },
+                "args": [{ "name": "id", "ty": builtin("u64") }],
+                "return_type": builtin("bool"),
+            },
+        ]));
+        let next = with_types(serde_json::json!([
+            {
+                "definition_type": "enum",
+                "name": "Status",
+                "meta": { "version": 1 },
+                "enum_def": {
+                    "enum_type": "int",
+                    "variants": [
+                        { "name": "Active", "value": 3 },
+                        { "name": "Pending", "value": 4 },
+                    ],
+                },
+            },
+            {
+                "definition_type": "one_of",
+                "name": "Payment",
+                "meta": { "version": 1 },
+                "variants": [
+                    { "name": "Card", "ty": builtin("str") },
+                    { "name": "Points", "ty": builtin("u64") },
+                    { "name": "Voucher", "ty": builtin("str") },
+                ],
+            },
+            {
+                "definition_type": "operation",
+                "name": "ban",
+                "meta": { "version": 1 },
+                "args": [
+                    { "name": "id", "ty": builtin("str") },
+                    { "name": "reason", "ty": builtin("str"), "default_value": "\"\"" },
+                ],
+                "return_type": builtin("str"),
+            },
+        ]));
+
+        assert_eq!(
+            breaking(&previous, &next),
+            vec![
+                "abc::Payment changes the type of variant `Points`",
+                "abc::Status changes the value of variant `Active`",
+                "abc::Status removes variant `Banned`",
+                "abc::ban changes its return type",
+                "abc::ban changes the type of argument `id`",
+            ]
+        );
+        assert_eq!(
+            changes(&previous, &next)
+                .iter()
+                .filter(|change| !change.breaking)
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "abc::Payment adds variant `Voucher`",
+                "abc::Status adds variant `Pending`",
+                "abc::ban adds optional argument `reason`",
+            ]
+        );
+    }
+
+    #[test_case::test_case("1.2.0", "1.3.0", false; "minor")]
+    #[test_case::test_case("1.2.0", "2.0.0", true; "major")]
+    #[test_case::test_case("0.2.0", "0.2.1", false; "unstable patch")]
+    #[test_case::test_case("0.2.0", "0.3.0", true; "unstable minor")]
+    fn breaking_releases(
+        previous: &str,
+        next: &str,
+        breaking: bool,
+    ) {
+        assert_eq!(
+            is_breaking_release(
+                &kintsu_manifests::version::parse_version(previous).unwrap(),
+                &kintsu_manifests::version::parse_version(next).unwrap()
+            ),
+            breaking
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Serialized forms of [`DeclarationVersion`] bundles.
+//!
+//! Bundles are JSON by default. The binary form is MessagePack (with field names, since
+//! declarations use untagged enums and skipped fields) behind a header of
+//! [`BINARY_MAGIC`] followed by a single [`BINARY_FORMAT_VERSION`] byte. JSON never starts
+//! with the magic, so [`decode`] accepts either form and readers need not know which one a
+//! bundle was written in.
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::DeclarationVersion;
+
+pub const BINARY_MAGIC: &[u8; 4] = b"KSDB";
+
+/// Bumped whenever the layout after the header changes.
+pub const BINARY_FORMAT_VERSION: u8 = 1;
+
+/// Media type of binary bundles served over HTTP.
+pub const BINARY_MEDIA_TYPE: &str = "application/vnd.kintsu.declarations";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeclarationEncoding {
+    #[default]
+    Json,
+    Binary,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EncodingError {
+    #[error("json declarations: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("binary declarations: {0}")]
+    Encode(#[from] rmp_serde::encode::Error),
+    #[error("binary declarations: {0}")]
+    Decode(#[from] rmp_serde::decode::Error),
+    #[error(
+        "binary declarations use format version {0}, this build reads version {BINARY_FORMAT_VERSION}"
+    )]
+    UnsupportedVersion(u8),
+    #[error("binary declarations are truncated")]
+    Truncated,
+}
+
+/// Serializes `value` in `encoding`. Generic so storage can encode any declarations type.
+pub fn encode<T: Serialize>(
+    value: &T,
+    encoding: DeclarationEncoding,
+) -> Result<Vec<u8>, EncodingError> {
+    match encoding {
+        DeclarationEncoding::Json => Ok(serde_json::to_vec(value)?),
+        DeclarationEncoding::Binary => {
+            let mut out = BINARY_MAGIC.to_vec();
+            out.push(BINARY_FORMAT_VERSION);
+            rmp_serde::encode::write_named(&mut out, value)?;
+            Ok(out)
+        },
+    }
+}
+
+/// The encoding `bytes` were written in.
+pub fn detect(bytes: &[u8]) -> DeclarationEncoding {
+    if bytes.starts_with(BINARY_MAGIC) {
+        DeclarationEncoding::Binary
+    } else {
+        DeclarationEncoding::Json
+    }
+}
+
+/// Deserializes bytes written by [`encode`] in either encoding.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, EncodingError> {
+    match detect(bytes) {
+        DeclarationEncoding::Json => Ok(serde_json::from_slice(bytes)?),
+        DeclarationEncoding::Binary => {
+            let (&version, body) = bytes[BINARY_MAGIC.len()..]
+                .split_first()
+                .ok_or(EncodingError::Truncated)?;
+            if version != BINARY_FORMAT_VERSION {
+                return Err(EncodingError::UnsupportedVersion(version));
+            }
+            Ok(rmp_serde::from_slice(body)?)
+        },
+    }
+}
+
+impl DeclarationVersion {
+    pub fn encode(
+        &self,
+        encoding: DeclarationEncoding,
+    ) -> Result<Vec<u8>, EncodingError> {
+        encode(self, encoding)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, EncodingError> {
+        decode(bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rejects_unknown_binary_versions() {
+        let mut bytes = encode(&vec!["a".to_string()], DeclarationEncoding::Binary).unwrap();
+        assert_eq!(decode::<Vec<String>>(&bytes).unwrap(), vec!["a"]);
+
+        bytes[BINARY_MAGIC.len()] = BINARY_FORMAT_VERSION + 1;
+        assert!(matches!(
+            decode::<Vec<String>>(&bytes),
+            Err(EncodingError::UnsupportedVersion(v)) if v == BINARY_FORMAT_VERSION + 1
+        ));
+        assert!(matches!(
+            decode::<Vec<String>>(BINARY_MAGIC),
+            Err(EncodingError::Truncated)
+        ));
+    }
+}
@@ -19,6 +19,8 @@ pub struct DeclIntVariant {
     pub value: u32,
     #[serde(default, skip_serializing_if = "DeclComment::is_empty")]
     pub comments: DeclComment,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<DeclEnumAlias>,
 }
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
@@ -28,6 +30,27 @@ pub struct DeclStringVariant {
     pub value: String,
     #[serde(default, skip_serializing_if = "DeclComment::is_empty")]
     pub comments: DeclComment,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<DeclEnumAlias>,
+}
+
+/// A value accepted for a variant when deserializing, never emitted when serializing.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DeclEnumAlias {
+    Int(i64),
+    Str(String),
+}
+
+impl From<crate::ast::constant::ConstLiteral> for DeclEnumAlias {
+    fn from(value: crate::ast::constant::ConstLiteral) -> Self {
+        use crate::ast::constant::ConstLiteral;
+        match value {
+            ConstLiteral::Int(v) => Self::Int(v),
+            ConstLiteral::Str(s) => Self::Str(s),
+        }
+    }
 }
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
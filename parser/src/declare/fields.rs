@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::{comments::DeclComment, types::DeclType};
+use super::{comments::DeclComment, constraints::DeclConstraint, types::DeclType};
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -13,6 +13,12 @@ pub struct DeclField {
     pub optional: bool,
     #[serde(default, skip_serializing_if = "DeclComment::is_empty")]
     pub comments: DeclComment,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<DeclConstraint>,
+    /// Example values from `#[example(...)]`, checked against `ty` at compile time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "api", schema(value_type = Vec<Object>))]
+    pub examples: Vec<serde_json::Value>,
 }
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
@@ -24,4 +30,10 @@ pub struct DeclArg {
     pub default_value: Option<String>,
     #[serde(default, skip_serializing_if = "DeclComment::is_empty")]
     pub comments: DeclComment,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<DeclConstraint>,
+    /// Example values from `#[example(...)]`, checked against `ty` at compile time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "api", schema(value_type = Vec<Object>))]
+    pub examples: Vec<serde_json::Value>,
 }
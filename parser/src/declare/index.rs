@@ -0,0 +1,92 @@
+//! Declarations of a package split into the items they declare, which the registry stores so
+//! single types are served without loading the whole bundle.
+
+use serde::{Deserialize, Serialize};
+
+use super::{DeclConst, DeclNamespace, DeclarationVersion, TypeDefinition};
+
+/// A type or constant declared in a namespace.
+#[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "item", content = "declaration", rename_all = "snake_case")]
+pub enum DeclaredItem {
+    Type(TypeDefinition),
+    Constant(DeclConst),
+}
+
+impl DeclaredItem {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Type(ty) => ty.name(),
+            Self::Constant(constant) => &constant.name,
+        }
+    }
+
+    /// What the item declares, e.g. `struct` or `constant`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Type(TypeDefinition::Struct(_)) => "struct",
+            Self::Type(TypeDefinition::Enum(_)) => "enum",
+            Self::Type(TypeDefinition::OneOf(_)) => "one_of",
+            Self::Type(TypeDefinition::TypeAlias(_)) => "type_alias",
+            Self::Type(TypeDefinition::Error(_)) => "error",
+            Self::Type(TypeDefinition::Operation(_)) => "operation",
+            Self::Constant(_) => "constant",
+        }
+    }
+}
+
+/// An item with the qualified name of the namespace declaring it, e.g. `abc::v1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedItem {
+    pub namespace: String,
+    pub item: DeclaredItem,
+}
+
+impl IndexedItem {
+    /// The qualified name of the item, e.g. `abc::v1::User`.
+    pub fn qualified_name(&self) -> String {
+        format!("{}::{}", self.namespace, self.item.name())
+    }
+}
+
+/// Every item a package declares, namespace by namespace. Declarations of its dependencies
+/// are not included.
+pub fn index(declarations: &DeclarationVersion) -> Vec<IndexedItem> {
+    let DeclarationVersion::V1(bundle) = declarations;
+
+    let mut items = vec![];
+    for (name, namespace) in &bundle.root.namespaces {
+        index_namespace(name, namespace, &mut items);
+    }
+    items
+}
+
+fn index_namespace(
+    path: &str,
+    namespace: &DeclNamespace,
+    items: &mut Vec<IndexedItem>,
+) {
+    let declared = namespace
+        .types
+        .iter()
+        .cloned()
+        .map(DeclaredItem::Type)
+        .chain(
+            namespace
+                .constants
+                .iter()
+                .cloned()
+                .map(DeclaredItem::Constant),
+        );
+    items.extend(declared.map(|item| {
+        IndexedItem {
+            namespace: path.to_string(),
+            item,
+        }
+    }));
+
+    for (name, child) in &namespace.namespaces {
+        index_namespace(&format!("{path}::{name}"), child, items);
+    }
+}
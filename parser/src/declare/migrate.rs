@@ -0,0 +1,138 @@
+//! Format versions of stored [`DeclarationVersion`] bundles.
+//!
+//! Loading a bundle reads its format version before anything else. Versions newer than
+//! [`LATEST_VERSION`] are refused with KPK6006, while older ones are brought up to the latest
+//! layout by running each of the [`MIGRATIONS`] from their version on, so bundles stored by
+//! earlier compilers stay loadable. Changing the layout therefore means adding a variant,
+//! bumping [`LATEST_VERSION`] and appending e.g. a `migrate_v1_to_v2`.
+
+use serde_json::Value;
+
+use super::DeclarationVersion;
+
+/// The format version bundles are emitted in.
+pub const LATEST_VERSION: u32 = 1;
+
+/// Rewrites the `declarations` of one format version into the layout of the next.
+pub type Migration = fn(Value) -> crate::Result<Value>;
+
+/// `MIGRATIONS[n - 1]` migrates version `n` to `n + 1`.
+pub const MIGRATIONS: &[Migration] = &[];
+
+const _: () = assert!(MIGRATIONS.len() as u32 + 1 == LATEST_VERSION);
+
+/// A bundle as stored, before its format version is checked.
+#[derive(serde::Deserialize)]
+pub(super) struct StoredDeclarations {
+    version: String,
+    declarations: Value,
+}
+
+/// The format version of a `version` tag, e.g. `1` for `v1`.
+pub fn format_version(tag: &str) -> Option<u32> {
+    tag.strip_prefix('v')?
+        .parse()
+        .ok()
+        .filter(|version| *version > 0)
+}
+
+/// Runs `migrations` on `declarations` of format version `from`, up to the version after the
+/// last migration.
+pub fn migrate(
+    mut declarations: Value,
+    from: u32,
+    migrations: &[Migration],
+) -> crate::Result<Value> {
+    for migration in migrations
+        .iter()
+        .skip(from.saturating_sub(1) as usize)
+    {
+        declarations = migration(declarations)?;
+    }
+    Ok(declarations)
+}
+
+fn unsupported(found: &str) -> crate::Error {
+    crate::PackageError::unsupported_declaration_version(found, format!("v{LATEST_VERSION}"))
+        .unlocated()
+        .build()
+        .into()
+}
+
+impl TryFrom<StoredDeclarations> for DeclarationVersion {
+    type Error = crate::Error;
+
+    fn try_from(stored: StoredDeclarations) -> crate::Result<Self> {
+        let from = format_version(&stored.version)
+            .filter(|version| *version <= LATEST_VERSION)
+            .ok_or_else(|| unsupported(&stored.version))?;
+
+        let declarations = migrate(stored.declarations, from, MIGRATIONS)?;
+        let bundle = serde_json::from_value(declarations).map_err(|err| -> crate::Error {
+            crate::InternalError::internal(format!(
+                "declarations do not match format version v{LATEST_VERSION}: {err}"
+            ))
+            .unlocated()
+            .build()
+            .into()
+        })?;
+        Ok(DeclarationVersion::V1(bundle))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_format_versions() {
+        assert_eq!(format_version("v1"), Some(1));
+        assert_eq!(format_version("v12"), Some(12));
+        assert_eq!(format_version("v0"), None);
+        assert_eq!(format_version("1"), None);
+        assert_eq!(format_version("vnext"), None);
+    }
+
+    #[test]
+    fn migrates_from_the_stored_version_on() {
+        fn migrate_v1_to_v2(mut value: Value) -> crate::Result<Value> {
+            value["items"] = value["names"].take();
+            Ok(value)
+        }
+        fn migrate_v2_to_v3(mut value: Value) -> crate::Result<Value> {
+            value["count"] = json!(value["items"].as_array().map_or(0, Vec::len));
+            Ok(value)
+        }
+        let migrations: &[Migration] = &[migrate_v1_to_v2, migrate_v2_to_v3];
+
+        assert_eq!(
+            migrate(json!({"names": ["a", "b"]}), 1, migrations).unwrap(),
+            json!({"names": null, "items": ["a", "b"], "count": 2})
+        );
+        assert_eq!(
+            migrate(json!({"items": ["a"]}), 2, migrations).unwrap(),
+            json!({"items": ["a"], "count": 1})
+        );
+        assert_eq!(
+            migrate(json!({"items": []}), 3, migrations).unwrap(),
+            json!({"items": []})
+        );
+    }
+
+    #[test]
+    fn refuses_newer_versions() {
+        for version in ["v2", "v99", "next"] {
+            let err = serde_json::from_value::<DeclarationVersion>(
+                json!({"version": version, "declarations": {}}),
+            )
+            .unwrap_err();
+            assert!(
+                err.to_string().contains(&format!(
+                    "declarations use format version {version}, but this build reads up to version v1"
+                )),
+                "{err}"
+            );
+        }
+    }
+}
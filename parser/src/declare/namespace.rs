@@ -2,7 +2,10 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use super::{comments::DeclComment, context::DeclNamedItemContext, definitions::TypeDefinition};
+use super::{
+    comments::DeclComment, constants::DeclConst, context::DeclNamedItemContext,
+    definitions::TypeDefinition,
+};
 
 #[cfg_attr(feature = "api", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -18,6 +21,9 @@ pub struct DeclNamespace {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub types: Vec<TypeDefinition>,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constants: Vec<DeclConst>,
+
     #[cfg_attr(feature = "api", schema(no_recursion))]
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub namespaces: BTreeMap<String, Box<DeclNamespace>>,
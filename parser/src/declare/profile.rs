@@ -0,0 +1,138 @@
+//! Narrowing the declarations of a package to the surface a compile profile selects.
+
+use std::collections::BTreeSet;
+
+use convert_case::{Case, Casing};
+use kintsu_manifests::profiles::Profile;
+
+use super::{
+    context::DeclNamedItemContext, namespace::DeclNamespace, root::TypeRegistryDeclaration,
+};
+
+/// The path of an item within its package, e.g. `users::purge`.
+fn item_path(
+    namespace: &[String],
+    name: &str,
+) -> String {
+    namespace
+        .iter()
+        .map(String::as_str)
+        .chain([name])
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Removes what `profile` excludes from `ns`, found at `path`, adding the path of each item
+/// removed to `removed`. Items of removed namespaces are not added, as their namespace
+/// already excludes them.
+fn exclude(
+    ns: &mut DeclNamespace,
+    path: &mut Vec<String>,
+    profile: &Profile,
+    removed: &mut BTreeSet<String>,
+) {
+    let mut excludes = |name: &str| {
+        path.push(name.to_string());
+        let excluded = profile.excludes(path);
+        path.pop();
+        if excluded {
+            removed.insert(item_path(path, name));
+        }
+        !excluded
+    };
+    ns.types.retain(|def| excludes(def.name()));
+    ns.constants
+        .retain(|def| excludes(&def.name));
+
+    ns.namespaces.retain(|name, nested| {
+        path.push(name.clone());
+        let excluded = profile.excludes(path);
+        if !excluded {
+            exclude(nested, path, profile, removed);
+        }
+        path.pop();
+        !excluded
+    });
+}
+
+impl TypeRegistryDeclaration {
+    /// Removes the namespaces and items `profile` excludes. Errors when an item which
+    /// remains refers to one which was removed, as the declarations would not resolve.
+    pub fn apply_profile(
+        &mut self,
+        name: &str,
+        profile: &Profile,
+    ) -> crate::Result<()> {
+        let mut removed = BTreeSet::new();
+        self.namespaces.retain(|ns_name, ns| {
+            let mut path = vec![ns_name.clone()];
+            if profile.excludes(&path) {
+                return false;
+            }
+            exclude(ns, &mut path, profile, &mut removed);
+            true
+        });
+
+        let package = self.package.to_case(Case::Snake);
+        // a reference is dangling if its item or any namespace containing it was removed
+        let is_removed = |reference: &DeclNamedItemContext| {
+            reference.context.package == package
+                && (removed.contains(&item_path(&reference.context.namespace, &reference.name))
+                    || profile.excludes(&reference.context.namespace))
+        };
+
+        // reversed, so that namespaces are checked in order
+        let mut stack = self
+            .namespaces
+            .values()
+            .rev()
+            .map(|ns| (vec![ns.name.clone()], ns))
+            .collect::<Vec<_>>();
+        while let Some((path, ns)) = stack.pop() {
+            if let Some(error) = ns
+                .error
+                .as_ref()
+                .filter(|error| is_removed(error))
+            {
+                return Err(Self::excluded_reference(name, error, path.join("::")));
+            }
+            for def in &ns.types {
+                let mut dangling = None;
+                def.for_each_ref(&mut |reference| {
+                    if dangling.is_none() && is_removed(reference) {
+                        dangling = Some(reference.clone());
+                    }
+                });
+                if let Some(reference) = dangling {
+                    return Err(Self::excluded_reference(
+                        name,
+                        &reference,
+                        item_path(&path, def.name()),
+                    ));
+                }
+            }
+            stack.extend(ns.namespaces.values().rev().map(|nested| {
+                let mut path = path.clone();
+                path.push(nested.name.clone());
+                (path, nested.as_ref())
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn excluded_reference(
+        profile: &str,
+        reference: &DeclNamedItemContext,
+        referrer: String,
+    ) -> crate::Error {
+        crate::PackageError::profile_excludes_reference(
+            profile,
+            item_path(&reference.context.namespace, &reference.name),
+            referrer,
+        )
+        .unlocated()
+        .build()
+        .into()
+    }
+}
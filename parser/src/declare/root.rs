@@ -11,6 +11,10 @@ pub struct TypeRegistryDeclaration {
     /// Map of namespace name to namespace declarations
     pub namespaces: BTreeMap<String, DeclNamespace>,
     pub external_refs: BTreeSet<DeclNamedItemContext>,
+    /// `[package.metadata.*]` tables, present when the manifest sets `embed_metadata`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[cfg_attr(feature = "api", schema(value_type = Object))]
+    pub metadata: kintsu_manifests::package::PackageMetadata,
 }
 
 impl TypeRegistryDeclaration {
@@ -19,6 +23,7 @@ impl TypeRegistryDeclaration {
             package,
             namespaces: BTreeMap::new(),
             external_refs: BTreeSet::new(),
+            metadata: BTreeMap::new(),
         }
     }
 
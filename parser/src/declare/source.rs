@@ -0,0 +1,648 @@
+//! Schema sources written back from declarations, so a package compiled elsewhere can be
+//! loaded as a dependency without its original sources.
+//!
+//! Every reference is written as a full path (`pkg::ns::Name`) next to a `use` of the
+//! namespace declaring it, and every item carries an explicit `#[version(..)]`, so compiling
+//! the sources yields the declarations they were written from.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use convert_case::{Case, Casing};
+
+use super::{
+    DeclArg, DeclComment, DeclConst, DeclConstValue, DeclConstraint, DeclEnum, DeclEnumAlias,
+    DeclExample, DeclField, DeclGraphqlKind, DeclHttpBinding, DeclNamedItemContext, DeclNamespace,
+    DeclOneOfVariant, DeclRefContext, DeclTagStyle, DeclTagging, TypeDefinition,
+    TypeRegistryDeclaration,
+    types::{Builtin, DeclType, DeclTypeExprOp},
+};
+
+const INDENT: &str = "    ";
+
+impl TypeRegistryDeclaration {
+    /// Schema sources declaring the same items, keyed by path relative to the `schema`
+    /// directory: a `lib.ks` using one file per top-level namespace.
+    pub fn to_sources(&self) -> crate::Result<BTreeMap<String, String>> {
+        let package = self.package.to_case(Case::Snake);
+
+        let mut lib = format!("namespace {package};\n");
+        let mut sources = BTreeMap::new();
+        for (name, ns) in &self.namespaces {
+            lib.push_str(&format!("\nuse {name};"));
+
+            let ctx = DeclRefContext {
+                package: package.clone(),
+                namespace: vec![name.clone()],
+            };
+            let mut out = SourceWriter::default();
+            out.namespace_file(ns, &ctx)?;
+            sources.insert(format!("{name}.ks"), out.finish());
+        }
+        lib.push('\n');
+        sources.insert("lib.ks".to_string(), lib);
+
+        Ok(sources)
+    }
+}
+
+#[derive(Default)]
+struct SourceWriter {
+    out: String,
+    depth: usize,
+}
+
+impl SourceWriter {
+    fn finish(self) -> String {
+        self.out
+    }
+
+    fn line(
+        &mut self,
+        line: impl AsRef<str>,
+    ) {
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(line.as_ref());
+        self.out.push('\n');
+    }
+
+    fn comments(
+        &mut self,
+        comments: &DeclComment,
+    ) {
+        for comment in &comments.comments {
+            // mirrors how comment tokens are printed, keeping `///` and `//!` intact
+            if comment.contains('\n') {
+                self.line("/*");
+                for ln in comment.lines() {
+                    self.line(ln);
+                }
+                self.line("*/");
+            } else if comment.starts_with(['/', '!']) {
+                self.line(format!("//{comment}"));
+            } else {
+                self.line(format!("// {comment}"));
+            }
+        }
+    }
+
+    fn namespace_file(
+        &mut self,
+        ns: &DeclNamespace,
+        ctx: &DeclRefContext,
+    ) -> crate::Result<()> {
+        // inner attributes lead the file, comments ahead of them would belong to the module
+        self.namespace_meta(ns, ctx);
+        self.comments(&ns.comments);
+        self.line(format!("namespace {};", ns.name));
+        self.namespace_items(ns, ctx)
+    }
+
+    fn nested_namespace(
+        &mut self,
+        ns: &DeclNamespace,
+        ctx: &DeclRefContext,
+    ) -> crate::Result<()> {
+        self.line("");
+        self.comments(&ns.comments);
+        self.line(format!("namespace {} {{", ns.name));
+        self.depth += 1;
+        self.namespace_meta(ns, ctx);
+        self.line(format!("namespace {};", ns.name));
+        self.namespace_items(ns, ctx)?;
+        self.depth -= 1;
+        self.line("};");
+        Ok(())
+    }
+
+    fn namespace_meta(
+        &mut self,
+        ns: &DeclNamespace,
+        ctx: &DeclRefContext,
+    ) {
+        if let Some(version) = ns.version {
+            self.line(format!("#![version({version})]"));
+        }
+        if let Some(error) = &ns.error {
+            let error = if &error.context == ctx {
+                error.name.clone()
+            } else {
+                reference(error)
+            };
+            self.line(format!("#![err({error})]"));
+        }
+    }
+
+    fn namespace_items(
+        &mut self,
+        ns: &DeclNamespace,
+        ctx: &DeclRefContext,
+    ) -> crate::Result<()> {
+        let mut imports = BTreeSet::new();
+        collect_imports(ns, &mut imports);
+        if !imports.is_empty() {
+            self.line("");
+        }
+        for (package, namespace) in imports {
+            self.line(format!("use {package}::{namespace};"));
+        }
+
+        for constant in &ns.constants {
+            self.line("");
+            self.constant(constant);
+        }
+
+        for ty in &ns.types {
+            self.line("");
+            self.type_definition(ty, ns, ctx)?;
+        }
+
+        for (name, nested) in &ns.namespaces {
+            let mut nested_ctx = ctx.clone();
+            nested_ctx.namespace.push(name.clone());
+            self.nested_namespace(nested, &nested_ctx)?;
+        }
+
+        Ok(())
+    }
+
+    fn constant(
+        &mut self,
+        constant: &DeclConst,
+    ) {
+        self.comments(&constant.comments);
+        let value = match &constant.value {
+            DeclConstValue::Int(v) => v.to_string(),
+            DeclConstValue::Str(s) => string(s),
+        };
+        self.line(format!(
+            "const {}: {} = {value};",
+            constant.name,
+            builtin(&constant.ty)
+        ));
+    }
+
+    fn type_definition(
+        &mut self,
+        ty: &TypeDefinition,
+        ns: &DeclNamespace,
+        ctx: &DeclRefContext,
+    ) -> crate::Result<()> {
+        match ty {
+            TypeDefinition::Struct(s) => {
+                self.comments(&s.comments);
+                self.line(format!("#[version({})]", s.meta.version));
+                self.line(format!("struct {} {{", s.name));
+                self.fields(&s.fields)?;
+                self.line("};");
+            },
+            TypeDefinition::Enum(e) => {
+                self.comments(&e.comments);
+                self.line(format!("#[version({})]", e.meta.version));
+                self.line(format!("enum {} {{", e.name));
+                self.depth += 1;
+                match &e.enum_def {
+                    DeclEnum::Int(variants) => {
+                        for (idx, variant) in variants.iter().enumerate() {
+                            self.comments(&variant.comments);
+                            // variants without a value are declared as 0
+                            let value = if variant.value == 0 && variant.aliases.is_empty() {
+                                String::new()
+                            } else {
+                                format!(" = {}{}", variant.value, aliases(&variant.aliases))
+                            };
+                            self.line(format!(
+                                "{}{value}{}",
+                                variant.name,
+                                separator(idx, variants.len())
+                            ));
+                        }
+                    },
+                    DeclEnum::String(variants) => {
+                        for (idx, variant) in variants.iter().enumerate() {
+                            self.comments(&variant.comments);
+                            self.line(format!(
+                                "{} = {}{}{}",
+                                variant.name,
+                                string(&variant.value),
+                                aliases(&variant.aliases),
+                                separator(idx, variants.len())
+                            ));
+                        }
+                    },
+                }
+                self.depth -= 1;
+                self.line("};");
+            },
+            TypeDefinition::OneOf(o) => {
+                self.comments(&o.comments);
+                self.line(format!("#[version({})]", o.meta.version));
+                self.tagging(&o.tag);
+                self.line(format!("oneof {} {{", o.name));
+                self.variants(&o.name, &o.variants, ns, ctx)?;
+                self.line("};");
+            },
+            TypeDefinition::Error(e) => {
+                self.comments(&e.comments);
+                self.line(format!("#[version({})]", e.meta.version));
+                self.tagging(&e.tag);
+                self.line(format!("error {} {{", e.name));
+                self.variants(&e.name, &e.variants, ns, ctx)?;
+                self.line("};");
+            },
+            TypeDefinition::TypeAlias(t) => {
+                self.comments(&t.comments);
+                self.line(format!("#[version({})]", t.meta.version));
+                self.line(format!("type {} = {};", t.name, type_expr(&t.target)?));
+            },
+            TypeDefinition::Operation(op) => {
+                self.comments(&op.comments);
+                self.line(format!("#[version({})]", op.meta.version));
+
+                let return_type = match &op.return_type {
+                    DeclType::Result { ok_type, error } => {
+                        self.line(format!("#[err({})]", error.name));
+                        format!("{}!", type_expr(ok_type)?)
+                    },
+                    other => type_expr(other)?,
+                };
+                if let Some(http) = &op.http {
+                    self.http(http);
+                }
+                if let Some(graphql) = &op.graphql {
+                    self.line(match graphql {
+                        DeclGraphqlKind::Query => "#[graphql(query)]",
+                        DeclGraphqlKind::Mutation => "#[graphql(mutation)]",
+                    });
+                }
+                for example in &op.examples {
+                    self.operation_example(example);
+                }
+
+                if op.args.is_empty() {
+                    self.line(format!("operation {}() -> {return_type};", op.name));
+                } else {
+                    self.line(format!("operation {}(", op.name));
+                    self.args(&op.args)?;
+                    self.line(format!(") -> {return_type};"));
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn fields(
+        &mut self,
+        fields: &[DeclField],
+    ) -> crate::Result<()> {
+        self.depth += 1;
+        for (idx, field) in fields.iter().enumerate() {
+            self.comments(&field.comments);
+            self.constraints(&field.constraints);
+            for example in &field.examples {
+                self.line(format!("#[example({})]", string(&example.to_string())));
+            }
+            self.line(format!(
+                "{}{}: {}{}",
+                field.name,
+                if field.optional {
+                    "?"
+                } else {
+                    ""
+                },
+                type_expr(&field.ty)?,
+                separator(idx, fields.len())
+            ));
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn args(
+        &mut self,
+        args: &[DeclArg],
+    ) -> crate::Result<()> {
+        self.depth += 1;
+        for (idx, arg) in args.iter().enumerate() {
+            self.comments(&arg.comments);
+            self.constraints(&arg.constraints);
+            for example in &arg.examples {
+                self.line(format!("#[example({})]", string(&example.to_string())));
+            }
+            self.line(format!(
+                "{}: {}{}",
+                arg.name,
+                type_expr(&arg.ty)?,
+                separator(idx, args.len())
+            ));
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn variants(
+        &mut self,
+        parent: &str,
+        variants: &[DeclOneOfVariant],
+        ns: &DeclNamespace,
+        ctx: &DeclRefContext,
+    ) -> crate::Result<()> {
+        self.depth += 1;
+        for (idx, variant) in variants.iter().enumerate() {
+            self.comments(&variant.comments);
+            let separator = separator(idx, variants.len());
+            // unit variants reference a `{Parent}{Variant}` struct which is never declared
+            let unit = match &variant.ty {
+                DeclType::Named { reference } => {
+                    &reference.context == ctx
+                        && reference.name == format!("{parent}{}", variant.name)
+                        && !ns
+                            .types
+                            .iter()
+                            .any(|ty| ty.name() == reference.name)
+                },
+                _ => false,
+            };
+            if unit {
+                self.line(format!("{}{separator}", variant.name));
+            } else {
+                self.line(format!(
+                    "{}({}){separator}",
+                    variant.name,
+                    type_expr(&variant.ty)?
+                ));
+            }
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn constraints(
+        &mut self,
+        constraints: &[DeclConstraint],
+    ) {
+        for constraint in constraints {
+            self.line(match constraint {
+                DeclConstraint::Min { value } => format!("#[min({value})]"),
+                DeclConstraint::Max { value } => format!("#[max({value})]"),
+                DeclConstraint::Pattern { value } => format!("#[pattern({})]", string(value)),
+                DeclConstraint::Len { min, max } => {
+                    format!(
+                        "#[len({}..{})]",
+                        min.map(|v| v.to_string())
+                            .unwrap_or_default(),
+                        max.map(|v| v.to_string())
+                            .unwrap_or_default()
+                    )
+                },
+            });
+        }
+    }
+
+    fn tagging(
+        &mut self,
+        tagging: &DeclTagging,
+    ) {
+        if tagging.is_default() {
+            return;
+        }
+
+        let mut args = match &tagging.style {
+            DeclTagStyle::TypeHint => vec![],
+            DeclTagStyle::External => vec!["external".to_string()],
+            DeclTagStyle::Internal { tag } => vec![format!("name = {}", string(tag))],
+            DeclTagStyle::Adjacent { tag, content } => {
+                vec![
+                    format!("name = {}", string(tag)),
+                    format!("content = {}", string(content)),
+                ]
+            },
+            DeclTagStyle::Untagged => vec!["untagged".to_string()],
+            DeclTagStyle::Index { tag } => {
+                let mut args = vec!["index".to_string()];
+                args.extend(
+                    tag.iter()
+                        .map(|tag| format!("name = {}", string(tag))),
+                );
+                args
+            },
+        };
+        // `untagged` turns the type hint off, every other style leaves it on
+        let implied_hint = !matches!(tagging.style, DeclTagStyle::Untagged);
+        if tagging.type_hint != implied_hint {
+            args.push(format!("type_hint = {}", tagging.type_hint));
+        }
+
+        self.line(format!("#[tag({})]", args.join(", ")));
+    }
+
+    fn http(
+        &mut self,
+        http: &DeclHttpBinding,
+    ) {
+        self.line(format!(
+            "#[http(method = {}, path = {})]",
+            string(&http.method),
+            string(&http.path)
+        ));
+    }
+
+    fn operation_example(
+        &mut self,
+        example: &DeclExample,
+    ) {
+        let mut args = vec![];
+        if let Some(input) = &example.input {
+            args.push(format!("input = {}", string(&input.to_string())));
+        }
+        if let Some(output) = &example.output {
+            args.push(format!("output = {}", string(&output.to_string())));
+        }
+        self.line(format!("#[example({})]", args.join(", ")));
+    }
+}
+
+fn separator(
+    idx: usize,
+    len: usize,
+) -> &'static str {
+    if idx + 1 < len {
+        ","
+    } else {
+        ""
+    }
+}
+
+fn string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn aliases(aliases: &[DeclEnumAlias]) -> String {
+    aliases
+        .iter()
+        .map(|alias| {
+            match alias {
+                DeclEnumAlias::Int(v) => format!(" | {v}"),
+                DeclEnumAlias::Str(s) => format!(" | {}", string(s)),
+            }
+        })
+        .collect()
+}
+
+/// `pkg::ns::Name`, resolved through the `use pkg::ns;` of its top-level namespace.
+fn reference(reference: &DeclNamedItemContext) -> String {
+    reference.qualified_path()
+}
+
+fn builtin(ty: &Builtin) -> &'static str {
+    match ty {
+        Builtin::I8 => "i8",
+        Builtin::I16 => "i16",
+        Builtin::I32 => "i32",
+        Builtin::I64 => "i64",
+        Builtin::U8 => "u8",
+        Builtin::U16 => "u16",
+        Builtin::U32 => "u32",
+        Builtin::U64 => "u64",
+        Builtin::Usize => "usize",
+        Builtin::F16 => "f16",
+        Builtin::F32 => "f32",
+        Builtin::F64 => "f64",
+        Builtin::Bool => "bool",
+        Builtin::Str => "str",
+        Builtin::DateTime => "datetime",
+        Builtin::Complex => "complex",
+        Builtin::Binary => "binary",
+        Builtin::Base64 => "base64",
+        Builtin::Never => "never",
+    }
+}
+
+fn type_expr(ty: &DeclType) -> crate::Result<String> {
+    Ok(match ty {
+        DeclType::Builtin { ty } => builtin(ty).to_string(),
+        DeclType::Named { reference: to } => reference(to),
+        DeclType::Array { element_type } => format!("{}[]", type_expr(element_type)?),
+        DeclType::SizedArray { element_type, size } => {
+            format!("{}[{size}]", type_expr(element_type)?)
+        },
+        DeclType::Paren { inner_type } => format!("({})", type_expr(inner_type)?),
+        DeclType::TypeExpr {
+            op,
+            target,
+            selectors,
+        } => {
+            let op = match op {
+                DeclTypeExprOp::Pick => "Pick",
+                DeclTypeExprOp::Omit => "Omit",
+                DeclTypeExprOp::Partial => "Partial",
+                DeclTypeExprOp::Required => "Required",
+                DeclTypeExprOp::Exclude => "Exclude",
+                DeclTypeExprOp::Extract => "Extract",
+                DeclTypeExprOp::ArrayItem => "ArrayItem",
+            };
+            match selectors {
+                Some(selectors) => {
+                    format!("{op}[{}, {}]", type_expr(target)?, selectors.join(" | "))
+                },
+                None => format!("{op}[{}]", type_expr(target)?),
+            }
+        },
+        DeclType::Result { .. } | DeclType::Optional { .. } | DeclType::Map { .. } => {
+            return Err(crate::InternalError::internal(format!(
+                "{ty:?} cannot be written in schema source"
+            ))
+            .unlocated()
+            .build()
+            .into());
+        },
+    })
+}
+
+/// `(package, top-level namespace)` of every reference made in `ns` and its nested
+/// namespaces.
+fn collect_imports(
+    ns: &DeclNamespace,
+    imports: &mut BTreeSet<(String, String)>,
+) {
+    let mut add = |reference: &DeclNamedItemContext| {
+        if let Some(first) = reference.context.namespace.first() {
+            imports.insert((reference.context.package.clone(), first.clone()));
+        }
+    };
+
+    fn visit(
+        ty: &DeclType,
+        add: &mut dyn FnMut(&DeclNamedItemContext),
+    ) {
+        match ty {
+            DeclType::Named { reference } => add(reference),
+            DeclType::Result { ok_type, error } => {
+                visit(ok_type, add);
+                add(error);
+            },
+            DeclType::Array { element_type } | DeclType::SizedArray { element_type, .. } => {
+                visit(element_type, add)
+            },
+            DeclType::Optional { inner_type } | DeclType::Paren { inner_type } => {
+                visit(inner_type, add)
+            },
+            DeclType::Map {
+                key_type,
+                value_type,
+            } => {
+                visit(key_type, add);
+                visit(value_type, add);
+            },
+            DeclType::TypeExpr { target, .. } => visit(target, add),
+            DeclType::Builtin { .. } => {},
+        }
+    }
+
+    if let Some(error) = &ns.error {
+        add(error);
+    }
+    for ty in &ns.types {
+        match ty {
+            TypeDefinition::Struct(s) => {
+                for field in &s.fields {
+                    visit(&field.ty, &mut add);
+                }
+            },
+            TypeDefinition::OneOf(o) => {
+                for variant in &o.variants {
+                    visit(&variant.ty, &mut add);
+                }
+            },
+            TypeDefinition::Error(e) => {
+                for variant in &e.variants {
+                    visit(&variant.ty, &mut add);
+                }
+            },
+            TypeDefinition::TypeAlias(t) => visit(&t.target, &mut add),
+            TypeDefinition::Operation(op) => {
+                for arg in &op.args {
+                    visit(&arg.ty, &mut add);
+                }
+                visit(&op.return_type, &mut add);
+            },
+            TypeDefinition::Enum(_) => {},
+        }
+    }
+    for nested in ns.namespaces.values() {
+        collect_imports(nested, imports);
+    }
+}
@@ -140,40 +140,40 @@ impl DeclType {
         &self,
         root_package: &str,
         refs: &mut HashSet<DeclNamedItemContext>,
+    ) {
+        self.for_each_ref(&mut |reference| {
+            if reference.is_external(root_package) {
+                refs.insert(reference.clone());
+            }
+        });
+    }
+
+    /// Calls `f` with each declaration this type refers to, including result errors.
+    pub fn for_each_ref(
+        &self,
+        f: &mut impl FnMut(&DeclNamedItemContext),
     ) {
         match self {
-            Self::Named { reference } => {
-                if reference.is_external(root_package) {
-                    refs.insert(reference.clone());
-                }
-            },
-            Self::Array { element_type } => {
-                element_type.collect_external_refs(root_package, refs);
-            },
-            Self::SizedArray { element_type, .. } => {
-                element_type.collect_external_refs(root_package, refs);
+            Self::Named { reference } => f(reference),
+            Self::Array { element_type } | Self::SizedArray { element_type, .. } => {
+                element_type.for_each_ref(f);
             },
             Self::Result { ok_type, error } => {
-                ok_type.collect_external_refs(root_package, refs);
-                if error.is_external(root_package) {
-                    refs.insert(error.clone());
-                }
+                ok_type.for_each_ref(f);
+                f(error);
             },
-            Self::Optional { inner_type } => {
-                inner_type.collect_external_refs(root_package, refs);
+            Self::Optional { inner_type } | Self::Paren { inner_type } => {
+                inner_type.for_each_ref(f);
             },
             Self::Map {
                 key_type,
                 value_type,
             } => {
-                key_type.collect_external_refs(root_package, refs);
-                value_type.collect_external_refs(root_package, refs);
-            },
-            Self::Paren { inner_type } => {
-                inner_type.collect_external_refs(root_package, refs);
+                key_type.for_each_ref(f);
+                value_type.for_each_ref(f);
             },
             Self::TypeExpr { target, .. } => {
-                target.collect_external_refs(root_package, refs);
+                target.for_each_ref(f);
             },
             Self::Builtin { .. } => {},
         }
@@ -0,0 +1,647 @@
+//! Checking JSON values against the types of a package, from its declarations alone, so that
+//! gateways in other languages validate payloads without compiling the schema.
+
+use convert_case::{Case, Casing};
+use serde_json::{Map, Value};
+
+use super::{
+    Builtin, DeclConstraint, DeclEnum, DeclEnumAlias, DeclField, DeclNamespace, DeclOneOfVariant,
+    DeclTagStyle, DeclTagging, DeclType, DeclarationBundle, TypeDefinition,
+    TypeRegistryDeclaration, context::DeclNamedItemContext,
+};
+
+/// Nesting limit for recursive types; deeper values are accepted unchecked.
+const MAX_DEPTH: usize = 32;
+
+/// Field a `TypeHint` tagged variant names itself with.
+const TYPE_HINT: &str = "@kintsu";
+
+impl DeclarationBundle {
+    /// Why `value` is not a value of the type at `path`, e.g. `users::User`, one reason per
+    /// mismatch. Types of dependencies are found by the package they are declared in, e.g.
+    /// `billing::invoices::Invoice`.
+    pub fn check_value(
+        &self,
+        path: &str,
+        value: &Value,
+    ) -> Result<Vec<String>, String> {
+        let segments = path.split("::").collect::<Vec<_>>();
+        let def = self
+            .find(&self.root, &segments)
+            .or_else(|| {
+                let (package, rest) = segments.split_first()?;
+                let registry = self
+                    .dependencies
+                    .get(&package.to_case(Case::Snake))?;
+                self.find(registry, rest)
+            })
+            .ok_or_else(|| format!("'{path}' is not a type of the package"))?;
+
+        let mut checker = Checker {
+            bundle: self,
+            errors: Vec::new(),
+        };
+        checker.check_definition(def, value, "$", 0);
+        Ok(checker.errors)
+    }
+
+    fn find<'a>(
+        &self,
+        registry: &'a TypeRegistryDeclaration,
+        path: &[&str],
+    ) -> Option<&'a TypeDefinition> {
+        let (name, namespace) = path.split_last()?;
+        let (root, nested) = namespace.split_first()?;
+        let ns = nested
+            .iter()
+            .try_fold(registry.namespaces.get(*root)?, |ns, segment| {
+                ns.namespaces.get(*segment).map(Box::as_ref)
+            })?;
+        find_in(ns, name)
+    }
+
+    /// The definition `reference` names, in the root package or one of its dependencies.
+    fn resolve(
+        &self,
+        reference: &DeclNamedItemContext,
+    ) -> Option<&TypeDefinition> {
+        let package = &reference.context.package;
+        let registry = if self.root.package.to_case(Case::Snake) == *package {
+            &self.root
+        } else {
+            self.dependencies.get(package)?
+        };
+        let path = reference
+            .context
+            .namespace
+            .iter()
+            .map(String::as_str)
+            .chain([reference.name.as_str()])
+            .collect::<Vec<_>>();
+        self.find(registry, &path)
+    }
+}
+
+fn find_in<'a>(
+    ns: &'a DeclNamespace,
+    name: &str,
+) -> Option<&'a TypeDefinition> {
+    ns.types
+        .iter()
+        .find(|def| def.name() == name)
+}
+
+struct Checker<'a> {
+    bundle: &'a DeclarationBundle,
+    errors: Vec<String>,
+}
+
+impl Checker<'_> {
+    fn mismatch(
+        &mut self,
+        path: &str,
+        expected: &str,
+        value: &Value,
+    ) {
+        self.errors.push(format!(
+            "at {path}: expected {expected}, found {}",
+            json_kind(value)
+        ));
+    }
+
+    fn check_definition(
+        &mut self,
+        def: &TypeDefinition,
+        value: &Value,
+        path: &str,
+        depth: usize,
+    ) {
+        match def {
+            TypeDefinition::Struct(def) => self.check_fields(&def.fields, value, path, depth),
+            TypeDefinition::TypeAlias(def) => self.check(&def.target, value, path, depth),
+            TypeDefinition::Enum(def) => {
+                let accepted = match &def.enum_def {
+                    DeclEnum::Int(variants) => {
+                        variants.iter().any(|variant| {
+                            value.as_i64() == Some(variant.value.into())
+                                || variant
+                                    .aliases
+                                    .iter()
+                                    .any(|alias| alias_is(alias, value))
+                        })
+                    },
+                    DeclEnum::String(variants) => {
+                        variants.iter().any(|variant| {
+                            value.as_str() == Some(&variant.value)
+                                || variant
+                                    .aliases
+                                    .iter()
+                                    .any(|alias| alias_is(alias, value))
+                        })
+                    },
+                };
+                if !accepted {
+                    self.errors.push(format!(
+                        "at {path}: {value} is not a value of enum '{}'",
+                        def.name
+                    ));
+                }
+            },
+            TypeDefinition::OneOf(def) => {
+                self.check_variants(&def.name, &def.variants, &def.tag, value, path, depth)
+            },
+            TypeDefinition::Error(def) => {
+                self.check_variants(&def.name, &def.variants, &def.tag, value, path, depth)
+            },
+            TypeDefinition::Operation(def) => {
+                self.errors.push(format!(
+                    "at {path}: '{}' is an operation, which has no values",
+                    def.name
+                ));
+            },
+        }
+    }
+
+    fn check(
+        &mut self,
+        ty: &DeclType,
+        value: &Value,
+        path: &str,
+        depth: usize,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+
+        match ty {
+            DeclType::Builtin { ty } => {
+                if let Err(expected) = check_builtin(ty, value) {
+                    self.mismatch(path, expected, value);
+                }
+            },
+            DeclType::Named { reference } => {
+                match self.bundle.resolve(reference) {
+                    Some(def) => self.check_definition(def, value, path, depth + 1),
+                    None => {
+                        self.errors
+                            .push(format!("at {path}: '{}' is not declared", reference.name));
+                    },
+                }
+            },
+            DeclType::Array { element_type } => {
+                let Value::Array(items) = value else {
+                    return self.mismatch(path, "array", value);
+                };
+                for (i, item) in items.iter().enumerate() {
+                    self.check(element_type, item, &format!("{path}[{i}]"), depth + 1);
+                }
+            },
+            DeclType::SizedArray { element_type, size } => {
+                let Value::Array(items) = value else {
+                    return self.mismatch(path, "array", value);
+                };
+                if items.len() as u64 != *size {
+                    self.errors.push(format!(
+                        "at {path}: expected {size} elements, found {}",
+                        items.len()
+                    ));
+                }
+                for (i, item) in items.iter().enumerate() {
+                    self.check(element_type, item, &format!("{path}[{i}]"), depth + 1);
+                }
+            },
+            DeclType::Optional { inner_type } => {
+                if !value.is_null() {
+                    self.check(inner_type, value, path, depth);
+                }
+            },
+            DeclType::Paren { inner_type } => self.check(inner_type, value, path, depth),
+            DeclType::Result { ok_type, .. } => self.check(ok_type, value, path, depth),
+            DeclType::Map { value_type, .. } => {
+                let Value::Object(entries) = value else {
+                    return self.mismatch(path, "object", value);
+                };
+                for (key, entry) in entries {
+                    self.check(value_type, entry, &format!("{path}.{key}"), depth + 1);
+                }
+            },
+            // type expressions are resolved by generators, so their values are not checked
+            DeclType::TypeExpr { .. } => {},
+        }
+    }
+
+    fn check_fields(
+        &mut self,
+        fields: &[DeclField],
+        value: &Value,
+        path: &str,
+        depth: usize,
+    ) {
+        let Value::Object(object) = value else {
+            return self.mismatch(path, "object", value);
+        };
+
+        for key in object.keys() {
+            if key != TYPE_HINT && !fields.iter().any(|field| field.name == *key) {
+                self.errors
+                    .push(format!("at {path}: unknown field '{key}'"));
+            }
+        }
+
+        for field in fields {
+            let optional = field.optional
+                || field.default_value.is_some()
+                || matches!(field.ty, DeclType::Optional { .. });
+            match object.get(&field.name) {
+                None if !optional => {
+                    self.errors.push(format!(
+                        "at {path}: missing required field '{}'",
+                        field.name
+                    ));
+                },
+                None => {},
+                Some(Value::Null) if optional => {},
+                Some(inner) => {
+                    let path = format!("{path}.{}", field.name);
+                    self.check(&field.ty, inner, &path, depth + 1);
+                    for constraint in &field.constraints {
+                        if let Err(reason) = check_constraint(constraint, inner) {
+                            self.errors
+                                .push(format!("at {path}: {reason}"));
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    /// Whether `value` is a value of `ty`, without reporting why not.
+    fn accepts(
+        &self,
+        ty: &DeclType,
+        value: &Value,
+        depth: usize,
+    ) -> bool {
+        let mut checker = Checker {
+            bundle: self.bundle,
+            errors: Vec::new(),
+        };
+        checker.check(ty, value, "$", depth);
+        checker.errors.is_empty()
+    }
+
+    fn check_variants(
+        &mut self,
+        name: &str,
+        variants: &[DeclOneOfVariant],
+        tag: &DeclTagging,
+        value: &Value,
+        path: &str,
+        depth: usize,
+    ) {
+        let variant = |name: &str| {
+            variants
+                .iter()
+                .position(|variant| variant.name == name)
+        };
+
+        let tagged = match &tag.style {
+            DeclTagStyle::TypeHint | DeclTagStyle::Untagged => None,
+            DeclTagStyle::External => {
+                match value
+                    .as_object()
+                    .filter(|object| object.len() == 1)
+                {
+                    Some(object) => {
+                        let (key, content) = object.iter().next().unwrap();
+                        Some(variant(key).map(|index| (index, content.clone())))
+                    },
+                    None => return self.mismatch(path, "object with one variant", value),
+                }
+            },
+            DeclTagStyle::Internal { tag } => {
+                let Some(object) = value.as_object() else {
+                    return self.mismatch(path, "object", value);
+                };
+                Some(
+                    object
+                        .get(tag)
+                        .and_then(Value::as_str)
+                        .and_then(variant)
+                        .map(|index| (index, without(object, tag))),
+                )
+            },
+            DeclTagStyle::Adjacent { tag, content } => {
+                let Some(object) = value.as_object() else {
+                    return self.mismatch(path, "object", value);
+                };
+                Some(
+                    object
+                        .get(tag)
+                        .and_then(Value::as_str)
+                        .and_then(variant)
+                        .map(|index| {
+                            let content = object
+                                .get(content)
+                                .cloned()
+                                .unwrap_or(Value::Null);
+                            (index, content)
+                        }),
+                )
+            },
+            DeclTagStyle::Index { tag: Some(tag) } => {
+                let Some(object) = value.as_object() else {
+                    return self.mismatch(path, "object", value);
+                };
+                Some(
+                    object
+                        .get(tag)
+                        .and_then(Value::as_u64)
+                        .map(|index| index as usize)
+                        .filter(|index| *index < variants.len())
+                        .map(|index| (index, without(object, tag))),
+                )
+            },
+            DeclTagStyle::Index { tag: None } => None,
+        };
+
+        match tagged {
+            Some(Some((index, content))) => {
+                let path = format!("{path}.{}", variants[index].name);
+                self.check(&variants[index].ty, &content, &path, depth + 1);
+            },
+            Some(None) => {
+                self.errors
+                    .push(format!("at {path}: does not name a variant of '{name}'"));
+            },
+            // told apart by shape, so any variant the value matches will do
+            None => {
+                let value = match value {
+                    Value::Object(object) => without(object, TYPE_HINT),
+                    value => value.clone(),
+                };
+                if !variants
+                    .iter()
+                    .any(|variant| self.accepts(&variant.ty, &value, depth + 1))
+                {
+                    self.errors
+                        .push(format!("at {path}: matches no variant of '{name}'"));
+                }
+            },
+        }
+    }
+}
+
+/// `object` without the field `key`.
+fn without(
+    object: &Map<String, Value>,
+    key: &str,
+) -> Value {
+    let mut object = object.clone();
+    object.remove(key);
+    Value::Object(object)
+}
+
+fn alias_is(
+    alias: &DeclEnumAlias,
+    value: &Value,
+) -> bool {
+    match alias {
+        DeclEnumAlias::Int(alias) => value.as_i64() == Some(*alias),
+        DeclEnumAlias::Str(alias) => value.as_str() == Some(alias),
+    }
+}
+
+fn check_builtin(
+    builtin: &Builtin,
+    value: &Value,
+) -> Result<(), &'static str> {
+    let int = |name: &'static str, min: i128, max: i128| {
+        let n = value
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| value.as_u64().map(i128::from));
+        match n {
+            Some(n) if (min..=max).contains(&n) => Ok(()),
+            _ => Err(name),
+        }
+    };
+
+    match builtin {
+        Builtin::I8 => int("i8", i8::MIN.into(), i8::MAX.into()),
+        Builtin::I16 => int("i16", i16::MIN.into(), i16::MAX.into()),
+        Builtin::I32 => int("i32", i32::MIN.into(), i32::MAX.into()),
+        Builtin::I64 => int("i64", i64::MIN.into(), i64::MAX.into()),
+        Builtin::U8 => int("u8", 0, u8::MAX.into()),
+        Builtin::U16 => int("u16", 0, u16::MAX.into()),
+        Builtin::U32 => int("u32", 0, u32::MAX.into()),
+        Builtin::U64 | Builtin::Usize => int("u64", 0, u64::MAX.into()),
+        Builtin::F16 | Builtin::F32 | Builtin::F64 if value.is_number() => Ok(()),
+        Builtin::F16 | Builtin::F32 | Builtin::F64 => Err("number"),
+        Builtin::Bool if value.is_boolean() => Ok(()),
+        Builtin::Bool => Err("bool"),
+        Builtin::Str | Builtin::DateTime | Builtin::Binary | Builtin::Base64
+            if value.is_string() =>
+        {
+            Ok(())
+        },
+        Builtin::Str | Builtin::DateTime | Builtin::Binary | Builtin::Base64 => Err("string"),
+        Builtin::Complex => Ok(()),
+        Builtin::Never => Err("no value (never)"),
+    }
+}
+
+fn check_constraint(
+    constraint: &DeclConstraint,
+    value: &Value,
+) -> Result<(), String> {
+    match constraint {
+        DeclConstraint::Min { value: min } => {
+            match value.as_f64() {
+                Some(n) if n < *min as f64 => Err(format!("{value} is less than {min}")),
+                _ => Ok(()),
+            }
+        },
+        DeclConstraint::Max { value: max } => {
+            match value.as_f64() {
+                Some(n) if n > *max as f64 => Err(format!("{value} is greater than {max}")),
+                _ => Ok(()),
+            }
+        },
+        DeclConstraint::Pattern { value: pattern } => {
+            let Some(text) = value.as_str() else {
+                return Ok(());
+            };
+            match regex::Regex::new(pattern) {
+                Ok(re) if re.is_match(text) => Ok(()),
+                Ok(_) => Err(format!("'{text}' does not match /{pattern}/")),
+                // patterns are checked when the schema compiles
+                Err(_) => Ok(()),
+            }
+        },
+        DeclConstraint::Len { min, max } => {
+            let len = match value {
+                Value::String(text) => text.chars().count(),
+                Value::Array(items) => items.len(),
+                _ => return Ok(()),
+            } as u64;
+            match (min, max) {
+                (Some(min), _) if len < *min => Err(format!("length {len} is less than {min}")),
+                (_, Some(max)) if len > *max => Err(format!("length {len} is greater than {max}")),
+                _ => Ok(()),
+            }
+        },
+    }
+}
+
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn named(name: &str) -> Value {
+        json!({
+            "type": "named",
+            "reference": {
+                "context": { "package": "shop", "namespace": ["orders"] },
+                "name": name,
+            },
+        })
+    }
+
+    fn bundle(tag: Value) -> DeclarationBundle {
+        serde_json::from_value(json!({
+            "root": {
+                "package": "shop",
+                "namespaces": {
+                    "orders": {
+                        "name": "orders",
+                        "types": [
+                            {
+                                "definition_type": "enum",
+                                "name": "Status",
+                                "enum_def": {
+                                    "enum_type": "string",
+                                    "variants": [
+                                        { "name": "Open", "value": "open", "aliases": ["opened"] },
+                                        { "name": "Paid", "value": "paid" },
+                                    ],
+                                },
+                                "meta": { "version": 1 },
+                            },
+                            {
+                                "definition_type": "one_of",
+                                "name": "Payment",
+                                "variants": [
+                                    { "name": "card", "ty": named("Card") },
+                                    { "name": "cash", "ty": { "type": "builtin", "ty": "u32" } },
+                                ],
+                                "meta": { "version": 1 },
+                                "tag": tag,
+                            },
+                            {
+                                "definition_type": "struct",
+                                "name": "Card",
+                                "fields": [{
+                                    "name": "number",
+                                    "ty": { "type": "builtin", "ty": "str" },
+                                    "constraints": [{ "kind": "pattern", "value": "^[0-9]{4}$" }],
+                                }],
+                                "meta": { "version": 1 },
+                            },
+                            {
+                                "definition_type": "struct",
+                                "name": "Order",
+                                "fields": [
+                                    { "name": "status", "ty": named("Status") },
+                                    { "name": "payment", "ty": named("Payment") },
+                                    {
+                                        "name": "notes",
+                                        "ty": {
+                                            "type": "array",
+                                            "element_type": { "type": "builtin", "ty": "str" },
+                                        },
+                                        "optional": true,
+                                    },
+                                ],
+                                "meta": { "version": 1 },
+                            },
+                        ],
+                    },
+                },
+                "external_refs": [],
+            },
+            "dependencies": {},
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn checks_fields_through_references() {
+        let bundle = bundle(json!({ "style": "type_hint", "type_hint": true }));
+        let check = |value: Value| {
+            bundle
+                .check_value("orders::Order", &value)
+                .unwrap()
+        };
+
+        assert_eq!(
+            check(json!({ "status": "opened", "payment": { "number": "1234" } })),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            check(json!({ "status": "closed", "payment": 5, "notes": [1], "extra": true })),
+            vec![
+                "at $: unknown field 'extra'",
+                "at $.status: \"closed\" is not a value of enum 'Status'",
+                "at $.notes[0]: expected string, found number",
+            ]
+        );
+        assert_eq!(
+            check(json!({ "payment": { "number": "12" } })),
+            vec![
+                "at $: missing required field 'status'",
+                "at $.payment: matches no variant of 'Payment'",
+            ]
+        );
+        assert_eq!(
+            bundle.check_value("orders::Refund", &json!({})),
+            Err("'orders::Refund' is not a type of the package".into())
+        );
+    }
+
+    #[test]
+    fn checks_tagged_variants() {
+        let bundle = bundle(json!({ "style": "internal", "tag": "kind", "type_hint": false }));
+        let check = |value: Value| {
+            bundle
+                .check_value("orders::Payment", &value)
+                .unwrap()
+        };
+
+        assert_eq!(
+            check(json!({ "kind": "card", "number": "1234" })),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            check(json!({ "kind": "card", "number": "12" })),
+            vec!["at $.card.number: '12' does not match /^[0-9]{4}$/"]
+        );
+        assert_eq!(
+            check(json!({ "kind": "cheque" })),
+            vec!["at $: does not name a variant of 'Payment'"]
+        );
+    }
+}
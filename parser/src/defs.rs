@@ -161,7 +161,9 @@ impl<T: ToTokens> ToTokens for Spanned<T> {
         &self,
         tt: &mut crate::fmt::Printer,
     ) {
-        tt.write(&self.value)
+        tt.leading_trivia(&self.span);
+        tt.write(&self.value);
+        tt.trailing_trivia(&self.span);
     }
 }
 
@@ -170,7 +172,7 @@ impl<T: ToTokens> ToTokens for &Spanned<T> {
         &self,
         tt: &mut crate::fmt::Printer,
     ) {
-        tt.write(&self.value)
+        (*self).write(tt)
     }
 }
 
@@ -1,12 +1,22 @@
 use kintsu_cli_core::ProgressManager;
 use kintsu_manifests::NewForConfig;
 use miette::IntoDiagnostic;
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
-use crate::tokens::tokenize_with;
+use crate::{
+    defs::Spanned,
+    diagnostics::SpanDiagnostic,
+    tokens::{tokenize, toks::Token},
+};
 
 pub mod printer;
+pub mod range;
+pub mod resolve;
+pub mod trivia;
 pub use printer::*;
+pub use range::{TextEdit, format_range};
+pub use resolve::FormatResolver;
+pub use trivia::Trivia;
 
 fn default_width() -> usize {
     120
@@ -48,22 +58,200 @@ impl Default for FormatConfig {
     }
 }
 
+/// Formats the schema source of the file or buffer `name`, keeping its comments and blank
+/// lines. Errors carry `source`, so they are reported against it.
+pub fn format_source(
+    name: impl AsRef<Path>,
+    source: &str,
+    config: &FormatConfig,
+) -> crate::Result<String> {
+    let with_source =
+        |err: crate::Error| err.with_source(name.as_ref().to_path_buf(), Arc::new(source.into()));
+
+    let mut tokens = tokenize(source).map_err(|err| with_source(err.into()))?;
+    let ast =
+        crate::ast::AstStream::from_tokens(&mut tokens).map_err(|err| with_source(err.into()))?;
+
+    let mut p = Printer::with_trivia(config, Trivia::from_tokens(&tokens));
+    p.write(&ast);
+    p.finish_trivia();
+    Ok(p.buf)
+}
+
+/// Formats `source` as read from stdin, with the settings resolved for `name` on top of the
+/// `op-fmt` config in `config_dir`. Nothing is written: editors pipe their buffer through
+/// and replace it with the result only on success.
+pub fn format_stdin<S: AsRef<str>>(
+    config_dir: Option<S>,
+    name: impl AsRef<Path>,
+    source: &str,
+    check_idempotent: bool,
+) -> crate::Result<String> {
+    let name = name.as_ref();
+    let config = FormatResolver::new(
+        FormatConfig::new(config_dir)?,
+        Arc::new(kintsu_fs::physical::Physical),
+    )
+    .resolve(name)?;
+
+    let formatted = format_source(name, source, &config)?;
+    if check_idempotent {
+        verify_idempotent(&config, name, source, &formatted).map_err(|report| {
+            kintsu_errors::InternalError::internal(report.to_string())
+                .unlocated()
+                .build()
+        })?;
+    }
+    Ok(formatted)
+}
+
+pub(crate) fn report(err: crate::Error) -> miette::Report {
+    err.to_report(None, None, None)
+}
+
+fn is_comment(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::CommentSingleLine(_) | Token::CommentMultiLine(_)
+    )
+}
+
+/// Tokens carrying meaning: separators and braces are left out since formatting may
+/// add or drop them, such as the braces around a single `use` item.
+fn is_code(token: &Token) -> bool {
+    !is_comment(token)
+        && !matches!(
+            token,
+            Token::Newline
+                | Token::Space
+                | Token::Tab
+                | Token::Comma
+                | Token::Semi
+                | Token::LBrace
+                | Token::RBrace
+        )
+}
+
+fn tokens_where(
+    data: &str,
+    keep: fn(&Token) -> bool,
+) -> Vec<Spanned<Token>> {
+    tokenize(data)
+        .map(|tokens| {
+            tokens
+                .all()
+                .iter()
+                .filter(|token| keep(&token.value))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Checks that `formatted`, the output of [`format_source`] for `data`, keeps every
+/// token and comment of `data` and is left unchanged when formatted again.
+pub fn verify_idempotent(
+    config: &FormatConfig,
+    path: impl AsRef<Path>,
+    data: &str,
+    formatted: &str,
+) -> miette::Result<()> {
+    let path = path.as_ref();
+
+    let before = tokens_where(data, is_comment);
+    let after = tokens_where(formatted, is_comment);
+    if let Some(lost) = before.iter().find(|comment| {
+        !after
+            .iter()
+            .any(|kept| kept.value == comment.value)
+    }) {
+        return Err(SpanDiagnostic::new(
+            lost,
+            path,
+            data,
+            format!(
+                "formatting dropped {} comment(s)",
+                before
+                    .len()
+                    .saturating_sub(after.len())
+                    .max(1)
+            ),
+            "this comment is missing from the formatted output",
+            Some("this is a formatter bug - please report it".into()),
+        )
+        .into());
+    }
+
+    let before = tokens_where(data, is_code);
+    let after = tokens_where(formatted, is_code);
+    if let Some(changed) = before
+        .iter()
+        .zip(
+            after
+                .iter()
+                .map(Some)
+                .chain(std::iter::repeat(None)),
+        )
+        .find(|(token, kept)| kept.is_none_or(|kept| kept.value != token.value))
+        .map(|(token, _)| token)
+    {
+        return Err(SpanDiagnostic::new(
+            changed,
+            path,
+            data,
+            "formatting changed the schema",
+            "this token is missing or altered in the formatted output",
+            Some("this is a formatter bug - please report it".into()),
+        )
+        .into());
+    }
+
+    let second = format_source(path, formatted, config).map_err(report)?;
+    if let Some(offset) = formatted
+        .char_indices()
+        .zip(second.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((offset, _), _)| offset)
+        .or_else(|| (formatted.len() != second.len()).then_some(formatted.len().min(second.len())))
+    {
+        let line_end = formatted[offset..]
+            .find('\n')
+            .map_or(formatted.len(), |len| offset + len);
+        return Err(SpanDiagnostic::new(
+            &Spanned::new(offset, line_end, ()),
+            path,
+            formatted,
+            "formatting is not idempotent",
+            "formatting again changes this line",
+            Some("this is a formatter bug - please report it".into()),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
 async fn format_file(
     config: &FormatConfig,
     target: impl AsRef<std::path::Path>,
     dry: bool,
+    check_idempotent: bool,
 ) -> miette::Result<Vec<miette::Report>> {
-    let data = tokio::fs::read_to_string(&target)
+    use kintsu_fs::FileSystem;
+
+    let fs = kintsu_fs::physical::Physical;
+    let data = fs
+        .read_to_string(target.as_ref())
         .await
         .into_diagnostic()?;
 
-    let mut tokens = tokenize_with(&target, &data)?;
-    let ast = crate::ast::AstStream::from_tokens_with(&target, &mut tokens)
-        .map_err(|err| err.to_report(None, None, None))?;
-    let formatted = crate::fmt::printer::print_ast(&ast, config);
+    let formatted = format_source(&target, &data, config).map_err(report)?;
+    if check_idempotent {
+        verify_idempotent(config, &target, &data, &formatted)?;
+    }
 
     if !dry && data != formatted {
-        tokio::fs::write(&target, formatted)
+        fs.write(target.as_ref(), formatted.into_bytes())
             .await
             .into_diagnostic()?;
     }
@@ -76,25 +264,36 @@ pub async fn fmt<S: AsRef<str>>(
     targets: Vec<impl AsRef<std::path::Path> + Send + Sync>,
     dry: bool,
 ) -> miette::Result<()> {
-    fmt_with_progress(config_dir, targets, dry, ProgressManager::disabled()).await
+    fmt_with_progress(config_dir, targets, dry, false, ProgressManager::disabled()).await
 }
 
+/// Formats `targets`, each with the settings resolved for its directory on top of the
+/// `op-fmt` config in `config_dir`. With `check_idempotent`, a file is only written once its
+/// formatted form is verified to keep every comment and to be stable under formatting
+/// again.
 pub async fn fmt_with_progress<S: AsRef<str>>(
     config_dir: Option<S>,
     targets: Vec<impl AsRef<std::path::Path> + Send + Sync>,
     dry: bool,
+    check_idempotent: bool,
     progress: ProgressManager,
 ) -> miette::Result<()> {
-    let config = Arc::new(FormatConfig::new(config_dir).into_diagnostic()?);
+    let resolver = Arc::new(FormatResolver::new(
+        FormatConfig::new(config_dir).into_diagnostic()?,
+        Arc::new(kintsu_fs::physical::Physical),
+    ));
     let bar = progress.add_bar(targets.len() as u64, kintsu_cli_core::prefixes::FORMATTING);
 
     let mut futs = vec![];
     for t in targets {
-        let config = config.clone();
+        let resolver = resolver.clone();
         let bar = bar.clone();
         futs.push(Box::pin(async move {
             let path_display = t.as_ref().display().to_string();
-            let result = format_file(&config, &t, dry).await;
+            let result = match resolver.resolve(&t).into_diagnostic() {
+                Ok(config) => format_file(&config, &t, dry, check_idempotent).await,
+                Err(err) => Err(err),
+            };
             bar.inc(1);
             bar.set_message(path_display);
             result
@@ -107,3 +306,97 @@ pub async fn fmt_with_progress<S: AsRef<str>>(
     bar.finish_and_clear();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENTED: &str = r#"namespace test;
+
+use foo::{
+	// leading use
+	a, // after a
+	b
+};
+
+/* op doc */
+operation get(id: i32) -> i32; // trailing op
+
+oneof Result {
+	// ok variant
+	Ok(i32),
+
+	Err(str) // failure
+};
+
+namespace inner {
+	struct In {
+		x: i32 // last
+	};
+	// end inner
+};
+// end of file
+"#;
+
+    #[test]
+    fn keeps_comments_and_blank_lines() {
+        let config = FormatConfig::default();
+        let formatted = format_source("test.ks", COMMENTED, &config).unwrap();
+
+        for comment in [
+            "// leading use",
+            "a, // after a",
+            "/* op doc */",
+            "-> i32; // trailing op",
+            "// ok variant",
+            "Err(str) // failure",
+            "x: i32 // last",
+            "// end inner",
+            "// end of file",
+        ] {
+            assert!(
+                formatted.contains(comment),
+                "missing `{comment}` in:\n{formatted}"
+            );
+        }
+        assert!(
+            formatted.contains("Ok(i32),\n\n"),
+            "blank line lost in:\n{formatted}"
+        );
+
+        verify_idempotent(&config, "test.ks", COMMENTED, &formatted).unwrap();
+    }
+
+    #[test]
+    fn errors_carry_the_source() {
+        let config = FormatConfig::default();
+        let source = "namespace a;\nstruct A {\n";
+
+        let err: kintsu_errors::CompilerError = format_source("buffer.ks", source, &config)
+            .unwrap_err()
+            .into();
+        assert_eq!(err.extract_source(), Some((Path::new("buffer.ks"), source)));
+    }
+
+    #[test]
+    fn reports_dropped_tokens() {
+        let config = FormatConfig::default();
+        let data = "namespace test;\n\nuse foo::{a, b};\n";
+
+        let err = verify_idempotent(&config, "test.ks", data, "namespace test;\n\nuse foo::a;\n")
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("changed the schema")
+        );
+
+        let err = verify_idempotent(
+            &config,
+            "test.ks",
+            "// note\nnamespace test;\n",
+            "namespace test;\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("dropped 1 comment"));
+    }
+}
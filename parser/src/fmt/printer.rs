@@ -1,6 +1,10 @@
-use crate::tokens::{ToTokens, toks::Token};
+use crate::{
+    ast::comment::CommentAst,
+    defs::{Spanned, span::Span},
+    tokens::{CommentMultiLineToken, CommentSingleLineToken, ToTokens, toks::Token},
+};
 
-use super::FormatConfig;
+use super::{FormatConfig, trivia::Trivia};
 
 #[derive(Default, Clone, Debug)]
 pub struct Printer {
@@ -10,6 +14,12 @@ pub struct Printer {
     pub brace_depth: usize,
     pub bracket_depth: usize,
     pub cfg: FormatConfig,
+    /// Source comments and blank lines to re-attach, when formatting a source file
+    pub trivia: Option<Trivia>,
+    /// End of the last source token written
+    last_end: usize,
+    /// Comments to place at the end of the current line
+    trailing: Vec<String>,
 }
 
 impl Printer {
@@ -21,6 +31,19 @@ impl Printer {
             brace_depth: 0,
             bracket_depth: 0,
             cfg: cfg.clone(),
+            trivia: None,
+            last_end: 0,
+            trailing: Vec::new(),
+        }
+    }
+
+    pub fn with_trivia(
+        cfg: &FormatConfig,
+        trivia: Trivia,
+    ) -> Self {
+        Self {
+            trivia: Some(trivia),
+            ..Self::new(cfg)
         }
     }
 
@@ -43,10 +66,40 @@ impl Printer {
     }
 
     pub fn add_newline(&mut self) {
+        for comment in std::mem::take(&mut self.trailing) {
+            if !self.buf.ends_with([' ', '\t']) {
+                self.space();
+            }
+            self.word(&comment);
+        }
         self.buf.push('\n');
         self.add_indent();
     }
 
+    /// Whether nothing but indentation has been written on the current line.
+    pub fn at_line_start(&self) -> bool {
+        self.buf
+            .rsplit('\n')
+            .next()
+            .is_none_or(|line| line.trim().is_empty())
+    }
+
+    /// Turns the current line into a blank one followed by a new line, unless the previous
+    /// line is already blank or opens a block. Only applies at the start of a line.
+    pub fn blank_line(&mut self) {
+        if !self.cfg.preserve_adjacent_blank_lines || !self.at_line_start() {
+            return;
+        }
+        let Some(newline) = self.buf.rfind('\n') else {
+            return;
+        };
+        let before = self.buf[..newline].trim_end_matches([' ', '\t']);
+        if before.is_empty() || before.ends_with('\n') || before.ends_with('{') {
+            return;
+        }
+        self.buf.insert(newline, '\n');
+    }
+
     pub fn add_indent(&mut self) {
         if self.cfg.indent_with_tabs {
             for _ in 0..self.indent_level {
@@ -65,10 +118,21 @@ impl Printer {
         self.add_newline();
     }
     pub fn close_block(&mut self) {
+        self.dangling_comments();
         if self.indent_level > 0 {
             self.indent_level -= 1;
         }
-        self.add_newline();
+        // the last line of the block may already be open, with nothing on it yet
+        if self.trailing.is_empty() && self.at_line_start() && self.buf.contains('\n') {
+            let line_start = self
+                .buf
+                .rfind('\n')
+                .map_or(0, |newline| newline + 1);
+            self.buf.truncate(line_start);
+            self.add_indent();
+        } else {
+            self.add_newline();
+        }
         self.token(&Token::RBrace);
     }
 
@@ -79,6 +143,144 @@ impl Printer {
         w.write(self);
     }
 
+    /// Emits the comments and blank lines preceding `span` in the source.
+    pub fn leading_trivia(
+        &mut self,
+        span: &Span,
+    ) {
+        let (Span::Known(raw), Some(trivia)) = (span, &self.trivia) else {
+            return;
+        };
+        let start = trivia.token_start(raw.start);
+
+        while let Some(idx) = self
+            .trivia
+            .as_ref()
+            .and_then(|trivia| trivia.next_before(start))
+        {
+            self.emit_comment(idx, false);
+        }
+
+        if self
+            .trivia
+            .as_ref()
+            .is_some_and(|trivia| trivia.is_blank_before(start))
+        {
+            self.blank_line();
+        }
+    }
+
+    /// Records `span` as written and queues comments trailing it on the same line.
+    pub fn trailing_trivia(
+        &mut self,
+        span: &Span,
+    ) {
+        let Span::Known(raw) = span else {
+            return;
+        };
+        let Some(trivia) = &mut self.trivia else {
+            return;
+        };
+        self.last_end = self.last_end.max(raw.end);
+
+        while let Some(idx) = trivia.next_trailing(raw.end) {
+            trivia.mark_emitted(idx);
+            let comment = trivia.comment(idx);
+            self.last_end = comment.end;
+            self.trailing.push(comment.token.to_string());
+        }
+    }
+
+    /// Marks the comment at `span` as emitted by its AST node, returning false if the
+    /// trivia already emitted it.
+    pub fn claim_comment(
+        &mut self,
+        span: &Span,
+    ) -> bool {
+        match (&mut self.trivia, span) {
+            (Some(trivia), Span::Known(raw)) => trivia.claim(raw.start),
+            _ => true,
+        }
+    }
+
+    /// Emits the comments left before the closing brace of the current block.
+    fn dangling_comments(&mut self) {
+        let Some(boundary) = self
+            .trivia
+            .as_ref()
+            .map(|trivia| trivia.next_significant(self.last_end))
+        else {
+            return;
+        };
+
+        while let Some(idx) = self
+            .trivia
+            .as_ref()
+            .and_then(|trivia| trivia.next_before(boundary))
+        {
+            self.emit_comment(idx, true);
+        }
+    }
+
+    /// Writes a comment only the trivia holds. Comments on the line of the code before them
+    /// stay there, as do comments placed inline; the rest get their own line.
+    fn emit_comment(
+        &mut self,
+        idx: usize,
+        own_line: bool,
+    ) {
+        let Some(trivia) = &mut self.trivia else {
+            return;
+        };
+        trivia.mark_emitted(idx);
+        let comment = trivia.comment(idx).clone();
+        let follows_code = self.last_end > 0 && !trivia.spans_lines(self.last_end, comment.start);
+        let blank_before = trivia.is_blank_before(comment.start);
+        self.last_end = self.last_end.max(comment.end);
+
+        if own_line && !follows_code && !self.at_line_start() {
+            self.add_newline();
+        }
+
+        if !self.at_line_start() {
+            if comment.is_single_line() {
+                self.trailing.push(comment.token.to_string());
+            } else {
+                self.word(&comment.token.to_string());
+                self.space();
+            }
+            return;
+        }
+
+        if blank_before {
+            self.blank_line();
+        }
+        let ast = match comment.token {
+            Token::CommentSingleLine(text) => {
+                CommentAst::SingleLine(Spanned::call_site(CommentSingleLineToken::new(text)))
+            },
+            Token::CommentMultiLine(text) => {
+                CommentAst::MultiLine(Spanned::call_site(CommentMultiLineToken::new(text)))
+            },
+            _ => return,
+        };
+        self.write(&ast);
+    }
+
+    /// Writes the comments no node claimed, such as those at the end of the source.
+    pub fn finish_trivia(&mut self) {
+        while let Some(idx) = self
+            .trivia
+            .as_ref()
+            .and_then(|trivia| trivia.next_before(usize::MAX))
+        {
+            self.emit_comment(idx, true);
+        }
+        if !self.trailing.is_empty() {
+            self.add_newline();
+        }
+    }
+
     pub fn write_comma_separated<T, I>(
         &mut self,
         items: I,
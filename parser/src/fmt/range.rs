@@ -0,0 +1,223 @@
+//! Formatting of part of a file.
+//!
+//! [`format_range`] lets an editor reformat the items it just touched without rewriting the
+//! rest of the file. The whole source is formatted so nested items get the indentation
+//! their context calls for, but only edits inside the items intersecting the range are
+//! returned, each trimmed to the text which actually changes.
+
+use std::ops::Range;
+
+use crate::{
+    ast::{AstStream, items::Items},
+    defs::Spanned,
+};
+
+use super::{FormatConfig, format_source, report};
+
+/// Replacement of the `range` bytes of the source with `new_text`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub new_text: String,
+}
+
+/// Formats the items of `source` intersecting `byte_range`, returning the edits to apply
+/// in source order. Items in a namespace block are formatted on their own when the range
+/// lies within one of them, otherwise the whole block is. An empty range, such as a
+/// cursor position, selects the item it touches.
+pub fn format_range(
+    source: &str,
+    byte_range: Range<usize>,
+    config: &FormatConfig,
+) -> miette::Result<Vec<TextEdit>> {
+    let formatted = format_source("", source, config).map_err(report)?;
+
+    let before = AstStream::from_string_with("", source)?;
+    let after = AstStream::from_string_with("", &formatted)?;
+
+    let mut edits = Vec::new();
+    collect_edits(
+        &before.nodes,
+        &after.nodes,
+        source,
+        &formatted,
+        &byte_range,
+        &mut edits,
+    )?;
+    Ok(edits)
+}
+
+fn collect_edits(
+    before: &[Spanned<Items>],
+    after: &[Spanned<Items>],
+    source: &str,
+    formatted: &str,
+    byte_range: &Range<usize>,
+    edits: &mut Vec<TextEdit>,
+) -> miette::Result<()> {
+    if before.len() != after.len() {
+        miette::bail!(
+            "formatting produced {} items where the source has {} - this is a formatter bug",
+            after.len(),
+            before.len()
+        );
+    }
+
+    for (item, formatted_item) in before.iter().zip(after) {
+        let range = item_range(source, item);
+        if !intersects(&range, byte_range) {
+            continue;
+        }
+
+        if let (Items::SpannedNamespace(ns), Items::SpannedNamespace(formatted_ns)) =
+            (&item.value, &formatted_item.value)
+            && ns.def.ast.nodes.iter().any(|child| {
+                let child = item_range(source, child);
+                child.start <= byte_range.start && byte_range.end <= child.end
+            })
+        {
+            collect_edits(
+                &ns.def.ast.nodes,
+                &formatted_ns.def.ast.nodes,
+                source,
+                formatted,
+                byte_range,
+                edits,
+            )?;
+            continue;
+        }
+
+        let replacement = item_range(formatted, formatted_item);
+        edits.extend(minimal_edit(source, range, &formatted[replacement]));
+    }
+
+    Ok(())
+}
+
+/// The source of an item, from the start of its first line when only indentation precedes
+/// it, so the indentation is formatted too.
+fn item_range(
+    source: &str,
+    item: &Spanned<Items>,
+) -> Range<usize> {
+    let span = item.span.span();
+    let text = &source[span.start..span.end];
+    let start = span.start + (text.len() - text.trim_start().len());
+    let end = span.start + text.trim_end().len();
+
+    let line_start = source[..start]
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1);
+    if source[line_start..start]
+        .chars()
+        .all(|c| c == ' ' || c == '\t')
+    {
+        line_start..end
+    } else {
+        start..end
+    }
+}
+
+fn intersects(
+    item: &Range<usize>,
+    byte_range: &Range<usize>,
+) -> bool {
+    byte_range.start <= item.end && item.start <= byte_range.end
+}
+
+/// The edit turning `source[range]` into `new_text`, without the prefix and suffix they
+/// share.
+fn minimal_edit(
+    source: &str,
+    range: Range<usize>,
+    new_text: &str,
+) -> Option<TextEdit> {
+    let old_text = &source[range.clone()];
+    if old_text == new_text {
+        return None;
+    }
+
+    let prefix = old_text
+        .char_indices()
+        .zip(new_text.chars())
+        .find(|((_, old), new)| old != new)
+        .map_or(old_text.len().min(new_text.len()), |((offset, _), _)| {
+            offset
+        });
+    let suffix = old_text[prefix..]
+        .chars()
+        .rev()
+        .zip(new_text[prefix..].chars().rev())
+        .take_while(|(old, new)| old == new)
+        .map(|(old, _)| old.len_utf8())
+        .sum::<usize>();
+
+    Some(TextEdit {
+        range: range.start + prefix..range.end - suffix,
+        new_text: new_text[prefix..new_text.len() - suffix].to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str =
+        "namespace test;\n\nstruct A {\n  x:   i32\n};\n\nstruct B {\n  y:   i32\n};\n";
+
+    fn apply(
+        source: &str,
+        edits: &[TextEdit],
+    ) -> String {
+        let mut out = source.to_string();
+        for edit in edits.iter().rev() {
+            out.replace_range(edit.range.clone(), &edit.new_text);
+        }
+        out
+    }
+
+    #[test]
+    fn formats_only_intersecting_items() {
+        let config = FormatConfig::default();
+        let b = SOURCE.find("struct B").unwrap();
+
+        let edits = format_range(SOURCE, b..b + 3, &config).unwrap();
+        let out = apply(SOURCE, &edits);
+
+        assert!(out.contains("struct A {\n  x:   i32\n};"), "{out}");
+        assert!(out.contains("struct B {\n\ty: i32\n};"), "{out}");
+        assert!(edits.iter().all(|edit| edit.range.start > b));
+    }
+
+    #[test]
+    fn edits_are_minimal() {
+        let config = FormatConfig::default();
+        let full = format_source("", SOURCE, &config).unwrap();
+
+        let edits = format_range(SOURCE, 0..SOURCE.len(), &config).unwrap();
+        assert_eq!(apply(SOURCE, &edits), full);
+        for edit in &edits {
+            assert!(!edit.new_text.contains("struct"), "{edit:?}");
+        }
+
+        assert!(
+            format_range(&full, 0..full.len(), &config)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn descends_into_namespace_blocks() {
+        let config = FormatConfig::default();
+        let source = "namespace outer {\nstruct A {\nx:   i32\n};\nstruct B {\ny:   i32\n};\n};\n";
+        let cursor = source.find("y:").unwrap();
+
+        let out = apply(
+            source,
+            &format_range(source, cursor..cursor, &config).unwrap(),
+        );
+        assert!(out.contains("struct A {\nx:   i32\n};"), "{out}");
+        assert!(out.contains("\tstruct B {\n\t\ty: i32\n\t};"), "{out}");
+    }
+}
@@ -0,0 +1,123 @@
+//! Resolution of the formatter settings applying to each file.
+//!
+//! The global `op-fmt` config is the base. On top of it come the settings of each directory
+//! from the outermost ancestor of a file down to its own directory, as read by
+//! [`FormatOverrides::in_dir`], so the closest settings win.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use kintsu_fs::FileSystem;
+use kintsu_manifests::fmt::FormatOverrides;
+
+use super::FormatConfig;
+
+impl FormatConfig {
+    /// This config with every setting of `overrides` applied.
+    pub fn with_overrides(
+        self,
+        overrides: &FormatOverrides,
+    ) -> Self {
+        Self {
+            max_width: overrides.max_width.unwrap_or(self.max_width),
+            indent_with_tabs: overrides
+                .indent_with_tabs
+                .unwrap_or(self.indent_with_tabs),
+            indent_width: overrides
+                .indent_width
+                .unwrap_or(self.indent_width),
+            preserve_adjacent_blank_lines: overrides
+                .preserve_adjacent_blank_lines
+                .unwrap_or(self.preserve_adjacent_blank_lines),
+        }
+    }
+}
+
+/// Resolves the [`FormatConfig`] of each file, reading the settings of each directory once.
+pub struct FormatResolver {
+    base: FormatConfig,
+    fs: Arc<dyn FileSystem>,
+    dirs: Mutex<BTreeMap<PathBuf, FormatOverrides>>,
+}
+
+impl FormatResolver {
+    pub fn new(
+        base: FormatConfig,
+        fs: Arc<dyn FileSystem>,
+    ) -> Self {
+        Self {
+            base,
+            fs,
+            dirs: Default::default(),
+        }
+    }
+
+    /// The settings for `file`.
+    pub fn resolve(
+        &self,
+        file: impl AsRef<Path>,
+    ) -> kintsu_manifests::Result<FormatConfig> {
+        let file = file.as_ref();
+        // relative paths run out of ancestors at the working directory
+        let file = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+
+        let mut overrides = FormatOverrides::default();
+        let dirs = file.ancestors().skip(1).collect::<Vec<_>>();
+        for dir in dirs.into_iter().rev() {
+            overrides = overrides.merge(self.in_dir(dir)?);
+        }
+        Ok(self.base.clone().with_overrides(&overrides))
+    }
+
+    fn in_dir(
+        &self,
+        dir: &Path,
+    ) -> kintsu_manifests::Result<FormatOverrides> {
+        if let Some(cached) = self.dirs.lock().unwrap().get(dir) {
+            return Ok(cached.clone());
+        }
+
+        let overrides = FormatOverrides::in_dir(self.fs.as_ref(), dir)?;
+        self.dirs
+            .lock()
+            .unwrap()
+            .insert(dir.to_path_buf(), overrides.clone());
+        Ok(overrides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kintsu_fs::memory::MemoryFileSystem;
+
+    use super::*;
+
+    #[test]
+    fn nearer_settings_win() {
+        let fs = MemoryFileSystem::with_files(vec![
+            (
+                "/ws/schema.toml",
+                "version = \"v1\"\n\n[workspace]\nmembers = [\"pkg\"]\n\n[fmt]\nmax_width = 80\nindent_with_tabs = false\n",
+            ),
+            ("/ws/pkg/schema/.op-fmt.toml", "max_width = 100\n"),
+        ]);
+        let resolver = FormatResolver::new(FormatConfig::default(), Arc::new(fs));
+
+        let nested = resolver
+            .resolve("/ws/pkg/schema/lib.ks")
+            .unwrap();
+        assert_eq!(nested.max_width, 100);
+        assert!(!nested.indent_with_tabs);
+        assert_eq!(nested.indent_width, FormatConfig::default().indent_width);
+
+        let outer = resolver.resolve("/ws/pkg/lib.ks").unwrap();
+        assert_eq!(outer.max_width, 80);
+
+        let elsewhere = resolver.resolve("/other/lib.ks").unwrap();
+        assert_eq!(elsewhere.max_width, FormatConfig::default().max_width);
+        assert!(elsewhere.indent_with_tabs);
+    }
+}
@@ -0,0 +1,198 @@
+//! Comments and blank lines of the source being formatted.
+//!
+//! Only comments before items, fields and variants have a place in the AST. Every other
+//! comment is re-attached by position: the printer consults [`Trivia`] as it writes each
+//! spanned node, emitting comments which precede the node in the source before it and
+//! comments on the same line as the node's end after it. Each comment is emitted exactly
+//! once, whether the AST or the trivia holds it.
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use crate::tokens::{TokenStream, toks::Token};
+
+#[derive(Clone, Debug)]
+pub(crate) struct Comment {
+    pub start: usize,
+    pub end: usize,
+    pub token: Token,
+    emitted: bool,
+}
+
+impl Comment {
+    pub fn is_single_line(&self) -> bool {
+        matches!(self.token, Token::CommentSingleLine(_))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Trivia {
+    source: Arc<str>,
+    comments: Vec<Comment>,
+    /// Starts of tokens preceded by at least one empty line
+    blank_before: BTreeSet<usize>,
+    /// Starts of tokens which are neither whitespace, comments nor separators
+    significant: Vec<usize>,
+    /// Starts of all tokens other than whitespace
+    starts: Vec<usize>,
+}
+
+impl Trivia {
+    pub fn from_tokens(tokens: &TokenStream) -> Self {
+        let mut comments = Vec::new();
+        let mut blank_before = BTreeSet::new();
+        let mut significant = Vec::new();
+        let mut starts = Vec::new();
+        let mut newlines = 0;
+
+        for token in tokens.all() {
+            let span = token.span.span();
+            match &token.value {
+                Token::Newline => {
+                    newlines += 1;
+                    continue;
+                },
+                Token::Space | Token::Tab => continue,
+                Token::CommentSingleLine(_) | Token::CommentMultiLine(_) => {
+                    comments.push(Comment {
+                        start: span.start,
+                        end: span.end,
+                        token: token.value.clone(),
+                        emitted: false,
+                    });
+                },
+                Token::Comma | Token::Semi => {},
+                _ => significant.push(span.start),
+            }
+
+            starts.push(span.start);
+            if newlines > 1 {
+                blank_before.insert(span.start);
+            }
+            newlines = 0;
+        }
+
+        Self {
+            source: Arc::from(tokens.source()),
+            comments,
+            blank_before,
+            significant,
+            starts,
+        }
+    }
+
+    /// The start of the first token at or after `offset`. Node spans may begin at the
+    /// whitespace before their first token.
+    pub(crate) fn token_start(
+        &self,
+        offset: usize,
+    ) -> usize {
+        let idx = self
+            .starts
+            .partition_point(|start| *start < offset);
+        self.starts
+            .get(idx)
+            .copied()
+            .unwrap_or(offset)
+    }
+
+    pub(crate) fn is_blank_before(
+        &self,
+        offset: usize,
+    ) -> bool {
+        self.blank_before
+            .contains(&self.token_start(offset))
+    }
+
+    /// Whether the source between `from` and `to` crosses a line break.
+    pub(crate) fn spans_lines(
+        &self,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        self.source
+            .get(from..to)
+            .is_some_and(|between| between.contains('\n'))
+    }
+
+    /// The first comment not yet emitted which starts before `offset`.
+    pub(crate) fn next_before(
+        &self,
+        offset: usize,
+    ) -> Option<usize> {
+        self.comments
+            .iter()
+            .position(|comment| !comment.emitted && comment.start < offset)
+    }
+
+    /// The first comment not yet emitted which trails the code ending at `end`: it is on the
+    /// same line, only separators come between, and nothing follows it on that line.
+    pub(crate) fn next_trailing(
+        &self,
+        end: usize,
+    ) -> Option<usize> {
+        let idx = self
+            .comments
+            .iter()
+            .position(|comment| !comment.emitted && comment.start >= end)?;
+        let comment = &self.comments[idx];
+
+        let between = self.source.get(end..comment.start)?;
+        if !between
+            .chars()
+            .all(|c| matches!(c, ' ' | '\t' | ',' | ';'))
+        {
+            return None;
+        }
+
+        let rest_of_line = self.source[comment.end..]
+            .split('\n')
+            .next()
+            .unwrap_or_default();
+        (comment.is_single_line() || rest_of_line.trim().is_empty()).then_some(idx)
+    }
+
+    /// The start of the first significant token at or after `offset`, such as the closing
+    /// brace of the block being printed.
+    pub(crate) fn next_significant(
+        &self,
+        offset: usize,
+    ) -> usize {
+        let idx = self
+            .significant
+            .partition_point(|start| *start < offset);
+        self.significant
+            .get(idx)
+            .copied()
+            .unwrap_or(usize::MAX)
+    }
+
+    pub(crate) fn comment(
+        &self,
+        idx: usize,
+    ) -> &Comment {
+        &self.comments[idx]
+    }
+
+    /// Marks the comment starting at `offset` as emitted, returning false if it already was.
+    pub(crate) fn claim(
+        &mut self,
+        offset: usize,
+    ) -> bool {
+        let offset = self.token_start(offset);
+        match self
+            .comments
+            .iter_mut()
+            .find(|comment| comment.start == offset)
+        {
+            Some(comment) => !std::mem::replace(&mut comment.emitted, true),
+            None => true,
+        }
+    }
+
+    pub(crate) fn mark_emitted(
+        &mut self,
+        idx: usize,
+    ) {
+        self.comments[idx].emitted = true;
+    }
+}
@@ -11,6 +11,7 @@ pub mod declare;
 pub mod defs;
 pub mod diagnostics;
 pub mod fmt;
+pub mod lint;
 pub mod tokens;
 pub(crate) mod utils;
 
@@ -23,9 +24,10 @@ use crate::tokens::{LexingError, ToTokens};
 use thiserror::Error;
 
 pub use kintsu_errors::{
-    CompilerError, DomainError, ErrorBuilder, FilesystemError, HasSpan, InternalError,
-    LexicalError, MetadataError, NamespaceError, PackageError, ParsingError, ResolutionError,
-    SourceContext, Span, TaggingError, TypeDefError, TypeExprError, UnionError,
+    Applicability, CompilerError, DomainError, ErrorBuilder, FilesystemError, HasSpan,
+    InternalError, LexicalError, LintError, MetadataError, NamespaceError, PackageError,
+    ParsingError, ResolutionError, SourceContext, Span, Suggestion, TaggingError, TypeDefError,
+    TypeExprError, UnionError,
 };
 pub use tokens::{ImplDiagnostic, Parse, Peek};
 
@@ -203,14 +205,23 @@ impl Error {
                     .build()
             },
             Self::Lexing(e) => {
-                let span = match e {
-                    LexingError::Spanned { span, .. } => {
-                        let s = span.span();
-                        Some(Span::new(s.start, s.end))
+                let span = e.error_span().map(|span| {
+                    let s = span.span();
+                    Span::new(s.start, s.end)
+                });
+                let err = LexicalError::lexer_error(e.to_string()).at_opt(span);
+
+                match e {
+                    LexingError::MissingSemicolon { at, .. } => {
+                        err.with_suggestion(Suggestion::insert(
+                            *at,
+                            ";",
+                            Applicability::MachineApplicable,
+                            "add `;` to end the declaration",
+                        ))
                     },
-                    _ => None,
-                };
-                LexicalError::lexer_error(e.to_string()).at_opt(span)
+                    _ => err,
+                }
             },
             Self::WithSource {
                 inner,
@@ -0,0 +1,339 @@
+//! Rules on the size of declarations. Each threshold can be set under `max` for the rule in
+//! the manifest.
+
+use convert_case::{Case, Casing};
+
+use crate::declare::{DeclError, DeclNamespace, DeclOneOf, DeclStruct, DeclType, TypeDefinition};
+
+use super::{Check, LintCx};
+
+crate::rule! {
+    MaxFields in Complexity @ Warn: Skip; "structs should not have too many fields"
+}
+
+impl MaxFields {
+    const DEFAULT: usize = 32;
+}
+
+impl Check for MaxFields {
+    fn check_struct(
+        &self,
+        def: &DeclStruct,
+        cx: &mut LintCx,
+    ) {
+        let max = cx.max(Self::DEFAULT);
+        if def.fields.len() > max {
+            cx.report(format!(
+                "struct '{}' has {} fields, more than {max}",
+                def.name,
+                def.fields.len()
+            ));
+        }
+    }
+}
+
+crate::rule! {
+    MaxVariants in Complexity @ Warn: Skip; "oneofs should not have too many variants"
+}
+
+impl MaxVariants {
+    const DEFAULT: usize = 32;
+}
+
+impl Check for MaxVariants {
+    fn check_one_of(
+        &self,
+        def: &DeclOneOf,
+        cx: &mut LintCx,
+    ) {
+        let max = cx.max(Self::DEFAULT);
+        if def.variants.len() > max {
+            cx.report(format!(
+                "oneof '{}' has {} variants, more than {max}",
+                def.name,
+                def.variants.len()
+            ));
+        }
+    }
+}
+
+crate::rule! {
+    MaxNesting in Complexity @ Warn: Skip; "anonymous structs should not be nested too deeply"
+}
+
+impl MaxNesting {
+    const DEFAULT: usize = 2;
+
+    /// The struct extracted from the anonymous struct written as `member` of `parent`. The
+    /// extracted struct is named by joining both, as in `anonymous.rs`.
+    fn extracted<'s>(
+        scope: &'s DeclNamespace,
+        cx: &LintCx,
+        parent: &str,
+        member: &str,
+        ty: &DeclType,
+    ) -> Option<&'s DeclStruct> {
+        let reference = match ty {
+            DeclType::Named { reference } => reference,
+            DeclType::Optional { inner_type }
+            | DeclType::Paren { inner_type }
+            | DeclType::Array {
+                element_type: inner_type,
+            }
+            | DeclType::SizedArray {
+                element_type: inner_type,
+                ..
+            } => return Self::extracted(scope, cx, parent, member, inner_type),
+            _ => return None,
+        };
+        if reference.context.package != cx.package()
+            || reference.context.namespace != cx.namespace()
+            || reference.name != format!("{parent}_{member}").to_case(Case::Pascal)
+        {
+            return None;
+        }
+
+        scope.types.iter().find_map(|def| {
+            match def {
+                TypeDefinition::Struct(def) if def.name == reference.name => Some(def),
+                _ => None,
+            }
+        })
+    }
+
+    /// The name and type of each field or variant of `def`.
+    fn members(def: &TypeDefinition) -> Vec<(&str, &DeclType)> {
+        match def {
+            TypeDefinition::Struct(def) => Self::fields(def),
+            TypeDefinition::OneOf(DeclOneOf { variants, .. })
+            | TypeDefinition::Error(DeclError { variants, .. }) => {
+                variants
+                    .iter()
+                    .map(|variant| (variant.name.as_str(), &variant.ty))
+                    .collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    fn fields(def: &DeclStruct) -> Vec<(&str, &DeclType)> {
+        def.fields
+            .iter()
+            .map(|field| (field.name.as_str(), &field.ty))
+            .collect()
+    }
+
+    /// How many anonymous structs deep the members of `parent` go, with the name of the
+    /// deepest.
+    fn depth(
+        scope: &DeclNamespace,
+        cx: &LintCx,
+        parent: &str,
+        members: Vec<(&str, &DeclType)>,
+    ) -> (usize, String) {
+        members
+            .into_iter()
+            .filter_map(|(member, ty)| Self::extracted(scope, cx, parent, member, ty))
+            .map(|child| {
+                match Self::depth(scope, cx, &child.name, Self::fields(child)) {
+                    (0, _) => (1, child.name.clone()),
+                    (depth, deepest) => (depth + 1, deepest),
+                }
+            })
+            .max_by_key(|(depth, _)| *depth)
+            .unwrap_or_default()
+    }
+}
+
+impl Check for MaxNesting {
+    /// Checks the nesting of structs, oneofs and errors, unless they were themselves
+    /// extracted from another type, whose check covers them.
+    fn check_type(
+        &self,
+        def: &TypeDefinition,
+        cx: &mut LintCx,
+    ) {
+        let kind = match def {
+            TypeDefinition::Struct(_) => "struct",
+            TypeDefinition::OneOf(_) => "oneof",
+            TypeDefinition::Error(_) => "error",
+            _ => return,
+        };
+        let Some(scope) = cx.scope() else {
+            return;
+        };
+
+        let nested = scope.types.iter().any(|parent| {
+            Self::members(parent)
+                .into_iter()
+                .any(|(member, ty)| {
+                    Self::extracted(scope, cx, parent.name(), member, ty)
+                        .is_some_and(|child| child.name == def.name())
+                })
+        });
+        if nested {
+            return;
+        }
+
+        let max = cx.max(Self::DEFAULT);
+        let (depth, deepest) = Self::depth(scope, cx, def.name(), Self::members(def));
+        if depth > max {
+            cx.report(format!(
+                "{kind} '{}' nests anonymous structs {depth} levels deep, more than {max}, \
+                 generating names like '{deepest}'",
+                def.name()
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        declare::{
+            Builtin, DeclField, DeclNamedItemContext, DeclNamespace, DeclRefContext, Meta,
+            TypeRegistryDeclaration,
+        },
+        lint::{LintConfig, RuleGroup, RuleOverrides, RuleRegistry, Violation},
+    };
+
+    fn field(
+        name: &str,
+        ty: DeclType,
+    ) -> DeclField {
+        DeclField {
+            name: name.into(),
+            ty,
+            default_value: None,
+            optional: false,
+            comments: Default::default(),
+            constraints: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    fn named(name: &str) -> DeclType {
+        DeclType::Named {
+            reference: DeclNamedItemContext {
+                context: DeclRefContext {
+                    package: "test".into(),
+                    namespace: vec!["pkg".into()],
+                },
+                name: name.into(),
+            },
+        }
+    }
+
+    fn structure(
+        name: &str,
+        fields: Vec<DeclField>,
+    ) -> TypeDefinition {
+        TypeDefinition::Struct(DeclStruct {
+            name: name.into(),
+            fields,
+            meta: Meta::new(1),
+            comments: Default::default(),
+        })
+    }
+
+    fn lint(
+        types: Vec<TypeDefinition>,
+        config: LintConfig,
+    ) -> Vec<Violation> {
+        let mut decl = TypeRegistryDeclaration::new("test".into());
+        decl.namespaces.insert(
+            "pkg".into(),
+            DeclNamespace {
+                name: "pkg".into(),
+                version: None,
+                error: None,
+                types,
+                constants: Vec::new(),
+                namespaces: BTreeMap::new(),
+                comments: Default::default(),
+            },
+        );
+        RuleRegistry::new(&config)
+            .run(&decl)
+            .into_iter()
+            .filter(|violation| violation.group == RuleGroup::Complexity)
+            .collect()
+    }
+
+    fn max(
+        rule: &str,
+        max: usize,
+    ) -> LintConfig {
+        let mut config = LintConfig::default();
+        config.rules.overrides.insert(
+            RuleGroup::Complexity,
+            BTreeMap::from([(
+                rule.to_string(),
+                RuleOverrides {
+                    level: None,
+                    fix: None,
+                    max: Some(max),
+                    min: None,
+                },
+            )]),
+        );
+        config
+    }
+
+    /// `Profile { settings: { theme: { colors: { primary: str } } } }` once extracted
+    fn nested() -> Vec<TypeDefinition> {
+        let bool = DeclType::Builtin { ty: Builtin::Bool };
+        vec![
+            structure("Profile", vec![field("settings", named("ProfileSettings"))]),
+            structure(
+                "ProfileSettings",
+                vec![field("theme", named("ProfileSettingsTheme"))],
+            ),
+            structure(
+                "ProfileSettingsTheme",
+                vec![field("colors", named("ProfileSettingsThemeColors"))],
+            ),
+            structure("ProfileSettingsThemeColors", vec![field("primary", bool)]),
+        ]
+    }
+
+    #[test]
+    fn reports_deep_nesting_once() {
+        let violations = lint(nested(), LintConfig::default());
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, "max_nesting");
+        assert_eq!(violations[0].item.as_deref(), Some("Profile"));
+        assert!(violations[0].reason.contains(
+            "3 levels deep, more than 2, generating names like 'ProfileSettingsThemeColors'"
+        ));
+    }
+
+    #[test]
+    fn named_structs_are_not_nesting() {
+        let bool = DeclType::Builtin { ty: Builtin::Bool };
+        let types = vec![
+            structure("Profile", vec![field("settings", named("Settings"))]),
+            structure("Settings", vec![field("theme", named("Theme"))]),
+            structure("Theme", vec![field("colors", named("Colors"))]),
+            structure("Colors", vec![field("primary", bool)]),
+        ];
+        assert_eq!(lint(types, LintConfig::default()), vec![]);
+    }
+
+    #[test]
+    fn thresholds_come_from_the_manifest() {
+        assert_eq!(lint(nested(), max("max_nesting", 3)), vec![]);
+
+        let violations = lint(nested(), max("max_fields", 0));
+        assert_eq!(
+            violations
+                .iter()
+                .filter(|violation| violation.rule == "max_fields")
+                .count(),
+            4
+        );
+    }
+}
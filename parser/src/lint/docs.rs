@@ -0,0 +1,422 @@
+//! Rules on the documentation of declarations, read from their `///` doc comments as
+//! Markdown.
+
+use crate::declare::{
+    DeclComment, DeclConst, DeclNamespace, NamespaceCoverage, TypeDefinition, doc_text,
+};
+
+use super::{Check, LintCx};
+
+/// Whether `ident` could name a declaration.
+fn is_ident(ident: &str) -> bool {
+    let mut chars = ident.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether `link` is a path to a declaration, e.g. `User`, `auth::User` or `User.name`.
+fn is_path(link: &str) -> bool {
+    let (path, member) = split(link);
+    path.into_iter().all(is_ident) && member.is_none_or(is_ident)
+}
+
+/// The intra-schema links of a line of Markdown, e.g. `User` for `[User]` or `` [`User`] ``.
+/// Links with a target, `[text](url)` or `[text][label]`, images, task list markers and
+/// brackets in code spans are not intra-schema links.
+fn links(line: &str) -> Vec<&str> {
+    let mut links = Vec::new();
+    let mut in_code = false;
+    let mut rest = line;
+    let mut offset = 0;
+    while let Some(at) = rest.find(['`', '\\', '[']) {
+        let start = offset + at;
+        let next = start + 1;
+        match line.as_bytes()[start] {
+            b'`' => in_code = !in_code,
+            // an escaped character is literal
+            b'\\' => {
+                offset = (next + 1).min(line.len());
+                rest = &line[offset..];
+                continue;
+            },
+            _ if in_code => {},
+            _ => {
+                let Some(len) = line[next..].find(']') else {
+                    break;
+                };
+                let inner = &line[next..next + len];
+                let after = line[next + len + 1..].chars().next();
+                let image = line[..start].ends_with('!');
+                let task = matches!(line[..start].trim(), "-" | "*" | "+");
+                let link = inner.trim_matches('`');
+                if !image
+                    && !task
+                    && !inner.contains('[')
+                    && !matches!(after, Some('(' | '[' | ':'))
+                    && is_path(link)
+                {
+                    links.push(link);
+                }
+                offset = next + len + 1;
+                // the label of a reference link is not a link of its own
+                if after == Some('[')
+                    && let Some(label) = line[offset..].find(']')
+                {
+                    offset += label + 1;
+                }
+                rest = &line[offset..];
+                continue;
+            },
+        }
+        offset = next;
+        rest = &line[offset..];
+    }
+    links
+}
+
+/// Splits a link into the path of the declaration and the member it names, if any.
+fn split(link: &str) -> (Vec<&str>, Option<&str>) {
+    match link.split_once('.') {
+        Some((path, member)) => (path.split("::").collect(), Some(member)),
+        None => (link.split("::").collect(), None),
+    }
+}
+
+/// Whether `path` names a declaration of `ns` or of the namespaces nested in it, which has
+/// `member` as a field, variant or argument when one is given.
+fn resolves_in(
+    path: &[&str],
+    member: Option<&str>,
+    ns: &DeclNamespace,
+) -> bool {
+    let Some((name, namespace)) = path.split_last() else {
+        return false;
+    };
+    let Some(ns) = namespace.iter().try_fold(ns, |ns, segment| {
+        ns.namespaces.get(*segment).map(Box::as_ref)
+    }) else {
+        return false;
+    };
+    match ns
+        .types
+        .iter()
+        .find(|def| def.name() == *name)
+    {
+        Some(def) => {
+            member.is_none_or(|member| {
+                def.members()
+                    .iter()
+                    .any(|(name, _)| *name == member)
+            })
+        },
+        None => {
+            member.is_none()
+                && (ns
+                    .constants
+                    .iter()
+                    .any(|def| def.name == *name)
+                    || ns.namespaces.contains_key(*name))
+        },
+    }
+}
+
+/// Whether `link` names a declaration of the package, relative to the namespace under check
+/// or to the root of the package.
+fn resolves(
+    link: &str,
+    cx: &LintCx,
+) -> bool {
+    let (path, member) = split(link);
+    let namespaces = &cx.declaration().namespaces;
+    cx.scope()
+        .is_some_and(|scope| resolves_in(&path, member, scope))
+        || match path.split_first() {
+            Some((root, [])) => member.is_none() && namespaces.contains_key(*root),
+            Some((root, rest)) => {
+                namespaces
+                    .get(*root)
+                    .is_some_and(|root| resolves_in(rest, member, root))
+            },
+            None => false,
+        }
+}
+
+crate::rule! {
+    BrokenLink in Docs @ Silent: Skip; "links in doc comments should name declarations of the package"
+}
+
+impl BrokenLink {
+    /// Why each link of `comments` which names nothing is broken.
+    fn broken(
+        comments: &DeclComment,
+        cx: &LintCx,
+    ) -> Vec<String> {
+        comments
+            .comments
+            .iter()
+            .filter_map(|comment| doc_text(comment))
+            .flat_map(links)
+            .filter(|link| !resolves(link, cx))
+            .map(|link| format!("doc link '[{link}]' does not resolve to a declaration"))
+            .collect()
+    }
+}
+
+impl Check for BrokenLink {
+    fn check_namespace(
+        &self,
+        ns: &DeclNamespace,
+        cx: &mut LintCx,
+    ) {
+        for reason in Self::broken(&ns.comments, cx) {
+            cx.report(reason);
+        }
+    }
+
+    fn check_type(
+        &self,
+        def: &TypeDefinition,
+        cx: &mut LintCx,
+    ) {
+        for reason in Self::broken(def.comments(), cx) {
+            cx.report(reason);
+        }
+        for (member, comments) in def.members() {
+            for reason in Self::broken(comments, cx) {
+                cx.report(reason).on_member(member);
+            }
+        }
+    }
+
+    fn check_const(
+        &self,
+        def: &DeclConst,
+        cx: &mut LintCx,
+    ) {
+        for reason in Self::broken(&def.comments, cx) {
+            cx.report(reason);
+        }
+    }
+}
+
+crate::rule! {
+    MinCoverage in Docs @ Silent: Skip; "namespaces should document a minimum share of their types, fields and operations"
+}
+
+impl Check for MinCoverage {
+    fn check_namespace(
+        &self,
+        ns: &DeclNamespace,
+        cx: &mut LintCx,
+    ) {
+        let min = cx.min(80);
+        let coverage = NamespaceCoverage::of(ns).overall();
+        if coverage.percent() < min as f64 {
+            cx.report(format!(
+                "namespace '{}' documents {:.1}% of its types, fields and operations ({} of {}), less than the required {min}%",
+                ns.name,
+                coverage.percent(),
+                coverage.documented,
+                coverage.total
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        declare::{Builtin, DeclField, DeclStruct, DeclType, Meta, TypeRegistryDeclaration},
+        lint::{LintConfig, RuleGroup, RuleLevel, RuleOverrides, RuleRegistry},
+    };
+
+    fn documented(docs: &[&str]) -> DeclComment {
+        DeclComment::from_vec(
+            docs.iter()
+                .map(|doc| format!("/ {doc}"))
+                .collect(),
+        )
+    }
+
+    fn structure(
+        name: &str,
+        docs: &[&str],
+        field_docs: &[&str],
+    ) -> TypeDefinition {
+        TypeDefinition::Struct(DeclStruct {
+            name: name.into(),
+            fields: vec![DeclField {
+                name: "id".into(),
+                ty: DeclType::Builtin { ty: Builtin::I64 },
+                default_value: None,
+                optional: false,
+                comments: documented(field_docs),
+                constraints: Vec::new(),
+                examples: Vec::new(),
+            }],
+            meta: Meta::new(1),
+            comments: documented(docs),
+        })
+    }
+
+    fn namespace(
+        name: &str,
+        types: Vec<TypeDefinition>,
+        namespaces: Vec<DeclNamespace>,
+    ) -> DeclNamespace {
+        DeclNamespace {
+            name: name.into(),
+            version: None,
+            error: None,
+            types,
+            constants: Vec::new(),
+            namespaces: namespaces
+                .into_iter()
+                .map(|ns| (ns.name.clone(), Box::new(ns)))
+                .collect(),
+            comments: Default::default(),
+        }
+    }
+
+    fn lint(ns: DeclNamespace) -> Vec<(Option<String>, Option<String>, String)> {
+        let mut decl = TypeRegistryDeclaration::new("test".into());
+        decl.namespaces.insert(ns.name.clone(), ns);
+
+        let mut config = LintConfig::default();
+        config.rules.overrides.insert(
+            RuleGroup::Docs,
+            BTreeMap::from([(
+                "broken_link".to_string(),
+                RuleOverrides {
+                    level: Some(RuleLevel::Warn),
+                    fix: None,
+                    max: None,
+                    min: None,
+                },
+            )]),
+        );
+        RuleRegistry::new(&config)
+            .run(&decl)
+            .into_iter()
+            .filter(|violation| violation.group == RuleGroup::Docs)
+            .map(|violation| (violation.item, violation.member, violation.reason))
+            .collect()
+    }
+
+    #[test]
+    fn finds_intra_schema_links() {
+        assert_eq!(
+            links("see [User], [`auth::Token`] and [User.id]"),
+            vec!["User", "auth::Token", "User.id"]
+        );
+        assert_eq!(
+            links("[site](https://kintsu.dev) ![logo][img] `[Code]` \\[Escaped] [not a path]"),
+            Vec::<&str>::new()
+        );
+        assert_eq!(links("- [x] done"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn resolves_links_in_scope_and_from_the_root() {
+        let auth = namespace("auth", vec![structure("Token", &[], &[])], Vec::new());
+        let ns = namespace(
+            "pkg",
+            vec![
+                structure(
+                    "User",
+                    &["Signed in with a [auth::Token]."],
+                    &["See [User.id]."],
+                ),
+                structure(
+                    "Session",
+                    &["Owned by [pkg::User], see [Account]."],
+                    &["[User.name]"],
+                ),
+            ],
+            vec![auth],
+        );
+
+        assert_eq!(
+            lint(ns),
+            vec![
+                (
+                    Some("Session".into()),
+                    None,
+                    "doc link '[Account]' does not resolve to a declaration".into()
+                ),
+                (
+                    Some("Session".into()),
+                    Some("id".into()),
+                    "doc link '[User.name]' does not resolve to a declaration".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn is_silent_by_default() {
+        let ns = namespace(
+            "pkg",
+            vec![structure("User", &["[Missing]"], &[])],
+            Vec::new(),
+        );
+        let mut decl = TypeRegistryDeclaration::new("test".into());
+        decl.namespaces.insert(ns.name.clone(), ns);
+        assert!(
+            RuleRegistry::new(&LintConfig::default())
+                .run(&decl)
+                .iter()
+                .all(|violation| violation.group != RuleGroup::Docs)
+        );
+    }
+
+    #[test]
+    fn requires_a_minimum_coverage() {
+        let ns = namespace(
+            "pkg",
+            vec![
+                structure("User", &["A user."], &["The id."]),
+                structure("Session", &[], &[]),
+            ],
+            Vec::new(),
+        );
+        let mut decl = TypeRegistryDeclaration::new("test".into());
+        decl.namespaces.insert(ns.name.clone(), ns);
+
+        let violations = |min| {
+            let mut config = LintConfig::default();
+            config.rules.overrides.insert(
+                RuleGroup::Docs,
+                BTreeMap::from([(
+                    "min_coverage".to_string(),
+                    RuleOverrides {
+                        level: Some(RuleLevel::Error),
+                        fix: None,
+                        max: None,
+                        min,
+                    },
+                )]),
+            );
+            RuleRegistry::new(&config)
+                .run(&decl)
+                .into_iter()
+                .filter(|violation| violation.rule == "min_coverage")
+                .map(|violation| violation.reason)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            violations(None),
+            vec![
+                "namespace 'pkg' documents 50.0% of its types, fields and operations (2 of 4), \
+                 less than the required 80%"
+            ]
+        );
+        assert!(violations(Some(50)).is_empty());
+    }
+}
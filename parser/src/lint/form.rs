@@ -0,0 +1,109 @@
+//! Rules on the shape of declarations.
+
+use crate::declare::{DeclEnum, DeclEnumDef, DeclError, DeclOneOf, DeclOneOfVariant};
+
+use super::{Check, LintCx};
+
+crate::rule! {
+    SingleVariant in Form @ Warn: Unsafe; "oneofs and errors should have more than one variant"
+}
+
+impl SingleVariant {
+    fn check_variants(
+        kind: &str,
+        name: &str,
+        variants: &[DeclOneOfVariant],
+        cx: &mut LintCx,
+    ) {
+        if let [variant] = variants {
+            cx.report(format!(
+                "{kind} '{name}' has the single variant '{}', so it could be that type instead",
+                variant.name
+            ));
+        }
+    }
+}
+
+impl Check for SingleVariant {
+    fn check_one_of(
+        &self,
+        def: &DeclOneOf,
+        cx: &mut LintCx,
+    ) {
+        Self::check_variants("oneof", &def.name, &def.variants, cx);
+    }
+
+    fn check_error(
+        &self,
+        def: &DeclError,
+        cx: &mut LintCx,
+    ) {
+        Self::check_variants("error", &def.name, &def.variants, cx);
+    }
+}
+
+/// The name and discriminant of each variant of an integer enum.
+fn discriminants(def: &DeclEnumDef) -> Vec<(&str, u32)> {
+    match &def.enum_def {
+        DeclEnum::Int(variants) => {
+            variants
+                .iter()
+                .map(|variant| (variant.name.as_str(), variant.value))
+                .collect()
+        },
+        DeclEnum::String(_) => Vec::new(),
+    }
+}
+
+crate::rule! {
+    ContiguousDiscriminants in Form @ Silent: Skip; "integer enums should have discriminants without gaps, as #[contiguous] requires"
+}
+
+impl Check for ContiguousDiscriminants {
+    fn check_enum(
+        &self,
+        def: &DeclEnumDef,
+        cx: &mut LintCx,
+    ) {
+        let mut variants = discriminants(def);
+        variants.sort_by_key(|(_, value)| *value);
+        for pair in variants.windows(2) {
+            let ((prev, prev_value), (name, value)) = (pair[0], pair[1]);
+            if value != prev_value + 1 {
+                cx.report(format!(
+                    "enum '{}' has '{name}' = {value} after '{prev}' = {prev_value}, so its discriminants are not contiguous",
+                    def.name
+                ))
+                .on_member(name);
+            }
+        }
+    }
+}
+
+crate::rule! {
+    MaxDiscriminant in Form @ Silent: Skip; "integer enum discriminants should not exceed a maximum"
+}
+
+impl MaxDiscriminant {
+    /// Discriminants fit a byte unless the manifest sets another `max`.
+    const DEFAULT: usize = u8::MAX as usize;
+}
+
+impl Check for MaxDiscriminant {
+    fn check_enum(
+        &self,
+        def: &DeclEnumDef,
+        cx: &mut LintCx,
+    ) {
+        let max = cx.max(Self::DEFAULT);
+        for (name, value) in discriminants(def) {
+            if value as usize > max {
+                cx.report(format!(
+                    "variant '{name}' of enum '{}' has the discriminant {value}, more than {max}",
+                    def.name
+                ))
+                .on_member(name);
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Rules keeping declared names within the identifier policy set under `[lint.identifiers]`,
+//! so that every backend generated for can use them as written.
+
+use crate::declare::{DeclConst, DeclNamespace, TypeDefinition};
+
+use super::{Check, IdentifierPolicy, LintCx, reserved};
+
+/// Why `name` breaks the policy, if it does.
+type Reason = fn(&str, &IdentifierPolicy) -> Option<String>;
+
+/// Reports the name of `def` and of each of its members `reason` objects to.
+fn check_type_names(
+    def: &TypeDefinition,
+    cx: &mut LintCx,
+    reason: Reason,
+) {
+    let policy = cx.identifiers();
+    if let Some(reason) = reason(def.name(), policy) {
+        cx.report(reason);
+    }
+    for (member, _) in def.members() {
+        if let Some(reason) = reason(member, policy) {
+            cx.report(reason).on_member(member);
+        }
+    }
+}
+
+fn check_name(
+    name: &str,
+    cx: &mut LintCx,
+    reason: Reason,
+) {
+    if let Some(reason) = reason(name, cx.identifiers()) {
+        cx.report(reason);
+    }
+}
+
+macro_rules! check_names {
+    ($rule: ident, $reason: expr) => {
+        impl Check for $rule {
+            fn check_namespace(
+                &self,
+                ns: &DeclNamespace,
+                cx: &mut LintCx,
+            ) {
+                check_name(&ns.name, cx, $reason);
+            }
+
+            fn check_type(
+                &self,
+                def: &TypeDefinition,
+                cx: &mut LintCx,
+            ) {
+                check_type_names(def, cx, $reason);
+            }
+
+            fn check_const(
+                &self,
+                def: &DeclConst,
+                cx: &mut LintCx,
+            ) {
+                check_name(&def.name, cx, $reason);
+            }
+        }
+    };
+}
+
+crate::rule! {
+    IdentifierLength in Naming @ Warn: Skip; "names should be no longer than the identifier policy allows"
+}
+
+check_names!(IdentifierLength, |name, policy| {
+    let length = name.chars().count();
+    (length > policy.max_length).then(|| {
+        format!(
+            "'{name}' is {length} characters long, more than the {} allowed",
+            policy.max_length
+        )
+    })
+});
+
+crate::rule! {
+    IdentifierChars in Naming @ Warn: Skip; "names should only use the characters the identifier policy allows"
+}
+
+check_names!(IdentifierChars, |name, policy| {
+    name.chars()
+        .find(|c| !policy.allows(*c))
+        .map(|c| format!("'{name}' contains '{c}', which the identifier policy does not allow"))
+});
+
+crate::rule! {
+    ReservedWord in Naming @ Warn: Skip; "names should not be reserved words of the backends generated for"
+}
+
+check_names!(ReservedWord, |name, policy| {
+    let backends = policy
+        .backends
+        .iter()
+        .filter(|backend| reserved::is_reserved(**backend, name))
+        .map(|backend| backend.display_name())
+        .collect::<Vec<_>>();
+    (!backends.is_empty())
+        .then(|| format!("'{name}' is a reserved word in {}", backends.join(", ")))
+});
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        declare::{
+            Builtin, DeclField, DeclOneOf, DeclOneOfVariant, DeclStruct, DeclType, Meta,
+            TypeRegistryDeclaration,
+        },
+        lint::{Backend, CharClass, LintConfig, RuleRegistry, Violation},
+    };
+
+    fn structure(
+        name: &str,
+        fields: &[&str],
+    ) -> TypeDefinition {
+        TypeDefinition::Struct(DeclStruct {
+            name: name.into(),
+            fields: fields
+                .iter()
+                .map(|name| {
+                    DeclField {
+                        name: name.to_string(),
+                        ty: DeclType::Builtin { ty: Builtin::Bool },
+                        default_value: None,
+                        optional: false,
+                        comments: Default::default(),
+                        constraints: Vec::new(),
+                        examples: Vec::new(),
+                    }
+                })
+                .collect(),
+            meta: Meta::new(1),
+            comments: Default::default(),
+        })
+    }
+
+    fn lint(
+        types: Vec<TypeDefinition>,
+        identifiers: IdentifierPolicy,
+    ) -> Vec<(String, Option<String>, String)> {
+        let mut decl = TypeRegistryDeclaration::new("test".into());
+        decl.namespaces.insert(
+            "pkg".into(),
+            DeclNamespace {
+                name: "pkg".into(),
+                version: None,
+                error: None,
+                types,
+                constants: Vec::new(),
+                namespaces: BTreeMap::new(),
+                comments: Default::default(),
+            },
+        );
+        let config = LintConfig {
+            identifiers,
+            ..Default::default()
+        };
+        RuleRegistry::new(&config)
+            .run(&decl)
+            .into_iter()
+            .filter(|violation| {
+                ["identifier_length", "identifier_chars", "reserved_word"]
+                    .contains(&violation.rule.as_str())
+            })
+            .map(
+                |Violation {
+                     rule,
+                     member,
+                     reason,
+                     ..
+                 }| (rule, member, reason),
+            )
+            .collect()
+    }
+
+    #[test]
+    fn default_policy_allows_conventional_names() {
+        assert_eq!(
+            lint(
+                vec![structure("User", &["user_id", "default"])],
+                IdentifierPolicy::default()
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn reports_reserved_words_of_selected_backends() {
+        let policy = IdentifierPolicy {
+            backends: vec![Backend::Typescript, Backend::Sql],
+            ..Default::default()
+        };
+        let one_of = TypeDefinition::OneOf(DeclOneOf {
+            name: "Select".into(),
+            variants: vec![DeclOneOfVariant {
+                name: "Id".into(),
+                ty: DeclType::Builtin { ty: Builtin::I64 },
+                comments: Default::default(),
+            }],
+            meta: Meta::new(1),
+            comments: Default::default(),
+            tag: Default::default(),
+        });
+
+        assert_eq!(
+            lint(
+                vec![structure("Account", &["default", "name"]), one_of],
+                policy
+            ),
+            vec![
+                (
+                    "reserved_word".into(),
+                    Some("default".into()),
+                    "'default' is a reserved word in TypeScript, SQL".into()
+                ),
+                (
+                    "reserved_word".into(),
+                    None,
+                    "'Select' is a reserved word in SQL".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn enforces_length_and_characters() {
+        let policy = IdentifierPolicy {
+            max_length: 8,
+            chars: vec![CharClass::Letter],
+            backends: Vec::new(),
+        };
+        let mut violations = lint(vec![structure("Account", &["created_at"])], policy);
+        violations.sort();
+        assert_eq!(
+            violations,
+            vec![
+                (
+                    "identifier_chars".into(),
+                    Some("created_at".into()),
+                    "'created_at' contains '_', which the identifier policy does not allow".into()
+                ),
+                (
+                    "identifier_length".into(),
+                    Some("created_at".into()),
+                    "'created_at' is 10 characters long, more than the 8 allowed".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn graphql_reserves_introspection_names() {
+        assert!(reserved::is_reserved(Backend::Graphql, "__typename"));
+        assert!(reserved::is_reserved(Backend::Graphql, "Query"));
+        assert!(reserved::is_reserved(Backend::Sql, "ORDER"));
+        assert!(!reserved::is_reserved(Backend::Python, "none"));
+    }
+}
@@ -0,0 +1,773 @@
+//! Lint rules run over the resolved declarations of a package.
+//!
+//! Each rule is a [`Check`] registered into the rule inventory with [`rule!`](crate::rule).
+//! [`CompileCtx::finalize`](crate::ctx::compile::CompileCtx::finalize) runs every registered
+//! rule over the declarations of the root package, at the level set by the rule unless the
+//! `[lint]` table of the package manifest overrides it:
+//!
+//! ```toml
+//! [lint.overrides.form]
+//! single_variant = { level = "error" }
+//!
+//! [lint.overrides.naming]
+//! type_case = { level = "silent" }
+//! ```
+//!
+//! Rules are keyed by their group and the snake case of their name. Some rules, such as
+//! `form::contiguous_discriminants`, are silent until the manifest sets their level.
+//!
+//! The `naming` rules follow the conventions under `[lint.naming]`, which default to
+//! PascalCase types, snake_case fields and operations, and SCREAMING_SNAKE_CASE variants:
+//!
+//! ```toml
+//! [lint.naming]
+//! fields = "camel"
+//! ```
+//!
+//! The `complexity` rules take their threshold from `max`:
+//!
+//! ```toml
+//! [lint.overrides.complexity]
+//! max_fields = { max = 48 }
+//! max_nesting = { max = 3, level = "error" }
+//! ```
+//!
+//! Names are also held to the identifier policy under `[lint.identifiers]`, which by default
+//! allows names of up to 64 letters, digits and underscores. Listing `backends` reports names
+//! which are reserved words of the languages generated for, e.g. `default` in TypeScript:
+//!
+//! ```toml
+//! [lint.identifiers]
+//! max_length = 32
+//! chars = ["letter", "digit"]
+//! backends = ["typescript", "sql"]
+//! ```
+//!
+//! Links in doc comments, such as `[User]` or `[auth::User.name]`, must name declarations
+//! of the package once `docs::broken_link` is given a level:
+//!
+//! ```toml
+//! [lint.overrides.docs]
+//! broken_link = { level = "warn" }
+//! ```
+//!
+//! `docs::min_coverage` requires each namespace to document a share of its types, fields and
+//! operations, 80 percent unless `min` sets another. Setting it to `error` keeps a package
+//! from being published while its documentation falls short:
+//!
+//! ```toml
+//! [lint.overrides.docs]
+//! min_coverage = { min = 90, level = "error" }
+//! ```
+//!
+//! A rule may suggest a rename with its violation. `kintsu check --fix` applies it when the
+//! rule allows safe fixes and nothing else refers to the old name.
+//!
+//! ## Plugins
+//!
+//! Rules can live in any crate linked into the compiler binary, as registration goes
+//! through [`inventory`]. The [`rule!`](crate::rule) macro is the stable way to register
+//! one: it declares the rule as a unit struct and submits it, so a plugin crate only needs a
+//! dependency on `kintsu-parser`.
+//!
+//! ```ignore
+//! use kintsu_parser::lint::{Check, LintCx, decl::DeclOperation};
+//!
+//! kintsu_parser::rule! {
+//!     OperationDocs in Form @ Warn: Skip; "operations should be documented"
+//! }
+//!
+//! impl Check for OperationDocs {
+//!     fn check_operation(
+//!         &self,
+//!         def: &DeclOperation,
+//!         cx: &mut LintCx,
+//!     ) {
+//!         if def.comments.is_empty() {
+//!             cx.report(format!("operation '{}' has no doc comment", def.name));
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+
+use convert_case::{Case, Casing};
+
+pub use inventory;
+pub use kintsu_manifests::rules::{
+    Backend, CharClass, Fix, IdentifierPolicy, LintConfig, NameCase, NamingConventions, RuleConfig,
+    RuleGroup, RuleLevel, RuleOverrides,
+};
+
+use crate::declare::{
+    DeclConst, DeclEnumDef, DeclError, DeclNamedItemContext, DeclNamespace, DeclOneOf,
+    DeclOperation, DeclStruct, DeclType, DeclTypeAlias, TypeDefinition, TypeRegistryDeclaration,
+};
+
+/// The declaration types rules inspect.
+pub mod decl {
+    pub use crate::declare::*;
+}
+
+mod complexity;
+mod docs;
+mod form;
+mod identifiers;
+mod naming;
+pub mod reserved;
+
+pub use complexity::{MaxFields, MaxNesting, MaxVariants};
+pub use docs::{BrokenLink, MinCoverage};
+pub use form::{ContiguousDiscriminants, MaxDiscriminant, SingleVariant};
+pub use identifiers::{IdentifierChars, IdentifierLength, ReservedWord};
+pub use naming::{FieldCase, OperationCase, TypeCase, VariantCase};
+
+/// A lint rule. Every method defaults to reporting nothing, so a rule implements only the
+/// checks for the declarations it cares about, reporting violations through the [`LintCx`].
+#[allow(unused_variables)]
+pub trait Check: Send + Sync {
+    fn check_namespace(
+        &self,
+        ns: &DeclNamespace,
+        cx: &mut LintCx,
+    ) {
+    }
+
+    fn check_struct(
+        &self,
+        def: &DeclStruct,
+        cx: &mut LintCx,
+    ) {
+    }
+
+    fn check_enum(
+        &self,
+        def: &DeclEnumDef,
+        cx: &mut LintCx,
+    ) {
+    }
+
+    fn check_one_of(
+        &self,
+        def: &DeclOneOf,
+        cx: &mut LintCx,
+    ) {
+    }
+
+    fn check_type_alias(
+        &self,
+        def: &DeclTypeAlias,
+        cx: &mut LintCx,
+    ) {
+    }
+
+    fn check_error(
+        &self,
+        def: &DeclError,
+        cx: &mut LintCx,
+    ) {
+    }
+
+    fn check_operation(
+        &self,
+        def: &DeclOperation,
+        cx: &mut LintCx,
+    ) {
+    }
+
+    fn check_const(
+        &self,
+        def: &DeclConst,
+        cx: &mut LintCx,
+    ) {
+    }
+
+    /// Dispatches `def` to the check for its kind.
+    fn check_type(
+        &self,
+        def: &TypeDefinition,
+        cx: &mut LintCx,
+    ) {
+        match def {
+            TypeDefinition::Struct(def) => self.check_struct(def, cx),
+            TypeDefinition::Enum(def) => self.check_enum(def, cx),
+            TypeDefinition::OneOf(def) => self.check_one_of(def, cx),
+            TypeDefinition::TypeAlias(def) => self.check_type_alias(def, cx),
+            TypeDefinition::Error(def) => self.check_error(def, cx),
+            TypeDefinition::Operation(def) => self.check_operation(def, cx),
+        }
+    }
+}
+
+dyn_inventory::dyn_inventory! {
+    Rule<Handle: Check> {
+        pub group: RuleGroup,
+        pub name: &'static str,
+        pub level: RuleLevel,
+        pub description: &'static str,
+
+        pub fix: Fix,
+        pub handle: Handle,
+    }
+}
+
+impl Rule {
+    /// The name of this rule in manifests and diagnostics.
+    pub fn key(&self) -> String {
+        self.name.to_case(Case::Snake)
+    }
+}
+
+impl kintsu_manifests::rules::WithRule for Rule {
+    fn with_fix(
+        &mut self,
+        fix: Fix,
+    ) {
+        self.fix = fix;
+    }
+
+    fn with_level(
+        &mut self,
+        level: RuleLevel,
+    ) {
+        self.level = level;
+    }
+}
+
+/// Declares the unit struct `$name` and registers it as a rule of `$group`, reported at
+/// `$level` by default. The struct must implement [`Check`].
+#[macro_export]
+macro_rules! rule {
+    (
+       $name: ident in $group: ident @ $level: ident : $fix: ident; $desc: literal
+    ) => {
+        pub struct $name;
+
+        $crate::lint::inventory::submit! {
+            $crate::lint::RuleInit {
+                group: $crate::lint::RuleGroup::$group,
+                name: stringify!($name),
+                level: $crate::lint::RuleLevel::$level,
+                description: $desc,
+                fix: $crate::lint::Fix::$fix,
+                __get: || Box::new($name),
+            }
+        }
+    };
+}
+
+/// A rename which would resolve a violation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rename {
+    pub to: String,
+    /// Whether the new name is free and nothing in the package refers to the old one, so
+    /// the rename can be applied without further edits
+    pub safe: bool,
+}
+
+/// A violation of a rule, located by the namespace and item it was reported in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub group: RuleGroup,
+    pub rule: String,
+    pub level: RuleLevel,
+    pub fix: Fix,
+    /// Path of the namespace, outermost first
+    pub namespace: Vec<String>,
+    /// The type or constant checked when the violation was reported, if any
+    pub item: Option<String>,
+    /// The field or variant of `item` the violation is on, if any
+    pub member: Option<String>,
+    pub reason: String,
+    pub rename: Option<Rename>,
+}
+
+impl Violation {
+    pub fn is_error(&self) -> bool {
+        self.level == RuleLevel::Error
+    }
+
+    pub fn to_error(
+        &self,
+        span: Option<crate::Span>,
+    ) -> crate::CompilerError {
+        let group = self.group.as_str();
+        let error = if self.is_error() {
+            crate::LintError::rule_denied(group, &self.rule, &self.reason).at_opt(span)
+        } else {
+            crate::LintError::rule_violated(group, &self.rule, &self.reason).at_opt(span)
+        };
+
+        match (span, self.suggestion(span)) {
+            (Some(_), Some(suggestion)) => error.with_suggestion(suggestion),
+            _ => error,
+        }
+    }
+
+    /// The rename of the name at `span`, applicable by `kintsu check --fix` when the rule
+    /// allows safe fixes and the rename is safe.
+    fn suggestion(
+        &self,
+        span: Option<crate::Span>,
+    ) -> Option<crate::Suggestion> {
+        let rename = self.rename.as_ref()?;
+        let span = span?;
+        let (applicability, message) = match self.fix {
+            Fix::Skip => return None,
+            Fix::Safe if rename.safe => {
+                (
+                    crate::Applicability::MachineApplicable,
+                    format!("rename to `{}`", rename.to),
+                )
+            },
+            _ => {
+                (
+                    crate::Applicability::MaybeIncorrect,
+                    format!("rename to `{}`, updating its uses", rename.to),
+                )
+            },
+        };
+        Some(crate::Suggestion::new(
+            span,
+            &rename.to,
+            applicability,
+            message,
+        ))
+    }
+}
+
+/// A violation just reported, to be refined by the rule.
+pub struct Reported<'v>(Option<&'v mut Violation>);
+
+impl Reported<'_> {
+    /// Places the violation on the field or variant `member` of the item under check.
+    pub fn on_member(
+        mut self,
+        member: impl Into<String>,
+    ) -> Self {
+        if let Some(violation) = &mut self.0 {
+            violation.member = Some(member.into());
+        }
+        self
+    }
+
+    /// Suggests renaming the name the violation is on to `to`.
+    pub fn rename(
+        mut self,
+        to: impl Into<String>,
+        safe: bool,
+    ) -> Self {
+        if let Some(violation) = &mut self.0 {
+            violation.rename = Some(Rename {
+                to: to.into(),
+                safe,
+            });
+        }
+        self
+    }
+}
+
+/// What a [`Check`] sees besides the declaration under check.
+pub struct LintCx<'a> {
+    package: &'a str,
+    naming: &'a NamingConventions,
+    identifiers: &'a IdentifierPolicy,
+    config: &'a RuleConfig<RuleGroup>,
+    declaration: &'a TypeRegistryDeclaration,
+    referenced: BTreeSet<(Vec<String>, String)>,
+    selected: BTreeSet<String>,
+    namespace: Vec<String>,
+    scope: Option<&'a DeclNamespace>,
+    item: Option<String>,
+    rule: Option<&'a Rule>,
+    violations: Vec<Violation>,
+}
+
+impl<'a> LintCx<'a> {
+    /// The package being linted, as it appears in references.
+    pub fn package(&self) -> &str {
+        self.package
+    }
+
+    /// The path of the namespace under check, outermost first.
+    pub fn namespace(&self) -> &[String] {
+        &self.namespace
+    }
+
+    /// The declarations being linted.
+    pub fn declaration(&self) -> &'a TypeRegistryDeclaration {
+        self.declaration
+    }
+
+    /// The namespace under check.
+    pub fn scope(&self) -> Option<&'a DeclNamespace> {
+        self.scope
+    }
+
+    /// The naming conventions set by the package.
+    pub fn naming(&self) -> &NamingConventions {
+        self.naming
+    }
+
+    /// The identifier policy set by the package.
+    pub fn identifiers(&self) -> &'a IdentifierPolicy {
+        self.identifiers
+    }
+
+    /// The threshold set for the running rule under `max` in the manifest, or `default`.
+    pub fn max(
+        &self,
+        default: usize,
+    ) -> usize {
+        self.rule
+            .and_then(|rule| self.config.get(&rule.group, &rule.key()))
+            .and_then(|overrides| overrides.max)
+            .unwrap_or(default)
+    }
+
+    /// The threshold set for the running rule under `min` in the manifest, or `default`.
+    pub fn min(
+        &self,
+        default: usize,
+    ) -> usize {
+        self.rule
+            .and_then(|rule| self.config.get(&rule.group, &rule.key()))
+            .and_then(|overrides| overrides.min)
+            .unwrap_or(default)
+    }
+
+    /// Whether a declaration of the package refers to the item `name` of the namespace
+    /// under check.
+    pub fn is_referenced(
+        &self,
+        name: &str,
+    ) -> bool {
+        self.referenced
+            .contains(&(self.namespace.clone(), name.to_string()))
+    }
+
+    /// Whether a type expression of the package selects a field or variant named `name`.
+    pub fn is_selected(
+        &self,
+        name: &str,
+    ) -> bool {
+        self.selected.contains(name)
+    }
+
+    /// Reports a violation of the running rule on the declaration under check.
+    pub fn report(
+        &mut self,
+        reason: impl Into<String>,
+    ) -> Reported<'_> {
+        let Some(rule) = self.rule else {
+            return Reported(None);
+        };
+        self.violations.push(Violation {
+            group: rule.group,
+            rule: rule.key(),
+            level: rule.level.clone(),
+            fix: rule.fix.clone(),
+            namespace: self.namespace.clone(),
+            item: self.item.clone(),
+            member: None,
+            reason: reason.into(),
+            rename: None,
+        });
+        Reported(self.violations.last_mut())
+    }
+
+    fn with_rule(
+        &mut self,
+        rule: &'a Rule,
+        check: impl FnOnce(&dyn Check, &mut Self),
+    ) {
+        self.rule = Some(rule);
+        check(rule.handle.as_ref(), self);
+        self.rule = None;
+    }
+
+    /// Records what the declarations of `ns` refer to.
+    fn collect_references(
+        &mut self,
+        ns: &DeclNamespace,
+    ) {
+        fn visit(
+            cx: &mut LintCx,
+            ty: &DeclType,
+        ) {
+            match ty {
+                DeclType::Builtin { .. } => {},
+                DeclType::Named { reference } => cx.reference(reference),
+                DeclType::Result { ok_type, error } => {
+                    cx.reference(error);
+                    visit(cx, ok_type);
+                },
+                DeclType::Array { element_type } | DeclType::SizedArray { element_type, .. } => {
+                    visit(cx, element_type)
+                },
+                DeclType::Optional { inner_type } | DeclType::Paren { inner_type } => {
+                    visit(cx, inner_type)
+                },
+                DeclType::Map {
+                    key_type,
+                    value_type,
+                } => {
+                    visit(cx, key_type);
+                    visit(cx, value_type);
+                },
+                DeclType::TypeExpr {
+                    target, selectors, ..
+                } => {
+                    cx.selected
+                        .extend(selectors.iter().flatten().cloned());
+                    visit(cx, target);
+                },
+            }
+        }
+
+        if let Some(error) = &ns.error {
+            self.reference(error);
+        }
+        for def in &ns.types {
+            match def {
+                TypeDefinition::Struct(def) => {
+                    for field in &def.fields {
+                        visit(self, &field.ty);
+                    }
+                },
+                TypeDefinition::OneOf(DeclOneOf { variants, .. })
+                | TypeDefinition::Error(DeclError { variants, .. }) => {
+                    for variant in variants {
+                        visit(self, &variant.ty);
+                    }
+                },
+                TypeDefinition::TypeAlias(def) => visit(self, &def.target),
+                TypeDefinition::Operation(def) => {
+                    for arg in &def.args {
+                        visit(self, &arg.ty);
+                    }
+                    visit(self, &def.return_type);
+                },
+                TypeDefinition::Enum(_) => {},
+            }
+        }
+        for child in ns.namespaces.values() {
+            self.collect_references(child);
+        }
+    }
+
+    fn reference(
+        &mut self,
+        reference: &DeclNamedItemContext,
+    ) {
+        if reference.context.package == self.package {
+            self.referenced
+                .insert((reference.context.namespace.clone(), reference.name.clone()));
+        }
+    }
+}
+
+/// Every registered rule, at the levels set by a package.
+pub struct RuleRegistry {
+    collector: RuleCollector,
+    naming: NamingConventions,
+    identifiers: IdentifierPolicy,
+    config: RuleConfig<RuleGroup>,
+}
+
+impl RuleRegistry {
+    pub fn new(config: &LintConfig) -> Self {
+        use kintsu_manifests::rules::WithRule;
+
+        let collector = RuleCollector::new_with(|rule| {
+            if let Some(overrides) = config.rules.get(&rule.group, &rule.key()) {
+                rule.with(overrides);
+            }
+        });
+
+        Self {
+            collector,
+            naming: config.naming.clone(),
+            identifiers: config.identifiers.clone(),
+            config: config.rules.clone(),
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.collector.plugins
+    }
+
+    /// Runs every rule not silenced over the namespaces of `decl`.
+    pub fn run(
+        &self,
+        decl: &TypeRegistryDeclaration,
+    ) -> Vec<Violation> {
+        let package = decl.package.to_case(Case::Snake);
+        let mut cx = LintCx {
+            package: &package,
+            naming: &self.naming,
+            identifiers: &self.identifiers,
+            config: &self.config,
+            declaration: decl,
+            referenced: BTreeSet::new(),
+            selected: BTreeSet::new(),
+            namespace: Vec::new(),
+            scope: None,
+            item: None,
+            rule: None,
+            violations: Vec::new(),
+        };
+        for ns in decl.namespaces.values() {
+            cx.collect_references(ns);
+        }
+
+        let rules = self
+            .rules()
+            .iter()
+            .filter(|rule| rule.level != RuleLevel::Silent)
+            .collect::<Vec<_>>();
+        for ns in decl.namespaces.values() {
+            Self::run_namespace(&rules, ns, &mut cx);
+        }
+        cx.violations
+    }
+
+    fn run_namespace<'a>(
+        rules: &[&'a Rule],
+        ns: &'a DeclNamespace,
+        cx: &mut LintCx<'a>,
+    ) {
+        cx.namespace.push(ns.name.clone());
+        cx.scope = Some(ns);
+
+        for rule in rules {
+            cx.with_rule(rule, |check, cx| check.check_namespace(ns, cx));
+        }
+        for def in &ns.types {
+            cx.item = Some(def.name().to_string());
+            for rule in rules {
+                cx.with_rule(rule, |check, cx| check.check_type(def, cx));
+            }
+        }
+        for def in &ns.constants {
+            cx.item = Some(def.name.clone());
+            for rule in rules {
+                cx.with_rule(rule, |check, cx| check.check_const(def, cx));
+            }
+        }
+        cx.item = None;
+
+        for child in ns.namespaces.values() {
+            Self::run_namespace(rules, child, cx);
+            cx.scope = Some(ns);
+        }
+
+        cx.namespace.pop();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    crate::rule!(Fails in Form @ Error: Safe; "rule abc: must be xyz");
+
+    impl Check for Fails {
+        fn check_namespace(
+            &self,
+            ns: &DeclNamespace,
+            cx: &mut LintCx,
+        ) {
+            if ns.name == "fails" {
+                cx.report("namespace must not be named fails");
+            }
+        }
+    }
+
+    fn declaration(namespaces: &[&str]) -> TypeRegistryDeclaration {
+        let mut decl = TypeRegistryDeclaration::new("test".into());
+        for name in namespaces {
+            decl.namespaces.insert(
+                name.to_string(),
+                DeclNamespace {
+                    name: name.to_string(),
+                    version: None,
+                    error: None,
+                    types: Vec::new(),
+                    constants: Vec::new(),
+                    namespaces: BTreeMap::new(),
+                    comments: Default::default(),
+                },
+            );
+        }
+        decl
+    }
+
+    fn overrides(level: RuleLevel) -> LintConfig {
+        let rules = BTreeMap::from([(
+            "fails".to_string(),
+            RuleOverrides {
+                level: Some(level),
+                fix: None,
+                max: None,
+                min: None,
+            },
+        )]);
+        LintConfig {
+            rules: RuleConfig {
+                overrides: BTreeMap::from([(RuleGroup::Form, rules)]),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plugin_loads() {
+        let registry = RuleRegistry::new(&LintConfig::default());
+        let rule = registry
+            .rules()
+            .iter()
+            .find(|rule| rule.name == "Fails")
+            .expect("rule is registered");
+        assert_eq!(rule.key(), "fails");
+        assert_eq!(rule.level, RuleLevel::Error);
+    }
+
+    #[test]
+    fn reports_violations() {
+        let registry = RuleRegistry::new(&LintConfig::default());
+        let violations = registry
+            .run(&declaration(&["fails", "passes"]))
+            .into_iter()
+            .filter(|violation| violation.rule == "fails")
+            .collect::<Vec<_>>();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].namespace, vec!["fails".to_string()]);
+        assert!(violations[0].is_error());
+    }
+
+    #[test]
+    fn respects_overrides() {
+        let decl = declaration(&["fails"]);
+
+        let registry = RuleRegistry::new(&overrides(RuleLevel::Warn));
+        let violation = registry
+            .run(&decl)
+            .into_iter()
+            .find(|violation| violation.rule == "fails")
+            .unwrap();
+        assert_eq!(violation.level, RuleLevel::Warn);
+
+        let registry = RuleRegistry::new(&overrides(RuleLevel::Silent));
+        assert!(
+            registry
+                .run(&decl)
+                .iter()
+                .all(|violation| violation.rule != "fails")
+        );
+    }
+}
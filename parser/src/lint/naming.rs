@@ -0,0 +1,347 @@
+//! Rules on the names given to declarations, following the conventions set under
+//! `[lint.naming]`.
+
+use convert_case::{Case, Casing};
+
+use crate::declare::{
+    DeclEnum, DeclEnumDef, DeclError, DeclOneOf, DeclOperation, DeclStruct, DeclTypeAlias,
+};
+
+use super::{Check, LintCx, NameCase};
+
+fn case_of(case: &NameCase) -> Case<'static> {
+    match case {
+        NameCase::Pascal => Case::Pascal,
+        NameCase::Camel => Case::Camel,
+        NameCase::Snake => Case::Snake,
+        NameCase::ScreamingSnake => Case::Constant,
+    }
+}
+
+fn describe(case: &NameCase) -> &'static str {
+    match case {
+        NameCase::Pascal => "PascalCase",
+        NameCase::Camel => "camelCase",
+        NameCase::Snake => "snake_case",
+        NameCase::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+    }
+}
+
+/// The name `name` takes in `case`, if it is not already written that way.
+fn expected(
+    name: &str,
+    case: &NameCase,
+) -> Option<String> {
+    let renamed = name.to_case(case_of(case));
+    (renamed != name && !renamed.is_empty()).then_some(renamed)
+}
+
+/// Whether `name` is free among the items and nested namespaces of the namespace under check.
+fn is_free_in_scope(
+    name: &str,
+    cx: &LintCx,
+) -> bool {
+    cx.scope().is_none_or(|scope| {
+        !scope
+            .types
+            .iter()
+            .any(|def| def.name() == name)
+            && !scope
+                .constants
+                .iter()
+                .any(|def| def.name == name)
+            && !scope.namespaces.contains_key(name)
+    })
+}
+
+crate::rule! {
+    TypeCase in Naming @ Warn: Safe; "type names should follow the type naming convention"
+}
+
+impl TypeCase {
+    fn check_name(
+        name: &str,
+        cx: &mut LintCx,
+    ) {
+        let case = cx.naming().types;
+        if let Some(to) = expected(name, &case) {
+            let safe = is_free_in_scope(&to, cx) && !cx.is_referenced(name);
+            cx.report(format!("type name '{name}' should be {}", describe(&case)))
+                .rename(to, safe);
+        }
+    }
+}
+
+impl Check for TypeCase {
+    fn check_struct(
+        &self,
+        def: &DeclStruct,
+        cx: &mut LintCx,
+    ) {
+        Self::check_name(&def.name, cx);
+    }
+
+    fn check_enum(
+        &self,
+        def: &DeclEnumDef,
+        cx: &mut LintCx,
+    ) {
+        Self::check_name(&def.name, cx);
+    }
+
+    fn check_one_of(
+        &self,
+        def: &DeclOneOf,
+        cx: &mut LintCx,
+    ) {
+        Self::check_name(&def.name, cx);
+    }
+
+    fn check_type_alias(
+        &self,
+        def: &DeclTypeAlias,
+        cx: &mut LintCx,
+    ) {
+        Self::check_name(&def.name, cx);
+    }
+
+    fn check_error(
+        &self,
+        def: &DeclError,
+        cx: &mut LintCx,
+    ) {
+        Self::check_name(&def.name, cx);
+    }
+}
+
+crate::rule! {
+    FieldCase in Naming @ Warn: Safe; "struct fields should follow the field naming convention"
+}
+
+impl Check for FieldCase {
+    fn check_struct(
+        &self,
+        def: &DeclStruct,
+        cx: &mut LintCx,
+    ) {
+        let case = cx.naming().fields;
+        for field in &def.fields {
+            if let Some(to) = expected(&field.name, &case) {
+                let safe = !def
+                    .fields
+                    .iter()
+                    .any(|other| other.name == to)
+                    && !cx.is_selected(&field.name);
+                cx.report(format!(
+                    "field '{}' should be {}",
+                    field.name,
+                    describe(&case)
+                ))
+                .on_member(&field.name)
+                .rename(to, safe);
+            }
+        }
+    }
+}
+
+crate::rule! {
+    OperationCase in Naming @ Warn: Safe; "operations should follow the operation naming convention"
+}
+
+impl Check for OperationCase {
+    fn check_operation(
+        &self,
+        def: &DeclOperation,
+        cx: &mut LintCx,
+    ) {
+        let case = cx.naming().operations;
+        if let Some(to) = expected(&def.name, &case) {
+            let safe = is_free_in_scope(&to, cx);
+            cx.report(format!(
+                "operation '{}' should be {}",
+                def.name,
+                describe(&case)
+            ))
+            .rename(to, safe);
+        }
+    }
+}
+
+crate::rule! {
+    VariantCase in Naming @ Warn: Safe; "enum variants should follow the variant naming convention"
+}
+
+impl Check for VariantCase {
+    fn check_enum(
+        &self,
+        def: &DeclEnumDef,
+        cx: &mut LintCx,
+    ) {
+        let case = cx.naming().variants;
+        let names: Vec<&str> = match &def.enum_def {
+            DeclEnum::Int(variants) => {
+                variants
+                    .iter()
+                    .map(|v| v.name.as_str())
+                    .collect()
+            },
+            DeclEnum::String(variants) => {
+                variants
+                    .iter()
+                    .map(|v| v.name.as_str())
+                    .collect()
+            },
+        };
+
+        for name in &names {
+            if let Some(to) = expected(name, &case) {
+                let safe = !names.contains(&to.as_str()) && !cx.is_selected(name);
+                cx.report(format!("variant '{name}' should be {}", describe(&case)))
+                    .on_member(*name)
+                    .rename(to, safe);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        declare::{
+            Builtin, DeclField, DeclIntVariant, DeclNamespace, DeclType, Meta, TypeDefinition,
+            TypeRegistryDeclaration,
+        },
+        lint::{LintConfig, RuleRegistry, Violation},
+    };
+
+    fn field(name: &str) -> DeclField {
+        DeclField {
+            name: name.into(),
+            ty: DeclType::Builtin { ty: Builtin::Bool },
+            default_value: None,
+            optional: false,
+            comments: Default::default(),
+            constraints: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    fn variant(
+        name: &str,
+        value: u32,
+    ) -> DeclIntVariant {
+        DeclIntVariant {
+            name: name.into(),
+            value,
+            comments: Default::default(),
+            aliases: Vec::new(),
+        }
+    }
+
+    fn lint(types: Vec<TypeDefinition>) -> Vec<Violation> {
+        let mut decl = TypeRegistryDeclaration::new("test".into());
+        decl.namespaces.insert(
+            "pkg".into(),
+            DeclNamespace {
+                name: "pkg".into(),
+                version: None,
+                error: None,
+                types,
+                constants: Vec::new(),
+                namespaces: BTreeMap::new(),
+                comments: Default::default(),
+            },
+        );
+        RuleRegistry::new(&LintConfig::default())
+            .run(&decl)
+            .into_iter()
+            .filter(|violation| violation.group == crate::lint::RuleGroup::Naming)
+            .collect()
+    }
+
+    fn structure(
+        name: &str,
+        fields: &[&str],
+    ) -> TypeDefinition {
+        TypeDefinition::Struct(DeclStruct {
+            name: name.into(),
+            fields: fields
+                .iter()
+                .map(|name| field(name))
+                .collect(),
+            meta: Meta::new(1),
+            comments: Default::default(),
+        })
+    }
+
+    #[test]
+    fn conventional_names_pass() {
+        let enm = TypeDefinition::Enum(DeclEnumDef {
+            name: "Status".into(),
+            enum_def: DeclEnum::Int(vec![variant("ACTIVE", 1), variant("IN_REVIEW", 2)]),
+            meta: Meta::new(1),
+            comments: Default::default(),
+        });
+        assert_eq!(lint(vec![structure("User", &["user_id"]), enm]), vec![]);
+    }
+
+    #[test]
+    fn suggests_renames() {
+        let violations = lint(vec![structure("user_account", &["userId"])]);
+        let mut renames = violations
+            .iter()
+            .map(|v| {
+                (
+                    v.rule.as_str(),
+                    v.member.as_deref(),
+                    v.rename.as_ref().unwrap().to.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        renames.sort();
+        assert_eq!(
+            renames,
+            vec![
+                ("field_case", Some("userId"), "user_id"),
+                ("type_case", None, "UserAccount"),
+            ]
+        );
+        assert!(
+            violations
+                .iter()
+                .all(|v| v.rename.as_ref().unwrap().safe)
+        );
+    }
+
+    #[test]
+    fn colliding_renames_are_unsafe() {
+        let violations = lint(vec![
+            structure("Account", &["userId", "user_id"]),
+            structure("account", &[]),
+        ]);
+        assert_eq!(violations.len(), 2);
+        assert!(
+            violations
+                .iter()
+                .all(|v| !v.rename.as_ref().unwrap().safe)
+        );
+    }
+
+    #[test]
+    fn variants_use_screaming_snake() {
+        let enm = TypeDefinition::Enum(DeclEnumDef {
+            name: "Status".into(),
+            enum_def: DeclEnum::Int(vec![variant("Active", 1), variant("InReview", 2)]),
+            meta: Meta::new(1),
+            comments: Default::default(),
+        });
+        let renames = lint(vec![enm])
+            .into_iter()
+            .map(|v| v.rename.unwrap().to)
+            .collect::<Vec<_>>();
+        assert_eq!(renames, vec!["ACTIVE", "IN_REVIEW"]);
+    }
+}
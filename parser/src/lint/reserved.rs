@@ -0,0 +1,178 @@
+//! The reserved words of each code generation [`Backend`], which declared names are checked
+//! against by the `naming::reserved_word` rule.
+
+use super::Backend;
+
+/// Keywords of Rust 2024, strict and reserved for future use.
+pub const RUST: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Reserved words of TypeScript, including those reserved in strict mode.
+pub const TYPESCRIPT: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Hard keywords of Python 3.
+pub const PYTHON: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Type names GraphQL or the GraphQL backend already declares.
+pub const GRAPHQL_TYPES: &[&str] = &[
+    "Int",
+    "Float",
+    "String",
+    "Boolean",
+    "ID",
+    "Query",
+    "Mutation",
+    "Subscription",
+    "Int64",
+    "UInt64",
+    "DateTime",
+    "Bytes",
+    "JSON",
+];
+
+/// Enum values GraphQL reserves for literals.
+pub const GRAPHQL_VALUES: &[&str] = &["true", "false", "null"];
+
+/// Reserved words of SQL:2016 most likely to be used as names, compared case-insensitively.
+pub const SQL: &[&str] = &[
+    "all",
+    "and",
+    "any",
+    "as",
+    "between",
+    "by",
+    "case",
+    "check",
+    "column",
+    "constraint",
+    "create",
+    "cross",
+    "current",
+    "default",
+    "delete",
+    "distinct",
+    "drop",
+    "else",
+    "end",
+    "except",
+    "exists",
+    "false",
+    "from",
+    "full",
+    "grant",
+    "group",
+    "having",
+    "in",
+    "inner",
+    "insert",
+    "intersect",
+    "into",
+    "is",
+    "join",
+    "left",
+    "like",
+    "limit",
+    "not",
+    "null",
+    "of",
+    "on",
+    "or",
+    "order",
+    "outer",
+    "primary",
+    "references",
+    "right",
+    "select",
+    "set",
+    "table",
+    "then",
+    "to",
+    "true",
+    "union",
+    "unique",
+    "update",
+    "user",
+    "using",
+    "values",
+    "when",
+    "where",
+    "with",
+];
+
+/// Whether `name` cannot be used as written by `backend`.
+pub fn is_reserved(
+    backend: Backend,
+    name: &str,
+) -> bool {
+    match backend {
+        Backend::Rust => RUST.contains(&name),
+        Backend::Typescript => TYPESCRIPT.contains(&name),
+        Backend::Python => PYTHON.contains(&name),
+        // names starting with `__` belong to introspection
+        Backend::Graphql => {
+            name.starts_with("__")
+                || GRAPHQL_TYPES.contains(&name)
+                || GRAPHQL_VALUES.contains(&name)
+        },
+        Backend::Sql => {
+            SQL.iter()
+                .any(|word| word.eq_ignore_ascii_case(name))
+        },
+    }
+}
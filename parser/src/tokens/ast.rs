@@ -120,6 +120,7 @@ impl<T: Peek + Parse + ImplDiagnostic, Sep: Peek + Parse + Clone + ImplDiagnosti
 {
     fn parse(stream: &mut TokenStream) -> Result<Self, LexingError> {
         let mut values = Vec::new();
+        stream.skip_comments_before::<T>();
         if !stream.peek::<T>() {
             return Err(LexingError::empty::<T>());
         }
@@ -134,6 +135,7 @@ impl<T: Peek + Parse + ImplDiagnostic, Sep: Peek + Parse + Clone + ImplDiagnosti
             sep: sep.clone(),
         });
         while sep.is_some() {
+            stream.skip_comments_before::<T>();
             if !stream.peek::<T>() {
                 break;
             }
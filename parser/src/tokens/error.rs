@@ -57,6 +57,10 @@ pub enum LexingError {
 
     #[error("{source}")]
     Spanned { source: Box<Self>, span: Span },
+
+    /// A complete item that is not followed by `;`. `at` is the byte offset the `;` belongs at.
+    #[error("{source}")]
+    MissingSemicolon { source: Box<Self>, at: usize },
 }
 
 impl LexingError {
@@ -113,6 +117,25 @@ impl LexingError {
         move |this| this.with_span(span)
     }
 
+    pub fn missing_semicolon(
+        self,
+        at: usize,
+    ) -> Self {
+        Self::MissingSemicolon {
+            source: Box::new(self),
+            at,
+        }
+    }
+
+    /// The span of the innermost located error.
+    pub fn error_span(&self) -> Option<&Span> {
+        match self {
+            Self::Spanned { span, .. } => Some(span),
+            Self::MissingSemicolon { source, .. } => source.error_span(),
+            _ => None,
+        }
+    }
+
     pub fn unknown_meta<I: IntoIterator<Item = &'static str>>(
         expect: I,
         found: String,
@@ -195,6 +195,20 @@ impl TokenStream {
         }
     }
 
+    /// Skips comments which `T` does not parse itself, so comments between repeated items
+    /// do not end the repetition. The formatter keeps them through its trivia.
+    pub fn skip_comments_before<T: Peek>(&mut self) {
+        while !self.peek::<T>()
+            && matches!(
+                self.peek_unchecked()
+                    .map(|token| &token.value),
+                Some(Token::CommentSingleLine(_) | Token::CommentMultiLine(_))
+            )
+        {
+            self.next();
+        }
+    }
+
     pub fn is_fork(&self) -> bool {
         self.is_fork
     }
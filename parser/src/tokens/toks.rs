@@ -62,7 +62,9 @@ macro_rules! tokens {
 
                 impl super::ToTokens for Spanned<[<$tok Token>]> {
                     fn write(&self, tt: &mut crate::fmt::Printer) {
+                        tt.leading_trivia(&self.span);
                         tt.token(&self.token());
+                        tt.trailing_trivia(&self.span);
                     }
                 }
 
@@ -177,6 +179,10 @@ declare_tokens! {
     Hash,
     #[token("!")]
     Bang,
+    #[token("*")]
+    Star,
+    #[token("..")]
+    DotDot,
 
     #[token("namespace")]
     KwNamespace,
@@ -194,6 +200,8 @@ declare_tokens! {
     KwError,
     #[token("operation")]
     KwOperation,
+    #[token("const")]
+    KwConst,
 
 
     #[token("bool")]
@@ -309,6 +317,8 @@ impl fmt::Display for Token {
             Pipe => write!(f, "|"),
             Hash => write!(f, "#"),
             Bang => write!(f, "!"),
+            Star => write!(f, "*"),
+            DotDot => write!(f, ".."),
             KwNamespace => write!(f, "namespace"),
             KwUse => write!(f, "use"),
             KwStruct => write!(f, "struct"),
@@ -317,6 +327,7 @@ impl fmt::Display for Token {
             KwOneof => write!(f, "oneof"),
             KwError => write!(f, "error"),
             KwOperation => write!(f, "operation"),
+            KwConst => write!(f, "const"),
             KwBool => write!(f, "bool"),
             KwNull => write!(f, "null"),
             KwStr => write!(f, "str"),
@@ -344,6 +355,8 @@ impl fmt::Display for Token {
             Path(p) => write!(f, "{}", p),
             Number(n) => write!(f, "{}", n),
             String(s) => write!(f, "\"{}\"", s),
+            // keeps `///` and `//!` comments intact
+            CommentSingleLine(s) if s.starts_with(['/', '!']) => write!(f, "//{}", s),
             CommentSingleLine(s) => write!(f, "// {}", s),
             CommentMultiLine(s) => {
                 if s.contains('\n') {
@@ -425,6 +438,8 @@ macro_rules! Token {
     [|] => { $crate::tokens::toks::PipeToken };
     [#] => { $crate::tokens::toks::HashToken };
     [!] => { $crate::tokens::toks::BangToken };
+    [*] => { $crate::tokens::toks::StarToken };
+    [..] => { $crate::tokens::toks::DotDotToken };
     [namespace] => { $crate::tokens::toks::KwNamespaceToken };
     [use] => { $crate::tokens::toks::KwUseToken };
     [struct] => { $crate::tokens::toks::KwStructToken };
@@ -433,6 +448,7 @@ macro_rules! Token {
     [oneof] => { $crate::tokens::toks::KwOneofToken };
     [error] => { $crate::tokens::toks::KwErrorToken };
     [operation] => { $crate::tokens::toks::KwOperationToken };
+    [const] => { $crate::tokens::toks::KwConstToken };
     [schema] => { $crate::tokens::toks::KwSchemaToken };
     [bool] => { $crate::tokens::toks::KwBoolToken };
     [null] => { $crate::tokens::toks::KwNullToken };
@@ -535,7 +551,8 @@ syntax_desc! {{
         type = "keyword `type`. used to declare a type alias.",
         oneof = "keyword `oneof`. used to declare a sequence of type variants or named enumeration of types.",
         error = "keyword `error`. used to declare an error type.",
-        operation = "keyword `operation`. used to declare an operation."
+        operation = "keyword `operation`. used to declare an operation.",
+        const = "keyword `const`. used to declare a named constant value, e.g. `const MAX_LEN: u32 = 16;`."
     ],
     builtin: [
         bool = "a boolean type (true | false)",
@@ -568,7 +585,8 @@ syntax_desc! {{
         ? = "used to indicate an optional type.",
         = = "equals is used to declare a named type, or provide a static value to an enum member.",
         # = "pound tokens are used in meta. e.g. `#[...]`",
-        ! = "bang tokens are used to set meta as inner meta, or declare a return type may raise an error. e.g. `-> i32!`."
+        ! = "bang tokens are used to set meta as inner meta, or declare a return type may raise an error. e.g. `-> i32!`.",
+        * = "star tokens are used in glob imports to bring every item of a namespace into scope. e.g. `use pkg::ns::*`."
     ]
 }}
 
@@ -103,6 +103,8 @@ pub fn create_raw_namespace(name: &str) -> NamespaceCtx {
         resolved_errors: Default::default(),
         resolved_aliases: Default::default(),
         resolved_versions: Default::default(),
+        resolved_constants: Default::default(),
+        source_map: Default::default(),
     }
 }
 
@@ -144,8 +146,15 @@ pub async fn resolver_with_checked(
                 license: None,
                 readme: None,
                 repository: None,
+                metadata: Default::default(),
+                embed_metadata: false,
             },
             files: FileConfig::default(),
+            fmt: None,
+            lint: Default::default(),
+            registries: Default::default(),
+            profiles: Default::default(),
+            hooks: Default::default(),
             dependencies: BTreeMap::new(),
         }),
         namespaces: vec![("test".into(), Arc::new(Mutex::new(ns)))]
@@ -153,6 +162,7 @@ pub async fn resolver_with_checked(
             .collect(),
         root_path: PathBuf::from("."),
         registry,
+        recovered_errors: Vec::new(),
     };
 
     let schema = Arc::new(schema);
@@ -234,8 +244,15 @@ pub async fn register_namespace_types(ns: NamespaceCtx) -> crate::Result<Arc<Mut
                 license: None,
                 readme: None,
                 repository: None,
+                metadata: Default::default(),
+                embed_metadata: false,
             },
             files: FileConfig::default(),
+            fmt: None,
+            lint: Default::default(),
+            registries: Default::default(),
+            profiles: Default::default(),
+            hooks: Default::default(),
             dependencies: BTreeMap::new(),
         }),
         namespaces: vec![("test".into(), Arc::new(Mutex::new(ns)))]
@@ -243,6 +260,7 @@ pub async fn register_namespace_types(ns: NamespaceCtx) -> crate::Result<Arc<Mut
             .collect(),
         root_path: PathBuf::from("."),
         registry,
+        recovered_errors: Vec::new(),
     };
 
     let schema = Arc::new(schema);
@@ -266,7 +284,7 @@ pub async fn resolver_from_source(source: &str) -> crate::Result<TypeResolver> {
         crate::Error::from(e).with_source(PathBuf::from("test.ks"), Arc::clone(&source))
     })?;
 
-    let ast = AstStream::from_tokens_with(&PathBuf::from("test.ks"), &mut tt)?;
+    let ast = AstStream::from_tokens_with(PathBuf::from("test.ks"), &mut tt)?;
 
     let ref_ctx = RefContext::new("test_package".to_string(), vec![]);
     let registry = TypeRegistry::new();
@@ -299,8 +317,15 @@ pub async fn resolver_from_source(source: &str) -> crate::Result<TypeResolver> {
                 license: None,
                 readme: None,
                 repository: None,
+                metadata: Default::default(),
+                embed_metadata: false,
             },
             files: FileConfig::default(),
+            fmt: None,
+            lint: Default::default(),
+            registries: Default::default(),
+            profiles: Default::default(),
+            hooks: Default::default(),
             dependencies: BTreeMap::new(),
         }),
         namespaces: vec![(ns_name.clone(), Arc::new(Mutex::new(ns)))]
@@ -308,6 +333,7 @@ pub async fn resolver_from_source(source: &str) -> crate::Result<TypeResolver> {
             .collect(),
         root_path: PathBuf::from("."),
         registry,
+        recovered_errors: Vec::new(),
     };
 
     let schema = Arc::new(schema);
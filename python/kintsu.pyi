@@ -0,0 +1,55 @@
+from os import PathLike
+from typing import Any, Literal, Optional, TypedDict
+
+class Span(TypedDict):
+    start: int
+    end: int
+
+class Label(TypedDict):
+    span: Span
+    message: str
+
+class Diagnostic(TypedDict, total=False):
+    code: str
+    message: str
+    severity: Literal["error", "warning", "info", "hint"]
+    span: Span
+    source_name: str
+    help: str
+    labels: list[Label]
+
+class Generated(TypedDict):
+    files: dict[str, str]
+    skipped: list[str]
+
+class CompileError(Exception):
+    diagnostics: list[Diagnostic]
+
+class Package:
+    @property
+    def name(self) -> str: ...
+    @property
+    def version(self) -> str: ...
+    def declarations(self, profile: Optional[str] = None) -> dict[str, Any]: ...
+    def diagnostics(self) -> list[Diagnostic]: ...
+
+def compile(path: str | PathLike[str]) -> Package: ...
+def format(
+    source: str,
+    name: str = "lib.ks",
+    *,
+    max_width: Optional[int] = None,
+    indent_width: Optional[int] = None,
+    indent_with_tabs: Optional[bool] = None,
+) -> str: ...
+def generate_graphql(
+    declarations: dict[str, Any],
+    *,
+    query_prefixes: Optional[list[str]] = None,
+) -> Generated: ...
+def generate_sql(
+    declarations: dict[str, Any],
+    *,
+    dialect: Literal["postgres", "sqlite"] = "postgres",
+    enums: Literal["check", "type"] = "check",
+) -> Generated: ...
@@ -0,0 +1,28 @@
+use pyo3::{exceptions::PyException, prelude::*};
+
+pyo3::create_exception!(
+    kintsu,
+    CompileError,
+    PyException,
+    "Compiling, formatting or generating failed. `diagnostics` holds why, as dicts of `code`, \
+     `message`, `severity`, `span`, `source_name`, `help` and `labels`, root causes first."
+);
+
+/// The [`CompileError`] raised for `err`, carrying its diagnostics.
+pub(crate) fn compile_error(
+    py: Python<'_>,
+    err: kintsu::Error,
+) -> PyErr {
+    let raised = CompileError::new_err(err.to_string());
+    let diagnostics = match crate::to_py(py, err.diagnostics()) {
+        Ok(diagnostics) => diagnostics,
+        Err(err) => return err,
+    };
+    match raised
+        .value(py)
+        .setattr("diagnostics", diagnostics)
+    {
+        Ok(()) => raised,
+        Err(err) => err,
+    }
+}
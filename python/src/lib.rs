@@ -0,0 +1,318 @@
+//! Python bindings to the compiler, so data-platform tooling checks and generates from
+//! schemas without shelling out to `kintsu`.
+//!
+//! Declarations and diagnostics cross into Python as the dicts and lists their JSON form
+//! decodes to. Failures raise [`CompileError`], carrying the diagnostics reported for them.
+//! Build with `maturin build -m python/Cargo.toml`.
+//!
+//! ```python
+//! import kintsu
+//!
+//! package = kintsu.compile("./schemas/shop")
+//! for diagnostic in package.diagnostics():
+//!     print(diagnostic["code"], diagnostic["message"])
+//!
+//! sdl = kintsu.generate_graphql(package.declarations())["files"]
+//! ```
+
+use std::{path::PathBuf, sync::LazyLock};
+
+use kintsu::{
+    declare::DeclarationBundle,
+    format::FormatConfig,
+    generate::{EnumStyle, Generated, GraphqlConfig, SqlConfig, SqlDialect},
+};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyString},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+mod error;
+
+pub use error::CompileError;
+use error::compile_error;
+
+/// Compilation is driven to completion by each call, with the GIL released.
+static RUNTIME: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime")
+});
+
+fn to_py<'py>(
+    py: Python<'py>,
+    value: &(impl Serialize + ?Sized),
+) -> PyResult<Bound<'py, PyAny>> {
+    let json =
+        serde_json::to_string(value).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    py.import("json")?
+        .call_method1("loads", (json,))
+}
+
+fn from_py<T: DeserializeOwned>(
+    value: &Bound<'_, PyAny>,
+    what: &str,
+) -> PyResult<T> {
+    let json = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?;
+    serde_json::from_str(json.cast::<PyString>()?.to_str()?)
+        .map_err(|err| PyValueError::new_err(format!("invalid {what}: {err}")))
+}
+
+fn generated<'py>(
+    py: Python<'py>,
+    result: kintsu::Result<Generated>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let generated = result.map_err(|err| compile_error(py, err))?;
+    let files = generated
+        .files
+        .into_iter()
+        .map(|(path, source)| (path.to_string_lossy().into_owned(), source))
+        .collect::<std::collections::BTreeMap<_, _>>();
+
+    let dict = PyDict::new(py);
+    dict.set_item("files", files)?;
+    dict.set_item("skipped", generated.skipped)?;
+    Ok(dict)
+}
+
+/// A compiled package and its resolved dependencies.
+#[pyclass(module = "kintsu", frozen)]
+pub struct Package(kintsu::Package);
+
+#[pymethods]
+impl Package {
+    #[getter]
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    #[getter]
+    fn version(&self) -> String {
+        self.0.version().to_string()
+    }
+
+    /// The declarations of the package and its dependencies, or of the surface the
+    /// manifest's `profile` selects.
+    #[pyo3(signature = (profile = None))]
+    fn declarations<'py>(
+        &self,
+        py: Python<'py>,
+        profile: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let bundle = py
+            .detach(|| {
+                RUNTIME.block_on(async {
+                    match profile {
+                        Some(profile) => self.0.profile_declarations(profile).await,
+                        None => self.0.declarations().await,
+                    }
+                })
+            })
+            .map_err(|err| compile_error(py, err))?;
+        to_py(py, &bundle)
+    }
+
+    /// The lint violations of the package, with the severity their rule is configured at.
+    fn diagnostics<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let diagnostics = py
+            .detach(|| RUNTIME.block_on(self.0.diagnostics()))
+            .map_err(|err| compile_error(py, err))?;
+        to_py(py, &diagnostics)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Package({} {})", self.0.name(), self.0.version())
+    }
+}
+
+/// Compiles the package in `path`, resolving its dependencies.
+#[pyfunction]
+fn compile(
+    py: Python<'_>,
+    path: PathBuf,
+) -> PyResult<Package> {
+    py.detach(|| RUNTIME.block_on(kintsu::compile(path)))
+        .map(Package)
+        .map_err(|err| compile_error(py, err))
+}
+
+/// Formats schema source the way `kintsu fmt` does. `name` is the file reported in
+/// diagnostics.
+#[pyfunction]
+#[pyo3(signature = (source, name = "lib.ks", *, max_width = None, indent_width = None, indent_with_tabs = None))]
+fn format(
+    py: Python<'_>,
+    source: &str,
+    name: &str,
+    max_width: Option<usize>,
+    indent_width: Option<usize>,
+    indent_with_tabs: Option<bool>,
+) -> PyResult<String> {
+    let mut config = FormatConfig::default();
+    if let Some(max_width) = max_width {
+        config.max_width = max_width;
+    }
+    if let Some(indent_width) = indent_width {
+        config.indent_width = indent_width;
+    }
+    if let Some(indent_with_tabs) = indent_with_tabs {
+        config.indent_with_tabs = indent_with_tabs;
+    }
+    kintsu::format::format(name, source, &config).map_err(|err| compile_error(py, err))
+}
+
+/// GraphQL SDL for `declarations`, as `{"files": {path: sdl}, "skipped": [reason]}`.
+#[pyfunction]
+#[pyo3(signature = (declarations, *, query_prefixes = None))]
+fn generate_graphql<'py>(
+    py: Python<'py>,
+    declarations: &Bound<'py, PyAny>,
+    query_prefixes: Option<Vec<String>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let bundle: DeclarationBundle = from_py(declarations, "declarations")?;
+    let mut config = GraphqlConfig::default();
+    if let Some(query_prefixes) = query_prefixes {
+        config.query_prefixes = query_prefixes;
+    }
+    generated(py, kintsu::generate::graphql(&bundle.root, config))
+}
+
+/// SQL DDL for `declarations`, as `{"files": {path: ddl}, "skipped": [reason]}`. `dialect`
+/// is `postgres` or `sqlite`, and `enums` is `check` or `type`.
+#[pyfunction]
+#[pyo3(signature = (declarations, *, dialect = "postgres", enums = "check"))]
+fn generate_sql<'py>(
+    py: Python<'py>,
+    declarations: &Bound<'py, PyAny>,
+    dialect: &str,
+    enums: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    let bundle: DeclarationBundle = from_py(declarations, "declarations")?;
+    let config = SqlConfig {
+        dialect: serde_json::from_value::<SqlDialect>(dialect.into())
+            .map_err(|_| PyValueError::new_err(format!("unknown dialect '{dialect}'")))?,
+        enums: serde_json::from_value::<EnumStyle>(enums.into())
+            .map_err(|_| PyValueError::new_err(format!("unknown enum style '{enums}'")))?,
+    };
+    generated(py, kintsu::generate::sql(&bundle.root, config))
+}
+
+#[pymodule]
+#[pyo3(name = "kintsu")]
+fn kintsu_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("CompileError", m.py().get_type::<CompileError>())?;
+    m.add_class::<Package>()?;
+    m.add_function(wrap_pyfunction!(compile, m)?)?;
+    m.add_function(wrap_pyfunction!(format, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_graphql, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_sql, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CString;
+
+    use super::*;
+
+    fn run(
+        script: &str,
+        dir: Option<&std::path::Path>,
+    ) {
+        Python::initialize();
+        Python::attach(|py| {
+            let kintsu = pyo3::wrap_pymodule!(kintsu_module)(py);
+            py.import("sys")
+                .unwrap()
+                .getattr("modules")
+                .unwrap()
+                .set_item("kintsu", kintsu)
+                .unwrap();
+
+            let globals = PyDict::new(py);
+            globals
+                .set_item("root", dir.map(|dir| dir.display().to_string()))
+                .unwrap();
+            let script = CString::new(script).unwrap();
+            if let Err(err) = py.run(&script, Some(&globals), None) {
+                err.display(py);
+                panic!("{err}");
+            }
+        });
+    }
+
+    fn shop(lib: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("schema.toml"),
+            "version = \"v1\"\n[package]\nname = \"shop\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("schema")).unwrap();
+        std::fs::write(dir.path().join("schema/lib.ks"), lib).unwrap();
+        dir
+    }
+
+    #[test]
+    fn compiles_and_generates() {
+        let dir = shop("namespace shop;\nnamespace users { struct User { id: i64 }; };");
+        run(
+            r#"
+import kintsu
+
+package = kintsu.compile(root)
+assert (package.name, package.version) == ("shop", "1.2.0")
+assert package.diagnostics() == []
+
+declarations = package.declarations()
+assert "users" in declarations["root"]["namespaces"]
+
+graphql = kintsu.generate_graphql(declarations)
+assert any("User" in sdl for sdl in graphql["files"].values())
+sql = kintsu.generate_sql(declarations, dialect="sqlite")
+assert set(sql) == {"files", "skipped"}
+"#,
+            Some(dir.path()),
+        );
+    }
+
+    #[test]
+    fn raises_diagnostics() {
+        let dir = shop("namespace shop;\nnamespace users { struct User { id: Missing }; };");
+        run(
+            r#"
+import kintsu
+
+try:
+    kintsu.compile(root).declarations()
+    raise AssertionError("compiled")
+except kintsu.CompileError as err:
+    assert any(d["severity"] == "error" for d in err.diagnostics), err.diagnostics
+
+try:
+    kintsu.format("struct {", name="broken.ks")
+    raise AssertionError("formatted")
+except kintsu.CompileError as err:
+    assert err.diagnostics[0]["source_name"] == "broken.ks"
+
+assert "struct User {" in kintsu.format("namespace pkg;\nstruct   User {id:i64};")
+
+try:
+    kintsu.generate_sql({}, dialect="oracle")
+    raise AssertionError("generated")
+except ValueError:
+    pass
+"#,
+            Some(dir.path()),
+        );
+    }
+}
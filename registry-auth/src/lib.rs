@@ -6,6 +6,10 @@ pub enum PrincipalType {
     UserSession,
     UserApiKey,
     OrgApiKey,
+    /// An unauthenticated client, identified only by its address
+    Anonymous,
+    /// The registry itself, acting from a background job
+    System,
 }
 
 /// Permission types for audit logging (mirrors registry-db Permission without sea-orm deps)
@@ -23,6 +27,8 @@ pub enum AuditPermission {
     ListOrgToken,
     CreatePersonalToken,
     RevokePersonalToken,
+    ManageTeam,
+    ManageTeamMembers,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -61,6 +67,12 @@ pub struct OrgRoleResource {
     pub user_id: i64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TeamResource {
+    pub org_id: i64,
+    pub team_id: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "resource_type", rename_all = "snake_case")]
 pub enum ResourceIdentifier {
@@ -69,6 +81,32 @@ pub enum ResourceIdentifier {
     Token(TokenResource),
     SchemaRole(SchemaRoleResource),
     OrgRole(OrgRoleResource),
+    Team(TeamResource),
+}
+
+/// What a rate limit was keyed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RateLimitScope {
+    Ip,
+    Principal,
+}
+
+/// Org role types for audit logging (mirrors registry-db OrgRoleType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditOrgRole {
+    Admin,
+    Member,
+}
+
+/// How syncing an org from GitHub changed a user's membership.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OrgSyncChange {
+    Joined,
+    RoleChanged,
+    Left,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -88,6 +126,20 @@ pub enum AuditEventType {
         org_id: i64,
         accepted: bool,
     },
+    /// An org membership was brought in line with the org at GitHub.
+    OrgMembershipSynced {
+        org_id: i64,
+        user_id: i64,
+        change: OrgSyncChange,
+        /// The role held after the change, `None` once the user left
+        role: Option<AuditOrgRole>,
+    },
+    /// A client kept sending requests after being rate limited.
+    RateLimitExceeded {
+        scope: RateLimitScope,
+        rejected: u32,
+        window_secs: u64,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -98,7 +150,10 @@ pub enum Policy {
     ScopeMatch,
     SchemaAdmin,
     FirstPublish,
+    /// Admin of the org owning a package scope
+    ScopeOwner,
     OrgAdmin,
+    TeamMaintainer,
     TokenOwnership,
     NotApplicable,
 }
@@ -3,6 +3,68 @@ use utoipa::ToSchema;
 
 pub mod models;
 
+/// Product token sent by kintsu tooling in the `User-Agent` header.
+pub const CLIENT_PRODUCT: &str = "kintsu";
+
+/// `User-Agent` of this build of the kintsu tooling, e.g. `kintsu/0.3.1`.
+pub fn client_user_agent() -> String {
+    format!("{CLIENT_PRODUCT}/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Extracts the compiler version from a `User-Agent` sent by kintsu tooling.
+///
+/// Returns `None` for other clients, and for kintsu clients whose version is empty,
+/// overly long or contains characters that cannot appear in a version.
+pub fn parse_client_user_agent(user_agent: &str) -> Option<&str> {
+    let version = user_agent
+        .split_whitespace()
+        .find_map(|token| {
+            token
+                .strip_prefix(CLIENT_PRODUCT)?
+                .strip_prefix('/')
+        })?;
+
+    let valid = !version.is_empty()
+        && version.len() <= 64
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+
+    valid.then_some(version)
+}
+
+/// Size of a package's files a registry accepts unless configured otherwise.
+pub const DEFAULT_MAX_PACKAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Number of files in a package a registry accepts unless configured otherwise.
+pub const DEFAULT_MAX_PACKAGE_FILES: usize = 1000;
+
+/// The quota packages are gathered within for publishing, so an oversized package fails
+/// before it is uploaded.
+pub fn package_quota() -> kintsu_fs::memory::MemoryQuota {
+    kintsu_fs::memory::MemoryQuota::default()
+        .with_max_bytes(DEFAULT_MAX_PACKAGE_BYTES)
+        .with_max_files(DEFAULT_MAX_PACKAGE_FILES)
+}
+
+/// Header carrying the id a request is correlated by in registry logs, audit events and
+/// error responses. Clients may send their own, and the registry echoes the id it used.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// A fresh random request id.
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Whether a request id sent by a client is safe to log and echo back.
+pub fn is_valid_request_id(request_id: &str) -> bool {
+    !request_id.is_empty()
+        && request_id.len() <= 128
+        && request_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
 #[derive(Debug, thiserror::Error, serde::Serialize, serde::Deserialize, ToSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum PackagingError {
@@ -10,6 +72,14 @@ pub enum PackagingError {
     EmptyPackageData,
     #[error("invalid file '{path}': {reason}")]
     InvalidFile { path: String, reason: String },
+    #[error("package data is {size} bytes, over the limit of {limit}")]
+    PackageTooLarge { size: usize, limit: usize },
+    #[error("package has {count} files, over the limit of {limit}")]
+    TooManyFiles { count: usize, limit: usize },
+    #[error("package and its dependencies are {size} bytes, over the compile limit of {limit}")]
+    CompileInputTooLarge { size: usize, limit: usize },
+    #[error("compiling the package took longer than {seconds}s")]
+    CompileTimedOut { seconds: u64 },
 }
 
 #[derive(serde::Serialize, serde::Deserialize, ToSchema)]
@@ -24,6 +94,8 @@ pub enum PublicErrorType {
     Unauthorized,
     Forbidden,
     NotFound,
+    NotImplemented,
+    RateLimited,
 
     InvalidToken,
     TokenExpired,
@@ -49,6 +121,8 @@ impl From<&PublicErrorType> for &'static str {
             PublicErrorType::Unauthorized => "unauthorized",
             PublicErrorType::Forbidden => "forbidden",
             PublicErrorType::NotFound => "not-found",
+            PublicErrorType::NotImplemented => "not-implemented",
+            PublicErrorType::RateLimited => "rate-limited",
             PublicErrorType::InvalidToken => "invalid-token",
             PublicErrorType::TokenExpired => "token-expired",
             PublicErrorType::AuthorizationRequired => "authorization-required",
@@ -88,6 +162,9 @@ pub struct ErrorResponse {
     pub error_description: Option<String>,
     pub error_uri: Option<String>,
     pub validation: Option<HashMap<String, Vec<String>>>,
+    /// Id of the failed request, see [`REQUEST_ID_HEADER`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl ErrorResponse {
@@ -98,6 +175,7 @@ impl ErrorResponse {
             error_description: None,
             error_uri: None,
             validation: None,
+            request_id: None,
         }
     }
 
@@ -111,6 +189,7 @@ impl ErrorResponse {
             error_description: desc,
             error_uri: None,
             validation: None,
+            request_id: None,
         }
     }
 
@@ -124,6 +203,38 @@ impl ErrorResponse {
             error_description: desc,
             error_uri: None,
             validation: None,
+            request_id: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_client_user_agent() {
+        assert_eq!(
+            parse_client_user_agent(&client_user_agent()),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(
+            parse_client_user_agent("kintsu/1.2.0-rc.1 (linux)"),
+            Some("1.2.0-rc.1")
+        );
+        assert_eq!(parse_client_user_agent("curl/8.5.0"), None);
+        assert_eq!(parse_client_user_agent("kintsu-web/1.0.0"), None);
+        assert_eq!(parse_client_user_agent("kintsu/"), None);
+        assert_eq!(parse_client_user_agent("kintsu/1.0;drop"), None);
+    }
+
+    #[test]
+    fn validates_request_ids() {
+        assert!(is_valid_request_id(&new_request_id()));
+        assert!(is_valid_request_id("ci-run:42.publish_1"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("has space"));
+        assert!(!is_valid_request_id("line\nbreak"));
+        assert!(!is_valid_request_id(&"a".repeat(129)));
+    }
+}
@@ -1,12 +1,15 @@
 use kintsu_manifests::config::NewForNamed;
-use kintsu_registry_db::entities::{OrgRoleType, Permission, SchemaRoleType, Scope};
+use kintsu_registry_db::entities::{OrgRoleType, Permission, SchemaRoleType, Scope, TeamRoleType};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
 
 use crate::PackagingError;
 
-pub use kintsu_registry_db::entities::{ApiKey, Org, Package, User, Version};
+pub use kintsu_registry_db::{
+    engine::{OneTimeApiKey, PublishPreview, PublishWarning, team::TeamMemberWithUser},
+    entities::{ApiKey, Org, OrgRole, Package, SchemaRole, Team, TeamMember, User, Version},
+};
 
 /// Response type for package download statistics
 #[derive(Serialize, ToSchema)]
@@ -75,51 +78,109 @@ pub struct PublishPackageRequest {
 
 impl PublishPackageRequest {
     pub fn validate_publishing_package_data(&self) -> std::result::Result<(), Vec<PackagingError>> {
-        if self.package_data.list_files().is_empty() {
-            return Err(vec![PackagingError::EmptyPackageData]);
-        }
-
-        let mut has_manifest = false;
-        let mut has_schema_lib = false;
-        let mut invalid_files = vec![];
-
-        self.package_data
-            .list_files()
-            .iter()
-            .for_each(|file| {
-                if let Some(name) = file.file_name()
-                    && let Some(ext) = file.extension()
-                {
-                    let name = name.to_string_lossy();
-                    let ext = ext.to_string_lossy();
-
-                    if name == kintsu_manifests::package::PackageManifests::NAME {
-                        has_manifest = true;
-                    } else if name == "lib.ks" {
-                        has_schema_lib = true;
-                    } else if ext != "ks" && ext != "toml" && ext != "md" && ext != "txt" {
-                        invalid_files.push(PackagingError::InvalidFile {
-                            path: format!("{}", file.display()),
-                            reason: format!("Invalid file extension: {}", ext),
-                        });
-                    }
-                }
-            });
+        validate_package_data(&self.package_data)
+    }
+}
+
+/// Checks the files of a package about to be published, shared by direct and staged publishes.
+pub fn validate_package_data(
+    package_data: &kintsu_fs::memory::MemoryFileSystem
+) -> std::result::Result<(), Vec<PackagingError>> {
+    if package_data.list_files().is_empty() {
+        return Err(vec![PackagingError::EmptyPackageData]);
+    }
+
+    let mut has_manifest = false;
+    let mut has_schema_lib = false;
+    let mut invalid_files = vec![];
 
-        if !invalid_files.is_empty() {
-            return Err(invalid_files);
-        }
+    package_data
+        .list_files()
+        .iter()
+        .for_each(|file| {
+            if let Some(name) = file.file_name()
+                && let Some(ext) = file.extension()
+            {
+                let name = name.to_string_lossy();
+                let ext = ext.to_string_lossy();
 
-        Ok(())
+                if name == kintsu_manifests::package::PackageManifests::NAME {
+                    has_manifest = true;
+                } else if name == "lib.ks" {
+                    has_schema_lib = true;
+                } else if ext != "ks" && ext != "toml" && ext != "md" && ext != "txt" {
+                    invalid_files.push(PackagingError::InvalidFile {
+                        path: format!("{}", file.display()),
+                        reason: format!("Invalid file extension: {}", ext),
+                    });
+                }
+            }
+        });
+
+    if !invalid_files.is_empty() {
+        return Err(invalid_files);
     }
+
+    Ok(())
+}
+
+/// First phase of a staged publish: asks for a URL the package source can be uploaded to
+/// directly, bypassing the registry API.
+#[derive(Serialize, Deserialize, ToSchema, validator::Validate)]
+pub struct PublishUploadRequest {
+    #[validate(length(min = 1))]
+    pub package: String,
+    #[validate(length(min = 1))]
+    pub version: String,
+    /// sha256 of the serialized package data which will be uploaded
+    #[validate(length(equal = 64))]
+    pub source_checksum: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PublishUploadResponse {
+    /// Presigned URL accepting a single `PUT` of the serialized package data as
+    /// `application/json`
+    pub url: String,
+    pub expires_at: kintsu_registry_db::DateTime,
+}
+
+/// Second phase of a staged publish: publishes the source uploaded under `source_checksum`.
+#[derive(Serialize, Deserialize, ToSchema, validator::Validate)]
+pub struct FinalizePublishRequest {
+    #[validate(nested)]
+    pub manifest: kintsu_manifests::package::PackageManifests,
+    #[validate(length(equal = 64))]
+    pub source_checksum: String,
+}
+
+/// README of a package version.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PackageReadme {
+    /// Markdown as published
+    pub markdown: String,
+    /// `markdown` rendered to HTML, with scripts, styles and event handlers removed
+    pub html: String,
 }
+
+/// Marks a package, or one version of it, deprecated.
+#[derive(Serialize, Deserialize, ToSchema, validator::Validate)]
+pub struct DeprecatePackageRequest {
+    /// Shown to everyone resolving the deprecated versions
+    #[validate(length(min = 1, max = 1024))]
+    pub message: String,
+    /// Name of the package to use instead
+    #[validate(length(min = 1))]
+    pub replacement: Option<String>,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct PublishPackageResponse {
     pub url: String,
     pub version: kintsu_registry_db::entities::Version,
 }
 
-#[derive(serde::Deserialize, Validate, ToSchema)]
+#[derive(serde::Serialize, serde::Deserialize, Validate, ToSchema)]
 #[validate(schema(function = "validate_grant_schema_role"))]
 pub struct GrantSchemaRoleRequest {
     #[validate(length(min = 1))]
@@ -127,44 +188,72 @@ pub struct GrantSchemaRoleRequest {
     pub role: SchemaRoleType,
     pub user_id: Option<i64>,
     pub org_id: Option<i64>,
+    /// Grant the role to every member of a team
+    pub team_id: Option<i64>,
 }
 
 fn validate_grant_schema_role(
     req: &GrantSchemaRoleRequest
 ) -> Result<(), validator::ValidationError> {
-    match (&req.user_id, &req.org_id) {
-        (Some(_), Some(_)) => {
-            let mut err = validator::ValidationError::new("exclusive_target");
-            err.message = Some("Cannot specify both user_id and org_id".into());
+    let targets = [req.user_id, req.org_id, req.team_id]
+        .iter()
+        .filter(|id| id.is_some())
+        .count();
+    match targets {
+        0 => {
+            let mut err = validator::ValidationError::new("missing_target");
+            err.message = Some("Must specify one of user_id, org_id or team_id".into());
             Err(err)
         },
-        (None, None) => {
-            let mut err = validator::ValidationError::new("missing_target");
-            err.message = Some("Must specify either user_id or org_id".into());
+        1 => Ok(()),
+        _ => {
+            let mut err = validator::ValidationError::new("exclusive_target");
+            err.message = Some("Cannot specify more than one of user_id, org_id or team_id".into());
             Err(err)
         },
-        _ => Ok(()),
     }
 }
 
-#[derive(serde::Deserialize, Validate, ToSchema)]
+#[derive(serde::Serialize, serde::Deserialize, Validate, ToSchema)]
 pub struct RevokeSchemaRoleRequest {
     pub role_id: i64,
 }
 
-#[derive(serde::Deserialize, Validate, ToSchema)]
+#[derive(serde::Serialize, serde::Deserialize, Validate, ToSchema)]
 pub struct GrantOrgRoleRequest {
     pub org_id: i64,
     pub user_id: i64,
     pub role: OrgRoleType,
 }
 
-#[derive(serde::Deserialize, Validate, ToSchema)]
+#[derive(serde::Serialize, serde::Deserialize, Validate, ToSchema)]
 pub struct RevokeOrgRoleRequest {
     pub org_id: i64,
     pub user_id: i64,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Validate, ToSchema)]
+pub struct CreateTeamRequest {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    #[validate(length(max = 1024))]
+    pub description: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Validate, ToSchema)]
+pub struct UpdateTeamRequest {
+    #[validate(length(min = 1, max = 64))]
+    pub name: Option<String>,
+    #[validate(length(max = 1024))]
+    pub description: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Validate, ToSchema)]
+pub struct AddTeamMemberRequest {
+    pub user_id: i64,
+    pub role: TeamRoleType,
+}
+
 /// Target for a user favourite (either package or org)
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 #[serde(tag = "type", content = "id", rename_all = "snake_case")]
@@ -1,6 +1,7 @@
 #!/bin/sh
 
-diesel migration redo
+cargo run -p kintsu-registry -- migrate down
+cargo run -p kintsu-registry -- migrate up
 
 rm -rf ./.stage/entities/*
 
@@ -0,0 +1,2 @@
+alter table version
+drop column metadata;
@@ -0,0 +1,5 @@
+-- free-form `[package.metadata.*]` tables from the published manifest
+alter table version
+add column metadata jsonb not null default '{}';
+
+comment on column version.metadata is 'Custom package metadata from the manifest. Not interpreted by the registry.';
@@ -0,0 +1,5 @@
+drop table download_detail;
+
+drop type download_origin;
+
+drop type download_artifact;
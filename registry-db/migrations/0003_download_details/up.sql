@@ -0,0 +1,23 @@
+create type download_artifact as enum ('source', 'declarations');
+
+create type download_origin as enum ('resolver', 'human');
+
+-- per-day download counts split by artifact, client and origin. `downloads` keeps the
+-- version-level totals.
+create table download_detail (
+    version bigint not null references version (id),
+    day date not null,
+    artifact download_artifact not null,
+    client_version text not null default '',
+    origin download_origin not null,
+    count int not null default '0',
+    primary key (version, day, artifact, client_version, origin)
+);
+
+create index download_detail_day_idx on download_detail (day);
+
+comment on table download_detail is 'Daily download counts of a version, broken down by artifact type, client compiler version and origin.';
+
+comment on column download_detail.client_version is 'Compiler version from a kintsu User-Agent. Empty when the client is not kintsu tooling.';
+
+comment on column download_detail.origin is 'resolver for kintsu tooling resolving dependencies, human for any other client.';
@@ -0,0 +1,14 @@
+-- one row per download, appended without contention on the daily buckets. rows are
+-- periodically compacted into `downloads` and `download_detail` and then removed.
+create table download_log (
+    id bigserial primary key,
+    version bigint not null references version (id),
+    at timestamptz not null default now(),
+    artifact download_artifact not null,
+    client_version text not null default '',
+    origin download_origin not null
+);
+
+create index download_log_at_idx on download_log (at);
+
+comment on table download_log is 'Raw downloads which have not yet been compacted into the daily download tables.';
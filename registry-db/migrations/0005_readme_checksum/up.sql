@@ -0,0 +1,6 @@
+-- readmes are also written to storage next to the source. versions published before this
+-- migration only have the copy in `version.readme`.
+alter table version
+add column readme_checksum text;
+
+comment on column version.readme_checksum is 'Checksum of the stored readme, or null when it was never written to storage.';
@@ -0,0 +1,9 @@
+alter table version
+drop column deprecated_at,
+drop column deprecation_message,
+drop column deprecation_replacement;
+
+alter table package
+drop column deprecated_at,
+drop column deprecation_message,
+drop column deprecation_replacement;
@@ -0,0 +1,15 @@
+-- owners can deprecate a whole package or a single version. a version is deprecated when
+-- either it or its package is; deprecated versions still resolve, clients only warn.
+alter table package
+add column deprecated_at timestamptz,
+add column deprecation_message text,
+add column deprecation_replacement text;
+
+alter table version
+add column deprecated_at timestamptz,
+add column deprecation_message text,
+add column deprecation_replacement text;
+
+comment on column package.deprecation_replacement is 'Name of the package to use instead, if any.';
+
+comment on column version.deprecation_replacement is 'Name of the package to use instead, if any.';
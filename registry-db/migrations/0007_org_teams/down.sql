@@ -0,0 +1,38 @@
+drop index schema_team_roles_idx;
+
+delete from schema_role
+where
+    team_id is not null;
+
+alter table schema_role
+drop constraint schema_role_check;
+
+alter table schema_role
+add constraint schema_role_check check (
+    (
+        user_id is null
+        and org_id is not null
+    )
+    or (
+        user_id is not null
+        and org_id is null
+    )
+);
+
+alter table schema_role
+drop column team_id;
+
+drop table team_member;
+
+drop table team;
+
+drop type team_role_type;
+
+-- postgres cannot drop values from an enum, so manage-team and manage-team-members remain
+-- on the permission type. keys holding them are stripped of the now unknown permissions.
+update api_key
+set
+    permissions = array_remove(
+        array_remove(permissions, 'manage-team'),
+        'manage-team-members'
+    );
@@ -0,0 +1,42 @@
+-- teams group org members so package roles can be granted to many users at once. members
+-- of a team inherit its schema roles for as long as they remain active members of the org.
+create type team_role_type as enum ('maintainer', 'member');
+
+alter type permission add value if not exists 'manage-team';
+
+alter type permission add value if not exists 'manage-team-members';
+
+create table team (
+    id bigserial primary key,
+    org_id bigint not null references org(id) on delete cascade,
+    name varchar(64) not null,
+    description varchar(1024),
+    created_at timestamptz not null default now()
+);
+
+create unique index team_org_name_idx on team(org_id, name);
+
+create table team_member (
+    team_id bigint not null references team(id) on delete cascade,
+    user_id bigint not null references users(id),
+    role team_role_type not null,
+    created_at timestamptz not null default now(),
+    primary key (team_id, user_id)
+);
+
+create index team_member_user_idx on team_member(user_id);
+
+alter table schema_role
+add column team_id bigint references team(id) on delete cascade;
+
+alter table schema_role
+drop constraint schema_role_check;
+
+alter table schema_role
+add constraint schema_role_check check (num_nonnulls(user_id, org_id, team_id) = 1);
+
+create unique index schema_team_roles_idx on schema_role(package, team_id)
+where
+    team_id is not null;
+
+comment on column schema_role.team_id is 'Team whose members inherit this role.';
@@ -0,0 +1,12 @@
+-- users without a github account cannot be represented without an identity table
+delete from users
+where
+    gh_id is null;
+
+alter table users
+alter column gh_id
+set not null;
+
+comment on table users is 'A user represents an individual account with access to the registry (only github for now).';
+
+drop table user_identity;
@@ -0,0 +1,26 @@
+-- users log in through an identity provider (github, or an OIDC issuer) and are known to it
+-- by a subject. gh_id is only kept for github accounts, which orgs are imported through.
+create table user_identity (
+    provider varchar(64) not null,
+    subject varchar(255) not null,
+    user_id bigint not null references users(id) on delete cascade,
+    created_at timestamptz not null default now(),
+    primary key (provider, subject)
+);
+
+create index user_identity_user_idx on user_identity(user_id);
+
+insert into
+    user_identity (provider, subject, user_id)
+select
+    'github',
+    gh_id::text,
+    id
+from
+    users;
+
+alter table users
+alter column gh_id
+drop not null;
+
+comment on table users is 'A user represents an individual account with access to the registry, logged in through one or more identity providers.';
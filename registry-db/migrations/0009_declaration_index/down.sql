@@ -0,0 +1 @@
+drop table declaration_item;
@@ -0,0 +1,12 @@
+-- the items a version declares, indexed when it is published so single types are queried
+-- without loading the whole declarations artifact.
+create table declaration_item (
+    version_id bigint not null references version(id) on delete cascade,
+    namespace varchar not null,
+    name varchar not null,
+    kind varchar(16) not null,
+    declaration jsonb not null,
+    primary key (version_id, namespace, name)
+);
+
+comment on table declaration_item is 'A type or constant declared by a published version, keyed by its namespace and name.';
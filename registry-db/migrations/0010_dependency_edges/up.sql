@@ -0,0 +1,21 @@
+-- one row per version a version depends on, so dependents are found through an index rather
+-- than by scanning every version's dependencies array.
+create table version_dependency (
+    version_id bigint not null references version(id) on delete cascade,
+    dependency_id bigint not null references version(id) on delete cascade,
+    primary key (version_id, dependency_id)
+);
+
+comment on table version_dependency is 'A direct dependency of a published version on another version.';
+
+create index version_dependency_dependency_idx on version_dependency(dependency_id);
+
+insert into
+    version_dependency (version_id, dependency_id)
+select distinct
+    v.id,
+    dep_id
+from
+    version v
+    cross join lateral unnest(v.dependencies) as dep_id
+    join version d on d.id = dep_id;
@@ -7,7 +7,7 @@ use sea_orm::{
 // Re-export local wrapper types from events module for use throughout engine
 pub use super::events::{
     OrgResource, OrgRoleResource, PackageResource, ResourceIdentifier, SchemaRoleResource,
-    TokenResource,
+    TeamResource, TokenResource,
 };
 
 #[allow(async_fn_in_trait)]
@@ -95,6 +95,32 @@ impl Authorize for PackageResource {
                             checks,
                         ));
                     }
+                } else if let Some(scope) = kintsu_manifests::package::package_scope(&self.name) {
+                    let owns_scope = self
+                        .check_scope_owner(db, principal, scope)
+                        .await?;
+
+                    checks.push(PolicyCheck {
+                        policy: Policy::ScopeOwner,
+                        passed: owns_scope,
+                        details: format!("Principal is admin of org {scope}, which owns @{scope}"),
+                    });
+
+                    if !owns_scope {
+                        return Ok(AuthorizationResult::deny(
+                            format!("Not admin of org {scope}, which owns @{scope}"),
+                            checks,
+                        ));
+                    }
+
+                    checks.push(PolicyCheck {
+                        policy: Policy::FirstPublish,
+                        passed: true,
+                        details: format!(
+                            "First publish of package {}, org {scope} will become admin",
+                            self.name
+                        ),
+                    });
                 } else {
                     checks.push(PolicyCheck {
                         policy: Policy::FirstPublish,
@@ -157,6 +183,23 @@ impl Authorize for PackageResource {
 }
 
 impl PackageResource {
+    /// Scoped packages are created by admins of the org the scope is named after, and nobody
+    /// may create packages in the scope of an org which does not exist.
+    async fn check_scope_owner<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        scope: &str,
+    ) -> Result<bool> {
+        let Some(org) = Org::by_name(db, scope).await? else {
+            return Ok(false);
+        };
+
+        OrgResource { id: org.id }
+            .check_org_admin(db, principal)
+            .await
+    }
+
     async fn check_schema_admin<C: ConnectionTrait>(
         &self,
         db: &C,
@@ -185,7 +228,8 @@ impl PackageResource {
                                 .select_only()
                                 .column(OrgRoleColumn::OrgId)
                                 .into_query(),
-                        )),
+                        ))
+                        .or(SchemaRoleColumn::TeamId.in_subquery(active_team_ids(user.id, None))),
                 )
                 .count(db)
                 .await?
@@ -195,9 +239,21 @@ impl PackageResource {
         }
 
         if let Some(org) = principal.org() {
+            use sea_orm::QuerySelect;
+
             let org_is_admin = SchemaRoleEntity::find()
                 .filter(SchemaRoleColumn::Package.eq(pkg_id))
-                .filter(SchemaRoleColumn::OrgId.eq(org.id))
+                .filter(
+                    SchemaRoleColumn::OrgId
+                        .eq(org.id)
+                        .or(SchemaRoleColumn::TeamId.in_subquery(
+                            TeamEntity::find()
+                                .filter(TeamColumn::OrgId.eq(org.id))
+                                .select_only()
+                                .column(TeamColumn::Id)
+                                .into_query(),
+                        )),
+                )
                 .filter(SchemaRoleColumn::Role.eq(SchemaRoleType::Admin))
                 .filter(SchemaRoleColumn::RevokedAt.is_null())
                 .count(db)
@@ -296,6 +352,143 @@ impl OrgResource {
     }
 }
 
+/// Teams the user belongs to, optionally only those where they hold `role`. Membership
+/// lapses while the user is not an active member of the team's org.
+fn active_team_ids(
+    user_id: i64,
+    role: Option<TeamRoleType>,
+) -> sea_orm::sea_query::SelectStatement {
+    use sea_orm::QuerySelect;
+
+    let mut query = TeamMemberEntity::find()
+        .inner_join(TeamEntity)
+        .filter(TeamMemberColumn::UserId.eq(user_id))
+        .filter(
+            TeamColumn::OrgId.in_subquery(
+                OrgRoleEntity::find()
+                    .filter(OrgRoleColumn::UserId.eq(user_id))
+                    .filter(OrgRoleColumn::RevokedAt.is_null())
+                    .select_only()
+                    .column(OrgRoleColumn::OrgId)
+                    .into_query(),
+            ),
+        );
+
+    if let Some(role) = role {
+        query = query.filter(TeamMemberColumn::Role.eq(role));
+    }
+
+    query
+        .select_only()
+        .column(TeamMemberColumn::TeamId)
+        .into_query()
+}
+
+impl Authorize for TeamResource {
+    async fn authorize<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        permission: Permission,
+    ) -> Result<AuthorizationResult> {
+        let mut checks = Vec::new();
+
+        match permission {
+            Permission::ManageTeam | Permission::ManageTeamMembers => {
+                if let Some(api_key) = principal.api_key() {
+                    let has_permission = api_key.permissions.contains(&permission);
+                    checks.push(PolicyCheck {
+                        policy: Policy::ExplicitPermission,
+                        passed: has_permission,
+                        details: format!("API key has {:?} permission", permission),
+                    });
+
+                    if !has_permission {
+                        return Ok(AuthorizationResult::deny(
+                            format!("API key missing {:?} permission", permission),
+                            checks,
+                        ));
+                    }
+                }
+
+                let is_admin = OrgResource { id: self.org_id }
+                    .check_org_admin(db, principal)
+                    .await?;
+                checks.push(PolicyCheck {
+                    policy: Policy::OrgAdmin,
+                    passed: is_admin,
+                    details: format!("Principal is admin of org {}", self.org_id),
+                });
+
+                if is_admin {
+                    return Ok(AuthorizationResult::allow("All checks passed", checks));
+                }
+
+                // maintainers may manage who is on their team, but not the team itself
+                if let (Permission::ManageTeamMembers, Some(team_id)) = (&permission, self.team_id)
+                {
+                    let is_maintainer = self
+                        .check_team_maintainer(db, principal, team_id)
+                        .await?;
+                    checks.push(PolicyCheck {
+                        policy: Policy::TeamMaintainer,
+                        passed: is_maintainer,
+                        details: format!("Principal is maintainer of team {}", team_id),
+                    });
+
+                    if is_maintainer {
+                        return Ok(AuthorizationResult::allow("All checks passed", checks));
+                    }
+
+                    return Ok(AuthorizationResult::deny(
+                        format!(
+                            "Not admin of organization {} or maintainer of team {}",
+                            self.org_id, team_id
+                        ),
+                        checks,
+                    ));
+                }
+
+                Ok(AuthorizationResult::deny(
+                    format!("Not admin of organization {}", self.org_id),
+                    checks,
+                ))
+            },
+
+            _ => {
+                Ok(AuthorizationResult::not_applicable(
+                    &format!("{:?}", permission),
+                    "TeamResource",
+                ))
+            },
+        }
+    }
+}
+
+impl TeamResource {
+    async fn check_team_maintainer<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        team_id: i64,
+    ) -> Result<bool> {
+        let Some(user) = principal.user() else {
+            return Ok(false);
+        };
+
+        Ok(TeamEntity::find()
+            .filter(TeamColumn::Id.eq(team_id))
+            .filter(TeamColumn::OrgId.eq(self.org_id))
+            .filter(
+                TeamColumn::Id
+                    .in_subquery(active_team_ids(user.id, Some(TeamRoleType::Maintainer))),
+            )
+            .count(db)
+            .await?
+            > 0)
+    }
+}
+
 impl Authorize for TokenResource {
     async fn authorize<C: ConnectionTrait>(
         &self,
@@ -0,0 +1,139 @@
+use crate::{Error, PackageStorage, Result, entities::*};
+use kintsu_parser::declare::{
+    DeclarationVersion,
+    index::{DeclaredItem, index},
+};
+use sea_orm::{
+    ColumnTrait, EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, sea_query::OnConflict,
+};
+
+/// An item a version declares, listed without its declaration.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema, FromQueryResult)]
+pub struct DeclaredItemSummary {
+    /// Qualified name of the declaring namespace, e.g. `abc::v1`
+    pub namespace: String,
+    pub name: String,
+    /// What the item declares, e.g. `struct` or `constant`
+    pub kind: String,
+}
+
+impl DeclarationItem {
+    /// Stores the items `declarations` declare as those of `version_id`. Items already indexed
+    /// are kept, so indexing a version twice is harmless.
+    pub async fn index<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        version_id: i64,
+        declarations: &DeclarationVersion,
+    ) -> Result<()> {
+        let items = index(declarations)
+            .into_iter()
+            .map(|indexed| {
+                Ok(DeclarationItemActiveModel {
+                    version_id: Set(version_id),
+                    namespace: Set(indexed.namespace),
+                    name: Set(indexed.item.name().to_string()),
+                    kind: Set(indexed.item.kind().to_string()),
+                    declaration: Set(serde_json::to_value(&indexed.item)?),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        DeclarationItemEntity::insert_many(items)
+            .on_conflict(
+                OnConflict::columns([
+                    DeclarationItemColumn::VersionId,
+                    DeclarationItemColumn::Namespace,
+                    DeclarationItemColumn::Name,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .do_nothing()
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Every item `version` declares, ordered by namespace and name.
+    pub async fn list<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        storage: &PackageStorage,
+        package_name: &str,
+        version: &Version,
+    ) -> Result<Vec<DeclaredItemSummary>> {
+        Self::backfill(db, storage, package_name, version).await?;
+
+        Ok(DeclarationItemEntity::find()
+            .select_only()
+            .columns([
+                DeclarationItemColumn::Namespace,
+                DeclarationItemColumn::Name,
+                DeclarationItemColumn::Kind,
+            ])
+            .filter(DeclarationItemColumn::VersionId.eq(version.id))
+            .order_by_asc(DeclarationItemColumn::Namespace)
+            .order_by_asc(DeclarationItemColumn::Name)
+            .into_model::<DeclaredItemSummary>()
+            .all(db)
+            .await?)
+    }
+
+    /// The item `version` declares as `name` in `namespace`.
+    pub async fn get<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        storage: &PackageStorage,
+        package_name: &str,
+        version: &Version,
+        namespace: &str,
+        name: &str,
+    ) -> Result<DeclaredItem> {
+        Self::backfill(db, storage, package_name, version).await?;
+
+        let item = DeclarationItemEntity::find_by_id((
+            version.id,
+            namespace.to_string(),
+            name.to_string(),
+        ))
+        .one(db)
+        .await?
+        .ok_or_else(|| {
+            Error::NotFound(format!(
+                "'{namespace}::{name}' in version '{}' of package '{package_name}'",
+                version.qualified_version
+            ))
+        })?;
+
+        Ok(serde_json::from_value(item.declaration)?)
+    }
+
+    /// Versions published before declarations were indexed are indexed from their stored
+    /// declarations the first time they are queried.
+    async fn backfill<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        storage: &PackageStorage,
+        package_name: &str,
+        version: &Version,
+    ) -> Result<()> {
+        let indexed = DeclarationItemEntity::find()
+            .filter(DeclarationItemColumn::VersionId.eq(version.id))
+            .count(db)
+            .await?;
+        if indexed > 0 {
+            return Ok(());
+        }
+
+        let declarations = storage
+            .get_declarations(
+                &storage
+                    .path_for_declarations(package_name, &version.qualified_version.to_string()),
+                version.declarations_checksum.clone().into(),
+            )
+            .await?;
+        Self::index(db, version.id, &declarations).await
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{Result, entities::*};
+use sea_orm::{EntityTrait, FromQueryResult, Set, sea_query::OnConflict};
+
+/// Versions reachable from a root version and the dependencies between them, shaped for
+/// rendering as a graph.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct DependencyGraph {
+    /// Id of the version the graph was resolved from
+    pub root: i64,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema, FromQueryResult)]
+pub struct GraphNode {
+    /// Version id, referenced by edges
+    pub id: i64,
+    pub package: String,
+    pub version: String,
+    pub yanked: bool,
+    /// Set when the version or its package is deprecated
+    pub deprecated: bool,
+}
+
+/// A dependency of the version `from` on the version `to`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema, FromQueryResult)]
+pub struct GraphEdge {
+    pub from: i64,
+    pub to: i64,
+}
+
+/// Which way a graph is walked from its root.
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    /// The versions the root depends on, transitively
+    Dependencies,
+    /// The versions depending on the root, transitively
+    Dependents,
+}
+
+impl Direction {
+    /// Columns of `version_dependency` a walk follows, from the version reached so far to the
+    /// next one.
+    fn columns(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Dependencies => ("version_id", "dependency_id"),
+            Self::Dependents => ("dependency_id", "version_id"),
+        }
+    }
+
+    fn reachable(self) -> String {
+        let (near, far) = self.columns();
+        format!(
+            "with recursive reachable(id) as (
+                select $1::bigint
+                union
+                select e.{far} from version_dependency e join reachable r on e.{near} = r.id
+            )"
+        )
+    }
+}
+
+impl Version {
+    /// Records the versions `version_id` depends on, which dependents and graphs are resolved
+    /// through.
+    pub async fn index_dependencies<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        version_id: i64,
+        dependencies: &[i64],
+    ) -> Result<()> {
+        if dependencies.is_empty() {
+            return Ok(());
+        }
+
+        VersionDependencyEntity::insert_many(dependencies.iter().map(|dependency_id| {
+            VersionDependencyActiveModel {
+                version_id: Set(version_id),
+                dependency_id: Set(*dependency_id),
+            }
+        }))
+        .on_conflict(
+            OnConflict::columns([
+                VersionDependencyColumn::VersionId,
+                VersionDependencyColumn::DependencyId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .do_nothing()
+        .exec(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Every version this depends on, directly or through other dependencies.
+    pub async fn dependency_graph<C: sea_orm::ConnectionTrait>(
+        &self,
+        db: &C,
+    ) -> Result<DependencyGraph> {
+        Self::graph(db, self.id, Direction::Dependencies).await
+    }
+
+    /// Every version depending on this, directly or through other dependents.
+    pub async fn dependent_graph<C: sea_orm::ConnectionTrait>(
+        &self,
+        db: &C,
+    ) -> Result<DependencyGraph> {
+        Self::graph(db, self.id, Direction::Dependents).await
+    }
+
+    async fn graph<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        root: i64,
+        direction: Direction,
+    ) -> Result<DependencyGraph> {
+        let reachable = direction.reachable();
+        let (near, _) = direction.columns();
+
+        let nodes = GraphNode::find_by_statement(sea_orm::Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            format!(
+                "{reachable}
+                select
+                    v.id,
+                    p.name as package,
+                    v.qualified_version as version,
+                    v.yanked_at is not null as yanked,
+                    (v.deprecated_at is not null or p.deprecated_at is not null) as deprecated
+                from
+                    reachable r
+                    inner join version v on v.id = r.id
+                    inner join package p on p.id = v.package
+                order by
+                    p.name,
+                    v.id"
+            ),
+            [root.into()],
+        ))
+        .all(db)
+        .await?;
+
+        let edges = GraphEdge::find_by_statement(sea_orm::Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            format!(
+                r#"{reachable}
+                select
+                    e.version_id as "from",
+                    e.dependency_id as "to"
+                from
+                    version_dependency e
+                    inner join reachable r on e.{near} = r.id
+                order by
+                    e.version_id,
+                    e.dependency_id"#
+            ),
+            [root.into()],
+        ))
+        .all(db)
+        .await?;
+
+        Ok(DependencyGraph { root, nodes, edges })
+    }
+}
@@ -1,5 +1,5 @@
-use crate::entities::Permission;
-use kintsu_registry_auth::AuditPermission;
+use crate::entities::{OrgRoleType, Permission};
+use kintsu_registry_auth::{AuditOrgRole, AuditPermission};
 use std::ops::Deref;
 
 /// Convert from sea-orm Permission to audit-safe AuditPermission
@@ -17,6 +17,17 @@ impl From<Permission> for AuditPermission {
             Permission::ListOrgToken => AuditPermission::ListOrgToken,
             Permission::CreatePersonalToken => AuditPermission::CreatePersonalToken,
             Permission::RevokePersonalToken => AuditPermission::RevokePersonalToken,
+            Permission::ManageTeam => AuditPermission::ManageTeam,
+            Permission::ManageTeamMembers => AuditPermission::ManageTeamMembers,
+        }
+    }
+}
+
+impl From<OrgRoleType> for AuditOrgRole {
+    fn from(role: OrgRoleType) -> Self {
+        match role {
+            OrgRoleType::Admin => AuditOrgRole::Admin,
+            OrgRoleType::Member => AuditOrgRole::Member,
         }
     }
 }
@@ -143,6 +154,36 @@ impl From<SchemaRoleResource> for kintsu_registry_auth::SchemaRoleResource {
     }
 }
 
+/// Wrapper for team resource identifier
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TeamResource {
+    pub org_id: i64,
+    pub team_id: Option<i64>,
+}
+
+impl Deref for TeamResource {
+    type Target = kintsu_registry_auth::TeamResource;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self as *const Self as *const Self::Target) }
+    }
+}
+
+impl AsRef<kintsu_registry_auth::TeamResource> for TeamResource {
+    fn as_ref(&self) -> &kintsu_registry_auth::TeamResource {
+        self.deref()
+    }
+}
+
+impl From<TeamResource> for kintsu_registry_auth::TeamResource {
+    fn from(r: TeamResource) -> Self {
+        kintsu_registry_auth::TeamResource {
+            org_id: r.org_id,
+            team_id: r.team_id,
+        }
+    }
+}
+
 /// Wrapper for token resource identifier
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct TokenResource {
@@ -191,6 +232,7 @@ pub enum ResourceIdentifier {
     Token(TokenResource),
     SchemaRole(SchemaRoleResource),
     OrgRole(OrgRoleResource),
+    Team(TeamResource),
 }
 
 impl From<ResourceIdentifier> for kintsu_registry_auth::ResourceIdentifier {
@@ -211,6 +253,7 @@ impl From<ResourceIdentifier> for kintsu_registry_auth::ResourceIdentifier {
             ResourceIdentifier::OrgRole(o) => {
                 kintsu_registry_auth::ResourceIdentifier::OrgRole(o.into())
             },
+            ResourceIdentifier::Team(t) => kintsu_registry_auth::ResourceIdentifier::Team(t.into()),
         }
     }
 }
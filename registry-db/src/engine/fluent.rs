@@ -1,5 +1,5 @@
 use super::{
-    authorization::{Authorize, OrgResource, PackageResource, TokenResource},
+    authorization::{Authorize, OrgResource, PackageResource, TeamResource, TokenResource},
     principal::PrincipalIdentity,
 };
 use crate::{Result, engine::OwnerId, entities::Permission};
@@ -45,6 +45,19 @@ impl<'a, C: ConnectionTrait> AuthCheck<'a, C> {
         }
     }
 
+    /// A team of `org_id`, or the org's teams as a whole when `team_id` is `None`.
+    pub fn team(
+        self,
+        org_id: i64,
+        team_id: Option<i64>,
+    ) -> TeamAuthCheck<'a, C> {
+        TeamAuthCheck {
+            db: self.db,
+            principal: self.principal,
+            resource: TeamResource { org_id, team_id },
+        }
+    }
+
     pub fn token(
         self,
         id: i64,
@@ -128,6 +141,26 @@ impl<'a, C: ConnectionTrait> OrgAuthCheck<'a, C> {
     }
 }
 
+pub struct TeamAuthCheck<'a, C: ConnectionTrait> {
+    db: &'a C,
+    principal: &'a PrincipalIdentity,
+    resource: TeamResource,
+}
+
+impl<'a, C: ConnectionTrait> TeamAuthCheck<'a, C> {
+    pub async fn can_manage(&self) -> Result<AuthorizationResult> {
+        self.resource
+            .authorize(self.db, self.principal, Permission::ManageTeam)
+            .await
+    }
+
+    pub async fn can_manage_members(&self) -> Result<AuthorizationResult> {
+        self.resource
+            .authorize(self.db, self.principal, Permission::ManageTeamMembers)
+            .await
+    }
+}
+
 pub struct TokenAuthCheck<'a, C: ConnectionTrait> {
     db: &'a C,
     principal: &'a PrincipalIdentity,
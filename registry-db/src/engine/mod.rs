@@ -1,19 +1,25 @@
 pub mod api_key;
 pub mod authorization;
+pub mod declaration;
+pub mod dependency_graph;
 pub mod events;
 pub mod favourites;
 pub mod fluent;
 pub mod org;
 pub mod org_invite;
+pub mod org_sync;
 pub mod package;
 pub mod principal;
 pub mod schema_admin;
 pub mod schema_role;
+pub mod team;
 pub mod user;
 pub mod version;
 
 pub use api_key::*;
 pub use authorization::*;
+pub use declaration::*;
+pub use dependency_graph::*;
 pub use events::*;
 pub use favourites::*;
 pub use fluent::*;
@@ -69,10 +75,10 @@ impl Entity {
         }
     }
 
-    pub fn gh_id(&self) -> i32 {
+    pub fn gh_id(&self) -> Option<i32> {
         match self {
             Entity::User(user) => user.gh_id,
-            Entity::Org(org) => org.gh_id,
+            Entity::Org(org) => Some(org.gh_id),
         }
     }
 
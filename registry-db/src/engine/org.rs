@@ -23,6 +23,14 @@ impl Org {
             .map_err(Into::into)
     }
 
+    pub async fn all<C: sea_orm::ConnectionTrait>(db: &C) -> Result<Vec<Self>> {
+        OrgEntity::find()
+            .order_by_asc(OrgColumn::Id)
+            .all(db)
+            .await
+            .map_err(Into::into)
+    }
+
     pub async fn by_name<C: sea_orm::ConnectionTrait>(
         db: &C,
         org_name: &str,
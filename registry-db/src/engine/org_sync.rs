@@ -0,0 +1,329 @@
+use crate::{Error, Result, entities::*};
+use kintsu_registry_auth::{AuditEvent, AuditEventType, OrgSyncChange, PrincipalType};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, NotSet, QueryFilter, Set, TransactionTrait,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// A member of an organization as listed by GitHub.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GhOrgMember {
+    pub gh_id: i32,
+    pub role: OrgRoleType,
+}
+
+/// A change made to an org membership by [`sync_members`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(tag = "change", rename_all = "kebab-case")]
+pub enum MembershipChange {
+    Joined {
+        user_id: i64,
+        role: OrgRoleType,
+    },
+    RoleChanged {
+        user_id: i64,
+        from: OrgRoleType,
+        to: OrgRoleType,
+    },
+    Left {
+        user_id: i64,
+    },
+}
+
+impl MembershipChange {
+    fn audit_event(
+        &self,
+        org_id: i64,
+        actor: Option<&super::principal::PrincipalIdentity>,
+    ) -> AuditEvent {
+        let (user_id, change, role) = match self {
+            Self::Joined { user_id, role } => {
+                (*user_id, OrgSyncChange::Joined, Some(role.clone().into()))
+            },
+            Self::RoleChanged { user_id, to, .. } => {
+                (
+                    *user_id,
+                    OrgSyncChange::RoleChanged,
+                    Some(to.clone().into()),
+                )
+            },
+            Self::Left { user_id } => (*user_id, OrgSyncChange::Left, None),
+        };
+
+        let (principal_type, principal_id) = match actor {
+            Some(actor) => (actor.principal_type(), actor.principal_id()),
+            None => (PrincipalType::System, 0),
+        };
+
+        AuditEvent::builder()
+            .timestamp(chrono::Utc::now())
+            .principal_type(principal_type)
+            .principal_id(principal_id)
+            .event_type(AuditEventType::OrgMembershipSynced {
+                org_id,
+                user_id,
+                change,
+                role,
+            })
+            .allowed(true)
+            .reason("Synced from GitHub".to_string())
+            .policy_checks(vec![])
+            .build()
+    }
+}
+
+/// Works out the changes which bring the active `roles` of an org in line with `wanted`, the
+/// registry users who are members at GitHub. Only users with a GitHub account leave, so members
+/// added by other means are kept.
+fn plan(
+    roles: &[OrgRole],
+    wanted: &BTreeMap<i64, OrgRoleType>,
+    on_github: impl Fn(i64) -> bool,
+) -> Vec<MembershipChange> {
+    let active: HashMap<i64, &OrgRole> = roles
+        .iter()
+        .filter(|role| role.revoked_at.is_none())
+        .map(|role| (role.user_id, role))
+        .collect();
+
+    let mut changes: Vec<_> = wanted
+        .iter()
+        .filter_map(|(&user_id, role)| {
+            match active.get(&user_id) {
+                Some(current) if current.role == *role => None,
+                Some(current) => {
+                    Some(MembershipChange::RoleChanged {
+                        user_id,
+                        from: current.role.clone(),
+                        to: role.clone(),
+                    })
+                },
+                None => {
+                    Some(MembershipChange::Joined {
+                        user_id,
+                        role: role.clone(),
+                    })
+                },
+            }
+        })
+        .collect();
+
+    let mut left: Vec<_> = active
+        .keys()
+        .copied()
+        .filter(|user_id| !wanted.contains_key(user_id) && on_github(*user_id))
+        .collect();
+    left.sort_unstable();
+    changes.extend(
+        left.into_iter()
+            .map(|user_id| MembershipChange::Left { user_id }),
+    );
+
+    changes
+}
+
+/// Checks `principal` may sync the members of an org.
+pub async fn authorize_sync<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    principal: &super::principal::PrincipalIdentity,
+    org_id: i64,
+) -> Result<()> {
+    let auth_result = super::fluent::AuthCheck::new(db, principal)
+        .org(org_id)
+        .can_grant_role()
+        .await?;
+
+    let event = principal.audit_event(
+        AuditEventType::PermissionProtected {
+            permission: Permission::GrantOrgRole.into(),
+            resource: super::authorization::ResourceIdentifier::Organization(
+                super::authorization::OrgResource { id: org_id },
+            )
+            .into(),
+        },
+        &auth_result,
+    );
+    kintsu_registry_events::emit_event(event)?;
+
+    Ok(auth_result.require()?)
+}
+
+/// Brings the roles of an org in line with its `members` at GitHub: members with a registry
+/// account gain their role, and users with a GitHub account who are no longer members lose
+/// theirs. Every change is audited against `actor`, or the registry itself when `None`.
+///
+/// This does not authorize `actor`, see [`authorize_sync`].
+pub async fn sync_members<C: sea_orm::ConnectionTrait + TransactionTrait>(
+    db: &C,
+    actor: Option<&super::principal::PrincipalIdentity>,
+    org_id: i64,
+    members: &[GhOrgMember],
+) -> Result<Vec<MembershipChange>> {
+    // an org always has an owner, so no members means GitHub did not answer in full
+    if members.is_empty() {
+        return Err(Error::Validation(format!(
+            "GitHub listed no members for organization {org_id}, refusing to remove everyone"
+        )));
+    }
+
+    let members = members.to_vec();
+    let changes = db
+        .transaction::<_, Vec<MembershipChange>, Error>(|txn| {
+            Box::pin(async move {
+                let roles = OrgRoleEntity::find()
+                    .filter(OrgRoleColumn::OrgId.eq(org_id))
+                    .all(txn)
+                    .await?;
+
+                let member_users = UserEntity::find()
+                    .filter(UserColumn::GhId.is_in(members.iter().map(|m| m.gh_id)))
+                    .all(txn)
+                    .await?;
+                let user_by_gh_id: HashMap<i32, i64> = member_users
+                    .iter()
+                    .filter_map(|user| user.gh_id.map(|gh_id| (gh_id, user.id)))
+                    .collect();
+
+                let wanted: BTreeMap<i64, OrgRoleType> = members
+                    .iter()
+                    .filter_map(|member| {
+                        user_by_gh_id
+                            .get(&member.gh_id)
+                            .map(|&user_id| (user_id, member.role.clone()))
+                    })
+                    .collect();
+
+                let holders = UserEntity::find()
+                    .filter(UserColumn::Id.is_in(roles.iter().map(|role| role.user_id)))
+                    .filter(UserColumn::GhId.is_not_null())
+                    .all(txn)
+                    .await?;
+                let holders_on_github: Vec<i64> = holders.iter().map(|user| user.id).collect();
+
+                let changes = plan(&roles, &wanted, |user_id| {
+                    holders_on_github.contains(&user_id)
+                });
+
+                for change in &changes {
+                    let existing = match change {
+                        MembershipChange::Joined { user_id, .. }
+                        | MembershipChange::RoleChanged { user_id, .. }
+                        | MembershipChange::Left { user_id } => {
+                            roles
+                                .iter()
+                                .find(|role| role.user_id == *user_id)
+                        },
+                    };
+
+                    match (change, existing) {
+                        (MembershipChange::Joined { user_id, role }, None) => {
+                            OrgRoleActiveModel {
+                                org_id: Set(org_id),
+                                user_id: Set(*user_id),
+                                role: Set(role.clone()),
+                                revoked_at: NotSet,
+                            }
+                            .insert(txn)
+                            .await?;
+                        },
+                        // rejoining restores the revoked row, which holds the primary key
+                        (MembershipChange::Joined { role, .. }, Some(existing))
+                        | (MembershipChange::RoleChanged { to: role, .. }, Some(existing)) => {
+                            let mut active_model: OrgRoleActiveModel = existing.clone().into();
+                            active_model.role = Set(role.clone());
+                            active_model.revoked_at = Set(None);
+                            active_model.update(txn).await?;
+                        },
+                        (MembershipChange::Left { .. }, Some(existing)) => {
+                            let mut active_model: OrgRoleActiveModel = existing.clone().into();
+                            active_model.revoked_at = Set(Some(chrono::Utc::now()));
+                            active_model.update(txn).await?;
+                        },
+                        (
+                            MembershipChange::RoleChanged { .. } | MembershipChange::Left { .. },
+                            None,
+                        ) => {
+                            return Err(Error::Internal(
+                                "Planned a change to a missing org role".into(),
+                            ));
+                        },
+                    }
+                }
+
+                Ok(changes)
+            })
+        })
+        .await?;
+
+    for change in &changes {
+        kintsu_registry_events::emit_event(change.audit_event(org_id, actor))?;
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn role(
+        user_id: i64,
+        role: OrgRoleType,
+        revoked: bool,
+    ) -> OrgRole {
+        OrgRole {
+            org_id: 1,
+            user_id,
+            role,
+            revoked_at: revoked.then(chrono::Utc::now),
+        }
+    }
+
+    #[test]
+    fn plans_joins_role_changes_and_departures() {
+        let roles = [
+            role(1, OrgRoleType::Admin, false),
+            role(2, OrgRoleType::Member, false),
+            role(3, OrgRoleType::Member, false),
+            role(4, OrgRoleType::Member, true),
+            // added without GitHub, e.g. through an invitation
+            role(5, OrgRoleType::Member, false),
+        ];
+        let wanted = BTreeMap::from([
+            (1, OrgRoleType::Admin),
+            (2, OrgRoleType::Admin),
+            (4, OrgRoleType::Member),
+            (6, OrgRoleType::Member),
+        ]);
+
+        let changes = plan(&roles, &wanted, |user_id| user_id != 5);
+
+        assert_eq!(
+            changes,
+            [
+                MembershipChange::RoleChanged {
+                    user_id: 2,
+                    from: OrgRoleType::Member,
+                    to: OrgRoleType::Admin,
+                },
+                MembershipChange::Joined {
+                    user_id: 4,
+                    role: OrgRoleType::Member,
+                },
+                MembershipChange::Joined {
+                    user_id: 6,
+                    role: OrgRoleType::Member,
+                },
+                MembershipChange::Left { user_id: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn plans_nothing_when_in_sync() {
+        let roles = [role(1, OrgRoleType::Admin, false)];
+        let wanted = BTreeMap::from([(1, OrgRoleType::Admin)]);
+
+        assert!(plan(&roles, &wanted, |_| true).is_empty());
+    }
+}
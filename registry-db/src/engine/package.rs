@@ -10,7 +10,7 @@ use crate::{
 use chrono::{NaiveDate, Utc};
 use kintsu_manifests::{
     InvalidManifest,
-    package::{Dependency, PathOrText},
+    package::{Dependency, PackageMetadata, PathOrText},
     version::VersionSerde,
 };
 use sea_orm::{
@@ -26,6 +26,134 @@ pub struct DownloadHistory {
     pub count: i32,
 }
 
+/// Default window of the download breakdown endpoints, in days.
+pub const DOWNLOAD_WINDOW_DAYS: i32 = 90;
+
+/// Longest window accepted by the download breakdown endpoints, in days.
+pub const MAX_DOWNLOAD_WINDOW_DAYS: i32 = 365;
+
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema, FromQueryResult)]
+pub struct ArtifactDownloads {
+    pub artifact: DownloadArtifact,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema, FromQueryResult)]
+pub struct OriginDownloads {
+    pub origin: DownloadOrigin,
+    pub count: i64,
+}
+
+/// Downloads of a package over the last `days` days, split by artifact and by origin.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct DownloadBreakdown {
+    pub days: i32,
+    pub artifacts: Vec<ArtifactDownloads>,
+    pub origins: Vec<OriginDownloads>,
+}
+
+/// Resolver downloads made by one compiler version.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema, FromQueryResult)]
+pub struct ClientDownloads {
+    /// Compiler version, `None` when the client did not report a usable one
+    pub client_version: Option<String>,
+    pub count: i64,
+}
+
+/// Downloads of one version of a package.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema, FromQueryResult)]
+pub struct VersionDownloads {
+    pub version: String,
+    pub count: i64,
+}
+
+/// Downloads of every version of a package on one day.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema, FromQueryResult)]
+pub struct DailyDownloads {
+    pub day: NaiveDate,
+    pub count: i64,
+}
+
+/// Downloads of a package over the last `days` days, per version and per day.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct PackageDownloads {
+    pub package: String,
+    pub days: i32,
+    pub total: i64,
+    /// Newest version first, only versions downloaded in the window
+    pub versions: Vec<VersionDownloads>,
+    /// Oldest day first, only days with downloads
+    pub daily: Vec<DailyDownloads>,
+}
+
+/// Something a publish would succeed with, but which its publisher should review first.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum PublishWarning {
+    /// Declarations of the previous release are removed or changed incompatibly without a
+    /// major version bump
+    BreakingChange {
+        previous_version: String,
+        /// Each breaking change, e.g. ``abc::User removes field `email` ``
+        changes: Vec<String>,
+    },
+    /// A dependency, possibly transitive, is deprecated
+    DeprecatedDependency {
+        package: String,
+        version: String,
+        message: String,
+        replacement: Option<String>,
+    },
+}
+
+impl std::fmt::Display for PublishWarning {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self {
+            Self::BreakingChange {
+                previous_version,
+                changes,
+            } => {
+                write!(
+                    f,
+                    "breaks {previous_version} without a major version bump: {}",
+                    changes.join(", ")
+                )
+            },
+            Self::DeprecatedDependency {
+                package,
+                version,
+                message,
+                replacement,
+            } => {
+                write!(f, "depends on deprecated {package}@{version}: {message}")?;
+                if let Some(replacement) = replacement {
+                    write!(f, " (use {replacement} instead)")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+/// The version a publish would create, returned by a dry-run publish which stores nothing.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct PublishPreview {
+    pub package: String,
+    #[schema(value_type = String, example = "0.2.0")]
+    pub version: VersionSerde,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub license: String,
+    pub repository: String,
+    pub keywords: Vec<String>,
+    /// The release the version was compared against
+    pub previous_version: Option<String>,
+    pub warnings: Vec<PublishWarning>,
+}
+
 pub struct StagePublishPackage {
     pub package_name: String,
     pub version: VersionSerde,
@@ -35,48 +163,55 @@ pub struct StagePublishPackage {
     pub readme: String,
     pub repository: String,
     pub keywords: Vec<String>,
+    pub metadata: PackageMetadata,
     pub manifest_dependencies: Vec<i64>,
 }
 
+/// The texts of a package stored with each version, read from the manifest or the files it
+/// names.
+struct PackageTexts {
+    description: Option<String>,
+    license: String,
+    readme: String,
+    repository: String,
+}
+
+impl PackageTexts {
+    fn read(
+        package: &kintsu_manifests::package::PackageMeta,
+        fs: &kintsu_fs::memory::MemoryFileSystem,
+    ) -> Result<Self> {
+        Ok(Self {
+            description: PathOrText::text_opt(package.description.as_ref(), fs)?,
+            license: PathOrText::text_opt(package.license.as_ref(), fs)?
+                .ok_or(InvalidManifest::PackageMissingLicense)?,
+            readme: PathOrText::text_opt(package.readme.as_ref(), fs)?
+                .ok_or(InvalidManifest::PackageMissingReadme)?,
+            repository: package
+                .repository
+                .clone()
+                .ok_or(InvalidManifest::PackageMissingRepository)?,
+        })
+    }
+}
+
 impl StagePublishPackage {
-    pub async fn process<C: sea_orm::ConnectionTrait + TransactionTrait>(
+    /// Checks `principal` may publish `package_name`, returning the package when it already
+    /// exists. Audited like any other permission protected action.
+    pub async fn authorize<C: sea_orm::ConnectionTrait>(
         db: &C,
         principal: &super::principal::PrincipalIdentity,
-        storage: std::sync::Arc<PackageStorage>,
-
-        fs: kintsu_fs::memory::MemoryFileSystem,
-        manifest: kintsu_manifests::package::PackageManifests,
-
-        declarations: kintsu_parser::declare::DeclarationVersion,
-        manifest_dependencies: Vec<i64>,
-    ) -> Result<Version> {
-        let package = manifest.package();
-
-        let description = PathOrText::text_opt(package.description.as_ref(), &fs)?;
-        let license = PathOrText::text_opt(package.license.as_ref(), &fs)?
-            .ok_or(InvalidManifest::PackageMissingLicense)?;
-        let readme = PathOrText::text_opt(package.readme.as_ref(), &fs)?
-            .ok_or(InvalidManifest::PackageMissingReadme)?;
-
-        let package_name = package.name.clone();
-        let version = package.version.clone();
-        let homepage = package.homepage.clone();
-        let repository = package
-            .repository
-            .clone()
-            .ok_or(InvalidManifest::PackageMissingRepository)?;
-
-        let keywords = package.keywords.clone();
-
+        package_name: &str,
+    ) -> Result<Option<Package>> {
         let pkg = PackageEntity::find()
-            .filter(PackageColumn::Name.eq(&package_name))
+            .filter(PackageColumn::Name.eq(package_name))
             .one(db)
             .await?;
 
         let package_id = pkg.as_ref().map(|p| p.id);
 
         let auth_result = super::fluent::AuthCheck::new(db, principal)
-            .package(&package_name, package_id)
+            .package(package_name, package_id)
             .can_publish()
             .await?;
 
@@ -85,7 +220,7 @@ impl StagePublishPackage {
                 permission: Permission::PublishPackage.into(),
                 resource: super::authorization::ResourceIdentifier::Package(
                     super::authorization::PackageResource {
-                        name: package_name.clone(),
+                        name: package_name.to_string(),
                         id: package_id,
                     },
                 )
@@ -97,8 +232,58 @@ impl StagePublishPackage {
 
         auth_result.require()?;
 
+        Ok(pkg)
+    }
+
+    pub async fn process<C: sea_orm::ConnectionTrait + TransactionTrait>(
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        storage: std::sync::Arc<PackageStorage>,
+
+        fs: kintsu_fs::memory::MemoryFileSystem,
+        manifest: kintsu_manifests::package::PackageManifests,
+
+        declarations: kintsu_parser::declare::DeclarationVersion,
+        manifest_dependencies: Vec<i64>,
+    ) -> Result<Version> {
+        let package = manifest.package();
+
+        let PackageTexts {
+            description,
+            license,
+            readme,
+            repository,
+        } = PackageTexts::read(package, &fs)?;
+
+        let package_name = package.name.clone();
+        let version = package.version.clone();
+        let homepage = package.homepage.clone();
+
+        let keywords = package.keywords.clone();
+        let metadata = serde_json::Value::Object(
+            package
+                .metadata
+                .clone()
+                .into_iter()
+                .collect(),
+        );
+
+        let pkg = Self::authorize(db, principal, &package_name).await?;
+
         let key_owner_id = principal.owner_id();
 
+        // new scoped packages belong to the org owning their scope, not to their publisher
+        let (admin_user_id, admin_org_id) =
+            match kintsu_manifests::package::package_scope(&package_name) {
+                Some(scope) if pkg.is_none() => {
+                    let org = Org::by_name(db, scope)
+                        .await?
+                        .ok_or_else(|| Error::NotFound(format!("org {scope}")))?;
+                    (None, Some(org.id))
+                },
+                _ => (key_owner_id.user_id(), key_owner_id.org_id()),
+            };
+
         Ok(db
             .transaction::<_, Version, Error>(move |db| {
                 Box::pin(async move {
@@ -115,14 +300,18 @@ impl StagePublishPackage {
                         let new_pkg_model = PackageActiveModel {
                             id: NotSet,
                             name: Set(package_name.clone()),
+                            deprecated_at: NotSet,
+                            deprecation_message: NotSet,
+                            deprecation_replacement: NotSet,
                         };
                         let new_pkg = new_pkg_model.insert(db).await?;
 
                         let schema_role_active_model = SchemaRoleActiveModel {
                             id: NotSet,
                             package: Set(new_pkg.id),
-                            user_id: Set(key_owner_id.user_id()),
-                            org_id: Set(key_owner_id.org_id()),
+                            user_id: Set(admin_user_id),
+                            org_id: Set(admin_org_id),
+                            team_id: NotSet,
                             role: Set(SchemaRoleType::Admin),
                             revoked_at: NotSet,
                         };
@@ -135,6 +324,10 @@ impl StagePublishPackage {
                         .store_package(&package_name, &version.to_string(), &fs, &declarations)
                         .await?;
 
+                    let readme_checksum = storage
+                        .store_readme(&package_name, &version.to_string(), &readme)
+                        .await?;
+
                     let new_version_model = VersionActiveModel {
                         id: NotSet,
                         package: Set(package_id),
@@ -149,21 +342,100 @@ impl StagePublishPackage {
                         license: Set(license.clone()),
                         license_text: Set(String::new()),
                         readme: Set(readme.clone()),
+                        readme_checksum: Set(Some(readme_checksum.value().to_string())),
                         repository: Set(repository.to_string()),
                         keywords: Set(keywords),
+                        metadata: Set(metadata),
                         publishing_user_id: Set(key_owner_id.user_id()),
                         publishing_org_id: Set(key_owner_id.org_id()),
                         dependencies: Set(manifest_dependencies.clone()),
                         created_at: NotSet,
                         yanked_at: NotSet,
+                        deprecated_at: NotSet,
+                        deprecation_message: NotSet,
+                        deprecation_replacement: NotSet,
                     };
 
-                    Ok(new_version_model.insert(db).await?)
+                    let new_version = new_version_model.insert(db).await?;
+                    DeclarationItem::index(db, new_version.id, &declarations).await?;
+                    Version::index_dependencies(db, new_version.id, &manifest_dependencies).await?;
+
+                    Ok(new_version)
                 })
             })
             .await?)
     }
 
+    /// Runs the checks [`Self::process`] does before storing anything, and compares the
+    /// declarations with the previous release, reporting the version which would be created.
+    pub async fn preview<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        storage: &PackageStorage,
+        fs: &kintsu_fs::memory::MemoryFileSystem,
+        manifest: &kintsu_manifests::package::PackageManifests,
+        declarations: &kintsu_parser::declare::DeclarationVersion,
+    ) -> Result<PublishPreview> {
+        use kintsu_parser::declare::diff;
+
+        let package = manifest.package();
+        let PackageTexts {
+            description,
+            license,
+            repository,
+            ..
+        } = PackageTexts::read(package, fs)?;
+
+        Self::authorize(db, principal, &package.name).await?;
+
+        if Version::exists(db, &package.name, &package.version.to_string()).await? {
+            return Err(Error::PackageVersionExists {
+                package: package.name.clone(),
+                version: package.version.to_string(),
+            });
+        }
+
+        let previous = Version::previous_release(db, &package.name, &package.version).await?;
+
+        let mut warnings = vec![];
+        if let Some(previous) = &previous
+            && !diff::is_breaking_release(&previous.qualified_version.0, &package.version.0)
+        {
+            let previous_declarations = storage
+                .get_declarations(
+                    &storage.path_for_declarations(
+                        &package.name,
+                        &previous.qualified_version.to_string(),
+                    ),
+                    previous.declarations_checksum.clone().into(),
+                )
+                .await?;
+
+            let changes = diff::breaking_changes(&previous_declarations, declarations);
+            if !changes.is_empty() {
+                warnings.push(PublishWarning::BreakingChange {
+                    previous_version: previous.qualified_version.to_string(),
+                    changes: changes
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                });
+            }
+        }
+
+        Ok(PublishPreview {
+            package: package.name.clone(),
+            version: package.version.clone(),
+            description,
+            homepage: package.homepage.clone(),
+            license,
+            repository,
+            keywords: package.keywords.clone(),
+            previous_version: previous.map(|previous| previous.qualified_version.to_string()),
+            warnings,
+        })
+    }
+
     /// Resolve manifest dependencies to their version IDs in the database.
     ///
     /// This function uses semver matching - a dependency on `^1.0.0` will match
@@ -180,7 +452,7 @@ impl StagePublishPackage {
 
         for (name, dep) in dependencies {
             match dep {
-                Dependency::Path(..) | Dependency::Git(..) => {
+                Dependency::Path(..) | Dependency::Git(..) | Dependency::Workspace(..) => {
                     unresolved.push(InvalidManifest::UnresolvedDependency {
                         name: name.clone(),
                         version: None,
@@ -354,6 +626,180 @@ impl Package {
         Ok(results)
     }
 
+    fn download_window(days: Option<i32>) -> Result<i32> {
+        match days.unwrap_or(DOWNLOAD_WINDOW_DAYS) {
+            days @ 1..=MAX_DOWNLOAD_WINDOW_DAYS => Ok(days),
+            days => {
+                Err(Error::Validation(format!(
+                    "days must be between 1 and {MAX_DOWNLOAD_WINDOW_DAYS}, got {days}"
+                )))
+            },
+        }
+    }
+
+    pub async fn package_download_breakdown<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        package_name: &str,
+        days: Option<i32>,
+    ) -> Result<DownloadBreakdown> {
+        use sea_orm::Statement;
+
+        let days = Self::download_window(days)?;
+
+        let grouped_by = |column: &str| {
+            let raw_sql = format!(
+                r#"
+                SELECT
+                    d.{column}::text as {column},
+                    SUM(d.count)::bigint as count
+                FROM download_detail d
+                INNER JOIN version v ON d.version = v.id
+                INNER JOIN package p ON v.package = p.id
+                WHERE p.name = $1
+                AND d.day > CURRENT_DATE - $2::int
+                GROUP BY d.{column}
+                ORDER BY count DESC
+            "#
+            );
+
+            Statement::from_sql_and_values(
+                sea_orm::DatabaseBackend::Postgres,
+                raw_sql,
+                vec![package_name.into(), days.into()],
+            )
+        };
+
+        let artifacts = ArtifactDownloads::find_by_statement(grouped_by("artifact"))
+            .all(db)
+            .await?;
+        let origins = OriginDownloads::find_by_statement(grouped_by("origin"))
+            .all(db)
+            .await?;
+
+        Ok(DownloadBreakdown {
+            days,
+            artifacts,
+            origins,
+        })
+    }
+
+    /// Downloads of `package_name` over the last `days` days, aggregated per version and
+    /// per day from the compacted daily buckets.
+    pub async fn package_downloads<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        package_name: &str,
+        days: Option<i32>,
+    ) -> Result<PackageDownloads> {
+        use sea_orm::Statement;
+
+        let days = Self::download_window(days)?;
+
+        if Self::by_name(db, package_name)
+            .await?
+            .is_none()
+        {
+            return Err(Error::NotFound(format!(
+                "Package '{}' not found",
+                package_name
+            )));
+        }
+
+        let statement = |raw_sql: &str| {
+            Statement::from_sql_and_values(
+                sea_orm::DatabaseBackend::Postgres,
+                raw_sql,
+                vec![package_name.into(), days.into()],
+            )
+        };
+
+        let versions = VersionDownloads::find_by_statement(statement(
+            r#"
+            SELECT
+                v.qualified_version as version,
+                SUM(d.count)::bigint as count
+            FROM downloads d
+            INNER JOIN version v ON d.version = v.id
+            INNER JOIN package p ON v.package = p.id
+            WHERE p.name = $1
+            AND d.day > CURRENT_DATE - $2::int
+            GROUP BY v.id, v.qualified_version
+            ORDER BY v.id DESC
+        "#,
+        ))
+        .all(db)
+        .await?;
+
+        let daily = DailyDownloads::find_by_statement(statement(
+            r#"
+            SELECT
+                d.day,
+                SUM(d.count)::bigint as count
+            FROM downloads d
+            INNER JOIN version v ON d.version = v.id
+            INNER JOIN package p ON v.package = p.id
+            WHERE p.name = $1
+            AND d.day > CURRENT_DATE - $2::int
+            GROUP BY d.day
+            ORDER BY d.day ASC
+        "#,
+        ))
+        .all(db)
+        .await?;
+
+        Ok(PackageDownloads {
+            package: package_name.to_string(),
+            days,
+            total: versions.iter().map(|v| v.count).sum(),
+            versions,
+            daily,
+        })
+    }
+
+    /// Resolver downloads over the last `days` days grouped by the compiler version that
+    /// made them, newest compiler first and unknown versions last.
+    pub async fn package_client_downloads<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        package_name: &str,
+        days: Option<i32>,
+    ) -> Result<Vec<ClientDownloads>> {
+        use sea_orm::Statement;
+
+        let days = Self::download_window(days)?;
+
+        let raw_sql = r#"
+            SELECT
+                NULLIF(d.client_version, '') as client_version,
+                SUM(d.count)::bigint as count
+            FROM download_detail d
+            INNER JOIN version v ON d.version = v.id
+            INNER JOIN package p ON v.package = p.id
+            WHERE p.name = $1
+            AND d.origin = 'resolver'
+            AND d.day > CURRENT_DATE - $2::int
+            GROUP BY NULLIF(d.client_version, '')
+        "#;
+
+        let stmt = Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            raw_sql,
+            vec![package_name.into(), days.into()],
+        );
+
+        let mut results = ClientDownloads::find_by_statement(stmt)
+            .all(db)
+            .await?;
+
+        results.sort_by_cached_key(|row| {
+            std::cmp::Reverse(
+                row.client_version
+                    .as_deref()
+                    .and_then(|v| kintsu_manifests::version::parse_version(v).ok()),
+            )
+        });
+
+        Ok(results)
+    }
+
     pub async fn user_admins<C: sea_orm::ConnectionTrait>(
         db: &C,
         package_id: i64,
@@ -400,6 +846,123 @@ impl Package {
         package_name: &str,
         version_str: &str,
     ) -> Result<()> {
+        Self::set_yanked(db, principal, package_name, version_str, true).await
+    }
+
+    /// Reverses [`Self::yank_version`], making the version available to new resolutions again.
+    pub async fn unyank_version<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        package_name: &str,
+        version_str: &str,
+    ) -> Result<()> {
+        Self::set_yanked(db, principal, package_name, version_str, false).await
+    }
+
+    async fn set_yanked<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        package_name: &str,
+        version_str: &str,
+        yanked: bool,
+    ) -> Result<()> {
+        let pkg = Self::authorize_lifecycle(db, principal, package_name).await?;
+        let version = Self::version_of(db, &pkg, version_str).await?;
+
+        let mut active_model: VersionActiveModel = version.into();
+        active_model.yanked_at = Set(yanked.then(Utc::now));
+        active_model.update(db).await?;
+
+        Ok(())
+    }
+
+    /// Marks `package_name` deprecated, or only `version_str` of it when given. Deprecated
+    /// versions still resolve; the compiler warns when one is chosen.
+    pub async fn deprecate<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        package_name: &str,
+        version_str: Option<&str>,
+        message: &str,
+        replacement: Option<&str>,
+    ) -> Result<()> {
+        if let Some(replacement) = replacement {
+            if replacement == package_name {
+                return Err(Error::Validation(format!(
+                    "'{package_name}' cannot replace itself"
+                )));
+            }
+
+            PackageEntity::find()
+                .filter(PackageColumn::Name.eq(replacement))
+                .one(db)
+                .await?
+                .ok_or_else(|| {
+                    Error::NotFound(format!("Replacement package '{}' not found", replacement))
+                })?;
+        }
+
+        Self::set_deprecation(
+            db,
+            principal,
+            package_name,
+            version_str,
+            Some((message.to_string(), replacement.map(str::to_string))),
+        )
+        .await
+    }
+
+    /// Reverses [`Self::deprecate`] for the package, or only `version_str` of it when given.
+    pub async fn undeprecate<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        package_name: &str,
+        version_str: Option<&str>,
+    ) -> Result<()> {
+        Self::set_deprecation(db, principal, package_name, version_str, None).await
+    }
+
+    async fn set_deprecation<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        package_name: &str,
+        version_str: Option<&str>,
+        deprecation: Option<(String, Option<String>)>,
+    ) -> Result<()> {
+        let pkg = Self::authorize_lifecycle(db, principal, package_name).await?;
+
+        let deprecated_at = deprecation.as_ref().map(|_| Utc::now());
+        let (message, replacement) = deprecation.unzip();
+
+        match version_str {
+            Some(version_str) => {
+                let version = Self::version_of(db, &pkg, version_str).await?;
+
+                let mut active_model: VersionActiveModel = version.into();
+                active_model.deprecated_at = Set(deprecated_at);
+                active_model.deprecation_message = Set(message);
+                active_model.deprecation_replacement = Set(replacement.flatten());
+                active_model.update(db).await?;
+            },
+            None => {
+                let mut active_model: PackageActiveModel = pkg.into();
+                active_model.deprecated_at = Set(deprecated_at);
+                active_model.deprecation_message = Set(message);
+                active_model.deprecation_replacement = Set(replacement.flatten());
+                active_model.update(db).await?;
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Looks up `package_name` and checks `principal` may change the lifecycle (yanks and
+    /// deprecations) of its versions.
+    async fn authorize_lifecycle<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        principal: &super::principal::PrincipalIdentity,
+        package_name: &str,
+    ) -> Result<Package> {
         let pkg = PackageEntity::find()
             .filter(PackageColumn::Name.eq(package_name))
             .one(db)
@@ -428,18 +991,20 @@ impl Package {
 
         auth_result.require()?;
 
-        let version = VersionEntity::find()
+        Ok(pkg)
+    }
+
+    async fn version_of<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        pkg: &Package,
+        version_str: &str,
+    ) -> Result<Version> {
+        VersionEntity::find()
             .filter(VersionColumn::Package.eq(pkg.id))
             .filter(VersionColumn::QualifiedVersion.eq(version_str))
             .one(db)
             .await?
-            .ok_or_else(|| Error::NotFound(format!("Version '{}' not found", version_str)))?;
-
-        let mut active_model: VersionActiveModel = version.into();
-        active_model.yanked_at = Set(Some(Utc::now()));
-        active_model.update(db).await?;
-
-        Ok(())
+            .ok_or_else(|| Error::NotFound(format!("Version '{}' not found", version_str)))
     }
 
     fn select_with_ordering(
@@ -668,7 +1233,13 @@ impl Package {
                     p.name as package_name,
                     v.qualified_version,
                     v.source_checksum,
-                    v.declarations_checksum
+                    v.declarations_checksum,
+                    case when v.deprecated_at is not null
+                        then v.deprecation_message else p.deprecation_message
+                    end as deprecation_message,
+                    case when v.deprecated_at is not null
+                        then v.deprecation_replacement else p.deprecation_replacement
+                    end as deprecation_replacement
                 FROM
                     get_dependency_tree($1::bigint []) dt
                 inner join package p on dt.package_id = p.id
@@ -689,6 +1260,10 @@ pub struct TransitiveDependency {
 
     pub source_checksum: String,
     pub declarations_checksum: String,
+
+    /// Set when the version or its package is deprecated
+    pub deprecation_message: Option<String>,
+    pub deprecation_replacement: Option<String>,
 }
 
 impl From<TransitiveDependency> for kintsu_registry_storage::BulkGetPackage {
@@ -8,11 +8,16 @@ pub async fn grant_role<C: sea_orm::ConnectionTrait>(
     package_name: &str,
     user_id: Option<i64>,
     org_id: Option<i64>,
+    team_id: Option<i64>,
     role: SchemaRoleType,
 ) -> Result<SchemaRole> {
-    if (user_id.is_some() && org_id.is_some()) || (user_id.is_none() && org_id.is_none()) {
+    let targets = [user_id, org_id, team_id]
+        .iter()
+        .filter(|id| id.is_some())
+        .count();
+    if targets != 1 {
         return Err(Error::Validation(
-            "Must specify exactly one of user_id or org_id".into(),
+            "Must specify exactly one of user_id, org_id or team_id".into(),
         ));
     }
 
@@ -44,6 +49,15 @@ pub async fn grant_role<C: sea_orm::ConnectionTrait>(
 
     auth_result.require()?;
 
+    if let Some(team_id) = team_id
+        && TeamEntity::find_by_id(team_id)
+            .one(db)
+            .await?
+            .is_none()
+    {
+        return Err(Error::NotFound(format!("Team {} not found", team_id)));
+    }
+
     let mut query = SchemaRoleEntity::find()
         .filter(SchemaRoleColumn::Package.eq(pkg.id))
         .filter(SchemaRoleColumn::Role.eq(role.clone()))
@@ -51,8 +65,10 @@ pub async fn grant_role<C: sea_orm::ConnectionTrait>(
 
     query = if let Some(uid) = user_id {
         query.filter(SchemaRoleColumn::UserId.eq(uid))
+    } else if let Some(oid) = org_id {
+        query.filter(SchemaRoleColumn::OrgId.eq(oid))
     } else {
-        query.filter(SchemaRoleColumn::OrgId.eq(org_id.unwrap()))
+        query.filter(SchemaRoleColumn::TeamId.eq(team_id.unwrap()))
     };
 
     if query.one(db).await?.is_some() {
@@ -64,6 +80,7 @@ pub async fn grant_role<C: sea_orm::ConnectionTrait>(
         package: Set(pkg.id),
         user_id: Set(user_id),
         org_id: Set(org_id),
+        team_id: Set(team_id),
         role: Set(role),
         revoked_at: NotSet,
     };
@@ -0,0 +1,275 @@
+use crate::{Error, Result, entities::*};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, ModelTrait, NotSet, QueryFilter, QueryOrder, Set,
+};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, utoipa::ToSchema)]
+pub struct TeamMemberWithUser {
+    #[serde(flatten)]
+    pub member: TeamMember,
+    pub user: User,
+}
+
+impl Team {
+    pub async fn by_id<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        org_id: i64,
+        team_id: i64,
+    ) -> Result<Option<Self>> {
+        TeamEntity::find()
+            .filter(TeamColumn::Id.eq(team_id))
+            .filter(TeamColumn::OrgId.eq(org_id))
+            .one(db)
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn for_org<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        org_id: i64,
+    ) -> Result<Vec<Self>> {
+        TeamEntity::find()
+            .filter(TeamColumn::OrgId.eq(org_id))
+            .order_by_asc(TeamColumn::Name)
+            .all(db)
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn members<C: sea_orm::ConnectionTrait>(
+        &self,
+        db: &C,
+    ) -> Result<Vec<TeamMemberWithUser>> {
+        let members = TeamMemberEntity::find()
+            .filter(TeamMemberColumn::TeamId.eq(self.id))
+            .find_also_related(UserEntity)
+            .order_by_asc(TeamMemberColumn::CreatedAt)
+            .all(db)
+            .await?;
+
+        members
+            .into_iter()
+            .map(|(member, user)| {
+                let user = user.ok_or_else(|| {
+                    Error::Internal(format!("Team member {} has no user", member.user_id))
+                })?;
+                Ok(TeamMemberWithUser { member, user })
+            })
+            .collect()
+    }
+
+    async fn find<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        org_id: i64,
+        team_id: i64,
+    ) -> Result<Self> {
+        Self::by_id(db, org_id, team_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("Team {} not found", team_id)))
+    }
+}
+
+async fn authorize<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    principal: &super::principal::PrincipalIdentity,
+    org_id: i64,
+    team_id: Option<i64>,
+    permission: Permission,
+) -> Result<()> {
+    let check = super::fluent::AuthCheck::new(db, principal).team(org_id, team_id);
+    let auth_result = match permission {
+        Permission::ManageTeamMembers => check.can_manage_members().await?,
+        _ => check.can_manage().await?,
+    };
+
+    let event = principal.audit_event(
+        kintsu_registry_auth::AuditEventType::PermissionProtected {
+            permission: permission.into(),
+            resource: super::authorization::ResourceIdentifier::Team(
+                super::authorization::TeamResource { org_id, team_id },
+            )
+            .into(),
+        },
+        &auth_result,
+    );
+    kintsu_registry_events::emit_event(event)?;
+
+    Ok(auth_result.require()?)
+}
+
+async fn ensure_name_free<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    org_id: i64,
+    name: &str,
+) -> Result<()> {
+    let existing = TeamEntity::find()
+        .filter(TeamColumn::OrgId.eq(org_id))
+        .filter(TeamColumn::Name.eq(name))
+        .one(db)
+        .await?;
+
+    if existing.is_some() {
+        return Err(Error::Conflict(format!(
+            "Team '{}' already exists in organization {}",
+            name, org_id
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn create_team<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    principal: &super::principal::PrincipalIdentity,
+    org_id: i64,
+    name: String,
+    description: Option<String>,
+) -> Result<Team> {
+    authorize(db, principal, org_id, None, Permission::ManageTeam).await?;
+
+    if Org::by_id(db, org_id).await?.is_none() {
+        return Err(Error::NotFound("Organization not found".into()));
+    }
+
+    ensure_name_free(db, org_id, &name).await?;
+
+    let active_model = TeamActiveModel {
+        id: NotSet,
+        org_id: Set(org_id),
+        name: Set(name),
+        description: Set(description),
+        created_at: NotSet,
+    };
+
+    Ok(active_model.insert(db).await?)
+}
+
+pub async fn update_team<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    principal: &super::principal::PrincipalIdentity,
+    org_id: i64,
+    team_id: i64,
+    name: Option<String>,
+    description: Option<String>,
+) -> Result<Team> {
+    authorize(db, principal, org_id, Some(team_id), Permission::ManageTeam).await?;
+
+    let team = Team::find(db, org_id, team_id).await?;
+
+    let mut active_model: TeamActiveModel = team.clone().into();
+    if let Some(name) = name
+        && name != team.name
+    {
+        ensure_name_free(db, org_id, &name).await?;
+        active_model.name = Set(name);
+    }
+    if let Some(description) = description {
+        active_model.description = Set(Some(description));
+    }
+
+    Ok(active_model.update(db).await?)
+}
+
+/// Deletes the team along with its memberships and the package roles granted to it.
+pub async fn delete_team<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    principal: &super::principal::PrincipalIdentity,
+    org_id: i64,
+    team_id: i64,
+) -> Result<()> {
+    authorize(db, principal, org_id, Some(team_id), Permission::ManageTeam).await?;
+
+    Team::find(db, org_id, team_id)
+        .await?
+        .delete(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Adds a user to the team, or changes their role if they are already on it. Only active
+/// members of the team's org can join.
+pub async fn add_member<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    principal: &super::principal::PrincipalIdentity,
+    org_id: i64,
+    team_id: i64,
+    user_id: i64,
+    role: TeamRoleType,
+) -> Result<TeamMember> {
+    authorize(
+        db,
+        principal,
+        org_id,
+        Some(team_id),
+        Permission::ManageTeamMembers,
+    )
+    .await?;
+
+    let team = Team::find(db, org_id, team_id).await?;
+
+    let in_org = OrgRoleEntity::find()
+        .filter(OrgRoleColumn::OrgId.eq(team.org_id))
+        .filter(OrgRoleColumn::UserId.eq(user_id))
+        .filter(OrgRoleColumn::RevokedAt.is_null())
+        .one(db)
+        .await?
+        .is_some();
+
+    if !in_org {
+        return Err(Error::Validation(format!(
+            "User {} is not a member of organization {}",
+            user_id, team.org_id
+        )));
+    }
+
+    let existing = TeamMemberEntity::find_by_id((team.id, user_id))
+        .one(db)
+        .await?;
+
+    match existing {
+        Some(member) if member.role == role => Ok(member),
+        Some(member) => {
+            let mut active_model: TeamMemberActiveModel = member.into();
+            active_model.role = Set(role);
+            Ok(active_model.update(db).await?)
+        },
+        None => {
+            let active_model = TeamMemberActiveModel {
+                team_id: Set(team.id),
+                user_id: Set(user_id),
+                role: Set(role),
+                created_at: NotSet,
+            };
+            Ok(active_model.insert(db).await?)
+        },
+    }
+}
+
+pub async fn remove_member<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    principal: &super::principal::PrincipalIdentity,
+    org_id: i64,
+    team_id: i64,
+    user_id: i64,
+) -> Result<()> {
+    authorize(
+        db,
+        principal,
+        org_id,
+        Some(team_id),
+        Permission::ManageTeamMembers,
+    )
+    .await?;
+
+    let team = Team::find(db, org_id, team_id).await?;
+
+    let member = TeamMemberEntity::find_by_id((team.id, user_id))
+        .one(db)
+        .await?
+        .ok_or_else(|| Error::NotFound("Team member not found".into()))?;
+
+    member.delete(db).await?;
+
+    Ok(())
+}
@@ -1,39 +1,22 @@
-use crate::{Result, engine::OrgWithAdmin, entities::*};
+use crate::{Error, Result, engine::OrgWithAdmin, entities::*};
 use chrono::{DateTime, Utc};
 use sea_orm::{
-    ColumnTrait, EntityTrait, NotSet, PaginatorTrait, QueryFilter, QueryOrder, Set,
-    sea_query::OnConflict,
+    ActiveModelTrait, ColumnTrait, EntityTrait, NotSet, PaginatorTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
 };
 
-pub struct NewUser {
+/// An account at an identity provider, as reported by it on login.
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    /// Name of the provider, e.g. `github`
+    pub provider: String,
+    /// Stable id of the account at the provider
+    pub subject: String,
     pub email: String,
-    pub gh_id: i32,
-    pub gh_login: String,
-    pub gh_avatar: Option<String>,
-}
-
-impl NewUser {
-    pub async fn qualify(
-        self,
-        db: &sea_orm::DatabaseConnection,
-    ) -> Result<User> {
-        let active_model = UserActiveModel {
-            id: NotSet,
-            email: Set(self.email.clone()),
-            gh_id: Set(self.gh_id),
-            gh_login: Set(self.gh_login.clone()),
-            gh_avatar: Set(self.gh_avatar.clone()),
-        };
-
-        Ok(UserEntity::insert(active_model)
-            .on_conflict(
-                OnConflict::column(UserColumn::GhId)
-                    .update_columns([UserColumn::Email, UserColumn::GhLogin, UserColumn::GhAvatar])
-                    .to_owned(),
-            )
-            .exec_with_returning(db)
-            .await?)
-    }
+    pub login: String,
+    pub avatar: Option<String>,
+    /// The GitHub user id, for accounts at GitHub
+    pub gh_id: Option<i32>,
 }
 
 impl User {
@@ -59,6 +42,18 @@ impl User {
             .map_err(Into::into)
     }
 
+    pub async fn identities(
+        &self,
+        db: &sea_orm::DatabaseConnection,
+    ) -> Result<Vec<UserIdentity>> {
+        UserIdentityEntity::find()
+            .filter(UserIdentityColumn::UserId.eq(self.id))
+            .order_by_asc(UserIdentityColumn::CreatedAt)
+            .all(db)
+            .await
+            .map_err(Into::into)
+    }
+
     pub async fn by_email(
         db: &sea_orm::DatabaseConnection,
         email: &str,
@@ -148,19 +143,71 @@ impl User {
     }
 }
 
-pub async fn create_or_update_user_from_oauth(
-    db: &sea_orm::DatabaseConnection,
-    gh_id: i32,
-    gh_login: &str,
-    gh_avatar: Option<&str>,
-    email: &str,
+/// Logs in the user behind `identity`, creating them on their first login and refreshing
+/// their profile on later ones.
+///
+/// Accounts are never linked by email: logging in through a second provider with an email
+/// which is already registered is a conflict.
+pub async fn login_with_identity<C: sea_orm::ConnectionTrait + TransactionTrait>(
+    db: &C,
+    identity: ExternalIdentity,
 ) -> Result<User> {
-    let new_user = NewUser {
-        gh_id,
-        gh_login: gh_login.to_string(),
-        gh_avatar: gh_avatar.map(|s| s.to_string()),
-        email: email.to_string(),
-    };
-
-    new_user.qualify(db).await
+    db.transaction::<_, User, Error>(|txn| {
+        Box::pin(async move {
+            let existing = UserIdentityEntity::find_by_id((
+                identity.provider.clone(),
+                identity.subject.clone(),
+            ))
+            .find_also_related(UserEntity)
+            .one(txn)
+            .await?;
+
+            if let Some((_, Some(user))) = existing {
+                let mut active_model: UserActiveModel = user.into();
+                active_model.email = Set(identity.email);
+                active_model.gh_login = Set(identity.login);
+                active_model.gh_avatar = Set(identity.avatar);
+                if identity.gh_id.is_some() {
+                    active_model.gh_id = Set(identity.gh_id);
+                }
+                return Ok(active_model.update(txn).await?);
+            }
+
+            let email_taken = UserEntity::find()
+                .filter(UserColumn::Email.eq(&identity.email))
+                .one(txn)
+                .await?
+                .is_some();
+
+            if email_taken {
+                return Err(Error::Conflict(format!(
+                    "An account with email {} already exists, log in with the provider it was created with",
+                    identity.email
+                )));
+            }
+
+            let user = UserActiveModel {
+                id: NotSet,
+                email: Set(identity.email),
+                gh_id: Set(identity.gh_id),
+                gh_login: Set(identity.login),
+                gh_avatar: Set(identity.avatar),
+            }
+            .insert(txn)
+            .await?;
+
+            UserIdentityActiveModel {
+                provider: Set(identity.provider),
+                subject: Set(identity.subject),
+                user_id: Set(user.id),
+                created_at: NotSet,
+            }
+            .insert(txn)
+            .await?;
+
+            Ok(user)
+        })
+    })
+    .await
+    .map_err(Into::into)
 }
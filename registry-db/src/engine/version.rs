@@ -7,8 +7,7 @@ use chrono::Utc;
 use kintsu_manifests::version::{VersionExt, VersionSerde, parse_version};
 use sea_orm::{
     ColumnTrait, EntityTrait, ExprTrait, Order, QueryFilter, QueryOrder, QuerySelect, Set,
-    prelude::Expr,
-    sea_query::{OnConflict, SimpleExpr},
+    prelude::Expr, sea_query::OnConflict,
 };
 use serde::Serialize;
 
@@ -17,13 +16,60 @@ pub struct QualifiedPackageVersion {
     pub package: Package,
     pub version: Version,
     pub publisher: Entity,
+    /// Deprecation of the version, or else of its package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecation: Option<Deprecation>,
+}
+
+/// Why owners marked a package or version deprecated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+pub struct Deprecation {
+    pub message: String,
+    /// Package to use instead
+    pub replacement: Option<String>,
+    pub deprecated_at: crate::DateTime,
+}
+
+impl Deprecation {
+    fn from_columns(
+        deprecated_at: Option<crate::DateTime>,
+        message: Option<String>,
+        replacement: Option<String>,
+    ) -> Option<Self> {
+        Some(Self {
+            message: message.unwrap_or_default(),
+            replacement,
+            deprecated_at: deprecated_at?,
+        })
+    }
+
+    /// The deprecation of `version`, falling back to that of its `package`.
+    pub fn of(
+        version: &Version,
+        package: &Package,
+    ) -> Option<Self> {
+        Self::from_columns(
+            version.deprecated_at,
+            version.deprecation_message.clone(),
+            version.deprecation_replacement.clone(),
+        )
+        .or_else(|| {
+            Self::from_columns(
+                package.deprecated_at,
+                package.deprecation_message.clone(),
+                package.deprecation_replacement.clone(),
+            )
+        })
+    }
 }
 
 impl From<(Version, Option<Package>, Option<User>, Option<Org>)> for QualifiedPackageVersion {
     fn from(tuple: (Version, Option<Package>, Option<User>, Option<Org>)) -> Self {
         let (version, package, user, org) = tuple;
+        let package = package.expect("postgres constraint ensures package exists");
         QualifiedPackageVersion {
-            package: package.expect("postgres constraint ensures package exists"),
+            deprecation: Deprecation::of(&version, &package),
+            package,
             version,
             publisher: user
                 .map(Entity::User)
@@ -43,6 +89,15 @@ impl QualifiedPackageVersion {
     }
 }
 
+/// How a single download was made, recorded alongside the version-level count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadEvent {
+    pub artifact: DownloadArtifact,
+    pub origin: DownloadOrigin,
+    /// Compiler version reported by kintsu tooling, `None` for other clients
+    pub client_version: Option<String>,
+}
+
 pub struct LatestVersions {
     pub latest_version: VersionSerde,
     pub latest_stable: Option<VersionSerde>,
@@ -98,6 +153,30 @@ impl Version {
         Ok(exists)
     }
 
+    /// The highest version of `package_name` below `version` which is not yanked, the release
+    /// a new `version` is compared against.
+    pub async fn previous_release<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        package_name: &str,
+        version: &VersionSerde,
+    ) -> Result<Option<Self>> {
+        let versions = VersionEntity::find()
+            .inner_join(PackageEntity)
+            .filter(PackageColumn::Name.eq(package_name))
+            .filter(VersionColumn::YankedAt.is_null())
+            .all(db)
+            .await?;
+
+        Ok(versions
+            .into_iter()
+            .filter(|candidate| candidate.qualified_version.0 < version.0)
+            .max_by(|a, b| {
+                a.qualified_version
+                    .0
+                    .cmp(&b.qualified_version.0)
+            }))
+    }
+
     pub async fn increment_download_count(
         db: &sea_orm::DatabaseConnection,
         version_id: i64,
@@ -129,16 +208,101 @@ impl Version {
         Ok(())
     }
 
+    /// Log one download of `version_id`. The daily totals and breakdown only include it
+    /// once [`Self::compact_downloads`] has run.
+    pub async fn record_download(
+        db: &sea_orm::DatabaseConnection,
+        version_id: i64,
+        event: &DownloadEvent,
+    ) -> Result<()> {
+        let active_model = DownloadLogActiveModel {
+            version: Set(version_id),
+            at: Set(Utc::now()),
+            artifact: Set(event.artifact),
+            client_version: Set(event
+                .client_version
+                .clone()
+                .unwrap_or_default()),
+            origin: Set(event.origin),
+            ..Default::default()
+        };
+
+        DownloadLogEntity::insert(active_model)
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Folds every logged download before `before` into the per-day `downloads` and
+    /// `download_detail` buckets and removes it from the log, returning how many were
+    /// compacted. Runs as one statement, so concurrent compactions never count a
+    /// download twice.
+    pub async fn compact_downloads<C: sea_orm::ConnectionTrait>(
+        db: &C,
+        before: crate::DateTime,
+    ) -> Result<u64> {
+        use sea_orm::{FromQueryResult, Statement};
+
+        #[derive(FromQueryResult)]
+        struct Compacted {
+            compacted: i64,
+        }
+
+        let raw_sql = r#"
+            WITH compacted AS (
+                DELETE FROM download_log
+                WHERE at < $1
+                RETURNING version, (at AT TIME ZONE 'UTC')::date AS day, artifact, client_version, origin
+            ),
+            details AS (
+                INSERT INTO download_detail (version, day, artifact, client_version, origin, count)
+                SELECT version, day, artifact, client_version, origin, COUNT(*)::int
+                FROM compacted
+                GROUP BY version, day, artifact, client_version, origin
+                ON CONFLICT (version, day, artifact, client_version, origin)
+                DO UPDATE SET count = download_detail.count + excluded.count
+            ),
+            totals AS (
+                INSERT INTO downloads (version, day, count)
+                SELECT version, day, COUNT(*)::int
+                FROM compacted
+                GROUP BY version, day
+                ON CONFLICT (version, day)
+                DO UPDATE SET count = downloads.count + excluded.count
+            )
+            SELECT COUNT(*)::bigint AS compacted FROM compacted
+        "#;
+
+        let stmt = Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            raw_sql,
+            vec![before.into()],
+        );
+
+        let compacted = Compacted::find_by_statement(stmt)
+            .one(db)
+            .await?
+            .map_or(0, |row| row.compacted);
+
+        Ok(compacted as u64)
+    }
+
     pub async fn dependents(
         &self,
         db: &sea_orm::DatabaseConnection,
     ) -> Result<Vec<QualifiedPackageVersion>> {
         let results = QualifiedPackageVersion::from_iter_tuple(
             VersionEntity::find()
-                .filter(SimpleExpr::cust_with_values(
-                    "$1 = ANY(dependencies)",
-                    [sea_orm::Value::BigInt(Some(self.id))],
-                ))
+                .filter(
+                    VersionColumn::Id.in_subquery(
+                        sea_orm::sea_query::Query::select()
+                            .column(VersionDependencyColumn::VersionId)
+                            .from(VersionDependencyEntity)
+                            .and_where(VersionDependencyColumn::DependencyId.eq(self.id))
+                            .to_owned(),
+                    ),
+                )
                 .find_also_related(PackageEntity)
                 .order_by(crate::entities::package::Column::Name, Order::Asc)
                 .find_also(VersionEntity, UserEntity)
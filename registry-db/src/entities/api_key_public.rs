@@ -1,7 +1,16 @@
 use super::api_key::Entity as ApiKeyFull;
 use sea_orm::entity::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, DerivePartialModel, Eq, utoipa::ToSchema, serde::Serialize)]
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    DerivePartialModel,
+    Eq,
+    utoipa::ToSchema,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 #[sea_orm(entity = "ApiKeyFull")]
 #[schema(as = ApiKey)]
 pub struct Model {
@@ -0,0 +1,45 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    DeriveEntityModel,
+    Eq,
+    utoipa::ToSchema,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[schema(as = DeclarationItem)]
+#[sea_orm(table_name = "declaration_item")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub version_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub namespace: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+    pub kind: String,
+    #[schema(value_type = Object)]
+    pub declaration: Json,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::version::Entity",
+        from = "Column::VersionId",
+        to = "super::version::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Version,
+}
+
+impl Related<super::version::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Version.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+
+use super::types::{DownloadArtifact, DownloadOrigin};
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    DeriveEntityModel,
+    Eq,
+    utoipa::ToSchema,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[sea_orm(table_name = "download_log")]
+#[schema(as = DownloadLog)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub version: i64,
+    pub at: crate::DateTime,
+    pub artifact: DownloadArtifact,
+    /// Compiler version of the client, empty when unknown
+    pub client_version: String,
+    pub origin: DownloadOrigin,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::version::Entity",
+        from = "Column::Version",
+        to = "super::version::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Version,
+}
+
+impl Related<super::version::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Version.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
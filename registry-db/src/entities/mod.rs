@@ -2,26 +2,39 @@ pub mod prelude;
 
 pub(crate) mod api_key;
 pub mod api_key_public;
+pub mod declaration_item;
+pub mod download_detail;
+pub mod download_log;
 pub mod downloads;
 pub mod org;
 pub mod org_invitation;
 pub mod org_role;
 pub mod package;
 pub mod schema_role;
+pub mod team;
+pub mod team_member;
 pub mod types;
 pub mod user_favourite;
+pub mod user_identity;
 pub mod users;
 pub mod version;
+pub mod version_dependency;
 
 pub use prelude::*;
 
 // Re-export ActiveModel types for tests
 #[cfg(feature = "test")]
 pub use {
+    declaration_item::ActiveModel as DeclarationItemActiveModel,
+    download_detail::ActiveModel as DownloadDetailActiveModel,
+    download_log::ActiveModel as DownloadLogActiveModel,
     downloads::ActiveModel as DownloadsActiveModel, org::ActiveModel as OrgActiveModel,
     org_invitation::ActiveModel as OrgInvitationActiveModel,
     org_role::ActiveModel as OrgRoleActiveModel, package::ActiveModel as PackageActiveModel,
-    schema_role::ActiveModel as SchemaRoleActiveModel,
-    user_favourite::ActiveModel as UserFavouriteActiveModel, users::ActiveModel as UserActiveModel,
+    schema_role::ActiveModel as SchemaRoleActiveModel, team::ActiveModel as TeamActiveModel,
+    team_member::ActiveModel as TeamMemberActiveModel,
+    user_favourite::ActiveModel as UserFavouriteActiveModel,
+    user_identity::ActiveModel as UserIdentityActiveModel, users::ActiveModel as UserActiveModel,
     version::ActiveModel as VersionActiveModel,
+    version_dependency::ActiveModel as VersionDependencyActiveModel,
 };
@@ -32,6 +32,8 @@ pub enum Relation {
     OrgRole,
     #[sea_orm(has_many = "super::schema_role::Entity")]
     SchemaRole,
+    #[sea_orm(has_many = "super::team::Entity")]
+    Team,
     #[sea_orm(has_many = "super::user_favourite::Entity")]
     UserFavourite,
     #[sea_orm(has_many = "super::version::Entity")]
@@ -62,6 +64,12 @@ impl Related<super::schema_role::Entity> for Entity {
     }
 }
 
+impl Related<super::team::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Team.def()
+    }
+}
+
 impl Related<super::user_favourite::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserFavourite.def()
@@ -17,6 +17,9 @@ pub struct Model {
     pub id: i64,
     #[sea_orm(unique)]
     pub name: String,
+    pub deprecated_at: Option<crate::DateTime>,
+    pub deprecation_message: Option<String>,
+    pub deprecation_replacement: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -3,30 +3,44 @@
 // public apis
 
 pub use super::{
+    declaration_item::Entity as DeclarationItemEntity,
+    download_detail::Entity as DownloadDetailEntity,
+    download_log::Entity as DownloadLogEntity,
     downloads::Entity as DownloadsEntity,
     org::Entity as OrgEntity,
     org_invitation::Entity as OrgInvitationEntity,
     org_role::Entity as OrgRoleEntity,
     package::Entity as PackageEntity,
     schema_role::Entity as SchemaRoleEntity,
+    team::Entity as TeamEntity,
+    team_member::Entity as TeamMemberEntity,
     user_favourite::Entity as UserFavouriteEntity,
+    user_identity::Entity as UserIdentityEntity,
     users::Entity as UserEntity,
     //
     version::Entity as VersionEntity,
+    version_dependency::Entity as VersionDependencyEntity,
 };
 
 pub use super::{
     api_key_public::Model as ApiKey,
+    declaration_item::Model as DeclarationItem,
+    download_detail::Model as DownloadDetail,
+    download_log::Model as DownloadLog,
     downloads::Model as Downloads,
     org::Model as Org,
     org_invitation::Model as OrgInvitation,
     org_role::Model as OrgRole,
     package::Model as Package,
     schema_role::Model as SchemaRole,
+    team::Model as Team,
+    team_member::Model as TeamMember,
     user_favourite::Model as UserFavourite,
+    user_identity::Model as UserIdentity,
     users::Model as User,
     //
     version::Model as Version,
+    version_dependency::Model as VersionDependency,
 };
 
 pub use super::types::*;
@@ -34,39 +48,60 @@ pub use super::types::*;
 // private apis
 pub(crate) use super::{
     api_key::Column as ApiKeyColumn,
+    declaration_item::Column as DeclarationItemColumn,
+    download_detail::Column as DownloadDetailColumn,
+    download_log::Column as DownloadLogColumn,
     downloads::Column as DownloadsColumn,
     org::Column as OrgColumn,
     org_invitation::Column as OrgInvitationColumn,
     org_role::Column as OrgRoleColumn,
     package::Column as PackageColumn,
     schema_role::Column as SchemaRoleColumn,
+    team::Column as TeamColumn,
+    team_member::Column as TeamMemberColumn,
     user_favourite::Column as UserFavouriteColumn,
+    user_identity::Column as UserIdentityColumn,
     users::Column as UserColumn,
     //
     version::Column as VersionColumn,
+    version_dependency::Column as VersionDependencyColumn,
 };
 
 pub(crate) use super::{
     api_key::Relation as ApiKeyRelation,
+    declaration_item::Relation as DeclarationItemRelation,
+    download_detail::Relation as DownloadDetailRelation,
+    download_log::Relation as DownloadLogRelation,
     downloads::Relation as DownloadsRelation,
     org::Relation as OrgRelation,
     org_invitation::Relation as OrgInvitationRelation,
     org_role::Relation as OrgRoleRelation,
     package::Relation as PackageRelation,
     schema_role::Relation as SchemaRoleRelation,
+    team::Relation as TeamRelation,
+    team_member::Relation as TeamMemberRelation,
     user_favourite::Relation as UserFavouriteRelation,
+    user_identity::Relation as UserIdentityRelation,
     users::Relation as UserRelation,
     //
     version::Relation as VersionRelation,
+    version_dependency::Relation as VersionDependencyRelation,
 };
 
 pub(crate) use super::{
-    api_key::ActiveModel as ApiKeyActiveModel, downloads::ActiveModel as DownloadsActiveModel,
-    org::ActiveModel as OrgActiveModel, org_invitation::ActiveModel as OrgInvitationActiveModel,
+    api_key::ActiveModel as ApiKeyActiveModel,
+    declaration_item::ActiveModel as DeclarationItemActiveModel,
+    download_detail::ActiveModel as DownloadDetailActiveModel,
+    download_log::ActiveModel as DownloadLogActiveModel,
+    downloads::ActiveModel as DownloadsActiveModel, org::ActiveModel as OrgActiveModel,
+    org_invitation::ActiveModel as OrgInvitationActiveModel,
     org_role::ActiveModel as OrgRoleActiveModel, package::ActiveModel as PackageActiveModel,
-    schema_role::ActiveModel as SchemaRoleActiveModel,
-    user_favourite::ActiveModel as UserFavouriteActiveModel, users::ActiveModel as UserActiveModel,
+    schema_role::ActiveModel as SchemaRoleActiveModel, team::ActiveModel as TeamActiveModel,
+    team_member::ActiveModel as TeamMemberActiveModel,
+    user_favourite::ActiveModel as UserFavouriteActiveModel,
+    user_identity::ActiveModel as UserIdentityActiveModel, users::ActiveModel as UserActiveModel,
     version::ActiveModel as VersionActiveModel,
+    version_dependency::ActiveModel as VersionDependencyActiveModel,
 };
 
 pub(crate) use super::api_key::{Entity as ApiKeyPrivateEntity, Model as ApiKeyPrivate};
@@ -18,13 +18,16 @@ pub struct Model {
     pub id: i64,
     #[sea_orm(
         unique_key = "schema_user_roles_idx",
-        unique_key = "schema_org_roles_idx"
+        unique_key = "schema_org_roles_idx",
+        unique_key = "schema_team_roles_idx"
     )]
     pub package: i64,
     #[sea_orm(unique_key = "schema_user_roles_idx")]
     pub user_id: Option<i64>,
     #[sea_orm(unique_key = "schema_org_roles_idx")]
     pub org_id: Option<i64>,
+    #[sea_orm(unique_key = "schema_team_roles_idx")]
+    pub team_id: Option<i64>,
     pub role: SchemaRoleType,
     pub revoked_at: Option<crate::DateTime>,
 }
@@ -47,6 +50,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Package,
+    #[sea_orm(
+        belongs_to = "super::team::Entity",
+        from = "Column::TeamId",
+        to = "super::team::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Team,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
@@ -69,6 +80,12 @@ impl Related<super::package::Entity> for Entity {
     }
 }
 
+impl Related<super::team::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Team.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
@@ -0,0 +1,73 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    DeriveEntityModel,
+    Eq,
+    utoipa::ToSchema,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[sea_orm(table_name = "team")]
+#[schema(as = Team)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique_key = "team_org_name_idx")]
+    pub org_id: i64,
+    #[sea_orm(unique_key = "team_org_name_idx")]
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: crate::DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::org::Entity",
+        from = "Column::OrgId",
+        to = "super::org::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Org,
+    #[sea_orm(has_many = "super::schema_role::Entity")]
+    SchemaRole,
+    #[sea_orm(has_many = "super::team_member::Entity")]
+    TeamMember,
+}
+
+impl Related<super::org::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Org.def()
+    }
+}
+
+impl Related<super::schema_role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SchemaRole.def()
+    }
+}
+
+impl Related<super::team_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TeamMember.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::team_member::Relation::Users.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(
+            super::team_member::Relation::Team
+                .def()
+                .rev(),
+        )
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
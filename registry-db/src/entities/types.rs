@@ -38,6 +38,74 @@ pub enum SchemaRoleType {
     Author,
 }
 
+/// A member's standing within a team. Maintainers manage the team's membership, while
+/// every member inherits the package roles granted to the team.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    utoipa :: ToSchema,
+    serde :: Serialize,
+    serde :: Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "team_role_type")]
+pub enum TeamRoleType {
+    #[sea_orm(string_value = "maintainer")]
+    Maintainer,
+    #[sea_orm(string_value = "member")]
+    Member,
+}
+
+/// Which stored artifact of a version was downloaded.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    utoipa :: ToSchema,
+    serde :: Serialize,
+    serde :: Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "download_artifact")]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadArtifact {
+    #[sea_orm(string_value = "source")]
+    Source,
+    #[sea_orm(string_value = "declarations")]
+    Declarations,
+}
+
+/// Whether a download came from kintsu tooling resolving dependencies or from any
+/// other client, such as a browser.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    utoipa :: ToSchema,
+    serde :: Serialize,
+    serde :: Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "download_origin")]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadOrigin {
+    #[sea_orm(string_value = "resolver")]
+    Resolver,
+    #[sea_orm(string_value = "human")]
+    Human,
+}
+
 #[derive(
     Debug,
     Clone,
@@ -74,6 +142,10 @@ pub enum Permission {
     CreatePersonalToken,
     #[sea_orm(string_value = "revoke-personal-token")]
     RevokePersonalToken,
+    #[sea_orm(string_value = "manage-team")]
+    ManageTeam,
+    #[sea_orm(string_value = "manage-team-members")]
+    ManageTeamMembers,
 }
 
 impl Permission {
@@ -96,6 +168,8 @@ impl From<&Permission> for &'static str {
             Permission::ListOrgToken => "list-org-token",
             Permission::CreatePersonalToken => "create-personal-token",
             Permission::RevokePersonalToken => "revoke-personal-token",
+            Permission::ManageTeam => "manage-team",
+            Permission::ManageTeamMembers => "manage-team-members",
         }
     }
 }
@@ -17,8 +17,9 @@ pub struct Model {
     pub id: i64,
     #[sea_orm(unique)]
     pub email: String,
+    /// Set for users who have logged in with GitHub
     #[sea_orm(unique)]
-    pub gh_id: i32,
+    pub gh_id: Option<i32>,
     pub gh_login: String,
     pub gh_avatar: Option<String>,
 }
@@ -33,8 +34,12 @@ pub enum Relation {
     OrgRole,
     #[sea_orm(has_many = "super::schema_role::Entity")]
     SchemaRole,
+    #[sea_orm(has_many = "super::team_member::Entity")]
+    TeamMember,
     #[sea_orm(has_many = "super::user_favourite::Entity")]
     UserFavourite,
+    #[sea_orm(has_many = "super::user_identity::Entity")]
+    UserIdentity,
     #[sea_orm(has_many = "super::version::Entity")]
     Version,
 }
@@ -63,12 +68,24 @@ impl Related<super::schema_role::Entity> for Entity {
     }
 }
 
+impl Related<super::team_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TeamMember.def()
+    }
+}
+
 impl Related<super::user_favourite::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserFavourite.def()
     }
 }
 
+impl Related<super::user_identity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserIdentity.def()
+    }
+}
+
 impl Related<super::version::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Version.def()
@@ -27,17 +27,25 @@ pub struct Model {
     pub license: String,
     pub license_text: String,
     pub readme: String,
+    pub readme_checksum: Option<String>,
     pub repository: String,
     pub dependencies: Vec<i64>,
     pub keywords: Vec<String>,
+    #[schema(value_type = Object)]
+    pub metadata: Json,
     pub created_at: crate::DateTime,
     pub yanked_at: Option<crate::DateTime>,
+    pub deprecated_at: Option<crate::DateTime>,
+    pub deprecation_message: Option<String>,
+    pub deprecation_replacement: Option<String>,
     pub publishing_org_id: Option<i64>,
     pub publishing_user_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::download_detail::Entity")]
+    DownloadDetail,
     #[sea_orm(has_many = "super::downloads::Entity")]
     Downloads,
     #[sea_orm(
@@ -66,6 +74,12 @@ pub enum Relation {
     Users,
 }
 
+impl Related<super::download_detail::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DownloadDetail.def()
+    }
+}
+
 impl Related<super::downloads::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Downloads.def()
@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    DeriveEntityModel,
+    Eq,
+    utoipa::ToSchema,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[schema(as = VersionDependency)]
+#[sea_orm(table_name = "version_dependency")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub version_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub dependency_id: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::version::Entity",
+        from = "Column::VersionId",
+        to = "super::version::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Version,
+    #[sea_orm(
+        belongs_to = "super::version::Entity",
+        from = "Column::DependencyId",
+        to = "super::version::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Dependency,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
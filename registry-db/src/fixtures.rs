@@ -19,6 +19,7 @@ use std::sync::atomic::{AtomicI32, AtomicI64, Ordering};
 static USER_COUNTER: AtomicI64 = AtomicI64::new(1);
 static ORG_COUNTER: AtomicI64 = AtomicI64::new(1);
 static PACKAGE_COUNTER: AtomicI64 = AtomicI64::new(1);
+static TEAM_COUNTER: AtomicI64 = AtomicI64::new(1);
 static GH_ID_COUNTER: AtomicI32 = AtomicI32::new(1000);
 
 fn next_user_n() -> i64 {
@@ -98,7 +99,7 @@ impl UserFixture {
             email: Set(self
                 .email
                 .unwrap_or_else(|| format!("test-{}@example.com", n))),
-            gh_id: Set(gh_id),
+            gh_id: Set(Some(gh_id)),
             gh_login: Set(self
                 .gh_login
                 .unwrap_or_else(|| format!("testuser{}", n))),
@@ -204,6 +205,9 @@ impl PackageFixture {
             name: Set(self
                 .name
                 .unwrap_or_else(|| format!("test-package{}", n))),
+            deprecated_at: NotSet,
+            deprecation_message: NotSet,
+            deprecation_replacement: NotSet,
         };
 
         active_model
@@ -368,19 +372,23 @@ impl VersionFixture {
             license: Set(self.license),
             license_text: Set(self.license_text),
             readme: Set(self.readme),
+            readme_checksum: Set(None),
             repository: Set(self.repository),
             dependencies: Set(self.dependencies),
             keywords: Set(self.keywords),
+            metadata: NotSet,
             created_at: Set(Utc::now()),
             yanked_at: Set(None),
+            deprecated_at: NotSet,
+            deprecation_message: NotSet,
+            deprecation_replacement: NotSet,
             publishing_org_id: Set(self.publishing_org_id),
             publishing_user_id: Set(self.publishing_user_id),
         };
 
-        active_model
-            .insert(db)
-            .await
-            .map_err(Into::into)
+        let version = active_model.insert(db).await?;
+        Version::index_dependencies(db, version.id, &version.dependencies).await?;
+        Ok(version)
     }
 }
 
@@ -543,10 +551,111 @@ impl OrgRoleFixture {
     }
 }
 
+pub struct TeamFixture {
+    org_id: i64,
+    name: Option<String>,
+    description: Option<String>,
+}
+
+pub fn team(org_id: i64) -> TeamFixture {
+    TeamFixture {
+        org_id,
+        name: None,
+        description: None,
+    }
+}
+
+impl TeamFixture {
+    pub fn name(
+        mut self,
+        name: &str,
+    ) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn description(
+        mut self,
+        description: &str,
+    ) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub async fn insert(
+        self,
+        db: &DatabaseConnection,
+    ) -> Result<Team> {
+        let n = TEAM_COUNTER.fetch_add(1, Ordering::SeqCst);
+
+        let active_model = TeamActiveModel {
+            id: NotSet,
+            org_id: Set(self.org_id),
+            name: Set(self
+                .name
+                .unwrap_or_else(|| format!("test-team-{}", n))),
+            description: Set(self.description),
+            created_at: NotSet,
+        };
+
+        active_model
+            .insert(db)
+            .await
+            .map_err(Into::into)
+    }
+}
+
+pub struct TeamMemberFixture {
+    team_id: i64,
+    user_id: i64,
+    role: TeamRoleType,
+}
+
+pub fn team_member(
+    team_id: i64,
+    user_id: i64,
+) -> TeamMemberFixture {
+    TeamMemberFixture {
+        team_id,
+        user_id,
+        role: TeamRoleType::Member,
+    }
+}
+
+impl TeamMemberFixture {
+    pub fn maintainer(mut self) -> Self {
+        self.role = TeamRoleType::Maintainer;
+        self
+    }
+
+    pub fn member(mut self) -> Self {
+        self.role = TeamRoleType::Member;
+        self
+    }
+
+    pub async fn insert(
+        self,
+        db: &DatabaseConnection,
+    ) -> Result<TeamMember> {
+        let active_model = TeamMemberActiveModel {
+            team_id: Set(self.team_id),
+            user_id: Set(self.user_id),
+            role: Set(self.role),
+            created_at: NotSet,
+        };
+
+        active_model
+            .insert(db)
+            .await
+            .map_err(Into::into)
+    }
+}
+
 pub struct SchemaRoleFixture {
     package_id: i64,
     user_id: Option<i64>,
     org_id: Option<i64>,
+    team_id: Option<i64>,
     role: SchemaRoleType,
 }
 
@@ -555,6 +664,7 @@ pub fn schema_role(package_id: i64) -> SchemaRoleFixture {
         package_id,
         user_id: None,
         org_id: None,
+        team_id: None,
         role: SchemaRoleType::Admin,
     }
 }
@@ -566,6 +676,7 @@ impl SchemaRoleFixture {
     ) -> Self {
         self.user_id = Some(user_id);
         self.org_id = None;
+        self.team_id = None;
         self
     }
 
@@ -575,6 +686,17 @@ impl SchemaRoleFixture {
     ) -> Self {
         self.org_id = Some(org_id);
         self.user_id = None;
+        self.team_id = None;
+        self
+    }
+
+    pub fn team(
+        mut self,
+        team_id: i64,
+    ) -> Self {
+        self.team_id = Some(team_id);
+        self.user_id = None;
+        self.org_id = None;
         self
     }
 
@@ -604,6 +726,7 @@ impl SchemaRoleFixture {
             package: Set(self.package_id),
             user_id: Set(self.user_id),
             org_id: Set(self.org_id),
+            team_id: Set(self.team_id),
             role: Set(self.role),
             revoked_at: Set(None),
         };
@@ -630,6 +753,7 @@ impl SchemaRoleFixtureRevoked {
             package: Set(self.base.package_id),
             user_id: Set(self.base.user_id),
             org_id: Set(self.base.org_id),
+            team_id: Set(self.base.team_id),
             role: Set(self.base.role),
             revoked_at: Set(Some(self.revoked_at)),
         };
@@ -2,6 +2,7 @@
 
 pub mod engine;
 pub mod entities;
+pub mod migrations;
 pub(crate) mod tokens;
 
 #[cfg(feature = "test")]
@@ -60,6 +61,9 @@ pub enum Error {
 
     #[error("Event error: {0}")]
     EventError(#[from] kintsu_registry_events::Error),
+
+    #[error("Database schema is behind, pending migrations: {}", pending.join(", "))]
+    SchemaBehind { pending: Vec<String> },
 }
 
 impl<E> From<sea_orm::TransactionError<E>> for Error
@@ -0,0 +1,176 @@
+//! Schema migrations, applied with `kintsu-registry migrate`.
+//!
+//! Each migration is a directory under `registry-db/migrations` holding an `up.sql` and a
+//! `down.sql`, listed below in the order they apply. Applied migrations are recorded in the
+//! `seaql_migrations` table.
+
+use sea_orm::{ActiveModelTrait, ConnectionTrait, EntityTrait, PaginatorTrait, Set};
+use sea_orm_migration::{
+    DbErr, MigrationName, MigrationTrait, MigratorTrait, SchemaManager, seaql_migrations,
+};
+
+macro_rules! sql_migrations {
+    ($($migration:ident => $dir:literal),* $(,)?) => {
+        $(
+            struct $migration;
+
+            impl MigrationName for $migration {
+                fn name(&self) -> &str {
+                    $dir
+                }
+            }
+
+            #[sea_orm_migration::async_trait::async_trait]
+            impl MigrationTrait for $migration {
+                async fn up(
+                    &self,
+                    manager: &SchemaManager,
+                ) -> std::result::Result<(), DbErr> {
+                    manager
+                        .get_connection()
+                        .execute_unprepared(include_str!(concat!("../migrations/", $dir, "/up.sql")))
+                        .await?;
+                    Ok(())
+                }
+
+                async fn down(
+                    &self,
+                    manager: &SchemaManager,
+                ) -> std::result::Result<(), DbErr> {
+                    manager
+                        .get_connection()
+                        .execute_unprepared(include_str!(concat!("../migrations/", $dir, "/down.sql")))
+                        .await?;
+                    Ok(())
+                }
+            }
+        )*
+
+        impl MigratorTrait for Migrator {
+            fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+                vec![$(Box::new($migration)),*]
+            }
+        }
+    };
+}
+
+pub struct Migrator;
+
+sql_migrations! {
+    Registry => "0001_registry",
+    PackageMetadata => "0002_package_metadata",
+    DownloadDetails => "0003_download_details",
+    DownloadLog => "0004_download_log",
+    ReadmeChecksum => "0005_readme_checksum",
+    Deprecation => "0006_deprecation",
+    OrgTeams => "0007_org_teams",
+    UserIdentity => "0008_user_identity",
+    DeclarationIndex => "0009_declaration_index",
+    DependencyEdges => "0010_dependency_edges",
+}
+
+/// Applies up to `steps` pending migrations, or all of them.
+pub async fn up(
+    db: &sea_orm::DatabaseConnection,
+    steps: Option<u32>,
+) -> crate::Result<()> {
+    adopt_diesel_history(db).await?;
+    Migrator::up(db, steps).await?;
+    Ok(())
+}
+
+/// Rolls back the last `steps` applied migrations.
+pub async fn down(
+    db: &sea_orm::DatabaseConnection,
+    steps: u32,
+) -> crate::Result<()> {
+    Migrator::down(db, Some(steps)).await?;
+    Ok(())
+}
+
+/// Every migration, in the order they apply, with whether it is applied to `db`.
+pub async fn status<C: ConnectionTrait>(db: &C) -> crate::Result<Vec<(String, bool)>> {
+    Ok(Migrator::get_migration_with_status(db)
+        .await?
+        .iter()
+        .map(|migration| {
+            (
+                migration.name().to_string(),
+                migration.status() == sea_orm_migration::MigrationStatus::Applied,
+            )
+        })
+        .collect())
+}
+
+/// Names of the migrations not yet applied to `db`, in the order they apply.
+pub async fn pending<C: ConnectionTrait>(db: &C) -> crate::Result<Vec<String>> {
+    Ok(Migrator::get_pending_migrations(db)
+        .await?
+        .iter()
+        .map(|migration| migration.name().to_string())
+        .collect())
+}
+
+/// Fails unless every migration is applied, so the registry never serves an outdated schema.
+pub async fn ensure_current<C: ConnectionTrait>(db: &C) -> crate::Result<()> {
+    let pending = pending(db).await?;
+    if !pending.is_empty() {
+        return Err(crate::Error::SchemaBehind { pending });
+    }
+    Ok(())
+}
+
+/// Databases set up before migrations moved here were migrated by the diesel CLI. Its history
+/// is copied over once, so the migrations it applied are not run again.
+async fn adopt_diesel_history<C: ConnectionTrait>(db: &C) -> crate::Result<()> {
+    Migrator::install(db).await?;
+    if seaql_migrations::Entity::find()
+        .count(db)
+        .await?
+        > 0
+    {
+        return Ok(());
+    }
+
+    let backend = db.get_database_backend();
+    let exists = db
+        .query_one_raw(sea_orm::Statement::from_string(
+            backend,
+            "SELECT to_regclass('__diesel_schema_migrations') IS NOT NULL AS adopted",
+        ))
+        .await?
+        .map(|row| row.try_get::<bool>("", "adopted"))
+        .transpose()?
+        .unwrap_or(false);
+    if !exists {
+        return Ok(());
+    }
+
+    let applied = db
+        .query_all_raw(sea_orm::Statement::from_string(
+            backend,
+            "SELECT version FROM __diesel_schema_migrations",
+        ))
+        .await?
+        .into_iter()
+        .map(|row| row.try_get::<String>("", "version"))
+        .collect::<std::result::Result<std::collections::HashSet<_>, _>>()?;
+
+    let now = chrono::Utc::now().timestamp();
+    for migration in Migrator::migrations() {
+        let name = migration.name();
+        let version = name
+            .split_once('_')
+            .map_or(name, |(version, _)| version);
+        if applied.contains(version) {
+            seaql_migrations::ActiveModel {
+                version: Set(name.to_string()),
+                applied_at: Set(now),
+            }
+            .insert(db)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
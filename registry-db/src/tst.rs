@@ -1,4 +1,3 @@
-use sea_orm::ConnectionTrait;
 use testcontainers::ContainerAsync;
 use testcontainers_modules::{postgres, testcontainers::runners::AsyncRunner};
 
@@ -12,8 +11,6 @@ pub struct TestDbCtx {
 
 impl TestDbCtx {
     pub async fn new() -> Self {
-        const UP: &[&str] = &[include_str!("../migrations/0001_registry/up.sql")];
-
         let container = postgres::Postgres::default()
             .pull_image()
             .await
@@ -34,9 +31,9 @@ impl TestDbCtx {
             .await
             .unwrap();
 
-        for up in UP {
-            conn.execute_unprepared(up).await.unwrap();
-        }
+        crate::migrations::up(&conn, None)
+            .await
+            .unwrap();
 
         Self {
             db_url: connection_string.to_string(),
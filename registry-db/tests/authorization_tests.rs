@@ -16,6 +16,7 @@ use kintsu_registry_db::{
     entities::*,
     tst::TestDbCtx,
 };
+use sea_orm::EntityTrait;
 
 async fn create_api_key_principal(
     ctx: &TestDbCtx,
@@ -85,6 +86,62 @@ async fn package_publish_first_time() {
     );
 }
 
+#[tokio::test]
+async fn scoped_package_first_publish_requires_scope_org_admin() {
+    let ctx = TestDbCtx::new().await;
+
+    let admin = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+    let member = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+
+    let org = fixtures::org()
+        .name("acme")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create org");
+    fixtures::org_role(org.id, admin.id)
+        .admin()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to grant admin");
+    fixtures::org_role(org.id, member.id)
+        .member()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to grant membership");
+
+    for (user, package, allowed) in [
+        (&admin, "@acme/billing", true),
+        (&member, "@acme/billing", false),
+        (&admin, "@unclaimed/billing", false),
+    ] {
+        let principal =
+            create_api_key_principal(&ctx, user, vec!["*"], vec![Permission::PublishPackage]).await;
+        let resource = PackageResource {
+            name: package.to_string(),
+            id: None,
+        };
+
+        let result = resource
+            .authorize(&ctx.conn, &principal, Permission::PublishPackage)
+            .await
+            .expect("Authorization failed");
+
+        assert_eq!(result.allowed, allowed, "{package}: {}", result.reason);
+        assert!(
+            result
+                .checks
+                .iter()
+                .any(|c| c.policy == Policy::ScopeOwner && c.passed == allowed)
+        );
+    }
+}
+
 #[tokio::test]
 async fn package_publish_as_admin() {
     let ctx = TestDbCtx::new().await;
@@ -358,6 +415,164 @@ async fn package_yank_not_admin() {
     assert!(!result.allowed);
 }
 
+#[tokio::test]
+async fn package_yank_and_unyank_version() {
+    let ctx = TestDbCtx::new().await;
+
+    let user = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+
+    let pkg = fixtures::package()
+        .name("unyank-pkg")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create package");
+
+    fixtures::schema_role(pkg.id)
+        .user(user.id)
+        .admin()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to grant admin");
+
+    let version = fixtures::version(pkg.id)
+        .version("1.0.0")
+        .publisher_user(user.id)
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create version");
+
+    let principal =
+        create_api_key_principal(&ctx, &user, vec!["*"], vec![Permission::YankPackage]).await;
+
+    let yanked_at = |conn| {
+        async move {
+            VersionEntity::find_by_id(version.id)
+                .one(conn)
+                .await
+                .expect("Lookup failed")
+                .expect("Version not found")
+                .yanked_at
+        }
+    };
+
+    Package::yank_version(&ctx.conn, &principal, "unyank-pkg", "1.0.0")
+        .await
+        .expect("Yank failed");
+    assert!(yanked_at(&ctx.conn).await.is_some());
+
+    Package::unyank_version(&ctx.conn, &principal, "unyank-pkg", "1.0.0")
+        .await
+        .expect("Unyank failed");
+    assert!(yanked_at(&ctx.conn).await.is_none());
+}
+
+#[tokio::test]
+async fn package_deprecate_and_undeprecate() {
+    let ctx = TestDbCtx::new().await;
+
+    let user = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+
+    let pkg = fixtures::package()
+        .name("deprecated-pkg")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create package");
+
+    fixtures::package()
+        .name("successor-pkg")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create package");
+
+    fixtures::schema_role(pkg.id)
+        .user(user.id)
+        .admin()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to grant admin");
+
+    fixtures::version(pkg.id)
+        .version("1.0.0")
+        .publisher_user(user.id)
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create version");
+
+    let principal =
+        create_api_key_principal(&ctx, &user, vec!["*"], vec![Permission::YankPackage]).await;
+
+    let deprecation = |conn| {
+        async move {
+            Version::get_package_version(conn, "deprecated-pkg", "1.0.0")
+                .await
+                .expect("Lookup failed")
+                .deprecation
+        }
+    };
+
+    Package::deprecate(
+        &ctx.conn,
+        &principal,
+        "deprecated-pkg",
+        None,
+        "superseded",
+        Some("successor-pkg"),
+    )
+    .await
+    .expect("Deprecate failed");
+    let found = deprecation(&ctx.conn)
+        .await
+        .expect("Not deprecated");
+    assert_eq!(found.message, "superseded");
+    assert_eq!(found.replacement.as_deref(), Some("successor-pkg"));
+
+    // a version deprecation takes precedence over the package
+    Package::deprecate(
+        &ctx.conn,
+        &principal,
+        "deprecated-pkg",
+        Some("1.0.0"),
+        "broken",
+        None,
+    )
+    .await
+    .expect("Deprecate failed");
+    assert_eq!(
+        deprecation(&ctx.conn)
+            .await
+            .map(|d| d.message),
+        Some("broken".to_string())
+    );
+
+    Package::undeprecate(&ctx.conn, &principal, "deprecated-pkg", Some("1.0.0"))
+        .await
+        .expect("Undeprecate failed");
+    Package::undeprecate(&ctx.conn, &principal, "deprecated-pkg", None)
+        .await
+        .expect("Undeprecate failed");
+    assert!(deprecation(&ctx.conn).await.is_none());
+
+    let missing = Package::deprecate(
+        &ctx.conn,
+        &principal,
+        "deprecated-pkg",
+        None,
+        "superseded",
+        Some("missing-pkg"),
+    )
+    .await;
+    assert!(matches!(
+        missing,
+        Err(kintsu_registry_db::Error::NotFound(_))
+    ));
+}
+
 #[tokio::test]
 async fn package_grant_role_as_admin() {
     let ctx = TestDbCtx::new().await;
@@ -3,7 +3,6 @@
 //! ScenarioBuilder orchestrates high-level test scenarios involving
 //! multiple related entities and their relationships.
 
-use chrono::{Duration, Utc};
 use kintsu_registry_db::{
     PackageStorage, Result,
     engine::{OneTimeApiKey, OwnerId, PrincipalIdentity},
@@ -272,7 +271,7 @@ mod tests {
         let user = User {
             id: 1,
             email: "test@example.com".to_string(),
-            gh_id: 123,
+            gh_id: Some(123),
             gh_login: "test".to_string(),
             gh_avatar: None,
         };
@@ -1,6 +1,8 @@
 // Common test utilities for registry-db integration tests
 // Re-exports fixtures and builders for use in test modules
 
+#![allow(dead_code, unused_imports)]
+
 pub mod builders;
 pub mod fixtures;
 
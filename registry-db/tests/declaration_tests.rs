@@ -0,0 +1,157 @@
+//! Declaration Index Tests
+//!
+//! Tests for registry-db/src/engine/declaration.rs
+//! Covers indexing declarations and querying single items, including versions indexed lazily.
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::fixtures;
+use kintsu_parser::declare::{
+    Builtin, DeclComment, DeclConst, DeclConstValue, DeclNamespace, DeclarationBundle,
+    DeclarationVersion, TypeRegistryDeclaration, index::DeclaredItem,
+};
+use kintsu_registry_db::{
+    Error, PackageStorage, engine::DeclaredItemSummary, entities::*, tst::TestDbCtx,
+};
+use kintsu_registry_storage::disk::{DiskConfig, LocalDiskStorage};
+
+fn constant(name: &str) -> DeclConst {
+    DeclConst {
+        name: name.to_string(),
+        ty: Builtin::I32,
+        value: DeclConstValue::Int(1),
+        comments: DeclComment::default(),
+    }
+}
+
+fn declarations() -> DeclarationVersion {
+    let v1 = DeclNamespace {
+        name: "v1".into(),
+        version: None,
+        error: None,
+        types: vec![],
+        constants: vec![constant("PAGE")],
+        namespaces: BTreeMap::new(),
+        comments: DeclComment::default(),
+    };
+    let root = DeclNamespace {
+        name: "abc".into(),
+        version: None,
+        error: None,
+        types: vec![],
+        constants: vec![constant("LIMIT")],
+        namespaces: BTreeMap::from([("v1".to_string(), Box::new(v1))]),
+        comments: DeclComment::default(),
+    };
+
+    let mut package = TypeRegistryDeclaration::new("abc".into());
+    package
+        .namespaces
+        .insert(root.name.clone(), root);
+    DeclarationVersion::V1(DeclarationBundle {
+        root: package,
+        dependencies: BTreeMap::new(),
+    })
+}
+
+fn storage(root: &tempfile::TempDir) -> PackageStorage {
+    LocalDiskStorage::<DeclarationVersion>::managed(&DiskConfig {
+        root: root.path().to_path_buf(),
+        declarations_encoding: Default::default(),
+    })
+}
+
+#[tokio::test]
+async fn index_and_get_item() {
+    let ctx = TestDbCtx::new().await;
+    let root = tempfile::tempdir().unwrap();
+    let storage = storage(&root);
+
+    let user = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+    let pkg = fixtures::package()
+        .name("abc")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create package");
+    let ver = fixtures::version(pkg.id)
+        .version("1.0.0")
+        .publisher_user(user.id)
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create version");
+
+    DeclarationItem::index(&ctx.conn, ver.id, &declarations())
+        .await
+        .expect("Indexing failed");
+    // indexing again keeps the items already stored
+    DeclarationItem::index(&ctx.conn, ver.id, &declarations())
+        .await
+        .expect("Reindexing failed");
+
+    let item = DeclarationItem::get(&ctx.conn, &storage, "abc", &ver, "abc::v1", "PAGE")
+        .await
+        .expect("Lookup failed");
+    assert_eq!(item, DeclaredItem::Constant(constant("PAGE")));
+
+    let missing = DeclarationItem::get(&ctx.conn, &storage, "abc", &ver, "abc", "PAGE").await;
+    assert!(matches!(missing, Err(Error::NotFound(_))));
+}
+
+#[tokio::test]
+async fn backfills_unindexed_versions() {
+    let ctx = TestDbCtx::new().await;
+    let root = tempfile::tempdir().unwrap();
+    let storage = storage(&root);
+
+    let checksums = storage
+        .store_package(
+            "abc",
+            "1.0.0",
+            &kintsu_fs::memory::MemoryFileSystem::with_files([("schema/lib.ks", "")]),
+            &declarations(),
+        )
+        .await
+        .expect("Failed to store package");
+
+    let user = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+    let pkg = fixtures::package()
+        .name("abc")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create package");
+    let ver = fixtures::version(pkg.id)
+        .version("1.0.0")
+        .declarations_checksum(checksums.declarations_checksum.value())
+        .publisher_user(user.id)
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create version");
+
+    let items = DeclarationItem::list(&ctx.conn, &storage, "abc", &ver)
+        .await
+        .expect("Listing failed");
+
+    assert_eq!(
+        items,
+        vec![
+            DeclaredItemSummary {
+                namespace: "abc".into(),
+                name: "LIMIT".into(),
+                kind: "constant".into(),
+            },
+            DeclaredItemSummary {
+                namespace: "abc::v1".into(),
+                name: "PAGE".into(),
+                kind: "constant".into(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,45 @@
+//! Migration Tests
+//!
+//! Tests for registry-db/src/migrations.rs
+//! Covers applying, rolling back and reapplying the schema migrations.
+
+use kintsu_registry_db::{migrations, tst::TestDbCtx};
+
+#[tokio::test]
+async fn test_ctx_is_current() {
+    let ctx = TestDbCtx::new().await;
+
+    migrations::ensure_current(&ctx.conn)
+        .await
+        .expect("Test database should be fully migrated");
+}
+
+#[tokio::test]
+async fn migrations_roll_back_and_reapply() {
+    let ctx = TestDbCtx::new().await;
+    let total = migrations::status(&ctx.conn)
+        .await
+        .expect("Failed to list migrations")
+        .len() as u32;
+
+    for steps in 1..=total {
+        migrations::down(&ctx.conn, steps)
+            .await
+            .expect("Failed to roll back migrations");
+        assert_eq!(
+            migrations::pending(&ctx.conn)
+                .await
+                .expect("Failed to list pending migrations")
+                .len() as u32,
+            steps
+        );
+
+        migrations::up(&ctx.conn, None)
+            .await
+            .expect("Failed to reapply migrations");
+    }
+
+    migrations::ensure_current(&ctx.conn)
+        .await
+        .expect("Database should be fully migrated");
+}
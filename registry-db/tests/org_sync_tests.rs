@@ -0,0 +1,202 @@
+mod common;
+
+use common::fixtures;
+use kintsu_registry_db::{
+    engine::{
+        PrincipalIdentity,
+        org_sync::{GhOrgMember, MembershipChange, authorize_sync, sync_members},
+    },
+    entities::*,
+    tst::TestDbCtx,
+};
+
+fn member(
+    user: &User,
+    role: OrgRoleType,
+) -> GhOrgMember {
+    GhOrgMember {
+        gh_id: user
+            .gh_id
+            .expect("fixture users have a gh_id"),
+        role,
+    }
+}
+
+#[tokio::test]
+async fn sync_grants_changes_and_revokes_roles() {
+    let ctx = TestDbCtx::new().await;
+
+    let owner = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    let promoted = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    let departed = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    let joined = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+
+    let org = fixtures::org()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    fixtures::org_role(org.id, owner.id)
+        .admin()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    fixtures::org_role(org.id, promoted.id)
+        .member()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    fixtures::org_role(org.id, departed.id)
+        .member()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+
+    let members = [
+        member(&owner, OrgRoleType::Admin),
+        member(&promoted, OrgRoleType::Admin),
+        member(&joined, OrgRoleType::Member),
+        // a GitHub member without a registry account is skipped
+        GhOrgMember {
+            gh_id: i32::MAX,
+            role: OrgRoleType::Member,
+        },
+    ];
+
+    let changes = sync_members(&ctx.conn, None, org.id, &members)
+        .await
+        .expect("Failed to sync members");
+
+    assert_eq!(changes.len(), 3);
+    assert!(changes.contains(&MembershipChange::RoleChanged {
+        user_id: promoted.id,
+        from: OrgRoleType::Member,
+        to: OrgRoleType::Admin,
+    }));
+    assert!(changes.contains(&MembershipChange::Joined {
+        user_id: joined.id,
+        role: OrgRoleType::Member,
+    }));
+    assert!(changes.contains(&MembershipChange::Left {
+        user_id: departed.id
+    }));
+
+    assert!(
+        org.is_user_admin(&ctx.conn, promoted.id)
+            .await
+            .unwrap()
+    );
+
+    let again = sync_members(&ctx.conn, None, org.id, &members)
+        .await
+        .expect("Failed to sync members");
+    assert!(again.is_empty());
+
+    // a departed member who comes back gets their role restored
+    let rejoined = sync_members(
+        &ctx.conn,
+        None,
+        org.id,
+        &[
+            member(&owner, OrgRoleType::Admin),
+            member(&promoted, OrgRoleType::Admin),
+            member(&joined, OrgRoleType::Member),
+            member(&departed, OrgRoleType::Admin),
+        ],
+    )
+    .await
+    .expect("Failed to sync members");
+    assert_eq!(
+        rejoined,
+        [MembershipChange::Joined {
+            user_id: departed.id,
+            role: OrgRoleType::Admin,
+        }]
+    );
+    assert!(
+        org.is_user_admin(&ctx.conn, departed.id)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn sync_refuses_empty_member_list() {
+    let ctx = TestDbCtx::new().await;
+
+    let owner = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    let org = fixtures::org()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    fixtures::org_role(org.id, owner.id)
+        .admin()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+
+    assert!(
+        sync_members(&ctx.conn, None, org.id, &[])
+            .await
+            .is_err()
+    );
+    assert!(
+        org.is_user_admin(&ctx.conn, owner.id)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn only_admins_may_sync() {
+    let ctx = TestDbCtx::new().await;
+
+    let admin = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    let plain = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    let org = fixtures::org()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    fixtures::org_role(org.id, admin.id)
+        .admin()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+    fixtures::org_role(org.id, plain.id)
+        .member()
+        .insert(&ctx.conn)
+        .await
+        .unwrap();
+
+    let admin = PrincipalIdentity::UserSession { user: admin };
+    let plain = PrincipalIdentity::UserSession { user: plain };
+
+    authorize_sync(&ctx.conn, &admin, org.id)
+        .await
+        .expect("Admin should be able to sync");
+    assert!(
+        authorize_sync(&ctx.conn, &plain, org.id)
+            .await
+            .is_err()
+    );
+}
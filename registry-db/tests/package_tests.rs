@@ -4,8 +4,6 @@ use common::fixtures;
 use kintsu_registry_db::{
     engine::{
         Entity as EngineEntity, OrderDirection, PackageOrdering, PackageOrderingField, Page,
-        Paginated, PrincipalIdentity,
-        package::{DownloadHistory, StagePublishPackage},
     },
     entities::*,
     tst::TestDbCtx,
@@ -116,7 +114,7 @@ async fn list_packages_paginated() {
         number: 1,
         size: 10,
     };
-    let result1 = Package::list_packages(&ctx.conn, page1, ordering.clone())
+    let result1 = Package::list_packages(&ctx.conn, page1, ordering)
         .await
         .expect("List failed");
 
@@ -95,6 +95,7 @@ async fn grant_role_user_success() {
         "grant-test-pkg",
         Some(grantee.id),
         None,
+        None,
         SchemaRoleType::Admin,
     )
     .await
@@ -150,6 +151,7 @@ async fn grant_role_org_success() {
         "org-grant-pkg",
         None,
         Some(org.id),
+        None,
         SchemaRoleType::Admin,
     )
     .await
@@ -196,6 +198,7 @@ async fn grant_role_requires_one_owner_both() {
         "both-owner-pkg",
         Some(user.id),
         Some(org.id),
+        None,
         SchemaRoleType::Admin,
     )
     .await;
@@ -235,6 +238,7 @@ async fn grant_role_requires_one_owner_neither() {
         "neither-owner-pkg",
         None,
         None,
+        None,
         SchemaRoleType::Admin,
     )
     .await;
@@ -260,6 +264,7 @@ async fn grant_role_package_not_found() {
         "nonexistent-package",
         Some(user.id),
         None,
+        None,
         SchemaRoleType::Admin,
     )
     .await;
@@ -312,6 +317,7 @@ async fn grant_role_unauthorized() {
         "unauth-pkg",
         Some(attacker.id),
         None,
+        None,
         SchemaRoleType::Admin,
     )
     .await;
@@ -363,6 +369,7 @@ async fn grant_role_duplicate() {
         "dup-grant-pkg",
         Some(grantee.id),
         None,
+        None,
         SchemaRoleType::Admin,
     )
     .await
@@ -375,6 +382,7 @@ async fn grant_role_duplicate() {
         "dup-grant-pkg",
         Some(grantee.id),
         None,
+        None,
         SchemaRoleType::Admin,
     )
     .await;
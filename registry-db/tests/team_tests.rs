@@ -0,0 +1,374 @@
+//! Team Engine Tests
+//!
+//! Tests for registry-db/src/engine/team.rs
+//! Covers team management, membership and package roles inherited through teams.
+
+mod common;
+
+use common::fixtures;
+use kintsu_registry_db::{
+    Error,
+    engine::{
+        PrincipalIdentity,
+        fluent::AuthCheck,
+        schema_role::grant_role,
+        team::{add_member, create_team, delete_team, remove_member, update_team},
+    },
+    entities::*,
+    tst::TestDbCtx,
+};
+use sea_orm::EntityTrait;
+
+struct TeamCtx {
+    ctx: TestDbCtx,
+    org: Org,
+    admin: User,
+    team: Team,
+}
+
+async fn team_ctx() -> TeamCtx {
+    let ctx = TestDbCtx::new().await;
+
+    let org = fixtures::org()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create org");
+
+    let admin = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create admin");
+
+    fixtures::org_role(org.id, admin.id)
+        .admin()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to grant org admin");
+
+    let team = fixtures::team(org.id)
+        .name("platform")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create team");
+
+    TeamCtx {
+        ctx,
+        org,
+        admin,
+        team,
+    }
+}
+
+async fn org_member(
+    ctx: &TeamCtx,
+    team_role: Option<TeamRoleType>,
+) -> User {
+    let user = fixtures::user()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to create user");
+
+    fixtures::org_role(ctx.org.id, user.id)
+        .member()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to grant org membership");
+
+    let member = fixtures::team_member(ctx.team.id, user.id);
+    let member = match team_role {
+        Some(TeamRoleType::Maintainer) => member.maintainer(),
+        Some(TeamRoleType::Member) => member.member(),
+        None => return user,
+    };
+    member
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to add team member");
+
+    user
+}
+
+fn session(user: &User) -> PrincipalIdentity {
+    PrincipalIdentity::UserSession { user: user.clone() }
+}
+
+#[tokio::test]
+async fn team_members_inherit_package_roles() {
+    let ctx = team_ctx().await;
+    let member = org_member(&ctx, Some(TeamRoleType::Member)).await;
+    let outsider = org_member(&ctx, None).await;
+
+    let pkg = fixtures::package()
+        .name("team-owned-pkg")
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to create package");
+
+    fixtures::schema_role(pkg.id)
+        .team(ctx.team.id)
+        .admin()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to grant team role");
+
+    let member_session = session(&member);
+    let result = AuthCheck::new(&ctx.ctx.conn, &member_session)
+        .package(&pkg.name, Some(pkg.id))
+        .can_grant_role()
+        .await
+        .expect("Authorization check failed");
+    assert!(result.allowed, "{}", result.reason);
+
+    let outsider_session = session(&outsider);
+    let result = AuthCheck::new(&ctx.ctx.conn, &outsider_session)
+        .package(&pkg.name, Some(pkg.id))
+        .can_grant_role()
+        .await
+        .expect("Authorization check failed");
+    assert!(!result.allowed);
+}
+
+#[tokio::test]
+async fn team_roles_lapse_when_leaving_org() {
+    let ctx = team_ctx().await;
+
+    // on the team, but never joined the org
+    let user = fixtures::user()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to create user");
+    fixtures::team_member(ctx.team.id, user.id)
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to add team member");
+
+    let pkg = fixtures::package()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to create package");
+    fixtures::schema_role(pkg.id)
+        .team(ctx.team.id)
+        .admin()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to grant team role");
+
+    let principal = session(&user);
+    let result = AuthCheck::new(&ctx.ctx.conn, &principal)
+        .package(&pkg.name, Some(pkg.id))
+        .can_grant_role()
+        .await
+        .expect("Authorization check failed");
+    assert!(!result.allowed);
+}
+
+#[tokio::test]
+async fn grant_package_role_to_team() {
+    let ctx = team_ctx().await;
+
+    let pkg = fixtures::package()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to create package");
+    fixtures::schema_role(pkg.id)
+        .user(ctx.admin.id)
+        .admin()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to grant admin");
+
+    let role = grant_role(
+        &ctx.ctx.conn,
+        &session(&ctx.admin),
+        &pkg.name,
+        None,
+        None,
+        Some(ctx.team.id),
+        SchemaRoleType::Author,
+    )
+    .await
+    .expect("Failed to grant team role");
+
+    assert_eq!(role.team_id, Some(ctx.team.id));
+    assert_eq!(role.user_id, None);
+    assert_eq!(role.org_id, None);
+
+    let result = grant_role(
+        &ctx.ctx.conn,
+        &session(&ctx.admin),
+        &pkg.name,
+        None,
+        Some(ctx.org.id),
+        Some(ctx.team.id),
+        SchemaRoleType::Author,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Validation(_))));
+}
+
+#[tokio::test]
+async fn only_org_admins_manage_teams() {
+    let ctx = team_ctx().await;
+    let maintainer = org_member(&ctx, Some(TeamRoleType::Maintainer)).await;
+
+    let result = create_team(
+        &ctx.ctx.conn,
+        &session(&maintainer),
+        ctx.org.id,
+        "security".into(),
+        None,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::AuthorizationDenied(_))));
+
+    let result = update_team(
+        &ctx.ctx.conn,
+        &session(&maintainer),
+        ctx.org.id,
+        ctx.team.id,
+        Some("renamed".into()),
+        None,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::AuthorizationDenied(_))));
+
+    let team = create_team(
+        &ctx.ctx.conn,
+        &session(&ctx.admin),
+        ctx.org.id,
+        "security".into(),
+        Some("Reviews security sensitive packages".into()),
+    )
+    .await
+    .expect("Failed to create team");
+    assert_eq!(team.org_id, ctx.org.id);
+    assert_eq!(team.name, "security");
+
+    let result = create_team(
+        &ctx.ctx.conn,
+        &session(&ctx.admin),
+        ctx.org.id,
+        "security".into(),
+        None,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
+}
+
+#[tokio::test]
+async fn maintainers_manage_membership() {
+    let ctx = team_ctx().await;
+    let maintainer = org_member(&ctx, Some(TeamRoleType::Maintainer)).await;
+    let member = org_member(&ctx, Some(TeamRoleType::Member)).await;
+    let newcomer = org_member(&ctx, None).await;
+
+    let added = add_member(
+        &ctx.ctx.conn,
+        &session(&maintainer),
+        ctx.org.id,
+        ctx.team.id,
+        newcomer.id,
+        TeamRoleType::Member,
+    )
+    .await
+    .expect("Maintainer should add members");
+    assert_eq!(added.role, TeamRoleType::Member);
+
+    // adding again changes the role
+    let promoted = add_member(
+        &ctx.ctx.conn,
+        &session(&ctx.admin),
+        ctx.org.id,
+        ctx.team.id,
+        newcomer.id,
+        TeamRoleType::Maintainer,
+    )
+    .await
+    .expect("Admin should change roles");
+    assert_eq!(promoted.role, TeamRoleType::Maintainer);
+
+    let result = remove_member(
+        &ctx.ctx.conn,
+        &session(&member),
+        ctx.org.id,
+        ctx.team.id,
+        newcomer.id,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::AuthorizationDenied(_))));
+
+    remove_member(
+        &ctx.ctx.conn,
+        &session(&maintainer),
+        ctx.org.id,
+        ctx.team.id,
+        newcomer.id,
+    )
+    .await
+    .expect("Maintainer should remove members");
+
+    assert!(
+        TeamMemberEntity::find_by_id((ctx.team.id, newcomer.id))
+            .one(&ctx.ctx.conn)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn members_must_belong_to_org() {
+    let ctx = team_ctx().await;
+
+    let stranger = fixtures::user()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to create user");
+
+    let result = add_member(
+        &ctx.ctx.conn,
+        &session(&ctx.admin),
+        ctx.org.id,
+        ctx.team.id,
+        stranger.id,
+        TeamRoleType::Member,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Validation(_))));
+}
+
+#[tokio::test]
+async fn deleting_team_drops_its_roles() {
+    let ctx = team_ctx().await;
+    org_member(&ctx, Some(TeamRoleType::Member)).await;
+
+    let pkg = fixtures::package()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to create package");
+    let role = fixtures::schema_role(pkg.id)
+        .team(ctx.team.id)
+        .admin()
+        .insert(&ctx.ctx.conn)
+        .await
+        .expect("Failed to grant team role");
+
+    delete_team(&ctx.ctx.conn, &session(&ctx.admin), ctx.org.id, ctx.team.id)
+        .await
+        .expect("Failed to delete team");
+
+    assert!(
+        TeamEntity::find_by_id(ctx.team.id)
+            .one(&ctx.ctx.conn)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        SchemaRoleEntity::find_by_id(role.id)
+            .one(&ctx.ctx.conn)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
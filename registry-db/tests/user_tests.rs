@@ -11,27 +11,59 @@ use kintsu_registry_db::{
     Error,
     engine::{
         PrincipalIdentity,
-        user::{NewUser, create_or_update_user_from_oauth},
+        user::{ExternalIdentity, login_with_identity},
     },
     entities::*,
     tst::TestDbCtx,
 };
 
+fn github(
+    gh_id: i32,
+    login: &str,
+    avatar: Option<&str>,
+    email: &str,
+) -> ExternalIdentity {
+    ExternalIdentity {
+        provider: "github".into(),
+        subject: gh_id.to_string(),
+        email: email.into(),
+        login: login.into(),
+        avatar: avatar.map(Into::into),
+        gh_id: Some(gh_id),
+    }
+}
+
+fn oidc(
+    subject: &str,
+    email: &str,
+) -> ExternalIdentity {
+    ExternalIdentity {
+        provider: "corp".into(),
+        subject: subject.into(),
+        email: email.into(),
+        login: email.into(),
+        avatar: None,
+        gh_id: None,
+    }
+}
+
 #[tokio::test]
 async fn create_new_user_success() {
     let ctx = TestDbCtx::new().await;
 
-    let user = create_or_update_user_from_oauth(
+    let user = login_with_identity(
         &ctx.conn,
-        12345,
-        "alice",
-        Some("https://github.com/alice.png"),
-        "alice@example.com",
+        github(
+            12345,
+            "alice",
+            Some("https://github.com/alice.png"),
+            "alice@example.com",
+        ),
     )
     .await
     .expect("Failed to create user");
 
-    assert_eq!(user.gh_id, 12345);
+    assert_eq!(user.gh_id, Some(12345));
     assert_eq!(user.gh_login, "alice");
     assert_eq!(user.email, "alice@example.com");
     assert_eq!(
@@ -39,37 +71,49 @@ async fn create_new_user_success() {
         Some("https://github.com/alice.png".to_string())
     );
     assert!(user.id > 0);
+
+    let identities = user
+        .identities(&ctx.conn)
+        .await
+        .expect("Failed to list identities");
+    assert_eq!(identities.len(), 1);
+    assert_eq!(identities[0].provider, "github");
+    assert_eq!(identities[0].subject, "12345");
 }
 
 #[tokio::test]
-async fn update_existing_user_on_conflict() {
+async fn update_existing_user_on_login() {
     let ctx = TestDbCtx::new().await;
 
     // Create initial user
-    let user1 = create_or_update_user_from_oauth(
+    let user1 = login_with_identity(
         &ctx.conn,
-        123,
-        "old_login",
-        Some("https://old-avatar.png"),
-        "old@example.com",
+        github(
+            123,
+            "old_login",
+            Some("https://old-avatar.png"),
+            "old@example.com",
+        ),
     )
     .await
     .expect("Failed to create user");
 
     let original_id = user1.id;
 
-    // Update with same gh_id but different fields
-    let user2 = create_or_update_user_from_oauth(
+    // Log in again with the same identity but different profile
+    let user2 = login_with_identity(
         &ctx.conn,
-        123,
-        "new_login",
-        Some("https://new-avatar.png"),
-        "new@example.com",
+        github(
+            123,
+            "new_login",
+            Some("https://new-avatar.png"),
+            "new@example.com",
+        ),
     )
     .await
     .expect("Failed to update user");
 
-    // ID should remain the same (upsert)
+    // ID should remain the same
     assert_eq!(user2.id, original_id);
     // Fields should be updated
     assert_eq!(user2.gh_login, "new_login");
@@ -82,17 +126,45 @@ async fn duplicate_email_different_gh_id() {
     let ctx = TestDbCtx::new().await;
 
     // Create first user
-    let _user1 =
-        create_or_update_user_from_oauth(&ctx.conn, 100, "user1", None, "shared@example.com")
-            .await
-            .expect("Failed to create user1");
+    let _user1 = login_with_identity(&ctx.conn, github(100, "user1", None, "shared@example.com"))
+        .await
+        .expect("Failed to create user1");
 
     // Attempt to create second user with same email but different gh_id
     let result =
-        create_or_update_user_from_oauth(&ctx.conn, 200, "user2", None, "shared@example.com").await;
+        login_with_identity(&ctx.conn, github(200, "user2", None, "shared@example.com")).await;
 
-    // Should fail due to unique email constraint
-    assert!(result.is_err());
+    assert!(matches!(result, Err(Error::Conflict(_))));
+}
+
+#[tokio::test]
+async fn oidc_login_creates_user_without_gh_id() {
+    let ctx = TestDbCtx::new().await;
+
+    let user = login_with_identity(&ctx.conn, oidc("abc-123", "jane@corp.example"))
+        .await
+        .expect("Failed to create user");
+
+    assert_eq!(user.gh_id, None);
+    assert_eq!(user.email, "jane@corp.example");
+
+    let again = login_with_identity(&ctx.conn, oidc("abc-123", "jane@corp.example"))
+        .await
+        .expect("Failed to log in again");
+    assert_eq!(again.id, user.id);
+}
+
+#[tokio::test]
+async fn identities_are_not_linked_by_email() {
+    let ctx = TestDbCtx::new().await;
+
+    login_with_identity(&ctx.conn, github(300, "jane", None, "jane@corp.example"))
+        .await
+        .expect("Failed to create user");
+
+    let result = login_with_identity(&ctx.conn, oidc("abc-123", "jane@corp.example")).await;
+
+    assert!(matches!(result, Err(Error::Conflict(_))));
 }
 
 #[tokio::test]
@@ -144,7 +216,7 @@ async fn lookup_by_gh_id() {
     assert!(found.is_some());
     let user = found.unwrap();
     assert_eq!(user.id, created.id);
-    assert_eq!(user.gh_id, 999);
+    assert_eq!(user.gh_id, Some(999));
 }
 
 #[tokio::test]
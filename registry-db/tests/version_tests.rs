@@ -1,12 +1,12 @@
 //! Version Engine Tests
 //!
 //! Tests for registry-db/src/engine/version.rs
-//! Covers version lookup, latest versions, downloads, dependencies, dependents.
+//! Covers version lookup, latest versions, downloads, dependencies, dependents, dependency graphs.
 
 mod common;
 
 use common::fixtures;
-use kintsu_registry_db::{engine::version::QualifiedPackageVersion, entities::*, tst::TestDbCtx};
+use kintsu_registry_db::{engine::version::DownloadEvent, entities::*, tst::TestDbCtx};
 
 #[tokio::test]
 async fn lookup_by_id_found() {
@@ -428,6 +428,195 @@ async fn increment_existing_download() {
     assert_eq!(count, 5);
 }
 
+#[tokio::test]
+async fn record_download_breakdown() {
+    let ctx = TestDbCtx::new().await;
+
+    let user = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+
+    let pkg = fixtures::package()
+        .name("breakdown-download-pkg")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create package");
+
+    let ver = fixtures::version(pkg.id)
+        .version("1.0.0")
+        .publisher_user(user.id)
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create version");
+
+    let events = [
+        (
+            DownloadArtifact::Declarations,
+            DownloadOrigin::Resolver,
+            Some("0.2.0"),
+        ),
+        (
+            DownloadArtifact::Declarations,
+            DownloadOrigin::Resolver,
+            Some("0.10.0"),
+        ),
+        (
+            DownloadArtifact::Declarations,
+            DownloadOrigin::Resolver,
+            Some("0.10.0"),
+        ),
+        (DownloadArtifact::Source, DownloadOrigin::Resolver, None),
+        (DownloadArtifact::Source, DownloadOrigin::Human, None),
+    ];
+    for (artifact, origin, client_version) in events {
+        let event = DownloadEvent {
+            artifact,
+            origin,
+            client_version: client_version.map(str::to_string),
+        };
+        Version::record_download(&ctx.conn, ver.id, &event)
+            .await
+            .expect("Failed to record download");
+    }
+
+    let compacted = Version::compact_downloads(&ctx.conn, chrono::Utc::now())
+        .await
+        .expect("Failed to compact downloads");
+    assert_eq!(compacted, 5);
+
+    let count = Package::get_package_download_count(&ctx.conn, "breakdown-download-pkg")
+        .await
+        .expect("Failed to get count");
+    assert_eq!(count, 5);
+
+    let breakdown = Package::package_download_breakdown(&ctx.conn, "breakdown-download-pkg", None)
+        .await
+        .expect("Failed to get breakdown");
+    assert_eq!(breakdown.days, 90);
+    let artifacts: Vec<_> = breakdown
+        .artifacts
+        .iter()
+        .map(|a| (a.artifact, a.count))
+        .collect();
+    assert_eq!(
+        artifacts,
+        vec![
+            (DownloadArtifact::Declarations, 3),
+            (DownloadArtifact::Source, 2)
+        ]
+    );
+    let origins: Vec<_> = breakdown
+        .origins
+        .iter()
+        .map(|o| (o.origin, o.count))
+        .collect();
+    assert_eq!(
+        origins,
+        vec![(DownloadOrigin::Resolver, 4), (DownloadOrigin::Human, 1)]
+    );
+
+    let clients = Package::package_client_downloads(&ctx.conn, "breakdown-download-pkg", None)
+        .await
+        .expect("Failed to get client downloads");
+    let clients: Vec<_> = clients
+        .iter()
+        .map(|c| (c.client_version.as_deref(), c.count))
+        .collect();
+    assert_eq!(
+        clients,
+        vec![(Some("0.10.0"), 2), (Some("0.2.0"), 1), (None, 1)]
+    );
+
+    assert!(
+        Package::package_client_downloads(&ctx.conn, "breakdown-download-pkg", Some(0))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn compact_downloads_merges_into_buckets() {
+    let ctx = TestDbCtx::new().await;
+
+    let user = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+
+    let pkg = fixtures::package()
+        .name("compact-download-pkg")
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create package");
+
+    let v1 = fixtures::version(pkg.id)
+        .version("1.0.0")
+        .publisher_user(user.id)
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create v1");
+
+    let v2 = fixtures::version(pkg.id)
+        .version("1.1.0")
+        .publisher_user(user.id)
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create v2");
+
+    // already compacted downloads of today
+    fixtures::downloads(v1.id, 10)
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to add v1 downloads");
+
+    let event = DownloadEvent {
+        artifact: DownloadArtifact::Source,
+        origin: DownloadOrigin::Human,
+        client_version: None,
+    };
+    for version in [v1.id, v2.id, v2.id] {
+        Version::record_download(&ctx.conn, version, &event)
+            .await
+            .expect("Failed to record download");
+    }
+
+    let before = Package::package_downloads(&ctx.conn, "compact-download-pkg", None)
+        .await
+        .expect("Failed to get downloads");
+    assert_eq!(before.total, 10);
+
+    let compacted = Version::compact_downloads(&ctx.conn, chrono::Utc::now())
+        .await
+        .expect("Failed to compact downloads");
+    assert_eq!(compacted, 3);
+
+    let again = Version::compact_downloads(&ctx.conn, chrono::Utc::now())
+        .await
+        .expect("Failed to compact downloads");
+    assert_eq!(again, 0);
+
+    let downloads = Package::package_downloads(&ctx.conn, "compact-download-pkg", None)
+        .await
+        .expect("Failed to get downloads");
+    assert_eq!(downloads.days, 90);
+    assert_eq!(downloads.total, 13);
+    let versions: Vec<_> = downloads
+        .versions
+        .iter()
+        .map(|v| (v.version.as_str(), v.count))
+        .collect();
+    assert_eq!(versions, vec![("1.1.0", 2), ("1.0.0", 11)]);
+    assert_eq!(downloads.daily.len(), 1);
+    assert_eq!(downloads.daily[0].count, 13);
+
+    assert!(
+        Package::package_downloads(&ctx.conn, "missing-download-pkg", None)
+            .await
+            .is_err()
+    );
+}
+
 #[tokio::test]
 async fn dependencies_empty() {
     let ctx = TestDbCtx::new().await;
@@ -615,3 +804,87 @@ async fn dependents_multiple() {
     assert_eq!(dependents[0].package.name, "aaa-consumer");
     assert_eq!(dependents[1].package.name, "bbb-consumer");
 }
+
+#[tokio::test]
+async fn dependency_graph_transitive() {
+    let ctx = TestDbCtx::new().await;
+
+    let user = fixtures::user()
+        .insert(&ctx.conn)
+        .await
+        .expect("Failed to create user");
+
+    let mut versions = vec![];
+    for (name, dependencies) in [
+        ("graph-base", vec![]),
+        ("graph-mid", vec![0]),
+        ("graph-top", vec![1, 0]),
+        ("graph-other", vec![0]),
+    ] {
+        let pkg = fixtures::package()
+            .name(name)
+            .insert(&ctx.conn)
+            .await
+            .expect("Failed to create package");
+        let ver = fixtures::version(pkg.id)
+            .version("1.0.0")
+            .publisher_user(user.id)
+            .dependencies(
+                dependencies
+                    .into_iter()
+                    .map(|i: usize| versions[i])
+                    .collect(),
+            )
+            .insert(&ctx.conn)
+            .await
+            .expect("Failed to create version");
+        versions.push(ver.id);
+    }
+    let [base, mid, top, other] = versions[..] else {
+        unreachable!()
+    };
+
+    let top_ver = Version::by_id(&ctx.conn, top)
+        .await
+        .expect("Lookup failed");
+    let graph = top_ver
+        .dependency_graph(&ctx.conn)
+        .await
+        .expect("Failed to resolve dependency graph");
+
+    assert_eq!(graph.root, top);
+    assert_eq!(
+        graph
+            .nodes
+            .iter()
+            .map(|node| node.package.as_str())
+            .collect::<Vec<_>>(),
+        ["graph-base", "graph-mid", "graph-top"]
+    );
+    assert_eq!(
+        graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to))
+            .collect::<Vec<_>>(),
+        [(mid, base), (top, base), (top, mid)]
+    );
+
+    let base_ver = Version::by_id(&ctx.conn, base)
+        .await
+        .expect("Lookup failed");
+    let dependents = base_ver
+        .dependent_graph(&ctx.conn)
+        .await
+        .expect("Failed to resolve dependent graph");
+
+    assert_eq!(dependents.nodes.len(), 4);
+    assert_eq!(
+        dependents
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to))
+            .collect::<Vec<_>>(),
+        [(mid, base), (top, base), (top, mid), (other, base)]
+    );
+}
@@ -149,7 +149,9 @@ impl RegistryError {
                 Some("request access from the package owner or organisation administrator")
             },
             Self::InvalidPackageName { .. } => {
-                Some("package names must be lowercase alphanumeric with hyphens only")
+                Some(
+                    "package names must be lowercase alphanumeric with hyphens only, optionally scoped as @org/name",
+                )
             },
             Self::VersionAlreadyExists { .. } => {
                 Some("increment the version number before publishing")
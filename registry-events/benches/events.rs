@@ -44,12 +44,12 @@ fn noop_single_emit(bencher: Bencher) {
     let event = create_test_event();
 
     bencher.bench(|| {
-        black_box(futures::executor::block_on(async {
+        futures::executor::block_on(async {
             reporter
                 .emit(black_box(&event))
                 .await
                 .unwrap()
-        }));
+        });
     });
 }
 
@@ -65,12 +65,12 @@ fn noop_batch_emit(
     let events = create_event_batch(batch_size);
 
     bencher.bench(|| {
-        black_box(futures::executor::block_on(async {
+        futures::executor::block_on(async {
             reporter
                 .emit_batch(black_box(&events))
                 .await
                 .unwrap()
-        }));
+        });
     });
 }
 
@@ -80,12 +80,12 @@ fn tracing_single_emit(bencher: Bencher) {
     let event = create_test_event();
 
     bencher.bench(|| {
-        black_box(futures::executor::block_on(async {
+        futures::executor::block_on(async {
             reporter
                 .emit(black_box(&event))
                 .await
                 .unwrap()
-        }));
+        });
     });
 }
 
@@ -101,12 +101,12 @@ fn tracing_batch_emit(
     let events = create_event_batch(batch_size);
 
     bencher.bench(|| {
-        black_box(futures::executor::block_on(async {
+        futures::executor::block_on(async {
             reporter
                 .emit_batch(black_box(&events))
                 .await
                 .unwrap()
-        }));
+        });
     });
 }
 
@@ -118,9 +118,9 @@ fn multi_reporter_noop(bencher: Bencher) {
     let event = create_test_event();
 
     bencher.bench(|| {
-        black_box(futures::executor::block_on(async {
+        futures::executor::block_on(async {
             multi.emit(black_box(&event)).await.unwrap()
-        }));
+        });
     });
 }
 
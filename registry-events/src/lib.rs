@@ -3,11 +3,12 @@ use actix::{
     Supervised, fut::wrap_future,
 };
 use kintsu_registry_auth::AuditEvent;
+use prometheus::IntCounter;
 use serde_jsonlines::WriteExt;
 use std::{
     future::Future,
     pin::Pin,
-    sync::{Arc, RwLock},
+    sync::{Arc, LazyLock, RwLock},
 };
 use tokio::time::Duration;
 
@@ -28,6 +29,28 @@ const BATCH_FLUSH_INTERVAL_MS: u64 = 100;
 
 static EVENT_SYSTEM: RwLock<Option<EventSystem>> = RwLock::new(None);
 
+static EVENTS_EMITTED: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "kintsu_audit_events_emitted_total",
+        "Audit events handed to the event system",
+    )
+    .expect("valid audit event metric")
+});
+
+static REPORTER_ERRORS: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "kintsu_audit_reporter_errors_total",
+        "Batches of audit events a reporter failed to emit",
+    )
+    .expect("valid audit reporter metric")
+});
+
+/// Registers the event system's counters with the registry's metrics.
+pub fn register_metrics(registry: &prometheus::Registry) -> prometheus::Result<()> {
+    registry.register(Box::new(EVENTS_EMITTED.clone()))?;
+    registry.register(Box::new(REPORTER_ERRORS.clone()))
+}
+
 /// Trait for event reporters that can emit events individually or in batches
 pub trait EventReporter: Send + Sync {
     fn emit<'e>(
@@ -234,6 +257,7 @@ impl Handler<ProcessBatch> for EventExecutor {
                 match reporter.emit_batch(&events).await {
                     Ok(_) => {},
                     Err(err) => {
+                        REPORTER_ERRORS.inc();
                         tracing::error!("Event reporter batch emit error: {err:#?}");
                     },
                 }
@@ -273,6 +297,7 @@ impl EventSystem {
         &self,
         event: AuditEvent,
     ) {
+        EVENTS_EMITTED.inc();
         self.collector.do_send(event);
     }
 
@@ -451,10 +476,31 @@ pub async fn start<F: FnOnce() -> Fut, Fut: Future<Output = R>, R>(
     out
 }
 
-/// Emit an event to the event system.
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Runs `fut` as part of the request `request_id`, which events it emits are tagged with.
+pub async fn with_request_id<F: Future>(
+    request_id: String,
+    fut: F,
+) -> F::Output {
+    REQUEST_ID.scope(request_id, fut).await
+}
+
+/// Id of the request the current task is handling, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Emit an event to the event system, tagged with the current request id when it has none.
 /// When compiled with the `test` feature, this becomes a no-op that always succeeds.
 #[cfg(not(feature = "test"))]
-pub fn emit_event(event: AuditEvent) -> Result<(), Error> {
+pub fn emit_event(mut event: AuditEvent) -> Result<(), Error> {
+    if event.request_id.is_none() {
+        event.request_id = current_request_id();
+    }
+
     match EVENT_SYSTEM.read().unwrap().as_ref() {
         Some(sys) => {
             sys.emit(event);
@@ -0,0 +1,415 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::*;
+
+use crate::manager::StorageManager;
+
+/// Stores packages on the local filesystem, for self-hosted registries without S3.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct DiskConfig {
+    /// Directory objects are stored under, created if missing
+    #[serde(alias = "ROOT")]
+    pub root: PathBuf,
+    /// Encoding new declarations are written in. Either encoding is always readable.
+    #[serde(alias = "DECLARATIONS_ENCODING", default)]
+    pub declarations_encoding: DeclarationEncoding,
+}
+
+/// Suffix of temporary files, so concurrent writes of one object never share a file.
+static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+pub struct LocalDiskStorage<D> {
+    root: PathBuf,
+    declarations_encoding: DeclarationEncoding,
+    ph: std::marker::PhantomData<D>,
+}
+
+impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned>
+    LocalDiskStorage<D>
+{
+    pub fn new(config: &DiskConfig) -> Self {
+        Self {
+            root: config.root.clone(),
+            declarations_encoding: config.declarations_encoding,
+            ph: std::marker::PhantomData,
+        }
+    }
+
+    pub fn managed(config: &DiskConfig) -> StorageManager<D> {
+        StorageManager::<D>::new(Arc::new(Self::new(config)))
+    }
+
+    /// The file for the object at `path`, which must stay below the root.
+    fn file(
+        &self,
+        path: &str,
+    ) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(path);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(StorageError::StoreError(format!(
+                "object path must be relative and normalized: {path}"
+            )));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    /// Writes `data` to a temporary file next to the object and renames it into place, so
+    /// readers never see a partial object.
+    pub async fn put_and_get_checksum<T: Serialize>(
+        &self,
+        path: &str,
+        data: &T,
+    ) -> Result<Checksum, StorageError> {
+        let data = serde_json::to_vec(data).map_err(|e| StorageError::StoreError(e.to_string()))?;
+        self.put_bytes(path, self.encode(data)).await
+    }
+
+    /// Stores declarations in the configured [`DeclarationEncoding`].
+    pub async fn put_declarations_encoded<T: Serialize>(
+        &self,
+        path: &str,
+        data: &T,
+    ) -> Result<Checksum, StorageError> {
+        let data = kintsu_parser::declare::encoding::encode(data, self.declarations_encoding)?;
+        self.put_bytes(path, self.encode(data)).await
+    }
+
+    async fn put_bytes(
+        &self,
+        path: &str,
+        data: Vec<u8>,
+    ) -> Result<Checksum, StorageError> {
+        let checksum = Checksum::hash(&data);
+
+        let file = self.file(path)?;
+        if let Some(parent) = file.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| StorageError::StoreError(e.to_string()))?;
+        }
+
+        let mut temp = file.clone().into_os_string();
+        temp.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        tokio::fs::write(&temp, &data)
+            .await
+            .map_err(|e| StorageError::StoreError(e.to_string()))?;
+        if let Err(e) = tokio::fs::rename(&temp, &file).await {
+            let _ = tokio::fs::remove_file(&temp).await;
+            return Err(StorageError::StoreError(e.to_string()));
+        }
+
+        Ok(checksum)
+    }
+
+    pub async fn get_and_verify<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        checksum: Checksum,
+    ) -> Result<T, StorageError> {
+        let data = self.get_bytes(path, checksum).await?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Reads declarations written in either [`DeclarationEncoding`].
+    pub async fn get_declarations_decoded<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        checksum: Checksum,
+    ) -> Result<T, StorageError> {
+        let data = self.get_bytes(path, checksum).await?;
+        Ok(kintsu_parser::declare::encoding::decode(&data)?)
+    }
+
+    async fn get_bytes(
+        &self,
+        path: &str,
+        checksum: Checksum,
+    ) -> Result<Vec<u8>, StorageError> {
+        let data = tokio::fs::read(self.file(path)?)
+            .await
+            .map_err(|e| StorageError::RetrievalError(e.to_string()))?;
+
+        let found = Checksum::hash(&data);
+        if found != checksum {
+            return Err(StorageError::ChecksumMismatch {
+                expected: checksum.value().to_string(),
+                found: found.value().to_string(),
+            });
+        }
+
+        Ok(self.decode(data))
+    }
+}
+
+impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned> PackageStorage<D>
+    for LocalDiskStorage<D>
+{
+    fn put_source<'d>(
+        &'d self,
+        path: &'d str,
+        data: &'d kintsu_fs::memory::MemoryFileSystem,
+    ) -> LocalFuture<'d, Checksum> {
+        Box::pin(async move { self.put_and_get_checksum(path, data).await })
+    }
+
+    fn put_declarations<'d>(
+        &'d self,
+        path: &'d str,
+        data: &'d D,
+    ) -> LocalFuture<'d, Checksum> {
+        Box::pin(async move {
+            self.put_declarations_encoded(path, data)
+                .await
+        })
+    }
+
+    fn get_source<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: Checksum,
+    ) -> LocalFuture<'d, kintsu_fs::memory::MemoryFileSystem> {
+        Box::pin(async move { self.get_and_verify(path, checksum).await })
+    }
+
+    fn get_declarations<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: Checksum,
+    ) -> LocalFuture<'d, D> {
+        Box::pin(async move {
+            self.get_declarations_decoded(path, checksum)
+                .await
+        })
+    }
+
+    fn put_readme<'d>(
+        &'d self,
+        path: &'d str,
+        readme: &'d str,
+    ) -> LocalFuture<'d, Checksum> {
+        Box::pin(async move {
+            self.put_and_get_checksum(path, &readme)
+                .await
+        })
+    }
+
+    fn get_readme<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: Checksum,
+    ) -> LocalFuture<'d, String> {
+        Box::pin(async move { self.get_and_verify(path, checksum).await })
+    }
+
+    fn check<'d>(&'d self) -> LocalFuture<'d, ()> {
+        Box::pin(async move {
+            let metadata = tokio::fs::metadata(&self.root)
+                .await
+                .map_err(|e| StorageError::RetrievalError(e.to_string()))
+                .map_err(StorageError::with_path(self.root.display().to_string()))?;
+
+            if !metadata.is_dir() {
+                return Err(StorageError::RetrievalError(format!(
+                    "{} is not a directory",
+                    self.root.display()
+                )));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use kintsu_fs::FileSystem;
+
+    use super::*;
+
+    #[derive(serde::Deserialize, serde::Serialize)]
+    struct TestDecl(String);
+
+    fn storage(root: &tempfile::TempDir) -> LocalDiskStorage<TestDecl> {
+        LocalDiskStorage::new(&DiskConfig {
+            root: root.path().join("objects"),
+            declarations_encoding: DeclarationEncoding::Json,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_put() {
+        let root = tempfile::tempdir().unwrap();
+        let disk = storage(&root);
+
+        let decl = TestDecl("declarations contents".to_string());
+        let chksum = disk
+            .put_and_get_checksum("f/foo/1.0.0/declarations.json", &decl)
+            .await
+            .unwrap();
+        assert_eq!(chksum, Checksum::hash(&serde_json::to_vec(&decl).unwrap()));
+
+        let out = disk
+            .get_and_verify::<TestDecl>("f/foo/1.0.0/declarations.json", chksum)
+            .await
+            .unwrap();
+        assert_eq!(out.0, "declarations contents".to_string());
+    }
+
+    #[tokio::test]
+    async fn test_disk_trait_impl() {
+        let root = tempfile::tempdir().unwrap();
+        let disk = storage(&root);
+
+        let data = TestDecl("baz".to_string());
+        let fs = kintsu_fs::memory! {
+            "data-we-want-flat" => "bar",
+        };
+
+        let stored = disk
+            .store_package("my-package", "1.0.0", &fs, &data)
+            .await
+            .unwrap();
+        assert!(
+            root.path()
+                .join("objects/m/my-package/1.0.0/source.json")
+                .is_file()
+        );
+
+        let content = disk
+            .retrieve_package("my-package", "1.0.0", stored)
+            .await
+            .unwrap();
+        assert_eq!(content.declarations.0, "baz");
+        assert_eq!(
+            content
+                .fs
+                .read_to_string_sync(&PathBuf::from("data-we-want-flat"))
+                .unwrap(),
+            "bar".to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_readme_roundtrip() {
+        let root = tempfile::tempdir().unwrap();
+        let disk = storage(&root);
+
+        let chksum = disk
+            .store_readme("my-package", "1.0.0", "# my-package\n")
+            .await
+            .unwrap();
+        assert!(
+            root.path()
+                .join("objects/m/my-package/1.0.0/readme.json")
+                .is_file()
+        );
+
+        let readme = disk
+            .retrieve_readme("my-package", "1.0.0", chksum)
+            .await
+            .unwrap();
+        assert_eq!(readme, "# my-package\n");
+    }
+
+    #[tokio::test]
+    async fn test_tampered_object_fails_verification() {
+        let root = tempfile::tempdir().unwrap();
+        let disk = storage(&root);
+
+        let chksum = disk
+            .put_and_get_checksum("decl.json", &TestDecl("original".into()))
+            .await
+            .unwrap();
+        std::fs::write(root.path().join("objects/decl.json"), "\"tampered\"").unwrap();
+
+        let err = disk
+            .get_and_verify::<TestDecl>("decl.json", chksum)
+            .await
+            .err()
+            .unwrap();
+        assert!(
+            matches!(err, StorageError::ChecksumMismatch { .. }),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paths_stay_below_root() {
+        let root = tempfile::tempdir().unwrap();
+        let disk = storage(&root);
+
+        for path in ["../escape.json", "/etc/escape.json", "a/../../escape.json"] {
+            assert!(
+                disk.put_and_get_checksum(path, &TestDecl("x".into()))
+                    .await
+                    .is_err(),
+                "{path}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_presigned_uploads_unsupported() {
+        let root = tempfile::tempdir().unwrap();
+        let disk = storage(&root);
+
+        let path = StorageIndex::path_for_upload("foo", "1.0.0", "abc");
+        assert_eq!(path, "uploads/foo/1.0.0/abc.json");
+        assert!(
+            disk.presign_upload(&path, chrono::Duration::minutes(5))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_binary_declarations() {
+        let root = tempfile::tempdir().unwrap();
+        let json = storage(&root);
+        let binary = LocalDiskStorage::<TestDecl>::new(&DiskConfig {
+            root: root.path().join("objects"),
+            declarations_encoding: DeclarationEncoding::Binary,
+        });
+
+        let written = binary
+            .put_declarations("bin.json", &TestDecl("binary".into()))
+            .await
+            .unwrap();
+        let raw = std::fs::read(root.path().join("objects/bin.json")).unwrap();
+        assert!(raw.starts_with(kintsu_parser::declare::encoding::BINARY_MAGIC));
+
+        // either encoding is readable regardless of the configured one
+        let legacy = json
+            .put_declarations("json.json", &TestDecl("json".into()))
+            .await
+            .unwrap();
+        assert_eq!(
+            binary
+                .get_declarations("json.json", legacy)
+                .await
+                .unwrap()
+                .0,
+            "json"
+        );
+        assert_eq!(
+            json.get_declarations("bin.json", written)
+                .await
+                .unwrap()
+                .0,
+            "binary"
+        );
+    }
+}
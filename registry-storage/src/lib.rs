@@ -1,7 +1,12 @@
 use aws_sdk_s3::presigning::PresigningConfigError;
+
+pub use kintsu_parser::declare::encoding::DeclarationEncoding;
+use sha2::Digest;
 use std::sync::Arc;
 
+pub mod disk;
 pub mod manager;
+pub mod metrics;
 pub mod s3;
 
 #[cfg(feature = "test")]
@@ -26,6 +31,8 @@ pub enum StorageError {
         source: Box<Self>,
     },
 
+    #[error("{0}")]
+    DeclarationEncoding(#[from] kintsu_parser::declare::encoding::EncodingError),
     #[error("presign error: {0}")]
     PresigningConfigError(#[from] PresigningConfigError),
 }
@@ -42,6 +49,13 @@ impl StorageError {
     }
 }
 
+/// Smallest part S3 accepts in a multipart upload, other than the last part.
+pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+fn default_part_size() -> usize {
+    8 * 1024 * 1024
+}
+
 /// AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY must be set in the environment
 #[derive(serde::Deserialize, Debug)]
 pub struct Config {
@@ -55,6 +69,13 @@ pub struct Config {
     pub access_key_id: secrecy::SecretString,
     #[serde(alias = "SECRET_ACCESS_KEY")]
     pub secret_access_key: secrecy::SecretString,
+    /// Payloads larger than this many bytes are uploaded in parts of this size. Raised to
+    /// [`MIN_PART_SIZE`] when smaller.
+    #[serde(alias = "PART_SIZE", default = "default_part_size")]
+    pub part_size: usize,
+    /// Encoding new declarations are written in. Either encoding is always readable.
+    #[serde(alias = "DECLARATIONS_ENCODING", default)]
+    pub declarations_encoding: DeclarationEncoding,
 }
 
 pub struct StorageIndex;
@@ -62,6 +83,7 @@ pub struct StorageIndex;
 pub enum AssetType {
     Source,
     Declarations,
+    Readme,
 }
 
 impl std::fmt::Display for AssetType {
@@ -72,16 +94,24 @@ impl std::fmt::Display for AssetType {
         match self {
             AssetType::Source => write!(f, "source"),
             AssetType::Declarations => write!(f, "declarations"),
+            AssetType::Readme => write!(f, "readme"),
         }
     }
 }
 
 impl StorageIndex {
+    /// Unscoped packages are sharded by their first letter. Scoped packages such as
+    /// `@acme/billing` are kept together under their scope instead, which cannot collide with a
+    /// shard since package names start with a letter.
     pub fn path_for_package(
         package_name: &str,
         version: &str,
         asset: AssetType,
     ) -> String {
+        if package_name.starts_with('@') {
+            return format!("{package_name}/{version}/{asset}.json");
+        }
+
         format!(
             "{}/{}/{}/{}.json",
             package_name.chars().next().unwrap(),
@@ -104,6 +134,23 @@ impl StorageIndex {
     ) -> String {
         Self::path_for_package(package_name, version, AssetType::Declarations)
     }
+
+    pub fn path_for_readme(
+        package_name: &str,
+        version: &str,
+    ) -> String {
+        Self::path_for_package(package_name, version, AssetType::Readme)
+    }
+
+    /// Where a client uploads the source of a version before finalizing its publish. Keyed by
+    /// checksum, so a retried upload never overwrites a different payload.
+    pub fn path_for_upload(
+        package_name: &str,
+        version: &str,
+        checksum: &str,
+    ) -> String {
+        format!("uploads/{package_name}/{version}/{checksum}.json")
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -134,6 +181,24 @@ impl Checksum {
     }
 }
 
+/// Computes a [`Checksum`] over data which arrives in pieces, matching [`Checksum::hash`]
+/// of the concatenated input.
+#[derive(Default, Clone)]
+pub struct ChecksumHasher(sha2::Sha256);
+
+impl ChecksumHasher {
+    pub fn update(
+        &mut self,
+        input: &[u8],
+    ) {
+        self.0.update(input);
+    }
+
+    pub fn finish(self) -> Checksum {
+        Checksum(format!("{:x}", self.0.finalize()))
+    }
+}
+
 pub type LocalFuture<'a, T = ()> =
     std::pin::Pin<Box<dyn Future<Output = Result<T, StorageError>> + Send + 'a>>;
 
@@ -201,6 +266,14 @@ pub trait PackageStorage<D: Send + Sync + serde::Serialize + serde::de::Deserial
         StorageIndex::path_for_declarations(package_name, version)
     }
 
+    fn path_for_readme(
+        &self,
+        package_name: &str,
+        version: &str,
+    ) -> String {
+        StorageIndex::path_for_readme(package_name, version)
+    }
+
     fn checksum(
         &self,
         bytes: &[u8],
@@ -240,6 +313,34 @@ pub trait PackageStorage<D: Send + Sync + serde::Serialize + serde::de::Deserial
         checksum: Checksum,
     ) -> LocalFuture<'d, kintsu_fs::memory::MemoryFileSystem>;
 
+    fn put_readme<'d>(
+        &'d self,
+        path: &'d str,
+        readme: &'d str,
+    ) -> LocalFuture<'d, Checksum>;
+
+    fn get_readme<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: Checksum,
+    ) -> LocalFuture<'d, String>;
+
+    /// Checks the backend can be reached, for readiness probes.
+    fn check<'d>(&'d self) -> LocalFuture<'d, ()> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// A URL the object at `path` can be written to with a single `PUT` of
+    /// `application/json` until `expires_in` passes, or `None` when the backend cannot hand
+    /// out direct uploads.
+    fn presign_upload<'d>(
+        &'d self,
+        _path: &'d str,
+        _expires_in: chrono::Duration,
+    ) -> LocalFuture<'d, Option<String>> {
+        Box::pin(async { Ok(None) })
+    }
+
     fn get_sources<'d>(
         &'d self,
         sources: Vec<BulkGetSource>,
@@ -363,6 +464,44 @@ pub trait PackageStorage<D: Send + Sync + serde::Serialize + serde::de::Deserial
         })
     }
 
+    /// Stores the README of a version next to its source. Kept out of [`Self::store_package`]
+    /// since most packages are fetched without it.
+    fn store_readme<'a>(
+        &'a self,
+        package_name: &'a str,
+        version: &'a str,
+        readme: &'a str,
+    ) -> LocalFuture<'a, Checksum> {
+        let readme_path = self.path_for_readme(package_name, version);
+
+        Box::pin(async move {
+            let checksum = self
+                .put_readme(&readme_path, readme)
+                .await
+                .map_err(StorageError::with_path(&readme_path))?;
+
+            Ok(checksum)
+        })
+    }
+
+    fn retrieve_readme<'a>(
+        &'a self,
+        package_name: &'a str,
+        version: &'a str,
+        expected_checksum: Checksum,
+    ) -> LocalFuture<'a, String> {
+        let readme_path = self.path_for_readme(package_name, version);
+
+        Box::pin(async move {
+            let readme = self
+                .get_readme(&readme_path, expected_checksum)
+                .await
+                .map_err(StorageError::with_path(&readme_path))?;
+
+            Ok(readme)
+        })
+    }
+
     fn retrieve_packages<'a>(
         &'a self,
         packages: Vec<BulkGetPackage>,
@@ -393,3 +532,33 @@ pub trait PackageStorage<D: Send + Sync + serde::Serialize + serde::de::Deserial
         Box::pin(async move { futures_util::future::try_join_all(futures).await })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn incremental_checksum_matches_hash() {
+        let input = b"a payload uploaded in several parts";
+
+        let mut hasher = ChecksumHasher::default();
+        for chunk in input.chunks(4) {
+            hasher.update(chunk);
+        }
+
+        assert_eq!(hasher.finish(), Checksum::hash(input));
+        assert_eq!(ChecksumHasher::default().finish(), Checksum::hash(b""));
+    }
+
+    #[test]
+    fn scoped_packages_are_kept_under_their_scope() {
+        assert_eq!(
+            StorageIndex::path_for_source("billing", "1.0.0"),
+            "b/billing/1.0.0/source.json"
+        );
+        assert_eq!(
+            StorageIndex::path_for_source("@acme/billing", "1.0.0"),
+            "@acme/billing/1.0.0/source.json"
+        );
+    }
+}
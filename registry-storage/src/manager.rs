@@ -1,4 +1,4 @@
-use crate::PackageStorage;
+use crate::{PackageStorage, metrics::observe};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -38,7 +38,7 @@ impl<D: Send + Sync + serde::Serialize + serde::de::DeserializeOwned> crate::Pac
         path: &'d str,
         data: &'d kintsu_fs::memory::MemoryFileSystem,
     ) -> crate::LocalFuture<'d, crate::Checksum> {
-        self.storage.put_source(path, data)
+        observe("put_source", self.storage.put_source(path, data))
     }
 
     fn put_declarations<'d>(
@@ -46,7 +46,10 @@ impl<D: Send + Sync + serde::Serialize + serde::de::DeserializeOwned> crate::Pac
         path: &'d str,
         data: &'d D,
     ) -> crate::LocalFuture<'d, crate::Checksum> {
-        self.storage.put_declarations(path, data)
+        observe(
+            "put_declarations",
+            self.storage.put_declarations(path, data),
+        )
     }
 
     fn get_declarations<'d>(
@@ -54,7 +57,10 @@ impl<D: Send + Sync + serde::Serialize + serde::de::DeserializeOwned> crate::Pac
         path: &'d str,
         checksum: crate::Checksum,
     ) -> crate::LocalFuture<'d, D> {
-        self.storage.get_declarations(path, checksum)
+        observe(
+            "get_declarations",
+            self.storage.get_declarations(path, checksum),
+        )
     }
 
     fn get_source<'d>(
@@ -62,6 +68,37 @@ impl<D: Send + Sync + serde::Serialize + serde::de::DeserializeOwned> crate::Pac
         path: &'d str,
         checksum: crate::Checksum,
     ) -> crate::LocalFuture<'d, kintsu_fs::memory::MemoryFileSystem> {
-        self.storage.get_source(path, checksum)
+        observe("get_source", self.storage.get_source(path, checksum))
+    }
+
+    fn put_readme<'d>(
+        &'d self,
+        path: &'d str,
+        readme: &'d str,
+    ) -> crate::LocalFuture<'d, crate::Checksum> {
+        observe("put_readme", self.storage.put_readme(path, readme))
+    }
+
+    fn get_readme<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: crate::Checksum,
+    ) -> crate::LocalFuture<'d, String> {
+        observe("get_readme", self.storage.get_readme(path, checksum))
+    }
+
+    fn presign_upload<'d>(
+        &'d self,
+        path: &'d str,
+        expires_in: chrono::Duration,
+    ) -> crate::LocalFuture<'d, Option<String>> {
+        observe(
+            "presign_upload",
+            self.storage.presign_upload(path, expires_in),
+        )
+    }
+
+    fn check<'d>(&'d self) -> crate::LocalFuture<'d, ()> {
+        observe("check", self.storage.check())
     }
 }
@@ -0,0 +1,76 @@
+//! Counters for storage operations, registered with the registry's metrics by
+//! [`register_metrics`].
+
+use crate::LocalFuture;
+use prometheus::{IntCounterVec, Opts, Registry};
+use std::sync::LazyLock;
+
+static STORAGE_ERRORS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "kintsu_storage_errors_total",
+            "Storage operations which failed, by operation",
+        ),
+        &["operation"],
+    )
+    .expect("valid storage error metric")
+});
+
+pub fn register_metrics(registry: &Registry) -> prometheus::Result<()> {
+    registry.register(Box::new(STORAGE_ERRORS.clone()))
+}
+
+/// Counts `fut` against [`STORAGE_ERRORS`] when it fails.
+pub(crate) fn observe<'d, T: Send + 'd>(
+    operation: &'static str,
+    fut: LocalFuture<'d, T>,
+) -> LocalFuture<'d, T> {
+    Box::pin(async move {
+        let result = fut.await;
+        if result.is_err() {
+            STORAGE_ERRORS
+                .with_label_values(&[operation])
+                .inc();
+        }
+        result
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::StorageError;
+
+    #[tokio::test]
+    async fn counts_failed_operations() {
+        let before = STORAGE_ERRORS
+            .with_label_values(&["test"])
+            .get();
+
+        observe::<()>("test", Box::pin(async { Ok(()) }))
+            .await
+            .unwrap();
+        observe::<()>(
+            "test",
+            Box::pin(async { Err(StorageError::StoreError("down".into())) }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            STORAGE_ERRORS
+                .with_label_values(&["test"])
+                .get(),
+            before + 1
+        );
+
+        let registry = Registry::new();
+        register_metrics(&registry).unwrap();
+        assert!(
+            registry
+                .gather()
+                .iter()
+                .any(|family| family.name() == "kintsu_storage_errors_total")
+        );
+    }
+}
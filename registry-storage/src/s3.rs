@@ -1,3 +1,5 @@
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt};
 use secrecy::ExposeSecret;
 use serde::{Serialize, de::DeserializeOwned};
 
@@ -5,12 +7,27 @@ use crate::*;
 
 use crate::manager::StorageManager;
 
+/// Parts of one multipart upload sent at the same time.
+const MAX_CONCURRENT_PARTS: usize = 4;
+
 pub struct S3Storage<D> {
     pub(crate) client: aws_sdk_s3::Client,
     bucket_name: String,
+    part_size: usize,
+    declarations_encoding: DeclarationEncoding,
     ph: std::marker::PhantomData<D>,
 }
 
+/// Byte ranges of the parts `len` bytes are uploaded in.
+fn part_ranges(
+    len: usize,
+    part_size: usize,
+) -> impl Iterator<Item = std::ops::Range<usize>> {
+    (0..len)
+        .step_by(part_size)
+        .map(move |start| start..(start + part_size).min(len))
+}
+
 // static is ok in this context because the data is owned by the caller / we never have ownership
 impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned> S3Storage<D> {
     pub async fn new(config: &Config) -> Self {
@@ -34,6 +51,8 @@ impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned>
         Self {
             client,
             bucket_name: config.bucket.clone(),
+            part_size: config.part_size.max(MIN_PART_SIZE),
+            declarations_encoding: config.declarations_encoding,
             ph: std::marker::PhantomData,
         }
     }
@@ -50,6 +69,31 @@ impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned>
         let data = serde_json::to_vec(data).map_err(|e| StorageError::StoreError(e.to_string()))?;
         let data = self.encode(data);
 
+        self.put_bytes(path, Bytes::from(data)).await
+    }
+
+    /// Stores declarations in the configured [`DeclarationEncoding`].
+    pub async fn put_declarations_encoded<T: Serialize>(
+        &self,
+        path: &str,
+        data: &T,
+    ) -> Result<Checksum, StorageError> {
+        let data = kintsu_parser::declare::encoding::encode(data, self.declarations_encoding)?;
+        let data = self.encode(data);
+
+        self.put_bytes(path, Bytes::from(data)).await
+    }
+
+    /// Stores `data`, as a multipart upload when it is larger than one part.
+    pub async fn put_bytes(
+        &self,
+        path: &str,
+        data: Bytes,
+    ) -> Result<Checksum, StorageError> {
+        if data.len() > self.part_size {
+            return self.put_multipart(path, data).await;
+        }
+
         let checksum = Checksum::hash(&data);
 
         self.client
@@ -69,11 +113,153 @@ impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned>
         Ok(checksum)
     }
 
+    /// Uploads `data` in parts of `part_size`, a few at a time. Parts share `data`'s
+    /// buffer, and the upload is aborted if any part fails.
+    async fn put_multipart(
+        &self,
+        path: &str,
+        data: Bytes,
+    ) -> Result<Checksum, StorageError> {
+        let upload = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket_name)
+            .key(path)
+            .content_type("application/json")
+            .checksum_algorithm(aws_sdk_s3::types::ChecksumAlgorithm::Sha256)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to start multipart upload to S3: {:#?}", e);
+                StorageError::StoreError(e.to_string())
+            })?;
+
+        let upload_id = upload
+            .upload_id()
+            .ok_or_else(|| StorageError::StoreError("multipart upload has no id".into()))?
+            .to_string();
+
+        let mut hasher = ChecksumHasher::default();
+        let parts: Vec<_> = part_ranges(data.len(), self.part_size)
+            .enumerate()
+            .map(|(index, range)| {
+                let part = data.slice(range);
+                hasher.update(&part);
+                (index as i32 + 1, part)
+            })
+            .collect();
+
+        let uploaded = futures_util::stream::iter(parts)
+            .map(|(part_number, part)| self.upload_part(path, &upload_id, part_number, part))
+            .buffered(MAX_CONCURRENT_PARTS)
+            .try_collect::<Vec<_>>()
+            .await;
+
+        let completed = match uploaded {
+            Ok(parts) => {
+                self.client
+                    .complete_multipart_upload()
+                    .bucket(&self.bucket_name)
+                    .key(path)
+                    .upload_id(&upload_id)
+                    .multipart_upload(
+                        aws_sdk_s3::types::CompletedMultipartUpload::builder()
+                            .set_parts(Some(parts))
+                            .build(),
+                    )
+                    .send()
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| {
+                        tracing::error!("Failed to complete multipart upload to S3: {:#?}", e);
+                        StorageError::StoreError(e.to_string())
+                    })
+            },
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = completed {
+            if let Err(abort) = self
+                .client
+                .abort_multipart_upload()
+                .bucket(&self.bucket_name)
+                .key(path)
+                .upload_id(&upload_id)
+                .send()
+                .await
+            {
+                tracing::error!("Failed to abort multipart upload to S3: {:#?}", abort);
+            }
+            return Err(err);
+        }
+
+        Ok(hasher.finish())
+    }
+
+    async fn upload_part(
+        &self,
+        path: &str,
+        upload_id: &str,
+        part_number: i32,
+        part: Bytes,
+    ) -> Result<aws_sdk_s3::types::CompletedPart, StorageError> {
+        let uploaded = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket_name)
+            .key(path)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .checksum_algorithm(aws_sdk_s3::types::ChecksumAlgorithm::Sha256)
+            .body(aws_sdk_s3::primitives::ByteStream::from(part))
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to upload part {part_number} to S3: {:#?}", e);
+                StorageError::StoreError(e.to_string())
+            })?;
+
+        Ok(aws_sdk_s3::types::CompletedPart::builder()
+            .part_number(part_number)
+            .set_e_tag(uploaded.e_tag)
+            .set_checksum_sha256(uploaded.checksum_sha256)
+            .build())
+    }
+
     pub async fn get_and_verify<T: DeserializeOwned>(
         &self,
         path: &str,
         checksum: Checksum,
     ) -> Result<T, StorageError> {
+        let data = self.get_bytes(path, checksum).await?;
+
+        let data = self.decode(data);
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Reads declarations written in either [`DeclarationEncoding`].
+    pub async fn get_declarations_decoded<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        checksum: Checksum,
+    ) -> Result<T, StorageError> {
+        let data = self.get_bytes(path, checksum).await?;
+
+        let data = self.decode(data);
+        Ok(kintsu_parser::declare::encoding::decode(&data)?)
+    }
+
+    /// Streams the object at `path` without buffering it. The checksum is computed as
+    /// chunks arrive, and the stream ends with [`StorageError::ChecksumMismatch`] instead
+    /// of its last chunk being trusted when it does not match.
+    pub async fn get_stream(
+        &self,
+        path: &str,
+        checksum: Checksum,
+    ) -> Result<
+        impl futures_util::Stream<Item = Result<Bytes, StorageError>> + Send + 'static,
+        StorageError,
+    > {
         let resp = self
             .client
             .get_object()
@@ -87,23 +273,47 @@ impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned>
                 StorageError::RetrievalError(e.to_string())
             })?;
 
-        let data = resp
-            .body
-            .collect()
-            .await
-            .map_err(|e| StorageError::RetrievalError(e.to_string()))?
-            .into_bytes();
-
-        let found = Checksum::hash(&data);
-        if found != checksum {
-            return Err(StorageError::ChecksumMismatch {
-                expected: checksum.value().to_string(),
-                found: found.value().to_string(),
-            });
-        }
+        let state = Some((resp.body, ChecksumHasher::default(), checksum));
+        Ok(futures_util::stream::try_unfold(state, |state| {
+            async move {
+                let Some((mut body, mut hasher, expected)) = state else {
+                    return Ok(None);
+                };
+
+                match body.try_next().await {
+                    Ok(Some(chunk)) => {
+                        hasher.update(&chunk);
+                        Ok(Some((chunk, Some((body, hasher, expected)))))
+                    },
+                    Ok(None) => {
+                        let found = hasher.finish();
+                        if found != expected {
+                            return Err(StorageError::ChecksumMismatch {
+                                expected: expected.value().to_string(),
+                                found: found.value().to_string(),
+                            });
+                        }
+                        Ok(None)
+                    },
+                    Err(e) => Err(StorageError::RetrievalError(e.to_string())),
+                }
+            }
+        }))
+    }
 
-        let data = self.decode(data.to_vec());
-        Ok(serde_json::from_slice(&data)?)
+    /// The verified contents of the object at `path`, read into a single buffer.
+    pub async fn get_bytes(
+        &self,
+        path: &str,
+        checksum: Checksum,
+    ) -> Result<Vec<u8>, StorageError> {
+        let mut stream = std::pin::pin!(self.get_stream(path, checksum).await?);
+
+        let mut data = vec![];
+        while let Some(chunk) = stream.try_next().await? {
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
     }
 
     pub async fn presign(
@@ -126,6 +336,30 @@ impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned>
 
         Ok(req.uri().to_string())
     }
+
+    pub async fn presign_put(
+        &self,
+        path: &str,
+        expires_in: chrono::Duration,
+    ) -> Result<String, StorageError> {
+        let presigner = aws_sdk_s3::presigning::PresigningConfig::expires_in(
+            expires_in
+                .to_std()
+                .map_err(|e| StorageError::StoreError(e.to_string()))?,
+        )?;
+
+        let req = self
+            .client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(path)
+            .content_type("application/json")
+            .presigned(presigner)
+            .await
+            .map_err(|e| StorageError::StoreError(e.to_string()))?;
+
+        Ok(req.uri().to_string())
+    }
 }
 
 impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned> PackageStorage<D>
@@ -144,7 +378,10 @@ impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned>
         path: &'d str,
         data: &'d D,
     ) -> LocalFuture<'d, Checksum> {
-        Box::pin(async move { self.put_and_get_checksum(path, data).await })
+        Box::pin(async move {
+            self.put_declarations_encoded(path, data)
+                .await
+        })
     }
 
     fn get_source<'d>(
@@ -160,15 +397,60 @@ impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned>
         path: &'d str,
         checksum: Checksum,
     ) -> LocalFuture<'d, D> {
+        Box::pin(async move {
+            self.get_declarations_decoded(path, checksum)
+                .await
+        })
+    }
+
+    fn put_readme<'d>(
+        &'d self,
+        path: &'d str,
+        readme: &'d str,
+    ) -> LocalFuture<'d, Checksum> {
+        Box::pin(async move {
+            self.put_and_get_checksum(path, &readme)
+                .await
+        })
+    }
+
+    fn get_readme<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: Checksum,
+    ) -> LocalFuture<'d, String> {
         Box::pin(async move { self.get_and_verify(path, checksum).await })
     }
+
+    fn check<'d>(&'d self) -> LocalFuture<'d, ()> {
+        Box::pin(async move {
+            self.client
+                .head_bucket()
+                .bucket(&self.bucket_name)
+                .send()
+                .await
+                .map_err(|e| StorageError::RetrievalError(e.to_string()))?;
+            Ok(())
+        })
+    }
+
+    fn presign_upload<'d>(
+        &'d self,
+        path: &'d str,
+        expires_in: chrono::Duration,
+    ) -> LocalFuture<'d, Option<String>> {
+        Box::pin(async move {
+            self.presign_put(path, expires_in)
+                .await
+                .map(Some)
+        })
+    }
 }
 
 #[cfg(all(test, feature = "test"))]
 mod test {
     use kintsu_fs::FileSystem;
 
-    use super::*;
     use crate::PackageStorage;
 
     #[derive(serde::Deserialize, serde::Serialize)]
@@ -203,7 +485,7 @@ mod test {
 
         let data = TestDecl("baz".to_string());
         let fs = kintsu_fs::memory! {
-            "data-we-want-flat" => "bar".to_string(),
+            "data-we-want-flat" => "bar",
         };
 
         let package_name = "my-package";
@@ -245,4 +527,41 @@ mod test {
             "bar".to_string()
         );
     }
+
+    #[test]
+    fn splits_into_parts() {
+        let ranges: Vec<_> = super::part_ranges(12, 5).collect();
+        assert_eq!(ranges, [0..5, 5..10, 10..12]);
+        assert_eq!(super::part_ranges(10, 5).count(), 2);
+        assert_eq!(super::part_ranges(0, 5).count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_multipart_roundtrip() {
+        let ctx = crate::tst::TestS3Ctx::new().await;
+        let s3 = super::S3Storage::<TestDecl>::new(&ctx.conf).await;
+
+        // a little over two parts
+        let decl = TestDecl("x".repeat(2 * crate::MIN_PART_SIZE + 1024));
+        let chksum = s3
+            .put_and_get_checksum("large.json", &decl)
+            .await
+            .unwrap();
+        assert_eq!(
+            chksum,
+            crate::Checksum::hash(&serde_json::to_vec(&decl).unwrap())
+        );
+
+        let out = s3
+            .get_and_verify::<TestDecl>("large.json", chksum)
+            .await
+            .unwrap();
+        assert_eq!(out.0, decl.0);
+
+        let err = s3
+            .get_bytes("large.json", crate::Checksum::hash(b"other"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::StorageError::ChecksumMismatch { .. }));
+    }
 }
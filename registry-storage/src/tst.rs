@@ -1,3 +1,8 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
 use testcontainers::{
     ContainerAsync, GenericImage, ImageExt,
     core::{IntoContainerPort, WaitFor},
@@ -43,6 +48,8 @@ impl TestS3Ctx {
             region: "ca-central-1".to_string(),
             access_key_id: "rustfsadmin".into(),
             secret_access_key: "rustfsadmin".into(),
+            part_size: crate::MIN_PART_SIZE,
+            declarations_encoding: Default::default(),
         };
 
         let client = crate::s3::S3Storage::<()>::new(&conf).await;
@@ -71,7 +78,281 @@ impl TestS3Ctx {
     }
 }
 
+/// A storage operation faults can be injected into, see [`StorageFaults`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageOp {
+    PutSource,
+    PutDeclarations,
+    PutReadme,
+    GetSource,
+    GetDeclarations,
+    GetReadme,
+    PresignUpload,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Fault {
+    /// Calls let through before failing
+    after: usize,
+    /// Calls failed before healing, `None` for every later call
+    times: Option<usize>,
+}
+
+#[derive(Default)]
+struct FaultState {
+    faults: HashMap<StorageOp, Fault>,
+    calls: HashMap<StorageOp, usize>,
+}
+
+/// Shared switches for the faults of a [`FaultyStorage`], kept by tests to break storage
+/// while a request is in flight, e.g. failing declarations after the source was stored.
+#[derive(Clone, Default)]
+pub struct StorageFaults {
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl StorageFaults {
+    /// Fails every call of `op` from now on.
+    pub fn fail(
+        &self,
+        op: StorageOp,
+    ) {
+        self.fail_after(op, 0, None);
+    }
+
+    /// Fails the next `times` calls of `op` after letting `after` through, or every later
+    /// call when `times` is `None`.
+    pub fn fail_after(
+        &self,
+        op: StorageOp,
+        after: usize,
+        times: Option<usize>,
+    ) {
+        self.state
+            .lock()
+            .unwrap()
+            .faults
+            .insert(op, Fault { after, times });
+    }
+
+    /// Removes every fault, calls are counted on.
+    pub fn heal(&self) {
+        self.state.lock().unwrap().faults.clear();
+    }
+
+    /// Calls of `op` so far, failed or not.
+    pub fn calls(
+        &self,
+        op: StorageOp,
+    ) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .calls
+            .get(&op)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Counts a call of `op`, returning the error it should fail with.
+    fn check(
+        &self,
+        op: StorageOp,
+        path: &str,
+    ) -> Result<(), crate::StorageError> {
+        let mut state = self.state.lock().unwrap();
+        *state.calls.entry(op).or_default() += 1;
+
+        let Some(fault) = state.faults.get_mut(&op) else {
+            return Ok(());
+        };
+        if fault.after > 0 {
+            fault.after -= 1;
+            return Ok(());
+        }
+        match &mut fault.times {
+            Some(0) => return Ok(()),
+            Some(times) => *times -= 1,
+            None => {},
+        }
+
+        let message = format!("injected {op:?} failure");
+        Err(crate::StorageError::with_path(path)(match op {
+            StorageOp::GetSource | StorageOp::GetDeclarations | StorageOp::GetReadme => {
+                crate::StorageError::RetrievalError(message)
+            },
+            _ => crate::StorageError::StoreError(message),
+        }))
+    }
+}
+
+/// Storage failing the operations its [`StorageFaults`] are set to, and passing everything
+/// else to the storage it wraps.
+pub struct FaultyStorage<D> {
+    inner: Arc<dyn crate::PackageStorage<D>>,
+    faults: StorageFaults,
+}
+
+impl<D: 'static + Send + Sync + serde::Serialize + serde::de::DeserializeOwned> FaultyStorage<D> {
+    /// Wraps `inner`, returning the storage and the switches for its faults.
+    pub fn managed(
+        inner: Arc<dyn crate::PackageStorage<D>>
+    ) -> (crate::manager::StorageManager<D>, StorageFaults) {
+        let faults = StorageFaults::default();
+        let storage = Self {
+            inner,
+            faults: faults.clone(),
+        };
+        (
+            crate::manager::StorageManager::new(Arc::new(storage)),
+            faults,
+        )
+    }
+}
+
+impl<D: Send + Sync + serde::Serialize + serde::de::DeserializeOwned> crate::PackageStorage<D>
+    for FaultyStorage<D>
+{
+    fn put_source<'d>(
+        &'d self,
+        path: &'d str,
+        data: &'d kintsu_fs::memory::MemoryFileSystem,
+    ) -> crate::LocalFuture<'d, crate::Checksum> {
+        Box::pin(async move {
+            self.faults
+                .check(StorageOp::PutSource, path)?;
+            self.inner.put_source(path, data).await
+        })
+    }
+
+    fn put_declarations<'d>(
+        &'d self,
+        path: &'d str,
+        data: &'d D,
+    ) -> crate::LocalFuture<'d, crate::Checksum> {
+        Box::pin(async move {
+            self.faults
+                .check(StorageOp::PutDeclarations, path)?;
+            self.inner.put_declarations(path, data).await
+        })
+    }
+
+    fn get_source<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: crate::Checksum,
+    ) -> crate::LocalFuture<'d, kintsu_fs::memory::MemoryFileSystem> {
+        Box::pin(async move {
+            self.faults
+                .check(StorageOp::GetSource, path)?;
+            self.inner.get_source(path, checksum).await
+        })
+    }
+
+    fn get_declarations<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: crate::Checksum,
+    ) -> crate::LocalFuture<'d, D> {
+        Box::pin(async move {
+            self.faults
+                .check(StorageOp::GetDeclarations, path)?;
+            self.inner
+                .get_declarations(path, checksum)
+                .await
+        })
+    }
+
+    fn put_readme<'d>(
+        &'d self,
+        path: &'d str,
+        readme: &'d str,
+    ) -> crate::LocalFuture<'d, crate::Checksum> {
+        Box::pin(async move {
+            self.faults
+                .check(StorageOp::PutReadme, path)?;
+            self.inner.put_readme(path, readme).await
+        })
+    }
+
+    fn get_readme<'d>(
+        &'d self,
+        path: &'d str,
+        checksum: crate::Checksum,
+    ) -> crate::LocalFuture<'d, String> {
+        Box::pin(async move {
+            self.faults
+                .check(StorageOp::GetReadme, path)?;
+            self.inner.get_readme(path, checksum).await
+        })
+    }
+
+    fn presign_upload<'d>(
+        &'d self,
+        path: &'d str,
+        expires_in: chrono::Duration,
+    ) -> crate::LocalFuture<'d, Option<String>> {
+        Box::pin(async move {
+            self.faults
+                .check(StorageOp::PresignUpload, path)?;
+            self.inner
+                .presign_upload(path, expires_in)
+                .await
+        })
+    }
+
+    fn check<'d>(&'d self) -> crate::LocalFuture<'d, ()> {
+        self.inner.check()
+    }
+}
+
 #[tokio::test]
 async fn ctx() {
     let _ = TestS3Ctx::new().await;
 }
+
+#[tokio::test]
+async fn faults_fail_only_the_calls_they_are_set_for() {
+    use crate::PackageStorage;
+
+    let root = tempfile::tempdir().unwrap();
+    let disk =
+        crate::disk::LocalDiskStorage::<serde_json::Value>::managed(&crate::disk::DiskConfig {
+            root: root.path().to_path_buf(),
+            declarations_encoding: Default::default(),
+        });
+    let (storage, faults) = FaultyStorage::managed(disk.storage());
+
+    faults.fail_after(StorageOp::PutReadme, 1, Some(1));
+    storage
+        .put_readme("a", "first")
+        .await
+        .unwrap();
+    let err = storage
+        .put_readme("b", "second")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "'b': storage error: injected PutReadme failure"
+    );
+    storage
+        .put_readme("c", "third")
+        .await
+        .unwrap();
+
+    faults.fail(StorageOp::PutDeclarations);
+    storage
+        .put_declarations("d", &serde_json::json!({}))
+        .await
+        .unwrap_err();
+    faults.heal();
+    storage
+        .put_declarations("d", &serde_json::json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(faults.calls(StorageOp::PutReadme), 3);
+    assert_eq!(faults.calls(StorageOp::PutDeclarations), 2);
+    assert_eq!(faults.calls(StorageOp::GetSource), 0);
+}
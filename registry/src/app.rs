@@ -60,6 +60,10 @@ macro_rules! bind_app {
         $s3: ident,
         $client: ident,
         $cookie_key: ident,
+        $rate_limiter: ident,
+        $org_sync: ident,
+        $metrics: ident,
+        $compiler: ident,
     ) => {
         move || {
             App::new()
@@ -70,14 +74,25 @@ macro_rules! bind_app {
                 .app_data($client.clone())
                 .app_data($cookie_key.clone())
                 .app_data($s3.clone())
+                .app_data($rate_limiter.clone())
+                .app_data($org_sync.clone())
+                .app_data($metrics.clone())
+                .app_data($compiler.clone())
+                // Health routes
+                .service(health::healthz)
+                .service(health::readyz)
+                .service(health::metrics)
                 // Auth routes
                 .service(auth::callback)
+                .service(auth::provider_callback)
+                .service(auth::list_providers)
                 .service(auth::whoami)
                 .service(auth::logout)
                 .service(auth::create_auth_token)
                 .service(auth::revoke_auth_token)
                 .service(auth::get_user_tokens)
                 .service(auth::redirect_to_login)
+                .service(auth::login_with_provider)
                 // Org routes
                 .service(org::get_org_by_id)
                 .service(org::check_org_exists)
@@ -86,8 +101,17 @@ macro_rules! bind_app {
                 .service(org::get_org_tokens)
                 .service(org::discover_orgs)
                 .service(org::import_org)
+                .service(org::sync_org_members)
                 .service(org::grant_org_role)
                 .service(org::revoke_org_role)
+                // Team routes
+                .service(teams::list_teams)
+                .service(teams::create_team)
+                .service(teams::update_team)
+                .service(teams::delete_team)
+                .service(teams::list_team_members)
+                .service(teams::add_team_member)
+                .service(teams::remove_team_member)
                 // Favourites routes
                 .service(favourites::list_favourites)
                 .service(favourites::create_favourite)
@@ -96,13 +120,32 @@ macro_rules! bind_app {
                 .service(favourites::package_favourite_count)
                 // Package routes
                 .service(packages::publish_package)
+                .service(packages::publish_dry_run)
+                .service(packages::request_publish_upload)
+                .service(packages::finalize_publish)
                 .service(packages::get_package_version)
+                .service(packages::yank_package_version)
+                .service(packages::unyank_package_version)
+                .service(packages::deprecate_package)
+                .service(packages::undeprecate_package)
+                .service(packages::deprecate_package_version)
+                .service(packages::undeprecate_package_version)
                 .service(packages::get_package_dependencies)
+                .service(packages::get_dependency_graph)
+                .service(packages::get_dependent_graph)
                 .service(packages::package_declarations)
+                .service(packages::list_declared_items)
+                .service(packages::get_declared_item)
                 .service(packages::get_dependent_packages)
                 .service(packages::download_package_version)
+                .service(packages::list_package_files)
+                .service(packages::get_package_file)
+                .service(packages::get_package_readme)
                 .service(packages::get_package_total_downloads)
                 .service(packages::get_package_download_history)
+                .service(packages::get_package_download_breakdown)
+                .service(packages::get_package_client_downloads)
+                .service(packages::get_package_downloads)
                 .service(packages::list_packages)
                 .service(packages::search_packages)
                 .service(packages::list_package_versions)
@@ -115,26 +158,35 @@ macro_rules! bind_app {
                     RapiDoc::with_openapi("/api-docs/openapi.json", api).path("/rapidoc")
                 })
                 .into_app()
+                .wrap(actix_web::middleware::from_fn(
+                    $crate::rate_limit::limit_by_ip,
+                ))
+                .wrap(actix_web::middleware::from_fn(
+                    $crate::metrics::track_requests,
+                ))
+                .wrap(actix_web::middleware::from_fn(
+                    $crate::request_id::assign_request_id,
+                ))
         }
     };
 }
 
 pub async fn start_server(config: crate::config::Config) -> crate::Result<()> {
     let db = web::Data::new(config.database.connect().await?);
-    let client = web::Data::new(AuthClient::new(config.gh)?);
+    kintsu_registry_db::migrations::ensure_current(db.get_ref()).await?;
+    let s3 = web::Data::new(config.storage().await?);
+    let gh_api_url = config
+        .gh
+        .as_ref()
+        .map(|gh| gh.api_url.clone())
+        .unwrap_or_else(|| url::Url::parse("https://api.github.com").unwrap());
+    let client = web::Data::new(AuthClient::from_config(config.gh, config.oidc).await?);
     let addr = config.addr;
     let session_config = web::Data::new(config.session);
     let cookie_key = web::Data::new(cookie::Key::derive_from(
         session_config.key.expose_secret().as_bytes(),
     ));
 
-    let s3 = web::Data::new(
-        kintsu_registry_storage::s3::S3Storage::<kintsu_parser::declare::DeclarationVersion>::managed(
-            &config.s3,
-        )
-        .await,
-    );
-
     tracing::info!(
         "starting server on {}://{addr}",
         if config.insecure {
@@ -144,7 +196,41 @@ pub async fn start_server(config: crate::config::Config) -> crate::Result<()> {
         }
     );
 
-    let server = HttpServer::new(bind_app!(session_config, db, s3, client, cookie_key,));
+    tokio::spawn(crate::jobs::compact_downloads(
+        db.get_ref().clone(),
+        config.downloads.compact_interval(),
+    ));
+
+    let org_sync = web::Data::new(crate::org_sync::OrgSync::new(
+        gh_api_url,
+        config.org_sync.token.clone(),
+    ));
+    if org_sync.is_scheduled() {
+        tokio::spawn(crate::jobs::sync_orgs(
+            db.get_ref().clone(),
+            org_sync.clone(),
+            config.org_sync.interval(),
+        ));
+    } else {
+        tracing::info!("org sync token not configured, imported orgs sync only on demand");
+    }
+
+    let rate_limiter =
+        web::Data::new(crate::rate_limit::RateLimiter::from_config(config.rate_limit).await?);
+    let metrics = web::Data::new(crate::metrics::Metrics::new()?);
+    let compiler = web::Data::new(crate::compile::CompileExecutor::new(config.compile));
+
+    let server = HttpServer::new(bind_app!(
+        session_config,
+        db,
+        s3,
+        client,
+        cookie_key,
+        rate_limiter,
+        org_sync,
+        metrics,
+        compiler,
+    ));
 
     let server_fut = {
         if config.insecure {
@@ -0,0 +1,159 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use kintsu_cli_core::config::ConfigLoader;
+use kintsu_registry::{
+    config::Config,
+    loadtest::{Catalog, RunConfig, SeedPlan, TrafficMix, seed, traffic},
+};
+use tracing::Level;
+
+#[derive(Parser)]
+#[command(about = "seed a registry with synthetic packages and measure it under load")]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// publish synthetic packages through the engine layer and write a catalog for `run`
+    Seed(SeedArgs),
+    /// replay a traffic mix against a running registry and report latency percentiles
+    Run(RunArgs),
+}
+
+#[derive(clap::Args)]
+struct SeedArgs {
+    /// directory containing the registry configuration, as used by the server
+    #[clap(long, short = 'd')]
+    config_dir: Option<String>,
+
+    #[clap(long, default_value = "loadtest-catalog.json")]
+    catalog: PathBuf,
+
+    #[clap(long, default_value = "loadtest")]
+    prefix: String,
+
+    #[clap(long, default_value_t = 1000)]
+    packages: usize,
+
+    #[clap(long, default_value_t = 3)]
+    versions: usize,
+
+    #[clap(long, default_value_t = 8)]
+    types: usize,
+
+    #[clap(long, default_value_t = 3)]
+    max_dependencies: usize,
+
+    #[clap(long, default_value_t = 8)]
+    concurrency: usize,
+
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(clap::Args)]
+struct RunArgs {
+    #[clap(
+        long,
+        env = "KINTSU_REGISTRY_URL",
+        default_value = "http://127.0.0.1:8000"
+    )]
+    url: String,
+
+    #[clap(long, default_value = "loadtest-catalog.json")]
+    catalog: PathBuf,
+
+    #[clap(long, default_value_t = 32)]
+    concurrency: usize,
+
+    /// run length in seconds
+    #[clap(long, default_value_t = 60)]
+    duration: u64,
+
+    /// stop after this many requests
+    #[clap(long)]
+    requests: Option<u64>,
+
+    /// relative operation weights, e.g. `resolve=50,download=30,search=15,publish=5`
+    #[clap(long, default_value = "resolve=50,download=30,search=15,publish=5")]
+    mix: TrafficMix,
+
+    #[clap(long, value_enum, default_value_t = ReportFormat::Table)]
+    format: ReportFormat,
+
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Table,
+    Json,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    rustls::crypto::ring::default_provider()
+        .install_default()
+        .unwrap();
+
+    dotenvy::dotenv().ok();
+
+    tracing_subscriber::fmt()
+        .with_max_level(Level::INFO)
+        .init();
+
+    match Cli::parse().command {
+        Command::Seed(args) => {
+            let config = ConfigLoader::<Config>::new()
+                .dir(args.config_dir.as_deref())
+                .load()?
+                .into_inner();
+            let plan = SeedPlan {
+                prefix: args.prefix,
+                packages: args.packages,
+                versions_per_package: args.versions,
+                types_per_package: args.types,
+                max_dependencies: args.max_dependencies,
+                concurrency: args.concurrency,
+                rng_seed: args.seed,
+            };
+
+            let event_reporter: Vec<Box<dyn kintsu_registry_events::EventReporter>> =
+                vec![Box::new(kintsu_registry_events::TracingEventReporter)];
+            let catalog = kintsu_registry_events::start(event_reporter, || {
+                async { seed::seed_with_config(&config, &plan).await }
+            })
+            .await?;
+
+            std::fs::write(&args.catalog, serde_json::to_vec_pretty(&catalog)?)?;
+            tracing::info!(
+                "seeded {} packages, catalog written to {}",
+                catalog.packages.len(),
+                args.catalog.display()
+            );
+        },
+        Command::Run(args) => {
+            let catalog: Catalog = serde_json::from_slice(&std::fs::read(&args.catalog)?)?;
+            let config = RunConfig {
+                base_url: args.url,
+                concurrency: args.concurrency,
+                duration: Duration::from_secs(args.duration),
+                max_requests: args.requests,
+                mix: args.mix,
+                rng_seed: args.seed,
+            };
+
+            let report = traffic::run(config, catalog).await?;
+            match args.format {
+                ReportFormat::Table => println!("{report}"),
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            }
+        },
+    }
+
+    Ok(())
+}
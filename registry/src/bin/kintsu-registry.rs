@@ -1,7 +1,51 @@
-use kintsu_manifests::NewForConfig;
+use clap::{Parser, Subcommand};
+use kintsu_cli_core::config::ConfigLoader;
 use kintsu_registry::config::Config;
 use tracing::Level;
 
+#[derive(Parser)]
+#[command(about = "the kintsu package registry")]
+struct Cli {
+    /// directory containing the registry configuration
+    #[clap(long, short = 'd', global = true)]
+    config_dir: Option<String>,
+
+    /// address to listen on, over `addr` from the configuration file or `KS_ADDR`
+    #[clap(long, global = true)]
+    addr: Option<String>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// serve the registry, the default. Refuses to start while migrations are pending.
+    Serve,
+    /// apply, roll back or list database schema migrations
+    Migrate {
+        #[clap(subcommand)]
+        command: Option<MigrateCommand>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// apply pending migrations, the default
+    Up {
+        /// apply at most this many migrations
+        #[clap(long)]
+        steps: Option<u32>,
+    },
+    /// roll back applied migrations
+    Down {
+        #[clap(long, default_value_t = 1)]
+        steps: u32,
+    },
+    /// list migrations and whether each is applied
+    Status,
+}
+
 #[actix_web::main]
 async fn main() -> kintsu_registry::Result<()> {
     rustls::crypto::ring::default_provider()
@@ -14,13 +58,49 @@ async fn main() -> kintsu_registry::Result<()> {
         .with_max_level(Level::DEBUG)
         .init();
 
-    let c = Config::new::<&str>(None)?;
+    let cli = Cli::parse();
+    let c = ConfigLoader::<Config>::new()
+        .dir(cli.config_dir.as_deref())
+        .flag("addr", "--addr", cli.addr)
+        .load()?;
+    for (key, provenance) in c.sources() {
+        tracing::debug!("{key} from {provenance}");
+    }
+    let c = c.into_inner();
 
-    let event_reporter: Vec<Box<dyn kintsu_registry_events::EventReporter>> =
-        vec![Box::new(kintsu_registry_events::TracingEventReporter)];
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => {
+            let event_reporter: Vec<Box<dyn kintsu_registry_events::EventReporter>> =
+                vec![Box::new(kintsu_registry_events::TracingEventReporter)];
 
-    kintsu_registry_events::start(event_reporter, move || {
-        async { kintsu_registry::app::start_server(c).await }
-    })
-    .await
+            kintsu_registry_events::start(event_reporter, move || {
+                async { kintsu_registry::app::start_server(c).await }
+            })
+            .await
+        },
+        Command::Migrate { command } => {
+            let db = c.connect_database().await?;
+            match command.unwrap_or(MigrateCommand::Up { steps: None }) {
+                MigrateCommand::Up { steps } => {
+                    kintsu_registry_db::migrations::up(&db, steps).await?;
+                },
+                MigrateCommand::Down { steps } => {
+                    kintsu_registry_db::migrations::down(&db, steps).await?;
+                },
+                MigrateCommand::Status => {
+                    for (name, applied) in kintsu_registry_db::migrations::status(&db).await? {
+                        println!(
+                            "{} {name}",
+                            if applied {
+                                "applied"
+                            } else {
+                                "pending"
+                            }
+                        );
+                    }
+                },
+            }
+            Ok(())
+        },
+    }
 }
@@ -0,0 +1,61 @@
+use kintsu_registry_db::{
+    engine::version::DownloadEvent,
+    entities::{DownloadArtifact, DownloadOrigin},
+};
+
+/// Identifies the client behind a request from its `User-Agent`.
+///
+/// Requests from kintsu tooling are attributed to the resolver along with the compiler
+/// version they report; everything else is counted as a human download.
+pub struct ClientInfo {
+    pub origin: DownloadOrigin,
+    pub client_version: Option<String>,
+}
+
+impl ClientInfo {
+    pub fn download(
+        self,
+        artifact: DownloadArtifact,
+    ) -> DownloadEvent {
+        DownloadEvent {
+            artifact,
+            origin: self.origin,
+            client_version: self.client_version,
+        }
+    }
+}
+
+impl actix_web::FromRequest for ClientInfo {
+    type Error = crate::Error;
+    type Future = std::future::Ready<std::result::Result<Self, Self::Error>>;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        _: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        let user_agent = req
+            .headers()
+            .get(actix_web::http::header::USER_AGENT)
+            .and_then(|header_value| header_value.to_str().ok())
+            .unwrap_or_default();
+
+        let product = format!("{}/", kintsu_registry_core::CLIENT_PRODUCT);
+        let info = if user_agent
+            .split_whitespace()
+            .any(|token| token.starts_with(&product))
+        {
+            Self {
+                origin: DownloadOrigin::Resolver,
+                client_version: kintsu_registry_core::parse_client_user_agent(user_agent)
+                    .map(str::to_string),
+            }
+        } else {
+            Self {
+                origin: DownloadOrigin::Human,
+                client_version: None,
+            }
+        };
+
+        std::future::ready(Ok(info))
+    }
+}
@@ -0,0 +1,399 @@
+//! Runs the compiles publishes need within configured limits.
+//!
+//! Compiles wait in a bounded queue for one of a fixed number of slots, so a burst of
+//! publishes cannot exhaust the server. Inputs are size checked before compiling, and each
+//! compile reads a copy of the package limited to the package quota, so nothing it writes can
+//! grow past it. Exceeding a limit is reported as a packaging error.
+//!
+//! Each compile runs on a thread and runtime of its own. Past its timeout the compile is
+//! dropped and the publish rejected, even when it is stuck in CPU-bound work which never
+//! yields to a timer. Such a compile keeps its slot until its thread ends, so runaway
+//! compiles hold back new ones instead of piling up. Compiles waiting for a slot give up
+//! after the same timeout and the publish is turned away to be retried.
+//!
+//! Only the inputs are bounded in size. The memory a compile uses beyond them is not capped.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use kintsu_fs::memory::{MemoryFileSystem, MemoryQuota, QuotaExceeded, QuotaKind};
+use kintsu_registry_core::PackagingError;
+
+use crate::config::CompileConfig;
+
+/// How long clients turned away from a full queue, or one which did not move, are told to
+/// wait.
+const QUEUE_FULL_RETRY_AFTER: std::time::Duration = std::time::Duration::from_secs(5);
+
+pub struct CompileExecutor {
+    config: CompileConfig,
+    slots: Arc<tokio::sync::Semaphore>,
+    queued: AtomicUsize,
+}
+
+impl CompileExecutor {
+    pub fn new(config: CompileConfig) -> Self {
+        Self {
+            slots: Arc::new(tokio::sync::Semaphore::new(config.max_concurrent.max(1))),
+            queued: AtomicUsize::new(0),
+            config,
+        }
+    }
+
+    /// Limits of the filesystem a compile reads the submitted package from.
+    pub fn quota(&self) -> MemoryQuota {
+        MemoryQuota::unlimited()
+            .with_max_bytes(self.config.max_package_bytes)
+            .with_max_files(self.config.max_package_files)
+    }
+
+    /// Checks the files submitted for a publish.
+    pub fn check_package(
+        &self,
+        package_data: &MemoryFileSystem,
+    ) -> crate::Result<()> {
+        let size = package_data.total_bytes();
+        if size > self.config.max_package_bytes {
+            return Err(PackagingError::PackageTooLarge {
+                size,
+                limit: self.config.max_package_bytes,
+            }
+            .into());
+        }
+
+        let count = package_data.file_count();
+        if count > self.config.max_package_files {
+            return Err(PackagingError::TooManyFiles {
+                count,
+                limit: self.config.max_package_files,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks the size of everything a compile reads, the package and its dependencies.
+    pub fn check_input<'a>(
+        &self,
+        sources: impl IntoIterator<Item = &'a MemoryFileSystem>,
+    ) -> crate::Result<()> {
+        let size = sources
+            .into_iter()
+            .map(MemoryFileSystem::total_bytes)
+            .sum();
+        if size > self.config.max_input_bytes {
+            return Err(PackagingError::CompileInputTooLarge {
+                size,
+                limit: self.config.max_input_bytes,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Runs `compile` on a copy of `package_data` limited to [`Self::quota`] once a slot is
+    /// free, giving up on it after the configured timeout.
+    pub async fn run<T, F>(
+        &self,
+        package_data: &MemoryFileSystem,
+        compile: impl FnOnce(MemoryFileSystem) -> F + Send + 'static,
+    ) -> crate::Result<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = crate::Result<T>> + 'static, {
+        let sandbox = MemoryFileSystem::merge("", vec![package_data.clone()], self.quota())
+            .map_err(|err| {
+                match err {
+                    kintsu_fs::Error::QuotaExceeded(exceeded) => quota_error(exceeded),
+                    err => crate::Error::IoError(std::io::Error::other(err)),
+                }
+            })?;
+
+        let timeout = self.config.timeout();
+        let timed_out = move || {
+            crate::Error::from(PackagingError::CompileTimedOut {
+                seconds: timeout.as_secs(),
+            })
+        };
+
+        let slot = match self.slots.clone().try_acquire_owned() {
+            Ok(slot) => slot,
+            Err(_) => {
+                let _queued = self.enqueue()?;
+                tokio::time::timeout(timeout, self.slots.clone().acquire_owned())
+                    .await
+                    .map_err(|_| {
+                        crate::Error::RateLimited {
+                            retry_after: QUEUE_FULL_RETRY_AFTER,
+                        }
+                    })?
+                    .expect("compile slots are never closed")
+            },
+        };
+
+        let (done, result) = tokio::sync::oneshot::channel();
+        std::thread::Builder::new()
+            .name("kintsu-compile".into())
+            .spawn(move || {
+                let _slot = slot;
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(err) => {
+                        let _ = done.send(Err(err.into()));
+                        return;
+                    },
+                };
+
+                // a compile which yields is dropped at the deadline with the tasks it spawned
+                let compiled = runtime
+                    .block_on(async { tokio::time::timeout(timeout, compile(sandbox)).await })
+                    .unwrap_or_else(|_| Err(timed_out()));
+                runtime.shutdown_background();
+                let _ = done.send(compiled);
+            })?;
+
+        // one which never yields is left to finish on its own thread
+        match tokio::time::timeout(timeout, result).await {
+            Ok(Ok(compiled)) => compiled,
+            Ok(Err(_)) => {
+                Err(crate::Error::IoError(std::io::Error::other(
+                    "compile thread stopped without a result",
+                )))
+            },
+            Err(_) => Err(timed_out()),
+        }
+    }
+
+    fn enqueue(&self) -> crate::Result<QueuedCompile<'_>> {
+        let queued = self.queued.fetch_add(1, Ordering::SeqCst);
+        let entry = QueuedCompile(&self.queued);
+        if queued >= self.config.max_queued {
+            return Err(crate::Error::RateLimited {
+                retry_after: QUEUE_FULL_RETRY_AFTER,
+            });
+        }
+        Ok(entry)
+    }
+}
+
+fn quota_error(exceeded: QuotaExceeded) -> crate::Error {
+    match exceeded.kind {
+        QuotaKind::Bytes => {
+            PackagingError::PackageTooLarge {
+                size: exceeded.requested,
+                limit: exceeded.limit,
+            }
+        },
+        QuotaKind::Files => {
+            PackagingError::TooManyFiles {
+                count: exceeded.requested,
+                limit: exceeded.limit,
+            }
+        },
+    }
+    .into()
+}
+
+/// A compile waiting for a slot, leaving the queue when dropped.
+struct QueuedCompile<'a>(&'a AtomicUsize);
+
+impl Drop for QueuedCompile<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use kintsu_fs::FileSystem;
+
+    use super::*;
+
+    fn executor(config: CompileConfig) -> std::sync::Arc<CompileExecutor> {
+        std::sync::Arc::new(CompileExecutor::new(config))
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_packages() {
+        let executor = executor(CompileConfig {
+            max_package_bytes: 8,
+            max_package_files: 1,
+            ..Default::default()
+        });
+
+        let large = MemoryFileSystem::with_files([("schema/lib.ks", "0123456789")]);
+        assert!(matches!(
+            executor.check_package(&large),
+            Err(crate::Error::PackagingError(
+                PackagingError::PackageTooLarge { size: 10, limit: 8 }
+            ))
+        ));
+
+        let many = MemoryFileSystem::with_files([("a.ks", ""), ("b.ks", "")]);
+        assert!(matches!(
+            executor.check_package(&many),
+            Err(crate::Error::PackagingError(PackagingError::TooManyFiles {
+                count: 2,
+                limit: 1
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancels_compiles_past_the_timeout() {
+        let executor = executor(CompileConfig {
+            timeout: 1,
+            ..Default::default()
+        });
+
+        let result = executor
+            .run(&MemoryFileSystem::new(), |_| {
+                async {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    Ok(())
+                }
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::PackagingError(
+                PackagingError::CompileTimedOut { seconds: 1 }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_compiles_which_never_yield() {
+        let executor = executor(CompileConfig {
+            timeout: 1,
+            max_concurrent: 1,
+            ..Default::default()
+        });
+        let started = std::time::Instant::now();
+
+        let result = executor
+            .run(&MemoryFileSystem::new(), move |_| {
+                async move {
+                    while started.elapsed() < std::time::Duration::from_secs(4) {
+                        std::hint::spin_loop();
+                    }
+                    Ok(())
+                }
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::PackagingError(
+                PackagingError::CompileTimedOut { seconds: 1 }
+            ))
+        ));
+        assert!(started.elapsed() < std::time::Duration::from_secs(3));
+        // the runaway compile holds its slot until it ends
+        assert_eq!(executor.slots.available_permits(), 0);
+
+        // so compiles queued behind it give up rather than wait for it
+        let queued_at = std::time::Instant::now();
+        let queued = executor
+            .run(&MemoryFileSystem::new(), |_| async { Ok(()) })
+            .await;
+        assert!(matches!(queued, Err(crate::Error::RateLimited { .. })));
+        assert!(queued_at.elapsed() < std::time::Duration::from_secs(2));
+        assert_eq!(executor.queued.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn limits_what_compiles_write_to_the_package_quota() {
+        let executor = executor(CompileConfig {
+            max_package_bytes: 16,
+            ..Default::default()
+        });
+        let package = MemoryFileSystem::with_files([("schema/lib.ks", "0123456789")]);
+
+        let result = executor
+            .run(&package, |fs| {
+                async move {
+                    assert_eq!(
+                        fs.read_to_string_sync("schema/lib.ks".as_ref())
+                            .unwrap(),
+                        "0123456789"
+                    );
+                    fs.write("schema/out.ks".as_ref(), b"0123456789".to_vec())
+                        .await
+                        .map_err(|err| std::io::Error::other(err).into())
+                }
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(!package.exists_sync("schema/out.ks".as_ref()));
+
+        let too_large = MemoryFileSystem::with_files([("schema/lib.ks", "0".repeat(20))]);
+        let result = executor
+            .run(&too_large, |_| async { Ok(()) })
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::PackagingError(
+                PackagingError::PackageTooLarge {
+                    size: 20,
+                    limit: 16
+                }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn turns_compiles_away_when_the_queue_is_full() {
+        let executor = executor(CompileConfig {
+            max_concurrent: 1,
+            max_queued: 1,
+            ..Default::default()
+        });
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+
+        let running = tokio::spawn({
+            let executor = executor.clone();
+            async move {
+                executor
+                    .run(&MemoryFileSystem::new(), |_| {
+                        async {
+                            released.await.unwrap();
+                            Ok(())
+                        }
+                    })
+                    .await
+            }
+        });
+        while executor.slots.available_permits() > 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let queued = tokio::spawn({
+            let executor = executor.clone();
+            async move {
+                executor
+                    .run(&MemoryFileSystem::new(), |_| async { Ok(()) })
+                    .await
+            }
+        });
+        while executor.queued.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let rejected = executor
+            .run(&MemoryFileSystem::new(), |_| async { Ok(()) })
+            .await;
+        assert!(matches!(rejected, Err(crate::Error::RateLimited { .. })));
+
+        release.send(()).unwrap();
+        running.await.unwrap().unwrap();
+        queued.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,73 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+fn default_timeout() -> u64 {
+    30
+}
+
+fn default_max_concurrent() -> usize {
+    4
+}
+
+fn default_max_queued() -> usize {
+    32
+}
+
+fn default_max_package_bytes() -> usize {
+    kintsu_registry_core::DEFAULT_MAX_PACKAGE_BYTES
+}
+
+fn default_max_package_files() -> usize {
+    kintsu_registry_core::DEFAULT_MAX_PACKAGE_FILES
+}
+
+fn default_max_input_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
+/// Limits on compiling submitted packages, which every publish and dry-run publish does.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CompileConfig {
+    /// Seconds a compile may run, or wait for a slot, before the publish is rejected
+    #[serde(alias = "TIMEOUT", default = "default_timeout")]
+    pub timeout: u64,
+
+    /// Compiles running at once
+    #[serde(alias = "MAX_CONCURRENT", default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+
+    /// Compiles waiting for a slot before further publishes are turned away
+    #[serde(alias = "MAX_QUEUED", default = "default_max_queued")]
+    pub max_queued: usize,
+
+    /// Size of the submitted package's files
+    #[serde(alias = "MAX_PACKAGE_BYTES", default = "default_max_package_bytes")]
+    pub max_package_bytes: usize,
+
+    #[serde(alias = "MAX_PACKAGE_FILES", default = "default_max_package_files")]
+    pub max_package_files: usize,
+
+    /// Size of the package and all of its dependencies' sources, which the compiler holds in
+    /// memory. This bounds the inputs only, not the memory a compile uses beyond them.
+    #[serde(alias = "MAX_INPUT_BYTES", default = "default_max_input_bytes")]
+    pub max_input_bytes: usize,
+}
+
+impl Default for CompileConfig {
+    fn default() -> Self {
+        Self {
+            timeout: default_timeout(),
+            max_concurrent: default_max_concurrent(),
+            max_queued: default_max_queued(),
+            max_package_bytes: default_max_package_bytes(),
+            max_package_files: default_max_package_files(),
+            max_input_bytes: default_max_input_bytes(),
+        }
+    }
+}
+
+impl CompileConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout.max(1))
+    }
+}
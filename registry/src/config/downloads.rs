@@ -0,0 +1,28 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+fn default_compact_interval() -> u64 {
+    60
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DownloadsConfig {
+    /// Seconds between compactions of the download log into daily buckets. Download
+    /// statistics lag behind by at most this long.
+    #[serde(alias = "COMPACT_INTERVAL", default = "default_compact_interval")]
+    pub compact_interval: u64,
+}
+
+impl Default for DownloadsConfig {
+    fn default() -> Self {
+        Self {
+            compact_interval: default_compact_interval(),
+        }
+    }
+}
+
+impl DownloadsConfig {
+    pub fn compact_interval(&self) -> Duration {
+        Duration::from_secs(self.compact_interval.max(1))
+    }
+}
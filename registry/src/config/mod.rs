@@ -1,8 +1,16 @@
+mod compile;
 mod database;
+mod downloads;
+mod org_sync;
+mod rate_limit;
 mod session;
 mod tls;
 
+pub use compile::CompileConfig;
 pub use database::DatabaseConfig;
+pub use downloads::DownloadsConfig;
+pub use org_sync::OrgSyncConfig;
+pub use rate_limit::{Quota, RateLimitConfig};
 pub use session::SessionConfig;
 pub use tls::TlsConfig;
 
@@ -25,8 +33,13 @@ pub struct Config {
     pub(crate) tls: TlsConfig,
 
     #[validate(nested)]
-    #[serde(alias = "GH")]
-    pub(crate) gh: crate::oauth::GhOauthConfig,
+    #[serde(default, alias = "GH")]
+    pub(crate) gh: Option<crate::oauth::GhOauthConfig>,
+
+    /// An OpenID Connect provider users can log in through, next to or instead of GitHub
+    #[validate(nested)]
+    #[serde(default, alias = "OIDC")]
+    pub(crate) oidc: Option<crate::oauth::OidcConfig>,
 
     #[serde(alias = "DATABASE")]
     pub(crate) database: DatabaseConfig,
@@ -34,11 +47,55 @@ pub struct Config {
     #[serde(alias = "SESSION")]
     pub(crate) session: SessionConfig,
 
-    #[serde(alias = "S3")]
-    pub(crate) s3: kintsu_registry_storage::Config,
+    #[serde(default, alias = "S3")]
+    pub(crate) s3: Option<kintsu_registry_storage::Config>,
+
+    /// Stores packages on local disk instead of S3
+    #[serde(default, alias = "DISK")]
+    pub(crate) disk: Option<kintsu_registry_storage::disk::DiskConfig>,
+
+    #[serde(default, alias = "DOWNLOADS")]
+    pub(crate) downloads: DownloadsConfig,
+
+    #[serde(default, alias = "ORG_SYNC")]
+    pub(crate) org_sync: OrgSyncConfig,
+
+    #[serde(default, alias = "RATE_LIMIT")]
+    pub(crate) rate_limit: RateLimitConfig,
+
+    #[serde(default, alias = "COMPILE")]
+    pub(crate) compile: CompileConfig,
 }
 
 impl kintsu_manifests::NewForConfig for Config {
     const NAME: &'static str = "registry";
     const ENV: &'static str = "KS";
 }
+
+impl Config {
+    pub async fn connect_database(&self) -> crate::Result<sea_orm::DatabaseConnection> {
+        self.database.connect().await
+    }
+
+    /// The configured package storage, preferring local disk when both are set.
+    pub async fn storage(&self) -> crate::Result<kintsu_registry_db::PackageStorage> {
+        type Declarations = kintsu_parser::declare::DeclarationVersion;
+
+        match (&self.disk, &self.s3) {
+            (Some(disk), _) => {
+                tracing::info!("storing packages under {}", disk.root.display());
+                Ok(kintsu_registry_storage::disk::LocalDiskStorage::<
+                    Declarations,
+                >::managed(disk))
+            },
+            (None, Some(s3)) => {
+                Ok(kintsu_registry_storage::s3::S3Storage::<Declarations>::managed(s3).await)
+            },
+            (None, None) => {
+                Err(crate::Error::StorageConfig(
+                    "either s3 or disk storage must be configured".into(),
+                ))
+            },
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use secrecy::SecretString;
+use serde::Deserialize;
+use std::time::Duration;
+
+fn default_interval() -> u64 {
+    60 * 60
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OrgSyncConfig {
+    /// Seconds between syncs of every imported org's members from GitHub
+    #[serde(alias = "INTERVAL", default = "default_interval")]
+    pub interval: u64,
+
+    /// GitHub token able to read the members of imported orgs. Scheduled syncs only run when
+    /// it is set, while manual syncs fall back to the GitHub session of the org admin.
+    #[serde(alias = "TOKEN", default)]
+    pub token: Option<SecretString>,
+}
+
+impl Default for OrgSyncConfig {
+    fn default() -> Self {
+        Self {
+            interval: default_interval(),
+            token: None,
+        }
+    }
+}
+
+impl OrgSyncConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval.max(60))
+    }
+}
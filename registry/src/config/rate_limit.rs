@@ -0,0 +1,111 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_ip_quota() -> Quota {
+    Quota {
+        burst: 300,
+        per_second: 10.0,
+    }
+}
+
+fn default_principal_quota() -> Quota {
+    Quota {
+        burst: 600,
+        per_second: 20.0,
+    }
+}
+
+fn default_abuse_threshold() -> u32 {
+    100
+}
+
+fn default_abuse_window() -> u64 {
+    60
+}
+
+/// A token bucket holding up to `burst` requests, refilled at `per_second`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Quota {
+    #[serde(alias = "BURST")]
+    pub burst: u32,
+    #[serde(alias = "PER_SECOND")]
+    pub per_second: f64,
+}
+
+impl Quota {
+    /// How long an untouched bucket takes to fill up from empty.
+    pub fn refill_time(&self) -> Duration {
+        Duration::from_secs_f64(f64::from(self.burst) / self.per_second)
+    }
+
+    fn check(
+        &self,
+        name: &str,
+    ) -> crate::Result<()> {
+        if self.burst == 0 || !(self.per_second.is_finite() && self.per_second > 0.0) {
+            return Err(crate::Error::RateLimitConfig(format!(
+                "{name} quota needs a burst of at least 1 and a positive refill rate"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RateLimitConfig {
+    #[serde(alias = "ENABLED", default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Applies to every request, keyed by the client address
+    #[serde(alias = "IP", default = "default_ip_quota")]
+    pub ip: Quota,
+
+    /// Applies to authenticated requests, keyed by the user or org behind them
+    #[serde(alias = "PRINCIPAL", default = "default_principal_quota")]
+    pub principal: Quota,
+
+    /// Takes the client address from `Forwarded` or `X-Forwarded-For`. Only enable behind a
+    /// proxy which overwrites these headers.
+    #[serde(alias = "TRUST_FORWARDED", default)]
+    pub trust_forwarded: bool,
+
+    /// Rejections of one client within `abuse_window` seconds which are audited as abuse
+    #[serde(alias = "ABUSE_THRESHOLD", default = "default_abuse_threshold")]
+    pub abuse_threshold: u32,
+
+    #[serde(alias = "ABUSE_WINDOW", default = "default_abuse_window")]
+    pub abuse_window: u64,
+
+    /// Shares buckets between registry instances. Requires the `redis` feature.
+    #[serde(alias = "REDIS_URL", default)]
+    pub redis_url: Option<String>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            ip: default_ip_quota(),
+            principal: default_principal_quota(),
+            trust_forwarded: false,
+            abuse_threshold: default_abuse_threshold(),
+            abuse_window: default_abuse_window(),
+            redis_url: None,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn abuse_window(&self) -> Duration {
+        Duration::from_secs(self.abuse_window.max(1))
+    }
+
+    pub(crate) fn check(&self) -> crate::Result<()> {
+        self.ip.check("ip")?;
+        self.principal.check("principal")
+    }
+}
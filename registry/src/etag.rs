@@ -0,0 +1,120 @@
+//! Strong ETags for package reads, so clients revalidate what they fetched before with
+//! `If-None-Match` and are answered `304 Not Modified` instead of receiving it again.
+
+use actix_web::{
+    HttpRequest, HttpResponse,
+    http::header::{self, HeaderValue},
+};
+use sha2::{Digest, Sha256};
+
+pub struct ETag(String);
+
+impl ETag {
+    /// Of a published artifact, which never changes for its checksum. `representation` tells
+    /// apart the encodings an artifact is served in.
+    pub fn of_artifact(
+        checksum: &str,
+        representation: &str,
+    ) -> Self {
+        Self::of_body(format!("{checksum}:{representation}").as_bytes())
+    }
+
+    /// Of a response body, for metadata which changes as packages are published and yanked.
+    pub fn of_body(body: &[u8]) -> Self {
+        Self(format!("\"{:x}\"", Sha256::digest(body)))
+    }
+
+    /// Whether `request` already holds the representation this tags. `If-None-Match` is
+    /// compared weakly, as RFC 9110 requires.
+    pub fn matches(
+        &self,
+        request: &HttpRequest,
+    ) -> bool {
+        request
+            .headers()
+            .get_all(header::IF_NONE_MATCH)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == self.0)
+    }
+
+    /// The `304 Not Modified` answering a request which [matches](Self::matches).
+    pub fn not_modified(&self) -> HttpResponse {
+        HttpResponse::NotModified()
+            .insert_header(self.header())
+            .finish()
+    }
+
+    pub fn header(&self) -> (header::HeaderName, HeaderValue) {
+        (
+            header::ETAG,
+            HeaderValue::from_str(&self.0).expect("etags are hex digests"),
+        )
+    }
+}
+
+/// Responds with `value` as JSON tagged by its body, or `304 Not Modified` when `request`
+/// already holds it.
+pub fn json(
+    request: &HttpRequest,
+    value: &impl serde::Serialize,
+) -> crate::Result<HttpResponse> {
+    let body = serde_json::to_vec(value)?;
+    let etag = ETag::of_body(&body);
+    if etag.matches(request) {
+        return Ok(etag.not_modified());
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header(etag.header())
+        .body(body))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request(if_none_match: &str) -> HttpRequest {
+        actix_web::test::TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, if_none_match))
+            .to_http_request()
+    }
+
+    #[test]
+    fn matches_if_none_match() {
+        let etag = ETag::of_artifact("abc", "json");
+        let tag = etag.0.clone();
+
+        assert!(etag.matches(&request(&tag)));
+        assert!(etag.matches(&request(&format!("W/{tag}"))));
+        assert!(etag.matches(&request(&format!("\"other\", {tag}"))));
+        assert!(etag.matches(&request("*")));
+        assert!(!etag.matches(&request("\"other\"")));
+        assert!(!ETag::of_artifact("abc", "binary").matches(&request(&tag)));
+    }
+
+    #[test]
+    fn json_answers_not_modified() {
+        let value = serde_json::json!({ "name": "abc" });
+        let tagged = json(
+            &actix_web::test::TestRequest::default().to_http_request(),
+            &value,
+        )
+        .unwrap();
+        assert_eq!(tagged.status(), actix_web::http::StatusCode::OK);
+
+        let tag = tagged
+            .headers()
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let revalidated = json(&request(tag), &value).unwrap();
+        assert_eq!(
+            revalidated.status(),
+            actix_web::http::StatusCode::NOT_MODIFIED
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Background jobs run alongside the server.
+
+use std::time::Duration;
+
+/// Compacts the download log into the daily download buckets every `interval`, until the
+/// process exits.
+pub(crate) async fn compact_downloads(
+    db: sea_orm::DatabaseConnection,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        match kintsu_registry_db::entities::Version::compact_downloads(&db, chrono::Utc::now())
+            .await
+        {
+            Ok(0) => {},
+            Ok(compacted) => tracing::debug!("compacted {compacted} downloads"),
+            Err(err) => tracing::error!("failed to compact downloads: {err:?}"),
+        }
+    }
+}
+
+/// Syncs the members of every imported org from GitHub every `interval`, until the process
+/// exits.
+pub(crate) async fn sync_orgs(
+    db: sea_orm::DatabaseConnection,
+    sync: actix_web::web::Data<crate::org_sync::OrgSync>,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        let orgs = match kintsu_registry_db::entities::Org::all(&db).await {
+            Ok(orgs) => orgs,
+            Err(err) => {
+                tracing::error!("failed to list orgs to sync: {err:?}");
+                continue;
+            },
+        };
+
+        for org in orgs {
+            match sync.sync_org(&db, None, &org, None).await {
+                Ok(changes) if changes.is_empty() => {},
+                Ok(changes) => {
+                    tracing::info!(
+                        "synced {} membership changes of {}",
+                        changes.len(),
+                        org.name
+                    )
+                },
+                Err(err) => tracing::error!("failed to sync members of {}: {err:?}", org.name),
+            }
+        }
+    }
+}
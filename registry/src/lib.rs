@@ -6,9 +6,20 @@ use std::collections::HashMap;
 
 pub(crate) mod apikey;
 pub mod app;
+pub(crate) mod client;
+pub mod compile;
 pub mod config;
+pub(crate) mod etag;
+pub(crate) mod jobs;
+#[cfg(feature = "loadtest")]
+pub mod loadtest;
+pub mod metrics;
 pub mod oauth;
+pub mod org_sync;
 pub mod principal;
+pub mod rate_limit;
+pub(crate) mod readme;
+pub mod request_id;
 pub(crate) mod resolver;
 pub mod routes;
 pub(crate) mod session;
@@ -19,6 +30,8 @@ pub use kintsu_registry_core::*;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("database error: {0:?}")]
@@ -75,18 +88,45 @@ pub enum Error {
     #[error("manifest error: {0}")]
     ManifestError(#[from] kintsu_manifests::Error),
 
+    #[error("configuration error: {0}")]
+    Config(#[from] kintsu_cli_core::config::ConfigError),
+
     #[error("{0}")]
     StorageError(#[from] kintsu_registry_storage::StorageError),
 
     #[error("{0}")]
     CompileError(#[from] kintsu_parser::Error),
 
+    #[error("{0}")]
+    DeclarationEncoding(#[from] kintsu_parser::declare::encoding::EncodingError),
+
     #[error("TLS configuration error: {0}")]
     TlsConfig(String),
 
+    #[error("storage configuration error: {0}")]
+    StorageConfig(String),
+
+    #[error("package storage does not support direct uploads")]
+    UploadNotSupported,
+
+    #[error("rate limit exceeded, retry in {}s", retry_after.as_secs().max(1))]
+    RateLimited { retry_after: std::time::Duration },
+
+    #[error("oauth configuration error: {0}")]
+    OAuthConfig(String),
+
+    #[error("rate limit configuration error: {0}")]
+    RateLimitConfig(String),
+
+    #[error("rate limit store error: {0}")]
+    RateLimitStore(String),
+
     #[error("TLS error: {0}")]
     Tls(#[from] rustls::Error),
 
+    #[error("metrics error: {0}")]
+    Metrics(#[from] prometheus::Error),
+
     #[error("multiple errors occurred: {0:?}")]
     Multiple(Vec<Error>),
 }
@@ -153,6 +193,7 @@ impl Error {
                     error_description: Some("Validation errors found".to_string()),
                     error_uri: None,
                     validation: Some(validation),
+                    request_id: None,
                 }
             },
             Error::AuthorizationRequired => {
@@ -225,6 +266,18 @@ impl Error {
                     Some(auth_err.to_string()),
                 )
             },
+            Error::UploadNotSupported => {
+                ErrorResponse::from_public_error(
+                    PublicErrorType::NotImplemented,
+                    Some(self.to_string()),
+                )
+            },
+            Error::RateLimited { .. } => {
+                ErrorResponse::from_public_error(
+                    PublicErrorType::RateLimited,
+                    Some(self.to_string()),
+                )
+            },
             Error::PackagingError(err) => {
                 ErrorResponse::from_public_error(
                     PublicErrorType::PackagingError(err.clone()),
@@ -254,6 +307,7 @@ impl Error {
                     }),
                     error_uri: error_uri.clone(),
                     validation: None,
+                    request_id: None,
                 }
             },
             _ => ErrorResponse::internal(),
@@ -298,14 +352,23 @@ impl ResponseError for Error {
             | Error::Database(kintsu_registry_db::Error::PackageVersionExists { .. }) => {
                 actix_web::http::StatusCode::CONFLICT
             },
+            Error::UploadNotSupported => actix_web::http::StatusCode::NOT_IMPLEMENTED,
+            Error::RateLimited { .. } => actix_web::http::StatusCode::TOO_MANY_REQUESTS,
             Error::Octocrab(_)
             | Error::RequestError(_)
             | Error::Database(_)
             | Error::IoError(_)
+            | Error::Config(_)
             | Error::TlsConfig(_)
+            | Error::StorageConfig(_)
+            | Error::OAuthConfig(_)
+            | Error::RateLimitConfig(_)
+            | Error::RateLimitStore(_)
             | Error::Tls(_)
+            | Error::Metrics(_)
             | Error::DatabaseConnect(_)
             | Error::StorageError(_)
+            | Error::DeclarationEncoding(_)
             | Error::MissingData { .. }
             | Error::AuthorizationError(AuthorizationError::NotApplicable { .. }) => {
                 actix_web::http::StatusCode::INTERNAL_SERVER_ERROR
@@ -314,7 +377,16 @@ impl ResponseError for Error {
     }
 
     fn error_response(&self) -> actix_web::HttpResponse<actix_web::body::BoxBody> {
-        let response = self.to_error_response();
-        actix_web::HttpResponse::build(self.status_code()).json(response)
+        let mut response = self.to_error_response();
+        response.request_id = kintsu_registry_events::current_request_id();
+
+        let mut builder = actix_web::HttpResponse::build(self.status_code());
+        if let Error::RateLimited { retry_after } = self {
+            builder.insert_header((
+                actix_web::http::header::RETRY_AFTER,
+                retry_after.as_secs().max(1),
+            ));
+        }
+        builder.json(response)
     }
 }
@@ -0,0 +1,31 @@
+//! Load-testing harness for the registry.
+//!
+//! [`seed`] populates a registry database and package storage with synthetic packages
+//! through the engine layer, [`traffic`] replays a weighted mix of resolve, download,
+//! search and publish requests against a running instance, and [`stats`] reduces the
+//! observed latencies to percentiles.
+//!
+//! Enabled with the `loadtest` feature and driven by the `kintsu-registry-loadtest`
+//! binary.
+
+pub mod seed;
+pub mod stats;
+pub mod traffic;
+
+pub use seed::{Catalog, CatalogPackage, SeedPlan};
+pub use stats::{LatencyRecorder, LatencySummary, LoadReport};
+pub use traffic::{Operation, RunConfig, TrafficMix};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Registry(#[from] crate::Error),
+
+    #[error("request error: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("invalid load-test configuration: {0}")]
+    Config(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,319 @@
+//! Seed data generation.
+//!
+//! Packages are published through [`StagePublishPackage::process`], so seeded versions get
+//! the same storage layout, checksums and role assignments as ones published over HTTP.
+//! Seeding is idempotent: versions that already exist are kept and added to the catalog.
+
+use std::{collections::HashMap, fmt::Write as _, sync::Arc};
+
+use convert_case::{Case, Casing};
+use futures_util::{StreamExt, TryStreamExt, stream};
+use kintsu_fs::memory::MemoryFileSystem;
+use kintsu_manifests::{config::NewForNamed, package::PackageManifests};
+use kintsu_registry_db::{
+    PackageStorage,
+    engine::{NewApiKey, PrincipalIdentity, package::StagePublishPackage, user::ExternalIdentity},
+    entities::{Permission, Scope, Version},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+
+/// Identity provider the load-test publisher logs in through, which no real login uses.
+const LOADTEST_PROVIDER: &str = "loadtest";
+
+/// Words used for package keywords and search traffic.
+pub const KEYWORDS: &[&str] = &[
+    "api",
+    "billing",
+    "events",
+    "geo",
+    "identity",
+    "inventory",
+    "ledger",
+    "media",
+    "metrics",
+    "orders",
+    "payments",
+    "search",
+    "shipping",
+    "storage",
+    "users",
+];
+
+/// Shape of the data set produced by [`seed`].
+#[derive(Debug, Clone)]
+pub struct SeedPlan {
+    /// Package names are `<prefix>-<n>`
+    pub prefix: String,
+    pub packages: usize,
+    pub versions_per_package: usize,
+    /// Structs generated per package, controls source and declaration size
+    pub types_per_package: usize,
+    /// Upper bound of dependencies per version, drawn from previously seeded packages
+    pub max_dependencies: usize,
+    /// Packages published concurrently
+    pub concurrency: usize,
+    pub rng_seed: u64,
+}
+
+impl Default for SeedPlan {
+    fn default() -> Self {
+        Self {
+            prefix: "loadtest".into(),
+            packages: 1000,
+            versions_per_package: 3,
+            types_per_package: 8,
+            max_dependencies: 3,
+            concurrency: 8,
+            rng_seed: 0,
+        }
+    }
+}
+
+/// Seeded packages and the publish token, consumed by the traffic runner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    pub prefix: String,
+    /// API key with publish permission on `<prefix>*`
+    pub token: String,
+    pub types_per_package: usize,
+    pub packages: Vec<CatalogPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogPackage {
+    pub name: String,
+    pub versions: Vec<String>,
+}
+
+/// Builds the manifest and sources of a synthetic package.
+pub fn synthetic_package(
+    name: &str,
+    version: &str,
+    types: usize,
+    keyword: &str,
+) -> crate::Result<(PackageManifests, MemoryFileSystem)> {
+    let fs = MemoryFileSystem::new();
+
+    fs.add_file(
+        "schema.toml",
+        format!(
+            r#"version = "v1"
+
+[package]
+name = "{name}"
+version = "{version}"
+description = "Synthetic {keyword} package generated by the registry load test"
+license = "MIT"
+readme = "Generated by kintsu-registry-loadtest."
+repository = "https://example.com/loadtest/{name}"
+keywords = ["loadtest", "{keyword}"]
+"#
+        ),
+    );
+
+    fs.add_file(
+        "schema/lib.ks",
+        format!("namespace {};\nuse types;\n", name.to_case(Case::Snake)),
+    );
+
+    let mut source = String::from("namespace types;\n");
+    for i in 0..types.max(1) {
+        let _ = write!(
+            source,
+            "\n/// Synthetic record {i}\nstruct Record{i} {{\n    id: i64,\n    name: str,\n    tags: str[],\n    score: f64\n}};\n"
+        );
+    }
+    source.push_str("\nenum Status {\n    Active = 1,\n    Inactive = 2\n};\n");
+    fs.add_file("schema/types.ks", source);
+
+    let manifest = PackageManifests::new(&fs, "")?;
+    Ok((manifest, fs))
+}
+
+/// Compiles a synthetic package and publishes it through the engine layer.
+async fn publish(
+    db: &DatabaseConnection,
+    storage: Arc<PackageStorage>,
+    principal: &PrincipalIdentity,
+    manifest: PackageManifests,
+    fs: MemoryFileSystem,
+    dependencies: Vec<i64>,
+) -> crate::Result<Version> {
+    let ctx = kintsu_parser::ctx::CompileCtx::with_fs_and_config(
+        Arc::new(fs.clone()),
+        Arc::new(crate::resolver::InternalPackageResolver::new(HashMap::new())),
+        "./",
+        4,
+        false,
+    )
+    .await?;
+
+    ctx.finalize().await?;
+    let declarations = ctx.emit_declarations().await?;
+
+    Ok(StagePublishPackage::process(
+        db,
+        principal,
+        storage,
+        fs,
+        manifest,
+        declarations,
+        dependencies,
+    )
+    .await?)
+}
+
+/// Publishes every version of one package, oldest first, returning their ids.
+async fn seed_package(
+    db: &DatabaseConnection,
+    storage: Arc<PackageStorage>,
+    principal: &PrincipalIdentity,
+    plan: &SeedPlan,
+    index: usize,
+    dependency_pool: &[i64],
+) -> crate::Result<(CatalogPackage, i64)> {
+    let name = format!("{}-{index:05}", plan.prefix);
+    let mut rng = StdRng::seed_from_u64(plan.rng_seed ^ index as u64);
+    let keyword = KEYWORDS[index % KEYWORDS.len()];
+
+    let mut versions = Vec::with_capacity(plan.versions_per_package);
+    let mut latest = 0;
+    for minor in 0..plan.versions_per_package.max(1) {
+        let version = format!("0.{minor}.0");
+
+        let count = rng.random_range(
+            0..=plan
+                .max_dependencies
+                .min(dependency_pool.len()),
+        );
+        let dependencies: Vec<i64> = dependency_pool
+            .choose_multiple(&mut rng, count)
+            .copied()
+            .collect();
+
+        let (manifest, fs) = synthetic_package(&name, &version, plan.types_per_package, keyword)?;
+        latest = match publish(db, storage.clone(), principal, manifest, fs, dependencies).await {
+            Ok(published) => published.id,
+            Err(crate::Error::Database(kintsu_registry_db::Error::PackageVersionExists {
+                ..
+            })) => {
+                Version::by_name_and_version(db, &name, &version)
+                    .await?
+                    .id
+            },
+            Err(err) => return Err(err),
+        };
+        versions.push(version);
+    }
+
+    Ok((CatalogPackage { name, versions }, latest))
+}
+
+/// Seeds the registry according to `plan`.
+///
+/// Packages are published in batches of `plan.concurrency`; dependencies are only drawn
+/// from the latest versions of earlier batches so every referenced version exists.
+pub async fn seed(
+    db: &DatabaseConnection,
+    storage: Arc<PackageStorage>,
+    plan: &SeedPlan,
+) -> crate::Result<Catalog> {
+    let user = kintsu_registry_db::engine::user::login_with_identity(
+        db,
+        ExternalIdentity {
+            provider: LOADTEST_PROVIDER.into(),
+            subject: plan.prefix.clone(),
+            email: format!("{}@loadtest.invalid", plan.prefix),
+            login: format!("{}-publisher", plan.prefix),
+            avatar: None,
+            gh_id: None,
+        },
+    )
+    .await?;
+
+    let principal = PrincipalIdentity::UserSession { user: user.clone() };
+
+    let token = NewApiKey::new_for_user(
+        Some("registry load test".into()),
+        vec![Scope::new(format!("{}*", plan.prefix))],
+        vec![Permission::PublishPackage],
+        chrono::Utc::now() + chrono::Duration::days(1),
+        user.id,
+    )
+    .qualify(db, &principal)
+    .await?
+    .key;
+
+    let mut packages = Vec::with_capacity(plan.packages);
+    let mut dependency_pool = Vec::with_capacity(plan.packages);
+    let batch_size = plan.concurrency.max(1);
+
+    for batch_start in (0..plan.packages).step_by(batch_size) {
+        let batch_end = (batch_start + batch_size).min(plan.packages);
+        let pool = dependency_pool.clone();
+
+        let mut batch: Vec<_> = stream::iter(batch_start..batch_end)
+            .map(|index| {
+                let storage = storage.clone();
+                let principal = &principal;
+                let pool = &pool;
+                async move { seed_package(db, storage, principal, plan, index, pool).await }
+            })
+            .buffer_unordered(batch_size)
+            .try_collect()
+            .await?;
+        batch.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+
+        for (package, latest) in batch {
+            dependency_pool.push(latest);
+            packages.push(package);
+        }
+
+        tracing::info!("seeded {}/{} packages", packages.len(), plan.packages);
+    }
+
+    Ok(Catalog {
+        prefix: plan.prefix.clone(),
+        token,
+        types_per_package: plan.types_per_package,
+        packages,
+    })
+}
+
+/// Connects to the database and package storage described by the registry `config` and
+/// seeds them according to `plan`.
+pub async fn seed_with_config(
+    config: &crate::config::Config,
+    plan: &SeedPlan,
+) -> crate::Result<Catalog> {
+    let db = config.database.connect().await?;
+    let storage = Arc::new(config.storage().await?);
+
+    seed(&db, storage, plan).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn synthetic_package_compiles() {
+        let (manifest, fs) = synthetic_package("loadtest-00001", "0.2.0", 3, "billing").unwrap();
+        assert_eq!(manifest.package().name, "loadtest-00001");
+        assert_eq!(manifest.package().keywords, vec!["loadtest", "billing"]);
+
+        let ctx = kintsu_parser::ctx::CompileCtx::with_fs_and_config(
+            Arc::new(fs),
+            Arc::new(crate::resolver::InternalPackageResolver::new(HashMap::new())),
+            "./",
+            4,
+            false,
+        )
+        .await
+        .unwrap();
+        ctx.finalize().await.unwrap();
+        ctx.emit_declarations().await.unwrap();
+    }
+}
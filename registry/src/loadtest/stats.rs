@@ -0,0 +1,246 @@
+//! Latency aggregation for load-test runs.
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use serde::Serialize;
+
+use super::traffic::Operation;
+
+#[derive(Debug, Default, Clone)]
+struct OperationSamples {
+    latencies: Vec<Duration>,
+    errors: u64,
+}
+
+/// Collects per-operation latencies. Each worker keeps its own recorder; they are
+/// merged once the run finishes so the hot path never contends on a lock.
+#[derive(Debug, Default, Clone)]
+pub struct LatencyRecorder {
+    samples: BTreeMap<Operation, OperationSamples>,
+}
+
+impl LatencyRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one request. Only successful requests contribute to latency percentiles.
+    pub fn record(
+        &mut self,
+        operation: Operation,
+        elapsed: Duration,
+        ok: bool,
+    ) {
+        let samples = self.samples.entry(operation).or_default();
+        if ok {
+            samples.latencies.push(elapsed);
+        } else {
+            samples.errors += 1;
+        }
+    }
+
+    pub fn merge(
+        &mut self,
+        other: LatencyRecorder,
+    ) {
+        for (operation, theirs) in other.samples {
+            let ours = self.samples.entry(operation).or_default();
+            ours.latencies.extend(theirs.latencies);
+            ours.errors += theirs.errors;
+        }
+    }
+
+    pub fn summarize(
+        mut self,
+        elapsed: Duration,
+    ) -> LoadReport {
+        let secs = elapsed.as_secs_f64();
+        let operations: Vec<_> = self
+            .samples
+            .iter_mut()
+            .map(|(operation, samples)| {
+                samples.latencies.sort_unstable();
+                LatencySummary::new(*operation, samples, secs)
+            })
+            .collect();
+
+        let requests = operations.iter().map(|op| op.requests).sum();
+        let errors = operations.iter().map(|op| op.errors).sum();
+
+        LoadReport {
+            duration_ms: elapsed.as_secs_f64() * 1000.0,
+            requests,
+            errors,
+            throughput_rps: rate(requests, secs),
+            operations,
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice. `p` is in `0.0..=100.0`.
+pub fn percentile(
+    sorted: &[Duration],
+    p: f64,
+) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn rate(
+    count: u64,
+    secs: f64,
+) -> f64 {
+    if secs > 0.0 {
+        count as f64 / secs
+    } else {
+        0.0
+    }
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub operation: Operation,
+    /// Successful and failed requests
+    pub requests: u64,
+    pub errors: u64,
+    pub throughput_rps: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    fn new(
+        operation: Operation,
+        samples: &OperationSamples,
+        secs: f64,
+    ) -> Self {
+        let sorted = &samples.latencies;
+        let requests = sorted.len() as u64 + samples.errors;
+        let mean_ms = if sorted.is_empty() {
+            0.0
+        } else {
+            ms(sorted.iter().sum::<Duration>()) / sorted.len() as f64
+        };
+
+        Self {
+            operation,
+            requests,
+            errors: samples.errors,
+            throughput_rps: rate(requests, secs),
+            mean_ms,
+            p50_ms: ms(percentile(sorted, 50.0)),
+            p90_ms: ms(percentile(sorted, 90.0)),
+            p95_ms: ms(percentile(sorted, 95.0)),
+            p99_ms: ms(percentile(sorted, 99.0)),
+            max_ms: ms(sorted.last().copied().unwrap_or_default()),
+        }
+    }
+}
+
+/// Result of a traffic run. Serializes to JSON and displays as a table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoadReport {
+    pub duration_ms: f64,
+    pub requests: u64,
+    pub errors: u64,
+    pub throughput_rps: f64,
+    pub operations: Vec<LatencySummary>,
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        writeln!(
+            f,
+            "{} requests in {:.1} s ({:.1} req/s, {} errors)",
+            self.requests,
+            self.duration_ms / 1000.0,
+            self.throughput_rps,
+            self.errors
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<10} {:>9} {:>7} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9} {:>9}",
+            "operation", "requests", "errors", "req/s", "mean", "p50", "p90", "p95", "p99", "max"
+        )?;
+        for op in &self.operations {
+            writeln!(
+                f,
+                "{:<10} {:>9} {:>7} {:>9.1} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+                op.operation,
+                op.requests,
+                op.errors,
+                op.throughput_rps,
+                op.mean_ms,
+                op.p50_ms,
+                op.p90_ms,
+                op.p95_ms,
+                op.p99_ms,
+                op.max_ms
+            )?;
+        }
+        write!(f, "\nlatencies in ms, successful requests only")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: impl IntoIterator<Item = u64>) -> Vec<Duration> {
+        values
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect()
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let sorted = millis(1..=100);
+        assert_eq!(percentile(&sorted, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&sorted, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&sorted, 100.0), Duration::from_millis(100));
+        assert_eq!(percentile(&sorted, 0.0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn merged_recorders_summarize_per_operation() {
+        let mut a = LatencyRecorder::new();
+        let mut b = LatencyRecorder::new();
+        for latency in millis([10, 20, 30]) {
+            a.record(Operation::Download, latency, true);
+        }
+        b.record(Operation::Download, Duration::from_millis(40), true);
+        b.record(Operation::Download, Duration::from_millis(1), false);
+        b.record(Operation::Search, Duration::from_millis(5), true);
+        a.merge(b);
+
+        let report = a.summarize(Duration::from_secs(2));
+        assert_eq!(report.requests, 6);
+        assert_eq!(report.errors, 1);
+        assert_eq!(report.throughput_rps, 3.0);
+
+        let download = &report.operations[0];
+        assert_eq!(download.operation, Operation::Download);
+        assert_eq!(download.requests, 5);
+        assert_eq!(download.mean_ms, 25.0);
+        assert_eq!(download.p50_ms, 20.0);
+        assert_eq!(download.max_ms, 40.0);
+        assert_eq!(report.operations[1].operation, Operation::Search);
+    }
+}
@@ -0,0 +1,372 @@
+//! Traffic replay against a running registry.
+
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use serde::Serialize;
+
+use super::{
+    seed::{Catalog, KEYWORDS, synthetic_package},
+    stats::{LatencyRecorder, LoadReport},
+};
+
+/// A request type in the traffic mix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// `GET /package/{name}/{version}/dependencies`
+    Resolve,
+    /// `GET /package/{name}/{version}/download`
+    Download,
+    /// `GET /packages/search?q=...`
+    Search,
+    /// `POST /packages/publish` with a new synthetic package
+    Publish,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 4] = [
+        Operation::Resolve,
+        Operation::Download,
+        Operation::Search,
+        Operation::Publish,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Resolve => "resolve",
+            Operation::Download => "download",
+            Operation::Search => "search",
+            Operation::Publish => "publish",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Relative weights of each [`Operation`], e.g. `resolve=50,download=30,search=15,publish=5`.
+///
+/// Operations missing from the string get a weight of zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrafficMix {
+    weights: Vec<(Operation, u32)>,
+}
+
+impl Default for TrafficMix {
+    fn default() -> Self {
+        Self {
+            weights: vec![
+                (Operation::Resolve, 50),
+                (Operation::Download, 30),
+                (Operation::Search, 15),
+                (Operation::Publish, 5),
+            ],
+        }
+    }
+}
+
+impl FromStr for TrafficMix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Vec::new();
+        for part in s
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            let (name, weight) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected `operation=weight`, got `{part}`"))?;
+            let operation = Operation::ALL
+                .into_iter()
+                .find(|op| op.name() == name.trim())
+                .ok_or_else(|| format!("unknown operation `{}`", name.trim()))?;
+            let weight: u32 = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid weight `{}` for {operation}", weight.trim()))?;
+            if weights
+                .iter()
+                .any(|(op, _)| *op == operation)
+            {
+                return Err(format!("{operation} is listed more than once"));
+            }
+            weights.push((operation, weight));
+        }
+
+        if weights.iter().all(|(_, w)| *w == 0) {
+            return Err("at least one operation needs a non-zero weight".into());
+        }
+        Ok(Self { weights })
+    }
+}
+
+impl TrafficMix {
+    pub fn weight(
+        &self,
+        operation: Operation,
+    ) -> u32 {
+        self.weights
+            .iter()
+            .find(|(op, _)| *op == operation)
+            .map(|(_, w)| *w)
+            .unwrap_or(0)
+    }
+
+    /// Picks an operation with probability proportional to its weight.
+    pub fn pick<R: Rng>(
+        &self,
+        rng: &mut R,
+    ) -> Operation {
+        let total: u32 = self.weights.iter().map(|(_, w)| w).sum();
+        let mut roll = rng.random_range(0..total);
+        for (operation, weight) in &self.weights {
+            if roll < *weight {
+                return *operation;
+            }
+            roll -= weight;
+        }
+        unreachable!("roll is always below the total weight")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    /// Registry base URL, e.g. `http://127.0.0.1:8000`
+    pub base_url: String,
+    /// Concurrent workers, each issuing one request at a time
+    pub concurrency: usize,
+    pub duration: Duration,
+    /// Stop after this many requests even if `duration` has not elapsed
+    pub max_requests: Option<u64>,
+    pub mix: TrafficMix,
+    pub rng_seed: u64,
+}
+
+struct RunState {
+    client: reqwest::Client,
+    config: RunConfig,
+    catalog: Catalog,
+    issued: AtomicU64,
+    published: AtomicU64,
+    /// Distinguishes publish names across runs against the same database
+    run_id: u64,
+}
+
+/// Replays `config.mix` against the registry until the duration or request budget runs out.
+pub async fn run(
+    config: RunConfig,
+    catalog: Catalog,
+) -> super::Result<LoadReport> {
+    let uses_catalog = [Operation::Resolve, Operation::Download]
+        .into_iter()
+        .any(|op| config.mix.weight(op) > 0);
+    if uses_catalog && catalog.packages.is_empty() {
+        return Err(super::Error::Config(
+            "catalog contains no packages; run the seed command first".into(),
+        ));
+    }
+
+    let state = Arc::new(RunState {
+        client: reqwest::Client::new(),
+        run_id: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        config,
+        catalog,
+        issued: AtomicU64::new(0),
+        published: AtomicU64::new(0),
+    });
+
+    let started = Instant::now();
+    let workers: Vec<_> = (0..state.config.concurrency.max(1))
+        .map(|worker| tokio::spawn(worker_loop(state.clone(), started, worker as u64)))
+        .collect();
+
+    let mut recorder = LatencyRecorder::new();
+    for worker in workers {
+        match worker.await {
+            Ok(samples) => recorder.merge(samples),
+            Err(err) => tracing::error!("load-test worker failed: {err}"),
+        }
+    }
+
+    Ok(recorder.summarize(started.elapsed()))
+}
+
+async fn worker_loop(
+    state: Arc<RunState>,
+    started: Instant,
+    worker: u64,
+) -> LatencyRecorder {
+    let mut rng = StdRng::seed_from_u64(state.config.rng_seed.wrapping_add(worker));
+    let mut recorder = LatencyRecorder::new();
+
+    while started.elapsed() < state.config.duration {
+        let issued = state.issued.fetch_add(1, Ordering::Relaxed);
+        if state
+            .config
+            .max_requests
+            .is_some_and(|max| issued >= max)
+        {
+            break;
+        }
+
+        let operation = state.config.mix.pick(&mut rng);
+        let request = build_request(&state, operation, &mut rng);
+
+        let start = Instant::now();
+        let ok = match request {
+            Ok(request) => {
+                match state.client.execute(request).await {
+                    // read the body so downloads are measured end to end
+                    Ok(response) => {
+                        let success = response.status().is_success();
+                        response.bytes().await.is_ok() && success
+                    },
+                    Err(err) => {
+                        tracing::debug!("{operation} request failed: {err}");
+                        false
+                    },
+                }
+            },
+            Err(err) => {
+                tracing::warn!("could not build {operation} request: {err}");
+                false
+            },
+        };
+        recorder.record(operation, start.elapsed(), ok);
+    }
+
+    recorder
+}
+
+fn build_request(
+    state: &RunState,
+    operation: Operation,
+    rng: &mut StdRng,
+) -> super::Result<reqwest::Request> {
+    let base = state.config.base_url.trim_end_matches('/');
+    let client = &state.client;
+
+    let pick_version = |rng: &mut StdRng| {
+        let package = state
+            .catalog
+            .packages
+            .choose(rng)
+            .expect("catalog is not empty");
+        let version = package
+            .versions
+            .choose(rng)
+            .expect("seeded packages have versions");
+        (package.name.as_str(), version.as_str())
+    };
+
+    let request = match operation {
+        Operation::Resolve => {
+            let (name, version) = pick_version(rng);
+            client.get(format!("{base}/package/{name}/{version}/dependencies"))
+        },
+        Operation::Download => {
+            let (name, version) = pick_version(rng);
+            client.get(format!("{base}/package/{name}/{version}/download"))
+        },
+        Operation::Search => {
+            // a name prefix of a random package, e.g. `loadtest-004`
+            let query = match state.catalog.packages.choose(rng) {
+                Some(package) => {
+                    let keep = state.catalog.prefix.len() + 1 + rng.random_range(1..=3);
+                    package.name[..keep.min(package.name.len())].to_string()
+                },
+                None => state.catalog.prefix.clone(),
+            };
+            let url =
+                url::Url::parse_with_params(&format!("{base}/packages/search"), [("q", query)])
+                    .map_err(|err| super::Error::Config(format!("invalid base url: {err}")))?;
+            client.get(url)
+        },
+        Operation::Publish => {
+            let n = state
+                .published
+                .fetch_add(1, Ordering::Relaxed);
+            let name = format!("{}-pub-{}-{n}", state.catalog.prefix, state.run_id);
+            let keyword = KEYWORDS
+                .choose(rng)
+                .expect("keywords are not empty");
+            let (manifest, package_data) =
+                synthetic_package(&name, "0.1.0", state.catalog.types_per_package, keyword)?;
+            let body = kintsu_registry_core::models::PublishPackageRequest {
+                manifest,
+                package_data,
+            };
+
+            client
+                .post(format!("{base}/packages/publish"))
+                .bearer_auth(&state.catalog.token)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(&body).map_err(crate::Error::from)?)
+        },
+    };
+
+    Ok(request.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mix() {
+        let mix: TrafficMix = "resolve=70, search=30".parse().unwrap();
+        assert_eq!(mix.weight(Operation::Resolve), 70);
+        assert_eq!(mix.weight(Operation::Search), 30);
+        assert_eq!(mix.weight(Operation::Publish), 0);
+
+        assert!("fetch=1".parse::<TrafficMix>().is_err());
+        assert!(
+            "resolve=1,resolve=2"
+                .parse::<TrafficMix>()
+                .is_err()
+        );
+        assert!("resolve=0".parse::<TrafficMix>().is_err());
+        assert!("resolve".parse::<TrafficMix>().is_err());
+    }
+
+    #[test]
+    fn pick_respects_weights() {
+        let mix: TrafficMix = "download=3,publish=1".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let picks: Vec<_> = (0..4000)
+            .map(|_| mix.pick(&mut rng))
+            .collect();
+        let downloads = picks
+            .iter()
+            .filter(|op| **op == Operation::Download)
+            .count();
+
+        assert!(
+            picks
+                .iter()
+                .all(|op| matches!(op, Operation::Download | Operation::Publish))
+        );
+        assert!((2800..3200).contains(&downloads), "downloads: {downloads}");
+    }
+}
@@ -0,0 +1,168 @@
+//! Prometheus metrics for the registry, served at `/metrics`.
+//!
+//! [`Metrics`] owns the registry every counter is gathered from. Subsystems outside this crate
+//! keep their own counters and register them through [`Metrics::registry`], as the event
+//! system and package storage do.
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web,
+};
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    publishes: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> crate::Result<Self> {
+        let registry = Registry::new();
+
+        let requests = IntCounterVec::new(
+            Opts::new("kintsu_http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )?;
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "kintsu_http_request_duration_seconds",
+                "Time taken to handle HTTP requests",
+            ),
+            &["method", "route"],
+        )?;
+        let publishes = IntCounterVec::new(
+            Opts::new("kintsu_publishes_total", "Package publishes, by outcome"),
+            &["outcome"],
+        )?;
+
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(request_duration.clone()))?;
+        registry.register(Box::new(publishes.clone()))?;
+
+        kintsu_registry_events::register_metrics(&registry)?;
+        kintsu_registry_storage::metrics::register_metrics(&registry)?;
+
+        Ok(Self {
+            registry,
+            requests,
+            request_duration,
+            publishes,
+        })
+    }
+
+    /// The registry metrics are gathered from, for subsystems to register their own with.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    pub fn record_publish(
+        &self,
+        published: bool,
+    ) {
+        let outcome = if published {
+            "published"
+        } else {
+            "failed"
+        };
+        self.publishes
+            .with_label_values(&[outcome])
+            .inc();
+    }
+
+    /// Every metric in the Prometheus text format.
+    pub fn render(&self) -> crate::Result<String> {
+        Ok(TextEncoder::new().encode_to_string(&self.registry.gather())?)
+    }
+}
+
+/// Counts and times every request by its route pattern, so paths with ids do not each get
+/// their own series.
+pub async fn track_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse, actix_web::Error> {
+    let Some(metrics) = req.app_data::<web::Data<Metrics>>().cloned() else {
+        return Ok(next.call(req).await?.map_into_boxed_body());
+    };
+
+    let method = req.method().to_string();
+    let route = req
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".into());
+    let timer = metrics
+        .request_duration
+        .with_label_values(&[&method, &route])
+        .start_timer();
+
+    let res = next.call(req).await;
+    timer.observe_duration();
+
+    let status = match &res {
+        Ok(res) => res.status(),
+        Err(err) => err.as_response_error().status_code(),
+    };
+    metrics
+        .requests
+        .with_label_values(&[&method, &route, status.as_str()])
+        .inc();
+
+    Ok(res?.map_into_boxed_body())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::{App, HttpResponse, test};
+
+    #[actix_web::test]
+    async fn tracks_requests_by_route_pattern() {
+        let metrics = web::Data::new(Metrics::new().unwrap());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(metrics.clone())
+                .route(
+                    "/package/{name}",
+                    web::get().to(|| async { HttpResponse::Ok().finish() }),
+                )
+                .wrap(actix_web::middleware::from_fn(track_requests)),
+        )
+        .await;
+
+        for name in ["a", "b"] {
+            let req = test::TestRequest::get()
+                .uri(&format!("/package/{name}"))
+                .to_request();
+            test::call_service(&app, req).await;
+        }
+        let req = test::TestRequest::get()
+            .uri("/nowhere")
+            .to_request();
+        test::call_service(&app, req).await;
+
+        assert_eq!(
+            metrics
+                .requests
+                .with_label_values(&["GET", "/package/{name}", "200"])
+                .get(),
+            2
+        );
+        assert_eq!(
+            metrics
+                .requests
+                .with_label_values(&["GET", "unmatched", "404"])
+                .get(),
+            1
+        );
+
+        metrics.record_publish(true);
+        let rendered = metrics.render().unwrap();
+        assert!(rendered.contains("kintsu_http_request_duration_seconds_bucket"));
+        assert!(rendered.contains("kintsu_publishes_total{outcome=\"published\"} 1"));
+        assert!(rendered.contains("kintsu_audit_events_emitted_total"));
+    }
+}
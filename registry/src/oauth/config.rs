@@ -36,3 +36,47 @@ pub struct GhOauthConfig {
     #[serde(alias = "CLIENT")]
     pub client: GhClientConfig,
 }
+
+fn default_oidc_name() -> String {
+    "oidc".into()
+}
+
+fn default_oidc_scope() -> String {
+    "openid email profile".into()
+}
+
+#[derive(validator::Validate, serde::Deserialize, Debug)]
+pub struct OidcClientConfig {
+    /// env: OIDC_CLIENT_ID
+    #[serde(alias = "ID")]
+    pub id: String,
+    /// env: OIDC_CLIENT_SECRET, unset for public clients which rely on PKCE alone
+    #[serde(default, alias = "SECRET")]
+    pub secret: Option<secrecy::SecretString>,
+}
+
+#[derive(validator::Validate, serde::Deserialize, Debug)]
+pub struct OidcConfig {
+    /// Logins go through `/auth/login/{name}`, and identities are recorded under it.
+    /// env: OIDC_NAME
+    #[validate(length(min = 1, max = 64))]
+    #[serde(default = "default_oidc_name", alias = "NAME")]
+    pub name: String,
+
+    /// Discovery document is read from `{issuer}/.well-known/openid-configuration`.
+    /// env: OIDC_ISSUER
+    #[serde(alias = "ISSUER")]
+    pub issuer: url::Url,
+
+    /// The registry's `/auth/callback/{name}` url, as registered with the provider.
+    /// env: OIDC_REDIRECT_URL
+    #[serde(alias = "REDIRECT_URL")]
+    pub redirect_url: url::Url,
+
+    /// Space separated, must include `openid` and `email`
+    #[serde(default = "default_oidc_scope", alias = "SCOPE")]
+    pub scope: String,
+
+    #[serde(alias = "CLIENT")]
+    pub client: OidcClientConfig,
+}
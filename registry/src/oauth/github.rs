@@ -1,19 +1,17 @@
-use crate::oauth::config::scopes;
-
-mod config;
-
-pub use config::{GhClientConfig, GhOauthConfig};
+use super::{GITHUB, LoginState, OAuthProvider, ProviderLogin, config};
+use crate::BoxFuture;
+use kintsu_registry_db::engine::user::ExternalIdentity;
 use octocrab::models::Author;
 use reqwest::Method;
 use secrecy::{ExposeSecret, SecretString};
 
-pub struct AuthClient {
+pub struct GithubProvider {
     config: config::GhOauthConfig,
     client: reqwest::Client,
-    pub(crate) login_url: url::Url,
+    login_url: url::Url,
 }
 
-impl AuthClient {
+impl GithubProvider {
     pub fn new(config: config::GhOauthConfig) -> crate::Result<Self> {
         Ok(Self {
             login_url: Self::create_login_url(config.base_url.clone(), &config.client.id),
@@ -29,7 +27,7 @@ impl AuthClient {
         let query = format!(
             "/login/oauth/authorize?client_id={}&scope={}",
             client_id,
-            scopes()
+            config::scopes()
         );
         base_url.join(&query).unwrap()
     }
@@ -44,14 +42,15 @@ impl AuthClient {
             .join("/login/oauth/access_token")
             .unwrap();
 
-        let mut query = url.query_pairs_mut();
+        // the serializer is not `Send`, so it cannot be held across the request
+        {
+            let mut query = url.query_pairs_mut();
 
-        query.append_pair("code", code.expose_secret());
-        query.append_pair("client_id", &self.config.client.id);
-        query.append_pair("client_secret", self.config.client.secret.expose_secret());
-        query.append_pair("accept", "json");
-
-        drop(query);
+            query.append_pair("code", code.expose_secret());
+            query.append_pair("client_id", &self.config.client.id);
+            query.append_pair("client_secret", self.config.client.secret.expose_secret());
+            query.append_pair("accept", "json");
+        }
 
         let mut request = reqwest::Request::new(Method::POST, url);
 
@@ -85,6 +84,51 @@ impl AuthClient {
     }
 }
 
+impl OAuthProvider for GithubProvider {
+    fn name(&self) -> &str {
+        GITHUB
+    }
+
+    fn authorize_url(
+        &self,
+        login: &LoginState,
+    ) -> url::Url {
+        let mut url = self.login_url.clone();
+        url.query_pairs_mut()
+            .append_pair("state", &login.state);
+        url
+    }
+
+    fn identify<'a>(
+        &'a self,
+        code: SecretString,
+        _: &'a LoginState,
+    ) -> BoxFuture<'a, crate::Result<ProviderLogin>> {
+        Box::pin(async move {
+            let token = self.exchange_token(code).await?;
+            let user = self
+                .saturate_user_data(&token.access_token)
+                .await?;
+
+            let email = user.email.ok_or_else(|| {
+                crate::Error::session("the GitHub account has no public email address")
+            })?;
+
+            Ok(ProviderLogin {
+                identity: ExternalIdentity {
+                    provider: GITHUB.into(),
+                    subject: user.id.0.to_string(),
+                    email,
+                    login: user.login,
+                    avatar: Some(user.avatar_url.to_string()),
+                    gh_id: Some(user.id.0 as i32),
+                },
+                access_token: token.access_token,
+            })
+        })
+    }
+}
+
 #[derive(serde::Deserialize, Debug)]
 #[serde(untagged)]
 pub enum ExchangeResponse {
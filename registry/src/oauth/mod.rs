@@ -0,0 +1,278 @@
+//! Login through external identity providers with the OAuth authorization code flow.
+//!
+//! Each provider reports an [`ExternalIdentity`], a provider and subject pair which users are
+//! looked up by. GitHub is built in, and any OpenID Connect issuer can be configured next to it.
+
+mod config;
+mod github;
+mod oidc;
+
+pub use config::{GhClientConfig, GhOauthConfig, OidcClientConfig, OidcConfig};
+pub use github::GithubProvider;
+pub use oidc::OidcProvider;
+
+use crate::BoxFuture;
+use actix_web::cookie::{Cookie, CookieJar, Key, SameSite};
+use kintsu_registry_db::engine::user::ExternalIdentity;
+use rand::{Rng, distr::Alphanumeric};
+use secrecy::SecretString;
+
+/// Name of the GitHub provider.
+pub const GITHUB: &str = "github";
+
+const LOGIN_COOKIE_NAME: &str = "kintsu_login";
+const LOGIN_COOKIE_MINUTES: i64 = 10;
+
+/// A user authenticated by a provider.
+pub struct ProviderLogin {
+    pub identity: ExternalIdentity,
+    /// Token for the provider's API, kept in the session
+    pub access_token: SecretString,
+}
+
+/// An identity provider users log in through.
+pub trait OAuthProvider: Send + Sync {
+    /// Addresses the provider in login routes and keys the identities it reports.
+    fn name(&self) -> &str;
+
+    /// Where to send the user to log in.
+    fn authorize_url(
+        &self,
+        login: &LoginState,
+    ) -> url::Url;
+
+    /// Exchanges the code the provider called back with for the user's identity.
+    fn identify<'a>(
+        &'a self,
+        code: SecretString,
+        login: &'a LoginState,
+    ) -> BoxFuture<'a, crate::Result<ProviderLogin>>;
+}
+
+/// A login in progress, kept in a private cookie between the redirect to the provider and its
+/// callback.
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct LoginState {
+    pub provider: String,
+    /// Echoed back by the provider, tying the callback to this browser
+    pub state: String,
+    /// PKCE code verifier
+    pub verifier: String,
+}
+
+fn random_string(len: usize) -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+impl LoginState {
+    pub fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            state: random_string(32),
+            verifier: random_string(64),
+        }
+    }
+
+    /// The S256 PKCE code challenge for the verifier.
+    pub fn challenge(&self) -> String {
+        use base64::Engine;
+        use sha2::Digest;
+
+        base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(sha2::Sha256::digest(self.verifier.as_bytes()))
+    }
+
+    /// Checks the callback belongs to this login.
+    pub fn verify(
+        &self,
+        provider: &str,
+        state: &str,
+    ) -> crate::Result<()> {
+        if self.provider != provider || self.state != state {
+            return Err(crate::Error::session(
+                "login state does not match, start the login again",
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn cookie(
+        &self,
+        key: &Key,
+        domain: String,
+    ) -> crate::Result<Cookie<'static>> {
+        let mut cookie = Cookie::new(LOGIN_COOKIE_NAME, serde_json::to_string(self)?);
+
+        cookie.set_path("/auth");
+        cookie.set_secure(true);
+        cookie.set_http_only(true);
+        // the callback is a cross site navigation from the provider
+        cookie.set_same_site(SameSite::Lax);
+        cookie.set_max_age(actix_web::cookie::time::Duration::minutes(
+            LOGIN_COOKIE_MINUTES,
+        ));
+        cookie.set_domain(domain);
+
+        let mut jar = CookieJar::new();
+        jar.private_mut(key).add(cookie);
+
+        jar.delta()
+            .next()
+            .cloned()
+            .ok_or_else(|| crate::Error::session("failed to encrypt login cookie"))
+    }
+
+    pub fn from_request(
+        req: &actix_web::HttpRequest,
+        key: &Key,
+    ) -> crate::Result<Self> {
+        let cookie = req
+            .cookie(LOGIN_COOKIE_NAME)
+            .ok_or_else(|| crate::Error::session("login expired, start the login again"))?;
+
+        let mut jar = CookieJar::new();
+        jar.add_original(cookie);
+
+        let cookie = jar
+            .private(key)
+            .get(LOGIN_COOKIE_NAME)
+            .ok_or_else(|| crate::Error::session("invalid login cookie"))?;
+
+        Ok(serde_json::from_str(cookie.value())?)
+    }
+
+    /// Creates a removal cookie that will clear the login once it completes
+    pub fn removal_cookie(domain: String) -> Cookie<'static> {
+        let mut cookie = Cookie::new(LOGIN_COOKIE_NAME, "");
+        cookie.set_path("/auth");
+        cookie.set_secure(true);
+        cookie.set_http_only(true);
+        cookie.set_same_site(SameSite::Lax);
+        cookie.set_max_age(actix_web::cookie::time::Duration::ZERO);
+        cookie.set_domain(domain);
+        cookie
+    }
+}
+
+pub struct AuthClient {
+    /// The first provider is the one `/auth/login` sends users to
+    providers: Vec<Box<dyn OAuthProvider>>,
+}
+
+impl AuthClient {
+    pub fn new(providers: Vec<Box<dyn OAuthProvider>>) -> crate::Result<Self> {
+        if providers.is_empty() {
+            return Err(crate::Error::OAuthConfig(
+                "at least one of gh or oidc must be configured".into(),
+            ));
+        }
+
+        for (i, provider) in providers.iter().enumerate() {
+            if providers[..i]
+                .iter()
+                .any(|other| other.name() == provider.name())
+            {
+                return Err(crate::Error::OAuthConfig(format!(
+                    "provider name '{}' is used more than once",
+                    provider.name()
+                )));
+            }
+        }
+
+        Ok(Self { providers })
+    }
+
+    pub async fn from_config(
+        gh: Option<GhOauthConfig>,
+        oidc: Option<OidcConfig>,
+    ) -> crate::Result<Self> {
+        let mut providers: Vec<Box<dyn OAuthProvider>> = vec![];
+
+        if let Some(gh) = gh {
+            providers.push(Box::new(GithubProvider::new(gh)?));
+        }
+
+        if let Some(oidc) = oidc {
+            tracing::info!("discovering oidc provider {}", oidc.issuer);
+            providers.push(Box::new(OidcProvider::discover(oidc).await?));
+        }
+
+        Self::new(providers)
+    }
+
+    pub fn provider(
+        &self,
+        name: &str,
+    ) -> crate::Result<&dyn OAuthProvider> {
+        self.providers
+            .iter()
+            .find(|provider| provider.name() == name)
+            .map(|provider| provider.as_ref())
+            .ok_or_else(|| {
+                crate::Error::Database(kintsu_registry_db::Error::NotFound(format!(
+                    "Login provider '{name}' not found"
+                )))
+            })
+    }
+
+    pub fn default_provider(&self) -> &dyn OAuthProvider {
+        self.providers[0].as_ref()
+    }
+
+    pub fn provider_names(&self) -> Vec<String> {
+        self.providers
+            .iter()
+            .map(|provider| provider.name().to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn challenge_matches_rfc7636() {
+        let login = LoginState {
+            provider: GITHUB.into(),
+            state: "state".into(),
+            verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".into(),
+        };
+
+        assert_eq!(
+            login.challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn login_cookie_round_trips() {
+        let key = Key::generate();
+        let login = LoginState::new("corp");
+
+        let cookie = login
+            .cookie(&key, "localhost".into())
+            .unwrap();
+        let req = actix_web::test::TestRequest::default()
+            .cookie(cookie)
+            .to_http_request();
+
+        let restored = LoginState::from_request(&req, &key).unwrap();
+        restored
+            .verify("corp", &login.state)
+            .unwrap();
+        assert_eq!(restored.verifier, login.verifier);
+
+        assert!(
+            restored
+                .verify(GITHUB, &login.state)
+                .is_err()
+        );
+        assert!(restored.verify("corp", "forged").is_err());
+        assert!(LoginState::from_request(&req, &Key::generate()).is_err());
+    }
+}
@@ -0,0 +1,413 @@
+use super::{GITHUB, LoginState, OAuthProvider, ProviderLogin, config::OidcConfig};
+use crate::BoxFuture;
+use kintsu_registry_db::engine::user::ExternalIdentity;
+use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
+
+/// The parts of an OpenID Provider's discovery document the login flow needs.
+#[derive(Deserialize, Debug)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: url::Url,
+    token_endpoint: url::Url,
+    userinfo_endpoint: Option<url::Url>,
+    #[serde(default)]
+    code_challenge_methods_supported: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum TokenResponse {
+    Valid {
+        access_token: SecretString,
+    },
+    Invalid {
+        error: String,
+        error_description: Option<String>,
+        error_uri: Option<String>,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+struct UserInfo {
+    sub: String,
+    email: Option<String>,
+    #[serde(default, deserialize_with = "de_lenient_bool")]
+    email_verified: Option<bool>,
+    preferred_username: Option<String>,
+    picture: Option<String>,
+}
+
+/// Some providers send `email_verified` as a string.
+fn de_lenient_bool<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+where
+    D: serde::Deserializer<'de>, {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lenient {
+        Bool(bool),
+        Str(String),
+    }
+
+    Ok(match Option::<Lenient>::deserialize(deserializer)? {
+        Some(Lenient::Bool(b)) => Some(b),
+        Some(Lenient::Str(s)) => Some(s.eq_ignore_ascii_case("true")),
+        None => None,
+    })
+}
+
+impl UserInfo {
+    fn into_identity(
+        self,
+        provider: &str,
+    ) -> crate::Result<ExternalIdentity> {
+        let email = self.email.ok_or_else(|| {
+            crate::Error::session(format!("{provider} did not share an email address"))
+        })?;
+
+        if self.email_verified == Some(false) {
+            return Err(crate::Error::session(format!(
+                "the email address at {provider} is not verified"
+            )));
+        }
+
+        Ok(ExternalIdentity {
+            provider: provider.to_string(),
+            subject: self.sub,
+            login: self
+                .preferred_username
+                .unwrap_or_else(|| email.clone()),
+            email,
+            avatar: self.picture,
+            gh_id: None,
+        })
+    }
+}
+
+/// A generic OpenID Connect provider, logging in with PKCE and reading claims from the
+/// userinfo endpoint.
+pub struct OidcProvider {
+    config: OidcConfig,
+    client: reqwest::Client,
+    authorization_endpoint: url::Url,
+    token_endpoint: url::Url,
+    userinfo_endpoint: url::Url,
+}
+
+fn discovery_url(issuer: &url::Url) -> url::Url {
+    let mut url = issuer.clone();
+    url.set_path(&format!(
+        "{}/.well-known/openid-configuration",
+        issuer.path().trim_end_matches('/')
+    ));
+    url
+}
+
+impl OidcProvider {
+    pub async fn discover(config: OidcConfig) -> crate::Result<Self> {
+        let client = reqwest::ClientBuilder::new().build()?;
+
+        let body = client
+            .get(discovery_url(&config.issuer))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        let discovery = serde_json::from_slice::<Discovery>(&body)?;
+
+        Self::from_discovery(config, client, discovery)
+    }
+
+    fn from_discovery(
+        config: OidcConfig,
+        client: reqwest::Client,
+        discovery: Discovery,
+    ) -> crate::Result<Self> {
+        if config.name == GITHUB {
+            return Err(crate::Error::OAuthConfig(format!(
+                "oidc provider cannot be named '{GITHUB}'"
+            )));
+        }
+
+        let scopes: Vec<_> = config.scope.split_whitespace().collect();
+        if !scopes.contains(&"openid") || !scopes.contains(&"email") {
+            return Err(crate::Error::OAuthConfig(
+                "oidc scope must include openid and email".into(),
+            ));
+        }
+
+        if discovery.issuer.trim_end_matches('/') != config.issuer.as_str().trim_end_matches('/') {
+            return Err(crate::Error::OAuthConfig(format!(
+                "discovery document is for issuer {}, expected {}",
+                discovery.issuer, config.issuer
+            )));
+        }
+
+        if let Some(methods) = &discovery.code_challenge_methods_supported
+            && !methods.iter().any(|method| method == "S256")
+        {
+            return Err(crate::Error::OAuthConfig(format!(
+                "{} does not support S256 PKCE challenges",
+                config.issuer
+            )));
+        }
+
+        let userinfo_endpoint = discovery.userinfo_endpoint.ok_or_else(|| {
+            crate::Error::OAuthConfig(format!("{} has no userinfo endpoint", config.issuer))
+        })?;
+
+        Ok(Self {
+            config,
+            client,
+            authorization_endpoint: discovery.authorization_endpoint,
+            token_endpoint: discovery.token_endpoint,
+            userinfo_endpoint,
+        })
+    }
+
+    async fn exchange_token(
+        &self,
+        code: SecretString,
+        login: &LoginState,
+    ) -> crate::Result<SecretString> {
+        let form = {
+            let mut form = url::form_urlencoded::Serializer::new(String::new());
+            form.append_pair("grant_type", "authorization_code")
+                .append_pair("code", code.expose_secret())
+                .append_pair("redirect_uri", self.config.redirect_url.as_str())
+                .append_pair("client_id", &self.config.client.id)
+                .append_pair("code_verifier", &login.verifier);
+            if let Some(secret) = &self.config.client.secret {
+                form.append_pair("client_secret", secret.expose_secret());
+            }
+            form.finish()
+        };
+
+        let resp = self
+            .client
+            .post(self.token_endpoint.clone())
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/x-www-form-urlencoded",
+            )
+            .header(reqwest::header::ACCEPT, "application/json")
+            .body(form)
+            .send()
+            .await?;
+
+        // errors are reported in a json body alongside a 400
+        let status = resp.status();
+        let body = resp.bytes().await?;
+
+        match serde_json::from_slice::<TokenResponse>(&body) {
+            Ok(TokenResponse::Valid { access_token }) => Ok(access_token),
+            Ok(TokenResponse::Invalid {
+                error,
+                error_description,
+                error_uri,
+            }) => {
+                Err(crate::Error::TokenExchangeError {
+                    error,
+                    error_description,
+                    error_uri,
+                })
+            },
+            Err(_) if !status.is_success() => {
+                Err(crate::Error::TokenExchangeError {
+                    error: status.to_string(),
+                    error_description: None,
+                    error_uri: None,
+                })
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn user_info(
+        &self,
+        access_token: &SecretString,
+    ) -> crate::Result<UserInfo> {
+        let body = self
+            .client
+            .get(self.userinfo_endpoint.clone())
+            .bearer_auth(access_token.expose_secret())
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+impl OAuthProvider for OidcProvider {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn authorize_url(
+        &self,
+        login: &LoginState,
+    ) -> url::Url {
+        let mut url = self.authorization_endpoint.clone();
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client.id)
+            .append_pair("redirect_uri", self.config.redirect_url.as_str())
+            .append_pair("scope", &self.config.scope)
+            .append_pair("state", &login.state)
+            .append_pair("code_challenge", &login.challenge())
+            .append_pair("code_challenge_method", "S256");
+        url
+    }
+
+    fn identify<'a>(
+        &'a self,
+        code: SecretString,
+        login: &'a LoginState,
+    ) -> BoxFuture<'a, crate::Result<ProviderLogin>> {
+        Box::pin(async move {
+            let access_token = self.exchange_token(code, login).await?;
+            let identity = self
+                .user_info(&access_token)
+                .await?
+                .into_identity(&self.config.name)?;
+
+            Ok(ProviderLogin {
+                identity,
+                access_token,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::oauth::OidcClientConfig;
+
+    fn config(issuer: &str) -> OidcConfig {
+        OidcConfig {
+            name: "corp".into(),
+            issuer: url::Url::parse(issuer).unwrap(),
+            redirect_url: url::Url::parse("https://registry.example.com/auth/callback/corp")
+                .unwrap(),
+            scope: "openid email profile".into(),
+            client: OidcClientConfig {
+                id: "registry".into(),
+                secret: None,
+            },
+        }
+    }
+
+    fn discovery(issuer: &str) -> Discovery {
+        serde_json::from_value(serde_json::json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{issuer}/authorize"),
+            "token_endpoint": format!("{issuer}/token"),
+            "userinfo_endpoint": format!("{issuer}/userinfo"),
+            "code_challenge_methods_supported": ["plain", "S256"],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn discovery_url_keeps_issuer_path() {
+        for (issuer, expected) in [
+            (
+                "https://idp.example.com",
+                "https://idp.example.com/.well-known/openid-configuration",
+            ),
+            (
+                "https://idp.example.com/realms/corp/",
+                "https://idp.example.com/realms/corp/.well-known/openid-configuration",
+            ),
+        ] {
+            assert_eq!(
+                discovery_url(&url::Url::parse(issuer).unwrap()).as_str(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn authorize_url_carries_pkce_challenge() {
+        let issuer = "https://idp.example.com/realms/corp";
+        let provider =
+            OidcProvider::from_discovery(config(issuer), reqwest::Client::new(), discovery(issuer))
+                .unwrap();
+        let login = LoginState::new("corp");
+
+        let url = provider.authorize_url(&login);
+        let query: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(url.path(), "/realms/corp/authorize");
+        assert_eq!(query["state"], login.state);
+        assert_eq!(query["code_challenge"], login.challenge());
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(
+            query["redirect_uri"],
+            "https://registry.example.com/auth/callback/corp"
+        );
+    }
+
+    #[test]
+    fn rejects_unusable_discovery() {
+        let issuer = "https://idp.example.com";
+
+        let mut other_issuer = discovery(issuer);
+        other_issuer.issuer = "https://evil.example.com".into();
+
+        let mut no_s256 = discovery(issuer);
+        no_s256.code_challenge_methods_supported = Some(vec!["plain".into()]);
+
+        let mut no_userinfo = discovery(issuer);
+        no_userinfo.userinfo_endpoint = None;
+
+        for discovery in [other_issuer, no_s256, no_userinfo] {
+            assert!(
+                OidcProvider::from_discovery(config(issuer), reqwest::Client::new(), discovery)
+                    .is_err()
+            );
+        }
+
+        let mut github = config(issuer);
+        github.name = GITHUB.into();
+        assert!(
+            OidcProvider::from_discovery(github, reqwest::Client::new(), discovery(issuer))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn identity_from_user_info() {
+        let info: UserInfo = serde_json::from_value(serde_json::json!({
+            "sub": "248289761001",
+            "email": "jane@example.com",
+            "email_verified": "true",
+            "preferred_username": "jane",
+        }))
+        .unwrap();
+
+        let identity = info.into_identity("corp").unwrap();
+        assert_eq!(identity.provider, "corp");
+        assert_eq!(identity.subject, "248289761001");
+        assert_eq!(identity.login, "jane");
+        assert_eq!(identity.gh_id, None);
+
+        let unverified: UserInfo = serde_json::from_value(serde_json::json!({
+            "sub": "1",
+            "email": "jane@example.com",
+            "email_verified": false,
+        }))
+        .unwrap();
+        assert!(unverified.into_identity("corp").is_err());
+
+        let no_email: UserInfo = serde_json::from_value(serde_json::json!({ "sub": "1" })).unwrap();
+        assert!(no_email.into_identity("corp").is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! Keeps the members of imported orgs in line with their orgs at GitHub.
+
+use kintsu_registry_db::{
+    engine::{
+        PrincipalIdentity,
+        org_sync::{GhOrgMember, MembershipChange},
+    },
+    entities::{Org, OrgRoleType},
+};
+use secrecy::SecretString;
+
+const PER_PAGE: usize = 100;
+
+pub struct OrgSync {
+    api_url: url::Url,
+    /// Token scheduled syncs read members with
+    token: Option<SecretString>,
+}
+
+impl OrgSync {
+    pub fn new(
+        api_url: url::Url,
+        token: Option<SecretString>,
+    ) -> Self {
+        Self { api_url, token }
+    }
+
+    pub fn is_scheduled(&self) -> bool {
+        self.token.is_some()
+    }
+
+    fn github(
+        &self,
+        token: SecretString,
+    ) -> crate::Result<octocrab::Octocrab> {
+        Ok(octocrab::Octocrab::builder()
+            .base_uri(self.api_url.as_str())?
+            .personal_token(token)
+            .build()?)
+    }
+
+    async fn list_members(
+        github: &octocrab::Octocrab,
+        org_name: &str,
+        role: OrgRoleType,
+    ) -> crate::Result<Vec<GhOrgMember>> {
+        let filter = match role {
+            OrgRoleType::Admin => "admin",
+            OrgRoleType::Member => "member",
+        };
+
+        let mut members = vec![];
+        for page in 1.. {
+            let authors: Vec<octocrab::models::Author> = github
+                .get(
+                    format!("/orgs/{org_name}/members"),
+                    Some(&[
+                        ("role", filter.to_string()),
+                        ("per_page", PER_PAGE.to_string()),
+                        ("page", page.to_string()),
+                    ]),
+                )
+                .await?;
+
+            let last = authors.len() < PER_PAGE;
+            members.extend(authors.into_iter().map(|author| {
+                GhOrgMember {
+                    gh_id: author.id.0 as i32,
+                    role: role.clone(),
+                }
+            }));
+
+            if last {
+                break;
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// Syncs `org` with its members at GitHub, read with `token` when given and the configured
+    /// token otherwise.
+    pub async fn sync_org(
+        &self,
+        db: &sea_orm::DatabaseConnection,
+        actor: Option<&PrincipalIdentity>,
+        org: &Org,
+        token: Option<SecretString>,
+    ) -> crate::Result<Vec<MembershipChange>> {
+        let token = token
+            .or_else(|| self.token.clone())
+            .ok_or_else(|| {
+                crate::Error::Database(kintsu_registry_db::Error::Validation(
+                    "No GitHub token to sync with, log in with GitHub".into(),
+                ))
+            })?;
+        let github = self.github(token)?;
+
+        let mut members = Self::list_members(&github, &org.name, OrgRoleType::Admin).await?;
+        members.extend(Self::list_members(&github, &org.name, OrgRoleType::Member).await?);
+
+        Ok(kintsu_registry_db::engine::org_sync::sync_members(db, actor, org.id, &members).await?)
+    }
+}
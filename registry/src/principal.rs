@@ -1,4 +1,4 @@
-use actix_web::FromRequest;
+use actix_web::{FromRequest, web};
 use kintsu_registry_db::engine::{Entity, PrincipalIdentity};
 
 pub struct Principal {
@@ -32,11 +32,17 @@ impl FromRequest for Principal {
         let api_key = super::apikey::ApiKey::extract(req);
         let session = super::session::SessionData::extract(req);
         let db = req.app_data::<crate::DbConn>().cloned();
+        let limiter = req
+            .app_data::<web::Data<crate::rate_limit::RateLimiter>>()
+            .cloned();
+        let ip = limiter
+            .as_ref()
+            .and_then(|limiter| limiter.client_ip(req));
 
         Box::pin(async move {
             let db = db.ok_or_else(|| crate::Error::missing_data("DbPool"))?;
 
-            Ok(Self {
+            let principal = Self {
                 id: match tokio::join!(api_key, session) {
                     (Ok(key), _) => {
                         let key = key.into_inner();
@@ -63,7 +69,15 @@ impl FromRequest for Principal {
                         return Err(crate::Error::Multiple(vec![key_err, sess_err]));
                     },
                 },
-            })
+            };
+
+            if let Some(limiter) = limiter {
+                limiter
+                    .check_principal(&principal.id, ip)
+                    .await?;
+            }
+
+            Ok(principal)
         })
     }
 }
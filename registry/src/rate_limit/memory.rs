@@ -0,0 +1,120 @@
+use super::{Decision, RateLimitStore};
+use crate::{BoxFuture, config::Quota};
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+/// Buckets tracked before idle ones are dropped.
+pub(super) const PRUNE_AT: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// When the bucket is full again if left alone, after which it can be forgotten
+    full_at: Instant,
+}
+
+/// Keeps buckets in this process.
+#[derive(Default)]
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl MemoryStore {
+    fn take(
+        &self,
+        key: &str,
+        quota: &Quota,
+        now: Instant,
+    ) -> Decision {
+        let capacity = f64::from(quota.burst);
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= PRUNE_AT {
+            buckets.retain(|_, bucket| bucket.full_at > now);
+        }
+
+        let bucket = buckets
+            .entry(key.to_string())
+            .or_insert(Bucket {
+                tokens: capacity,
+                updated: now,
+                full_at: now,
+            });
+
+        let elapsed = now
+            .duration_since(bucket.updated)
+            .as_secs_f64();
+        let tokens = (bucket.tokens + elapsed * quota.per_second).min(capacity);
+
+        let decision = Decision::from_tokens(tokens, quota);
+        bucket.tokens = if decision.allowed {
+            tokens - 1.0
+        } else {
+            tokens
+        };
+        bucket.updated = now;
+        bucket.full_at =
+            now + std::time::Duration::from_secs_f64((capacity - bucket.tokens) / quota.per_second);
+
+        decision
+    }
+}
+
+impl RateLimitStore for MemoryStore {
+    fn acquire<'a>(
+        &'a self,
+        key: &'a str,
+        quota: &'a Quota,
+    ) -> BoxFuture<'a, crate::Result<Decision>> {
+        let decision = self.take(key, quota, Instant::now());
+        Box::pin(async move { Ok(decision) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    const QUOTA: Quota = Quota {
+        burst: 3,
+        per_second: 2.0,
+    };
+
+    #[test]
+    fn refills_over_time() {
+        let store = MemoryStore::default();
+        let now = Instant::now();
+
+        for remaining in [2, 1, 0] {
+            let decision = store.take("k", &QUOTA, now);
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+        }
+        assert!(!store.take("k", &QUOTA, now).allowed);
+
+        // half a second refills one token at two per second
+        let later = now + Duration::from_millis(500);
+        assert!(store.take("k", &QUOTA, later).allowed);
+        assert!(!store.take("k", &QUOTA, later).allowed);
+
+        // never beyond the burst
+        let much_later = later + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(store.take("k", &QUOTA, much_later).allowed);
+        }
+        assert!(!store.take("k", &QUOTA, much_later).allowed);
+    }
+
+    #[test]
+    fn forgets_idle_buckets() {
+        let store = MemoryStore::default();
+        let now = Instant::now();
+
+        for n in 0..PRUNE_AT {
+            store.take(&format!("k{n}"), &QUOTA, now);
+        }
+        store.take("fresh", &QUOTA, now + QUOTA.refill_time());
+
+        assert_eq!(store.buckets.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,368 @@
+//! Token bucket rate limiting, per client address for every request and per principal once
+//! a request is authenticated.
+//!
+//! Buckets live in memory by default, so each registry instance limits on its own. With the
+//! `redis` feature and `rate_limit.redis_url` set, instances share buckets instead.
+
+mod memory;
+#[cfg(feature = "redis")]
+mod redis_store;
+
+pub use memory::MemoryStore;
+#[cfg(feature = "redis")]
+pub use redis_store::RedisStore;
+
+use crate::{
+    BoxFuture,
+    config::{Quota, RateLimitConfig},
+};
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web,
+};
+use kintsu_registry_auth::{AuditEvent, AuditEventType, PrincipalType, RateLimitScope};
+use kintsu_registry_db::engine::{OwnerId, PrincipalIdentity};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Outcome of taking a token from a bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decision {
+    pub allowed: bool,
+    /// Tokens left after this request
+    pub remaining: u32,
+    /// How long until a token is available again, zero when allowed
+    pub retry_after: Duration,
+}
+
+impl Decision {
+    /// Decides from the number of tokens in a bucket, before taking one.
+    pub fn from_tokens(
+        tokens: f64,
+        quota: &Quota,
+    ) -> Self {
+        if tokens >= 1.0 {
+            Self {
+                allowed: true,
+                remaining: (tokens - 1.0).floor() as u32,
+                retry_after: Duration::ZERO,
+            }
+        } else {
+            Self {
+                allowed: false,
+                remaining: 0,
+                retry_after: Duration::from_secs_f64((1.0 - tokens) / quota.per_second),
+            }
+        }
+    }
+}
+
+/// Where buckets are kept.
+pub trait RateLimitStore: Send + Sync {
+    /// Refills the bucket at `key` and takes a token from it if one is available.
+    fn acquire<'a>(
+        &'a self,
+        key: &'a str,
+        quota: &'a Quota,
+    ) -> BoxFuture<'a, crate::Result<Decision>>;
+}
+
+struct Strikes {
+    since: Instant,
+    count: u32,
+}
+
+/// Limits requests against the configured quotas, auditing clients which keep going once
+/// limited.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    /// `None` when rate limiting is disabled
+    store: Option<Box<dyn RateLimitStore>>,
+    strikes: Mutex<HashMap<String, Strikes>>,
+}
+
+impl RateLimiter {
+    pub fn new(
+        config: RateLimitConfig,
+        store: Box<dyn RateLimitStore>,
+    ) -> Self {
+        Self {
+            config,
+            store: Some(store),
+            strikes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A limiter which lets every request through.
+    pub fn disabled() -> Self {
+        Self {
+            config: RateLimitConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            store: None,
+            strikes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn from_config(config: RateLimitConfig) -> crate::Result<Self> {
+        if !config.enabled {
+            tracing::warn!("rate limiting disabled");
+            return Ok(Self::disabled());
+        }
+        config.check()?;
+
+        let store: Box<dyn RateLimitStore> = match &config.redis_url {
+            #[cfg(feature = "redis")]
+            Some(url) => {
+                tracing::info!("sharing rate limits through redis");
+                Box::new(RedisStore::connect(url).await?)
+            },
+            #[cfg(not(feature = "redis"))]
+            Some(_) => {
+                return Err(crate::Error::RateLimitConfig(
+                    "rate_limit.redis_url requires the registry to be built with the `redis` feature"
+                        .into(),
+                ));
+            },
+            None => Box::new(MemoryStore::default()),
+        };
+
+        Ok(Self::new(config, store))
+    }
+
+    /// The address requests from `req` are limited by, if it is known.
+    pub fn client_ip(
+        &self,
+        req: &actix_web::HttpRequest,
+    ) -> Option<IpAddr> {
+        self.store.as_ref()?;
+
+        let addr = if self.config.trust_forwarded {
+            req.connection_info()
+                .realip_remote_addr()
+                .map(str::to_string)
+        } else {
+            req.peer_addr().map(|addr| addr.to_string())
+        }?;
+
+        addr.parse::<SocketAddr>()
+            .map(|addr| addr.ip())
+            .or_else(|_| addr.parse::<IpAddr>())
+            .ok()
+    }
+
+    pub async fn check_ip(
+        &self,
+        ip: IpAddr,
+    ) -> crate::Result<()> {
+        let key = format!("ip:{ip}");
+        self.check(&key, &self.config.ip, || {
+            AuditEvent::builder()
+                .timestamp(chrono::Utc::now())
+                .principal_type(PrincipalType::Anonymous)
+                .principal_id(0)
+                .event_type(self.abuse_event(RateLimitScope::Ip))
+                .allowed(false)
+                .reason(format!("{ip} kept requesting while rate limited"))
+                .policy_checks(vec![])
+                .ip_address(ip.to_string())
+                .build()
+        })
+        .await
+    }
+
+    pub async fn check_principal(
+        &self,
+        principal: &PrincipalIdentity,
+        ip: Option<IpAddr>,
+    ) -> crate::Result<()> {
+        // sessions and keys of the same user share a bucket
+        let key = match principal.owner_id() {
+            OwnerId::User(id) => format!("user:{id}"),
+            OwnerId::Org(id) => format!("org:{id}"),
+        };
+        self.check(&key, &self.config.principal, || {
+            AuditEvent::builder()
+                .timestamp(chrono::Utc::now())
+                .principal_type(principal.principal_type())
+                .principal_id(principal.principal_id())
+                .event_type(self.abuse_event(RateLimitScope::Principal))
+                .allowed(false)
+                .reason(format!("{key} kept requesting while rate limited"))
+                .policy_checks(vec![])
+                .maybe_ip_address(ip.map(|ip| ip.to_string()))
+                .build()
+        })
+        .await
+    }
+
+    async fn check(
+        &self,
+        key: &str,
+        quota: &Quota,
+        abuse: impl FnOnce() -> AuditEvent,
+    ) -> crate::Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+
+        let decision = match store.acquire(key, quota).await {
+            Ok(decision) => decision,
+            Err(e) => {
+                // an unavailable store must not take the registry down with it
+                tracing::warn!("rate limit store failed, allowing request: {e}");
+                return Ok(());
+            },
+        };
+
+        if decision.allowed {
+            return Ok(());
+        }
+
+        if self.strike(key, Instant::now()) {
+            tracing::warn!("{key} exceeded its rate limit repeatedly");
+            if let Err(e) = kintsu_registry_events::emit_event(abuse()) {
+                tracing::error!("failed to audit rate limit abuse: {e}");
+            }
+        }
+
+        Err(crate::Error::RateLimited {
+            retry_after: Duration::from_secs(decision.retry_after.as_secs_f64().ceil() as u64),
+        })
+    }
+
+    /// Counts a rejection of `key`, returning true when it reaches the abuse threshold. Each
+    /// client is reported at most once per window.
+    fn strike(
+        &self,
+        key: &str,
+        now: Instant,
+    ) -> bool {
+        let window = self.config.abuse_window();
+        let mut strikes = self.strikes.lock().unwrap();
+
+        if strikes.len() >= memory::PRUNE_AT {
+            strikes.retain(|_, s| now.duration_since(s.since) < window);
+        }
+
+        let entry = strikes
+            .entry(key.to_string())
+            .or_insert(Strikes {
+                since: now,
+                count: 0,
+            });
+        if now.duration_since(entry.since) >= window {
+            *entry = Strikes {
+                since: now,
+                count: 0,
+            };
+        }
+
+        entry.count += 1;
+        entry.count == self.config.abuse_threshold
+    }
+
+    fn abuse_event(
+        &self,
+        scope: RateLimitScope,
+    ) -> AuditEventType {
+        AuditEventType::RateLimitExceeded {
+            scope,
+            rejected: self.config.abuse_threshold,
+            window_secs: self.config.abuse_window,
+        }
+    }
+}
+
+/// Middleware limiting every request by client address. Does nothing unless a
+/// [`RateLimiter`] is registered as app data.
+pub async fn limit_by_ip(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse, actix_web::Error> {
+    if let Some(limiter) = req.app_data::<web::Data<RateLimiter>>()
+        && let Some(ip) = limiter.client_ip(req.request())
+    {
+        limiter.check_ip(ip).await?;
+    }
+
+    Ok(next.call(req).await?.map_into_boxed_body())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn limiter(threshold: u32) -> RateLimiter {
+        RateLimiter::new(
+            RateLimitConfig {
+                ip: Quota {
+                    burst: 2,
+                    per_second: 1.0,
+                },
+                abuse_threshold: threshold,
+                ..Default::default()
+            },
+            Box::new(MemoryStore::default()),
+        )
+    }
+
+    #[test]
+    fn decides_from_tokens() {
+        let quota = Quota {
+            burst: 10,
+            per_second: 2.0,
+        };
+
+        let allowed = Decision::from_tokens(3.5, &quota);
+        assert!(allowed.allowed);
+        assert_eq!(allowed.remaining, 2);
+
+        let denied = Decision::from_tokens(0.5, &quota);
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn rejects_once_burst_is_spent() {
+        let limiter = limiter(100);
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+
+        limiter.check_ip(ip).await.unwrap();
+        limiter.check_ip(ip).await.unwrap();
+        let err = limiter.check_ip(ip).await.unwrap_err();
+        assert!(
+            matches!(err, crate::Error::RateLimited { retry_after } if retry_after == Duration::from_secs(1)),
+            "{err}"
+        );
+
+        // other clients are unaffected
+        limiter
+            .check_ip("203.0.113.8".parse().unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn reports_abuse_once_per_window() {
+        let limiter = limiter(3);
+        let now = Instant::now();
+
+        let reported: Vec<_> = (0..6)
+            .map(|_| limiter.strike("ip:203.0.113.7", now))
+            .collect();
+        assert_eq!(reported, [false, false, true, false, false, false]);
+
+        let later = now + limiter.config.abuse_window();
+        assert!(!limiter.strike("ip:203.0.113.7", later));
+        assert!(!limiter.strike("ip:203.0.113.7", later));
+        assert!(limiter.strike("ip:203.0.113.7", later));
+    }
+}
@@ -0,0 +1,75 @@
+use super::{Decision, RateLimitStore};
+use crate::{BoxFuture, config::Quota};
+
+/// Refills and takes from the bucket atomically, on the redis clock so instances agree.
+/// Returns the tokens held before taking one.
+const TAKE: &str = r"
+local capacity = tonumber(ARGV[1])
+local per_second = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'updated')
+local tokens = tonumber(state[1]) or capacity
+local updated = tonumber(state[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - updated) / 1000 * per_second)
+
+local available = tokens
+if tokens >= 1 then
+    tokens = tokens - 1
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / per_second * 1000))
+return tostring(available)
+";
+
+const KEY_PREFIX: &str = "kintsu:rate-limit:";
+
+/// Keeps buckets in redis, shared by every registry instance using it.
+pub struct RedisStore {
+    conn: redis::aio::ConnectionManager,
+    script: redis::Script,
+}
+
+impl RedisStore {
+    pub async fn connect(url: &str) -> crate::Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| crate::Error::RateLimitConfig(format!("invalid redis url: {e}")))?;
+        let conn = client
+            .get_connection_manager()
+            .await
+            .map_err(|e| crate::Error::RateLimitConfig(format!("connecting to redis: {e}")))?;
+
+        Ok(Self {
+            conn,
+            script: redis::Script::new(TAKE),
+        })
+    }
+}
+
+impl RateLimitStore for RedisStore {
+    fn acquire<'a>(
+        &'a self,
+        key: &'a str,
+        quota: &'a Quota,
+    ) -> BoxFuture<'a, crate::Result<Decision>> {
+        Box::pin(async move {
+            let mut conn = self.conn.clone();
+            let available: String = self
+                .script
+                .key(format!("{KEY_PREFIX}{key}"))
+                .arg(quota.burst)
+                .arg(quota.per_second)
+                .invoke_async(&mut conn)
+                .await
+                .map_err(|e| crate::Error::RateLimitStore(e.to_string()))?;
+
+            let available = available
+                .parse::<f64>()
+                .map_err(|e| crate::Error::RateLimitStore(e.to_string()))?;
+
+            Ok(Decision::from_tokens(available, quota))
+        })
+    }
+}
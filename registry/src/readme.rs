@@ -0,0 +1,43 @@
+use pulldown_cmark::{Options, Parser, html};
+
+/// Renders a package README to HTML which is safe to embed in a page. Raw HTML in the
+/// markdown is kept, minus scripts, styles, event handlers and unsafe URL schemes.
+pub(crate) fn render(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+
+    let mut rendered = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut rendered, Parser::new_ext(markdown, options));
+
+    ammonia::Builder::default()
+        .link_rel(Some("nofollow noopener noreferrer"))
+        .clean(&rendered)
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn renders_markdown() {
+        let html = render("# my-package\n\n| a | b |\n|---|---|\n| 1 | 2 |\n");
+
+        assert!(html.contains("<h1>my-package</h1>"));
+        assert!(html.contains("<table>"));
+    }
+
+    #[test]
+    fn strips_unsafe_html() {
+        let html = render(
+            "<script>alert(1)</script>\n\n<img src=\"x.png\" onerror=\"alert(1)\">\n\n[link](javascript:alert(1))\n",
+        );
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("<img src=\"x.png\">"));
+    }
+}
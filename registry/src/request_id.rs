@@ -0,0 +1,116 @@
+//! Correlates everything a request causes by one id, taken from the client's
+//! [`REQUEST_ID_HEADER`] or generated. The id is attached to the request's log span, tagged on
+//! the audit events it emits, and returned in its response header and error body.
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    error::InternalError,
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+};
+use kintsu_registry_core::{REQUEST_ID_HEADER, is_valid_request_id, new_request_id};
+use tracing::Instrument;
+
+pub async fn assign_request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse, actix_web::Error> {
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|request_id| is_valid_request_id(request_id))
+        .map(String::from)
+        .unwrap_or_else(new_request_id);
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.path(),
+    );
+
+    let header = HeaderValue::from_str(&request_id).ok();
+    let error_header = header.clone();
+    let res = kintsu_registry_events::with_request_id(request_id, async move {
+        next.call(req).await.map_err(|err| {
+            // errors from middleware are rendered here rather than by the server, so their
+            // body is rendered while the request id is still set
+            let mut response = err.error_response();
+            if let Some(header) = error_header {
+                response
+                    .headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), header);
+            }
+            actix_web::Error::from(InternalError::from_response(err, response))
+        })
+    })
+    .instrument(span)
+    .await;
+
+    let mut res = res?.map_into_boxed_body();
+    if let Some(header) = header {
+        res.headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), header);
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::{App, HttpResponse, test, web};
+
+    async fn echo_request_id() -> HttpResponse {
+        HttpResponse::Ok().body(kintsu_registry_events::current_request_id().unwrap_or_default())
+    }
+
+    async fn fail() -> crate::Result<HttpResponse> {
+        Err(crate::Error::AuthorizationRequired)
+    }
+
+    #[actix_web::test]
+    async fn echoes_or_generates_request_ids() {
+        let app = test::init_service(
+            App::new()
+                .route("/echo", web::get().to(echo_request_id))
+                .route("/fail", web::get().to(fail))
+                .wrap(actix_web::middleware::from_fn(assign_request_id)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/echo")
+            .insert_header((REQUEST_ID_HEADER, "client-42"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "client-42");
+        assert_eq!(test::read_body(res).await, "client-42");
+
+        let req = test::TestRequest::get()
+            .uri("/echo")
+            .insert_header((REQUEST_ID_HEADER, "not valid"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let generated = res
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_ne!(generated, "not valid");
+        assert_eq!(test::read_body(res).await, generated);
+
+        let req = test::TestRequest::get()
+            .uri("/fail")
+            .insert_header((REQUEST_ID_HEADER, "client-43"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "client-43");
+        let body: kintsu_registry_core::ErrorResponse = test::read_body_json(res).await;
+        assert_eq!(body.request_id.as_deref(), Some("client-43"));
+    }
+}
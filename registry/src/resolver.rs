@@ -1,14 +1,19 @@
 use std::path::Path;
 
 use convert_case::{Case, Casing};
-use kintsu_manifests::{package::Dependency, version::VersionSerde};
+use kintsu_manifests::{
+    config::NewForNamed,
+    package::{Dependency, PackageManifests},
+    version::{Version, VersionReq, VersionSerde},
+};
 use kintsu_parser::ctx::compile::resolver::{
-    DependencyMutability, GitResolver, PackageResolver, PathResolver, RemoteResolver,
-    ResolvedDependency,
+    DependencyMutability, DeprecationNotice, GitResolver, PackageIndex, PackageResolver,
+    PathResolver, RemoteResolver, ResolvedDependency,
 };
 pub struct InternalPackageResolver {
     pre_computed:
         std::collections::HashMap<(String, VersionSerde), kintsu_fs::memory::MemoryFileSystem>,
+    deprecations: std::collections::HashMap<(String, VersionSerde), DeprecationNotice>,
 }
 
 impl InternalPackageResolver {
@@ -20,8 +25,18 @@ impl InternalPackageResolver {
     ) -> Self {
         Self {
             pre_computed: sources,
+            deprecations: Default::default(),
         }
     }
+
+    /// Deprecated versions among the sources, reported as warnings when they are resolved.
+    pub fn with_deprecations(
+        mut self,
+        deprecations: std::collections::HashMap<(String, VersionSerde), DeprecationNotice>,
+    ) -> Self {
+        self.deprecations = deprecations;
+        self
+    }
 }
 
 impl PathResolver for InternalPackageResolver {
@@ -55,7 +70,63 @@ impl RemoteResolver for InternalPackageResolver {
     }
 }
 
+impl PackageIndex for InternalPackageResolver {
+    fn versions(
+        &self,
+        package: &str,
+    ) -> kintsu_parser::Result<Vec<Version>> {
+        Ok(self
+            .pre_computed
+            .keys()
+            .filter(|(name, _)| name == package)
+            .map(|(_, version)| version.0.clone())
+            .collect())
+    }
+
+    fn dependencies(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> kintsu_parser::Result<Vec<(String, VersionReq)>> {
+        let Some(fs) = self
+            .pre_computed
+            .get(&(package.to_string(), VersionSerde(version.clone())))
+        else {
+            return Ok(vec![]);
+        };
+
+        let manifest = PackageManifests::new(fs, "./")?;
+        Ok(manifest
+            .dependencies()
+            .iter()
+            .filter_map(|(name, dep)| {
+                dep.version()
+                    .map(|req| (name.clone(), req.0.clone()))
+            })
+            .collect())
+    }
+
+    fn deprecation(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> kintsu_parser::Result<Option<DeprecationNotice>> {
+        Ok(self
+            .deprecations
+            .get(&(package.to_string(), VersionSerde(version.clone())))
+            .cloned())
+    }
+}
+
 impl PackageResolver for InternalPackageResolver {
+    fn dependency_as_remote(&self) -> bool {
+        true
+    }
+
+    fn index(&self) -> Option<&dyn PackageIndex> {
+        Some(self)
+    }
+
     fn resolve(
         &self,
         _: &Path,
@@ -73,11 +144,12 @@ impl PackageResolver for InternalPackageResolver {
                     .into()
             })?;
 
-        // Find a matching version from pre-computed sources
+        // Take the highest matching version; solved dependencies arrive pinned to one
         let (found_version, found_fs) = self
             .pre_computed
             .iter()
-            .find(|((name, ver), _)| name == &dep_name && version_req.matches(&ver.0))
+            .filter(|((name, ver), _)| name == &dep_name && version_req.matches(&ver.0))
+            .max_by(|((_, a), _), ((_, b), _)| a.0.cmp(&b.0))
             .map(|((_, ver), fs)| (ver.clone(), fs.clone()))
             .ok_or_else(|| -> kintsu_parser::Error {
                 kintsu_parser::NamespaceError::unresolved_dep(&dep_name)
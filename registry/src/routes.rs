@@ -1,4 +1,6 @@
 pub mod auth;
 pub mod favourites;
+pub mod health;
 pub mod org;
 pub mod packages;
+pub mod teams;
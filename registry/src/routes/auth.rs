@@ -1,5 +1,9 @@
 use crate::{
-    DbConn, config::SessionConfig, oauth::AuthClient, principal::Principal, session::SessionData,
+    DbConn,
+    config::SessionConfig,
+    oauth::{AuthClient, LoginState, OAuthProvider},
+    principal::Principal,
+    session::SessionData,
 };
 use actix_web::{
     Responder, cookie, delete, get, post,
@@ -13,6 +17,75 @@ const AUTH: &str = "auth";
 #[derive(serde::Deserialize)]
 struct CallbackQuery {
     code: SecretString,
+    state: String,
+}
+
+fn set_cookie(
+    resp: &mut actix_web::HttpResponse,
+    cookie: &cookie::Cookie<'_>,
+) {
+    resp.headers_mut().append(
+        actix_web::http::header::SET_COOKIE,
+        cookie.encoded().to_string().parse().unwrap(),
+    );
+}
+
+/// Sends the user to `provider`, remembering the login in a cookie until it calls back.
+fn start_login(
+    req: &actix_web::HttpRequest,
+    provider: &dyn OAuthProvider,
+    cookie_key: &cookie::Key,
+    session_config: &SessionConfig,
+) -> crate::Result<actix_web::HttpResponse> {
+    let login = LoginState::new(provider.name());
+    let login_cookie = login.cookie(cookie_key, session_config.domain.clone())?;
+
+    let mut resp = Redirect::to(provider.authorize_url(&login).to_string())
+        .respond_to(req)
+        .map_into_boxed_body();
+    set_cookie(&mut resp, &login_cookie);
+
+    Ok(resp)
+}
+
+/// Completes the login the provider called back for, starting a session for its user.
+async fn finish_login(
+    req: &actix_web::HttpRequest,
+    client: &AuthClient,
+    provider: Option<&str>,
+    query: CallbackQuery,
+    cookie_key: &cookie::Key,
+    session_config: &SessionConfig,
+    conn: &sea_orm::DatabaseConnection,
+) -> crate::Result<actix_web::HttpResponse> {
+    let login = LoginState::from_request(req, cookie_key)?;
+    login.verify(provider.unwrap_or(&login.provider), &query.state)?;
+
+    let provider = client.provider(&login.provider)?;
+    let provider_login = provider.identify(query.code, &login).await?;
+
+    let user = kintsu_registry_db::engine::user::login_with_identity(conn, provider_login.identity)
+        .await?;
+
+    let mut session = SessionData::new(
+        user,
+        provider.name().to_string(),
+        provider_login.access_token,
+    );
+    let mut jar = actix_web::cookie::CookieJar::new();
+    session.jar(&mut jar, cookie_key, session_config.domain.clone())?;
+
+    let mut resp = Redirect::to("/")
+        .respond_to(req)
+        .map_into_boxed_body();
+
+    set_cookie(&mut resp, jar.delta().next().unwrap());
+    set_cookie(
+        &mut resp,
+        &LoginState::removal_cookie(session_config.domain.clone()),
+    );
+
+    Ok(resp)
 }
 
 #[utoipa::path(
@@ -20,6 +93,8 @@ struct CallbackQuery {
     responses(
         (status = 307, description = "Redirect to home page after successful authentication"),
         (status = 400, description = "Bad request", body = crate::ErrorResponse),
+        (status = 401, description = "Login failed or expired", body = crate::ErrorResponse),
+        (status = 409, description = "Email already registered through another provider", body = crate::ErrorResponse),
     )
 )]
 #[get("/auth/callback")]
@@ -28,44 +103,54 @@ pub async fn callback(
     client: web::Data<AuthClient>,
     cookie_key: web::Data<cookie::Key>,
     session_config: web::Data<SessionConfig>,
-    code: web::Query<CallbackQuery>,
+    query: web::Query<CallbackQuery>,
     conn: DbConn,
 ) -> crate::Result<impl Responder> {
-    let token = client
-        .exchange_token(code.into_inner().code)
-        .await?;
-
-    let user = client
-        .saturate_user_data(&token.access_token)
-        .await?;
-
-    let created = kintsu_registry_db::engine::user::create_or_update_user_from_oauth(
+    finish_login(
+        &req,
+        &client,
+        None,
+        query.into_inner(),
+        &cookie_key,
+        &session_config,
         conn.as_ref(),
-        user.id.0 as i32,
-        &user.login,
-        Some(user.avatar_url.as_str()),
-        &user.email.unwrap(),
     )
-    .await?;
-
-    let mut session = SessionData::new(created.clone(), token.access_token);
-    let mut jar = actix_web::cookie::CookieJar::new();
-    session.jar(&mut jar, &cookie_key, session_config.domain.clone())?;
-
-    let mut resp = Redirect::to("/").respond_to(&req);
-
-    resp.headers_mut().append(
-        actix_web::http::header::SET_COOKIE,
-        jar.delta()
-            .next()
-            .unwrap()
-            .encoded()
-            .to_string()
-            .parse()
-            .unwrap(),
-    );
+    .await
+}
 
-    Ok(resp)
+#[utoipa::path(
+    tag = AUTH,
+    params(
+        ("provider" = String, Path, description = "Login provider name"),
+    ),
+    responses(
+        (status = 307, description = "Redirect to home page after successful authentication"),
+        (status = 400, description = "Bad request", body = crate::ErrorResponse),
+        (status = 401, description = "Login failed or expired", body = crate::ErrorResponse),
+        (status = 404, description = "Login provider not found", body = crate::ErrorResponse),
+        (status = 409, description = "Email already registered through another provider", body = crate::ErrorResponse),
+    )
+)]
+#[get("/auth/callback/{provider}")]
+pub async fn provider_callback(
+    req: actix_web::HttpRequest,
+    provider: web::Path<String>,
+    client: web::Data<AuthClient>,
+    cookie_key: web::Data<cookie::Key>,
+    session_config: web::Data<SessionConfig>,
+    query: web::Query<CallbackQuery>,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    finish_login(
+        &req,
+        &client,
+        Some(provider.as_str()),
+        query.into_inner(),
+        &cookie_key,
+        &session_config,
+        conn.as_ref(),
+    )
+    .await
 }
 
 #[utoipa::path(
@@ -161,6 +246,18 @@ pub async fn get_user_tokens(
     Ok(web::Json(tokens))
 }
 
+#[utoipa::path(
+    tag = AUTH,
+    responses(
+        (status = 200, description = "Names of the providers users can log in with, the default first", body = Vec<String>),
+    )
+)]
+#[get("/auth/providers")]
+pub async fn list_providers(client: web::Data<AuthClient>) -> impl Responder {
+    web::Json(client.provider_names())
+}
+
+/// Log in with the default provider
 #[utoipa::path(
     tag = AUTH,
     responses(
@@ -168,8 +265,45 @@ pub async fn get_user_tokens(
     )
 )]
 #[get("/auth/login")]
-pub async fn redirect_to_login(client: web::Data<AuthClient>) -> impl Responder {
-    Redirect::to(client.login_url.to_string())
+pub async fn redirect_to_login(
+    req: actix_web::HttpRequest,
+    client: web::Data<AuthClient>,
+    cookie_key: web::Data<cookie::Key>,
+    session_config: web::Data<SessionConfig>,
+) -> crate::Result<impl Responder> {
+    start_login(
+        &req,
+        client.default_provider(),
+        &cookie_key,
+        &session_config,
+    )
+}
+
+/// Log in with a specific provider
+#[utoipa::path(
+    tag = AUTH,
+    params(
+        ("provider" = String, Path, description = "Login provider name"),
+    ),
+    responses(
+        (status = 307),
+        (status = 404, description = "Login provider not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/auth/login/{provider}")]
+pub async fn login_with_provider(
+    req: actix_web::HttpRequest,
+    provider: web::Path<String>,
+    client: web::Data<AuthClient>,
+    cookie_key: web::Data<cookie::Key>,
+    session_config: web::Data<SessionConfig>,
+) -> crate::Result<impl Responder> {
+    start_login(
+        &req,
+        client.provider(&provider)?,
+        &cookie_key,
+        &session_config,
+    )
 }
 
 #[utoipa::path(
@@ -0,0 +1,94 @@
+use crate::{DbConn, metrics::Metrics};
+use actix_web::{HttpResponse, Responder, get, web};
+use kintsu_registry_storage::PackageStorage;
+
+const HEALTH: &str = "health";
+
+/// The outcome of one readiness check. Failures are logged rather than returned, since the
+/// endpoint is unauthenticated.
+#[derive(serde::Serialize, utoipa::ToSchema, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckStatus {
+    Ok,
+    Failed,
+}
+
+impl CheckStatus {
+    fn from_result<E: std::fmt::Display>(
+        check: &str,
+        result: Result<(), E>,
+    ) -> Self {
+        match result {
+            Ok(()) => Self::Ok,
+            Err(err) => {
+                tracing::warn!("readiness check {check} failed: {err}");
+                Self::Failed
+            },
+        }
+    }
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema, Debug)]
+pub struct Readiness {
+    pub database: CheckStatus,
+    pub storage: CheckStatus,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        self.database == CheckStatus::Ok && self.storage == CheckStatus::Ok
+    }
+}
+
+/// Liveness probe, answering as long as the server is running
+#[utoipa::path(
+    tag = HEALTH,
+    responses(
+        (status = 200, description = "The server is running"),
+    )
+)]
+#[get("/healthz")]
+pub async fn healthz() -> impl Responder {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness probe, checking the database and package storage can be reached
+#[utoipa::path(
+    tag = HEALTH,
+    responses(
+        (status = 200, description = "The registry can serve requests", body = Readiness),
+        (status = 503, description = "A dependency cannot be reached", body = Readiness),
+    )
+)]
+#[get("/readyz")]
+pub async fn readyz(
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+) -> impl Responder {
+    let (database, storage) = tokio::join!(conn.ping(), storage.check());
+
+    let readiness = Readiness {
+        database: CheckStatus::from_result("database", database),
+        storage: CheckStatus::from_result("storage", storage),
+    };
+
+    if readiness.is_ready() {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
+    }
+}
+
+/// Metrics in the Prometheus text format
+#[utoipa::path(
+    tag = HEALTH,
+    responses(
+        (status = 200, description = "Prometheus metrics", content_type = "text/plain"),
+    )
+)]
+#[get("/metrics")]
+pub async fn metrics(metrics: web::Data<Metrics>) -> crate::Result<impl Responder> {
+    Ok(HttpResponse::Ok()
+        .content_type(prometheus::TEXT_FORMAT)
+        .body(metrics.render()?))
+}
@@ -176,6 +176,7 @@ pub async fn get_org_tokens(
     tag = ORGS,
     responses(
         (status = 200, description = "List of candidate organizations", body = Vec<kintsu_registry_core::models::CandidateOrg>),
+        (status = 400, description = "Session was not started by logging in with GitHub", body = crate::ErrorResponse),
         (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
         (status = 502, description = "GitHub API error", body = crate::ErrorResponse),
     ),
@@ -186,11 +187,9 @@ pub async fn discover_orgs(
     session: SessionData,
     conn: DbConn,
 ) -> crate::Result<impl Responder> {
-    use secrecy::SecretString;
-
     // Build Octocrab client with user's token
     let github = octocrab::Octocrab::builder()
-        .personal_token(SecretString::new(session.token.clone().into_boxed_str()))
+        .personal_token(session.github_token()?)
         .build()?;
 
     // Fetch user's organizations from GitHub
@@ -258,8 +257,6 @@ pub async fn import_org(
     conn: DbConn,
     req: web::Json<kintsu_registry_core::models::ImportOrgRequest>,
 ) -> crate::Result<impl Responder> {
-    use secrecy::SecretString;
-
     req.validate()?;
 
     if !principal.is_session() {
@@ -271,7 +268,7 @@ pub async fn import_org(
     }
 
     let github = octocrab::Octocrab::builder()
-        .personal_token(SecretString::new(session.token.clone().into()))
+        .personal_token(session.github_token()?)
         .build()?;
 
     let gh_org = github.orgs(&req.org_name).get().await?;
@@ -304,6 +301,54 @@ pub async fn import_org(
     Ok(web::Json(org))
 }
 
+/// Sync the members of an imported organization from GitHub now (admin only)
+#[utoipa::path(
+    tag = ORGS,
+    params(
+        ("id" = i64, Path, description = "Organization ID"),
+    ),
+    responses(
+        (status = 200, description = "Membership changes made by the sync", body = Vec<kintsu_registry_db::engine::org_sync::MembershipChange>),
+        (status = 400, description = "No GitHub token to sync with", body = crate::ErrorResponse),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "User is not an org admin", body = crate::ErrorResponse),
+        (status = 404, description = "Organization not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/org/{id}/sync")]
+pub async fn sync_org_members(
+    org_id: web::Path<i64>,
+    principal: Principal,
+    session: Option<SessionData>,
+    conn: DbConn,
+    sync: web::Data<crate::org_sync::OrgSync>,
+) -> crate::Result<impl Responder> {
+    kintsu_registry_db::engine::org_sync::authorize_sync(
+        conn.as_ref(),
+        principal.as_ref(),
+        *org_id,
+    )
+    .await?;
+
+    let org = Org::by_id(conn.as_ref(), *org_id)
+        .await?
+        .ok_or_else(|| {
+            crate::Error::Database(kintsu_registry_db::Error::NotFound(
+                "Organization not found".into(),
+            ))
+        })?;
+
+    // prefer the caller's own GitHub login, falling back to the configured token
+    let token = session.and_then(|session| session.github_token().ok());
+
+    let changes = sync
+        .sync_org(conn.as_ref(), Some(principal.as_ref()), &org, token)
+        .await?;
+
+    Ok(web::Json(changes))
+}
+
 #[utoipa::path(
     tag = ORGS,
     request_body = GrantOrgRoleRequest,
@@ -1,10 +1,15 @@
-use crate::{DbConn, principal::Principal};
+use crate::{DbConn, client::ClientInfo, principal::Principal};
 use actix_web::{
     Responder, delete, get, post,
     web::{self},
 };
-use kintsu_registry_core::models::{GrantSchemaRoleRequest, RevokeSchemaRoleRequest};
-use kintsu_registry_db::engine::{OrderDirection, PackageOrdering, PackageOrderingField, Page};
+use kintsu_registry_core::models::{
+    DeprecatePackageRequest, GrantSchemaRoleRequest, PackageReadme, RevokeSchemaRoleRequest,
+};
+use kintsu_registry_db::{
+    engine::{OrderDirection, PackageOrdering, PackageOrderingField, Page},
+    entities::DownloadArtifact,
+};
 use validator::Validate;
 
 const PACKAGES: &str = "packages";
@@ -13,16 +18,18 @@ const PACKAGES: &str = "packages";
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
         ("version" = String, Path, description = "Version string or 'latest'"),
     ),
     responses(
         (status = 200, description = "Package version metadata", body = kintsu_registry_db::engine::version::QualifiedPackageVersion),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
         (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
     )
 )]
 #[get("/package/{name}/{version}")]
 pub async fn get_package_version(
+    request: actix_web::HttpRequest,
     path: web::Path<(String, String)>,
     conn: DbConn,
 ) -> crate::Result<impl Responder> {
@@ -32,22 +39,24 @@ pub async fn get_package_version(
         kintsu_registry_db::entities::Version::get_package_version(conn.as_ref(), &name, &version)
             .await?;
 
-    Ok(web::Json(qualified))
+    crate::etag::json(&request, &qualified)
 }
 
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
         ("version" = String, Path, description = "Version string or 'latest'"),
     ),
     responses(
         (status = 200, description = "List of package dependents", body = Vec<kintsu_registry_db::engine::version::QualifiedPackageVersion>),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
         (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
     )
 )]
 #[get("/package/{name}/{version}/dependents")]
 pub async fn get_dependent_packages(
+    request: actix_web::HttpRequest,
     path: web::Path<(String, kintsu_manifests::version::VersionSerde)>,
     conn: DbConn,
 ) -> crate::Result<impl Responder> {
@@ -61,22 +70,24 @@ pub async fn get_dependent_packages(
     .await?;
     let dependents = found.dependents(conn.as_ref()).await?;
 
-    Ok(web::Json(dependents))
+    crate::etag::json(&request, &dependents)
 }
 
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
         ("version" = String, Path, description = "Version string or 'latest'"),
     ),
     responses(
         (status = 200, description = "List of package dependencies", body = Vec<kintsu_registry_db::engine::version::QualifiedPackageVersion>),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
         (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
     )
 )]
 #[get("/package/{name}/{version}/dependencies")]
 pub async fn get_package_dependencies(
+    request: actix_web::HttpRequest,
     path: web::Path<(String, kintsu_manifests::version::VersionSerde)>,
     conn: DbConn,
 ) -> crate::Result<impl Responder> {
@@ -90,23 +101,88 @@ pub async fn get_package_dependencies(
     .await?;
     let dependencies = found.dependencies(conn.as_ref()).await?;
 
-    Ok(web::Json(dependencies))
+    crate::etag::json(&request, &dependencies)
+}
+
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version string or 'latest'"),
+    ),
+    responses(
+        (status = 200, description = "Transitive dependencies of the version as graph nodes and edges", body = kintsu_registry_db::engine::DependencyGraph),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package/{name}/{version}/dependency-graph")]
+pub async fn get_dependency_graph(
+    request: actix_web::HttpRequest,
+    path: web::Path<(String, kintsu_manifests::version::VersionSerde)>,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let (name, version) = path.into_inner();
+
+    let found = kintsu_registry_db::entities::Version::by_name_and_version(
+        conn.as_ref(),
+        &name,
+        &version.to_string(),
+    )
+    .await?;
+    let graph = found.dependency_graph(conn.as_ref()).await?;
+
+    crate::etag::json(&request, &graph)
+}
+
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version string or 'latest'"),
+    ),
+    responses(
+        (status = 200, description = "Versions depending on the version, directly or transitively, as graph nodes and edges", body = kintsu_registry_db::engine::DependencyGraph),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package/{name}/{version}/dependent-graph")]
+pub async fn get_dependent_graph(
+    request: actix_web::HttpRequest,
+    path: web::Path<(String, kintsu_manifests::version::VersionSerde)>,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let (name, version) = path.into_inner();
+
+    let found = kintsu_registry_db::entities::Version::by_name_and_version(
+        conn.as_ref(),
+        &name,
+        &version.to_string(),
+    )
+    .await?;
+    let graph = found.dependent_graph(conn.as_ref()).await?;
+
+    crate::etag::json(&request, &graph)
 }
 
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
         ("version" = String, Path, description = "Version string or 'latest'"),
     ),
     responses(
-        (status = 200, description = "Package declarations", body = kintsu_parser::declare::DeclarationVersion),
+        (status = 200, description = "Package declarations, in the binary encoding when the `Accept` header asks for `application/vnd.kintsu.declarations`", body = kintsu_parser::declare::DeclarationVersion),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
         (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
     )
 )]
 #[get("/package/{name}/{version}/declarations")]
 pub async fn package_declarations(
+    request: actix_web::HttpRequest,
     path: web::Path<(String, String)>,
+    client: ClientInfo,
     conn: DbConn,
     storage: web::Data<kintsu_registry_db::PackageStorage>,
 ) -> crate::Result<impl Responder> {
@@ -117,38 +193,212 @@ pub async fn package_declarations(
 
     let version_id = version.id;
 
+    let event = client.download(DownloadArtifact::Declarations);
+
     tokio::spawn(async move {
-        let _ = kintsu_registry_db::entities::Version::increment_download_count(
+        let _ = kintsu_registry_db::entities::Version::record_download(
             conn.as_ref(),
             version_id,
+            &event,
         )
         .await;
     });
 
+    let binary = request
+        .headers()
+        .get(actix_web::http::header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains(kintsu_parser::declare::encoding::BINARY_MEDIA_TYPE));
+
+    let etag = crate::etag::ETag::of_artifact(
+        &version.declarations_checksum,
+        if binary {
+            "binary"
+        } else {
+            "json"
+        },
+    );
+    if etag.matches(&request) {
+        return Ok(etag.not_modified());
+    }
+
     let declarations = storage
         .get_declarations(
             &storage.path_for_declarations(&name, &version.qualified_version.to_string()),
             version.declarations_checksum.into(),
         )
         .await?;
-    Ok(web::Json(declarations))
+
+    let mut response = actix_web::HttpResponse::Ok();
+    response
+        .insert_header(etag.header())
+        .insert_header((actix_web::http::header::VARY, "Accept"));
+
+    if binary {
+        let encoded =
+            declarations.encode(kintsu_parser::declare::encoding::DeclarationEncoding::Binary)?;
+        return Ok(response
+            .content_type(kintsu_parser::declare::encoding::BINARY_MEDIA_TYPE)
+            .body(encoded));
+    }
+
+    Ok(response.json(declarations))
+}
+
+/// List the types and constants a version declares
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version string or 'latest'"),
+    ),
+    responses(
+        (status = 200, description = "Types and constants the version declares, by namespace and name", body = Vec<kintsu_registry_db::engine::DeclaredItemSummary>),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package/{name}/{version}/types")]
+pub async fn list_declared_items(
+    request: actix_web::HttpRequest,
+    path: web::Path<(String, String)>,
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+) -> crate::Result<impl Responder> {
+    let (name, version) = path.into_inner();
+    let version =
+        kintsu_registry_db::entities::Version::by_name_and_version(conn.as_ref(), &name, &version)
+            .await?;
+
+    let items = kintsu_registry_db::entities::DeclarationItem::list(
+        conn.as_ref(),
+        &storage,
+        &name,
+        &version,
+    )
+    .await?;
+
+    crate::etag::json(&request, &items)
+}
+
+/// Query a single type or constant a version declares, without downloading its declarations
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version string or 'latest'"),
+        ("namespace" = String, Path, description = "Qualified name of the declaring namespace, e.g. `abc::v1`"),
+        ("item" = String, Path, description = "Name of the type or constant"),
+    ),
+    responses(
+        (status = 200, description = "Declaration of the item", body = kintsu_parser::declare::index::DeclaredItem),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 404, description = "Package, version or item not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package/{name}/{version}/types/{namespace}/{item}")]
+pub async fn get_declared_item(
+    request: actix_web::HttpRequest,
+    path: web::Path<(String, String, String, String)>,
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+) -> crate::Result<impl Responder> {
+    let (name, version, namespace, item) = path.into_inner();
+    let version =
+        kintsu_registry_db::entities::Version::by_name_and_version(conn.as_ref(), &name, &version)
+            .await?;
+
+    let declared = kintsu_registry_db::entities::DeclarationItem::get(
+        conn.as_ref(),
+        &storage,
+        &name,
+        &version,
+        &namespace,
+        &item,
+    )
+    .await?;
+
+    crate::etag::json(&request, &declared)
 }
 
 /// Download a package version
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
         ("version" = String, Path, description = "Version string or 'latest'"),
     ),
     responses(
         (status = 302, description = "Redirect to package download URL"),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
         (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
     )
 )]
 #[get("/package/{name}/{version}/download")]
 pub async fn download_package_version(
+    request: actix_web::HttpRequest,
+    path: web::Path<(String, String)>,
+    client: ClientInfo,
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+) -> crate::Result<impl Responder> {
+    let (name, version) = path.into_inner();
+
+    let version =
+        kintsu_registry_db::entities::Version::by_name_and_version(conn.as_ref(), &name, &version)
+            .await?;
+
+    let version_id = version.id;
+
+    let event = client.download(DownloadArtifact::Source);
+
+    tokio::spawn(async move {
+        let _ = kintsu_registry_db::entities::Version::record_download(
+            conn.as_ref(),
+            version_id,
+            &event,
+        )
+        .await;
+    });
+
+    let etag = crate::etag::ETag::of_artifact(&version.source_checksum, "source");
+    if etag.matches(&request) {
+        return Ok(etag.not_modified());
+    }
+
+    let source = storage
+        .get_source(
+            &storage.path_for_source(&name, &version.qualified_version.to_string()),
+            version.source_checksum.into(),
+        )
+        .await?;
+
+    Ok(actix_web::HttpResponse::Ok()
+        .insert_header(etag.header())
+        .json(source))
+}
+
+/// List the files of a package version
+///
+/// Clients resolving a dependency lazily read this listing first and then fetch only the files
+/// they need from `/package/{name}/{version}/files/{file}`, so this counts as the download.
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version string or 'latest'"),
+    ),
+    responses(
+        (status = 200, description = "Sorted paths of the files in the package source", body = Vec<String>),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package/{name}/{version}/files")]
+pub async fn list_package_files(
+    request: actix_web::HttpRequest,
     path: web::Path<(String, String)>,
+    client: ClientInfo,
     conn: DbConn,
     storage: web::Data<kintsu_registry_db::PackageStorage>,
 ) -> crate::Result<impl Responder> {
@@ -160,14 +410,61 @@ pub async fn download_package_version(
 
     let version_id = version.id;
 
+    let event = client.download(DownloadArtifact::Source);
+
     tokio::spawn(async move {
-        let _ = kintsu_registry_db::entities::Version::increment_download_count(
+        let _ = kintsu_registry_db::entities::Version::record_download(
             conn.as_ref(),
             version_id,
+            &event,
         )
         .await;
     });
 
+    let etag = crate::etag::ETag::of_artifact(&version.source_checksum, "files");
+    if etag.matches(&request) {
+        return Ok(etag.not_modified());
+    }
+
+    let source = storage
+        .get_source(
+            &storage.path_for_source(&name, &version.qualified_version.to_string()),
+            version.source_checksum.into(),
+        )
+        .await?;
+
+    Ok(actix_web::HttpResponse::Ok()
+        .insert_header(etag.header())
+        .json(source.list_files()))
+}
+
+/// Get one file of a package version
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version string or 'latest'"),
+        ("file" = String, Path, description = "Path of the file within the package"),
+    ),
+    responses(
+        (status = 200, description = "File contents", content_type = "application/octet-stream"),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 404, description = "Package, version or file not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package/{name}/{version}/files/{file:.*}")]
+pub async fn get_package_file(
+    request: actix_web::HttpRequest,
+    path: web::Path<(String, String, String)>,
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+) -> crate::Result<impl Responder> {
+    let (name, version, file) = path.into_inner();
+
+    let version =
+        kintsu_registry_db::entities::Version::by_name_and_version(conn.as_ref(), &name, &version)
+            .await?;
+
     let source = storage
         .get_source(
             &storage.path_for_source(&name, &version.qualified_version.to_string()),
@@ -175,7 +472,73 @@ pub async fn download_package_version(
         )
         .await?;
 
-    Ok(web::Json(source))
+    let contents = source
+        .get_file_content(std::path::Path::new(&file))
+        .ok_or_else(|| {
+            kintsu_registry_db::Error::NotFound(format!(
+                "file {file} in {name}@{}",
+                version.qualified_version
+            ))
+        })?;
+
+    let etag = crate::etag::ETag::of_body(&contents);
+    if etag.matches(&request) {
+        return Ok(etag.not_modified());
+    }
+
+    Ok(actix_web::HttpResponse::Ok()
+        .content_type("application/octet-stream")
+        .insert_header(etag.header())
+        .body(contents))
+}
+
+/// Get the README of a package version
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version string or 'latest'"),
+    ),
+    responses(
+        (status = 200, description = "README as published and rendered to sanitized HTML", body = PackageReadme),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
+        (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package/{name}/{version}/readme")]
+pub async fn get_package_readme(
+    request: actix_web::HttpRequest,
+    path: web::Path<(String, String)>,
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+) -> crate::Result<impl Responder> {
+    let (name, version) = path.into_inner();
+
+    let version =
+        kintsu_registry_db::entities::Version::by_name_and_version(conn.as_ref(), &name, &version)
+            .await?;
+
+    let markdown = match version.readme_checksum {
+        Some(checksum) => {
+            storage
+                .retrieve_readme(
+                    &name,
+                    &version.qualified_version.to_string(),
+                    checksum.into(),
+                )
+                .await?
+        },
+        // published before readmes were written to storage
+        None => version.readme,
+    };
+
+    crate::etag::json(
+        &request,
+        &PackageReadme {
+            html: crate::readme::render(&markdown),
+            markdown,
+        },
+    )
 }
 
 #[utoipa::path(
@@ -205,6 +568,7 @@ pub async fn grant_package_role(
         &req.package_name,
         req.user_id,
         req.org_id,
+        req.team_id,
         req.role,
     )
     .await?;
@@ -246,7 +610,7 @@ pub async fn revoke_package_role(
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
     ),
     responses(
         (status = 200, description = "Total download count", body = kintsu_registry_core::models::DownloadStats),
@@ -272,7 +636,7 @@ pub async fn get_package_total_downloads(
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
     ),
     responses(
         (status = 200, description = "90-day download history", body = Vec<kintsu_registry_db::engine::DownloadHistory>),
@@ -291,6 +655,88 @@ pub async fn get_package_download_history(
     Ok(web::Json(history))
 }
 
+/// Get downloads of a package split by artifact and by origin
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("days" = Option<i32>, Query, description = "Window in days, 1 to 365 (default: 90)"),
+    ),
+    responses(
+        (status = 200, description = "Download breakdown", body = kintsu_registry_db::engine::DownloadBreakdown),
+        (status = 400, description = "Invalid query parameters", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package-analytics/{name}/downloads/breakdown")]
+pub async fn get_package_download_breakdown(
+    name: web::Path<String>,
+    query: web::Query<DownloadWindowQuery>,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let breakdown = kintsu_registry_db::entities::Package::package_download_breakdown(
+        conn.as_ref(),
+        &name,
+        query.days,
+    )
+    .await?;
+
+    Ok(web::Json(breakdown))
+}
+
+/// Get resolver downloads of a package grouped by compiler version
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("days" = Option<i32>, Query, description = "Window in days, 1 to 365 (default: 90)"),
+    ),
+    responses(
+        (status = 200, description = "Downloads per compiler version, newest first", body = Vec<kintsu_registry_db::engine::ClientDownloads>),
+        (status = 400, description = "Invalid query parameters", body = crate::ErrorResponse),
+    )
+)]
+#[get("/package-analytics/{name}/downloads/clients")]
+pub async fn get_package_client_downloads(
+    name: web::Path<String>,
+    query: web::Query<DownloadWindowQuery>,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let clients = kintsu_registry_db::entities::Package::package_client_downloads(
+        conn.as_ref(),
+        &name,
+        query.days,
+    )
+    .await?;
+
+    Ok(web::Json(clients))
+}
+
+/// Get downloads of a package aggregated per version and per day
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("days" = Option<i32>, Query, description = "Window in days, 1 to 365 (default: 90)"),
+    ),
+    responses(
+        (status = 200, description = "Downloads per version and per day", body = kintsu_registry_db::engine::PackageDownloads),
+        (status = 400, description = "Invalid query parameters", body = crate::ErrorResponse),
+        (status = 404, description = "Package not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/packages/{name}/downloads")]
+pub async fn get_package_downloads(
+    name: web::Path<String>,
+    query: web::Query<DownloadWindowQuery>,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let downloads =
+        kintsu_registry_db::entities::Package::package_downloads(conn.as_ref(), &name, query.days)
+            .await?;
+
+    Ok(web::Json(downloads))
+}
+
 /// List packages with pagination and ordering
 #[utoipa::path(
     tag = PACKAGES,
@@ -379,8 +825,13 @@ pub async fn search_packages(
 }
 
 #[derive(serde::Deserialize)]
-pub struct ListPackagesQuery {
-    pub page: Option<i64>,
+pub struct DownloadWindowQuery {
+    pub days: Option<i32>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ListPackagesQuery {
+    pub page: Option<i64>,
     pub size: Option<i64>,
     pub order_by: Option<PackageOrderingField>,
     pub order_dir: Option<OrderDirection>,
@@ -400,7 +851,7 @@ pub struct SearchPackagesQuery {
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
         ("page" = Option<i64>, Query, description = "Page number (default: 1)"),
         ("size" = Option<i64>, Query, description = "Page size (default: 20)"),
         ("user_id" = Option<i64>, Query, description = "Filter by user publisher ID"),
@@ -408,12 +859,14 @@ pub struct SearchPackagesQuery {
     ),
     responses(
         (status = 200, description = "Paginated list of versions", body = kintsu_registry_db::engine::Paginated<kintsu_registry_db::engine::version::QualifiedPackageVersion>),
+        (status = 304, description = "Unchanged since the `ETag` sent in `If-None-Match`"),
         (status = 404, description = "Package not found", body = crate::ErrorResponse),
         (status = 400, description = "Invalid query parameters", body = crate::ErrorResponse),
     )
 )]
 #[get("/packages/{name}/versions")]
 pub async fn list_package_versions(
+    request: actix_web::HttpRequest,
     name: web::Path<String>,
     query: web::Query<ListVersionsQuery>,
     conn: DbConn,
@@ -433,14 +886,14 @@ pub async fn list_package_versions(
     )
     .await?;
 
-    Ok(web::Json(paginated))
+    crate::etag::json(&request, &paginated)
 }
 
 /// Get unique publishers of a package
 #[utoipa::path(
     tag = PACKAGES,
     params(
-        ("name" = String, Path, description = "Package name"),
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
     ),
     responses(
         (status = 200, description = "List of unique publishers ordered by latest published version", body = Vec<kintsu_registry_db::engine::Entity>),
@@ -490,30 +943,301 @@ pub async fn publish_package(
     storage: web::Data<kintsu_registry_db::PackageStorage>,
     principal: crate::principal::Principal,
     request: web::Json<kintsu_registry_core::models::PublishPackageRequest>,
+    metrics: web::Data<crate::metrics::Metrics>,
+    compiler: web::Data<crate::compile::CompileExecutor>,
 ) -> crate::Result<impl Responder> {
     request.validate()?;
     if let Err(err) = request.validate_publishing_package_data() {
         return Err(crate::Error::PackagingErrors(err));
     }
 
-    let conn = conn.into_inner();
+    let request = request.into_inner();
+    let package = compile_and_publish(
+        conn.as_ref(),
+        storage.into_inner(),
+        &compiler,
+        principal.as_ref(),
+        request.manifest,
+        request.package_data,
+    )
+    .await;
+    metrics.record_publish(package.is_ok());
 
-    let deps = kintsu_registry_db::engine::package::StagePublishPackage::manifest_dependencies(
+    Ok(web::Json(package?))
+}
+
+#[utoipa::path(
+    tag = PACKAGES,
+    responses(
+        (status = 200, description = "The version the publish would create", body = kintsu_registry_core::models::PublishPreview),
+        (status = 400, description = "Invalid package data", body = crate::ErrorResponse),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden", body = crate::ErrorResponse),
+        (status = 409, description = "Version already exists", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []))
+)]
+#[post("/packages/publish/dry-run")]
+/// Validate a package version without publishing it.
+/// Runs the same checks as `/packages/publish`, then compares the declarations with the
+/// previous release. Returns the version which would be created and any warnings; nothing is
+/// stored.
+pub async fn publish_dry_run(
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+    principal: crate::principal::Principal,
+    request: web::Json<kintsu_registry_core::models::PublishPackageRequest>,
+    compiler: web::Data<crate::compile::CompileExecutor>,
+) -> crate::Result<impl Responder> {
+    request.validate()?;
+    if let Err(err) = request.validate_publishing_package_data() {
+        return Err(crate::Error::PackagingErrors(err));
+    }
+
+    let request = request.into_inner();
+    let compiled = compile_package(
         conn.as_ref(),
-        request.manifest.dependencies(),
+        &storage,
+        &compiler,
+        &request.manifest,
+        &request.package_data,
     )
     .await?;
 
-    let transitive_deps = kintsu_registry_db::entities::Package::get_transitive_dependencies(
+    let mut preview = kintsu_registry_db::engine::package::StagePublishPackage::preview(
         conn.as_ref(),
-        deps.clone(),
+        principal.as_ref(),
+        &storage,
+        &request.package_data,
+        &request.manifest,
+        &compiled.declarations,
+    )
+    .await?;
+    preview
+        .warnings
+        .extend(compiled.deprecations);
+
+    Ok(web::Json(preview))
+}
+
+/// How long a presigned publish upload URL stays valid.
+const PUBLISH_UPLOAD_EXPIRY_MINUTES: i64 = 15;
+
+#[utoipa::path(
+    tag = PACKAGES,
+    responses(
+        (status = 200, description = "Presigned upload URL for the package source", body = kintsu_registry_core::models::PublishUploadResponse),
+        (status = 400, description = "Invalid request", body = crate::ErrorResponse),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden", body = crate::ErrorResponse),
+        (status = 409, description = "Version already exists", body = crate::ErrorResponse),
+        (status = 501, description = "Package storage does not support direct uploads", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []))
+)]
+#[post("/packages/publish/upload")]
+/// Start a staged publish for large packages.
+/// Returns a presigned URL the serialized package data is uploaded to with a single `PUT`,
+/// after which the publish is completed with `/packages/publish/finalize`.
+pub async fn request_publish_upload(
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+    principal: crate::principal::Principal,
+    request: web::Json<kintsu_registry_core::models::PublishUploadRequest>,
+) -> crate::Result<impl Responder> {
+    request.validate()?;
+
+    kintsu_registry_db::engine::package::StagePublishPackage::authorize(
+        conn.as_ref(),
+        principal.as_ref(),
+        &request.package,
+    )
+    .await?;
+
+    if kintsu_registry_db::entities::Version::exists(
+        conn.as_ref(),
+        &request.package,
+        &request.version,
     )
     .await?
-    .into_iter()
-    .map(Into::into)
-    .collect::<Vec<_>>();
+    {
+        return Err(kintsu_registry_db::Error::PackageVersionExists {
+            package: request.package.clone(),
+            version: request.version.clone(),
+        }
+        .into());
+    }
+
+    let expires_in = chrono::Duration::minutes(PUBLISH_UPLOAD_EXPIRY_MINUTES);
+    let path = kintsu_registry_storage::StorageIndex::path_for_upload(
+        &request.package,
+        &request.version,
+        &request.source_checksum,
+    );
+    let url = storage
+        .presign_upload(&path, expires_in)
+        .await?
+        .ok_or(crate::Error::UploadNotSupported)?;
+
+    Ok(web::Json(
+        kintsu_registry_core::models::PublishUploadResponse {
+            url,
+            expires_at: chrono::Utc::now() + expires_in,
+        },
+    ))
+}
+
+#[utoipa::path(
+    tag = PACKAGES,
+    responses(
+        (status = 200, description = "Successfully published package", body = kintsu_registry_core::models::PublishPackageResponse),
+        (status = 400, description = "Invalid package data", body = crate::ErrorResponse),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []))
+)]
+#[post("/packages/publish/finalize")]
+/// Complete a staged publish.
+/// The uploaded package data is read back and verified against `source_checksum`, then
+/// published with the same requirements as `/packages/publish`.
+pub async fn finalize_publish(
+    conn: DbConn,
+    storage: web::Data<kintsu_registry_db::PackageStorage>,
+    principal: crate::principal::Principal,
+    request: web::Json<kintsu_registry_core::models::FinalizePublishRequest>,
+    metrics: web::Data<crate::metrics::Metrics>,
+    compiler: web::Data<crate::compile::CompileExecutor>,
+) -> crate::Result<impl Responder> {
+    request.validate()?;
+    let request = request.into_inner();
+
+    let package = request.manifest.package();
+    let path = kintsu_registry_storage::StorageIndex::path_for_upload(
+        &package.name,
+        &package.version.to_string(),
+        &request.source_checksum,
+    );
+    let package_data = storage
+        .get_source(&path, request.source_checksum.into())
+        .await?;
+
+    kintsu_registry_core::models::validate_package_data(&package_data)
+        .map_err(crate::Error::PackagingErrors)?;
+
+    let package = compile_and_publish(
+        conn.as_ref(),
+        storage.into_inner(),
+        &compiler,
+        principal.as_ref(),
+        request.manifest,
+        package_data,
+    )
+    .await;
+    metrics.record_publish(package.is_ok());
+
+    Ok(web::Json(package?))
+}
+
+/// Compiles `package_data` against its registry dependencies and stores the new version,
+/// shared by direct and staged publishes.
+async fn compile_and_publish(
+    conn: &sea_orm::DatabaseConnection,
+    storage: std::sync::Arc<kintsu_registry_db::PackageStorage>,
+    compiler: &crate::compile::CompileExecutor,
+    principal: &kintsu_registry_db::engine::PrincipalIdentity,
+    manifest: kintsu_manifests::package::PackageManifests,
+    package_data: kintsu_fs::memory::MemoryFileSystem,
+) -> crate::Result<kintsu_registry_db::entities::Version> {
+    let compiled = compile_package(conn, &storage, compiler, &manifest, &package_data).await?;
+
+    let package = kintsu_registry_db::engine::package::StagePublishPackage::process(
+        conn,
+        principal,
+        storage,
+        package_data,
+        manifest,
+        compiled.declarations,
+        compiled.deps,
+    )
+    .await?;
+
+    Ok(package)
+}
+
+/// A package compiled against its registry dependencies, not yet stored.
+struct CompiledPackage {
+    declarations: kintsu_parser::declare::DeclarationVersion,
+    deps: Vec<i64>,
+    deprecations: Vec<kintsu_registry_db::engine::package::PublishWarning>,
+}
+
+/// Compiles within the limits of `compiler`, which are checked before anything is fetched.
+async fn compile_package(
+    conn: &sea_orm::DatabaseConnection,
+    storage: &kintsu_registry_db::PackageStorage,
+    compiler: &crate::compile::CompileExecutor,
+    manifest: &kintsu_manifests::package::PackageManifests,
+    package_data: &kintsu_fs::memory::MemoryFileSystem,
+) -> crate::Result<CompiledPackage> {
+    compiler.check_package(package_data)?;
+
+    let deps = kintsu_registry_db::engine::package::StagePublishPackage::manifest_dependencies(
+        conn,
+        manifest.dependencies(),
+    )
+    .await?;
+
+    let transitive_deps =
+        kintsu_registry_db::entities::Package::get_transitive_dependencies(conn, deps.clone())
+            .await?;
+
+    let deprecations = transitive_deps
+        .iter()
+        .filter_map(|dep| {
+            let message = dep.deprecation_message.clone()?;
+            Some((
+                (
+                    dep.package_name.clone(),
+                    kintsu_manifests::version::VersionSerde(
+                        kintsu_manifests::version::parse_version(&dep.qualified_version).unwrap(),
+                    ),
+                ),
+                kintsu_parser::ctx::compile::resolver::DeprecationNotice {
+                    message,
+                    replacement: dep.deprecation_replacement.clone(),
+                },
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let warnings = deprecations
+        .iter()
+        .map(|((package, version), notice)| {
+            kintsu_registry_db::engine::package::PublishWarning::DeprecatedDependency {
+                package: package.clone(),
+                version: version.to_string(),
+                message: notice.message.clone(),
+                replacement: notice.replacement.clone(),
+            }
+        })
+        .collect();
+
+    let deps_sources = storage
+        .get_sources(
+            transitive_deps
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        )
+        .await?;
+    compiler.check_input(
+        deps_sources
+            .iter()
+            .map(|source| &source.fs)
+            .chain([package_data]),
+    )?;
 
-    let deps_sources = storage.get_sources(transitive_deps).await?;
     let resolver = crate::resolver::InternalPackageResolver::new(
         deps_sources
             .into_iter()
@@ -529,47 +1253,240 @@ pub async fn publish_package(
                 )
             })
             .collect(),
-    );
+    )
+    .with_deprecations(deprecations.into_iter().collect());
+
+    let declarations = compiler
+        .run(package_data, move |sandbox| {
+            async move {
+                let ctx = kintsu_parser::ctx::CompileCtx::with_fs_and_config(
+                    std::sync::Arc::new(sandbox),
+                    std::sync::Arc::new(resolver),
+                    "./",
+                    4,
+                    false,
+                )
+                .await?;
+
+                ctx.finalize().await?;
+                Ok(ctx.emit_declarations().await?)
+            }
+        })
+        .await?;
+
+    Ok(CompiledPackage {
+        declarations,
+        deps,
+        deprecations: warnings,
+    })
+}
 
-    let ctx = kintsu_parser::ctx::CompileCtx::with_fs_and_config(
-        std::sync::Arc::new(request.package_data.clone()),
-        std::sync::Arc::new(resolver),
-        "./",
-        4,
-        false,
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version to yank"),
+    ),
+    responses(
+        (status = 204, description = "Version yanked successfully"),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - insufficient permissions", body = crate::ErrorResponse),
+        (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/package/{name}/{version}/yank")]
+/// Yank a published version, so it is no longer chosen for new dependency resolutions.
+pub async fn yank_package_version(
+    path: web::Path<(String, String)>,
+    conn: DbConn,
+    principal: crate::principal::Principal,
+) -> crate::Result<impl Responder> {
+    let (name, version) = path.into_inner();
+
+    kintsu_registry_db::entities::Package::yank_version(
+        conn.as_ref(),
+        principal.as_ref(),
+        &name,
+        &version,
     )
     .await?;
 
-    ctx.finalize().await?;
-    let declarations = ctx.emit_declarations().await?;
+    Ok(actix_web::HttpResponse::NoContent().finish())
+}
 
-    let package = kintsu_registry_db::engine::package::StagePublishPackage::process(
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version to unyank"),
+    ),
+    responses(
+        (status = 204, description = "Version unyanked successfully"),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - insufficient permissions", body = crate::ErrorResponse),
+        (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/package/{name}/{version}/unyank")]
+/// Unyank a previously yanked version, so it can be chosen for new dependency resolutions again.
+pub async fn unyank_package_version(
+    path: web::Path<(String, String)>,
+    conn: DbConn,
+    principal: crate::principal::Principal,
+) -> crate::Result<impl Responder> {
+    let (name, version) = path.into_inner();
+
+    kintsu_registry_db::entities::Package::unyank_version(
         conn.as_ref(),
         principal.as_ref(),
-        storage.into_inner(),
-        request.0.package_data,
-        request.0.manifest,
-        declarations,
-        deps,
+        &name,
+        &version,
     )
     .await?;
 
-    Ok(web::Json(package))
+    Ok(actix_web::HttpResponse::NoContent().finish())
 }
 
-#[post("/package/{name}/{version}/yank")]
-pub async fn yank_package_version(
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+    ),
+    request_body = DeprecatePackageRequest,
+    responses(
+        (status = 204, description = "Package deprecated successfully"),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - insufficient permissions", body = crate::ErrorResponse),
+        (status = 404, description = "Package or replacement not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/package/{name}/deprecate")]
+/// Deprecate every version of a package. Deprecated versions still resolve, with a warning.
+pub async fn deprecate_package(
+    path: web::Path<String>,
+    conn: DbConn,
+    principal: crate::principal::Principal,
+    request: web::Json<DeprecatePackageRequest>,
+) -> crate::Result<impl Responder> {
+    request.validate()?;
+    let name = path.into_inner();
+
+    kintsu_registry_db::entities::Package::deprecate(
+        conn.as_ref(),
+        principal.as_ref(),
+        &name,
+        None,
+        &request.message,
+        request.replacement.as_deref(),
+    )
+    .await?;
+
+    Ok(actix_web::HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+    ),
+    responses(
+        (status = 204, description = "Package deprecation removed"),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - insufficient permissions", body = crate::ErrorResponse),
+        (status = 404, description = "Package not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/package/{name}/undeprecate")]
+/// Remove the deprecation of a package. Versions deprecated on their own stay deprecated.
+pub async fn undeprecate_package(
+    path: web::Path<String>,
+    conn: DbConn,
+    principal: crate::principal::Principal,
+) -> crate::Result<impl Responder> {
+    let name = path.into_inner();
+
+    kintsu_registry_db::entities::Package::undeprecate(
+        conn.as_ref(),
+        principal.as_ref(),
+        &name,
+        None,
+    )
+    .await?;
+
+    Ok(actix_web::HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version to deprecate"),
+    ),
+    request_body = DeprecatePackageRequest,
+    responses(
+        (status = 204, description = "Version deprecated successfully"),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - insufficient permissions", body = crate::ErrorResponse),
+        (status = 404, description = "Package, version or replacement not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/package/{name}/{version}/deprecate")]
+/// Deprecate a single published version. It still resolves, with a warning.
+pub async fn deprecate_package_version(
     path: web::Path<(String, String)>,
     conn: DbConn,
     principal: crate::principal::Principal,
+    request: web::Json<DeprecatePackageRequest>,
 ) -> crate::Result<impl Responder> {
+    request.validate()?;
     let (name, version) = path.into_inner();
 
-    kintsu_registry_db::entities::Package::yank_version(
+    kintsu_registry_db::entities::Package::deprecate(
         conn.as_ref(),
         principal.as_ref(),
         &name,
-        &version,
+        Some(&version),
+        &request.message,
+        request.replacement.as_deref(),
+    )
+    .await?;
+
+    Ok(actix_web::HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    tag = PACKAGES,
+    params(
+        ("name" = String, Path, description = "Package name, with the `/` of scoped names encoded as `%2F`"),
+        ("version" = String, Path, description = "Version to undeprecate"),
+    ),
+    responses(
+        (status = 204, description = "Version deprecation removed"),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - insufficient permissions", body = crate::ErrorResponse),
+        (status = 404, description = "Package or version not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/package/{name}/{version}/undeprecate")]
+/// Remove the deprecation of a single version. A deprecation of its package still applies.
+pub async fn undeprecate_package_version(
+    path: web::Path<(String, String)>,
+    conn: DbConn,
+    principal: crate::principal::Principal,
+) -> crate::Result<impl Responder> {
+    let (name, version) = path.into_inner();
+
+    kintsu_registry_db::entities::Package::undeprecate(
+        conn.as_ref(),
+        principal.as_ref(),
+        &name,
+        Some(&version),
     )
     .await?;
 
@@ -0,0 +1,246 @@
+use crate::{DbConn, principal::Principal};
+use actix_web::{Responder, delete, get, patch, post, web};
+use kintsu_registry_core::models::{AddTeamMemberRequest, CreateTeamRequest, UpdateTeamRequest};
+use kintsu_registry_db::entities::Team;
+use validator::Validate;
+
+const TEAMS: &str = "teams";
+
+fn team_not_found() -> crate::Error {
+    crate::Error::Database(kintsu_registry_db::Error::NotFound("Team not found".into()))
+}
+
+/// List the teams of an organization
+#[utoipa::path(
+    tag = TEAMS,
+    params(
+        ("id" = i64, Path, description = "Organization ID"),
+    ),
+    responses(
+        (status = 200, description = "Teams of the organization", body = Vec<kintsu_registry_db::entities::Team>),
+    )
+)]
+#[get("/org/{id}/teams")]
+pub async fn list_teams(
+    org_id: web::Path<i64>,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let teams = Team::for_org(conn.as_ref(), *org_id).await?;
+
+    Ok(web::Json(teams))
+}
+
+/// Create a team in an organization
+#[utoipa::path(
+    tag = TEAMS,
+    params(
+        ("id" = i64, Path, description = "Organization ID"),
+    ),
+    request_body = CreateTeamRequest,
+    responses(
+        (status = 200, description = "Team created", body = kintsu_registry_db::entities::Team),
+        (status = 400, description = "Invalid request", body = crate::ErrorResponse),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - not an org admin", body = crate::ErrorResponse),
+        (status = 404, description = "Organization not found", body = crate::ErrorResponse),
+        (status = 409, description = "Team name already taken", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/org/{id}/teams")]
+pub async fn create_team(
+    org_id: web::Path<i64>,
+    principal: Principal,
+    conn: DbConn,
+    req: web::Json<CreateTeamRequest>,
+) -> crate::Result<impl Responder> {
+    req.validate()?;
+
+    let req = req.into_inner();
+    let team = kintsu_registry_db::engine::team::create_team(
+        conn.as_ref(),
+        principal.as_ref(),
+        *org_id,
+        req.name,
+        req.description,
+    )
+    .await?;
+
+    Ok(web::Json(team))
+}
+
+/// Rename a team or change its description
+#[utoipa::path(
+    tag = TEAMS,
+    params(
+        ("id" = i64, Path, description = "Organization ID"),
+        ("team_id" = i64, Path, description = "Team ID"),
+    ),
+    request_body = UpdateTeamRequest,
+    responses(
+        (status = 200, description = "Team updated", body = kintsu_registry_db::entities::Team),
+        (status = 400, description = "Invalid request", body = crate::ErrorResponse),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - not an org admin", body = crate::ErrorResponse),
+        (status = 404, description = "Team not found", body = crate::ErrorResponse),
+        (status = 409, description = "Team name already taken", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[patch("/org/{id}/teams/{team_id}")]
+pub async fn update_team(
+    path: web::Path<(i64, i64)>,
+    principal: Principal,
+    conn: DbConn,
+    req: web::Json<UpdateTeamRequest>,
+) -> crate::Result<impl Responder> {
+    req.validate()?;
+
+    let (org_id, team_id) = path.into_inner();
+    let req = req.into_inner();
+    let team = kintsu_registry_db::engine::team::update_team(
+        conn.as_ref(),
+        principal.as_ref(),
+        org_id,
+        team_id,
+        req.name,
+        req.description,
+    )
+    .await?;
+
+    Ok(web::Json(team))
+}
+
+/// Delete a team, revoking the package roles granted to it
+#[utoipa::path(
+    tag = TEAMS,
+    params(
+        ("id" = i64, Path, description = "Organization ID"),
+        ("team_id" = i64, Path, description = "Team ID"),
+    ),
+    responses(
+        (status = 204, description = "Team deleted"),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - not an org admin", body = crate::ErrorResponse),
+        (status = 404, description = "Team not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[delete("/org/{id}/teams/{team_id}")]
+pub async fn delete_team(
+    path: web::Path<(i64, i64)>,
+    principal: Principal,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let (org_id, team_id) = path.into_inner();
+    kintsu_registry_db::engine::team::delete_team(
+        conn.as_ref(),
+        principal.as_ref(),
+        org_id,
+        team_id,
+    )
+    .await?;
+
+    Ok(actix_web::HttpResponse::NoContent().finish())
+}
+
+/// List the members of a team
+#[utoipa::path(
+    tag = TEAMS,
+    params(
+        ("id" = i64, Path, description = "Organization ID"),
+        ("team_id" = i64, Path, description = "Team ID"),
+    ),
+    responses(
+        (status = 200, description = "Members of the team", body = Vec<kintsu_registry_db::engine::team::TeamMemberWithUser>),
+        (status = 404, description = "Team not found", body = crate::ErrorResponse),
+    )
+)]
+#[get("/org/{id}/teams/{team_id}/members")]
+pub async fn list_team_members(
+    path: web::Path<(i64, i64)>,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let (org_id, team_id) = path.into_inner();
+    let team = Team::by_id(conn.as_ref(), org_id, team_id)
+        .await?
+        .ok_or_else(team_not_found)?;
+
+    Ok(web::Json(team.members(conn.as_ref()).await?))
+}
+
+/// Add a member to a team, or change the role of an existing member
+#[utoipa::path(
+    tag = TEAMS,
+    params(
+        ("id" = i64, Path, description = "Organization ID"),
+        ("team_id" = i64, Path, description = "Team ID"),
+    ),
+    request_body = AddTeamMemberRequest,
+    responses(
+        (status = 200, description = "Member added", body = kintsu_registry_db::entities::TeamMember),
+        (status = 400, description = "User is not a member of the organization", body = crate::ErrorResponse),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - not an org admin or team maintainer", body = crate::ErrorResponse),
+        (status = 404, description = "Team not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[post("/org/{id}/teams/{team_id}/members")]
+pub async fn add_team_member(
+    path: web::Path<(i64, i64)>,
+    principal: Principal,
+    conn: DbConn,
+    req: web::Json<AddTeamMemberRequest>,
+) -> crate::Result<impl Responder> {
+    req.validate()?;
+
+    let (org_id, team_id) = path.into_inner();
+    let req = req.into_inner();
+    let member = kintsu_registry_db::engine::team::add_member(
+        conn.as_ref(),
+        principal.as_ref(),
+        org_id,
+        team_id,
+        req.user_id,
+        req.role,
+    )
+    .await?;
+
+    Ok(web::Json(member))
+}
+
+/// Remove a member from a team
+#[utoipa::path(
+    tag = TEAMS,
+    params(
+        ("id" = i64, Path, description = "Organization ID"),
+        ("team_id" = i64, Path, description = "Team ID"),
+        ("user_id" = i64, Path, description = "User ID"),
+    ),
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 401, description = "Unauthorized", body = crate::ErrorResponse),
+        (status = 403, description = "Forbidden - not an org admin or team maintainer", body = crate::ErrorResponse),
+        (status = 404, description = "Team member not found", body = crate::ErrorResponse),
+    ),
+    security(("api_key" = []), ("session" = []))
+)]
+#[delete("/org/{id}/teams/{team_id}/members/{user_id}")]
+pub async fn remove_team_member(
+    path: web::Path<(i64, i64, i64)>,
+    principal: Principal,
+    conn: DbConn,
+) -> crate::Result<impl Responder> {
+    let (org_id, team_id, user_id) = path.into_inner();
+    kintsu_registry_db::engine::team::remove_member(
+        conn.as_ref(),
+        principal.as_ref(),
+        org_id,
+        team_id,
+        user_id,
+    )
+    .await?;
+
+    Ok(actix_web::HttpResponse::NoContent().finish())
+}
@@ -13,8 +13,16 @@ pub struct PublicData {
     pub expires_at: chrono::DateTime<chrono::Utc>,
 }
 
+fn default_provider() -> String {
+    crate::oauth::GITHUB.into()
+}
+
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 pub struct SessionData {
+    /// The login provider the session was started with, and which `token` is for
+    #[serde(default = "default_provider")]
+    pub provider: String,
+
     pub token: String,
 
     pub user: PublicData,
@@ -26,10 +34,12 @@ pub struct SessionData {
 impl SessionData {
     pub fn new(
         user: kintsu_registry_db::entities::User,
+        provider: String,
         token: SecretString,
     ) -> Self {
         Self {
             dirty: false,
+            provider,
             token: token.expose_secret().to_string(),
             user: PublicData {
                 user,
@@ -39,6 +49,18 @@ impl SessionData {
         }
     }
 
+    /// The GitHub access token of sessions started by logging in with GitHub.
+    pub fn github_token(&self) -> crate::Result<SecretString> {
+        if self.provider != crate::oauth::GITHUB {
+            return Err(crate::Error::Database(
+                kintsu_registry_db::Error::Validation(
+                    "This requires logging in with GitHub".into(),
+                ),
+            ));
+        }
+        Ok(SecretString::from(self.token.clone()))
+    }
+
     pub fn jar(
         &mut self,
         jar: &mut CookieJar,
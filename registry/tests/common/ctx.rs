@@ -5,7 +5,7 @@ use kintsu_parser::declare::DeclarationVersion;
 use kintsu_registry::{
     app::ApiDoc,
     bind_app,
-    routes::{auth, favourites, org, packages},
+    routes::{auth, favourites, health, org, packages, teams},
 };
 use kintsu_registry_db::{
     engine::PrincipalIdentity,
@@ -13,7 +13,10 @@ use kintsu_registry_db::{
     fixtures,
     tst::TestDbCtx,
 };
-use kintsu_registry_storage::{manager::StorageManager, tst::TestS3Ctx};
+use kintsu_registry_storage::{
+    disk::{DiskConfig, LocalDiskStorage},
+    manager::StorageManager,
+};
 use secrecy::SecretString;
 use utoipa::OpenApi;
 use utoipa_actix_web::AppExt;
@@ -25,22 +28,83 @@ use super::RequestBuilder;
 /// Test registry context providing database, storage, and app for integration tests
 pub struct TestRegistryCtx {
     pub db: TestDbCtx,
-    pub s3: TestS3Ctx,
+    /// Package storage root when stored on disk, removed when the context is dropped
+    pub storage_root: Option<tempfile::TempDir>,
     pub storage: web::Data<StorageManager<DeclarationVersion>>,
     pub cookie_key: web::Data<Key>,
     pub session_config: web::Data<kintsu_registry::config::SessionConfig>,
     pub client: web::Data<kintsu_registry::oauth::AuthClient>,
 }
 
+/// The app factory of `bind_app!` over the data of a [`TestRegistryCtx`]
+macro_rules! test_app {
+    ($ctx:expr) => {{
+        let ctx: &TestRegistryCtx = $ctx;
+        let db = actix_web::web::Data::new(ctx.db.conn.clone());
+        let s3 = ctx.storage.clone();
+        let session_config = ctx.session_config.clone();
+        let cookie_key = ctx.cookie_key.clone();
+        let client = ctx.client.clone();
+        let rate_limiter =
+            actix_web::web::Data::new(kintsu_registry::rate_limit::RateLimiter::disabled());
+        let org_sync = actix_web::web::Data::new(kintsu_registry::org_sync::OrgSync::new(
+            url::Url::parse("https://api.github.com").unwrap(),
+            None,
+        ));
+        let metrics = actix_web::web::Data::new(kintsu_registry::metrics::Metrics::new().unwrap());
+        let compiler = actix_web::web::Data::new(kintsu_registry::compile::CompileExecutor::new(
+            Default::default(),
+        ));
+
+        bind_app!(
+            session_config,
+            db,
+            s3,
+            client,
+            cookie_key,
+            rate_limiter,
+            org_sync,
+            metrics,
+            compiler,
+        )
+    }};
+}
+
+/// A running app from [`TestRegistryCtx::serve`], stopped when dropped
+pub struct TestServer {
+    pub base_url: String,
+    handle: actix_web::dev::ServerHandle,
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(self.handle.stop(false));
+        }
+    }
+}
+
 const TEST_SESSION_KEY: &str =
     "test-session-key-must-be-at-least-64-bytes-long-for-cookie-key-derivation-0123456789";
 
 impl TestRegistryCtx {
-    /// Create a new test context with database and storage containers
+    /// Create a new test context with a database container and on-disk package storage
     pub async fn new() -> Self {
-        let (db, s3) = tokio::join!(TestDbCtx::new(), TestS3Ctx::new());
+        let storage_root = tempfile::tempdir().unwrap();
+        let storage = LocalDiskStorage::<DeclarationVersion>::managed(&DiskConfig {
+            root: storage_root.path().to_path_buf(),
+            declarations_encoding: kintsu_registry_storage::DeclarationEncoding::Binary,
+        });
+
+        let mut ctx = Self::with_storage(storage).await;
+        ctx.storage_root = Some(storage_root);
+        ctx
+    }
 
-        let storage = web::Data::new(s3.managed::<DeclarationVersion>().await);
+    /// Create a new test context with a database container and the given package storage
+    pub async fn with_storage(storage: StorageManager<DeclarationVersion>) -> Self {
+        let db = TestDbCtx::new().await;
+        let storage = web::Data::new(storage);
         let cookie_key = web::Data::new(Key::derive_from(TEST_SESSION_KEY.as_bytes()));
         let session_config = web::Data::new(kintsu_registry::config::SessionConfig {
             domain: "localhost".to_string(),
@@ -56,11 +120,16 @@ impl TestRegistryCtx {
                 secret: SecretString::from("test-client-secret"),
             },
         };
-        let client = web::Data::new(kintsu_registry::oauth::AuthClient::new(gh_config).unwrap());
+        let client = web::Data::new(
+            kintsu_registry::oauth::AuthClient::new(vec![Box::new(
+                kintsu_registry::oauth::GithubProvider::new(gh_config).unwrap(),
+            )])
+            .unwrap(),
+        );
 
         Self {
             db,
-            s3,
+            storage_root: None,
             storage,
             cookie_key,
             session_config,
@@ -81,13 +150,23 @@ impl TestRegistryCtx {
         Response = ServiceResponse,
         Error = actix_web::Error,
     > {
-        let db = web::Data::new(self.db.conn.clone());
-        let s3 = self.storage.clone();
-        let session_config = self.session_config.clone();
-        let cookie_key = self.cookie_key.clone();
-        let client = self.client.clone();
+        test::init_service(test_app!(self)()).await
+    }
+
+    /// Serve the app over HTTP on an ephemeral local port, for clients outside the process
+    pub fn serve(&self) -> TestServer {
+        let server = actix_web::HttpServer::new(test_app!(self))
+            .workers(1)
+            .disable_signals()
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let base_url = format!("http://{}", server.addrs()[0]);
+
+        let server = server.run();
+        let handle = server.handle();
+        tokio::spawn(server);
 
-        test::init_service(bind_app!(session_config, db, s3, client, cookie_key,)()).await
+        TestServer { base_url, handle }
     }
 
     // Helper methods for common test setups
@@ -1,12 +1,18 @@
 //! Test infrastructure for registry integration tests
 //!
-//! Provides TestRegistryCtx which composes TestDbCtx and TestS3Ctx
-//! along with fluent builders for making HTTP requests.
+//! Provides TestRegistryCtx which composes TestDbCtx and on-disk package storage
+//! along with fluent builders for making HTTP requests, and TestPublishCtx which serves
+//! the registry on S3 storage for end to end client flows.
+
+#![allow(dead_code)]
 
 mod ctx;
+mod publish;
 mod request;
 mod response;
 
 pub use ctx::*;
+#[allow(unused_imports)] // only used by the end to end tests
+pub use publish::*;
 pub use request::*;
 pub use response::*;
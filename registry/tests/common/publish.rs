@@ -0,0 +1,100 @@
+//! Test publish context - the registry served over HTTP on S3 storage, with a client
+
+use std::sync::Arc;
+
+use kintsu_env_client::{Mutation, RegistryClient, RetryPolicy};
+use kintsu_fs::memory::MemoryFileSystem;
+use kintsu_manifests::{config::NewForNamed, package::PackageManifests};
+use kintsu_registry_storage::tst::{FaultyStorage, StorageFaults, TestS3Ctx};
+use secrecy::SecretString;
+
+use super::{TestRegistryCtx, TestServer};
+
+/// Test context for end to end flows: a registry app served on a local port, storing
+/// packages in an S3 container through storage whose faults tests control, and
+/// [`RegistryClient`]s pointed at it.
+pub struct TestPublishCtx {
+    pub registry: TestRegistryCtx,
+    pub s3: TestS3Ctx,
+    /// Faults of the registry's storage, e.g. to fail storing declarations mid-publish
+    pub faults: StorageFaults,
+    pub server: TestServer,
+}
+
+impl TestPublishCtx {
+    /// Start database and S3 containers, and serve the registry on them
+    pub async fn new() -> Self {
+        let s3 = TestS3Ctx::new().await;
+        let (storage, faults) = FaultyStorage::managed(s3.managed().await.storage());
+        let registry = TestRegistryCtx::with_storage(storage).await;
+        let server = registry.serve();
+
+        Self {
+            registry,
+            s3,
+            faults,
+            server,
+        }
+    }
+
+    /// A client of the served registry authenticated with `token`, which does not retry so
+    /// injected faults surface on the first attempt
+    pub fn client(
+        &self,
+        token: Option<&str>,
+    ) -> RegistryClient {
+        RegistryClient::builder(&self.server.base_url)
+            .maybe_token(token.map(SecretString::from))
+            .retry(RetryPolicy::none())
+            .build()
+            .unwrap()
+    }
+
+    /// Publish the package in the root of `package` with `token`
+    pub async fn publish(
+        &self,
+        token: &str,
+        package: &MemoryFileSystem,
+    ) -> Result<kintsu_registry_core::models::Version, kintsu_env_client::Error> {
+        let manifest = PackageManifests::new(package, "").unwrap();
+        match self
+            .client(Some(token))
+            .publish_compiled_package(manifest, Arc::new(package.clone()), "")
+            .await?
+        {
+            Mutation::Performed(version) => Ok(version),
+            Mutation::DryRun(_) => unreachable!("test clients are not in dry-run mode"),
+        }
+    }
+}
+
+/// A publishable package named `name` at `version`, with `dependencies` as written in the
+/// manifest and `lib` as its `schema/lib.ks`
+pub fn package(
+    name: &str,
+    version: &str,
+    dependencies: &str,
+    lib: &str,
+) -> MemoryFileSystem {
+    let fs = MemoryFileSystem::new();
+    fs.add_file(
+        "schema.toml",
+        format!(
+            r#"version = "v1"
+
+[package]
+name = "{name}"
+version = "{version}"
+description = "Test package {name}"
+license = "MIT"
+readme = "Published by the registry end to end tests."
+repository = "https://example.com/tests/{name}"
+
+[dependencies]
+{dependencies}
+"#
+        ),
+    );
+    fs.add_file("schema/lib.ks", lib);
+    fs
+}
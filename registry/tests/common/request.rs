@@ -1,11 +1,7 @@
 //! Fluent request builder for test HTTP requests
 
 use actix_http::Request;
-use actix_web::{
-    dev::{Service, ServiceResponse},
-    http::Method,
-    test::TestRequest,
-};
+use actix_web::{dev::Service, http::Method, test::TestRequest};
 use serde::Serialize;
 
 use super::{TestRegistryCtx, TestResponse};
@@ -289,7 +289,7 @@ async fn revoke_schema_role_as_non_admin_fails() {
 #[actix_web::test]
 async fn grant_org_role_as_admin() {
     let ctx = TestRegistryCtx::new().await;
-    let (org, admin, admin_token) = ctx.create_org_with_admin().await;
+    let (org, _admin, admin_token) = ctx.create_org_with_admin().await;
 
     // Create target user
     let target_user = fixtures::user()
@@ -315,7 +315,7 @@ async fn grant_org_role_as_admin() {
 async fn grant_org_role_as_member_fails() {
     let ctx = TestRegistryCtx::new().await;
     let (org, _admin, _admin_token) = ctx.create_org_with_admin().await;
-    let (member, member_token) = ctx.create_org_member(org.id).await;
+    let (_member, member_token) = ctx.create_org_member(org.id).await;
 
     // Create target user
     let target_user = fixtures::user()
@@ -654,3 +654,56 @@ async fn org_admin_inherits_package_admin() {
         .await
         .assert_ok();
 }
+
+// Staged Publish - POST /packages/publish/upload
+
+/// Test disk backed registries reject staged publishes, so clients fall back to direct ones
+#[actix_web::test]
+async fn publish_upload_unsupported_by_disk_storage() {
+    let ctx = TestRegistryCtx::new().await;
+    let (_, token) = ctx.create_publisher().await;
+
+    ctx.post("/packages/publish/upload")
+        .bearer(&token)
+        .json(&json!({
+            "package": "staged-package",
+            "version": "1.0.0",
+            "source_checksum": "0".repeat(64)
+        }))
+        .send()
+        .await
+        .assert_status(actix_web::http::StatusCode::NOT_IMPLEMENTED)
+        .assert_error_type(kintsu_registry_core::PublicErrorType::NotImplemented);
+}
+
+/// Test a token without publish permission cannot start a staged publish
+#[actix_web::test]
+async fn publish_upload_requires_publish_permission() {
+    let ctx = TestRegistryCtx::new().await;
+    let (_, token) = ctx.create_reader().await;
+
+    ctx.post("/packages/publish/upload")
+        .bearer(&token)
+        .json(&json!({
+            "package": "staged-package",
+            "version": "1.0.0",
+            "source_checksum": "0".repeat(64)
+        }))
+        .send()
+        .await
+        .assert_forbidden();
+}
+
+// Publish Dry Run - POST /packages/publish/dry-run
+
+/// Test a dry-run publish requires authentication, as the publish it previews does
+#[actix_web::test]
+async fn publish_dry_run_requires_authentication() {
+    let ctx = TestRegistryCtx::new().await;
+
+    ctx.post("/packages/publish/dry-run")
+        .json(&json!({}))
+        .send()
+        .await
+        .assert_unauthorized();
+}
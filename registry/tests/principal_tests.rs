@@ -11,7 +11,6 @@
 mod common;
 
 use common::TestRegistryCtx;
-use kintsu_registry_db::entities::SchemaRoleType;
 use serde_json::json;
 
 // 1. No Authentication Provided Tests
@@ -0,0 +1,199 @@
+//! End to end publish tests
+//!
+//! Publishes packages with the registry client against a served registry, resolves and
+//! downloads them back, and injects storage faults while publishing.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{TestPublishCtx, package};
+use kintsu_manifests::{
+    config::NewForNamed,
+    package::PackageManifests,
+    version::{Version, VersionSerde},
+};
+use kintsu_parser::declare::DeclarationVersion;
+use kintsu_registry_storage::tst::StorageOp;
+
+const BASE_LIB: &str = "namespace base;\nnamespace money { struct Money { cents: i64 }; };";
+const APP_LIB: &str =
+    "namespace app;\nnamespace orders { use base::money; struct Order { price: money::Money }; };";
+
+fn version(version: &str) -> VersionSerde {
+    VersionSerde(Version::parse(version).unwrap())
+}
+
+/// Publish a package, publish a dependent the registry resolves against it, then download both
+#[tokio::test(flavor = "multi_thread")]
+async fn publish_resolve_download() {
+    let ctx = TestPublishCtx::new().await;
+    let (_user, token) = ctx.registry.create_publisher().await;
+
+    let base = package("base", "0.1.0", "", BASE_LIB);
+    ctx.publish(&token, &base).await.unwrap();
+    let published = ctx
+        .publish(
+            &token,
+            &package("app", "1.0.0", "base = { version = \"^0.1\" }", APP_LIB),
+        )
+        .await
+        .unwrap();
+    assert_eq!(published.qualified_version, version("1.0.0"));
+
+    let client = ctx.client(None);
+    assert!(
+        client
+            .version_exists("app", &version("1.0.0"))
+            .await
+            .unwrap()
+    );
+
+    let fetched = client
+        .fetch_package("app", &version("1.0.0"))
+        .await
+        .unwrap();
+    assert_eq!(
+        fetched
+            .source
+            .get_file_content("schema/lib.ks".as_ref()),
+        Some(APP_LIB.as_bytes().to_vec())
+    );
+    let DeclarationVersion::V1(bundle) = fetched.declarations;
+    assert!(bundle.dependencies.contains_key("base"));
+
+    let downloaded = client
+        .download_package("base", &version("0.1.0"))
+        .await
+        .unwrap();
+    assert_eq!(
+        downloaded.get_file_content("schema/lib.ks".as_ref()),
+        base.get_file_content("schema/lib.ks".as_ref())
+    );
+}
+
+/// A dry run of a minor release which changes the type of a field warns that it breaks the
+/// previous release, although every item it declared is still declared
+#[tokio::test(flavor = "multi_thread")]
+async fn dry_run_warns_of_changed_field_types() {
+    let ctx = TestPublishCtx::new().await;
+    let (_user, token) = ctx.registry.create_publisher().await;
+
+    ctx.publish(&token, &package("base", "1.0.0", "", BASE_LIB))
+        .await
+        .unwrap();
+
+    let next = package(
+        "base",
+        "1.1.0",
+        "",
+        "namespace base;\nnamespace money { struct Money { cents: str }; };",
+    );
+    let preview = ctx
+        .client(Some(&token))
+        .publish_dry_run(
+            PackageManifests::new(&next, "").unwrap(),
+            Arc::new(next.clone()),
+            "",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        preview.warnings,
+        vec![
+            kintsu_registry_core::models::PublishWarning::BreakingChange {
+                previous_version: "1.0.0".into(),
+                changes: vec!["base::money::Money changes the type of field `cents`".into()],
+            }
+        ]
+    );
+}
+
+/// A storage error after the source is stored fails the publish without creating the version,
+/// so it can be published again
+#[tokio::test(flavor = "multi_thread")]
+async fn storage_failure_mid_publish_creates_no_version() {
+    let ctx = TestPublishCtx::new().await;
+    let (_user, token) = ctx.registry.create_publisher().await;
+    let base = package("base", "0.1.0", "", BASE_LIB);
+
+    ctx.faults.fail(StorageOp::PutDeclarations);
+    ctx.publish(&token, &base).await.unwrap_err();
+    assert_eq!(ctx.faults.calls(StorageOp::PutSource), 1);
+    assert!(
+        !ctx.client(None)
+            .version_exists("base", &version("0.1.0"))
+            .await
+            .unwrap()
+    );
+
+    ctx.faults.heal();
+    ctx.publish(&token, &base).await.unwrap();
+    assert!(
+        ctx.client(None)
+            .version_exists("base", &version("0.1.0"))
+            .await
+            .unwrap()
+    );
+}
+
+/// A storage error on the README, stored after the package, fails the publish the same way
+#[tokio::test(flavor = "multi_thread")]
+async fn readme_storage_failure_creates_no_version() {
+    let ctx = TestPublishCtx::new().await;
+    let (_user, token) = ctx.registry.create_publisher().await;
+    let base = package("base", "0.1.0", "", BASE_LIB);
+
+    ctx.faults.fail(StorageOp::PutReadme);
+    ctx.publish(&token, &base).await.unwrap_err();
+    assert!(
+        !ctx.client(None)
+            .version_exists("base", &version("0.1.0"))
+            .await
+            .unwrap()
+    );
+
+    ctx.faults.heal();
+    ctx.publish(&token, &base).await.unwrap();
+}
+
+/// Dependents cannot be published while their dependencies cannot be read from storage
+#[tokio::test(flavor = "multi_thread")]
+async fn dependency_storage_failure_fails_dependent_publish() {
+    let ctx = TestPublishCtx::new().await;
+    let (_user, token) = ctx.registry.create_publisher().await;
+    ctx.publish(&token, &package("base", "0.1.0", "", BASE_LIB))
+        .await
+        .unwrap();
+    let app = package("app", "1.0.0", "base = { version = \"^0.1\" }", APP_LIB);
+
+    ctx.faults.fail(StorageOp::GetSource);
+    ctx.publish(&token, &app).await.unwrap_err();
+
+    ctx.faults.heal();
+    ctx.publish(&token, &app).await.unwrap();
+}
+
+/// Downloads fail while storage does, and succeed once it recovers
+#[tokio::test(flavor = "multi_thread")]
+async fn storage_failure_fails_download() {
+    let ctx = TestPublishCtx::new().await;
+    let (_user, token) = ctx.registry.create_publisher().await;
+    ctx.publish(&token, &package("base", "0.1.0", "", BASE_LIB))
+        .await
+        .unwrap();
+    let client = ctx.client(None);
+
+    ctx.faults.fail(StorageOp::GetSource);
+    client
+        .download_package("base", &version("0.1.0"))
+        .await
+        .unwrap_err();
+
+    ctx.faults.heal();
+    client
+        .download_package("base", &version("0.1.0"))
+        .await
+        .unwrap();
+}
@@ -0,0 +1,110 @@
+//! Derive inputs which must be rejected at compile time.
+//!
+//! Skipped fields cannot be renamed:
+//!
+//! ```compile_fail
+//! use kintsu_sdk::{Struct, namespace};
+//!
+//! #[derive(Struct)]
+//! #[fields(version = 1)]
+//! pub struct Bad {
+//!     #[fields(skip, rename = "other")]
+//!     a: i32,
+//! }
+//!
+//! namespace! { "abc.corp.fail" { Bad } }
+//! ```
+//!
+//! Skipped fields cannot be flattened:
+//!
+//! ```compile_fail
+//! use kintsu_sdk::{Struct, namespace};
+//!
+//! #[derive(Struct)]
+//! #[fields(version = 1)]
+//! pub struct Inner {
+//!     a: i32,
+//! }
+//!
+//! #[derive(Struct)]
+//! #[fields(version = 1)]
+//! pub struct Bad {
+//!     #[fields(skip, flatten)]
+//!     inner: Inner,
+//! }
+//!
+//! namespace! { "abc.corp.fail" { Inner, Bad } }
+//! ```
+//!
+//! Flattened fields cannot be renamed:
+//!
+//! ```compile_fail
+//! use kintsu_sdk::{Struct, namespace};
+//!
+//! #[derive(Struct)]
+//! #[fields(version = 1)]
+//! pub struct Inner {
+//!     a: i32,
+//! }
+//!
+//! #[derive(Struct)]
+//! #[fields(version = 1)]
+//! pub struct Bad {
+//!     #[fields(flatten, rename = "other")]
+//!     inner: Inner,
+//! }
+//!
+//! namespace! { "abc.corp.fail" { Inner, Bad } }
+//! ```
+//!
+//! Only struct fields can be flattened:
+//!
+//! ```compile_fail
+//! use kintsu_sdk::{Enum, Struct, namespace};
+//!
+//! #[derive(Enum)]
+//! #[fields(version = 1)]
+//! pub enum Kind {
+//!     A = 1,
+//! }
+//!
+//! #[derive(Struct)]
+//! #[fields(version = 1)]
+//! pub struct Bad {
+//!     #[fields(flatten, enm)]
+//!     kind: Kind,
+//! }
+//!
+//! namespace! { "abc.corp.fail" { Kind, Bad } }
+//! ```
+//!
+//! Renamed fields cannot collide with another field:
+//!
+//! ```compile_fail
+//! use kintsu_sdk::{Struct, namespace};
+//!
+//! #[derive(Struct)]
+//! #[fields(version = 1)]
+//! pub struct Bad {
+//!     a: i32,
+//!     #[fields(rename = "a")]
+//!     b: i32,
+//! }
+//!
+//! namespace! { "abc.corp.fail" { Bad } }
+//! ```
+//!
+//! Renamed fields need a name:
+//!
+//! ```compile_fail
+//! use kintsu_sdk::{Struct, namespace};
+//!
+//! #[derive(Struct)]
+//! #[fields(version = 1)]
+//! pub struct Bad {
+//!     #[fields(rename = "")]
+//!     a: i32,
+//! }
+//!
+//! namespace! { "abc.corp.fail" { Bad } }
+//! ```
@@ -1,7 +1,9 @@
 pub use kintsu_core::{
     CompoundType, DeclDefined, Defined, Definitions, Enum, ErrorTy as Error, Field, FieldsList,
     Meta, Named, OneOf, OneOfVariant, Operation, StrOrInt, Struct, Type, Typed, VariantKind,
-    Version, map, namespace, namespace::OfNamespace,
+    Version, instance_definition, instance_name, map, namespace,
+    namespace::OfNamespace,
+    protocol::{NoError, OperationId, Transport},
 };
 
 pub mod declare {
@@ -9,5 +11,8 @@ pub mod declare {
     pub use kintsu_core::declare::*;
 }
 
+#[cfg(doctest)]
+mod compile_fail;
+
 pub use kintsu_derives::{Enum, Error, OneOf, Struct, module, operation};
 pub use serde_repr::{Deserialize_repr as IntDeserialize, Serialize_repr as IntSerialize};
@@ -0,0 +1,129 @@
+use std::sync::Mutex;
+
+use kintsu_sdk::{OperationId, Transport, module};
+
+#[module("samples/config-client")]
+mod client {}
+
+use client::{abc_corp_namespace as ns, abc_corp_test as test};
+
+enum Reply {
+    Ok(serde_json::Value),
+    Err(serde_json::Value),
+    Down,
+}
+
+/// Answers every call with the same reply, recording what was sent.
+struct Canned {
+    reply: Reply,
+    calls: Mutex<Vec<(OperationId, serde_json::Value)>>,
+}
+
+impl Canned {
+    fn new(reply: Reply) -> Self {
+        Self {
+            reply,
+            calls: Default::default(),
+        }
+    }
+}
+
+impl Transport for Canned {
+    type Error = String;
+
+    fn call<Req, Res, Err>(
+        &self,
+        operation: OperationId,
+        request: &Req,
+    ) -> impl Future<Output = Result<Result<Res, Err>, Self::Error>> + Send
+    where
+        Req: serde::Serialize + Sync,
+        Res: serde::de::DeserializeOwned + Send,
+        Err: serde::de::DeserializeOwned + Send, {
+        let request = serde_json::to_value(request).unwrap();
+        self.calls
+            .lock()
+            .unwrap()
+            .push((operation, request));
+
+        let reply = match &self.reply {
+            Reply::Ok(value) => {
+                serde_json::from_value(value.clone())
+                    .map(Ok)
+                    .map_err(|e| e.to_string())
+            },
+            Reply::Err(value) => {
+                serde_json::from_value(value.clone())
+                    .map(Err)
+                    .map_err(|e| e.to_string())
+            },
+            Reply::Down => Err("connection refused".into()),
+        };
+        std::future::ready(reply)
+    }
+}
+
+#[tokio::test]
+async fn infallible_operation_round_trip() {
+    let client = ns::Client::new(Canned::new(Reply::Ok(serde_json::json!({ "value": 6 }))));
+
+    let Ok(response) = client
+        .add(ns::AddRequest {
+            values: vec![1, 2, 3],
+        })
+        .await
+    else {
+        panic!("expected a response");
+    };
+    assert_eq!(response.value, 6);
+
+    let calls = client.transport().calls.lock().unwrap();
+    assert_eq!(
+        calls[0],
+        (
+            OperationId {
+                namespace: "abc.corp.namespace",
+                name: "add",
+                version: 1,
+            },
+            serde_json::json!({ "values": [1, 2, 3] })
+        )
+    );
+}
+
+#[tokio::test]
+async fn declared_error_is_surfaced() {
+    let client = test::Client::new(Canned::new(Reply::Err(serde_json::json!({
+        "type": "unknown",
+        "desc": "no such code",
+    }))));
+
+    let result = client
+        .lookup_code(test::LookupCodeRequest {
+            code: test::ErrorCode::Baz,
+        })
+        .await;
+    let Err(test::LookupCodeError::Operation(test::OperationError::Unknown(err))) = result else {
+        panic!("expected the declared error");
+    };
+    assert_eq!(err.desc, "no such code");
+
+    let calls = client.transport().calls.lock().unwrap();
+    assert_eq!(calls[0].0.name, "lookup_code");
+    assert_eq!(calls[0].1, serde_json::json!({ "code": 2 }));
+}
+
+#[tokio::test]
+async fn transport_failure_is_surfaced() {
+    let client = test::Client::new(Canned::new(Reply::Down));
+
+    let result = client
+        .lookup_code(test::LookupCodeRequest {
+            code: test::ErrorCode::Foo,
+        })
+        .await;
+    let Err(test::LookupCodeError::Transport(err)) = result else {
+        panic!("expected a transport error");
+    };
+    assert_eq!(err, "connection refused");
+}
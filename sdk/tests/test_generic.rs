@@ -0,0 +1,101 @@
+#![allow(unused)]
+
+use kintsu_core::{Reffable, namespace};
+use kintsu_derives::{OneOf, Struct};
+use kintsu_sdk::{CompoundType, Defined, Definitions, Type, Typed};
+
+#[derive(Struct)]
+#[fields(version = 1)]
+pub struct Item {
+    id: i64,
+}
+
+#[derive(Struct)]
+#[fields(version = 1)]
+#[fields(describe(text = "A page of results"))]
+pub struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(Struct)]
+#[fields(version = 1)]
+pub struct Pair<A: Typed, B: Typed> {
+    first: A,
+    second: B,
+}
+
+#[derive(OneOf)]
+#[fields(version = 1)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+namespace! {
+    "abc.corp.generic" {
+        Item, Page<T>, Pair<A: Typed, B: Typed>, Either<L, R>
+    }
+}
+
+fn struct_ref(name: &str) -> Type {
+    Type::CompoundType(CompoundType::Struct { to: name.into() })
+}
+
+#[test]
+fn generic_struct_is_named_per_instantiation() {
+    assert_eq!(Page::<Item>::ty(), struct_ref("PageItem"));
+    assert_eq!(Page::<i32>::ty(), struct_ref("PageI32"));
+    assert_eq!(Page::<Vec<Item>>::ty(), struct_ref("PageItemList"));
+    assert_eq!(
+        Pair::<u8, Option<Item>>::ty(),
+        struct_ref("PairU8OptionalItem")
+    );
+}
+
+#[test]
+fn generic_struct_fields_use_type_arguments() {
+    let Definitions::StructV1(page) = Page::<Item>::definition() else {
+        panic!("expected a struct definition");
+    };
+    assert_eq!(page.meta.name.to_string(), "PageItem");
+    assert_eq!(page.meta.namespace.to_string(), "abc.corp.generic");
+    assert_eq!(page.meta.description.as_deref(), Some("A page of results"));
+
+    let Some(Reffable::Value(items)) = page.fields.get(&"items".into()) else {
+        panic!("expected an items field");
+    };
+    assert_eq!(items.ty, Vec::<Item>::ty());
+}
+
+#[test]
+fn generic_definitions_are_cached_per_instantiation() {
+    assert!(std::ptr::eq(
+        Page::<Item>::definition(),
+        Page::<Item>::definition()
+    ));
+    assert!(!std::ptr::eq(
+        Page::<Item>::definition(),
+        Page::<i32>::definition()
+    ));
+}
+
+#[test]
+fn generic_one_of_variants_use_type_arguments() {
+    assert_eq!(
+        Either::<Item, String>::ty(),
+        Type::CompoundType(CompoundType::OneOf {
+            to: "EitherItemString".into()
+        })
+    );
+
+    let Definitions::OneOfV1(either) = Either::<Item, String>::definition() else {
+        panic!("expected a one_of definition");
+    };
+    let variants: Vec<_> = either
+        .variants
+        .values()
+        .map(|v| v.ty.clone())
+        .collect();
+    assert_eq!(variants, vec![Item::ty(), String::ty()]);
+}
@@ -0,0 +1,102 @@
+#![allow(unused)]
+
+use kintsu_core::{Reffable, namespace};
+use kintsu_derives::Struct;
+use kintsu_sdk::{Defined, Definitions, Typed};
+
+#[derive(Struct)]
+#[fields(version = 1)]
+pub struct Audit {
+    created_by: String,
+    revision: i64,
+}
+
+/// Only known at runtime, never part of the schema.
+pub struct Handle;
+
+#[derive(Struct)]
+#[fields(version = 1)]
+pub struct Account {
+    #[fields(rename = "accountId")]
+    id: i64,
+    #[fields(rename = "displayName", describe(text = "shown to other users"))]
+    name: String,
+    #[fields(skip)]
+    handle: Handle,
+    #[fields(flatten)]
+    audit: Audit,
+}
+
+#[derive(Struct)]
+#[fields(version = 1)]
+pub struct Clash {
+    revision: i64,
+    #[fields(flatten)]
+    audit: Audit,
+}
+
+namespace! {
+    "abc.corp.fields" {
+        Audit, Account, Clash
+    }
+}
+
+fn struct_fields(def: &Definitions) -> Vec<String> {
+    let Definitions::StructV1(def) = def else {
+        panic!("expected a struct definition");
+    };
+    def.fields
+        .keys()
+        .map(|k| k.to_string())
+        .collect()
+}
+
+#[test]
+fn renamed_skipped_and_flattened_fields() {
+    assert_eq!(
+        struct_fields(Account::definition()),
+        vec!["accountId", "created_by", "displayName", "revision"]
+    );
+}
+
+#[test]
+fn renamed_field_keeps_meta() {
+    let Definitions::StructV1(def) = Account::definition() else {
+        panic!("expected a struct definition");
+    };
+    let Some(Reffable::Value(name)) = def.fields.get(&"displayName".into()) else {
+        panic!("expected a displayName field");
+    };
+    assert_eq!(name.meta.name.as_ref().unwrap().to_string(), "displayName");
+    assert_eq!(
+        name.meta.description.as_deref(),
+        Some("shown to other users")
+    );
+    assert_eq!(name.ty, String::ty());
+}
+
+#[test]
+fn flattened_fields_keep_their_namespace() {
+    let Definitions::StructV1(def) = Account::definition() else {
+        panic!("expected a struct definition");
+    };
+    let Some(Reffable::Value(revision)) = def.fields.get(&"revision".into()) else {
+        panic!("expected a revision field");
+    };
+    assert_eq!(revision.ty, i64::ty());
+    assert_eq!(
+        revision
+            .meta
+            .namespace
+            .as_ref()
+            .unwrap()
+            .to_string(),
+        "abc.corp.fields"
+    );
+}
+
+#[test]
+#[should_panic(expected = "flattened field `audit` redefines field `revision`")]
+fn flattened_field_collision_panics() {
+    Clash::definition();
+}
@@ -9,6 +9,7 @@ struct Opts {
     tags: syn::Expr,
     root: syn::LitStr,
     memory: syn::Expr,
+    expect_codes: Option<syn::ExprArray>,
     assertions: syn::ExprClosure,
 }
 
@@ -22,6 +23,7 @@ impl Parse for Opts {
         let mut tags = None;
         let mut root = None;
         let mut memory = None;
+        let mut expect_codes = None;
         let mut assertions = None;
 
         loop {
@@ -55,6 +57,9 @@ impl Parse for Opts {
                     let expr: syn::Expr = input.parse()?;
                     memory = Some(expr);
                 },
+                "expect_codes" => {
+                    expect_codes = Some(input.parse::<syn::ExprArray>()?);
+                },
                 "assertions" => {
                     let closure: syn::ExprClosure = input.parse()?;
                     assertions = Some(closure);
@@ -87,6 +92,7 @@ impl Parse for Opts {
             root: root.ok_or_else(|| syn::Error::new(input.span(), "Missing 'root' parameter"))?,
             memory: memory
                 .ok_or_else(|| syn::Error::new(input.span(), "Missing 'memory' parameter"))?,
+            expect_codes,
             assertions: assertions
                 .ok_or_else(|| syn::Error::new(input.span(), "Missing 'assertions' parameter"))?,
         })
@@ -113,10 +119,15 @@ pub fn compiler_test(stream: proc_macro::TokenStream) -> proc_macro::TokenStream
         quote::quote! {}
     };
 
-    let handle = if expect_pass {
-        quote::quote! { compile_pass }
-    } else {
-        quote::quote! { compile_fail }
+    let handle = match (expect_pass, &opts.expect_codes) {
+        (true, Some(codes)) => {
+            return syn::Error::new_spanned(codes, "'expect_codes' requires 'expect_pass: false'")
+                .to_compile_error()
+                .into();
+        },
+        (true, None) => quote::quote! { compile_pass() },
+        (false, Some(codes)) => quote::quote! { compile_fail_with(&#codes) },
+        (false, None) => quote::quote! { compile_fail() },
     };
 
     quote::quote!(
@@ -137,7 +148,7 @@ pub fn compiler_test(stream: proc_macro::TokenStream) -> proc_macro::TokenStream
                 #tags,
             ).with_root(#root);
 
-            let ctx = harness.#handle().await;
+            let ctx = harness.#handle.await;
 
             let mut assertions: Box<dyn Fn(TestHarness, _)> = Box::new(#assertions);
             (assertions)(harness, ctx);
@@ -3,7 +3,9 @@ use std::sync::Arc;
 use divan::black_box;
 use kintsu_fs::memory;
 use kintsu_parser::ctx::CompileCtx;
-use kintsu_test_suite::many::{GraphGenerator, GraphPattern, populate_fs};
+use kintsu_test_suite::many::{
+    GraphGenerator, GraphPattern, SchemaOptions, populate_fs, populate_random_schema,
+};
 
 fn compile_bench(
     b: divan::Bencher,
@@ -176,6 +178,40 @@ gen_from_expr! {
     },
 }
 
+fn random_schema(options: SchemaOptions) -> Arc<kintsu_fs::memory::MemoryFileSystem> {
+    let mut fs = kintsu_fs::memory::MemoryFileSystem::new();
+    populate_random_schema(&mut fs, options);
+    Arc::new(fs)
+}
+
+#[divan::bench(name = "compile random schema of 4 namespaces with 25 types")]
+fn bench_compile_random_small(b: divan::Bencher) {
+    compile_bench(
+        b,
+        random_schema(SchemaOptions {
+            namespaces: 4,
+            types: 25,
+            ..Default::default()
+        }),
+        "pkg",
+    );
+}
+
+#[divan::bench(name = "compile random schema of 16 namespaces with 50 types")]
+fn bench_compile_random_large(b: divan::Bencher) {
+    compile_bench(
+        b,
+        random_schema(SchemaOptions {
+            namespaces: 16,
+            types: 50,
+            union_density: 0.25,
+            type_expr_density: 0.25,
+            ..Default::default()
+        }),
+        "pkg",
+    );
+}
+
 fn main() {
     divan::main();
 }
@@ -392,6 +392,8 @@ fn run_cli(args: &[&str]) -> Output {
         .env("NO_COLOR", "1")
         .env("TERM", "dumb")
         .env("LOG_LEVEL", "off")
+        // progress is logged in CI
+        .env_remove("CI")
         .output()
         .expect("failed to execute kintsu command")
 }
@@ -476,6 +478,94 @@ pub fn run_check_command(dir: &Path) -> CheckOutput {
     }
 }
 
+/// Run `kintsu check --fix` in a directory.
+pub fn run_fix_command(dir: &Path) -> CheckOutput {
+    let output = run_cli(&["check", "--fix", "-d", &dir.to_string_lossy()]);
+
+    CheckOutput {
+        exit_code: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: strip_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+    }
+}
+
+/// Run `kintsu check --deny-warnings` in a directory.
+pub fn run_deny_warnings_command(dir: &Path) -> CheckOutput {
+    let output = run_cli(&["check", "--deny-warnings", "-d", &dir.to_string_lossy()]);
+
+    CheckOutput {
+        exit_code: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: strip_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+    }
+}
+
+/// Run `kintsu init` with arguments.
+pub fn run_init_command(args: &[&str]) -> CheckOutput {
+    let output = run_cli(&[&["init"], args].concat());
+
+    CheckOutput {
+        exit_code: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: strip_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+    }
+}
+
+/// Run `kintsu coverage --format json` in a directory.
+pub fn run_coverage_command(dir: &Path) -> CheckOutput {
+    let output = run_cli(&["coverage", "--format", "json", "-d", &dir.to_string_lossy()]);
+
+    CheckOutput {
+        exit_code: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: strip_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+    }
+}
+
+/// Run `kintsu sql` in a directory, writing DDL to `output`.
+pub fn run_sql_command(
+    dir: &Path,
+    output: &Path,
+    dialect: &str,
+) -> CheckOutput {
+    let output = run_cli(&[
+        "sql",
+        "-d",
+        &dir.to_string_lossy(),
+        "-o",
+        &output.to_string_lossy(),
+        "--dialect",
+        dialect,
+    ]);
+
+    CheckOutput {
+        exit_code: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: strip_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+    }
+}
+
+/// Run `kintsu generate graphql` in a directory, writing SDL to `output`.
+pub fn run_graphql_command(
+    dir: &Path,
+    output: &Path,
+) -> CheckOutput {
+    let output = run_cli(&[
+        "generate",
+        "graphql",
+        "-d",
+        &dir.to_string_lossy(),
+        "-o",
+        &output.to_string_lossy(),
+    ]);
+
+    CheckOutput {
+        exit_code: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: strip_ansi_codes(&String::from_utf8_lossy(&output.stderr)),
+    }
+}
+
 /// Minimal manifest helper.
 pub fn minimal_manifest(name: &str) -> String {
     format!(
@@ -523,7 +613,7 @@ pub fn flush_results_to_jsonl() {
         .open(&jsonl_path)
     {
         for result in results.iter() {
-            if let Ok(json) = serde_json::to_string(result) {
+            if let Ok(json) = serde_json::to_string(&TestReport::cli_test(result)) {
                 let _ = writeln!(file, "{}", json);
             }
         }
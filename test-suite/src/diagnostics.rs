@@ -0,0 +1,139 @@
+//! Assertions on the errors of a failed compile by error code and location, so failure tests
+//! check what was reported rather than only that compilation failed.
+
+use std::{ops::Range, path::PathBuf};
+
+use kintsu_errors::{CompilerError, Span};
+
+/// One error of a failed compile, reduced to what tests assert on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportedError {
+    /// Error code, e.g. `KTR1001`
+    pub code: String,
+    pub message: String,
+    /// File the error was reported in, when known
+    pub path: Option<PathBuf>,
+    pub span: Option<Span>,
+    /// Source text under `span`
+    pub text: Option<String>,
+}
+
+impl ReportedError {
+    fn new(err: &CompilerError) -> Self {
+        let source = err.extract_source();
+        let span = err.extract_deepest_span();
+        Self {
+            code: err.error_code().to_string(),
+            message: err.message(),
+            path: source.map(|(path, _)| path.to_path_buf()),
+            span,
+            text: source
+                .zip(span)
+                .and_then(|((_, source), span)| source.get(span.start..span.end))
+                .map(str::to_string),
+        }
+    }
+}
+
+impl std::fmt::Display for ReportedError {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)?;
+        if let Some(path) = &self.path {
+            write!(f, " in {}", path.display())?;
+        }
+        if let Some(span) = self.span {
+            write!(f, " at {}..{}", span.start, span.end)?;
+        }
+        if let Some(text) = &self.text {
+            write!(f, " ({text:?})")?;
+        }
+        Ok(())
+    }
+}
+
+/// The errors in `err`, root causes first.
+pub fn reported_errors(err: &kintsu_parser::Error) -> Vec<ReportedError> {
+    err.to_compiler_error()
+        .flatten()
+        .into_iter()
+        .map(ReportedError::new)
+        .collect()
+}
+
+fn listed(errors: &[ReportedError]) -> String {
+    errors
+        .iter()
+        .map(|err| format!("  {err}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Asserts `err` holds exactly the errors `expect_codes`, in the order they are reported.
+pub fn assert_error_codes(
+    err: &kintsu_parser::Error,
+    expect_codes: &[&str],
+) {
+    let errors = reported_errors(err);
+    let codes = errors
+        .iter()
+        .map(|err| err.code.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        expect_codes,
+        "unexpected error codes, reported:\n{}",
+        listed(&errors)
+    );
+}
+
+fn find<'e>(
+    errors: &'e [ReportedError],
+    code: &str,
+) -> &'e ReportedError {
+    errors
+        .iter()
+        .find(|err| err.code == code)
+        .unwrap_or_else(|| panic!("no {code} error was reported:\n{}", listed(errors)))
+}
+
+/// Asserts the first `code` error in `err` points at exactly `text` in the file ending in
+/// `path`, e.g. `assert_error_at(&err, "KTR1001", "lib.ks", "Missing")`.
+pub fn assert_error_at(
+    err: &kintsu_parser::Error,
+    code: &str,
+    path: &str,
+    text: &str,
+) {
+    let errors = reported_errors(err);
+    let found = find(&errors, code);
+    assert!(
+        found
+            .path
+            .as_ref()
+            .is_some_and(|found| found.ends_with(path)),
+        "{code} was not reported in {path}: {found}"
+    );
+    assert_eq!(
+        found.text.as_deref(),
+        Some(text),
+        "{code} points elsewhere: {found}"
+    );
+}
+
+/// Asserts the first `code` error in `err` spans exactly the bytes `span`.
+pub fn assert_error_span(
+    err: &kintsu_parser::Error,
+    code: &str,
+    span: Range<usize>,
+) {
+    let errors = reported_errors(err);
+    let found = find(&errors, code);
+    assert_eq!(
+        found.span,
+        Some(Span::new(span.start, span.end)),
+        "{code} points elsewhere: {found}"
+    );
+}
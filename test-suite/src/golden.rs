@@ -0,0 +1,150 @@
+//! Golden files: values checked in as pretty JSON under `tests/golden`, which tests compare
+//! their output against.
+//!
+//! Mismatches are reported path by path rather than as a text diff, since a single moved
+//! type shifts every line after it. Set `KINTSU_UPDATE_GOLDEN=1` to write the current
+//! output instead, e.g. after an intended change to the declarations.
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+/// Env var which, when set, makes [`assert_golden`] write golden files instead of comparing.
+pub const UPDATE_GOLDEN: &str = "KINTSU_UPDATE_GOLDEN";
+
+/// The longest rendering of a value in a difference before it is cut short.
+const MAX_VALUE_LEN: usize = 120;
+
+pub fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{name}.json"))
+}
+
+/// Compares `actual` with the golden file `name`, panicking with the differences if they do
+/// not match. A missing golden file is written and fails the test, so new goldens are
+/// reviewed before they are checked in.
+pub fn assert_golden(
+    name: &str,
+    actual: &Value,
+) {
+    let path = golden_path(name);
+    let rendered = format!(
+        "{}\n",
+        serde_json::to_string_pretty(actual).expect("serialize golden")
+    );
+
+    let update = std::env::var_os(UPDATE_GOLDEN).is_some();
+    if update || !path.exists() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, rendered).unwrap();
+        assert!(
+            update,
+            "wrote new golden file {}, review and commit it",
+            path.display()
+        );
+        return;
+    }
+
+    let expected: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap())
+        .unwrap_or_else(|err| panic!("golden file {} is not json: {err}", path.display()));
+
+    let differences = diff(&expected, actual);
+    assert!(
+        differences.is_empty(),
+        "output differs from golden file {} (rerun with {UPDATE_GOLDEN}=1 to accept it):\n{}",
+        path.display(),
+        differences.join("\n")
+    );
+}
+
+/// The differences from `expected` to `actual`, one line per changed path: `-` for values
+/// only in `expected`, `+` for values only in `actual` and `~` for changed values.
+pub fn diff(
+    expected: &Value,
+    actual: &Value,
+) -> Vec<String> {
+    let mut differences = vec![];
+    diff_at("$", expected, actual, &mut differences);
+    differences
+}
+
+fn diff_at(
+    path: &str,
+    expected: &Value,
+    actual: &Value,
+    differences: &mut Vec<String>,
+) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                let path = format!("{path}.{key}");
+                match actual.get(key) {
+                    Some(other) => diff_at(&path, value, other, differences),
+                    None => differences.push(format!("- {path}: {}", render(value))),
+                }
+            }
+            for (key, value) in actual {
+                if !expected.contains_key(key) {
+                    differences.push(format!("+ {path}.{key}: {}", render(value)));
+                }
+            }
+        },
+        (Value::Array(expected), Value::Array(actual)) => {
+            for (index, value) in expected.iter().enumerate() {
+                let path = format!("{path}[{index}]");
+                match actual.get(index) {
+                    Some(other) => diff_at(&path, value, other, differences),
+                    None => differences.push(format!("- {path}: {}", render(value))),
+                }
+            }
+            for (index, value) in actual
+                .iter()
+                .enumerate()
+                .skip(expected.len())
+            {
+                differences.push(format!("+ {path}[{index}]: {}", render(value)));
+            }
+        },
+        (expected, actual) if expected != actual => {
+            differences.push(format!(
+                "~ {path}: {} -> {}",
+                render(expected),
+                render(actual)
+            ));
+        },
+        _ => {},
+    }
+}
+
+fn render(value: &Value) -> String {
+    let rendered = value.to_string();
+    match rendered.char_indices().nth(MAX_VALUE_LEN) {
+        Some((end, _)) => format!("{}...", &rendered[..end]),
+        None => rendered,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_changes_by_path() {
+        let expected = json!({"a": 1, "b": [1, 2], "c": {"d": "x"}, "gone": true});
+        let actual = json!({"a": 2, "b": [1], "c": {"d": "x", "e": null}});
+
+        assert_eq!(
+            diff(&expected, &actual),
+            [
+                "~ $.a: 1 -> 2",
+                "- $.b[1]: 2",
+                "+ $.c.e: null",
+                "- $.gone: true",
+            ]
+        );
+        assert!(diff(&expected, &expected).is_empty());
+    }
+}
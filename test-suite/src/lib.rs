@@ -1,3 +1,5 @@
+#![allow(clippy::result_large_err)]
+
 use kintsu_fs::{FileSystem, memory::MemoryFileSystem};
 use kintsu_manifests::{config::NewForNamed, lock::Lockfiles};
 pub use kintsu_parser::ctx::CompileCtx;
@@ -7,7 +9,11 @@ use std::{
 };
 
 pub mod cli_tests;
+pub mod diagnostics;
+pub mod golden;
 pub mod many;
+pub mod registry;
+pub mod report;
 
 pub use cli_tests::*;
 
@@ -151,6 +157,14 @@ pub struct TestHarness {
     pub metadata: TestMetadata,
 
     pub result: Option<TestResult>,
+
+    /// Declarations emitted by [`TestHarness::compile_pass`]
+    pub declarations: Option<kintsu_parser::declare::DeclarationVersion>,
+
+    /// Registry serving the registry dependencies of compiles, see
+    /// [`TestHarness::with_registry`]
+    #[serde(skip)]
+    pub registry: Option<registry::MemoryRegistry>,
 }
 
 impl TestHarness {
@@ -175,9 +189,20 @@ impl TestHarness {
                 tags,
             },
             result: None,
+            declarations: None,
+            registry: None,
         }
     }
 
+    /// Compiles against `registry` instead of failing on registry dependencies.
+    pub fn with_registry(
+        mut self,
+        registry: registry::MemoryRegistry,
+    ) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
     pub fn with_root(
         mut self,
         root: impl Into<String>,
@@ -228,6 +253,17 @@ impl TestHarness {
         });
     }
 
+    async fn compile(&self) -> Result<CompileCtx, Error> {
+        let fs: Arc<dyn FileSystem> = Arc::new(self.fs.clone());
+        match &self.registry {
+            Some(registry) => {
+                let resolver = registry.resolver(fs.clone());
+                CompileCtx::with_fs_and_config(fs, resolver, &self.root, 2, false).await
+            },
+            None => CompileCtx::with_fs(fs, &self.root).await,
+        }
+    }
+
     fn id(&self) -> &str {
         &self.metadata.id
     }
@@ -256,25 +292,20 @@ impl TestHarness {
             .danger_write_to_physical(format!("./tmp/{}", self.id()))
             .unwrap();
 
-        let result = compile_pass(Arc::new(self.fs.clone()), &self.root).await;
+        let result = match self.compile().await {
+            Ok(ctx) => ctx.finalize().await.map(|_| ctx),
+            Err(e) => Err(e),
+        };
 
         self.handle_meta(&result);
 
         match result {
             Ok(ctx) => {
-                let decl = ctx
-                    .emit_declarations()
-                    .await
-                    .expect("emit declarations");
-
-                self.add_file(
-                    "declarations.json",
-                    serde_json::to_string(&decl).expect("serialize declarations"),
+                self.declarations = Some(
+                    ctx.emit_declarations()
+                        .await
+                        .expect("emit declarations"),
                 );
-                self.fs
-                    .danger_write_to_physical(format!("./tmp/{}", self.id()))
-                    .unwrap();
-
                 ctx
             },
             Err(e) => {
@@ -299,7 +330,7 @@ impl TestHarness {
             .danger_write_to_physical(format!("./tmp/{}", self.id()))
             .unwrap();
 
-        let result = CompileCtx::with_fs(Arc::new(self.fs.clone()), self.root.clone()).await;
+        let result = self.compile().await;
 
         self.handle_meta(&result);
 
@@ -312,6 +343,38 @@ impl TestHarness {
         }
     }
 
+    /// Declarations emitted by a passing compile.
+    pub fn declarations(&self) -> &kintsu_parser::declare::DeclarationVersion {
+        self.declarations
+            .as_ref()
+            .expect("declarations are emitted by compile_pass")
+    }
+
+    /// [`TestHarness::declarations`] as compact JSON.
+    pub fn declarations_json(&self) -> String {
+        serde_json::to_string(self.declarations()).expect("serialize declarations")
+    }
+
+    /// Compares the emitted declarations with the golden file named after the test id, see
+    /// [`golden`].
+    pub fn assert_declarations_snapshot(&self) {
+        golden::assert_golden(
+            self.id(),
+            &serde_json::to_value(self.declarations()).expect("serialize declarations"),
+        );
+    }
+
+    /// [`TestHarness::compile_fail`], asserting the compile fails with exactly the errors
+    /// `expect_codes`, root causes first. Use [`diagnostics`] to assert on their locations.
+    pub async fn compile_fail_with(
+        &mut self,
+        expect_codes: &[&str],
+    ) -> kintsu_parser::Error {
+        let err = self.compile_fail().await;
+        diagnostics::assert_error_codes(&err, expect_codes);
+        err
+    }
+
     pub fn result(&self) -> Option<&TestResult> {
         self.result.as_ref()
     }
@@ -480,7 +543,13 @@ impl TestCollector {
         &self,
         path: &Path,
     ) {
-        serde_jsonlines::append_json_lines(path, &self.tests).unwrap();
+        serde_jsonlines::append_json_lines(
+            path,
+            self.tests
+                .iter()
+                .map(TestReport::compile_test),
+        )
+        .unwrap();
     }
 }
 
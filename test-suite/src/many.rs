@@ -629,6 +629,346 @@ pub fn populate_root_only(
     populate_fs(fs, std::slice::from_ref(root));
 }
 
+/// Shape of a schema produced by [`populate_random_schema`].
+#[derive(Debug, Clone)]
+pub struct SchemaOptions {
+    /// Name of the generated package
+    pub package: String,
+    pub namespaces: usize,
+    /// Types declared in each namespace
+    pub types: usize,
+    /// Chance in `0..=1` of a type being a union-or of two structs, e.g. `A &| B`
+    pub union_density: f64,
+    /// Chance in `0..=1` of a type being a type expression, e.g. `Pick[A, f0 | f1]`
+    pub type_expr_density: f64,
+    /// Seed of the generator, so a failing schema can be generated again
+    pub seed: u64,
+}
+
+impl Default for SchemaOptions {
+    fn default() -> Self {
+        Self {
+            package: "pkg".to_string(),
+            namespaces: 4,
+            types: 8,
+            union_density: 0.15,
+            type_expr_density: 0.15,
+            seed: 0,
+        }
+    }
+}
+
+const BUILTINS: &[&str] = &["i32", "i64", "u64", "f64", "bool", "str", "datetime"];
+
+/// A type declared by the [`SchemaGenerator`], which later types may reference.
+struct GeneratedType {
+    namespace: usize,
+    name: String,
+    /// Fields of structs, as their name and how they are declared after it, e.g. `?: i32`
+    fields: Option<Vec<(String, String)>>,
+}
+
+/// Generates a single package of random, valid types. Types only reference types declared
+/// before them, in their own namespace or an earlier one, so the schema is free of cycles.
+/// Unions and type expressions only take structs of their own namespace, as operands
+/// imported from another namespace do not resolve yet.
+pub struct SchemaGenerator {
+    rng: rand::rngs::StdRng,
+    options: SchemaOptions,
+    types: Vec<GeneratedType>,
+}
+
+impl SchemaGenerator {
+    pub fn new(options: SchemaOptions) -> Self {
+        use rand::SeedableRng;
+
+        Self {
+            rng: rand::rngs::StdRng::seed_from_u64(options.seed),
+            options,
+            types: vec![],
+        }
+    }
+
+    /// The files of the package, by path relative to the filesystem root.
+    pub fn generate(&mut self) -> Vec<(String, String)> {
+        let package = self.options.package.clone();
+        let pkg_name = package.replace('-', "_");
+
+        let mut files = vec![(
+            format!("{package}/schema.toml"),
+            generate_manifest(&package, &[]),
+        )];
+
+        let mut lib = format!("namespace {pkg_name};\n\n");
+        for namespace in 0..self.options.namespaces {
+            lib.push_str(&format!("use ns_{namespace};\n"));
+            let content = self.generate_namespace(namespace, &pkg_name);
+            files.push((format!("{package}/schema/ns_{namespace}.ks"), content));
+        }
+        files.insert(1, (format!("{package}/schema/lib.ks"), lib));
+
+        files
+    }
+
+    fn generate_namespace(
+        &mut self,
+        namespace: usize,
+        pkg_name: &str,
+    ) -> String {
+        let mut uses = std::collections::BTreeSet::new();
+        let mut body = String::new();
+
+        for index in 0..self.options.types {
+            let roll = self.rng.random::<f64>();
+            let declaration = if roll < self.options.union_density {
+                self.generate_union(namespace, index, &mut uses)
+            } else if roll < self.options.union_density + self.options.type_expr_density {
+                self.generate_type_expr(namespace, index, &mut uses)
+            } else {
+                None
+            };
+
+            let declaration = match declaration {
+                Some(declaration) => declaration,
+                None => {
+                    match self.rng.random_range(0..5) {
+                        0 => self.generate_enum(namespace, index),
+                        1 => self.generate_oneof(namespace, index, &mut uses),
+                        _ => self.generate_struct(namespace, index, &mut uses),
+                    }
+                },
+            };
+            body.push_str(&declaration);
+        }
+
+        let mut content = format!("namespace ns_{namespace};\n\n");
+        for (used, name) in &uses {
+            content.push_str(&format!("use {pkg_name}::ns_{used}::{name};\n"));
+        }
+        if !uses.is_empty() {
+            content.push('\n');
+        }
+        content.push_str(&body);
+        content
+    }
+
+    /// How `ty` is referred to from `namespace`, recording the import it needs. Names are
+    /// unique across namespaces, so types from other namespaces are imported by name.
+    fn reference(
+        ty: &GeneratedType,
+        namespace: usize,
+        uses: &mut std::collections::BTreeSet<(usize, String)>,
+    ) -> String {
+        if ty.namespace != namespace {
+            uses.insert((ty.namespace, ty.name.clone()));
+        }
+        ty.name.clone()
+    }
+
+    fn pick_type(&mut self) -> Option<usize> {
+        (!self.types.is_empty()).then(|| self.rng.random_range(0..self.types.len()))
+    }
+
+    /// Structs declared in `namespace`.
+    fn structs(
+        &self,
+        namespace: usize,
+    ) -> Vec<usize> {
+        self.types
+            .iter()
+            .enumerate()
+            .filter(|(_, ty)| ty.fields.is_some() && ty.namespace == namespace)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn field_type(
+        &mut self,
+        namespace: usize,
+        uses: &mut std::collections::BTreeSet<(usize, String)>,
+    ) -> String {
+        let ty = match self.pick_type() {
+            Some(index) if self.rng.random_bool(0.4) => {
+                Self::reference(&self.types[index], namespace, uses)
+            },
+            _ => BUILTINS[self.rng.random_range(0..BUILTINS.len())].to_string(),
+        };
+        if self.rng.random_bool(0.2) {
+            format!("{ty}[]")
+        } else {
+            ty
+        }
+    }
+
+    fn generate_struct(
+        &mut self,
+        namespace: usize,
+        index: usize,
+        uses: &mut std::collections::BTreeSet<(usize, String)>,
+    ) -> String {
+        let name = format!("Struct{namespace}x{index}");
+        let fields = (0..self.rng.random_range(1..=5))
+            .map(|field| {
+                let optional = if self.rng.random_bool(0.2) {
+                    "?"
+                } else {
+                    ""
+                };
+                let ty = self.field_type(namespace, uses);
+                (format!("f{field}"), format!("{optional}: {ty}"))
+            })
+            .collect::<Vec<_>>();
+
+        let body = fields
+            .iter()
+            .map(|(field, declared)| format!("\t{field}{declared}"))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let out = format!("struct {name} {{\n{body}\n}};\n\n");
+
+        self.types.push(GeneratedType {
+            namespace,
+            name,
+            fields: Some(fields),
+        });
+        out
+    }
+
+    fn generate_enum(
+        &mut self,
+        namespace: usize,
+        index: usize,
+    ) -> String {
+        let name = format!("Enum{namespace}x{index}");
+        let variants = (0..self.rng.random_range(1..=4))
+            .map(|variant| format!("\tV{variant} = {variant}"))
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        self.types.push(GeneratedType {
+            namespace,
+            name: name.clone(),
+            fields: None,
+        });
+        format!("enum {name} {{\n{variants}\n}};\n\n")
+    }
+
+    fn generate_oneof(
+        &mut self,
+        namespace: usize,
+        index: usize,
+        uses: &mut std::collections::BTreeSet<(usize, String)>,
+    ) -> String {
+        let name = format!("OneOf{namespace}x{index}");
+        let mut members = vec!["str".to_string()];
+        if let Some(member) = self.pick_type() {
+            members.push(Self::reference(&self.types[member], namespace, uses));
+        }
+        members.push("i64".to_string());
+
+        self.types.push(GeneratedType {
+            namespace,
+            name: name.clone(),
+            fields: None,
+        });
+        format!("type {name} = oneof {};\n\n", members.join(" | "))
+    }
+
+    /// A union-or of two structs of `namespace`, or `None` while no two can be merged. Both
+    /// structs declare their shared fields alike: conflicting fields merge into anonymous
+    /// oneofs, which declarations cannot express yet.
+    fn generate_union(
+        &mut self,
+        namespace: usize,
+        index: usize,
+        uses: &mut std::collections::BTreeSet<(usize, String)>,
+    ) -> Option<String> {
+        let structs = self.structs(namespace);
+        let fields = |index: usize| self.types[index].fields.iter().flatten();
+        let compatible = |left: usize, right: usize| {
+            fields(left).all(|(name, declared)| {
+                fields(right)
+                    .all(|(other, other_declared)| name != other || declared == other_declared)
+            })
+        };
+        let pairs = structs
+            .iter()
+            .enumerate()
+            .flat_map(|(position, left)| {
+                structs[position + 1..]
+                    .iter()
+                    .map(move |right| (*left, *right))
+            })
+            .filter(|(left, right)| compatible(*left, *right))
+            .collect::<Vec<_>>();
+        if pairs.is_empty() {
+            return None;
+        }
+        let (left, right) = pairs[self.rng.random_range(0..pairs.len())];
+
+        let name = format!("Union{namespace}x{index}");
+        let declaration = format!(
+            "type {name} = {} &| {};\n\n",
+            Self::reference(&self.types[left], namespace, uses),
+            Self::reference(&self.types[right], namespace, uses),
+        );
+        self.types.push(GeneratedType {
+            namespace,
+            name,
+            fields: None,
+        });
+        Some(declaration)
+    }
+
+    /// A type expression over a struct of `namespace`, or `None` while it declares none.
+    fn generate_type_expr(
+        &mut self,
+        namespace: usize,
+        index: usize,
+        uses: &mut std::collections::BTreeSet<(usize, String)>,
+    ) -> Option<String> {
+        let structs = self.structs(namespace);
+        if structs.is_empty() {
+            return None;
+        }
+        let target = structs[self.rng.random_range(0..structs.len())];
+        let fields = self.types[target]
+            .fields
+            .iter()
+            .flatten()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        let target = Self::reference(&self.types[target], namespace, uses);
+
+        let selected = fields[..self.rng.random_range(1..=fields.len())].join(" | ");
+        let expr = match self.rng.random_range(0..4) {
+            0 => format!("Pick[{target}, {selected}]"),
+            // omitting every field would leave an empty struct
+            1 if fields.len() > 1 => format!("Omit[{target}, {}]", fields[0]),
+            2 => format!("Partial[{target}]"),
+            _ => format!("Required[{target}]"),
+        };
+
+        let name = format!("Expr{namespace}x{index}");
+        self.types.push(GeneratedType {
+            namespace,
+            name: name.clone(),
+            fields: None,
+        });
+        Some(format!("type {name} = {expr};\n\n"))
+    }
+}
+
+/// Populates `fs` with a random package shaped by `options`, rooted at `options.package`.
+pub fn populate_random_schema(
+    fs: &mut MemoryFileSystem,
+    options: SchemaOptions,
+) {
+    for (path, content) in SchemaGenerator::new(options).generate() {
+        fs.add_file(path, content.as_bytes());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::Path;
@@ -714,7 +1054,6 @@ mod tests {
     fn test_compilation_order() {
         use std::path::Path;
 
-        let mut g = GraphGenerator::new();
         // Create diamond: d <- b,c <- a
         let specs = vec![
             PackageSpec {
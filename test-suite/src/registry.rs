@@ -0,0 +1,279 @@
+//! An in-memory registry, so tests of registry dependencies run without a live registry.
+//!
+//! Packages are served from a fixture [`MemoryFileSystem`]: every directory holding a
+//! `schema.toml` is one published package, at the name and version in its manifest.
+//! [`MemoryRegistry::resolver`] resolves registry dependencies from those packages, through
+//! the version solver like the registry does, and everything else from the compiled sources.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use kintsu_fs::{FileSystem, memory::MemoryFileSystem};
+use kintsu_manifests::{
+    config::NewForNamed,
+    package::{GitDependency, PackageManifests, PathDependency, RemoteDependency},
+    version::{Version, VersionReq},
+};
+use kintsu_parser::ctx::compile::resolver::{
+    DependencyMutability, DeprecationNotice, GitResolver, PackageIndex, PackageResolver,
+    PathPackageResolver, PathResolver, RemoteResolver, ResolvedDependency,
+};
+
+/// Published packages by name, then version.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryRegistry {
+    packages: BTreeMap<String, BTreeMap<Version, MemoryFileSystem>>,
+    yanked: BTreeSet<(String, Version)>,
+    deprecations: BTreeMap<(String, Version), DeprecationNotice>,
+}
+
+impl MemoryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes every package in `fixtures`, e.g. `dep/1.0.0/schema.toml` with its
+    /// `dep/1.0.0/schema/**`.
+    pub fn from_fixtures(fixtures: &MemoryFileSystem) -> kintsu_parser::Result<Self> {
+        let files = fixtures.list_files();
+        files
+            .iter()
+            .filter(|path| path.file_name() == Some("schema.toml".as_ref()))
+            .try_fold(Self::new(), |registry, manifest| {
+                let root = manifest.parent().unwrap_or(Path::new(""));
+                let package = MemoryFileSystem::new();
+                for path in &files {
+                    if let Ok(relative) = path.strip_prefix(root) {
+                        package.add_file(relative, fixtures.get_file_content(path).unwrap());
+                    }
+                }
+                registry.with_package(package)
+            })
+    }
+
+    /// Publishes the package in the root of `package`, at the name and version in its
+    /// manifest.
+    pub fn with_package(
+        mut self,
+        package: MemoryFileSystem,
+    ) -> kintsu_parser::Result<Self> {
+        let manifest = PackageManifests::new(&package, "./")?;
+        let manifest = manifest.package();
+        self.packages
+            .entry(manifest.name.clone())
+            .or_default()
+            .insert(manifest.version.0.clone(), package);
+        Ok(self)
+    }
+
+    pub fn with_yanked(
+        mut self,
+        package: impl Into<String>,
+        version: Version,
+    ) -> Self {
+        self.yanked.insert((package.into(), version));
+        self
+    }
+
+    pub fn with_deprecation(
+        mut self,
+        package: impl Into<String>,
+        version: Version,
+        notice: DeprecationNotice,
+    ) -> Self {
+        self.deprecations
+            .insert((package.into(), version), notice);
+        self
+    }
+
+    /// Published versions of `package`, lowest first.
+    pub fn versions_of(
+        &self,
+        package: &str,
+    ) -> Vec<&Version> {
+        self.packages
+            .get(package)
+            .map(|versions| versions.keys().collect())
+            .unwrap_or_default()
+    }
+
+    /// A resolver serving registry dependencies from this registry, and path dependencies
+    /// from `fs`.
+    pub fn resolver(
+        &self,
+        fs: Arc<dyn FileSystem>,
+    ) -> Arc<dyn PackageResolver> {
+        Arc::new(MemoryRegistryResolver {
+            path: PathPackageResolver::with_fs(fs),
+            registry: self.clone(),
+        })
+    }
+
+    fn unresolved(package: &str) -> kintsu_parser::Error {
+        kintsu_parser::NamespaceError::unresolved_dep(package)
+            .unlocated()
+            .build()
+            .into()
+    }
+}
+
+impl PackageIndex for MemoryRegistry {
+    fn versions(
+        &self,
+        package: &str,
+    ) -> kintsu_parser::Result<Vec<Version>> {
+        Ok(self
+            .versions_of(package)
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
+    fn dependencies(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> kintsu_parser::Result<Vec<(String, VersionReq)>> {
+        let Some(fs) = self
+            .packages
+            .get(package)
+            .and_then(|versions| versions.get(version))
+        else {
+            return Ok(vec![]);
+        };
+
+        let manifest = PackageManifests::new(fs, "./")?;
+        Ok(manifest
+            .dependencies()
+            .iter()
+            .filter_map(|(name, dep)| {
+                dep.version()
+                    .map(|req| (name.clone(), req.0.clone()))
+            })
+            .collect())
+    }
+
+    fn is_yanked(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> kintsu_parser::Result<bool> {
+        Ok(self
+            .yanked
+            .contains(&(package.to_string(), version.clone())))
+    }
+
+    fn deprecation(
+        &self,
+        package: &str,
+        version: &Version,
+    ) -> kintsu_parser::Result<Option<DeprecationNotice>> {
+        Ok(self
+            .deprecations
+            .get(&(package.to_string(), version.clone()))
+            .cloned())
+    }
+}
+
+/// See [`MemoryRegistry::resolver`].
+struct MemoryRegistryResolver {
+    path: PathPackageResolver,
+    registry: MemoryRegistry,
+}
+
+impl PathResolver for MemoryRegistryResolver {
+    fn resolve_path(
+        &self,
+        dep_name: &str,
+        root_path: &Path,
+        path: &PathDependency,
+    ) -> kintsu_parser::Result<ResolvedDependency> {
+        self.path
+            .resolve_path(dep_name, root_path, path)
+    }
+}
+
+impl GitResolver for MemoryRegistryResolver {
+    fn resolve_git(
+        &self,
+        dep_name: &str,
+        _: &GitDependency,
+    ) -> kintsu_parser::Result<ResolvedDependency> {
+        Err(MemoryRegistry::unresolved(dep_name))
+    }
+}
+
+impl RemoteResolver for MemoryRegistryResolver {
+    fn resolve_remote(
+        &self,
+        dep_name: &str,
+        remote: &RemoteDependency,
+    ) -> kintsu_parser::Result<ResolvedDependency> {
+        let package = dep_name.replace('_', "-");
+
+        // the highest matching version; solved dependencies arrive pinned to one
+        let (version, fs) = self
+            .registry
+            .packages
+            .get(&package)
+            .and_then(|versions| {
+                versions
+                    .iter()
+                    .rev()
+                    .find(|(version, _)| remote.version.0.matches(version))
+            })
+            .ok_or_else(|| MemoryRegistry::unresolved(&package))?;
+
+        Ok(ResolvedDependency {
+            fs: Arc::new(fs.clone()),
+            path: PathBuf::from("./"),
+            mutability: DependencyMutability::Immutable,
+            version: version.clone(),
+        })
+    }
+}
+
+impl PackageResolver for MemoryRegistryResolver {
+    fn index(&self) -> Option<&dyn PackageIndex> {
+        Some(&self.registry)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn manifest(
+        name: &str,
+        version: &str,
+    ) -> String {
+        format!("version = \"v1\"\n[package]\nname = \"{name}\"\nversion = \"{version}\"\n")
+    }
+
+    #[test]
+    fn serves_packages_from_fixtures() {
+        let fixtures = kintsu_fs::memory! {
+            "dep/1.0.0/schema.toml" => manifest("dep", "1.0.0"),
+            "dep/1.0.0/schema/lib.ks" => "namespace dep;",
+            "dep/1.2.0/schema.toml" => manifest("dep", "1.2.0"),
+            "dep/1.2.0/schema/lib.ks" => "namespace dep;",
+            "other/schema.toml" => manifest("other", "0.1.0"),
+        };
+        let registry = MemoryRegistry::from_fixtures(&fixtures).unwrap();
+
+        assert_eq!(
+            registry.versions_of("dep"),
+            [&Version::new(1, 0, 0), &Version::new(1, 2, 0)]
+        );
+        assert_eq!(registry.versions_of("other"), [&Version::new(0, 1, 0)]);
+        assert!(registry.versions_of("missing").is_empty());
+        assert!(
+            registry.packages["dep"][&Version::new(1, 2, 0)]
+                .get_file_content(Path::new("schema/lib.ks"))
+                .is_some()
+        );
+    }
+}
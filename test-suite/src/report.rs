@@ -0,0 +1,377 @@
+//! Reading `test-suite.jsonl` reports back, e.g. for CI dashboards.
+//!
+//! Every line is a [`TestReport`](crate::TestReport) described by [`REPORT_SCHEMA`], and is
+//! checked against it by [`validate`] before it is read. Reports may hold several runs
+//! appended to one another, which is what [`flaky`] compares.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::Path,
+};
+
+use serde_json::Value;
+
+use crate::{CliTestResult, TestMetadata, TestResult};
+
+/// JSON schema of one report line.
+pub const REPORT_SCHEMA: &str = include_str!("../report.schema.json");
+
+/// One line of a report.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(tag = "type", content = "test", rename_all = "snake_case")]
+pub enum ReportEntry {
+    CliTest(CliTestResult),
+    CompileTest(TestResult),
+}
+
+impl ReportEntry {
+    pub fn metadata(&self) -> &TestMetadata {
+        match self {
+            Self::CliTest(test) => &test.metadata,
+            Self::CompileTest(test) => &test.metadata,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.metadata().id
+    }
+
+    /// Whether the test behaved as expected.
+    pub fn passed(&self) -> bool {
+        match self {
+            Self::CliTest(test) => test.passed,
+            Self::CompileTest(test) => test.matches_expectation,
+        }
+    }
+}
+
+/// Counts of report entries.
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+impl Summary {
+    fn add(
+        &mut self,
+        entry: &ReportEntry,
+    ) {
+        self.total += 1;
+        if entry.passed() {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+}
+
+/// Parses the lines of a report, validating each against [`REPORT_SCHEMA`].
+pub fn parse_report(content: &str) -> io::Result<Vec<ReportEntry>> {
+    let invalid = |line: usize, reason: String| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("line {line}: {reason}"))
+    };
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let mut value: Value =
+                serde_json::from_str(line).map_err(|err| invalid(index + 1, err.to_string()))?;
+            // compile tests were written without the report wrapper before it was validated
+            if value.get("type").is_none() && value.get("fs").is_some() {
+                value = serde_json::json!({ "type": "compile_test", "test": value });
+            }
+            validate(&value).map_err(|reason| invalid(index + 1, reason))?;
+            serde_json::from_value(value).map_err(|err| invalid(index + 1, err.to_string()))
+        })
+        .collect()
+}
+
+pub fn read_report(path: &Path) -> io::Result<Vec<ReportEntry>> {
+    parse_report(&std::fs::read_to_string(path)?)
+}
+
+/// Checks `line` against [`REPORT_SCHEMA`], returning the first violation.
+pub fn validate(line: &Value) -> Result<(), String> {
+    let schema: Value = serde_json::from_str(REPORT_SCHEMA).expect("report schema is json");
+    validate_at(&schema, &schema, line, "$")
+}
+
+/// Validates the parts of JSON schema [`REPORT_SCHEMA`] uses: `type`, `const`, `enum`,
+/// `oneOf`, `$ref` into `$defs`, `properties`, `required`, `additionalProperties` and `items`.
+fn validate_at(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+) -> Result<(), String> {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference
+            .strip_prefix("#/$defs/")
+            .ok_or_else(|| format!("unsupported reference {reference}"))?;
+        return validate_at(root, &root["$defs"][name], value, path);
+    }
+
+    if let Some(variants) = schema["oneOf"].as_array() {
+        let matching = variants
+            .iter()
+            .filter(|variant| validate_at(root, variant, value, path).is_ok())
+            .count();
+        if matching != 1 {
+            return Err(format!(
+                "{path} matches {matching} of the report kinds, instead of one"
+            ));
+        }
+    }
+
+    if let Some(expected) = schema.get("const")
+        && value != expected
+    {
+        return Err(format!("{path} is {value}, expected {expected}"));
+    }
+
+    if let Some(allowed) = schema["enum"].as_array()
+        && !allowed.contains(value)
+    {
+        return Err(format!(
+            "{path} is {value}, which is not one of {allowed:?}"
+        ));
+    }
+
+    if let Some(ty) = schema.get("type") {
+        let types = match ty {
+            Value::Array(types) => {
+                types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect()
+            },
+            ty => ty.as_str().into_iter().collect::<Vec<_>>(),
+        };
+        let matches = types.iter().any(|ty| {
+            match *ty {
+                "object" => value.is_object(),
+                "array" => value.is_array(),
+                "string" => value.is_string(),
+                "boolean" => value.is_boolean(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "number" => value.is_number(),
+                "null" => value.is_null(),
+                _ => false,
+            }
+        });
+        if !matches {
+            return Err(format!(
+                "{path} is {value}, expected {}",
+                types.join(" or ")
+            ));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        for required in schema["required"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !object.contains_key(required) {
+                return Err(format!("{path} is missing '{required}'"));
+            }
+        }
+
+        for (key, field) in object {
+            let path = format!("{path}.{key}");
+            match (
+                schema["properties"].get(key),
+                &schema["additionalProperties"],
+            ) {
+                (Some(property), _) => validate_at(root, property, field, &path)?,
+                (None, Value::Bool(false)) => return Err(format!("{path} is not expected")),
+                (None, additional) if additional.is_object() => {
+                    validate_at(root, additional, field, &path)?
+                },
+                (None, _) => {},
+            }
+        }
+    }
+
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (index, item) in array.iter().enumerate() {
+            validate_at(root, items, item, &format!("{path}[{index}]"))?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn summarize<'e>(entries: impl IntoIterator<Item = &'e ReportEntry>) -> Summary {
+    let mut summary = Summary::default();
+    for entry in entries {
+        summary.add(entry);
+    }
+    summary
+}
+
+/// Summaries by tag, as named in reports, e.g. `one-of`. Entries count towards each of their
+/// tags.
+pub fn summarize_by_tag<'e>(
+    entries: impl IntoIterator<Item = &'e ReportEntry>
+) -> BTreeMap<String, Summary> {
+    let mut summaries = BTreeMap::<String, Summary>::new();
+    for entry in entries {
+        for tag in &entry.metadata().tags {
+            let tag = serde_json::to_value(tag)
+                .ok()
+                .and_then(|tag| tag.as_str().map(str::to_string))
+                .unwrap_or_default();
+            summaries.entry(tag).or_default().add(entry);
+        }
+    }
+    summaries
+}
+
+/// Entries of tests which did not behave as expected.
+pub fn failures<'e>(
+    entries: impl IntoIterator<Item = &'e ReportEntry>
+) -> impl Iterator<Item = &'e ReportEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| !entry.passed())
+}
+
+/// Ids of tests which both passed and failed across the runs in `entries`.
+pub fn flaky<'e>(entries: impl IntoIterator<Item = &'e ReportEntry>) -> BTreeSet<String> {
+    let mut outcomes = BTreeMap::<&str, (bool, bool)>::new();
+    for entry in entries {
+        let (passed, failed) = outcomes.entry(entry.id()).or_default();
+        if entry.passed() {
+            *passed = true;
+        } else {
+            *failed = true;
+        }
+    }
+    outcomes
+        .into_iter()
+        .filter(|(_, (passed, failed))| *passed && *failed)
+        .map(|(id, _)| id.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Tag, TestReport};
+
+    fn compile_test(
+        id: &str,
+        passed: bool,
+        tags: Vec<Tag>,
+    ) -> String {
+        let test = TestResult {
+            fs: kintsu_fs::memory! { "pkg/schema/lib.ks" => "namespace pkg;" },
+            metadata: TestMetadata::new(id, id, tags),
+            actual_pass: passed,
+            matches_expectation: passed,
+            error_message: (!passed).then(|| "failed".to_string()),
+        };
+        serde_json::to_string(&TestReport::compile_test(&test)).unwrap()
+    }
+
+    fn cli_test(
+        id: &str,
+        passed: bool,
+    ) -> String {
+        let test = CliTestResult {
+            metadata: TestMetadata::new(id, id, vec![Tag::Validations]).expect_fail(),
+            exit_code: 1,
+            passed,
+            expected_error_code: Some("KTR1002".into()),
+            actual_error_code: passed.then(|| "KTR1002".into()),
+            expected_span: true,
+            has_source_span: true,
+            span_matches_expectation: true,
+            stdout: String::new(),
+            stderr: "KTR1002".into(),
+            error_message: "KTR1002".into(),
+        };
+        serde_json::to_string(&TestReport::cli_test(&test)).unwrap()
+    }
+
+    #[test]
+    fn reads_and_aggregates_reports() {
+        let report = [
+            compile_test("a", true, vec![Tag::Smoke, Tag::OneOf]),
+            compile_test("b", false, vec![Tag::Smoke]),
+            cli_test("c", true),
+            // a second run
+            compile_test("a", false, vec![Tag::Smoke, Tag::OneOf]),
+            cli_test("c", true),
+        ]
+        .join("\n");
+        let entries = parse_report(&report).unwrap();
+
+        assert_eq!(
+            summarize(&entries),
+            Summary {
+                total: 5,
+                passed: 3,
+                failed: 2
+            }
+        );
+        let by_tag = summarize_by_tag(&entries);
+        assert_eq!(by_tag["smoke"].total, 3);
+        assert_eq!(by_tag["one-of"].failed, 1);
+        assert_eq!(by_tag["validations"].passed, 2);
+        assert_eq!(
+            failures(&entries)
+                .map(ReportEntry::id)
+                .collect::<Vec<_>>(),
+            ["b", "a"]
+        );
+        assert_eq!(flaky(&entries), BTreeSet::from(["a".to_string()]));
+    }
+
+    #[test]
+    fn reads_unwrapped_compile_tests() {
+        let line = compile_test("a", true, vec![]);
+        let unwrapped = serde_json::from_str::<Value>(&line).unwrap()["test"].to_string();
+
+        let entries = parse_report(&unwrapped).unwrap();
+        assert!(
+            matches!(&entries[..], [ReportEntry::CompileTest(test)] if test.metadata.id == "a")
+        );
+    }
+
+    #[test]
+    fn rejects_lines_outside_the_schema() {
+        let mut line = serde_json::from_str::<Value>(&cli_test("c", true)).unwrap();
+        line["test"]["metadata"]["tags"] = serde_json::json!(["smoke", "unknown"]);
+        assert!(
+            validate(&line)
+                .unwrap_err()
+                .contains("matches 0 of the report kinds")
+        );
+
+        let mut line = serde_json::from_str::<Value>(&cli_test("c", true)).unwrap();
+        line["test"]["metadata"]["tags"] = serde_json::json!(["smoke", "unknown"]);
+        let nested = validate_at(
+            &serde_json::from_str(REPORT_SCHEMA).unwrap(),
+            &serde_json::json!({ "$ref": "#/$defs/cli_test" }),
+            &line["test"],
+            "$.test",
+        )
+        .unwrap_err();
+        assert!(
+            nested.starts_with("$.test.metadata.tags[1] is \"unknown\""),
+            "{nested}"
+        );
+
+        let err = parse_report("{\"type\": \"cli_test\", \"test\": {}}").unwrap_err();
+        assert!(err.to_string().starts_with("line 1:"), "{err}");
+    }
+}
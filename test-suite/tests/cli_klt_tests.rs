@@ -0,0 +1,379 @@
+//! CLI tests for KLT (Lint) diagnostics per ERR-0015.
+//!
+//! Lint rules run over the declarations of the root package when a compilation is
+//! finalized, at the level set by each rule or by the `[lint]` table of the manifest.
+
+use std::path::PathBuf;
+
+use kintsu_fs::memory;
+use kintsu_test_suite::cli_tests::{
+    CliErrorTest, minimal_manifest, run_deny_warnings_command, run_fix_command,
+};
+
+const SINGLE_VARIANT: &str = r#"namespace types;
+
+oneof Payload {
+    Text { content: str }
+};
+"#;
+
+const CAMEL_FIELDS: &str = r#"namespace types;
+
+struct User {
+    userId: i64,
+    displayName: str
+};
+"#;
+
+/// KLT8001: Lint rule violated
+/// Per ERR-0015: Severity=Warning, requires span on the offending item
+#[tokio::test]
+async fn klt8001_rule_violated() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-klt8001"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => SINGLE_VARIANT,
+    };
+
+    let result = CliErrorTest::new("klt8001_rule_violated")
+        .name("Lint Rule Violated")
+        .purpose("Verify KLT8001 warning for a oneof with a single variant")
+        .expect_warning("KLT8001")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("klt8001_rule_violated", result.stderr);
+}
+
+/// KLT2001: Lint rule denied at error level by the manifest
+#[tokio::test]
+async fn klt2001_rule_denied() {
+    let manifest = format!(
+        "{}\n[lint.overrides.form]\nsingle_variant = {{ level = \"error\" }}\n",
+        minimal_manifest("test-klt2001")
+    );
+    let fs = memory! {
+        "pkg/schema.toml" => manifest,
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => SINGLE_VARIANT,
+    };
+
+    let result = CliErrorTest::new("klt2001_rule_denied")
+        .name("Lint Rule Denied")
+        .purpose("Verify KLT2001 when the manifest raises a lint rule to the error level")
+        .expect_error("KLT2001")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("klt2001_rule_denied", result.stderr);
+}
+
+/// Rules silenced by the manifest report nothing
+#[tokio::test]
+async fn silenced_rule_reports_nothing() {
+    let manifest = format!(
+        "{}\n[lint.overrides.form]\nsingle_variant = {{ level = \"silent\" }}\n",
+        minimal_manifest("test-klt-silent")
+    );
+    let fs = memory! {
+        "pkg/schema.toml" => manifest,
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => SINGLE_VARIANT,
+    };
+
+    let result = CliErrorTest::new("klt_silenced_rule")
+        .name("Lint Rule Silenced")
+        .purpose("Verify a rule set to silent in the manifest emits no diagnostic")
+        .expect_success()
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    assert!(!result.stderr.contains("KLT"), "{}", result.stderr);
+}
+
+/// KLT8001: Names not following the naming conventions are reported on the name
+#[tokio::test]
+async fn klt8001_naming_convention() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-klt8001-naming"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => CAMEL_FIELDS,
+    };
+
+    let result = CliErrorTest::new("klt8001_naming_convention")
+        .name("Naming Convention Violated")
+        .purpose("Verify KLT8001 warnings for fields which are not snake_case")
+        .expect_warning("KLT8001")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("klt8001_naming_convention", result.stderr);
+}
+
+/// Conventions set under `[lint.naming]` replace the defaults
+#[tokio::test]
+async fn naming_convention_from_manifest() {
+    let manifest = format!(
+        "{}\n[lint.naming]\nfields = \"camel\"\n",
+        minimal_manifest("test-klt-naming")
+    );
+    let fs = memory! {
+        "pkg/schema.toml" => manifest,
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => CAMEL_FIELDS,
+    };
+
+    let result = CliErrorTest::new("klt_naming_from_manifest")
+        .name("Naming Convention From Manifest")
+        .purpose("Verify fields following a convention set in the manifest are not reported")
+        .expect_success()
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    assert!(!result.stderr.contains("KLT"), "{}", result.stderr);
+}
+
+/// `check --fix` renames to the convention when nothing else needs to change
+#[tokio::test]
+async fn naming_fix_renames() {
+    let temp_dir = PathBuf::from("./tmp/cli_test_klt_naming_fix");
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    std::fs::create_dir_all(temp_dir.join("schema")).ok();
+    std::fs::write(temp_dir.join("schema.toml"), minimal_manifest("naming-fix")).ok();
+    std::fs::write(
+        temp_dir.join("schema/lib.ks"),
+        "namespace naming_fix;\nuse types;\n",
+    )
+    .ok();
+    std::fs::write(
+        temp_dir.join("schema/types.ks"),
+        "namespace types;\n\nstruct user {\n    userId: i64\n};\n\nstruct Holder {\n    owner: user\n};\n\nenum Status {\n    Active = 1\n};\n",
+    )
+    .ok();
+
+    let output = run_fix_command(&temp_dir);
+    let fixed = std::fs::read_to_string(temp_dir.join("schema/types.ks")).unwrap_or_default();
+
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    assert!(
+        output.success(),
+        "fix run failed:\nstdout: {}\nstderr: {}",
+        output.stdout,
+        output.stderr
+    );
+    // `user` is referenced by `Holder`, so renaming it is left to the author
+    assert_eq!(
+        fixed,
+        "namespace types;\n\nstruct user {\n    user_id: i64\n};\n\nstruct Holder {\n    owner: user\n};\n\nenum Status {\n    ACTIVE = 1\n};\n"
+    );
+    assert!(
+        output.stderr.contains("applied 2 fix(es)"),
+        "{}",
+        output.stderr
+    );
+}
+
+/// KLT8001: Anonymous structs nested past the threshold are reported on the outermost type
+#[tokio::test]
+async fn klt8001_nesting_depth() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-klt8001-nesting"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+struct Profile {
+    settings: {
+        theme: {
+            colors: {
+                primary: str
+            }
+        }
+    }
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("klt8001_nesting_depth")
+        .name("Anonymous Struct Nesting Too Deep")
+        .purpose("Verify KLT8001 when anonymous structs nest deeper than the threshold")
+        .expect_warning("KLT8001")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("klt8001_nesting_depth", result.stderr);
+}
+
+/// KLT8001: Rules silent by default report once the manifest sets their level
+#[tokio::test]
+async fn klt8001_discriminant_gap() {
+    let manifest = format!(
+        "{}\n[lint.overrides.form]\ncontiguous_discriminants = {{ level = \"warn\" }}\n",
+        minimal_manifest("test-klt8001-discriminants")
+    );
+    let fs = memory! {
+        "pkg/schema.toml" => manifest,
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+enum Level {
+    LOW = 1,
+    HIGH = 3
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("klt8001_discriminant_gap")
+        .name("Discriminant Gap Lint")
+        .purpose("Verify KLT8001 for a gap in discriminants when the manifest enables the rule")
+        .expect_warning("KLT8001")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("klt8001_discriminant_gap", result.stderr);
+}
+
+/// KLT2001: Reserved words of the backends listed under `[lint.identifiers]`, denied by the
+/// manifest
+#[tokio::test]
+async fn klt2001_reserved_word() {
+    let manifest = format!(
+        "{}\n[lint.identifiers]\nbackends = [\"typescript\"]\n\n[lint.overrides.naming]\nreserved_word = {{ level = \"error\" }}\n",
+        minimal_manifest("test-klt2001-reserved")
+    );
+    let fs = memory! {
+        "pkg/schema.toml" => manifest,
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+struct Settings {
+    default: bool
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("klt2001_reserved_word")
+        .name("Reserved Word Denied")
+        .purpose("Verify KLT2001 for a field named after a TypeScript reserved word")
+        .expect_error("KLT2001")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("klt2001_reserved_word", result.stderr);
+}
+
+/// KLT8001: Doc links which name nothing, once `docs::broken_link` is given a level
+#[tokio::test]
+async fn klt8001_broken_doc_link() {
+    let manifest = format!(
+        "{}\n[lint.overrides.docs]\nbroken_link = {{ level = \"warn\" }}\n",
+        minimal_manifest("test-klt8001-docs")
+    );
+    let fs = memory! {
+        "pkg/schema.toml" => manifest,
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+/// Signed in as a [User]
+struct Session {
+    /// See [Account.id]
+    account: i64
+};
+
+struct User {
+    id: i64
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("klt8001_broken_doc_link")
+        .name("Broken Doc Link")
+        .purpose("Verify KLT8001 for a doc link to a type which is not declared")
+        .expect_warning("KLT8001")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("klt8001_broken_doc_link", result.stderr);
+}
+
+fn write_single_variant(
+    dir: &std::path::Path,
+    manifest: &str,
+) {
+    let _ = std::fs::remove_dir_all(dir);
+    std::fs::create_dir_all(dir.join("schema")).unwrap();
+    std::fs::write(dir.join("schema.toml"), manifest).unwrap();
+    std::fs::write(dir.join("schema/lib.ks"), "namespace pkg;\nuse types;\n").unwrap();
+    std::fs::write(dir.join("schema/types.ks"), SINGLE_VARIANT).unwrap();
+}
+
+/// KLT2002: `check --deny-warnings` fails on warnings, naming their codes
+#[tokio::test]
+async fn klt2002_warnings_denied() {
+    let temp_dir = PathBuf::from("./tmp/cli_test_klt2002_warnings_denied");
+    write_single_variant(&temp_dir, &minimal_manifest("test-klt2002"));
+
+    let output = run_deny_warnings_command(&temp_dir);
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    assert!(!output.success(), "{}", output.stderr);
+    assert!(
+        output
+            .stderr
+            .contains("warnings are denied: KLT8001"),
+        "{}",
+        output.stderr
+    );
+}
+
+/// Codes listed under `allow_warnings` do not fail `check --deny-warnings`
+#[tokio::test]
+async fn allowed_warnings_are_not_denied() {
+    let temp_dir = PathBuf::from("./tmp/cli_test_klt_allowed_warnings");
+    let manifest = format!(
+        "{}\n[lint]\nallow_warnings = [\"KLT8001\"]\n",
+        minimal_manifest("test-klt-allowed")
+    );
+    write_single_variant(&temp_dir, &manifest);
+
+    let output = run_deny_warnings_command(&temp_dir);
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    assert!(output.success(), "{}", output.stderr);
+    assert!(output.stderr.contains("KLT8001"), "{}", output.stderr);
+}
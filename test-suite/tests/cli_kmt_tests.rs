@@ -128,3 +128,266 @@ struct User {
 
     insta::assert_snapshot!("kmt2002_invalid_error_attribute", result.stderr);
 }
+
+/// KMT2003: Constraint attribute incompatible with the field type
+#[tokio::test]
+async fn kmt2003_invalid_constraint() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2003"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use users;
+"#,
+        "pkg/schema/users.ks" => r#"namespace users;
+
+struct User {
+    name: str
+};
+
+operation find_user(
+    #[pattern("^[0-9]+$")]
+    id: i32
+) -> User;
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2003_invalid_constraint")
+        .name("Invalid Constraint")
+        .purpose("Verify KMT2003 for a pattern constraint on a numeric operation argument")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2003_invalid_constraint", result.stderr);
+}
+
+/// KMT2004: Constraint attribute placed on an item instead of a field
+#[tokio::test]
+async fn kmt2004_misplaced_constraint() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2004"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use users;
+"#,
+        "pkg/schema/users.ks" => r#"namespace users;
+
+#[min(1)]
+struct User {
+    name: str
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2004_misplaced_constraint")
+        .name("Misplaced Constraint")
+        .purpose("Verify KMT2004 for a constraint attribute on a struct declaration")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2004_misplaced_constraint", result.stderr);
+}
+
+/// KMT2005: HTTP path parameter does not match an operation argument
+#[tokio::test]
+async fn kmt2005_invalid_http_binding() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2005"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use users;
+"#,
+        "pkg/schema/users.ks" => r#"namespace users;
+
+#[http(method = "GET", path = "/users/{user_id}")]
+operation get_user(id: i64) -> str;
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2005_invalid_http_binding")
+        .name("Invalid HTTP Binding")
+        .purpose("Verify KMT2005 when an HTTP path parameter names no operation argument")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2005_invalid_http_binding", result.stderr);
+}
+
+/// KMT2006: Gap in the discriminants of a `#[contiguous]` enum
+#[tokio::test]
+async fn kmt2006_discriminant_gap() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2006"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+#[contiguous]
+enum Level {
+    LOW = 1,
+    MEDIUM = 2,
+    HIGH = 4
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2006_discriminant_gap")
+        .name("Discriminant Gap")
+        .purpose("Verify KMT2006 when a #[contiguous] enum skips a discriminant")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2006_discriminant_gap", result.stderr);
+}
+
+/// KMT2006: Discriminant above the `#[max]` of its enum
+#[tokio::test]
+async fn kmt2006_discriminant_above_max() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2006-max"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+#[max(255)]
+enum Opcode {
+    NOOP = 0,
+    HALT = 256
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2006_discriminant_above_max")
+        .name("Discriminant Above Maximum")
+        .purpose("Verify KMT2006 when a discriminant exceeds the #[max] of its enum")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2006_discriminant_above_max", result.stderr);
+}
+
+/// KMT2004: `#[contiguous]` outside an integer enum
+#[tokio::test]
+async fn kmt2004_misplaced_contiguous() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2004-contiguous"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+#[contiguous]
+enum Color {
+    RED = "red",
+    BLUE = "blue"
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2004_misplaced_contiguous")
+        .name("Misplaced Contiguous Attribute")
+        .purpose("Verify KMT2004 when #[contiguous] is set on a string enum")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2004_misplaced_contiguous", result.stderr);
+}
+
+/// KMT2007: Field example that does not match the field type
+#[tokio::test]
+async fn kmt2007_example_type_mismatch() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2007"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use users;
+"#,
+        "pkg/schema/users.ks" => r#"namespace users;
+
+struct User {
+    #[example('"forty-two"')]
+    id: i64
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2007_example_type_mismatch")
+        .name("Example Type Mismatch")
+        .purpose("Verify KMT2007 when a field example does not match the field type")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2007_example_type_mismatch", result.stderr);
+}
+
+/// KMT2007: Operation example input naming no argument
+#[tokio::test]
+async fn kmt2007_example_unknown_argument() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2007-input"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use users;
+"#,
+        "pkg/schema/users.ks" => r#"namespace users;
+
+#[example(input = '{"id": 1, "verbose": true}', output = '"ada"')]
+operation get_name(id: i64) -> str;
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2007_example_unknown_argument")
+        .name("Example Unknown Argument")
+        .purpose("Verify KMT2007 when an operation example input names no argument")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2007_example_unknown_argument", result.stderr);
+}
+
+/// KMT2008: GraphQL attribute naming both operation kinds
+#[tokio::test]
+async fn kmt2008_invalid_graphql_kind() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kmt2008"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use users;
+"#,
+        "pkg/schema/users.ks" => r#"namespace users;
+
+#[graphql(query, mutation)]
+operation get_user(id: i64) -> str;
+"#,
+    };
+
+    let result = CliErrorTest::new("kmt2008_invalid_graphql_kind")
+        .name("Invalid GraphQL Kind")
+        .purpose("Verify KMT2008 when #[graphql] names both query and mutation")
+        .expect_error("KMT")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kmt2008_invalid_graphql_kind", result.stderr);
+}
@@ -71,3 +71,39 @@ async fn kpr_empty_file_list() {
 
     insta::assert_snapshot!("kpr_empty_file_list", result.stderr);
 }
+
+/// Syntax errors in one file do not abort the package. The parse error is reported first,
+/// followed by the undefined type in the file that referenced it, marked as a follow-on.
+#[tokio::test]
+async fn kpr_recovers_past_broken_file() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kpr-recover"),
+        "pkg/schema/lib.ks" => "namespace pkg;\nuse broken;\nuse consumer;\n",
+        "pkg/schema/broken.ks" => r#"namespace broken;
+
+struct Foo {
+    id: i64,
+    name:
+};
+"#,
+        "pkg/schema/consumer.ks" => r#"namespace consumer;
+
+use schema::broken::Foo;
+
+struct Bar {
+    foo: Foo
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kpr_recovers_past_broken_file")
+        .name("Recover Past Broken File")
+        .purpose("Verify parse errors are reported before follow-on resolution errors")
+        .expect_error("KLX")
+        .requires_span(false)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kpr_recovers_past_broken_file", result.stderr);
+}
@@ -202,3 +202,36 @@ oneof Result {
 
     insta::assert_snapshot!("ktg2003_internal_tag_requires_struct", result.stderr);
 }
+
+/// KTG3004: Untagged duplicate type via the `#[untagged]` shorthand on an error type
+#[tokio::test]
+async fn ktg3004_untagged_shorthand_error_duplicate_type() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-ktg3004-error"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+#[untagged]
+error Failure {
+    NotFound(str),
+    Forbidden(str)
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("ktg3004_untagged_shorthand_error_duplicate_type")
+        .name("Untagged Shorthand Error Duplicate Type")
+        .purpose("Verify KTG3004 for an #[untagged] error with duplicate variant types")
+        .expect_error("KTG")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!(
+        "ktg3004_untagged_shorthand_error_duplicate_type",
+        result.stderr
+    );
+}
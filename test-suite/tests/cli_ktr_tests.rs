@@ -5,7 +5,7 @@
 //! All KTR errors require source spans per SPEC-0022.
 
 use kintsu_fs::memory;
-use kintsu_test_suite::cli_tests::{CliErrorTest, minimal_manifest};
+use kintsu_test_suite::cli_tests::{CliErrorTest, manifest_with_deps, minimal_manifest};
 
 /// KTR1002: Undefined type
 #[tokio::test]
@@ -37,6 +37,44 @@ namespace types {
     insta::assert_snapshot!("ktr1002_undefined_type", result.stderr);
 }
 
+/// KTR1002: Undefined type close to a declared name suggests the declared name
+#[tokio::test]
+async fn ktr1002_undefined_type_suggestion() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-ktr1002c"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+struct User {
+    id: i64
+};
+
+struct Handler {
+    user: Usr
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("ktr1002_undefined_type_suggestion")
+        .name("Undefined Type (suggestion)")
+        .purpose("Verify KTR1002 suggests a declared type with a similar name")
+        .expect_error("KTR1002")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    assert!(
+        result
+            .stderr
+            .contains("a type with a similar name exists: `User`")
+    );
+
+    insta::assert_snapshot!("ktr1002_undefined_type_suggestion", result.stderr);
+}
+
 /// KTR1002: Undefined type in separate file
 #[tokio::test]
 async fn ktr1002_undefined_type_separate_file() {
@@ -164,3 +202,147 @@ namespace types {
 
     insta::assert_snapshot!("ktr_undefined_import", result.stderr);
 }
+
+/// KTR1004: Internal item referenced from another package
+#[tokio::test]
+async fn ktr1004_internal_item() {
+    let fs = memory! {
+        "dep/schema.toml" => minimal_manifest("dep"),
+        "dep/schema/lib.ks" => r#"namespace dep;
+
+namespace types {
+    internal struct Secret {
+        value: str
+    };
+
+    struct Shared {
+        id: i64
+    };
+};
+"#,
+        "pkg/schema.toml" => manifest_with_deps("test-ktr1004", &[("dep", "../dep")]),
+        "pkg/schema/lib.ks" => "use types;",
+        "pkg/schema/types.ks" => r#"namespace types;
+
+use dep::types::Secret;
+
+struct Foo {
+    secret: Secret
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("ktr1004_internal_item")
+        .name("Internal Item")
+        .purpose("Verify KTR1004 when referencing an internal type of a dependency")
+        .expect_error("KTR")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    assert!(result.stderr.contains("KTR1004"));
+
+    insta::assert_snapshot!("ktr1004_internal_item", result.stderr);
+}
+
+/// KTR1005: Public item exposing an internal type
+#[tokio::test]
+async fn ktr1005_internal_type_exposed() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-ktr1005"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+
+namespace types {
+    internal struct Cursor {
+        offset: i64
+    };
+
+    struct Page {
+        cursor: Cursor
+    };
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("ktr1005_internal_type_exposed")
+        .name("Internal Type Exposed")
+        .purpose("Verify KTR1005 when a public type references an internal type")
+        .expect_error("KTR")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    assert!(result.stderr.contains("KTR1005"));
+
+    insta::assert_snapshot!("ktr1005_internal_type_exposed", result.stderr);
+}
+
+/// KTR5004: Recursive type through required fields, reported with the full cycle path
+#[tokio::test]
+async fn ktr5004_recursive_type() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-ktr5004"),
+        "pkg/schema/lib.ks" => "use types;",
+        "pkg/schema/types.ks" => r#"namespace types;
+
+struct Order {
+    id: i64,
+    customer: Customer
+};
+
+struct Customer {
+    name: str,
+    account: Account,
+    previous?: Order
+};
+
+struct Account {
+    latest: Order,
+    history: Order[]
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("ktr5004_recursive_type")
+        .name("Recursive Type")
+        .purpose("Verify KTR5004 lists every type and field on a required cycle")
+        .expect_error("KTR")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    assert!(result.stderr.contains("KTR5004"));
+
+    insta::assert_snapshot!("ktr5004_recursive_type", result.stderr);
+}
+
+/// KTR5004: Struct holding itself through a fixed-size array
+#[tokio::test]
+async fn ktr5004_self_recursive_sized_array() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-ktr5004-self"),
+        "pkg/schema/lib.ks" => "use types;",
+        "pkg/schema/types.ks" => r#"namespace types;
+
+struct Pair {
+    halves: Pair[2]
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("ktr5004_self_recursive_sized_array")
+        .name("Self Recursive Sized Array")
+        .purpose("Verify fixed-size arrays do not break recursion")
+        .expect_error("KTR")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    assert!(result.stderr.contains("KTR5004"));
+
+    insta::assert_snapshot!("ktr5004_self_recursive_sized_array", result.stderr);
+}
@@ -104,6 +104,64 @@ struct User {
     insta::assert_snapshot!("kty3003_duplicate_field", result.stderr);
 }
 
+/// KTY3004: Enum alias colliding with another variant's value
+#[tokio::test]
+async fn kty3004_duplicate_enum_value() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kty3004"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+enum Status {
+    Active = 1 | "active",
+    Inactive = 2 | 1
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kty3004_duplicate_enum_value")
+        .name("Duplicate Enum Value")
+        .purpose("Verify KTY3004 when an enum alias repeats the value of another variant")
+        .expect_error("KTY3004")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kty3004_duplicate_enum_value", result.stderr);
+}
+
+/// KTY3004: Enum alias repeated between variants
+#[tokio::test]
+async fn kty3004_duplicate_enum_alias() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kty3004-alias"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+enum Status {
+    Active = "active" | "on",
+    Inactive = "inactive" | "on"
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kty3004_duplicate_enum_alias")
+        .name("Duplicate Enum Alias")
+        .purpose("Verify KTY3004 when two variants declare the same alias")
+        .expect_error("KTY3004")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kty3004_duplicate_enum_alias", result.stderr);
+}
+
 /// KTY2001: Missing error type on fallible operation
 #[tokio::test]
 async fn kty2001_missing_error_type() {
@@ -133,3 +191,63 @@ operation create_user(input: str) -> User!;
 
     insta::assert_snapshot!("kty2001_missing_error_type", result.stderr);
 }
+
+/// KTY2003: Sized array length that is not a constant
+#[tokio::test]
+async fn kty2003_non_constant_array_size() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kty2003"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+struct Header {
+    len: u32
+};
+
+struct Packet {
+    data: u8[Header]
+};
+"#,
+    };
+
+    let result = CliErrorTest::new("kty2003_non_constant_array_size")
+        .name("Non-constant Array Size")
+        .purpose("Verify KTY2003 when a sized array length names a type instead of a const")
+        .expect_error("KTY")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    assert!(result.stderr.contains("Header"));
+
+    insta::assert_snapshot!("kty2003_non_constant_array_size", result.stderr);
+}
+
+/// KTY2004: Constant value out of range for its declared type
+#[tokio::test]
+async fn kty2004_const_type_mismatch() {
+    let fs = memory! {
+        "pkg/schema.toml" => minimal_manifest("test-kty2004"),
+        "pkg/schema/lib.ks" => r#"namespace pkg;
+use types;
+"#,
+        "pkg/schema/types.ks" => r#"namespace types;
+
+const MAX_RETRIES: u8 = 300;
+"#,
+    };
+
+    let result = CliErrorTest::new("kty2004_const_type_mismatch")
+        .name("Constant Type Mismatch")
+        .purpose("Verify KTY2004 when a constant does not fit its declared type")
+        .expect_error("KTY")
+        .requires_span(true)
+        .with_fs(fs)
+        .root("pkg")
+        .run_and_assert();
+
+    insta::assert_snapshot!("kty2004_const_type_mismatch", result.stderr);
+}
@@ -257,3 +257,210 @@ async fn integration_check_command() {
         output.stderr
     );
 }
+
+/// `check --fix` applies machine-applicable suggestions until the package compiles
+#[tokio::test]
+async fn integration_check_fix_command() {
+    use kintsu_test_suite::cli_tests::run_fix_command;
+
+    let temp_dir = PathBuf::from("./tmp/cli_test_integration_check_fix");
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    std::fs::create_dir_all(temp_dir.join("schema")).ok();
+    std::fs::write(temp_dir.join("schema.toml"), minimal_manifest("fix-test")).ok();
+    std::fs::write(
+        temp_dir.join("schema/lib.ks"),
+        "namespace fix_test;\nuse types;\n",
+    )
+    .ok();
+    std::fs::write(
+        temp_dir.join("schema/types.ks"),
+        "namespace types;\n\nstruct User {\n    id: i64\n}\n\nstruct Handler {\n    user: Usre\n};\n",
+    )
+    .ok();
+
+    let output = run_fix_command(&temp_dir);
+    let fixed = std::fs::read_to_string(temp_dir.join("schema/types.ks")).unwrap_or_default();
+
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    assert!(
+        output.success(),
+        "fix run failed:\nstdout: {}\nstderr: {}",
+        output.stdout,
+        output.stderr
+    );
+    assert_eq!(
+        fixed,
+        "namespace types;\n\nstruct User {\n    id: i64\n};\n\nstruct Handler {\n    user: User\n};\n"
+    );
+    assert!(
+        output.stderr.contains("applied 1 fix(es)"),
+        "{}",
+        output.stderr
+    );
+}
+
+/// `sql` writes the DDL of the root package's structs, one file per namespace
+#[tokio::test]
+async fn integration_sql_command() {
+    use kintsu_test_suite::cli_tests::run_sql_command;
+
+    let temp_dir = PathBuf::from("./tmp/cli_test_integration_sql");
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    std::fs::create_dir_all(temp_dir.join("schema")).ok();
+    std::fs::write(temp_dir.join("schema.toml"), minimal_manifest("sql-test")).ok();
+    std::fs::write(
+        temp_dir.join("schema/lib.ks"),
+        "namespace sql_test;\nnamespace types {\n    struct Order { id: i64, note?: str, status: Status };\n    enum Status { OPEN = 1, CLOSED = 2 };\n    operation close(id: i64) -> bool;\n};",
+    )
+    .ok();
+
+    let output = run_sql_command(&temp_dir, &temp_dir.join("sql"), "sqlite");
+    let ddl = std::fs::read_to_string(temp_dir.join("sql/types.sql")).unwrap_or_default();
+
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    assert!(
+        output.success(),
+        "sql run failed:\nstdout: {}\nstderr: {}",
+        output.stdout,
+        output.stderr
+    );
+    assert!(
+        ddl.contains(
+            "CREATE TABLE \"order\" (\n    \"id\" INTEGER NOT NULL,\n    \"note\" TEXT,\n    \"status\" INTEGER NOT NULL CHECK (\"status\" IN (1, 2))\n);"
+        ),
+        "{ddl}"
+    );
+    assert!(
+        output
+            .stderr
+            .contains("warning: skipped types::close: operations have no table mapping"),
+        "{}",
+        output.stderr
+    );
+}
+
+/// `coverage` reports the documented share of each namespace, with what is undocumented
+#[tokio::test]
+async fn integration_coverage_command() {
+    use kintsu_test_suite::cli_tests::run_coverage_command;
+
+    let temp_dir = PathBuf::from("./tmp/cli_test_integration_coverage");
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    std::fs::create_dir_all(temp_dir.join("schema")).ok();
+    std::fs::write(
+        temp_dir.join("schema.toml"),
+        minimal_manifest("coverage-test"),
+    )
+    .ok();
+    std::fs::write(
+        temp_dir.join("schema/lib.ks"),
+        "namespace coverage_test;\nnamespace types {\n    /// An order.\n    struct Order {\n        /// The id.\n        id: i64,\n        note?: str\n    };\n    operation close(id: i64) -> bool;\n};",
+    )
+    .ok();
+
+    let output = run_coverage_command(&temp_dir);
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    assert!(
+        output.success(),
+        "coverage run failed:\nstdout: {}\nstderr: {}",
+        output.stdout,
+        output.stderr
+    );
+    // debug builds print the compilation groups before the report
+    let json = &output.stdout[output.stdout.find('{').unwrap_or(0)..];
+    let coverage: serde_json::Value = serde_json::from_str(json).unwrap();
+    let types = &coverage["namespaces"]["types"];
+    assert_eq!(types["types"]["documented"], 1);
+    assert_eq!(types["fields"]["total"], 3);
+    assert_eq!(types["operations"]["documented"], 0);
+    assert_eq!(
+        types["undocumented"],
+        serde_json::json!(["Order.note", "close", "close.id"])
+    );
+}
+
+/// `generate graphql` writes object types per namespace and the root operation types
+#[tokio::test]
+async fn integration_graphql_command() {
+    use kintsu_test_suite::cli_tests::run_graphql_command;
+
+    let temp_dir = PathBuf::from("./tmp/cli_test_integration_graphql");
+    let _ = std::fs::remove_dir_all(&temp_dir);
+
+    std::fs::create_dir_all(temp_dir.join("schema")).ok();
+    std::fs::write(
+        temp_dir.join("schema.toml"),
+        minimal_manifest("graphql-test"),
+    )
+    .ok();
+    std::fs::write(
+        temp_dir.join("schema/lib.ks"),
+        "namespace graphql_test;\nnamespace types {\n    struct Order { id: i32, note?: str, status: Status };\n    enum Status { OPEN = 1, CLOSED = 2 };\n    operation get_order(id: i32) -> Order;\n    #[graphql(mutation)]\n    operation get_or_create(order: Order) -> Order;\n    operation close(id: i32) -> bool;\n};",
+    )
+    .ok();
+
+    let output = run_graphql_command(&temp_dir, &temp_dir.join("graphql"));
+    let types = std::fs::read_to_string(temp_dir.join("graphql/types.graphql")).unwrap_or_default();
+    let schema =
+        std::fs::read_to_string(temp_dir.join("graphql/_schema.graphql")).unwrap_or_default();
+
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    assert!(
+        output.success(),
+        "graphql run failed:\nstdout: {}\nstderr: {}",
+        output.stdout,
+        output.stderr
+    );
+    assert!(
+        types.contains("type Order {\n  id: Int!\n  note: String\n  status: Status!\n}"),
+        "{types}"
+    );
+    assert!(types.contains("input OrderInput {"), "{types}");
+    assert!(
+        schema.contains("type Query {\n  get_order(id: Int!): Order!\n}"),
+        "{schema}"
+    );
+    assert!(
+        schema.contains(
+            "type Mutation {\n  close(id: Int!): Boolean!\n  get_or_create(order: OrderInput!): Order!\n}"
+        ),
+        "{schema}"
+    );
+}
+
+#[tokio::test]
+async fn integration_init_list_templates() {
+    use kintsu_test_suite::cli_tests::run_init_command;
+
+    let output = run_init_command(&["--list-templates"]);
+    assert!(output.success(), "{}", output.stderr);
+    assert!(output.stdout.contains("service"), "{}", output.stdout);
+
+    // listing templates never creates a package, so it cannot be given a name
+    let temp_dir = PathBuf::from("./tmp/cli_test_init_list_templates");
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    let output = run_init_command(&[
+        "--list-templates",
+        "-n",
+        "listed",
+        "-d",
+        &temp_dir.to_string_lossy(),
+    ]);
+    let created = temp_dir.exists();
+    std::fs::remove_dir_all(&temp_dir).ok();
+
+    assert!(!output.success(), "{}", output.stdout);
+    assert!(
+        output.stderr.contains("cannot be used with"),
+        "{}",
+        output.stderr
+    );
+    assert!(!created);
+}
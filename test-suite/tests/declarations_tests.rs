@@ -0,0 +1,334 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use kintsu_fs::{FileSystem, memory};
+use kintsu_manifests::version::Version;
+use kintsu_parser::{
+    ctx::{
+        CompileCtx,
+        compile::resolver::{DeclarationResolver, PackageResolver, Resolver},
+    },
+    declare::{DeclarationBundle, DeclarationVersion},
+};
+
+const PKG_LIB: &str = "namespace pkg;\nnamespace orders {\n    use dep_pkg::data;\n    struct Order { item: data::Data, kind: data::Kind };\n};";
+
+fn manifest(
+    name: &str,
+    version: &str,
+    dependencies: &str,
+) -> String {
+    format!(
+        "version = \"v1\"\n[package]\nname = \"{name}\"\nversion = \"{version}\"\n\n[dependencies]\n{dependencies}"
+    )
+}
+
+fn sources() -> memory::MemoryFileSystem {
+    memory! {
+        "base/schema.toml" => manifest("base", "0.2.0", ""),
+        "base/schema/lib.ks" => "namespace base;\nnamespace money { struct Money { cents: i64 }; };",
+        "dep/schema.toml" => manifest("dep-pkg", "0.3.0", "base = { path = \"../base\" }\n"),
+        "dep/schema/lib.ks" => "namespace dep_pkg;\nuse data;\nuse other;\n",
+        "dep/schema/data.ks" => "#![version(2)]\n#![err(Boom)]\n/// the data\nnamespace data;\nuse base::money;\nuse dep_pkg::other;\n\n\
+            // a comment\n\
+            struct Data {\n\
+                /// id doc\n\
+                #[min(1)]\n\
+                id: i64,\n\
+                #[len(..4)]\n\
+                tags?: str[],\n\
+                price: money::Money,\n\
+                nested: { a: i32 },\n\
+                thing: other::Thing\n\
+            };\n\
+            enum Kind { A = 1 | 7, B = 2 };\n\
+            enum Plain { X, Y };\n\
+            enum Color { Red = \"red\" };\n\
+            const MAX: u32 = 4;\n\
+            #[tag(adjacent, type_hint = false)]\n\
+            oneof Event { Created(i32), Moved { to: str }, Dropped };\n\
+            type Key = oneof i32 | str;\n\
+            error Boom { Bad(str), Worse { why: str } };\n\
+            #[http(method = \"get\", path = \"/data/{id}\")]\n\
+            #[graphql(query)]\n\
+            #[example(input = '{\"id\": 1}')]\n\
+            operation get_data(#[example('3')] id: i64) -> Data!;",
+        "dep/schema/other.ks" => "namespace other;\nstruct Thing { v: i32[4] };\nstruct Pair { a: i32, b: str };\ntype First = Pick[Pair, a];",
+        "pkg/schema.toml" => manifest("pkg", "1.0.0", "dep-pkg = { path = \"../dep\" }\n"),
+        "pkg/schema/lib.ks" => PKG_LIB,
+    }
+}
+
+fn versions() -> BTreeMap<String, Version> {
+    BTreeMap::from([
+        ("base".to_string(), Version::new(0, 2, 0)),
+        ("dep-pkg".to_string(), Version::new(0, 3, 0)),
+        ("pkg".to_string(), Version::new(1, 0, 0)),
+    ])
+}
+
+async fn compile(
+    fs: memory::MemoryFileSystem,
+    declarations: Option<DeclarationBundle>,
+) -> kintsu_parser::Result<CompileCtx> {
+    kintsu_testing::logging();
+    let fs: Arc<dyn FileSystem> = Arc::new(fs);
+    let mut resolver: Arc<dyn PackageResolver> = Arc::new(Resolver::new(fs.clone()));
+    if let Some(bundle) = declarations {
+        resolver = Arc::new(DeclarationResolver::new(resolver).with_bundle(bundle, &versions())?);
+    }
+    CompileCtx::with_fs_and_config(fs, resolver, "pkg", 2, false).await
+}
+
+async fn emit(ctx: &CompileCtx) -> DeclarationBundle {
+    let DeclarationVersion::V1(bundle) = ctx.emit_declarations().await.unwrap();
+    bundle
+}
+
+#[tokio::test]
+async fn test_dependencies_compile_from_their_declarations() {
+    let compiled = emit(&compile(sources(), None).await.unwrap()).await;
+    assert_eq!(
+        compiled
+            .dependencies
+            .keys()
+            .collect::<Vec<_>>(),
+        ["base", "dep_pkg"]
+    );
+
+    // only the root package has sources, its dependencies come from the declarations
+    let fs = memory! {
+        "pkg/schema.toml" => manifest("pkg", "1.0.0", "dep-pkg = { path = \"../dep\" }\n"),
+        "pkg/schema/lib.ks" => PKG_LIB,
+    };
+    let ctx = compile(fs, Some(compiled.clone()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ctx.get_dependency("base")
+            .await
+            .unwrap()
+            .package
+            .package()
+            .version
+            .to_string(),
+        "0.2.0"
+    );
+    assert_eq!(emit(&ctx).await, compiled);
+}
+
+#[tokio::test]
+async fn test_declarations_must_satisfy_the_requirement() {
+    let compiled = emit(&compile(sources(), None).await.unwrap()).await;
+
+    let fs = memory! {
+        "pkg/schema.toml" => manifest("pkg", "1.0.0", "dep-pkg = { version = \"^0.4.0\" }\n"),
+        "pkg/schema/lib.ks" => PKG_LIB,
+    };
+    let err = compile(fs, Some(compiled))
+        .await
+        .err()
+        .unwrap();
+
+    assert!(
+        err.to_string()
+            .contains("only available for 0.3.0"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn test_bundle_needs_a_version_for_every_package() {
+    let compiled = emit(&compile(sources(), None).await.unwrap()).await;
+    let resolver: Arc<dyn PackageResolver> = Arc::new(Resolver::new(Arc::new(sources())));
+
+    let mut versions = versions();
+    versions.remove("base");
+
+    assert!(
+        DeclarationResolver::new(resolver)
+            .with_bundle(compiled, &versions)
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_doc_comments_are_declared_on_their_items() {
+    let fs = memory! {
+        "pkg/schema.toml" => manifest("pkg", "1.0.0", ""),
+        "pkg/schema/lib.ks" => "namespace pkg;\nuse types;\n",
+        "pkg/schema/types.ks" => "// Copyright the pkg authors\n\n\
+            /// The types of the package\n\
+            namespace types;\n\n\
+            // not documentation\n\
+            /// A user\n\
+            struct User {\n\
+                /// Their id\n\
+                id: i64\n\
+            };\n\
+            oneof Lookup {\n\
+                /// By [User.id]\n\
+                ById(i64)\n\
+            };\n\
+            /// Finds a [User]\n\
+            operation find(\n\
+                /// How to find them\n\
+                by: Lookup\n\
+            ) -> User;",
+    };
+    let bundle = emit(&compile(fs, None).await.unwrap()).await;
+    let ns = &bundle.root.namespaces["types"];
+    let docs = |comments: &kintsu_parser::declare::DeclComment| {
+        comments
+            .docs()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(docs(&ns.comments), ["The types of the package"]);
+    let find = |name: &str| {
+        ns.types
+            .iter()
+            .find(|def| def.name() == name)
+            .unwrap()
+    };
+    assert_eq!(docs(find("User").comments()), ["A user"]);
+    assert_eq!(
+        find("User")
+            .members()
+            .into_iter()
+            .map(|(name, comments)| (name.to_string(), docs(comments)))
+            .collect::<Vec<_>>(),
+        [("id".to_string(), vec!["Their id".to_string()])]
+    );
+    assert_eq!(docs(find("Lookup").members()[0].1), ["By [User.id]"]);
+    assert_eq!(docs(find("find").comments()), ["Finds a [User]"]);
+    assert_eq!(docs(find("find").members()[0].1), ["How to find them"]);
+}
+
+fn profiled_sources() -> memory::MemoryFileSystem {
+    let profiles = "\n[profiles.public]\nexclude = [\"admin\", \"users::purge\"]\n\n\
+        [profiles.internal]\ninternal = true\n\n\
+        [profiles.broken]\nexclude = [\"users::User\"]\n";
+    memory! {
+        "pkg/schema.toml" => format!("{}{profiles}", manifest("pkg", "1.0.0", "")),
+        "pkg/schema/lib.ks" => "namespace pkg;\nuse users;\nuse admin;\n",
+        "pkg/schema/users.ks" => "namespace users;\n\
+            struct User { id: i64 };\n\
+            internal struct Audit { by: i64 };\n\
+            operation get_user(id: i64) -> User;\n\
+            operation purge() -> i64;",
+        "pkg/schema/admin.ks" => "namespace admin;\nuse pkg::users;\nstruct Ban { user: users::User };",
+    }
+}
+
+fn names(
+    bundle: &DeclarationBundle,
+    ns: &str,
+) -> Vec<String> {
+    bundle.root.namespaces[ns]
+        .types
+        .iter()
+        .map(|def| def.name().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_profiles_select_the_emitted_surface() {
+    let ctx = compile(profiled_sources(), None)
+        .await
+        .unwrap();
+    let profile = |name: &'static str| {
+        let ctx = &ctx;
+        async move {
+            let DeclarationVersion::V1(bundle) = ctx
+                .emit_profile_declarations(name)
+                .await
+                .unwrap();
+            bundle
+        }
+    };
+
+    let all = emit(&ctx).await;
+    assert_eq!(names(&all, "users"), ["User", "get_user", "purge"]);
+    assert!(all.root.namespaces.contains_key("admin"));
+
+    let public = profile("public").await;
+    assert_eq!(names(&public, "users"), ["User", "get_user"]);
+    assert!(!public.root.namespaces.contains_key("admin"));
+
+    let internal = profile("internal").await;
+    assert_eq!(
+        names(&internal, "users"),
+        ["Audit", "User", "get_user", "purge"]
+    );
+}
+
+#[tokio::test]
+async fn test_profiles_cannot_exclude_referenced_items() {
+    let ctx = compile(profiled_sources(), None)
+        .await
+        .unwrap();
+
+    let err = ctx
+        .emit_profile_declarations("broken")
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("profile 'broken' excludes 'users::User', which 'admin::Ban' refers to"),
+        "{err}"
+    );
+
+    let err = ctx
+        .emit_profile_declarations("partner")
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("no profile named 'partner'"),
+        "{err}"
+    );
+}
+
+struct RequireNamespace(&'static str);
+
+impl kintsu_parser::ctx::compile::CompileHook for RequireNamespace {
+    fn name(&self) -> &str {
+        "require-namespace"
+    }
+
+    fn after_emit(
+        &self,
+        bundle: &DeclarationBundle,
+    ) -> Result<(), String> {
+        match bundle.root.namespaces.contains_key(self.0) {
+            true => Ok(()),
+            false => Err(format!("'{}' is not declared", self.0)),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_hooks_run_after_emission() {
+    let mut ctx = compile(profiled_sources(), None)
+        .await
+        .unwrap();
+    ctx.add_hook(RequireNamespace("admin"));
+    assert!(ctx.emit_declarations().await.is_ok());
+
+    // the public profile leaves the namespace out
+    let err = ctx
+        .emit_profile_declarations("public")
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("hook 'require-namespace' failed: 'admin' is not declared"),
+        "{err}"
+    );
+}
@@ -15,15 +15,9 @@ compiler_test! {
             "pkg/schema/lib.ks" => include_str!("../fragments/missing_dep_use.ks"),
         }
     },
+    expect_codes: ["KNS4001"],
     assertions: |_, err: kintsu_parser::Error| {
-        let err_msg = format!("{:?}", err);
-        assert!(
-            err_msg.contains("external_pkg")
-                || err_msg.contains("not found")
-                || err_msg.contains("dependency"),
-            "Error should mention missing dependency: {}",
-            err_msg
-        );
+        diagnostics::assert_error_at(&err, "KNS4001", "pkg/schema/lib.ks", "use external_pkg");
     }
 }
 
@@ -181,13 +175,9 @@ compiler_test! {
             "pkg/schema/lib.ks" => include_str!("../fragments/missing_namespace_use.ks"),
         }
     },
+    expect_codes: ["KNS4001"],
     assertions: |_, err: kintsu_parser::Error| {
-        let err_msg = format!("{:?}", err);
-        assert!(
-            err_msg.contains("missing_namespace") || err_msg.contains("not found"),
-            "Error should mention missing namespace: {}",
-            err_msg
-        );
+        diagnostics::assert_error_at(&err, "KNS4001", "pkg/schema/lib.ks", "use missing_namespace");
     }
 }
 
@@ -204,15 +194,31 @@ compiler_test! {
             "pkg/schema/lib.ks" => include_str!("../fragments/undefined_type.ks"),
         }
     },
+    expect_codes: ["KTR1002"],
     assertions: |_, err: kintsu_parser::Error| {
-        let err_msg = format!("{:?}", err);
-        assert!(
-            err_msg.contains("UndefinedType")
-                || err_msg.contains("not found")
-                || err_msg.contains("undefined"),
-            "Error should mention undefined type: {}",
-            err_msg
-        );
+        diagnostics::assert_error_at(&err, "KTR1002", "pkg/schema/lib.ks", "UndefinedType");
+    }
+}
+
+compiler_test! {
+    id: compile_fail_ambiguous_glob_import,
+    name: "Ambiguous Glob Import",
+    purpose: "Reject a name exported by more than one glob import",
+    expect_pass: false,
+    tags: vec![Tag::Validations, Tag::Imports],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/ambiguous_glob_lib.ks"),
+            "pkg/schema/consumer.ks" => include_str!("../fragments/ambiguous_glob_use.ks"),
+            "pkg/schema/bar.ks" => include_str!("../fragments/bar_enum.ks"),
+            "pkg/schema/baz.ks" => include_str!("../fragments/baz_enum.ks"),
+        }
+    },
+    expect_codes: ["KTR3001"],
+    assertions: |_, err: kintsu_parser::Error| {
+        diagnostics::assert_error_at(&err, "KTR3001", "pkg/schema/consumer.ks", "Foo");
     }
 }
 
@@ -229,15 +235,10 @@ compiler_test! {
             "pkg/schema/lib.ks" => include_str!("../fragments/duplicate_type.ks"),
         }
     },
+    expect_codes: ["KTY3001"],
     assertions: |_, err: kintsu_parser::Error| {
-        let err_msg = format!("{:?}", err);
-        assert!(
-            err_msg.contains("duplicate")
-                || err_msg.contains("Foo")
-                || err_msg.contains("already defined"),
-            "Error should mention duplicate type: {}",
-            err_msg
-        );
+        // points at the second declaration
+        diagnostics::assert_error_span(&err, "KTY3001", 74..77);
     }
 }
 
@@ -254,8 +255,9 @@ compiler_test! {
             "pkg/schema/lib.ks" => include_str!("../fragments/invalid_enum_discriminant.ks"),
         }
     },
+    expect_codes: ["KLX9001"],
     assertions: |_, err: kintsu_parser::Error| {
-        // Just verify compilation failed as expected
+        diagnostics::assert_error_at(&err, "KLX9001", "pkg/schema/lib.ks", ".");
     }
 }
 
@@ -272,6 +274,7 @@ compiler_test! {
             "pkg/schema/lib.ks" => "namespace pkg;",
         }
     },
+    expect_codes: ["KPK9001"],
     assertions: |_, err: kintsu_parser::Error| {
         let err_msg = format!("{:?}", err);
         assert!(
@@ -296,11 +299,46 @@ compiler_test! {
             "pkg/schema/internal.ks" => include_str!("../fragments/internal_namespace.ks"),
         }
     },
+    expect_codes: ["KLX9001"],
     assertions: |_, err: kintsu_parser::Error| {
-        let err_msg = format!("{:?}", err);
-        assert!(
-            !err_msg.is_empty(),
-            "Should produce an error for incorrect import syntax"
-        );
+        diagnostics::assert_error_at(&err, "KLX9001", "pkg/schema/lib.ks", ";");
+    }
+}
+
+compiler_test! {
+    id: compile_fail_generated_struct_name_taken,
+    name: "Generated Struct Name Taken",
+    purpose: "Report a declared type named like a lifted anonymous struct at the anonymous struct",
+    expect_pass: false,
+    tags: vec![Tag::Validations],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => "namespace pkg;\nnamespace foo {\n    struct Holder { inner: { x: i64 } };\n    struct HolderInner { y: i64 };\n};",
+        }
+    },
+    expect_codes: ["KTY3002"],
+    assertions: |_, err: kintsu_parser::Error| {
+        diagnostics::assert_error_at(&err, "KTY3002", "pkg/schema/lib.ks", "{ x: i64 }");
+    }
+}
+
+compiler_test! {
+    id: compile_fail_merged_union_name_taken,
+    name: "Merged Union Name Taken",
+    purpose: "Report a declared type named like a merged union at the union expression",
+    expect_pass: false,
+    tags: vec![Tag::Validations],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => "namespace pkg;\nnamespace foo {\n    struct User { id: i64 };\n    struct Permissions { role: str };\n    struct Request { auth: User & Permissions };\n    struct RequestAuth { token: str };\n};",
+        }
+    },
+    expect_codes: ["KTY3002"],
+    assertions: |_, err: kintsu_parser::Error| {
+        diagnostics::assert_error_at(&err, "KTY3002", "pkg/schema/lib.ks", "User & Permissions");
     }
 }
@@ -23,7 +23,7 @@ async fn compile_linear_dependency_chain() {
     )
     .with_root(g.root_package());
 
-    let ctx = harness.compile_pass().await;
+    let _ctx = harness.compile_pass().await;
     harness.assert_lockfile_written();
 }
 
@@ -183,11 +183,7 @@ async fn compile_linear_with_operations_and_errors() {
     )
     .unwrap();
 
-    std::fs::write(
-        "declarations.json",
-        serde_json::to_string_pretty(&ctx.emit_declarations().await.unwrap()).unwrap(),
-    )
-    .unwrap();
+    harness.assert_declarations_snapshot();
     harness.assert_lockfile_written();
 }
 
@@ -228,3 +224,46 @@ async fn compile_multi_chain_root() {
 
     harness.assert_lockfile_written();
 }
+
+#[tokio::test]
+async fn compile_random_schemas_deterministically() {
+    use kintsu_test_suite::many::{SchemaGenerator, SchemaOptions, populate_random_schema};
+
+    for seed in 0..16 {
+        let options = SchemaOptions {
+            namespaces: 5,
+            types: 12,
+            union_density: 0.2,
+            type_expr_density: 0.25,
+            seed,
+            ..Default::default()
+        };
+        assert_eq!(
+            SchemaGenerator::new(options.clone()).generate(),
+            SchemaGenerator::new(options.clone()).generate(),
+            "seed {seed} generated different schemas"
+        );
+
+        let mut fs = MemFs::new();
+        populate_random_schema(&mut fs, options);
+        let fs = std::sync::Arc::new(fs);
+
+        let mut emitted = vec![];
+        for _ in 0..2 {
+            let ctx = compile_pass(fs.clone(), "pkg")
+                .await
+                .unwrap_or_else(|err| {
+                    fs.debug_print_files();
+                    panic!(
+                        "seed {seed}: {:#?}",
+                        kintsu_test_suite::diagnostics::reported_errors(&err)
+                    )
+                });
+            emitted.push(ctx.emit_declarations().await.unwrap());
+        }
+        assert_eq!(
+            emitted[0], emitted[1],
+            "seed {seed} emitted different declarations"
+        );
+    }
+}
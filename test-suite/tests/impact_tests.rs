@@ -0,0 +1,115 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use kintsu_fs::memory;
+use kintsu_parser::ctx::{CompileCtx, TypeUsage, UsageKind};
+
+async fn compile() -> CompileCtx {
+    kintsu_testing::logging();
+    let fs = memory! {
+        "dep/schema.toml" => "version = \"v1\"\n[package]\nname = \"dep\"\nversion = \"0.3.0\"\n",
+        "dep/schema/lib.ks" => "namespace dep;\nnamespace data { struct Data { id: i64 }; struct Unrelated { v: i32 }; };",
+        "pkg/schema.toml" => "version = \"v1\"\n[package]\nname = \"pkg\"\nversion = \"1.0.0\"\n[dependencies]\ndep = { path = \"../dep\" }\n",
+        "pkg/schema/lib.ks" => "namespace pkg;\n\
+            namespace foo {\n\
+                use dep::data;\n\
+                struct Wrapper { data: data::Data, note?: str };\n\
+                type Alias = Wrapper;\n\
+                oneof Event { Created(Alias), Deleted { id: i64 } };\n\
+                operation get_wrapper(id: i64) -> Wrapper;\n\
+                operation emit(event: Event) -> bool;\n\
+                operation ping(id: i64) -> bool;\n\
+            };",
+    };
+
+    CompileCtx::with_fs(Arc::new(fs), "pkg")
+        .await
+        .unwrap()
+}
+
+fn usage(
+    item: &str,
+    kind: UsageKind,
+    path: &[&str],
+    uses: &str,
+) -> TypeUsage {
+    TypeUsage {
+        item: item.to_string(),
+        kind,
+        path: path
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        uses: uses.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_impact_of_dependency_type() {
+    let ctx = compile().await;
+    let impact = ctx
+        .impact_of("dep::data::Data")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        impact.usages,
+        vec![
+            usage(
+                "pkg::foo::Alias",
+                UsageKind::Alias,
+                &[],
+                "pkg::foo::Wrapper"
+            ),
+            usage(
+                "pkg::foo::Event",
+                UsageKind::OneOf,
+                &["Created"],
+                "pkg::foo::Alias"
+            ),
+            usage(
+                "pkg::foo::Wrapper",
+                UsageKind::Struct,
+                &["data"],
+                "dep::data::Data"
+            ),
+            usage(
+                "pkg::foo::emit",
+                UsageKind::Operation,
+                &["param:event"],
+                "pkg::foo::Event"
+            ),
+            usage(
+                "pkg::foo::get_wrapper",
+                UsageKind::Operation,
+                &["return"],
+                "pkg::foo::Wrapper"
+            ),
+        ]
+    );
+    assert_eq!(
+        impact.operations(),
+        BTreeSet::from(["pkg::foo::emit", "pkg::foo::get_wrapper"])
+    );
+}
+
+#[tokio::test]
+async fn test_impact_of_unused_type() {
+    let ctx = compile().await;
+    let impact = ctx
+        .impact_of("dep::data::Unrelated")
+        .await
+        .unwrap();
+
+    assert!(impact.usages.is_empty());
+}
+
+#[tokio::test]
+async fn test_impact_of_unknown_type() {
+    let ctx = compile().await;
+
+    assert!(
+        ctx.impact_of("pkg::foo::Missing")
+            .await
+            .is_err()
+    );
+}
@@ -309,3 +309,108 @@ dep = { path = "../dep" }
         "Both compilations should have operations"
     );
 }
+
+fn release_package(schema: &str) -> kintsu_fs::memory::MemoryFileSystem {
+    memory! {
+        "pkg/schema.toml" => "version = \"v1\"\n[package]\nname = \"pkg\"\nversion = \"1.0.0\"\n",
+        "pkg/schema/lib.ks" => schema,
+    }
+}
+
+fn read(
+    fs: &kintsu_fs::memory::MemoryFileSystem,
+    path: &str,
+) -> Option<String> {
+    fs.get_file_content(std::path::Path::new(path))
+        .map(|content| String::from_utf8(content).unwrap())
+}
+
+async fn bump_version(
+    fs: &kintsu_fs::memory::MemoryFileSystem,
+    options: kintsu_parser::ctx::BumpOptions,
+) -> kintsu_parser::Result<kintsu_parser::ctx::VersionBump> {
+    let shared: std::sync::Arc<dyn kintsu_fs::FileSystem> = std::sync::Arc::new(fs.clone());
+    CompileCtx::bump_version(
+        shared.clone(),
+        std::sync::Arc::new(kintsu_parser::ctx::compile::resolver::Resolver::new(shared)),
+        "pkg",
+        options,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_release_bump_rewrites_and_tags_lockfile() {
+    let fs = release_package("namespace pkg;\nnamespace foo { struct User { id: i64 }; };");
+
+    let bumped = bump_version(
+        &fs,
+        kintsu_parser::ctx::BumpOptions {
+            bump: kintsu_manifests::version::Bump::Minor,
+            published: None,
+            tag: true,
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(bumped.version, "1.1.0");
+    assert!(
+        read(&fs, "pkg/schema.toml")
+            .unwrap()
+            .contains("version = \"1.1.0\"")
+    );
+    let lockfile = read(&fs, "pkg/schema.lock.toml").unwrap();
+    assert!(lockfile.contains("version = \"1.1.0\""), "{lockfile}");
+    assert_eq!(
+        read(&fs, "pkg/releases/v1.1.0.schema.lock.toml").as_ref(),
+        Some(&lockfile)
+    );
+}
+
+#[tokio::test]
+async fn test_release_bump_checked_against_published_declarations() {
+    let published = {
+        let fs = release_package(
+            "namespace pkg;\nnamespace foo { struct User { id: i64 }; struct Team { id: i64 }; };",
+        );
+        CompileCtx::with_fs(std::sync::Arc::new(fs), "pkg")
+            .await
+            .unwrap()
+            .emit_declarations()
+            .await
+            .unwrap()
+    };
+    let fs = release_package("namespace pkg;\nnamespace foo { struct User { id: i64 }; };");
+    let options = |bump| {
+        kintsu_parser::ctx::BumpOptions {
+            bump,
+            published: Some(published.clone()),
+            tag: false,
+        }
+    };
+
+    let err = bump_version(&fs, options(kintsu_manifests::version::Bump::Minor))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("needs at least a major bump"),
+        "{err}"
+    );
+    assert!(
+        read(&fs, "pkg/schema.toml")
+            .unwrap()
+            .contains("version = \"1.0.0\"")
+    );
+
+    let bumped = bump_version(&fs, options(kintsu_manifests::version::Bump::Major))
+        .await
+        .unwrap();
+    assert_eq!(bumped.version, "2.0.0");
+    assert_eq!(
+        bumped.required,
+        Some(kintsu_manifests::version::Bump::Major)
+    );
+    assert_eq!(bumped.removed, vec!["foo::Team"]);
+}
@@ -0,0 +1,262 @@
+use kintsu_fs::memory;
+use kintsu_manifests::version::Version;
+use kintsu_parser::ctx::DependencySource;
+use kintsu_test_suite::{registry::MemoryRegistry, *};
+
+fn manifest(
+    name: &str,
+    version: &str,
+    dependencies: &str,
+) -> String {
+    format!(
+        "version = \"v1\"\n[package]\nname = \"{name}\"\nversion = \"{version}\"\n\n[dependencies]\n{dependencies}"
+    )
+}
+
+/// `dep` at 1.0.0, 1.1.0 (with `Extra`) and 2.0.0, and `base` which 2.0.0 depends on.
+fn registry() -> MemoryRegistry {
+    MemoryRegistry::from_fixtures(&memory! {
+        "dep/1.0.0/schema.toml" => manifest("dep", "1.0.0", ""),
+        "dep/1.0.0/schema/lib.ks" => "namespace dep;\nnamespace data { struct Data { id: i64 }; };",
+        "dep/1.1.0/schema.toml" => manifest("dep", "1.1.0", ""),
+        "dep/1.1.0/schema/lib.ks" => "namespace dep;\nnamespace data { struct Data { id: i64 }; struct Extra { v: str }; };",
+        "dep/2.0.0/schema.toml" => manifest("dep", "2.0.0", "base = { version = \"^0.1\" }\n"),
+        "dep/2.0.0/schema/lib.ks" => "namespace dep;\nnamespace data { use base::money; struct Data { price: money::Money }; };",
+        "base/schema.toml" => manifest("base", "0.1.0", ""),
+        "base/schema/lib.ks" => "namespace base;\nnamespace money { struct Money { cents: i64 }; };",
+    })
+    .unwrap()
+}
+
+fn harness(
+    id: &str,
+    purpose: &str,
+    requirement: &str,
+    expect_pass: bool,
+) -> TestHarness {
+    let fs = memory! {
+        "pkg/schema.toml" => manifest("pkg", "1.0.0", &format!("dep = {{ version = \"{requirement}\" }}\n")),
+        "pkg/schema/lib.ks" => "namespace pkg;\nnamespace foo { use dep::data;\nstruct Wrapper { data: data::Data }; };",
+    };
+    TestHarness::with_metadata(
+        fs,
+        id,
+        id,
+        purpose,
+        expect_pass,
+        vec![Tag::Dependencies, Tag::VersionResolution],
+    )
+}
+
+async fn dependency_version(ctx: &CompileCtx) -> String {
+    ctx.get_dependency("dep")
+        .await
+        .unwrap()
+        .package
+        .package()
+        .version
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_registry_serves_highest_matching_version() {
+    let mut harness = harness(
+        "test_registry_serves_highest_matching_version",
+        "Registry dependencies resolve to the highest published version matching them",
+        "^1.0",
+        true,
+    )
+    .with_registry(registry());
+
+    let ctx = harness.compile_pass().await;
+    assert_eq!(dependency_version(&ctx).await, "1.1.0");
+    harness.assert_lockfile_contains("1.1.0");
+}
+
+#[tokio::test]
+async fn test_registry_resolves_transitive_dependencies() {
+    let mut harness = harness(
+        "test_registry_resolves_transitive_dependencies",
+        "Registry dependencies of registry packages are served from the same registry",
+        "^2",
+        true,
+    )
+    .with_registry(registry());
+
+    let ctx = harness.compile_pass().await;
+    assert_eq!(dependency_version(&ctx).await, "2.0.0");
+    assert!(ctx.get_dependency("base").await.is_some());
+}
+
+#[tokio::test]
+async fn test_registry_skips_yanked_versions() {
+    let mut harness = harness(
+        "test_registry_skips_yanked_versions",
+        "Yanked versions are not chosen for new resolutions",
+        "^1.0",
+        true,
+    )
+    .with_registry(registry().with_yanked("dep", Version::new(1, 1, 0)));
+
+    let ctx = harness.compile_pass().await;
+    assert_eq!(dependency_version(&ctx).await, "1.0.0");
+}
+
+#[tokio::test]
+async fn test_registry_without_matching_version() {
+    let mut harness = harness(
+        "test_registry_without_matching_version",
+        "Requirements no published version satisfies fail to resolve",
+        "^3",
+        false,
+    )
+    .with_registry(registry());
+
+    harness.compile_fail_with(&["KPK6003"]).await;
+}
+
+fn read(
+    fs: &kintsu_fs::memory::MemoryFileSystem,
+    path: &str,
+) -> String {
+    String::from_utf8(
+        fs.get_file_content(std::path::Path::new(path))
+            .unwrap(),
+    )
+    .unwrap()
+}
+
+/// `pkg`, without dependencies, using `dep` unless `standalone`.
+fn package(standalone: bool) -> kintsu_fs::memory::MemoryFileSystem {
+    let schema = if standalone {
+        "namespace pkg;\nnamespace foo { struct Wrapper { id: i64 }; };"
+    } else {
+        "namespace pkg;\nnamespace foo { use dep::data;\nstruct Wrapper { data: data::Data }; };"
+    };
+    memory! {
+        "pkg/schema.toml" => manifest("pkg", "1.0.0", ""),
+        "pkg/schema/lib.ks" => schema,
+    }
+}
+
+#[tokio::test]
+async fn test_add_dependency_locks_latest_version() {
+    let fs = package(false);
+    let registry = registry().with_yanked("dep", Version::new(2, 0, 0));
+    let shared: std::sync::Arc<dyn kintsu_fs::FileSystem> = std::sync::Arc::new(fs.clone());
+
+    let added = CompileCtx::add_dependency(
+        shared.clone(),
+        registry.resolver(shared),
+        "pkg",
+        "dep",
+        DependencySource::Registry(None),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(added.requirement.as_deref(), Some("^1.1.0"));
+    assert_eq!(added.version, "1.1.0");
+    assert_eq!(added.to_string(), "added dep ^1.1.0 (resolved 1.1.0)");
+    assert!(
+        read(&fs, "pkg/schema.toml").ends_with("[dependencies]\ndep = { version = \"^1.1.0\" }\n")
+    );
+    assert!(read(&fs, "pkg/schema.lock.toml").contains("dep@1.1.0"));
+}
+
+#[tokio::test]
+async fn test_add_dependency_with_requirement() {
+    let fs = package(false);
+    let registry = registry();
+    let shared: std::sync::Arc<dyn kintsu_fs::FileSystem> = std::sync::Arc::new(fs.clone());
+
+    let added = CompileCtx::add_dependency(
+        shared.clone(),
+        registry.resolver(shared),
+        "pkg",
+        "dep",
+        DependencySource::Registry(Some("~1.0".parse().unwrap())),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(added.requirement.as_deref(), Some("~1.0"));
+    assert_eq!(added.version, "1.0.0");
+    assert!(read(&fs, "pkg/schema.lock.toml").contains("dep@1.0.0"));
+}
+
+#[tokio::test]
+async fn test_add_unpublished_dependency_keeps_manifest() {
+    let fs = package(false);
+    let before = read(&fs, "pkg/schema.toml");
+    let shared: std::sync::Arc<dyn kintsu_fs::FileSystem> = std::sync::Arc::new(fs.clone());
+
+    let err = CompileCtx::add_dependency(
+        shared.clone(),
+        registry().resolver(shared),
+        "pkg",
+        "dep",
+        DependencySource::Registry(Some("^3".parse().unwrap())),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(err.to_string().contains("dep"), "{err}");
+    assert_eq!(read(&fs, "pkg/schema.toml"), before);
+}
+
+#[tokio::test]
+async fn test_remove_dependency() {
+    let fs = package(true);
+    let registry = registry();
+    let shared: std::sync::Arc<dyn kintsu_fs::FileSystem> = std::sync::Arc::new(fs.clone());
+
+    let added = CompileCtx::add_dependency(
+        shared.clone(),
+        registry.resolver(shared.clone()),
+        "pkg",
+        "dep",
+        DependencySource::Registry(Some("^1".parse().unwrap())),
+    )
+    .await
+    .unwrap();
+    // solved without being imported
+    assert_eq!(added.version, "1.1.0");
+
+    for declared in [true, false] {
+        let removed = CompileCtx::remove_dependency(
+            shared.clone(),
+            registry.resolver(shared.clone()),
+            "pkg",
+            "dep",
+        )
+        .await
+        .unwrap();
+        assert_eq!(removed, declared);
+    }
+    assert!(!read(&fs, "pkg/schema.toml").contains("dep ="));
+}
+
+#[tokio::test]
+async fn test_remove_imported_dependency_keeps_manifest() {
+    let fs = package(false);
+    let registry = registry();
+    let shared: std::sync::Arc<dyn kintsu_fs::FileSystem> = std::sync::Arc::new(fs.clone());
+
+    CompileCtx::add_dependency(
+        shared.clone(),
+        registry.resolver(shared.clone()),
+        "pkg",
+        "dep",
+        DependencySource::Registry(None),
+    )
+    .await
+    .unwrap();
+    let before = read(&fs, "pkg/schema.toml");
+
+    CompileCtx::remove_dependency(shared.clone(), registry.resolver(shared), "pkg", "dep")
+        .await
+        .unwrap_err();
+    assert_eq!(read(&fs, "pkg/schema.toml"), before);
+    assert!(read(&fs, "pkg/schema.lock.toml").contains("dep@2.0.0"));
+}
@@ -76,6 +76,180 @@ compiler_test! {
     }
 }
 
+compiler_test! {
+    id: compile_glob_import,
+    name: "Glob Import",
+    purpose: "Test resolving a type through a `use ns::*` glob import",
+    expect_pass: true,
+    tags: vec![Tag::Smoke, Tag::Imports],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/glob_lib.ks"),
+            "pkg/schema/consumer.ks" => include_str!("../fragments/glob_use.ks"),
+            "pkg/schema/bar.ks" => include_str!("../fragments/bar_enum.ks"),
+        }
+    },
+    assertions: |_, ctx: CompileCtx| {
+        assert_eq!(ctx.type_registry().all_types().len(), 2);
+    }
+}
+
+compiler_test! {
+    id: compile_constants,
+    name: "Constants",
+    purpose: "Test declaring constants and using them as sized array lengths",
+    expect_pass: true,
+    tags: vec![Tag::Smoke, Tag::Struct],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/use_limits.ks"),
+            "pkg/schema/limits.ks" => include_str!("../fragments/constants.ks"),
+        }
+    },
+    assertions: |harness, ctx: CompileCtx| {
+        assert_eq!(ctx.type_registry().all_types().len(), 1);
+
+        harness.assert_declarations_snapshot();
+        let decl = harness.declarations_json();
+        assert!(decl.contains(r#""name":"BUFFER_LEN","ty":"u32","value":16"#), "{decl}");
+        assert!(decl.contains(r#""size":16"#), "{decl}");
+    }
+}
+
+compiler_test! {
+    id: compile_binary_declarations,
+    name: "Binary Declarations",
+    purpose: "Test that emitted declarations survive the binary encoding unchanged and shrink",
+    expect_pass: true,
+    tags: vec![Tag::Smoke],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/use_limits.ks"),
+            "pkg/schema/limits.ks" => include_str!("../fragments/constants.ks"),
+        }
+    },
+    assertions: |harness, _ctx: CompileCtx| {
+        use kintsu_parser::declare::{
+            DeclarationVersion,
+            encoding::{DeclarationEncoding, detect},
+        };
+
+        let json = harness.declarations_json().into_bytes();
+        let decl = DeclarationVersion::decode(&json).unwrap();
+        assert_eq!(&decl, harness.declarations());
+
+        let binary = decl.encode(DeclarationEncoding::Binary).unwrap();
+        assert_eq!(detect(&binary), DeclarationEncoding::Binary);
+        assert!(binary.len() < json.len(), "{} >= {}", binary.len(), json.len());
+        assert_eq!(DeclarationVersion::decode(&binary).unwrap(), decl);
+    }
+}
+
+compiler_test! {
+    id: compile_recursive_types,
+    name: "Recursive Types",
+    purpose: "Test that types may recurse through optional fields and unsized arrays",
+    expect_pass: true,
+    tags: vec![Tag::Smoke, Tag::Struct],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/use_tree.ks"),
+            "pkg/schema/tree.ks" => include_str!("../fragments/recursive_types.ks"),
+        }
+    },
+    assertions: |_harness, ctx: CompileCtx| {
+        assert_eq!(ctx.type_registry().all_types().len(), 3);
+    }
+}
+
+compiler_test! {
+    id: compile_internal_items,
+    name: "Internal Items",
+    purpose: "Test that internal items are usable within the package and left out of declarations",
+    expect_pass: true,
+    tags: vec![Tag::Smoke, Tag::Struct],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/use_visibility.ks"),
+            "pkg/schema/visibility.ks" => include_str!("../fragments/visibility.ks"),
+        }
+    },
+    assertions: |harness, _ctx: CompileCtx| {
+        harness.assert_declarations_snapshot();
+        let decl = harness.declarations_json();
+        assert!(decl.contains(r#""name":"Item""#), "{decl}");
+        assert!(decl.contains(r#""name":"Page""#), "{decl}");
+        assert!(decl.contains(r#""name":"Created""#), "{decl}");
+        assert!(decl.contains(r#""size":25"#), "{decl}");
+        for internal in ["Audit", "PageCursor", "PAGE_LEN"] {
+            assert!(!decl.contains(&format!(r#""name":"{internal}""#)), "{decl}");
+        }
+    }
+}
+
+compiler_test! {
+    id: compile_field_constraints,
+    name: "Field Constraints",
+    purpose: "Test that validation constraint attributes are carried into field declarations",
+    expect_pass: true,
+    tags: vec![Tag::Smoke, Tag::Struct],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/use_accounts.ks"),
+            "pkg/schema/accounts.ks" => include_str!("../fragments/constraints.ks"),
+        }
+    },
+    assertions: |harness, _ctx: CompileCtx| {
+        harness.assert_declarations_snapshot();
+        let decl = harness.declarations_json();
+        assert!(
+            decl.contains(
+                r#""constraints":[{"kind":"pattern","value":"^[a-z]+$"},{"kind":"len","min":1,"max":64}]"#
+            ),
+            "{decl}"
+        );
+        assert!(
+            decl.contains(r#""constraints":[{"kind":"min","value":13},{"kind":"max","value":130}]"#),
+            "{decl}"
+        );
+    }
+}
+
+compiler_test! {
+    id: compile_package_metadata,
+    name: "Package Metadata",
+    purpose: "Test that `[package.metadata.*]` tables are embedded into declarations when enabled",
+    expect_pass: true,
+    tags: vec![Tag::Smoke],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/metadata_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/minimal_lib.ks"),
+        }
+    },
+    assertions: |harness, _ctx: CompileCtx| {
+        harness.assert_declarations_snapshot();
+        let decl = harness.declarations_json();
+        assert!(
+            decl.contains(r#""metadata":{"acme":{"owner":"payments-team","tier":1}}"#),
+            "{decl}"
+        );
+    }
+}
+
 compiler_test! {
     id: compile_external_path_dependency,
     name: "Smoke Package with External Path Dependency",
@@ -248,6 +422,62 @@ compiler_test! {
     }
 }
 
+compiler_test! {
+    id: compile_enum_aliases,
+    name: "Enum Value Aliases",
+    purpose: "Test that int and string enum aliases are carried into variant declarations",
+    expect_pass: true,
+    tags: vec![Tag::Smoke, Tag::Soundness],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/enum_aliases.ks"),
+        }
+    },
+    assertions: |harness, _ctx: CompileCtx| {
+        harness.assert_declarations_snapshot();
+        let decl = harness.declarations_json();
+        assert!(
+            decl.contains(r#""name":"Active","value":1,"aliases":["active","enabled"]"#),
+            "{decl}"
+        );
+        assert!(
+            decl.contains(r#""name":"Gray","value":"gray","aliases":["grey"]"#),
+            "{decl}"
+        );
+    }
+}
+
+compiler_test! {
+    id: compile_examples,
+    name: "Example Attributes",
+    purpose: "Test that field, argument and operation examples are carried into declarations",
+    expect_pass: true,
+    tags: vec![Tag::Smoke, Tag::Soundness],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/examples.ks"),
+        }
+    },
+    assertions: |harness, _ctx: CompileCtx| {
+        harness.assert_declarations_snapshot();
+        let decl = harness.declarations_json();
+        assert!(decl.contains(r#""examples":[42]"#), "{decl}");
+        assert!(decl.contains(r#""examples":["ada"]"#), "{decl}");
+        assert!(decl.contains(r#""examples":[null]"#), "{decl}");
+        assert!(decl.contains(r#""examples":[7]"#), "{decl}");
+        assert!(
+            decl.contains(
+                r#""examples":[{"input":{"id":42},"output":{"id":42,"name":"ada","role":"admin"}}]"#
+            ),
+            "{decl}"
+        );
+    }
+}
+
 compiler_test! {
     id: compile_oneof_mixed_types,
     name: "OneOf with Multiple Types",
@@ -306,6 +536,33 @@ compiler_test! {
     }
 }
 
+compiler_test! {
+    id: compile_tagging_overrides,
+    name: "Per-Type Tagging Overrides (RFC-0017)",
+    purpose: "Test that per-oneof tag overrides and namespace defaults are recorded in declarations",
+    expect_pass: true,
+    tags: vec![Tag::Smoke, Tag::OneOf],
+    root: "pkg",
+    memory: || {
+        memory! {
+            "pkg/schema.toml" => include_str!("../fragments/minimal_manifest.toml"),
+            "pkg/schema/lib.ks" => include_str!("../fragments/tagging_overrides.ks"),
+        }
+    },
+    assertions: |harness, _ctx: CompileCtx| {
+        harness.assert_declarations_snapshot();
+        let decl = harness.declarations_json();
+        for tag in [
+            r#""tag":{"style":"external","type_hint":true}"#,
+            r#""tag":{"style":"internal","tag":"type","type_hint":true}"#,
+            r#""tag":{"style":"adjacent","tag":"t","content":"c","type_hint":true}"#,
+            r#""tag":{"style":"untagged","type_hint":false}"#,
+        ] {
+            assert!(decl.contains(tag), "missing {tag} in {decl}");
+        }
+    }
+}
+
 compiler_test! {
     id: compile_type_expr_basic,
     name: "Type Expressions Basic (RFC-0018)",
@@ -344,3 +601,120 @@ compiler_test! {
         assert!(types.len() >= 4, "Should have Profile, Organization, type aliases");
     }
 }
+
+#[tokio::test]
+async fn compile_build_report() {
+    let fs = memory! {
+        "abc-corp/schema.toml" => include_str!("../fragments/abc_corp_manifest.toml"),
+        "abc-corp/schema/lib.ks" => include_str!("../fragments/abc_corp_lib.ks"),
+        "pkg/schema.toml" => include_str!("../fragments/pkg_with_dep_manifest.toml"),
+        "pkg/schema/lib.ks" => include_str!("../fragments/use_foo.ks"),
+        "pkg/schema/foo/test.ks" => include_str!("../fragments/bar_with_external_type.ks"),
+    };
+
+    let mut harness = TestHarness::with_metadata(
+        fs,
+        "compile_build_report",
+        "Build Report",
+        "Test that the build report covers phases, cache usage and dependency fetches",
+        true,
+        vec![Tag::Smoke, Tag::Dependencies],
+    );
+
+    let ctx = harness.compile_pass().await;
+    let report = ctx.build_report().await;
+
+    let phases: Vec<_> = report
+        .phases
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(
+        phases,
+        vec![
+            "load root schema",
+            "load dependencies",
+            "analyze schema graph",
+            "compile schemas",
+            "resolve types",
+            "finalize",
+        ]
+    );
+
+    assert_eq!(report.dependencies.count, 1);
+    let dep = &report.dependencies.packages[0];
+    // A path dependency with a version is locked as a registry source
+    assert_eq!(dep.source, "registry");
+    assert_eq!(dep.files, 1);
+    assert_eq!(
+        dep.bytes,
+        include_str!("../fragments/abc_corp_lib.ks").len() as u64
+    );
+    assert_eq!(report.diagnostics.recovered, 0);
+
+    let table = report.to_string();
+    assert!(table.contains("load dependencies"), "{table}");
+    assert!(table.contains(&dep.name), "{table}");
+}
+
+#[tokio::test]
+async fn compile_outcome_keeps_warnings() {
+    let fs = memory! {
+        "pkg/schema.toml" => cli_tests::minimal_manifest("test-outcome"),
+        "pkg/schema/lib.ks" => "namespace pkg;\nuse types;\n",
+        "pkg/schema/types.ks" => "namespace types;\n\noneof Payload {\n    Text { content: str }\n};\n",
+    };
+
+    let outcome = CompileCtx::with_fs(std::sync::Arc::new(fs), "pkg")
+        .await
+        .unwrap()
+        .into_outcome()
+        .await
+        .unwrap();
+
+    assert!(!outcome.warnings.has_errors());
+    assert_eq!(
+        outcome
+            .warnings
+            .warnings
+            .iter()
+            .map(|diagnostic| diagnostic.code.to_string())
+            .collect::<Vec<_>>(),
+        vec!["KLT8001"]
+    );
+
+    assert_eq!(outcome.artifacts.len(), 1);
+    assert_eq!(outcome.artifacts[0].package, "test-outcome");
+    assert_eq!(outcome.artifacts[0].version, "1.0.0");
+
+    let phases: Vec<_> = outcome
+        .metrics
+        .phases
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(phases.first(), Some(&"load root schema"));
+    assert_eq!(phases.last(), Some(&"lint"));
+    assert!(outcome.metrics.total_ms >= 0.0);
+}
+
+#[tokio::test]
+async fn compile_outcome_reports_denied_warnings() {
+    let fs = memory! {
+        "pkg/schema.toml" => cli_tests::minimal_manifest("test-outcome-denied"),
+        "pkg/schema/lib.ks" => "namespace pkg;\nuse types;\n",
+        "pkg/schema/types.ks" => "namespace types;\n\noneof Payload {\n    Text { content: str }\n};\n",
+    };
+
+    let mut ctx = CompileCtx::with_fs(std::sync::Arc::new(fs), "pkg")
+        .await
+        .unwrap();
+    ctx.set_deny_warnings(true);
+    let outcome = ctx.into_outcome().await.unwrap();
+
+    assert!(!outcome.is_success());
+    assert_eq!(
+        outcome.denied_warnings,
+        std::collections::BTreeSet::from(["KLT8001".to_string()])
+    );
+}
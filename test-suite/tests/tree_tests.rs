@@ -0,0 +1,91 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use kintsu_fs::memory;
+use kintsu_parser::ctx::{CompileCtx, DependencyTree};
+
+async fn compile(root: &str) -> CompileCtx {
+    kintsu_testing::logging();
+    let fs = memory! {
+        "base/schema.toml" => "version = \"v1\"\n[package]\nname = \"base-pkg\"\nversion = \"1.2.0\"\n",
+        "base/schema/lib.ks" => "namespace base_pkg;\nnamespace ids { struct Id { v: i32 }; };",
+        "dep/schema.toml" => "version = \"v1\"\n[package]\nname = \"dep\"\nversion = \"0.3.0\"\n[dependencies]\nbase-pkg = { path = \"../base\" }\n",
+        "dep/schema/lib.ks" => "namespace dep;\nnamespace data { use base_pkg::ids; struct Data { id: ids::Id }; };",
+        "pkg/schema.toml" => "version = \"v1\"\n[package]\nname = \"pkg\"\nversion = \"1.0.0\"\n[dependencies]\ndep = { path = \"../dep\" }\nbase-pkg = { path = \"../base\" }\n",
+        "pkg/schema/lib.ks" => "namespace pkg;\nnamespace foo { use dep::data; use base_pkg::ids::Id; struct Wrapper { data: data::Data, id: Id }; };\nnamespace bar { use schema::foo; struct B { w: foo::Wrapper }; };",
+        "base-next/schema.toml" => "version = \"v1\"\n[package]\nname = \"base-pkg\"\nversion = \"1.3.0\"\n",
+        "base-next/schema/lib.ks" => "namespace base_pkg;\nnamespace ids { struct Id { v: i64 }; };",
+        "other/schema.toml" => "version = \"v1\"\n[package]\nname = \"other\"\nversion = \"0.1.0\"\n[dependencies]\nbase-pkg = { path = \"../base-next\" }\n",
+        "other/schema/lib.ks" => "namespace other;\nnamespace refs { use base_pkg::ids; struct Ref { id: ids::Id }; };",
+    };
+
+    CompileCtx::with_fs(Arc::new(fs), root)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_tree_shows_imported_packages() {
+    let tree = compile("pkg").await.dependency_tree().await;
+
+    assert_eq!(
+        tree.render(&BTreeSet::new()),
+        "pkg v1.0.0\n\
+         ├── base-pkg v1.2.0\n\
+         └── dep v0.3.0\n    \
+             └── base-pkg v1.2.0\n"
+    );
+}
+
+#[tokio::test]
+async fn test_tree_inverted_explains_package() {
+    let tree = compile("pkg").await.dependency_tree().await;
+
+    assert_eq!(
+        tree.render_inverted("base-pkg", &BTreeSet::new())
+            .unwrap(),
+        "base-pkg v1.2.0\n\
+         ├── dep v0.3.0\n\
+         │   └── pkg v1.0.0\n\
+         └── pkg v1.0.0\n"
+    );
+    assert!(
+        tree.render_inverted("missing", &BTreeSet::new())
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_tree_shows_imported_namespaces() {
+    let tree = compile("pkg").await.dependency_tree().await;
+
+    assert_eq!(
+        tree.render_namespaces(),
+        "pkg::bar\n\
+         └── pkg::foo\n    \
+             ├── base_pkg::ids (base-pkg v1.2.0)\n    \
+             └── dep::data (dep v0.3.0)\n        \
+                 └── base_pkg::ids (base-pkg v1.2.0)\n\
+         \n\
+         pkg::foo\n\
+         ├── base_pkg::ids (base-pkg v1.2.0)\n\
+         └── dep::data (dep v0.3.0)\n    \
+             └── base_pkg::ids (base-pkg v1.2.0)\n"
+    );
+}
+
+#[tokio::test]
+async fn test_tree_highlights_duplicate_versions() {
+    let tree = compile("pkg").await.dependency_tree().await;
+    let other = compile("other")
+        .await
+        .dependency_tree()
+        .await;
+
+    let duplicates = DependencyTree::duplicates([&tree, &other]);
+    assert_eq!(duplicates, BTreeSet::from(["base-pkg".to_string()]));
+    assert_eq!(
+        other.render(&duplicates),
+        "other v0.1.0\n\
+         └── base-pkg v1.3.0 (duplicate)\n"
+    );
+}
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use kintsu_fs::{FileSystem, memory};
+use kintsu_manifests::{
+    config::NewForNamed,
+    lock::{LockedSource, WorkspaceLockfiles},
+};
+use kintsu_parser::ctx::{
+    WorkspaceCtx,
+    compile::resolver::{PackageResolver, Resolver},
+};
+
+const WORKSPACE: &str = r#"version = "v1"
+
+[workspace]
+members = ["packages/*"]
+
+[workspace.dependencies]
+common = { path = "packages/common", version = "^0.1.0" }
+"#;
+
+const COMMON: &str = r#"version = "v1"
+[package]
+name = "common"
+version = "0.1.2"
+"#;
+
+fn workspace(api_dependencies: &str) -> memory::MemoryFileSystem {
+    memory! {
+        "schema.toml" => WORKSPACE,
+        "packages/common/schema.toml" => COMMON,
+        "packages/common/schema/lib.ks" => "namespace common;\nnamespace data { struct Money { cents: i64 }; };",
+        "packages/api/schema.toml" => format!(
+            "version = \"v1\"\n[package]\nname = \"api\"\nversion = \"0.3.0\"\n\n[dependencies]\n{api_dependencies}"
+        ),
+        "packages/api/schema/lib.ks" => "namespace api;\nnamespace orders { use common::data;\nstruct Order { total: data::Money }; };",
+    }
+}
+
+async fn compile(fs: &memory::MemoryFileSystem) -> WorkspaceCtx {
+    kintsu_testing::logging();
+    let fs: Arc<dyn FileSystem> = Arc::new(fs.clone());
+    let resolver: Arc<dyn PackageResolver> = Arc::new(Resolver::new(fs.clone()));
+    let ctx = WorkspaceCtx::with_fs_and_config(fs, resolver, "", 2, false)
+        .await
+        .unwrap();
+    ctx.finalize().await.unwrap();
+    ctx
+}
+
+#[tokio::test]
+async fn test_workspace_compiles_members_with_one_lockfile() {
+    let fs = workspace("common = { workspace = true }\n");
+    let ctx = compile(&fs).await;
+
+    let names: Vec<_> = ctx
+        .members
+        .iter()
+        .map(|member| member.root.package.package().name.clone())
+        .collect();
+    assert_eq!(names, ["api", "common"]);
+    assert!(
+        ctx.member("api")
+            .unwrap()
+            .get_dependency("common")
+            .await
+            .is_some()
+    );
+
+    let WorkspaceLockfiles::V1(lockfile) = WorkspaceLockfiles::new(&fs, "").unwrap();
+    assert_eq!(
+        lockfile.members["api"].source,
+        LockedSource::Path {
+            path: "packages/api".into()
+        }
+    );
+    assert!(
+        lockfile.members["api"]
+            .dependencies
+            .contains_key("common")
+    );
+    assert!(
+        lockfile
+            .packages
+            .contains_key("common@0.1.2")
+    );
+
+    for member in ["packages/api", "packages/common"] {
+        assert!(
+            !fs.exists_sync(&std::path::Path::new(member).join("schema.lock.toml")),
+            "{member} should share the workspace lockfile"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_workspace_resolves_registry_dependencies_on_members_locally() {
+    // the default resolver cannot reach a registry, so this only compiles if `common`
+    // resolves to the member
+    let fs = workspace("common = { version = \"^0.1.0\" }\n");
+    let ctx = compile(&fs).await;
+
+    let common = ctx
+        .member("api")
+        .unwrap()
+        .get_dependency("common")
+        .await
+        .unwrap();
+    assert_eq!(common.package.package().version.to_string(), "0.1.2");
+}
+
+#[tokio::test]
+async fn test_workspace_lockfile_unchanged_no_rewrite() {
+    let fs = workspace("common = { workspace = true }\n");
+    compile(&fs).await;
+    assert!(fs.exists_sync("schema.lock.toml".as_ref()));
+
+    fs.clear_operations();
+    compile(&fs).await;
+
+    let writes: Vec<_> = fs
+        .operations()
+        .into_iter()
+        .filter(|op| matches!(op, memory::FsOperation::Write { .. }))
+        .collect();
+    assert!(
+        writes.is_empty(),
+        "lockfile should not be rewritten when unchanged: {writes:?}"
+    );
+}
+
+/// A oneof with a single variant, which lints as KLT8001
+const SINGLE_VARIANT: &str = "namespace types;\n\noneof Payload {\n    Text { content: str }\n};\n";
+
+fn linted_workspace(other_types: &str) -> memory::MemoryFileSystem {
+    memory! {
+        "schema.toml" => "version = \"v1\"\n\n[workspace]\nmembers = [\"packages/*\"]\n",
+        "packages/allowed/schema.toml" => "version = \"v1\"\n[package]\nname = \"allowed\"\nversion = \"0.1.0\"\n\n[lint]\nallow_warnings = [\"KLT8001\"]\n",
+        "packages/allowed/schema/lib.ks" => "namespace allowed;\nuse types;\n",
+        "packages/allowed/schema/types.ks" => SINGLE_VARIANT,
+        "packages/other/schema.toml" => "version = \"v1\"\n[package]\nname = \"other\"\nversion = \"0.1.0\"\n",
+        "packages/other/schema/lib.ks" => "namespace other;\nuse types;\n",
+        "packages/other/schema/types.ks" => other_types,
+    }
+}
+
+async fn finalize_denying_warnings(fs: &memory::MemoryFileSystem) -> kintsu_parser::Result<()> {
+    kintsu_testing::logging();
+    let fs: Arc<dyn FileSystem> = Arc::new(fs.clone());
+    let resolver: Arc<dyn PackageResolver> = Arc::new(Resolver::new(fs.clone()));
+    let mut ctx = WorkspaceCtx::with_fs_and_config(fs, resolver, "", 2, false)
+        .await
+        .unwrap();
+    ctx.set_deny_warnings(true);
+    ctx.finalize().await
+}
+
+#[tokio::test]
+async fn test_workspace_checks_warnings_against_their_own_member() {
+    // the warning `allowed` allows is not held against `other`
+    let fs = linted_workspace("namespace types;\n\nstruct Money { cents: i64 };\n");
+    finalize_denying_warnings(&fs).await.unwrap();
+
+    // while `other` raising it without allowing it still fails
+    let fs = linted_workspace(SINGLE_VARIANT);
+    let err = finalize_denying_warnings(&fs)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("warnings are denied: KLT8001"),
+        "{err}"
+    );
+}
@@ -0,0 +1,143 @@
+//! The compiler built for the browser, so that the playground and the registry UI validate
+//! schemas client-side.
+//!
+//! Packages are compiled from the files given to [`compile_source`], held in a
+//! [`MemoryFileSystem`]. Build with `wasm-pack build wasm --target web`.
+
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use kintsu_errors::CompilerError;
+use kintsu_events::Diagnostic;
+use kintsu_fs::memory::MemoryFileSystem;
+use kintsu_parser::{ctx::CompileCtx, declare::DeclarationBundle};
+use wasm_bindgen::prelude::*;
+
+/// The directory the files of a package are placed in.
+const ROOT: &str = "package";
+
+/// The outcome of compiling a package.
+#[derive(Debug, serde::Serialize)]
+pub struct CompileOutput {
+    /// Whether the package compiled, even if with warnings. Lint violations at the error
+    /// level fail it.
+    pub ok: bool,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The declarations of the package, once it compiled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declarations: Option<DeclarationBundle>,
+}
+
+fn diagnostics(err: impl Into<CompilerError>) -> Vec<Diagnostic> {
+    err.into()
+        .flatten()
+        .into_iter()
+        .map(|err| Diagnostic::from(err.clone()))
+        .collect()
+}
+
+async fn compile(fs: Arc<MemoryFileSystem>) -> kintsu_parser::Result<CompileOutput> {
+    let outcome = CompileCtx::with_fs(fs, ROOT)
+        .await?
+        .into_outcome()
+        .await?;
+    let kintsu_parser::declare::DeclarationVersion::V1(declarations) =
+        outcome.ctx.emit_declarations().await?;
+
+    Ok(CompileOutput {
+        ok: true,
+        diagnostics: outcome.warnings.warnings,
+        declarations: Some(declarations),
+    })
+}
+
+/// Compiles the package made of `files`, by their path within the package, e.g.
+/// `schema.toml` and `schema/lib.ks`. Dependencies must be given by path, within `files`.
+pub fn compile_files(files: BTreeMap<String, String>) -> CompileOutput {
+    let fs = MemoryFileSystem::new();
+    for (path, source) in files {
+        fs.add_file(Path::new(ROOT).join(path), source);
+    }
+
+    // the browser has no threads to run a multi-threaded runtime on
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("runtime");
+    runtime
+        .block_on(compile(Arc::new(fs)))
+        .unwrap_or_else(|err| {
+            CompileOutput {
+                ok: false,
+                diagnostics: diagnostics(err),
+                declarations: None,
+            }
+        })
+}
+
+/// Compiles the package made of `files`, a `Map` or object of paths to their source, into
+/// `{ ok, diagnostics, declarations }`.
+#[wasm_bindgen(js_name = compileSource)]
+pub fn compile_source(files: JsValue) -> Result<JsValue, JsError> {
+    let entries = match files.dyn_ref::<js_sys::Map>() {
+        Some(map) => js_sys::Array::from(map),
+        None => js_sys::Object::entries(files.unchecked_ref()),
+    };
+
+    let mut sources = BTreeMap::new();
+    for entry in entries.iter() {
+        let entry = js_sys::Array::from(&entry);
+        let (Some(path), Some(source)) = (entry.get(0).as_string(), entry.get(1).as_string())
+        else {
+            return Err(JsError::new("files must map paths to their source"));
+        };
+        sources.insert(path, source);
+    }
+
+    let output = serde_json::to_string(&compile_files(sources))?;
+    js_sys::JSON::parse(&output).map_err(|_| JsError::new("output is not valid JSON"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn package(lib: &str) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (
+                "schema.toml".into(),
+                "version = \"v1\"\n[package]\nname = \"play\"\nversion = \"0.1.0\"\n".into(),
+            ),
+            ("schema/lib.ks".into(), lib.into()),
+        ])
+    }
+
+    #[test]
+    fn compiles_a_package() {
+        let output = compile_files(package(
+            "namespace play;\nnamespace users { struct User { id: i64 }; };",
+        ));
+        assert!(output.ok, "{:?}", output.diagnostics);
+        assert!(
+            output
+                .declarations
+                .unwrap()
+                .root
+                .namespaces
+                .contains_key("users")
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        let output = compile_files(package(
+            "namespace play;\nnamespace users { struct User { id: Missing }; };",
+        ));
+        assert!(!output.ok);
+        assert!(output.declarations.is_none());
+        assert!(
+            output
+                .diagnostics
+                .iter()
+                .any(Diagnostic::is_error)
+        );
+    }
+}